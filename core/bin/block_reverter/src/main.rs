@@ -24,6 +24,9 @@ use zksync_types::{Address, L1BatchNumber};
 struct Cli {
     #[command(subcommand)]
     command: Command,
+    /// Name of the operator recorded in the operator audit log. Defaults to the `USER` env variable.
+    #[arg(long, global = true)]
+    operator: Option<String>,
 }
 
 #[derive(Debug, Subcommand)]
@@ -80,11 +83,22 @@ enum Command {
     /// Clears failed L1 transactions.
     #[command(name = "clear-failed-transactions")]
     ClearFailedL1Transactions,
+
+    /// Restores archived L1 transactions and sending attempts for a range of L1 transactions.
+    #[command(name = "restore-archived-eth-txs-history")]
+    RestoreArchivedEthTxsHistory {
+        /// First L1 transaction ID in the range (inclusive).
+        #[arg(long)]
+        from_eth_tx_id: u32,
+        /// Last L1 transaction ID in the range (inclusive).
+        #[arg(long)]
+        to_eth_tx_id: u32,
+    },
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let command = Cli::parse().command;
+    let Cli { command, operator } = Cli::parse();
     let observability_config =
         ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
     let log_format: vlog::LogFormat = observability_config
@@ -128,6 +142,9 @@ async fn main() -> anyhow::Result<()> {
     .await
     .context("failed to build a connection pool")?;
     let mut block_reverter = BlockReverter::new(NodeRole::Main, connection_pool);
+    if let Some(operator) = operator.or_else(|| env::var("USER").ok()) {
+        block_reverter.set_audit_actor(operator);
+    }
 
     match command {
         Command::Display {
@@ -248,6 +265,18 @@ async fn main() -> anyhow::Result<()> {
         Command::ClearFailedL1Transactions => {
             block_reverter.clear_failed_l1_transactions().await?;
         }
+        Command::RestoreArchivedEthTxsHistory {
+            from_eth_tx_id,
+            to_eth_tx_id,
+        } => {
+            anyhow::ensure!(
+                from_eth_tx_id <= to_eth_tx_id,
+                "invalid range of L1 transaction IDs: {from_eth_tx_id}..={to_eth_tx_id}"
+            );
+            block_reverter
+                .restore_archived_eth_txs_history(from_eth_tx_id..=to_eth_tx_id)
+                .await?;
+        }
     }
     Ok(())
 }
@@ -0,0 +1,137 @@
+//! Utility re-executing a sealed L1 batch with bootloader debugging enabled.
+//!
+//! Set `RUST_LOG=multivm=debug` to log all bootloader hooks as they are encountered.
+
+use anyhow::Context as _;
+use clap::Parser;
+use tokio::runtime::Handle;
+use vm_utils::bootloader_debug::{debug_l1_batch, L1BatchDebugReport};
+use zksync_config::configs::{chain::NetworkConfig, DatabaseSecrets, ObservabilityConfig};
+use zksync_dal::{ConnectionPool, Core};
+use zksync_env_config::FromEnv;
+use zksync_types::{L1BatchNumber, H256};
+
+#[derive(Debug, Parser)]
+#[command(author = "Matter Labs", version, about = "Bootloader debugging utility", long_about = None)]
+struct Cli {
+    /// Number of the sealed L1 batch to re-execute.
+    #[arg(long)]
+    l1_batch_number: u32,
+    /// Hash of the EVM emulator bytecode, if the EVM emulator is enabled on the chain. Must match
+    /// `evm_emulator_hash` in the genesis config.
+    #[arg(long)]
+    evm_emulator_hash: Option<H256>,
+    /// Dump non-zero words of the bootloader heap after each execution. This is slow and memory-intensive.
+    #[arg(long)]
+    dump_heap: bool,
+    /// Print all recorded bootloader hooks, rather than only debug logs.
+    #[arg(long)]
+    all_hooks: bool,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opts = Cli::parse();
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+    let _guard = vlog::ObservabilityBuilder::new()
+        .with_log_format(log_format)
+        .build();
+
+    let network = NetworkConfig::from_env().context("NetworkConfig::from_env()")?;
+    let database_secrets = DatabaseSecrets::from_env().context("DatabaseSecrets::from_env()")?;
+    let connection_pool = ConnectionPool::<Core>::singleton(database_secrets.master_url()?)
+        .build()
+        .await
+        .context("failed to build a connection pool")?;
+
+    let l1_batch_number = L1BatchNumber(opts.l1_batch_number);
+    let rt_handle = Handle::current();
+    let report = tokio::task::spawn_blocking(move || {
+        let connection = rt_handle
+            .block_on(connection_pool.connection())
+            .context("failed to get connection")?;
+        debug_l1_batch(
+            rt_handle,
+            l1_batch_number,
+            connection,
+            network.zksync_network_id,
+            opts.evm_emulator_hash,
+            opts.dump_heap,
+        )
+    })
+    .await
+    .context("debugging task panicked")??;
+
+    print_report(&report, opts.all_hooks);
+    Ok(())
+}
+
+fn print_report(report: &L1BatchDebugReport, all_hooks: bool) {
+    println!(
+        "L1 batch #{} (protocol version {:?}): {} transactions",
+        report.l1_batch_number,
+        report.protocol_version,
+        report.transactions.len()
+    );
+
+    let bootloader_reports = report
+        .transactions
+        .iter()
+        .map(|tx| (format!("tx {:?}", tx.tx_hash), &tx.result, &tx.bootloader))
+        .chain([(
+            "batch tip".to_owned(),
+            &report.batch_tip_result,
+            &report.batch_tip,
+        )]);
+    for (name, result, bootloader) in bootloader_reports {
+        println!(
+            "\n{name}: {result:?}; bootloader gas remaining: {}",
+            bootloader.gas_remaining
+        );
+        for record in &bootloader.hooks {
+            if let Some(message) = &record.debug_message {
+                println!("  [gas {}] {message}", record.gas_remaining);
+            } else if all_hooks {
+                println!(
+                    "  [gas {}] {:?} (tx #{})",
+                    record.gas_remaining, record.hook, record.tx_number_in_block
+                );
+            }
+        }
+        for (index, word) in &bootloader.bootloader_heap {
+            println!("  heap[{index}] = {word:#x}");
+        }
+    }
+    for tx in &report.transactions {
+        if tx.compression_failed {
+            println!(
+                "\ntx {:?} was executed without bytecode compression",
+                tx.tx_hash
+            );
+        }
+    }
+
+    if let Some(memory) = &report.bootloader_memory {
+        println!("\nBootloader memory filled by the operator:");
+        for (index, word) in memory {
+            println!("  memory[{index}] = {word:#x}");
+        }
+    }
+
+    if report.system_log_mismatches.is_empty() {
+        println!("\nSystem logs match the persisted L1 batch");
+    } else {
+        println!("\nSystem log mismatches:");
+        for mismatch in &report.system_log_mismatches {
+            println!(
+                "  #{}: expected {:?}, got {:?}",
+                mismatch.index, mismatch.expected, mismatch.actual
+            );
+        }
+    }
+}
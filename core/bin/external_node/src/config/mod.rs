@@ -9,24 +9,24 @@ use anyhow::Context;
 use serde::Deserialize;
 use zksync_config::{
     configs::{
-        api::{MaxResponseSize, MaxResponseSizeOverrides},
+        api::{GethCompatibilityShim, MaxResponseSize, MaxResponseSizeOverrides},
         consensus::{ConsensusConfig, ConsensusSecrets},
     },
     ObjectStoreConfig,
 };
-use zksync_core_leftovers::temp_config_store::decode_yaml_repr;
+use zksync_core_leftovers::temp_config_store::{decode_yaml_repr, decode_yaml_repr_with_keystores};
 #[cfg(test)]
-use zksync_dal::{ConnectionPool, Core};
+use zksync_dal::{watermarks_dal::WatermarkComponent, ConnectionPool, Core};
 use zksync_metadata_calculator::MetadataCalculatorRecoveryConfig;
 use zksync_node_api_server::{
     tx_sender::TxSenderConfig,
     web3::{state::InternalApiConfig, Namespace},
 };
-use zksync_protobuf_config::proto;
+use zksync_protobuf_config::{keystore::KeystorePassphrase, proto};
 use zksync_snapshots_applier::SnapshotsApplierConfig;
 use zksync_types::{
     api::BridgeAddresses, commitment::L1BatchCommitmentMode, url::SensitiveUrl, Address,
-    L1BatchNumber, L1ChainId, L2ChainId, ETHEREUM_ADDRESS,
+    L1BatchNumber, L1ChainId, L2ChainId, ETHEREUM_ADDRESS, H256,
 };
 use zksync_web3_decl::{
     client::{DynClient, L2},
@@ -87,6 +87,8 @@ pub(crate) struct RemoteENConfig {
     pub base_token_addr: Address,
     pub l1_batch_commit_data_generator_mode: L1BatchCommitmentMode,
     pub dummy_verifier: bool,
+    #[serde(default)]
+    pub evm_emulator_hash: Option<H256>,
 }
 
 impl RemoteENConfig {
@@ -168,6 +170,7 @@ impl RemoteENConfig {
                 .as_ref()
                 .map(|a| a.dummy_verifier)
                 .unwrap_or_default(),
+            evm_emulator_hash: genesis.as_ref().and_then(|a| a.evm_emulator_hash),
         })
     }
 
@@ -188,6 +191,7 @@ impl RemoteENConfig {
             l2_shared_bridge_addr: Some(Address::repeat_byte(6)),
             l1_batch_commit_data_generator_mode: L1BatchCommitmentMode::Rollup,
             dummy_verifier: true,
+            evm_emulator_hash: None,
         }
     }
 }
@@ -249,6 +253,19 @@ pub(crate) struct OptionalENConfig {
     /// This option can be tweaked down if the API server is running out of memory.
     #[serde(default = "OptionalENConfig::default_vm_concurrency_limit")]
     pub vm_concurrency_limit: usize,
+    /// Max number of VM instances that can be concurrently used by a single API client, identified by `vm_client_id_header`.
+    /// If not set, only the global VM concurrency limit is enforced.
+    pub vm_concurrency_limit_per_client: Option<NonZeroUsize>,
+    /// HTTP header used to identify API clients for fair VM scheduling. Default is `x-forwarded-for`.
+    #[serde(default = "OptionalENConfig::default_vm_client_id_header")]
+    pub vm_client_id_header: String,
+    /// Number of trusted reverse proxies in front of the API server appending hops to `vm_client_id_header`.
+    /// The client ID is taken from the rightmost hop not appended by a trusted proxy. Default is 1.
+    #[serde(default = "OptionalENConfig::default_vm_client_id_trusted_proxies")]
+    pub vm_client_id_trusted_proxies: usize,
+    /// Timeout for acquiring a VM permit if fair VM scheduling is enabled. Default is 5000 milliseconds.
+    #[serde(default = "OptionalENConfig::default_vm_permit_timeout_ms")]
+    vm_permit_timeout_ms: u64,
     /// Smart contract bytecode cache size for the API server. Default value is 128 MiB.
     #[serde(default = "OptionalENConfig::default_factory_deps_cache_size_mb")]
     factory_deps_cache_size_mb: usize,
@@ -281,10 +298,38 @@ pub(crate) struct OptionalENConfig {
     /// Maximum number of transactions to be stored in the mempool cache.
     #[serde(default = "OptionalENConfig::default_mempool_cache_size")]
     pub mempool_cache_size: usize,
+    /// Maximum number of `eth_call` results to be stored in the call cache. If not specified, the cache is disabled.
+    pub eth_call_cache_size: Option<NonZeroUsize>,
+    /// Time-to-live for entries in the `eth_call` cache. Default is 1000 milliseconds.
+    #[serde(default = "OptionalENConfig::default_eth_call_cache_ttl_ms")]
+    pub eth_call_cache_ttl_ms: u64,
+    /// Maximum number of idle VM memory instances retained by the API sandbox for each set of base system contracts.
+    /// If not specified, VM memory is not reused.
+    pub vm_memory_pool_size: Option<NonZeroUsize>,
+    /// Statement timeout for expensive read queries, such as ones for `eth_getLogs` and `debug_traceBlock*`.
+    /// If not specified, only the global statement timeout applies.
+    query_statement_timeout_ms: Option<u64>,
+    /// Maximum number of idempotency keys remembered by the HTTP server for transaction submissions.
+    /// If not specified, the `idempotency-key` header is ignored.
+    pub idempotency_keys_cache_size: Option<NonZeroUsize>,
+    /// Time-to-live for remembered idempotency keys. Default is 600 seconds.
+    #[serde(default = "OptionalENConfig::default_idempotency_key_ttl_sec")]
+    idempotency_key_ttl_sec: u64,
+    /// Maximum number of transactions queued for forwarding to the main node while it's unavailable. Queued transactions
+    /// are accepted by the node and are retried in the background; their status can be queried via
+    /// `zks_getForwardedTransaction`. If not specified, transactions are not queued, and submission fails
+    /// if the main node is unavailable.
+    pub tx_forwarding_queue_capacity: Option<NonZeroUsize>,
+    /// Maximum time a transaction can spend in the forwarding queue before it's dropped. Default is 300 seconds.
+    #[serde(default = "OptionalENConfig::default_tx_forwarding_timeout_sec")]
+    tx_forwarding_timeout_sec: u64,
     /// Enables extended tracing of RPC calls. This may negatively impact performance for nodes under high load
     /// (hundreds or thousands RPS).
     #[serde(default = "OptionalENConfig::default_extended_api_tracing")]
     pub extended_rpc_tracing: bool,
+    /// Compatibility shims for the JSON-RPC API mimicking geth behavior that some Ethereum tooling relies on.
+    #[serde(default)]
+    pub geth_compatibility: Vec<GethCompatibilityShim>,
 
     // Health checks
     /// Time limit in milliseconds to mark a health check as slow and log the corresponding warning.
@@ -301,6 +346,9 @@ pub(crate) struct OptionalENConfig {
     /// The max possible number of gas that `eth_estimateGas` is allowed to overestimate.
     #[serde(default = "OptionalENConfig::default_estimate_gas_acceptable_overestimation")]
     pub estimate_gas_acceptable_overestimation: u32,
+    /// Max number of transactions in a single `zks_estimateFeeBatch` request. Default is 16.
+    #[serde(default = "OptionalENConfig::default_estimate_fee_batch_max_size")]
+    pub estimate_fee_batch_max_size: usize,
     /// The multiplier to use when suggesting gas price. Should be higher than one,
     /// otherwise if the L1 prices soar, the suggested gas price won't be sufficient to be included in block.
     #[serde(default = "OptionalENConfig::default_gas_price_scale_factor")]
@@ -368,6 +416,11 @@ pub(crate) struct OptionalENConfig {
     /// By default, set to `true` as a temporary safety measure.
     #[serde(default = "OptionalENConfig::default_protective_reads_persistence_enabled")]
     pub protective_reads_persistence_enabled: bool,
+    /// Whether to persist call traces in the flat format. If disabled (the default), call traces are persisted
+    /// in the legacy format understood by all node versions. Should only be enabled once all nodes reading call traces
+    /// from the node database are updated.
+    #[serde(default)]
+    pub flat_call_traces: bool,
     /// Address of the L1 diamond proxy contract used by the consistency checker to match with the origin of logs emitted
     /// by commit transactions. If not set, it will not be verified.
     // This is intentionally not a part of `RemoteENConfig` because fetching this info from the main node would defeat
@@ -410,6 +463,11 @@ pub(crate) struct OptionalENConfig {
     /// If set to 0, L1 batches will not be retained based on their timestamp. The default value is 1 hour.
     #[serde(default = "OptionalENConfig::default_pruning_data_retention_sec")]
     pruning_data_retention_sec: u64,
+    /// Components that must process an L1 batch (as indicated by their published watermarks) before it can be pruned,
+    /// e.g. `tree,protective_reads`. Components not running on the node must not be specified, since they would
+    /// block pruning indefinitely. By default, no watermarks are checked.
+    #[serde(default)]
+    pub pruning_watermark_components: Vec<WatermarkComponent>,
 }
 
 impl OptionalENConfig {
@@ -441,6 +499,10 @@ impl OptionalENConfig {
         1_000
     }
 
+    const fn default_estimate_fee_batch_max_size() -> usize {
+        16
+    }
+
     const fn default_gas_price_scale_factor() -> f64 {
         1.2
     }
@@ -520,6 +582,30 @@ impl OptionalENConfig {
         10_000
     }
 
+    const fn default_eth_call_cache_ttl_ms() -> u64 {
+        1_000
+    }
+
+    const fn default_idempotency_key_ttl_sec() -> u64 {
+        600
+    }
+
+    const fn default_tx_forwarding_timeout_sec() -> u64 {
+        300
+    }
+
+    fn default_vm_client_id_header() -> String {
+        "x-forwarded-for".to_owned()
+    }
+
+    const fn default_vm_client_id_trusted_proxies() -> usize {
+        1
+    }
+
+    const fn default_vm_permit_timeout_ms() -> u64 {
+        5_000
+    }
+
     const fn default_extended_api_tracing() -> bool {
         true
     }
@@ -626,6 +712,26 @@ impl OptionalENConfig {
         Duration::from_millis(self.mempool_cache_update_interval_ms)
     }
 
+    pub fn eth_call_cache_ttl(&self) -> Duration {
+        Duration::from_millis(self.eth_call_cache_ttl_ms)
+    }
+
+    pub fn query_statement_timeout(&self) -> Option<Duration> {
+        self.query_statement_timeout_ms.map(Duration::from_millis)
+    }
+
+    pub fn idempotency_key_ttl(&self) -> Duration {
+        Duration::from_secs(self.idempotency_key_ttl_sec)
+    }
+
+    pub fn tx_forwarding_timeout(&self) -> Duration {
+        Duration::from_secs(self.tx_forwarding_timeout_sec)
+    }
+
+    pub fn vm_permit_timeout(&self) -> Duration {
+        Duration::from_millis(self.vm_permit_timeout_ms)
+    }
+
     pub fn pruning_removal_delay(&self) -> Duration {
         Duration::from_secs(self.pruning_removal_delay_sec.get())
     }
@@ -766,6 +872,14 @@ pub(crate) struct ExperimentalENConfig {
     /// Maximum degree of parallelism during commitment generation, i.e., the maximum number of L1 batches being processed in parallel.
     /// If not specified, commitment generator will use a value roughly equal to the number of CPU cores with some clamping applied.
     pub commitment_generator_max_parallelism: Option<NonZeroU32>,
+
+    // Consistency checker
+    /// Enables the verify-on-sync mode. In this mode, each synced L1 batch is verified against L1 (its commit calldata
+    /// and the batch hash stored by the diamond proxy contract, which covers the state root hash and pubdata commitments)
+    /// before it is marked as executed locally; the node stops on any mismatch. Thus, locally finalized data doesn't rely
+    /// on trusting the main node.
+    #[serde(default)]
+    pub verify_on_sync: bool,
 }
 
 impl ExperimentalENConfig {
@@ -787,6 +901,7 @@ impl ExperimentalENConfig {
             snapshots_recovery_tree_chunk_size: Self::default_snapshots_recovery_tree_chunk_size(),
             snapshots_recovery_tree_parallel_persistence_buffer: None,
             commitment_generator_max_parallelism: None,
+            verify_on_sync: false,
         }
     }
 
@@ -802,8 +917,11 @@ pub(crate) fn read_consensus_secrets() -> anyhow::Result<Option<ConsensusSecrets
     };
     let cfg = std::fs::read_to_string(&path).context(path)?;
     Ok(Some(
-        decode_yaml_repr::<proto::secrets::ConsensusSecrets>(&cfg)
-            .context("failed decoding YAML")?,
+        decode_yaml_repr_with_keystores::<proto::secrets::ConsensusSecrets>(
+            &cfg,
+            KeystorePassphrase::from_env,
+        )
+        .context("failed decoding YAML")?,
     ))
 }
 
@@ -925,6 +1043,7 @@ impl From<&ExternalNodeConfig> for InternalApiConfig {
             estimate_gas_acceptable_overestimation: config
                 .optional
                 .estimate_gas_acceptable_overestimation,
+            estimate_fee_batch_max_size: config.optional.estimate_fee_batch_max_size,
             bridge_addresses: BridgeAddresses {
                 l1_erc20_default_bridge: config.remote.l1_erc20_bridge_proxy_addr,
                 l2_erc20_default_bridge: config.remote.l2_erc20_bridge_addr,
@@ -944,6 +1063,18 @@ impl From<&ExternalNodeConfig> for InternalApiConfig {
             filters_disabled: config.optional.filters_disabled,
             dummy_verifier: config.remote.dummy_verifier,
             l1_batch_commit_data_generator_mode: config.remote.l1_batch_commit_data_generator_mode,
+            evm_emulator_hash: config.remote.evm_emulator_hash,
+            geth_compatibility: config.optional.geth_compatibility.iter().copied().collect(),
+            vm_client_id_header: config
+                .optional
+                .vm_concurrency_limit_per_client
+                .map(|_| config.optional.vm_client_id_header.clone()),
+            vm_client_id_trusted_proxies: config.optional.vm_client_id_trusted_proxies,
+            query_statement_timeout: config.optional.query_statement_timeout(),
+            idempotency_keys_cache_size: config.optional.idempotency_keys_cache_size,
+            idempotency_key_ttl: config.optional.idempotency_key_ttl(),
+            // The `admin` namespace is not served by the external node.
+            admin_api_keys: vec![],
         }
     }
 }
@@ -966,6 +1097,17 @@ impl From<&ExternalNodeConfig> for TxSenderConfig {
             chain_id: config.required.l2_chain_id,
             // Does not matter for EN.
             whitelisted_tokens_for_aa: Default::default(),
+            eth_call_cache_size: config.optional.eth_call_cache_size,
+            eth_call_cache_ttl: config.optional.eth_call_cache_ttl(),
+            // Rejected transactions are recorded by the main node.
+            rejected_txs_retention: None,
+            // Gas per pubdata bounds are enforced by the main node.
+            min_gas_per_pubdata_limit: None,
+            max_gas_per_pubdata_limit: None,
+            // Replay protection is enforced by the main node.
+            reject_unprotected_txs: false,
+            vm_memory_pool_size: config.optional.vm_memory_pool_size,
+            evm_emulator_hash: config.remote.evm_emulator_hash,
         }
     }
 }
@@ -196,6 +196,7 @@ fn parsing_experimental_config_from_empty_env() {
     let config: ExperimentalENConfig = envy::prefixed("EN_EXPERIMENTAL_").from_iter([]).unwrap();
     assert_eq!(config.state_keeper_db_block_cache_capacity(), 128 << 20);
     assert_eq!(config.state_keeper_db_max_open_files, None);
+    assert!(!config.verify_on_sync);
 }
 
 #[test]
@@ -206,6 +207,7 @@ fn parsing_experimental_config_from_env() {
             "64",
         ),
         ("EN_EXPERIMENTAL_STATE_KEEPER_DB_MAX_OPEN_FILES", "100"),
+        ("EN_EXPERIMENTAL_VERIFY_ON_SYNC", "true"),
     ];
     let env_vars = env_vars
         .into_iter()
@@ -216,4 +218,5 @@ fn parsing_experimental_config_from_env() {
         .unwrap();
     assert_eq!(config.state_keeper_db_block_cache_capacity(), 64 << 20);
     assert_eq!(config.state_keeper_db_max_open_files, NonZeroU32::new(100));
+    assert!(config.verify_on_sync);
 }
@@ -15,7 +15,9 @@ use zksync_concurrency::{ctx, scope};
 use zksync_config::configs::{api::MerkleTreeApiConfig, database::MerkleTreeMode};
 use zksync_consistency_checker::ConsistencyChecker;
 use zksync_core_leftovers::setup_sigint_handler;
-use zksync_dal::{metrics::PostgresMetrics, ConnectionPool, Core};
+use zksync_dal::{
+    metrics::PostgresMetrics, transactions_dal::CallTraceFormat, ConnectionPool, Core,
+};
 use zksync_db_connection::{
     connection_pool::ConnectionPoolBuilder, healthcheck::ConnectionPoolHealthCheck,
 };
@@ -25,7 +27,7 @@ use zksync_metadata_calculator::{
     MetadataCalculator, MetadataCalculatorConfig, MetadataCalculatorRecoveryConfig,
 };
 use zksync_node_api_server::{
-    execution_sandbox::VmConcurrencyLimiter,
+    execution_sandbox::{VmConcurrencyLimiter, VmFairSchedulingConfig},
     healthcheck::HealthCheckHandle,
     tx_sender::{proxy::TxProxy, ApiContracts, TxSenderBuilder},
     web3::{mempool_cache::MempoolCache, ApiBuilder, Namespace},
@@ -104,7 +106,8 @@ async fn build_state_keeper(
         chain_id,
     )
     .await
-    .context("Failed initializing I/O for external node state keeper")?;
+    .context("Failed initializing I/O for external node state keeper")?
+    .with_evm_emulator_hash(config.remote.evm_emulator_hash);
 
     Ok(ZkSyncStateKeeper::new(
         stop_receiver,
@@ -232,6 +235,9 @@ async fn run_core(
         tracing::warn!("Disabling persisting protective reads; this should be safe, but is considered an experimental option at the moment");
         persistence = persistence.without_protective_reads();
     }
+    if config.optional.flat_call_traces {
+        persistence = persistence.with_call_trace_format(CallTraceFormat::Flat);
+    }
     let tree_writes_persistence = TreeWritesPersistence::new(connection_pool.clone());
 
     let output_handler = OutputHandler::new(Box::new(persistence))
@@ -306,6 +312,7 @@ async fn run_core(
                 removal_delay: config.optional.pruning_removal_delay(),
                 pruned_batch_chunk_size: config.optional.pruning_chunk_size,
                 minimum_l1_batch_age,
+                watermark_components: config.optional.pruning_watermark_components.clone(),
             },
             connection_pool.clone(),
         );
@@ -339,7 +346,7 @@ async fn run_core(
     );
     task_handles.push(tokio::spawn(validation_task.run(stop_receiver.clone())));
 
-    let consistency_checker = ConsistencyChecker::new(
+    let mut consistency_checker = ConsistencyChecker::new(
         eth_client,
         10, // TODO (BFT-97): Make it a part of a proper EN config
         singleton_pool_builder
@@ -350,17 +357,26 @@ async fn run_core(
     )
     .context("cannot initialize consistency checker")?
     .with_diamond_proxy_addr(diamond_proxy_addr);
+    if config.experimental.verify_on_sync {
+        tracing::info!(
+            "Verify-on-sync mode is enabled; L1 batches will be finalized after L1 verification"
+        );
+        consistency_checker = consistency_checker.with_verify_on_sync();
+    }
 
     app_health.insert_component(consistency_checker.health_check().clone())?;
     let consistency_checker_handle = tokio::spawn(consistency_checker.run(stop_receiver.clone()));
 
-    let batch_status_updater = BatchStatusUpdater::new(
+    let mut batch_status_updater = BatchStatusUpdater::new(
         main_node_client.clone(),
         singleton_pool_builder
             .build()
             .await
             .context("failed to build a connection pool for BatchStatusUpdater")?,
     );
+    if config.experimental.verify_on_sync {
+        batch_status_updater = batch_status_updater.with_verify_on_sync();
+    }
     app_health.insert_component(batch_status_updater.health_check())?;
 
     let mut commitment_generator = CommitmentGenerator::new(
@@ -421,7 +437,12 @@ async fn run_api(
         );
     }
 
-    let tx_proxy = TxProxy::new(main_node_client.clone());
+    let mut tx_proxy = TxProxy::new(main_node_client.clone());
+    if let Some(capacity) = config.optional.tx_forwarding_queue_capacity {
+        tx_proxy =
+            tx_proxy.with_forwarding_queue(capacity.get(), config.optional.tx_forwarding_timeout());
+        task_handles.push(tokio::spawn(tx_proxy.run_forwarder(stop_receiver.clone())));
+    }
     let proxy_cache_updater_pool = singleton_pool_builder
         .build()
         .await
@@ -439,7 +460,14 @@ async fn run_api(
         TxSenderBuilder::new(config.into(), connection_pool.clone(), Arc::new(tx_proxy));
 
     let max_concurrency = config.optional.vm_concurrency_limit;
-    let (vm_concurrency_limiter, vm_barrier) = VmConcurrencyLimiter::new(max_concurrency);
+    let (mut vm_concurrency_limiter, vm_barrier) = VmConcurrencyLimiter::new(max_concurrency);
+    if let Some(max_concurrency_per_client) = config.optional.vm_concurrency_limit_per_client {
+        vm_concurrency_limiter =
+            vm_concurrency_limiter.with_fair_scheduling(VmFairSchedulingConfig {
+                max_concurrency_per_client,
+                acquire_timeout: config.optional.vm_permit_timeout(),
+            });
+    }
     let mut storage_caches = PostgresStorageCaches::new(
         config.optional.factory_deps_cache_size() as u64,
         config.optional.initial_writes_cache_size() as u64,
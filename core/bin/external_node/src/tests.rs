@@ -1,5 +1,7 @@
 //! High-level tests for EN.
 
+use std::collections::BTreeMap;
+
 use assert_matches::assert_matches;
 use test_casing::test_casing;
 use zksync_dal::CoreDal;
@@ -173,6 +175,7 @@ async fn external_node_basics(components_str: &'static str) {
             Ok(api::L1BatchDetails {
                 number: L1BatchNumber(0),
                 base: block_details_base(genesis_params.root_hash),
+                custom_metadata: BTreeMap::new(),
             })
         })
         .method("eth_blockNumber", || Ok(U64::from(0)))
@@ -9,7 +9,7 @@ use clap::Parser;
 use serde_yaml::Serializer;
 use zksync_config::{configs::DatabaseSecrets, GenesisConfig};
 use zksync_contracts::BaseSystemContracts;
-use zksync_core_leftovers::temp_config_store::decode_yaml_repr;
+use zksync_core_leftovers::temp_config_store::{decode_yaml_repr, decode_yaml_repr_with_keystores};
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_env_config::FromEnv;
 use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
@@ -17,7 +17,7 @@ use zksync_protobuf::{
     build::{prost_reflect, prost_reflect::ReflectMessage},
     ProtoRepr,
 };
-use zksync_protobuf_config::proto::genesis::Genesis;
+use zksync_protobuf_config::{keystore::KeystorePassphrase, proto::genesis::Genesis};
 use zksync_types::{
     protocol_version::ProtocolSemanticVersion, url::SensitiveUrl, ProtocolVersionId,
 };
@@ -42,8 +42,10 @@ async fn main() -> anyhow::Result<()> {
         Some(path) => {
             let yaml =
                 std::fs::read_to_string(&path).with_context(|| path.display().to_string())?;
-            let config = decode_yaml_repr::<zksync_protobuf_config::proto::secrets::Secrets>(&yaml)
-                .context("failed decoding general YAML config")?;
+            let config = decode_yaml_repr_with_keystores::<
+                zksync_protobuf_config::proto::secrets::Secrets,
+            >(&yaml, KeystorePassphrase::from_env)
+            .context("failed decoding general YAML config")?;
             config.database.context("Database secrets must exist")?
         }
     };
@@ -0,0 +1,301 @@
+//! Utility recalculating metadata (commitments and compressed pubdata) for a range of sealed L1 batches
+//! and comparing it with the values stored in Postgres. Optionally, the stored root hashes can be compared
+//! with the Merkle tree, and mismatched commitment artifacts can be repaired.
+//!
+//! The Merkle tree RocksDB instance can only be opened if it's not used by a running node.
+//!
+//! With `--re-execute`, batches are instead re-executed in the VM, and commitments, bootloader memory
+//! and auxiliary outputs are recomputed from the VM outputs rather than from the data stored in Postgres.
+//! Progress of re-execution is persisted, so an interrupted run resumes where it stopped.
+
+use std::path::Path;
+
+use anyhow::Context as _;
+use clap::Parser;
+use tokio::sync::{mpsc, watch};
+use zksync_commitment_generator::recalculation::{L1BatchMetadataCheck, MetadataRecalculator};
+use zksync_config::{
+    configs::{DatabaseSecrets, ObservabilityConfig},
+    DBConfig, GenesisConfig,
+};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_env_config::FromEnv;
+use zksync_merkle_tree::domain::ZkSyncTreeReader;
+use zksync_storage::RocksDB;
+use zksync_types::{block::L1BatchTreeData, L1BatchNumber};
+use zksync_vm_runner::{
+    CommitmentRecomputationReport, CommitmentRecomputer, VmRunnerStorageBackend,
+};
+
+/// Number of Postgres connections used in the re-execution mode.
+const RE_EXECUTION_CONNECTIONS: u32 = 3;
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "L1 batch metadata recalculation utility",
+    long_about = None
+)]
+struct Cli {
+    /// First L1 batch to check (inclusive).
+    #[arg(long)]
+    from_batch: u32,
+    /// Last L1 batch to check (inclusive). If not specified, all sealed batches starting from `--from-batch`
+    /// are checked.
+    #[arg(long)]
+    to_batch: Option<u32>,
+    /// Compare stored root hashes and leaf indices with the Merkle tree at the path specified in the DB config.
+    #[arg(long)]
+    compare_tree: bool,
+    /// Overwrite mismatched commitment artifacts with the recalculated ones. Batches committed on L1
+    /// are never repaired.
+    #[arg(long)]
+    repair: bool,
+    /// Re-execute batches in the VM and recompute their data from the VM outputs. Cannot be combined
+    /// with `--compare-tree` or `--repair`.
+    #[arg(long, conflicts_with_all = ["compare_tree", "repair"])]
+    re_execute: bool,
+    /// Path to the RocksDB cache used for re-execution. Must not be shared with other components.
+    /// If not specified, storage is read directly from Postgres, which is slower but doesn't require disk space.
+    #[arg(long, requires = "re_execute")]
+    rocksdb_path: Option<String>,
+    /// Number of L1 batches re-executed concurrently.
+    #[arg(long, default_value_t = 1)]
+    window_size: u32,
+    /// Discard progress persisted by a previous re-execution run and process the whole range.
+    #[arg(long, requires = "re_execute")]
+    restart: bool,
+}
+
+impl Cli {
+    async fn run(self, pool: ConnectionPool<Core>, genesis: &GenesisConfig) -> anyhow::Result<()> {
+        if self.re_execute {
+            return self.re_execute(pool, genesis).await;
+        }
+
+        let tree = if self.compare_tree {
+            let db_config = DBConfig::from_env().context("DBConfig::from_env()")?;
+            let db_path = &db_config.merkle_tree.path;
+            tracing::info!("Opening Merkle tree at {db_path}");
+            let db = RocksDB::new(Path::new(db_path))
+                .context("failed initializing Merkle tree RocksDB")?;
+            Some(ZkSyncTreeReader::new(db.into()).context("cannot initialize Merkle tree")?)
+        } else {
+            None
+        };
+
+        let (from_batch, to_batch) = self.batch_range(&pool).await?;
+
+        let mut recalculator =
+            MetadataRecalculator::new(pool, genesis.l1_batch_commit_data_generator_mode);
+        recalculator.set_repair(self.repair);
+
+        let mut failed_batch_count = 0;
+        for number in from_batch.0..=to_batch.0 {
+            let number = L1BatchNumber(number);
+            let tree_data = tree.as_ref().and_then(|tree| {
+                let (hash, leaf_count) = tree.root_info(number)?;
+                Some(L1BatchTreeData {
+                    hash,
+                    rollup_last_leaf_index: leaf_count + 1,
+                })
+            });
+            if tree.is_some() && tree_data.is_none() {
+                println!("L1 batch #{number}: missing in the Merkle tree");
+                failed_batch_count += 1;
+            }
+
+            let check = recalculator.check_batch(number, tree_data).await?;
+            print_check(&check);
+            let has_unrepaired_mismatches = !check.tree_mismatches.is_empty()
+                || (!check.commitment_mismatches.is_empty() && !check.repaired);
+            if has_unrepaired_mismatches {
+                failed_batch_count += 1;
+            }
+        }
+
+        anyhow::ensure!(
+            failed_batch_count == 0,
+            "found {failed_batch_count} issue(s) with metadata for L1 batches #{from_batch}..=#{to_batch}"
+        );
+        Ok(())
+    }
+
+    async fn batch_range(
+        &self,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<(L1BatchNumber, L1BatchNumber)> {
+        let from_batch = L1BatchNumber(self.from_batch);
+        let to_batch = if let Some(number) = self.to_batch {
+            L1BatchNumber(number)
+        } else {
+            let mut connection = pool.connection().await?;
+            connection
+                .blocks_dal()
+                .get_sealed_l1_batch_number()
+                .await?
+                .context("no sealed L1 batches in Postgres")?
+        };
+        anyhow::ensure!(
+            from_batch <= to_batch,
+            "invalid L1 batch range: #{from_batch}..=#{to_batch}"
+        );
+        Ok((from_batch, to_batch))
+    }
+
+    async fn re_execute(
+        self,
+        pool: ConnectionPool<Core>,
+        genesis: &GenesisConfig,
+    ) -> anyhow::Result<()> {
+        let (from_batch, to_batch) = self.batch_range(&pool).await?;
+        anyhow::ensure!(
+            from_batch > L1BatchNumber(0),
+            "genesis L1 batch cannot be re-executed"
+        );
+        if self.restart {
+            CommitmentRecomputer::reset_progress(&pool).await?;
+        }
+
+        let (report_sender, mut report_receiver) = mpsc::unbounded_channel();
+        let (recomputer, tasks) = CommitmentRecomputer::new(
+            pool,
+            self.rocksdb_path.map_or(
+                VmRunnerStorageBackend::PostgresOnly,
+                VmRunnerStorageBackend::Rocksdb,
+            ),
+            genesis.l2_chain_id,
+            genesis.evm_emulator_hash,
+            genesis.l1_batch_commit_data_generator_mode,
+            from_batch - 1,
+            to_batch,
+            self.window_size,
+            Some(report_sender),
+        )
+        .await?;
+
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let loader_task = tokio::spawn(tasks.loader_task.run(stop_receiver.clone()));
+        let output_handler_factory_task =
+            tokio::spawn(tasks.output_handler_factory_task.run(stop_receiver.clone()));
+        let (recomputer_stop_sender, recomputer_stop_receiver) = watch::channel(false);
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                tracing::info!("Received stop signal");
+                recomputer_stop_sender.send_replace(true);
+            }
+        });
+
+        let mut failed_batch_count = 0;
+        let recomputer_result = {
+            let recomputer = recomputer.run(&recomputer_stop_receiver);
+            tokio::pin!(recomputer);
+            loop {
+                tokio::select! {
+                    res = &mut recomputer => break res,
+                    Some(report) = report_receiver.recv() => {
+                        if !report.is_empty() {
+                            failed_batch_count += 1;
+                        }
+                        print_report(&report);
+                    }
+                }
+            }
+        };
+        // Print reports that were sent after the recomputer has returned.
+        while let Ok(report) = report_receiver.try_recv() {
+            if !report.is_empty() {
+                failed_batch_count += 1;
+            }
+            print_report(&report);
+        }
+
+        stop_sender.send_replace(true);
+        loader_task
+            .await
+            .context("loader task panicked")?
+            .context("loader task failed")?;
+        output_handler_factory_task
+            .await
+            .context("output handler factory task panicked")?
+            .context("output handler factory task failed")?;
+        recomputer_result?;
+
+        anyhow::ensure!(
+            failed_batch_count == 0,
+            "found {failed_batch_count} issue(s) with re-executed L1 batches #{from_batch}..=#{to_batch}"
+        );
+        Ok(())
+    }
+}
+
+fn print_report(report: &CommitmentRecomputationReport) {
+    let number = report.l1_batch_number;
+    if let Some(reason) = &report.skipped_commitment_reason {
+        println!("L1 batch #{number}: commitments not recomputed, {reason}");
+    }
+    if report.is_empty() {
+        println!("L1 batch #{number}: OK");
+        return;
+    }
+    println!("L1 batch #{number}: re-execution mismatch");
+    for mismatch in &report.mismatches {
+        println!("  {mismatch}");
+    }
+}
+
+fn print_check(check: &L1BatchMetadataCheck) {
+    let number = check.l1_batch_number;
+    if let Some(reason) = &check.incomplete_reason {
+        println!("L1 batch #{number}: not checked, {reason}");
+        return;
+    }
+    if check.is_ok() {
+        println!("L1 batch #{number}: OK");
+        return;
+    }
+
+    let committed = if check.committed_on_l1 {
+        " (committed on L1)"
+    } else {
+        ""
+    };
+    println!("L1 batch #{number}{committed}: metadata mismatch");
+    for mismatch in &check.tree_mismatches {
+        println!("  tree {mismatch}");
+    }
+    for mismatch in &check.commitment_mismatches {
+        println!("  {mismatch}");
+    }
+    if check.repaired {
+        println!("  repaired commitment artifacts");
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opts = Cli::parse();
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+    let _guard = vlog::ObservabilityBuilder::new()
+        .with_log_format(log_format)
+        .build();
+
+    let genesis = GenesisConfig::from_env().context("GenesisConfig::from_env()")?;
+    let database_secrets = DatabaseSecrets::from_env().context("DatabaseSecrets::from_env()")?;
+    let max_connections = if opts.re_execute {
+        RE_EXECUTION_CONNECTIONS
+    } else {
+        1
+    };
+    let pool = ConnectionPool::<Core>::builder(database_secrets.master_url()?, max_connections)
+        .build()
+        .await
+        .context("failed to build a connection pool")?;
+    opts.run(pool, &genesis).await
+}
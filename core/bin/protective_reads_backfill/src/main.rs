@@ -0,0 +1,117 @@
+//! Utility backfilling protective reads for a range of sealed L1 batches by re-executing them in the VM.
+//!
+//! The utility is intended to be run against a Postgres replica alongside a running node. To avoid starving
+//! other components of Postgres connections, it uses a small dedicated connection pool, and the rate
+//! of processing batches can be limited.
+
+use std::num::NonZeroU32;
+
+use anyhow::Context as _;
+use clap::Parser;
+use tokio::sync::watch;
+use zksync_config::configs::{chain::NetworkConfig, DatabaseSecrets, ObservabilityConfig};
+use zksync_dal::{ConnectionPool, Core};
+use zksync_env_config::FromEnv;
+use zksync_types::{L1BatchNumber, H256};
+use zksync_vm_runner::{ProtectiveReadsBackfill, VmRunnerStorageBackend};
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "Protective reads backfill utility",
+    long_about = None
+)]
+struct Cli {
+    /// First L1 batch to process (inclusive).
+    #[arg(long)]
+    from_batch: u32,
+    /// Last L1 batch to process (inclusive).
+    #[arg(long)]
+    to_batch: u32,
+    /// Maximum number of L1 batches processed per minute. If not specified, the rate is not limited.
+    #[arg(long)]
+    max_batches_per_minute: Option<NonZeroU32>,
+    /// Path to the RocksDB cache used by the utility. Must not be shared with the protective reads writer
+    /// or other components. If not specified, storage is read directly from Postgres, which is slower
+    /// but doesn't require disk space; this is recommended for short ranges.
+    #[arg(long)]
+    rocksdb_path: Option<String>,
+    /// Hash of the EVM emulator bytecode, if the EVM emulator is enabled on the chain. Must match
+    /// `evm_emulator_hash` in the genesis config.
+    #[arg(long)]
+    evm_emulator_hash: Option<H256>,
+    /// Number of L1 batches processed concurrently.
+    #[arg(long, default_value_t = 1)]
+    window_size: u32,
+    /// Maximum number of Postgres connections used by the utility.
+    #[arg(long, default_value_t = 3)]
+    max_connections: u32,
+}
+
+impl Cli {
+    async fn run(self, pool: ConnectionPool<Core>, network: &NetworkConfig) -> anyhow::Result<()> {
+        let batch_range = L1BatchNumber(self.from_batch)..=L1BatchNumber(self.to_batch);
+        let (backfill, tasks) = ProtectiveReadsBackfill::new(
+            pool,
+            self.rocksdb_path.map_or(
+                VmRunnerStorageBackend::PostgresOnly,
+                VmRunnerStorageBackend::Rocksdb,
+            ),
+            network.zksync_network_id,
+            self.evm_emulator_hash,
+            batch_range,
+            self.window_size,
+            self.max_batches_per_minute,
+        )
+        .await?;
+
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let loader_task = tokio::spawn(tasks.loader_task.run(stop_receiver.clone()));
+        let output_handler_factory_task =
+            tokio::spawn(tasks.output_handler_factory_task.run(stop_receiver.clone()));
+
+        let (backfill_stop_sender, backfill_stop_receiver) = watch::channel(false);
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                tracing::info!("Received stop signal");
+                backfill_stop_sender.send_replace(true);
+            }
+        });
+        let backfill_result = backfill.run(&backfill_stop_receiver).await;
+
+        stop_sender.send_replace(true);
+        loader_task
+            .await
+            .context("loader task panicked")?
+            .context("loader task failed")?;
+        output_handler_factory_task
+            .await
+            .context("output handler factory task panicked")?
+            .context("output handler factory task failed")?;
+        backfill_result
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opts = Cli::parse();
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+    let _guard = vlog::ObservabilityBuilder::new()
+        .with_log_format(log_format)
+        .build();
+
+    let network = NetworkConfig::from_env().context("NetworkConfig::from_env()")?;
+    let database_secrets = DatabaseSecrets::from_env().context("DatabaseSecrets::from_env()")?;
+    let pool =
+        ConnectionPool::<Core>::builder(database_secrets.replica_url()?, opts.max_connections)
+            .build()
+            .await
+            .context("failed to build a connection pool")?;
+    opts.run(pool, &network).await
+}
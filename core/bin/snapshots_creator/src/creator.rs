@@ -188,9 +188,11 @@ impl SnapshotCreator {
     }
 
     /// Returns `Ok(None)` if the created snapshot would coincide with `latest_snapshot`.
+    /// If `max_l1_batch_number` is specified, the snapshot L1 batch will not exceed it.
     async fn initialize_snapshot_progress(
         config: &SnapshotsCreatorConfig,
         min_chunk_count: u64,
+        max_l1_batch_number: Option<L1BatchNumber>,
         latest_snapshot: Option<&SnapshotMetadata>,
         conn: &mut Connection<'_, Core>,
     ) -> anyhow::Result<Option<SnapshotProgress>> {
@@ -201,7 +203,10 @@ impl SnapshotCreator {
             sealed_l1_batch_number != L1BatchNumber(0),
             "Cannot create snapshot when only the genesis L1 batch is present in Postgres"
         );
-        let l1_batch_number = sealed_l1_batch_number - 1;
+        let mut l1_batch_number = sealed_l1_batch_number - 1;
+        if let Some(max_l1_batch_number) = max_l1_batch_number {
+            l1_batch_number = l1_batch_number.min(max_l1_batch_number);
+        }
 
         // Sanity check: the selected L1 batch should have Merkle tree data; otherwise, it could be impossible
         // to recover from the generated snapshot.
@@ -246,6 +251,7 @@ impl SnapshotCreator {
         &self,
         config: &SnapshotsCreatorConfig,
         min_chunk_count: u64,
+        max_l1_batch_number: Option<L1BatchNumber>,
     ) -> anyhow::Result<Option<SnapshotProgress>> {
         let mut master_conn = self
             .master_pool
@@ -266,6 +272,7 @@ impl SnapshotCreator {
             Self::initialize_snapshot_progress(
                 config,
                 min_chunk_count,
+                max_l1_batch_number,
                 latest_snapshot.as_ref(),
                 &mut self.connect_to_replica().await?,
             )
@@ -273,6 +280,7 @@ impl SnapshotCreator {
         }
     }
 
+    /// Creates a single snapshot (or resumes creating a pending snapshot).
     pub async fn run(
         self,
         config: SnapshotsCreatorConfig,
@@ -282,14 +290,25 @@ impl SnapshotCreator {
             "Starting snapshot creator with object store {:?} and config {config:?}",
             self.blob_store
         );
+        self.create_snapshot(&config, min_chunk_count, None).await?;
+        Ok(())
+    }
+
+    /// Returns the L1 batch of the created snapshot, or `None` if no snapshot was created.
+    pub(crate) async fn create_snapshot(
+        &self,
+        config: &SnapshotsCreatorConfig,
+        min_chunk_count: u64,
+        max_l1_batch_number: Option<L1BatchNumber>,
+    ) -> anyhow::Result<Option<L1BatchNumber>> {
         let latency = METRICS.snapshot_generation_duration.start();
 
         let Some(progress) = self
-            .load_or_initialize_snapshot_progress(&config, min_chunk_count)
+            .load_or_initialize_snapshot_progress(config, min_chunk_count, max_l1_batch_number)
             .await?
         else {
             // No snapshot creation is necessary; a snapshot for the current L1 batch is already created
-            return Ok(());
+            return Ok(None);
         };
 
         let mut conn = self.connect_to_replica().await?;
@@ -353,6 +372,6 @@ impl SnapshotCreator {
             "storage_logs_chunks_count: {}",
             METRICS.storage_logs_chunks_count.get()
         );
-        Ok(())
+        Ok(Some(progress.l1_batch_number))
     }
 }
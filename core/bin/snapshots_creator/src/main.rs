@@ -1,4 +1,7 @@
 //! Snapshot creator utility. Intended to run on a schedule, with each run creating a new snapshot.
+//! Alternatively, the creator can run continuously, creating snapshots according to the policy specified
+//! in its config (every N L1 batches executed on L1, within a low API load window, and / or on demand
+//! via the `admin_requestSnapshot` RPC method).
 //!
 //! # Assumptions
 //!
@@ -20,10 +23,11 @@ use zksync_dal::{ConnectionPool, Core};
 use zksync_env_config::{object_store::SnapshotsObjectStoreConfig, FromEnv};
 use zksync_object_store::ObjectStoreFactory;
 
-use crate::creator::SnapshotCreator;
+use crate::{creator::SnapshotCreator, schedule::SnapshotSchedule};
 
 mod creator;
 mod metrics;
+mod schedule;
 #[cfg(test)]
 mod tests;
 
@@ -60,7 +64,7 @@ async fn main() -> anyhow::Result<()> {
         .parse()
         .context("Invalid log format")?;
 
-    let prometheus_exporter_task = maybe_enable_prometheus_metrics(stop_receiver).await?;
+    let prometheus_exporter_task = maybe_enable_prometheus_metrics(stop_receiver.clone()).await?;
     let mut builder = vlog::ObservabilityBuilder::new().with_log_format(log_format);
     if let Some(sentry_url) = observability_config.sentry_url {
         builder = builder
@@ -99,7 +103,25 @@ async fn main() -> anyhow::Result<()> {
         #[cfg(test)]
         event_listener: Box::new(()),
     };
-    creator.run(creator_config, MIN_CHUNK_COUNT).await?;
+    if let Some(schedule) = SnapshotSchedule::new(&creator_config) {
+        let (creator_stop_sender, creator_stop_receiver) = watch::channel(false);
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                tracing::info!("Received stop signal");
+                creator_stop_sender.send_replace(true);
+            }
+        });
+        creator
+            .run_on_schedule(
+                creator_config,
+                schedule,
+                MIN_CHUNK_COUNT,
+                creator_stop_receiver,
+            )
+            .await?;
+    } else {
+        creator.run(creator_config, MIN_CHUNK_COUNT).await?;
+    }
 
     tracing::info!("Finished running snapshot creator!");
     stop_sender.send(true).ok();
@@ -0,0 +1,164 @@
+//! Scheduled mode of the snapshot creator.
+
+use std::time::Duration;
+
+use tokio::sync::watch;
+use zksync_config::SnapshotsCreatorConfig;
+use zksync_dal::CoreDal;
+use zksync_types::L1BatchNumber;
+use zksync_utils::time::seconds_since_epoch;
+
+use crate::creator::SnapshotCreator;
+
+/// Policy determining when snapshots are created in the scheduled mode.
+#[derive(Debug)]
+pub(crate) struct SnapshotSchedule {
+    poll_interval: Duration,
+    l1_batch_interval: Option<u32>,
+    low_load_window_utc: Option<(u32, u32)>,
+}
+
+impl SnapshotSchedule {
+    /// Returns `None` if the scheduled mode is disabled in the config.
+    pub fn new(config: &SnapshotsCreatorConfig) -> Option<Self> {
+        Some(Self {
+            poll_interval: config.schedule_poll_interval()?,
+            l1_batch_interval: config.l1_batch_interval,
+            low_load_window_utc: config.low_load_window_utc(),
+        })
+    }
+
+    pub(crate) fn is_within_low_load_window(&self, unix_timestamp: u64) -> bool {
+        let Some((start_hour, end_hour)) = self.low_load_window_utc else {
+            return true;
+        };
+        let hour = ((unix_timestamp / 3_600) % 24) as u32;
+        if start_hour <= end_hour {
+            (start_hour..end_hour).contains(&hour)
+        } else {
+            // The window wraps around midnight.
+            hour >= start_hour || hour < end_hour
+        }
+    }
+
+    pub(crate) fn is_snapshot_due(
+        &self,
+        last_executed_l1_batch: L1BatchNumber,
+        latest_snapshot_l1_batch: Option<L1BatchNumber>,
+    ) -> bool {
+        let Some(l1_batch_interval) = self.l1_batch_interval else {
+            return false;
+        };
+        latest_snapshot_l1_batch.map_or(true, |latest| {
+            last_executed_l1_batch.0 >= latest.0.saturating_add(l1_batch_interval)
+        })
+    }
+}
+
+impl SnapshotCreator {
+    /// Creates a snapshot if it's required by `schedule` or if a snapshot was requested on demand.
+    /// Returns the L1 batch of the created snapshot, or `None` if no snapshot was created.
+    pub(crate) async fn create_snapshot_if_due(
+        &self,
+        config: &SnapshotsCreatorConfig,
+        schedule: &SnapshotSchedule,
+        min_chunk_count: u64,
+        unix_timestamp: u64,
+    ) -> anyhow::Result<Option<L1BatchNumber>> {
+        let mut master_conn = self
+            .master_pool
+            .connection_tagged("snapshots_creator")
+            .await?;
+        let pending_request_id = master_conn
+            .snapshots_dal()
+            .get_latest_pending_snapshot_request()
+            .await?;
+        let latest_snapshot = master_conn
+            .snapshots_dal()
+            .get_newest_snapshot_metadata()
+            .await?;
+        drop(master_conn);
+
+        // Scheduled snapshots are tied to L1 batch finality; we never create them for batches not executed on L1.
+        let last_executed_l1_batch = self
+            .replica_pool
+            .connection_tagged("snapshots_creator")
+            .await?
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await?;
+
+        let should_create = if let Some(request_id) = pending_request_id {
+            tracing::info!("Creating snapshot requested on demand (request #{request_id})");
+            true
+        } else if !schedule.is_within_low_load_window(unix_timestamp) {
+            false
+        } else if let Some(snapshot) = latest_snapshot.as_ref().filter(|s| !s.is_complete()) {
+            tracing::info!(
+                "Resuming creating snapshot for L1 batch #{}",
+                snapshot.l1_batch_number
+            );
+            true
+        } else if let Some(last_executed_l1_batch) = last_executed_l1_batch {
+            let latest_snapshot_l1_batch = latest_snapshot.as_ref().map(|s| s.l1_batch_number);
+            schedule.is_snapshot_due(last_executed_l1_batch, latest_snapshot_l1_batch)
+        } else {
+            false
+        };
+        if !should_create {
+            return Ok(None);
+        }
+
+        let snapshot_l1_batch = self
+            .create_snapshot(config, min_chunk_count, last_executed_l1_batch)
+            .await?;
+        if let Some(request_id) = pending_request_id {
+            self.master_pool
+                .connection_tagged("snapshots_creator")
+                .await?
+                .snapshots_dal()
+                .mark_snapshot_requests_as_processed(request_id)
+                .await?;
+        }
+        Ok(snapshot_l1_batch)
+    }
+
+    /// Runs the creator in the scheduled mode until a stop signal is received. Snapshot creation is interrupted
+    /// on stop; it will be resumed after the restart.
+    pub async fn run_on_schedule(
+        self,
+        config: SnapshotsCreatorConfig,
+        schedule: SnapshotSchedule,
+        min_chunk_count: u64,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        tracing::info!(
+            "Starting snapshot creator with object store {:?}, config {config:?} and schedule {schedule:?}",
+            self.blob_store
+        );
+
+        while !*stop_receiver.borrow_and_update() {
+            let creation = self.create_snapshot_if_due(
+                &config,
+                &schedule,
+                min_chunk_count,
+                seconds_since_epoch(),
+            );
+            tokio::select! {
+                res = creation => {
+                    if let Some(l1_batch_number) = res? {
+                        tracing::info!("Created snapshot for L1 batch #{l1_batch_number}");
+                    }
+                }
+                _ = stop_receiver.changed() => break,
+            }
+
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(schedule.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        tracing::info!("Stop signal received, snapshot creator is shutting down");
+        Ok(())
+    }
+}
@@ -23,16 +23,21 @@ use zksync_types::{
 };
 
 use super::*;
+use crate::schedule::SnapshotSchedule;
 
 const TEST_CONFIG: SnapshotsCreatorConfig = SnapshotsCreatorConfig {
     storage_logs_chunk_size: 1_000_000,
     concurrent_queries_count: 10,
     object_store: None,
+    schedule_poll_interval_ms: None,
+    l1_batch_interval: None,
+    low_load_window_start_hour_utc: None,
+    low_load_window_end_hour_utc: None,
 };
 const SEQUENTIAL_TEST_CONFIG: SnapshotsCreatorConfig = SnapshotsCreatorConfig {
     storage_logs_chunk_size: 1_000_000,
     concurrent_queries_count: 1,
-    object_store: None,
+    ..TEST_CONFIG
 };
 
 #[derive(Debug)]
@@ -458,3 +463,82 @@ async fn recovery_workflow_with_varying_chunk_size() {
 
     assert_storage_logs(&*object_store, snapshot_l1_batch_number, &expected_outputs).await;
 }
+
+#[test]
+fn snapshot_schedule_policy() {
+    let config = SnapshotsCreatorConfig {
+        schedule_poll_interval_ms: Some(1_000),
+        l1_batch_interval: Some(10),
+        low_load_window_start_hour_utc: Some(22),
+        low_load_window_end_hour_utc: Some(4),
+        ..TEST_CONFIG
+    };
+    let schedule = SnapshotSchedule::new(&config).unwrap();
+    const HOUR: u64 = 3_600;
+    assert!(schedule.is_within_low_load_window(23 * HOUR));
+    assert!(schedule.is_within_low_load_window(24 * HOUR + 3 * HOUR));
+    assert!(!schedule.is_within_low_load_window(4 * HOUR));
+    assert!(!schedule.is_within_low_load_window(12 * HOUR + 30));
+
+    assert!(schedule.is_snapshot_due(L1BatchNumber(1), None));
+    assert!(!schedule.is_snapshot_due(L1BatchNumber(19), Some(L1BatchNumber(10))));
+    assert!(schedule.is_snapshot_due(L1BatchNumber(20), Some(L1BatchNumber(10))));
+
+    let config = SnapshotsCreatorConfig {
+        low_load_window_start_hour_utc: Some(1),
+        l1_batch_interval: None,
+        ..config
+    };
+    let schedule = SnapshotSchedule::new(&config).unwrap();
+    assert!(schedule.is_within_low_load_window(HOUR));
+    assert!(!schedule.is_within_low_load_window(0));
+    assert!(!schedule.is_within_low_load_window(4 * HOUR));
+    assert!(!schedule.is_snapshot_due(L1BatchNumber(100), None));
+
+    assert!(SnapshotSchedule::new(&TEST_CONFIG).is_none());
+}
+
+#[tokio::test]
+async fn creating_snapshot_on_demand() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store = MockObjectStore::arc();
+    let mut conn = pool.connection().await.unwrap();
+    prepare_postgres(&mut rng, &mut conn, 10).await;
+
+    let config = SnapshotsCreatorConfig {
+        schedule_poll_interval_ms: Some(1_000),
+        l1_batch_interval: Some(1),
+        ..TEST_CONFIG
+    };
+    let schedule = SnapshotSchedule::new(&config).unwrap();
+    let creator = SnapshotCreator::for_tests(object_store, pool.clone());
+    // No L1 batches are executed, so no snapshot should be created.
+    let snapshot_l1_batch = creator
+        .create_snapshot_if_due(&config, &schedule, MIN_CHUNK_COUNT, 0)
+        .await
+        .unwrap();
+    assert_eq!(snapshot_l1_batch, None);
+
+    let request_id = conn.snapshots_dal().request_snapshot("test").await.unwrap();
+    let snapshot_l1_batch = creator
+        .create_snapshot_if_due(&config, &schedule, MIN_CHUNK_COUNT, 0)
+        .await
+        .unwrap();
+    assert_eq!(snapshot_l1_batch, Some(L1BatchNumber(8)));
+    let snapshots = conn
+        .snapshots_dal()
+        .get_all_complete_snapshots()
+        .await
+        .unwrap();
+    assert_eq!(snapshots.snapshots_l1_batch_numbers, [L1BatchNumber(8)]);
+    let pending_request_id = conn
+        .snapshots_dal()
+        .get_latest_pending_snapshot_request()
+        .await
+        .unwrap();
+    assert_eq!(
+        pending_request_id, None,
+        "request #{request_id} was not processed"
+    );
+}
@@ -0,0 +1,206 @@
+//! Utility converting the largest Postgres tables (`events` and `storage_logs`) to tables partitioned by L2 block ranges.
+//!
+//! Conversion is performed online: data is copied in chunks to a partitioned shadow table while the node is running,
+//! and the tables are swapped in a short transaction afterwards. The conversion can be interrupted and resumed
+//! at any time before the swap. Postgres pruning and block reverts must be disabled for the duration of the conversion.
+//! After the swap, partitions for new L2 blocks are created by the house keeper.
+
+use anyhow::Context as _;
+use clap::{Parser, Subcommand, ValueEnum};
+use zksync_config::configs::{
+    house_keeper::HouseKeeperConfig, DatabaseSecrets, ObservabilityConfig,
+};
+use zksync_dal::{
+    partitioning_dal::{PartitionedTable, PartitioningStatus},
+    Connection, ConnectionPool, Core, CoreDal,
+};
+use zksync_env_config::FromEnv;
+use zksync_types::L2BlockNumber;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Table {
+    Events,
+    StorageLogs,
+}
+
+impl From<Table> for PartitionedTable {
+    fn from(table: Table) -> Self {
+        match table {
+            Table::Events => Self::Events,
+            Table::StorageLogs => Self::StorageLogs,
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+#[command(author = "Matter Labs", version, about = "Postgres table partitioning utility", long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Displays partitioning status of all supported tables.
+    Status,
+    /// Copies data of the table to a partitioned shadow table and optionally swaps the tables.
+    Convert {
+        #[arg(long, value_enum)]
+        table: Table,
+        /// Number of L2 blocks copied in a single chunk.
+        #[arg(long, default_value_t = 1_000)]
+        chunk_size: u32,
+        /// Number of L2 blocks in a single partition. If not specified, taken from the house keeper config.
+        #[arg(long)]
+        l2_blocks_per_partition: Option<u32>,
+        /// Swap the tables after copying. Writes to the table are blocked while the last chunk is copied,
+        /// which usually takes a few seconds.
+        #[arg(long)]
+        swap: bool,
+    },
+    /// Drops the original unpartitioned table retained after conversion.
+    DropUnpartitioned {
+        #[arg(long, value_enum)]
+        table: Table,
+    },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opts = Cli::parse();
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+    let _guard = vlog::ObservabilityBuilder::new()
+        .with_log_format(log_format)
+        .build();
+
+    let database_secrets = DatabaseSecrets::from_env().context("DatabaseSecrets::from_env()")?;
+    let connection_pool = ConnectionPool::<Core>::singleton(database_secrets.master_url()?)
+        .build()
+        .await
+        .context("failed to build a connection pool")?;
+    let mut conn = connection_pool.connection().await?;
+
+    match opts.command {
+        Command::Status => {
+            for table in PartitionedTable::ALL {
+                let status = conn.partitioning_dal().get_status(table).await?;
+                println!("{table}: {status:?}");
+            }
+        }
+        Command::Convert {
+            table,
+            chunk_size,
+            l2_blocks_per_partition,
+            swap,
+        } => {
+            anyhow::ensure!(chunk_size > 0, "chunk size must be positive");
+            let l2_blocks_per_partition = match l2_blocks_per_partition {
+                Some(size) => size,
+                None => HouseKeeperConfig::from_env()
+                    .context("HouseKeeperConfig::from_env()")?
+                    .l2_blocks_per_partition
+                    .context("partition size is not specified")?,
+            };
+            anyhow::ensure!(
+                l2_blocks_per_partition > 0,
+                "partition size must be positive"
+            );
+            convert(
+                &mut conn,
+                table.into(),
+                chunk_size,
+                l2_blocks_per_partition,
+                swap,
+            )
+            .await?;
+        }
+        Command::DropUnpartitioned { table } => {
+            let table = PartitionedTable::from(table);
+            let status = conn.partitioning_dal().get_status(table).await?;
+            anyhow::ensure!(
+                status == PartitioningStatus::Partitioned,
+                "table `{table}` is not partitioned yet"
+            );
+            conn.partitioning_dal()
+                .drop_unpartitioned_table(table)
+                .await?;
+            tracing::info!("Dropped unpartitioned table `{table}`");
+        }
+    }
+    Ok(())
+}
+
+async fn convert(
+    conn: &mut Connection<'_, Core>,
+    table: PartitionedTable,
+    chunk_size: u32,
+    l2_blocks_per_partition: u32,
+    swap: bool,
+) -> anyhow::Result<()> {
+    let copied_up_to = match conn.partitioning_dal().get_status(table).await? {
+        PartitioningStatus::Partitioned => {
+            tracing::info!("Table `{table}` is already partitioned");
+            return Ok(());
+        }
+        PartitioningStatus::Unpartitioned => None,
+        PartitioningStatus::Converting { copied_up_to } => {
+            tracing::info!(
+                "Resuming conversion of table `{table}` after L2 block {copied_up_to:?}"
+            );
+            copied_up_to
+        }
+    };
+
+    // The last copied chunk is copied again since it may be incomplete if the conversion was interrupted.
+    let mut next_l2_block = copied_up_to.map_or(0, |number| number.0);
+    loop {
+        // New L2 blocks are sealed while copying, so the sealed L2 block is re-read on each iteration.
+        let sealed_l2_block = get_sealed_l2_block(conn).await?;
+        if next_l2_block > sealed_l2_block.0 {
+            break;
+        }
+        let chunk_end = next_l2_block
+            .saturating_add(chunk_size - 1)
+            .min(sealed_l2_block.0);
+        let chunk = L2BlockNumber(next_l2_block)..=L2BlockNumber(chunk_end);
+        // Partitions must exist before copying; otherwise, rows would end up in the default partition.
+        conn.partitioning_dal()
+            .create_shadow_table(table, *chunk.end(), l2_blocks_per_partition)
+            .await?;
+        let copied_rows = conn
+            .partitioning_dal()
+            .copy_chunk(table, chunk.clone())
+            .await?;
+        tracing::info!(
+            "Copied {copied_rows} rows for L2 blocks {chunk:?} to the shadow table for `{table}`"
+        );
+        next_l2_block = chunk_end + 1;
+    }
+
+    if swap {
+        let copied_up_to = L2BlockNumber(next_l2_block.saturating_sub(1));
+        let copied_rows = conn
+            .partitioning_dal()
+            .swap_tables(table, copied_up_to, l2_blocks_per_partition)
+            .await?;
+        tracing::info!(
+            "Copied {copied_rows} remaining rows and swapped tables; `{table}` is now partitioned"
+        );
+    } else {
+        tracing::info!("Copied `{table}` data; run with `--swap` to finish the conversion");
+    }
+    Ok(())
+}
+
+async fn get_sealed_l2_block(conn: &mut Connection<'_, Core>) -> anyhow::Result<L2BlockNumber> {
+    Ok(conn
+        .blocks_dal()
+        .get_sealed_l2_block_number()
+        .await?
+        .unwrap_or_default())
+}
@@ -0,0 +1,137 @@
+//! Utility re-executing a range of sealed L1 batches in the VM and comparing execution results with the data
+//! stored in Postgres. Intended to audit DB integrity after incidents; the utility doesn't write to Postgres.
+//!
+//! Divergences are logged for each batch; the utility exits with an error if any divergence was found.
+
+use anyhow::Context as _;
+use clap::Parser;
+use tokio::sync::{mpsc, watch};
+use zksync_config::configs::{chain::NetworkConfig, DatabaseSecrets, ObservabilityConfig};
+use zksync_dal::{ConnectionPool, Core};
+use zksync_env_config::FromEnv;
+use zksync_types::{L1BatchNumber, H256};
+use zksync_vm_runner::{DryRunVmRunner, VmRunnerStorageBackend};
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "Dry-run VM runner auditing stored L1 batch data",
+    long_about = None
+)]
+struct Cli {
+    /// First L1 batch to check (inclusive). Must be positive.
+    #[arg(long)]
+    from_batch: u32,
+    /// Last L1 batch to check (inclusive). If not specified, the utility keeps checking new batches
+    /// until it is stopped.
+    #[arg(long)]
+    to_batch: Option<u32>,
+    /// Path to the RocksDB cache used by the utility. Must not be shared with other components.
+    /// If not specified, storage is read directly from Postgres, which is slower but doesn't require disk space.
+    #[arg(long)]
+    rocksdb_path: Option<String>,
+    /// Hash of the EVM emulator bytecode, if the EVM emulator is enabled on the chain. Must match
+    /// `evm_emulator_hash` in the genesis config.
+    #[arg(long)]
+    evm_emulator_hash: Option<H256>,
+    /// Number of L1 batches processed concurrently.
+    #[arg(long, default_value_t = 1)]
+    window_size: u32,
+    /// Maximum number of Postgres connections used by the utility.
+    #[arg(long, default_value_t = 3)]
+    max_connections: u32,
+}
+
+impl Cli {
+    async fn run(self, pool: ConnectionPool<Core>, network: &NetworkConfig) -> anyhow::Result<()> {
+        anyhow::ensure!(self.from_batch > 0, "`from_batch` must be positive");
+        let first_processed_batch = L1BatchNumber(self.from_batch - 1);
+        let last_batch = self.to_batch.map(L1BatchNumber);
+        if let Some(last_batch) = last_batch {
+            anyhow::ensure!(
+                last_batch > first_processed_batch,
+                "invalid L1 batch range: #{}..=#{last_batch}",
+                self.from_batch
+            );
+        }
+
+        let (report_sender, mut report_receiver) = mpsc::unbounded_channel();
+        let (dry_runner, tasks) = DryRunVmRunner::new(
+            pool,
+            self.rocksdb_path.map_or(
+                VmRunnerStorageBackend::PostgresOnly,
+                VmRunnerStorageBackend::Rocksdb,
+            ),
+            network.zksync_network_id,
+            self.evm_emulator_hash,
+            first_processed_batch,
+            last_batch,
+            self.window_size,
+            Some(report_sender),
+        )
+        .await?;
+
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let loader_task = tokio::spawn(tasks.loader_task.run(stop_receiver.clone()));
+        let output_handler_factory_task =
+            tokio::spawn(tasks.output_handler_factory_task.run(stop_receiver.clone()));
+
+        let (dry_run_stop_sender, dry_run_stop_receiver) = watch::channel(false);
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                tracing::info!("Received stop signal");
+                dry_run_stop_sender.send_replace(true);
+            }
+        });
+        let dry_run_result = dry_runner.run(&dry_run_stop_receiver).await;
+
+        stop_sender.send_replace(true);
+        loader_task
+            .await
+            .context("loader task panicked")?
+            .context("loader task failed")?;
+        output_handler_factory_task
+            .await
+            .context("output handler factory task panicked")?
+            .context("output handler factory task failed")?;
+        dry_run_result?;
+
+        // Divergences are already logged by the runner; here, we only collect divergent batches.
+        let mut divergent_batches = vec![];
+        while let Ok(report) = report_receiver.try_recv() {
+            if !report.is_empty() {
+                divergent_batches.push(report.l1_batch_number);
+            }
+        }
+        divergent_batches.sort_unstable();
+        anyhow::ensure!(
+            divergent_batches.is_empty(),
+            "execution results diverge from the data stored in Postgres for L1 batches {divergent_batches:?}"
+        );
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opts = Cli::parse();
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+    let _guard = vlog::ObservabilityBuilder::new()
+        .with_log_format(log_format)
+        .build();
+
+    let network = NetworkConfig::from_env().context("NetworkConfig::from_env()")?;
+    let database_secrets = DatabaseSecrets::from_env().context("DatabaseSecrets::from_env()")?;
+    let pool =
+        ConnectionPool::<Core>::builder(database_secrets.replica_url()?, opts.max_connections)
+            .build()
+            .await
+            .context("failed to build a connection pool")?;
+    opts.run(pool, &network).await
+}
@@ -1,7 +1,7 @@
 use anyhow::Context as _;
 use zksync_config::configs::consensus::{ConsensusConfig, ConsensusSecrets};
-use zksync_core_leftovers::temp_config_store::decode_yaml_repr;
-use zksync_protobuf_config::proto;
+use zksync_core_leftovers::temp_config_store::{decode_yaml_repr, decode_yaml_repr_with_keystores};
+use zksync_protobuf_config::{keystore::KeystorePassphrase, proto};
 
 pub(crate) fn read_consensus_secrets() -> anyhow::Result<Option<ConsensusSecrets>> {
     // Read public config.
@@ -10,8 +10,11 @@ pub(crate) fn read_consensus_secrets() -> anyhow::Result<Option<ConsensusSecrets
     };
     let secrets = std::fs::read_to_string(&path).context(path)?;
     Ok(Some(
-        decode_yaml_repr::<proto::secrets::ConsensusSecrets>(&secrets)
-            .context("failed decoding YAML")?,
+        decode_yaml_repr_with_keystores::<proto::secrets::ConsensusSecrets>(
+            &secrets,
+            KeystorePassphrase::from_env,
+        )
+        .context("failed decoding YAML")?,
     ))
 }
 
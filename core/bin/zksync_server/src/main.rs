@@ -1,4 +1,4 @@
-use std::{str::FromStr, time::Duration};
+use std::{path::Path, str::FromStr, time::Duration};
 
 use anyhow::Context as _;
 use clap::Parser;
@@ -9,23 +9,31 @@ use zksync_config::{
             CircuitBreakerConfig, MempoolConfig, NetworkConfig, OperationsManagerConfig,
             StateKeeperConfig,
         },
+        consensus::ConsensusConfig,
         fri_prover_group::FriProverGroupConfig,
         house_keeper::HouseKeeperConfig,
-        ContractsConfig, DatabaseSecrets, FriProofCompressorConfig, FriProverConfig,
-        FriProverGatewayConfig, FriWitnessGeneratorConfig, FriWitnessVectorGeneratorConfig,
-        L1Secrets, ObservabilityConfig, PrometheusConfig, ProofDataHandlerConfig,
-        ProtectiveReadsWriterConfig, Secrets,
+        wallets::Wallets,
+        BasicWitnessInputProducerConfig, ContractsConfig, DatabaseSecrets, DiskSpaceMonitorConfig,
+        FeatureFlagsConfig, FriProofCompressorConfig, FriProverConfig, FriProverGatewayConfig,
+        FriWitnessGeneratorConfig, FriWitnessVectorGeneratorConfig, GeneralConfig, L1Secrets,
+        ObservabilityConfig, PrometheusConfig, ProofDataHandlerConfig, ProtectiveReadsWriterConfig,
+        SchedulerConfig, Secrets,
     },
     ApiConfig, ContractVerifierConfig, DBConfig, EthConfig, EthWatchConfig, GasAdjusterConfig,
     GenesisConfig, ObjectStoreConfig, PostgresConfig, SnapshotsCreatorConfig,
 };
+use zksync_contracts::BaseSystemContracts;
 use zksync_core_leftovers::{
-    genesis_init, initialize_components, is_genesis_needed, setup_sigint_handler,
-    temp_config_store::{decode_yaml_repr, TempConfigStore},
-    Component, Components,
+    config_requirements::LoadedConfigs,
+    genesis_init, initialize_components, is_genesis_needed,
+    preflight::{PreflightChecks, PreflightReport},
+    setup_sigint_handler,
+    temp_config_store::{decode_yaml_repr, decode_yaml_repr_with_keystores, TempConfigStore},
+    validate_base_system_contracts, Component, Components,
 };
 use zksync_env_config::FromEnv;
 use zksync_eth_client::clients::Client;
+use zksync_protobuf_config::keystore::KeystorePassphrase;
 use zksync_storage::RocksDB;
 use zksync_utils::wait_for_tasks::ManagedTasks;
 
@@ -46,7 +54,13 @@ struct Cli {
     /// Rebuild tree.
     #[arg(long)]
     rebuild_tree: bool,
-    /// Comma-separated list of components to launch.
+    /// Run preflight checks for the selected components, print the report and exit.
+    /// The checks are also run automatically before starting the components.
+    #[arg(long)]
+    preflight: bool,
+    /// Comma-separated list of components to launch. Besides individual components, aliases are supported:
+    /// `api` (HTTP, WS and contract verification APIs), `web3_api` (HTTP and WS APIs) and `eth` (all L1 interaction components).
+    /// Only config sections required by the selected components need to be present.
     #[arg(
         long,
         default_value = "api,tree,eth,state_keeper,housekeeper,tee_verifier_input_producer,commitment_generator"
@@ -81,7 +95,12 @@ impl FromStr for ComponentsToRun {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let components = s.split(',').try_fold(vec![], |mut acc, component_str| {
             let components = Components::from_str(component_str.trim())?;
-            acc.extend(components.0);
+            // Aliases may overlap with each other (e.g., `api,http_api`), so we deduplicate components.
+            for component in components.0 {
+                if !acc.contains(&component) {
+                    acc.push(component);
+                }
+            }
             Ok::<_, String>(acc)
         })?;
         Ok(Self(components))
@@ -140,8 +159,11 @@ async fn main() -> anyhow::Result<()> {
         Some(path) => {
             let yaml =
                 std::fs::read_to_string(&path).with_context(|| path.display().to_string())?;
-            decode_yaml_repr::<zksync_protobuf_config::proto::wallets::Wallets>(&yaml)
-                .context("failed decoding wallets YAML config")?
+            decode_yaml_repr_with_keystores::<zksync_protobuf_config::proto::wallets::Wallets>(
+                &yaml,
+                KeystorePassphrase::from_env,
+            )
+            .context("failed decoding wallets YAML config")?
         }
     };
 
@@ -149,8 +171,11 @@ async fn main() -> anyhow::Result<()> {
         Some(path) => {
             let yaml =
                 std::fs::read_to_string(&path).with_context(|| path.display().to_string())?;
-            decode_yaml_repr::<zksync_protobuf_config::proto::secrets::Secrets>(&yaml)
-                .context("failed decoding secrets YAML config")?
+            decode_yaml_repr_with_keystores::<zksync_protobuf_config::proto::secrets::Secrets>(
+                &yaml,
+                KeystorePassphrase::from_env,
+            )
+            .context("failed decoding secrets YAML config")?
         }
         None => Secrets {
             consensus: config::read_consensus_secrets().context("read_consensus_secrets()")?,
@@ -171,7 +196,7 @@ async fn main() -> anyhow::Result<()> {
         }
     };
 
-    let genesis = match opt.genesis_path {
+    let mut genesis = match opt.genesis_path {
         None => GenesisConfig::from_env().context("Genesis config")?,
         Some(path) => {
             let yaml =
@@ -181,12 +206,46 @@ async fn main() -> anyhow::Result<()> {
         }
     };
 
+    let base_system_contracts = load_base_system_contracts(&configs)?;
+    if let Some(contracts) = &base_system_contracts {
+        // Allows to omit hashes in the genesis config for overridden contracts; if the hashes are specified,
+        // they are checked during genesis.
+        let hashes = contracts.hashes();
+        genesis.bootloader_hash.get_or_insert(hashes.bootloader);
+        genesis.default_aa_hash.get_or_insert(hashes.default_aa);
+    }
+
+    let components = if opt.rebuild_tree {
+        vec![Component::Tree]
+    } else {
+        opt.components.0
+    };
+
+    if opt.preflight {
+        let report = run_preflight_checks(
+            &configs,
+            &secrets,
+            &wallets,
+            consensus.as_ref(),
+            &genesis,
+            &contracts_config,
+            &components,
+        )
+        .await;
+        anyhow::ensure!(!report.has_failures(), "preflight checks failed:\n{report}");
+        return Ok(());
+    }
+
     let database_secrets = secrets.database.clone().context("DatabaseSecrets")?;
 
     if opt.genesis || is_genesis_needed(&database_secrets).await {
-        genesis_init(genesis.clone(), &database_secrets)
-            .await
-            .context("genesis_init")?;
+        genesis_init(
+            genesis.clone(),
+            &database_secrets,
+            base_system_contracts.clone(),
+        )
+        .await
+        .context("genesis_init")?;
 
         if let Some(ecosystem_contracts) = &contracts_config.ecosystem_contracts {
             let l1_secrets = secrets.l1.as_ref().context("l1_screts")?;
@@ -209,11 +268,23 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
-    let components = if opt.rebuild_tree {
-        vec![Component::Tree]
-    } else {
-        opt.components.0
-    };
+    if let Some(contracts) = &base_system_contracts {
+        validate_base_system_contracts(&database_secrets, contracts)
+            .await
+            .context("validate_base_system_contracts")?;
+    }
+
+    let report = run_preflight_checks(
+        &configs,
+        &secrets,
+        &wallets,
+        consensus.as_ref(),
+        &genesis,
+        &contracts_config,
+        &components,
+    )
+    .await;
+    anyhow::ensure!(!report.has_failures(), "preflight checks failed:\n{report}");
 
     // If the node framework is used, run the node.
     if opt.use_node_framework {
@@ -278,6 +349,52 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn run_preflight_checks(
+    configs: &GeneralConfig,
+    secrets: &Secrets,
+    wallets: &Wallets,
+    consensus: Option<&ConsensusConfig>,
+    genesis: &GenesisConfig,
+    contracts: &ContractsConfig,
+    components: &[Component],
+) -> PreflightReport {
+    let configs = LoadedConfigs {
+        general: configs,
+        secrets,
+        wallets,
+        consensus,
+    };
+    let report = PreflightChecks::new(configs, genesis, contracts)
+        .run(components)
+        .await;
+    if report.has_failures() {
+        tracing::error!("Preflight checks report:\n{report}");
+    } else {
+        tracing::info!("Preflight checks report:\n{report}");
+    }
+    report
+}
+
+/// Loads overridden base system contracts if the corresponding directory is specified in the state keeper config.
+fn load_base_system_contracts(
+    configs: &GeneralConfig,
+) -> anyhow::Result<Option<BaseSystemContracts>> {
+    let Some(path) = configs
+        .state_keeper_config
+        .as_ref()
+        .and_then(|config| config.base_system_contracts_path.as_ref())
+    else {
+        return Ok(None);
+    };
+    let contracts = BaseSystemContracts::load_from_dir(Path::new(path))
+        .with_context(|| format!("failed loading base system contracts from {path}"))?;
+    tracing::info!(
+        "Loaded overridden base system contracts from {path}: {:?}",
+        contracts.hashes()
+    );
+    Ok(Some(contracts))
+}
+
 fn load_env_config() -> anyhow::Result<TempConfigStore> {
     Ok(TempConfigStore {
         postgres_config: PostgresConfig::from_env().ok(),
@@ -308,5 +425,9 @@ fn load_env_config() -> anyhow::Result<TempConfigStore> {
         snapshot_creator: SnapshotsCreatorConfig::from_env().ok(),
         protective_reads_writer_config: ProtectiveReadsWriterConfig::from_env().ok(),
         core_object_store: ObjectStoreConfig::from_env().ok(),
+        basic_witness_input_producer_config: BasicWitnessInputProducerConfig::from_env().ok(),
+        disk_space_monitor_config: DiskSpaceMonitorConfig::from_env().ok(),
+        scheduler_config: SchedulerConfig::from_env().ok(),
+        feature_flags_config: FeatureFlagsConfig::from_env().ok(),
     })
 }
@@ -7,10 +7,14 @@ use zksync_config::{
     configs::{consensus::ConsensusConfig, wallets::Wallets, GeneralConfig, Secrets},
     ContractsConfig, GenesisConfig,
 };
-use zksync_core_leftovers::Component;
+use zksync_core_leftovers::{
+    config_requirements::{is_config_required, LoadedConfigs, RequiredConfig},
+    Component,
+};
 use zksync_metadata_calculator::MetadataCalculatorConfig;
 use zksync_node_api_server::{
-    tx_sender::{ApiContracts, TxSenderConfig},
+    execution_sandbox::VmFairSchedulingConfig,
+    tx_sender::{master_pool_sink::MempoolQuotas, ApiContracts, TxSenderConfig},
     web3::{state::InternalApiConfig, Namespace},
 };
 use zksync_node_framework::{
@@ -19,8 +23,10 @@ use zksync_node_framework::{
         commitment_generator::CommitmentGeneratorLayer,
         consensus::{ConsensusLayer, Mode as ConsensusMode},
         contract_verification_api::ContractVerificationApiLayer,
+        disk_space_monitor::DiskSpaceMonitorLayer,
         eth_sender::{EthTxAggregatorLayer, EthTxManagerLayer},
         eth_watch::EthWatchLayer,
+        feature_flags::FeatureFlagsLayer,
         healtcheck_server::HealthCheckLayer,
         house_keeper::HouseKeeperLayer,
         l1_gas::SequencerL1GasLayer,
@@ -31,13 +37,20 @@ use zksync_node_framework::{
         prometheus_exporter::PrometheusExporterLayer,
         proof_data_handler::ProofDataHandlerLayer,
         query_eth_client::QueryEthClientLayer,
+        scheduler::SchedulerLayer,
         sigint::SigintHandlerLayer,
         state_keeper::{
             main_batch_executor::MainBatchExecutorLayer, mempool_io::MempoolIOLayer,
             StateKeeperLayer,
         },
+        supply_invariant_checker::SupplyInvariantCheckerLayer,
         tee_verifier_input_producer::TeeVerifierInputProducerLayer,
-        vm_runner::protective_reads::ProtectiveReadsWriterLayer,
+        vm_runner::{
+            bwip::BasicWitnessInputProducerLayer, presimulation::TxPresimulatorLayer,
+            protective_reads::ProtectiveReadsWriterLayer,
+            sealed_batches::SealedBatchesListenerLayer, shared_storage::SharedVmRunnerStorageLayer,
+            upgrade_canary::UpgradeCanaryLayer,
+        },
         web3_api::{
             caches::MempoolCacheLayer,
             server::{Web3ServerLayer, Web3ServerOptionalConfig},
@@ -46,8 +59,10 @@ use zksync_node_framework::{
             tx_sink::TxSinkLayer,
         },
     },
-    service::{ZkStackService, ZkStackServiceBuilder},
+    service::{RuntimeConfig, ZkStackService, ZkStackServiceBuilder},
 };
+use zksync_state_keeper::ShutdownMode;
+use zksync_vm_runner::VmRunnerStorageBackend;
 
 /// Macro that looks into a path to fetch an optional config,
 /// and clones it into a variable.
@@ -111,6 +126,24 @@ impl MainNodeBuilder {
         Ok(self)
     }
 
+    fn add_disk_space_monitor_layer(mut self) -> anyhow::Result<Self> {
+        let config = try_load_config!(self.configs.disk_space_monitor_config);
+        self.node.add_layer(DiskSpaceMonitorLayer::new(config));
+        Ok(self)
+    }
+
+    fn add_feature_flags_layer(mut self) -> anyhow::Result<Self> {
+        let config = try_load_config!(self.configs.feature_flags_config);
+        self.node.add_layer(FeatureFlagsLayer::new(config));
+        Ok(self)
+    }
+
+    fn add_scheduler_layer(mut self) -> anyhow::Result<Self> {
+        let config = try_load_config!(self.configs.scheduler_config);
+        self.node.add_layer(SchedulerLayer::new(config));
+        Ok(self)
+    }
+
     fn add_pk_signing_client_layer(mut self) -> anyhow::Result<Self> {
         let eth_config = try_load_config!(self.configs.eth);
         let wallets = try_load_config!(self.wallets.eth_sender);
@@ -175,16 +208,42 @@ impl MainNodeBuilder {
     fn add_state_keeper_layer(mut self) -> anyhow::Result<Self> {
         let wallets = self.wallets.clone();
         let sk_config = try_load_config!(self.configs.state_keeper_config);
-        let mempool_io_layer = MempoolIOLayer::new(
+        let mut mempool_io_layer = MempoolIOLayer::new(
             self.genesis_config.l2_chain_id,
             self.contracts_config.clone(),
             sk_config.clone(),
             try_load_config!(self.configs.mempool_config),
             try_load_config!(wallets.state_keeper),
-        );
+        )
+        .with_evm_emulator_hash(self.genesis_config.evm_emulator_hash);
         let db_config = try_load_config!(self.configs.db_config);
+        let shutdown_mode = ShutdownMode::from_config(&sk_config);
+        let upgrade_canary_batches = sk_config.upgrade_canary_batches;
+        if let Some(batch_size) = sk_config.tx_presimulation_batch_size {
+            let presimulator_layer = TxPresimulatorLayer::new(
+                batch_size,
+                sk_config.validation_computational_gas_limit,
+                self.genesis_config.l2_chain_id,
+            )
+            .with_evm_emulator_hash(self.genesis_config.evm_emulator_hash);
+            mempool_io_layer =
+                mempool_io_layer.with_transaction_filter(presimulator_layer.transaction_filter());
+            self.node.add_layer(presimulator_layer);
+        }
         let main_node_batch_executor_builder_layer = MainBatchExecutorLayer::new(sk_config);
-        let state_keeper_layer = StateKeeperLayer::new(db_config);
+        if let Some(canary_batches) = upgrade_canary_batches {
+            // The canary only executes a few batches per scheduled upgrade, so keeping a dedicated RocksDB cache
+            // in sync with Postgres all the time isn't worth it.
+            self.node.add_layer(
+                UpgradeCanaryLayer::new(
+                    VmRunnerStorageBackend::PostgresOnly,
+                    canary_batches,
+                    self.genesis_config.l2_chain_id,
+                )
+                .with_evm_emulator_hash(self.genesis_config.evm_emulator_hash),
+            );
+        }
+        let state_keeper_layer = StateKeeperLayer::new(db_config).with_shutdown_mode(shutdown_mode);
         self.node
             .add_layer(mempool_io_layer)
             .add_layer(main_node_batch_executor_builder_layer)
@@ -225,20 +284,27 @@ impl MainNodeBuilder {
         };
 
         // On main node we always use master pool sink.
-        self.node.add_layer(TxSinkLayer::MasterPoolSink);
-        self.node.add_layer(TxSenderLayer::new(
-            TxSenderConfig::new(
-                &sk_config,
-                &rpc_config,
-                try_load_config!(self.wallets.state_keeper)
-                    .fee_account
-                    .address(),
-                self.genesis_config.l2_chain_id,
-            ),
-            postgres_storage_caches_config,
-            rpc_config.vm_concurrency_limit(),
-            ApiContracts::load_from_disk(), // TODO (BFT-138): Allow to dynamically reload API contracts
-        ));
+        self.node.add_layer(TxSinkLayer::MasterPoolSink {
+            replacement_fee_bump_percent: rpc_config.tx_replacement_fee_bump_percent(),
+            quotas: MempoolQuotas::from_web3_config(&rpc_config),
+        });
+        self.node.add_layer(
+            TxSenderLayer::new(
+                TxSenderConfig::new(
+                    &sk_config,
+                    &rpc_config,
+                    try_load_config!(self.wallets.state_keeper)
+                        .fee_account
+                        .address(),
+                    self.genesis_config.l2_chain_id,
+                )
+                .with_evm_emulator_hash(self.genesis_config.evm_emulator_hash),
+                postgres_storage_caches_config,
+                rpc_config.vm_concurrency_limit(),
+                ApiContracts::load_from_disk(), // TODO (BFT-138): Allow to dynamically reload API contracts
+            )
+            .with_vm_fair_scheduling(VmFairSchedulingConfig::from_web3_config(&rpc_config)),
+        );
         Ok(self)
     }
 
@@ -268,6 +334,9 @@ impl MainNodeBuilder {
             namespaces.push(Namespace::Debug)
         }
         namespaces.push(Namespace::Snapshots);
+        if rpc_config.admin_namespace_enabled {
+            namespaces.push(Namespace::Admin);
+        }
 
         let optional_config = Web3ServerOptionalConfig {
             namespaces: Some(namespaces),
@@ -347,13 +416,22 @@ impl MainNodeBuilder {
         let fri_prover_group_config = try_load_config!(self.configs.prover_group_config);
         let fri_proof_compressor_config = try_load_config!(self.configs.proof_compressor_config);
 
-        self.node.add_layer(HouseKeeperLayer::new(
-            house_keeper_config,
-            fri_prover_config,
-            fri_witness_generator_config,
-            fri_prover_group_config,
-            fri_proof_compressor_config,
-        ));
+        let rejected_txs_retention = self
+            .configs
+            .api_config
+            .as_ref()
+            .and_then(|config| config.web3_json_rpc.rejected_txs_retention());
+
+        self.node.add_layer(
+            HouseKeeperLayer::new(
+                house_keeper_config,
+                fri_prover_config,
+                fri_witness_generator_config,
+                fri_prover_group_config,
+                fri_proof_compressor_config,
+            )
+            .with_rejected_txs_retention(rejected_txs_retention),
+        );
 
         Ok(self)
     }
@@ -366,6 +444,17 @@ impl MainNodeBuilder {
         Ok(self)
     }
 
+    fn add_supply_invariant_checker_layer(mut self) -> anyhow::Result<Self> {
+        let circuit_breaker_config = try_load_config!(self.configs.circuit_breaker_config);
+        self.node.add_layer(SupplyInvariantCheckerLayer::new(
+            circuit_breaker_config,
+            self.contracts_config.clone(),
+            self.genesis_config.l2_chain_id,
+        ));
+
+        Ok(self)
+    }
+
     fn add_circuit_breaker_checker_layer(mut self) -> anyhow::Result<Self> {
         let circuit_breaker_config = try_load_config!(self.configs.circuit_breaker_config);
         self.node
@@ -391,9 +480,10 @@ impl MainNodeBuilder {
     }
 
     fn add_tee_verifier_input_producer_layer(mut self) -> anyhow::Result<Self> {
-        self.node.add_layer(TeeVerifierInputProducerLayer::new(
-            self.genesis_config.l2_chain_id,
-        ));
+        self.node.add_layer(
+            TeeVerifierInputProducerLayer::new(self.genesis_config.l2_chain_id)
+                .with_evm_emulator_hash(self.genesis_config.evm_emulator_hash),
+        );
 
         Ok(self)
     }
@@ -401,25 +491,108 @@ impl MainNodeBuilder {
     fn add_vm_runner_protective_reads_layer(mut self) -> anyhow::Result<Self> {
         let protective_reads_writer_config =
             try_load_config!(self.configs.protective_reads_writer_config);
-        self.node.add_layer(ProtectiveReadsWriterLayer::new(
-            protective_reads_writer_config,
-            self.genesis_config.l2_chain_id,
-        ));
+        self.node.add_layer(
+            ProtectiveReadsWriterLayer::new(
+                protective_reads_writer_config,
+                self.genesis_config.l2_chain_id,
+            )
+            .with_evm_emulator_hash(self.genesis_config.evm_emulator_hash),
+        );
+
+        Ok(self)
+    }
+
+    fn add_vm_runner_bwip_layer(mut self) -> anyhow::Result<Self> {
+        let basic_witness_input_producer_config =
+            try_load_config!(self.configs.basic_witness_input_producer_config);
+        self.node.add_layer(
+            BasicWitnessInputProducerLayer::new(
+                basic_witness_input_producer_config,
+                self.genesis_config.l2_chain_id,
+            )
+            .with_evm_emulator_hash(self.genesis_config.evm_emulator_hash),
+        );
+
+        Ok(self)
+    }
+
+    /// Adds resources for VM runners: the sealed batches listener, and shared storage for co-located VM runners
+    /// if they are configured with the same RocksDB path.
+    fn add_vm_runner_shared_layers(mut self, components: &[Component]) -> anyhow::Result<Self> {
+        if components.contains(&Component::VmRunnerProtectiveReads)
+            || components.contains(&Component::VmRunnerBwip)
+        {
+            self.node.add_layer(SealedBatchesListenerLayer);
+        }
 
+        if !components.contains(&Component::VmRunnerProtectiveReads)
+            || !components.contains(&Component::VmRunnerBwip)
+        {
+            return Ok(self);
+        }
+        let (Some(protective_reads_writer_config), Some(basic_witness_input_producer_config)) = (
+            &self.configs.protective_reads_writer_config,
+            &self.configs.basic_witness_input_producer_config,
+        ) else {
+            return Ok(self);
+        };
+        if protective_reads_writer_config.db_path == basic_witness_input_producer_config.db_path {
+            self.node.add_layer(
+                SharedVmRunnerStorageLayer::new(
+                    protective_reads_writer_config.db_path.clone(),
+                    self.genesis_config.l2_chain_id,
+                )
+                .with_evm_emulator_hash(self.genesis_config.evm_emulator_hash),
+            );
+        }
         Ok(self)
     }
 
     pub fn build(mut self, mut components: Vec<Component>) -> anyhow::Result<ZkStackService> {
+        LoadedConfigs {
+            general: &self.configs,
+            secrets: &self.secrets,
+            wallets: &self.wallets,
+            consensus: self.consensus_config.as_ref(),
+        }
+        .check_required(&components)?;
+
         // Add "base" layers (resources and helper tasks).
         self = self
             .add_sigint_handler_layer()?
             .add_pools_layer()?
-            .add_object_store_layer()?
-            .add_circuit_breaker_checker_layer()?
             .add_healthcheck_layer()?
-            .add_prometheus_exporter_layer()?
-            .add_query_eth_client_layer()?
-            .add_sequencer_l1_gas_layer()?;
+            .add_prometheus_exporter_layer()?;
+        // Shared resources are only provided if they are required by the selected components.
+        // The Merkle tree may require an object store depending on its mode, so it's checked separately.
+        if is_config_required(&components, RequiredConfig::CoreObjectStore)
+            || self.configs.core_object_store.is_some()
+        {
+            self = self.add_object_store_layer()?;
+        }
+        // Disk space monitoring is opt-in and is not tied to specific components.
+        if self.configs.disk_space_monitor_config.is_some() {
+            self = self.add_disk_space_monitor_layer()?;
+        }
+        // Feature flags must be resolved before component-specific layers consult them.
+        if self.configs.feature_flags_config.is_some() {
+            self = self.add_feature_flags_layer()?;
+        }
+        // Scheduler is opt-in as well; jobs are registered in it by component-specific layers.
+        if self.configs.scheduler_config.is_some() {
+            self = self.add_scheduler_layer()?;
+        }
+        // Shared VM runner resources must be added before the VM runner layers using them.
+        self = self.add_vm_runner_shared_layers(&components)?;
+        if is_config_required(&components, RequiredConfig::CircuitBreaker) {
+            self = self.add_circuit_breaker_checker_layer()?;
+        }
+        if is_config_required(&components, RequiredConfig::L1Secrets) {
+            self = self.add_query_eth_client_layer()?;
+        }
+        if is_config_required(&components, RequiredConfig::EthGasAdjuster) {
+            self = self.add_sequencer_l1_gas_layer()?;
+        }
 
         // Sort the components, so that the components they may depend on each other are added in the correct order.
         components.sort_unstable_by_key(|component| match component {
@@ -467,7 +640,8 @@ impl MainNodeBuilder {
                 Component::EthTxAggregator => {
                     self = self
                         .add_pk_signing_client_layer()?
-                        .add_eth_tx_aggregator_layer()?;
+                        .add_eth_tx_aggregator_layer()?
+                        .add_supply_invariant_checker_layer()?;
                 }
                 Component::EthTxManager => {
                     self = self.add_eth_tx_manager_layer()?;
@@ -493,8 +667,22 @@ impl MainNodeBuilder {
                 Component::VmRunnerProtectiveReads => {
                     self = self.add_vm_runner_protective_reads_layer()?;
                 }
+                Component::VmRunnerBwip => {
+                    self = self.add_vm_runner_bwip_layer()?;
+                }
             }
         }
+
+        let has_api_components = components
+            .iter()
+            .any(|component| matches!(component, Component::HttpApi | Component::WsApi));
+        if has_api_components {
+            let rpc_config = try_load_config!(self.configs.api_config).web3_json_rpc;
+            self.node.with_runtime_config(RuntimeConfig {
+                main_worker_threads: None,
+                latency_sensitive_worker_threads: rpc_config.api_runtime_worker_threads,
+            });
+        }
         Ok(self.node.build()?)
     }
 }
@@ -52,6 +52,69 @@ impl Add for ExtendedJobCountStatistics {
     }
 }
 
+/// Lifecycle state of a job in one of the prover job queues, standardized across prover components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JobState {
+    /// The job is waiting to be picked by a component.
+    Queued,
+    /// The job is being processed.
+    InProgress,
+    /// Processing the job failed, but it will be re-queued.
+    Failed { attempts: u32 },
+    /// The job was processed (or skipped) successfully.
+    Successful,
+    /// Processing the job failed the maximum number of attempts; the job won't be re-queued.
+    Dead,
+}
+
+impl JobState {
+    /// Maps a job status stored in Postgres to the lifecycle state. Returns `None` for jobs that aren't
+    /// queued yet (e.g., waiting for proofs or artifacts).
+    pub fn from_db(status: &str, attempts: u32, max_attempts: u32) -> Option<Self> {
+        Some(match status {
+            "queued" => Self::Queued,
+            "in_progress" | "in_gpu_proof" => Self::InProgress,
+            "failed" if attempts >= max_attempts => Self::Dead,
+            "failed" => Self::Failed { attempts },
+            "successful" | "skipped" | "sent_to_server" => Self::Successful,
+            _ => return None,
+        })
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::InProgress => "in_progress",
+            Self::Failed { .. } => "failed",
+            Self::Successful => "successful",
+            Self::Dead => "dead",
+        }
+    }
+}
+
+/// Number of jobs in each [`JobState`] for a job queue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JobStateCounts {
+    pub queued: usize,
+    pub in_progress: usize,
+    pub failed: usize,
+    pub successful: usize,
+    pub dead: usize,
+}
+
+impl JobStateCounts {
+    pub fn add(&mut self, state: JobState, count: usize) {
+        let counter = match state {
+            JobState::Queued => &mut self.queued,
+            JobState::InProgress => &mut self.in_progress,
+            JobState::Failed { .. } => &mut self.failed,
+            JobState::Successful => &mut self.successful,
+            JobState::Dead => &mut self.dead,
+        };
+        *counter += count;
+    }
+}
+
 #[derive(Debug)]
 pub struct StuckJobs {
     pub id: u64,
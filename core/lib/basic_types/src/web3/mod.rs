@@ -62,9 +62,13 @@ impl Serialize for Bytes {
         S: Serializer,
     {
         if serializer.is_human_readable() {
-            let mut serialized = "0x".to_owned();
-            serialized.push_str(&hex::encode(&self.0));
-            serializer.serialize_str(serialized.as_ref())
+            // Encode into a single pre-sized buffer. `Bytes` are used for the largest parts of RPC responses
+            // (e.g., calldata and log data), so we don't want to allocate intermediate strings.
+            let mut serialized = vec![0_u8; 2 + 2 * self.0.len()];
+            serialized[..2].copy_from_slice(b"0x");
+            hex::encode_to_slice(&self.0, &mut serialized[2..]).expect("buffer has correct length");
+            let serialized = std::str::from_utf8(&serialized).expect("hex string is valid UTF-8");
+            serializer.serialize_str(serialized)
         } else {
             self.0.serialize(serializer)
         }
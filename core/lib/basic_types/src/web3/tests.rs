@@ -125,6 +125,10 @@ fn test_bytes_serde_bincode_snapshot() {
 fn test_bytes_serde_json() {
     let original = Bytes(vec![0, 1, 2, 3, 4]);
     let encoded = serde_json::to_string(&original).unwrap();
+    assert_eq!(encoded, r#""0x0001020304""#);
     let decoded: Bytes = serde_json::from_str(&encoded).unwrap();
     assert_eq!(original, decoded);
+
+    let encoded = serde_json::to_string(&Bytes::default()).unwrap();
+    assert_eq!(encoded, r#""0x""#);
 }
@@ -2,10 +2,12 @@ use std::{fmt, sync::Arc, time::Duration};
 
 use thiserror::Error;
 use tokio::sync::{watch, Mutex};
+use zksync_types::{Address, U256};
 
 pub mod l1_txs;
 mod metrics;
 pub mod replication_lag;
+pub mod supply_invariant;
 
 #[derive(Default, Debug)]
 pub struct CircuitBreakers(Mutex<Vec<Box<dyn CircuitBreaker>>>);
@@ -35,6 +37,13 @@ pub enum CircuitBreakerError {
     FailedL1Transaction,
     #[error("Replication lag ({lag:?}) is above the threshold ({threshold:?})")]
     ReplicationLag { lag: Duration, threshold: Duration },
+    #[error("L2 supply of token {token:?} ({l2_supply}) exceeds funds locked on L1 ({l1_locked})")]
+    SupplyInvariantViolated {
+        /// L1 address of the token.
+        token: Address,
+        l2_supply: U256,
+        l1_locked: U256,
+    },
     #[error("Internal error running circuit breaker checks")]
     Internal(#[from] anyhow::Error),
 }
@@ -2,13 +2,19 @@
 
 use std::time::Duration;
 
-use vise::{Gauge, Global, Metrics};
+use vise::{Counter, Gauge, Global, LabeledFamily, Metrics};
 
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "circuit_breaker")]
 pub(crate) struct CircuitBreakerMetrics {
     /// Replication lag for Postgres in seconds.
     pub replication_lag: Gauge<Duration>,
+    /// Difference between the funds locked on L1 and the L2 token supply, in token units, per L1 token address.
+    /// Negative values mean that the L2 supply exceeds the locked funds.
+    #[metrics(labels = ["token"])]
+    pub supply_divergence: LabeledFamily<String, Gauge<f64>>,
+    /// Number of checks that found the L2 supply invariant violated.
+    pub supply_invariant_violations: Counter,
 }
 
 #[vise::register]
@@ -0,0 +1,267 @@
+use zksync_config::configs::chain::SupplyInvariantAction;
+use zksync_dal::{ConnectionPool, Core, CoreDal, DalError};
+use zksync_eth_client::{
+    clients::{DynClient, L1},
+    CallFunctionArgs, ContractCallError,
+};
+use zksync_types::{
+    ethabi,
+    utils::{storage_key_for_base_token_total_supply, storage_key_for_standard_token_total_supply},
+    AccountTreeId, Address, L2ChainId, StorageKey, L2_BASE_TOKEN_ADDRESS, U256,
+};
+use zksync_utils::{h256_to_u256, u256_to_f64};
+
+use crate::{metrics::METRICS, CircuitBreaker, CircuitBreakerError};
+
+/// Checks that the total supply of the base token and of bridged ERC20 tokens on L2 doesn't exceed the funds
+/// locked for the chain in the L1 shared bridge.
+///
+/// In normal operation, L1 locked funds can only exceed the L2 supply (deposits are locked on L1 before they are
+/// processed on L2, and withdrawals are burned on L2 before they are finalized on L1), so exceeding the L2 supply
+/// signals a bug or an exploit.
+#[derive(Debug)]
+pub struct SupplyInvariantChecker {
+    pool: ConnectionPool<Core>,
+    eth_client: Box<DynClient<L1>>,
+    l1_shared_bridge_addr: Address,
+    l1_base_token_addr: Address,
+    l2_chain_id: L2ChainId,
+    tolerance: U256,
+    action: SupplyInvariantAction,
+    shared_bridge_abi: ethabi::Contract,
+}
+
+/// Token checked by [`SupplyInvariantChecker`].
+#[derive(Debug, Clone, Copy)]
+struct CheckedToken {
+    l1_address: Address,
+    l2_supply_key: StorageKey,
+    /// Allowed excess of the L2 supply over the locked funds.
+    tolerance: U256,
+}
+
+/// Result of comparing the L2 supply of a token with the funds locked for it on L1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SupplyComparison {
+    /// Difference between the locked funds and the L2 supply. Negative if the L2 supply exceeds the locked funds.
+    divergence: f64,
+    /// Whether the L2 supply exceeds the locked funds by more than the tolerance.
+    is_violated: bool,
+}
+
+impl SupplyComparison {
+    fn new(l1_locked: U256, l2_supply: U256, tolerance: U256) -> Self {
+        let divergence = if l1_locked >= l2_supply {
+            u256_to_f64(l1_locked - l2_supply)
+        } else {
+            -u256_to_f64(l2_supply - l1_locked)
+        };
+        Self {
+            divergence,
+            is_violated: l2_supply > l1_locked.saturating_add(tolerance),
+        }
+    }
+}
+
+impl SupplyInvariantChecker {
+    pub fn new(
+        pool: ConnectionPool<Core>,
+        eth_client: Box<DynClient<L1>>,
+        l1_shared_bridge_addr: Address,
+        l1_base_token_addr: Address,
+        l2_chain_id: L2ChainId,
+        tolerance: U256,
+        action: SupplyInvariantAction,
+    ) -> Self {
+        Self {
+            pool,
+            eth_client: eth_client.for_component("supply_invariant_checker"),
+            l1_shared_bridge_addr,
+            l1_base_token_addr,
+            l2_chain_id,
+            tolerance,
+            action,
+            shared_bridge_abi: zksync_contracts::l1_shared_bridge_contract(),
+        }
+    }
+
+    /// Returns the base token and all bridged ERC20 tokens. The tolerance only applies to the base token;
+    /// bridged tokens are minted only when the corresponding deposits are locked on L1, so their supply is checked exactly.
+    async fn checked_tokens(&self) -> Result<Vec<CheckedToken>, CircuitBreakerError> {
+        let mut storage = self.pool.connection_tagged("circuit_breaker").await?;
+        let bridged_tokens = storage
+            .tokens_web3_dal()
+            .get_all_tokens(None)
+            .await
+            .map_err(DalError::generalize)?;
+
+        let base_token = CheckedToken {
+            l1_address: self.l1_base_token_addr,
+            l2_supply_key: storage_key_for_base_token_total_supply(),
+            tolerance: self.tolerance,
+        };
+        let bridged_tokens = bridged_tokens.into_iter().filter_map(|token| {
+            let is_bridged =
+                !token.l2_address.is_zero() && token.l2_address != L2_BASE_TOKEN_ADDRESS;
+            is_bridged.then(|| CheckedToken {
+                l1_address: token.l1_address,
+                l2_supply_key: storage_key_for_standard_token_total_supply(AccountTreeId::new(
+                    token.l2_address,
+                )),
+                tolerance: U256::zero(),
+            })
+        });
+        Ok([base_token].into_iter().chain(bridged_tokens).collect())
+    }
+
+    async fn l2_supplies(&self, tokens: &[CheckedToken]) -> Result<Vec<U256>, CircuitBreakerError> {
+        let hashed_keys: Vec<_> = tokens
+            .iter()
+            .map(|token| token.l2_supply_key.hashed_key())
+            .collect();
+        let values = self
+            .pool
+            .connection_tagged("circuit_breaker")
+            .await?
+            .storage_web3_dal()
+            .get_values(&hashed_keys)
+            .await
+            .map_err(DalError::generalize)?;
+        Ok(hashed_keys
+            .iter()
+            .map(|key| {
+                values
+                    .get(key)
+                    .copied()
+                    .map_or_else(U256::zero, h256_to_u256)
+            })
+            .collect())
+    }
+
+    async fn l1_locked_funds(&self, l1_token_addr: Address) -> Result<U256, ContractCallError> {
+        CallFunctionArgs::new(
+            "chainBalance",
+            (U256::from(self.l2_chain_id.as_u64()), l1_token_addr),
+        )
+        .for_contract(self.l1_shared_bridge_addr, &self.shared_bridge_abi)
+        .call(self.eth_client.as_ref())
+        .await
+    }
+}
+
+#[async_trait::async_trait]
+impl CircuitBreaker for SupplyInvariantChecker {
+    fn name(&self) -> &'static str {
+        "supply_invariant"
+    }
+
+    async fn check(&self) -> Result<(), CircuitBreakerError> {
+        let tokens = self.checked_tokens().await?;
+        // Load L2 supplies before L1 locked funds, so that a concurrent deposit cannot make the L2 supply
+        // exceed the loaded locked funds.
+        let l2_supplies = self.l2_supplies(&tokens).await?;
+
+        for (token, l2_supply) in tokens.iter().zip(l2_supplies) {
+            let l1_locked = match self.l1_locked_funds(token.l1_address).await {
+                Ok(locked) => locked,
+                Err(ContractCallError::EthereumGateway(err)) if err.is_transient() => {
+                    tracing::warn!(
+                        "Transient error getting locked funds for token {:?} from L1, skipping check: {err}",
+                        token.l1_address
+                    );
+                    continue;
+                }
+                Err(err) => {
+                    return Err(anyhow::Error::new(err)
+                        .context(format!(
+                            "failed getting locked funds for token {:?} from L1",
+                            token.l1_address
+                        ))
+                        .into());
+                }
+            };
+
+            let comparison = SupplyComparison::new(l1_locked, l2_supply, token.tolerance);
+            METRICS.supply_divergence[&format!("{:?}", token.l1_address)]
+                .set(comparison.divergence);
+            if !comparison.is_violated {
+                continue;
+            }
+
+            METRICS.supply_invariant_violations.inc();
+            match self.action {
+                SupplyInvariantAction::Alert => {
+                    tracing::error!(
+                        "L2 supply of token {:?} ({l2_supply}) exceeds funds locked on L1 ({l1_locked}) \
+                         by more than the tolerance ({})",
+                        token.l1_address,
+                        token.tolerance
+                    );
+                }
+                SupplyInvariantAction::Halt => {
+                    return Err(CircuitBreakerError::SupplyInvariantViolated {
+                        token: token.l1_address,
+                        l2_supply,
+                        l1_locked,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn supply_within_locked_funds() {
+        let comparison = SupplyComparison::new(1_000.into(), 900.into(), U256::zero());
+        assert_eq!(
+            comparison,
+            SupplyComparison {
+                divergence: 100.0,
+                is_violated: false,
+            }
+        );
+
+        let comparison = SupplyComparison::new(1_000.into(), 1_000.into(), U256::zero());
+        assert_eq!(comparison.divergence, 0.0);
+        assert!(!comparison.is_violated);
+    }
+
+    #[test]
+    fn supply_exceeding_locked_funds() {
+        let comparison = SupplyComparison::new(1_000.into(), 1_001.into(), U256::zero());
+        assert_eq!(
+            comparison,
+            SupplyComparison {
+                divergence: -1.0,
+                is_violated: true,
+            }
+        );
+
+        // Excess within the tolerance is allowed.
+        let comparison = SupplyComparison::new(1_000.into(), 1_050.into(), 50.into());
+        assert_eq!(comparison.divergence, -50.0);
+        assert!(!comparison.is_violated);
+        let comparison = SupplyComparison::new(1_000.into(), 1_051.into(), 50.into());
+        assert!(comparison.is_violated);
+    }
+
+    #[test]
+    fn supply_comparison_with_extreme_values() {
+        let comparison = SupplyComparison::new(U256::MAX, U256::MAX, U256::MAX);
+        assert_eq!(comparison.divergence, 0.0);
+        assert!(!comparison.is_violated);
+
+        let comparison = SupplyComparison::new(U256::zero(), U256::MAX, U256::zero());
+        assert!(comparison.divergence < -1e77, "{comparison:?}");
+        assert!(comparison.is_violated);
+
+        let comparison = SupplyComparison::new(U256::MAX, U256::zero(), U256::zero());
+        assert!(comparison.divergence > 1e77, "{comparison:?}");
+        assert!(!comparison.is_violated);
+    }
+}
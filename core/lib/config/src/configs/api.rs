@@ -132,6 +132,25 @@ pub struct MaxResponseSize {
     pub overrides: MaxResponseSizeOverrides,
 }
 
+/// Compatibility shim for the Web3 API mimicking a geth behavior.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GethCompatibilityShim {
+    /// Report the fee account address as the `miner` of L2 blocks instead of the zero address.
+    BlockMiner,
+    /// Return the generic `-32000` error code used by geth for unknown blocks, transaction submission failures
+    /// (other than reverted execution) and serialization errors, with geth-like messages where they differ
+    /// (e.g., "header not found" for unknown blocks).
+    ErrorCodes,
+    /// Make the `net_` and `web3_` namespaces behave like in geth: `net_listening` returns `true`,
+    /// and `web3_sha3` is registered (and thus advertised by `zks_getCapabilities`).
+    NetWeb3Methods,
+    /// Return `eth_getBlockByNumber` and `eth_getBlockByHash` responses in the geth shape: zkSync-specific fields
+    /// (`l1BatchNumber`, `l1BatchTimestamp`) and `sealFields` are omitted, and blocks without transactions report
+    /// the empty trie root as `transactionsRoot` and `receiptsRoot`.
+    BlockFields,
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct Web3JsonRpcConfig {
     /// Port to which the HTTP RPC server is listening.
@@ -173,6 +192,10 @@ pub struct Web3JsonRpcConfig {
     pub estimate_gas_scale_factor: f64,
     /// The max possible number of gas that `eth_estimateGas` is allowed to overestimate.
     pub estimate_gas_acceptable_overestimation: u32,
+    /// Max number of transactions in a single `zks_estimateFeeBatch` request. Each transaction in a batch is executed
+    /// once more after its fee is estimated, so this value bounds the number of extra VM executions per request.
+    /// Default is 16.
+    pub estimate_fee_batch_max_size: Option<usize>,
     ///  Max possible size of an ABI encoded tx (in bytes).
     pub max_tx_size: usize,
     /// Max number of cache misses during one VM execution. If the number of cache misses exceeds this value, the API server panics.
@@ -182,6 +205,22 @@ pub struct Web3JsonRpcConfig {
     /// This option can be tweaked down if the API server is running out of memory.
     /// If not set, the VM concurrency limit will be efficiently disabled.
     pub vm_concurrency_limit: Option<usize>,
+    /// Max number of VM instances that can be concurrently used by a single API client. If set, VM permits are shared fairly
+    /// among clients identified by `vm_client_id_header`, so that a single client cannot monopolize the VM.
+    /// If not set, only the global VM concurrency limit is enforced.
+    pub vm_concurrency_limit_per_client: Option<usize>,
+    /// HTTP header used to identify API clients for fair VM scheduling, e.g. a header with an API key set by a proxy.
+    /// The header is treated as a comma-separated list of hops as in `x-forwarded-for`; see `vm_client_id_trusted_proxies`
+    /// for how the client ID is chosen. Default is `x-forwarded-for`.
+    /// Requests without a client ID are only subject to the global VM concurrency limit.
+    pub vm_client_id_header: Option<String>,
+    /// Number of trusted reverse proxies in front of the API server, each of which appends a hop to `vm_client_id_header`.
+    /// The client ID is taken from the rightmost hop not appended by a trusted proxy; hops to the left of it can be spoofed
+    /// by clients and are ignored. If the header has fewer hops, or if set to 0, requests are not attributed to any client.
+    /// Default is 1.
+    pub vm_client_id_trusted_proxies: Option<usize>,
+    /// Timeout for acquiring a VM permit if fair VM scheduling is enabled. In milliseconds. Default is 5000 milliseconds.
+    pub vm_permit_timeout_ms: Option<u64>,
     /// Smart contract cache size in MiBs. The default value is 128 MiB.
     pub factory_deps_cache_size_mb: Option<usize>,
     /// Initial writes cache size in MiBs. The default value is 32 MiB.
@@ -209,10 +248,81 @@ pub struct Web3JsonRpcConfig {
     pub mempool_cache_update_interval: Option<u64>,
     /// Maximum number of transactions to be stored in the mempool cache. Default is 10000.
     pub mempool_cache_size: Option<usize>,
+    /// Maximum number of `eth_call` results to be stored in the call cache. If not specified, the cache is disabled.
+    pub eth_call_cache_size: Option<usize>,
+    /// Time-to-live for entries in the `eth_call` cache. In milliseconds. Default is 1000 milliseconds.
+    pub eth_call_cache_ttl_ms: Option<u64>,
+    /// Retention period for the journal of transactions rejected on submission (in seconds). Rejected transactions
+    /// can be queried via `zks_getRejectedTransaction`. Recording is rate-limited, so not all rejected transactions
+    /// may be recorded under load. Old records are pruned by the house keeper. If not specified, rejected transactions
+    /// are not recorded.
+    pub rejected_txs_retention_sec: Option<u64>,
+    /// Minimum fee bump (in percent) required to replace a pending transaction with the same initiator and nonce.
+    /// Both `max_fee_per_gas` and `max_priority_fee_per_gas` must be bumped. If not specified or 0, pending transactions
+    /// can be replaced regardless of fees. Geth uses 10% by default.
+    pub tx_replacement_fee_bump_percent: Option<u32>,
+    /// Statement timeout for expensive read queries, such as ones for `eth_getLogs` and `debug_traceBlock*`
+    /// (in milliseconds). Queries exceeding the timeout are aborted, and an error asking to narrow the requested range
+    /// is returned to the client. If not specified, only the global statement timeout for the API connection pool applies.
+    pub query_statement_timeout_ms: Option<u64>,
+    /// Maximum number of pending transactions from a single sender in the mempool. Transactions exceeding the quota
+    /// are rejected, unless they replace a pending transaction. If not specified, the number is not limited.
+    pub mempool_max_txs_per_sender: Option<u32>,
+    /// Maximum number of pending L2 transactions in the mempool. If the mempool is full, the pending transaction
+    /// with the lowest max fee per gas (or the oldest one among them) is evicted to make space for a new transaction
+    /// with a higher fee; otherwise, the new transaction is rejected. If not specified, the number is not limited.
+    ///
+    /// The quota is soft: it's checked against a mempool size snapshot refreshed about every second rather than
+    /// under a global lock, so it may be exceeded by concurrent submissions (including ones accepted via other
+    /// API servers) in the meantime.
+    pub mempool_max_txs: Option<u64>,
+    /// Maximum total size of pending L2 transactions in the mempool (in MiB). Enforced in the same way
+    /// as `mempool_max_txs`. If not specified, the size is not limited.
+    pub mempool_max_size_mb: Option<u64>,
+    /// Whether to serve the `admin` namespace on the HTTP server. The namespace allows changing node behavior
+    /// at runtime (e.g., enabling call tracing for specific addresses), so all its methods require authentication
+    /// with `admin_api_keys`, which must be non-empty if this is enabled.
+    #[serde(default)]
+    pub admin_namespace_enabled: bool,
+    /// Operators authorized to access the `admin` namespace, each in the `<operator_id>:<API key hash>` format,
+    /// where the hash is a hex-encoded keccak256 digest of the API key. Operators authenticate with the
+    /// `Authorization: Bearer <API key>` header; their IDs are recorded in the operator audit log.
+    #[serde(default)]
+    pub admin_api_keys: Vec<String>,
+    /// Number of worker threads of a dedicated tokio runtime running the API servers. Isolating API servers
+    /// from CPU-heavy background tasks (e.g., Merkle tree or VM runner) reduces RPC tail latency. If not specified,
+    /// API servers share the runtime with other node components.
+    pub api_runtime_worker_threads: Option<usize>,
+    /// Maximum number of idempotency keys remembered by the HTTP server for transaction submissions
+    /// (`eth_sendRawTransaction` and `zks_sendRawTransactionWithDetailedOutput`). If a client retries a submission
+    /// with the same `idempotency-key` header, the original result is returned. If not specified, the header is ignored.
+    pub idempotency_keys_cache_size: Option<usize>,
+    /// Time-to-live for remembered idempotency keys (in seconds). Default is 600 seconds.
+    pub idempotency_key_ttl_sec: Option<u64>,
+    /// Minimum `gas_per_pubdata_limit` accepted for submitted L2 transactions. Transactions with a lower limit
+    /// are rejected with an error containing the currently required gas per pubdata. If not specified,
+    /// the limit is not checked on submission.
+    pub min_gas_per_pubdata_limit: Option<u64>,
+    /// Maximum `gas_per_pubdata_limit` accepted for submitted L2 transactions. Transactions with a higher limit
+    /// are rejected with an error containing the currently required gas per pubdata. If not specified,
+    /// the limit is not checked on submission.
+    pub max_gas_per_pubdata_limit: Option<u64>,
+    /// Whether to reject legacy transactions without replay protection (i.e., ones signed without a chain ID
+    /// as per EIP-155). Such transactions can be replayed on any chain, in particular on other hyperchains.
+    /// Disabled by default since some tooling (e.g., deterministic deployment proxies) relies on these transactions.
+    #[serde(default)]
+    pub reject_unprotected_txs: bool,
+    /// Maximum number of idle VM memory instances retained by the API sandbox for each set of base system contracts.
+    /// Reusing VM memory reduces allocation churn for `eth_call` and gas estimation. If not specified, VM memory
+    /// is not reused.
+    pub vm_memory_pool_size: Option<usize>,
     /// List of L2 token addresses that are white-listed to use by paymasters
     /// (additionally to natively bridged tokens).
     #[serde(default)]
     pub whitelisted_tokens_for_aa: Vec<Address>,
+    /// Compatibility shims mimicking geth behavior that some Ethereum tooling relies on. All shims are disabled by default.
+    #[serde(default)]
+    pub geth_compatibility: Vec<GethCompatibilityShim>,
 }
 
 impl Web3JsonRpcConfig {
@@ -236,9 +346,14 @@ impl Web3JsonRpcConfig {
             account_pks: Default::default(),
             estimate_gas_scale_factor: 1.2,
             estimate_gas_acceptable_overestimation: 1000,
+            estimate_fee_batch_max_size: None,
             max_tx_size: 1000000,
             vm_execution_cache_misses_limit: Default::default(),
             vm_concurrency_limit: Default::default(),
+            vm_concurrency_limit_per_client: Default::default(),
+            vm_client_id_header: None,
+            vm_client_id_trusted_proxies: None,
+            vm_permit_timeout_ms: Default::default(),
             factory_deps_cache_size_mb: Default::default(),
             initial_writes_cache_size_mb: Default::default(),
             latest_values_cache_size_mb: Default::default(),
@@ -249,8 +364,26 @@ impl Web3JsonRpcConfig {
             websocket_requests_per_minute_limit: Default::default(),
             mempool_cache_update_interval: Default::default(),
             mempool_cache_size: Default::default(),
+            eth_call_cache_size: Default::default(),
+            eth_call_cache_ttl_ms: Default::default(),
+            rejected_txs_retention_sec: Default::default(),
+            tx_replacement_fee_bump_percent: Default::default(),
+            query_statement_timeout_ms: Default::default(),
+            mempool_max_txs_per_sender: Default::default(),
+            mempool_max_txs: Default::default(),
+            mempool_max_size_mb: Default::default(),
+            admin_namespace_enabled: false,
+            admin_api_keys: vec![],
+            api_runtime_worker_threads: None,
+            idempotency_keys_cache_size: None,
+            idempotency_key_ttl_sec: None,
+            min_gas_per_pubdata_limit: None,
+            max_gas_per_pubdata_limit: None,
+            reject_unprotected_txs: false,
+            vm_memory_pool_size: None,
             tree_api_url: None,
             whitelisted_tokens_for_aa: Default::default(),
+            geth_compatibility: Default::default(),
         }
     }
 
@@ -293,6 +426,24 @@ impl Web3JsonRpcConfig {
         self.vm_concurrency_limit.unwrap_or(2_048)
     }
 
+    pub fn vm_client_id_header(&self) -> &str {
+        self.vm_client_id_header
+            .as_deref()
+            .unwrap_or("x-forwarded-for")
+    }
+
+    pub fn estimate_fee_batch_max_size(&self) -> usize {
+        self.estimate_fee_batch_max_size.unwrap_or(16)
+    }
+
+    pub fn vm_client_id_trusted_proxies(&self) -> usize {
+        self.vm_client_id_trusted_proxies.unwrap_or(1)
+    }
+
+    pub fn vm_permit_timeout(&self) -> Duration {
+        Duration::from_millis(self.vm_permit_timeout_ms.unwrap_or(5_000))
+    }
+
     /// Returns the size of factory dependencies cache in bytes.
     pub fn factory_deps_cache_size(&self) -> usize {
         self.factory_deps_cache_size_mb.unwrap_or(128) * super::BYTES_IN_MEGABYTE
@@ -342,6 +493,31 @@ impl Web3JsonRpcConfig {
     pub fn mempool_cache_size(&self) -> usize {
         self.mempool_cache_size.unwrap_or(10_000)
     }
+
+    pub fn eth_call_cache_ttl(&self) -> Duration {
+        Duration::from_millis(self.eth_call_cache_ttl_ms.unwrap_or(1_000))
+    }
+
+    pub fn idempotency_key_ttl(&self) -> Duration {
+        Duration::from_secs(self.idempotency_key_ttl_sec.unwrap_or(600))
+    }
+
+    pub fn rejected_txs_retention(&self) -> Option<Duration> {
+        self.rejected_txs_retention_sec.map(Duration::from_secs)
+    }
+
+    pub fn tx_replacement_fee_bump_percent(&self) -> u32 {
+        self.tx_replacement_fee_bump_percent.unwrap_or(0)
+    }
+
+    pub fn mempool_max_size_bytes(&self) -> Option<u64> {
+        self.mempool_max_size_mb
+            .map(|size| size * super::BYTES_IN_MEGABYTE as u64)
+    }
+
+    pub fn query_statement_timeout(&self) -> Option<Duration> {
+        self.query_statement_timeout_ms.map(Duration::from_millis)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
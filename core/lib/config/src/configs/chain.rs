@@ -1,8 +1,8 @@
-use std::{str::FromStr, time::Duration};
+use std::{num::NonZeroU64, str::FromStr, time::Duration};
 
 use serde::Deserialize;
 use zksync_basic_types::{
-    commitment::L1BatchCommitmentMode, network::Network, Address, L2ChainId, H256,
+    commitment::L1BatchCommitmentMode, network::Network, Address, L2ChainId, H256, U256,
 };
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -66,6 +66,75 @@ pub struct StateKeeperConfig {
     /// The max payload size threshold (in bytes) that triggers sealing of an L2 block.
     #[serde(alias = "miniblock_max_payload_size")]
     pub l2_block_max_payload_size: usize,
+    /// The max amount of pubdata (in bytes) in an L2 block that triggers sealing of the block. Intended for DA layers
+    /// with small blob size limits. Since the block is sealed after a transaction is executed, a block may exceed this
+    /// threshold by the pubdata of its last transaction; transactions whose pubdata alone exceeds the threshold are rejected.
+    /// If not specified, L2 blocks are not sealed based on pubdata.
+    pub l2_block_max_pubdata: Option<u64>,
+    /// Maximum allowed drift (in seconds) of the previous L2 block timestamp into the future relative to the wall clock.
+    /// If the drift is larger, the state keeper returns an error instead of waiting for the wall clock to catch up.
+    /// If not specified, the state keeper always waits.
+    pub max_timestamp_drift_sec: Option<u64>,
+    /// Whether the state keeper should seal the current L2 block and flush all outputs upon receiving a stop signal.
+    /// If disabled, the pending L2 block is discarded and re-executed after the restart.
+    #[serde(default)]
+    pub graceful_shutdown: bool,
+    /// If graceful shutdown is enabled, also seal the current L1 batch on shutdown provided that it can be done
+    /// within this time budget (in ms). If not specified or if the budget is exceeded, the batch remains pending
+    /// and is re-executed after the restart.
+    pub graceful_shutdown_l1_batch_seal_timeout_ms: Option<u64>,
+    /// Whether the state keeper should discard the pending (i.e., unsealed) part of the open L1 batch on restart
+    /// instead of re-executing it. Discarded transactions are returned to the mempool and are subject to the current
+    /// transaction filters. Pending L2 blocks containing L1 or upgrade transactions are never discarded; neither are
+    /// pending L2 blocks if consensus is enabled, since they may have already been certified.
+    ///
+    /// **Warning:** Discarded L2 blocks may have already been served via the API, e.g. to external nodes or to users
+    /// who received transaction receipts. Discarding them rewrites the chain: external nodes will detect a reorg
+    /// and roll back, and previously returned receipts become invalid. Only enable this option for recovery
+    /// if the pending L2 blocks cannot be re-executed.
+    #[serde(default)]
+    pub discard_pending_l1_batch_on_restart: bool,
+    /// Relative increase of the L1 gas price since the L1 batch was opened (e.g., 0.2 for a 20% increase) at which
+    /// the L1 batch commit deadline is shortened, so that the batch is committed before L1 prices rise further.
+    /// If not specified, the deadline is not shortened.
+    pub l1_gas_price_rise_threshold: Option<f64>,
+    /// Factor applied to `block_commit_deadline_ms` if the L1 gas price rises above `l1_gas_price_rise_threshold`.
+    /// Should be in (0, 1]. If not specified, 0.5 is used.
+    pub l1_gas_price_rise_deadline_factor: Option<f64>,
+    /// Relative decrease of the L1 gas price since the L1 batch was opened (e.g., 0.2 for a 20% decrease) at which
+    /// the L1 batch commit deadline is extended, so that more transactions are amortized over cheap L1 commitment.
+    /// If not specified, the deadline is not extended.
+    pub l1_gas_price_fall_threshold: Option<f64>,
+    /// Factor applied to `block_commit_deadline_ms` if the L1 gas price falls below `l1_gas_price_fall_threshold`.
+    /// Should be at least 1. If not specified, 2 is used.
+    pub l1_gas_price_fall_deadline_factor: Option<f64>,
+    /// Budget for the time spent executing a single L1 batch in the VM (in ms). Time spent by the state keeper
+    /// waiting for new transactions is not included. A transaction running past the budget is interrupted; it is excluded
+    /// from the batch, and the batch is sealed. If the first transaction in a batch is interrupted, it is returned
+    /// to the mempool and retried without the budget. The budget doesn't apply to re-executing a pending batch
+    /// after a restart. If not specified, execution time is not limited.
+    pub max_batch_execution_time_ms: Option<u64>,
+    /// Budget for the memory used by the VM executing a single L1 batch (in MiB). A transaction making the batch exceed
+    /// the budget is excluded from the batch, and the batch is sealed. The budget doesn't apply to re-executing
+    /// a pending batch after a restart. If not specified, VM memory is not limited.
+    pub max_batch_vm_memory_mb: Option<usize>,
+    /// Number of L1 batches to shadow-execute under the next protocol version once a protocol upgrade
+    /// is scheduled (upgrade canary). Divergences from the actual execution results are reported.
+    /// If not specified, the upgrade canary is disabled.
+    pub upgrade_canary_batches: Option<u32>,
+    /// Maximum number of pending L2 transactions pre-simulated against the latest sealed L1 batch state
+    /// in a single iteration. Transactions that are found to fail validation regardless of the pending batch state
+    /// (e.g., because of an invalid signature) are rejected by the state keeper without executing them.
+    /// If not specified, transaction pre-simulation is disabled.
+    pub tx_presimulation_batch_size: Option<usize>,
+    /// Period (in seconds) for which each fee account is active if extra fee accounts are configured
+    /// in the state keeper wallets. The active account is chosen based on the L1 batch timestamp. If not specified,
+    /// fee accounts are rotated round-robin for each L1 batch.
+    pub fee_account_rotation_period_sec: Option<u64>,
+    /// Maximum number of 8-byte chunks in the dictionary of frequently deployed bytecode chunks shared
+    /// across L1 batches. The dictionary is only used to measure potential pubdata savings; published bytecodes
+    /// are still compressed with embedded dictionaries. If not specified, the shared dictionary is disabled.
+    pub bytecode_compression_dictionary_size: Option<usize>,
 
     /// The max number of gas to spend on an L1 tx before its batch should be sealed by the gas sealer.
     pub max_single_tx_gas: u32,
@@ -114,11 +183,21 @@ pub struct StateKeeperConfig {
     /// Max number of computational gas that validation step is allowed to take.
     pub validation_computational_gas_limit: u32,
     pub save_call_traces: bool,
+    /// Whether to persist call traces in the flat format. If disabled, call traces are persisted in the legacy format
+    /// understood by all node versions. Should only be enabled once all nodes reading call traces
+    /// from the same database (e.g., API servers) are updated.
+    #[serde(default)]
+    pub flat_call_traces: bool,
 
     /// The maximal number of circuits that a batch can support.
     /// Note, that this number corresponds to the "base layer" circuits, i.e. it does not include
     /// the recursion layers' circuits.
     pub max_circuits_per_batch: usize,
+    /// Path to the directory with overridden base system contracts: the proved batch bootloader
+    /// (`proved_batch.yul.zbin`) and the default account (`DefaultAccount.json` artifact).
+    /// If set, the server loads these contracts at startup instead of ones in the workspace and verifies
+    /// their hashes against the genesis config or the latest protocol version. Intended for local development.
+    pub base_system_contracts_path: Option<String>,
 
     // Base system contract hashes, required only for generating genesis config.
     // #PLA-811
@@ -132,6 +211,19 @@ pub struct StateKeeperConfig {
 }
 
 impl StateKeeperConfig {
+    pub fn graceful_shutdown_l1_batch_seal_timeout(&self) -> Option<Duration> {
+        self.graceful_shutdown_l1_batch_seal_timeout_ms
+            .map(Duration::from_millis)
+    }
+
+    pub fn max_batch_execution_time(&self) -> Option<Duration> {
+        self.max_batch_execution_time_ms.map(Duration::from_millis)
+    }
+
+    pub fn max_batch_vm_memory_bytes(&self) -> Option<usize> {
+        self.max_batch_vm_memory_mb.map(|mb| mb * 1_024 * 1_024)
+    }
+
     /// Creates a config object suitable for use in unit tests.
     /// Values mostly repeat the values used in the localhost environment.
     pub fn for_tests() -> Self {
@@ -142,6 +234,21 @@ impl StateKeeperConfig {
             l2_block_commit_deadline_ms: 1000,
             l2_block_seal_queue_capacity: 10,
             l2_block_max_payload_size: 1_000_000,
+            l2_block_max_pubdata: None,
+            max_timestamp_drift_sec: None,
+            graceful_shutdown: false,
+            graceful_shutdown_l1_batch_seal_timeout_ms: None,
+            discard_pending_l1_batch_on_restart: false,
+            l1_gas_price_rise_threshold: None,
+            l1_gas_price_rise_deadline_factor: None,
+            l1_gas_price_fall_threshold: None,
+            l1_gas_price_fall_deadline_factor: None,
+            max_batch_execution_time_ms: None,
+            max_batch_vm_memory_mb: None,
+            upgrade_canary_batches: None,
+            tx_presimulation_batch_size: None,
+            fee_account_rotation_period_sec: None,
+            bytecode_compression_dictionary_size: None,
             max_single_tx_gas: 6000000,
             max_allowed_l2_tx_gas_limit: 4000000000,
             reject_tx_at_geometry_percentage: 0.95,
@@ -162,7 +269,9 @@ impl StateKeeperConfig {
             fee_model_version: FeeModelVersion::V2,
             validation_computational_gas_limit: 300000,
             save_call_traces: true,
+            flat_call_traces: false,
             max_circuits_per_batch: 24100,
+            base_system_contracts_path: None,
             bootloader_hash: None,
             default_aa_hash: None,
             l1_batch_commit_data_generator_mode: L1BatchCommitmentMode::Rollup,
@@ -188,6 +297,12 @@ pub struct CircuitBreakerConfig {
     pub http_req_max_retry_number: usize,
     pub http_req_retry_interval_sec: u8,
     pub replication_lag_limit_sec: Option<u32>,
+    /// Action taken if the L2 supply of the base token or a bridged ERC20 token exceeds the funds locked
+    /// for the chain on L1. If not specified, the supply invariant is not checked.
+    pub supply_invariant_action: Option<SupplyInvariantAction>,
+    /// Allowed excess of the L2 base token supply over the funds locked on L1, in gwei (i.e., 10^9 base token units).
+    /// Doesn't apply to bridged ERC20 tokens, which are checked exactly.
+    pub supply_invariant_tolerance_gwei: Option<u64>,
 }
 
 impl CircuitBreakerConfig {
@@ -203,6 +318,20 @@ impl CircuitBreakerConfig {
         self.replication_lag_limit_sec
             .map(|limit| Duration::from_secs(limit.into()))
     }
+
+    /// Returns the allowed excess of the L2 base token supply in base token units.
+    pub fn supply_invariant_tolerance(&self) -> U256 {
+        U256::from(self.supply_invariant_tolerance_gwei.unwrap_or(0)) * U256::exp10(9)
+    }
+}
+
+/// Action taken by the circuit breaker if the L2 supply invariant is violated.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum SupplyInvariantAction {
+    /// Log an error and report the violation in metrics.
+    Alert,
+    /// Halt the server, same as for other circuit breakers.
+    Halt,
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -213,9 +342,48 @@ pub struct MempoolConfig {
     pub stuck_tx_timeout: u64,
     pub remove_stuck_txs: bool,
     pub delay_interval: u64,
+    /// Whether L2 transactions from different senders are ordered by their effective priority fee
+    /// instead of the arrival order. Transactions from the same sender are always ordered by nonce.
+    #[serde(default)]
+    pub priority_fee_ordering: bool,
+    /// Caps how much the priority fee ordering can deviate from the arrival order: a transaction can only be ordered
+    /// ahead of transactions received at most this long (in ms) before it.
+    #[serde(default = "MempoolConfig::default_priority_fee_ordering_window_ms")]
+    pub priority_fee_ordering_window_ms: NonZeroU64,
+    /// Priority fee per gas (in wei) that provides the maximum ordering advance (`priority_fee_ordering_window_ms`).
+    /// The advance is proportional to the priority fee; fees above this value do not provide further advantage.
+    #[serde(default = "MempoolConfig::default_priority_fee_ordering_max_fee")]
+    pub priority_fee_ordering_max_fee: NonZeroU64,
+    /// L2 transactions from these accounts are placed into the priority lane, i.e., are ordered ahead of all other
+    /// L2 transactions. Transactions can also be placed into the priority lane individually via the admin API.
+    #[serde(default)]
+    pub priority_lane_addresses: Vec<Address>,
+    /// Maximum number of transactions in the priority lane. Excess transactions are ordered among other L2 transactions.
+    #[serde(default = "MempoolConfig::default_priority_lane_capacity")]
+    pub priority_lane_capacity: usize,
+    /// Retention period (in seconds) for inclusion deadlines of transactions that are no longer pending.
+    #[serde(default = "MempoolConfig::default_deadlines_retention_sec")]
+    pub deadlines_retention_sec: u64,
 }
 
 impl MempoolConfig {
+    pub fn default_priority_fee_ordering_window_ms() -> NonZeroU64 {
+        NonZeroU64::new(1_000).unwrap()
+    }
+
+    /// 1 gwei.
+    pub fn default_priority_fee_ordering_max_fee() -> NonZeroU64 {
+        NonZeroU64::new(1_000_000_000).unwrap()
+    }
+
+    pub const fn default_priority_lane_capacity() -> usize {
+        1_000
+    }
+
+    pub const fn default_deadlines_retention_sec() -> u64 {
+        86_400
+    }
+
     pub fn sync_interval(&self) -> Duration {
         Duration::from_millis(self.sync_interval_ms)
     }
@@ -227,4 +395,8 @@ impl MempoolConfig {
     pub fn delay_interval(&self) -> Duration {
         Duration::from_millis(self.delay_interval)
     }
+
+    pub fn deadlines_retention(&self) -> Duration {
+        Duration::from_secs(self.deadlines_retention_sec)
+    }
 }
@@ -0,0 +1,84 @@
+use std::{str::FromStr, time::Duration};
+
+use anyhow::Context as _;
+use serde::Deserialize;
+
+/// Free disk space thresholds for a single monitored path.
+///
+/// Can be parsed from a string in the `<path>=<warn_threshold_mb>[:<stop_threshold_mb>]` format.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct MonitoredPathConfig {
+    /// Path to monitor, e.g. a RocksDB directory or a mount point of the Postgres data volume.
+    pub path: String,
+    /// If free space on the volume containing the path drops below this threshold, a warning is logged.
+    pub warn_threshold_mb: u64,
+    /// If free space on the volume containing the path drops below this threshold, the node is stopped
+    /// so that RocksDB instances are not corrupted by running out of disk space. If not specified,
+    /// the node is never stopped because of this path.
+    pub stop_threshold_mb: Option<u64>,
+}
+
+impl MonitoredPathConfig {
+    pub fn warn_threshold(&self) -> u64 {
+        self.warn_threshold_mb * super::BYTES_IN_MEGABYTE as u64
+    }
+
+    pub fn stop_threshold(&self) -> Option<u64> {
+        self.stop_threshold_mb
+            .map(|mb| mb * super::BYTES_IN_MEGABYTE as u64)
+    }
+}
+
+impl FromStr for MonitoredPathConfig {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (path, thresholds) = s.rsplit_once('=').context(
+            "monitored path must have `<path>=<warn_threshold_mb>[:<stop_threshold_mb>]` format",
+        )?;
+        let (warn_threshold_mb, stop_threshold_mb) = match thresholds.split_once(':') {
+            Some((warn, stop)) => (warn, Some(stop)),
+            None => (thresholds, None),
+        };
+        Ok(Self {
+            path: path.trim().to_owned(),
+            warn_threshold_mb: warn_threshold_mb
+                .trim()
+                .parse()
+                .context("invalid warning threshold")?,
+            stop_threshold_mb: stop_threshold_mb
+                .map(|mb| mb.trim().parse())
+                .transpose()
+                .context("invalid stop threshold")?,
+        })
+    }
+}
+
+impl TryFrom<String> for MonitoredPathConfig {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Configuration for the disk space monitor.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DiskSpaceMonitorConfig {
+    /// Interval between disk space checks.
+    #[serde(default = "DiskSpaceMonitorConfig::default_check_interval_ms")]
+    pub check_interval_ms: u64,
+    /// Monitored paths together with their thresholds.
+    pub paths: Vec<MonitoredPathConfig>,
+}
+
+impl DiskSpaceMonitorConfig {
+    const fn default_check_interval_ms() -> u64 {
+        30_000
+    }
+
+    pub fn check_interval(&self) -> Duration {
+        Duration::from_millis(self.check_interval_ms)
+    }
+}
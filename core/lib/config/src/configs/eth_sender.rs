@@ -1,8 +1,8 @@
-use std::time::Duration;
+use std::{str::FromStr, time::Duration};
 
 use anyhow::Context as _;
 use serde::Deserialize;
-use zksync_basic_types::H256;
+use zksync_basic_types::{Address, L1BatchNumber, H256, U256};
 use zksync_crypto_primitives::K256PrivateKey;
 
 use crate::EthWatchConfig;
@@ -15,6 +15,8 @@ pub struct EthConfig {
     /// Options related to the `GasAdjuster` submodule.
     pub gas_adjuster: Option<GasAdjusterConfig>,
     pub watcher: Option<EthWatchConfig>,
+    /// Options related to the withdrawal limiter. If not specified, withdrawals are not limited.
+    pub withdrawal_limits: Option<WithdrawalLimitsConfig>,
 }
 
 impl EthConfig {
@@ -38,6 +40,8 @@ impl EthConfig {
                 aggregated_block_execute_deadline: 10,
                 timestamp_criteria_max_allowed_lag: 30,
                 l1_batch_min_age_before_execute_seconds: None,
+                max_timestamp_drift_from_l1_sec: None,
+                l1_tx_forwarder_addr: None,
                 max_acceptable_priority_fee_in_gwei: 100000000000,
                 pubdata_sending_mode: PubdataSendingMode::Calldata,
             }),
@@ -59,6 +63,7 @@ impl EthConfig {
                 confirmations_for_eth_event: None,
                 eth_node_poll_interval: 0,
             }),
+            withdrawal_limits: None,
         }
     }
 }
@@ -111,6 +116,15 @@ pub struct SenderConfig {
     /// Note that this number must be slightly higher than the one set on the contract,
     /// because the contract uses block.timestamp which lags behind the clock time.
     pub l1_batch_min_age_before_execute_seconds: Option<u64>,
+    /// Maximum allowed drift (in seconds) of L1 batch timestamps into the future relative to the latest L1 block timestamp.
+    /// L1 batches with larger drift are not committed since the commitment would be rejected by the L1 contract.
+    /// If not specified, timestamps are not checked.
+    pub max_timestamp_drift_from_l1_sec: Option<u64>,
+    /// Address of an ERC-2771 forwarder contract relaying commit / prove / execute transactions. If set, L1 transactions
+    /// are paid for by the fee payer wallet, while the operator only signs forwarded requests, so that the operator
+    /// account doesn't need to hold ETH. Blob transactions are always sent directly by the blob operator.
+    /// The validator timelock must trust the forwarder; this is checked when the eth sender starts.
+    pub l1_tx_forwarder_addr: Option<Address>,
     // Max acceptable fee for sending tx it acts as a safeguard to prevent sending tx with very high fees.
     pub max_acceptable_priority_fee_in_gwei: u64,
 
@@ -150,6 +164,14 @@ impl SenderConfig {
             .ok()
             .map(|pk| pk.parse().unwrap())
     }
+
+    // Don't load fee payer private key, if it's not required
+    #[deprecated]
+    pub fn private_key_fee_payer(&self) -> Option<H256> {
+        std::env::var("ETH_SENDER_SENDER_FEE_PAYER_PRIVATE_KEY")
+            .ok()
+            .map(|pk| pk.parse().unwrap())
+    }
 }
 
 #[derive(Debug, Deserialize, Copy, Clone, PartialEq)]
@@ -204,3 +226,71 @@ impl GasAdjusterConfig {
         1.0
     }
 }
+
+/// Action taken by the withdrawal limiter if executing an L1 batch would exceed one of the limits.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum WithdrawalLimitAction {
+    /// Only raise an alert (log an error and report metrics); L1 batches are still executed.
+    Alert,
+    /// Delay execution of the offending L1 batch (and all following ones) until the limit allows it.
+    #[default]
+    DelayExecution,
+}
+
+/// Limit on the aggregate amount of a single token withdrawn within the limiter window.
+///
+/// Can be parsed from a string in the `<token address>=<max amount in decimal>` format.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(try_from = "String")]
+pub struct TokenWithdrawalLimit {
+    /// L2 token address. For the base token, this is the `L2BaseToken` system contract address.
+    pub token: Address,
+    /// Maximum amount of the token (in its base units) that can be withdrawn within the window.
+    pub max_amount: U256,
+}
+
+impl FromStr for TokenWithdrawalLimit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (token, max_amount) = s
+            .split_once('=')
+            .context("withdrawal limit must have `<token>=<max_amount>` format")?;
+        Ok(Self {
+            token: token.trim().parse().context("invalid token address")?,
+            max_amount: U256::from_dec_str(max_amount.trim()).context("invalid max amount")?,
+        })
+    }
+}
+
+impl TryFrom<String> for TokenWithdrawalLimit {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Configuration for the withdrawal limiter, which tracks the aggregate value of withdrawals
+/// in executed L1 batches over a sliding time window.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct WithdrawalLimitsConfig {
+    /// Length of the sliding window in seconds.
+    pub window_sec: u64,
+    /// Per-token withdrawal limits. Tokens not mentioned here are not limited.
+    #[serde(default)]
+    pub limits: Vec<TokenWithdrawalLimit>,
+    /// Action taken if a limit is exceeded.
+    #[serde(default)]
+    pub action: WithdrawalLimitAction,
+    /// Admin override: L1 batches up to and including this number are executed regardless of the limits.
+    /// Withdrawals in these batches are still accounted for.
+    pub override_up_to_l1_batch: Option<L1BatchNumber>,
+}
+
+impl WithdrawalLimitsConfig {
+    /// Converts `self.window_sec` into `Duration`.
+    pub fn window(&self) -> Duration {
+        Duration::from_secs(self.window_sec)
+    }
+}
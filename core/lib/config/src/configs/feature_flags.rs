@@ -0,0 +1,27 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// Configuration for feature flags used by node components for incremental rollouts.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FeatureFlagsConfig {
+    /// Names of enabled feature flags. Flags not mentioned here are disabled unless enabled by the remote source.
+    #[serde(default)]
+    pub enabled: Vec<String>,
+    /// URL of the remote source of feature flags. The source must respond to `GET` requests with a JSON object
+    /// mapping flag names to boolean values; these values override the ones specified in this config.
+    pub remote_url: Option<String>,
+    /// Interval between polling the remote source.
+    #[serde(default = "FeatureFlagsConfig::default_remote_poll_interval_ms")]
+    pub remote_poll_interval_ms: u64,
+}
+
+impl FeatureFlagsConfig {
+    const fn default_remote_poll_interval_ms() -> u64 {
+        60_000
+    }
+
+    pub fn remote_poll_interval(&self) -> Duration {
+        Duration::from_millis(self.remote_poll_interval_ms)
+    }
+}
@@ -23,6 +23,15 @@ pub struct FriProverConfig {
     pub witness_vector_receiver_port: u16,
     pub zone_read_url: String,
     pub availability_check_interval_in_secs: Option<u32>,
+    /// Maximum number of consecutive jobs of the same circuit type (and thus, using the same setup data)
+    /// the prover picks before falling back to regular job selection. If not set or 0, jobs
+    /// are always picked in the regular order.
+    pub max_sticky_jobs: Option<u32>,
+    /// Number of jobs prefetched (i.e., claimed and loaded from the object store) while the current job
+    /// is being processed. If not set or 0, jobs are not prefetched.
+    pub prefetch_depth: Option<u32>,
+    /// Maximum estimated memory used by prefetched jobs, in MiB. If not set, memory usage is not capped.
+    pub prefetch_max_memory_mb: Option<u32>,
 
     // whether to write to public GCS bucket for https://github.com/matter-labs/era-boojum-validator-cli
     pub shall_save_to_public_bucket: bool,
@@ -34,4 +43,17 @@ impl FriProverConfig {
     pub fn proof_generation_timeout(&self) -> Duration {
         Duration::from_secs(self.generation_timeout_in_secs as u64)
     }
+
+    pub fn max_sticky_jobs(&self) -> u32 {
+        self.max_sticky_jobs.unwrap_or(0)
+    }
+
+    pub fn prefetch_depth(&self) -> usize {
+        self.prefetch_depth.unwrap_or(0) as usize
+    }
+
+    pub fn prefetch_max_memory_bytes(&self) -> Option<usize> {
+        self.prefetch_max_memory_mb
+            .map(|mb| (mb as usize).saturating_mul(1 << 20))
+    }
 }
@@ -6,6 +6,8 @@ use serde::Deserialize;
 pub struct FriProverGatewayConfig {
     pub api_url: String,
     pub api_poll_duration_secs: u16,
+    /// API key used to authenticate in the proof data handler API.
+    pub api_key: Option<String>,
 
     /// Configurations for prometheus
     pub prometheus_listener_port: u16,
@@ -23,6 +23,11 @@ pub struct FriWitnessGeneratorConfig {
 
     // whether to write to public GCS bucket for https://github.com/matter-labs/era-boojum-validator-cli
     pub shall_save_to_public_bucket: bool,
+    /// Number of jobs prefetched (i.e., claimed and loaded from the object store) while the current job
+    /// is being processed. If not set or 0, jobs are not prefetched.
+    pub prefetch_depth: Option<u32>,
+    /// Maximum estimated memory used by prefetched jobs, in MiB. If not set, memory usage is not capped.
+    pub prefetch_max_memory_mb: Option<u32>,
 }
 
 #[derive(Debug)]
@@ -85,4 +90,13 @@ impl FriWitnessGeneratorConfig {
     pub fn last_l1_batch_to_process(&self) -> u32 {
         self.last_l1_batch_to_process.unwrap_or(u32::MAX)
     }
+
+    pub fn prefetch_depth(&self) -> usize {
+        self.prefetch_depth.unwrap_or(0) as usize
+    }
+
+    pub fn prefetch_max_memory_bytes(&self) -> Option<usize> {
+        self.prefetch_max_memory_mb
+            .map(|mb| (mb as usize).saturating_mul(1 << 20))
+    }
 }
@@ -17,6 +17,9 @@ pub struct GenesisConfig {
     pub genesis_commitment: Option<H256>,
     pub bootloader_hash: Option<H256>,
     pub default_aa_hash: Option<H256>,
+    /// Hash of the EVM emulator bytecode. If not set, the EVM emulator is disabled for the chain.
+    #[serde(default)]
+    pub evm_emulator_hash: Option<H256>,
     pub l1_chain_id: L1ChainId,
     pub l2_chain_id: L2ChainId,
     pub recursion_node_level_vk_hash: H256,
@@ -41,6 +44,7 @@ impl GenesisConfig {
             genesis_commitment: Some(H256::repeat_byte(0x17)),
             bootloader_hash: Default::default(),
             default_aa_hash: Default::default(),
+            evm_emulator_hash: None,
             l1_chain_id: L1ChainId(9),
             protocol_version: Some(ProtocolSemanticVersion {
                 minor: ProtocolVersionId::latest(),
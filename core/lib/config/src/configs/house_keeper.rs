@@ -17,6 +17,23 @@ pub struct HouseKeeperConfig {
     pub prover_job_archiver_archive_after_secs: Option<u64>,
     pub fri_gpu_prover_archiver_archiving_interval_ms: Option<u64>,
     pub fri_gpu_prover_archiver_archive_after_secs: Option<u64>,
+    pub eth_txs_history_archiver_archiving_interval_ms: Option<u64>,
+    pub eth_txs_history_archiver_archive_after_secs: Option<u64>,
+    /// Age (in days) after which mined L1 transactions are moved to the archive together with their sending attempts.
+    /// The latest mined transaction of each type is never archived. If not set, L1 transactions are not archived.
+    /// The API server doesn't return L1 transaction hashes for L1 batches whose transactions were archived.
+    pub eth_txs_history_archiver_archive_eth_txs_after_days: Option<u32>,
+    pub l2_block_partitions_maintenance_interval_ms: Option<u64>,
+    /// Number of L2 blocks in a single partition of partitioned tables (e.g., `events`).
+    pub l2_blocks_per_partition: Option<u32>,
+    /// Interval between runs of the task converting call traces stored in the legacy format to the flat format.
+    /// If not set, the task is disabled. Nodes that do not support the flat format cannot read converted traces,
+    /// so the task should only be enabled once all nodes sharing the DB are updated.
+    pub call_traces_migration_interval_ms: Option<u64>,
+    /// Interval between runs of the task pruning the journal of transactions rejected by the API server
+    /// on submission. The task only runs if the journal is enabled (i.e., the API `rejected_txs_retention_sec`
+    /// is set). If not set, 1 minute is used.
+    pub rejected_txs_pruning_interval_ms: Option<u64>,
 }
 
 impl HouseKeeperConfig {
@@ -29,4 +46,18 @@ impl HouseKeeperConfig {
         self.fri_gpu_prover_archiver_archiving_interval_ms
             .zip(self.fri_gpu_prover_archiver_archive_after_secs)
     }
+
+    pub fn eth_txs_history_archiver_params(&self) -> Option<(u64, u64)> {
+        self.eth_txs_history_archiver_archiving_interval_ms
+            .zip(self.eth_txs_history_archiver_archive_after_secs)
+    }
+
+    pub fn l2_block_partitions_maintainer_params(&self) -> Option<(u64, u32)> {
+        self.l2_block_partitions_maintenance_interval_ms
+            .zip(self.l2_blocks_per_partition)
+    }
+
+    pub fn rejected_txs_pruning_interval_ms(&self) -> u64 {
+        self.rejected_txs_pruning_interval_ms.unwrap_or(60_000)
+    }
 }
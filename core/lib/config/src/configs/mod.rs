@@ -4,9 +4,11 @@ pub use self::{
     contract_verifier::ContractVerifierConfig,
     contracts::{ContractsConfig, EcosystemContracts},
     database::{DBConfig, PostgresConfig},
+    disk_space_monitor::DiskSpaceMonitorConfig,
     eth_sender::{EthConfig, GasAdjusterConfig},
     eth_watch::EthWatchConfig,
     experimental::ExperimentalDBConfig,
+    feature_flags::FeatureFlagsConfig,
     fri_proof_compressor::FriProofCompressorConfig,
     fri_prover::FriProverConfig,
     fri_prover_gateway::FriProverGatewayConfig,
@@ -17,10 +19,11 @@ pub use self::{
     object_store::ObjectStoreConfig,
     observability::{ObservabilityConfig, OpentelemetryConfig},
     proof_data_handler::ProofDataHandlerConfig,
+    scheduler::SchedulerConfig,
     secrets::{DatabaseSecrets, L1Secrets, Secrets},
     snapshots_creator::SnapshotsCreatorConfig,
     utils::PrometheusConfig,
-    vm_runner::ProtectiveReadsWriterConfig,
+    vm_runner::{BasicWitnessInputProducerConfig, ProtectiveReadsWriterConfig},
 };
 
 pub mod api;
@@ -29,9 +32,11 @@ pub mod consensus;
 pub mod contract_verifier;
 pub mod contracts;
 pub mod database;
+pub mod disk_space_monitor;
 pub mod eth_sender;
 pub mod eth_watch;
 mod experimental;
+pub mod feature_flags;
 pub mod fri_proof_compressor;
 pub mod fri_prover;
 pub mod fri_prover_gateway;
@@ -44,6 +49,7 @@ pub mod house_keeper;
 pub mod object_store;
 pub mod observability;
 pub mod proof_data_handler;
+pub mod scheduler;
 pub mod secrets;
 pub mod snapshots_creator;
 pub mod utils;
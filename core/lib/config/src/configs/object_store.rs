@@ -16,6 +16,14 @@ pub struct ObjectStoreConfig {
     /// **Important.** Mirroring logic assumes that objects in the underlying store are immutable. If this is not the case,
     /// the mirrored objects may become stale.
     pub local_mirror_path: Option<String>,
+    /// Object store used as a replica for critical objects (final proofs and snapshots). If specified, such objects
+    /// are copied to the replica after they are stored, and reads fail over to the replica if the primary store
+    /// is unavailable. The replica cannot have a replica itself.
+    ///
+    /// When loading from env variables, the replica is configured using the same variables as the primary store
+    /// with the `REPLICA_` infix, e.g. `OBJECT_STORE_REPLICA_MODE`.
+    #[serde(skip)]
+    pub replica: Option<Box<ObjectStoreConfig>>,
 }
 
 impl ObjectStoreConfig {
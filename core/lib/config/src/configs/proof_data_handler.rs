@@ -6,6 +6,14 @@ use serde::Deserialize;
 pub struct ProofDataHandlerConfig {
     pub http_port: u16,
     pub proof_generation_timeout_in_secs: u16,
+    /// Provers authorized to access the API, each in the `<prover_id>:<API key hash>` format, where the hash
+    /// is a hex-encoded keccak256 digest of the API key. If empty, `allow_unauthenticated_access` must be set.
+    #[serde(default)]
+    pub authorized_provers: Vec<String>,
+    /// Explicitly allows accessing the API without authentication if no `authorized_provers` are configured.
+    /// If not set and there are no authorized provers, the proof data handler fails on startup.
+    #[serde(default)]
+    pub allow_unauthenticated_access: bool,
 }
 
 impl ProofDataHandlerConfig {
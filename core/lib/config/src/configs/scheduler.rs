@@ -0,0 +1,81 @@
+use std::{str::FromStr, time::Duration};
+
+use anyhow::Context as _;
+use serde::Deserialize;
+
+/// Schedule of a single periodic maintenance job.
+///
+/// Can be parsed from a string in the `<name>=<cron_schedule>[ jitter_ms=<ms>][ group=<group>]` format,
+/// e.g. `EthTxsHistoryArchiver=*/10 * * * * jitter_ms=30000 group=db`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct ScheduledJobConfig {
+    /// Name of the job as registered in the scheduler. Currently, the house keeper registers `EthTxsHistoryArchiver`,
+    /// `L2BlockPartitionsMaintainer`, `CallTracesMigrator`, `FriProverJobsArchiver` and `FriGpuProverArchiver`;
+    /// these jobs are run by the scheduler instead of their own interval loops if they have a schedule.
+    pub name: String,
+    /// Cron-like schedule with 5 fields: minute, hour, day of month, month and day of week (all in UTC).
+    /// Each field supports `*`, single values, ranges (`1-5`), steps (`*/10`, `0-30/5`) and comma-separated lists.
+    pub schedule: String,
+    /// Maximum random delay added to each scheduled run, so that jobs with the same schedule
+    /// on different nodes don't start simultaneously.
+    pub jitter_ms: u64,
+    /// Jobs in the same mutual exclusion group never run concurrently; if a job is scheduled while
+    /// another job in its group is running, it waits for the running job to finish.
+    pub exclusion_group: Option<String>,
+}
+
+impl ScheduledJobConfig {
+    pub fn jitter(&self) -> Duration {
+        Duration::from_millis(self.jitter_ms)
+    }
+}
+
+impl FromStr for ScheduledJobConfig {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, rest) = s.split_once('=').context(
+            "scheduled job must have `<name>=<cron_schedule>[ jitter_ms=<ms>][ group=<group>]` format",
+        )?;
+        let mut schedule_fields = vec![];
+        let mut jitter_ms = 0;
+        let mut exclusion_group = None;
+        for token in rest.split_whitespace() {
+            match token.split_once('=') {
+                Some(("jitter_ms", value)) => {
+                    jitter_ms = value.parse().context("invalid jitter")?;
+                }
+                Some(("group", value)) => {
+                    exclusion_group = Some(value.to_owned());
+                }
+                Some((key, _)) => anyhow::bail!("unknown scheduled job option: `{key}`"),
+                None => schedule_fields.push(token),
+            }
+        }
+        anyhow::ensure!(!schedule_fields.is_empty(), "schedule is not specified");
+
+        Ok(Self {
+            name: name.trim().to_owned(),
+            schedule: schedule_fields.join(" "),
+            jitter_ms,
+            exclusion_group,
+        })
+    }
+}
+
+impl TryFrom<String> for ScheduledJobConfig {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Configuration for the scheduler of periodic maintenance jobs (pruning, GC, consistency checks etc.).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SchedulerConfig {
+    /// Schedules of the jobs. Jobs registered in the scheduler without a schedule are not run.
+    #[serde(default)]
+    pub jobs: Vec<ScheduledJobConfig>,
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use serde::Deserialize;
 
 use crate::ObjectStoreConfig;
@@ -7,9 +9,36 @@ pub struct SnapshotsCreatorConfig {
     #[serde(default = "snapshots_creator_storage_logs_chunk_size_default")]
     pub storage_logs_chunk_size: u64,
 
+    /// Maximum number of concurrent queries to the replica DB. Also used as the replica connection pool size.
     #[serde(default = "snapshots_creator_concurrent_queries_count")]
     pub concurrent_queries_count: u32,
     pub object_store: Option<ObjectStoreConfig>,
+
+    /// If set, the creator runs continuously, checking the snapshot creation policy with this interval.
+    /// Otherwise, the creator creates a single snapshot and exits.
+    pub schedule_poll_interval_ms: Option<u64>,
+    /// Scheduled mode: create a snapshot once this many L1 batches are executed on L1 after the latest snapshot.
+    /// If not set, snapshots are only created on demand (i.e., requested via the `admin_requestSnapshot` RPC method).
+    pub l1_batch_interval: Option<u32>,
+    /// Scheduled mode: start of the daily low API load window (UTC hour, 0..=23). Scheduled snapshots are only
+    /// started within the window; on-demand snapshots are created regardless of it. If not set together with
+    /// `low_load_window_end_hour_utc`, snapshots can be started at any time.
+    pub low_load_window_start_hour_utc: Option<u32>,
+    /// Scheduled mode: end (exclusive) of the daily low API load window (UTC hour, 0..=23). May be less than
+    /// the start hour, in which case the window wraps around midnight.
+    pub low_load_window_end_hour_utc: Option<u32>,
+}
+
+impl SnapshotsCreatorConfig {
+    pub fn schedule_poll_interval(&self) -> Option<Duration> {
+        self.schedule_poll_interval_ms.map(Duration::from_millis)
+    }
+
+    /// Returns the low API load window as `(start_hour, end_hour)` in UTC.
+    pub fn low_load_window_utc(&self) -> Option<(u32, u32)> {
+        self.low_load_window_start_hour_utc
+            .zip(self.low_load_window_end_hour_utc)
+    }
 }
 
 fn snapshots_creator_storage_logs_chunk_size_default() -> u64 {
@@ -62,11 +62,17 @@ impl Wallet {
 pub struct EthSender {
     pub operator: Wallet,
     pub blob_operator: Option<Wallet>,
+    /// Wallet paying for L1 transactions relayed via an ERC-2771 forwarder. Required if
+    /// [`SenderConfig::l1_tx_forwarder_addr`](crate::configs::eth_sender::SenderConfig::l1_tx_forwarder_addr) is set.
+    pub fee_payer: Option<Wallet>,
 }
 
 #[derive(Debug, Clone)]
 pub struct StateKeeper {
     pub fee_account: AddressWallet,
+    /// Additional fee accounts rotated with `fee_account` across L1 batches. If empty, `fee_account`
+    /// is used for all batches.
+    pub extra_fee_accounts: Vec<AddressWallet>,
 }
 
 #[derive(Debug, Clone)]
@@ -83,9 +89,11 @@ impl Wallets {
                 blob_operator: Some(
                     Wallet::from_private_key_bytes(H256::repeat_byte(0x2), None).unwrap(),
                 ),
+                fee_payer: None,
             }),
             state_keeper: Some(StateKeeper {
                 fee_account: AddressWallet::from_address(H160::repeat_byte(0x3)),
+                extra_fee_accounts: vec![],
             }),
         }
     }
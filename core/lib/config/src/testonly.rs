@@ -1,4 +1,4 @@
-use std::num::NonZeroUsize;
+use std::num::{NonZeroU64, NonZeroUsize};
 
 use rand::{distributions::Distribution, Rng};
 use zksync_basic_types::{
@@ -6,7 +6,7 @@ use zksync_basic_types::{
     commitment::L1BatchCommitmentMode,
     network::Network,
     protocol_version::{ProtocolSemanticVersion, ProtocolVersionId, VersionPatch},
-    L1ChainId, L2ChainId,
+    L1BatchNumber, L1ChainId, L2ChainId, U256,
 };
 use zksync_consensus_utils::EncodeDist;
 
@@ -71,9 +71,14 @@ impl Distribution<configs::api::Web3JsonRpcConfig> for EncodeDist {
             account_pks: self.sample_opt(|| self.sample_range(rng).map(|_| rng.gen()).collect()),
             estimate_gas_scale_factor: self.sample(rng),
             estimate_gas_acceptable_overestimation: self.sample(rng),
+            estimate_fee_batch_max_size: self.sample(rng),
             max_tx_size: self.sample(rng),
             vm_execution_cache_misses_limit: self.sample(rng),
             vm_concurrency_limit: self.sample(rng),
+            vm_concurrency_limit_per_client: self.sample(rng),
+            vm_client_id_header: self.sample(rng),
+            vm_client_id_trusted_proxies: self.sample(rng),
+            vm_permit_timeout_ms: self.sample(rng),
             factory_deps_cache_size_mb: self.sample(rng),
             initial_writes_cache_size_mb: self.sample(rng),
             latest_values_cache_size_mb: self.sample(rng),
@@ -96,7 +101,37 @@ impl Distribution<configs::api::Web3JsonRpcConfig> for EncodeDist {
             tree_api_url: self.sample(rng),
             mempool_cache_update_interval: self.sample(rng),
             mempool_cache_size: self.sample(rng),
+            eth_call_cache_size: self.sample(rng),
+            eth_call_cache_ttl_ms: self.sample(rng),
+            rejected_txs_retention_sec: self.sample(rng),
+            tx_replacement_fee_bump_percent: self.sample(rng),
+            query_statement_timeout_ms: self.sample(rng),
+            mempool_max_txs_per_sender: self.sample(rng),
+            mempool_max_txs: self.sample(rng),
+            mempool_max_size_mb: self.sample(rng),
+            admin_namespace_enabled: self.sample(rng),
+            admin_api_keys: self.sample_range(rng).map(|_| self.sample(rng)).collect(),
+            api_runtime_worker_threads: self.sample(rng),
+            idempotency_keys_cache_size: self.sample(rng),
+            idempotency_key_ttl_sec: self.sample(rng),
+            min_gas_per_pubdata_limit: self.sample(rng),
+            max_gas_per_pubdata_limit: self.sample(rng),
+            reject_unprotected_txs: self.sample(rng),
+            vm_memory_pool_size: self.sample(rng),
             whitelisted_tokens_for_aa: self.sample_range(rng).map(|_| rng.gen()).collect(),
+            geth_compatibility: self.sample_collect(rng),
+        }
+    }
+}
+
+impl Distribution<configs::api::GethCompatibilityShim> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::api::GethCompatibilityShim {
+        type T = configs::api::GethCompatibilityShim;
+        match rng.gen_range(0..4) {
+            0 => T::BlockMiner,
+            1 => T::ErrorCodes,
+            2 => T::NetWeb3Methods,
+            _ => T::BlockFields,
         }
     }
 }
@@ -157,6 +192,21 @@ impl Distribution<configs::chain::StateKeeperConfig> for EncodeDist {
             l2_block_commit_deadline_ms: self.sample(rng),
             l2_block_seal_queue_capacity: self.sample(rng),
             l2_block_max_payload_size: self.sample(rng),
+            l2_block_max_pubdata: self.sample(rng),
+            max_timestamp_drift_sec: self.sample(rng),
+            graceful_shutdown: self.sample(rng),
+            graceful_shutdown_l1_batch_seal_timeout_ms: self.sample(rng),
+            discard_pending_l1_batch_on_restart: self.sample(rng),
+            l1_gas_price_rise_threshold: self.sample(rng),
+            l1_gas_price_rise_deadline_factor: self.sample(rng),
+            l1_gas_price_fall_threshold: self.sample(rng),
+            l1_gas_price_fall_deadline_factor: self.sample(rng),
+            max_batch_execution_time_ms: self.sample(rng),
+            max_batch_vm_memory_mb: self.sample(rng),
+            upgrade_canary_batches: self.sample(rng),
+            tx_presimulation_batch_size: self.sample(rng),
+            fee_account_rotation_period_sec: self.sample(rng),
+            bytecode_compression_dictionary_size: self.sample(rng),
             max_single_tx_gas: self.sample(rng),
             max_allowed_l2_tx_gas_limit: self.sample(rng),
             reject_tx_at_geometry_percentage: self.sample(rng),
@@ -174,7 +224,9 @@ impl Distribution<configs::chain::StateKeeperConfig> for EncodeDist {
             fee_model_version: self.sample(rng),
             validation_computational_gas_limit: self.sample(rng),
             save_call_traces: self.sample(rng),
+            flat_call_traces: self.sample(rng),
             max_circuits_per_batch: self.sample(rng),
+            base_system_contracts_path: self.sample(rng),
             // These values are not involved into files serialization skip them
             fee_account_addr: None,
             bootloader_hash: None,
@@ -199,6 +251,18 @@ impl Distribution<configs::chain::CircuitBreakerConfig> for EncodeDist {
             http_req_max_retry_number: self.sample(rng),
             http_req_retry_interval_sec: self.sample(rng),
             replication_lag_limit_sec: self.sample(rng),
+            supply_invariant_action: self.sample(rng),
+            supply_invariant_tolerance_gwei: self.sample(rng),
+        }
+    }
+}
+
+impl Distribution<configs::chain::SupplyInvariantAction> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::chain::SupplyInvariantAction {
+        type T = configs::chain::SupplyInvariantAction;
+        match rng.gen_range(0..2) {
+            0 => T::Alert,
+            _ => T::Halt,
         }
     }
 }
@@ -212,6 +276,12 @@ impl Distribution<configs::chain::MempoolConfig> for EncodeDist {
             stuck_tx_timeout: self.sample(rng),
             remove_stuck_txs: self.sample(rng),
             delay_interval: self.sample(rng),
+            priority_fee_ordering: self.sample(rng),
+            priority_fee_ordering_window_ms: NonZeroU64::new(rng.gen_range(1..=10_000)).unwrap(),
+            priority_fee_ordering_max_fee: NonZeroU64::new(rng.gen_range(1..=u64::MAX)).unwrap(),
+            priority_lane_addresses: self.sample_range(rng).map(|_| rng.gen()).collect(),
+            priority_lane_capacity: self.sample(rng),
+            deadlines_retention_sec: self.sample(rng),
         }
     }
 }
@@ -294,6 +364,57 @@ impl Distribution<configs::database::DBConfig> for EncodeDist {
     }
 }
 
+impl Distribution<configs::disk_space_monitor::MonitoredPathConfig> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+    ) -> configs::disk_space_monitor::MonitoredPathConfig {
+        configs::disk_space_monitor::MonitoredPathConfig {
+            path: self.sample(rng),
+            warn_threshold_mb: self.sample(rng),
+            stop_threshold_mb: self.sample(rng),
+        }
+    }
+}
+
+impl Distribution<configs::DiskSpaceMonitorConfig> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::DiskSpaceMonitorConfig {
+        configs::DiskSpaceMonitorConfig {
+            check_interval_ms: self.sample(rng),
+            paths: self.sample_collect(rng),
+        }
+    }
+}
+
+impl Distribution<configs::scheduler::ScheduledJobConfig> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::scheduler::ScheduledJobConfig {
+        configs::scheduler::ScheduledJobConfig {
+            name: self.sample(rng),
+            schedule: self.sample(rng),
+            jitter_ms: self.sample(rng),
+            exclusion_group: self.sample(rng),
+        }
+    }
+}
+
+impl Distribution<configs::SchedulerConfig> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::SchedulerConfig {
+        configs::SchedulerConfig {
+            jobs: self.sample_collect(rng),
+        }
+    }
+}
+
+impl Distribution<configs::FeatureFlagsConfig> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::FeatureFlagsConfig {
+        configs::FeatureFlagsConfig {
+            enabled: self.sample_collect(rng),
+            remote_url: self.sample(rng),
+            remote_poll_interval_ms: self.sample(rng),
+        }
+    }
+}
+
 impl Distribution<configs::database::PostgresConfig> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::database::PostgresConfig {
         configs::database::PostgresConfig {
@@ -315,6 +436,7 @@ impl Distribution<configs::EthConfig> for EncodeDist {
             sender: self.sample(rng),
             gas_adjuster: self.sample(rng),
             watcher: self.sample(rng),
+            withdrawal_limits: self.sample(rng),
         }
     }
 }
@@ -368,6 +490,8 @@ impl Distribution<configs::eth_sender::SenderConfig> for EncodeDist {
             aggregated_block_execute_deadline: self.sample(rng),
             timestamp_criteria_max_allowed_lag: self.sample(rng),
             l1_batch_min_age_before_execute_seconds: self.sample(rng),
+            max_timestamp_drift_from_l1_sec: self.sample(rng),
+            l1_tx_forwarder_addr: self.sample_opt(|| rng.gen()),
             max_acceptable_priority_fee_in_gwei: self.sample(rng),
             pubdata_sending_mode: PubdataSendingMode::Calldata,
         }
@@ -393,6 +517,36 @@ impl Distribution<configs::eth_sender::GasAdjusterConfig> for EncodeDist {
     }
 }
 
+impl Distribution<configs::eth_sender::WithdrawalLimitAction> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::eth_sender::WithdrawalLimitAction {
+        type T = configs::eth_sender::WithdrawalLimitAction;
+        match rng.gen_range(0..2) {
+            0 => T::Alert,
+            _ => T::DelayExecution,
+        }
+    }
+}
+
+impl Distribution<configs::eth_sender::TokenWithdrawalLimit> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::eth_sender::TokenWithdrawalLimit {
+        configs::eth_sender::TokenWithdrawalLimit {
+            token: rng.gen(),
+            max_amount: U256([rng.gen(), rng.gen(), rng.gen(), rng.gen()]),
+        }
+    }
+}
+
+impl Distribution<configs::eth_sender::WithdrawalLimitsConfig> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::eth_sender::WithdrawalLimitsConfig {
+        configs::eth_sender::WithdrawalLimitsConfig {
+            window_sec: self.sample(rng),
+            limits: self.sample_collect(rng),
+            action: self.sample(rng),
+            override_up_to_l1_batch: self.sample_opt(|| L1BatchNumber(rng.gen())),
+        }
+    }
+}
+
 impl Distribution<configs::EthWatchConfig> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::EthWatchConfig {
         configs::EthWatchConfig {
@@ -442,6 +596,9 @@ impl Distribution<configs::FriProverConfig> for EncodeDist {
             zone_read_url: self.sample(rng),
             shall_save_to_public_bucket: self.sample(rng),
             availability_check_interval_in_secs: self.sample(rng),
+            max_sticky_jobs: self.sample(rng),
+            prefetch_depth: self.sample(rng),
+            prefetch_max_memory_mb: self.sample(rng),
             prover_object_store: self.sample(rng),
             public_object_store: self.sample(rng),
         }
@@ -453,6 +610,7 @@ impl Distribution<configs::FriProverGatewayConfig> for EncodeDist {
         configs::FriProverGatewayConfig {
             api_url: self.sample(rng),
             api_poll_duration_secs: self.sample(rng),
+            api_key: self.sample(rng),
             prometheus_listener_port: self.sample(rng),
             prometheus_pushgateway_url: self.sample(rng),
             prometheus_push_interval_ms: self.sample(rng),
@@ -551,6 +709,8 @@ impl Distribution<configs::FriWitnessGeneratorConfig> for EncodeDist {
             max_attempts: self.sample(rng),
             last_l1_batch_to_process: self.sample(rng),
             shall_save_to_public_bucket: self.sample(rng),
+            prefetch_depth: self.sample(rng),
+            prefetch_max_memory_mb: self.sample(rng),
         }
     }
 }
@@ -586,6 +746,13 @@ impl Distribution<configs::house_keeper::HouseKeeperConfig> for EncodeDist {
             prover_job_archiver_archive_after_secs: self.sample(rng),
             fri_gpu_prover_archiver_archiving_interval_ms: self.sample(rng),
             fri_gpu_prover_archiver_archive_after_secs: self.sample(rng),
+            eth_txs_history_archiver_archiving_interval_ms: self.sample(rng),
+            eth_txs_history_archiver_archive_after_secs: self.sample(rng),
+            eth_txs_history_archiver_archive_eth_txs_after_days: self.sample(rng),
+            l2_block_partitions_maintenance_interval_ms: self.sample(rng),
+            l2_blocks_per_partition: self.sample(rng),
+            call_traces_migration_interval_ms: self.sample(rng),
+            rejected_txs_pruning_interval_ms: self.sample(rng),
         }
     }
 }
@@ -617,6 +784,14 @@ impl Distribution<configs::ObjectStoreConfig> for EncodeDist {
             mode: self.sample(rng),
             max_retries: self.sample(rng),
             local_mirror_path: self.sample(rng),
+            replica: rng.gen_bool(0.5).then(|| {
+                Box::new(configs::ObjectStoreConfig {
+                    mode: self.sample(rng),
+                    max_retries: self.sample(rng),
+                    local_mirror_path: self.sample(rng),
+                    replica: None,
+                })
+            }),
         }
     }
 }
@@ -626,6 +801,8 @@ impl Distribution<configs::ProofDataHandlerConfig> for EncodeDist {
         configs::ProofDataHandlerConfig {
             http_port: self.sample(rng),
             proof_generation_timeout_in_secs: self.sample(rng),
+            authorized_provers: self.sample_range(rng).map(|_| self.sample(rng)).collect(),
+            allow_unauthenticated_access: self.sample(rng),
         }
     }
 }
@@ -636,6 +813,10 @@ impl Distribution<configs::SnapshotsCreatorConfig> for EncodeDist {
             storage_logs_chunk_size: self.sample(rng),
             concurrent_queries_count: self.sample(rng),
             object_store: self.sample(rng),
+            schedule_poll_interval_ms: self.sample(rng),
+            l1_batch_interval: self.sample(rng),
+            low_load_window_start_hour_utc: self.sample(rng),
+            low_load_window_end_hour_utc: self.sample(rng),
         }
     }
 }
@@ -676,6 +857,7 @@ impl Distribution<configs::GenesisConfig> for EncodeDist {
             genesis_commitment: rng.gen(),
             bootloader_hash: rng.gen(),
             default_aa_hash: rng.gen(),
+            evm_emulator_hash: rng.gen(),
             fee_account: rng.gen(),
             l1_chain_id: L1ChainId(self.sample(rng)),
             l2_chain_id: L2ChainId::default(),
@@ -6,6 +6,7 @@
 
 use std::{
     fs::{self, File},
+    io,
     path::{Path, PathBuf},
 };
 
@@ -45,6 +46,10 @@ const DIAMOND_INIT_CONTRACT_FILE: (&str, &str) = (
     "state-transition",
     "chain-interfaces/IDiamondInit.sol/IDiamondInit.json",
 );
+const L1_SHARED_BRIDGE_CONTRACT_FILE: (&str, &str) = (
+    "bridge",
+    "interfaces/IL1SharedBridge.sol/IL1SharedBridge.json",
+);
 const GOVERNANCE_CONTRACT_FILE: (&str, &str) = ("governance", "IGovernance.sol/IGovernance.json");
 const MULTICALL3_CONTRACT_FILE: (&str, &str) = ("dev-contracts", "Multicall3.sol/Multicall3.json");
 const VERIFIER_CONTRACT_FILE: (&str, &str) = ("state-transition", "Verifier.sol/Verifier.json");
@@ -123,6 +128,10 @@ pub fn bridgehub_contract() -> Contract {
     load_contract_for_both_compilers(BRIDGEHUB_CONTRACT_FILE)
 }
 
+pub fn l1_shared_bridge_contract() -> Contract {
+    load_contract_for_both_compilers(L1_SHARED_BRIDGE_CONTRACT_FILE)
+}
+
 pub fn governance_contract() -> Contract {
     load_contract_for_both_compilers(GOVERNANCE_CONTRACT_FILE)
 }
@@ -288,9 +297,12 @@ pub struct SystemContractCode {
 pub struct BaseSystemContracts {
     pub bootloader: SystemContractCode,
     pub default_aa: SystemContractCode,
+    /// Code of the EVM emulator. Only set for chains with the EVM emulator enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evm_emulator: Option<SystemContractCode>,
 }
 
-#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct BaseSystemContractsHashes {
     pub bootloader: H256,
     pub default_aa: H256,
@@ -300,11 +312,18 @@ impl PartialEq for BaseSystemContracts {
     fn eq(&self, other: &Self) -> bool {
         self.bootloader.hash == other.bootloader.hash
             && self.default_aa.hash == other.default_aa.hash
+            && self.evm_emulator.as_ref().map(|code| code.hash)
+                == other.evm_emulator.as_ref().map(|code| code.hash)
     }
 }
 
+/// Name of the proved batch bootloader file in the directory with overridden base system contracts.
+pub const BOOTLOADER_OVERRIDE_FILE: &str = "proved_batch.yul.zbin";
+/// Name of the default account artifact in the directory with overridden base system contracts.
+pub const DEFAULT_AA_OVERRIDE_FILE: &str = "DefaultAccount.json";
+
 impl BaseSystemContracts {
-    fn load_with_bootloader(bootloader_bytecode: Vec<u8>) -> Self {
+    fn from_bytecodes(bootloader_bytecode: Vec<u8>, default_aa_bytecode: Vec<u8>) -> Self {
         let hash = hash_bytecode(&bootloader_bytecode);
 
         let bootloader = SystemContractCode {
@@ -312,19 +331,77 @@ impl BaseSystemContracts {
             hash,
         };
 
-        let bytecode = read_sys_contract_bytecode("", "DefaultAccount", ContractLanguage::Sol);
-        let hash = hash_bytecode(&bytecode);
+        let hash = hash_bytecode(&default_aa_bytecode);
 
         let default_aa = SystemContractCode {
-            code: bytes_to_be_words(bytecode),
+            code: bytes_to_be_words(default_aa_bytecode),
             hash,
         };
 
         BaseSystemContracts {
             bootloader,
             default_aa,
+            evm_emulator: None,
         }
     }
+
+    /// Sets the EVM emulator code for these contracts.
+    pub fn with_evm_emulator(mut self, evm_emulator: SystemContractCode) -> Self {
+        self.evm_emulator = Some(evm_emulator);
+        self
+    }
+
+    /// Returns the EVM emulator code hash, if the EVM emulator is set.
+    pub fn evm_emulator_hash(&self) -> Option<H256> {
+        self.evm_emulator.as_ref().map(|code| code.hash)
+    }
+
+    fn load_with_bootloader(bootloader_bytecode: Vec<u8>) -> Self {
+        let bytecode = read_sys_contract_bytecode("", "DefaultAccount", ContractLanguage::Sol);
+        Self::from_bytecodes(bootloader_bytecode, bytecode)
+    }
+
+    /// Loads base system contracts from the specified directory. The directory must contain the proved batch
+    /// bootloader ([`BOOTLOADER_OVERRIDE_FILE`]) and the default account artifact ([`DEFAULT_AA_OVERRIDE_FILE`]).
+    ///
+    /// Unlike other loading methods, this one doesn't depend on the workspace location and returns an error
+    /// instead of panicking if contracts cannot be read.
+    pub fn load_from_dir(dir: &Path) -> io::Result<Self> {
+        let bootloader_path = dir.join(BOOTLOADER_OVERRIDE_FILE);
+        let bootloader_bytecode = fs::read(&bootloader_path).map_err(|err| {
+            io::Error::new(
+                err.kind(),
+                format!("cannot read bootloader at {bootloader_path:?}: {err}"),
+            )
+        })?;
+
+        let default_aa_path = dir.join(DEFAULT_AA_OVERRIDE_FILE);
+        let artifact: serde_json::Value = fs::read(&default_aa_path)
+            .and_then(|bytes| Ok(serde_json::from_slice(&bytes)?))
+            .map_err(|err| {
+                io::Error::new(
+                    err.kind(),
+                    format!("cannot read default account artifact at {default_aa_path:?}: {err}"),
+                )
+            })?;
+        let default_aa_bytecode = artifact["bytecode"]
+            .as_str()
+            .and_then(|bytecode| bytecode.strip_prefix("0x"))
+            .and_then(|bytecode| hex::decode(bytecode).ok())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "no valid hex bytecode in default account artifact at {default_aa_path:?}"
+                    ),
+                )
+            })?;
+
+        Ok(Self::from_bytecodes(
+            bootloader_bytecode,
+            default_aa_bytecode,
+        ))
+    }
     // BaseSystemContracts with proved bootloader - for handling transactions.
     pub fn load_from_disk() -> Self {
         let bootloader_bytecode = read_proved_batch_bootloader_bytecode();
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS rejected_transactions
+(
+    tx_hash           BYTEA     NOT NULL PRIMARY KEY,
+    initiator_address BYTEA     NOT NULL,
+    nonce             BIGINT    NOT NULL,
+    error_code        TEXT      NOT NULL,
+    reason            TEXT      NOT NULL,
+    rejected_at       TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS rejected_transactions_rejected_at_idx ON rejected_transactions (rejected_at);
@@ -0,0 +1,5 @@
+-- Superseded sending attempts for confirmed L1 transactions moved out of `eth_txs_history` by the house keeper.
+-- Confirmed attempts are never archived since they are referenced from `eth_txs`.
+CREATE TABLE IF NOT EXISTS eth_txs_history_archive (LIKE eth_txs_history INCLUDING DEFAULTS);
+ALTER TABLE eth_txs_history_archive ADD PRIMARY KEY (id);
+CREATE INDEX IF NOT EXISTS eth_txs_history_archive_eth_tx_id_idx ON eth_txs_history_archive (eth_tx_id);
@@ -0,0 +1 @@
+ALTER TABLE proof_generation_details ADD COLUMN IF NOT EXISTS picked_by TEXT;
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS l1_batch_fee_params;
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS l1_batch_fee_params
+(
+    l1_batch_number BIGINT    NOT NULL PRIMARY KEY,
+    fee_params      JSONB     NOT NULL,
+    created_at      TIMESTAMP NOT NULL,
+    updated_at      TIMESTAMP NOT NULL
+);
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS traced_addresses
+(
+    address    BYTEA     NOT NULL PRIMARY KEY,
+    created_at TIMESTAMP NOT NULL
+);
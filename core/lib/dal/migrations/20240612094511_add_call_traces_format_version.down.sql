@@ -0,0 +1 @@
+ALTER TABLE call_traces DROP COLUMN format_version;
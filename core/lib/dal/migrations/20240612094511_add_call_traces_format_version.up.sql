@@ -0,0 +1 @@
+ALTER TABLE call_traces ADD COLUMN format_version SMALLINT NOT NULL DEFAULT 1;
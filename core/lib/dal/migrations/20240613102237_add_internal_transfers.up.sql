@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS internal_transfers (
+    tx_hash BYTEA NOT NULL REFERENCES transactions (hash) ON DELETE CASCADE,
+    transfer_index INT NOT NULL,
+    miniblock_number BIGINT NOT NULL,
+    from_address BYTEA NOT NULL,
+    to_address BYTEA NOT NULL,
+    value NUMERIC(80, 0) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (tx_hash, transfer_index)
+);
+
+CREATE INDEX IF NOT EXISTS internal_transfers_miniblock_number_idx ON internal_transfers (miniblock_number);
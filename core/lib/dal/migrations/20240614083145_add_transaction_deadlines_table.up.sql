@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS transaction_deadlines
+(
+    tx_hash             BYTEA     NOT NULL PRIMARY KEY,
+    not_after_l2_block  BIGINT,
+    not_after_timestamp BIGINT,
+    expired_at          TIMESTAMP,
+    created_at          TIMESTAMP NOT NULL
+);
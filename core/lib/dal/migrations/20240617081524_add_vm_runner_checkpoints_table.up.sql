@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS vm_runner_checkpoints
+(
+    runner_name            TEXT      NOT NULL,
+    l1_batch_number        BIGINT    NOT NULL,
+    last_executed_l2_block BIGINT    NOT NULL,
+    created_at             TIMESTAMP NOT NULL,
+    updated_at             TIMESTAMP NOT NULL,
+    PRIMARY KEY (runner_name, l1_batch_number)
+);
@@ -0,0 +1,2 @@
+DROP TRIGGER IF EXISTS l1_batch_sealed_notification ON l1_batches;
+DROP FUNCTION IF EXISTS notify_l1_batch_sealed;
@@ -0,0 +1,10 @@
+CREATE OR REPLACE FUNCTION notify_l1_batch_sealed() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('l1_batch_sealed', NEW.number::TEXT);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER l1_batch_sealed_notification
+    AFTER INSERT ON l1_batches
+    FOR EACH ROW EXECUTE FUNCTION notify_l1_batch_sealed();
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS component_watermarks
+(
+    component       TEXT      NOT NULL PRIMARY KEY,
+    l1_batch_number BIGINT    NOT NULL,
+    created_at      TIMESTAMP NOT NULL,
+    updated_at      TIMESTAMP NOT NULL
+);
@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS operator_audit_log_append_only ON operator_audit_log;
+DROP FUNCTION IF EXISTS forbid_operator_audit_log_changes;
+DROP TABLE IF EXISTS operator_audit_log;
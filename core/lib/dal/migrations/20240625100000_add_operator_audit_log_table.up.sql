@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS operator_audit_log
+(
+    id         BIGSERIAL PRIMARY KEY,
+    actor      TEXT      NOT NULL,
+    action     TEXT      NOT NULL,
+    details    JSONB     NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+-- The audit log is append-only; entries cannot be modified or removed (except by truncating the table).
+CREATE OR REPLACE FUNCTION forbid_operator_audit_log_changes() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'operator_audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER operator_audit_log_append_only
+    BEFORE UPDATE OR DELETE ON operator_audit_log
+    FOR EACH ROW EXECUTE FUNCTION forbid_operator_audit_log_changes();
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS snapshot_requests
+(
+    id           BIGSERIAL PRIMARY KEY,
+    requested_by TEXT      NOT NULL,
+    created_at   TIMESTAMP NOT NULL,
+    processed_at TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS snapshot_requests_pending_idx ON snapshot_requests (id) WHERE processed_at IS NULL;
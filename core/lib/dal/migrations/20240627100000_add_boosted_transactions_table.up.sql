@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS boosted_transactions
+(
+    tx_hash    BYTEA     NOT NULL PRIMARY KEY REFERENCES transactions (hash) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL
+);
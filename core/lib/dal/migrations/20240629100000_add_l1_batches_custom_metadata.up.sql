@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS l1_batches_custom_metadata
+(
+    l1_batch_number BIGINT    NOT NULL REFERENCES l1_batches (number) ON DELETE CASCADE,
+    key             TEXT      NOT NULL,
+    value           TEXT      NOT NULL,
+    created_at      TIMESTAMP NOT NULL,
+    PRIMARY KEY (l1_batch_number, key)
+);
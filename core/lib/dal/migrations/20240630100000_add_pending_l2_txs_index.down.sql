@@ -0,0 +1 @@
+DROP INDEX IF EXISTS transactions_pending_l2_fee_idx;
@@ -0,0 +1,3 @@
+-- Used to enforce global mempool quotas and to select eviction candidates.
+CREATE INDEX IF NOT EXISTS transactions_pending_l2_fee_idx ON transactions (max_fee_per_gas, received_at)
+    WHERE miniblock_number IS NULL AND is_priority = FALSE AND error IS NULL;
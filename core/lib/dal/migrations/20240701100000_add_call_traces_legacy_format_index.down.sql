@@ -0,0 +1 @@
+DROP INDEX IF EXISTS call_traces_legacy_format_idx;
@@ -0,0 +1,3 @@
+-- Used by the call traces migrator to iterate over traces in the legacy format.
+CREATE INDEX IF NOT EXISTS call_traces_legacy_format_idx ON call_traces (tx_hash)
+    WHERE format_version = 1;
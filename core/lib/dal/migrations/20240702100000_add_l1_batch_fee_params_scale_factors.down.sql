@@ -0,0 +1,3 @@
+ALTER TABLE l1_batch_fee_params
+    DROP COLUMN IF EXISTS l1_gas_price_scale_factor,
+    DROP COLUMN IF EXISTS l1_pubdata_price_scale_factor;
@@ -0,0 +1,5 @@
+-- Scale factors used for previously persisted batches are unknown (they were taken from the node config at the time),
+-- so they are left NULL for these batches.
+ALTER TABLE l1_batch_fee_params
+    ADD COLUMN IF NOT EXISTS l1_gas_price_scale_factor DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS l1_pubdata_price_scale_factor DOUBLE PRECISION;
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS vm_runner_commitment_recomputer
+(
+    l1_batch_number BIGINT    NOT NULL PRIMARY KEY,
+    created_at      TIMESTAMP NOT NULL,
+    updated_at      TIMESTAMP NOT NULL
+);
@@ -0,0 +1,12 @@
+-- Restore cursors for batches processed since the watermarks replaced them.
+INSERT INTO
+    vm_runner_protective_reads (l1_batch_number, created_at, updated_at)
+SELECT
+    l1_batch_number,
+    NOW(),
+    NOW()
+FROM
+    component_watermarks
+WHERE
+    component = 'protective_reads'
+ON CONFLICT (l1_batch_number) DO NOTHING;
@@ -0,0 +1,36 @@
+-- Protective reads writer and basic witness input producer now track their progress using component watermarks
+-- instead of `vm_runner_*` tables. The tables are no longer used as cursors, but are retained so that older
+-- server versions can still run on this schema.
+INSERT INTO
+    component_watermarks (component, l1_batch_number, created_at, updated_at)
+SELECT
+    'protective_reads',
+    MAX(l1_batch_number),
+    NOW(),
+    NOW()
+FROM
+    vm_runner_protective_reads
+HAVING
+    MAX(l1_batch_number) IS NOT NULL
+ON CONFLICT (component) DO
+UPDATE
+SET
+    l1_batch_number = GREATEST(component_watermarks.l1_batch_number, excluded.l1_batch_number),
+    updated_at = NOW();
+
+INSERT INTO
+    component_watermarks (component, l1_batch_number, created_at, updated_at)
+SELECT
+    'basic_witness_input_producer',
+    MAX(l1_batch_number),
+    NOW(),
+    NOW()
+FROM
+    vm_runner_bwip
+HAVING
+    MAX(l1_batch_number) IS NOT NULL
+ON CONFLICT (component) DO
+UPDATE
+SET
+    l1_batch_number = GREATEST(component_watermarks.l1_batch_number, excluded.l1_batch_number),
+    updated_at = NOW();
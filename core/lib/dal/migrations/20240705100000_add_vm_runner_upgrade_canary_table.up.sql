@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS vm_runner_upgrade_canary
+(
+    l1_batch_number  BIGINT    NOT NULL PRIMARY KEY,
+    protocol_version INT       NOT NULL,
+    created_at       TIMESTAMP NOT NULL,
+    updated_at       TIMESTAMP NOT NULL
+);
+CREATE INDEX IF NOT EXISTS vm_runner_upgrade_canary_protocol_version_idx
+    ON vm_runner_upgrade_canary (protocol_version);
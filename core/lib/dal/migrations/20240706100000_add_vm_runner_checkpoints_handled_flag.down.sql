@@ -0,0 +1,2 @@
+ALTER TABLE vm_runner_checkpoints
+    DROP COLUMN IF EXISTS is_l1_batch_handled;
@@ -0,0 +1,2 @@
+ALTER TABLE vm_runner_checkpoints
+    ADD COLUMN IF NOT EXISTS is_l1_batch_handled BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,12 @@
+DROP TRIGGER IF EXISTS reset_l1_batches_eth_tx_ids ON eth_txs;
+DROP FUNCTION IF EXISTS reset_l1_batches_eth_tx_ids;
+
+-- Constraints are not validated since `l1_batches` may reference archived L1 transactions.
+ALTER TABLE l1_batches ADD CONSTRAINT blocks_eth_commit_tx_id_fkey
+    FOREIGN KEY (eth_commit_tx_id) REFERENCES eth_txs (id) ON DELETE SET NULL NOT VALID;
+ALTER TABLE l1_batches ADD CONSTRAINT blocks_eth_prove_tx_id_fkey
+    FOREIGN KEY (eth_prove_tx_id) REFERENCES eth_txs (id) ON DELETE SET NULL NOT VALID;
+ALTER TABLE l1_batches ADD CONSTRAINT blocks_eth_execute_tx_id_fkey
+    FOREIGN KEY (eth_execute_tx_id) REFERENCES eth_txs (id) ON DELETE SET NULL NOT VALID;
+
+DROP TABLE IF EXISTS eth_txs_archive;
@@ -0,0 +1,33 @@
+-- L1 transactions moved out of `eth_txs` together with all their sending attempts by the house keeper
+-- once they have been confirmed for longer than the configured retention period.
+CREATE TABLE IF NOT EXISTS eth_txs_archive (LIKE eth_txs INCLUDING DEFAULTS);
+ALTER TABLE eth_txs_archive ADD PRIMARY KEY (id);
+
+-- Archiving an L1 transaction must not reset references to it from `l1_batches`; otherwise, the archived
+-- operations would be considered not sent and would be resent by `eth_sender`. Hence, `ON DELETE SET NULL`
+-- foreign keys are replaced with a trigger that only resets references to deleted transactions
+-- that weren't archived (e.g., when clearing failed transactions or reverting L1 batches).
+ALTER TABLE l1_batches DROP CONSTRAINT IF EXISTS blocks_eth_commit_tx_id_fkey;
+ALTER TABLE l1_batches DROP CONSTRAINT IF EXISTS blocks_eth_prove_tx_id_fkey;
+ALTER TABLE l1_batches DROP CONSTRAINT IF EXISTS blocks_eth_execute_tx_id_fkey;
+
+CREATE OR REPLACE FUNCTION reset_l1_batches_eth_tx_ids() RETURNS TRIGGER AS $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM eth_txs_archive WHERE id = OLD.id) THEN
+        UPDATE l1_batches
+        SET
+            eth_commit_tx_id = NULLIF(eth_commit_tx_id, OLD.id),
+            eth_prove_tx_id = NULLIF(eth_prove_tx_id, OLD.id),
+            eth_execute_tx_id = NULLIF(eth_execute_tx_id, OLD.id)
+        WHERE
+            eth_commit_tx_id = OLD.id
+            OR eth_prove_tx_id = OLD.id
+            OR eth_execute_tx_id = OLD.id;
+    END IF;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER reset_l1_batches_eth_tx_ids
+    AFTER DELETE ON eth_txs
+    FOR EACH ROW EXECUTE FUNCTION reset_l1_batches_eth_tx_ids();
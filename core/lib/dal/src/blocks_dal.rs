@@ -3,6 +3,7 @@ use std::{
     convert::{Into, TryInto},
     ops,
     sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
 };
 
 use anyhow::Context as _;
@@ -12,12 +13,14 @@ use zksync_db_connection::{
     error::{DalResult, SqlxContext},
     instrument::{InstrumentExt, Instrumented},
     interpolate_query, match_query_as,
+    utils::pg_interval_from_duration,
 };
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
     block::{BlockGasCount, L1BatchHeader, L1BatchTreeData, L2BlockHeader, StorageOracleInfo},
     circuit::CircuitStatistic,
     commitment::{L1BatchCommitmentArtifacts, L1BatchWithMetadata},
+    fee_model::BatchFeeInputParams,
     writes::TreeWrite,
     Address, L1BatchNumber, L2BlockNumber, ProtocolVersionId, H256, U256,
 };
@@ -32,6 +35,10 @@ use crate::{
     Core, CoreDal,
 };
 
+/// Postgres notification channel receiving a notification each time an L1 batch is sealed (i.e., inserted
+/// into the `l1_batches` table). The notification payload is the decimal number of the sealed batch.
+pub const L1_BATCH_SEALED_CHANNEL: &str = "l1_batch_sealed";
+
 #[derive(Debug)]
 pub struct BlocksDal<'a, 'c> {
     pub(crate) storage: &'a mut Connection<'c, Core>,
@@ -985,6 +992,88 @@ impl BlocksDal<'_, '_> {
         Ok(())
     }
 
+    /// Unconditionally overwrites commitment artifacts for the specified L1 batch. Unlike
+    /// [`Self::save_l1_batch_commitment_artifacts()`], this doesn't check that the existing commitment
+    /// matches the provided one, so it should only be used by maintenance tooling repairing corrupted metadata.
+    pub async fn overwrite_l1_batch_commitment_artifacts(
+        &mut self,
+        number: L1BatchNumber,
+        commitment_artifacts: &L1BatchCommitmentArtifacts,
+    ) -> DalResult<()> {
+        let mut transaction = self.storage.start_transaction().await?;
+
+        let update_result = sqlx::query!(
+            r#"
+            UPDATE l1_batches
+            SET
+                commitment = $1,
+                aux_data_hash = $2,
+                pass_through_data_hash = $3,
+                meta_parameters_hash = $4,
+                l2_l1_merkle_root = $5,
+                zkporter_is_available = $6,
+                compressed_state_diffs = $7,
+                compressed_initial_writes = $8,
+                compressed_repeated_writes = $9,
+                updated_at = NOW()
+            WHERE
+                number = $10
+            "#,
+            commitment_artifacts.commitment_hash.commitment.as_bytes(),
+            commitment_artifacts.commitment_hash.aux_output.as_bytes(),
+            commitment_artifacts
+                .commitment_hash
+                .pass_through_data
+                .as_bytes(),
+            commitment_artifacts
+                .commitment_hash
+                .meta_parameters
+                .as_bytes(),
+            commitment_artifacts.l2_l1_merkle_root.as_bytes(),
+            commitment_artifacts.zkporter_is_available,
+            commitment_artifacts.compressed_state_diffs,
+            commitment_artifacts.compressed_initial_writes,
+            commitment_artifacts.compressed_repeated_writes,
+            i64::from(number.0),
+        )
+        .instrument("overwrite_l1_batch_commitment_artifacts")
+        .with_arg("number", &number)
+        .report_latency()
+        .execute(&mut transaction)
+        .await?;
+        if update_result.rows_affected() == 0 {
+            tracing::warn!(
+                "L1 batch #{number}: commitment info wasn't overwritten as the batch is missing"
+            );
+            return Ok(());
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                commitments (l1_batch_number, events_queue_commitment, bootloader_initial_content_commitment)
+            VALUES
+                ($1, $2, $3)
+            ON CONFLICT (l1_batch_number) DO
+            UPDATE
+            SET
+                events_queue_commitment = excluded.events_queue_commitment,
+                bootloader_initial_content_commitment = excluded.bootloader_initial_content_commitment
+            "#,
+            i64::from(number.0),
+            commitment_artifacts.aux_commitments.map(|a| a.events_queue_commitment.0.to_vec()),
+            commitment_artifacts.aux_commitments
+                .map(|a| a.bootloader_initial_content_commitment.0.to_vec()),
+        )
+        .instrument("overwrite_batch_aux_commitments")
+        .with_arg("number", &number)
+        .report_latency()
+        .execute(&mut transaction)
+        .await?;
+
+        transaction.commit().await
+    }
+
     pub async fn get_last_committed_to_eth_l1_batch(
         &mut self,
     ) -> DalResult<Option<L1BatchWithMetadata>> {
@@ -2054,6 +2143,38 @@ impl BlocksDal<'_, '_> {
         .map(|row| row.timestamp as u64))
     }
 
+    /// Returns numbers of L1 batches for which an execute transaction was created within `window`
+    /// (i.e., in the last `window` time from now), in ascending order.
+    pub async fn get_l1_batches_executed_within(
+        &mut self,
+        window: Duration,
+    ) -> DalResult<Vec<L1BatchNumber>> {
+        let window = pg_interval_from_duration(window);
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batches.number
+            FROM
+                l1_batches
+                JOIN eth_txs ON l1_batches.eth_execute_tx_id = eth_txs.id
+            WHERE
+                eth_txs.created_at >= NOW() - $1::INTERVAL
+            ORDER BY
+                l1_batches.number
+            "#,
+            &window
+        )
+        .instrument("get_l1_batches_executed_within")
+        .with_arg("window", &window)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| L1BatchNumber(row.number as u32))
+            .collect())
+    }
+
     pub async fn get_batch_protocol_version_id(
         &mut self,
         l1_batch_number: L1BatchNumber,
@@ -2273,6 +2394,111 @@ impl BlocksDal<'_, '_> {
         .map(|row| row.tree_writes_are_present)
         .unwrap_or(false))
     }
+
+    /// Saves fee model inputs used to compute the fee input of the specified L1 batch. Inputs for a re-opened batch
+    /// (e.g., after a node restart or a revert) are overwritten.
+    pub async fn insert_l1_batch_fee_params(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        params: &BatchFeeInputParams,
+    ) -> DalResult<()> {
+        let instrumentation = Instrumented::new("insert_l1_batch_fee_params")
+            .with_arg("l1_batch_number", &l1_batch_number)
+            .with_arg("params", params);
+        let fee_params = serde_json::to_value(params.fee_params)
+            .map_err(|err| instrumentation.arg_error("params.fee_params", err))?;
+        let query = sqlx::query!(
+            r#"
+            INSERT INTO
+                l1_batch_fee_params (
+                    l1_batch_number,
+                    fee_params,
+                    l1_gas_price_scale_factor,
+                    l1_pubdata_price_scale_factor,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, $4, NOW(), NOW())
+            ON CONFLICT (l1_batch_number) DO
+            UPDATE
+            SET
+                fee_params = $2,
+                l1_gas_price_scale_factor = $3,
+                l1_pubdata_price_scale_factor = $4,
+                updated_at = NOW()
+            "#,
+            i64::from(l1_batch_number.0),
+            fee_params,
+            params.l1_gas_price_scale_factor,
+            params.l1_pubdata_price_scale_factor
+        );
+        instrumentation.with(query).execute(self.storage).await?;
+        Ok(())
+    }
+
+    /// Returns fee model inputs used to compute the fee input of the specified L1 batch, or `None` if they were not persisted
+    /// (e.g., for batches produced by an external node or before params started being persisted). Also returns `None`
+    /// for batches persisted before scale factors started being persisted, since the inputs are incomplete for them.
+    pub async fn get_l1_batch_fee_params(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<BatchFeeInputParams>> {
+        let Some(row) = sqlx::query!(
+            r#"
+            SELECT
+                fee_params,
+                l1_gas_price_scale_factor,
+                l1_pubdata_price_scale_factor
+            FROM
+                l1_batch_fee_params
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("get_l1_batch_fee_params")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let (Some(l1_gas_price_scale_factor), Some(l1_pubdata_price_scale_factor)) = (
+            row.l1_gas_price_scale_factor,
+            row.l1_pubdata_price_scale_factor,
+        ) else {
+            return Ok(None);
+        };
+        let fee_params = serde_json::from_value(row.fee_params)
+            .context("invalid value for fee_params in the DB")?;
+        Ok(Some(BatchFeeInputParams {
+            fee_params,
+            l1_gas_price_scale_factor,
+            l1_pubdata_price_scale_factor,
+        }))
+    }
+
+    /// Deletes fee model inputs for all L1 batches after the specified one.
+    pub async fn delete_l1_batch_fee_params(
+        &mut self,
+        last_batch_to_keep: L1BatchNumber,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM l1_batch_fee_params
+            WHERE
+                l1_batch_number > $1
+            "#,
+            i64::from(last_batch_to_keep.0)
+        )
+        .instrument("delete_l1_batch_fee_params")
+        .with_arg("last_batch_to_keep", &last_batch_to_keep)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
 }
 
 /// These methods should only be used for tests.
@@ -2361,6 +2587,7 @@ impl BlocksDal<'_, '_> {
 mod tests {
     use zksync_contracts::BaseSystemContractsHashes;
     use zksync_types::{
+        fee_model::FeeParams,
         l2_to_l1_log::{L2ToL1Log, UserL2ToL1Log},
         Address, ProtocolVersion, ProtocolVersionId,
     };
@@ -2578,4 +2805,80 @@ mod tests {
             assert_eq!(gas, 3 * expected_gas);
         }
     }
+
+    #[tokio::test]
+    async fn persisting_l1_batch_fee_params() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let fee_params = conn
+            .blocks_dal()
+            .get_l1_batch_fee_params(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert!(fee_params.is_none());
+
+        conn.blocks_dal()
+            .insert_l1_batch_fee_params(
+                L1BatchNumber(1),
+                &BatchFeeInputParams::unscaled(FeeParams::sensible_v1_default()),
+            )
+            .await
+            .unwrap();
+        let FeeParams::V1(mut fee_params) = FeeParams::sensible_v1_default() else {
+            unreachable!();
+        };
+        fee_params.l1_gas_price *= 2;
+        let params = BatchFeeInputParams {
+            fee_params: FeeParams::V1(fee_params),
+            l1_gas_price_scale_factor: 1.5,
+            l1_pubdata_price_scale_factor: 2.0,
+        };
+        conn.blocks_dal()
+            .insert_l1_batch_fee_params(L1BatchNumber(1), &params)
+            .await
+            .unwrap();
+
+        let loaded_params = conn
+            .blocks_dal()
+            .get_l1_batch_fee_params(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded_params.l1_gas_price_scale_factor, 1.5);
+        assert_eq!(loaded_params.l1_pubdata_price_scale_factor, 2.0);
+        let FeeParams::V1(loaded_params) = loaded_params.fee_params else {
+            panic!("unexpected fee params: {loaded_params:?}");
+        };
+        assert_eq!(loaded_params.l1_gas_price, fee_params.l1_gas_price);
+        assert_eq!(
+            loaded_params.config.minimal_l2_gas_price,
+            fee_params.config.minimal_l2_gas_price
+        );
+
+        // Emulate a row persisted before scale factors were added.
+        sqlx::query(
+            "INSERT INTO l1_batch_fee_params (l1_batch_number, fee_params, created_at, updated_at) \
+             SELECT 2, fee_params, NOW(), NOW() FROM l1_batch_fee_params WHERE l1_batch_number = 1",
+        )
+        .execute(conn.conn())
+        .await
+        .unwrap();
+        let fee_params = conn
+            .blocks_dal()
+            .get_l1_batch_fee_params(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert!(fee_params.is_none());
+
+        conn.blocks_dal()
+            .delete_l1_batch_fee_params(L1BatchNumber(0))
+            .await
+            .unwrap();
+        let fee_params = conn
+            .blocks_dal()
+            .get_l1_batch_fee_params(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert!(fee_params.is_none());
+    }
 }
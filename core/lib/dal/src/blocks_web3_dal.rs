@@ -1,6 +1,10 @@
+use std::time::Duration;
+
 use zksync_db_connection::{
-    connection::Connection, error::DalResult, instrument::InstrumentExt, interpolate_query,
-    match_query_as,
+    connection::Connection,
+    error::{DalResult, SqlxContext},
+    instrument::InstrumentExt,
+    interpolate_query, match_query_as,
 };
 use zksync_system_constants::EMPTY_UNCLES_HASH;
 use zksync_types::{
@@ -19,7 +23,7 @@ use crate::{
             ResolvedL1BatchForL2Block, StorageBlockDetails, StorageL1BatchDetails,
             LEGACY_BLOCK_GAS_LIMIT,
         },
-        storage_transaction::CallTrace,
+        storage_call_trace::CallTrace,
     },
     Core,
 };
@@ -514,9 +518,13 @@ impl BlocksWeb3Dal<'_, '_> {
     }
 
     /// Returns call traces for all transactions in the specified L2 block in the order of their execution.
+    /// At most `limit` traces are returned. If `statement_timeout` is specified, loading traces is aborted
+    /// if it takes longer than the timeout.
     pub async fn get_traces_for_l2_block(
         &mut self,
         block_number: L2BlockNumber,
+        limit: usize,
+        statement_timeout: Option<Duration>,
     ) -> DalResult<Vec<Call>> {
         let protocol_version = sqlx::query!(
             r#"
@@ -540,11 +548,12 @@ impl BlocksWeb3Dal<'_, '_> {
         let protocol_version =
             protocol_version.unwrap_or_else(ProtocolVersionId::last_potentially_undefined);
 
-        Ok(sqlx::query_as!(
+        sqlx::query_as!(
             CallTrace,
             r#"
             SELECT
-                call_trace
+                call_trace,
+                format_version
             FROM
                 call_traces
                 INNER JOIN transactions ON tx_hash = transactions.hash
@@ -552,16 +561,23 @@ impl BlocksWeb3Dal<'_, '_> {
                 transactions.miniblock_number = $1
             ORDER BY
                 transactions.index_in_block
+            LIMIT
+                $2
             "#,
-            i64::from(block_number.0)
+            i64::from(block_number.0),
+            limit as i64
         )
+        .try_map(|call_trace| {
+            call_trace
+                .into_call(protocol_version)
+                .decode_column("call_trace")
+        })
         .instrument("get_traces_for_l2_block")
         .with_arg("block_number", &block_number)
+        .with_arg("limit", &limit)
+        .with_statement_timeout(statement_timeout)
         .fetch_all(self.storage)
-        .await?
-        .into_iter()
-        .map(|call_trace| call_trace.into_call(protocol_version))
-        .collect())
+        .await
     }
 
     /// Returns `base_fee_per_gas` for L2 block range [min(newest_block - block_count + 1, 0), newest_block]
@@ -741,6 +757,7 @@ mod tests {
             create_l2_block_header, create_snapshot_recovery, mock_execution_result,
             mock_l2_transaction,
         },
+        transactions_dal::CallTraceFormat,
         ConnectionPool, Core, CoreDal,
     };
 
@@ -964,6 +981,7 @@ mod tests {
                 &tx_results,
                 1.into(),
                 ProtocolVersionId::latest(),
+                CallTraceFormat::Legacy,
                 false,
             )
             .await
@@ -971,7 +989,7 @@ mod tests {
 
         let traces = conn
             .blocks_web3_dal()
-            .get_traces_for_l2_block(L2BlockNumber(1))
+            .get_traces_for_l2_block(L2BlockNumber(1), 100, None)
             .await
             .unwrap();
         assert_eq!(traces.len(), 2);
@@ -979,5 +997,12 @@ mod tests {
             let expected_trace = tx_result.call_trace().unwrap();
             assert_eq!(*trace, expected_trace);
         }
+
+        let traces = conn
+            .blocks_web3_dal()
+            .get_traces_for_l2_block(L2BlockNumber(1), 1, Some(Duration::from_secs(10)))
+            .await
+            .unwrap();
+        assert_eq!(traces.len(), 1);
     }
 }
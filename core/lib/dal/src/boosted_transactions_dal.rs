@@ -0,0 +1,142 @@
+use std::collections::HashSet;
+
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+use zksync_types::H256;
+
+use crate::Core;
+
+/// DAL for L2 transactions boosted by the operator to the mempool priority lane.
+#[derive(Debug)]
+pub struct BoostedTransactionsDal<'c, 'a> {
+    pub(crate) storage: &'c mut Connection<'a, Core>,
+}
+
+impl BoostedTransactionsDal<'_, '_> {
+    /// Boosts a pending L2 transaction. The transaction is scheduled to be re-fetched into the mempool so that
+    /// the boost takes effect even if the transaction is already in the mempool. Returns `false` if the transaction
+    /// is not pending or is already boosted.
+    pub async fn boost_transaction(&mut self, tx_hash: H256) -> DalResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            WITH
+                pending AS (
+                    UPDATE transactions
+                    SET
+                        in_mempool = FALSE
+                    WHERE
+                        hash = $1
+                        AND miniblock_number IS NULL
+                        AND error IS NULL
+                        AND is_priority = FALSE
+                    RETURNING
+                        hash
+                )
+            INSERT INTO
+                boosted_transactions (tx_hash, created_at)
+            SELECT
+                hash,
+                NOW()
+            FROM
+                pending
+            ON CONFLICT (tx_hash) DO NOTHING
+            "#,
+            tx_hash.as_bytes()
+        )
+        .instrument("boost_transaction")
+        .with_arg("tx_hash", &tx_hash)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Returns hashes of boosted transactions among the specified ones.
+    pub async fn get_boosted_transactions(
+        &mut self,
+        tx_hashes: &[H256],
+    ) -> DalResult<HashSet<H256>> {
+        let hashes: Vec<_> = tx_hashes.iter().map(H256::as_bytes).collect();
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                tx_hash
+            FROM
+                boosted_transactions
+            WHERE
+                tx_hash = ANY ($1)
+            "#,
+            &hashes as &[&[u8]]
+        )
+        .instrument("get_boosted_transactions")
+        .with_arg("tx_hashes.len", &tx_hashes.len())
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| H256::from_slice(&row.tx_hash))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::fee::TransactionExecutionMetrics;
+
+    use super::*;
+    use crate::{tests::mock_l2_transaction, ConnectionPool, CoreDal};
+
+    #[tokio::test]
+    async fn boosting_transactions() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+
+        let boosted = conn
+            .boosted_transactions_dal()
+            .boost_transaction(tx_hash)
+            .await
+            .unwrap();
+        assert!(!boosted, "unknown transaction was boosted");
+
+        conn.transactions_dal()
+            .insert_transaction_l2(&tx, TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        let synced = conn
+            .transactions_dal()
+            .sync_mempool(&[], &[], 0, 0, 100)
+            .await
+            .unwrap();
+        assert_eq!(synced.len(), 1);
+
+        let boosted = conn
+            .boosted_transactions_dal()
+            .boost_transaction(tx_hash)
+            .await
+            .unwrap();
+        assert!(boosted);
+        let boosted = conn
+            .boosted_transactions_dal()
+            .boost_transaction(tx_hash)
+            .await
+            .unwrap();
+        assert!(!boosted, "transaction was boosted twice");
+
+        let boosted_hashes = conn
+            .boosted_transactions_dal()
+            .get_boosted_transactions(&[tx_hash, H256::repeat_byte(1)])
+            .await
+            .unwrap();
+        assert_eq!(boosted_hashes, HashSet::from([tx_hash]));
+
+        // The boosted transaction should be returned to the mempool.
+        let synced = conn
+            .transactions_dal()
+            .sync_mempool(&[], &[], 0, 0, 100)
+            .await
+            .unwrap();
+        assert_eq!(synced.len(), 1);
+        assert_eq!(synced[0].hash(), tx_hash);
+    }
+}
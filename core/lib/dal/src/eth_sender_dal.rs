@@ -1,10 +1,14 @@
-use std::{convert::TryFrom, str::FromStr};
+use std::{convert::TryFrom, ops::RangeInclusive, str::FromStr, time::Duration};
 
 use anyhow::Context as _;
 use sqlx::types::chrono::{DateTime, Utc};
-use zksync_db_connection::{connection::Connection, interpolate_query, match_query_as};
+use zksync_db_connection::{
+    connection::Connection, error::DalResult, instrument::InstrumentExt, interpolate_query,
+    match_query_as, utils::pg_interval_from_duration,
+};
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
+    api::{EthSenderStatus, FailedEthTx, InFlightEthTx, OperatorNonceStatus, PendingEthTxsSummary},
     eth_sender::{EthTx, EthTxBlobSidecar, TxHistory, TxHistoryToSend},
     Address, L1BatchNumber, H256, U256,
 };
@@ -13,7 +17,8 @@ use crate::{
     models::storage_eth_tx::{
         L1BatchEthSenderStats, StorageEthTx, StorageTxHistory, StorageTxHistoryToSend,
     },
-    Core,
+    watermarks_dal::WatermarkComponent,
+    Core, CoreDal,
 };
 
 #[derive(Debug)]
@@ -346,6 +351,32 @@ impl EthSenderDal<'_, '_> {
         .execute(transaction.conn())
         .await?;
 
+        // Publish the proof watermark if the transaction proves L1 batches. Since proofs are sent to L1
+        // in order, all batches up to the last proven one have their proofs published.
+        let proven_batch = sqlx::query!(
+            r#"
+            SELECT
+                MAX(number) AS "number"
+            FROM
+                l1_batches
+            WHERE
+                eth_prove_tx_id = $1
+            "#,
+            ids.eth_tx_id
+        )
+        .fetch_one(transaction.conn())
+        .await?
+        .number;
+        if let Some(proven_batch) = proven_batch {
+            transaction
+                .watermarks_dal()
+                .set_watermark(
+                    WatermarkComponent::Proofs,
+                    L1BatchNumber(proven_batch as u32),
+                )
+                .await?;
+        }
+
         transaction.commit().await?;
         Ok(())
     }
@@ -568,16 +599,23 @@ impl EthSenderDal<'_, '_> {
         Ok(nonce.map(|n| n + 1))
     }
 
-    pub async fn mark_failed_transaction(&mut self, eth_tx_id: u32) -> sqlx::Result<()> {
+    pub async fn mark_failed_transaction(
+        &mut self,
+        eth_tx_id: u32,
+        failure_reason: Option<&str>,
+    ) -> sqlx::Result<()> {
         sqlx::query!(
             r#"
             UPDATE eth_txs
             SET
-                has_failed = TRUE
+                has_failed = TRUE,
+                failure_reason = $2,
+                updated_at = NOW()
             WHERE
                 id = $1
             "#,
-            eth_tx_id as i32
+            eth_tx_id as i32,
+            failure_reason
         )
         .execute(self.storage.conn())
         .await?;
@@ -601,6 +639,208 @@ impl EthSenderDal<'_, '_> {
         .context("count field is missing")
     }
 
+    /// Returns a summary of the L1 sender state, including up to `failures_limit` recently failed transactions.
+    pub async fn get_status(&mut self, failures_limit: usize) -> DalResult<EthSenderStatus> {
+        Ok(EthSenderStatus {
+            pending: self.get_pending_txs_summary().await?,
+            in_flight: self.get_in_flight_txs_with_attempts().await?,
+            operator_nonces: self.get_operator_nonces().await?,
+            recent_failures: self.get_recent_failed_txs(failures_limit).await?,
+        })
+    }
+
+    /// Returns pending (neither confirmed nor failed) transactions aggregated by the operation type.
+    async fn get_pending_txs_summary(&mut self) -> DalResult<Vec<PendingEthTxsSummary>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                eth_txs.tx_type,
+                COUNT(*) AS "count!",
+                COUNT(sent.eth_tx_id) AS "sent_count!",
+                MIN(eth_txs.created_at) AS "oldest_created_at!"
+            FROM
+                eth_txs
+                LEFT JOIN (
+                    SELECT DISTINCT
+                        eth_tx_id
+                    FROM
+                        eth_txs_history
+                    WHERE
+                        sent_at_block IS NOT NULL
+                ) sent ON sent.eth_tx_id = eth_txs.id
+            WHERE
+                eth_txs.confirmed_eth_tx_history_id IS NULL
+                AND eth_txs.has_failed = FALSE
+            GROUP BY
+                eth_txs.tx_type
+            ORDER BY
+                eth_txs.tx_type
+            "#
+        )
+        .instrument("get_pending_txs_summary")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| PendingEthTxsSummary {
+                tx_type: row.tx_type,
+                count: row.count as u64,
+                sent_count: row.sent_count as u64,
+                oldest_created_at: DateTime::<Utc>::from_naive_utc_and_offset(
+                    row.oldest_created_at,
+                    Utc,
+                ),
+            })
+            .collect())
+    }
+
+    /// Returns transactions that were sent at least once, but are neither confirmed nor failed, together
+    /// with their latest send attempts.
+    async fn get_in_flight_txs_with_attempts(&mut self) -> DalResult<Vec<InFlightEthTx>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                eth_txs.id,
+                eth_txs.nonce,
+                eth_txs.tx_type,
+                eth_txs.from_addr,
+                attempts.count AS "attempts!",
+                attempts.first_sent_at_block,
+                latest.tx_hash AS "tx_hash!",
+                latest.base_fee_per_gas AS "base_fee_per_gas!",
+                latest.priority_fee_per_gas AS "priority_fee_per_gas!",
+                latest.blob_base_fee_per_gas,
+                latest.sent_at_block AS last_sent_at_block
+            FROM
+                eth_txs
+                JOIN LATERAL (
+                    SELECT
+                        tx_hash,
+                        base_fee_per_gas,
+                        priority_fee_per_gas,
+                        blob_base_fee_per_gas,
+                        sent_at_block
+                    FROM
+                        eth_txs_history
+                    WHERE
+                        eth_tx_id = eth_txs.id
+                    ORDER BY
+                        created_at DESC
+                    LIMIT
+                        1
+                ) latest ON TRUE
+                JOIN LATERAL (
+                    SELECT
+                        COUNT(*) AS count,
+                        MIN(sent_at_block) AS first_sent_at_block
+                    FROM
+                        eth_txs_history
+                    WHERE
+                        eth_tx_id = eth_txs.id
+                ) attempts ON TRUE
+            WHERE
+                eth_txs.confirmed_eth_tx_history_id IS NULL
+                AND eth_txs.has_failed = FALSE
+            ORDER BY
+                eth_txs.id
+            "#
+        )
+        .instrument("get_in_flight_txs_with_attempts")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| InFlightEthTx {
+                id: row.id as u32,
+                nonce: row.nonce as u64,
+                tx_type: row.tx_type,
+                from_addr: row.from_addr.map(|addr| Address::from_slice(&addr)),
+                attempts: row.attempts as u64,
+                tx_hash: H256::from_str(&row.tx_hash).expect("Incorrect hash"),
+                base_fee_per_gas: row.base_fee_per_gas as u64,
+                priority_fee_per_gas: row.priority_fee_per_gas as u64,
+                blob_base_fee_per_gas: row.blob_base_fee_per_gas.map(|fee| fee as u64),
+                first_sent_at_block: row.first_sent_at_block.map(|block| block as u32),
+                last_sent_at_block: row.last_sent_at_block.map(|block| block as u32),
+            })
+            .collect())
+    }
+
+    /// Returns nonce status for each operator that has created L1 transactions.
+    async fn get_operator_nonces(&mut self) -> DalResult<Vec<OperatorNonceStatus>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                from_addr,
+                MAX(nonce) AS "last_nonce!",
+                MAX(nonce) FILTER (
+                    WHERE
+                        confirmed_eth_tx_history_id IS NOT NULL
+                ) AS last_confirmed_nonce
+            FROM
+                eth_txs
+            GROUP BY
+                from_addr
+            ORDER BY
+                from_addr NULLS FIRST
+            "#
+        )
+        .instrument("get_operator_nonces")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| OperatorNonceStatus {
+                from_addr: row.from_addr.map(|addr| Address::from_slice(&addr)),
+                next_nonce: row.last_nonce as u64 + 1,
+                last_confirmed_nonce: row.last_confirmed_nonce.map(|nonce| nonce as u64),
+            })
+            .collect())
+    }
+
+    /// Returns up to `limit` most recently failed transactions, latest first.
+    async fn get_recent_failed_txs(&mut self, limit: usize) -> DalResult<Vec<FailedEthTx>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                id,
+                nonce,
+                tx_type,
+                from_addr,
+                failure_reason,
+                updated_at
+            FROM
+                eth_txs
+            WHERE
+                has_failed = TRUE
+            ORDER BY
+                id DESC
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .instrument("get_recent_failed_txs")
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| FailedEthTx {
+                id: row.id as u32,
+                nonce: row.nonce as u64,
+                tx_type: row.tx_type,
+                from_addr: row.from_addr.map(|addr| Address::from_slice(&addr)),
+                failure_reason: row.failure_reason,
+                failed_at: DateTime::<Utc>::from_naive_utc_and_offset(row.updated_at, Utc),
+            })
+            .collect())
+    }
+
     pub async fn clear_failed_transactions(&mut self) -> sqlx::Result<()> {
         sqlx::query!(
             r#"
@@ -662,4 +902,662 @@ impl EthSenderDal<'_, '_> {
 
         Ok(())
     }
+
+    /// Moves up to `limit` superseded sending attempts of L1 transactions confirmed more than `archive_after` ago
+    /// to the `eth_txs_history_archive` table. Returns the number of archived attempts.
+    pub async fn archive_eth_txs_history(
+        &mut self,
+        archive_after: Duration,
+        limit: usize,
+    ) -> sqlx::Result<usize> {
+        let archive_after = pg_interval_from_duration(archive_after);
+        let archived_count = sqlx::query_scalar!(
+            r#"
+            WITH
+                deleted AS (
+                    DELETE FROM eth_txs_history
+                    WHERE
+                        id IN (
+                            SELECT
+                                eth_txs_history.id
+                            FROM
+                                eth_txs_history
+                                JOIN eth_txs ON eth_txs.id = eth_txs_history.eth_tx_id
+                                JOIN eth_txs_history AS confirmed ON confirmed.id = eth_txs.confirmed_eth_tx_history_id
+                            WHERE
+                                eth_txs_history.id <> confirmed.id
+                                AND confirmed.confirmed_at < NOW() - $1::INTERVAL
+                            ORDER BY
+                                eth_txs_history.id
+                            LIMIT
+                                $2
+                        )
+                    RETURNING
+                        id,
+                        eth_tx_id,
+                        tx_hash,
+                        created_at,
+                        updated_at,
+                        base_fee_per_gas,
+                        priority_fee_per_gas,
+                        confirmed_at,
+                        signed_raw_tx,
+                        sent_at_block,
+                        sent_at,
+                        blob_base_fee_per_gas
+                ),
+                inserted_count AS (
+                    INSERT INTO
+                        eth_txs_history_archive (
+                            id,
+                            eth_tx_id,
+                            tx_hash,
+                            created_at,
+                            updated_at,
+                            base_fee_per_gas,
+                            priority_fee_per_gas,
+                            confirmed_at,
+                            signed_raw_tx,
+                            sent_at_block,
+                            sent_at,
+                            blob_base_fee_per_gas
+                        )
+                    SELECT
+                        id,
+                        eth_tx_id,
+                        tx_hash,
+                        created_at,
+                        updated_at,
+                        base_fee_per_gas,
+                        priority_fee_per_gas,
+                        confirmed_at,
+                        signed_raw_tx,
+                        sent_at_block,
+                        sent_at,
+                        blob_base_fee_per_gas
+                    FROM
+                        deleted
+                )
+            SELECT
+                COUNT(*)
+            FROM
+                deleted
+            "#,
+            &archive_after,
+            limit as i64
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(archived_count.unwrap_or(0) as usize)
+    }
+
+    /// Moves up to `limit` L1 transactions confirmed more than `archive_after` ago, together with all their
+    /// sending attempts, to the `eth_txs_archive` and `eth_txs_history_archive` tables. The latest confirmed
+    /// transaction of each type is never archived, so that the last committed / proven / executed L1 batches
+    /// can still be determined. Returns the number of archived L1 transactions.
+    pub async fn archive_eth_txs(
+        &mut self,
+        archive_after: Duration,
+        limit: usize,
+    ) -> sqlx::Result<usize> {
+        let archive_after = pg_interval_from_duration(archive_after);
+        // All CTEs see the same snapshot, so sending attempts are copied to the archive before
+        // they are removed by `ON DELETE CASCADE`.
+        let archived_count = sqlx::query_scalar!(
+            r#"
+            WITH
+                archived_ids AS (
+                    SELECT
+                        eth_txs.id
+                    FROM
+                        eth_txs
+                        JOIN eth_txs_history AS confirmed ON confirmed.id = eth_txs.confirmed_eth_tx_history_id
+                    WHERE
+                        confirmed.confirmed_at < NOW() - $1::INTERVAL
+                        AND eth_txs.id < (
+                            SELECT
+                                MAX(latest.id)
+                            FROM
+                                eth_txs AS latest
+                            WHERE
+                                latest.tx_type = eth_txs.tx_type
+                                AND latest.confirmed_eth_tx_history_id IS NOT NULL
+                        )
+                    ORDER BY
+                        eth_txs.id
+                    LIMIT
+                        $2
+                ),
+                archived_history AS (
+                    INSERT INTO
+                        eth_txs_history_archive (
+                            id,
+                            eth_tx_id,
+                            tx_hash,
+                            created_at,
+                            updated_at,
+                            base_fee_per_gas,
+                            priority_fee_per_gas,
+                            confirmed_at,
+                            signed_raw_tx,
+                            sent_at_block,
+                            sent_at,
+                            blob_base_fee_per_gas
+                        )
+                    SELECT
+                        id,
+                        eth_tx_id,
+                        tx_hash,
+                        created_at,
+                        updated_at,
+                        base_fee_per_gas,
+                        priority_fee_per_gas,
+                        confirmed_at,
+                        signed_raw_tx,
+                        sent_at_block,
+                        sent_at,
+                        blob_base_fee_per_gas
+                    FROM
+                        eth_txs_history
+                    WHERE
+                        eth_tx_id IN (
+                            SELECT
+                                id
+                            FROM
+                                archived_ids
+                        )
+                ),
+                archived_txs AS (
+                    INSERT INTO
+                        eth_txs_archive (
+                            id,
+                            nonce,
+                            raw_tx,
+                            contract_address,
+                            tx_type,
+                            gas_used,
+                            created_at,
+                            updated_at,
+                            has_failed,
+                            sent_at_block,
+                            confirmed_eth_tx_history_id,
+                            predicted_gas_cost,
+                            from_addr,
+                            blob_sidecar,
+                            failure_reason
+                        )
+                    SELECT
+                        id,
+                        nonce,
+                        raw_tx,
+                        contract_address,
+                        tx_type,
+                        gas_used,
+                        created_at,
+                        updated_at,
+                        has_failed,
+                        sent_at_block,
+                        confirmed_eth_tx_history_id,
+                        predicted_gas_cost,
+                        from_addr,
+                        blob_sidecar,
+                        failure_reason
+                    FROM
+                        eth_txs
+                    WHERE
+                        id IN (
+                            SELECT
+                                id
+                            FROM
+                                archived_ids
+                        )
+                ),
+                deleted AS (
+                    DELETE FROM eth_txs
+                    WHERE
+                        id IN (
+                            SELECT
+                                id
+                            FROM
+                                archived_ids
+                        )
+                    RETURNING
+                        id
+                )
+            SELECT
+                COUNT(*)
+            FROM
+                deleted
+            "#,
+            &archive_after,
+            limit as i64
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(archived_count.unwrap_or(0) as usize)
+    }
+
+    /// Moves archived L1 transactions and sending attempts for the specified range of L1 transaction IDs back
+    /// to `eth_txs` and `eth_txs_history`. Attempts for L1 transactions that no longer exist are left in the archive.
+    /// Returns the number of restored L1 transactions and sending attempts.
+    pub async fn restore_archived_eth_txs_history(
+        &mut self,
+        eth_tx_ids: RangeInclusive<u32>,
+    ) -> anyhow::Result<(usize, usize)> {
+        let mut transaction = self
+            .storage
+            .start_transaction()
+            .await
+            .context("start_transaction()")?;
+        // Transactions and their attempts reference each other, so confirmed attempts are linked
+        // to the restored transactions only after the attempts are restored.
+        let restored_tx_count = sqlx::query!(
+            r#"
+            INSERT INTO
+                eth_txs (
+                    id,
+                    nonce,
+                    raw_tx,
+                    contract_address,
+                    tx_type,
+                    gas_used,
+                    created_at,
+                    updated_at,
+                    has_failed,
+                    sent_at_block,
+                    confirmed_eth_tx_history_id,
+                    predicted_gas_cost,
+                    from_addr,
+                    blob_sidecar,
+                    failure_reason
+                )
+            SELECT
+                id,
+                nonce,
+                raw_tx,
+                contract_address,
+                tx_type,
+                gas_used,
+                created_at,
+                updated_at,
+                has_failed,
+                sent_at_block,
+                NULL,
+                predicted_gas_cost,
+                from_addr,
+                blob_sidecar,
+                failure_reason
+            FROM
+                eth_txs_archive
+            WHERE
+                id BETWEEN $1 AND $2
+            "#,
+            *eth_tx_ids.start() as i32,
+            *eth_tx_ids.end() as i32
+        )
+        .execute(transaction.conn())
+        .await
+        .context("restore_archived_eth_txs()")?
+        .rows_affected();
+
+        let restored_count = sqlx::query_scalar!(
+            r#"
+            WITH
+                restored AS (
+                    DELETE FROM eth_txs_history_archive
+                    WHERE
+                        eth_tx_id BETWEEN $1 AND $2
+                        AND EXISTS (
+                            SELECT
+                                1
+                            FROM
+                                eth_txs
+                            WHERE
+                                eth_txs.id = eth_txs_history_archive.eth_tx_id
+                        )
+                    RETURNING
+                        id,
+                        eth_tx_id,
+                        tx_hash,
+                        created_at,
+                        updated_at,
+                        base_fee_per_gas,
+                        priority_fee_per_gas,
+                        confirmed_at,
+                        signed_raw_tx,
+                        sent_at_block,
+                        sent_at,
+                        blob_base_fee_per_gas
+                ),
+                inserted_count AS (
+                    INSERT INTO
+                        eth_txs_history (
+                            id,
+                            eth_tx_id,
+                            tx_hash,
+                            created_at,
+                            updated_at,
+                            base_fee_per_gas,
+                            priority_fee_per_gas,
+                            confirmed_at,
+                            signed_raw_tx,
+                            sent_at_block,
+                            sent_at,
+                            blob_base_fee_per_gas
+                        )
+                    SELECT
+                        id,
+                        eth_tx_id,
+                        tx_hash,
+                        created_at,
+                        updated_at,
+                        base_fee_per_gas,
+                        priority_fee_per_gas,
+                        confirmed_at,
+                        signed_raw_tx,
+                        sent_at_block,
+                        sent_at,
+                        blob_base_fee_per_gas
+                    FROM
+                        restored
+                )
+            SELECT
+                COUNT(*)
+            FROM
+                restored
+            "#,
+            *eth_tx_ids.start() as i32,
+            *eth_tx_ids.end() as i32
+        )
+        .fetch_one(transaction.conn())
+        .await
+        .context("restore_archived_eth_txs_history()")?;
+
+        sqlx::query!(
+            r#"
+            WITH
+                restored AS (
+                    DELETE FROM eth_txs_archive
+                    WHERE
+                        id BETWEEN $1 AND $2
+                    RETURNING
+                        id,
+                        confirmed_eth_tx_history_id
+                )
+            UPDATE eth_txs
+            SET
+                confirmed_eth_tx_history_id = restored.confirmed_eth_tx_history_id
+            FROM
+                restored
+            WHERE
+                eth_txs.id = restored.id
+            "#,
+            *eth_tx_ids.start() as i32,
+            *eth_tx_ids.end() as i32
+        )
+        .execute(transaction.conn())
+        .await
+        .context("link_restored_eth_txs_history()")?;
+
+        transaction.commit().await.context("commit()")?;
+        Ok((
+            restored_tx_count as usize,
+            restored_count.unwrap_or(0) as usize,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{block::L1BatchHeader, ProtocolVersion, ProtocolVersionId};
+
+    use super::*;
+    use crate::{ConnectionPool, Core, CoreDal};
+
+    async fn get_tx_history(conn: &mut Connection<'_, Core>, eth_tx_id: u32) -> Vec<(u32, H256)> {
+        let mut history: Vec<_> = conn
+            .eth_sender_dal()
+            .get_tx_history_to_check(eth_tx_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|entry| {
+                assert_eq!(entry.eth_tx_id, eth_tx_id);
+                assert_eq!(entry.signed_raw_tx, entry.tx_hash.as_bytes());
+                (entry.id, entry.tx_hash)
+            })
+            .collect();
+        history.sort_unstable();
+        history
+    }
+
+    #[tokio::test]
+    async fn archiving_and_restoring_eth_txs_history() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let eth_tx = conn
+            .eth_sender_dal()
+            .save_eth_tx(
+                0,
+                vec![],
+                AggregatedActionType::Execute,
+                Address::default(),
+                0,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        for i in 1..=3 {
+            let tx_hash = H256::repeat_byte(i);
+            conn.eth_sender_dal()
+                .insert_tx_history(eth_tx.id, 100, 10, None, tx_hash, tx_hash.as_bytes())
+                .await
+                .unwrap()
+                .expect("attempt not inserted");
+        }
+        let confirmed_tx_hash = H256::repeat_byte(3);
+        conn.eth_sender_dal()
+            .confirm_tx(confirmed_tx_hash, 21_000.into())
+            .await
+            .unwrap();
+        let full_history = get_tx_history(&mut conn, eth_tx.id).await;
+        assert_eq!(full_history.len(), 3);
+
+        // Attempts of recently confirmed transactions are not archived.
+        let archived_count = conn
+            .eth_sender_dal()
+            .archive_eth_txs_history(Duration::from_secs(3_600), 10)
+            .await
+            .unwrap();
+        assert_eq!(archived_count, 0);
+
+        // Attempts are archived in chunks.
+        for _ in 0..2 {
+            let archived_count = conn
+                .eth_sender_dal()
+                .archive_eth_txs_history(Duration::ZERO, 1)
+                .await
+                .unwrap();
+            assert_eq!(archived_count, 1);
+        }
+        let archived_count = conn
+            .eth_sender_dal()
+            .archive_eth_txs_history(Duration::ZERO, 1)
+            .await
+            .unwrap();
+        assert_eq!(archived_count, 0);
+        let history = get_tx_history(&mut conn, eth_tx.id).await;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].1, confirmed_tx_hash);
+        let confirmed_tx_hash_in_db = conn
+            .eth_sender_dal()
+            .get_confirmed_tx_hash_by_eth_tx_id(eth_tx.id)
+            .await
+            .unwrap();
+        assert_eq!(confirmed_tx_hash_in_db, Some(confirmed_tx_hash));
+
+        // Attempts for non-existing transactions are not restored.
+        let restored_count = conn
+            .eth_sender_dal()
+            .restore_archived_eth_txs_history(eth_tx.id + 1..=eth_tx.id + 10)
+            .await
+            .unwrap();
+        assert_eq!(restored_count, (0, 0));
+
+        let restored_count = conn
+            .eth_sender_dal()
+            .restore_archived_eth_txs_history(eth_tx.id..=eth_tx.id)
+            .await
+            .unwrap();
+        assert_eq!(restored_count, (0, 2));
+        assert_eq!(get_tx_history(&mut conn, eth_tx.id).await, full_history);
+    }
+
+    async fn save_confirmed_eth_tx(
+        conn: &mut Connection<'_, Core>,
+        nonce: u64,
+        attempt_count: u8,
+    ) -> (EthTx, H256) {
+        let eth_tx = conn
+            .eth_sender_dal()
+            .save_eth_tx(
+                nonce,
+                vec![],
+                AggregatedActionType::Execute,
+                Address::default(),
+                0,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let mut tx_hash = H256::zero();
+        for i in 1..=attempt_count {
+            tx_hash = H256::from_low_u64_be((nonce << 8) | u64::from(i));
+            conn.eth_sender_dal()
+                .insert_tx_history(eth_tx.id, 100, 10, None, tx_hash, tx_hash.as_bytes())
+                .await
+                .unwrap()
+                .expect("attempt not inserted");
+        }
+        conn.eth_sender_dal()
+            .confirm_tx(tx_hash, 21_000.into())
+            .await
+            .unwrap();
+        (eth_tx, tx_hash)
+    }
+
+    #[tokio::test]
+    async fn archiving_and_restoring_eth_txs() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut eth_txs = vec![];
+        for nonce in 0..3 {
+            eth_txs.push(save_confirmed_eth_tx(&mut conn, nonce, 2).await);
+        }
+        let full_history = get_tx_history(&mut conn, eth_txs[0].0.id).await;
+        assert_eq!(full_history.len(), 2);
+
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(&ProtocolVersion::default())
+            .await
+            .unwrap();
+        let l1_batch_header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            100,
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::latest(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&l1_batch_header)
+            .await
+            .unwrap();
+        let first_eth_tx_id = eth_txs[0].0.id;
+        conn.blocks_dal()
+            .set_eth_tx_id(
+                L1BatchNumber(1)..=L1BatchNumber(1),
+                first_eth_tx_id,
+                AggregatedActionType::Execute,
+            )
+            .await
+            .unwrap();
+
+        // Recently confirmed transactions are not archived.
+        let archived_count = conn
+            .eth_sender_dal()
+            .archive_eth_txs(Duration::from_secs(3_600), 10)
+            .await
+            .unwrap();
+        assert_eq!(archived_count, 0);
+
+        let archived_count = conn
+            .eth_sender_dal()
+            .archive_eth_txs(Duration::ZERO, 1)
+            .await
+            .unwrap();
+        assert_eq!(archived_count, 1);
+        // The latest confirmed transaction is never archived.
+        let archived_count = conn
+            .eth_sender_dal()
+            .archive_eth_txs(Duration::ZERO, 10)
+            .await
+            .unwrap();
+        assert_eq!(archived_count, 1);
+
+        for (eth_tx, _) in &eth_txs[..2] {
+            assert!(get_tx_history(&mut conn, eth_tx.id).await.is_empty());
+            let eth_tx = conn.eth_sender_dal().get_eth_tx(eth_tx.id).await.unwrap();
+            assert!(eth_tx.is_none(), "{eth_tx:?}");
+        }
+        let (last_eth_tx, _) = &eth_txs[2];
+        assert_eq!(get_tx_history(&mut conn, last_eth_tx.id).await.len(), 2);
+        // References to archived transactions must be retained.
+        let l1_batches = conn
+            .blocks_dal()
+            .get_l1_batches_for_eth_tx_id(first_eth_tx_id)
+            .await
+            .unwrap();
+        assert_eq!(l1_batches.len(), 1);
+
+        let restored_count = conn
+            .eth_sender_dal()
+            .restore_archived_eth_txs_history(first_eth_tx_id..=first_eth_tx_id)
+            .await
+            .unwrap();
+        assert_eq!(restored_count, (1, 2));
+        assert_eq!(
+            get_tx_history(&mut conn, first_eth_tx_id).await,
+            full_history
+        );
+        let confirmed_tx_hash_in_db = conn
+            .eth_sender_dal()
+            .get_confirmed_tx_hash_by_eth_tx_id(first_eth_tx_id)
+            .await
+            .unwrap();
+        assert_eq!(confirmed_tx_hash_in_db, Some(eth_txs[0].1));
+        let restored_eth_tx = conn
+            .eth_sender_dal()
+            .get_eth_tx(first_eth_tx_id)
+            .await
+            .unwrap()
+            .expect("transaction not restored");
+        assert_eq!(restored_eth_tx.nonce, eth_txs[0].0.nonce);
+        // The restored transaction must be linked to its confirmed attempt; otherwise, its attempts would be resent.
+        let unsent_txs = conn.eth_sender_dal().get_unsent_txs().await.unwrap();
+        assert!(unsent_txs.is_empty(), "{unsent_txs:?}");
+
+        // Deleting (rather than archiving) transactions resets references to them.
+        conn.eth_sender_dal()
+            .delete_eth_txs(L1BatchNumber(0))
+            .await
+            .unwrap();
+        let l1_batches = conn
+            .blocks_dal()
+            .get_l1_batches_for_eth_tx_id(first_eth_tx_id)
+            .await
+            .unwrap();
+        assert!(l1_batches.is_empty());
+    }
 }
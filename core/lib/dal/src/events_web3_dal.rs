@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use sqlx::{
     postgres::PgArguments,
     query::{Query, QueryAs},
@@ -19,10 +21,12 @@ pub struct EventsWeb3Dal<'a, 'c> {
 impl EventsWeb3Dal<'_, '_> {
     /// Returns L2 block number of log for given filter and offset.
     /// Used to determine if there is more than `offset` logs that satisfies filter.
+    /// If `statement_timeout` is specified, the query is aborted if it takes longer than the timeout.
     pub async fn get_log_block_number(
         &mut self,
         filter: &GetLogsFilter,
         offset: usize,
+        statement_timeout: Option<Duration>,
     ) -> DalResult<Option<L2BlockNumber>> {
         let (where_sql, arg_index) = self.build_get_logs_where_clause(filter);
 
@@ -57,6 +61,7 @@ impl EventsWeb3Dal<'_, '_> {
             .report_latency()
             .with_arg("filter", filter)
             .with_arg("offset", &offset)
+            .with_statement_timeout(statement_timeout)
             .fetch_optional(self.storage)
             .await?;
 
@@ -64,8 +69,14 @@ impl EventsWeb3Dal<'_, '_> {
     }
 
     /// Returns logs for given filter.
+    /// If `statement_timeout` is specified, the query is aborted if it takes longer than the timeout.
     #[allow(clippy::type_complexity)]
-    pub async fn get_logs(&mut self, filter: GetLogsFilter, limit: usize) -> DalResult<Vec<Log>> {
+    pub async fn get_logs(
+        &mut self,
+        filter: GetLogsFilter,
+        limit: usize,
+        statement_timeout: Option<Duration>,
+    ) -> DalResult<Vec<Log>> {
         let (where_sql, arg_index) = self.build_get_logs_where_clause(&filter);
         let query = format!(
             r#"
@@ -108,6 +119,7 @@ impl EventsWeb3Dal<'_, '_> {
             .report_latency()
             .with_arg("filter", &filter)
             .with_arg("limit", &limit)
+            .with_statement_timeout(statement_timeout)
             .fetch_all(self.storage)
             .await?;
         let logs = db_logs.into_iter().map(Into::into).collect();
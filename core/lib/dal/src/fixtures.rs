@@ -0,0 +1,402 @@
+//! Fixtures for integration tests using Postgres.
+//!
+//! [`ChainBuilder`] inserts a consistent chain of L1 batches, L2 blocks, transactions and storage logs,
+//! so that tests don't need to replicate the sequence of DAL calls performed by the state keeper.
+//! Basic building blocks (L2 block headers, transactions and their execution results) are exposed as well.
+
+use zksync_contracts::BaseSystemContractsHashes;
+use zksync_db_connection::connection::Connection;
+use zksync_types::{
+    block::{L1BatchHeader, L1BatchTreeData, L2BlockHasher, L2BlockHeader},
+    fee::{Fee, TransactionExecutionMetrics},
+    fee_model::BatchFeeInput,
+    l2::L2Tx,
+    protocol_upgrade::ProtocolVersion,
+    protocol_version::{ProtocolSemanticVersion, VersionPatch},
+    tx::{tx_execution_info::TxExecutionStatus, ExecutionMetrics, TransactionExecutionResult},
+    AccountTreeId, Address, K256PrivateKey, L1BatchNumber, L2BlockNumber, L2ChainId,
+    ProtocolVersionId, StorageKey, StorageLog, H256, U256,
+};
+
+use crate::{transactions_dal::CallTraceFormat, Core, CoreDal};
+
+const DEFAULT_GAS_PER_PUBDATA: u32 = 100;
+
+/// Creates an L2 block header with the specified number and mock values for other fields.
+pub fn create_l2_block_header(number: u32) -> L2BlockHeader {
+    let number = L2BlockNumber(number);
+    let protocol_version = ProtocolVersionId::default();
+    L2BlockHeader {
+        number,
+        timestamp: number.0.into(),
+        hash: L2BlockHasher::new(number, 0, H256::zero()).finalize(protocol_version),
+        l1_tx_count: 0,
+        l2_tx_count: 0,
+        fee_account_address: Address::default(),
+        gas_per_pubdata_limit: 100,
+        base_fee_per_gas: 100,
+        batch_fee_input: BatchFeeInput::l1_pegged(100, 100),
+        base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+        protocol_version: Some(protocol_version),
+        virtual_blocks: 1,
+        gas_limit: 0,
+    }
+}
+
+/// Creates a signed L2 transaction from a random account.
+pub fn mock_l2_transaction() -> L2Tx {
+    let fee = Fee {
+        gas_limit: U256::from(1_000_000u32),
+        max_fee_per_gas: U256::from(250_000_000u32),
+        max_priority_fee_per_gas: U256::zero(),
+        gas_per_pubdata_limit: U256::from(DEFAULT_GAS_PER_PUBDATA),
+    };
+    let mut l2_tx = L2Tx::new_signed(
+        Address::random(),
+        vec![],
+        zksync_types::Nonce(0),
+        fee,
+        Default::default(),
+        L2ChainId::from(270),
+        &K256PrivateKey::random(),
+        None,
+        Default::default(),
+    )
+    .unwrap();
+
+    l2_tx.set_input(H256::random().0.to_vec(), H256::random());
+    l2_tx
+}
+
+/// Creates a successful execution result for the provided transaction.
+pub fn mock_execution_result(transaction: L2Tx) -> TransactionExecutionResult {
+    TransactionExecutionResult {
+        hash: transaction.hash(),
+        transaction: transaction.into(),
+        execution_info: ExecutionMetrics::default(),
+        execution_status: TxExecutionStatus::Success,
+        refunded_gas: 0,
+        operator_suggested_refund: 0,
+        compressed_bytecodes: vec![],
+        call_traces: vec![],
+        revert_reason: None,
+    }
+}
+
+/// Builder inserting a chain of sealed L1 batches to Postgres. The chain is appended to the existing L2 blocks
+/// and L1 batches (e.g., the genesis ones), or starts from the genesis if there are none.
+///
+/// Each L1 batch consists of the configured number of L2 blocks with transactions, followed by a fictive L2 block
+/// without transactions. Each transaction writes to the configured number of new storage slots.
+#[derive(Debug, Clone)]
+pub struct ChainBuilder {
+    l1_batch_count: u32,
+    l2_blocks_per_batch: u32,
+    txs_per_l2_block: u32,
+    storage_logs_per_tx: u32,
+    protocol_version: ProtocolVersionId,
+    base_system_contracts_hashes: BaseSystemContractsHashes,
+    insert_tree_data: bool,
+}
+
+impl Default for ChainBuilder {
+    fn default() -> Self {
+        Self {
+            l1_batch_count: 1,
+            l2_blocks_per_batch: 1,
+            txs_per_l2_block: 1,
+            storage_logs_per_tx: 1,
+            protocol_version: ProtocolVersionId::latest(),
+            base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+            insert_tree_data: false,
+        }
+    }
+}
+
+impl ChainBuilder {
+    /// Sets the number of inserted L1 batches. The default value is 1.
+    #[must_use]
+    pub fn with_l1_batch_count(mut self, count: u32) -> Self {
+        self.l1_batch_count = count;
+        self
+    }
+
+    /// Sets the number of L2 blocks with transactions in each L1 batch, not counting the fictive L2 block.
+    /// The default value is 1.
+    #[must_use]
+    pub fn with_l2_blocks_per_batch(mut self, count: u32) -> Self {
+        self.l2_blocks_per_batch = count;
+        self
+    }
+
+    /// Sets the number of transactions in each non-fictive L2 block. The default value is 1.
+    #[must_use]
+    pub fn with_txs_per_l2_block(mut self, count: u32) -> Self {
+        self.txs_per_l2_block = count;
+        self
+    }
+
+    /// Sets the number of storage logs produced by each transaction. The default value is 1.
+    #[must_use]
+    pub fn with_storage_logs_per_tx(mut self, count: u32) -> Self {
+        self.storage_logs_per_tx = count;
+        self
+    }
+
+    /// Sets the protocol version for inserted L2 blocks and L1 batches. The version is inserted
+    /// if it doesn't exist. The default value is [`ProtocolVersionId::latest()`].
+    #[must_use]
+    pub fn with_protocol_version(mut self, protocol_version: ProtocolVersionId) -> Self {
+        self.protocol_version = protocol_version;
+        self
+    }
+
+    /// Sets base system contract hashes for inserted L2 blocks and L1 batches.
+    #[must_use]
+    pub fn with_base_system_contracts_hashes(mut self, hashes: BaseSystemContractsHashes) -> Self {
+        self.base_system_contracts_hashes = hashes;
+        self
+    }
+
+    /// Enables or disables inserting mock tree data (the root hash and the last leaf index) for inserted L1 batches.
+    /// Disabled by default.
+    #[must_use]
+    pub fn with_tree_data(mut self, insert: bool) -> Self {
+        self.insert_tree_data = insert;
+        self
+    }
+
+    /// Inserts the chain in a single DB transaction.
+    ///
+    /// # Errors
+    ///
+    /// Propagates DB errors.
+    pub async fn insert(self, conn: &mut Connection<'_, Core>) -> anyhow::Result<InsertedChain> {
+        let mut transaction = conn.start_transaction().await?;
+        self.insert_protocol_version(&mut transaction).await?;
+
+        let last_l2_block = transaction
+            .blocks_dal()
+            .get_last_sealed_l2_block_header()
+            .await?;
+        let (mut l2_block_number, mut prev_l2_block_hash, mut timestamp) = match last_l2_block {
+            Some(block) => (block.number + 1, block.hash, block.timestamp + 1),
+            None => (L2BlockNumber(0), H256::zero(), 0),
+        };
+        let mut l1_batch_number = transaction
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .map_or(L1BatchNumber(0), |number| number + 1);
+
+        let mut chain = InsertedChain::default();
+        for _ in 0..self.l1_batch_count {
+            let batch_timestamp = timestamp;
+            let mut batch_txs = vec![];
+            let mut written_keys = vec![];
+            for block_idx in 0..=self.l2_blocks_per_batch {
+                let is_fictive = block_idx == self.l2_blocks_per_batch;
+                let tx_count = if is_fictive { 0 } else { self.txs_per_l2_block };
+                let txs: Vec<_> = (0..tx_count).map(|_| mock_l2_transaction()).collect();
+                let mut hasher = L2BlockHasher::new(l2_block_number, timestamp, prev_l2_block_hash);
+                for tx in &txs {
+                    transaction
+                        .transactions_dal()
+                        .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+                        .await?;
+                    hasher.push_tx_hash(tx.hash());
+                }
+
+                let header = L2BlockHeader {
+                    timestamp,
+                    hash: hasher.finalize(self.protocol_version),
+                    l2_tx_count: tx_count.try_into()?,
+                    base_system_contracts_hashes: self.base_system_contracts_hashes,
+                    protocol_version: Some(self.protocol_version),
+                    ..create_l2_block_header(l2_block_number.0)
+                };
+                transaction.blocks_dal().insert_l2_block(&header).await?;
+
+                let tx_results: Vec<_> = txs.into_iter().map(mock_execution_result).collect();
+                let storage_logs: Vec<_> = tx_results
+                    .iter()
+                    .map(|tx| {
+                        let logs = (0..self.storage_logs_per_tx).map(|_| {
+                            let key = StorageKey::new(
+                                AccountTreeId::new(Address::random()),
+                                H256::random(),
+                            );
+                            StorageLog::new_write_log(key, H256::random())
+                        });
+                        (tx.hash, logs.collect::<Vec<_>>())
+                    })
+                    .collect();
+                if !tx_results.is_empty() {
+                    transaction
+                        .transactions_dal()
+                        .mark_txs_as_executed_in_l2_block(
+                            header.number,
+                            &tx_results,
+                            header.base_fee_per_gas.into(),
+                            ProtocolVersionId::latest(),
+                            CallTraceFormat::Legacy,
+                            false,
+                        )
+                        .await?;
+                    transaction
+                        .storage_logs_dal()
+                        .insert_storage_logs(header.number, &storage_logs)
+                        .await?;
+                }
+
+                for (_, logs) in storage_logs {
+                    written_keys.extend(logs.iter().map(|log| log.key));
+                    chain.storage_logs.extend(logs);
+                }
+                batch_txs.extend(tx_results);
+                prev_l2_block_hash = header.hash;
+                l2_block_number += 1;
+                timestamp += 1;
+                chain.l2_blocks.push(header);
+            }
+
+            transaction
+                .storage_logs_dedup_dal()
+                .insert_initial_writes(l1_batch_number, &written_keys)
+                .await?;
+            let mut header = L1BatchHeader::new(
+                l1_batch_number,
+                batch_timestamp,
+                self.base_system_contracts_hashes,
+                self.protocol_version,
+            );
+            header.l2_tx_count = batch_txs.len().try_into()?;
+            transaction
+                .blocks_dal()
+                .insert_mock_l1_batch(&header)
+                .await?;
+            transaction
+                .blocks_dal()
+                .mark_l2_blocks_as_executed_in_l1_batch(l1_batch_number)
+                .await?;
+            transaction
+                .transactions_dal()
+                .mark_txs_as_executed_in_l1_batch(l1_batch_number, &batch_txs)
+                .await?;
+            if self.insert_tree_data {
+                let rollup_last_leaf_index = transaction
+                    .storage_logs_dedup_dal()
+                    .max_enumeration_index_by_l1_batch(l1_batch_number)
+                    .await?
+                    .unwrap_or(0)
+                    + 1;
+                let tree_data = L1BatchTreeData {
+                    hash: H256::from_low_u64_be(l1_batch_number.0.into()),
+                    rollup_last_leaf_index,
+                };
+                transaction
+                    .blocks_dal()
+                    .save_l1_batch_tree_data(l1_batch_number, &tree_data)
+                    .await?;
+            }
+
+            chain.transactions.extend(batch_txs);
+            chain.l1_batches.push(header);
+            l1_batch_number += 1;
+        }
+        transaction.commit().await?;
+        Ok(chain)
+    }
+
+    async fn insert_protocol_version(&self, conn: &mut Connection<'_, Core>) -> anyhow::Result<()> {
+        let existing_version = conn
+            .protocol_versions_dal()
+            .get_protocol_version_with_latest_patch(self.protocol_version)
+            .await?;
+        if existing_version.is_none() {
+            let version = ProtocolVersion {
+                version: ProtocolSemanticVersion {
+                    minor: self.protocol_version,
+                    patch: VersionPatch(0),
+                },
+                base_system_contracts_hashes: self.base_system_contracts_hashes,
+                ..ProtocolVersion::default()
+            };
+            conn.protocol_versions_dal()
+                .save_protocol_version_with_tx(&version)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Information about the chain inserted by [`ChainBuilder`].
+#[derive(Debug, Default)]
+pub struct InsertedChain {
+    /// Headers of inserted L1 batches.
+    pub l1_batches: Vec<L1BatchHeader>,
+    /// Headers of inserted L2 blocks, including fictive ones.
+    pub l2_blocks: Vec<L2BlockHeader>,
+    /// Executed transactions.
+    pub transactions: Vec<TransactionExecutionResult>,
+    /// Storage logs produced by the transactions. All logs are initial writes.
+    pub storage_logs: Vec<StorageLog>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn inserting_chain() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let chain = ChainBuilder::default()
+            .with_l1_batch_count(2)
+            .with_l2_blocks_per_batch(2)
+            .with_txs_per_l2_block(3)
+            .with_storage_logs_per_tx(2)
+            .with_tree_data(true)
+            .insert(&mut conn)
+            .await
+            .unwrap();
+
+        assert_eq!(chain.l1_batches.len(), 2);
+        assert_eq!(chain.l2_blocks.len(), 6);
+        assert_eq!(chain.transactions.len(), 12);
+        assert_eq!(chain.storage_logs.len(), 24);
+        assert_eq!(
+            conn.blocks_dal()
+                .get_sealed_l1_batch_number()
+                .await
+                .unwrap(),
+            Some(L1BatchNumber(1))
+        );
+        let l2_block_range = conn
+            .blocks_dal()
+            .get_l2_block_range_of_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(l2_block_range, Some((L2BlockNumber(3), L2BlockNumber(5))));
+        let tree_data = conn
+            .blocks_dal()
+            .get_l1_batch_tree_data(L1BatchNumber(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(tree_data.rollup_last_leaf_index, 25);
+
+        // Check that the chain can be extended.
+        let chain = ChainBuilder::default().insert(&mut conn).await.unwrap();
+        assert_eq!(chain.l1_batches[0].number, L1BatchNumber(2));
+        assert_eq!(chain.l2_blocks[0].number, L2BlockNumber(6));
+        let last_tx = chain.transactions[0].hash;
+        let receipts = conn
+            .transactions_web3_dal()
+            .get_transaction_receipts(&[last_tx])
+            .await
+            .unwrap();
+        assert_eq!(receipts[0].l1_batch_number, Some(2.into()));
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+use zksync_types::L1BatchNumber;
+
+use crate::Core;
+
+/// DAL for custom key / value metadata attached to L1 batches by operators (e.g., compliance tags or build info).
+#[derive(Debug)]
+pub struct L1BatchesCustomMetadataDal<'c, 'a> {
+    pub(crate) storage: &'c mut Connection<'a, Core>,
+}
+
+impl L1BatchesCustomMetadataDal<'_, '_> {
+    /// Inserts custom metadata for a sealed L1 batch. Values for existing keys are overwritten.
+    pub async fn insert_metadata(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        metadata: &BTreeMap<String, String>,
+    ) -> DalResult<()> {
+        let (keys, values): (Vec<_>, Vec<_>) = metadata
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .unzip();
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                l1_batches_custom_metadata (l1_batch_number, key, value, created_at)
+            SELECT
+                $1,
+                u.key,
+                u.value,
+                NOW()
+            FROM
+                UNNEST($2::TEXT[], $3::TEXT[]) AS u (key, value)
+            ON CONFLICT (l1_batch_number, key) DO
+            UPDATE
+            SET
+                value = excluded.value
+            "#,
+            i64::from(l1_batch_number.0),
+            &keys as &[&str],
+            &values as &[&str]
+        )
+        .instrument("insert_l1_batch_custom_metadata")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("metadata.len", &metadata.len())
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns custom metadata for the specified L1 batch. If the batch is unknown or has no metadata,
+    /// returns an empty map.
+    pub async fn get_metadata(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> DalResult<BTreeMap<String, String>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                key,
+                value
+            FROM
+                l1_batches_custom_metadata
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("get_l1_batch_custom_metadata")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| (row.key, row.value)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{
+        block::L1BatchHeader, protocol_version::ProtocolVersion, ProtocolVersionId, H256,
+    };
+
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    #[tokio::test]
+    async fn inserting_and_getting_custom_metadata() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(&ProtocolVersion::default())
+            .await
+            .unwrap();
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            100,
+            BaseSystemContractsHashes {
+                bootloader: H256::repeat_byte(1),
+                default_aa: H256::repeat_byte(42),
+            },
+            ProtocolVersionId::latest(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+
+        let metadata = conn
+            .l1_batches_custom_metadata_dal()
+            .get_metadata(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert!(metadata.is_empty());
+
+        let metadata = BTreeMap::from([
+            ("build".to_owned(), "v1.0.0".to_owned()),
+            ("region".to_owned(), "eu".to_owned()),
+        ]);
+        conn.l1_batches_custom_metadata_dal()
+            .insert_metadata(L1BatchNumber(1), &metadata)
+            .await
+            .unwrap();
+        let updated_metadata = BTreeMap::from([("region".to_owned(), "us".to_owned())]);
+        conn.l1_batches_custom_metadata_dal()
+            .insert_metadata(L1BatchNumber(1), &updated_metadata)
+            .await
+            .unwrap();
+
+        let stored_metadata = conn
+            .l1_batches_custom_metadata_dal()
+            .get_metadata(L1BatchNumber(1))
+            .await
+            .unwrap();
+        let expected_metadata = BTreeMap::from([
+            ("build".to_owned(), "v1.0.0".to_owned()),
+            ("region".to_owned(), "us".to_owned()),
+        ]);
+        assert_eq!(stored_metadata, expected_metadata);
+
+        // Metadata should be removed together with the batch.
+        conn.blocks_dal()
+            .delete_l1_batches(L1BatchNumber(0))
+            .await
+            .unwrap();
+        let metadata = conn
+            .l1_batches_custom_metadata_dal()
+            .get_metadata(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert!(metadata.is_empty());
+    }
+}
@@ -7,27 +7,33 @@ pub use sqlx::{types::BigDecimal, Error as SqlxError};
 use zksync_db_connection::connection::DbMarker;
 pub use zksync_db_connection::{
     connection::Connection,
-    connection_pool::{ConnectionPool, ConnectionPoolBuilder},
+    connection_pool::{ConnectionPool, ConnectionPoolBuilder, NotificationListener},
     error::{DalError, DalResult},
 };
 
 use crate::{
-    blocks_dal::BlocksDal, blocks_web3_dal::BlocksWeb3Dal, consensus_dal::ConsensusDal,
+    blocks_dal::BlocksDal, blocks_web3_dal::BlocksWeb3Dal,
+    boosted_transactions_dal::BoostedTransactionsDal, consensus_dal::ConsensusDal,
     contract_verification_dal::ContractVerificationDal, eth_sender_dal::EthSenderDal,
     events_dal::EventsDal, events_web3_dal::EventsWeb3Dal, factory_deps_dal::FactoryDepsDal,
+    l1_batches_custom_metadata_dal::L1BatchesCustomMetadataDal,
+    operator_audit_log_dal::OperatorAuditLogDal, partitioning_dal::PartitioningDal,
     proof_generation_dal::ProofGenerationDal, protocol_versions_dal::ProtocolVersionsDal,
     protocol_versions_web3_dal::ProtocolVersionsWeb3Dal, pruning_dal::PruningDal,
-    snapshot_recovery_dal::SnapshotRecoveryDal, snapshots_creator_dal::SnapshotsCreatorDal,
-    snapshots_dal::SnapshotsDal, storage_logs_dal::StorageLogsDal,
-    storage_logs_dedup_dal::StorageLogsDedupDal, storage_web3_dal::StorageWeb3Dal,
-    sync_dal::SyncDal, system_dal::SystemDal,
+    rejected_transactions_dal::RejectedTransactionsDal, snapshot_recovery_dal::SnapshotRecoveryDal,
+    snapshots_creator_dal::SnapshotsCreatorDal, snapshots_dal::SnapshotsDal,
+    storage_logs_dal::StorageLogsDal, storage_logs_dedup_dal::StorageLogsDedupDal,
+    storage_web3_dal::StorageWeb3Dal, sync_dal::SyncDal, system_dal::SystemDal,
     tee_verifier_input_producer_dal::TeeVerifierInputProducerDal, tokens_dal::TokensDal,
-    tokens_web3_dal::TokensWeb3Dal, transactions_dal::TransactionsDal,
+    tokens_web3_dal::TokensWeb3Dal, traced_addresses_dal::TracedAddressesDal,
+    transaction_deadlines_dal::TransactionDeadlinesDal, transactions_dal::TransactionsDal,
     transactions_web3_dal::TransactionsWeb3Dal, vm_runner_dal::VmRunnerDal,
+    watermarks_dal::WatermarksDal,
 };
 
 pub mod blocks_dal;
 pub mod blocks_web3_dal;
+pub mod boosted_transactions_dal;
 pub mod consensus;
 pub mod consensus_dal;
 pub mod contract_verification_dal;
@@ -35,13 +41,18 @@ pub mod eth_sender_dal;
 pub mod events_dal;
 pub mod events_web3_dal;
 pub mod factory_deps_dal;
+pub mod fixtures;
 pub mod helpers;
+pub mod l1_batches_custom_metadata_dal;
 pub mod metrics;
 mod models;
+pub mod operator_audit_log_dal;
+pub mod partitioning_dal;
 pub mod proof_generation_dal;
 pub mod protocol_versions_dal;
 pub mod protocol_versions_web3_dal;
 pub mod pruning_dal;
+pub mod rejected_transactions_dal;
 pub mod snapshot_recovery_dal;
 pub mod snapshots_creator_dal;
 pub mod snapshots_dal;
@@ -53,9 +64,12 @@ pub mod system_dal;
 pub mod tee_verifier_input_producer_dal;
 pub mod tokens_dal;
 pub mod tokens_web3_dal;
+pub mod traced_addresses_dal;
+pub mod transaction_deadlines_dal;
 pub mod transactions_dal;
 pub mod transactions_web3_dal;
 pub mod vm_runner_dal;
+pub mod watermarks_dal;
 
 #[cfg(test)]
 mod tests;
@@ -121,7 +135,23 @@ where
 
     fn pruning_dal(&mut self) -> PruningDal<'_, 'a>;
 
+    fn partitioning_dal(&mut self) -> PartitioningDal<'_, 'a>;
+
     fn vm_runner_dal(&mut self) -> VmRunnerDal<'_, 'a>;
+
+    fn rejected_transactions_dal(&mut self) -> RejectedTransactionsDal<'_, 'a>;
+
+    fn traced_addresses_dal(&mut self) -> TracedAddressesDal<'_, 'a>;
+
+    fn transaction_deadlines_dal(&mut self) -> TransactionDeadlinesDal<'_, 'a>;
+
+    fn boosted_transactions_dal(&mut self) -> BoostedTransactionsDal<'_, 'a>;
+
+    fn l1_batches_custom_metadata_dal(&mut self) -> L1BatchesCustomMetadataDal<'_, 'a>;
+
+    fn watermarks_dal(&mut self) -> WatermarksDal<'_, 'a>;
+
+    fn operator_audit_log_dal(&mut self) -> OperatorAuditLogDal<'_, 'a>;
 }
 
 #[derive(Clone, Debug)]
@@ -233,7 +263,39 @@ impl<'a> CoreDal<'a> for Connection<'a, Core> {
         PruningDal { storage: self }
     }
 
+    fn partitioning_dal(&mut self) -> PartitioningDal<'_, 'a> {
+        PartitioningDal { storage: self }
+    }
+
     fn vm_runner_dal(&mut self) -> VmRunnerDal<'_, 'a> {
         VmRunnerDal { storage: self }
     }
+
+    fn rejected_transactions_dal(&mut self) -> RejectedTransactionsDal<'_, 'a> {
+        RejectedTransactionsDal { storage: self }
+    }
+
+    fn traced_addresses_dal(&mut self) -> TracedAddressesDal<'_, 'a> {
+        TracedAddressesDal { storage: self }
+    }
+
+    fn transaction_deadlines_dal(&mut self) -> TransactionDeadlinesDal<'_, 'a> {
+        TransactionDeadlinesDal { storage: self }
+    }
+
+    fn boosted_transactions_dal(&mut self) -> BoostedTransactionsDal<'_, 'a> {
+        BoostedTransactionsDal { storage: self }
+    }
+
+    fn l1_batches_custom_metadata_dal(&mut self) -> L1BatchesCustomMetadataDal<'_, 'a> {
+        L1BatchesCustomMetadataDal { storage: self }
+    }
+
+    fn watermarks_dal(&mut self) -> WatermarksDal<'_, 'a> {
+        WatermarksDal { storage: self }
+    }
+
+    fn operator_audit_log_dal(&mut self) -> OperatorAuditLogDal<'_, 'a> {
+        OperatorAuditLogDal { storage: self }
+    }
 }
@@ -1,4 +1,5 @@
 pub mod storage_block;
+pub mod storage_call_trace;
 use anyhow::Context as _;
 use zksync_db_connection::error::SqlxContext;
 use zksync_types::{ProtocolVersionId, H160, H256};
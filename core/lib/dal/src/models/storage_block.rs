@@ -1,4 +1,4 @@
-use std::{convert::TryInto, str::FromStr};
+use std::{collections::BTreeMap, convert::TryInto, str::FromStr};
 
 use bigdecimal::{BigDecimal, ToPrimitive};
 use sqlx::types::chrono::{DateTime, NaiveDateTime, Utc};
@@ -393,6 +393,7 @@ impl From<StorageL1BatchDetails> for api::L1BatchDetails {
         api::L1BatchDetails {
             base,
             number: L1BatchNumber(details.number as u32),
+            custom_metadata: BTreeMap::new(),
         }
     }
 }
@@ -0,0 +1,517 @@
+//! Storage formats for call traces.
+//!
+//! Legacy traces are bincode-serialized [`Call`] trees, which can only be decoded in full. New traces
+//! are written in the legacy format by default, so that nodes unaware of the flat format can read them.
+//! Once all nodes are upgraded, new traces can be written in the flat format; existing legacy traces are
+//! converted to it by a separate migration. Flat traces store call frames without subcalls in the depth-first
+//! (pre-order) traversal order, prefixed with an index that allows decoding only the selected frames:
+//!
+//! ```text
+//! frame_count: u32
+//! index: [(parent: u32, subtree_end: u32, data_end: u32); frame_count]
+//! frames: [bincode(frame); frame_count]
+//! ```
+//!
+//! All integers are little-endian. `parent` is `u32::MAX` for the top call; `subtree_end` is the index
+//! following the last descendant of the frame; `data_end` is the end offset of the frame data relative
+//! to the start of `frames`.
+
+use std::borrow::Cow;
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use zksync_types::{
+    vm_trace::{Call, CallType, LegacyCall, LegacyMixedCall},
+    Address, ProtocolVersionId, U256,
+};
+
+/// Format of a call trace stored in the `call_traces.format_version` column.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CallTraceFormat {
+    /// Bincode-serialized call tree, which can be read by all node versions.
+    #[default]
+    Legacy = 1,
+    /// Flat format allowing to decode selected call frames. Cannot be read by nodes unaware of it.
+    Flat = 2,
+}
+
+impl CallTraceFormat {
+    fn from_db(raw: i16) -> anyhow::Result<Self> {
+        Ok(match raw {
+            1 => Self::Legacy,
+            2 => Self::Flat,
+            _ => anyhow::bail!("unknown call trace format: {raw}"),
+        })
+    }
+}
+
+/// Selects the part of a call trace to load.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallTraceFilter {
+    /// Number of direct subcalls of the top call to skip.
+    pub top_calls_offset: usize,
+    /// Maximum number of direct subcalls of the top call to return. If not set, all remaining subcalls are returned.
+    pub top_calls_limit: Option<usize>,
+    /// Maximum number of frames (including the top call) to return. Frames are taken in the depth-first order,
+    /// so the returned trace is always a connected tree.
+    pub max_frames: Option<usize>,
+}
+
+impl CallTraceFilter {
+    fn is_trivial(&self) -> bool {
+        self.top_calls_offset == 0 && self.top_calls_limit.is_none() && self.max_frames.is_none()
+    }
+
+    fn includes_top_call(&self, top_call_idx: usize) -> bool {
+        top_call_idx >= self.top_calls_offset
+            && self
+                .top_calls_limit
+                .map_or(true, |limit| top_call_idx - self.top_calls_offset < limit)
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub(crate) struct CallTrace {
+    pub call_trace: Vec<u8>,
+    pub format_version: i16,
+}
+
+impl CallTrace {
+    pub(crate) const LEGACY_FORMAT_VERSION: i16 = CallTraceFormat::Legacy as i16;
+    pub(crate) const FLAT_FORMAT_VERSION: i16 = CallTraceFormat::Flat as i16;
+
+    pub(crate) fn into_call(self, protocol_version: ProtocolVersionId) -> anyhow::Result<Call> {
+        self.into_filtered_call(protocol_version, &CallTraceFilter::default())
+    }
+
+    pub(crate) fn into_filtered_call(
+        self,
+        protocol_version: ProtocolVersionId,
+        filter: &CallTraceFilter,
+    ) -> anyhow::Result<Call> {
+        match CallTraceFormat::from_db(self.format_version)? {
+            CallTraceFormat::Legacy => {
+                let call = decode_legacy(&self.call_trace, protocol_version)?;
+                if filter.is_trivial() {
+                    Ok(call)
+                } else {
+                    decode_flat(&encode_flat(&call)?, filter)
+                }
+            }
+            CallTraceFormat::Flat => decode_flat(&self.call_trace, filter),
+        }
+    }
+
+    /// Encodes a call trace in the specified format. `protocol_version` is only used for the legacy format.
+    pub(crate) fn from_call(
+        call: &Call,
+        format: CallTraceFormat,
+        protocol_version: ProtocolVersionId,
+    ) -> anyhow::Result<Self> {
+        let call_trace = match format {
+            CallTraceFormat::Legacy if protocol_version.is_pre_1_5_0() => {
+                let legacy_call = LegacyCall::try_from(call.clone())
+                    .context("call trace cannot be represented in the pre-1.5.0 format")?;
+                bincode::serialize(&legacy_call).context("failed serializing call trace")?
+            }
+            CallTraceFormat::Legacy => {
+                bincode::serialize(call).context("failed serializing call trace")?
+            }
+            CallTraceFormat::Flat => encode_flat(call)?,
+        };
+        Ok(Self {
+            call_trace,
+            format_version: format as i16,
+        })
+    }
+
+    /// Converts a trace in the legacy format to the flat format. Returns `None` if the trace is already flat.
+    pub(crate) fn migrate(
+        self,
+        protocol_version: ProtocolVersionId,
+    ) -> anyhow::Result<Option<Self>> {
+        Ok(match CallTraceFormat::from_db(self.format_version)? {
+            CallTraceFormat::Legacy => {
+                let call = decode_legacy(&self.call_trace, protocol_version)?;
+                Some(Self {
+                    call_trace: encode_flat(&call)?,
+                    format_version: Self::FLAT_FORMAT_VERSION,
+                })
+            }
+            CallTraceFormat::Flat => None,
+        })
+    }
+}
+
+fn decode_legacy(bytes: &[u8], protocol_version: ProtocolVersionId) -> anyhow::Result<Call> {
+    if protocol_version.is_pre_1_5_0() {
+        if let Ok(legacy_call_trace) = bincode::deserialize::<LegacyCall>(bytes) {
+            Ok(legacy_call_trace.into())
+        } else {
+            let legacy_mixed_call_trace = bincode::deserialize::<LegacyMixedCall>(bytes)
+                .context("failed deserializing pre-1.5.0 call trace")?;
+            Ok(legacy_mixed_call_trace.into())
+        }
+    } else {
+        bincode::deserialize(bytes).context("failed deserializing call trace")
+    }
+}
+
+const NO_PARENT: u32 = u32::MAX;
+const INDEX_ENTRY_SIZE: usize = 12;
+
+/// Call frame without subcalls.
+#[derive(Serialize, Deserialize)]
+struct CallFrame<'a> {
+    r#type: CallType,
+    from: Address,
+    to: Address,
+    parent_gas: u64,
+    gas: u64,
+    gas_used: u64,
+    value: U256,
+    input: Cow<'a, [u8]>,
+    output: Cow<'a, [u8]>,
+    error: Option<Cow<'a, str>>,
+    revert_reason: Option<Cow<'a, str>>,
+}
+
+impl<'a> From<&'a Call> for CallFrame<'a> {
+    fn from(call: &'a Call) -> Self {
+        Self {
+            r#type: call.r#type,
+            from: call.from,
+            to: call.to,
+            parent_gas: call.parent_gas,
+            gas: call.gas,
+            gas_used: call.gas_used,
+            value: call.value,
+            input: Cow::Borrowed(&call.input),
+            output: Cow::Borrowed(&call.output),
+            error: call.error.as_deref().map(Cow::Borrowed),
+            revert_reason: call.revert_reason.as_deref().map(Cow::Borrowed),
+        }
+    }
+}
+
+impl From<CallFrame<'_>> for Call {
+    fn from(frame: CallFrame<'_>) -> Self {
+        Self {
+            r#type: frame.r#type,
+            from: frame.from,
+            to: frame.to,
+            parent_gas: frame.parent_gas,
+            gas: frame.gas,
+            gas_used: frame.gas_used,
+            value: frame.value,
+            input: frame.input.into_owned(),
+            output: frame.output.into_owned(),
+            error: frame.error.map(Cow::into_owned),
+            revert_reason: frame.revert_reason.map(Cow::into_owned),
+            calls: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FrameIndex {
+    parent: u32,
+    subtree_end: u32,
+    data_end: u32,
+}
+
+fn encode_flat(call: &Call) -> anyhow::Result<Vec<u8>> {
+    let mut index = Vec::<FrameIndex>::new();
+    let mut data = vec![];
+    let mut stack = vec![(call, NO_PARENT)];
+    while let Some((call, parent)) = stack.pop() {
+        let frame_idx = index.len() as u32;
+        bincode::serialize_into(&mut data, &CallFrame::from(call))
+            .context("failed serializing call frame")?;
+        index.push(FrameIndex {
+            parent,
+            subtree_end: frame_idx + 1,
+            data_end: data.len() as u32,
+        });
+        stack.extend(call.calls.iter().rev().map(|child| (child, frame_idx)));
+    }
+    // Children always follow their parents, so iterating in the reverse order propagates subtree ends correctly.
+    for i in (1..index.len()).rev() {
+        let FrameIndex {
+            parent,
+            subtree_end,
+            ..
+        } = index[i];
+        let parent = &mut index[parent as usize];
+        parent.subtree_end = parent.subtree_end.max(subtree_end);
+    }
+
+    let mut bytes = Vec::with_capacity(4 + index.len() * INDEX_ENTRY_SIZE + data.len());
+    bytes.extend_from_slice(&(index.len() as u32).to_le_bytes());
+    for entry in &index {
+        bytes.extend_from_slice(&entry.parent.to_le_bytes());
+        bytes.extend_from_slice(&entry.subtree_end.to_le_bytes());
+        bytes.extend_from_slice(&entry.data_end.to_le_bytes());
+    }
+    bytes.extend_from_slice(&data);
+    Ok(bytes)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> anyhow::Result<u32> {
+    let chunk = bytes
+        .get(offset..offset + 4)
+        .context("unexpected end of call trace")?;
+    Ok(u32::from_le_bytes(chunk.try_into().unwrap()))
+}
+
+fn parse_index(bytes: &[u8]) -> anyhow::Result<(Vec<FrameIndex>, &[u8])> {
+    let frame_count = read_u32(bytes, 0)? as usize;
+    anyhow::ensure!(frame_count > 0, "call trace has no frames");
+    let data_start = frame_count
+        .checked_mul(INDEX_ENTRY_SIZE)
+        .and_then(|len| len.checked_add(4))
+        .context("call trace index overflow")?;
+    let data = bytes
+        .get(data_start..)
+        .context("unexpected end of call trace index")?;
+
+    let mut index = Vec::with_capacity(frame_count);
+    let mut prev_data_end = 0;
+    for i in 0..frame_count {
+        let offset = 4 + i * INDEX_ENTRY_SIZE;
+        let entry = FrameIndex {
+            parent: read_u32(bytes, offset)?,
+            subtree_end: read_u32(bytes, offset + 4)?,
+            data_end: read_u32(bytes, offset + 8)?,
+        };
+        if i == 0 {
+            anyhow::ensure!(entry.parent == NO_PARENT, "top call has a parent");
+        } else {
+            anyhow::ensure!((entry.parent as usize) < i, "invalid parent for frame #{i}");
+        }
+        anyhow::ensure!(
+            entry.subtree_end as usize > i && entry.subtree_end as usize <= frame_count,
+            "invalid subtree end for frame #{i}"
+        );
+        anyhow::ensure!(
+            entry.data_end >= prev_data_end && entry.data_end as usize <= data.len(),
+            "invalid data end for frame #{i}"
+        );
+        prev_data_end = entry.data_end;
+        index.push(entry);
+    }
+    Ok((index, data))
+}
+
+/// Returns sorted indices of frames selected by the filter. Decodes only the index.
+fn select_frames(index: &[FrameIndex], filter: &CallTraceFilter) -> Vec<usize> {
+    let mut selected = vec![0];
+    let mut child = 1;
+    let mut top_call_idx = 0;
+    while child < index[0].subtree_end as usize {
+        let child_end = index[child].subtree_end as usize;
+        if filter.includes_top_call(top_call_idx) {
+            selected.extend(child..child_end);
+        }
+        top_call_idx += 1;
+        child = child_end;
+    }
+    if let Some(max_frames) = filter.max_frames {
+        selected.truncate(max_frames.max(1));
+    }
+    selected
+}
+
+fn decode_flat(bytes: &[u8], filter: &CallTraceFilter) -> anyhow::Result<Call> {
+    let (index, data) = parse_index(bytes)?;
+    let selected = select_frames(&index, filter);
+
+    let mut calls = Vec::<(Call, usize)>::with_capacity(selected.len());
+    for &frame_idx in &selected {
+        let data_start = match frame_idx {
+            0 => 0,
+            _ => index[frame_idx - 1].data_end as usize,
+        };
+        let frame_data = &data[data_start..index[frame_idx].data_end as usize];
+        let frame: CallFrame<'_> = bincode::deserialize(frame_data)
+            .with_context(|| format!("failed deserializing call frame #{frame_idx}"))?;
+        // All ancestors of a selected frame are selected as well, so the lookup cannot fail.
+        let parent_pos = match index[frame_idx].parent {
+            NO_PARENT => 0,
+            parent => selected
+                .binary_search(&(parent as usize))
+                .map_err(|_| anyhow::anyhow!("parent of frame #{frame_idx} is not selected"))?,
+        };
+        calls.push((frame.into(), parent_pos));
+    }
+
+    // Subcalls are attached in the reverse order; when a call is detached, all its subcalls are already attached to it.
+    while calls.len() > 1 {
+        let (mut call, parent_pos) = calls.pop().unwrap();
+        call.calls.reverse();
+        calls[parent_pos].0.calls.push(call);
+    }
+    let (mut top_call, _) = calls.pop().unwrap();
+    top_call.calls.reverse();
+    Ok(top_call)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(gas_used: u64, calls: Vec<Call>) -> Call {
+        Call {
+            from: Address::repeat_byte(1),
+            to: Address::repeat_byte(2),
+            gas_used,
+            input: vec![gas_used as u8; 4],
+            error: (gas_used % 2 == 0).then(|| format!("error #{gas_used}")),
+            calls,
+            ..Call::default()
+        }
+    }
+
+    fn test_trace() -> Call {
+        call(
+            0,
+            vec![
+                call(1, vec![call(2, vec![]), call(3, vec![call(4, vec![])])]),
+                call(5, vec![]),
+                call(6, vec![call(7, vec![])]),
+            ],
+        )
+    }
+
+    fn gas_used_in_dfs_order(call: &Call, output: &mut Vec<u64>) {
+        output.push(call.gas_used);
+        for subcall in &call.calls {
+            gas_used_in_dfs_order(subcall, output);
+        }
+    }
+
+    #[test]
+    fn flat_call_trace_roundtrip() {
+        let trace = test_trace();
+        let bytes = encode_flat(&trace).unwrap();
+        let (index, _) = parse_index(&bytes).unwrap();
+        let subtree_ends: Vec<_> = index.iter().map(|entry| entry.subtree_end).collect();
+        assert_eq!(subtree_ends, [8, 5, 3, 5, 5, 6, 8, 8]);
+
+        let decoded = decode_flat(&bytes, &CallTraceFilter::default()).unwrap();
+        assert_eq!(decoded, trace);
+        let mut gas_used = vec![];
+        gas_used_in_dfs_order(&decoded, &mut gas_used);
+        assert_eq!(gas_used, [0, 1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn decoding_part_of_flat_call_trace() {
+        let bytes = encode_flat(&test_trace()).unwrap();
+
+        let filter = CallTraceFilter {
+            max_frames: Some(3),
+            ..CallTraceFilter::default()
+        };
+        let decoded = decode_flat(&bytes, &filter).unwrap();
+        let mut gas_used = vec![];
+        gas_used_in_dfs_order(&decoded, &mut gas_used);
+        assert_eq!(gas_used, [0, 1, 2]);
+
+        let filter = CallTraceFilter {
+            top_calls_offset: 1,
+            top_calls_limit: Some(1),
+            max_frames: None,
+        };
+        let decoded = decode_flat(&bytes, &filter).unwrap();
+        let mut gas_used = vec![];
+        gas_used_in_dfs_order(&decoded, &mut gas_used);
+        assert_eq!(gas_used, [0, 5]);
+
+        let filter = CallTraceFilter {
+            top_calls_offset: 1,
+            top_calls_limit: None,
+            max_frames: None,
+        };
+        let decoded = decode_flat(&bytes, &filter).unwrap();
+        let mut gas_used = vec![];
+        gas_used_in_dfs_order(&decoded, &mut gas_used);
+        assert_eq!(gas_used, [0, 5, 6, 7]);
+
+        let filter = CallTraceFilter {
+            top_calls_offset: 10,
+            ..CallTraceFilter::default()
+        };
+        let decoded = decode_flat(&bytes, &filter).unwrap();
+        assert_eq!(decoded.gas_used, 0);
+        assert!(decoded.calls.is_empty());
+    }
+
+    #[test]
+    fn migrating_legacy_call_trace() {
+        let trace = test_trace();
+        let protocol_version = ProtocolVersionId::latest();
+        let legacy_trace =
+            CallTrace::from_call(&trace, CallTraceFormat::Legacy, protocol_version).unwrap();
+        assert_eq!(legacy_trace.format_version, CallTraceFormat::Legacy as i16);
+        assert_eq!(legacy_trace.call_trace, bincode::serialize(&trace).unwrap());
+        let filter = CallTraceFilter {
+            max_frames: Some(2),
+            ..CallTraceFilter::default()
+        };
+        let filtered_legacy_call = legacy_trace
+            .clone()
+            .into_filtered_call(protocol_version, &filter)
+            .unwrap();
+
+        let migrated_trace = legacy_trace.migrate(protocol_version).unwrap().unwrap();
+        assert_eq!(migrated_trace.format_version, CallTraceFormat::Flat as i16);
+        assert_eq!(
+            migrated_trace.call_trace,
+            CallTrace::from_call(&trace, CallTraceFormat::Flat, protocol_version)
+                .unwrap()
+                .call_trace
+        );
+        assert!(migrated_trace
+            .clone()
+            .migrate(protocol_version)
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            migrated_trace
+                .clone()
+                .into_filtered_call(protocol_version, &filter)
+                .unwrap(),
+            filtered_legacy_call
+        );
+        assert_eq!(migrated_trace.into_call(protocol_version).unwrap(), trace);
+    }
+
+    #[test]
+    fn corrupted_call_traces_are_errors() {
+        let protocol_version = ProtocolVersionId::latest();
+        let legacy_trace = CallTrace {
+            call_trace: vec![1, 2, 3],
+            format_version: CallTraceFormat::Legacy as i16,
+        };
+        legacy_trace
+            .clone()
+            .into_call(protocol_version)
+            .unwrap_err();
+        legacy_trace.migrate(protocol_version).unwrap_err();
+
+        let unknown_trace = CallTrace {
+            call_trace: encode_flat(&test_trace()).unwrap(),
+            format_version: 100,
+        };
+        unknown_trace.into_call(protocol_version).unwrap_err();
+    }
+
+    #[test]
+    fn truncated_flat_call_trace_is_rejected() {
+        let bytes = encode_flat(&test_trace()).unwrap();
+        for len in [0, 3, 20, bytes.len() - 1] {
+            decode_flat(&bytes[..len], &CallTraceFilter::default()).unwrap_err();
+        }
+    }
+}
@@ -29,6 +29,8 @@ pub struct StorageEthTx {
     //
     // Format a `bincode`-encoded `EthTxBlobSidecar` enum.
     pub blob_sidecar: Option<Vec<u8>>,
+    // Revert reason reported by L1 for a failed transaction.
+    pub failure_reason: Option<String>,
 }
 
 #[derive(Debug, Default)]
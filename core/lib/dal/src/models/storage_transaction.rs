@@ -9,11 +9,10 @@ use zksync_types::{
     l2::TransactionType,
     protocol_upgrade::ProtocolUpgradeTxCommonData,
     transaction_request::PaymasterParams,
-    vm_trace::{Call, LegacyCall, LegacyMixedCall},
     web3::Bytes,
-    Address, Execute, ExecuteTransactionCommon, L1TxCommonData, L2ChainId, L2TxCommonData, Nonce,
-    PackedEthSignature, PriorityOpId, ProtocolVersionId, Transaction, EIP_1559_TX_TYPE,
-    EIP_2930_TX_TYPE, EIP_712_TX_TYPE, H160, H256, PRIORITY_OPERATION_L2_TX_TYPE,
+    Address, Execute, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, L1TxCommonData,
+    L2BlockNumber, L2ChainId, L2TxCommonData, Nonce, PackedEthSignature, PriorityOpId, Transaction,
+    EIP_1559_TX_TYPE, EIP_2930_TX_TYPE, EIP_712_TX_TYPE, H160, H256, PRIORITY_OPERATION_L2_TX_TYPE,
     PROTOCOL_UPGRADE_TX_TYPE, U256, U64,
 };
 use zksync_utils::{bigdecimal_to_u256, h256_to_account_address};
@@ -471,6 +470,62 @@ impl From<StorageTransactionDetails> for TransactionDetails {
     }
 }
 
+#[derive(Debug, Clone)]
+pub(crate) struct StoragePriorityOpDetails {
+    pub hash: Vec<u8>,
+    pub priority_op_id: i64,
+    pub initiator_address: Vec<u8>,
+    pub l1_block_number: Option<i32>,
+    pub received_at: NaiveDateTime,
+    pub error: Option<String>,
+    pub miniblock_number: Option<i64>,
+    pub l1_batch_number: Option<i64>,
+    pub eth_commit_tx_hash: Option<String>,
+    pub eth_prove_tx_hash: Option<String>,
+    pub eth_execute_tx_hash: Option<String>,
+}
+
+impl StoragePriorityOpDetails {
+    fn status(&self) -> api::PriorityOpStatus {
+        if self.eth_execute_tx_hash.is_some() {
+            api::PriorityOpStatus::Executed
+        } else if self.eth_prove_tx_hash.is_some() {
+            api::PriorityOpStatus::Proven
+        } else if self.eth_commit_tx_hash.is_some() {
+            api::PriorityOpStatus::Committed
+        } else if self.miniblock_number.is_some() {
+            api::PriorityOpStatus::Included
+        } else {
+            api::PriorityOpStatus::Pending
+        }
+    }
+}
+
+impl From<StoragePriorityOpDetails> for api::PriorityOpDetails {
+    fn from(details: StoragePriorityOpDetails) -> Self {
+        let status = details.status();
+        Self {
+            serial_id: PriorityOpId(details.priority_op_id as u64),
+            tx_hash: H256::from_slice(&details.hash),
+            sender: H160::from_slice(&details.initiator_address),
+            l1_block_number: details
+                .l1_block_number
+                .map(|number| L1BlockNumber(number as u32)),
+            received_at: DateTime::<Utc>::from_naive_utc_and_offset(details.received_at, Utc),
+            status,
+            l2_block_number: details
+                .miniblock_number
+                .map(|number| L2BlockNumber(number as u32)),
+            l1_batch_number: details
+                .l1_batch_number
+                .map(|number| L1BatchNumber(number as u32)),
+            error: details.error,
+            queue_position: None,
+            estimated_inclusion_at: None,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct StorageApiTransaction {
     pub tx_hash: Vec<u8>,
@@ -538,36 +593,3 @@ impl StorageApiTransaction {
         tx
     }
 }
-
-#[derive(Debug, Clone, sqlx::FromRow)]
-pub(crate) struct CallTrace {
-    pub call_trace: Vec<u8>,
-}
-
-impl CallTrace {
-    pub(crate) fn into_call(self, protocol_version: ProtocolVersionId) -> Call {
-        if protocol_version.is_pre_1_5_0() {
-            if let Ok(legacy_call_trace) = bincode::deserialize::<LegacyCall>(&self.call_trace) {
-                legacy_call_trace.into()
-            } else {
-                let legacy_mixed_call_trace =
-                    bincode::deserialize::<LegacyMixedCall>(&self.call_trace)
-                        .expect("Failed to deserialize call trace");
-                legacy_mixed_call_trace.into()
-            }
-        } else {
-            bincode::deserialize(&self.call_trace).unwrap()
-        }
-    }
-
-    pub(crate) fn from_call(call: Call, protocol_version: ProtocolVersionId) -> Self {
-        let call_trace = if protocol_version.is_pre_1_5_0() {
-            bincode::serialize(&LegacyCall::try_from(call).unwrap())
-        } else {
-            bincode::serialize(&call)
-        }
-        .unwrap();
-
-        Self { call_trace }
-    }
-}
@@ -0,0 +1,183 @@
+use chrono::{DateTime, Utc};
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+use zksync_types::api::OperatorAuditLogEntry;
+
+use crate::Core;
+
+/// Tracing target for audit log entries. Allows routing entries to a dedicated log sink.
+pub const AUDIT_LOG_TARGET: &str = "operator_audit";
+
+/// DAL for the append-only log of administrative actions taken by node operators.
+#[derive(Debug)]
+pub struct OperatorAuditLogDal<'c, 'a> {
+    pub(crate) storage: &'c mut Connection<'a, Core>,
+}
+
+impl OperatorAuditLogDal<'_, '_> {
+    /// Records an action performed by `actor`. Besides being persisted, the entry is emitted as a structured
+    /// log event with the [`AUDIT_LOG_TARGET`] target. Returns the ID of the recorded entry.
+    pub async fn record_action(
+        &mut self,
+        actor: &str,
+        action: &str,
+        details: serde_json::Value,
+    ) -> DalResult<u64> {
+        let row = sqlx::query!(
+            r#"
+            INSERT INTO
+                operator_audit_log (actor, action, details, created_at)
+            VALUES
+                ($1, $2, $3, NOW())
+            RETURNING
+                id
+            "#,
+            actor,
+            action,
+            &details
+        )
+        .instrument("record_action")
+        .with_arg("actor", &actor)
+        .with_arg("action", &action)
+        .fetch_one(self.storage)
+        .await?;
+
+        let id = row.id as u64;
+        tracing::info!(
+            target: AUDIT_LOG_TARGET,
+            id,
+            actor,
+            action,
+            %details,
+            "Operator `{actor}` performed `{action}`"
+        );
+        Ok(id)
+    }
+
+    /// Returns up to `limit` entries with IDs greater than `after_id`, ordered by ID.
+    pub async fn get_entries(
+        &mut self,
+        after_id: Option<u64>,
+        limit: usize,
+    ) -> DalResult<Vec<OperatorAuditLogEntry>> {
+        let after_id = after_id.map_or(0, |id| id as i64);
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                id,
+                actor,
+                action,
+                details,
+                created_at
+            FROM
+                operator_audit_log
+            WHERE
+                id > $1
+            ORDER BY
+                id
+            LIMIT
+                $2
+            "#,
+            after_id,
+            limit as i64
+        )
+        .instrument("get_audit_log_entries")
+        .with_arg("after_id", &after_id)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| OperatorAuditLogEntry {
+                id: row.id as u64,
+                actor: row.actor,
+                action: row.action,
+                details: row.details,
+                created_at: DateTime::<Utc>::from_naive_utc_and_offset(row.created_at, Utc),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    #[tokio::test]
+    async fn recording_and_reading_audit_log() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+
+        let entries = conn
+            .operator_audit_log_dal()
+            .get_entries(None, 10)
+            .await
+            .unwrap();
+        assert!(entries.is_empty(), "{entries:?}");
+
+        let first_id = conn
+            .operator_audit_log_dal()
+            .record_action(
+                "alice",
+                "add_traced_addresses",
+                json!({ "addresses": ["0x01"] }),
+            )
+            .await
+            .unwrap();
+        let second_id = conn
+            .operator_audit_log_dal()
+            .record_action(
+                "bob",
+                "roll_back_l1_batches",
+                json!({ "last_l1_batch_to_keep": 1 }),
+            )
+            .await
+            .unwrap();
+        assert!(second_id > first_id);
+
+        let entries = conn
+            .operator_audit_log_dal()
+            .get_entries(None, 10)
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id, first_id);
+        assert_eq!(entries[0].actor, "alice");
+        assert_eq!(entries[0].action, "add_traced_addresses");
+        assert_eq!(entries[0].details, json!({ "addresses": ["0x01"] }));
+        assert_eq!(entries[1].id, second_id);
+
+        let entries = conn
+            .operator_audit_log_dal()
+            .get_entries(Some(first_id), 10)
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].actor, "bob");
+        let entries = conn
+            .operator_audit_log_dal()
+            .get_entries(None, 1)
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].actor, "alice");
+    }
+
+    #[tokio::test]
+    async fn audit_log_is_append_only() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        conn.operator_audit_log_dal()
+            .record_action("alice", "clear_failed_l1_transactions", json!({}))
+            .await
+            .unwrap();
+
+        sqlx::query("UPDATE operator_audit_log SET actor = 'mallory'")
+            .execute(conn.conn())
+            .await
+            .unwrap_err();
+    }
+}
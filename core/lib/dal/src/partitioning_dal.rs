@@ -0,0 +1,521 @@
+//! Native Postgres partitioning of the largest append-only tables by L2 block ranges.
+//!
+//! Both partitioned tables have `miniblock_number` as a part of their primary key, so they can be partitioned
+//! by `RANGE (miniblock_number)` without changing their schema. DAL queries for these tables are already bounded
+//! by `miniblock_number`, so Postgres prunes irrelevant partitions both at planning and at execution time;
+//! e.g., `eth_getLogs` with a block range only touches partitions overlapping the range, and queries for the latest
+//! value of a storage slot scan partitions in the descending order and stop on the first match.
+//!
+//! An existing unpartitioned table is converted online: data is copied in chunks to a partitioned shadow table,
+//! after which the tables are swapped in a short transaction that copies the remaining tail. Conversion must not run
+//! concurrently with Postgres pruning or block reverts, since they modify data in already copied chunks.
+
+use std::{collections::HashSet, fmt, ops};
+
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+use zksync_types::L2BlockNumber;
+
+use crate::{Core, CoreDal};
+
+/// Table that can be partitioned by L2 block ranges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PartitionedTable {
+    Events,
+    StorageLogs,
+}
+
+impl PartitionedTable {
+    pub const ALL: [Self; 2] = [Self::Events, Self::StorageLogs];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Events => "events",
+            Self::StorageLogs => "storage_logs",
+        }
+    }
+
+    fn shadow_table_name(self) -> String {
+        format!("{}_partitioned", self.name())
+    }
+
+    fn unpartitioned_table_name(self) -> String {
+        format!("{}_unpartitioned", self.name())
+    }
+
+    fn partitions_prefix(self) -> String {
+        format!("{}_l2_blocks_", self.name())
+    }
+
+    /// Partitions are named after the logical table (rather than the parent table) so that they retain
+    /// meaningful names after the shadow table is renamed.
+    fn partition_name(self, range: &ops::Range<u32>) -> String {
+        format!("{}{}_{}", self.partitions_prefix(), range.start, range.end)
+    }
+
+    fn parse_partition_name(self, name: &str) -> Option<ops::Range<u32>> {
+        let bounds = name.strip_prefix(&self.partitions_prefix())?;
+        let (start, end) = bounds.split_once('_')?;
+        Some(start.parse().ok()?..end.parse().ok()?)
+    }
+}
+
+impl fmt::Display for PartitionedTable {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.name())
+    }
+}
+
+/// Conversion status of a [`PartitionedTable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartitioningStatus {
+    /// The table is not partitioned, and no conversion was started.
+    Unpartitioned,
+    /// The table is being converted; data is copied to the shadow table up to the specified L2 block (inclusive).
+    Converting { copied_up_to: Option<L2BlockNumber> },
+    /// The table is partitioned.
+    Partitioned,
+}
+
+#[derive(Debug)]
+pub struct PartitioningDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl PartitioningDal<'_, '_> {
+    async fn is_partitioned_table(&mut self, table_name: &str) -> DalResult<bool> {
+        let is_partitioned = sqlx::query!(
+            r#"
+            SELECT
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        pg_partitioned_table
+                    WHERE
+                        partrelid = TO_REGCLASS($1)
+                ) AS "is_partitioned!"
+            "#,
+            table_name
+        )
+        .instrument("is_partitioned_table")
+        .with_arg("table_name", &table_name)
+        .fetch_one(self.storage)
+        .await?
+        .is_partitioned;
+        Ok(is_partitioned)
+    }
+
+    async fn table_exists(&mut self, table_name: &str) -> DalResult<bool> {
+        let exists = sqlx::query!(
+            r#"
+            SELECT
+                TO_REGCLASS($1) IS NOT NULL AS "exists!"
+            "#,
+            table_name
+        )
+        .instrument("table_exists")
+        .with_arg("table_name", &table_name)
+        .fetch_one(self.storage)
+        .await?
+        .exists;
+        Ok(exists)
+    }
+
+    pub async fn get_status(&mut self, table: PartitionedTable) -> DalResult<PartitioningStatus> {
+        if self.is_partitioned_table(table.name()).await? {
+            return Ok(PartitioningStatus::Partitioned);
+        }
+        if !self.table_exists(&table.shadow_table_name()).await? {
+            return Ok(PartitioningStatus::Unpartitioned);
+        }
+        let copied_up_to = self.shadow_table_copied_up_to(table).await?;
+        Ok(PartitioningStatus::Converting { copied_up_to })
+    }
+
+    async fn shadow_table_copied_up_to(
+        &mut self,
+        table: PartitionedTable,
+    ) -> DalResult<Option<L2BlockNumber>> {
+        let query = format!(
+            "SELECT MAX(miniblock_number) FROM {}",
+            table.shadow_table_name()
+        );
+        let max_l2_block: Option<i64> = sqlx::query_scalar(&query)
+            .instrument("shadow_table_copied_up_to")
+            .with_arg("table", &table.name())
+            .fetch_one(self.storage)
+            .await?;
+        Ok(max_l2_block.map(|number| L2BlockNumber(number as u32)))
+    }
+
+    /// Returns L2 block ranges of the existing partitions for the specified parent table.
+    async fn get_partitions(
+        &mut self,
+        table: PartitionedTable,
+        parent_table_name: &str,
+    ) -> DalResult<Vec<ops::Range<u32>>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                child.relname::TEXT AS "partition_name!"
+            FROM
+                pg_inherits
+                JOIN pg_class parent ON pg_inherits.inhparent = parent.oid
+                JOIN pg_class child ON pg_inherits.inhrelid = child.oid
+            WHERE
+                parent.oid = TO_REGCLASS($1)
+            "#,
+            parent_table_name
+        )
+        .instrument("get_partitions")
+        .with_arg("parent_table_name", &parent_table_name)
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut partitions: Vec<_> = rows
+            .iter()
+            .filter_map(|row| table.parse_partition_name(&row.partition_name))
+            .collect();
+        partitions.sort_unstable_by_key(|range| range.start);
+        Ok(partitions)
+    }
+
+    /// Creates partitions for the partitioned `table` so that all L2 blocks up to `up_to` (inclusive) are covered
+    /// by range partitions. Returns the number of created partitions. Does nothing if the table is not partitioned.
+    ///
+    /// Partitioned tables have a default partition that catches rows for L2 blocks not covered by range partitions,
+    /// but new partitions cannot be created for ranges that have rows in the default partition. Thus, partitions
+    /// should be created ahead of the sealed L2 block.
+    pub async fn ensure_partitions(
+        &mut self,
+        table: PartitionedTable,
+        up_to: L2BlockNumber,
+        l2_blocks_per_partition: u32,
+    ) -> DalResult<usize> {
+        if !self.is_partitioned_table(table.name()).await? {
+            return Ok(0);
+        }
+        self.create_partitions(table, table.name(), up_to, l2_blocks_per_partition)
+            .await
+    }
+
+    async fn create_partitions(
+        &mut self,
+        table: PartitionedTable,
+        parent_table_name: &str,
+        up_to: L2BlockNumber,
+        l2_blocks_per_partition: u32,
+    ) -> DalResult<usize> {
+        assert!(
+            l2_blocks_per_partition > 0,
+            "partition size must be positive"
+        );
+
+        let partitions = self.get_partitions(table, parent_table_name).await?;
+        let mut next_start = partitions.last().map_or(0, |range| range.end);
+        let mut created_count = 0;
+        while next_start <= up_to.0 {
+            let range = next_start..next_start.saturating_add(l2_blocks_per_partition);
+            let query = format!(
+                "CREATE TABLE IF NOT EXISTS {partition} PARTITION OF {parent_table_name} \
+                 FOR VALUES FROM ({start}) TO ({end})",
+                partition = table.partition_name(&range),
+                start = range.start,
+                end = range.end
+            );
+            sqlx::query(&query)
+                .instrument("create_partitions#create_partition")
+                .with_arg("table", &table.name())
+                .with_arg("range", &range)
+                .execute(self.storage)
+                .await?;
+
+            created_count += 1;
+            if range.end == u32::MAX {
+                break;
+            }
+            next_start = range.end;
+        }
+        Ok(created_count)
+    }
+
+    /// Starts converting `table`: creates a partitioned shadow table with the same columns, constraints and indexes,
+    /// and creates partitions for it up to `up_to` (inclusive). Idempotent.
+    pub async fn create_shadow_table(
+        &mut self,
+        table: PartitionedTable,
+        up_to: L2BlockNumber,
+        l2_blocks_per_partition: u32,
+    ) -> DalResult<()> {
+        let shadow_table = table.shadow_table_name();
+        let query = format!(
+            "CREATE TABLE IF NOT EXISTS {shadow_table} (LIKE {table} INCLUDING ALL) \
+             PARTITION BY RANGE (miniblock_number)"
+        );
+        sqlx::query(&query)
+            .instrument("create_shadow_table")
+            .with_arg("table", &table.name())
+            .execute(self.storage)
+            .await?;
+
+        let query = format!(
+            "CREATE TABLE IF NOT EXISTS {table}_default PARTITION OF {shadow_table} DEFAULT"
+        );
+        sqlx::query(&query)
+            .instrument("create_shadow_table#create_default_partition")
+            .with_arg("table", &table.name())
+            .execute(self.storage)
+            .await?;
+
+        self.create_partitions(table, &shadow_table, up_to, l2_blocks_per_partition)
+            .await?;
+        Ok(())
+    }
+
+    /// Copies rows for the specified L2 block range from `table` to its shadow table. Rows that are already copied
+    /// are skipped, so a chunk can be safely re-copied if the conversion was interrupted. Returns the number of copied rows.
+    pub async fn copy_chunk(
+        &mut self,
+        table: PartitionedTable,
+        l2_blocks: ops::RangeInclusive<L2BlockNumber>,
+    ) -> DalResult<u64> {
+        let query = format!(
+            "INSERT INTO {shadow_table} SELECT * FROM {table} \
+             WHERE miniblock_number BETWEEN $1 AND $2 \
+             ON CONFLICT DO NOTHING",
+            shadow_table = table.shadow_table_name()
+        );
+        let result = sqlx::query(&query)
+            .bind(i64::from(l2_blocks.start().0))
+            .bind(i64::from(l2_blocks.end().0))
+            .instrument("copy_chunk")
+            .with_arg("table", &table.name())
+            .with_arg("l2_blocks", &l2_blocks)
+            .report_latency()
+            .execute(self.storage)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Finishes converting `table`: copies rows after `copied_up_to` to the shadow table and swaps the tables.
+    /// Writes to the table are blocked until the swap is completed; to keep the blocking short, the tail after
+    /// `copied_up_to` should be small. The original table is retained as `{table}_unpartitioned`
+    /// and can be dropped using [`Self::drop_unpartitioned_table()`].
+    pub async fn swap_tables(
+        &mut self,
+        table: PartitionedTable,
+        copied_up_to: L2BlockNumber,
+        l2_blocks_per_partition: u32,
+    ) -> DalResult<u64> {
+        let mut transaction = self.storage.start_transaction().await?;
+        sqlx::query("SET LOCAL lock_timeout = '10s'")
+            .instrument("swap_tables#set_lock_timeout")
+            .execute(&mut transaction)
+            .await?;
+        let query = format!("LOCK TABLE {table} IN ACCESS EXCLUSIVE MODE");
+        sqlx::query(&query)
+            .instrument("swap_tables#lock")
+            .with_arg("table", &table.name())
+            .execute(&mut transaction)
+            .await?;
+
+        let query = format!("SELECT MAX(miniblock_number) FROM {table}");
+        let max_l2_block: Option<i64> = sqlx::query_scalar(&query)
+            .instrument("swap_tables#get_max_l2_block")
+            .with_arg("table", &table.name())
+            .fetch_one(&mut transaction)
+            .await?;
+        let shadow_table = table.shadow_table_name();
+        if let Some(max_l2_block) = max_l2_block {
+            // Create partitions with a margin so that the default partition stays empty after the swap.
+            let up_to = (max_l2_block as u32).saturating_add(l2_blocks_per_partition);
+            transaction
+                .partitioning_dal()
+                .create_partitions(
+                    table,
+                    &shadow_table,
+                    L2BlockNumber(up_to),
+                    l2_blocks_per_partition,
+                )
+                .await?;
+        }
+
+        let query = format!(
+            "INSERT INTO {shadow_table} SELECT * FROM {table} \
+             WHERE miniblock_number > $1 \
+             ON CONFLICT DO NOTHING"
+        );
+        let copied_rows = sqlx::query(&query)
+            .bind(i64::from(copied_up_to.0))
+            .instrument("swap_tables#copy_tail")
+            .with_arg("table", &table.name())
+            .with_arg("copied_up_to", &copied_up_to)
+            .execute(&mut transaction)
+            .await?
+            .rows_affected();
+
+        let query = format!(
+            "ALTER TABLE {table} RENAME TO {unpartitioned_table}",
+            unpartitioned_table = table.unpartitioned_table_name()
+        );
+        sqlx::query(&query)
+            .instrument("swap_tables#rename_original")
+            .with_arg("table", &table.name())
+            .execute(&mut transaction)
+            .await?;
+        let query = format!("ALTER TABLE {shadow_table} RENAME TO {table}");
+        sqlx::query(&query)
+            .instrument("swap_tables#rename_shadow")
+            .with_arg("table", &table.name())
+            .execute(&mut transaction)
+            .await?;
+
+        transaction.commit().await?;
+        Ok(copied_rows)
+    }
+
+    /// Drops the original table retained after [`Self::swap_tables()`].
+    pub async fn drop_unpartitioned_table(&mut self, table: PartitionedTable) -> DalResult<()> {
+        let query = format!("DROP TABLE IF EXISTS {}", table.unpartitioned_table_name());
+        sqlx::query(&query)
+            .instrument("drop_unpartitioned_table")
+            .with_arg("table", &table.name())
+            .execute(self.storage)
+            .await?;
+        Ok(())
+    }
+
+    /// Returns the set of tables that are partitioned.
+    pub async fn get_partitioned_tables(&mut self) -> DalResult<HashSet<PartitionedTable>> {
+        let mut tables = HashSet::new();
+        for table in PartitionedTable::ALL {
+            if self.is_partitioned_table(table.name()).await? {
+                tables.insert(table);
+            }
+        }
+        Ok(tables)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{tx::IncludedTxLocation, Address, ProtocolVersion};
+    use zksync_utils::u256_to_h256;
+
+    use super::*;
+    use crate::{
+        tests::{create_l2_block_header, mock_vm_event},
+        ConnectionPool,
+    };
+
+    async fn insert_l2_block_with_event(conn: &mut Connection<'_, Core>, number: u32) {
+        conn.blocks_dal()
+            .insert_l2_block(&create_l2_block_header(number))
+            .await
+            .unwrap();
+        let location = IncludedTxLocation {
+            tx_hash: u256_to_h256(number.into()),
+            tx_index_in_l2_block: 0,
+            tx_initiator_address: Address::default(),
+        };
+        let event = mock_vm_event(0);
+        conn.events_dal()
+            .save_events(L2BlockNumber(number), &[(location, vec![&event])])
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn parsing_partition_names() {
+        let table = PartitionedTable::StorageLogs;
+        let name = table.partition_name(&(100..200));
+        assert_eq!(name, "storage_logs_l2_blocks_100_200");
+        assert_eq!(table.parse_partition_name(&name), Some(100..200));
+        assert_eq!(PartitionedTable::Events.parse_partition_name(&name), None);
+        assert_eq!(table.parse_partition_name("storage_logs_default"), None);
+    }
+
+    #[tokio::test]
+    async fn converting_events_table() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(&ProtocolVersion::default())
+            .await
+            .unwrap();
+        for number in 1..5 {
+            insert_l2_block_with_event(&mut conn, number).await;
+        }
+
+        let table = PartitionedTable::Events;
+        let mut dal = conn.partitioning_dal();
+        assert_eq!(
+            dal.get_status(table).await.unwrap(),
+            PartitioningStatus::Unpartitioned
+        );
+        dal.create_shadow_table(table, L2BlockNumber(4), 2)
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.get_status(table).await.unwrap(),
+            PartitioningStatus::Converting { copied_up_to: None }
+        );
+        let copied_rows = dal
+            .copy_chunk(table, L2BlockNumber(0)..=L2BlockNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(copied_rows, 2);
+        // Re-copying a chunk is a no-op.
+        let copied_rows = dal
+            .copy_chunk(table, L2BlockNumber(0)..=L2BlockNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(copied_rows, 0);
+        assert_eq!(
+            dal.get_status(table).await.unwrap(),
+            PartitioningStatus::Converting {
+                copied_up_to: Some(L2BlockNumber(2))
+            }
+        );
+
+        drop(dal);
+        insert_l2_block_with_event(&mut conn, 5).await;
+        let copied_rows = conn
+            .partitioning_dal()
+            .swap_tables(table, L2BlockNumber(2), 2)
+            .await
+            .unwrap();
+        assert_eq!(copied_rows, 3);
+        assert_eq!(
+            conn.partitioning_dal().get_status(table).await.unwrap(),
+            PartitioningStatus::Partitioned
+        );
+        let partitions = conn
+            .partitioning_dal()
+            .get_partitions(table, table.name())
+            .await
+            .unwrap();
+        assert_eq!(partitions, [0..2, 2..4, 4..6, 6..8]);
+
+        let created_count = conn
+            .partitioning_dal()
+            .ensure_partitions(table, L2BlockNumber(10), 2)
+            .await
+            .unwrap();
+        assert_eq!(created_count, 2);
+
+        // Check that the partitioned table is usable by the DAL.
+        insert_l2_block_with_event(&mut conn, 6).await;
+        let logs = conn
+            .events_web3_dal()
+            .get_all_logs(L2BlockNumber(0))
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 6);
+
+        conn.partitioning_dal()
+            .drop_unpartitioned_table(table)
+            .await
+            .unwrap();
+    }
+}
@@ -25,9 +25,12 @@ enum ProofGenerationJobStatus {
 }
 
 impl ProofGenerationDal<'_, '_> {
+    /// Picks the next L1 batch to be proven. `prover_id` is recorded as the owner of the job
+    /// (`None` if the prover is not authenticated).
     pub async fn get_next_block_to_be_proven(
         &mut self,
         processing_timeout: Duration,
+        prover_id: Option<&str>,
     ) -> Option<L1BatchNumber> {
         let processing_timeout = pg_interval_from_duration(processing_timeout);
         let result: Option<L1BatchNumber> = sqlx::query!(
@@ -36,7 +39,8 @@ impl ProofGenerationDal<'_, '_> {
             SET
                 status = 'picked_by_prover',
                 updated_at = NOW(),
-                prover_taken_at = NOW()
+                prover_taken_at = NOW(),
+                picked_by = $2
             WHERE
                 l1_batch_number = (
                     SELECT
@@ -60,6 +64,7 @@ impl ProofGenerationDal<'_, '_> {
                 proof_generation_details.l1_batch_number
             "#,
             &processing_timeout,
+            prover_id,
         )
         .fetch_optional(self.storage.conn())
         .await
@@ -69,6 +74,28 @@ impl ProofGenerationDal<'_, '_> {
         result
     }
 
+    /// Returns the ID of the prover that has picked the specified L1 batch, or `None` if the batch was picked
+    /// by an unauthenticated prover or wasn't picked at all. Returns [`SqlxError::RowNotFound`] if the batch is missing.
+    pub async fn get_prover_id_for_batch(
+        &mut self,
+        block_number: L1BatchNumber,
+    ) -> Result<Option<String>, SqlxError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                picked_by
+            FROM
+                proof_generation_details
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(block_number.0)
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(row.picked_by)
+    }
+
     pub async fn save_proof_artifacts_metadata(
         &mut self,
         block_number: L1BatchNumber,
@@ -10,7 +10,7 @@ use zksync_db_connection::{
 use zksync_types::{
     protocol_upgrade::{ProtocolUpgradeTx, ProtocolVersion},
     protocol_version::{L1VerifierConfig, ProtocolSemanticVersion, VerifierParams, VersionPatch},
-    ProtocolVersionId, H256,
+    Address, ProtocolVersionId, H256,
 };
 
 use crate::{
@@ -182,6 +182,31 @@ impl ProtocolVersionsDal<'_, '_> {
         db_transaction.commit().await
     }
 
+    /// Records the address of the L1 verifier contract set by the upgrade to the specified protocol version.
+    pub async fn save_verifier_address(
+        &mut self,
+        version_id: ProtocolVersionId,
+        verifier_address: Address,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE protocol_versions
+            SET
+                verifier_address = $2
+            WHERE
+                id = $1
+            "#,
+            version_id as i32,
+            verifier_address.as_bytes()
+        )
+        .instrument("save_verifier_address")
+        .with_arg("version_id", &version_id)
+        .with_arg("verifier_address", &verifier_address)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
     pub async fn protocol_version_id_by_timestamp(
         &mut self,
         current_timestamp: u64,
@@ -207,9 +232,12 @@ impl ProtocolVersionsDal<'_, '_> {
         ProtocolVersionId::try_from(row.id as u16).map_err(|err| sqlx::Error::Decode(err.into()))
     }
 
+    /// Loads base system contracts for the specified protocol version. The EVM emulator is chain-wide rather than
+    /// version-specific, so its hash (if the emulator is enabled) must be provided by the caller.
     pub async fn load_base_system_contracts_by_version_id(
         &mut self,
         version_id: u16,
+        evm_emulator_hash: Option<H256>,
     ) -> anyhow::Result<Option<BaseSystemContracts>> {
         let row = sqlx::query!(
             r#"
@@ -234,6 +262,7 @@ impl ProtocolVersionsDal<'_, '_> {
                 .get_base_system_contracts(
                     H256::from_slice(&row.bootloader_code_hash),
                     H256::from_slice(&row.default_account_code_hash),
+                    evm_emulator_hash,
                 )
                 .await?;
             Some(contracts)
@@ -403,6 +432,34 @@ impl ProtocolVersionsDal<'_, '_> {
         .await
     }
 
+    /// Returns the earliest protocol version newer than `version_id` together with its activation timestamp.
+    pub async fn get_next_protocol_version(
+        &mut self,
+        version_id: ProtocolVersionId,
+    ) -> DalResult<Option<(ProtocolVersionId, u64)>> {
+        sqlx::query!(
+            r#"
+            SELECT
+                id,
+                timestamp
+            FROM
+                protocol_versions
+            WHERE
+                id > $1
+            ORDER BY
+                id
+            LIMIT
+                1
+            "#,
+            version_id as i32
+        )
+        .try_map(|row| parse_protocol_version(row.id).map(|id| (id, row.timestamp as u64)))
+        .instrument("get_next_protocol_version")
+        .with_arg("version_id", &version_id)
+        .fetch_optional(self.storage)
+        .await
+    }
+
     pub async fn last_used_version_id(&mut self) -> Option<ProtocolVersionId> {
         let id = sqlx::query!(
             r#"
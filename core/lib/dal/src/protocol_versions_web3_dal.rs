@@ -1,5 +1,11 @@
 use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
-use zksync_types::api::ProtocolVersion;
+use zksync_types::{
+    api::{ProtocolVersion, ProtocolVersionHistoryEntry, VerificationKeysHashes},
+    protocol_version::{
+        L1VerifierConfig, ProtocolSemanticVersion, ProtocolVersionId, VerifierParams, VersionPatch,
+    },
+    Address, L1BatchNumber, L2BlockNumber, H256,
+};
 
 use crate::{models::storage_protocol_version::StorageApiProtocolVersion, Core, CoreDal};
 
@@ -37,6 +43,135 @@ impl ProtocolVersionsWeb3Dal<'_, '_> {
         Ok(storage_protocol_version.map(ProtocolVersion::from))
     }
 
+    /// Returns verification key hashes for all patches of the specified minor protocol version,
+    /// or for all known protocol versions if `version_id` is not specified.
+    pub async fn get_verification_keys_hashes(
+        &mut self,
+        version_id: Option<u16>,
+    ) -> DalResult<Vec<VerificationKeysHashes>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                minor,
+                patch,
+                recursion_scheduler_level_vk_hash,
+                recursion_node_level_vk_hash,
+                recursion_leaf_level_vk_hash,
+                recursion_circuits_set_vks_hash
+            FROM
+                protocol_patches
+            WHERE
+                $1::INT IS NULL
+                OR minor = $1
+            ORDER BY
+                minor,
+                patch
+            "#,
+            version_id.map(i32::from)
+        )
+        .instrument("get_verification_keys_hashes")
+        .with_arg("version_id", &version_id)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| VerificationKeysHashes {
+                protocol_version: ProtocolSemanticVersion {
+                    minor: ProtocolVersionId::try_from(row.minor as u16).unwrap(),
+                    patch: VersionPatch(row.patch as u32),
+                },
+                verification_keys_hashes: L1VerifierConfig {
+                    params: VerifierParams {
+                        recursion_node_level_vk_hash: H256::from_slice(
+                            &row.recursion_node_level_vk_hash,
+                        ),
+                        recursion_leaf_level_vk_hash: H256::from_slice(
+                            &row.recursion_leaf_level_vk_hash,
+                        ),
+                        recursion_circuits_set_vks_hash: H256::from_slice(
+                            &row.recursion_circuits_set_vks_hash,
+                        ),
+                    },
+                    recursion_scheduler_level_vk_hash: H256::from_slice(
+                        &row.recursion_scheduler_level_vk_hash,
+                    ),
+                },
+            })
+            .collect())
+    }
+
+    /// Returns all known protocol versions ordered by the minor version, together with their activation
+    /// L1 batch and L2 block. Since the verifier address is only recorded for upgrades changing it,
+    /// it is carried over from the previous versions.
+    pub async fn get_protocol_version_history(
+        &mut self,
+    ) -> DalResult<Vec<ProtocolVersionHistoryEntry>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                protocol_versions.id AS "minor!",
+                protocol_versions.timestamp,
+                protocol_versions.bootloader_code_hash,
+                protocol_versions.default_account_code_hash,
+                protocol_versions.upgrade_tx_hash,
+                protocol_versions.verifier_address,
+                activation.l1_batch_number AS "activation_l1_batch_number?",
+                (
+                    SELECT
+                        MIN(miniblocks.number)
+                    FROM
+                        miniblocks
+                    WHERE
+                        miniblocks.l1_batch_number = activation.l1_batch_number
+                ) AS activation_l2_block_number
+            FROM
+                protocol_versions
+                LEFT JOIN LATERAL (
+                    SELECT
+                        number AS l1_batch_number
+                    FROM
+                        l1_batches
+                    WHERE
+                        l1_batches.protocol_version = protocol_versions.id
+                    ORDER BY
+                        number
+                    LIMIT
+                        1
+                ) activation ON TRUE
+            ORDER BY
+                protocol_versions.id
+            "#
+        )
+        .instrument("get_protocol_version_history")
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut verifier_address = None;
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                if let Some(address) = &row.verifier_address {
+                    verifier_address = Some(Address::from_slice(address));
+                }
+                ProtocolVersionHistoryEntry {
+                    minor_version: row.minor as u16,
+                    timestamp: row.timestamp as u64,
+                    activation_l1_batch: row
+                        .activation_l1_batch_number
+                        .map(|number| L1BatchNumber(number as u32)),
+                    activation_l2_block: row
+                        .activation_l2_block_number
+                        .map(|number| L2BlockNumber(number as u32)),
+                    bootloader_code_hash: H256::from_slice(&row.bootloader_code_hash),
+                    default_account_code_hash: H256::from_slice(&row.default_account_code_hash),
+                    verifier_address,
+                    l2_system_upgrade_tx_hash: row.upgrade_tx_hash.as_deref().map(H256::from_slice),
+                }
+            })
+            .collect())
+    }
+
     pub async fn get_latest_protocol_version(&mut self) -> DalResult<ProtocolVersion> {
         let latest_version = self
             .storage
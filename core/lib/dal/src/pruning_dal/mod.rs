@@ -31,6 +31,7 @@ pub struct HardPruningStats {
     pub deleted_storage_logs_from_pruned_batches: u64,
     pub deleted_events: u64,
     pub deleted_call_traces: u64,
+    pub deleted_internal_transfers: u64,
     pub deleted_l2_to_l1_logs: u64,
 }
 
@@ -171,6 +172,9 @@ impl PruningDal<'_, '_> {
             let deleted_call_traces = self
                 .delete_call_traces(first_l2_block_to_prune..=last_l2_block_to_prune)
                 .await?;
+            let deleted_internal_transfers = self
+                .delete_internal_transfers(first_l2_block_to_prune..=last_l2_block_to_prune)
+                .await?;
             self.clear_transaction_fields(first_l2_block_to_prune..=last_l2_block_to_prune)
                 .await?;
 
@@ -194,6 +198,7 @@ impl PruningDal<'_, '_> {
                 deleted_events,
                 deleted_l2_to_l1_logs,
                 deleted_call_traces,
+                deleted_internal_transfers,
                 deleted_storage_logs_from_past_batches,
                 deleted_storage_logs_from_pruned_batches,
             }
@@ -279,6 +284,29 @@ impl PruningDal<'_, '_> {
         Ok(execution_result.rows_affected())
     }
 
+    // Internal transfers are returned via `TransactionsWeb3Dal::get_internal_transfers()`; similarly to call traces,
+    // an empty list is returned for transactions in pruned L2 blocks.
+    async fn delete_internal_transfers(
+        &mut self,
+        l2_blocks_to_prune: ops::RangeInclusive<L2BlockNumber>,
+    ) -> DalResult<u64> {
+        let execution_result = sqlx::query!(
+            r#"
+            DELETE FROM internal_transfers
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            "#,
+            i64::from(l2_blocks_to_prune.start().0),
+            i64::from(l2_blocks_to_prune.end().0)
+        )
+        .instrument("hard_prune_batches_range#delete_internal_transfers")
+        .with_arg("l2_blocks_to_prune", &l2_blocks_to_prune)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        Ok(execution_result.rows_affected())
+    }
+
     // The pruned fields are accessed as follows:
     //
     // - `input`: is a part of `StorageTransaction`, read via `TransactionsDal` (`get_l2_blocks_to_reexecute`,
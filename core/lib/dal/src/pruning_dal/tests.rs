@@ -18,6 +18,7 @@ use crate::{
         create_l2_block_header, mock_execution_result, mock_l2_to_l1_log, mock_l2_transaction,
         mock_vm_event,
     },
+    transactions_dal::CallTraceFormat,
     ConnectionPool, Core, CoreDal,
 };
 
@@ -487,6 +488,7 @@ async fn transactions_are_handled_correctly_after_pruning() {
             &[mock_execution_result(tx.clone())],
             1.into(),
             ProtocolVersionId::latest(),
+            CallTraceFormat::Legacy,
             false,
         )
         .await
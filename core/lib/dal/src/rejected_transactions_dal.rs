@@ -0,0 +1,175 @@
+use chrono::{DateTime, Utc};
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+use zksync_types::{api::RejectedTransaction, Address, H256};
+
+use crate::Core;
+
+/// DAL for the journal of transactions rejected by the API server on submission.
+#[derive(Debug)]
+pub struct RejectedTransactionsDal<'c, 'a> {
+    pub(crate) storage: &'c mut Connection<'a, Core>,
+}
+
+impl RejectedTransactionsDal<'_, '_> {
+    /// Records a rejected transaction. If the transaction with the same hash is already recorded, the record is overwritten.
+    pub async fn insert_rejected_transaction(&mut self, tx: &RejectedTransaction) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                rejected_transactions (
+                    tx_hash,
+                    initiator_address,
+                    nonce,
+                    error_code,
+                    reason,
+                    rejected_at
+                )
+            VALUES
+                ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (tx_hash) DO
+            UPDATE
+            SET
+                initiator_address = excluded.initiator_address,
+                nonce = excluded.nonce,
+                error_code = excluded.error_code,
+                reason = excluded.reason,
+                rejected_at = excluded.rejected_at
+            "#,
+            tx.hash.as_bytes(),
+            tx.initiator_address.as_bytes(),
+            tx.nonce.low_u64() as i64,
+            &tx.error_code,
+            &tx.reason,
+            tx.rejected_at.naive_utc()
+        )
+        .instrument("insert_rejected_transaction")
+        .with_arg("tx.hash", &tx.hash)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_rejected_transaction(
+        &mut self,
+        tx_hash: H256,
+    ) -> DalResult<Option<RejectedTransaction>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                tx_hash,
+                initiator_address,
+                nonce,
+                error_code,
+                reason,
+                rejected_at
+            FROM
+                rejected_transactions
+            WHERE
+                tx_hash = $1
+            "#,
+            tx_hash.as_bytes()
+        )
+        .instrument("get_rejected_transaction")
+        .with_arg("tx_hash", &tx_hash)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| RejectedTransaction {
+            hash: H256::from_slice(&row.tx_hash),
+            initiator_address: Address::from_slice(&row.initiator_address),
+            nonce: (row.nonce as u64).into(),
+            error_code: row.error_code,
+            reason: row.reason,
+            rejected_at: DateTime::<Utc>::from_naive_utc_and_offset(row.rejected_at, Utc),
+        }))
+    }
+
+    /// Removes transactions rejected before the specified timestamp. Returns the number of removed transactions.
+    pub async fn prune_rejected_transactions(
+        &mut self,
+        rejected_before: DateTime<Utc>,
+    ) -> DalResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM rejected_transactions
+            WHERE
+                rejected_at < $1
+            "#,
+            rejected_before.naive_utc()
+        )
+        .instrument("prune_rejected_transactions")
+        .with_arg("rejected_before", &rejected_before)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    fn rejected_tx(hash: H256, rejected_at: DateTime<Utc>) -> RejectedTransaction {
+        RejectedTransaction {
+            hash,
+            initiator_address: Address::repeat_byte(1),
+            nonce: 3.into(),
+            error_code: "max-fee-per-gas-too-low".to_owned(),
+            reason: "max fee per gas less than block base fee".to_owned(),
+            rejected_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn recording_and_pruning_rejected_transactions() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let now = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let old_tx = rejected_tx(H256::repeat_byte(1), now - Duration::hours(2));
+        let new_tx = rejected_tx(H256::repeat_byte(2), now);
+        for tx in [&old_tx, &new_tx] {
+            conn.rejected_transactions_dal()
+                .insert_rejected_transaction(tx)
+                .await
+                .unwrap();
+        }
+
+        let loaded_tx = conn
+            .rejected_transactions_dal()
+            .get_rejected_transaction(old_tx.hash)
+            .await
+            .unwrap();
+        assert_eq!(loaded_tx, Some(old_tx.clone()));
+
+        let updated_tx = RejectedTransaction {
+            reason: "updated".to_owned(),
+            ..new_tx.clone()
+        };
+        conn.rejected_transactions_dal()
+            .insert_rejected_transaction(&updated_tx)
+            .await
+            .unwrap();
+        let loaded_tx = conn
+            .rejected_transactions_dal()
+            .get_rejected_transaction(new_tx.hash)
+            .await
+            .unwrap();
+        assert_eq!(loaded_tx, Some(updated_tx));
+
+        let pruned_count = conn
+            .rejected_transactions_dal()
+            .prune_rejected_transactions(now - Duration::hours(1))
+            .await
+            .unwrap();
+        assert_eq!(pruned_count, 1);
+        let loaded_tx = conn
+            .rejected_transactions_dal()
+            .get_rejected_transaction(old_tx.hash)
+            .await
+            .unwrap();
+        assert_eq!(loaded_tx, None);
+    }
+}
@@ -214,6 +214,67 @@ impl SnapshotsDal<'_, '_> {
         .fetch_all(self.storage)
         .await
     }
+
+    /// Records an on-demand snapshot creation request. Returns the ID of the recorded request.
+    pub async fn request_snapshot(&mut self, requested_by: &str) -> DalResult<u64> {
+        let row = sqlx::query!(
+            r#"
+            INSERT INTO
+                snapshot_requests (requested_by, created_at)
+            VALUES
+                ($1, NOW())
+            RETURNING
+                id
+            "#,
+            requested_by
+        )
+        .instrument("request_snapshot")
+        .with_arg("requested_by", &requested_by)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.id as u64)
+    }
+
+    /// Returns the ID of the latest unprocessed snapshot creation request, if any.
+    pub async fn get_latest_pending_snapshot_request(&mut self) -> DalResult<Option<u64>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(id) AS "id"
+            FROM
+                snapshot_requests
+            WHERE
+                processed_at IS NULL
+            "#
+        )
+        .instrument("get_latest_pending_snapshot_request")
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.id.map(|id| id as u64))
+    }
+
+    /// Marks all snapshot creation requests with IDs up to and including `last_request_id` as processed.
+    pub async fn mark_snapshot_requests_as_processed(
+        &mut self,
+        last_request_id: u64,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE snapshot_requests
+            SET
+                processed_at = NOW()
+            WHERE
+                id <= $1
+                AND processed_at IS NULL
+            "#,
+            last_request_id as i64
+        )
+        .instrument("mark_snapshot_requests_as_processed")
+        .with_arg("last_request_id", &last_request_id)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -379,4 +440,38 @@ mod tests {
             ]
         );
     }
+
+    #[tokio::test]
+    async fn processing_snapshot_requests() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.snapshots_dal();
+        assert_eq!(
+            dal.get_latest_pending_snapshot_request().await.unwrap(),
+            None
+        );
+
+        let first_id = dal.request_snapshot("alice").await.unwrap();
+        let second_id = dal.request_snapshot("bob").await.unwrap();
+        assert!(second_id > first_id);
+        assert_eq!(
+            dal.get_latest_pending_snapshot_request().await.unwrap(),
+            Some(second_id)
+        );
+
+        dal.mark_snapshot_requests_as_processed(first_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.get_latest_pending_snapshot_request().await.unwrap(),
+            Some(second_id)
+        );
+        dal.mark_snapshot_requests_as_processed(second_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.get_latest_pending_snapshot_request().await.unwrap(),
+            None
+        );
+    }
 }
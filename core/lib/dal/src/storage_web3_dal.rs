@@ -228,17 +228,20 @@ impl StorageWeb3Dal<'_, '_> {
         Ok(l1_batch_number)
     }
 
+    /// Returns the bytecode hash and the bytecode of the contract deployed at `address`.
+    ///
     /// This method doesn't check if block with number equals to `block_number`
     /// is present in the database. For such blocks `None` will be returned.
     pub async fn get_contract_code_unchecked(
         &mut self,
         address: Address,
         block_number: L2BlockNumber,
-    ) -> DalResult<Option<Vec<u8>>> {
+    ) -> DalResult<Option<(H256, Vec<u8>)>> {
         let hashed_key = get_code_key(&address).hashed_key();
         let row = sqlx::query!(
             r#"
             SELECT
+                bytecode_hash,
                 bytecode
             FROM
                 (
@@ -268,7 +271,7 @@ impl StorageWeb3Dal<'_, '_> {
         .with_arg("block_number", &block_number)
         .fetch_optional(self.storage)
         .await?;
-        Ok(row.map(|row| row.bytecode))
+        Ok(row.map(|row| (H256::from_slice(&row.bytecode_hash), row.bytecode)))
     }
 
     /// Given bytecode hash, returns bytecode and L2 block number at which it was inserted.
@@ -103,6 +103,7 @@ mod tests {
             create_l2_block_header, create_snapshot_recovery, mock_execution_result,
             mock_l2_transaction,
         },
+        transactions_dal::CallTraceFormat,
         ConnectionPool, Core,
     };
 
@@ -163,6 +164,7 @@ mod tests {
                 &[mock_execution_result(tx.clone())],
                 1.into(),
                 ProtocolVersionId::latest(),
+                CallTraceFormat::Legacy,
                 false,
             )
             .await
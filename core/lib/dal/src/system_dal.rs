@@ -1,4 +1,7 @@
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
 
 use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
 
@@ -12,6 +15,60 @@ pub(crate) struct TableSize {
     pub total_size: u64,
 }
 
+/// Migrations embedded into the binary. Used to check that the migrations applied to the database match the code.
+static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("./migrations");
+
+/// Status of the database migrations applied by `sqlx`, compared to the migrations known to this binary.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationsStatus {
+    /// Number of successfully applied migrations.
+    pub applied_count: usize,
+    /// Version of the latest successfully applied migration.
+    pub latest_version: Option<i64>,
+    /// Versions of migrations that were started, but have not completed successfully.
+    pub failed_versions: Vec<i64>,
+    /// Versions of migrations known to this binary that are not applied to the database.
+    pub missing_versions: Vec<i64>,
+    /// Versions of applied migrations unknown to this binary, e.g. because they were applied by a newer server version.
+    pub unknown_versions: Vec<i64>,
+    /// Versions of applied migrations with a checksum differing from the migration known to this binary,
+    /// i.e., migrations that were edited after being applied.
+    pub checksum_mismatches: Vec<i64>,
+}
+
+impl MigrationsStatus {
+    fn new<'a>(
+        applied: impl Iterator<Item = (i64, bool, &'a [u8])>,
+        expected: impl Iterator<Item = (i64, &'a [u8])>,
+    ) -> Self {
+        let mut expected: HashMap<_, _> = expected.collect();
+        let mut applied_versions = HashSet::new();
+        let mut status = Self::default();
+        for (version, success, checksum) in applied {
+            applied_versions.insert(version);
+            if !success {
+                status.failed_versions.push(version);
+                continue;
+            }
+            status.applied_count += 1;
+            status.latest_version = Some(version);
+            match expected.remove(&version) {
+                Some(expected_checksum) if expected_checksum != checksum => {
+                    status.checksum_mismatches.push(version);
+                }
+                Some(_) => { /* The migration matches the expected one */ }
+                None => status.unknown_versions.push(version),
+            }
+        }
+        status.missing_versions = expected
+            .into_keys()
+            .filter(|version| !applied_versions.contains(version))
+            .collect();
+        status.missing_versions.sort_unstable();
+        status
+    }
+}
+
 #[derive(Debug)]
 pub struct SystemDal<'a, 'c> {
     pub(crate) storage: &'a mut Connection<'c, Core>,
@@ -44,6 +101,35 @@ impl SystemDal<'_, '_> {
         })
     }
 
+    /// Returns the status of migrations recorded in the `sqlx` migrations table compared to migrations
+    /// embedded into this binary. Errors if the table does not exist, i.e., if migrations were never run on the database.
+    pub async fn get_migrations_status(&mut self) -> DalResult<MigrationsStatus> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                version,
+                success,
+                checksum
+            FROM
+                _sqlx_migrations
+            ORDER BY
+                version
+            "#
+        )
+        .instrument("get_migrations_status")
+        .fetch_all(self.storage)
+        .await?;
+
+        let applied = rows
+            .iter()
+            .map(|row| (row.version, row.success, row.checksum.as_slice()));
+        let expected = MIGRATOR
+            .iter()
+            .filter(|migration| !migration.migration_type.is_down_migration())
+            .map(|migration| (migration.version, &*migration.checksum));
+        Ok(MigrationsStatus::new(applied, expected))
+    }
+
     pub(crate) async fn get_table_sizes(&mut self) -> DalResult<HashMap<String, TableSize>> {
         let rows = sqlx::query!(
             r#"
@@ -79,3 +165,55 @@ impl SystemDal<'_, '_> {
         Ok(table_sizes.collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use zksync_db_connection::connection_pool::ConnectionPool;
+
+    use super::*;
+    use crate::CoreDal;
+
+    #[test]
+    fn comparing_migrations() {
+        let expected = [(1, b"1".as_slice()), (2, b"2"), (3, b"3"), (4, b"4")];
+        let applied = [
+            (1, true, b"1".as_slice()),
+            (2, true, b"edited"),
+            (3, false, b"3"),
+            (5, true, b"5"),
+        ];
+        let status = MigrationsStatus::new(applied.into_iter(), expected.into_iter());
+        assert_eq!(
+            status,
+            MigrationsStatus {
+                applied_count: 3,
+                latest_version: Some(5),
+                failed_versions: vec![3],
+                missing_versions: vec![4],
+                unknown_versions: vec![5],
+                checksum_mismatches: vec![2],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn getting_migrations_status() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let status = conn.system_dal().get_migrations_status().await.unwrap();
+
+        let expected_count = MIGRATOR
+            .iter()
+            .filter(|migration| !migration.migration_type.is_down_migration())
+            .count();
+        assert_eq!(status.applied_count, expected_count);
+        assert_eq!(
+            status.latest_version,
+            MIGRATOR.iter().map(|migration| migration.version).max()
+        );
+        assert!(status.failed_versions.is_empty(), "{status:?}");
+        assert!(status.missing_versions.is_empty(), "{status:?}");
+        assert!(status.unknown_versions.is_empty(), "{status:?}");
+        assert!(status.checksum_mismatches.is_empty(), "{status:?}");
+    }
+}
@@ -1,80 +1,32 @@
 use std::time::Duration;
 
-use zksync_contracts::BaseSystemContractsHashes;
 use zksync_db_connection::connection_pool::ConnectionPool;
 use zksync_types::{
-    block::{L2BlockHasher, L2BlockHeader},
-    fee::{Fee, TransactionExecutionMetrics},
-    fee_model::BatchFeeInput,
+    fee::TransactionExecutionMetrics,
     helpers::unix_timestamp_ms,
     l1::{L1Tx, OpProcessingType, PriorityQueueType},
-    l2::L2Tx,
     l2_to_l1_log::{L2ToL1Log, UserL2ToL1Log},
     protocol_upgrade::{ProtocolUpgradeTx, ProtocolUpgradeTxCommonData},
     snapshots::SnapshotRecoveryStatus,
-    tx::{tx_execution_info::TxExecutionStatus, ExecutionMetrics, TransactionExecutionResult},
-    Address, Execute, K256PrivateKey, L1BatchNumber, L1BlockNumber, L1TxCommonData, L2BlockNumber,
-    L2ChainId, PriorityOpId, ProtocolVersion, ProtocolVersionId, VmEvent, H160, H256, U256,
+    Address, Execute, L1BatchNumber, L1BlockNumber, L1TxCommonData, L2BlockNumber, PriorityOpId,
+    ProtocolVersion, ProtocolVersionId, VmEvent, H160, H256, U256,
 };
 
+pub(crate) use crate::fixtures::{
+    create_l2_block_header, mock_execution_result, mock_l2_transaction,
+};
 use crate::{
     blocks_dal::BlocksDal,
     protocol_versions_dal::ProtocolVersionsDal,
-    transactions_dal::{L2TxSubmissionResult, TransactionsDal},
+    transactions_dal::{CallTraceFormat, L2TxSubmissionResult, TransactionsDal},
     transactions_web3_dal::TransactionsWeb3Dal,
     Core,
 };
 
-const DEFAULT_GAS_PER_PUBDATA: u32 = 100;
-
 fn mock_tx_execution_metrics() -> TransactionExecutionMetrics {
     TransactionExecutionMetrics::default()
 }
 
-pub(crate) fn create_l2_block_header(number: u32) -> L2BlockHeader {
-    let number = L2BlockNumber(number);
-    let protocol_version = ProtocolVersionId::default();
-    L2BlockHeader {
-        number,
-        timestamp: number.0.into(),
-        hash: L2BlockHasher::new(number, 0, H256::zero()).finalize(protocol_version),
-        l1_tx_count: 0,
-        l2_tx_count: 0,
-        fee_account_address: Address::default(),
-        gas_per_pubdata_limit: 100,
-        base_fee_per_gas: 100,
-        batch_fee_input: BatchFeeInput::l1_pegged(100, 100),
-        base_system_contracts_hashes: BaseSystemContractsHashes::default(),
-        protocol_version: Some(protocol_version),
-        virtual_blocks: 1,
-        gas_limit: 0,
-    }
-}
-
-pub(crate) fn mock_l2_transaction() -> L2Tx {
-    let fee = Fee {
-        gas_limit: U256::from(1_000_000u32),
-        max_fee_per_gas: U256::from(250_000_000u32),
-        max_priority_fee_per_gas: U256::zero(),
-        gas_per_pubdata_limit: U256::from(DEFAULT_GAS_PER_PUBDATA),
-    };
-    let mut l2_tx = L2Tx::new_signed(
-        Address::random(),
-        vec![],
-        zksync_types::Nonce(0),
-        fee,
-        Default::default(),
-        L2ChainId::from(270),
-        &K256PrivateKey::random(),
-        None,
-        Default::default(),
-    )
-    .unwrap();
-
-    l2_tx.set_input(H256::random().0.to_vec(), H256::random());
-    l2_tx
-}
-
 pub(crate) fn mock_l1_execute() -> L1Tx {
     let serial_id = 1;
     let priority_op_data = L1TxCommonData {
@@ -136,20 +88,6 @@ pub(crate) fn mock_protocol_upgrade_transaction() -> ProtocolUpgradeTx {
     }
 }
 
-pub(crate) fn mock_execution_result(transaction: L2Tx) -> TransactionExecutionResult {
-    TransactionExecutionResult {
-        hash: transaction.hash(),
-        transaction: transaction.into(),
-        execution_info: ExecutionMetrics::default(),
-        execution_status: TxExecutionStatus::Success,
-        refunded_gas: 0,
-        operator_suggested_refund: 0,
-        compressed_bytecodes: vec![],
-        call_traces: vec![],
-        revert_reason: None,
-    }
-}
-
 pub(crate) fn create_snapshot_recovery() -> SnapshotRecoveryStatus {
     SnapshotRecoveryStatus {
         l1_batch_number: L1BatchNumber(23),
@@ -234,6 +172,129 @@ async fn workflow_with_submit_tx_diff_hashes() {
     assert_eq!(result, L2TxSubmissionResult::Replaced);
 }
 
+#[tokio::test]
+async fn replacing_tx_with_fee_bump() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let storage = &mut connection_pool.connection().await.unwrap();
+    let mut transactions_dal = TransactionsDal { storage };
+
+    let tx = mock_l2_transaction();
+    let result = transactions_dal
+        .insert_transaction_l2_with_fee_bump(&tx, mock_tx_execution_metrics(), 10)
+        .await
+        .unwrap();
+    assert_eq!(result, L2TxSubmissionResult::Added);
+
+    let replacement_tx = |max_fee_per_gas: u32| {
+        let mut new_tx = mock_l2_transaction();
+        new_tx.common_data.nonce = tx.common_data.nonce;
+        new_tx.common_data.initiator_address = tx.common_data.initiator_address;
+        new_tx.common_data.fee.max_fee_per_gas = max_fee_per_gas.into();
+        new_tx
+    };
+
+    // The fee is bumped by less than 10%.
+    let result = transactions_dal
+        .insert_transaction_l2_with_fee_bump(
+            &replacement_tx(270_000_000),
+            mock_tx_execution_metrics(),
+            10,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result, L2TxSubmissionResult::ReplacementUnderpriced);
+
+    let result = transactions_dal
+        .insert_transaction_l2_with_fee_bump(
+            &replacement_tx(275_000_000),
+            mock_tx_execution_metrics(),
+            10,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result, L2TxSubmissionResult::Replaced);
+}
+
+#[tokio::test]
+async fn evicting_pending_txs() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let storage = &mut connection_pool.connection().await.unwrap();
+    let mut transactions_dal = TransactionsDal { storage };
+
+    let first_sender_tx = |nonce: u32, max_fee_per_gas: u32| {
+        let mut tx = mock_l2_transaction();
+        tx.common_data.initiator_address = Address::repeat_byte(1);
+        tx.common_data.nonce = zksync_types::Nonce(nonce);
+        tx.common_data.fee.max_fee_per_gas = max_fee_per_gas.into();
+        tx
+    };
+    let mut second_sender_tx = mock_l2_transaction();
+    second_sender_tx.common_data.fee.max_fee_per_gas = 200_000_000.into();
+    let txs = [
+        first_sender_tx(0, 100_000_000),
+        first_sender_tx(1, 300_000_000),
+        second_sender_tx,
+    ];
+    for tx in &txs {
+        transactions_dal
+            .insert_transaction_l2(tx, mock_tx_execution_metrics())
+            .await
+            .unwrap();
+    }
+
+    let size = transactions_dal.get_pending_l2_txs_size().await.unwrap();
+    assert_eq!(size.count, 3);
+    assert_eq!(size.size_bytes, 3 * 32);
+    let count = transactions_dal
+        .get_pending_l2_txs_count_for_sender(Address::repeat_byte(1), zksync_types::Nonce(1))
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+
+    // The first transaction has the lowest fee, but it cannot be evicted since it's followed by another transaction.
+    let candidate = transactions_dal
+        .get_l2_tx_eviction_candidate()
+        .await
+        .unwrap()
+        .expect("no eviction candidate");
+    assert_eq!(candidate.hash, txs[2].hash());
+    assert_eq!(candidate.max_fee_per_gas, 200_000_000.into());
+    assert_eq!(candidate.size_bytes, 32);
+
+    assert!(transactions_dal
+        .evict_pending_l2_tx(candidate.hash)
+        .await
+        .unwrap());
+    assert!(!transactions_dal
+        .evict_pending_l2_tx(candidate.hash)
+        .await
+        .unwrap());
+    let candidate = transactions_dal
+        .get_l2_tx_eviction_candidate()
+        .await
+        .unwrap()
+        .expect("no eviction candidate");
+    assert_eq!(candidate.hash, txs[1].hash());
+
+    // Transactions loaded into the state keeper mempool must not be evicted.
+    let loaded_txs = transactions_dal
+        .sync_mempool(&[], &[], 0, 0, 1000)
+        .await
+        .unwrap();
+    assert_eq!(loaded_txs.len(), 2);
+    let candidate = transactions_dal
+        .get_l2_tx_eviction_candidate()
+        .await
+        .unwrap();
+    assert!(candidate.is_none(), "{candidate:?}");
+    assert!(!transactions_dal
+        .evict_pending_l2_tx(txs[1].hash())
+        .await
+        .unwrap());
+    let size = transactions_dal.get_pending_l2_txs_size().await.unwrap();
+    assert_eq!(size.count, 2);
+}
+
 #[tokio::test]
 async fn remove_stuck_txs() {
     let connection_pool = ConnectionPool::<Core>::test_pool().await;
@@ -299,6 +360,7 @@ async fn remove_stuck_txs() {
             &[mock_execution_result(executed_tx.clone())],
             U256::from(1),
             ProtocolVersionId::latest(),
+            CallTraceFormat::Legacy,
             false,
         )
         .await
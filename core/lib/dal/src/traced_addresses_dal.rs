@@ -0,0 +1,123 @@
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+use zksync_types::Address;
+
+use crate::Core;
+
+/// DAL for addresses for which the state keeper saves call traces of touching transactions
+/// regardless of the global call tracing setting.
+#[derive(Debug)]
+pub struct TracedAddressesDal<'c, 'a> {
+    pub(crate) storage: &'c mut Connection<'a, Core>,
+}
+
+impl TracedAddressesDal<'_, '_> {
+    /// Adds the specified addresses to the traced set. Addresses already present in the set are ignored.
+    pub async fn add_traced_addresses(&mut self, addresses: &[Address]) -> DalResult<()> {
+        let addresses: Vec<_> = addresses.iter().map(Address::as_bytes).collect();
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                traced_addresses (address, created_at)
+            SELECT
+                u.address,
+                NOW()
+            FROM
+                UNNEST($1::bytea[]) AS u (address)
+            ON CONFLICT (address) DO NOTHING
+            "#,
+            &addresses as &[&[u8]]
+        )
+        .instrument("add_traced_addresses")
+        .with_arg("addresses.len", &addresses.len())
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes the specified addresses from the traced set.
+    pub async fn remove_traced_addresses(&mut self, addresses: &[Address]) -> DalResult<()> {
+        let addresses: Vec<_> = addresses.iter().map(Address::as_bytes).collect();
+        sqlx::query!(
+            r#"
+            DELETE FROM traced_addresses
+            WHERE
+                address = ANY ($1)
+            "#,
+            &addresses as &[&[u8]]
+        )
+        .instrument("remove_traced_addresses")
+        .with_arg("addresses.len", &addresses.len())
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns all traced addresses ordered by address.
+    pub async fn get_traced_addresses(&mut self) -> DalResult<Vec<Address>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                address
+            FROM
+                traced_addresses
+            ORDER BY
+                address
+            "#
+        )
+        .instrument("get_traced_addresses")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Address::from_slice(&row.address))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    #[tokio::test]
+    async fn managing_traced_addresses() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let addresses = conn
+            .traced_addresses_dal()
+            .get_traced_addresses()
+            .await
+            .unwrap();
+        assert!(addresses.is_empty());
+
+        let first_address = Address::repeat_byte(1);
+        let second_address = Address::repeat_byte(2);
+        conn.traced_addresses_dal()
+            .add_traced_addresses(&[second_address, first_address])
+            .await
+            .unwrap();
+        // Adding an address twice is a no-op.
+        conn.traced_addresses_dal()
+            .add_traced_addresses(&[first_address])
+            .await
+            .unwrap();
+        let addresses = conn
+            .traced_addresses_dal()
+            .get_traced_addresses()
+            .await
+            .unwrap();
+        assert_eq!(addresses, [first_address, second_address]);
+
+        conn.traced_addresses_dal()
+            .remove_traced_addresses(&[first_address, Address::repeat_byte(3)])
+            .await
+            .unwrap();
+        let addresses = conn
+            .traced_addresses_dal()
+            .get_traced_addresses()
+            .await
+            .unwrap();
+        assert_eq!(addresses, [second_address]);
+    }
+}
@@ -0,0 +1,496 @@
+use std::{collections::HashMap, time::Duration};
+
+use chrono::{DateTime, Utc};
+use zksync_db_connection::{
+    connection::Connection, error::DalResult, instrument::InstrumentExt,
+    utils::pg_interval_from_duration,
+};
+use zksync_types::{
+    api::{TransactionDeadline, TransactionExpiry, TransactionExpiryStatus},
+    L2BlockNumber, H256,
+};
+
+use crate::Core;
+
+/// DAL for client-specified inclusion deadlines of L2 transactions.
+#[derive(Debug)]
+pub struct TransactionDeadlinesDal<'c, 'a> {
+    pub(crate) storage: &'c mut Connection<'a, Core>,
+}
+
+impl TransactionDeadlinesDal<'_, '_> {
+    /// Records an inclusion deadline for a transaction. Returns `false` if the transaction already has a deadline;
+    /// in this case, the existing deadline is not changed.
+    pub async fn insert_deadline(
+        &mut self,
+        tx_hash: H256,
+        deadline: &TransactionDeadline,
+    ) -> DalResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO
+                transaction_deadlines (tx_hash, not_after_l2_block, not_after_timestamp, created_at)
+            VALUES
+                ($1, $2, $3, NOW())
+            ON CONFLICT (tx_hash) DO NOTHING
+            "#,
+            tx_hash.as_bytes(),
+            deadline
+                .not_after_l2_block
+                .map(|number| i64::from(number.0)),
+            deadline
+                .not_after_timestamp
+                .map(|timestamp| timestamp as i64)
+        )
+        .instrument("insert_deadline")
+        .with_arg("tx_hash", &tx_hash)
+        .with_arg("deadline", deadline)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Removes the deadline for a transaction, unless the deadline has already expired.
+    pub async fn remove_deadline(&mut self, tx_hash: H256) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM transaction_deadlines
+            WHERE
+                tx_hash = $1
+                AND expired_at IS NULL
+            "#,
+            tx_hash.as_bytes()
+        )
+        .instrument("remove_deadline")
+        .with_arg("tx_hash", &tx_hash)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns non-expired deadlines for the specified transactions. Transactions without a deadline are not present
+    /// in the returned map.
+    pub async fn get_pending_deadlines(
+        &mut self,
+        tx_hashes: &[H256],
+    ) -> DalResult<HashMap<H256, TransactionDeadline>> {
+        let hashes: Vec<_> = tx_hashes.iter().map(H256::as_bytes).collect();
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                tx_hash,
+                not_after_l2_block,
+                not_after_timestamp
+            FROM
+                transaction_deadlines
+            WHERE
+                tx_hash = ANY ($1)
+                AND expired_at IS NULL
+            "#,
+            &hashes as &[&[u8]]
+        )
+        .instrument("get_pending_deadlines")
+        .with_arg("tx_hashes.len", &tx_hashes.len())
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let deadline = TransactionDeadline {
+                    not_after_l2_block: row
+                        .not_after_l2_block
+                        .map(|number| L2BlockNumber(number as u32)),
+                    not_after_timestamp: row.not_after_timestamp.map(|timestamp| timestamp as u64),
+                };
+                (H256::from_slice(&row.tx_hash), deadline)
+            })
+            .collect())
+    }
+
+    /// Marks the deadline for a transaction as expired.
+    pub async fn mark_deadline_expired(&mut self, tx_hash: H256) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE transaction_deadlines
+            SET
+                expired_at = NOW()
+            WHERE
+                tx_hash = $1
+            "#,
+            tx_hash.as_bytes()
+        )
+        .instrument("mark_deadline_expired")
+        .with_arg("tx_hash", &tx_hash)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Expires all pending transactions which deadlines do not allow including them into any L2 block with number
+    /// `>= next_l2_block_number` and timestamp `>= min_timestamp`. Expired transactions are marked as rejected
+    /// with the specified `error`. Returns hashes of the expired transactions.
+    pub async fn expire_pending_transactions(
+        &mut self,
+        next_l2_block_number: L2BlockNumber,
+        min_timestamp: u64,
+        error: &str,
+    ) -> DalResult<Vec<H256>> {
+        let rows = sqlx::query!(
+            r#"
+            WITH
+                expired AS (
+                    UPDATE transaction_deadlines
+                    SET
+                        expired_at = NOW()
+                    FROM
+                        transactions
+                    WHERE
+                        transactions.hash = transaction_deadlines.tx_hash
+                        AND transactions.miniblock_number IS NULL
+                        AND transactions.error IS NULL
+                        AND transaction_deadlines.expired_at IS NULL
+                        AND (
+                            transaction_deadlines.not_after_l2_block < $1
+                            OR transaction_deadlines.not_after_timestamp < $2
+                        )
+                    RETURNING
+                        transaction_deadlines.tx_hash
+                )
+            UPDATE transactions
+            SET
+                error = $3,
+                updated_at = NOW()
+            FROM
+                expired
+            WHERE
+                transactions.hash = expired.tx_hash
+            RETURNING
+                transactions.hash
+            "#,
+            i64::from(next_l2_block_number.0),
+            min_timestamp as i64,
+            error
+        )
+        .instrument("expire_pending_transactions")
+        .with_arg("next_l2_block_number", &next_l2_block_number)
+        .with_arg("min_timestamp", &min_timestamp)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| H256::from_slice(&row.hash))
+            .collect())
+    }
+
+    /// Removes deadlines created more than `retention` ago, unless their transactions are still pending.
+    /// Returns the number of removed deadlines.
+    pub async fn prune_deadlines(&mut self, retention: Duration) -> DalResult<u64> {
+        let retention = pg_interval_from_duration(retention);
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM transaction_deadlines
+            WHERE
+                created_at < NOW() - $1::INTERVAL
+                AND NOT EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        transactions
+                    WHERE
+                        transactions.hash = transaction_deadlines.tx_hash
+                        AND transactions.miniblock_number IS NULL
+                        AND transactions.error IS NULL
+                )
+            "#,
+            retention
+        )
+        .instrument("prune_deadlines")
+        .with_arg("retention", &retention)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Returns expiry information for a transaction, or `None` if the transaction wasn't submitted with a deadline.
+    pub async fn get_transaction_expiry(
+        &mut self,
+        tx_hash: H256,
+    ) -> DalResult<Option<TransactionExpiry>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                transaction_deadlines.tx_hash,
+                transaction_deadlines.not_after_l2_block,
+                transaction_deadlines.not_after_timestamp,
+                transaction_deadlines.expired_at,
+                transactions.hash AS "known_hash?",
+                transactions.miniblock_number AS "miniblock_number?",
+                transactions.error AS "error?"
+            FROM
+                transaction_deadlines
+                LEFT JOIN transactions ON transactions.hash = transaction_deadlines.tx_hash
+            WHERE
+                transaction_deadlines.tx_hash = $1
+            "#,
+            tx_hash.as_bytes()
+        )
+        .instrument("get_transaction_expiry")
+        .with_arg("tx_hash", &tx_hash)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| {
+            let status = if row.expired_at.is_some() {
+                TransactionExpiryStatus::Expired
+            } else if row.miniblock_number.is_some() {
+                TransactionExpiryStatus::Included
+            } else if row.known_hash.is_none() || row.error.is_some() {
+                TransactionExpiryStatus::Dropped
+            } else {
+                TransactionExpiryStatus::Pending
+            };
+            TransactionExpiry {
+                hash: H256::from_slice(&row.tx_hash),
+                deadline: TransactionDeadline {
+                    not_after_l2_block: row
+                        .not_after_l2_block
+                        .map(|number| L2BlockNumber(number as u32)),
+                    not_after_timestamp: row.not_after_timestamp.map(|timestamp| timestamp as u64),
+                },
+                status,
+                expired_at: row
+                    .expired_at
+                    .map(|time| DateTime::<Utc>::from_naive_utc_and_offset(time, Utc)),
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{fee::TransactionExecutionMetrics, ProtocolVersion, ProtocolVersionId};
+
+    use super::*;
+    use crate::{
+        tests::{create_l2_block_header, mock_execution_result, mock_l2_transaction},
+        transactions_dal::CallTraceFormat,
+        ConnectionPool, CoreDal,
+    };
+
+    #[tokio::test]
+    async fn transaction_deadlines_lifecycle() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(&ProtocolVersion::default())
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .insert_l2_block(&create_l2_block_header(1))
+            .await
+            .unwrap();
+
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        let deadline = TransactionDeadline {
+            not_after_l2_block: Some(L2BlockNumber(5)),
+            not_after_timestamp: None,
+        };
+        let inserted = conn
+            .transaction_deadlines_dal()
+            .insert_deadline(tx_hash, &deadline)
+            .await
+            .unwrap();
+        assert!(inserted);
+        let inserted = conn
+            .transaction_deadlines_dal()
+            .insert_deadline(tx_hash, &TransactionDeadline::default())
+            .await
+            .unwrap();
+        assert!(!inserted);
+
+        let expiry = conn
+            .transaction_deadlines_dal()
+            .get_transaction_expiry(tx_hash)
+            .await
+            .unwrap()
+            .expect("no expiry info");
+        assert_eq!(expiry.deadline, deadline);
+        assert_eq!(expiry.status, TransactionExpiryStatus::Dropped);
+
+        conn.transactions_dal()
+            .insert_transaction_l2(&tx, TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        let deadlines = conn
+            .transaction_deadlines_dal()
+            .get_pending_deadlines(&[tx_hash, H256::repeat_byte(1)])
+            .await
+            .unwrap();
+        assert_eq!(deadlines, HashMap::from([(tx_hash, deadline)]));
+        let expiry = conn
+            .transaction_deadlines_dal()
+            .get_transaction_expiry(tx_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(expiry.status, TransactionExpiryStatus::Pending);
+
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_l2_block(
+                L2BlockNumber(1),
+                &[mock_execution_result(tx)],
+                1.into(),
+                ProtocolVersionId::latest(),
+                CallTraceFormat::Legacy,
+                false,
+            )
+            .await
+            .unwrap();
+        let expiry = conn
+            .transaction_deadlines_dal()
+            .get_transaction_expiry(tx_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(expiry.status, TransactionExpiryStatus::Included);
+        assert_eq!(expiry.expired_at, None);
+    }
+
+    #[tokio::test]
+    async fn expiring_transaction_deadline() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(&tx, TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        let deadline = TransactionDeadline {
+            not_after_l2_block: None,
+            not_after_timestamp: Some(1_000),
+        };
+        conn.transaction_deadlines_dal()
+            .insert_deadline(tx_hash, &deadline)
+            .await
+            .unwrap();
+
+        conn.transaction_deadlines_dal()
+            .mark_deadline_expired(tx_hash)
+            .await
+            .unwrap();
+        let expiry = conn
+            .transaction_deadlines_dal()
+            .get_transaction_expiry(tx_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(expiry.status, TransactionExpiryStatus::Expired);
+        assert!(expiry.expired_at.is_some());
+        let deadlines = conn
+            .transaction_deadlines_dal()
+            .get_pending_deadlines(&[tx_hash])
+            .await
+            .unwrap();
+        assert!(deadlines.is_empty());
+
+        // Expired deadlines must not be removed.
+        conn.transaction_deadlines_dal()
+            .remove_deadline(tx_hash)
+            .await
+            .unwrap();
+        let expiry = conn
+            .transaction_deadlines_dal()
+            .get_transaction_expiry(tx_hash)
+            .await
+            .unwrap();
+        assert_eq!(expiry.unwrap().status, TransactionExpiryStatus::Expired);
+    }
+
+    #[tokio::test]
+    async fn expiring_and_pruning_deadlines() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let deadlines = [
+            (Some(L2BlockNumber(3)), None),
+            (Some(L2BlockNumber(10)), Some(1_000)),
+            (None, Some(100)),
+        ];
+        let mut tx_hashes = vec![];
+        for (not_after_l2_block, not_after_timestamp) in deadlines {
+            let tx = mock_l2_transaction();
+            conn.transactions_dal()
+                .insert_transaction_l2(&tx, TransactionExecutionMetrics::default())
+                .await
+                .unwrap();
+            let deadline = TransactionDeadline {
+                not_after_l2_block,
+                not_after_timestamp,
+            };
+            conn.transaction_deadlines_dal()
+                .insert_deadline(tx.hash(), &deadline)
+                .await
+                .unwrap();
+            tx_hashes.push(tx.hash());
+        }
+
+        let mut expired_hashes = conn
+            .transaction_deadlines_dal()
+            .expire_pending_transactions(L2BlockNumber(4), 500, "rejected: expired")
+            .await
+            .unwrap();
+        expired_hashes.sort_unstable();
+        let mut expected_hashes = vec![tx_hashes[0], tx_hashes[2]];
+        expected_hashes.sort_unstable();
+        assert_eq!(expired_hashes, expected_hashes);
+        for &tx_hash in &expected_hashes {
+            let expiry = conn
+                .transaction_deadlines_dal()
+                .get_transaction_expiry(tx_hash)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(expiry.status, TransactionExpiryStatus::Expired);
+        }
+        let expiry = conn
+            .transaction_deadlines_dal()
+            .get_transaction_expiry(tx_hashes[1])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(expiry.status, TransactionExpiryStatus::Pending);
+
+        // Transactions must not be expired repeatedly.
+        let expired_hashes = conn
+            .transaction_deadlines_dal()
+            .expire_pending_transactions(L2BlockNumber(4), 500, "rejected: expired")
+            .await
+            .unwrap();
+        assert!(expired_hashes.is_empty(), "{expired_hashes:?}");
+
+        let pruned_count = conn
+            .transaction_deadlines_dal()
+            .prune_deadlines(Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(pruned_count, 2);
+        for &tx_hash in &expected_hashes {
+            let expiry = conn
+                .transaction_deadlines_dal()
+                .get_transaction_expiry(tx_hash)
+                .await
+                .unwrap();
+            assert!(expiry.is_none(), "{expiry:?}");
+        }
+        // The deadline of the pending transaction must be retained.
+        let deadlines = conn
+            .transaction_deadlines_dal()
+            .get_pending_deadlines(&[tx_hashes[1]])
+            .await
+            .unwrap();
+        assert_eq!(deadlines.len(), 1);
+    }
+}
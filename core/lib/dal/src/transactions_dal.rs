@@ -5,7 +5,7 @@ use itertools::Itertools;
 use sqlx::types::chrono::NaiveDateTime;
 use zksync_db_connection::{
     connection::Connection,
-    error::DalResult,
+    error::{DalResult, SqlxContext},
     instrument::{InstrumentExt, Instrumented},
     utils::pg_interval_from_duration,
 };
@@ -17,13 +17,18 @@ use zksync_types::{
     protocol_upgrade::ProtocolUpgradeTx,
     tx::{tx_execution_info::TxExecutionStatus, TransactionExecutionResult},
     vm_trace::Call,
-    Address, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, L2BlockNumber, PriorityOpId,
-    ProtocolVersionId, Transaction, H256, PROTOCOL_UPGRADE_TX_TYPE, U256,
+    web3::keccak256,
+    Address, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, L2BlockNumber, Nonce,
+    PriorityOpId, ProtocolVersionId, Transaction, H256, PROTOCOL_UPGRADE_TX_TYPE, U256,
 };
-use zksync_utils::u256_to_big_decimal;
+use zksync_utils::{bigdecimal_to_u256, u256_to_big_decimal};
 
+pub use crate::models::storage_call_trace::{CallTraceFilter, CallTraceFormat};
 use crate::{
-    models::storage_transaction::{CallTrace, StorageTransaction},
+    models::{
+        parse_protocol_version, storage_call_trace::CallTrace,
+        storage_transaction::StorageTransaction,
+    },
     Core, CoreDal,
 };
 
@@ -35,6 +40,8 @@ pub enum L2TxSubmissionResult {
     Duplicate,
     Proxied,
     InsertionInProgress,
+    /// Pending transaction with the same nonce cannot be replaced because the new transaction doesn't bump fees enough.
+    ReplacementUnderpriced,
 }
 
 impl fmt::Display for L2TxSubmissionResult {
@@ -46,10 +53,61 @@ impl fmt::Display for L2TxSubmissionResult {
             Self::Duplicate => "duplicate",
             Self::Proxied => "proxied",
             Self::InsertionInProgress => "insertion_in_progress",
+            Self::ReplacementUnderpriced => "replacement_underpriced",
         })
     }
 }
 
+/// Number and total size of pending L2 transactions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PendingL2TxsSize {
+    pub count: u64,
+    pub size_bytes: u64,
+}
+
+/// Result of converting a chunk of legacy call traces to the flat format.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigratedCallTraces {
+    /// Sorted hashes of transactions with converted call traces.
+    pub migrated: Vec<H256>,
+    /// Sorted hashes of transactions with call traces that cannot be decoded. These traces are left as is.
+    pub skipped: Vec<H256>,
+}
+
+impl MigratedCallTraces {
+    /// Returns the greatest processed transaction hash, or `None` if no traces were processed.
+    pub fn last_processed(&self) -> Option<H256> {
+        self.migrated.last().max(self.skipped.last()).copied()
+    }
+}
+
+/// Pending L2 transaction that can be evicted from the mempool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct L2TxEvictionCandidate {
+    pub hash: H256,
+    pub max_fee_per_gas: U256,
+    pub size_bytes: u64,
+}
+
+/// Keyset cursor for iterating over pending L2 transactions in the order they were received. Ties between
+/// transactions received at the same time are broken by transaction hashes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingL2TxCursor {
+    received_at: NaiveDateTime,
+    hash: H256,
+}
+
+impl PendingL2TxCursor {
+    /// Creates a cursor pointing at the specified time (a Unix timestamp in milliseconds).
+    pub fn received_after_ms(received_after_ms: u64) -> Self {
+        Self {
+            received_at: NaiveDateTime::from_timestamp_millis(received_after_ms as i64)
+                .unwrap_or(NaiveDateTime::MAX),
+            hash: H256::zero(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct TransactionsDal<'c, 'a> {
     pub(crate) storage: &'c mut Connection<'a, Core>,
@@ -262,6 +320,20 @@ impl TransactionsDal<'_, '_> {
         &mut self,
         tx: &L2Tx,
         exec_info: TransactionExecutionMetrics,
+    ) -> DalResult<L2TxSubmissionResult> {
+        self.insert_transaction_l2_with_fee_bump(tx, exec_info, 0)
+            .await
+    }
+
+    /// Inserts an L2 transaction into the mempool. If there's a pending transaction with the same initiator and nonce,
+    /// it's atomically replaced, provided that both `max_fee_per_gas` and `max_priority_fee_per_gas` of the new transaction
+    /// are at least `min_fee_bump_percent` percent higher than for the replaced one. If `min_fee_bump_percent` is 0,
+    /// replacement is unconditional.
+    pub async fn insert_transaction_l2_with_fee_bump(
+        &mut self,
+        tx: &L2Tx,
+        exec_info: TransactionExecutionMetrics,
+        min_fee_bump_percent: u32,
     ) -> DalResult<L2TxSubmissionResult> {
         let tx_hash = tx.hash();
         let is_duplicate = sqlx::query!(
@@ -394,6 +466,13 @@ impl TransactionsDal<'_, '_> {
             WHERE
                 transactions.is_priority = FALSE
                 AND transactions.miniblock_number IS NULL
+                AND (
+                    $20::INT = 0
+                    OR (
+                        transactions.max_fee_per_gas * (100 + $20::INT) <= $6 * 100
+                        AND transactions.max_priority_fee_per_gas * (100 + $20::INT) <= $7 * 100
+                    )
+                )
             RETURNING
                 (
                     SELECT
@@ -423,10 +502,12 @@ impl TransactionsDal<'_, '_> {
             exec_info.gas_used as i64,
             (exec_info.initial_storage_writes + exec_info.repeated_storage_writes) as i32,
             exec_info.contracts_used as i32,
-            received_at
+            received_at,
+            min_fee_bump_percent as i32
         )
         .instrument("insert_transaction_l2")
         .with_arg("tx_hash", &tx_hash)
+        .with_arg("min_fee_bump_percent", &min_fee_bump_percent)
         .fetch_optional(self.storage)
         .await
         .map(|option_record| option_record.map(|record| record.is_replaced));
@@ -435,6 +516,18 @@ impl TransactionsDal<'_, '_> {
             Ok(option_query_result) => match option_query_result {
                 Some(true) => L2TxSubmissionResult::Replaced,
                 Some(false) => L2TxSubmissionResult::Added,
+                // The update conditions aren't met either because the transaction with the same nonce
+                // is already executed, or because the replacement doesn't bump fees enough.
+                None if min_fee_bump_percent > 0 => {
+                    if self
+                        .has_pending_l2_transaction(initiator_address, nonce)
+                        .await?
+                    {
+                        L2TxSubmissionResult::ReplacementUnderpriced
+                    } else {
+                        L2TxSubmissionResult::AlreadyExecuted
+                    }
+                }
                 None => L2TxSubmissionResult::AlreadyExecuted,
             },
             Err(err) => {
@@ -470,6 +563,113 @@ impl TransactionsDal<'_, '_> {
         Ok(l2_tx_insertion_result)
     }
 
+    /// Checks whether [`Self::insert_transaction_l2_with_fee_bump()`] would reject the transaction without modifying
+    /// the storage. Returns the rejection result, or `None` if the transaction would be added or would replace
+    /// a pending transaction.
+    pub async fn check_l2_tx_insertion(
+        &mut self,
+        tx: &L2Tx,
+        min_fee_bump_percent: u32,
+    ) -> DalResult<Option<L2TxSubmissionResult>> {
+        let tx_hash = tx.hash();
+        let is_duplicate = sqlx::query!(
+            r#"
+            SELECT
+                TRUE
+            FROM
+                transactions
+            WHERE
+                hash = $1
+            "#,
+            tx_hash.as_bytes(),
+        )
+        .instrument("check_l2_tx_insertion#is_duplicate")
+        .with_arg("tx_hash", &tx_hash)
+        .fetch_optional(self.storage)
+        .await?
+        .is_some();
+        if is_duplicate {
+            return Ok(Some(L2TxSubmissionResult::Duplicate));
+        }
+
+        let initiator_address = tx.initiator_account();
+        let nonce = i64::from(tx.common_data.nonce.0);
+        let max_fee_per_gas = u256_to_big_decimal(tx.common_data.fee.max_fee_per_gas);
+        let max_priority_fee_per_gas =
+            u256_to_big_decimal(tx.common_data.fee.max_priority_fee_per_gas);
+        // Mirrors the `WHERE` clause of the `ON CONFLICT` update in `insert_transaction_l2_with_fee_bump()`.
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                (
+                    is_priority
+                    OR miniblock_number IS NOT NULL
+                ) AS "is_executed!",
+                (
+                    $3::INT = 0
+                    OR (
+                        max_fee_per_gas * (100 + $3::INT) <= $4 * 100
+                        AND max_priority_fee_per_gas * (100 + $3::INT) <= $5 * 100
+                    )
+                ) AS "is_fee_bumped!"
+            FROM
+                transactions
+            WHERE
+                initiator_address = $1
+                AND nonce = $2
+            "#,
+            initiator_address.as_bytes(),
+            nonce,
+            min_fee_bump_percent as i32,
+            max_fee_per_gas,
+            max_priority_fee_per_gas
+        )
+        .instrument("check_l2_tx_insertion")
+        .with_arg("initiator_address", &initiator_address)
+        .with_arg("nonce", &nonce)
+        .with_arg("min_fee_bump_percent", &min_fee_bump_percent)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.and_then(|row| {
+            if row.is_executed {
+                Some(L2TxSubmissionResult::AlreadyExecuted)
+            } else if !row.is_fee_bumped {
+                Some(L2TxSubmissionResult::ReplacementUnderpriced)
+            } else {
+                None
+            }
+        }))
+    }
+
+    async fn has_pending_l2_transaction(
+        &mut self,
+        initiator_address: Address,
+        nonce: i64,
+    ) -> DalResult<bool> {
+        Ok(sqlx::query!(
+            r#"
+            SELECT
+                TRUE
+            FROM
+                transactions
+            WHERE
+                initiator_address = $1
+                AND nonce = $2
+                AND is_priority = FALSE
+                AND miniblock_number IS NULL
+            "#,
+            initiator_address.as_bytes(),
+            nonce
+        )
+        .instrument("has_pending_l2_transaction")
+        .with_arg("initiator_address", &initiator_address)
+        .with_arg("nonce", &nonce)
+        .fetch_optional(self.storage)
+        .await?
+        .is_some())
+    }
+
     pub async fn mark_txs_as_executed_in_l1_batch(
         &mut self,
         l1_batch_number: L1BatchNumber,
@@ -505,24 +705,43 @@ impl TransactionsDal<'_, '_> {
         Ok(())
     }
 
+    /// Marks transactions as executed in the specified L2 block and saves their call traces in `call_trace_format`.
+    /// `protocol_version` of the L2 block is used to encode call traces in the legacy format.
     pub async fn mark_txs_as_executed_in_l2_block(
         &mut self,
         l2_block_number: L2BlockNumber,
         transactions: &[TransactionExecutionResult],
         block_base_fee_per_gas: U256,
         protocol_version: ProtocolVersionId,
+        call_trace_format: CallTraceFormat,
         // On the main node, transactions are inserted into the DB by API servers.
         // However on the EN, they need to be inserted after they are executed by the state keeper.
         insert_txs: bool,
     ) -> DalResult<()> {
+        let instrumentation = Instrumented::new("mark_txs_as_executed_in_l2_block")
+            .with_arg("l2_block_number", &l2_block_number);
         let mut transaction = self.storage.start_transaction().await?;
 
         let mut call_traces_tx_hashes = Vec::with_capacity(transactions.len());
         let mut bytea_call_traces = Vec::with_capacity(transactions.len());
+        let mut transfers_tx_hashes = vec![];
+        let mut transfer_indices = vec![];
+        let mut transfer_senders = vec![];
+        let mut transfer_recipients = vec![];
+        let mut transfer_values = vec![];
         for tx_res in transactions {
             if let Some(call_trace) = tx_res.call_trace() {
-                bytea_call_traces
-                    .push(CallTrace::from_call(call_trace, protocol_version).call_trace);
+                for (i, transfer) in call_trace.internal_transfers().into_iter().enumerate() {
+                    transfers_tx_hashes.push(tx_res.hash.as_bytes());
+                    transfer_indices.push(i as i32);
+                    transfer_senders.push(transfer.from.as_bytes().to_vec());
+                    transfer_recipients.push(transfer.to.as_bytes().to_vec());
+                    transfer_values.push(u256_to_big_decimal(transfer.value));
+                }
+                let call_trace =
+                    CallTrace::from_call(&call_trace, call_trace_format, protocol_version)
+                        .map_err(|err| instrumentation.arg_error("transactions", err))?;
+                bytea_call_traces.push(call_trace.call_trace);
                 call_traces_tx_hashes.push(tx_res.hash.as_bytes());
             }
         }
@@ -603,15 +822,17 @@ impl TransactionsDal<'_, '_> {
             sqlx::query!(
                 r#"
                 INSERT INTO
-                    call_traces (tx_hash, call_trace)
+                    call_traces (tx_hash, call_trace, format_version)
                 SELECT
                     u.tx_hash,
-                    u.call_trace
+                    u.call_trace,
+                    $3
                 FROM
                     UNNEST($1::bytea[], $2::bytea[]) AS u (tx_hash, call_trace)
                 "#,
                 &call_traces_tx_hashes as &[&[u8]],
-                &bytea_call_traces
+                &bytea_call_traces,
+                call_trace_format as i16
             )
             .instrument("insert_call_tracer")
             .report_latency()
@@ -619,6 +840,62 @@ impl TransactionsDal<'_, '_> {
             .await?;
         }
 
+        // Transactions may have been executed before, e.g. if the L2 block was rolled back.
+        let tx_hashes: Vec<_> = transactions.iter().map(|tx| tx.hash.as_bytes()).collect();
+        sqlx::query!(
+            r#"
+            DELETE FROM internal_transfers
+            WHERE
+                tx_hash = ANY ($1)
+            "#,
+            &tx_hashes as &[&[u8]],
+        )
+        .instrument("mark_txs_as_executed_in_l2_block#remove_old_internal_transfers")
+        .execute(&mut transaction)
+        .await?;
+
+        if !transfers_tx_hashes.is_empty() {
+            sqlx::query!(
+                r#"
+                INSERT INTO
+                    internal_transfers (
+                        tx_hash,
+                        transfer_index,
+                        miniblock_number,
+                        from_address,
+                        to_address,
+                        value
+                    )
+                SELECT
+                    u.tx_hash,
+                    u.transfer_index,
+                    $6,
+                    u.from_address,
+                    u.to_address,
+                    u.value
+                FROM
+                    UNNEST($1::bytea[], $2::INT[], $3::bytea[], $4::bytea[], $5::NUMERIC[]) AS u (
+                        tx_hash,
+                        transfer_index,
+                        from_address,
+                        to_address,
+                        value
+                    )
+                "#,
+                &transfers_tx_hashes as &[&[u8]],
+                &transfer_indices,
+                &transfer_senders,
+                &transfer_recipients,
+                &transfer_values,
+                i64::from(l2_block_number.0)
+            )
+            .instrument("insert_internal_transfers")
+            .with_arg("transfers.len", &transfers_tx_hashes.len())
+            .report_latency()
+            .execute(&mut transaction)
+            .await?;
+        }
+
         transaction.commit().await
     }
 
@@ -1691,6 +1968,248 @@ impl TransactionsDal<'_, '_> {
         Ok(rows.len())
     }
 
+    /// Acquires a Postgres advisory lock for submitting an L2 transaction with the specified initiator and nonce.
+    /// The lock is held until the end of the current Postgres transaction, so that concurrent submissions
+    /// with the same initiator and nonce (e.g., via different API servers sharing the database) are serialized.
+    pub async fn lock_l2_tx_submission(
+        &mut self,
+        initiator_address: Address,
+        nonce: Nonce,
+    ) -> DalResult<()> {
+        assert!(
+            self.storage.in_transaction(),
+            "submission lock must be acquired in a transaction"
+        );
+        let mut lock_preimage = initiator_address.as_bytes().to_vec();
+        lock_preimage.extend_from_slice(&nonce.0.to_be_bytes());
+
+        sqlx::query!(
+            r#"
+            SELECT
+                1 AS "locked!"
+            FROM
+                PG_ADVISORY_XACT_LOCK($1)
+            "#,
+            Self::advisory_lock_key(&lock_preimage)
+        )
+        .instrument("lock_l2_tx_submission")
+        .with_arg("initiator_address", &initiator_address)
+        .with_arg("nonce", &nonce)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    fn advisory_lock_key(preimage: &[u8]) -> i64 {
+        let lock_hash = keccak256(preimage);
+        i64::from_be_bytes(lock_hash[..8].try_into().unwrap())
+    }
+
+    /// Acquires a Postgres advisory lock for checking the per-sender mempool quota for the specified sender,
+    /// so that the quota check and the following transaction insertion are atomic across concurrent submissions
+    /// (including ones via different API servers). The lock is held until the end of the current Postgres transaction.
+    pub async fn lock_mempool_quota(&mut self, initiator_address: Address) -> DalResult<()> {
+        assert!(
+            self.storage.in_transaction(),
+            "mempool quota lock must be acquired in a transaction"
+        );
+        let mut lock_preimage = b"mempool_quota".to_vec();
+        lock_preimage.extend_from_slice(initiator_address.as_bytes());
+
+        sqlx::query!(
+            r#"
+            SELECT
+                1 AS "locked!"
+            FROM
+                PG_ADVISORY_XACT_LOCK($1)
+            "#,
+            Self::advisory_lock_key(&lock_preimage)
+        )
+        .instrument("lock_mempool_quota")
+        .with_arg("initiator_address", &initiator_address)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the number of pending L2 transactions from the specified sender, excluding the transaction
+    /// with the specified nonce (which would be replaced by a new transaction with the same nonce).
+    pub async fn get_pending_l2_txs_count_for_sender(
+        &mut self,
+        initiator_address: Address,
+        excluded_nonce: Nonce,
+    ) -> DalResult<u64> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!"
+            FROM
+                transactions
+            WHERE
+                initiator_address = $1
+                AND nonce <> $2
+                AND miniblock_number IS NULL
+                AND is_priority = FALSE
+                AND error IS NULL
+            "#,
+            initiator_address.as_bytes(),
+            i64::from(excluded_nonce.0)
+        )
+        .instrument("get_pending_l2_txs_count_for_sender")
+        .with_arg("initiator_address", &initiator_address)
+        .with_arg("excluded_nonce", &excluded_nonce)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(count as u64)
+    }
+
+    /// Returns the number of pending L2 transactions and their total size in bytes.
+    pub async fn get_pending_l2_txs_size(&mut self) -> DalResult<PendingL2TxsSize> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!",
+                COALESCE(SUM(LENGTH(input)), 0) AS "size_bytes!"
+            FROM
+                transactions
+            WHERE
+                miniblock_number IS NULL
+                AND is_priority = FALSE
+                AND error IS NULL
+            "#
+        )
+        .instrument("get_pending_l2_txs_size")
+        .fetch_one(self.storage)
+        .await?;
+        Ok(PendingL2TxsSize {
+            count: row.count as u64,
+            size_bytes: row.size_bytes as u64,
+        })
+    }
+
+    /// Returns pending L2 transactions received after the specified cursor, ordered by the time they were received.
+    /// Also returns the cursor pointing at the last returned transaction (or the provided cursor if no transactions
+    /// are returned).
+    pub async fn get_pending_l2_txs_received_after(
+        &mut self,
+        cursor: PendingL2TxCursor,
+        limit: usize,
+    ) -> DalResult<(Vec<Transaction>, PendingL2TxCursor)> {
+        let transactions = sqlx::query_as!(
+            StorageTransaction,
+            r#"
+            SELECT
+                *
+            FROM
+                transactions
+            WHERE
+                received_at >= $1
+                AND (received_at, hash) > ($1, $2)
+                AND miniblock_number IS NULL
+                AND is_priority = FALSE
+                AND error IS NULL
+            ORDER BY
+                received_at,
+                hash
+            LIMIT
+                $3
+            "#,
+            cursor.received_at,
+            cursor.hash.as_bytes(),
+            limit as i64
+        )
+        .instrument("get_pending_l2_txs_received_after")
+        .with_arg("cursor", &cursor)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        let next_cursor = transactions.last().map_or(cursor, |tx| PendingL2TxCursor {
+            received_at: tx.received_at,
+            hash: H256::from_slice(&tx.hash),
+        });
+        let transactions = transactions.into_iter().map(Into::into).collect();
+        Ok((transactions, next_cursor))
+    }
+
+    /// Returns the pending L2 transaction that should be evicted first if the mempool is full: the one with
+    /// the lowest max fee per gas (and, among them, the oldest one). Only transactions with the greatest nonce
+    /// for their sender are considered, so that eviction doesn't produce nonce gaps. Transactions already loaded
+    /// into the state keeper mempool are not considered either, since removing them from Postgres wouldn't remove
+    /// them from the in-memory mempool.
+    pub async fn get_l2_tx_eviction_candidate(
+        &mut self,
+    ) -> DalResult<Option<L2TxEvictionCandidate>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                max_fee_per_gas,
+                LENGTH(input) AS size_bytes
+            FROM
+                transactions
+            WHERE
+                miniblock_number IS NULL
+                AND is_priority = FALSE
+                AND error IS NULL
+                AND in_mempool = FALSE
+                AND NOT EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        transactions AS next_txs
+                    WHERE
+                        next_txs.initiator_address = transactions.initiator_address
+                        AND next_txs.nonce > transactions.nonce
+                        AND next_txs.miniblock_number IS NULL
+                        AND next_txs.is_priority = FALSE
+                        AND next_txs.error IS NULL
+                )
+            ORDER BY
+                max_fee_per_gas,
+                received_at
+            LIMIT
+                1
+            "#
+        )
+        .instrument("get_l2_tx_eviction_candidate")
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| L2TxEvictionCandidate {
+            hash: H256::from_slice(&row.hash),
+            max_fee_per_gas: row
+                .max_fee_per_gas
+                .map(bigdecimal_to_u256)
+                .unwrap_or_default(),
+            size_bytes: row.size_bytes.unwrap_or(0) as u64,
+        }))
+    }
+
+    /// Removes the pending L2 transaction with the specified hash. Returns `false` if the transaction
+    /// is not pending (e.g., if it was already executed) or was loaded into the state keeper mempool
+    /// (see [`Self::get_l2_tx_eviction_candidate()`]).
+    pub async fn evict_pending_l2_tx(&mut self, tx_hash: H256) -> DalResult<bool> {
+        let row = sqlx::query!(
+            r#"
+            DELETE FROM transactions
+            WHERE
+                hash = $1
+                AND miniblock_number IS NULL
+                AND is_priority = FALSE
+                AND in_mempool = FALSE
+            RETURNING
+                hash
+            "#,
+            tx_hash.as_bytes()
+        )
+        .instrument("evict_pending_l2_tx")
+        .with_arg("tx_hash", &tx_hash)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.is_some())
+    }
+
     /// Fetches new updates for mempool. Returns new transactions and current nonces for related accounts;
     /// the latter are only used to bootstrap mempool for given account.
     pub async fn sync_mempool(
@@ -2090,8 +2609,13 @@ impl TransactionsDal<'_, '_> {
         Ok(data)
     }
 
-    pub async fn get_call_trace(&mut self, tx_hash: H256) -> DalResult<Option<Call>> {
-        let row = sqlx::query!(
+    /// Returns the call trace for the specified transaction. Only the part of the trace selected by `filter` is decoded.
+    pub async fn get_call_trace(
+        &mut self,
+        tx_hash: H256,
+        filter: &CallTraceFilter,
+    ) -> DalResult<Option<Call>> {
+        let protocol_version = sqlx::query!(
             r#"
             SELECT
                 protocol_version
@@ -2103,25 +2627,24 @@ impl TransactionsDal<'_, '_> {
             "#,
             tx_hash.as_bytes()
         )
+        .try_map(|row| row.protocol_version.map(parse_protocol_version).transpose())
         .instrument("get_call_trace")
         .with_arg("tx_hash", &tx_hash)
         .fetch_optional(self.storage)
         .await?;
 
-        let Some(row) = row else {
+        let Some(protocol_version) = protocol_version else {
             return Ok(None);
         };
+        let protocol_version =
+            protocol_version.unwrap_or_else(ProtocolVersionId::last_potentially_undefined);
 
-        let protocol_version = row
-            .protocol_version
-            .map(|v| (v as u16).try_into().unwrap())
-            .unwrap_or_else(ProtocolVersionId::last_potentially_undefined);
-
-        Ok(sqlx::query_as!(
+        sqlx::query_as!(
             CallTrace,
             r#"
             SELECT
-                call_trace
+                call_trace,
+                format_version
             FROM
                 call_traces
             WHERE
@@ -2129,11 +2652,125 @@ impl TransactionsDal<'_, '_> {
             "#,
             tx_hash.as_bytes()
         )
+        .try_map(|call_trace| {
+            call_trace
+                .into_filtered_call(protocol_version, filter)
+                .decode_column("call_trace")
+        })
         .instrument("get_call_trace")
         .with_arg("tx_hash", &tx_hash)
         .fetch_optional(self.storage)
-        .await?
-        .map(|call_trace| call_trace.into_call(protocol_version)))
+        .await
+    }
+
+    /// Converts up to `limit` call traces stored in the legacy format to the flat format. Traces are processed
+    /// in the order of transaction hashes, starting after the `after` hash (or from the start if `after` is `None`).
+    /// Traces that cannot be decoded are logged and left as is. [`MigratedCallTraces::last_processed()`] of the result
+    /// can be used as `after` for the next call; if it's `None`, there are no more legacy traces after the cursor.
+    ///
+    /// Nodes that do not know about the flat format cannot read converted traces, so this method must only be called
+    /// once all nodes reading call traces from the DB are updated.
+    pub async fn migrate_legacy_call_traces(
+        &mut self,
+        after: Option<H256>,
+        limit: usize,
+    ) -> DalResult<MigratedCallTraces> {
+        let mut transaction = self.storage.start_transaction().await?;
+        // The format version literal must match the predicate of `call_traces_legacy_format_idx`.
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                call_traces.tx_hash,
+                call_traces.call_trace,
+                miniblocks.protocol_version
+            FROM
+                call_traces
+                INNER JOIN transactions ON call_traces.tx_hash = transactions.hash
+                INNER JOIN miniblocks ON transactions.miniblock_number = miniblocks.number
+            WHERE
+                call_traces.format_version = 1
+                AND (
+                    $1::BYTEA IS NULL
+                    OR call_traces.tx_hash > $1
+                )
+            ORDER BY
+                call_traces.tx_hash
+            LIMIT
+                $2
+            FOR UPDATE
+                OF call_traces SKIP LOCKED
+            "#,
+            after.as_ref().map(H256::as_bytes),
+            limit as i64
+        )
+        .instrument("migrate_legacy_call_traces#get_traces")
+        .with_arg("after", &after)
+        .with_arg("limit", &limit)
+        .fetch_all(&mut transaction)
+        .await?;
+        if rows.is_empty() {
+            return Ok(MigratedCallTraces::default());
+        }
+
+        let mut migrated = MigratedCallTraces::default();
+        let mut tx_hashes = Vec::with_capacity(rows.len());
+        let mut call_traces = Vec::with_capacity(rows.len());
+        for row in rows {
+            let tx_hash = H256::from_slice(&row.tx_hash);
+            let legacy_trace = CallTrace {
+                call_trace: row.call_trace,
+                format_version: CallTrace::LEGACY_FORMAT_VERSION,
+            };
+            let trace = row
+                .protocol_version
+                .map(parse_protocol_version)
+                .transpose()
+                .map_err(anyhow::Error::from)
+                .and_then(|protocol_version| {
+                    legacy_trace.migrate(
+                        protocol_version
+                            .unwrap_or_else(ProtocolVersionId::last_potentially_undefined),
+                    )
+                });
+            match trace {
+                Ok(Some(trace)) => {
+                    tx_hashes.push(row.tx_hash);
+                    call_traces.push(trace.call_trace);
+                    migrated.migrated.push(tx_hash);
+                }
+                Ok(None) => unreachable!("trace is marked as legacy"),
+                Err(err) => {
+                    tracing::warn!(
+                        "Skipping legacy call trace for transaction {tx_hash:?} that cannot be decoded: {err:#}"
+                    );
+                    migrated.skipped.push(tx_hash);
+                }
+            }
+        }
+
+        if !tx_hashes.is_empty() {
+            sqlx::query!(
+                r#"
+                UPDATE call_traces
+                SET
+                    call_trace = u.call_trace,
+                    format_version = $3
+                FROM
+                    UNNEST($1::bytea[], $2::bytea[]) AS u (tx_hash, call_trace)
+                WHERE
+                    call_traces.tx_hash = u.tx_hash
+                "#,
+                &tx_hashes,
+                &call_traces,
+                CallTrace::FLAT_FORMAT_VERSION
+            )
+            .instrument("migrate_legacy_call_traces#update_traces")
+            .with_arg("traces.len", &tx_hashes.len())
+            .execute(&mut transaction)
+            .await?;
+        }
+        transaction.commit().await?;
+        Ok(migrated)
     }
 
     pub(crate) async fn get_tx_by_hash(&mut self, hash: H256) -> DalResult<Option<Transaction>> {
@@ -2159,6 +2796,8 @@ impl TransactionsDal<'_, '_> {
 
 #[cfg(test)]
 mod tests {
+    use std::slice;
+
     use zksync_types::ProtocolVersion;
 
     use super::*;
@@ -2200,6 +2839,7 @@ mod tests {
                 &[tx_result],
                 1.into(),
                 ProtocolVersionId::latest(),
+                CallTraceFormat::Legacy,
                 false,
             )
             .await
@@ -2207,11 +2847,251 @@ mod tests {
 
         let call_trace = conn
             .transactions_dal()
-            .get_call_trace(tx_hash)
+            .get_call_trace(tx_hash, &CallTraceFilter::default())
             .await
             .unwrap()
             .expect("no call trace");
         assert_eq!(call_trace, expected_call_trace);
+
+        let filter = CallTraceFilter {
+            max_frames: Some(1),
+            ..CallTraceFilter::default()
+        };
+        let top_call = conn
+            .transactions_dal()
+            .get_call_trace(tx_hash, &filter)
+            .await
+            .unwrap()
+            .expect("no call trace");
+        assert!(top_call.calls.is_empty());
+        assert_eq!(top_call.gas_used, expected_call_trace.gas_used);
+    }
+
+    async fn get_call_trace_format_versions(conn: &mut Connection<'_, Core>) -> Vec<i16> {
+        sqlx::query_scalar("SELECT format_version FROM call_traces ORDER BY tx_hash")
+            .fetch_all(conn.conn())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn writing_call_traces_in_different_formats() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = connection_pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(&ProtocolVersion::default())
+            .await
+            .unwrap();
+
+        let formats = [CallTraceFormat::Legacy, CallTraceFormat::Flat];
+        for (i, format) in formats.into_iter().enumerate() {
+            let l2_block_number = L2BlockNumber(i as u32 + 1);
+            conn.blocks_dal()
+                .insert_l2_block(&create_l2_block_header(l2_block_number.0))
+                .await
+                .unwrap();
+
+            let tx = mock_l2_transaction();
+            conn.transactions_dal()
+                .insert_transaction_l2(&tx, TransactionExecutionMetrics::default())
+                .await
+                .unwrap();
+            let mut tx_result = mock_execution_result(tx);
+            tx_result.call_traces.push(Call {
+                from: Address::from_low_u64_be(1),
+                to: Address::from_low_u64_be(2),
+                value: 100.into(),
+                calls: vec![Call::default()],
+                ..Call::default()
+            });
+            let expected_call_trace = tx_result.call_trace().unwrap();
+            conn.transactions_dal()
+                .mark_txs_as_executed_in_l2_block(
+                    l2_block_number,
+                    slice::from_ref(&tx_result),
+                    1.into(),
+                    ProtocolVersionId::latest(),
+                    format,
+                    false,
+                )
+                .await
+                .unwrap();
+
+            let format_version: i16 =
+                sqlx::query_scalar("SELECT format_version FROM call_traces WHERE tx_hash = $1")
+                    .bind(tx_result.hash.as_bytes())
+                    .fetch_one(conn.conn())
+                    .await
+                    .unwrap();
+            assert_eq!(format_version, format as i16);
+
+            let call_trace = conn
+                .transactions_dal()
+                .get_call_trace(tx_result.hash, &CallTraceFilter::default())
+                .await
+                .unwrap()
+                .expect("no call trace");
+            assert_eq!(call_trace, expected_call_trace);
+        }
+    }
+
+    #[tokio::test]
+    async fn migrating_legacy_call_traces() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = connection_pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(&ProtocolVersion::default())
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .insert_l2_block(&create_l2_block_header(1))
+            .await
+            .unwrap();
+
+        let mut tx_results = vec![];
+        for i in 1..=3 {
+            let tx = mock_l2_transaction();
+            conn.transactions_dal()
+                .insert_transaction_l2(&tx, TransactionExecutionMetrics::default())
+                .await
+                .unwrap();
+            let mut tx_result = mock_execution_result(tx);
+            tx_result.call_traces.push(Call {
+                from: Address::from_low_u64_be(1),
+                to: Address::from_low_u64_be(2),
+                value: i.into(),
+                ..Call::default()
+            });
+            tx_results.push(tx_result);
+        }
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_l2_block(
+                L2BlockNumber(1),
+                &tx_results,
+                1.into(),
+                ProtocolVersionId::latest(),
+                CallTraceFormat::Legacy,
+                false,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            get_call_trace_format_versions(&mut conn).await,
+            [CallTrace::LEGACY_FORMAT_VERSION; 3]
+        );
+
+        let mut tx_hashes: Vec<_> = tx_results.iter().map(|tx_result| tx_result.hash).collect();
+        tx_hashes.sort_unstable();
+        let migrated = conn
+            .transactions_dal()
+            .migrate_legacy_call_traces(None, 2)
+            .await
+            .unwrap();
+        assert_eq!(migrated.migrated, tx_hashes[..2]);
+        assert!(migrated.skipped.is_empty());
+        assert_eq!(migrated.last_processed(), Some(tx_hashes[1]));
+        assert_eq!(
+            get_call_trace_format_versions(&mut conn).await,
+            [
+                CallTrace::FLAT_FORMAT_VERSION,
+                CallTrace::FLAT_FORMAT_VERSION,
+                CallTrace::LEGACY_FORMAT_VERSION
+            ]
+        );
+
+        let migrated = conn
+            .transactions_dal()
+            .migrate_legacy_call_traces(Some(tx_hashes[1]), 2)
+            .await
+            .unwrap();
+        assert_eq!(migrated.migrated, [tx_hashes[2]]);
+        let migrated = conn
+            .transactions_dal()
+            .migrate_legacy_call_traces(Some(tx_hashes[2]), 2)
+            .await
+            .unwrap();
+        assert_eq!(migrated.last_processed(), None);
+        let migrated = conn
+            .transactions_dal()
+            .migrate_legacy_call_traces(None, 2)
+            .await
+            .unwrap();
+        assert_eq!(migrated.last_processed(), None);
+
+        for tx_result in &tx_results {
+            let call_trace = conn
+                .transactions_dal()
+                .get_call_trace(tx_result.hash, &CallTraceFilter::default())
+                .await
+                .unwrap()
+                .expect("no call trace");
+            assert_eq!(call_trace, tx_result.call_trace().unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn corrupted_call_trace_is_an_error() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = connection_pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(&ProtocolVersion::default())
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .insert_l2_block(&create_l2_block_header(1))
+            .await
+            .unwrap();
+
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(&tx, TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        let mut tx_result = mock_execution_result(tx);
+        tx_result.call_traces.push(Call::default());
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_l2_block(
+                L2BlockNumber(1),
+                &[tx_result],
+                1.into(),
+                ProtocolVersionId::latest(),
+                CallTraceFormat::Legacy,
+                false,
+            )
+            .await
+            .unwrap();
+        sqlx::query("UPDATE call_traces SET call_trace = '\\x0102'")
+            .execute(conn.conn())
+            .await
+            .unwrap();
+        conn.transactions_dal()
+            .get_call_trace(tx_hash, &CallTraceFilter::default())
+            .await
+            .unwrap_err();
+
+        sqlx::query("UPDATE call_traces SET format_version = $1")
+            .bind(CallTrace::LEGACY_FORMAT_VERSION)
+            .execute(conn.conn())
+            .await
+            .unwrap();
+        conn.transactions_dal()
+            .get_call_trace(tx_hash, &CallTraceFilter::default())
+            .await
+            .unwrap_err();
+        // Undecodable traces are skipped by the migration and left as is.
+        let migrated = conn
+            .transactions_dal()
+            .migrate_legacy_call_traces(None, 10)
+            .await
+            .unwrap();
+        assert!(migrated.migrated.is_empty());
+        assert_eq!(migrated.skipped, [tx_hash]);
+        assert_eq!(migrated.last_processed(), Some(tx_hash));
+        assert_eq!(
+            get_call_trace_format_versions(&mut conn).await,
+            [CallTrace::LEGACY_FORMAT_VERSION]
+        );
     }
 
     #[tokio::test]
@@ -2232,6 +3112,7 @@ mod tests {
                 &[tx_result],
                 1.into(),
                 ProtocolVersionId::latest(),
+                CallTraceFormat::Legacy,
                 true,
             )
             .await
@@ -2244,4 +3125,42 @@ mod tests {
             .unwrap();
         assert_eq!(tx_from_db[0].hash, tx_hash);
     }
+
+    #[tokio::test]
+    async fn iterating_over_pending_l2_txs_with_equal_receipt_time() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = connection_pool.connection().await.unwrap();
+        let mut tx_hashes = vec![];
+        for _ in 0..3 {
+            let tx = mock_l2_transaction();
+            tx_hashes.push(tx.hash());
+            conn.transactions_dal()
+                .insert_transaction_l2(&tx, TransactionExecutionMetrics::default())
+                .await
+                .unwrap();
+        }
+        tx_hashes.sort_unstable();
+        // Emulate transactions received simultaneously.
+        sqlx::query("UPDATE transactions SET received_at = '2024-01-01 00:00:00'")
+            .execute(conn.conn())
+            .await
+            .unwrap();
+
+        let mut cursor = PendingL2TxCursor::received_after_ms(0);
+        let mut returned_hashes = vec![];
+        loop {
+            let (transactions, next_cursor) = conn
+                .transactions_dal()
+                .get_pending_l2_txs_received_after(cursor, 2)
+                .await
+                .unwrap();
+            if transactions.is_empty() {
+                assert_eq!(next_cursor, cursor);
+                break;
+            }
+            returned_hashes.extend(transactions.iter().map(Transaction::hash));
+            cursor = next_cursor;
+        }
+        assert_eq!(returned_hashes, tx_hashes);
+    }
 }
@@ -1,17 +1,20 @@
+use std::time::Duration;
+
 use sqlx::types::chrono::NaiveDateTime;
 use zksync_db_connection::{
     connection::Connection, error::DalResult, instrument::InstrumentExt, interpolate_query,
     match_query_as,
 };
 use zksync_types::{
-    api, api::TransactionReceipt, Address, L2BlockNumber, L2ChainId, Transaction,
+    api, api::TransactionReceipt, Address, L2BlockNumber, L2ChainId, PriorityOpId, Transaction,
     ACCOUNT_CODE_STORAGE_ADDRESS, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256, U256,
 };
+use zksync_utils::bigdecimal_to_u256;
 
 use crate::{
     models::storage_transaction::{
-        StorageApiTransaction, StorageTransaction, StorageTransactionDetails,
-        StorageTransactionReceipt,
+        StorageApiTransaction, StoragePriorityOpDetails, StorageTransaction,
+        StorageTransactionDetails, StorageTransactionReceipt,
     },
     Core, CoreDal,
 };
@@ -288,6 +291,200 @@ impl TransactionsWeb3Dal<'_, '_> {
         Ok(row.map(Into::into))
     }
 
+    /// Returns details for the priority operation with the specified serial ID.
+    pub async fn get_priority_op_details(
+        &mut self,
+        serial_id: PriorityOpId,
+    ) -> DalResult<Option<api::PriorityOpDetails>> {
+        let row = sqlx::query_as!(
+            StoragePriorityOpDetails,
+            r#"
+            SELECT
+                transactions.hash,
+                transactions.priority_op_id AS "priority_op_id!",
+                transactions.initiator_address,
+                transactions.l1_block_number,
+                transactions.received_at,
+                transactions.error,
+                transactions.miniblock_number,
+                transactions.l1_batch_number,
+                commit_tx.tx_hash AS "eth_commit_tx_hash?",
+                prove_tx.tx_hash AS "eth_prove_tx_hash?",
+                execute_tx.tx_hash AS "eth_execute_tx_hash?"
+            FROM
+                transactions
+                LEFT JOIN l1_batches ON l1_batches.number = transactions.l1_batch_number
+                LEFT JOIN eth_txs_history AS commit_tx ON (
+                    l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id
+                    AND commit_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS prove_tx ON (
+                    l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id
+                    AND prove_tx.confirmed_at IS NOT NULL
+                )
+                LEFT JOIN eth_txs_history AS execute_tx ON (
+                    l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id
+                    AND execute_tx.confirmed_at IS NOT NULL
+                )
+            WHERE
+                transactions.priority_op_id = $1
+            "#,
+            serial_id.0 as i64
+        )
+        .instrument("get_priority_op_details")
+        .with_arg("serial_id", &serial_id)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(Into::into))
+    }
+
+    /// Returns priority operations that are not included into any L2 block yet, ordered by their serial ID.
+    pub async fn get_pending_priority_ops(
+        &mut self,
+        limit: usize,
+    ) -> DalResult<Vec<api::PriorityOpDetails>> {
+        let rows = sqlx::query_as!(
+            StoragePriorityOpDetails,
+            r#"
+            SELECT
+                hash,
+                priority_op_id AS "priority_op_id!",
+                initiator_address,
+                l1_block_number,
+                received_at,
+                error,
+                miniblock_number,
+                l1_batch_number,
+                NULL::TEXT AS "eth_commit_tx_hash?",
+                NULL::TEXT AS "eth_prove_tx_hash?",
+                NULL::TEXT AS "eth_execute_tx_hash?"
+            FROM
+                transactions
+            WHERE
+                priority_op_id IS NOT NULL
+                AND miniblock_number IS NULL
+            ORDER BY
+                priority_op_id
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .instrument("get_pending_priority_ops")
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns the total number of pending priority operations and the time the oldest of them was received.
+    pub async fn get_pending_priority_ops_stats(
+        &mut self,
+    ) -> DalResult<(u64, Option<NaiveDateTime>)> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!",
+                MIN(received_at) AS "oldest_received_at"
+            FROM
+                transactions
+            WHERE
+                priority_op_id IS NOT NULL
+                AND miniblock_number IS NULL
+            "#
+        )
+        .instrument("get_pending_priority_ops_stats")
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok((row.count as u64, row.oldest_received_at))
+    }
+
+    /// Returns the number of pending priority operations with serial IDs less than the specified one.
+    pub async fn get_pending_priority_ops_count_before(
+        &mut self,
+        serial_id: PriorityOpId,
+    ) -> DalResult<u64> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!"
+            FROM
+                transactions
+            WHERE
+                priority_op_id < $1
+                AND miniblock_number IS NULL
+            "#,
+            serial_id.0 as i64
+        )
+        .instrument("get_pending_priority_ops_count_before")
+        .with_arg("serial_id", &serial_id)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row.count as u64)
+    }
+
+    /// Returns the serial ID following the ID of the last priority operation included into an L2 block.
+    /// Returns `None` if there are no included priority operations (e.g., if the node was recovered from a snapshot).
+    pub async fn get_next_expected_priority_op_id(&mut self) -> DalResult<Option<PriorityOpId>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(priority_op_id) AS "op_id"
+            FROM
+                transactions
+            WHERE
+                priority_op_id IS NOT NULL
+                AND miniblock_number IS NOT NULL
+            "#
+        )
+        .instrument("get_next_expected_priority_op_id")
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(row.op_id.map(|op_id| PriorityOpId(op_id as u64 + 1)))
+    }
+
+    /// Returns delays between receiving and including the latest `limit` priority operations into L2 blocks,
+    /// from the newest to the oldest operation.
+    pub async fn get_recent_priority_op_inclusion_delays(
+        &mut self,
+        limit: usize,
+    ) -> DalResult<Vec<Duration>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                transactions.received_at,
+                miniblocks.timestamp
+            FROM
+                transactions
+                INNER JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+            WHERE
+                transactions.priority_op_id IS NOT NULL
+            ORDER BY
+                transactions.priority_op_id DESC
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .instrument("get_recent_priority_op_inclusion_delays")
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        let delays = rows.into_iter().map(|row| {
+            let received_at_ms = row.received_at.timestamp_millis();
+            let included_at_ms = row.timestamp * 1_000;
+            // L2 block timestamps have second granularity, so the difference may be slightly negative.
+            Duration::from_millis(included_at_ms.saturating_sub(received_at_ms).max(0) as u64)
+        });
+        Ok(delays.collect())
+    }
+
     /// Returns hashes of txs which were received after `from_timestamp` and the time of receiving the last tx.
     pub async fn get_pending_txs_hashes_after(
         &mut self,
@@ -377,6 +574,95 @@ impl TransactionsWeb3Dal<'_, '_> {
         Ok(U256::from(pending_nonce))
     }
 
+    /// Returns pending (i.e., not rejected and not included into an L2 block) transactions for the specified account
+    /// starting from `committed_next_nonce`, ordered by nonce.
+    pub async fn get_pending_transactions_by_initiator_account(
+        &mut self,
+        initiator_address: Address,
+        committed_next_nonce: u64,
+    ) -> DalResult<Vec<api::PendingTransactionInfo>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                nonce AS "nonce!",
+                max_fee_per_gas,
+                max_priority_fee_per_gas
+            FROM
+                transactions
+            WHERE
+                initiator_address = $1
+                AND nonce >= $2
+                AND is_priority = FALSE
+                AND miniblock_number IS NULL
+                AND error IS NULL
+            ORDER BY
+                nonce
+            "#,
+            initiator_address.as_bytes(),
+            committed_next_nonce as i64
+        )
+        .instrument("get_pending_transactions_by_initiator_account")
+        .with_arg("initiator_address", &initiator_address)
+        .with_arg("committed_next_nonce", &committed_next_nonce)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| api::PendingTransactionInfo {
+                hash: H256::from_slice(&row.hash),
+                nonce: (row.nonce as u64).into(),
+                max_fee_per_gas: row
+                    .max_fee_per_gas
+                    .map(bigdecimal_to_u256)
+                    .unwrap_or_default(),
+                max_priority_fee_per_gas: row
+                    .max_priority_fee_per_gas
+                    .map(bigdecimal_to_u256)
+                    .unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// Returns value transfers performed inside the specified transaction, in the execution order.
+    /// Returns an empty list if the transaction is not executed, or if its call trace was not saved.
+    pub async fn get_internal_transfers(
+        &mut self,
+        tx_hash: H256,
+    ) -> DalResult<Vec<api::InternalTransfer>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                internal_transfers.from_address,
+                internal_transfers.to_address,
+                internal_transfers.value
+            FROM
+                internal_transfers
+                INNER JOIN transactions ON transactions.hash = internal_transfers.tx_hash
+            WHERE
+                internal_transfers.tx_hash = $1
+                AND transactions.miniblock_number = internal_transfers.miniblock_number
+            ORDER BY
+                internal_transfers.transfer_index
+            "#,
+            tx_hash.as_bytes()
+        )
+        .instrument("get_internal_transfers")
+        .with_arg("tx_hash", &tx_hash)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| api::InternalTransfer {
+                from: Address::from_slice(&row.from_address),
+                to: Address::from_slice(&row.to_address),
+                value: bigdecimal_to_u256(row.value),
+            })
+            .collect())
+    }
+
     /// Returns the server transactions (not API ones) from a certain L2 block.
     /// Returns an empty list if the L2 block doesn't exist.
     pub async fn get_raw_l2_block_transactions(
@@ -412,12 +698,20 @@ mod tests {
     use std::collections::HashMap;
 
     use zksync_types::{
-        fee::TransactionExecutionMetrics, l2::L2Tx, Nonce, ProtocolVersion, ProtocolVersionId,
+        fee::TransactionExecutionMetrics,
+        l1::L1Tx,
+        l2::L2Tx,
+        tx::{tx_execution_info::TxExecutionStatus, ExecutionMetrics, TransactionExecutionResult},
+        vm_trace::Call,
+        L1BlockNumber, Nonce, ProtocolVersion, ProtocolVersionId,
     };
 
     use super::*;
     use crate::{
-        tests::{create_l2_block_header, mock_execution_result, mock_l2_transaction},
+        tests::{
+            create_l2_block_header, mock_execution_result, mock_l1_execute, mock_l2_transaction,
+        },
+        transactions_dal::CallTraceFormat,
         ConnectionPool, Core, CoreDal,
     };
 
@@ -455,6 +749,7 @@ mod tests {
                 &tx_results,
                 U256::from(1),
                 ProtocolVersionId::latest(),
+                CallTraceFormat::Legacy,
                 false,
             )
             .await
@@ -632,6 +927,7 @@ mod tests {
                 &executed_txs,
                 1.into(),
                 ProtocolVersionId::latest(),
+                CallTraceFormat::Legacy,
                 false,
             )
             .await
@@ -674,4 +970,179 @@ mod tests {
             .unwrap();
         assert_eq!(next_nonce, 2.into());
     }
+
+    #[tokio::test]
+    async fn getting_internal_transfers() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = connection_pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(&ProtocolVersion::default())
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .insert_l2_block(&create_l2_block_header(1))
+            .await
+            .unwrap();
+
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(&tx, TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        let transfers = conn
+            .transactions_web3_dal()
+            .get_internal_transfers(tx_hash)
+            .await
+            .unwrap();
+        assert!(transfers.is_empty());
+
+        let mut tx_result = mock_execution_result(tx);
+        tx_result.call_traces.push(Call {
+            from: Address::repeat_byte(1),
+            to: Address::repeat_byte(2),
+            value: 100.into(),
+            calls: vec![Call {
+                from: Address::repeat_byte(2),
+                to: Address::repeat_byte(3),
+                value: 42.into(),
+                ..Call::default()
+            }],
+            ..Call::default()
+        });
+        let expected_transfers = tx_result.call_trace().unwrap().internal_transfers();
+        assert_eq!(expected_transfers.len(), 2);
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_l2_block(
+                L2BlockNumber(1),
+                &[tx_result],
+                1.into(),
+                ProtocolVersionId::latest(),
+                CallTraceFormat::Legacy,
+                false,
+            )
+            .await
+            .unwrap();
+
+        let transfers = conn
+            .transactions_web3_dal()
+            .get_internal_transfers(tx_hash)
+            .await
+            .unwrap();
+        assert_eq!(transfers, expected_transfers);
+    }
+
+    fn mock_priority_op(serial_id: u64) -> L1Tx {
+        let mut tx = mock_l1_execute();
+        tx.common_data.serial_id = PriorityOpId(serial_id);
+        tx.common_data.canonical_tx_hash = H256::from_low_u64_be(serial_id + 1);
+        tx
+    }
+
+    fn mock_priority_op_execution_result(tx: L1Tx) -> TransactionExecutionResult {
+        TransactionExecutionResult {
+            hash: tx.hash(),
+            transaction: tx.into(),
+            execution_info: ExecutionMetrics::default(),
+            execution_status: TxExecutionStatus::Success,
+            refunded_gas: 0,
+            operator_suggested_refund: 0,
+            compressed_bytecodes: vec![],
+            call_traces: vec![],
+            revert_reason: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn getting_priority_queue_info() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = connection_pool.connection().await.unwrap();
+        let ops: Vec<_> = (0..3).map(mock_priority_op).collect();
+        for op in &ops {
+            conn.transactions_dal()
+                .insert_transaction_l1(op, L1BlockNumber(1))
+                .await
+                .unwrap();
+        }
+
+        let pending_ops = conn
+            .transactions_web3_dal()
+            .get_pending_priority_ops(10)
+            .await
+            .unwrap();
+        let pending_ids: Vec<_> = pending_ops.iter().map(|op| op.serial_id).collect();
+        assert_eq!(
+            pending_ids,
+            [PriorityOpId(0), PriorityOpId(1), PriorityOpId(2)]
+        );
+        assert!(pending_ops
+            .iter()
+            .all(|op| op.status == api::PriorityOpStatus::Pending));
+        let (pending_count, oldest_received_at) = conn
+            .transactions_web3_dal()
+            .get_pending_priority_ops_stats()
+            .await
+            .unwrap();
+        assert_eq!(pending_count, 3);
+        assert!(oldest_received_at.is_some());
+        let next_expected_id = conn
+            .transactions_web3_dal()
+            .get_next_expected_priority_op_id()
+            .await
+            .unwrap();
+        assert_eq!(next_expected_id, None);
+
+        conn.blocks_dal()
+            .insert_l2_block(&create_l2_block_header(1))
+            .await
+            .unwrap();
+        let tx_results = [mock_priority_op_execution_result(ops[0].clone())];
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_l2_block(
+                L2BlockNumber(1),
+                &tx_results,
+                U256::from(1),
+                ProtocolVersionId::latest(),
+                CallTraceFormat::Legacy,
+                false,
+            )
+            .await
+            .unwrap();
+
+        let pending_ops = conn
+            .transactions_web3_dal()
+            .get_pending_priority_ops(1)
+            .await
+            .unwrap();
+        assert_eq!(pending_ops.len(), 1);
+        assert_eq!(pending_ops[0].serial_id, PriorityOpId(1));
+        let next_expected_id = conn
+            .transactions_web3_dal()
+            .get_next_expected_priority_op_id()
+            .await
+            .unwrap();
+        assert_eq!(next_expected_id, Some(PriorityOpId(1)));
+        let count_before = conn
+            .transactions_web3_dal()
+            .get_pending_priority_ops_count_before(PriorityOpId(2))
+            .await
+            .unwrap();
+        assert_eq!(count_before, 1);
+
+        let included_op = conn
+            .transactions_web3_dal()
+            .get_priority_op_details(PriorityOpId(0))
+            .await
+            .unwrap()
+            .expect("no priority op");
+        assert_eq!(included_op.tx_hash, ops[0].hash());
+        assert_eq!(included_op.status, api::PriorityOpStatus::Included);
+        assert_eq!(included_op.l2_block_number, Some(L2BlockNumber(1)));
+        let delays = conn
+            .transactions_web3_dal()
+            .get_recent_priority_op_inclusion_delays(10)
+            .await
+            .unwrap();
+        assert_eq!(delays.len(), 1);
+    }
 }
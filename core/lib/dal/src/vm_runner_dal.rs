@@ -1,7 +1,13 @@
-use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
-use zksync_types::L1BatchNumber;
+use std::time::Duration;
 
-use crate::Core;
+use sqlx::types::chrono::NaiveTime;
+use zksync_db_connection::{
+    connection::Connection, error::DalResult, instrument::InstrumentExt,
+    utils::duration_to_naive_time,
+};
+use zksync_types::{L1BatchNumber, L2BlockNumber, ProtocolVersionId};
+
+use crate::{models::parse_protocol_version, watermarks_dal::WatermarkComponent, Core};
 
 #[derive(Debug)]
 pub struct VmRunnerDal<'c, 'a> {
@@ -9,28 +15,47 @@ pub struct VmRunnerDal<'c, 'a> {
 }
 
 impl VmRunnerDal<'_, '_> {
+    /// Returns the last L1 batch processed by the protective reads writer according to its watermark
+    /// (see [`WatermarksDal`](crate::watermarks_dal::WatermarksDal)).
     pub async fn get_protective_reads_latest_processed_batch(
         &mut self,
         default_batch: L1BatchNumber,
+    ) -> DalResult<L1BatchNumber> {
+        self.get_latest_processed_batch_by_watermark(
+            WatermarkComponent::ProtectiveReads,
+            default_batch,
+        )
+        .await
+    }
+
+    async fn get_latest_processed_batch_by_watermark(
+        &mut self,
+        component: WatermarkComponent,
+        default_batch: L1BatchNumber,
     ) -> DalResult<L1BatchNumber> {
         let row = sqlx::query!(
             r#"
             SELECT
                 COALESCE(MAX(l1_batch_number), $1) AS "last_processed_l1_batch!"
             FROM
-                vm_runner_protective_reads
+                component_watermarks
+            WHERE
+                component = $2
             "#,
-            default_batch.0 as i32
+            i64::from(default_batch.0),
+            component.as_str()
         )
-        .instrument("get_protective_reads_latest_processed_batch")
+        .instrument("get_latest_processed_batch_by_watermark")
+        .with_arg("component", &component.as_str())
         .report_latency()
         .fetch_one(self.storage)
         .await?;
         Ok(L1BatchNumber(row.last_processed_l1_batch as u32))
     }
 
-    pub async fn get_protective_reads_last_ready_batch(
+    async fn get_last_ready_batch_by_watermark(
         &mut self,
+        component: WatermarkComponent,
         window_size: u32,
     ) -> DalResult<L1BatchNumber> {
         let row = sqlx::query!(
@@ -46,7 +71,9 @@ impl VmRunnerDal<'_, '_> {
                     SELECT
                         COALESCE(MAX(l1_batch_number), 0) + $1 AS "last_ready_batch"
                     FROM
-                        vm_runner_protective_reads
+                        component_watermarks
+                    WHERE
+                        component = $2
                 )
             SELECT
                 LEAST(last_batch, last_ready_batch) AS "last_ready_batch!"
@@ -54,32 +81,620 @@ impl VmRunnerDal<'_, '_> {
                 available_batches
                 FULL JOIN processed_batches ON TRUE
             "#,
-            window_size as i32
+            i64::from(window_size),
+            component.as_str()
         )
-        .instrument("get_protective_reads_last_ready_batch")
+        .instrument("get_last_ready_batch_by_watermark")
+        .with_arg("component", &component.as_str())
         .report_latency()
         .fetch_one(self.storage)
         .await?;
         Ok(L1BatchNumber(row.last_ready_batch as u32))
     }
 
-    pub async fn mark_protective_reads_batch_as_completed(
+    pub async fn get_protective_reads_last_ready_batch(
+        &mut self,
+        window_size: u32,
+    ) -> DalResult<L1BatchNumber> {
+        self.get_last_ready_batch_by_watermark(WatermarkComponent::ProtectiveReads, window_size)
+            .await
+    }
+
+    pub async fn get_bwip_latest_processed_batch(
+        &mut self,
+        default_batch: L1BatchNumber,
+    ) -> DalResult<L1BatchNumber> {
+        self.get_latest_processed_batch_by_watermark(
+            WatermarkComponent::BasicWitnessInputProducer,
+            default_batch,
+        )
+        .await
+    }
+
+    pub async fn get_bwip_last_ready_batch(
+        &mut self,
+        window_size: u32,
+    ) -> DalResult<L1BatchNumber> {
+        self.get_last_ready_batch_by_watermark(
+            WatermarkComponent::BasicWitnessInputProducer,
+            window_size,
+        )
+        .await
+    }
+
+    /// Marks an L1 batch as processed by the basic witness input producer. `time_taken` is the time it took
+    /// to produce witness inputs for the batch, if known.
+    pub async fn mark_bwip_batch_as_completed(
         &mut self,
         l1_batch_number: L1BatchNumber,
+        time_taken: Option<Duration>,
     ) -> DalResult<()> {
         sqlx::query!(
             r#"
             INSERT INTO
-                vm_runner_protective_reads (l1_batch_number, created_at, updated_at)
+                vm_runner_bwip (l1_batch_number, created_at, updated_at, time_taken)
             VALUES
-                ($1, NOW(), NOW())
+                ($1, NOW(), NOW(), $2)
             "#,
             i64::from(l1_batch_number.0),
+            time_taken.map(duration_to_naive_time)
         )
-        .instrument("mark_protective_reads_batch_as_completed")
+        .instrument("mark_bwip_batch_as_completed")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("time_taken", &time_taken)
         .report_latency()
         .execute(self.storage)
         .await?;
         Ok(())
     }
+
+    /// Returns the time it took to produce basic witness inputs for the specified L1 batch. Returns `None`
+    /// if the batch is not processed yet, or if the time is unknown.
+    pub async fn get_bwip_time_taken(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> DalResult<Option<Duration>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                time_taken
+            FROM
+                vm_runner_bwip
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("get_bwip_time_taken")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.and_then(|row| row.time_taken).and_then(|time| {
+            (time - NaiveTime::from_hms_opt(0, 0, 0).unwrap())
+                .to_std()
+                .ok()
+        }))
+    }
+
+    /// Returns the latest L1 batch processed by the commitment recomputer so that all batches in
+    /// `(first_processed_batch, latest]` are processed. The returned batch is in the
+    /// `first_processed_batch..=last_batch` range.
+    pub async fn get_commitment_recomputer_latest_processed_batch(
+        &mut self,
+        first_processed_batch: L1BatchNumber,
+        last_batch: L1BatchNumber,
+    ) -> DalResult<L1BatchNumber> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COALESCE(
+                    (
+                        SELECT
+                            MIN(series.number)
+                        FROM
+                            GENERATE_SERIES($1 + 1, $2) AS series (number)
+                        WHERE
+                            NOT EXISTS (
+                                SELECT
+                                    1
+                                FROM
+                                    vm_runner_commitment_recomputer
+                                WHERE
+                                    l1_batch_number = series.number
+                            )
+                    ) - 1,
+                    $2
+                ) AS "latest_processed_batch!"
+            "#,
+            i64::from(first_processed_batch.0),
+            i64::from(last_batch.0)
+        )
+        .instrument("get_commitment_recomputer_latest_processed_batch")
+        .with_arg("first_processed_batch", &first_processed_batch)
+        .with_arg("last_batch", &last_batch)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(L1BatchNumber(row.latest_processed_batch as u32))
+    }
+
+    pub async fn mark_commitment_recomputer_batch_as_completed(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                vm_runner_commitment_recomputer (l1_batch_number, created_at, updated_at)
+            VALUES
+                ($1, NOW(), NOW())
+            ON CONFLICT (l1_batch_number) DO
+            UPDATE
+            SET
+                updated_at = NOW()
+            "#,
+            i64::from(l1_batch_number.0),
+        )
+        .instrument("mark_commitment_recomputer_batch_as_completed")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes all progress of the commitment recomputer, so that the batches are processed again.
+    pub async fn reset_commitment_recomputer_progress(&mut self) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM vm_runner_commitment_recomputer
+            "#
+        )
+        .instrument("reset_commitment_recomputer_progress")
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the last L1 batch considered by the upgrade canary according to its watermark.
+    pub async fn get_upgrade_canary_latest_processed_batch(
+        &mut self,
+        default_batch: L1BatchNumber,
+    ) -> DalResult<L1BatchNumber> {
+        self.get_latest_processed_batch_by_watermark(
+            WatermarkComponent::UpgradeCanary,
+            default_batch,
+        )
+        .await
+    }
+
+    /// Returns the protocol version, under which the upgrade canary shadow-executes the specified L1 batch,
+    /// or `None` if the batch isn't selected for shadow execution.
+    pub async fn get_upgrade_canary_batch_version(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> DalResult<Option<ProtocolVersionId>> {
+        sqlx::query!(
+            r#"
+            SELECT
+                protocol_version
+            FROM
+                vm_runner_upgrade_canary
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .try_map(|row| parse_protocol_version(row.protocol_version))
+        .instrument("get_upgrade_canary_batch_version")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
+        .await
+    }
+
+    /// Selects an L1 batch for shadow execution by the upgrade canary under the specified protocol version,
+    /// provided that less than `max_batches` batches are selected for this version. Returns whether the batch
+    /// was selected.
+    pub async fn select_upgrade_canary_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        protocol_version: ProtocolVersionId,
+        max_batches: u32,
+    ) -> DalResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO
+                vm_runner_upgrade_canary (l1_batch_number, protocol_version, created_at, updated_at)
+            SELECT
+                $1,
+                $2,
+                NOW(),
+                NOW()
+            WHERE
+                (
+                    SELECT
+                        COUNT(*)
+                    FROM
+                        vm_runner_upgrade_canary
+                    WHERE
+                        protocol_version = $2
+                ) < $3
+            ON CONFLICT (l1_batch_number) DO NOTHING
+            "#,
+            i64::from(l1_batch_number.0),
+            protocol_version as i32,
+            i64::from(max_batches)
+        )
+        .instrument("select_upgrade_canary_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("protocol_version", &protocol_version)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Records the last L2 block fully executed and handled by the specified VM runner within an L1 batch.
+    pub async fn save_l2_block_checkpoint(
+        &mut self,
+        runner_name: &str,
+        l1_batch_number: L1BatchNumber,
+        l2_block_number: L2BlockNumber,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                vm_runner_checkpoints (
+                    runner_name,
+                    l1_batch_number,
+                    last_executed_l2_block,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, NOW(), NOW())
+            ON CONFLICT (runner_name, l1_batch_number) DO
+            UPDATE
+            SET
+                last_executed_l2_block = excluded.last_executed_l2_block,
+                updated_at = NOW()
+            "#,
+            runner_name,
+            i64::from(l1_batch_number.0),
+            i64::from(l2_block_number.0)
+        )
+        .instrument("save_l2_block_checkpoint")
+        .with_arg("runner_name", &runner_name)
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("l2_block_number", &l2_block_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Records that the output of an L1 batch was fully handled by the specified VM runner, including
+    /// the batch-level output. `last_l2_block_number` is the last (normally, fictive) L2 block in the batch.
+    pub async fn mark_l1_batch_as_handled(
+        &mut self,
+        runner_name: &str,
+        l1_batch_number: L1BatchNumber,
+        last_l2_block_number: L2BlockNumber,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                vm_runner_checkpoints (
+                    runner_name,
+                    l1_batch_number,
+                    last_executed_l2_block,
+                    is_l1_batch_handled,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, TRUE, NOW(), NOW())
+            ON CONFLICT (runner_name, l1_batch_number) DO
+            UPDATE
+            SET
+                last_executed_l2_block = excluded.last_executed_l2_block,
+                is_l1_batch_handled = TRUE,
+                updated_at = NOW()
+            "#,
+            runner_name,
+            i64::from(l1_batch_number.0),
+            i64::from(last_l2_block_number.0)
+        )
+        .instrument("mark_l1_batch_as_handled")
+        .with_arg("runner_name", &runner_name)
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("last_l2_block_number", &last_l2_block_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the last L2 block fully executed and handled by the specified VM runner within an L1 batch,
+    /// together with a flag whether the entire batch was handled (see [`Self::mark_l1_batch_as_handled()`]).
+    pub async fn get_l2_block_checkpoint(
+        &mut self,
+        runner_name: &str,
+        l1_batch_number: L1BatchNumber,
+    ) -> DalResult<Option<(L2BlockNumber, bool)>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                last_executed_l2_block,
+                is_l1_batch_handled
+            FROM
+                vm_runner_checkpoints
+            WHERE
+                runner_name = $1
+                AND l1_batch_number = $2
+            "#,
+            runner_name,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("get_l2_block_checkpoint")
+        .with_arg("runner_name", &runner_name)
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map(|row| {
+            (
+                L2BlockNumber(row.last_executed_l2_block as u32),
+                row.is_l1_batch_handled,
+            )
+        }))
+    }
+
+    /// Removes checkpoints of the specified VM runner for all L1 batches up to and including the specified one.
+    pub async fn remove_l2_block_checkpoints(
+        &mut self,
+        runner_name: &str,
+        last_l1_batch_number: L1BatchNumber,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM vm_runner_checkpoints
+            WHERE
+                runner_name = $1
+                AND l1_batch_number <= $2
+            "#,
+            runner_name,
+            i64::from(last_l1_batch_number.0)
+        )
+        .instrument("remove_l2_block_checkpoints")
+        .with_arg("runner_name", &runner_name)
+        .with_arg("last_l1_batch_number", &last_l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    #[tokio::test]
+    async fn l2_block_checkpoints_lifecycle() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.vm_runner_dal();
+        assert_eq!(
+            dal.get_l2_block_checkpoint("test", L1BatchNumber(1))
+                .await
+                .unwrap(),
+            None
+        );
+
+        for (l1_batch_number, l2_block_number) in [(1, 2), (1, 3), (2, 5)] {
+            dal.save_l2_block_checkpoint(
+                "test",
+                L1BatchNumber(l1_batch_number),
+                L2BlockNumber(l2_block_number),
+            )
+            .await
+            .unwrap();
+        }
+        dal.save_l2_block_checkpoint("other", L1BatchNumber(1), L2BlockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.get_l2_block_checkpoint("test", L1BatchNumber(1))
+                .await
+                .unwrap(),
+            Some((L2BlockNumber(3), false))
+        );
+
+        dal.mark_l1_batch_as_handled("test", L1BatchNumber(1), L2BlockNumber(4))
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.get_l2_block_checkpoint("test", L1BatchNumber(1))
+                .await
+                .unwrap(),
+            Some((L2BlockNumber(4), true))
+        );
+
+        dal.remove_l2_block_checkpoints("test", L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.get_l2_block_checkpoint("test", L1BatchNumber(1))
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            dal.get_l2_block_checkpoint("test", L1BatchNumber(2))
+                .await
+                .unwrap(),
+            Some((L2BlockNumber(5), false))
+        );
+        assert_eq!(
+            dal.get_l2_block_checkpoint("other", L1BatchNumber(1))
+                .await
+                .unwrap(),
+            Some((L2BlockNumber(1), false))
+        );
+    }
+
+    #[tokio::test]
+    async fn bwip_batches_lifecycle() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.vm_runner_dal();
+        assert_eq!(
+            dal.get_bwip_latest_processed_batch(L1BatchNumber(0))
+                .await
+                .unwrap(),
+            L1BatchNumber(0)
+        );
+        assert_eq!(
+            dal.get_bwip_time_taken(L1BatchNumber(1)).await.unwrap(),
+            None
+        );
+
+        dal.mark_bwip_batch_as_completed(L1BatchNumber(1), Some(Duration::from_secs(75)))
+            .await
+            .unwrap();
+        dal.mark_bwip_batch_as_completed(L1BatchNumber(2), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.get_bwip_latest_processed_batch(L1BatchNumber(0))
+                .await
+                .unwrap(),
+            L1BatchNumber(2)
+        );
+        assert_eq!(
+            dal.get_bwip_time_taken(L1BatchNumber(1)).await.unwrap(),
+            Some(Duration::from_secs(75))
+        );
+        assert_eq!(
+            dal.get_bwip_time_taken(L1BatchNumber(2)).await.unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn selecting_upgrade_canary_batches() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.vm_runner_dal();
+        let version = ProtocolVersionId::next();
+        assert_eq!(
+            dal.get_upgrade_canary_batch_version(L1BatchNumber(1))
+                .await
+                .unwrap(),
+            None
+        );
+
+        for number in [1, 2] {
+            let is_selected = dal
+                .select_upgrade_canary_batch(L1BatchNumber(number), version, 2)
+                .await
+                .unwrap();
+            assert!(is_selected);
+        }
+        // The batch limit is reached.
+        let is_selected = dal
+            .select_upgrade_canary_batch(L1BatchNumber(3), version, 2)
+            .await
+            .unwrap();
+        assert!(!is_selected);
+        // Selecting an already selected batch is a no-op.
+        let is_selected = dal
+            .select_upgrade_canary_batch(L1BatchNumber(1), ProtocolVersionId::latest(), 2)
+            .await
+            .unwrap();
+        assert!(!is_selected);
+
+        assert_eq!(
+            dal.get_upgrade_canary_batch_version(L1BatchNumber(1))
+                .await
+                .unwrap(),
+            Some(version)
+        );
+        assert_eq!(
+            dal.get_upgrade_canary_batch_version(L1BatchNumber(3))
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn commitment_recomputer_progress_lifecycle() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.vm_runner_dal();
+        assert_eq!(
+            dal.get_commitment_recomputer_latest_processed_batch(
+                L1BatchNumber(0),
+                L1BatchNumber(5)
+            )
+            .await
+            .unwrap(),
+            L1BatchNumber(0)
+        );
+
+        for number in [1, 2, 4] {
+            dal.mark_commitment_recomputer_batch_as_completed(L1BatchNumber(number))
+                .await
+                .unwrap();
+        }
+        // Marking a batch as completed again is a no-op.
+        dal.mark_commitment_recomputer_batch_as_completed(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.get_commitment_recomputer_latest_processed_batch(
+                L1BatchNumber(0),
+                L1BatchNumber(5)
+            )
+            .await
+            .unwrap(),
+            L1BatchNumber(2)
+        );
+        // Batches processed outside the range must not be taken into account.
+        assert_eq!(
+            dal.get_commitment_recomputer_latest_processed_batch(
+                L1BatchNumber(2),
+                L1BatchNumber(5)
+            )
+            .await
+            .unwrap(),
+            L1BatchNumber(2)
+        );
+        assert_eq!(
+            dal.get_commitment_recomputer_latest_processed_batch(
+                L1BatchNumber(3),
+                L1BatchNumber(5)
+            )
+            .await
+            .unwrap(),
+            L1BatchNumber(4)
+        );
+        assert_eq!(
+            dal.get_commitment_recomputer_latest_processed_batch(
+                L1BatchNumber(0),
+                L1BatchNumber(2)
+            )
+            .await
+            .unwrap(),
+            L1BatchNumber(2)
+        );
+
+        dal.reset_commitment_recomputer_progress().await.unwrap();
+        assert_eq!(
+            dal.get_commitment_recomputer_latest_processed_batch(
+                L1BatchNumber(0),
+                L1BatchNumber(5)
+            )
+            .await
+            .unwrap(),
+            L1BatchNumber(0)
+        );
+    }
 }
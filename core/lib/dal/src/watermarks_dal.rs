@@ -0,0 +1,317 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use serde::Deserialize;
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+use zksync_types::L1BatchNumber;
+
+use crate::Core;
+
+/// Component publishing its progress to the watermark registry (see [`WatermarksDal`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatermarkComponent {
+    /// Metadata calculator (Merkle tree). The watermark is the last L1 batch with computed tree data.
+    Tree,
+    /// Commitment generator. The watermark is the last L1 batch with generated commitment artifacts.
+    Commitments,
+    /// Protective reads writer. The watermark is the last L1 batch with persisted protective reads.
+    ProtectiveReads,
+    /// Basic witness input producer. The watermark is the last L1 batch with produced witness inputs.
+    BasicWitnessInputProducer,
+    /// ETH sender. The watermark is the last L1 batch with a proof confirmed on L1.
+    Proofs,
+    /// Upgrade canary. The watermark is the last L1 batch considered for shadow execution under
+    /// the next protocol version.
+    UpgradeCanary,
+}
+
+impl WatermarkComponent {
+    /// All known components.
+    pub const ALL: [Self; 6] = [
+        Self::Tree,
+        Self::Commitments,
+        Self::ProtectiveReads,
+        Self::BasicWitnessInputProducer,
+        Self::Proofs,
+        Self::UpgradeCanary,
+    ];
+
+    /// Returns the name of the component as stored in Postgres.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Tree => "tree",
+            Self::Commitments => "commitments",
+            Self::ProtectiveReads => "protective_reads",
+            Self::BasicWitnessInputProducer => "basic_witness_input_producer",
+            Self::Proofs => "proofs",
+            Self::UpgradeCanary => "upgrade_canary",
+        }
+    }
+}
+
+impl fmt::Display for WatermarkComponent {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WatermarkComponent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|component| component.as_str() == s)
+            .ok_or_else(|| format!("unknown watermark component: `{s}`"))
+    }
+}
+
+/// DAL for the watermark registry: a central place where components publish their progress in terms of L1 batches,
+/// so that other components (e.g., pruning) can query it without relying on component-specific tables.
+///
+/// A watermark for a component means that the component has processed *all* L1 batches up to and including
+/// the watermark. Watermarks never decrease, except on rollbacks.
+#[derive(Debug)]
+pub struct WatermarksDal<'c, 'a> {
+    pub(crate) storage: &'c mut Connection<'a, Core>,
+}
+
+impl WatermarksDal<'_, '_> {
+    /// Publishes progress of the specified component. If the component already has a greater watermark,
+    /// it is left as is.
+    pub async fn set_watermark(
+        &mut self,
+        component: WatermarkComponent,
+        l1_batch_number: L1BatchNumber,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                component_watermarks (component, l1_batch_number, created_at, updated_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            ON CONFLICT (component) DO
+            UPDATE
+            SET
+                l1_batch_number = GREATEST(component_watermarks.l1_batch_number, $2),
+                updated_at = NOW()
+            "#,
+            component.as_str(),
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("set_watermark")
+        .with_arg("component", &component.as_str())
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the watermark of the specified component, or `None` if the component hasn't published its progress yet.
+    pub async fn get_watermark(
+        &mut self,
+        component: WatermarkComponent,
+    ) -> DalResult<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number
+            FROM
+                component_watermarks
+            WHERE
+                component = $1
+            "#,
+            component.as_str()
+        )
+        .instrument("get_watermark")
+        .with_arg("component", &component.as_str())
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map(|row| L1BatchNumber(row.l1_batch_number as u32)))
+    }
+
+    /// Returns watermarks of all components that have published their progress. Watermarks of unknown components
+    /// (e.g., ones published by a newer server version) are ignored.
+    pub async fn get_all_watermarks(
+        &mut self,
+    ) -> DalResult<HashMap<WatermarkComponent, L1BatchNumber>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                component,
+                l1_batch_number
+            FROM
+                component_watermarks
+            "#
+        )
+        .instrument("get_all_watermarks")
+        .fetch_all(self.storage)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let component = row.component.parse().ok()?;
+                Some((component, L1BatchNumber(row.l1_batch_number as u32)))
+            })
+            .collect())
+    }
+
+    /// Returns the minimum watermark among the specified components, i.e., the last L1 batch processed by all of them.
+    /// Returns `None` if any of the components hasn't published its progress yet.
+    pub async fn get_min_watermark(
+        &mut self,
+        components: &[WatermarkComponent],
+    ) -> DalResult<Option<L1BatchNumber>> {
+        let watermarks = self.get_all_watermarks().await?;
+        let mut min_watermark: Option<L1BatchNumber> = None;
+        for component in components {
+            let Some(&watermark) = watermarks.get(component) else {
+                return Ok(None);
+            };
+            min_watermark = Some(min_watermark.map_or(watermark, |min| min.min(watermark)));
+        }
+        Ok(min_watermark)
+    }
+
+    /// Removes the watermark of the specified component, e.g., if the component is decommissioned. Otherwise, its stale
+    /// watermark would hold back [`Self::get_min_watermark()`] for components lists including it.
+    pub async fn remove_watermark(&mut self, component: WatermarkComponent) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM component_watermarks
+            WHERE
+                component = $1
+            "#,
+            component.as_str()
+        )
+        .instrument("remove_watermark")
+        .with_arg("component", &component.as_str())
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Lowers all watermarks exceeding the specified L1 batch to it. Used when rolling back L1 batches.
+    pub async fn roll_back_watermarks(
+        &mut self,
+        last_l1_batch_to_keep: L1BatchNumber,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE component_watermarks
+            SET
+                l1_batch_number = $1,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number > $1
+            "#,
+            i64::from(last_l1_batch_to_keep.0)
+        )
+        .instrument("roll_back_watermarks")
+        .with_arg("last_l1_batch_to_keep", &last_l1_batch_to_keep)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    #[test]
+    fn watermark_component_names_roundtrip() {
+        for component in WatermarkComponent::ALL {
+            assert_eq!(
+                component.as_str().parse::<WatermarkComponent>(),
+                Ok(component)
+            );
+        }
+        "unknown".parse::<WatermarkComponent>().unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn watermarks_lifecycle() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.watermarks_dal();
+        assert_eq!(
+            dal.get_min_watermark(&[WatermarkComponent::Tree])
+                .await
+                .unwrap(),
+            None
+        );
+
+        dal.set_watermark(WatermarkComponent::Tree, L1BatchNumber(10))
+            .await
+            .unwrap();
+        dal.set_watermark(WatermarkComponent::Commitments, L1BatchNumber(7))
+            .await
+            .unwrap();
+        // Watermarks must not decrease.
+        dal.set_watermark(WatermarkComponent::Tree, L1BatchNumber(5))
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.get_watermark(WatermarkComponent::Tree).await.unwrap(),
+            Some(L1BatchNumber(10))
+        );
+        assert_eq!(
+            dal.get_watermark(WatermarkComponent::ProtectiveReads)
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            dal.get_all_watermarks().await.unwrap(),
+            HashMap::from([
+                (WatermarkComponent::Tree, L1BatchNumber(10)),
+                (WatermarkComponent::Commitments, L1BatchNumber(7)),
+            ])
+        );
+        assert_eq!(
+            dal.get_min_watermark(&[WatermarkComponent::Tree, WatermarkComponent::Commitments])
+                .await
+                .unwrap(),
+            Some(L1BatchNumber(7))
+        );
+        assert_eq!(
+            dal.get_min_watermark(&[
+                WatermarkComponent::Tree,
+                WatermarkComponent::ProtectiveReads
+            ])
+            .await
+            .unwrap(),
+            None
+        );
+
+        dal.roll_back_watermarks(L1BatchNumber(8)).await.unwrap();
+        assert_eq!(
+            dal.get_watermark(WatermarkComponent::Tree).await.unwrap(),
+            Some(L1BatchNumber(8))
+        );
+        assert_eq!(
+            dal.get_watermark(WatermarkComponent::Commitments)
+                .await
+                .unwrap(),
+            Some(L1BatchNumber(7))
+        );
+
+        dal.remove_watermark(WatermarkComponent::Commitments)
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.get_watermark(WatermarkComponent::Commitments)
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            dal.get_min_watermark(&[WatermarkComponent::Tree])
+                .await
+                .unwrap(),
+            Some(L1BatchNumber(8))
+        );
+    }
+}
@@ -14,7 +14,7 @@ use anyhow::Context as _;
 use rand::Rng;
 use sqlx::{
     pool::PoolConnection,
-    postgres::{PgConnectOptions, PgPool, PgPoolOptions, Postgres},
+    postgres::{PgConnectOptions, PgListener, PgPool, PgPoolOptions, Postgres},
 };
 use zksync_basic_types::url::SensitiveUrl;
 
@@ -203,6 +203,25 @@ impl TestTemplate {
     }
 }
 
+/// Listener for Postgres notifications created with [`ConnectionPool::listen()`].
+#[derive(Debug)]
+pub struct NotificationListener {
+    inner: PgListener,
+}
+
+impl NotificationListener {
+    /// Waits for the next notification and returns its payload. If the connection to Postgres is lost,
+    /// it is transparently re-established; notifications sent in the meantime are lost.
+    pub async fn recv(&mut self) -> anyhow::Result<String> {
+        let notification = self
+            .inner
+            .recv()
+            .await
+            .context("failed receiving Postgres notification")?;
+        Ok(notification.payload().to_owned())
+    }
+}
+
 /// Global DB connection parameters applied to all [`ConnectionPool`] instances.
 #[derive(Debug)]
 pub struct GlobalConnectionPoolConfig {
@@ -339,6 +358,19 @@ impl<DB: DbMarker> ConnectionPool<DB> {
         self.max_size
     }
 
+    /// Creates a listener for Postgres notifications (i.e., ones sent with `NOTIFY` or `pg_notify()`)
+    /// on the specified channel. The listener holds a connection from this pool for its entire lifetime.
+    pub async fn listen(&self, channel: &str) -> anyhow::Result<NotificationListener> {
+        let mut inner = PgListener::connect_with(&self.inner)
+            .await
+            .context("failed creating Postgres listener")?;
+        inner
+            .listen(channel)
+            .await
+            .with_context(|| format!("failed listening to channel `{channel}`"))?;
+        Ok(NotificationListener { inner })
+    }
+
     /// Creates a `Connection` entity over a recoverable connection.
     /// Upon a database outage connection will block the thread until
     /// it will be able to recover the connection (or, if connection cannot
@@ -22,6 +22,18 @@ impl DalError {
         }
     }
 
+    /// Checks whether this error is caused by a statement timeout, e.g. one set with
+    /// [`Instrumented::with_statement_timeout()`](crate::instrument::Instrumented::with_statement_timeout()).
+    pub fn is_statement_timeout(&self) -> bool {
+        // `query_canceled` error code, which is also used for statement timeouts.
+        const QUERY_CANCELED_CODE: &str = "57014";
+
+        match self.inner() {
+            sqlx::Error::Database(err) => err.code().as_deref() == Some(QUERY_CANCELED_CODE),
+            _ => false,
+        }
+    }
+
     /// Wraps this error into an `anyhow` wrapper.
     pub fn generalize(self) -> anyhow::Error {
         anyhow::Error::from(self).context("Postgres error")
@@ -11,7 +11,7 @@
 //! [`Instrumented`] methods on the returned struct, e.g. to [report query latency](Instrumented::report_latency())
 //! and/or [to add logged args](Instrumented::with_arg()) for a query.
 
-use std::{fmt, future::Future, panic::Location};
+use std::{fmt, future::Future, panic::Location, time::Duration};
 
 use sqlx::{
     postgres::{PgCopyIn, PgQueryResult, PgRow},
@@ -187,6 +187,7 @@ struct InstrumentedData<'a> {
     args: QueryArgs<'a>,
     report_latency: bool,
     slow_query_reporting_enabled: bool,
+    statement_timeout: Option<Duration>,
 }
 
 impl<'a> InstrumentedData<'a> {
@@ -197,6 +198,7 @@ impl<'a> InstrumentedData<'a> {
             args: QueryArgs::default(),
             report_latency: false,
             slow_query_reporting_enabled: true,
+            statement_timeout: None,
         }
     }
 
@@ -211,6 +213,7 @@ impl<'a> InstrumentedData<'a> {
             args,
             report_latency,
             slow_query_reporting_enabled,
+            ..
         } = self;
         let started_at = Instant::now();
         tokio::pin!(query_future);
@@ -267,6 +270,43 @@ impl<'a> InstrumentedData<'a> {
     }
 }
 
+/// Starts a transaction with the specified statement timeout. The timeout is reset once the transaction ends.
+async fn start_transaction_with_statement_timeout<'c, DB: DbMarker>(
+    storage: &'c mut Connection<'_, DB>,
+    timeout: Duration,
+) -> DalResult<Connection<'c, DB>> {
+    let mut transaction = storage.start_transaction().await?;
+    let query = format!("SET LOCAL statement_timeout = {}", timeout.as_millis());
+    // Instrumentation isn't used here since it would lead to a recursive `async fn`.
+    let (conn, tags) = transaction.conn_and_tags();
+    if let Err(err) = sqlx::query(&query).execute(conn).await {
+        let err = DalRequestError::new(err, "set_statement_timeout", Location::caller())
+            .with_connection_tags(tags.cloned());
+        return Err(err.into());
+    }
+    Ok(transaction)
+}
+
+/// Executes an instrumented query, optionally in a transaction with a statement timeout.
+macro_rules! fetch {
+    ($self:ident, $storage:ident, $method:ident) => {
+        match $self.data.statement_timeout {
+            Some(timeout) => {
+                let mut transaction =
+                    start_transaction_with_statement_timeout($storage, timeout).await?;
+                let (conn, tags) = transaction.conn_and_tags();
+                let output = $self.data.fetch(tags, $self.query.$method(conn)).await?;
+                transaction.commit().await?;
+                Ok(output)
+            }
+            None => {
+                let (conn, tags) = $storage.conn_and_tags();
+                $self.data.fetch(tags, $self.query.$method(conn)).await
+            }
+        }
+    };
+}
+
 /// Instrumented `sqlx` query that wraps and can be used as a drop-in replacement for `sqlx::query!` / `query_as!` output
 /// (i.e., [`Map`]).
 ///
@@ -343,6 +383,19 @@ impl<'a, Q> Instrumented<'a, Q> {
         self
     }
 
+    /// Sets the statement timeout for this query, so that Postgres aborts the query if it runs for too long.
+    /// The query is executed in a separate transaction, so the timeout doesn't affect other queries on the connection.
+    /// If the connection is already in a transaction, the query is executed in a nested transaction (i.e., a savepoint).
+    /// In this case, the timeout is **not** reset after the query: Postgres retains `SET LOCAL` settings
+    /// from a released savepoint until the outer transaction ends, so the timeout applies to all subsequent queries
+    /// in the outer transaction.
+    /// If the timeout is exceeded, the query fails with an error for which [`DalError::is_statement_timeout()`]
+    /// returns `true`.
+    pub fn with_statement_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.data.statement_timeout = timeout;
+        self
+    }
+
     /// Adds a traced query argument. The argument will be logged (using `Debug`) if the query executes too slow
     /// or finishes with an error.
     pub fn with_arg(mut self, name: &'static str, value: &'a ThreadSafeDebug) -> Self {
@@ -360,8 +413,7 @@ where
         self,
         storage: &mut Connection<'_, DB>,
     ) -> DalResult<PgQueryResult> {
-        let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.execute(conn)).await
+        fetch!(self, storage, execute)
     }
 
     /// Fetches an optional row using this query.
@@ -369,8 +421,7 @@ where
         self,
         storage: &mut Connection<'_, DB>,
     ) -> DalResult<Option<PgRow>> {
-        let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_optional(conn)).await
+        fetch!(self, storage, fetch_optional)
     }
 }
 
@@ -384,8 +435,7 @@ where
         self,
         storage: &mut Connection<'_, DB>,
     ) -> DalResult<Vec<O>> {
-        let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_all(conn)).await
+        fetch!(self, storage, fetch_all)
     }
 }
 
@@ -400,14 +450,12 @@ where
         self,
         storage: &mut Connection<'_, DB>,
     ) -> DalResult<Option<O>> {
-        let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_optional(conn)).await
+        fetch!(self, storage, fetch_optional)
     }
 
     /// Fetches a single row using this query.
     pub async fn fetch_one<DB: DbMarker>(self, storage: &mut Connection<'_, DB>) -> DalResult<O> {
-        let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_one(conn)).await
+        fetch!(self, storage, fetch_one)
     }
 }
 
@@ -422,14 +470,12 @@ where
         self,
         storage: &mut Connection<'_, DB>,
     ) -> DalResult<Option<O>> {
-        let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_optional(conn)).await
+        fetch!(self, storage, fetch_optional)
     }
 
     /// Fetches a single row using this query.
     pub async fn fetch_one<DB: DbMarker>(self, storage: &mut Connection<'_, DB>) -> DalResult<O> {
-        let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_one(conn)).await
+        fetch!(self, storage, fetch_one)
     }
 
     /// Fetches all rows using this query and collects them into a `Vec`.
@@ -437,8 +483,7 @@ where
         self,
         storage: &mut Connection<'_, DB>,
     ) -> DalResult<Vec<O>> {
-        let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_all(conn)).await
+        fetch!(self, storage, fetch_all)
     }
 }
 
@@ -503,4 +548,26 @@ mod tests {
             .await
             .unwrap();
     }
+
+    #[tokio::test]
+    async fn query_with_statement_timeout() {
+        let pool = ConnectionPool::<InternalMarker>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let err = sqlx::query("SELECT pg_sleep(1.5)")
+            .map(drop)
+            .instrument("slow")
+            .with_statement_timeout(Some(Duration::from_millis(100)))
+            .fetch_optional(&mut conn)
+            .await
+            .unwrap_err();
+        assert!(err.is_statement_timeout(), "{err}");
+
+        // The timeout should not affect subsequent queries.
+        sqlx::query("SELECT pg_sleep(0.2)")
+            .map(drop)
+            .instrument("not_so_slow")
+            .fetch_optional(&mut conn)
+            .await
+            .unwrap();
+    }
 }
@@ -48,6 +48,8 @@ impl FromEnv for MerkleTreeApiConfig {
 mod tests {
     use std::num::{NonZeroU32, NonZeroUsize};
 
+    use zksync_config::configs::api::GethCompatibilityShim;
+
     use super::*;
     use crate::test_utils::{addr, hash, EnvMutex};
 
@@ -74,9 +76,14 @@ mod tests {
                 estimate_gas_scale_factor: 1.0f64,
                 gas_price_scale_factor: 1.2,
                 estimate_gas_acceptable_overestimation: 1000,
+                estimate_fee_batch_max_size: Some(8),
                 max_tx_size: 1000000,
                 vm_execution_cache_misses_limit: None,
                 vm_concurrency_limit: Some(512),
+                vm_concurrency_limit_per_client: Some(64),
+                vm_client_id_header: Some("x-api-key".to_owned()),
+                vm_client_id_trusted_proxies: Some(2),
+                vm_permit_timeout_ms: Some(2000),
                 factory_deps_cache_size_mb: Some(128),
                 initial_writes_cache_size_mb: Some(32),
                 latest_values_cache_size_mb: Some(256),
@@ -94,10 +101,35 @@ mod tests {
                 tree_api_url: None,
                 mempool_cache_update_interval: Some(50),
                 mempool_cache_size: Some(10000),
+                eth_call_cache_size: Some(1000),
+                eth_call_cache_ttl_ms: Some(500),
+                rejected_txs_retention_sec: Some(86400),
+                tx_replacement_fee_bump_percent: Some(10),
+                query_statement_timeout_ms: Some(10_000),
+                mempool_max_txs_per_sender: Some(64),
+                mempool_max_txs: Some(100_000),
+                mempool_max_size_mb: Some(512),
+                admin_namespace_enabled: true,
+                admin_api_keys: vec![
+                    "alice:0x0101010101010101010101010101010101010101010101010101010101010101"
+                        .to_owned(),
+                ],
+                api_runtime_worker_threads: Some(4),
+                idempotency_keys_cache_size: Some(10_000),
+                idempotency_key_ttl_sec: Some(300),
+                min_gas_per_pubdata_limit: Some(50),
+                max_gas_per_pubdata_limit: Some(50_000),
+                reject_unprotected_txs: true,
+                vm_memory_pool_size: Some(16),
                 whitelisted_tokens_for_aa: vec![
                     addr("0x0000000000000000000000000000000000000001"),
                     addr("0x0000000000000000000000000000000000000002"),
                 ],
+                geth_compatibility: vec![
+                    GethCompatibilityShim::BlockMiner,
+                    GethCompatibilityShim::NetWeb3Methods,
+                    GethCompatibilityShim::BlockFields,
+                ],
             },
             prometheus: PrometheusConfig {
                 listener_port: 3312,
@@ -131,10 +163,16 @@ mod tests {
             API_WEB3_JSON_RPC_REQUEST_TIMEOUT=10
             API_WEB3_JSON_RPC_ACCOUNT_PKS="0x0000000000000000000000000000000000000000000000000000000000000001,0x0000000000000000000000000000000000000000000000000000000000000002"
             API_WEB3_JSON_RPC_WHITELISTED_TOKENS_FOR_AA="0x0000000000000000000000000000000000000001,0x0000000000000000000000000000000000000002"
+            API_WEB3_JSON_RPC_GETH_COMPATIBILITY="block_miner,net_web3_methods,block_fields"
             API_WEB3_JSON_RPC_ESTIMATE_GAS_SCALE_FACTOR=1.0
             API_WEB3_JSON_RPC_ESTIMATE_GAS_ACCEPTABLE_OVERESTIMATION=1000
+            API_WEB3_JSON_RPC_ESTIMATE_FEE_BATCH_MAX_SIZE=8
             API_WEB3_JSON_RPC_MAX_TX_SIZE=1000000
             API_WEB3_JSON_RPC_VM_CONCURRENCY_LIMIT=512
+            API_WEB3_JSON_RPC_VM_CONCURRENCY_LIMIT_PER_CLIENT=64
+            API_WEB3_JSON_RPC_VM_CLIENT_ID_HEADER="x-api-key"
+            API_WEB3_JSON_RPC_VM_CLIENT_ID_TRUSTED_PROXIES=2
+            API_WEB3_JSON_RPC_VM_PERMIT_TIMEOUT_MS=2000
             API_WEB3_JSON_RPC_FACTORY_DEPS_CACHE_SIZE_MB=128
             API_WEB3_JSON_RPC_INITIAL_WRITES_CACHE_SIZE_MB=32
             API_WEB3_JSON_RPC_LATEST_VALUES_CACHE_SIZE_MB=256
@@ -143,6 +181,23 @@ mod tests {
             API_WEB3_JSON_RPC_WEBSOCKET_REQUESTS_PER_MINUTE_LIMIT=10
             API_WEB3_JSON_RPC_MEMPOOL_CACHE_SIZE=10000
             API_WEB3_JSON_RPC_MEMPOOL_CACHE_UPDATE_INTERVAL=50
+            API_WEB3_JSON_RPC_ETH_CALL_CACHE_SIZE=1000
+            API_WEB3_JSON_RPC_ETH_CALL_CACHE_TTL_MS=500
+            API_WEB3_JSON_RPC_REJECTED_TXS_RETENTION_SEC=86400
+            API_WEB3_JSON_RPC_TX_REPLACEMENT_FEE_BUMP_PERCENT=10
+            API_WEB3_JSON_RPC_MEMPOOL_MAX_TXS_PER_SENDER=64
+            API_WEB3_JSON_RPC_MEMPOOL_MAX_TXS=100000
+            API_WEB3_JSON_RPC_MEMPOOL_MAX_SIZE_MB=512
+            API_WEB3_JSON_RPC_ADMIN_NAMESPACE_ENABLED=true
+            API_WEB3_JSON_RPC_ADMIN_API_KEYS="alice:0x0101010101010101010101010101010101010101010101010101010101010101"
+            API_WEB3_JSON_RPC_API_RUNTIME_WORKER_THREADS=4
+            API_WEB3_JSON_RPC_IDEMPOTENCY_KEYS_CACHE_SIZE=10000
+            API_WEB3_JSON_RPC_IDEMPOTENCY_KEY_TTL_SEC=300
+            API_WEB3_JSON_RPC_MIN_GAS_PER_PUBDATA_LIMIT=50
+            API_WEB3_JSON_RPC_MAX_GAS_PER_PUBDATA_LIMIT=50000
+            API_WEB3_JSON_RPC_REJECT_UNPROTECTED_TXS=true
+            API_WEB3_JSON_RPC_VM_MEMORY_POOL_SIZE=16
+            API_WEB3_JSON_RPC_QUERY_STATEMENT_TIMEOUT_MS=10000
             API_CONTRACT_VERIFICATION_PORT="3070"
             API_CONTRACT_VERIFICATION_URL="http://127.0.0.1:3070"
             API_WEB3_JSON_RPC_MAX_RESPONSE_BODY_SIZE_MB=10
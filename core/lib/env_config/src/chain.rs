@@ -36,8 +36,10 @@ impl FromEnv for MempoolConfig {
 
 #[cfg(test)]
 mod tests {
+    use std::num::NonZeroU64;
+
     use zksync_basic_types::{commitment::L1BatchCommitmentMode, L2ChainId};
-    use zksync_config::configs::chain::FeeModelVersion;
+    use zksync_config::configs::chain::{FeeModelVersion, SupplyInvariantAction};
 
     use super::*;
     use crate::test_utils::{addr, hash, EnvMutex};
@@ -78,6 +80,21 @@ mod tests {
             l2_block_commit_deadline_ms: 1000,
             l2_block_seal_queue_capacity: 10,
             l2_block_max_payload_size: 1_000_000,
+            l2_block_max_pubdata: Some(50_000),
+            max_timestamp_drift_sec: Some(3600),
+            graceful_shutdown: true,
+            graceful_shutdown_l1_batch_seal_timeout_ms: Some(5000),
+            discard_pending_l1_batch_on_restart: true,
+            l1_gas_price_rise_threshold: Some(0.2),
+            l1_gas_price_rise_deadline_factor: Some(0.5),
+            l1_gas_price_fall_threshold: Some(0.3),
+            l1_gas_price_fall_deadline_factor: None,
+            max_batch_execution_time_ms: Some(60_000),
+            max_batch_vm_memory_mb: Some(4_096),
+            upgrade_canary_batches: Some(10),
+            tx_presimulation_batch_size: Some(100),
+            fee_account_rotation_period_sec: Some(3_600),
+            bytecode_compression_dictionary_size: Some(4_096),
             max_single_tx_gas: 1_000_000,
             max_allowed_l2_tx_gas_limit: 2_000_000_000,
             close_block_at_eth_params_percentage: 0.2,
@@ -96,6 +113,7 @@ mod tests {
             fee_model_version: FeeModelVersion::V2,
             validation_computational_gas_limit: 10_000_000,
             save_call_traces: false,
+            flat_call_traces: true,
             bootloader_hash: Some(hash(
                 "0x010007ede999d096c84553fb514d3d6ca76fbf39789dda76bfeda9f3ae06236e",
             )),
@@ -104,6 +122,7 @@ mod tests {
             )),
             l1_batch_commit_data_generator_mode,
             max_circuits_per_batch: 24100,
+            base_system_contracts_path: Some("/etc/base_system_contracts".to_owned()),
         }
     }
 
@@ -124,6 +143,20 @@ mod tests {
             CHAIN_STATE_KEEPER_MINIBLOCK_COMMIT_DEADLINE_MS="1000"
             CHAIN_STATE_KEEPER_MINIBLOCK_SEAL_QUEUE_CAPACITY="10"
             CHAIN_STATE_KEEPER_MINIBLOCK_MAX_PAYLOAD_SIZE="1000000"
+            CHAIN_STATE_KEEPER_L2_BLOCK_MAX_PUBDATA="50000"
+            CHAIN_STATE_KEEPER_MAX_TIMESTAMP_DRIFT_SEC="3600"
+            CHAIN_STATE_KEEPER_GRACEFUL_SHUTDOWN="true"
+            CHAIN_STATE_KEEPER_GRACEFUL_SHUTDOWN_L1_BATCH_SEAL_TIMEOUT_MS="5000"
+            CHAIN_STATE_KEEPER_DISCARD_PENDING_L1_BATCH_ON_RESTART="true"
+            CHAIN_STATE_KEEPER_L1_GAS_PRICE_RISE_THRESHOLD="0.2"
+            CHAIN_STATE_KEEPER_L1_GAS_PRICE_RISE_DEADLINE_FACTOR="0.5"
+            CHAIN_STATE_KEEPER_L1_GAS_PRICE_FALL_THRESHOLD="0.3"
+            CHAIN_STATE_KEEPER_MAX_BATCH_EXECUTION_TIME_MS="60000"
+            CHAIN_STATE_KEEPER_MAX_BATCH_VM_MEMORY_MB="4096"
+            CHAIN_STATE_KEEPER_TX_PRESIMULATION_BATCH_SIZE="100"
+            CHAIN_STATE_KEEPER_FEE_ACCOUNT_ROTATION_PERIOD_SEC="3600"
+            CHAIN_STATE_KEEPER_BYTECODE_COMPRESSION_DICTIONARY_SIZE="4096"
+            CHAIN_STATE_KEEPER_UPGRADE_CANARY_BATCHES="10"
             CHAIN_STATE_KEEPER_MINIMAL_L2_GAS_PRICE="100000000"
             CHAIN_STATE_KEEPER_COMPUTE_OVERHEAD_PART="0.0"
             CHAIN_STATE_KEEPER_PUBDATA_OVERHEAD_PART="1.0"
@@ -133,6 +166,8 @@ mod tests {
             CHAIN_STATE_KEEPER_FEE_MODEL_VERSION="V2"
             CHAIN_STATE_KEEPER_VALIDATION_COMPUTATIONAL_GAS_LIMIT="10000000"
             CHAIN_STATE_KEEPER_SAVE_CALL_TRACES="false"
+            CHAIN_STATE_KEEPER_FLAT_CALL_TRACES="true"
+            CHAIN_STATE_KEEPER_BASE_SYSTEM_CONTRACTS_PATH="/etc/base_system_contracts"
             CHAIN_STATE_KEEPER_BOOTLOADER_HASH=0x010007ede999d096c84553fb514d3d6ca76fbf39789dda76bfeda9f3ae06236e
             CHAIN_STATE_KEEPER_DEFAULT_AA_HASH=0x0100055b041eb28aff6e3a6e0f37c31fd053fc9ef142683b05e5f0aee6934066
             CHAIN_STATE_KEEPER_L1_BATCH_COMMIT_DATA_GENERATOR_MODE="{l1_batch_commit_data_generator_mode}"
@@ -168,6 +203,12 @@ mod tests {
             stuck_tx_timeout: 10,
             remove_stuck_txs: true,
             delay_interval: 100,
+            priority_fee_ordering: true,
+            priority_fee_ordering_window_ms: NonZeroU64::new(500).unwrap(),
+            priority_fee_ordering_max_fee: NonZeroU64::new(1_000_000).unwrap(),
+            priority_lane_addresses: vec![addr("0x0000000000000000000000000000000000000001")],
+            priority_lane_capacity: 100,
+            deadlines_retention_sec: 3600,
         }
     }
 
@@ -181,6 +222,12 @@ mod tests {
             CHAIN_MEMPOOL_REMOVE_STUCK_TXS="true"
             CHAIN_MEMPOOL_DELAY_INTERVAL="100"
             CHAIN_MEMPOOL_CAPACITY="1000000"
+            CHAIN_MEMPOOL_PRIORITY_FEE_ORDERING="true"
+            CHAIN_MEMPOOL_PRIORITY_FEE_ORDERING_WINDOW_MS="500"
+            CHAIN_MEMPOOL_PRIORITY_FEE_ORDERING_MAX_FEE="1000000"
+            CHAIN_MEMPOOL_PRIORITY_LANE_ADDRESSES="0x0000000000000000000000000000000000000001"
+            CHAIN_MEMPOOL_PRIORITY_LANE_CAPACITY="100"
+            CHAIN_MEMPOOL_DEADLINES_RETENTION_SEC="3600"
         "#;
         lock.set_env(config);
 
@@ -194,6 +241,8 @@ mod tests {
             http_req_max_retry_number: 5,
             http_req_retry_interval_sec: 2,
             replication_lag_limit_sec: Some(10),
+            supply_invariant_action: Some(SupplyInvariantAction::Halt),
+            supply_invariant_tolerance_gwei: Some(1_000),
         }
     }
 
@@ -205,6 +254,8 @@ mod tests {
             CHAIN_CIRCUIT_BREAKER_HTTP_REQ_MAX_RETRY_NUMBER="5"
             CHAIN_CIRCUIT_BREAKER_HTTP_REQ_RETRY_INTERVAL_SEC="2"
             CHAIN_CIRCUIT_BREAKER_REPLICATION_LAG_LIMIT_SEC="10"
+            CHAIN_CIRCUIT_BREAKER_SUPPLY_INVARIANT_ACTION="Halt"
+            CHAIN_CIRCUIT_BREAKER_SUPPLY_INVARIANT_TOLERANCE_GWEI="1000"
         "#;
         lock.set_env(config);
 
@@ -0,0 +1,50 @@
+use zksync_config::configs::DiskSpaceMonitorConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for DiskSpaceMonitorConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("disk_space_monitor", "DISK_SPACE_MONITOR_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_config::configs::disk_space_monitor::MonitoredPathConfig;
+
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    fn expected_config() -> DiskSpaceMonitorConfig {
+        DiskSpaceMonitorConfig {
+            check_interval_ms: 10_000,
+            paths: vec![
+                MonitoredPathConfig {
+                    path: "./db/main/state_keeper".to_owned(),
+                    warn_threshold_mb: 10_240,
+                    stop_threshold_mb: Some(2_048),
+                },
+                MonitoredPathConfig {
+                    path: "/var/lib/postgresql".to_owned(),
+                    warn_threshold_mb: 20_480,
+                    stop_threshold_mb: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            DISK_SPACE_MONITOR_CHECK_INTERVAL_MS="10000"
+            DISK_SPACE_MONITOR_PATHS="./db/main/state_keeper=10240:2048,/var/lib/postgresql=20480"
+        "#;
+        lock.set_env(config);
+
+        let actual = DiskSpaceMonitorConfig::from_env().unwrap();
+        assert_eq!(actual, expected_config());
+    }
+}
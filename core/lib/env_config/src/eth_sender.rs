@@ -1,6 +1,9 @@
 use anyhow::Context as _;
 use zksync_config::{
-    configs::{eth_sender::SenderConfig, L1Secrets},
+    configs::{
+        eth_sender::{SenderConfig, WithdrawalLimitsConfig},
+        L1Secrets,
+    },
     EthConfig, EthWatchConfig, GasAdjusterConfig,
 };
 
@@ -12,6 +15,7 @@ impl FromEnv for EthConfig {
             sender: SenderConfig::from_env().ok(),
             gas_adjuster: GasAdjusterConfig::from_env().ok(),
             watcher: EthWatchConfig::from_env().ok(),
+            withdrawal_limits: WithdrawalLimitsConfig::from_env().ok(),
         })
     }
 }
@@ -33,6 +37,15 @@ impl FromEnv for SenderConfig {
     }
 }
 
+impl FromEnv for WithdrawalLimitsConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load(
+            "eth_sender.withdrawal_limits",
+            "ETH_SENDER_WITHDRAWAL_LIMITS_",
+        )
+    }
+}
+
 impl FromEnv for GasAdjusterConfig {
     fn from_env() -> anyhow::Result<Self> {
         envy_load("eth_sender.gas_adjuster", "ETH_SENDER_GAS_ADJUSTER_")
@@ -41,7 +54,10 @@ impl FromEnv for GasAdjusterConfig {
 
 #[cfg(test)]
 mod tests {
-    use zksync_config::configs::eth_sender::{ProofSendingMode, PubdataSendingMode};
+    use zksync_basic_types::{Address, L1BatchNumber, U256};
+    use zksync_config::configs::eth_sender::{
+        ProofSendingMode, PubdataSendingMode, TokenWithdrawalLimit, WithdrawalLimitAction,
+    };
 
     use super::*;
     use crate::test_utils::{hash, EnvMutex};
@@ -68,6 +84,8 @@ mod tests {
                     max_txs_in_flight: 3,
                     proof_sending_mode: ProofSendingMode::SkipEveryProof,
                     l1_batch_min_age_before_execute_seconds: Some(1000),
+                    max_timestamp_drift_from_l1_sec: Some(3600),
+                    l1_tx_forwarder_addr: Some(Address::repeat_byte(0x03)),
                     max_acceptable_priority_fee_in_gwei: 100_000_000_000,
                     pubdata_sending_mode: PubdataSendingMode::Calldata,
                 }),
@@ -89,6 +107,21 @@ mod tests {
                     confirmations_for_eth_event: Some(0),
                     eth_node_poll_interval: 300,
                 }),
+                withdrawal_limits: Some(WithdrawalLimitsConfig {
+                    window_sec: 86_400,
+                    limits: vec![
+                        TokenWithdrawalLimit {
+                            token: Address::repeat_byte(0x01),
+                            max_amount: U256::from(1_000_000_000_000_000_000_u64),
+                        },
+                        TokenWithdrawalLimit {
+                            token: Address::repeat_byte(0x02),
+                            max_amount: U256::from(500),
+                        },
+                    ],
+                    action: WithdrawalLimitAction::Alert,
+                    override_up_to_l1_batch: Some(L1BatchNumber(42)),
+                }),
             },
             L1Secrets {
                 l1_rpc_url: "http://127.0.0.1:8545".to_string().parse().unwrap(),
@@ -129,8 +162,14 @@ mod tests {
             ETH_SENDER_SENDER_MAX_AGGREGATED_TX_GAS="4000000"
             ETH_SENDER_SENDER_MAX_ETH_TX_DATA_SIZE="120000"
             ETH_SENDER_SENDER_L1_BATCH_MIN_AGE_BEFORE_EXECUTE_SECONDS="1000"
+            ETH_SENDER_SENDER_MAX_TIMESTAMP_DRIFT_FROM_L1_SEC="3600"
+            ETH_SENDER_SENDER_L1_TX_FORWARDER_ADDR="0x0303030303030303030303030303030303030303"
             ETH_SENDER_SENDER_MAX_ACCEPTABLE_PRIORITY_FEE_IN_GWEI="100000000000"
             ETH_SENDER_SENDER_PUBDATA_SENDING_MODE="Calldata"
+            ETH_SENDER_WITHDRAWAL_LIMITS_WINDOW_SEC="86400"
+            ETH_SENDER_WITHDRAWAL_LIMITS_LIMITS="0x0101010101010101010101010101010101010101=1000000000000000000,0x0202020202020202020202020202020202020202=500"
+            ETH_SENDER_WITHDRAWAL_LIMITS_ACTION="Alert"
+            ETH_SENDER_WITHDRAWAL_LIMITS_OVERRIDE_UP_TO_L1_BATCH="42"
             ETH_CLIENT_WEB3_URL="http://127.0.0.1:8545"
 
         "#;
@@ -0,0 +1,41 @@
+use zksync_config::configs::FeatureFlagsConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for FeatureFlagsConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("feature_flags", "FEATURE_FLAGS_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            FEATURE_FLAGS_ENABLED="vm_runner_debug_traces,new_fee_model"
+            FEATURE_FLAGS_REMOTE_URL="http://127.0.0.1:8080/flags"
+            FEATURE_FLAGS_REMOTE_POLL_INTERVAL_MS="10000"
+        "#;
+        lock.set_env(config);
+
+        let actual = FeatureFlagsConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            FeatureFlagsConfig {
+                enabled: vec![
+                    "vm_runner_debug_traces".to_owned(),
+                    "new_fee_model".to_owned()
+                ],
+                remote_url: Some("http://127.0.0.1:8080/flags".to_owned()),
+                remote_poll_interval_ms: 10_000,
+            }
+        );
+    }
+}
@@ -47,6 +47,7 @@ mod tests {
                 },
                 max_retries: 5,
                 local_mirror_path: None,
+                replica: None,
             }),
             public_object_store: Some(ObjectStoreConfig {
                 mode: ObjectStoreMode::GCSWithCredentialFile {
@@ -55,8 +56,12 @@ mod tests {
                 },
                 max_retries: 5,
                 local_mirror_path: None,
+                replica: None,
             }),
             availability_check_interval_in_secs: Some(1_800),
+            max_sticky_jobs: Some(16),
+            prefetch_depth: Some(2),
+            prefetch_max_memory_mb: Some(4096),
         }
     }
 
@@ -75,6 +80,9 @@ mod tests {
             FRI_PROVER_ZONE_READ_URL="http://metadata.google.internal/computeMetadata/v1/instance/zone"
             FRI_PROVER_SHALL_SAVE_TO_PUBLIC_BUCKET=true
             FRI_PROVER_AVAILABILITY_CHECK_INTERVAL_IN_SECS="1800"
+            FRI_PROVER_MAX_STICKY_JOBS="16"
+            FRI_PROVER_PREFETCH_DEPTH="2"
+            FRI_PROVER_PREFETCH_MAX_MEMORY_MB="4096"
             PROVER_OBJECT_STORE_BUCKET_BASE_URL="/base/url"
             PROVER_OBJECT_STORE_MODE="GCSWithCredentialFile"
             PROVER_OBJECT_STORE_GCS_CREDENTIAL_FILE_PATH="/path/to/credentials1.json"
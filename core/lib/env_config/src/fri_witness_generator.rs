@@ -26,6 +26,8 @@ mod tests {
             max_attempts: 4,
             last_l1_batch_to_process: None,
             shall_save_to_public_bucket: true,
+            prefetch_depth: Some(1),
+            prefetch_max_memory_mb: None,
         }
     }
 
@@ -41,6 +43,7 @@ mod tests {
             FRI_WITNESS_SCHEDULER_GENERATION_TIMEOUT_IN_SECS=900
             FRI_WITNESS_MAX_ATTEMPTS=4
             FRI_WITNESS_SHALL_SAVE_TO_PUBLIC_BUCKET=true
+            FRI_WITNESS_PREFETCH_DEPTH=1
         "#;
         lock.set_env(config);
 
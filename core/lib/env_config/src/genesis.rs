@@ -17,6 +17,7 @@ struct ContractsForGenesis {
     pub genesis_batch_commitment: Option<H256>,
     pub genesis_protocol_version: Option<u16>,
     pub genesis_protocol_semantic_version: Option<ProtocolSemanticVersion>,
+    pub evm_emulator_hash: Option<H256>,
     pub fri_recursion_scheduler_level_vk_hash: H256,
     pub fri_recursion_node_level_vk_hash: H256,
     pub fri_recursion_leaf_level_vk_hash: H256,
@@ -68,6 +69,7 @@ impl FromEnv for GenesisConfig {
             genesis_commitment: contracts_config.genesis_batch_commitment,
             bootloader_hash: state_keeper.bootloader_hash,
             default_aa_hash: state_keeper.default_aa_hash,
+            evm_emulator_hash: contracts_config.evm_emulator_hash,
             l1_chain_id: network_config.network.chain_id(),
             l2_chain_id: network_config.zksync_network_id,
             recursion_node_level_vk_hash: contracts_config.fri_recursion_node_level_vk_hash,
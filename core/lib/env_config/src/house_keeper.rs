@@ -33,6 +33,17 @@ mod tests {
             fri_gpu_prover_archiver_archiving_interval_ms: Some(86_400_000),
             // 48 hours
             fri_gpu_prover_archiver_archive_after_secs: Some(172_800),
+            // 1 hour
+            eth_txs_history_archiver_archiving_interval_ms: Some(3_600_000),
+            // 7 days
+            eth_txs_history_archiver_archive_after_secs: Some(604_800),
+            eth_txs_history_archiver_archive_eth_txs_after_days: Some(90),
+            // 10 minutes
+            l2_block_partitions_maintenance_interval_ms: Some(600_000),
+            l2_blocks_per_partition: Some(1_000_000),
+            // 1 minute
+            call_traces_migration_interval_ms: Some(60_000),
+            rejected_txs_pruning_interval_ms: Some(60_000),
         }
     }
 
@@ -56,6 +67,13 @@ mod tests {
             HOUSE_KEEPER_PROVER_JOB_ARCHIVER_ARCHIVE_AFTER_SECS="172800"
             HOUSE_KEEPER_FRI_GPU_PROVER_ARCHIVER_ARCHIVING_INTERVAL_MS="86400000"
             HOUSE_KEEPER_FRI_GPU_PROVER_ARCHIVER_ARCHIVE_AFTER_SECS="172800"
+            HOUSE_KEEPER_ETH_TXS_HISTORY_ARCHIVER_ARCHIVING_INTERVAL_MS="3600000"
+            HOUSE_KEEPER_ETH_TXS_HISTORY_ARCHIVER_ARCHIVE_AFTER_SECS="604800"
+            HOUSE_KEEPER_ETH_TXS_HISTORY_ARCHIVER_ARCHIVE_ETH_TXS_AFTER_DAYS="90"
+            HOUSE_KEEPER_L2_BLOCK_PARTITIONS_MAINTENANCE_INTERVAL_MS="600000"
+            HOUSE_KEEPER_L2_BLOCKS_PER_PARTITION="1000000"
+            HOUSE_KEEPER_CALL_TRACES_MIGRATION_INTERVAL_MS="60000"
+            HOUSE_KEEPER_REJECTED_TXS_PRUNING_INTERVAL_MS="60000"
         "#;
         lock.set_env(config);
 
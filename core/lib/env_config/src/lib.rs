@@ -6,8 +6,10 @@ mod chain;
 mod contract_verifier;
 mod contracts;
 mod database;
+mod disk_space_monitor;
 mod eth_sender;
 mod eth_watch;
+mod feature_flags;
 mod fri_proof_compressor;
 mod fri_prover;
 mod fri_prover_gateway;
@@ -18,6 +20,7 @@ mod house_keeper;
 pub mod object_store;
 mod observability;
 mod proof_data_handler;
+mod scheduler;
 mod snapshots_creator;
 mod utils;
 
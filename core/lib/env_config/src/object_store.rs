@@ -2,9 +2,21 @@ use zksync_config::ObjectStoreConfig;
 
 use crate::{envy_load, FromEnv};
 
+/// Loads an object store config with the specified prefix. The replica config (if any) is loaded
+/// using the `{prefix}REPLICA_` prefix.
+fn load_object_store_config(name: &str, prefix: &str) -> anyhow::Result<ObjectStoreConfig> {
+    let mut config: ObjectStoreConfig = envy_load(name, prefix)?;
+    let replica_prefix = format!("{prefix}REPLICA_");
+    if std::env::var_os(format!("{replica_prefix}MODE")).is_some() {
+        let replica = envy_load(&format!("{name}_replica"), &replica_prefix)?;
+        config.replica = Some(Box::new(replica));
+    }
+    Ok(config)
+}
+
 impl FromEnv for ObjectStoreConfig {
     fn from_env() -> anyhow::Result<Self> {
-        envy_load("object_store", "OBJECT_STORE_")
+        load_object_store_config("object_store", "OBJECT_STORE_")
     }
 }
 
@@ -14,7 +26,7 @@ pub struct PublicObjectStoreConfig(pub ObjectStoreConfig);
 
 impl FromEnv for PublicObjectStoreConfig {
     fn from_env() -> anyhow::Result<Self> {
-        let config = envy_load("public_object_store", "PUBLIC_OBJECT_STORE_")?;
+        let config = load_object_store_config("public_object_store", "PUBLIC_OBJECT_STORE_")?;
         Ok(Self(config))
     }
 }
@@ -25,7 +37,7 @@ pub struct ProverObjectStoreConfig(pub ObjectStoreConfig);
 
 impl FromEnv for ProverObjectStoreConfig {
     fn from_env() -> anyhow::Result<Self> {
-        let config = envy_load("prover_object_store", "PROVER_OBJECT_STORE_")?;
+        let config = load_object_store_config("prover_object_store", "PROVER_OBJECT_STORE_")?;
         Ok(Self(config))
     }
 }
@@ -35,7 +47,7 @@ pub struct SnapshotsObjectStoreConfig(pub ObjectStoreConfig);
 
 impl FromEnv for SnapshotsObjectStoreConfig {
     fn from_env() -> anyhow::Result<Self> {
-        let config = envy_load("snapshots_object_store", "SNAPSHOTS_OBJECT_STORE_")?;
+        let config = load_object_store_config("snapshots_object_store", "SNAPSHOTS_OBJECT_STORE_")?;
         Ok(Self(config))
     }
 }
@@ -57,6 +69,7 @@ mod tests {
             },
             max_retries: 5,
             local_mirror_path: Some("/var/cache".to_owned()),
+            replica: None,
         }
     }
 
@@ -133,6 +146,9 @@ mod tests {
             SNAPSHOTS_OBJECT_STORE_BUCKET_BASE_URL="/snapshots_base_url"
             SNAPSHOTS_OBJECT_STORE_MODE="GCS"
             SNAPSHOTS_OBJECT_STORE_MAX_RETRIES="5"
+            SNAPSHOTS_OBJECT_STORE_REPLICA_MODE="FileBacked"
+            SNAPSHOTS_OBJECT_STORE_REPLICA_FILE_BACKED_BASE_PATH="/snapshots_replica"
+            SNAPSHOTS_OBJECT_STORE_REPLICA_MAX_RETRIES="3"
         "#;
         lock.set_env(config);
         let actual = SnapshotsObjectStoreConfig::from_env().unwrap().0;
@@ -142,5 +158,14 @@ mod tests {
                 bucket_base_url: "/snapshots_base_url".to_owned(),
             }
         );
+        let replica = actual.replica.unwrap();
+        assert_eq!(
+            replica.mode,
+            ObjectStoreMode::FileBacked {
+                file_backed_base_path: "/snapshots_replica".to_owned(),
+            }
+        );
+        assert_eq!(replica.max_retries, 3);
+        assert_eq!(replica.replica, None);
     }
 }
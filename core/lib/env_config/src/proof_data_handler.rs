@@ -19,6 +19,13 @@ mod tests {
         ProofDataHandlerConfig {
             http_port: 3320,
             proof_generation_timeout_in_secs: 18000,
+            authorized_provers: vec![
+                "prover-1:0x0101010101010101010101010101010101010101010101010101010101010101"
+                    .to_owned(),
+                "prover-2:0x0202020202020202020202020202020202020202020202020202020202020202"
+                    .to_owned(),
+            ],
+            allow_unauthenticated_access: false,
         }
     }
 
@@ -27,6 +34,7 @@ mod tests {
         let config = r#"
             PROOF_DATA_HANDLER_PROOF_GENERATION_TIMEOUT_IN_SECS="18000"
             PROOF_DATA_HANDLER_HTTP_PORT="3320"
+            PROOF_DATA_HANDLER_AUTHORIZED_PROVERS="prover-1:0x0101010101010101010101010101010101010101010101010101010101010101,prover-2:0x0202020202020202020202020202020202020202020202020202020202020202"
         "#;
         let mut lock = MUTEX.lock();
         lock.set_env(config);
@@ -0,0 +1,77 @@
+use std::env;
+
+use anyhow::Context as _;
+use zksync_config::configs::SchedulerConfig;
+
+use crate::FromEnv;
+
+impl FromEnv for SchedulerConfig {
+    /// Loads the config from the `SCHEDULER_JOBS` env variable. Since cron schedules may contain commas,
+    /// jobs are separated by semicolons rather than commas (as in other list-like env variables).
+    fn from_env() -> anyhow::Result<Self> {
+        let jobs = env::var("SCHEDULER_JOBS").context("SCHEDULER_JOBS")?;
+        let jobs = jobs
+            .split(';')
+            .map(str::trim)
+            .filter(|job| !job.is_empty())
+            .map(|job| {
+                job.parse()
+                    .with_context(|| format!("invalid scheduled job: `{job}`"))
+            })
+            .collect::<anyhow::Result<_>>()
+            .context("Cannot load config <scheduler>")?;
+        Ok(Self { jobs })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_config::configs::scheduler::ScheduledJobConfig;
+
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    fn expected_config() -> SchedulerConfig {
+        SchedulerConfig {
+            jobs: vec![
+                ScheduledJobConfig {
+                    name: "db_pruner".to_owned(),
+                    schedule: "0,30 */2 * * 1-5".to_owned(),
+                    jitter_ms: 10_000,
+                    exclusion_group: Some("db".to_owned()),
+                },
+                ScheduledJobConfig {
+                    name: "vacuum".to_owned(),
+                    schedule: "15 3 * * *".to_owned(),
+                    jitter_ms: 0,
+                    exclusion_group: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            SCHEDULER_JOBS="db_pruner=0,30 */2 * * 1-5 jitter_ms=10000 group=db; vacuum=15 3 * * *"
+        "#;
+        lock.set_env(config);
+
+        let actual = SchedulerConfig::from_env().unwrap();
+        assert_eq!(actual, expected_config());
+    }
+
+    #[test]
+    fn from_env_with_invalid_job() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            SCHEDULER_JOBS="db_pruner=* * * * * timeout=100"
+        "#;
+        lock.set_env(config);
+
+        SchedulerConfig::from_env().unwrap_err();
+    }
+}
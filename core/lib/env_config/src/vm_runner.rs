@@ -1,4 +1,4 @@
-use zksync_config::configs::ProtectiveReadsWriterConfig;
+use zksync_config::configs::{BasicWitnessInputProducerConfig, ProtectiveReadsWriterConfig};
 
 use crate::{envy_load, FromEnv};
 
@@ -7,3 +7,9 @@ impl FromEnv for ProtectiveReadsWriterConfig {
         envy_load("vm_runner.protective_reads", "VM_RUNNER_PROTECTIVE_READS_")
     }
 }
+
+impl FromEnv for BasicWitnessInputProducerConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("vm_runner.bwip", "VM_RUNNER_BWIP_")
+    }
+}
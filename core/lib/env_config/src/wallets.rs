@@ -17,6 +17,11 @@ impl FromEnv for Wallets {
             .map(|pk| pk.parse::<H256>().context("Malformed pk"))
             .transpose()?;
 
+        let fee_payer = std::env::var("ETH_SENDER_SENDER_FEE_PAYER_PRIVATE_KEY")
+            .ok()
+            .map(|pk| pk.parse::<H256>().context("Malformed pk"))
+            .transpose()?;
+
         let eth_sender = if let Some(operator) = operator {
             let operator = Wallet::from_private_key_bytes(operator, None)?;
             let blob_operator = if let Some(blob_operator) = blob_operator {
@@ -24,9 +29,15 @@ impl FromEnv for Wallets {
             } else {
                 None
             };
+            let fee_payer = if let Some(fee_payer) = fee_payer {
+                Some(Wallet::from_private_key_bytes(fee_payer, None)?)
+            } else {
+                None
+            };
             Some(EthSender {
                 operator,
                 blob_operator,
+                fee_payer,
             })
         } else {
             None
@@ -35,7 +46,22 @@ impl FromEnv for Wallets {
         let fee_account = std::env::var("CHAIN_STATE_KEEPER_FEE_ACCOUNT_ADDR").ok();
         let state_keeper = if let Some(fee_account) = fee_account {
             let fee_account = AddressWallet::from_address(Address::from_str(&fee_account)?);
-            Some(StateKeeper { fee_account })
+            let extra_fee_accounts =
+                std::env::var("CHAIN_STATE_KEEPER_EXTRA_FEE_ACCOUNT_ADDRS").unwrap_or_default();
+            let extra_fee_accounts = extra_fee_accounts
+                .split(',')
+                .map(str::trim)
+                .filter(|addr| !addr.is_empty())
+                .map(|addr| {
+                    let address = Address::from_str(addr)
+                        .with_context(|| format!("malformed extra fee account address: {addr}"))?;
+                    Ok(AddressWallet::from_address(address))
+                })
+                .collect::<anyhow::Result<_>>()?;
+            Some(StateKeeper {
+                fee_account,
+                extra_fee_accounts,
+            })
         } else {
             None
         };
@@ -43,6 +43,9 @@ pub(super) trait L1EthNamespace {
     #[method(name = "getBalance")]
     async fn get_balance(&self, address: Address, block: web3::BlockNumber) -> RpcResult<U256>;
 
+    #[method(name = "getCode")]
+    async fn get_code(&self, address: Address, block: web3::BlockNumber) -> RpcResult<web3::Bytes>;
+
     #[method(name = "getLogs")]
     async fn get_logs(&self, filter: web3::Filter) -> RpcResult<Vec<web3::Log>>;
 
@@ -27,6 +27,7 @@ enum Method {
     CallContractFunction,
     TxReceipt,
     EthBalance,
+    ContractCode,
     Logs,
     Block,
     #[metrics(name = "sign_prepared_tx_for_addr")]
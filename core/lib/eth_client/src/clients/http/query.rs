@@ -284,6 +284,18 @@ where
         Ok(balance)
     }
 
+    async fn contract_code(&self, address: Address) -> EnrichedClientResult<Vec<u8>> {
+        COUNTERS.call[&(Method::ContractCode, self.component())].inc();
+        let latency = LATENCIES.direct[&Method::ContractCode].start();
+        let code = self
+            .get_code(address, web3::BlockNumber::Latest)
+            .rpc_context("get_code")
+            .with_arg("address", &address)
+            .await?;
+        latency.observe();
+        Ok(code.0)
+    }
+
     async fn logs(&self, filter: &web3::Filter) -> EnrichedClientResult<Vec<web3::Log>> {
         COUNTERS.call[&(Method::Logs, self.component())].inc();
         let latency = LATENCIES.direct[&Method::Logs].start();
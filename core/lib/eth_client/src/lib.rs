@@ -133,6 +133,10 @@ pub trait EthInterface: Sync + Send {
     /// Returns the ETH balance of the specified token for the specified address.
     async fn eth_balance(&self, address: Address) -> EnrichedClientResult<U256>;
 
+    /// Returns the bytecode deployed at the specified address. Returns an empty vector if there is no contract
+    /// at the address.
+    async fn contract_code(&self, address: Address) -> EnrichedClientResult<Vec<u8>>;
+
     /// Invokes a function on a contract specified by `contract_address` / `contract_abi` using `eth_call`.
     async fn call_contract_function(
         &self,
@@ -0,0 +1,209 @@
+//! Feature flags used by node components for incremental rollouts.
+//!
+//! Flags are resolved at runtime. Initial flag values are taken from [`FeatureFlagsConfig`]; they can be
+//! overridden by an optional remote source polled by [`FeatureFlagsUpdater`]. Components check flags
+//! using a [`FeatureFlags`] handle. Since flags may change while the node is running, components should check
+//! a flag each time the corresponding behavior is triggered rather than caching its value.
+
+use std::{collections::BTreeMap, fmt, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_config::configs::FeatureFlagsConfig;
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+
+#[cfg(test)]
+mod tests;
+
+type FlagValues = BTreeMap<String, bool>;
+
+/// Handle allowing to check feature flags. Cheap to clone.
+#[derive(Debug, Clone)]
+pub struct FeatureFlags(watch::Receiver<Arc<FlagValues>>);
+
+impl Default for FeatureFlags {
+    /// Returns flags with all features disabled.
+    fn default() -> Self {
+        Self::fixed([])
+    }
+}
+
+impl FeatureFlags {
+    /// Creates flags with the specified features enabled that never change.
+    pub fn fixed<S: Into<String>>(enabled: impl IntoIterator<Item = S>) -> Self {
+        let values = enabled
+            .into_iter()
+            .map(|name| (name.into(), true))
+            .collect();
+        let (_, receiver) = watch::channel(Arc::new(values));
+        Self(receiver)
+    }
+
+    /// Checks whether the specified feature is enabled. Unknown features are considered disabled.
+    pub fn is_enabled(&self, feature: &str) -> bool {
+        self.0.borrow().get(feature).copied().unwrap_or(false)
+    }
+
+    /// Returns current values of all known flags.
+    pub fn values(&self) -> Arc<BTreeMap<String, bool>> {
+        self.0.borrow().clone()
+    }
+}
+
+/// Remote source of feature flag values.
+#[async_trait::async_trait]
+pub trait FeatureFlagsSource: 'static + Send + Sync + fmt::Debug {
+    /// Fetches flag values. Returned values override the ones from the node config.
+    async fn fetch(&self) -> anyhow::Result<BTreeMap<String, bool>>;
+}
+
+/// [`FeatureFlagsSource`] fetching flag values from an HTTP endpoint responding with a JSON object
+/// mapping flag names to boolean values.
+#[derive(Debug)]
+pub struct HttpFeatureFlagsSource {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpFeatureFlagsSource {
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+    pub fn new(url: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl FeatureFlagsSource for HttpFeatureFlagsSource {
+    async fn fetch(&self) -> anyhow::Result<BTreeMap<String, bool>> {
+        let response = self
+            .client
+            .get(&self.url)
+            .timeout(Self::REQUEST_TIMEOUT)
+            .send()
+            .await
+            .context("failed sending request")?
+            .error_for_status()
+            .context("remote source responded with an error")?;
+        response.json().await.context("failed parsing response")
+    }
+}
+
+/// Health details of [`FeatureFlagsUpdater`].
+#[derive(Debug, Serialize)]
+struct FeatureFlagsHealthDetails<'a> {
+    flags: &'a FlagValues,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remote_error: Option<String>,
+}
+
+/// Resolves feature flags by merging values from the node config with ones from the remote source (if any).
+/// Resolved flags are reported in the health check details, so that operators can see active features on each node.
+#[derive(Debug)]
+pub struct FeatureFlagsUpdater {
+    config_values: FlagValues,
+    remote_source: Option<Box<dyn FeatureFlagsSource>>,
+    poll_interval: Duration,
+    sender: watch::Sender<Arc<FlagValues>>,
+    health_updater: HealthUpdater,
+}
+
+impl FeatureFlagsUpdater {
+    pub fn new(config: &FeatureFlagsConfig) -> Self {
+        let config_values: FlagValues = config
+            .enabled
+            .iter()
+            .map(|name| (name.clone(), true))
+            .collect();
+        let (sender, _) = watch::channel(Arc::new(config_values.clone()));
+        let (_, health_updater) = ReactiveHealthCheck::new("feature_flags");
+        let this = Self {
+            config_values,
+            remote_source: config.remote_url.clone().map(|url| {
+                Box::new(HttpFeatureFlagsSource::new(url)) as Box<dyn FeatureFlagsSource>
+            }),
+            poll_interval: config.remote_poll_interval(),
+            sender,
+            health_updater,
+        };
+        this.update_health(None);
+        this
+    }
+
+    /// Replaces the remote source of flag values.
+    pub fn with_remote_source(mut self, source: Box<dyn FeatureFlagsSource>) -> Self {
+        self.remote_source = Some(source);
+        self
+    }
+
+    /// Returns a handle to the resolved flags.
+    pub fn flags(&self) -> FeatureFlags {
+        FeatureFlags(self.sender.subscribe())
+    }
+
+    /// Returns a health check for the updater. Health details contain the resolved flags.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    fn update_health(&self, remote_error: Option<String>) {
+        let status = if remote_error.is_some() {
+            HealthStatus::Affected
+        } else {
+            HealthStatus::Ready
+        };
+        let flags = self.sender.borrow().clone();
+        let details = FeatureFlagsHealthDetails {
+            flags: &flags,
+            remote_error,
+        };
+        self.health_updater
+            .update(Health::from(status).with_details(details));
+    }
+
+    async fn update_from_remote(&self, source: &dyn FeatureFlagsSource) {
+        let remote_values = match source.fetch().await {
+            Ok(values) => values,
+            Err(err) => {
+                tracing::warn!("Failed fetching feature flags from remote source: {err:#}");
+                self.update_health(Some(format!("{err:#}")));
+                return;
+            }
+        };
+
+        let mut values = self.config_values.clone();
+        values.extend(remote_values);
+        self.sender.send_if_modified(|current| {
+            if **current == values {
+                return false;
+            }
+            tracing::info!("Feature flags updated: {values:?}");
+            *current = Arc::new(values);
+            true
+        });
+        self.update_health(None);
+    }
+
+    /// Runs the updater. If there is no remote source, this just waits for the stop signal.
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::info!("Feature flags from config: {:?}", self.config_values);
+        let Some(source) = &self.remote_source else {
+            stop_receiver.changed().await?;
+            return Ok(());
+        };
+
+        while !*stop_receiver.borrow_and_update() {
+            self.update_from_remote(source.as_ref()).await;
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        tracing::info!("Stop signal received, feature flags updater is shutting down");
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+//! Tests for feature flags.
+
+use std::sync::Mutex;
+
+use anyhow::Context as _;
+use zksync_health_check::CheckHealth;
+
+use super::*;
+
+#[derive(Debug, Default)]
+struct MockSource(Mutex<Option<FlagValues>>);
+
+impl MockSource {
+    fn set(&self, values: impl IntoIterator<Item = (&'static str, bool)>) {
+        let values = values
+            .into_iter()
+            .map(|(name, value)| (name.to_owned(), value))
+            .collect();
+        *self.0.lock().unwrap() = Some(values);
+    }
+}
+
+#[async_trait::async_trait]
+impl FeatureFlagsSource for Arc<MockSource> {
+    async fn fetch(&self) -> anyhow::Result<FlagValues> {
+        self.0
+            .lock()
+            .unwrap()
+            .clone()
+            .context("source is unavailable")
+    }
+}
+
+fn config() -> FeatureFlagsConfig {
+    FeatureFlagsConfig {
+        enabled: vec!["from_config".to_owned(), "overridden".to_owned()],
+        remote_url: None,
+        remote_poll_interval_ms: 10,
+    }
+}
+
+#[test]
+fn fixed_flags() {
+    let flags = FeatureFlags::default();
+    assert!(!flags.is_enabled("test"));
+
+    let flags = FeatureFlags::fixed(["test"]);
+    assert!(flags.is_enabled("test"));
+    assert!(!flags.is_enabled("other"));
+}
+
+#[tokio::test]
+async fn flags_from_config() {
+    let updater = FeatureFlagsUpdater::new(&config());
+    let flags = updater.flags();
+    assert!(flags.is_enabled("from_config"));
+    assert!(flags.is_enabled("overridden"));
+    assert!(!flags.is_enabled("unknown"));
+
+    let health = updater.health_check().check_health().await;
+    assert_eq!(health.status(), HealthStatus::Ready);
+    let details = health.details().unwrap();
+    assert_eq!(
+        details["flags"],
+        serde_json::json!({ "from_config": true, "overridden": true })
+    );
+}
+
+#[tokio::test]
+async fn flags_are_overridden_by_remote_source() {
+    let source = Arc::new(MockSource::default());
+    let updater = FeatureFlagsUpdater::new(&config()).with_remote_source(Box::new(source.clone()));
+    let mut flags = updater.flags();
+    let health_check = updater.health_check();
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let updater_task = tokio::spawn(updater.run(stop_receiver));
+
+    // Wait until the updater reports that the remote source is unavailable.
+    let mut health_subscriber = health_check.clone();
+    health_subscriber
+        .wait_for(|health| health.status() == HealthStatus::Affected)
+        .await;
+    assert!(flags.is_enabled("overridden"));
+
+    source.set([("overridden", false), ("from_remote", true)]);
+    flags.0.changed().await.unwrap();
+    assert!(flags.is_enabled("from_config"));
+    assert!(!flags.is_enabled("overridden"));
+    assert!(flags.is_enabled("from_remote"));
+
+    health_subscriber
+        .wait_for(|health| health.status() == HealthStatus::Ready)
+        .await;
+    let health = health_check.check_health().await;
+    assert_eq!(
+        health.details().unwrap()["flags"],
+        serde_json::json!({ "from_config": true, "from_remote": true, "overridden": false })
+    );
+
+    stop_sender.send_replace(true);
+    updater_task.await.unwrap().unwrap();
+}
@@ -1,10 +1,10 @@
 use std::collections::{hash_map, BTreeSet, HashMap, HashSet};
 
 use zksync_types::{
-    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction,
+    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction, H256,
 };
 
-use crate::types::{AccountTransactions, L2TxFilter, MempoolScore};
+use crate::types::{l2_tx_hash, AccountTransactions, L2TxFilter, L2TxOrdering, MempoolScore};
 
 #[derive(Debug)]
 pub struct MempoolInfo {
@@ -17,6 +17,7 @@ pub struct MempoolStats {
     pub l1_transaction_count: usize,
     pub l2_transaction_count: u64,
     pub l2_priority_queue_size: usize,
+    pub l2_priority_lane_size: usize,
 }
 
 #[derive(Debug)]
@@ -27,12 +28,25 @@ pub struct MempoolStore {
     l2_transactions_per_account: HashMap<Address, AccountTransactions>,
     /// Global priority queue for L2 transactions. Used for scoring
     l2_priority_queue: BTreeSet<MempoolScore>,
+    /// Priority lane for L2 transactions from priority lane accounts and boosted transactions.
+    /// Transactions in the lane are fetched ahead of the ones in `l2_priority_queue`.
+    l2_priority_lane: BTreeSet<MempoolScore>,
+    /// Accounts whose transactions are placed into the priority lane.
+    priority_lane_accounts: HashSet<Address>,
+    /// Maximum number of entries in the priority lane. If the lane is full, transactions that would be placed
+    /// into it are placed into the general queue instead.
+    priority_lane_capacity: usize,
+    /// Hashes of transactions boosted to the priority lane by the operator.
+    boosted_transactions: HashSet<H256>,
+    /// Hash of the last boosted transaction returned by `next_transaction()`. Used to retain the boost on rollback.
+    last_taken_boosted_transaction: Option<H256>,
     /// Next priority operation
     next_priority_id: PriorityOpId,
     stashed_accounts: Vec<Address>,
     /// Number of L2 transactions in the mempool.
     size: u64,
     capacity: u64,
+    ordering: L2TxOrdering,
 }
 
 impl MempoolStore {
@@ -41,13 +55,52 @@ impl MempoolStore {
             l1_transactions: HashMap::new(),
             l2_transactions_per_account: HashMap::new(),
             l2_priority_queue: BTreeSet::new(),
+            l2_priority_lane: BTreeSet::new(),
+            priority_lane_accounts: HashSet::new(),
+            priority_lane_capacity: 0,
+            boosted_transactions: HashSet::new(),
+            last_taken_boosted_transaction: None,
             next_priority_id,
             stashed_accounts: vec![],
             size: 0,
             capacity,
+            ordering: L2TxOrdering::default(),
         }
     }
 
+    /// Sets the ordering of L2 transactions in the mempool. Must be called before any transactions are inserted.
+    pub fn with_ordering(mut self, ordering: L2TxOrdering) -> Self {
+        assert!(
+            self.l2_transactions_per_account.is_empty(),
+            "mempool ordering must be set before inserting transactions"
+        );
+        self.ordering = ordering;
+        self
+    }
+
+    /// Enables the priority lane for L2 transactions. Transactions from `accounts` and transactions boosted
+    /// via [`Self::boost_transactions()`] are fetched ahead of all other L2 transactions, as long as they match
+    /// the fee filter. The lane holds at most `capacity` accounts' next transactions; lane transactions
+    /// exceeding this limit are ordered together with other transactions.
+    ///
+    /// Must be called before any transactions are inserted.
+    pub fn with_priority_lane(mut self, accounts: HashSet<Address>, capacity: usize) -> Self {
+        assert!(
+            self.l2_transactions_per_account.is_empty(),
+            "mempool priority lane must be set before inserting transactions"
+        );
+        self.priority_lane_accounts = accounts;
+        self.priority_lane_capacity = capacity;
+        self
+    }
+
+    /// Boosts transactions with the specified hashes to the priority lane. Must be called before
+    /// the transactions are inserted into the mempool; boosting transactions already in the mempool
+    /// only takes effect once they are re-inserted.
+    pub fn boost_transactions(&mut self, tx_hashes: impl IntoIterator<Item = H256>) {
+        self.boosted_transactions.extend(tx_hashes);
+    }
+
     /// Inserts batch of new transactions to mempool
     /// `initial_nonces` provides current committed nonce information to mempool
     /// variable is used only if account is not present in mempool yet and we have to bootstrap it
@@ -107,15 +160,15 @@ impl MempoolStore {
             hash_map::Entry::Vacant(entry) => {
                 let account_nonce = initial_nonces.get(&account).cloned().unwrap_or(Nonce(0));
                 entry
-                    .insert(AccountTransactions::new(account_nonce))
+                    .insert(AccountTransactions::new(account_nonce, self.ordering))
                     .insert(transaction)
             }
         };
         if let Some(score) = metadata.previous_score {
-            self.l2_priority_queue.remove(&score);
+            self.dequeue(&score);
         }
         if let Some(score) = metadata.new_score {
-            self.l2_priority_queue.insert(score);
+            self.enqueue(score);
         }
         if metadata.is_new {
             self.size += 1;
@@ -126,10 +179,47 @@ impl MempoolStore {
     pub fn has_next(&self, filter: &L2TxFilter) -> bool {
         self.l1_transactions.contains_key(&self.next_priority_id)
             || self
-                .l2_priority_queue
+                .l2_priority_lane
                 .iter()
-                .rfind(|el| el.matches_filter(filter))
-                .is_some()
+                .chain(&self.l2_priority_queue)
+                .any(|el| el.matches_filter(filter))
+    }
+
+    /// Places the score into the priority lane or the general priority queue.
+    fn enqueue(&mut self, score: MempoolScore) {
+        let is_prioritized = self.priority_lane_accounts.contains(&score.account)
+            || self.boosted_transactions.contains(&score.tx_hash);
+        if is_prioritized && self.l2_priority_lane.len() < self.priority_lane_capacity {
+            self.l2_priority_lane.insert(score);
+        } else {
+            self.l2_priority_queue.insert(score);
+        }
+    }
+
+    fn dequeue(&mut self, score: &MempoolScore) {
+        if !self.l2_priority_lane.remove(score) {
+            self.l2_priority_queue.remove(score);
+        }
+    }
+
+    /// Takes the next transaction of the account, which must be pointed to by a removed queue entry,
+    /// and enqueues the account's successor transaction if it exists.
+    fn take_account_transaction(&mut self, account: Address) -> L2Tx {
+        let (transaction, score) = self
+            .l2_transactions_per_account
+            .get_mut(&account)
+            .expect("mempool: dangling pointer in priority queue")
+            .next();
+
+        if let Some(score) = score {
+            self.enqueue(score);
+        }
+        let tx_hash = l2_tx_hash(&transaction);
+        self.last_taken_boosted_transaction = self
+            .boosted_transactions
+            .remove(&tx_hash)
+            .then_some(tx_hash);
+        transaction
     }
 
     /// Returns next transaction for execution from mempool
@@ -139,6 +229,23 @@ impl MempoolStore {
             return Some(transaction.into());
         }
 
+        // Transactions in the priority lane are not stashed if they don't meet the fee requirements
+        // since they are expected to be few; they are just skipped.
+        let lane_pointer = self
+            .l2_priority_lane
+            .iter()
+            .rfind(|el| el.matches_filter(filter))
+            .cloned();
+        if let Some(tx_pointer) = lane_pointer {
+            self.l2_priority_lane.remove(&tx_pointer);
+            let transaction = self.take_account_transaction(tx_pointer.account);
+            self.size = self
+                .size
+                .checked_sub(1)
+                .expect("mempool size can't be negative");
+            return Some(transaction.into());
+        }
+
         let mut removed = 0;
         // We want to fetch the next transaction that would match the fee requirements.
         let tx_pointer = self
@@ -163,15 +270,7 @@ impl MempoolStore {
             self.stashed_accounts.push(stashed_pointer.account);
         }
         // insert pointer to the next transaction if it exists
-        let (transaction, score) = self
-            .l2_transactions_per_account
-            .get_mut(&tx_pointer.account)
-            .expect("mempool: dangling pointer in priority queue")
-            .next();
-
-        if let Some(score) = score {
-            self.l2_priority_queue.insert(score);
-        }
+        let transaction = self.take_account_transaction(tx_pointer.account);
         self.size = self
             .size
             .checked_sub((removed + 1) as u64)
@@ -189,14 +288,20 @@ impl MempoolStore {
                 // reset next priority id
                 self.next_priority_id = self.next_priority_id.min(data.serial_id);
             }
-            ExecuteTransactionCommon::L2(_) => {
+            ExecuteTransactionCommon::L2(data) => {
                 if let Some(score) = self
                     .l2_transactions_per_account
                     .get_mut(&tx.initiator_account())
                     .expect("account is not available in mempool")
                     .reset(tx)
                 {
-                    self.l2_priority_queue.remove(&score);
+                    self.dequeue(&score);
+                }
+                // The transaction is going to be re-inserted, so it should retain the boost.
+                if let Some(input) = &data.input {
+                    if self.last_taken_boosted_transaction == Some(input.hash) {
+                        self.boosted_transactions.insert(input.hash);
+                    }
                 }
             }
             ExecuteTransactionCommon::ProtocolUpgrade(_) => {
@@ -217,14 +322,23 @@ impl MempoolStore {
             l1_transaction_count: self.l1_transactions.len(),
             l2_transaction_count: self.size,
             l2_priority_queue_size: self.l2_priority_queue.len(),
+            l2_priority_lane_size: self.l2_priority_lane.len(),
         }
     }
 
+    /// Returns hashes of all L2 transactions in the mempool.
+    pub fn l2_transaction_hashes(&self) -> impl Iterator<Item = H256> + '_ {
+        self.l2_transactions_per_account
+            .values()
+            .flat_map(AccountTransactions::hashes)
+    }
+
     fn gc(&mut self) -> Vec<Address> {
         if self.size >= self.capacity {
             let index: HashSet<_> = self
                 .l2_priority_queue
                 .iter()
+                .chain(&self.l2_priority_lane)
                 .map(|pointer| pointer.account)
                 .collect();
             let transactions = std::mem::take(&mut self.l2_transactions_per_account);
@@ -236,6 +350,10 @@ impl MempoolStore {
                 .l2_transactions_per_account
                 .iter()
                 .fold(0, |agg, (_, tnxs)| agg + tnxs.len() as u64);
+            // Forget boosts for transactions that are no longer in the mempool.
+            let retained_hashes: HashSet<_> = self.l2_transaction_hashes().collect();
+            self.boosted_transactions
+                .retain(|hash| retained_hashes.contains(hash));
             return drained.into_keys().collect();
         }
         vec![]
@@ -1,6 +1,7 @@
 use std::{
     collections::{HashMap, HashSet},
     iter::FromIterator,
+    num::NonZeroU64,
 };
 
 use zksync_types::{
@@ -12,7 +13,10 @@ use zksync_types::{
     H256, U256,
 };
 
-use crate::{mempool_store::MempoolStore, types::L2TxFilter};
+use crate::{
+    mempool_store::MempoolStore,
+    types::{L2TxFilter, L2TxOrdering},
+};
 
 #[test]
 fn basic_flow() {
@@ -365,6 +369,185 @@ fn mempool_capacity() {
     );
 }
 
+fn priority_fee_ordering(window_ms: u64, max_priority_fee: u64) -> L2TxOrdering {
+    L2TxOrdering::PriorityFee {
+        window_ms: NonZeroU64::new(window_ms).unwrap(),
+        max_priority_fee: NonZeroU64::new(max_priority_fee).unwrap(),
+    }
+}
+
+#[test]
+fn ordering_by_priority_fee() {
+    let ordering = priority_fee_ordering(1_000, 10);
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100).with_ordering(ordering);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let account2 = Address::random();
+    let transactions = vec![
+        gen_l2_tx_with_priority_fee(account0, Nonce(0), 10_000, 1),
+        gen_l2_tx_with_priority_fee(account0, Nonce(1), 10_001, 100),
+        gen_l2_tx_with_priority_fee(account1, Nonce(0), 10_002, 10),
+        gen_l2_tx_with_priority_fee(account2, Nonce(0), 10_003, 10),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    // Transactions with equal fees are ordered by arrival time.
+    let filter = L2TxFilter::default();
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account2, 0));
+    // Nonce ordering is preserved even though the second transaction of `account0` has the highest fee.
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 1));
+    assert_eq!(mempool.next_transaction(&filter), None);
+}
+
+#[test]
+fn priority_fee_ordering_is_bounded_by_window() {
+    let ordering = priority_fee_ordering(1_000, 100);
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100).with_ordering(ordering);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let account2 = Address::random();
+    let account3 = Address::random();
+    let transactions = vec![
+        gen_l2_tx_with_priority_fee(account0, Nonce(0), 10_000, 0),
+        // Advanced by 1,000 ms, i.e., ahead of `account0`.
+        gen_l2_tx_with_priority_fee(account1, Nonce(0), 10_900, 100),
+        // Advanced by 500 ms, i.e., still behind `account0`.
+        gen_l2_tx_with_priority_fee(account2, Nonce(0), 10_600, 50),
+        // Received more than the window after `account0`, so it cannot overtake it despite the max fee.
+        gen_l2_tx_with_priority_fee(account3, Nonce(0), 11_200, 100),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    let filter = L2TxFilter::default();
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account2, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account3, 0));
+}
+
+#[test]
+fn priority_fees_above_cap_do_not_provide_advantage() {
+    let ordering = priority_fee_ordering(1_000, 100);
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100).with_ordering(ordering);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let transactions = vec![
+        gen_l2_tx_with_priority_fee(account0, Nonce(0), 10_000, 100),
+        gen_l2_tx_with_priority_fee(account1, Nonce(0), 10_001, 1_000),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    let filter = L2TxFilter::default();
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 0));
+}
+
+#[test]
+fn priority_lane_accounts() {
+    let operator = Address::random();
+    let other_operator = Address::random();
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100)
+        .with_priority_lane(HashSet::from([operator, other_operator]), 1);
+    let account = Address::random();
+    let transactions = vec![
+        gen_l2_tx_with_timestamp(account, Nonce(0), 0),
+        gen_l2_tx_with_timestamp(operator, Nonce(0), 1),
+        gen_l2_tx_with_timestamp(operator, Nonce(1), 2),
+        // The lane is full, so this transaction is placed into the general queue.
+        gen_l2_tx_with_timestamp(other_operator, Nonce(0), 3),
+    ];
+    mempool.insert(transactions, HashMap::new());
+    assert_eq!(mempool.stats().l2_priority_lane_size, 1);
+    assert_eq!(mempool.stats().l2_priority_queue_size, 2);
+
+    let filter = L2TxFilter::default();
+    assert_eq!(view(mempool.next_transaction(&filter)), (operator, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (operator, 1));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (other_operator, 0));
+    assert_eq!(mempool.next_transaction(&filter), None);
+    assert_eq!(mempool.stats().l2_transaction_count, 0);
+}
+
+#[test]
+fn priority_lane_respects_filter() {
+    let operator = Address::random();
+    let mut mempool =
+        MempoolStore::new(PriorityOpId(0), 100).with_priority_lane(HashSet::from([operator]), 10);
+    let account = Address::random();
+    let transactions =
+        gen_transactions_for_filtering(vec![(operator, Nonce(0), 0, 0), (account, Nonce(0), 1, 1)]);
+    mempool.insert(transactions, HashMap::new());
+
+    let filter_non_zero = L2TxFilter {
+        gas_per_pubdata: 1,
+        ..L2TxFilter::default()
+    };
+    // The lane transaction doesn't match the filter, so it's skipped, but not stashed.
+    assert_eq!(
+        view(mempool.next_transaction(&filter_non_zero)),
+        (account, 0)
+    );
+    assert!(!mempool.has_next(&filter_non_zero));
+    assert!(mempool.get_mempool_info().stashed_accounts.is_empty());
+    assert!(mempool.has_next(&L2TxFilter::default()));
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (operator, 0)
+    );
+}
+
+#[test]
+fn boosted_transactions() {
+    let mut mempool =
+        MempoolStore::new(PriorityOpId(0), 100).with_priority_lane(HashSet::new(), 10);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let boosted_tx = gen_l2_tx_with_hash(account1, Nonce(0), 1);
+    mempool.boost_transactions([boosted_tx.hash()]);
+    let transactions = vec![
+        gen_l2_tx_with_hash(account0, Nonce(0), 0),
+        boosted_tx.clone(),
+        gen_l2_tx_with_hash(account1, Nonce(1), 2),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    let filter = L2TxFilter::default();
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 0));
+    // The boost should be retained after a rollback.
+    mempool.rollback(&boosted_tx);
+    mempool.insert(vec![boosted_tx], HashMap::new());
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 0));
+    // Only the boosted transaction itself is prioritized, not its successors.
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 1));
+}
+
+#[test]
+fn boosting_transaction_already_in_mempool() {
+    let mut mempool =
+        MempoolStore::new(PriorityOpId(0), 100).with_priority_lane(HashSet::new(), 10);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let tx = gen_l2_tx_with_hash(account1, Nonce(0), 1);
+    mempool.insert(
+        vec![gen_l2_tx_with_hash(account0, Nonce(0), 0), tx.clone()],
+        HashMap::new(),
+    );
+
+    // Emulate the transaction being re-fetched from the storage after it was boosted.
+    mempool.boost_transactions([tx.hash()]);
+    mempool.insert(vec![tx], HashMap::new());
+    assert_eq!(mempool.stats().l2_transaction_count, 2);
+    assert_eq!(mempool.stats().l2_priority_lane_size, 1);
+
+    let filter = L2TxFilter::default();
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 0));
+}
+
 fn gen_l2_tx(address: Address, nonce: Nonce) -> Transaction {
     gen_l2_tx_with_timestamp(address, nonce, unix_timestamp_ms())
 }
@@ -384,6 +567,33 @@ fn gen_l2_tx_with_timestamp(address: Address, nonce: Nonce, received_at_ms: u64)
     txn.into()
 }
 
+/// Generates a transaction with a unique hash.
+fn gen_l2_tx_with_hash(address: Address, nonce: Nonce, received_at_ms: u64) -> Transaction {
+    let mut tx = gen_l2_tx_with_timestamp(address, nonce, received_at_ms);
+    match &mut tx.common_data {
+        ExecuteTransactionCommon::L2(data) => data.set_input(vec![], H256::random()),
+        _ => unreachable!(),
+    }
+    tx
+}
+
+fn gen_l2_tx_with_priority_fee(
+    address: Address,
+    nonce: Nonce,
+    received_at_ms: u64,
+    max_priority_fee_per_gas: u64,
+) -> Transaction {
+    let mut tx = gen_l2_tx_with_timestamp(address, nonce, received_at_ms);
+    match &mut tx.common_data {
+        ExecuteTransactionCommon::L2(data) => {
+            data.fee.max_fee_per_gas = U256::from(1_000);
+            data.fee.max_priority_fee_per_gas = U256::from(max_priority_fee_per_gas);
+        }
+        _ => unreachable!(),
+    }
+    tx
+}
+
 fn gen_l1_tx(priority_id: PriorityOpId) -> Transaction {
     let execute = Execute {
         contract_address: Address::repeat_byte(0x11),
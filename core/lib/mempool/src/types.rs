@@ -1,7 +1,7 @@
-use std::{cmp::Ordering, collections::HashMap};
+use std::{cmp::Ordering, collections::HashMap, num::NonZeroU64};
 
 use zksync_types::{
-    fee::Fee, fee_model::BatchFeeInput, l2::L2Tx, Address, Nonce, Transaction, U256,
+    fee::Fee, fee_model::BatchFeeInput, l2::L2Tx, Address, Nonce, Transaction, H256, U256,
 };
 
 /// Pending mempool transactions of account
@@ -12,13 +12,15 @@ pub(crate) struct AccountTransactions {
     /// account nonce in mempool
     /// equals to committed nonce in db + number of transactions sent to state keeper
     nonce: Nonce,
+    ordering: L2TxOrdering,
 }
 
 impl AccountTransactions {
-    pub fn new(nonce: Nonce) -> Self {
+    pub fn new(nonce: Nonce, ordering: L2TxOrdering) -> Self {
         Self {
             transactions: HashMap::new(),
             nonce,
+            ordering,
         }
     }
 
@@ -30,11 +32,11 @@ impl AccountTransactions {
         if nonce < self.nonce {
             return metadata;
         }
-        let new_score = Self::score_for_transaction(&transaction);
+        let new_score = self.score_for_transaction(&transaction);
         let previous_score = self
             .transactions
             .insert(nonce, transaction)
-            .map(|tx| Self::score_for_transaction(&tx));
+            .map(|tx| self.score_for_transaction(&tx));
         metadata.is_new = previous_score.is_none();
         if nonce == self.nonce {
             metadata.new_score = Some(new_score);
@@ -54,7 +56,7 @@ impl AccountTransactions {
         let score = self
             .transactions
             .get(&self.nonce)
-            .map(Self::score_for_transaction);
+            .map(|tx| self.score_for_transaction(tx));
         (transaction, score)
     }
 
@@ -67,28 +69,93 @@ impl AccountTransactions {
         self.nonce = self.nonce.min(tx_nonce);
         self.transactions
             .get(&(tx_nonce + 1))
-            .map(Self::score_for_transaction)
+            .map(|tx| self.score_for_transaction(tx))
     }
 
     pub fn len(&self) -> usize {
         self.transactions.len()
     }
 
-    fn score_for_transaction(transaction: &L2Tx) -> MempoolScore {
+    /// Returns hashes of all transactions for the account.
+    pub fn hashes(&self) -> impl Iterator<Item = H256> + '_ {
+        self.transactions.values().map(l2_tx_hash)
+    }
+
+    fn score_for_transaction(&self, transaction: &L2Tx) -> MempoolScore {
+        let received_at_ms = transaction.received_timestamp_ms;
+        let fee_data = transaction.common_data.fee.clone();
+        let (ordered_at_ms, priority_fee) = match self.ordering {
+            L2TxOrdering::Fifo => (received_at_ms, U256::zero()),
+            L2TxOrdering::PriorityFee {
+                window_ms,
+                max_priority_fee,
+            } => {
+                // The effective priority fee cannot exceed the max fee per gas. Fees above the cap
+                // do not give any further advantage.
+                let priority_fee = fee_data
+                    .max_priority_fee_per_gas
+                    .min(fee_data.max_fee_per_gas)
+                    .min(max_priority_fee.get().into());
+                // Cannot overflow and is guaranteed to not exceed `window_ms`.
+                let advance_ms =
+                    U256::from(window_ms.get()) * priority_fee / U256::from(max_priority_fee.get());
+                let ordered_at_ms = received_at_ms.saturating_sub(advance_ms.as_u64());
+                (ordered_at_ms, priority_fee)
+            }
+        };
         MempoolScore {
             account: transaction.initiator_account(),
-            received_at_ms: transaction.received_timestamp_ms,
-            fee_data: transaction.common_data.fee.clone(),
+            tx_hash: l2_tx_hash(transaction),
+            received_at_ms,
+            ordered_at_ms,
+            priority_fee,
+            fee_data,
         }
     }
 }
 
-/// Mempool score of transaction. Used to prioritize L2 transactions in mempool
-/// Currently trivial ordering is used based on received at timestamp
+/// Returns the hash of an L2 transaction. Unlike [`L2Tx::hash()`], doesn't panic for transactions without input data
+/// (such transactions are only created in tests); zero hash is returned for them.
+pub(crate) fn l2_tx_hash(transaction: &L2Tx) -> H256 {
+    transaction
+        .common_data
+        .input
+        .as_ref()
+        .map_or_else(H256::zero, |input| input.hash)
+}
+
+/// Ordering of L2 transactions from different accounts in the mempool. Transactions from the same account
+/// are always ordered by nonce.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum L2TxOrdering {
+    /// Transactions are ordered by the time they were received.
+    #[default]
+    Fifo,
+    /// Transactions are ordered by the time they were received, advanced proportionally to their effective
+    /// priority fee. A transaction paying at least `max_priority_fee` is advanced by `window_ms`, so a transaction
+    /// can only be fetched ahead of transactions received at most `window_ms` before it.
+    PriorityFee {
+        /// Maximum advance (in ms) a transaction can get from its priority fee.
+        window_ms: NonZeroU64,
+        /// Priority fee per gas (in wei) for which the advance is maximum. Greater fees do not provide
+        /// any further advantage.
+        max_priority_fee: NonZeroU64,
+    },
+}
+
+/// Mempool score of transaction. Used to prioritize L2 transactions in mempool.
+/// Transactions are ordered by the fee-adjusted arrival time, then by priority fee, then by the received at timestamp.
 #[derive(Eq, PartialEq, Clone, Debug, Hash)]
 pub struct MempoolScore {
     pub account: Address,
+    /// Hash of the transaction. Not used for scoring; used to check whether the transaction is boosted
+    /// to the priority lane.
+    pub tx_hash: H256,
     pub received_at_ms: u64,
+    /// Arrival time of the transaction adjusted by its priority fee. Equals `received_at_ms` for FIFO ordering.
+    pub ordered_at_ms: u64,
+    /// Priority fee used for ordering. Always zero for FIFO ordering.
+    pub priority_fee: U256,
     // Not used for actual scoring, but state keeper would request
     // transactions that have acceptable fee values (so transactions
     // with fee too low would be ignored until prices go down).
@@ -105,11 +172,13 @@ impl MempoolScore {
 
 impl Ord for MempoolScore {
     fn cmp(&self, other: &MempoolScore) -> Ordering {
-        match self.received_at_ms.cmp(&other.received_at_ms).reverse() {
-            Ordering::Equal => {}
-            ordering => return ordering,
-        }
-        self.account.cmp(&other.account)
+        // Greater scores are fetched from the mempool first.
+        self.ordered_at_ms
+            .cmp(&other.ordered_at_ms)
+            .reverse()
+            .then_with(|| self.priority_fee.cmp(&other.priority_fee))
+            .then_with(|| self.received_at_ms.cmp(&other.received_at_ms).reverse())
+            .then_with(|| self.account.cmp(&other.account))
     }
 }
 
@@ -159,7 +228,10 @@ mod tests {
 
         let score = MempoolScore {
             account: Address::random(),
+            tx_hash: H256::zero(),              // Not important
             received_at_ms: Default::default(), // Not important
+            ordered_at_ms: Default::default(),  // Not important
+            priority_fee: Default::default(),   // Not important
             fee_data: Fee {
                 gas_limit: Default::default(), // Not important
                 max_fee_per_gas: U256::from(MAX_FEE_PER_GAS),
@@ -0,0 +1,101 @@
+//! Tracer collecting bootloader hooks and memory for debugging bootloader execution outside of the proving pipeline.
+
+use std::sync::Arc;
+
+use once_cell::sync::OnceCell;
+use zksync_types::{vm_version::VmVersion, U256};
+
+use crate::vm_latest::MultiVMSubversion;
+
+pub mod vm_latest;
+
+/// Hook invoked by the bootloader to communicate with the operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BootloaderHook {
+    AccountValidationEntered,
+    PaymasterValidationEntered,
+    NoValidationEntered,
+    ValidationStepEnded,
+    TxHasEnded,
+    DebugLog,
+    DebugReturnData,
+    NearCallCatch,
+    AskOperatorForRefund,
+    NotifyAboutRefund,
+    ExecutionResult,
+    FinalBatchInfo,
+    PubdataRequested,
+}
+
+/// Record of a single bootloader hook invocation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BootloaderHookRecord {
+    pub hook: BootloaderHook,
+    /// Index of the transaction in the L1 batch being processed by the bootloader.
+    pub tx_number_in_block: u16,
+    /// Gas remaining in the current frame when the hook was invoked.
+    pub gas_remaining: u32,
+    /// Monotonic VM cycle counter when the hook was invoked.
+    pub cycle: u32,
+    /// Message logged by the bootloader; only set for [`BootloaderHook::DebugLog`].
+    pub debug_message: Option<String>,
+}
+
+/// Data collected by [`BootloaderDebugTracer`] during a single VM execution.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BootloaderDebugReport {
+    pub hooks: Vec<BootloaderHookRecord>,
+    /// Non-zero words of the bootloader heap after the execution as `(word index, value)` pairs.
+    /// Only collected if the tracer was created with heap dumping enabled.
+    pub bootloader_heap: Vec<(usize, U256)>,
+    /// Gas remaining in the bootloader frame after the execution.
+    pub gas_remaining: u32,
+}
+
+/// Tracer recording all bootloader hooks (including debug logs emitted by the bootloader) and, optionally,
+/// the bootloader heap after the execution. Hooks are additionally logged on the `DEBUG` level.
+///
+/// Only VM 1.5.0 is supported.
+#[derive(Debug, Clone)]
+pub struct BootloaderDebugTracer {
+    subversion: MultiVMSubversion,
+    dump_heap: bool,
+    hooks: Vec<BootloaderHookRecord>,
+    result: Arc<OnceCell<BootloaderDebugReport>>,
+}
+
+impl BootloaderDebugTracer {
+    /// Creates a tracer for the specified VM version.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `vm_version` doesn't correspond to VM 1.5.0.
+    pub fn new(
+        vm_version: VmVersion,
+        dump_heap: bool,
+        result: Arc<OnceCell<BootloaderDebugReport>>,
+    ) -> Self {
+        Self {
+            subversion: vm_version
+                .try_into()
+                .expect("bootloader debugging is only supported for VM 1.5.0"),
+            dump_heap,
+            hooks: vec![],
+            result,
+        }
+    }
+
+    /// Checks whether the tracer supports the specified VM version.
+    pub fn supports(vm_version: VmVersion) -> bool {
+        MultiVMSubversion::try_from(vm_version).is_ok()
+    }
+
+    fn store_result(&mut self, bootloader_heap: Vec<(usize, U256)>, gas_remaining: u32) {
+        let report = BootloaderDebugReport {
+            hooks: std::mem::take(&mut self.hooks),
+            bootloader_heap,
+            gas_remaining,
+        };
+        self.result.set(report).unwrap();
+    }
+}
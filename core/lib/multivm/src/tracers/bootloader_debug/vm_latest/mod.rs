@@ -0,0 +1,95 @@
+use zk_evm_1_5_0::tracing::{BeforeExecutionData, VmLocalStateData};
+use zksync_state::{StoragePtr, WriteStorage};
+
+use crate::{
+    interface::{tracer::VmExecutionStopReason, traits::tracers::dyn_tracers::vm_1_5_0::DynTracer},
+    tracers::bootloader_debug::{BootloaderDebugTracer, BootloaderHook, BootloaderHookRecord},
+    vm_latest::{
+        constants::{get_used_bootloader_memory_words, BOOTLOADER_HEAP_PAGE},
+        tracers::utils::{get_debug_log, VmHook},
+        BootloaderState, HistoryMode, SimpleMemory, VmTracer, ZkSyncVmState,
+    },
+};
+
+impl BootloaderHook {
+    fn from_vm_hook(hook: VmHook) -> Option<Self> {
+        Some(match hook {
+            VmHook::AccountValidationEntered => Self::AccountValidationEntered,
+            VmHook::PaymasterValidationEntered => Self::PaymasterValidationEntered,
+            VmHook::NoValidationEntered => Self::NoValidationEntered,
+            VmHook::ValidationStepEndeded => Self::ValidationStepEnded,
+            VmHook::TxHasEnded => Self::TxHasEnded,
+            VmHook::DebugLog => Self::DebugLog,
+            VmHook::DebugReturnData => Self::DebugReturnData,
+            VmHook::NearCallCatch => Self::NearCallCatch,
+            VmHook::AskOperatorForRefund => Self::AskOperatorForRefund,
+            VmHook::NotifyAboutRefund => Self::NotifyAboutRefund,
+            VmHook::ExecutionResult => Self::ExecutionResult,
+            VmHook::FinalBatchInfo => Self::FinalBatchInfo,
+            VmHook::PubdataRequested => Self::PubdataRequested,
+            VmHook::NoHook => return None,
+        })
+    }
+}
+
+impl<S, H: HistoryMode> DynTracer<S, SimpleMemory<H>> for BootloaderDebugTracer {
+    fn before_execution(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        data: BeforeExecutionData,
+        memory: &SimpleMemory<H>,
+        _storage: StoragePtr<S>,
+    ) {
+        let vm_hook = VmHook::from_opcode_memory(&state, &data, self.subversion);
+        let Some(hook) = BootloaderHook::from_vm_hook(vm_hook) else {
+            return;
+        };
+        let debug_message = matches!(hook, BootloaderHook::DebugLog)
+            .then(|| get_debug_log(&state, memory, self.subversion));
+
+        let record = BootloaderHookRecord {
+            hook,
+            tx_number_in_block: state.vm_local_state.tx_number_in_block,
+            gas_remaining: state.vm_local_state.callstack.current.ergs_remaining,
+            cycle: state.vm_local_state.monotonic_cycle_counter,
+            debug_message,
+        };
+        tracing::debug!(
+            "Bootloader hook {:?} (tx #{}, gas remaining: {}, cycle: {}){}",
+            record.hook,
+            record.tx_number_in_block,
+            record.gas_remaining,
+            record.cycle,
+            record
+                .debug_message
+                .as_ref()
+                .map(|msg| format!(": {msg}"))
+                .unwrap_or_default()
+        );
+        self.hooks.push(record);
+    }
+}
+
+impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H> for BootloaderDebugTracer {
+    fn after_vm_execution(
+        &mut self,
+        state: &mut ZkSyncVmState<S, H>,
+        _bootloader_state: &BootloaderState,
+        _stop_reason: VmExecutionStopReason,
+    ) {
+        let bootloader_heap = if self.dump_heap {
+            let heap_size = get_used_bootloader_memory_words(self.subversion) as u32;
+            state
+                .memory
+                .dump_page_content_as_u256_words(BOOTLOADER_HEAP_PAGE, 0..heap_size)
+                .into_iter()
+                .enumerate()
+                .filter(|(_, word)| !word.is_zero())
+                .collect()
+        } else {
+            vec![]
+        };
+        let gas_remaining = state.local_state.callstack.current.ergs_remaining;
+        self.store_result(bootloader_heap, gas_remaining);
+    }
+}
@@ -0,0 +1,51 @@
+use std::time::Instant;
+
+use crate::{glue::tracers::IntoOldVmTracer, interface::Halt};
+
+pub mod vm_1_4_1;
+pub mod vm_1_4_2;
+pub mod vm_boojum_integration;
+pub mod vm_latest;
+pub mod vm_refunds_enhancement;
+pub mod vm_virtual_blocks;
+
+/// Tracer stopping the VM execution once the specified deadline has passed.
+///
+/// The deadline is checked periodically rather than on each VM cycle, so the execution may overrun the deadline
+/// slightly. Old VM versions (before virtual blocks) are not supported; the execution is never stopped for them.
+#[derive(Debug, Clone)]
+pub struct ExecutionDeadline {
+    deadline: Instant,
+    cycles: usize,
+    is_expired: bool,
+}
+
+impl ExecutionDeadline {
+    /// Number of VM cycles between deadline checks.
+    const CHECK_INTERVAL: usize = 1_024;
+
+    pub fn new(deadline: Instant) -> Self {
+        Self {
+            deadline,
+            cycles: 0,
+            is_expired: false,
+        }
+    }
+
+    /// Accounts for a VM cycle and checks whether the deadline has passed.
+    fn check_cycle(&mut self) -> bool {
+        if !self.is_expired {
+            self.cycles += 1;
+            if self.cycles % Self::CHECK_INTERVAL == 0 {
+                self.is_expired = Instant::now() >= self.deadline;
+            }
+        }
+        self.is_expired
+    }
+
+    fn halt_reason() -> Halt {
+        Halt::TracerCustom("Execution deadline reached".to_owned())
+    }
+}
+
+impl IntoOldVmTracer for ExecutionDeadline {}
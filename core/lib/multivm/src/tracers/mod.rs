@@ -1,11 +1,15 @@
+pub mod bootloader_debug;
 pub mod call_tracer;
+pub mod execution_deadline;
 mod multivm_dispatcher;
 pub mod old_tracers;
 pub mod prestate_tracer;
 pub mod storage_invocation;
 pub mod validator;
 
+pub use bootloader_debug::BootloaderDebugTracer;
 pub use call_tracer::CallTracer;
+pub use execution_deadline::ExecutionDeadline;
 pub use multivm_dispatcher::TracerDispatcher;
 pub use prestate_tracer::PrestateTracer;
 pub use storage_invocation::StorageInvocations;
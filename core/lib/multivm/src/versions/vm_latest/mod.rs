@@ -12,7 +12,7 @@ pub use self::{
         dispatcher::TracerDispatcher,
         traits::{ToTracerPointer, TracerPointer, VmTracer},
     },
-    types::internals::ZkSyncVmState,
+    types::internals::{ReusableVmMemory, ZkSyncVmState},
     utils::transaction_encoding::TransactionVmExt,
     vm::Vm,
 };
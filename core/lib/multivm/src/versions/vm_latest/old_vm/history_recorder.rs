@@ -180,6 +180,11 @@ impl<T: WithHistory, H: HistoryMode> HistoryRecorder<T, H> {
         &self.inner
     }
 
+    /// Returns the inner value, discarding the history.
+    pub(crate) fn into_inner(self) -> T {
+        self.inner
+    }
+
     /// If history exists, modify it using `f`.
     pub fn mutate_history<F: FnOnce(&mut T, &mut EventList<T>)>(&mut self, f: F) {
         H::mutate_history(self, f);
@@ -548,6 +553,13 @@ impl MemoryPage {
         }
     }
 
+    /// Clears all slots on the page while retaining allocated leaves.
+    fn clear(&mut self) {
+        for leaf in self.root.iter_mut().flatten() {
+            leaf.fill(PRIMITIVE_VALUE_EMPTY);
+        }
+    }
+
     fn get_size(&self) -> usize {
         self.root.iter().filter_map(|x| x.as_ref()).count()
             * PAGE_SUBDIVISION_LEN
@@ -626,6 +638,16 @@ impl MemoryWrapper {
     pub fn get_size(&self) -> usize {
         self.memory.iter().map(|page| page.get_size()).sum()
     }
+
+    /// Clears all pages except for `retained_page`. Allocated page leaves are retained,
+    /// so that they can be reused without reallocation.
+    pub(crate) fn clear_pages_except(&mut self, retained_page: usize) {
+        for (i, page) in self.memory.iter_mut().enumerate() {
+            if i != retained_page {
+                page.clear();
+            }
+        }
+    }
 }
 
 impl WithHistory for MemoryWrapper {
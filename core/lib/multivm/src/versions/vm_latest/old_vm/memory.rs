@@ -40,6 +40,20 @@ impl OracleWithHistory for SimpleMemory<HistoryEnabled> {
 }
 
 impl<H: HistoryMode> SimpleMemory<H> {
+    /// Clears all memory pages except for `retained_page`, and resets the history and observable pages.
+    /// Allocated pages are retained, so that the memory can be reused for another VM
+    /// without reallocation.
+    pub(crate) fn reset_except_page(self, retained_page: u32) -> Self {
+        let mut inner = self.memory.into_inner();
+        inner.clear_pages_except(retained_page as usize);
+        let mut memory = MemoryWithHistory::from_inner(inner);
+        memory.mutate_history(|_, h| h.reserve(607));
+        Self {
+            memory,
+            observable_pages: Default::default(),
+        }
+    }
+
     pub fn populate(&mut self, elements: Vec<(u32, Vec<U256>)>, timestamp: Timestamp) {
         for (page, values) in elements.into_iter() {
             for (i, value) in values.into_iter().enumerate() {
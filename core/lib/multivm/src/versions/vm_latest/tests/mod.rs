@@ -19,6 +19,7 @@ mod precompiles;
 mod prestate_tracer;
 mod refunds;
 mod require_eip712;
+mod reusable_memory;
 mod rollbacks;
 mod sekp256r1;
 mod simple_execution;
@@ -0,0 +1,90 @@
+use zk_evm_1_5_0::zkevm_opcode_defs::BOOTLOADER_CODE_PAGE;
+use zksync_types::L1BatchNumber;
+
+use crate::{
+    interface::{ExecutionResult, VmExecutionMode, VmInterface},
+    vm_latest::{
+        tests::tester::{default_l1_batch, TxType, VmTesterBuilder},
+        HistoryDisabled, MultiVMSubversion, ReusableVmMemory, Vm,
+    },
+};
+
+#[test]
+fn executing_tx_with_reused_memory() {
+    let builder = VmTesterBuilder::new(HistoryDisabled)
+        .with_empty_in_memory_storage()
+        .with_l1_batch_env(default_l1_batch(L1BatchNumber(1)))
+        .with_deployer()
+        .with_random_rich_accounts(1);
+
+    let mut vm_tester = builder.clone().build();
+    vm_tester.deploy_test_contract();
+    let tx = vm_tester.rich_accounts[0].get_test_contract_transaction(
+        vm_tester.test_contract.unwrap(),
+        false,
+        Default::default(),
+        false,
+        TxType::L2,
+    );
+    vm_tester.vm.push_transaction(tx.clone());
+    let expected_result = vm_tester.vm.execute(VmExecutionMode::OneTx);
+    assert!(matches!(
+        expected_result.result,
+        ExecutionResult::Success { .. }
+    ));
+    let base_system_contracts = vm_tester.vm.system_env.base_system_smart_contracts.hashes();
+    let memory = vm_tester.vm.into_reusable_memory();
+    assert_eq!(memory.base_system_contracts_hashes(), base_system_contracts);
+
+    // Execute the same transactions on a VM reusing memory from the VM above.
+    let mut vm_tester = builder.build();
+    vm_tester.vm = Vm::new_with_reusable_memory(
+        vm_tester.vm.batch_env.clone(),
+        vm_tester.vm.system_env.clone(),
+        vm_tester.storage.clone(),
+        MultiVMSubversion::latest(),
+        memory,
+    );
+    vm_tester.deploy_test_contract();
+    vm_tester.vm.push_transaction(tx);
+    let result = vm_tester.vm.execute(VmExecutionMode::OneTx);
+
+    assert_eq!(result.result, expected_result.result);
+    assert_eq!(result.logs, expected_result.logs);
+    assert_eq!(
+        result.refunds.gas_refunded,
+        expected_result.refunds.gas_refunded
+    );
+    assert_eq!(
+        result.statistics.gas_used,
+        expected_result.statistics.gas_used
+    );
+}
+
+#[test]
+fn creating_fresh_reusable_memory() {
+    let vm_tester = VmTesterBuilder::new(HistoryDisabled)
+        .with_empty_in_memory_storage()
+        .build();
+    let base_system_contracts = &vm_tester.vm.system_env.base_system_smart_contracts;
+    let memory = ReusableVmMemory::<HistoryDisabled>::new(base_system_contracts);
+    assert_eq!(
+        memory.base_system_contracts_hashes(),
+        base_system_contracts.hashes()
+    );
+
+    let vm = Vm::<_, HistoryDisabled>::new_with_reusable_memory(
+        vm_tester.vm.batch_env.clone(),
+        vm_tester.vm.system_env.clone(),
+        vm_tester.storage.clone(),
+        MultiVMSubversion::latest(),
+        memory,
+    );
+    let bootloader_code = &base_system_contracts.bootloader.code;
+    let code_page_range = 0..bootloader_code.len() as u32;
+    let code_page = vm
+        .state
+        .memory
+        .dump_page_content_as_u256_words(BOOTLOADER_CODE_PAGE, code_page_range);
+    assert_eq!(code_page, *bootloader_code);
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use zk_evm_1_5_0::{aux_structures::Timestamp, zkevm_opcode_defs::BOOTLOADER_CODE_PAGE};
+use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes};
+use zksync_types::{VmVersion, H256, U256};
+use zksync_utils::h256_to_u256;
+
+use crate::vm_latest::old_vm::{history_recorder::HistoryMode, memory::SimpleMemory};
+
+/// Maximum total length of bytecodes (in 32-byte words) retained in the decommitter cache
+/// of [`ReusableVmMemory`] between VM instances (4 MiB).
+const MAX_RETAINED_BYTECODES_LEN: usize = 1 << 17;
+
+/// VM memory and decommitter cache that can be reused across VM instances initialized with the same
+/// base system contracts.
+///
+/// Reusing memory avoids re-populating the bootloader code page, allocating memory pages and
+/// re-loading frequently used bytecodes for each VM instance. This matters for short-lived VMs
+/// created at a high rate, e.g. in the API server sandbox.
+#[derive(Debug)]
+pub struct ReusableVmMemory<H: HistoryMode> {
+    base_system_contracts: BaseSystemContractsHashes,
+    evm_emulator: Option<H256>,
+    pub(crate) memory: SimpleMemory<H>,
+    /// Bytecodes known to the decommitter. Since bytecodes are content-addressed, they remain valid
+    /// for any VM instance.
+    pub(crate) known_bytecodes: HashMap<U256, Vec<U256>>,
+}
+
+impl<H: HistoryMode> ReusableVmMemory<H> {
+    /// Creates memory initialized with the specified base system contracts.
+    pub fn new(base_system_contracts: &BaseSystemContracts) -> Self {
+        let mut memory = SimpleMemory::default();
+        memory.populate(
+            vec![(
+                BOOTLOADER_CODE_PAGE,
+                base_system_contracts.bootloader.code.clone(),
+            )],
+            Timestamp(0),
+        );
+        let default_aa = &base_system_contracts.default_aa;
+        let mut known_bytecodes =
+            HashMap::from([(h256_to_u256(default_aa.hash), default_aa.code.clone())]);
+        if let Some(evm_emulator) = &base_system_contracts.evm_emulator {
+            known_bytecodes.insert(h256_to_u256(evm_emulator.hash), evm_emulator.code.clone());
+        }
+
+        Self {
+            base_system_contracts: base_system_contracts.hashes(),
+            evm_emulator: base_system_contracts.evm_emulator_hash(),
+            memory,
+            known_bytecodes,
+        }
+    }
+
+    /// Checks whether memory reuse is supported by the specified VM version.
+    pub fn is_supported_by(vm_version: VmVersion) -> bool {
+        matches!(
+            vm_version,
+            VmVersion::Vm1_5_0SmallBootloaderMemory | VmVersion::Vm1_5_0IncreasedBootloaderMemory
+        )
+    }
+
+    /// Returns hashes of the base system contracts this memory is initialized with.
+    pub fn base_system_contracts_hashes(&self) -> BaseSystemContractsHashes {
+        self.base_system_contracts
+    }
+
+    /// Returns the hash of the EVM emulator this memory is initialized with, if any.
+    pub fn evm_emulator_hash(&self) -> Option<H256> {
+        self.evm_emulator
+    }
+
+    /// Recovers memory from a VM after it has finished executing, so that it can be used for the next VM instance.
+    pub(crate) fn recover(
+        base_system_contracts: BaseSystemContractsHashes,
+        evm_emulator: Option<H256>,
+        memory: SimpleMemory<H>,
+        mut known_bytecodes: HashMap<U256, Vec<U256>>,
+    ) -> Self {
+        // The bootloader code page is read-only, so it doesn't need to be re-populated.
+        let memory = memory.reset_except_page(BOOTLOADER_CODE_PAGE);
+
+        let retained_bytecodes_len: usize = known_bytecodes.values().map(Vec::len).sum();
+        if retained_bytecodes_len > MAX_RETAINED_BYTECODES_LEN {
+            let default_aa_hash = h256_to_u256(base_system_contracts.default_aa);
+            let evm_emulator_hash = evm_emulator.map(h256_to_u256);
+            known_bytecodes
+                .retain(|hash, _| *hash == default_aa_hash || Some(*hash) == evm_emulator_hash);
+        }
+
+        Self {
+            base_system_contracts,
+            evm_emulator,
+            memory,
+            known_bytecodes,
+        }
+    }
+}
@@ -22,14 +22,14 @@ use crate::{
         constants::BOOTLOADER_HEAP_PAGE,
         old_vm::{
             event_sink::InMemoryEventSink,
-            history_recorder::HistoryMode,
+            history_recorder::{HistoryMode, HistoryRecorder},
             memory::SimpleMemory,
             oracles::{
                 decommitter::DecommitterOracle, precompile::PrecompilesProcessorWithHistory,
             },
         },
         oracles::storage::StorageOracle,
-        types::l1_batch::bootloader_initial_memory,
+        types::{internals::ReusableVmMemory, l1_batch::bootloader_initial_memory},
         utils::l2_blocks::{assert_next_block, load_last_l2_block},
     },
 };
@@ -57,11 +57,13 @@ fn formal_calldata_abi() -> PrimitiveValue {
     }
 }
 
-/// Initialize the vm state and all necessary oracles
+/// Initialize the vm state and all necessary oracles. If `reusable_memory` is provided, it must be initialized
+/// with the same base system contracts as specified in `system_env`.
 pub(crate) fn new_vm_state<S: WriteStorage, H: HistoryMode>(
     storage: StoragePtr<S>,
     system_env: &SystemEnv,
     l1_batch_env: &L1BatchEnv,
+    reusable_memory: Option<ReusableVmMemory<H>>,
 ) -> (ZkSyncVmState<S, H>, BootloaderState) {
     let last_l2_block = if let Some(last_l2_block) = load_last_l2_block(storage.clone()) {
         last_l2_block
@@ -78,35 +80,50 @@ pub(crate) fn new_vm_state<S: WriteStorage, H: HistoryMode>(
     assert_next_block(&last_l2_block, &l1_batch_env.first_l2_block);
     let first_l2_block = l1_batch_env.first_l2_block;
     let storage_oracle: StorageOracle<S, H> = StorageOracle::new(storage.clone());
-    let mut memory = SimpleMemory::default();
     let event_sink = InMemoryEventSink::default();
     let precompiles_processor = PrecompilesProcessorWithHistory::<H>::default();
     let mut decommittment_processor: DecommitterOracle<false, S, H> =
         DecommitterOracle::new(storage);
 
-    decommittment_processor.populate(
-        vec![(
-            h256_to_u256(system_env.base_system_smart_contracts.default_aa.hash),
-            system_env
-                .base_system_smart_contracts
-                .default_aa
-                .code
-                .clone(),
-        )],
-        Timestamp(0),
-    );
+    let mut memory = if let Some(reusable_memory) = reusable_memory {
+        assert_eq!(
+            reusable_memory.base_system_contracts_hashes(),
+            system_env.base_system_smart_contracts.hashes(),
+            "Reusable VM memory is initialized with different base system contracts"
+        );
+        assert_eq!(
+            reusable_memory.evm_emulator_hash(),
+            system_env.base_system_smart_contracts.evm_emulator_hash(),
+            "Reusable VM memory is initialized with a different EVM emulator"
+        );
+        decommittment_processor.known_bytecodes =
+            HistoryRecorder::from_inner(reusable_memory.known_bytecodes);
+        reusable_memory.memory
+    } else {
+        let base_system_contracts = &system_env.base_system_smart_contracts;
+        let mut known_bytecodes = vec![(
+            h256_to_u256(base_system_contracts.default_aa.hash),
+            base_system_contracts.default_aa.code.clone(),
+        )];
+        if let Some(evm_emulator) = &base_system_contracts.evm_emulator {
+            known_bytecodes.push((h256_to_u256(evm_emulator.hash), evm_emulator.code.clone()));
+        }
+        decommittment_processor.populate(known_bytecodes, Timestamp(0));
 
-    memory.populate(
-        vec![(
-            BOOTLOADER_CODE_PAGE,
-            system_env
-                .base_system_smart_contracts
-                .bootloader
-                .code
-                .clone(),
-        )],
-        Timestamp(0),
-    );
+        let mut memory = SimpleMemory::default();
+        memory.populate(
+            vec![(
+                BOOTLOADER_CODE_PAGE,
+                system_env
+                    .base_system_smart_contracts
+                    .bootloader
+                    .code
+                    .clone(),
+            )],
+            Timestamp(0),
+        );
+        memory
+    };
 
     let bootloader_initial_memory = bootloader_initial_memory(l1_batch_env);
     memory.populate_page(
@@ -126,10 +143,13 @@ pub(crate) fn new_vm_state<S: WriteStorage, H: HistoryMode>(
             default_aa_code_hash: h256_to_u256(
                 system_env.base_system_smart_contracts.default_aa.hash,
             ),
-            // For now, the default account hash is used as the code hash for the EVM simulator.
-            // In the 1.5.0 version, it is not possible to instantiate EVM bytecode.
+            // If the EVM emulator is not enabled for the chain, the default account hash is used
+            // as the code hash for the EVM simulator, i.e. EVM bytecode cannot be instantiated.
             evm_simulator_code_hash: h256_to_u256(
-                system_env.base_system_smart_contracts.default_aa.hash,
+                system_env
+                    .base_system_smart_contracts
+                    .evm_emulator_hash()
+                    .unwrap_or(system_env.base_system_smart_contracts.default_aa.hash),
             ),
             zkporter_is_available: system_env.zk_porter_available,
         },
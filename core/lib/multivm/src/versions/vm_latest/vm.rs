@@ -18,7 +18,7 @@ use crate::{
         bootloader_state::BootloaderState,
         old_vm::{events::merge_events, history_recorder::HistoryEnabled},
         tracers::dispatcher::TracerDispatcher,
-        types::internals::{new_vm_state, VmSnapshot, ZkSyncVmState},
+        types::internals::{new_vm_state, ReusableVmMemory, VmSnapshot, ZkSyncVmState},
     },
     HistoryMode,
 };
@@ -226,7 +226,28 @@ impl<S: WriteStorage, H: HistoryMode> Vm<S, H> {
         storage: StoragePtr<S>,
         subversion: MultiVMSubversion,
     ) -> Self {
-        let (state, bootloader_state) = new_vm_state(storage.clone(), &system_env, &batch_env);
+        Self::new_inner(batch_env, system_env, storage, subversion, None)
+    }
+
+    pub(crate) fn new_with_reusable_memory(
+        batch_env: L1BatchEnv,
+        system_env: SystemEnv,
+        storage: StoragePtr<S>,
+        subversion: MultiVMSubversion,
+        memory: ReusableVmMemory<H::Vm1_5_0>,
+    ) -> Self {
+        Self::new_inner(batch_env, system_env, storage, subversion, Some(memory))
+    }
+
+    fn new_inner(
+        batch_env: L1BatchEnv,
+        system_env: SystemEnv,
+        storage: StoragePtr<S>,
+        subversion: MultiVMSubversion,
+        memory: Option<ReusableVmMemory<H::Vm1_5_0>>,
+    ) -> Self {
+        let (state, bootloader_state) =
+            new_vm_state(storage.clone(), &system_env, &batch_env, memory);
         Self {
             bootloader_state,
             state,
@@ -238,6 +259,21 @@ impl<S: WriteStorage, H: HistoryMode> Vm<S, H> {
             _phantom: Default::default(),
         }
     }
+
+    /// Converts this VM into memory that can be reused by another VM instance with the same base system contracts.
+    pub fn into_reusable_memory(self) -> ReusableVmMemory<H::Vm1_5_0> {
+        ReusableVmMemory::recover(
+            self.system_env.base_system_smart_contracts.hashes(),
+            self.system_env
+                .base_system_smart_contracts
+                .evm_emulator_hash(),
+            self.state.memory,
+            self.state
+                .decommittment_processor
+                .known_bytecodes
+                .into_inner(),
+        )
+    }
 }
 
 /// Methods of vm, which required some history manipulations
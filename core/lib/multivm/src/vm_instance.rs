@@ -236,4 +236,49 @@ impl<S: WriteStorage, H: HistoryMode> VmInstance<S, H> {
             }
         }
     }
+
+    /// Same as [`Self::new_with_specific_version()`], but reuses the provided memory if it's supported by the VM version
+    /// (see [`ReusableVmMemory::is_supported_by()`](crate::vm_latest::ReusableVmMemory::is_supported_by())).
+    /// Memory must be initialized with the base system contracts from `system_env`.
+    pub fn new_with_reusable_memory(
+        l1_batch_env: L1BatchEnv,
+        system_env: SystemEnv,
+        storage_view: StoragePtr<S>,
+        vm_version: VmVersion,
+        memory: crate::vm_latest::ReusableVmMemory<H::Vm1_5_0>,
+    ) -> Self {
+        let subversion = match vm_version {
+            VmVersion::Vm1_5_0SmallBootloaderMemory => {
+                crate::vm_latest::MultiVMSubversion::SmallBootloaderMemory
+            }
+            VmVersion::Vm1_5_0IncreasedBootloaderMemory => {
+                crate::vm_latest::MultiVMSubversion::IncreasedBootloaderMemory
+            }
+            _ => {
+                return Self::new_with_specific_version(
+                    l1_batch_env,
+                    system_env,
+                    storage_view,
+                    vm_version,
+                );
+            }
+        };
+        let vm = crate::vm_latest::Vm::new_with_reusable_memory(
+            l1_batch_env,
+            system_env,
+            storage_view,
+            subversion,
+            memory,
+        );
+        VmInstance::Vm1_5_0(vm)
+    }
+
+    /// Converts this VM into memory that can be reused by another VM instance. Returns `None` if memory reuse
+    /// is not supported by the VM version.
+    pub fn into_reusable_memory(self) -> Option<crate::vm_latest::ReusableVmMemory<H::Vm1_5_0>> {
+        match self {
+            VmInstance::Vm1_5_0(vm) => Some(vm.into_reusable_memory()),
+            _ => None,
+        }
+    }
 }
@@ -9,6 +9,7 @@ use crate::{
     gcs::{GoogleCloudStore, GoogleCloudStoreAuthMode},
     mirror::MirroringObjectStore,
     raw::{ObjectStore, ObjectStoreError},
+    replication::ReplicatingObjectStore,
     retries::StoreWithRetries,
 };
 
@@ -65,7 +66,7 @@ impl ObjectStoreFactory {
                     )
                 })
                 .await?;
-                Self::wrap_mirroring(store, config.local_mirror_path.as_ref()).await
+                Self::wrap_store(store, config).await
             }
             ObjectStoreMode::GCSWithCredentialFile {
                 bucket_base_url,
@@ -80,7 +81,7 @@ impl ObjectStoreFactory {
                     )
                 })
                 .await?;
-                Self::wrap_mirroring(store, config.local_mirror_path.as_ref()).await
+                Self::wrap_store(store, config).await
             }
             ObjectStoreMode::GCSAnonymousReadOnly { bucket_base_url } => {
                 let store = StoreWithRetries::try_new(config.max_retries, || {
@@ -90,7 +91,7 @@ impl ObjectStoreFactory {
                     )
                 })
                 .await?;
-                Self::wrap_mirroring(store, config.local_mirror_path.as_ref()).await
+                Self::wrap_store(store, config).await
             }
 
             ObjectStoreMode::FileBacked {
@@ -104,8 +105,41 @@ impl ObjectStoreFactory {
                 if let Some(mirror_path) = &config.local_mirror_path {
                     tracing::warn!("Mirroring doesn't make sense with file-backed object store; ignoring mirror path `{mirror_path}`");
                 }
-                Ok(Arc::new(store))
+                Ok(match Self::create_replica(config).await? {
+                    Some(replica) => Arc::new(ReplicatingObjectStore::new(store, replica)),
+                    None => Arc::new(store),
+                })
+            }
+        }
+    }
+
+    async fn create_replica(
+        config: &ObjectStoreConfig,
+    ) -> Result<Option<Arc<dyn ObjectStore>>, ObjectStoreError> {
+        let Some(replica_config) = config.replica.as_deref() else {
+            return Ok(None);
+        };
+        if replica_config.replica.is_some() {
+            return Err(ObjectStoreError::Initialization {
+                source: "object store replica cannot have a replica itself".into(),
+                is_transient: false,
+            });
+        }
+        let replica = Box::pin(Self::create_from_config(replica_config)).await?;
+        Ok(Some(replica))
+    }
+
+    async fn wrap_store(
+        store: impl ObjectStore,
+        config: &ObjectStoreConfig,
+    ) -> Result<Arc<dyn ObjectStore>, ObjectStoreError> {
+        let mirror_path = config.local_mirror_path.as_ref();
+        match Self::create_replica(config).await? {
+            Some(replica) => {
+                let store = ReplicatingObjectStore::new(store, replica);
+                Self::wrap_mirroring(store, mirror_path).await
             }
+            None => Self::wrap_mirroring(store, mirror_path).await,
         }
     }
 
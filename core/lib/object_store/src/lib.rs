@@ -31,6 +31,7 @@ mod mirror;
 mod mock;
 mod objects;
 mod raw;
+mod replication;
 mod retries;
 
 // Re-export `bincode` crate so that client binaries can conveniently use it.
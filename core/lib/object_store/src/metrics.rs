@@ -2,7 +2,7 @@
 
 use std::time::Duration;
 
-use vise::{Buckets, Histogram, LabeledFamily, LatencyObserver, Metrics};
+use vise::{Buckets, Counter, Histogram, LabeledFamily, LatencyObserver, Metrics};
 
 use crate::Bucket;
 
@@ -15,6 +15,15 @@ pub(crate) struct ObjectStoreMetrics {
     /// Latency to store an object in the store (accounting for retries).
     #[metrics(buckets = Buckets::LATENCIES, labels = ["bucket"])]
     storing_time: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Number of objects successfully copied to the replica store.
+    #[metrics(labels = ["bucket"])]
+    pub replicated_objects: LabeledFamily<&'static str, Counter>,
+    /// Number of objects that failed to be copied to the replica store (after retries).
+    #[metrics(labels = ["bucket"])]
+    pub replication_failures: LabeledFamily<&'static str, Counter>,
+    /// Number of reads served by the replica store because the primary store has failed.
+    #[metrics(labels = ["bucket"])]
+    pub read_failovers: LabeledFamily<&'static str, Counter>,
 }
 
 impl ObjectStoreMetrics {
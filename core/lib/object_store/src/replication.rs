@@ -0,0 +1,376 @@
+//! Object store replicating critical objects to a secondary store.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::Semaphore;
+
+use crate::{
+    metrics::OBJECT_STORE_METRICS,
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+};
+
+/// Replicated objects specified as buckets and key prefixes. These are objects that cannot be easily regenerated
+/// if the primary store is lost: final proofs for L1 batches (as opposed to intermediate proofs stored in the same bucket)
+/// and snapshot chunks.
+const REPLICATED_OBJECTS: [(Bucket, &str); 2] = [
+    (Bucket::ProofsFri, "l1_batch_proof_"),
+    (Bucket::StorageSnapshot, ""),
+];
+
+/// Maximum number of objects concurrently put to the replica store.
+const MAX_CONCURRENT_REPLICATIONS: usize = 16;
+
+/// [`ObjectStore`] wrapper copying [`REPLICATED_OBJECTS`] to a replica store.
+///
+/// An object is replicated as a part of the put operation once it is stored in the primary store, so replication
+/// is not lost if the process is stopped. The number of concurrent replications is bounded; a put operation waits
+/// for a free slot. Replication is best-effort: replication errors (after retries configured for the replica store)
+/// are logged and reported in metrics, but do not fail the put operation. Reads fail over to the replica
+/// if the primary store returns an error other than [`ObjectStoreError::KeyNotFound`].
+#[derive(Debug)]
+pub(crate) struct ReplicatingObjectStore<S> {
+    inner: S,
+    replica: Arc<dyn ObjectStore>,
+    replication_permits: Semaphore,
+}
+
+impl<S: ObjectStore> ReplicatingObjectStore<S> {
+    pub fn new(inner: S, replica: Arc<dyn ObjectStore>) -> Self {
+        tracing::info!("Initializing replication for store {inner:?} to {replica:?}");
+        Self {
+            inner,
+            replica,
+            replication_permits: Semaphore::new(MAX_CONCURRENT_REPLICATIONS),
+        }
+    }
+
+    fn is_replicated(bucket: Bucket, key: &str) -> bool {
+        REPLICATED_OBJECTS
+            .iter()
+            .any(|&(replicated_bucket, prefix)| {
+                replicated_bucket == bucket && key.starts_with(prefix)
+            })
+    }
+
+    async fn replicate(&self, bucket: Bucket, key: &str, value: Vec<u8>) {
+        let _permit = self
+            .replication_permits
+            .acquire()
+            .await
+            .expect("replication semaphore is never closed");
+        match self.replica.put_raw(bucket, key, value).await {
+            Ok(()) => {
+                tracing::trace!("replicated object `{key}` in bucket {bucket}");
+                OBJECT_STORE_METRICS.replicated_objects[&bucket.as_str()].inc();
+            }
+            Err(err) => {
+                tracing::error!(
+                    "failed replicating object `{key}` in bucket {bucket}: {:#}",
+                    anyhow::Error::from(err)
+                );
+                OBJECT_STORE_METRICS.replication_failures[&bucket.as_str()].inc();
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<S: ObjectStore> ObjectStore for ReplicatingObjectStore<S> {
+    #[tracing::instrument(skip(self))]
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let err = match self.inner.get_raw(bucket, key).await {
+            Ok(object) => return Ok(object),
+            Err(err) if !Self::is_replicated(bucket, key) => return Err(err),
+            Err(err @ ObjectStoreError::KeyNotFound(_)) => return Err(err),
+            Err(err) => err,
+        };
+
+        tracing::warn!(
+            "failed getting object from primary store, failing over to replica: {:#}",
+            anyhow::Error::from(err)
+        );
+        OBJECT_STORE_METRICS.read_failovers[&bucket.as_str()].inc();
+        self.replica.get_raw(bucket, key).await
+    }
+
+    #[tracing::instrument(skip(self, value), fields(value.len = value.len()))]
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        if !Self::is_replicated(bucket, key) {
+            return self.inner.put_raw(bucket, key, value).await;
+        }
+
+        self.inner.put_raw(bucket, key, value.clone()).await?;
+        // Only replicate the value once it has been put in the underlying store
+        self.replicate(bucket, key, value).await;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        self.inner.remove_raw(bucket, key).await?;
+        if Self::is_replicated(bucket, key) {
+            if let Err(err) = self.replica.remove_raw(bucket, key).await {
+                tracing::warn!(
+                    "failed removing object from replica: {:#}",
+                    anyhow::Error::from(err)
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        self.inner.storage_prefix_raw(bucket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use assert_matches::assert_matches;
+
+    use super::*;
+    use crate::MockObjectStore;
+
+    const PROOF_KEY: &str = "l1_batch_proof_1_0_24_0.bin";
+
+    /// Store that can be switched into a state in which all operations fail.
+    #[derive(Debug, Default)]
+    struct FlakyStore {
+        inner: MockObjectStore,
+        is_down: AtomicBool,
+    }
+
+    impl FlakyStore {
+        fn check(&self) -> Result<(), ObjectStoreError> {
+            if self.is_down.load(Ordering::SeqCst) {
+                Err(ObjectStoreError::Other {
+                    source: "store is down".into(),
+                    is_transient: true,
+                })
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for Arc<FlakyStore> {
+        async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+            self.check()?;
+            self.inner.get_raw(bucket, key).await
+        }
+
+        async fn put_raw(
+            &self,
+            bucket: Bucket,
+            key: &str,
+            value: Vec<u8>,
+        ) -> Result<(), ObjectStoreError> {
+            self.check()?;
+            self.inner.put_raw(bucket, key, value).await
+        }
+
+        async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+            self.check()?;
+            self.inner.remove_raw(bucket, key).await
+        }
+
+        fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+            self.inner.storage_prefix_raw(bucket)
+        }
+    }
+
+    #[test]
+    fn replicated_objects() {
+        type ReplicatingStore = ReplicatingObjectStore<MockObjectStore>;
+
+        assert!(ReplicatingStore::is_replicated(
+            Bucket::ProofsFri,
+            PROOF_KEY
+        ));
+        assert!(!ReplicatingStore::is_replicated(
+            Bucket::ProofsFri,
+            "proof_1.bin"
+        ));
+        assert!(ReplicatingStore::is_replicated(
+            Bucket::StorageSnapshot,
+            "snapshot_l1_batch_1_storage_logs_part_0000.proto.gzip"
+        ));
+        assert!(!ReplicatingStore::is_replicated(
+            Bucket::ProverJobsFri,
+            PROOF_KEY
+        ));
+    }
+
+    #[tokio::test]
+    async fn replication_basics() {
+        let primary = Arc::new(FlakyStore::default());
+        let replica = MockObjectStore::arc();
+        let store = ReplicatingObjectStore::new(primary.clone(), replica.clone());
+
+        store
+            .put_raw(Bucket::ProofsFri, PROOF_KEY, vec![1, 2, 3])
+            .await
+            .unwrap();
+        store
+            .put_raw(Bucket::ProofsFri, "proof_1.bin", vec![1, 1, 1])
+            .await
+            .unwrap();
+        store
+            .put_raw(Bucket::ProverJobsFri, "job", vec![3, 2, 1])
+            .await
+            .unwrap();
+        // Replication is completed once the put operation returns.
+        let replicated = replica.get_raw(Bucket::ProofsFri, PROOF_KEY).await.unwrap();
+        assert_eq!(replicated, [1, 2, 3]);
+        // Intermediate proofs are not replicated.
+        let err = replica
+            .get_raw(Bucket::ProofsFri, "proof_1.bin")
+            .await
+            .unwrap_err();
+        assert_matches!(err, ObjectStoreError::KeyNotFound(_));
+
+        primary.is_down.store(true, Ordering::SeqCst);
+        let object = store.get_raw(Bucket::ProofsFri, PROOF_KEY).await.unwrap();
+        assert_eq!(object, [1, 2, 3]);
+        // Non-replicated objects are not available during the primary store outage.
+        let err = store
+            .get_raw(Bucket::ProofsFri, "proof_1.bin")
+            .await
+            .unwrap_err();
+        assert_matches!(err, ObjectStoreError::Other { .. });
+        let err = store
+            .get_raw(Bucket::ProverJobsFri, "job")
+            .await
+            .unwrap_err();
+        assert_matches!(err, ObjectStoreError::Other { .. });
+        let err = replica
+            .get_raw(Bucket::ProverJobsFri, "job")
+            .await
+            .unwrap_err();
+        assert_matches!(err, ObjectStoreError::KeyNotFound(_));
+
+        primary.is_down.store(false, Ordering::SeqCst);
+        let err = store
+            .get_raw(Bucket::ProofsFri, "l1_batch_proof_2_0_24_0.bin")
+            .await
+            .unwrap_err();
+        assert_matches!(err, ObjectStoreError::KeyNotFound(_));
+
+        store
+            .remove_raw(Bucket::ProofsFri, PROOF_KEY)
+            .await
+            .unwrap();
+        let err = replica
+            .get_raw(Bucket::ProofsFri, PROOF_KEY)
+            .await
+            .unwrap_err();
+        assert_matches!(err, ObjectStoreError::KeyNotFound(_));
+    }
+
+    #[tokio::test]
+    async fn replica_outage_does_not_affect_primary_store() {
+        let replica = Arc::new(FlakyStore::default());
+        replica.is_down.store(true, Ordering::SeqCst);
+        let store = ReplicatingObjectStore::new(MockObjectStore::default(), Arc::new(replica));
+
+        store
+            .put_raw(Bucket::StorageSnapshot, "chunk", vec![1, 2, 3])
+            .await
+            .unwrap();
+        let object = store
+            .get_raw(Bucket::StorageSnapshot, "chunk")
+            .await
+            .unwrap();
+        assert_eq!(object, [1, 2, 3]);
+        store
+            .remove_raw(Bucket::StorageSnapshot, "chunk")
+            .await
+            .unwrap();
+    }
+
+    /// Store tracking the maximum number of concurrent put operations.
+    #[derive(Debug, Default)]
+    struct SlowStore {
+        inner: MockObjectStore,
+        current_puts: AtomicUsize,
+        max_concurrent_puts: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ObjectStore for Arc<SlowStore> {
+        async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+            self.inner.get_raw(bucket, key).await
+        }
+
+        async fn put_raw(
+            &self,
+            bucket: Bucket,
+            key: &str,
+            value: Vec<u8>,
+        ) -> Result<(), ObjectStoreError> {
+            let current_puts = self.current_puts.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_concurrent_puts
+                .fetch_max(current_puts, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            self.current_puts.fetch_sub(1, Ordering::SeqCst);
+            self.inner.put_raw(bucket, key, value).await
+        }
+
+        async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+            self.inner.remove_raw(bucket, key).await
+        }
+
+        fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+            self.inner.storage_prefix_raw(bucket)
+        }
+    }
+
+    #[tokio::test]
+    async fn replication_concurrency_is_bounded() {
+        let replica = Arc::new(SlowStore::default());
+        let store = Arc::new(ReplicatingObjectStore::new(
+            MockObjectStore::default(),
+            Arc::new(replica.clone()),
+        ));
+
+        let object_count = MAX_CONCURRENT_REPLICATIONS * 3;
+        let put_tasks = (0..object_count).map(|i| {
+            let store = store.clone();
+            tokio::spawn(async move {
+                let key = format!("chunk_{i}");
+                store
+                    .put_raw(Bucket::StorageSnapshot, &key, vec![1, 2, 3])
+                    .await
+            })
+        });
+        for task in put_tasks.collect::<Vec<_>>() {
+            task.await.unwrap().unwrap();
+        }
+
+        let max_concurrent_puts = replica.max_concurrent_puts.load(Ordering::SeqCst);
+        assert!(
+            max_concurrent_puts <= MAX_CONCURRENT_REPLICATIONS,
+            "{max_concurrent_puts}"
+        );
+        for i in 0..object_count {
+            let key = format!("chunk_{i}");
+            let object = replica
+                .get_raw(Bucket::StorageSnapshot, &key)
+                .await
+                .unwrap();
+            assert_eq!(object, [1, 2, 3]);
+        }
+    }
+}
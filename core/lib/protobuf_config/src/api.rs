@@ -30,6 +30,28 @@ impl ProtoRepr for proto::Api {
     }
 }
 
+impl proto::GethCompatibilityShim {
+    fn new(x: &api::GethCompatibilityShim) -> Self {
+        use api::GethCompatibilityShim as From;
+        match x {
+            From::BlockMiner => Self::BlockMiner,
+            From::ErrorCodes => Self::ErrorCodes,
+            From::NetWeb3Methods => Self::NetWeb3Methods,
+            From::BlockFields => Self::BlockFields,
+        }
+    }
+
+    fn parse(&self) -> api::GethCompatibilityShim {
+        use api::GethCompatibilityShim as To;
+        match self {
+            Self::BlockMiner => To::BlockMiner,
+            Self::ErrorCodes => To::ErrorCodes,
+            Self::NetWeb3Methods => To::NetWeb3Methods,
+            Self::BlockFields => To::BlockFields,
+        }
+    }
+}
+
 impl ProtoRepr for proto::Web3JsonRpc {
     type Type = api::Web3JsonRpcConfig;
 
@@ -95,6 +117,11 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 &self.estimate_gas_acceptable_overestimation,
             )
             .context("acceptable_overestimation")?,
+            estimate_fee_batch_max_size: self
+                .estimate_fee_batch_max_size
+                .map(|x| x.try_into())
+                .transpose()
+                .context("estimate_fee_batch_max_size")?,
             max_tx_size: required(&self.max_tx_size)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("max_tx_size")?,
@@ -108,6 +135,18 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("vm_concurrency_limit")?,
+            vm_concurrency_limit_per_client: self
+                .vm_concurrency_limit_per_client
+                .map(|x| x.try_into())
+                .transpose()
+                .context("vm_concurrency_limit_per_client")?,
+            vm_client_id_header: self.vm_client_id_header.clone(),
+            vm_client_id_trusted_proxies: self
+                .vm_client_id_trusted_proxies
+                .map(|x| x.try_into())
+                .transpose()
+                .context("vm_client_id_trusted_proxies")?,
+            vm_permit_timeout_ms: self.vm_permit_timeout_ms,
             factory_deps_cache_size_mb: self
                 .factory_deps_cache_size_mb
                 .map(|x| x.try_into())
@@ -147,6 +186,39 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("mempool_cache_size")?,
+            eth_call_cache_size: self
+                .eth_call_cache_size
+                .map(|x| x.try_into())
+                .transpose()
+                .context("eth_call_cache_size")?,
+            eth_call_cache_ttl_ms: self.eth_call_cache_ttl_ms,
+            rejected_txs_retention_sec: self.rejected_txs_retention_sec,
+            tx_replacement_fee_bump_percent: self.tx_replacement_fee_bump_percent,
+            query_statement_timeout_ms: self.query_statement_timeout_ms,
+            mempool_max_txs_per_sender: self.mempool_max_txs_per_sender,
+            mempool_max_txs: self.mempool_max_txs,
+            mempool_max_size_mb: self.mempool_max_size_mb,
+            admin_namespace_enabled: self.admin_namespace_enabled.unwrap_or(false),
+            admin_api_keys: self.admin_api_keys.clone(),
+            api_runtime_worker_threads: self
+                .api_runtime_worker_threads
+                .map(|x| x.try_into())
+                .transpose()
+                .context("api_runtime_worker_threads")?,
+            idempotency_keys_cache_size: self
+                .idempotency_keys_cache_size
+                .map(|x| x.try_into())
+                .transpose()
+                .context("idempotency_keys_cache_size")?,
+            idempotency_key_ttl_sec: self.idempotency_key_ttl_sec,
+            min_gas_per_pubdata_limit: self.min_gas_per_pubdata_limit,
+            max_gas_per_pubdata_limit: self.max_gas_per_pubdata_limit,
+            reject_unprotected_txs: self.reject_unprotected_txs.unwrap_or(false),
+            vm_memory_pool_size: self
+                .vm_memory_pool_size
+                .map(|x| x.try_into())
+                .transpose()
+                .context("vm_memory_pool_size")?,
             whitelisted_tokens_for_aa: self
                 .whitelisted_tokens_for_aa
                 .iter()
@@ -154,6 +226,19 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .map(|(i, k)| parse_h160(k).context(i))
                 .collect::<Result<Vec<_>, _>>()
                 .context("account_pks")?,
+            geth_compatibility: self
+                .geth_compatibility
+                .iter()
+                .enumerate()
+                .map(|(i, x)| {
+                    anyhow::Ok(
+                        proto::GethCompatibilityShim::try_from(*x)
+                            .context(i)?
+                            .parse(),
+                    )
+                })
+                .collect::<Result<_, _>>()
+                .context("geth_compatibility")?,
         })
     }
 
@@ -167,6 +252,27 @@ impl ProtoRepr for proto::Web3JsonRpc {
             filters_disabled: Some(this.filters_disabled),
             mempool_cache_update_interval: this.mempool_cache_update_interval,
             mempool_cache_size: this.mempool_cache_size.map(|x| x.try_into().unwrap()),
+            eth_call_cache_size: this.eth_call_cache_size.map(|x| x.try_into().unwrap()),
+            eth_call_cache_ttl_ms: this.eth_call_cache_ttl_ms,
+            rejected_txs_retention_sec: this.rejected_txs_retention_sec,
+            tx_replacement_fee_bump_percent: this.tx_replacement_fee_bump_percent,
+            query_statement_timeout_ms: this.query_statement_timeout_ms,
+            mempool_max_txs_per_sender: this.mempool_max_txs_per_sender,
+            mempool_max_txs: this.mempool_max_txs,
+            mempool_max_size_mb: this.mempool_max_size_mb,
+            admin_namespace_enabled: Some(this.admin_namespace_enabled),
+            admin_api_keys: this.admin_api_keys.clone(),
+            api_runtime_worker_threads: this
+                .api_runtime_worker_threads
+                .map(|x| x.try_into().unwrap()),
+            idempotency_keys_cache_size: this
+                .idempotency_keys_cache_size
+                .map(|x| x.try_into().unwrap()),
+            idempotency_key_ttl_sec: this.idempotency_key_ttl_sec,
+            min_gas_per_pubdata_limit: this.min_gas_per_pubdata_limit,
+            max_gas_per_pubdata_limit: this.max_gas_per_pubdata_limit,
+            reject_unprotected_txs: Some(this.reject_unprotected_txs),
+            vm_memory_pool_size: this.vm_memory_pool_size.map(|x| x.try_into().unwrap()),
             filters_limit: this.filters_limit,
             subscriptions_limit: this.subscriptions_limit,
             pubsub_polling_interval: this.pubsub_polling_interval,
@@ -182,11 +288,22 @@ impl ProtoRepr for proto::Web3JsonRpc {
             estimate_gas_acceptable_overestimation: Some(
                 this.estimate_gas_acceptable_overestimation,
             ),
+            estimate_fee_batch_max_size: this
+                .estimate_fee_batch_max_size
+                .map(|x| x.try_into().unwrap()),
             max_tx_size: Some(this.max_tx_size.try_into().unwrap()),
             vm_execution_cache_misses_limit: this
                 .vm_execution_cache_misses_limit
                 .map(|x| x.try_into().unwrap()),
             vm_concurrency_limit: this.vm_concurrency_limit.map(|x| x.try_into().unwrap()),
+            vm_concurrency_limit_per_client: this
+                .vm_concurrency_limit_per_client
+                .map(|x| x.try_into().unwrap()),
+            vm_client_id_header: this.vm_client_id_header.clone(),
+            vm_client_id_trusted_proxies: this
+                .vm_client_id_trusted_proxies
+                .map(|x| x.try_into().unwrap()),
+            vm_permit_timeout_ms: this.vm_permit_timeout_ms,
             factory_deps_cache_size_mb: this
                 .factory_deps_cache_size_mb
                 .map(|x| x.try_into().unwrap()),
@@ -222,6 +339,11 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .iter()
                 .map(|k| format!("{:?}", k))
                 .collect(),
+            geth_compatibility: this
+                .geth_compatibility
+                .iter()
+                .map(|x| proto::GethCompatibilityShim::new(x).into())
+                .collect(),
         }
     }
 }
@@ -1,8 +1,10 @@
+use std::num::NonZeroU64;
+
 use anyhow::Context as _;
 use zksync_config::configs;
 use zksync_protobuf::{repr::ProtoRepr, required};
 
-use crate::proto::chain as proto;
+use crate::{parse_h160, proto::chain as proto};
 
 impl proto::FeeModelVersion {
     fn new(n: &configs::chain::FeeModelVersion) -> Self {
@@ -40,6 +42,36 @@ impl ProtoRepr for proto::StateKeeper {
             l2_block_max_payload_size: required(&self.miniblock_max_payload_size)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("miniblock_max_payload_size")?,
+            l2_block_max_pubdata: self.l2_block_max_pubdata,
+            max_timestamp_drift_sec: self.max_timestamp_drift_sec,
+            graceful_shutdown: self.graceful_shutdown.unwrap_or(false),
+            graceful_shutdown_l1_batch_seal_timeout_ms: self
+                .graceful_shutdown_l1_batch_seal_timeout_ms,
+            discard_pending_l1_batch_on_restart: self
+                .discard_pending_l1_batch_on_restart
+                .unwrap_or(false),
+            l1_gas_price_rise_threshold: self.l1_gas_price_rise_threshold,
+            l1_gas_price_rise_deadline_factor: self.l1_gas_price_rise_deadline_factor,
+            l1_gas_price_fall_threshold: self.l1_gas_price_fall_threshold,
+            l1_gas_price_fall_deadline_factor: self.l1_gas_price_fall_deadline_factor,
+            max_batch_execution_time_ms: self.max_batch_execution_time_ms,
+            max_batch_vm_memory_mb: self
+                .max_batch_vm_memory_mb
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_batch_vm_memory_mb")?,
+            upgrade_canary_batches: self.upgrade_canary_batches,
+            tx_presimulation_batch_size: self
+                .tx_presimulation_batch_size
+                .map(|x| x.try_into())
+                .transpose()
+                .context("tx_presimulation_batch_size")?,
+            fee_account_rotation_period_sec: self.fee_account_rotation_period_sec,
+            bytecode_compression_dictionary_size: self
+                .bytecode_compression_dictionary_size
+                .map(|x| x.try_into())
+                .transpose()
+                .context("bytecode_compression_dictionary_size")?,
             max_single_tx_gas: *required(&self.max_single_tx_gas).context("max_single_tx_gas")?,
             max_allowed_l2_tx_gas_limit: *required(&self.max_allowed_l2_tx_gas_limit)
                 .context("max_allowed_l2_tx_gas_limit")?,
@@ -75,9 +107,11 @@ impl ProtoRepr for proto::StateKeeper {
             validation_computational_gas_limit: *required(&self.validation_computational_gas_limit)
                 .context("validation_computational_gas_limit")?,
             save_call_traces: *required(&self.save_call_traces).context("save_call_traces")?,
+            flat_call_traces: self.flat_call_traces.unwrap_or(false),
             max_circuits_per_batch: required(&self.max_circuits_per_batch)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("max_circuits_per_batch")?,
+            base_system_contracts_path: self.base_system_contracts_path.clone(),
 
             // We need these values only for instantiating configs from environmental variables, so it's not
             // needed during the initialization from files
@@ -97,6 +131,24 @@ impl ProtoRepr for proto::StateKeeper {
                 this.l2_block_seal_queue_capacity.try_into().unwrap(),
             ),
             miniblock_max_payload_size: Some(this.l2_block_max_payload_size.try_into().unwrap()),
+            l2_block_max_pubdata: this.l2_block_max_pubdata,
+            max_timestamp_drift_sec: this.max_timestamp_drift_sec,
+            graceful_shutdown: Some(this.graceful_shutdown),
+            graceful_shutdown_l1_batch_seal_timeout_ms: this
+                .graceful_shutdown_l1_batch_seal_timeout_ms,
+            discard_pending_l1_batch_on_restart: Some(this.discard_pending_l1_batch_on_restart),
+            l1_gas_price_rise_threshold: this.l1_gas_price_rise_threshold,
+            l1_gas_price_rise_deadline_factor: this.l1_gas_price_rise_deadline_factor,
+            l1_gas_price_fall_threshold: this.l1_gas_price_fall_threshold,
+            l1_gas_price_fall_deadline_factor: this.l1_gas_price_fall_deadline_factor,
+            max_batch_execution_time_ms: this.max_batch_execution_time_ms,
+            max_batch_vm_memory_mb: this.max_batch_vm_memory_mb.map(|x| x as u64),
+            upgrade_canary_batches: this.upgrade_canary_batches,
+            tx_presimulation_batch_size: this.tx_presimulation_batch_size.map(|x| x as u64),
+            fee_account_rotation_period_sec: this.fee_account_rotation_period_sec,
+            bytecode_compression_dictionary_size: this
+                .bytecode_compression_dictionary_size
+                .map(|x| x as u64),
             max_single_tx_gas: Some(this.max_single_tx_gas),
             max_allowed_l2_tx_gas_limit: Some(this.max_allowed_l2_tx_gas_limit),
             reject_tx_at_geometry_percentage: Some(this.reject_tx_at_geometry_percentage),
@@ -114,7 +166,9 @@ impl ProtoRepr for proto::StateKeeper {
             fee_model_version: Some(proto::FeeModelVersion::new(&this.fee_model_version).into()),
             validation_computational_gas_limit: Some(this.validation_computational_gas_limit),
             save_call_traces: Some(this.save_call_traces),
+            flat_call_traces: Some(this.flat_call_traces),
             max_circuits_per_batch: Some(this.max_circuits_per_batch.try_into().unwrap()),
+            base_system_contracts_path: this.base_system_contracts_path.clone(),
         }
     }
 }
@@ -146,6 +200,33 @@ impl ProtoRepr for proto::Mempool {
             stuck_tx_timeout: *required(&self.stuck_tx_timeout).context("stuck_tx_timeout")?,
             remove_stuck_txs: *required(&self.remove_stuck_txs).context("remove_stuck_txs")?,
             delay_interval: *required(&self.delay_interval).context("delay_interval")?,
+            priority_fee_ordering: self.priority_fee_ordering.unwrap_or(false),
+            priority_fee_ordering_window_ms: match self.priority_fee_ordering_window_ms {
+                Some(window) => NonZeroU64::new(window)
+                    .context("cannot be 0")
+                    .context("priority_fee_ordering_window_ms")?,
+                None => Self::Type::default_priority_fee_ordering_window_ms(),
+            },
+            priority_fee_ordering_max_fee: match self.priority_fee_ordering_max_fee {
+                Some(fee) => NonZeroU64::new(fee)
+                    .context("cannot be 0")
+                    .context("priority_fee_ordering_max_fee")?,
+                None => Self::Type::default_priority_fee_ordering_max_fee(),
+            },
+            priority_lane_addresses: self
+                .priority_lane_addresses
+                .iter()
+                .enumerate()
+                .map(|(i, address)| parse_h160(address).context(i))
+                .collect::<anyhow::Result<_>>()
+                .context("priority_lane_addresses")?,
+            priority_lane_capacity: match self.priority_lane_capacity {
+                Some(capacity) => capacity.try_into().context("priority_lane_capacity")?,
+                None => Self::Type::default_priority_lane_capacity(),
+            },
+            deadlines_retention_sec: self
+                .deadlines_retention_sec
+                .unwrap_or_else(Self::Type::default_deadlines_retention_sec),
         })
     }
 
@@ -157,6 +238,16 @@ impl ProtoRepr for proto::Mempool {
             stuck_tx_timeout: Some(this.stuck_tx_timeout),
             remove_stuck_txs: Some(this.remove_stuck_txs),
             delay_interval: Some(this.delay_interval),
+            priority_fee_ordering: Some(this.priority_fee_ordering),
+            priority_fee_ordering_window_ms: Some(this.priority_fee_ordering_window_ms.get()),
+            priority_fee_ordering_max_fee: Some(this.priority_fee_ordering_max_fee.get()),
+            priority_lane_addresses: this
+                .priority_lane_addresses
+                .iter()
+                .map(|address| format!("{address:?}"))
+                .collect(),
+            priority_lane_capacity: Some(this.priority_lane_capacity.try_into().unwrap()),
+            deadlines_retention_sec: Some(this.deadlines_retention_sec),
         }
     }
 }
@@ -4,6 +4,24 @@ use zksync_protobuf::{required, ProtoRepr};
 
 use crate::proto::circuit_breaker as proto;
 
+impl proto::SupplyInvariantAction {
+    fn new(x: &configs::chain::SupplyInvariantAction) -> Self {
+        use configs::chain::SupplyInvariantAction as From;
+        match x {
+            From::Alert => Self::Alert,
+            From::Halt => Self::Halt,
+        }
+    }
+
+    fn parse(&self) -> configs::chain::SupplyInvariantAction {
+        use configs::chain::SupplyInvariantAction as To;
+        match self {
+            Self::Alert => To::Alert,
+            Self::Halt => To::Halt,
+        }
+    }
+}
+
 impl ProtoRepr for proto::CircuitBreaker {
     type Type = configs::chain::CircuitBreakerConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
@@ -16,6 +34,12 @@ impl ProtoRepr for proto::CircuitBreaker {
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("http_req_retry_interval_sec")?,
             replication_lag_limit_sec: self.replication_lag_limit_sec,
+            supply_invariant_action: self
+                .supply_invariant_action
+                .map(|x| anyhow::Ok(proto::SupplyInvariantAction::try_from(x)?.parse()))
+                .transpose()
+                .context("supply_invariant_action")?,
+            supply_invariant_tolerance_gwei: self.supply_invariant_tolerance_gwei,
         })
     }
 
@@ -25,6 +49,11 @@ impl ProtoRepr for proto::CircuitBreaker {
             http_req_max_retry_number: Some(this.http_req_max_retry_number.try_into().unwrap()),
             http_req_retry_interval_sec: Some(this.http_req_retry_interval_sec.into()),
             replication_lag_limit_sec: this.replication_lag_limit_sec,
+            supply_invariant_action: this
+                .supply_invariant_action
+                .as_ref()
+                .map(|x| proto::SupplyInvariantAction::new(x).into()),
+            supply_invariant_tolerance_gwei: this.supply_invariant_tolerance_gwei,
         }
     }
 }
@@ -0,0 +1,49 @@
+use anyhow::Context as _;
+use zksync_config::configs::{self, disk_space_monitor::MonitoredPathConfig};
+use zksync_protobuf::{required, ProtoRepr};
+
+use crate::proto::disk_space_monitor as proto;
+
+impl ProtoRepr for proto::MonitoredPath {
+    type Type = MonitoredPathConfig;
+
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            path: required(&self.path).context("path")?.clone(),
+            warn_threshold_mb: *required(&self.warn_threshold_mb).context("warn_threshold_mb")?,
+            stop_threshold_mb: self.stop_threshold_mb,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            path: Some(this.path.clone()),
+            warn_threshold_mb: Some(this.warn_threshold_mb),
+            stop_threshold_mb: this.stop_threshold_mb,
+        }
+    }
+}
+
+impl ProtoRepr for proto::DiskSpaceMonitor {
+    type Type = configs::DiskSpaceMonitorConfig;
+
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            check_interval_ms: *required(&self.check_interval_ms).context("check_interval_ms")?,
+            paths: self
+                .paths
+                .iter()
+                .enumerate()
+                .map(|(i, x)| x.read().context(i))
+                .collect::<Result<_, _>>()
+                .context("paths")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            check_interval_ms: Some(this.check_interval_ms),
+            paths: this.paths.iter().map(ProtoRepr::build).collect(),
+        }
+    }
+}
@@ -1,8 +1,9 @@
 use anyhow::Context as _;
+use zksync_basic_types::{L1BatchNumber, U256};
 use zksync_config::configs::{self};
 use zksync_protobuf::{required, ProtoRepr};
 
-use crate::{proto::eth as proto, read_optional_repr};
+use crate::{parse_h160, proto::eth as proto, read_optional_repr};
 
 impl proto::ProofSendingMode {
     fn new(x: &configs::eth_sender::ProofSendingMode) -> Self {
@@ -42,6 +43,24 @@ impl proto::PubdataSendingMode {
     }
 }
 
+impl proto::WithdrawalLimitAction {
+    fn new(x: &configs::eth_sender::WithdrawalLimitAction) -> Self {
+        use configs::eth_sender::WithdrawalLimitAction as From;
+        match x {
+            From::Alert => Self::Alert,
+            From::DelayExecution => Self::DelayExecution,
+        }
+    }
+
+    fn parse(&self) -> configs::eth_sender::WithdrawalLimitAction {
+        use configs::eth_sender::WithdrawalLimitAction as To;
+        match self {
+            Self::Alert => To::Alert,
+            Self::DelayExecution => To::DelayExecution,
+        }
+    }
+}
+
 impl ProtoRepr for proto::Eth {
     type Type = configs::eth_sender::EthConfig;
 
@@ -50,6 +69,8 @@ impl ProtoRepr for proto::Eth {
             sender: read_optional_repr(&self.sender).context("sender")?,
             gas_adjuster: read_optional_repr(&self.gas_adjuster).context("gas_adjuster")?,
             watcher: read_optional_repr(&self.watcher).context("watcher")?,
+            withdrawal_limits: read_optional_repr(&self.withdrawal_limits)
+                .context("withdrawal_limits")?,
         })
     }
 
@@ -58,6 +79,7 @@ impl ProtoRepr for proto::Eth {
             sender: this.sender.as_ref().map(ProtoRepr::build),
             gas_adjuster: this.gas_adjuster.as_ref().map(ProtoRepr::build),
             watcher: this.watcher.as_ref().map(ProtoRepr::build),
+            withdrawal_limits: this.withdrawal_limits.as_ref().map(ProtoRepr::build),
         }
     }
 }
@@ -101,6 +123,13 @@ impl ProtoRepr for proto::Sender {
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("timestamp_criteria_max_allowed_lag")?,
             l1_batch_min_age_before_execute_seconds: self.l1_batch_min_age_before_execute_seconds,
+            max_timestamp_drift_from_l1_sec: self.max_timestamp_drift_from_l1_sec,
+            l1_tx_forwarder_addr: self
+                .l1_tx_forwarder_addr
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("l1_tx_forwarder_addr")?,
             max_acceptable_priority_fee_in_gwei: *required(
                 &self.max_acceptable_priority_fee_in_gwei,
             )
@@ -135,6 +164,8 @@ impl ProtoRepr for proto::Sender {
                 this.timestamp_criteria_max_allowed_lag.try_into().unwrap(),
             ),
             l1_batch_min_age_before_execute_seconds: this.l1_batch_min_age_before_execute_seconds,
+            max_timestamp_drift_from_l1_sec: this.max_timestamp_drift_from_l1_sec,
+            l1_tx_forwarder_addr: this.l1_tx_forwarder_addr.map(|x| format!("{:?}", x)),
             max_acceptable_priority_fee_in_gwei: Some(this.max_acceptable_priority_fee_in_gwei),
             pubdata_sending_mode: Some(
                 proto::PubdataSendingMode::new(&this.pubdata_sending_mode).into(),
@@ -215,3 +246,58 @@ impl ProtoRepr for proto::EthWatch {
         }
     }
 }
+
+impl ProtoRepr for proto::TokenWithdrawalLimit {
+    type Type = configs::eth_sender::TokenWithdrawalLimit;
+
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            token: required(&self.token)
+                .and_then(|x| parse_h160(x))
+                .context("token")?,
+            max_amount: required(&self.max_amount)
+                .and_then(|x| Ok(U256::from_dec_str(x)?))
+                .context("max_amount")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            token: Some(format!("{:?}", this.token)),
+            max_amount: Some(this.max_amount.to_string()),
+        }
+    }
+}
+
+impl ProtoRepr for proto::WithdrawalLimits {
+    type Type = configs::eth_sender::WithdrawalLimitsConfig;
+
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            window_sec: *required(&self.window_sec).context("window_sec")?,
+            limits: self
+                .limits
+                .iter()
+                .enumerate()
+                .map(|(i, x)| x.read().context(i))
+                .collect::<Result<_, _>>()
+                .context("limits")?,
+            action: self
+                .action
+                .map(proto::WithdrawalLimitAction::try_from)
+                .transpose()
+                .context("action")?
+                .map_or_else(Default::default, |action| action.parse()),
+            override_up_to_l1_batch: self.override_up_to_l1_batch.map(L1BatchNumber),
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            window_sec: Some(this.window_sec),
+            limits: this.limits.iter().map(ProtoRepr::build).collect(),
+            action: Some(proto::WithdrawalLimitAction::new(&this.action).into()),
+            override_up_to_l1_batch: this.override_up_to_l1_batch.map(|number| number.0),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use anyhow::Context as _;
+use zksync_config::configs;
+use zksync_protobuf::{required, ProtoRepr};
+
+use crate::proto::feature_flags as proto;
+
+impl ProtoRepr for proto::FeatureFlags {
+    type Type = configs::FeatureFlagsConfig;
+
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            enabled: self.enabled.clone(),
+            remote_url: self.remote_url.clone(),
+            remote_poll_interval_ms: *required(&self.remote_poll_interval_ms)
+                .context("remote_poll_interval_ms")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            enabled: this.enabled.clone(),
+            remote_url: this.remote_url.clone(),
+            remote_poll_interval_ms: Some(this.remote_poll_interval_ms),
+        }
+    }
+}
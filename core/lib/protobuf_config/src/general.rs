@@ -41,6 +41,15 @@ impl ProtoRepr for proto::GeneralConfig {
                 .context("protective_reads_writer")?,
             core_object_store: read_optional_repr(&self.core_object_store)
                 .context("core_object_store")?,
+            basic_witness_input_producer_config: read_optional_repr(
+                &self.basic_witness_input_producer,
+            )
+            .context("basic_witness_input_producer")?,
+            disk_space_monitor_config: read_optional_repr(&self.disk_space_monitor)
+                .context("disk_space_monitor")?,
+            scheduler_config: read_optional_repr(&self.scheduler).context("scheduler")?,
+            feature_flags_config: read_optional_repr(&self.feature_flags)
+                .context("feature_flags")?,
         })
     }
 
@@ -77,6 +86,16 @@ impl ProtoRepr for proto::GeneralConfig {
                 .as_ref()
                 .map(ProtoRepr::build),
             core_object_store: this.core_object_store.as_ref().map(ProtoRepr::build),
+            basic_witness_input_producer: this
+                .basic_witness_input_producer_config
+                .as_ref()
+                .map(ProtoRepr::build),
+            disk_space_monitor: this
+                .disk_space_monitor_config
+                .as_ref()
+                .map(ProtoRepr::build),
+            scheduler: this.scheduler_config.as_ref().map(ProtoRepr::build),
+            feature_flags: this.feature_flags_config.as_ref().map(ProtoRepr::build),
         }
     }
 }
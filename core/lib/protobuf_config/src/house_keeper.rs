@@ -49,6 +49,17 @@ impl ProtoRepr for proto::HouseKeeper {
                 .fri_gpu_prover_archiver_archiving_interval_ms,
             fri_gpu_prover_archiver_archive_after_secs: self
                 .fri_gpu_prover_archiver_archive_after_secs,
+            eth_txs_history_archiver_archiving_interval_ms: self
+                .eth_txs_history_archiver_archiving_interval_ms,
+            eth_txs_history_archiver_archive_after_secs: self
+                .eth_txs_history_archiver_archive_after_secs,
+            eth_txs_history_archiver_archive_eth_txs_after_days: self
+                .eth_txs_history_archiver_archive_eth_txs_after_days,
+            l2_block_partitions_maintenance_interval_ms: self
+                .l2_block_partitions_maintenance_interval_ms,
+            l2_blocks_per_partition: self.l2_blocks_per_partition,
+            call_traces_migration_interval_ms: self.call_traces_migration_interval_ms,
+            rejected_txs_pruning_interval_ms: self.rejected_txs_pruning_interval_ms,
         })
     }
 
@@ -83,6 +94,17 @@ impl ProtoRepr for proto::HouseKeeper {
                 .fri_gpu_prover_archiver_archiving_interval_ms,
             fri_gpu_prover_archiver_archive_after_secs: this
                 .fri_gpu_prover_archiver_archive_after_secs,
+            eth_txs_history_archiver_archiving_interval_ms: this
+                .eth_txs_history_archiver_archiving_interval_ms,
+            eth_txs_history_archiver_archive_after_secs: this
+                .eth_txs_history_archiver_archive_after_secs,
+            eth_txs_history_archiver_archive_eth_txs_after_days: this
+                .eth_txs_history_archiver_archive_eth_txs_after_days,
+            l2_block_partitions_maintenance_interval_ms: this
+                .l2_block_partitions_maintenance_interval_ms,
+            l2_blocks_per_partition: this.l2_blocks_per_partition,
+            call_traces_migration_interval_ms: this.call_traces_migration_interval_ms,
+            rejected_txs_pruning_interval_ms: this.rejected_txs_pruning_interval_ms,
         }
     }
 }
@@ -0,0 +1,228 @@
+//! Encrypted keystores for private keys referenced from the config files.
+//!
+//! Keystores use the [Web3 Secret Storage] format (scrypt + AES-128-CTR), which is also used by `zk_inception`
+//! to write them. Configs referencing keystores must have them resolved via [`ResolveKeystores`] before they are read;
+//! reading a config with unresolved keystores fails. The passphrase is provided by the binary wiring the configs,
+//! normally via [`KeystorePassphrase::from_env()`].
+//!
+//! [Web3 Secret Storage]: https://ethereum.org/en/developers/docs/data-structures-and-encoding/web3-secret-storage/
+
+use std::{env, fs, path::Path};
+
+use anyhow::Context as _;
+use secrecy::{ExposeSecret, Secret};
+
+/// Env variable containing the passphrase for encrypted keystores.
+pub const PASSPHRASE_ENV_VAR: &str = "ZKSYNC_KEYSTORE_PASSPHRASE";
+/// Env variable containing the path to a file with the passphrase for encrypted keystores.
+pub const PASSPHRASE_FILE_ENV_VAR: &str = "ZKSYNC_KEYSTORE_PASSPHRASE_FILE";
+
+/// Passphrase for encrypted keystores.
+#[derive(Debug, Clone)]
+pub struct KeystorePassphrase(Secret<String>);
+
+impl From<String> for KeystorePassphrase {
+    fn from(passphrase: String) -> Self {
+        Self(passphrase.into())
+    }
+}
+
+impl KeystorePassphrase {
+    /// Reads the passphrase from the [`PASSPHRASE_ENV_VAR`] env variable or, if it is not set, from the file
+    /// specified in the [`PASSPHRASE_FILE_ENV_VAR`] env variable. The latter allows unwrapping the passphrase
+    /// (e.g., using a KMS) before the node starts without exposing it in the process environment.
+    pub fn from_env() -> anyhow::Result<Self> {
+        if let Ok(passphrase) = env::var(PASSPHRASE_ENV_VAR) {
+            return Ok(passphrase.into());
+        }
+        let path = env::var(PASSPHRASE_FILE_ENV_VAR).with_context(|| {
+            format!(
+                "keystore passphrase is not provided; set either {PASSPHRASE_ENV_VAR} or {PASSPHRASE_FILE_ENV_VAR}"
+            )
+        })?;
+        Self::from_file(&path)
+    }
+
+    /// Reads the passphrase from the specified file. Trailing line breaks are trimmed.
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let passphrase = fs::read_to_string(path)
+            .with_context(|| format!("failed reading keystore passphrase from {path:?}"))?;
+        Ok(passphrase.trim_end_matches(['\r', '\n']).to_owned().into())
+    }
+
+    /// Decrypts the secret stored in the keystore at the specified path.
+    pub fn decrypt_keystore(&self, path: impl AsRef<Path>) -> anyhow::Result<Secret<Vec<u8>>> {
+        let path = path.as_ref();
+        let secret = eth_keystore::decrypt_key(path, self.0.expose_secret())
+            .with_context(|| format!("failed decrypting keystore at {path:?}"))?;
+        Ok(secret.into())
+    }
+
+    /// Decrypts a UTF-8 string (e.g., a text-encoded consensus key) stored in the keystore at the specified path.
+    pub(crate) fn decrypt_keystore_string(&self, path: &str) -> anyhow::Result<String> {
+        let secret = self.decrypt_keystore(path)?;
+        String::from_utf8(secret.expose_secret().clone())
+            .with_context(|| format!("keystore at {path} doesn't contain a UTF-8 string"))
+    }
+}
+
+/// Config representation that may reference secrets stored in encrypted keystores.
+pub trait ResolveKeystores {
+    /// Checks whether this config references any keystores.
+    fn has_keystores(&self) -> bool;
+
+    /// Decrypts all referenced keystores using the provided passphrase and replaces references with the decrypted secrets.
+    fn resolve_keystores(&mut self, passphrase: &KeystorePassphrase) -> anyhow::Result<()>;
+}
+
+/// Resolves a secret specified either in plaintext or as a path to an encrypted keystore. `decrypt` converts
+/// the keystore path to the plaintext secret.
+pub(crate) fn resolve_secret(
+    plaintext: &mut Option<String>,
+    keystore: &mut Option<String>,
+    decrypt: impl FnOnce(&str) -> anyhow::Result<String>,
+) -> anyhow::Result<()> {
+    let Some(path) = keystore.take() else {
+        return Ok(());
+    };
+    anyhow::ensure!(
+        plaintext.is_none(),
+        "secret must be specified either in plaintext or as a keystore, not both"
+    );
+    *plaintext = Some(decrypt(&path)?);
+    Ok(())
+}
+
+/// Checks that the keystore reference for a secret was resolved.
+pub(crate) fn ensure_resolved(keystore: &Option<String>) -> anyhow::Result<()> {
+    if let Some(path) = keystore {
+        anyhow::bail!("keystore at {path} must be resolved before reading the config");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_config::configs::{consensus::ConsensusSecrets, wallets::Wallet};
+    use zksync_protobuf::ProtoRepr;
+    use zksync_types::H256;
+
+    use super::*;
+    use crate::proto;
+
+    const PASSPHRASE: &str = "correct horse battery staple";
+
+    fn write_keystore(dir: &Path, secret: &[u8]) -> String {
+        let name =
+            eth_keystore::encrypt_key(dir, &mut rand::thread_rng(), secret, PASSPHRASE, None)
+                .unwrap();
+        dir.join(name).to_str().unwrap().to_owned()
+    }
+
+    fn private_key_wallet(keystore: String) -> proto::wallets::PrivateKeyWallet {
+        proto::wallets::PrivateKeyWallet {
+            address: None,
+            private_key: None,
+            keystore: Some(keystore),
+        }
+    }
+
+    #[test]
+    fn reading_passphrase_from_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("passphrase");
+        fs::write(&path, format!("{PASSPHRASE}\n")).unwrap();
+        let passphrase = KeystorePassphrase::from_file(&path).unwrap();
+        assert_eq!(passphrase.0.expose_secret(), PASSPHRASE);
+    }
+
+    #[test]
+    fn resolving_wallet_keystores() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let private_key = H256::repeat_byte(0x42);
+        let keystore = write_keystore(dir.path(), private_key.as_bytes());
+        let mut wallets = proto::wallets::Wallets {
+            operator: Some(private_key_wallet(keystore.clone())),
+            blob_operator: Some(private_key_wallet(keystore)),
+            ..proto::wallets::Wallets::default()
+        };
+        assert!(wallets.has_keystores());
+        let err = wallets.read().unwrap_err();
+        assert!(format!("{err:#}").contains("must be resolved"), "{err:#}");
+
+        let wrong_passphrase = KeystorePassphrase::from("wrong".to_owned());
+        wallets
+            .clone()
+            .resolve_keystores(&wrong_passphrase)
+            .unwrap_err();
+
+        wallets
+            .resolve_keystores(&PASSPHRASE.to_owned().into())
+            .unwrap();
+        assert!(!wallets.has_keystores());
+        let wallets = wallets.read().unwrap();
+        let eth_sender = wallets.eth_sender.unwrap();
+        let expected_address = Wallet::from_private_key_bytes(private_key, None)
+            .unwrap()
+            .address();
+        assert_eq!(eth_sender.operator.address(), expected_address);
+        assert_eq!(
+            eth_sender.blob_operator.unwrap().address(),
+            expected_address
+        );
+    }
+
+    #[test]
+    fn resolving_wallet_keystore_with_invalid_key() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let keystore = write_keystore(dir.path(), &[1; 16]);
+        let mut wallet = private_key_wallet(keystore);
+        let mut wallets = proto::wallets::Wallets {
+            operator: Some(wallet.clone()),
+            ..proto::wallets::Wallets::default()
+        };
+        let err = wallets
+            .resolve_keystores(&PASSPHRASE.to_owned().into())
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("16 bytes"), "{err:#}");
+
+        wallet.private_key = Some(format!("{:?}", H256::repeat_byte(1)));
+        let mut wallets = proto::wallets::Wallets {
+            operator: Some(wallet),
+            ..proto::wallets::Wallets::default()
+        };
+        let err = wallets
+            .resolve_keystores(&PASSPHRASE.to_owned().into())
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("not both"), "{err:#}");
+    }
+
+    #[test]
+    fn resolving_consensus_secrets_keystores() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let node_key = "node:secret:ed25519:0000";
+        let keystore = write_keystore(dir.path(), node_key.as_bytes());
+        let mut secrets = proto::secrets::Secrets {
+            consensus: Some(proto::secrets::ConsensusSecrets {
+                validator_key: None,
+                node_key: None,
+                validator_key_keystore: None,
+                node_key_keystore: Some(keystore),
+            }),
+            ..proto::secrets::Secrets::default()
+        };
+        assert!(secrets.has_keystores());
+        secrets.read().unwrap_err();
+
+        secrets
+            .resolve_keystores(&PASSPHRASE.to_owned().into())
+            .unwrap();
+        let ConsensusSecrets {
+            validator_key,
+            node_key: resolved_node_key,
+        } = secrets.read().unwrap().consensus.unwrap();
+        assert!(validator_key.is_none());
+        assert_eq!(resolved_node_key.unwrap().0.expose_secret(), node_key);
+    }
+}
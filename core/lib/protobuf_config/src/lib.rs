@@ -11,16 +11,20 @@ mod consensus;
 mod contract_verifier;
 mod contracts;
 mod database;
+mod disk_space_monitor;
 mod eth;
 mod experimental;
+mod feature_flags;
 mod general;
 mod genesis;
 mod house_keeper;
+pub mod keystore;
 mod object_store;
 mod observability;
 mod proof_data_handler;
 pub mod proto;
 mod prover;
+mod scheduler;
 mod secrets;
 mod snapshots_creator;
 pub mod testonly;
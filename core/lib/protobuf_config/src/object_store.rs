@@ -45,6 +45,13 @@ impl ProtoRepr for proto::ObjectStore {
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("max_retries")?,
             local_mirror_path: self.local_mirror_path.clone(),
+            replica: self
+                .replica
+                .as_ref()
+                .map(|replica| replica.read())
+                .transpose()
+                .context("replica")?
+                .map(Box::new),
         })
     }
 
@@ -82,6 +89,10 @@ impl ProtoRepr for proto::ObjectStore {
             mode: Some(mode),
             max_retries: Some(this.max_retries.into()),
             local_mirror_path: this.local_mirror_path.clone(),
+            replica: this
+                .replica
+                .as_ref()
+                .map(|replica| Box::new(Self::build(replica))),
         }
     }
 }
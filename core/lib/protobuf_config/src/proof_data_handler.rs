@@ -14,6 +14,8 @@ impl ProtoRepr for proto::ProofDataHandler {
             proof_generation_timeout_in_secs: required(&self.proof_generation_timeout_in_secs)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("proof_generation_timeout_in_secs")?,
+            authorized_provers: self.authorized_provers.clone(),
+            allow_unauthenticated_access: self.allow_unauthenticated_access.unwrap_or(false),
         })
     }
 
@@ -21,6 +23,8 @@ impl ProtoRepr for proto::ProofDataHandler {
         Self {
             http_port: Some(this.http_port.into()),
             proof_generation_timeout_in_secs: Some(this.proof_generation_timeout_in_secs.into()),
+            authorized_provers: this.authorized_provers.clone(),
+            allow_unauthenticated_access: Some(this.allow_unauthenticated_access),
         }
     }
 }
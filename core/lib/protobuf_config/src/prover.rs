@@ -136,6 +136,7 @@ impl ProtoRepr for proto::ProverGateway {
             api_poll_duration_secs: required(&self.api_poll_duration_secs)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("api_poll_duration_secs")?,
+            api_key: self.api_key.clone(),
             prometheus_listener_port: required(&self.prometheus_listener_port)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("prometheus_listener_port")?,
@@ -150,6 +151,7 @@ impl ProtoRepr for proto::ProverGateway {
         Self {
             api_url: Some(this.api_url.clone()),
             api_poll_duration_secs: Some(this.api_poll_duration_secs.into()),
+            api_key: this.api_key.clone(),
             prometheus_listener_port: Some(this.prometheus_listener_port.into()),
             prometheus_pushgateway_url: Some(this.prometheus_pushgateway_url.clone()),
             prometheus_push_interval_ms: this.prometheus_push_interval_ms,
@@ -193,6 +195,8 @@ impl ProtoRepr for proto::WitnessGenerator {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("scheduler_generation_timeout_in_secs")?,
+            prefetch_depth: self.prefetch_depth,
+            prefetch_max_memory_mb: self.prefetch_max_memory_mb,
         })
     }
 
@@ -213,6 +217,8 @@ impl ProtoRepr for proto::WitnessGenerator {
             scheduler_generation_timeout_in_secs: this
                 .scheduler_generation_timeout_in_secs
                 .map(|x| x.into()),
+            prefetch_depth: this.prefetch_depth,
+            prefetch_max_memory_mb: this.prefetch_max_memory_mb,
         }
     }
 }
@@ -328,6 +334,9 @@ impl ProtoRepr for proto::Prover {
                 .context("zone_read_url")?
                 .clone(),
             availability_check_interval_in_secs: self.availability_check_interval_in_secs,
+            max_sticky_jobs: self.max_sticky_jobs,
+            prefetch_depth: self.prefetch_depth,
+            prefetch_max_memory_mb: self.prefetch_max_memory_mb,
             shall_save_to_public_bucket: *required(&self.shall_save_to_public_bucket)
                 .context("shall_save_to_public_bucket")?,
             public_object_store,
@@ -347,6 +356,9 @@ impl ProtoRepr for proto::Prover {
             witness_vector_receiver_port: Some(this.witness_vector_receiver_port.into()),
             zone_read_url: Some(this.zone_read_url.clone()),
             availability_check_interval_in_secs: this.availability_check_interval_in_secs,
+            max_sticky_jobs: this.max_sticky_jobs,
+            prefetch_depth: this.prefetch_depth,
+            prefetch_max_memory_mb: this.prefetch_max_memory_mb,
             shall_save_to_public_bucket: Some(this.shall_save_to_public_bucket),
             prover_object_store: this.prover_object_store.as_ref().map(ProtoRepr::build),
             public_object_store: this.public_object_store.as_ref().map(ProtoRepr::build),
@@ -0,0 +1,49 @@
+use anyhow::Context as _;
+use zksync_config::configs::{self, scheduler::ScheduledJobConfig};
+use zksync_protobuf::{required, ProtoRepr};
+
+use crate::proto::scheduler as proto;
+
+impl ProtoRepr for proto::ScheduledJob {
+    type Type = ScheduledJobConfig;
+
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            name: required(&self.name).context("name")?.clone(),
+            schedule: required(&self.schedule).context("schedule")?.clone(),
+            jitter_ms: self.jitter_ms.unwrap_or(0),
+            exclusion_group: self.exclusion_group.clone(),
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            name: Some(this.name.clone()),
+            schedule: Some(this.schedule.clone()),
+            jitter_ms: Some(this.jitter_ms),
+            exclusion_group: this.exclusion_group.clone(),
+        }
+    }
+}
+
+impl ProtoRepr for proto::Scheduler {
+    type Type = configs::SchedulerConfig;
+
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            jobs: self
+                .jobs
+                .iter()
+                .enumerate()
+                .map(|(i, x)| x.read().context(i))
+                .collect::<Result<_, _>>()
+                .context("jobs")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            jobs: this.jobs.iter().map(ProtoRepr::build).collect(),
+        }
+    }
+}
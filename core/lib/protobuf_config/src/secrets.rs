@@ -10,7 +10,11 @@ use zksync_config::configs::{
 };
 use zksync_protobuf::{required, ProtoRepr};
 
-use crate::{proto::secrets as proto, read_optional_repr};
+use crate::{
+    keystore::{ensure_resolved, resolve_secret, KeystorePassphrase, ResolveKeystores},
+    proto::secrets as proto,
+    read_optional_repr,
+};
 
 impl ProtoRepr for proto::Secrets {
     type Type = Secrets;
@@ -93,15 +97,14 @@ impl ProtoRepr for proto::L1Secrets {
 impl ProtoRepr for proto::ConsensusSecrets {
     type Type = ConsensusSecrets;
     fn read(&self) -> anyhow::Result<Self::Type> {
+        ensure_resolved(&self.validator_key_keystore).context("validator_key")?;
+        ensure_resolved(&self.node_key_keystore).context("node_key")?;
         Ok(Self::Type {
             validator_key: self
                 .validator_key
-                .as_ref()
-                .map(|x| ValidatorSecretKey(x.clone().into())),
-            node_key: self
-                .node_key
-                .as_ref()
-                .map(|x| NodeSecretKey(x.clone().into())),
+                .clone()
+                .map(|x| ValidatorSecretKey(x.into())),
+            node_key: self.node_key.clone().map(|x| NodeSecretKey(x.into())),
         })
     }
 
@@ -112,6 +115,45 @@ impl ProtoRepr for proto::ConsensusSecrets {
                 .as_ref()
                 .map(|x| x.0.expose_secret().clone()),
             node_key: this.node_key.as_ref().map(|x| x.0.expose_secret().clone()),
+            // Keys are always serialized in plaintext.
+            validator_key_keystore: None,
+            node_key_keystore: None,
+        }
+    }
+}
+
+impl ResolveKeystores for proto::ConsensusSecrets {
+    fn has_keystores(&self) -> bool {
+        self.validator_key_keystore.is_some() || self.node_key_keystore.is_some()
+    }
+
+    fn resolve_keystores(&mut self, passphrase: &KeystorePassphrase) -> anyhow::Result<()> {
+        resolve_secret(
+            &mut self.validator_key,
+            &mut self.validator_key_keystore,
+            |path| passphrase.decrypt_keystore_string(path),
+        )
+        .context("validator_key")?;
+        resolve_secret(&mut self.node_key, &mut self.node_key_keystore, |path| {
+            passphrase.decrypt_keystore_string(path)
+        })
+        .context("node_key")
+    }
+}
+
+impl ResolveKeystores for proto::Secrets {
+    fn has_keystores(&self) -> bool {
+        self.consensus
+            .as_ref()
+            .map_or(false, ResolveKeystores::has_keystores)
+    }
+
+    fn resolve_keystores(&mut self, passphrase: &KeystorePassphrase) -> anyhow::Result<()> {
+        if let Some(consensus) = &mut self.consensus {
+            consensus
+                .resolve_keystores(passphrase)
+                .context("consensus")?;
         }
+        Ok(())
     }
 }
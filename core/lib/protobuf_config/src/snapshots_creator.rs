@@ -18,6 +18,10 @@ impl ProtoRepr for proto::SnapshotsCreator {
             concurrent_queries_count: *required(&self.concurrent_queries_count)
                 .context("concurrent_queries_count")?,
             object_store,
+            schedule_poll_interval_ms: self.schedule_poll_interval_ms,
+            l1_batch_interval: self.l1_batch_interval,
+            low_load_window_start_hour_utc: self.low_load_window_start_hour_utc,
+            low_load_window_end_hour_utc: self.low_load_window_end_hour_utc,
         })
     }
 
@@ -26,6 +30,10 @@ impl ProtoRepr for proto::SnapshotsCreator {
             storage_logs_chunk_size: Some(this.storage_logs_chunk_size),
             concurrent_queries_count: Some(this.concurrent_queries_count),
             object_store: this.object_store.as_ref().map(ProtoRepr::build),
+            schedule_poll_interval_ms: this.schedule_poll_interval_ms,
+            l1_batch_interval: this.l1_batch_interval,
+            low_load_window_start_hour_utc: this.low_load_window_start_hour_utc,
+            low_load_window_end_hour_utc: this.low_load_window_end_hour_utc,
         }
     }
 }
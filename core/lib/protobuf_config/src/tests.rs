@@ -30,7 +30,9 @@ fn test_encoding() {
     test_encode_all_formats::<ReprConv<proto::database::MerkleTree>>(rng);
     test_encode_all_formats::<ReprConv<proto::database::Db>>(rng);
     test_encode_all_formats::<ReprConv<proto::database::Postgres>>(rng);
+    test_encode_all_formats::<ReprConv<proto::disk_space_monitor::DiskSpaceMonitor>>(rng);
     test_encode_all_formats::<ReprConv<proto::eth::Eth>>(rng);
+    test_encode_all_formats::<ReprConv<proto::feature_flags::FeatureFlags>>(rng);
     test_encode_all_formats::<ReprConv<proto::prover::ProofCompressor>>(rng);
     test_encode_all_formats::<ReprConv<proto::prover::Prover>>(rng);
     test_encode_all_formats::<ReprConv<proto::prover::ProverGateway>>(rng);
@@ -40,6 +42,7 @@ fn test_encoding() {
     test_encode_all_formats::<ReprConv<proto::house_keeper::HouseKeeper>>(rng);
     test_encode_all_formats::<ReprConv<proto::object_store::ObjectStore>>(rng);
     test_encode_all_formats::<ReprConv<proto::prover::ProofDataHandler>>(rng);
+    test_encode_all_formats::<ReprConv<proto::scheduler::Scheduler>>(rng);
     test_encode_all_formats::<ReprConv<proto::snapshot_creator::SnapshotsCreator>>(rng);
     test_encode_all_formats::<ReprConv<proto::observability::Observability>>(rng);
 }
@@ -1,41 +1,41 @@
 use anyhow::Context;
+use secrecy::ExposeSecret;
 use zksync_config::configs::{
     self,
     wallets::{AddressWallet, EthSender, StateKeeper, Wallet},
 };
 use zksync_protobuf::{required, ProtoRepr};
+use zksync_types::H256;
 
-use crate::{parse_h160, parse_h256, proto::wallets as proto};
+use crate::{
+    keystore::{ensure_resolved, resolve_secret, KeystorePassphrase, ResolveKeystores},
+    parse_h160, parse_h256,
+    proto::wallets as proto,
+};
 
 impl ProtoRepr for proto::Wallets {
     type Type = configs::wallets::Wallets;
     fn read(&self) -> anyhow::Result<Self::Type> {
         let eth_sender = if self.operator.is_some() && self.blob_operator.is_some() {
             let blob_operator = if let Some(blob_operator) = &self.blob_operator {
-                Some(Wallet::from_private_key_bytes(
-                    parse_h256(required(&blob_operator.private_key).context("blob operator")?)?,
-                    blob_operator
-                        .address
-                        .as_ref()
-                        .and_then(|a| parse_h160(a).ok()),
-                )?)
+                Some(read_private_key_wallet(blob_operator).context("blob operator")?)
             } else {
                 None
             };
 
-            let operator_wallet = &self.operator.clone().context("Operator private key")?;
+            let fee_payer = if let Some(fee_payer) = &self.fee_payer {
+                Some(read_private_key_wallet(fee_payer).context("fee payer")?)
+            } else {
+                None
+            };
 
-            let operator = Wallet::from_private_key_bytes(
-                parse_h256(required(&operator_wallet.private_key).context("operator")?)?,
-                operator_wallet
-                    .address
-                    .as_ref()
-                    .and_then(|a| parse_h160(a).ok()),
-            )?;
+            let operator_wallet = &self.operator.clone().context("Operator private key")?;
+            let operator = read_private_key_wallet(operator_wallet).context("operator")?;
 
             Some(EthSender {
                 operator,
                 blob_operator,
+                fee_payer,
             })
         } else {
             None
@@ -46,8 +46,20 @@ impl ProtoRepr for proto::Wallets {
                 required(&fee_account.address).context("fee_account.address requireed")?,
             )
             .context("fee_account.address")?;
+            let extra_fee_accounts = self
+                .extra_fee_accounts
+                .iter()
+                .enumerate()
+                .map(|(i, wallet)| {
+                    let address = required(&wallet.address)
+                        .and_then(|address| parse_h160(address))
+                        .with_context(|| format!("extra_fee_accounts[{i}].address"))?;
+                    Ok(AddressWallet::from_address(address))
+                })
+                .collect::<anyhow::Result<_>>()?;
             Some(StateKeeper {
                 fee_account: AddressWallet::from_address(address),
+                extra_fee_accounts,
             })
         } else {
             None
@@ -60,23 +72,35 @@ impl ProtoRepr for proto::Wallets {
     }
 
     fn build(this: &Self::Type) -> Self {
-        let (operator, blob_operator) = if let Some(eth_sender) = &this.eth_sender {
+        let (operator, blob_operator, fee_payer) = if let Some(eth_sender) = &this.eth_sender {
             let blob = eth_sender
                 .blob_operator
                 .as_ref()
                 .map(|blob| proto::PrivateKeyWallet {
                     address: Some(format!("{:?}", blob.address())),
                     private_key: Some(format!("{:?}", blob.private_key())),
+                    keystore: None,
                 });
+            let fee_payer =
+                eth_sender
+                    .fee_payer
+                    .as_ref()
+                    .map(|fee_payer| proto::PrivateKeyWallet {
+                        address: Some(format!("{:?}", fee_payer.address())),
+                        private_key: Some(format!("{:?}", fee_payer.private_key())),
+                        keystore: None,
+                    });
             (
                 Some(proto::PrivateKeyWallet {
                     address: Some(format!("{:?}", eth_sender.operator.address())),
                     private_key: Some(format!("{:?}", eth_sender.operator.private_key())),
+                    keystore: None,
                 }),
                 blob,
+                fee_payer,
             )
         } else {
-            (None, None)
+            (None, None, None)
         };
 
         let fee_account = this
@@ -85,10 +109,69 @@ impl ProtoRepr for proto::Wallets {
             .map(|state_keeper| proto::AddressWallet {
                 address: Some(format!("{:?}", state_keeper.fee_account.address())),
             });
+        let extra_fee_accounts = this
+            .state_keeper
+            .iter()
+            .flat_map(|state_keeper| &state_keeper.extra_fee_accounts)
+            .map(|wallet| proto::AddressWallet {
+                address: Some(format!("{:?}", wallet.address())),
+            })
+            .collect();
         Self {
             blob_operator,
             operator,
             fee_account,
+            fee_payer,
+            extra_fee_accounts,
         }
     }
 }
+
+impl ResolveKeystores for proto::Wallets {
+    fn has_keystores(&self) -> bool {
+        [&self.operator, &self.blob_operator, &self.fee_payer]
+            .into_iter()
+            .flatten()
+            .any(|wallet| wallet.keystore.is_some())
+    }
+
+    fn resolve_keystores(&mut self, passphrase: &KeystorePassphrase) -> anyhow::Result<()> {
+        let wallets = [
+            ("operator", &mut self.operator),
+            ("blob operator", &mut self.blob_operator),
+            ("fee payer", &mut self.fee_payer),
+        ];
+        for (name, wallet) in wallets {
+            if let Some(wallet) = wallet {
+                resolve_private_key_keystore(wallet, passphrase).context(name)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn resolve_private_key_keystore(
+    wallet: &mut proto::PrivateKeyWallet,
+    passphrase: &KeystorePassphrase,
+) -> anyhow::Result<()> {
+    resolve_secret(&mut wallet.private_key, &mut wallet.keystore, |path| {
+        let private_key = passphrase.decrypt_keystore(path)?;
+        let private_key = private_key.expose_secret();
+        anyhow::ensure!(
+            private_key.len() == 32,
+            "keystore at {path} contains {} bytes, while a private key is expected (32 bytes)",
+            private_key.len()
+        );
+        Ok(format!("{:?}", H256::from_slice(private_key)))
+    })
+}
+
+/// Reads a wallet with the private key. Keystores must be resolved beforehand.
+fn read_private_key_wallet(wallet: &proto::PrivateKeyWallet) -> anyhow::Result<Wallet> {
+    ensure_resolved(&wallet.keystore)?;
+    let private_key = parse_h256(required(&wallet.private_key).context("private_key")?)?;
+    Wallet::from_private_key_bytes(
+        private_key,
+        wallet.address.as_ref().and_then(|a| parse_h160(a).ok()),
+    )
+}
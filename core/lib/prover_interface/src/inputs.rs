@@ -1,9 +1,11 @@
-use std::{convert::TryInto, fmt::Debug};
+use std::{collections::HashMap, convert::TryInto, fmt::Debug};
 
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, Bytes};
-use zksync_object_store::{serialize_using_bincode, Bucket, StoredObject};
-use zksync_types::{L1BatchNumber, H256, U256};
+use zksync_object_store::{
+    serialize_using_bincode, Bucket, ObjectStore, ObjectStoreError, StoredObject,
+};
+use zksync_types::{L1BatchNumber, ProtocolVersionId, H256, U256};
 
 const HASH_LEN: usize = H256::len_bytes();
 
@@ -144,8 +146,179 @@ pub struct BasicCircuitWitnessGeneratorInput {
     pub merkle_paths_input: PrepareBasicCircuitsJob,
 }
 
+/// Witness generator inputs obtained by re-executing an L1 batch in the VM. Unlike
+/// [`BasicCircuitWitnessGeneratorInput`], these don't depend on the Merkle tree and are produced by
+/// the basic witness input producer asynchronously to state keeper.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VmRunWitnessInputData {
+    pub l1_batch_number: L1BatchNumber,
+    pub protocol_version: ProtocolVersionId,
+    /// Bytecodes of all contracts used in the batch, except for the bootloader.
+    pub used_bytecodes: HashMap<U256, Vec<[u8; 32]>>,
+    pub initial_heap_content: Vec<(usize, U256)>,
+    pub bootloader_code: Vec<[u8; 32]>,
+    pub default_account_code_hash: U256,
+    pub storage_refunds: Vec<u32>,
+    pub pubdata_costs: Vec<i32>,
+}
+
+impl StoredObject for VmRunWitnessInputData {
+    const BUCKET: Bucket = Bucket::WitnessInput;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("vm_run_data_{key}.bin")
+    }
+
+    serialize_using_bincode!();
+}
+
+/// Chunk of [`VmRunWitnessInputData`] streamed to the object store while an L1 batch is being executed.
+/// Chunks are indexed sequentially within an L1 batch starting from 0.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VmRunWitnessInputChunk {
+    pub l1_batch_number: L1BatchNumber,
+    pub chunk_index: u32,
+    /// Bytecodes used in the batch. Bytecodes may be duplicated across chunks.
+    pub used_bytecodes: HashMap<U256, Vec<[u8; 32]>>,
+}
+
+impl StoredObject for VmRunWitnessInputChunk {
+    const BUCKET: Bucket = Bucket::WitnessInput;
+    type Key<'a> = (L1BatchNumber, u32);
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        let (l1_batch_number, chunk_index) = key;
+        format!("vm_run_data_{l1_batch_number}_chunk_{chunk_index}.bin")
+    }
+
+    serialize_using_bincode!();
+}
+
+/// [`VmRunWitnessInputData`] with used bytecodes split into [`VmRunWitnessInputChunk`]s. The header is persisted
+/// after all chunks, so its presence in the object store signals that the witness input is complete.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamedVmRunWitnessInputData {
+    pub l1_batch_number: L1BatchNumber,
+    pub protocol_version: ProtocolVersionId,
+    /// Number of chunks with used bytecodes, with indices `0..chunk_count`.
+    pub chunk_count: u32,
+    pub initial_heap_content: Vec<(usize, U256)>,
+    pub bootloader_code: Vec<[u8; 32]>,
+    pub default_account_code_hash: U256,
+    pub storage_refunds: Vec<u32>,
+    pub pubdata_costs: Vec<i32>,
+}
+
+impl StoredObject for StreamedVmRunWitnessInputData {
+    const BUCKET: Bucket = Bucket::WitnessInput;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("vm_run_data_{key}_streamed.bin")
+    }
+
+    serialize_using_bincode!();
+}
+
+/// Errors that can occur when reading a [`StreamedVmRunWitnessInputData`].
+#[derive(Debug, thiserror::Error)]
+pub enum StreamedWitnessInputError {
+    #[error(
+        "witness input chunk #{chunk_index} belongs to L1 batch #{actual}, expected #{expected}"
+    )]
+    ChunkFromOtherBatch {
+        chunk_index: u32,
+        expected: L1BatchNumber,
+        actual: L1BatchNumber,
+    },
+    #[error("witness input chunk #{chunk_index} is out of range (chunk count is {chunk_count})")]
+    ChunkOutOfRange { chunk_index: u32, chunk_count: u32 },
+    #[error("witness input chunk #{0} is provided multiple times")]
+    DuplicateChunk(u32),
+    #[error("witness input chunk #{0} is missing")]
+    MissingChunk(u32),
+    #[error("failed accessing object store")]
+    ObjectStore(#[from] ObjectStoreError),
+}
+
+impl StreamedVmRunWitnessInputData {
+    /// Assembles the full witness input from this header and the provided chunks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a chunk belongs to another L1 batch, has an out-of-range index or is duplicated,
+    /// or if any of the chunks `0..chunk_count` is missing.
+    pub fn assemble(
+        self,
+        chunks: impl IntoIterator<Item = VmRunWitnessInputChunk>,
+    ) -> Result<VmRunWitnessInputData, StreamedWitnessInputError> {
+        let mut used_bytecodes = HashMap::new();
+        let mut has_chunk = vec![false; self.chunk_count as usize];
+        for chunk in chunks {
+            if chunk.l1_batch_number != self.l1_batch_number {
+                return Err(StreamedWitnessInputError::ChunkFromOtherBatch {
+                    chunk_index: chunk.chunk_index,
+                    expected: self.l1_batch_number,
+                    actual: chunk.l1_batch_number,
+                });
+            }
+            let has_chunk = has_chunk.get_mut(chunk.chunk_index as usize).ok_or(
+                StreamedWitnessInputError::ChunkOutOfRange {
+                    chunk_index: chunk.chunk_index,
+                    chunk_count: self.chunk_count,
+                },
+            )?;
+            if std::mem::replace(has_chunk, true) {
+                return Err(StreamedWitnessInputError::DuplicateChunk(chunk.chunk_index));
+            }
+            used_bytecodes.extend(chunk.used_bytecodes);
+        }
+        if let Some(missing_idx) = has_chunk.iter().position(|&has_chunk| !has_chunk) {
+            return Err(StreamedWitnessInputError::MissingChunk(missing_idx as u32));
+        }
+
+        Ok(VmRunWitnessInputData {
+            l1_batch_number: self.l1_batch_number,
+            protocol_version: self.protocol_version,
+            used_bytecodes,
+            initial_heap_content: self.initial_heap_content,
+            bootloader_code: self.bootloader_code,
+            default_account_code_hash: self.default_account_code_hash,
+            storage_refunds: self.storage_refunds,
+            pubdata_costs: self.pubdata_costs,
+        })
+    }
+
+    /// Loads the streamed witness input for the specified L1 batch from the object store and assembles it.
+    /// Chunks are loaded one by one, so the peak memory usage is roughly the size of the assembled input.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ObjectStoreError::KeyNotFound`] (wrapped in [`StreamedWitnessInputError::ObjectStore`])
+    /// if the header is not persisted yet, i.e., the witness input is not complete.
+    pub async fn load(
+        object_store: &dyn ObjectStore,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<VmRunWitnessInputData, StreamedWitnessInputError> {
+        let header: Self = object_store.get(l1_batch_number).await?;
+        let mut chunks = Vec::with_capacity(header.chunk_count as usize);
+        for chunk_index in 0..header.chunk_count {
+            chunks.push(
+                object_store
+                    .get::<VmRunWitnessInputChunk>((l1_batch_number, chunk_index))
+                    .await?,
+            );
+        }
+        header.assemble(chunks)
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use assert_matches::assert_matches;
+    use zksync_object_store::MockObjectStore;
+
     use super::*;
 
     #[test]
@@ -182,4 +355,131 @@ mod tests {
         let logs_from_job: Vec<_> = job.into_merkle_paths().collect();
         assert_eq!(logs_from_job, logs);
     }
+
+    fn streamed_witness_input() -> (StreamedVmRunWitnessInputData, Vec<VmRunWitnessInputChunk>) {
+        let l1_batch_number = L1BatchNumber(1);
+        let header = StreamedVmRunWitnessInputData {
+            l1_batch_number,
+            protocol_version: ProtocolVersionId::latest(),
+            chunk_count: 3,
+            initial_heap_content: vec![(0, U256::one())],
+            bootloader_code: vec![[1; 32]],
+            default_account_code_hash: U256::from(2),
+            storage_refunds: vec![3],
+            pubdata_costs: vec![-4],
+        };
+        let chunks = [
+            HashMap::from([(U256::from(10), vec![[10; 32]])]),
+            HashMap::new(),
+            HashMap::from([
+                (U256::from(10), vec![[10; 32]]),
+                (U256::from(11), vec![[11; 32]; 2]),
+            ]),
+        ];
+        let chunks = chunks
+            .into_iter()
+            .enumerate()
+            .map(|(i, used_bytecodes)| VmRunWitnessInputChunk {
+                l1_batch_number,
+                chunk_index: i as u32,
+                used_bytecodes,
+            })
+            .collect();
+        (header, chunks)
+    }
+
+    fn assert_assembled_witness_input(
+        witness_input: &VmRunWitnessInputData,
+        header: &StreamedVmRunWitnessInputData,
+    ) {
+        assert_eq!(witness_input.l1_batch_number, header.l1_batch_number);
+        assert_eq!(
+            witness_input.used_bytecodes,
+            HashMap::from([
+                (U256::from(10), vec![[10; 32]]),
+                (U256::from(11), vec![[11; 32]; 2]),
+            ])
+        );
+        assert_eq!(
+            witness_input.initial_heap_content,
+            header.initial_heap_content
+        );
+        assert_eq!(witness_input.pubdata_costs, header.pubdata_costs);
+    }
+
+    #[test]
+    fn assembling_streamed_witness_input() {
+        let (header, mut chunks) = streamed_witness_input();
+        // Chunks may be provided in any order.
+        chunks.reverse();
+        let witness_input = header.clone().assemble(chunks).unwrap();
+        assert_assembled_witness_input(&witness_input, &header);
+    }
+
+    #[test]
+    fn assembling_streamed_witness_input_with_invalid_chunks() {
+        let (header, chunks) = streamed_witness_input();
+
+        let err = header.clone().assemble(chunks[1..].to_vec()).unwrap_err();
+        assert_matches!(err, StreamedWitnessInputError::MissingChunk(0));
+
+        let mut duplicate_chunks = chunks.clone();
+        duplicate_chunks.push(chunks[1].clone());
+        let err = header.clone().assemble(duplicate_chunks).unwrap_err();
+        assert_matches!(err, StreamedWitnessInputError::DuplicateChunk(1));
+
+        let mut foreign_chunks = chunks.clone();
+        foreign_chunks[2].l1_batch_number = L1BatchNumber(2);
+        let err = header.clone().assemble(foreign_chunks).unwrap_err();
+        assert_matches!(
+            err,
+            StreamedWitnessInputError::ChunkFromOtherBatch {
+                chunk_index: 2,
+                expected: L1BatchNumber(1),
+                actual: L1BatchNumber(2),
+            }
+        );
+
+        let mut out_of_range_chunks = chunks;
+        out_of_range_chunks[2].chunk_index = 3;
+        let err = header.assemble(out_of_range_chunks).unwrap_err();
+        assert_matches!(
+            err,
+            StreamedWitnessInputError::ChunkOutOfRange {
+                chunk_index: 3,
+                chunk_count: 3,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn loading_streamed_witness_input() {
+        let object_store = MockObjectStore::arc();
+        let (header, chunks) = streamed_witness_input();
+        let l1_batch_number = header.l1_batch_number;
+        for chunk in &chunks[..2] {
+            object_store
+                .put((l1_batch_number, chunk.chunk_index), chunk)
+                .await
+                .unwrap();
+        }
+        object_store.put(l1_batch_number, &header).await.unwrap();
+
+        let err = StreamedVmRunWitnessInputData::load(&*object_store, l1_batch_number)
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            StreamedWitnessInputError::ObjectStore(ObjectStoreError::KeyNotFound(_))
+        );
+
+        object_store
+            .put((l1_batch_number, 2), &chunks[2])
+            .await
+            .unwrap();
+        let witness_input = StreamedVmRunWitnessInputData::load(&*object_store, l1_batch_number)
+            .await
+            .unwrap();
+        assert_assembled_witness_input(&witness_input, &header);
+    }
 }
@@ -1,4 +1,5 @@
 use std::{
+    collections::VecDeque,
     fmt::Debug,
     time::{Duration, Instant},
 };
@@ -6,9 +7,15 @@ use std::{
 use anyhow::Context as _;
 pub use async_trait::async_trait;
 use tokio::{sync::watch, task::JoinHandle, time::sleep};
-use vise::{Buckets, Counter, Histogram, LabeledFamily, Metrics};
+use vise::{Buckets, Counter, Gauge, Histogram, LabeledFamily, Metrics};
 use zksync_utils::panic_extractor::try_extract_panic_message;
 
+pub use crate::queued::{JobQueue, QueuedJobProcessor};
+
+mod queued;
+#[cfg(test)]
+mod tests;
+
 const ATTEMPT_BUCKETS: Buckets = Buckets::exponential(1.0..=64.0, 2.0);
 
 #[derive(Debug, Metrics)]
@@ -18,11 +25,50 @@ struct JobProcessorMetrics {
     max_attempts_reached: LabeledFamily<(&'static str, String), Counter, 2>,
     #[metrics(labels = ["service_name"], buckets = ATTEMPT_BUCKETS)]
     attempts: LabeledFamily<&'static str, Histogram<usize>>,
+    /// Number of jobs fetched while the previous job was being processed.
+    #[metrics(labels = ["service_name"])]
+    prefetched_jobs: LabeledFamily<&'static str, Counter>,
+    /// Estimated memory used by prefetched jobs waiting to be processed.
+    #[metrics(labels = ["service_name"])]
+    prefetched_jobs_memory_bytes: LabeledFamily<&'static str, Gauge<usize>>,
+    /// Number of errors occurred during prefetching jobs.
+    #[metrics(labels = ["service_name"])]
+    prefetch_errors: LabeledFamily<&'static str, Counter>,
+    /// Number of prefetched jobs dropped because they were re-queued before being processed.
+    #[metrics(labels = ["service_name"])]
+    dropped_prefetched_jobs: LabeledFamily<&'static str, Counter>,
 }
 
 #[vise::register]
 static METRICS: vise::Global<JobProcessorMetrics> = vise::Global::new();
 
+/// Configuration of job prefetching for a [`JobProcessor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobPrefetchConfig {
+    /// Maximum number of jobs fetched ahead while the current job is being processed.
+    pub depth: usize,
+    /// Maximum estimated memory used by prefetched jobs (see [`JobProcessor::job_size_in_bytes()`]).
+    /// The limit is checked before fetching each job, so it may be exceeded by a single job.
+    pub max_memory_bytes: Option<usize>,
+}
+
+/// Claim on a job picked by a [`JobProcessor`]. A job may be re-queued (e.g., after its processing timeout has expired)
+/// and picked again, possibly by another processor; the claim allows to distinguish between these picks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobClaim {
+    /// Identifier of the processor that has picked the job.
+    pub picked_by: Option<String>,
+    /// Number of attempts made to process the job, including the current one.
+    pub attempts: u32,
+}
+
+struct PrefetchedJob<P: JobProcessor> {
+    id: P::JobId,
+    job: P::Job,
+    claim: Option<JobClaim>,
+    size_in_bytes: usize,
+}
+
 #[async_trait]
 pub trait JobProcessor: Sync + Send {
     type Job: Send + 'static;
@@ -51,6 +97,41 @@ pub trait JobProcessor: Sync + Send {
         started_at: Instant,
     ) -> JoinHandle<anyhow::Result<Self::JobArtifacts>>;
 
+    /// Returns the prefetching configuration. If prefetching is enabled, `get_next_job()` is called while
+    /// the current job is being processed, overlapping loading job inputs with computations.
+    ///
+    /// Note that prefetched jobs are claimed in the same way as processed ones, so if the processor is stopped,
+    /// they will be retried only after their processing timeout expires. To prevent prefetched jobs
+    /// from timing out while waiting to be processed, claims on them are refreshed after each processed job
+    /// (see [`Self::refresh_job_claim()`]).
+    fn prefetch_config(&self) -> Option<JobPrefetchConfig> {
+        None
+    }
+
+    /// Returns the current claim on a job. Called for prefetched jobs right after they are picked, so that
+    /// the claim can be refreshed later (see [`Self::refresh_job_claim()`]). If `None` is returned,
+    /// the claim on the job is not refreshed.
+    async fn get_job_claim(&self, _job_id: &Self::JobId) -> anyhow::Result<Option<JobClaim>> {
+        Ok(None)
+    }
+
+    /// Refreshes the claim on a prefetched job, so that its processing timeout is counted from the moment
+    /// of the refresh. Returns `false` if the job is no longer held by the specified `claim` (e.g., it was re-queued
+    /// after its processing timeout has expired, and possibly picked again); in this case, the job is dropped
+    /// without processing.
+    async fn refresh_job_claim(
+        &self,
+        _job_id: &Self::JobId,
+        _claim: &JobClaim,
+    ) -> anyhow::Result<bool> {
+        Ok(true)
+    }
+
+    /// Estimates memory used by the job. Used to cap memory consumed by prefetched jobs.
+    fn job_size_in_bytes(&self, _job: &Self::Job) -> usize {
+        0
+    }
+
     /// `iterations_left`:
     /// To run indefinitely, pass `None`,
     /// To process one job, pass `Some(1)`,
@@ -64,17 +145,32 @@ pub trait JobProcessor: Sync + Send {
         Self: Sized,
     {
         let mut backoff: u64 = Self::POLLING_INTERVAL_MS;
+        let prefetch_config = self.prefetch_config().filter(|config| config.depth > 0);
+        let mut prefetched_jobs = VecDeque::<PrefetchedJob<Self>>::new();
         while iterations_left.map_or(true, |i| i > 0) {
             if *stop_receiver.borrow() {
                 tracing::warn!(
                     "Stop signal received, shutting down {} component while waiting for a new job",
                     Self::SERVICE_NAME
                 );
+                if !prefetched_jobs.is_empty() {
+                    tracing::warn!(
+                        "{} prefetched {} jobs will be retried after their processing timeout",
+                        Self::SERVICE_NAME,
+                        prefetched_jobs.len()
+                    );
+                }
                 return Ok(());
             }
-            if let Some((job_id, job)) =
+
+            let next_job = if let Some(prefetched) = prefetched_jobs.pop_front() {
+                METRICS.prefetched_jobs_memory_bytes[&Self::SERVICE_NAME]
+                    .set(prefetched_jobs_size(&prefetched_jobs));
+                Some((prefetched.id, prefetched.job))
+            } else {
                 Self::get_next_job(&self).await.context("get_next_job()")?
-            {
+            };
+            if let Some((job_id, job)) = next_job {
                 let started_at = Instant::now();
                 backoff = Self::POLLING_INTERVAL_MS;
                 iterations_left = iterations_left.map(|i| i - 1);
@@ -86,9 +182,23 @@ pub trait JobProcessor: Sync + Send {
                 );
                 let task = self.process_job(&job_id, job, started_at).await;
 
-                self.wait_for_task(job_id, started_at, task)
-                    .await
-                    .context("wait_for_task")?;
+                let wait_for_task = self.wait_for_task(job_id, started_at, task);
+                if let Some(config) = prefetch_config {
+                    // Do not fetch jobs that won't be processed.
+                    let max_jobs = iterations_left.map_or(config.depth, |i| i.min(config.depth));
+                    let prefetch = prefetch_jobs(
+                        &self,
+                        &mut prefetched_jobs,
+                        config,
+                        max_jobs,
+                        &stop_receiver,
+                    );
+                    let (wait_result, ()) = tokio::join!(wait_for_task, prefetch);
+                    wait_result.context("wait_for_task")?;
+                    refresh_prefetched_jobs(&self, &mut prefetched_jobs).await;
+                } else {
+                    wait_for_task.await.context("wait_for_task")?;
+                }
             } else if iterations_left.is_some() {
                 tracing::info!("No more jobs to process. Server can stop now.");
                 return Ok(());
@@ -172,3 +282,102 @@ pub trait JobProcessor: Sync + Send {
     /// Invoked in `wait_for_task` for in-progress job.
     async fn get_job_attempts(&self, job_id: &Self::JobId) -> anyhow::Result<u32>;
 }
+
+/// Fetches jobs into `prefetched_jobs` until the queue is full, the memory cap is reached, or there are no jobs.
+/// Errors are not fatal: they are logged, and prefetching is retried after the next processed job.
+async fn prefetch_jobs<P: JobProcessor>(
+    processor: &P,
+    prefetched_jobs: &mut VecDeque<PrefetchedJob<P>>,
+    config: JobPrefetchConfig,
+    max_jobs: usize,
+    stop_receiver: &watch::Receiver<bool>,
+) {
+    while prefetched_jobs.len() < max_jobs && !*stop_receiver.borrow() {
+        let used_memory = prefetched_jobs_size(prefetched_jobs);
+        if config
+            .max_memory_bytes
+            .map_or(false, |max_memory| used_memory >= max_memory)
+        {
+            tracing::debug!(
+                "{} prefetched jobs use {used_memory} bytes; stopping prefetching",
+                P::SERVICE_NAME
+            );
+            break;
+        }
+
+        let (job_id, job) = match processor.get_next_job().await {
+            Ok(Some(job)) => job,
+            Ok(None) => break,
+            Err(err) => {
+                tracing::warn!("Failed prefetching {} job: {err:#}", P::SERVICE_NAME);
+                METRICS.prefetch_errors[&P::SERVICE_NAME].inc();
+                break;
+            }
+        };
+        tracing::debug!("Prefetched {} job with id {job_id:?}", P::SERVICE_NAME);
+        let claim = match processor.get_job_claim(&job_id).await {
+            Ok(claim) => claim,
+            Err(err) => {
+                // The job is still processed, but its claim won't be refreshed.
+                tracing::warn!(
+                    "Failed getting claim on prefetched {} job with id {job_id:?}: {err:#}",
+                    P::SERVICE_NAME
+                );
+                METRICS.prefetch_errors[&P::SERVICE_NAME].inc();
+                None
+            }
+        };
+        let size_in_bytes = processor.job_size_in_bytes(&job);
+        prefetched_jobs.push_back(PrefetchedJob {
+            id: job_id,
+            job,
+            claim,
+            size_in_bytes,
+        });
+        METRICS.prefetched_jobs[&P::SERVICE_NAME].inc();
+        METRICS.prefetched_jobs_memory_bytes[&P::SERVICE_NAME]
+            .set(prefetched_jobs_size(prefetched_jobs));
+    }
+}
+
+/// Refreshes claims on all prefetched jobs, dropping jobs that are no longer claimed. Errors are logged;
+/// jobs for which the refresh has failed are retained.
+async fn refresh_prefetched_jobs<P: JobProcessor>(
+    processor: &P,
+    prefetched_jobs: &mut VecDeque<PrefetchedJob<P>>,
+) {
+    let mut retained_jobs = VecDeque::with_capacity(prefetched_jobs.len());
+    for prefetched in prefetched_jobs.drain(..) {
+        let Some(claim) = &prefetched.claim else {
+            retained_jobs.push_back(prefetched);
+            continue;
+        };
+        match processor.refresh_job_claim(&prefetched.id, claim).await {
+            Ok(true) => retained_jobs.push_back(prefetched),
+            Ok(false) => {
+                tracing::warn!(
+                    "Prefetched {} job with id {:?} is no longer claimed; dropping it",
+                    P::SERVICE_NAME,
+                    prefetched.id
+                );
+                METRICS.dropped_prefetched_jobs[&P::SERVICE_NAME].inc();
+            }
+            Err(err) => {
+                tracing::warn!(
+                    "Failed refreshing claim on prefetched {} job with id {:?}: {err:#}",
+                    P::SERVICE_NAME,
+                    prefetched.id
+                );
+                METRICS.prefetch_errors[&P::SERVICE_NAME].inc();
+                retained_jobs.push_back(prefetched);
+            }
+        }
+    }
+    *prefetched_jobs = retained_jobs;
+    METRICS.prefetched_jobs_memory_bytes[&P::SERVICE_NAME]
+        .set(prefetched_jobs_size(prefetched_jobs));
+}
+
+fn prefetched_jobs_size<P: JobProcessor>(prefetched_jobs: &VecDeque<PrefetchedJob<P>>) -> usize {
+    prefetched_jobs.iter().map(|job| job.size_in_bytes).sum()
+}
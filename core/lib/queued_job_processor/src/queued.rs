@@ -0,0 +1,156 @@
+//! Second version of the job processor API, in which job lifecycle operations are delegated to a [`JobQueue`].
+
+use std::{fmt::Debug, time::Instant};
+
+use async_trait::async_trait;
+use tokio::task::JoinHandle;
+
+use crate::{JobClaim, JobPrefetchConfig, JobProcessor};
+
+/// Persistent queue of jobs with a standardized lifecycle: jobs are queued, picked (i.e., moved to the in-progress
+/// state), and then either succeed or fail. Failed jobs are re-queued until they run out of attempts.
+///
+/// The queue is responsible for lifecycle transitions common for all jobs. Transitions specific to a certain
+/// job type (picking jobs and saving their results) are implemented by [`QueuedJobProcessor`]s.
+#[async_trait]
+pub trait JobQueue: Debug + Send + Sync {
+    type JobId: Send + Sync + Debug + 'static;
+
+    /// Marks the job as failed. The job may be re-queued if it has attempts left.
+    async fn mark_job_failed(&self, job_id: &Self::JobId, error: &str) -> anyhow::Result<()>;
+
+    /// Returns the number of attempts made to process the job (including the current one).
+    async fn get_job_attempts(&self, job_id: &Self::JobId) -> anyhow::Result<u32>;
+
+    /// Returns the current claim on an in-progress job, or `None` if the job is not in progress.
+    async fn get_job_claim(&self, job_id: &Self::JobId) -> anyhow::Result<Option<JobClaim>>;
+
+    /// Refreshes the claim on an in-progress job. See [`JobProcessor::refresh_job_claim()`] for details.
+    async fn refresh_job_claim(
+        &self,
+        job_id: &Self::JobId,
+        claim: &JobClaim,
+    ) -> anyhow::Result<bool>;
+}
+
+/// Job processor backed by a [`JobQueue`]. Each `QueuedJobProcessor` is a [`JobProcessor`], with lifecycle
+/// operations (marking jobs as failed, getting job attempts, getting and refreshing claims) delegated to the queue.
+/// Other methods have the same meaning as in [`JobProcessor`].
+#[async_trait]
+pub trait QueuedJobProcessor: Sync + Send {
+    type Job: Send + 'static;
+    type JobId: Send + Sync + Debug + 'static;
+    type JobArtifacts: Send + 'static;
+    type Queue: JobQueue<JobId = Self::JobId>;
+
+    const POLLING_INTERVAL_MS: u64 = 1000;
+    const MAX_BACKOFF_MS: u64 = 60_000;
+    const BACKOFF_MULTIPLIER: u64 = 2;
+    const SERVICE_NAME: &'static str;
+
+    /// Returns the queue storing jobs for this processor.
+    fn job_queue(&self) -> &Self::Queue;
+
+    /// See [`JobProcessor::get_next_job()`].
+    async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>>;
+
+    /// See [`JobProcessor::process_job()`].
+    async fn process_job(
+        &self,
+        job_id: &Self::JobId,
+        job: Self::Job,
+        started_at: Instant,
+    ) -> JoinHandle<anyhow::Result<Self::JobArtifacts>>;
+
+    /// See [`JobProcessor::save_result()`].
+    async fn save_result(
+        &self,
+        job_id: Self::JobId,
+        started_at: Instant,
+        artifacts: Self::JobArtifacts,
+    ) -> anyhow::Result<()>;
+
+    fn max_attempts(&self) -> u32;
+
+    /// See [`JobProcessor::prefetch_config()`].
+    fn prefetch_config(&self) -> Option<JobPrefetchConfig> {
+        None
+    }
+
+    /// See [`JobProcessor::job_size_in_bytes()`].
+    fn job_size_in_bytes(&self, _job: &Self::Job) -> usize {
+        0
+    }
+}
+
+#[async_trait]
+impl<P: QueuedJobProcessor> JobProcessor for P {
+    type Job = P::Job;
+    type JobId = P::JobId;
+    type JobArtifacts = P::JobArtifacts;
+
+    const POLLING_INTERVAL_MS: u64 = P::POLLING_INTERVAL_MS;
+    const MAX_BACKOFF_MS: u64 = P::MAX_BACKOFF_MS;
+    const BACKOFF_MULTIPLIER: u64 = P::BACKOFF_MULTIPLIER;
+    const SERVICE_NAME: &'static str = P::SERVICE_NAME;
+
+    async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
+        QueuedJobProcessor::get_next_job(self).await
+    }
+
+    async fn save_failure(&self, job_id: Self::JobId, _started_at: Instant, error: String) {
+        if let Err(err) = self.job_queue().mark_job_failed(&job_id, &error).await {
+            // The job will be re-queued after its processing timeout expires.
+            tracing::error!(
+                "Failed marking {} job {job_id:?} as failed: {err:#}",
+                P::SERVICE_NAME
+            );
+        }
+    }
+
+    async fn process_job(
+        &self,
+        job_id: &Self::JobId,
+        job: Self::Job,
+        started_at: Instant,
+    ) -> JoinHandle<anyhow::Result<Self::JobArtifacts>> {
+        QueuedJobProcessor::process_job(self, job_id, job, started_at).await
+    }
+
+    fn prefetch_config(&self) -> Option<JobPrefetchConfig> {
+        QueuedJobProcessor::prefetch_config(self)
+    }
+
+    fn job_size_in_bytes(&self, job: &Self::Job) -> usize {
+        QueuedJobProcessor::job_size_in_bytes(self, job)
+    }
+
+    async fn get_job_claim(&self, job_id: &Self::JobId) -> anyhow::Result<Option<JobClaim>> {
+        self.job_queue().get_job_claim(job_id).await
+    }
+
+    async fn refresh_job_claim(
+        &self,
+        job_id: &Self::JobId,
+        claim: &JobClaim,
+    ) -> anyhow::Result<bool> {
+        self.job_queue().refresh_job_claim(job_id, claim).await
+    }
+
+    async fn save_result(
+        &self,
+        job_id: Self::JobId,
+        started_at: Instant,
+        artifacts: Self::JobArtifacts,
+    ) -> anyhow::Result<()> {
+        QueuedJobProcessor::save_result(self, job_id, started_at, artifacts).await
+    }
+
+    fn max_attempts(&self) -> u32 {
+        QueuedJobProcessor::max_attempts(self)
+    }
+
+    async fn get_job_attempts(&self, job_id: &Self::JobId) -> anyhow::Result<u32> {
+        self.job_queue().get_job_attempts(job_id).await
+    }
+}
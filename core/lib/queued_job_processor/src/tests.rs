@@ -0,0 +1,361 @@
+//! Tests for the job processor.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+use super::*;
+
+const JOB_SIZE: usize = 100;
+
+#[derive(Debug, Default)]
+struct MockQueue {
+    queued: VecDeque<u32>,
+    claimed: HashSet<u32>,
+    /// Number of times each job was picked.
+    attempts: HashMap<u32, u32>,
+    processed: Vec<u32>,
+    fetch_count: usize,
+    /// Index of `get_next_job()` call that will fail.
+    failing_fetch: Option<usize>,
+    /// Job that will be re-queued once the specified job is processed, emulating an expired processing timeout.
+    requeued_on_save: Option<(u32, u32)>,
+    /// Job that will be re-queued and picked by another processor once the specified job is processed.
+    repicked_on_save: Option<(u32, u32)>,
+    max_claimed_jobs: usize,
+}
+
+#[derive(Debug, Clone)]
+struct MockJobProcessor {
+    queue: Arc<Mutex<MockQueue>>,
+    prefetch_config: Option<JobPrefetchConfig>,
+}
+
+impl MockJobProcessor {
+    fn new(job_count: u32, prefetch_config: Option<JobPrefetchConfig>) -> Self {
+        let queue = MockQueue {
+            queued: (0..job_count).collect(),
+            ..MockQueue::default()
+        };
+        Self {
+            queue: Arc::new(Mutex::new(queue)),
+            prefetch_config,
+        }
+    }
+
+    fn prefetching(job_count: u32, depth: usize) -> Self {
+        Self::new(
+            job_count,
+            Some(JobPrefetchConfig {
+                depth,
+                max_memory_bytes: None,
+            }),
+        )
+    }
+
+    fn queue(&self) -> std::sync::MutexGuard<'_, MockQueue> {
+        self.queue.lock().unwrap()
+    }
+}
+
+#[async_trait]
+impl JobProcessor for MockJobProcessor {
+    type Job = u32;
+    type JobId = u32;
+    type JobArtifacts = u32;
+
+    const POLLING_INTERVAL_MS: u64 = 10;
+    const SERVICE_NAME: &'static str = "mock_job_processor";
+
+    async fn get_next_job(&self) -> anyhow::Result<Option<(u32, u32)>> {
+        let mut queue = self.queue();
+        let fetch_idx = queue.fetch_count;
+        queue.fetch_count += 1;
+        if queue.failing_fetch == Some(fetch_idx) {
+            anyhow::bail!("emulated fetch error");
+        }
+        let Some(job_id) = queue.queued.pop_front() else {
+            return Ok(None);
+        };
+        queue.claimed.insert(job_id);
+        *queue.attempts.entry(job_id).or_default() += 1;
+        Ok(Some((job_id, job_id)))
+    }
+
+    async fn save_failure(&self, job_id: u32, _started_at: Instant, error: String) {
+        panic!("job {job_id} failed: {error}");
+    }
+
+    async fn process_job(
+        &self,
+        _job_id: &u32,
+        job: u32,
+        _started_at: Instant,
+    ) -> JoinHandle<anyhow::Result<u32>> {
+        tokio::spawn(async move { Ok(job) })
+    }
+
+    fn prefetch_config(&self) -> Option<JobPrefetchConfig> {
+        self.prefetch_config
+    }
+
+    fn job_size_in_bytes(&self, _job: &u32) -> usize {
+        JOB_SIZE
+    }
+
+    async fn get_job_claim(&self, job_id: &u32) -> anyhow::Result<Option<JobClaim>> {
+        let queue = self.queue();
+        Ok(queue.claimed.contains(job_id).then(|| JobClaim {
+            picked_by: Some("mock".to_owned()),
+            attempts: queue.attempts[job_id],
+        }))
+    }
+
+    async fn refresh_job_claim(&self, job_id: &u32, claim: &JobClaim) -> anyhow::Result<bool> {
+        let queue = self.queue();
+        Ok(queue.claimed.contains(job_id) && queue.attempts[job_id] == claim.attempts)
+    }
+
+    async fn save_result(
+        &self,
+        job_id: u32,
+        _started_at: Instant,
+        artifacts: u32,
+    ) -> anyhow::Result<()> {
+        let mut queue = self.queue();
+        queue.max_claimed_jobs = queue.max_claimed_jobs.max(queue.claimed.len());
+        assert!(queue.claimed.remove(&job_id), "job {job_id} is not claimed");
+        queue.processed.push(artifacts);
+
+        if let Some((processed_job_id, requeued_job_id)) = queue.requeued_on_save {
+            if processed_job_id == job_id {
+                assert!(queue.claimed.remove(&requeued_job_id));
+                queue.queued.push_back(requeued_job_id);
+            }
+        }
+        if let Some((processed_job_id, repicked_job_id)) = queue.repicked_on_save {
+            if processed_job_id == job_id {
+                *queue.attempts.get_mut(&repicked_job_id).unwrap() += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn max_attempts(&self) -> u32 {
+        10
+    }
+
+    async fn get_job_attempts(&self, _job_id: &u32) -> anyhow::Result<u32> {
+        Ok(1)
+    }
+}
+
+#[tokio::test]
+async fn processing_jobs_without_prefetching() {
+    let processor = MockJobProcessor::new(3, None);
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    processor.clone().run(stop_receiver, Some(3)).await.unwrap();
+
+    let queue = processor.queue();
+    assert_eq!(queue.processed, [0, 1, 2]);
+    assert_eq!(queue.max_claimed_jobs, 1);
+}
+
+#[tokio::test]
+async fn processing_jobs_with_prefetching() {
+    let processor = MockJobProcessor::prefetching(5, 2);
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    processor.clone().run(stop_receiver, Some(5)).await.unwrap();
+
+    let queue = processor.queue();
+    assert_eq!(queue.processed, [0, 1, 2, 3, 4]);
+    assert_eq!(queue.max_claimed_jobs, 3);
+    assert!(queue.claimed.is_empty(), "{:?}", queue.claimed);
+}
+
+#[tokio::test]
+async fn jobs_that_will_not_be_processed_are_not_prefetched() {
+    let processor = MockJobProcessor::prefetching(5, 2);
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    processor.clone().run(stop_receiver, Some(3)).await.unwrap();
+
+    let queue = processor.queue();
+    assert_eq!(queue.processed, [0, 1, 2]);
+    assert!(queue.claimed.is_empty(), "{:?}", queue.claimed);
+    assert_eq!(queue.queued, [3, 4]);
+}
+
+#[tokio::test]
+async fn prefetching_respects_memory_cap() {
+    let processor = MockJobProcessor::new(
+        5,
+        Some(JobPrefetchConfig {
+            depth: 3,
+            max_memory_bytes: Some(JOB_SIZE),
+        }),
+    );
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    processor.clone().run(stop_receiver, Some(5)).await.unwrap();
+
+    let queue = processor.queue();
+    assert_eq!(queue.processed, [0, 1, 2, 3, 4]);
+    // The current job + a single prefetched job
+    assert_eq!(queue.max_claimed_jobs, 2);
+}
+
+#[tokio::test]
+async fn prefetching_errors_are_not_fatal() {
+    let processor = MockJobProcessor::prefetching(3, 1);
+    // The first fetch is performed for the first job; the second one is the first prefetch.
+    processor.queue().failing_fetch = Some(1);
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    processor.clone().run(stop_receiver, Some(3)).await.unwrap();
+
+    let queue = processor.queue();
+    assert_eq!(queue.processed, [0, 1, 2]);
+    assert!(queue.claimed.is_empty(), "{:?}", queue.claimed);
+}
+
+#[tokio::test]
+async fn requeued_prefetched_jobs_are_dropped() {
+    let processor = MockJobProcessor::prefetching(3, 1);
+    processor.queue().requeued_on_save = Some((0, 1));
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    processor.clone().run(stop_receiver, Some(3)).await.unwrap();
+
+    let queue = processor.queue();
+    // Job 1 is re-fetched after it was re-queued.
+    assert_eq!(queue.processed, [0, 2, 1]);
+    assert!(queue.claimed.is_empty(), "{:?}", queue.claimed);
+}
+
+#[tokio::test]
+async fn repicked_prefetched_jobs_are_dropped() {
+    let processor = MockJobProcessor::prefetching(3, 1);
+    processor.queue().repicked_on_save = Some((0, 1));
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    processor.clone().run(stop_receiver, Some(2)).await.unwrap();
+
+    let queue = processor.queue();
+    // Job 1 is processed by another processor.
+    assert_eq!(queue.processed, [0, 2]);
+    assert_eq!(queue.claimed, HashSet::from([1]));
+}
+
+#[derive(Debug, Clone, Default)]
+struct MockJobQueue {
+    failed_jobs: Arc<Mutex<Vec<(u32, String)>>>,
+    refreshed_jobs: Arc<Mutex<Vec<u32>>>,
+}
+
+#[async_trait]
+impl JobQueue for MockJobQueue {
+    type JobId = u32;
+
+    async fn mark_job_failed(&self, job_id: &u32, error: &str) -> anyhow::Result<()> {
+        self.failed_jobs
+            .lock()
+            .unwrap()
+            .push((*job_id, error.to_owned()));
+        Ok(())
+    }
+
+    async fn get_job_attempts(&self, _job_id: &u32) -> anyhow::Result<u32> {
+        Ok(1)
+    }
+
+    async fn get_job_claim(&self, _job_id: &u32) -> anyhow::Result<Option<JobClaim>> {
+        Ok(Some(JobClaim {
+            picked_by: None,
+            attempts: 1,
+        }))
+    }
+
+    async fn refresh_job_claim(&self, job_id: &u32, _claim: &JobClaim) -> anyhow::Result<bool> {
+        self.refreshed_jobs.lock().unwrap().push(*job_id);
+        Ok(true)
+    }
+}
+
+/// Processor failing jobs with odd IDs.
+#[derive(Debug, Default)]
+struct MockQueuedJobProcessor {
+    queued: Mutex<VecDeque<u32>>,
+    processed: Arc<Mutex<Vec<u32>>>,
+    queue: MockJobQueue,
+}
+
+#[async_trait]
+impl QueuedJobProcessor for MockQueuedJobProcessor {
+    type Job = u32;
+    type JobId = u32;
+    type JobArtifacts = u32;
+    type Queue = MockJobQueue;
+
+    const POLLING_INTERVAL_MS: u64 = 10;
+    const SERVICE_NAME: &'static str = "mock_queued_job_processor";
+
+    fn job_queue(&self) -> &MockJobQueue {
+        &self.queue
+    }
+
+    async fn get_next_job(&self) -> anyhow::Result<Option<(u32, u32)>> {
+        let job_id = self.queued.lock().unwrap().pop_front();
+        Ok(job_id.map(|id| (id, id)))
+    }
+
+    async fn process_job(
+        &self,
+        _job_id: &u32,
+        job: u32,
+        _started_at: Instant,
+    ) -> JoinHandle<anyhow::Result<u32>> {
+        tokio::spawn(async move {
+            if job % 2 == 1 {
+                anyhow::bail!("job {job} failed");
+            }
+            Ok(job)
+        })
+    }
+
+    async fn save_result(
+        &self,
+        _job_id: u32,
+        _started_at: Instant,
+        artifacts: u32,
+    ) -> anyhow::Result<()> {
+        self.processed.lock().unwrap().push(artifacts);
+        Ok(())
+    }
+
+    fn max_attempts(&self) -> u32 {
+        10
+    }
+
+    fn prefetch_config(&self) -> Option<JobPrefetchConfig> {
+        Some(JobPrefetchConfig {
+            depth: 1,
+            max_memory_bytes: None,
+        })
+    }
+}
+
+#[tokio::test]
+async fn queued_job_processor_delegates_lifecycle_to_queue() {
+    let processor = MockQueuedJobProcessor {
+        queued: Mutex::new((0..3).collect()),
+        ..MockQueuedJobProcessor::default()
+    };
+    let processed = processor.processed.clone();
+    let queue = processor.queue.clone();
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    processor.run(stop_receiver, Some(3)).await.unwrap();
+
+    assert_eq!(*processed.lock().unwrap(), [0, 2]);
+    let failed_jobs = queue.failed_jobs.lock().unwrap();
+    assert_eq!(failed_jobs.len(), 1);
+    assert_eq!(failed_jobs[0].0, 1);
+    assert!(failed_jobs[0].1.contains("job 1 failed"), "{failed_jobs:?}");
+    assert_eq!(*queue.refreshed_jobs.lock().unwrap(), [1, 2]);
+}
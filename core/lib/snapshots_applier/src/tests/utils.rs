@@ -1,6 +1,10 @@
 //! Test utils.
 
-use std::{collections::HashMap, fmt, future, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, future,
+    sync::Arc,
+};
 
 use async_trait::async_trait;
 use tokio::sync::watch;
@@ -178,6 +182,7 @@ fn l1_batch_details(number: L1BatchNumber, root_hash: H256) -> api::L1BatchDetai
     api::L1BatchDetails {
         number,
         base: block_details_base(root_hash),
+        custom_metadata: BTreeMap::new(),
     }
 }
 
@@ -25,6 +25,7 @@ mod rocksdb;
 mod shadow_storage;
 mod storage_factory;
 mod storage_view;
+mod storage_view_at;
 #[cfg(test)]
 mod test_utils;
 
@@ -41,6 +42,7 @@ pub use self::{
     shadow_storage::ShadowStorage,
     storage_factory::{BatchDiff, PgOrRocksdbStorage, ReadStorageFactory, RocksdbWithMemory},
     storage_view::{StorageView, StorageViewMetrics},
+    storage_view_at::StorageViewAt,
 };
 
 /// Functionality to read from the VM storage.
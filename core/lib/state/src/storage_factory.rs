@@ -1,14 +1,15 @@
-use std::{collections::HashMap, fmt::Debug};
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
 use anyhow::Context as _;
 use async_trait::async_trait;
-use tokio::{runtime::Handle, sync::watch};
-use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use tokio::sync::watch;
+use zksync_dal::{Connection, ConnectionPool, Core};
 use zksync_storage::RocksDB;
 use zksync_types::{L1BatchNumber, StorageKey, StorageValue, H256};
 
 use crate::{
-    PostgresStorage, ReadStorage, RocksdbStorage, RocksdbStorageBuilder, StateKeeperColumnFamily,
+    InMemoryStorage, PostgresStorage, ReadStorage, RocksdbStorage, RocksdbStorageBuilder,
+    StateKeeperColumnFamily, StorageViewAt,
 };
 
 /// Factory that can produce a [`ReadStorage`] implementation on demand.
@@ -57,6 +58,8 @@ pub enum PgOrRocksdbStorage<'a> {
     Rocksdb(RocksdbStorage),
     /// Implementation over a RocksDB cache instance with in-memory DB diffs.
     RocksdbWithMemory(RocksdbWithMemory),
+    /// Implementation over a shared in-memory storage. Mostly useful for tests.
+    InMemory(Arc<InMemoryStorage>),
 }
 
 impl<'a> PgOrRocksdbStorage<'a> {
@@ -69,35 +72,11 @@ impl<'a> PgOrRocksdbStorage<'a> {
         pool: &'a ConnectionPool<Core>,
         l1_batch_number: L1BatchNumber,
     ) -> anyhow::Result<PgOrRocksdbStorage<'a>> {
-        let mut connection = pool.connection().await?;
-        let l2_block_number = if let Some((_, l2_block_number)) = connection
-            .blocks_dal()
-            .get_l2_block_range_of_l1_batch(l1_batch_number)
-            .await?
-        {
-            l2_block_number
-        } else {
-            tracing::info!("Could not find latest sealed L2 block, loading from snapshot");
-            let snapshot_recovery = connection
-                .snapshot_recovery_dal()
-                .get_applied_snapshot_status()
-                .await?
-                .context("Could not find snapshot, no state available")?;
-            if snapshot_recovery.l1_batch_number != l1_batch_number {
-                anyhow::bail!(
-                    "Snapshot contains L1 batch #{} while #{} was expected",
-                    snapshot_recovery.l1_batch_number,
-                    l1_batch_number
-                );
-            }
-            snapshot_recovery.l2_block_number
-        };
-        tracing::debug!(%l1_batch_number, %l2_block_number, "Using Postgres-based storage");
-        Ok(
-            PostgresStorage::new_async(Handle::current(), connection, l2_block_number, true)
-                .await?
-                .into(),
-        )
+        let connection = pool.connection().await?;
+        let storage = StorageViewAt::l1_batch(l1_batch_number)
+            .postgres(connection)
+            .await?;
+        Ok(storage.into())
     }
 
     /// Catches up RocksDB synchronously (i.e. assumes the gap is small) and
@@ -191,6 +170,7 @@ impl ReadStorage for PgOrRocksdbStorage<'_> {
             Self::Postgres(postgres) => postgres.read_value(key),
             Self::Rocksdb(rocksdb) => rocksdb.read_value(key),
             Self::RocksdbWithMemory(rocksdb_mem) => rocksdb_mem.read_value(key),
+            Self::InMemory(storage) => (&**storage).read_value(key),
         }
     }
 
@@ -199,6 +179,7 @@ impl ReadStorage for PgOrRocksdbStorage<'_> {
             Self::Postgres(postgres) => postgres.is_write_initial(key),
             Self::Rocksdb(rocksdb) => rocksdb.is_write_initial(key),
             Self::RocksdbWithMemory(rocksdb_mem) => rocksdb_mem.is_write_initial(key),
+            Self::InMemory(storage) => (&**storage).is_write_initial(key),
         }
     }
 
@@ -207,6 +188,7 @@ impl ReadStorage for PgOrRocksdbStorage<'_> {
             Self::Postgres(postgres) => postgres.load_factory_dep(hash),
             Self::Rocksdb(rocksdb) => rocksdb.load_factory_dep(hash),
             Self::RocksdbWithMemory(rocksdb_mem) => rocksdb_mem.load_factory_dep(hash),
+            Self::InMemory(storage) => (&**storage).load_factory_dep(hash),
         }
     }
 
@@ -215,6 +197,7 @@ impl ReadStorage for PgOrRocksdbStorage<'_> {
             Self::Postgres(postgres) => postgres.get_enumeration_index(key),
             Self::Rocksdb(rocksdb) => rocksdb.get_enumeration_index(key),
             Self::RocksdbWithMemory(rocksdb_mem) => rocksdb_mem.get_enumeration_index(key),
+            Self::InMemory(storage) => (&**storage).get_enumeration_index(key),
         }
     }
 }
@@ -230,3 +213,15 @@ impl<'a> From<RocksdbStorage> for PgOrRocksdbStorage<'a> {
         Self::Rocksdb(value)
     }
 }
+
+impl<'a> From<InMemoryStorage> for PgOrRocksdbStorage<'a> {
+    fn from(value: InMemoryStorage) -> Self {
+        Self::InMemory(Arc::new(value))
+    }
+}
+
+impl<'a> From<Arc<InMemoryStorage>> for PgOrRocksdbStorage<'a> {
+    fn from(value: Arc<InMemoryStorage>) -> Self {
+        Self::InMemory(value)
+    }
+}
@@ -0,0 +1,294 @@
+//! Uniform access to the storage state at a certain point in the chain history.
+
+use anyhow::Context as _;
+use tokio::runtime::Handle;
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_types::{L1BatchNumber, L2BlockNumber};
+
+use crate::{PgOrRocksdbStorage, PostgresStorage, PostgresStorageCaches, RocksdbStorage};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HistoryPoint {
+    L2Block(L2BlockNumber),
+    L1Batch(L1BatchNumber),
+}
+
+/// Factory of [`ReadStorage`](crate::ReadStorage) implementations providing the storage state
+/// at a certain point in the chain history, i.e., after a certain L2 block or L1 batch is applied.
+///
+/// Postgres-based storage is available for any point present in Postgres, including the snapshot
+/// L1 batch for nodes recovered from a snapshot. A RocksDB cache can be used instead of Postgres
+/// if it is exactly at the requested L1 batch; see [`Self::rocksdb_or_postgres()`].
+#[derive(Debug, Clone)]
+pub struct StorageViewAt {
+    point: HistoryPoint,
+    consider_new_l1_batch: bool,
+    caches: Option<PostgresStorageCaches>,
+}
+
+impl StorageViewAt {
+    /// Views the storage state after the specified L2 block is applied.
+    pub fn l2_block(number: L2BlockNumber) -> Self {
+        Self::new(HistoryPoint::L2Block(number))
+    }
+
+    /// Views the storage state after all L2 blocks in the specified L1 batch are applied.
+    pub fn l1_batch(number: L1BatchNumber) -> Self {
+        Self::new(HistoryPoint::L1Batch(number))
+    }
+
+    fn new(point: HistoryPoint) -> Self {
+        Self {
+            point,
+            consider_new_l1_batch: true,
+            caches: None,
+        }
+    }
+
+    /// Specifies whether writes in the L1 batch containing the viewed L2 block are taken into account
+    /// when checking initial writes and enumeration indices. This is `true` by default; the API sandbox
+    /// sets it to `false` since it executes transactions as if the L1 batch were not sealed yet.
+    #[must_use]
+    pub fn consider_new_l1_batch(mut self, consider: bool) -> Self {
+        self.consider_new_l1_batch = consider;
+        self
+    }
+
+    /// Sets the caches to use with the Postgres-based storage.
+    #[must_use]
+    pub fn with_caches(mut self, caches: PostgresStorageCaches) -> Self {
+        self.caches = Some(caches);
+        self
+    }
+
+    /// Resolves the L2 block after which the storage state is viewed.
+    ///
+    /// # Errors
+    ///
+    /// Propagates Postgres errors. Errors if the viewed L1 batch is not present in Postgres.
+    pub async fn resolve_l2_block(
+        &self,
+        connection: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L2BlockNumber> {
+        let l1_batch_number = match self.point {
+            HistoryPoint::L2Block(number) => return Ok(number),
+            HistoryPoint::L1Batch(number) => number,
+        };
+
+        let l2_block_range = connection
+            .blocks_dal()
+            .get_l2_block_range_of_l1_batch(l1_batch_number)
+            .await?;
+        if let Some((_, l2_block_number)) = l2_block_range {
+            return Ok(l2_block_number);
+        }
+
+        tracing::info!(
+            "Could not find L2 blocks for L1 batch #{l1_batch_number}, loading from snapshot"
+        );
+        let snapshot_recovery = connection
+            .snapshot_recovery_dal()
+            .get_applied_snapshot_status()
+            .await?
+            .context("Could not find snapshot, no state available")?;
+        if snapshot_recovery.l1_batch_number != l1_batch_number {
+            anyhow::bail!(
+                "Snapshot contains L1 batch #{} while #{} was expected",
+                snapshot_recovery.l1_batch_number,
+                l1_batch_number
+            );
+        }
+        Ok(snapshot_recovery.l2_block_number)
+    }
+
+    /// Creates Postgres-based storage over the provided connection.
+    ///
+    /// # Errors
+    ///
+    /// Propagates Postgres errors.
+    pub async fn postgres<'a>(
+        &self,
+        connection: Connection<'a, Core>,
+    ) -> anyhow::Result<PostgresStorage<'a>> {
+        self.postgres_inner(Handle::current(), connection).await
+    }
+
+    /// Blocking version of [`Self::postgres()`]. Must be called from a blocking context.
+    ///
+    /// # Errors
+    ///
+    /// Propagates Postgres errors.
+    pub fn postgres_blocking<'a>(
+        &self,
+        rt_handle: Handle,
+        connection: Connection<'a, Core>,
+    ) -> anyhow::Result<PostgresStorage<'a>> {
+        rt_handle
+            .clone()
+            .block_on(self.postgres_inner(rt_handle, connection))
+    }
+
+    async fn postgres_inner<'a>(
+        &self,
+        rt_handle: Handle,
+        mut connection: Connection<'a, Core>,
+    ) -> anyhow::Result<PostgresStorage<'a>> {
+        let l2_block_number = self.resolve_l2_block(&mut connection).await?;
+        tracing::debug!(point = ?self.point, %l2_block_number, "Using Postgres-based storage");
+        let storage = PostgresStorage::new_async(
+            rt_handle,
+            connection,
+            l2_block_number,
+            self.consider_new_l1_batch,
+        )
+        .await
+        .context("cannot create `PostgresStorage`")?;
+        Ok(match &self.caches {
+            Some(caches) => storage.with_caches(caches.clone()),
+            None => storage,
+        })
+    }
+
+    /// Returns RocksDB-based storage if `rocksdb` is exactly at the viewed L1 batch, and falls back
+    /// to Postgres-based storage otherwise. The caller must ensure that `rocksdb` is not updated concurrently
+    /// while the returned storage is used.
+    ///
+    /// # Errors
+    ///
+    /// Propagates Postgres errors.
+    pub async fn rocksdb_or_postgres<'a>(
+        &self,
+        rocksdb: RocksdbStorage,
+        pool: &'a ConnectionPool<Core>,
+    ) -> anyhow::Result<PgOrRocksdbStorage<'a>> {
+        if let HistoryPoint::L1Batch(l1_batch_number) = self.point {
+            // RocksDB always includes writes in its latest L1 batch.
+            if self.consider_new_l1_batch
+                && rocksdb.l1_batch_number().await == Some(l1_batch_number + 1)
+            {
+                tracing::debug!(%l1_batch_number, "Using RocksDB-based storage");
+                return Ok(rocksdb.into());
+            }
+        }
+        let connection = pool.connection().await?;
+        Ok(self.postgres(connection).await?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use tempfile::TempDir;
+    use tokio::sync::watch;
+
+    use super::*;
+    use crate::{
+        test_utils::{
+            create_l1_batch, create_l2_block, gen_storage_logs, prepare_postgres,
+            prepare_postgres_for_snapshot_recovery,
+        },
+        ReadStorage,
+    };
+
+    #[tokio::test]
+    async fn resolving_l2_block_for_l1_batch() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        prepare_postgres(&mut conn).await;
+        let logs = gen_storage_logs(20..40);
+        create_l2_block(&mut conn, L2BlockNumber(1), logs[..10].to_vec()).await;
+        create_l2_block(&mut conn, L2BlockNumber(2), logs[10..].to_vec()).await;
+        create_l1_batch(&mut conn, L1BatchNumber(1), &logs).await;
+
+        let view = StorageViewAt::l1_batch(L1BatchNumber(0));
+        let l2_block = view.resolve_l2_block(&mut conn).await.unwrap();
+        assert_eq!(l2_block, L2BlockNumber(0));
+        let view = StorageViewAt::l1_batch(L1BatchNumber(1));
+        let l2_block = view.resolve_l2_block(&mut conn).await.unwrap();
+        assert_eq!(l2_block, L2BlockNumber(2));
+        let view = StorageViewAt::l2_block(L2BlockNumber(1));
+        let l2_block = view.resolve_l2_block(&mut conn).await.unwrap();
+        assert_eq!(l2_block, L2BlockNumber(1));
+
+        let mut storage = StorageViewAt::l1_batch(L1BatchNumber(0))
+            .postgres(conn)
+            .await
+            .unwrap();
+        for log in &logs {
+            assert!(storage.read_value(&log.key).is_zero());
+        }
+
+        let err = StorageViewAt::l1_batch(L1BatchNumber(2))
+            .postgres(pool.connection().await.unwrap())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("snapshot"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn resolving_l2_block_after_snapshot_recovery() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let (snapshot_recovery, snapshot_logs) =
+            prepare_postgres_for_snapshot_recovery(&mut conn).await;
+
+        let view = StorageViewAt::l1_batch(snapshot_recovery.l1_batch_number);
+        let l2_block = view.resolve_l2_block(&mut conn).await.unwrap();
+        assert_eq!(l2_block, snapshot_recovery.l2_block_number);
+
+        let mut storage = view.postgres(conn).await.unwrap();
+        for log in &snapshot_logs {
+            assert_eq!(storage.read_value(&log.key), log.value);
+        }
+
+        let view = StorageViewAt::l1_batch(snapshot_recovery.l1_batch_number - 1);
+        let err = view
+            .resolve_l2_block(&mut pool.connection().await.unwrap())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Snapshot contains"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn choosing_between_rocksdb_and_postgres() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        prepare_postgres(&mut conn).await;
+        let logs = gen_storage_logs(20..40);
+        create_l2_block(&mut conn, L2BlockNumber(1), logs.clone()).await;
+        create_l1_batch(&mut conn, L1BatchNumber(1), &logs).await;
+
+        let dir = TempDir::new().unwrap();
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        let rocksdb = RocksdbStorage::builder(dir.path())
+            .await
+            .unwrap()
+            .synchronize(&mut conn, &stop_receiver, None)
+            .await
+            .unwrap()
+            .unwrap();
+        drop(conn);
+
+        let storage = StorageViewAt::l1_batch(L1BatchNumber(1))
+            .rocksdb_or_postgres(rocksdb.clone(), &pool)
+            .await
+            .unwrap();
+        assert_matches!(storage, PgOrRocksdbStorage::Rocksdb(_));
+
+        let storage = StorageViewAt::l1_batch(L1BatchNumber(1))
+            .consider_new_l1_batch(false)
+            .rocksdb_or_postgres(rocksdb.clone(), &pool)
+            .await
+            .unwrap();
+        assert_matches!(storage, PgOrRocksdbStorage::Postgres(_));
+
+        let mut storage = StorageViewAt::l1_batch(L1BatchNumber(0))
+            .rocksdb_or_postgres(rocksdb, &pool)
+            .await
+            .unwrap();
+        assert_matches!(storage, PgOrRocksdbStorage::Postgres(_));
+        for log in &logs {
+            assert!(storage.read_value(&log.key).is_zero());
+        }
+    }
+}
@@ -1,9 +1,11 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use strum::Display;
 use zksync_basic_types::{
     web3::{AccessList, Bytes, Index},
-    L1BatchNumber, H160, H2048, H256, H64, U256, U64,
+    L1BatchNumber, L1BlockNumber, L2ChainId, PriorityOpId, H160, H2048, H256, H64, U256, U64,
 };
 use zksync_contracts::BaseSystemContractsHashes;
 
@@ -11,7 +13,8 @@ pub use crate::transaction_request::{
     Eip712Meta, SerializationTransactionError, TransactionRequest,
 };
 use crate::{
-    protocol_version::L1VerifierConfig,
+    fee_model::{FeeParams, FeeParamsV1, FeeParamsV2},
+    protocol_version::{L1VerifierConfig, ProtocolSemanticVersion},
     vm_trace::{Call, CallType},
     Address, L2BlockNumber, ProtocolVersionId,
 };
@@ -188,6 +191,22 @@ pub struct L2ToL1LogProof {
     pub root: H256,
 }
 
+/// Proof that an L2->L1 message was sent by a specific chain, which can be used to verify the message
+/// on another chain in the same ecosystem. See [`crate::interop`] for verification helpers.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InteropMessageProof {
+    /// ID of the chain that has sent the message.
+    pub chain_id: L2ChainId,
+    /// Number of the L1 batch containing the message. The root of L2->L1 logs for this batch
+    /// is committed to on L1.
+    pub l1_batch_number: L1BatchNumber,
+    /// Index of the transaction sending the message in the L1 batch.
+    pub tx_number_in_batch: u16,
+    /// Merkle proof for the L2->L1 log corresponding to the message.
+    pub log_proof: L2ToL1LogProof,
+}
+
 /// A struct with the two default bridge contracts.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -578,6 +597,312 @@ pub struct TransactionDetails {
     pub eth_execute_tx_hash: Option<H256>,
 }
 
+/// Transaction rejected by the API server on submission. Rejections are only recorded if the rejected transactions journal
+/// is enabled on the server.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedTransaction {
+    pub hash: H256,
+    pub initiator_address: Address,
+    pub nonce: U256,
+    /// Machine-readable error code, e.g. `max-fee-per-gas-too-low`.
+    pub error_code: String,
+    /// Human-readable rejection reason.
+    pub reason: String,
+    pub rejected_at: DateTime<Utc>,
+}
+
+/// Entry of the operator audit log recording an administrative action (e.g., a change made via the `admin` namespace
+/// or a block revert).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorAuditLogEntry {
+    /// Sequential ID of the entry.
+    pub id: u64,
+    /// Operator who performed the action, e.g. an ID of an authenticated admin API user.
+    pub actor: String,
+    /// Machine-readable action name, e.g. `add_traced_addresses`.
+    pub action: String,
+    /// Action-specific details, such as its arguments.
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Summary of the L1 sender state returned by `admin_getEthSenderStatus`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EthSenderStatus {
+    /// Pending (neither confirmed nor failed) L1 transactions aggregated by the operation type.
+    pub pending: Vec<PendingEthTxsSummary>,
+    /// L1 transactions that were sent at least once, but are not confirmed yet, ordered by ID.
+    pub in_flight: Vec<InFlightEthTx>,
+    /// Nonce status for each operator key that has sent L1 transactions.
+    pub operator_nonces: Vec<OperatorNonceStatus>,
+    /// Most recently failed L1 transactions, latest first.
+    pub recent_failures: Vec<FailedEthTx>,
+}
+
+/// Pending L1 transactions of a certain operation type.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingEthTxsSummary {
+    /// Operation type, e.g. `CommitBlocks`.
+    pub tx_type: String,
+    /// Total number of pending transactions.
+    pub count: u64,
+    /// Number of pending transactions that were sent at least once.
+    pub sent_count: u64,
+    /// Creation time of the oldest pending transaction.
+    pub oldest_created_at: DateTime<Utc>,
+}
+
+/// L1 transaction awaiting confirmation, together with its latest send attempt.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InFlightEthTx {
+    pub id: u32,
+    pub nonce: u64,
+    pub tx_type: String,
+    /// Operator address if the transaction is sent by a custom operator (e.g., the blob operator);
+    /// `None` for the main operator.
+    pub from_addr: Option<Address>,
+    /// Number of send attempts (i.e., of distinct fee bids).
+    pub attempts: u64,
+    /// Hash of the latest attempt.
+    pub tx_hash: H256,
+    /// Base fee bid of the latest attempt.
+    pub base_fee_per_gas: u64,
+    /// Priority fee bid of the latest attempt.
+    pub priority_fee_per_gas: u64,
+    /// Blob base fee bid of the latest attempt; only set for blob transactions.
+    pub blob_base_fee_per_gas: Option<u64>,
+    /// L1 block at which the transaction was first sent.
+    pub first_sent_at_block: Option<u32>,
+    /// L1 block at which the latest attempt was sent.
+    pub last_sent_at_block: Option<u32>,
+}
+
+/// Nonce status of an operator key.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorNonceStatus {
+    /// Operator address; `None` for the main operator.
+    pub from_addr: Option<Address>,
+    /// Nonce to be used by the next L1 transaction created by the operator.
+    pub next_nonce: u64,
+    /// Nonce of the last confirmed L1 transaction of the operator.
+    pub last_confirmed_nonce: Option<u64>,
+}
+
+/// L1 transaction that has failed on L1.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedEthTx {
+    pub id: u32,
+    pub nonce: u64,
+    pub tx_type: String,
+    /// Operator address; `None` for the main operator.
+    pub from_addr: Option<Address>,
+    /// Revert reason reported by L1, if known.
+    pub failure_reason: Option<String>,
+    pub failed_at: DateTime<Utc>,
+}
+
+/// Client-specified deadline for including a transaction into an L2 block. If the transaction cannot be included
+/// before the deadline, it's dropped from the mempool and reported as expired instead of being executed late.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionDeadline {
+    /// Last L2 block the transaction can be included in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_after_l2_block: Option<L2BlockNumber>,
+    /// Latest timestamp (in seconds since UNIX epoch) of the L2 block the transaction can be included in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_after_timestamp: Option<u64>,
+}
+
+impl TransactionDeadline {
+    /// Checks whether the deadline doesn't have any constraints.
+    pub fn is_empty(&self) -> bool {
+        self.not_after_l2_block.is_none() && self.not_after_timestamp.is_none()
+    }
+
+    /// Checks whether a transaction can be included into an L2 block with the specified number and timestamp.
+    pub fn allows(&self, l2_block_number: L2BlockNumber, timestamp: u64) -> bool {
+        let block_fits = self
+            .not_after_l2_block
+            .map_or(true, |number| l2_block_number <= number);
+        let timestamp_fits = self
+            .not_after_timestamp
+            .map_or(true, |not_after| timestamp <= not_after);
+        block_fits && timestamp_fits
+    }
+}
+
+/// Status of a transaction submitted with an inclusion deadline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TransactionExpiryStatus {
+    /// Transaction is in the mempool, and its deadline hasn't passed yet.
+    Pending,
+    /// Transaction was included into an L2 block before its deadline.
+    Included,
+    /// Transaction was dropped from the mempool because its deadline has passed.
+    Expired,
+    /// Transaction was removed from the mempool for another reason (e.g., it was rejected by the state keeper
+    /// or replaced by another transaction).
+    Dropped,
+}
+
+/// Expiry information for a transaction submitted with an inclusion deadline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionExpiry {
+    pub hash: H256,
+    pub deadline: TransactionDeadline,
+    pub status: TransactionExpiryStatus,
+    /// Time when the transaction was dropped from the mempool because of the expired deadline.
+    pub expired_at: Option<DateTime<Utc>>,
+}
+
+/// Status of a transaction forwarded by an external node to the main node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ForwardedTransactionStatus {
+    /// The main node was unavailable when the transaction was submitted, so the transaction is queued
+    /// for forwarding.
+    Queued,
+    /// The transaction was accepted by the main node.
+    Forwarded,
+    /// The transaction was rejected by the main node after being queued.
+    Rejected,
+    /// The transaction was dropped from the forwarding queue after retries were exhausted.
+    Dropped,
+}
+
+/// Information about a transaction forwarded by an external node to the main node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForwardedTransaction {
+    pub hash: H256,
+    pub status: ForwardedTransactionStatus,
+    /// Number of attempts to forward the transaction to the main node.
+    pub attempts: u32,
+    /// Error encountered during the last failed forwarding attempt.
+    pub last_error: Option<String>,
+    pub received_at: DateTime<Utc>,
+    /// Time when the transaction was accepted by the main node.
+    pub forwarded_at: Option<DateTime<Utc>>,
+}
+
+/// Pending (i.e., not yet included into an L2 block) transaction of an account.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTransactionInfo {
+    pub hash: H256,
+    pub nonce: U256,
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+}
+
+/// Nonce information for an account taking pending transactions into account.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountNonceInfo {
+    /// Account nonce as of the latest sealed L2 block.
+    pub committed_nonce: U256,
+    /// Next nonce that can be used by a new transaction, i.e., the first nonce starting from the committed one
+    /// that is not occupied by a pending transaction. Same as the nonce returned by `eth_getTransactionCount`
+    /// for the pending block.
+    pub next_nonce: U256,
+    /// Pending transactions of the account ordered by nonce. A pending transaction can be replaced
+    /// by submitting a transaction with the same nonce and higher fees.
+    pub pending_transactions: Vec<PendingTransactionInfo>,
+}
+
+/// Processing status of an L1 priority operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PriorityOpStatus {
+    /// Operation is observed on L1, but is not included into an L2 block yet.
+    Pending,
+    /// Operation is included into an L2 block.
+    Included,
+    /// L1 batch with the operation is committed on L1.
+    Committed,
+    /// L1 batch with the operation is proven on L1.
+    Proven,
+    /// L1 batch with the operation is executed on L1.
+    Executed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityOpDetails {
+    pub serial_id: PriorityOpId,
+    pub tx_hash: H256,
+    pub sender: Address,
+    pub l1_block_number: Option<L1BlockNumber>,
+    pub received_at: DateTime<Utc>,
+    pub status: PriorityOpStatus,
+    pub l2_block_number: Option<L2BlockNumber>,
+    pub l1_batch_number: Option<L1BatchNumber>,
+    /// Error returned by the VM if the operation has failed on L2. Note that failed operations
+    /// are still considered processed by the priority queue.
+    pub error: Option<String>,
+    /// Zero-based position of the operation in the queue of pending operations. `None` for processed operations.
+    pub queue_position: Option<u64>,
+    /// Estimated time of inclusion into an L2 block based on recently processed operations.
+    /// `None` for processed operations, or if there are no recently processed operations.
+    pub estimated_inclusion_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityQueueInfo {
+    /// ID of the priority operation expected to be included next, i.e., the ID following the last included operation.
+    /// `None` if no priority operations were included yet (or the node was recovered from a snapshot).
+    pub next_expected_priority_op_id: Option<PriorityOpId>,
+    /// Total number of pending priority operations known to the node.
+    pub pending_ops_count: u64,
+    /// Time when the oldest pending priority operation was received by the node.
+    pub oldest_pending_op_received_at: Option<DateTime<Utc>>,
+    /// Average delay between receiving and including an operation, computed over recently processed operations.
+    pub average_inclusion_delay_ms: Option<u64>,
+    /// Whether the queue has a gap, i.e. the first pending operation doesn't immediately follow the last included one.
+    /// This usually means that some operations weren't observed by the node on L1, and can indicate a stuck queue.
+    pub has_gap: bool,
+    /// Pending operations ordered by their serial ID. May be truncated according to the requested limit.
+    pub pending_ops: Vec<PriorityOpDetails>,
+}
+
+/// Features of the chain that affect how clients should interact with it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainFeatures {
+    /// Whether the chain is a validium, i.e., doesn't publish pubdata to L1.
+    pub validium: bool,
+    /// Whether the chain uses a base token other than ETH.
+    pub custom_base_token: bool,
+    /// Whether the chain supports EVM bytecode emulation.
+    pub evm_emulator: bool,
+}
+
+/// Capabilities of the API server returned by `zks_getCapabilities`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiCapabilities {
+    /// Semantic version of the API. The major version is bumped on breaking changes.
+    pub api_version: String,
+    /// RPC namespaces enabled on the server, e.g. `eth` or `zks`.
+    pub namespaces: Vec<String>,
+    /// Names of all RPC methods supported by the server, sorted alphabetically.
+    pub methods: Vec<String>,
+    pub features: ChainFeatures,
+    /// Latest protocol version known to the server. `None` if the server has no protocol versions in storage.
+    pub protocol_version: Option<ProtocolSemanticVersion>,
+}
+
 #[derive(Debug, Clone)]
 pub struct GetLogsFilter {
     pub from_block: L2BlockNumber,
@@ -600,6 +925,15 @@ pub enum DebugCallType {
     Create,
 }
 
+/// Value transfer performed by a call or a contract deployment inside a transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InternalTransfer {
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DebugCall {
@@ -640,6 +974,39 @@ impl From<Call> for DebugCall {
     }
 }
 
+/// Verification key hashes used by the L1 verifier for a specific protocol version.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationKeysHashes {
+    /// Semantic protocol version (`0.<minor>.<patch>`).
+    pub protocol_version: ProtocolSemanticVersion,
+    /// Verifier configuration
+    pub verification_keys_hashes: L1VerifierConfig,
+}
+
+/// Entry of the protocol version history returned by `zks_getProtocolVersionHistory`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolVersionHistoryEntry {
+    /// Minor version of the protocol.
+    pub minor_version: u16,
+    /// Timestamp at which the upgrade should be performed.
+    pub timestamp: u64,
+    /// First L1 batch executed with this protocol version, or `None` if the version is not activated yet.
+    pub activation_l1_batch: Option<L1BatchNumber>,
+    /// First L2 block executed with this protocol version, or `None` if the version is not activated yet.
+    pub activation_l2_block: Option<L2BlockNumber>,
+    /// Bootloader code hash.
+    pub bootloader_code_hash: H256,
+    /// Default account code hash.
+    pub default_account_code_hash: H256,
+    /// Address of the L1 verifier contract as of this version, if known. The address is taken from
+    /// the latest upgrade proposal on L1 that has changed the verifier.
+    pub verifier_address: Option<Address>,
+    /// L2 upgrade transaction hash.
+    pub l2_system_upgrade_tx_hash: Option<H256>,
+}
+
 // TODO (PLA-965): remove deprecated fields from the struct. It is currently in a "migration" phase
 // to keep compatibility between old and new versions.
 #[derive(Default, Serialize, Deserialize, Clone, Debug)]
@@ -727,6 +1094,15 @@ pub enum SupportedTracers {
 #[serde(rename_all = "camelCase")]
 pub struct CallTracerConfig {
     pub only_top_call: bool,
+    /// Maximum number of call frames (including the top call) to return. Frames are taken in the depth-first order.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_frames: Option<usize>,
+    /// Number of direct subcalls of the top call to skip. Used together with `top_calls_limit` to paginate traces.
+    #[serde(default)]
+    pub top_calls_offset: usize,
+    /// Maximum number of direct subcalls of the top call to return.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_calls_limit: Option<usize>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -781,6 +1157,68 @@ pub struct L1BatchDetails {
     pub number: L1BatchNumber,
     #[serde(flatten)]
     pub base: BlockDetailsBase,
+    /// Custom key / value metadata attached to the batch by the operator.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom_metadata: BTreeMap<String, String>,
+}
+
+/// Fee parameters returned by `zks_getFeeParams`. Serialized in the same way as [`FeeParams`], with an additional
+/// field for each variant, so that it can be deserialized by the clients expecting [`FeeParams`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum ApiFeeParams {
+    V1(WithFeeRequirements<FeeParamsV1>),
+    V2(WithFeeRequirements<FeeParamsV2>),
+}
+
+impl ApiFeeParams {
+    pub fn new(params: FeeParams, required_gas_per_pubdata: Option<u64>) -> Self {
+        match params {
+            FeeParams::V1(params) => Self::V1(WithFeeRequirements {
+                params,
+                required_gas_per_pubdata,
+            }),
+            FeeParams::V2(params) => Self::V2(WithFeeRequirements {
+                params,
+                required_gas_per_pubdata,
+            }),
+        }
+    }
+
+    /// Returns the gas per pubdata currently required for transactions to be included into a batch.
+    pub fn required_gas_per_pubdata(&self) -> Option<u64> {
+        match self {
+            Self::V1(params) => params.required_gas_per_pubdata,
+            Self::V2(params) => params.required_gas_per_pubdata,
+        }
+    }
+}
+
+impl From<ApiFeeParams> for FeeParams {
+    fn from(params: ApiFeeParams) -> Self {
+        match params {
+            ApiFeeParams::V1(params) => Self::V1(params.params),
+            ApiFeeParams::V2(params) => Self::V2(params.params),
+        }
+    }
+}
+
+/// Fee parameters together with the live fee requirements derived from them.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WithFeeRequirements<T> {
+    #[serde(flatten)]
+    pub params: T,
+    /// Gas per pubdata currently required for transactions to be included into a batch. Transactions with
+    /// a lower `gas_per_pubdata_limit` are not rejected, but stay in the mempool until the pubdata price drops.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_gas_per_pubdata: Option<u64>,
+}
+
+/// Reference to either an L2 block or an L1 batch, e.g. `{ "l2Block": 123 }` or `{ "l1Batch": 45 }`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum L2BlockOrL1Batch {
+    L2Block(L2BlockNumber),
+    L1Batch(L1BatchNumber),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -815,6 +1253,31 @@ pub struct ApiStorageLog {
     pub written_value: U256,
 }
 
+/// Result of simulating the L2 part of an L1 -> L2 transaction (aka priority operation).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1ToL2ExecutionSimulation {
+    /// Whether the transaction succeeds. Failed priority operations are still included into a batch,
+    /// with the transferred value refunded.
+    pub success: bool,
+    /// Gas limit used for the simulation.
+    pub gas_limit: U256,
+    /// Max fee per gas used for the simulation.
+    pub max_fee_per_gas: U256,
+    /// Gas charged for the transaction, i.e. the gas limit minus the refunded gas.
+    pub gas_used: U256,
+    pub gas_refunded: U256,
+    pub refund_recipient: Address,
+    /// Amount of the base token refunded to [`Self::refund_recipient`]; includes the transferred value
+    /// if the transaction fails.
+    pub refund_amount: U256,
+    /// Returned data for successful transactions, or raw revert data for reverted ones.
+    pub output: Bytes,
+    /// Decoded revert reason for failed transactions.
+    pub revert_reason: Option<String>,
+    pub events: Vec<Log>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -848,4 +1311,45 @@ mod tests {
         serde_json::from_str::<OldProtocolVersion>(&serde_json::to_string(&new_version).unwrap())
             .unwrap();
     }
+
+    #[test]
+    fn api_fee_params_compatibility() {
+        let params = ApiFeeParams::new(FeeParams::sensible_v1_default(), Some(800));
+        let json = serde_json::to_value(params).unwrap();
+        assert_eq!(json["V1"]["required_gas_per_pubdata"], 800);
+        assert_eq!(json["V1"]["l1_gas_price"], 1_000_000_000);
+
+        let old_params: FeeParams = serde_json::from_value(json.clone()).unwrap();
+        assert!(matches!(old_params, FeeParams::V1(_)));
+        let params: ApiFeeParams = serde_json::from_value(json).unwrap();
+        assert_eq!(params.required_gas_per_pubdata(), Some(800));
+
+        let old_json = serde_json::to_value(FeeParams::sensible_v1_default()).unwrap();
+        let params: ApiFeeParams = serde_json::from_value(old_json).unwrap();
+        assert_eq!(params.required_gas_per_pubdata(), None);
+    }
+
+    #[test]
+    fn checking_transaction_deadline() {
+        let deadline = TransactionDeadline::default();
+        assert!(deadline.is_empty());
+        assert!(deadline.allows(L2BlockNumber(u32::MAX), u64::MAX));
+
+        let deadline: TransactionDeadline =
+            serde_json::from_str(r#"{ "notAfterL2Block": 10, "notAfterTimestamp": 1000 }"#)
+                .unwrap();
+        assert!(!deadline.is_empty());
+        assert!(deadline.allows(L2BlockNumber(10), 1000));
+        assert!(!deadline.allows(L2BlockNumber(11), 1000));
+        assert!(!deadline.allows(L2BlockNumber(10), 1001));
+
+        let deadline: TransactionDeadline =
+            serde_json::from_str(r#"{ "notAfterTimestamp": 1000 }"#).unwrap();
+        assert_eq!(deadline.not_after_l2_block, None);
+        assert!(deadline.allows(L2BlockNumber(u32::MAX), 1000));
+        assert_eq!(
+            serde_json::to_value(deadline).unwrap(),
+            serde_json::json!({ "notAfterTimestamp": 1000 })
+        );
+    }
 }
@@ -190,6 +190,73 @@ impl ops::AddAssign for BlockGasCount {
     }
 }
 
+/// Version of the algorithm used to hash L2 blocks.
+///
+/// A new variant should be added for each change of the hash format, together with the protocol version
+/// activating it in [`Self::activation_protocol_version()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum L2BlockHashVersion {
+    /// The hash is computed based only on the L2 block number.
+    Legacy,
+    /// The hash is computed based on the L2 block number, timestamp, previous L2 block hash
+    /// and the rolling hash of block transactions.
+    RollingTxHash,
+}
+
+impl L2BlockHashVersion {
+    /// All hash versions in the order of their activation.
+    const ALL: [Self; 2] = [Self::Legacy, Self::RollingTxHash];
+
+    /// Returns the first protocol version using this hash version.
+    pub fn activation_protocol_version(self) -> ProtocolVersionId {
+        match self {
+            Self::Legacy => ProtocolVersionId::Version0,
+            Self::RollingTxHash => ProtocolVersionId::Version13,
+        }
+    }
+
+    /// Returns the hash version used for L2 blocks with the specified protocol version.
+    pub fn for_protocol_version(protocol_version: ProtocolVersionId) -> Self {
+        Self::ALL
+            .into_iter()
+            .rev()
+            .find(|version| version.activation_protocol_version() <= protocol_version)
+            .unwrap_or(Self::Legacy)
+    }
+
+    /// Returns the hash version preceding this one, if any.
+    pub fn previous(self) -> Option<Self> {
+        let idx = Self::ALL.iter().position(|&version| version == self)?;
+        idx.checked_sub(1).map(|idx| Self::ALL[idx])
+    }
+}
+
+/// L2 block hashes computed by [`L2BlockHasher::finalize_for_migration()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct L2BlockHashes {
+    /// Hash version corresponding to the block protocol version.
+    pub version: L2BlockHashVersion,
+    /// Hash computed using [`Self::version`].
+    pub hash: H256,
+    /// Hash computed using the previous hash version. Only present for L2 blocks on the upgrade boundary,
+    /// i.e., having the protocol version activating [`Self::version`].
+    pub pre_upgrade_hash: Option<(L2BlockHashVersion, H256)>,
+}
+
+impl L2BlockHashes {
+    /// Checks whether the reference hash (e.g., received from the main node) matches one of the hash variants.
+    /// Returns the matching hash version, or `None` if there is no match.
+    pub fn verify(&self, reference_hash: H256) -> Option<L2BlockHashVersion> {
+        if reference_hash == self.hash {
+            return Some(self.version);
+        }
+        match self.pre_upgrade_hash {
+            Some((version, hash)) if hash == reference_hash => Some(version),
+            _ => None,
+        }
+    }
+}
+
 /// Hasher of L2 block contents used by the VM.
 #[derive(Debug)]
 pub struct L2BlockHasher {
@@ -238,15 +305,38 @@ impl L2BlockHasher {
     /// - If the L2 block has i transactions, then `txs_rolling_hash` is equal to `H(H_{i-1}, H(tx_i))`, where
     ///   `H_{i-1}` is the `txs_rolling_hash` of the first `i - 1` transactions.
     pub fn finalize(self, protocol_version: ProtocolVersionId) -> H256 {
-        if protocol_version >= ProtocolVersionId::Version13 {
-            let mut digest = [0_u8; 128];
-            U256::from(self.number.0).to_big_endian(&mut digest[0..32]);
-            U256::from(self.timestamp).to_big_endian(&mut digest[32..64]);
-            digest[64..96].copy_from_slice(self.prev_l2_block_hash.as_bytes());
-            digest[96..128].copy_from_slice(self.txs_rolling_hash.as_bytes());
-            H256(keccak256(&digest))
-        } else {
-            Self::legacy_hash(self.number)
+        self.finalize_with_version(L2BlockHashVersion::for_protocol_version(protocol_version))
+    }
+
+    /// Returns the hash of the L2 block computed using the specified hash version.
+    pub fn finalize_with_version(&self, version: L2BlockHashVersion) -> H256 {
+        match version {
+            L2BlockHashVersion::Legacy => Self::legacy_hash(self.number),
+            L2BlockHashVersion::RollingTxHash => {
+                let mut digest = [0_u8; 128];
+                U256::from(self.number.0).to_big_endian(&mut digest[0..32]);
+                U256::from(self.timestamp).to_big_endian(&mut digest[32..64]);
+                digest[64..96].copy_from_slice(self.prev_l2_block_hash.as_bytes());
+                digest[96..128].copy_from_slice(self.txs_rolling_hash.as_bytes());
+                H256(keccak256(&digest))
+            }
+        }
+    }
+
+    /// Returns hashes of the L2 block to be used when verifying hashes produced by other nodes. If the block
+    /// is on the hash version upgrade boundary, the hash computed using the previous hash version is retained
+    /// as well, since the block may have been hashed by a node not aware of the upgrade.
+    pub fn finalize_for_migration(self, protocol_version: ProtocolVersionId) -> L2BlockHashes {
+        let version = L2BlockHashVersion::for_protocol_version(protocol_version);
+        let is_upgrade_boundary = version.activation_protocol_version() == protocol_version;
+        let pre_upgrade_hash = version
+            .previous()
+            .filter(|_| is_upgrade_boundary)
+            .map(|prev_version| (prev_version, self.finalize_with_version(prev_version)));
+        L2BlockHashes {
+            version,
+            hash: self.finalize_with_version(version),
+            pre_upgrade_hash,
         }
     }
 
@@ -327,6 +417,57 @@ mod tests {
         )
     }
 
+    #[test]
+    fn l2_block_hash_versions() {
+        assert_eq!(
+            L2BlockHashVersion::for_protocol_version(ProtocolVersionId::Version12),
+            L2BlockHashVersion::Legacy
+        );
+        assert_eq!(
+            L2BlockHashVersion::for_protocol_version(ProtocolVersionId::Version13),
+            L2BlockHashVersion::RollingTxHash
+        );
+        assert_eq!(
+            L2BlockHashVersion::for_protocol_version(ProtocolVersionId::latest()),
+            L2BlockHashVersion::RollingTxHash
+        );
+        assert_eq!(L2BlockHashVersion::Legacy.previous(), None);
+        assert_eq!(
+            L2BlockHashVersion::RollingTxHash.previous(),
+            Some(L2BlockHashVersion::Legacy)
+        );
+    }
+
+    #[test]
+    fn l2_block_hashes_on_upgrade_boundary() {
+        let hasher = || L2BlockHasher::new(L2BlockNumber(5), 12, H256::repeat_byte(1));
+        let legacy_hash = L2BlockHasher::legacy_hash(L2BlockNumber(5));
+        let new_hash = hasher().finalize(ProtocolVersionId::latest());
+        assert_ne!(legacy_hash, new_hash);
+
+        let hashes = hasher().finalize_for_migration(ProtocolVersionId::Version12);
+        assert_eq!(hashes.hash, legacy_hash);
+        assert_eq!(hashes.pre_upgrade_hash, None);
+
+        let hashes = hasher().finalize_for_migration(ProtocolVersionId::Version13);
+        assert_eq!(hashes.hash, new_hash);
+        assert_eq!(
+            hashes.pre_upgrade_hash,
+            Some((L2BlockHashVersion::Legacy, legacy_hash))
+        );
+        assert_eq!(
+            hashes.verify(new_hash),
+            Some(L2BlockHashVersion::RollingTxHash)
+        );
+        assert_eq!(hashes.verify(legacy_hash), Some(L2BlockHashVersion::Legacy));
+        assert_eq!(hashes.verify(H256::zero()), None);
+
+        let hashes = hasher().finalize_for_migration(ProtocolVersionId::latest());
+        assert_eq!(hashes.hash, new_hash);
+        assert_eq!(hashes.pre_upgrade_hash, None);
+        assert_eq!(hashes.verify(legacy_hash), None);
+    }
+
     #[test]
     fn test_block_packing() {
         let block_number = 101;
@@ -27,7 +27,7 @@ use crate::{
     },
     web3::keccak256,
     writes::{
-        compress_state_diffs, InitialStorageWrite, RepeatedStorageWrite, StateDiffRecord,
+        state_diff_compression, InitialStorageWrite, RepeatedStorageWrite, StateDiffRecord,
         PADDED_ENCODED_STORAGE_DIFF_LEN_BYTES,
     },
     ProtocolVersionId, H256,
@@ -355,7 +355,8 @@ impl L1BatchAuxiliaryOutput {
 
                 let state_diffs_packed = serialize_commitments(&state_diffs);
                 let state_diffs_hash = H256::from(keccak256(&(state_diffs_packed)));
-                let state_diffs_compressed = compress_state_diffs(state_diffs);
+                let state_diffs_compressed =
+                    state_diff_compression(common_input.protocol_version).compress(state_diffs);
 
                 let blob_linear_hashes =
                     parse_system_logs_for_blob_hashes(&common_input.protocol_version, &system_logs);
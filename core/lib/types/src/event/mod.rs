@@ -4,7 +4,7 @@ use itertools::Itertools;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use zksync_basic_types::ethabi::Token;
-use zksync_system_constants::EVENT_WRITER_ADDRESS;
+use zksync_system_constants::{EVENT_WRITER_ADDRESS, L2_BASE_TOKEN_ADDRESS};
 use zksync_utils::{
     address_to_u256, h256_to_account_address, h256_to_u256, u256_to_bytes_be, u256_to_h256,
 };
@@ -174,6 +174,41 @@ static PUBLISHED_BYTECODE_SIGNATURE: Lazy<H256> = Lazy::new(|| {
     )
 });
 
+static BASE_TOKEN_WITHDRAWAL_SIGNATURE: Lazy<H256> = Lazy::new(|| {
+    ethabi::long_signature(
+        "Withdrawal",
+        &[
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Uint(256),
+        ],
+    )
+});
+
+static BASE_TOKEN_WITHDRAWAL_WITH_MESSAGE_SIGNATURE: Lazy<H256> = Lazy::new(|| {
+    ethabi::long_signature(
+        "WithdrawalWithMessage",
+        &[
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Uint(256),
+            ethabi::ParamType::Bytes,
+        ],
+    )
+});
+
+static BRIDGE_WITHDRAWAL_INITIATED_SIGNATURE: Lazy<H256> = Lazy::new(|| {
+    ethabi::long_signature(
+        "WithdrawalInitiated",
+        &[
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Uint(256),
+        ],
+    )
+});
+
 // moved from Runtime Context
 pub fn extract_added_tokens(
     l2_shared_bridge_addr: Address,
@@ -362,6 +397,40 @@ pub fn extract_published_bytecodes(all_generated_events: &[VmEvent]) -> Vec<H256
         .collect()
 }
 
+/// Extracts withdrawals initiated by the provided events as `(token, amount)` pairs.
+///
+/// Base token withdrawals are reported with `L2_BASE_TOKEN_ADDRESS` as the token; ERC20 withdrawals
+/// are only taken into account if they are emitted by one of `l2_bridge_addresses`, and are reported
+/// with the L2 token address.
+pub fn extract_withdrawals(
+    l2_bridge_addresses: &[Address],
+    all_generated_events: &[VmEvent],
+) -> Vec<(Address, U256)> {
+    all_generated_events
+        .iter()
+        .filter_map(|event| {
+            let signature = *event.indexed_topics.first()?;
+            let is_base_token_withdrawal = event.address == L2_BASE_TOKEN_ADDRESS
+                && event.indexed_topics.len() == 3
+                && (signature == *BASE_TOKEN_WITHDRAWAL_SIGNATURE
+                    || signature == *BASE_TOKEN_WITHDRAWAL_WITH_MESSAGE_SIGNATURE);
+            let is_bridge_withdrawal = l2_bridge_addresses.contains(&event.address)
+                && event.indexed_topics.len() == 4
+                && signature == *BRIDGE_WITHDRAWAL_INITIATED_SIGNATURE;
+
+            // In both cases, the withdrawn amount is the first non-indexed event parameter.
+            let amount = event.value.get(..32).map(U256::from_big_endian)?;
+            if is_base_token_withdrawal {
+                Some((L2_BASE_TOKEN_ADDRESS, amount))
+            } else if is_bridge_withdrawal {
+                Some((h256_to_account_address(&event.indexed_topics[3]), amount))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct VmEventGroupKey {
     pub address: Address,
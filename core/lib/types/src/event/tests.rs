@@ -148,6 +148,72 @@ fn test_extract_bytecode_publication_requests_from_l1_messenger() {
     assert_eq!(expected, logs);
 }
 
+fn create_withdrawal_vm_event(
+    from: Address,
+    signature: H256,
+    token: Option<Address>,
+    amount: U256,
+) -> VmEvent {
+    let address_topic = |address: Address| u256_to_h256(address_to_u256(&address));
+    let mut indexed_topics = vec![
+        signature,
+        address_topic(Address::repeat_byte(1)),
+        address_topic(Address::repeat_byte(2)),
+    ];
+    indexed_topics.extend(token.map(address_topic));
+
+    VmEvent {
+        location: (L1BatchNumber(1), 0u32),
+        address: from,
+        indexed_topics,
+        value: ethabi::encode(&[Token::Uint(amount)]),
+    }
+}
+
+#[test]
+fn test_extract_withdrawals() {
+    let bridge = Address::repeat_byte(0xb);
+    let token = Address::repeat_byte(0xc);
+    let events = vec![
+        create_withdrawal_vm_event(
+            L2_BASE_TOKEN_ADDRESS,
+            *BASE_TOKEN_WITHDRAWAL_SIGNATURE,
+            None,
+            U256::from(100),
+        ),
+        create_withdrawal_vm_event(
+            bridge,
+            *BRIDGE_WITHDRAWAL_INITIATED_SIGNATURE,
+            Some(token),
+            U256::from(200),
+        ),
+        // Events from unknown contracts must be ignored.
+        create_withdrawal_vm_event(
+            Address::repeat_byte(0xd),
+            *BRIDGE_WITHDRAWAL_INITIATED_SIGNATURE,
+            Some(token),
+            U256::from(300),
+        ),
+        create_withdrawal_vm_event(
+            bridge,
+            *BASE_TOKEN_WITHDRAWAL_SIGNATURE,
+            None,
+            U256::from(400),
+        ),
+        create_bytecode_publication_vm_event(L1_MESSENGER_ADDRESS, U256::from(1337)),
+    ];
+
+    let withdrawals = extract_withdrawals(&[bridge], &events);
+
+    assert_eq!(
+        withdrawals,
+        [
+            (L2_BASE_TOKEN_ADDRESS, U256::from(100)),
+            (token, U256::from(200))
+        ]
+    );
+}
+
 #[test]
 fn test_convert_vm_events_to_log_queries() {
     let cases: Vec<serde_json::Value> = vec![
@@ -257,4 +257,33 @@ impl FeeParams {
             l1_gas_price: 1_000_000_000,
         })
     }
+
+    /// Returns the L1 gas price used by these params.
+    pub fn l1_gas_price(&self) -> u64 {
+        match self {
+            Self::V1(params) => params.l1_gas_price,
+            Self::V2(params) => params.l1_gas_price,
+        }
+    }
+}
+
+/// Exact inputs of the fee model used to compute a [`BatchFeeInput`]: fee model params and scale factors
+/// applied to L1 prices.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchFeeInputParams {
+    pub fee_params: FeeParams,
+    pub l1_gas_price_scale_factor: f64,
+    pub l1_pubdata_price_scale_factor: f64,
+}
+
+impl BatchFeeInputParams {
+    /// Creates params without scaling of L1 prices.
+    pub fn unscaled(fee_params: FeeParams) -> Self {
+        Self {
+            fee_params,
+            l1_gas_price_scale_factor: 1.0,
+            l1_pubdata_price_scale_factor: 1.0,
+        }
+    }
 }
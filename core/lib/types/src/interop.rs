@@ -0,0 +1,181 @@
+//! Primitives for verifying L2->L1 messages sent by other chains in the same ecosystem.
+//!
+//! Each chain commits to the Merkle root of L2->L1 logs for every L1 batch it executes on L1. A message sent
+//! by a chain via the L1 messenger can thus be proven to another chain by providing a Merkle path
+//! from the corresponding log to this root; the root itself must be obtained from a trusted source
+//! (e.g., the L1 diamond proxy of the sending chain).
+
+use zksync_system_constants::L1_MESSENGER_ADDRESS;
+use zksync_utils::address_to_h256;
+
+use crate::{
+    api::InteropMessageProof, l2_to_l1_log::L2ToL1Log, web3::keccak256, Address, L2ChainId, H256,
+};
+
+/// Errors that can occur when verifying an [`InteropMessageProof`].
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum InteropVerificationError {
+    #[error(
+        "proof is produced for chain {}, while chain {} was expected",
+        actual.as_u64(),
+        expected.as_u64()
+    )]
+    ChainMismatch {
+        expected: L2ChainId,
+        actual: L2ChainId,
+    },
+    #[error("Merkle path has {path_len} elements, which is insufficient for leaf index {index}")]
+    InvalidLeafIndex { index: u32, path_len: usize },
+    #[error(
+        "root computed from the proof ({computed:?}) differs from the expected root ({expected:?})"
+    )]
+    RootMismatch { computed: H256, expected: H256 },
+}
+
+/// Message sent from a chain to L1 via the L1 messenger system contract.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InteropMessage {
+    /// L2 address that has sent the message.
+    pub sender: Address,
+    /// Keccak-256 hash of the message contents.
+    pub message_hash: H256,
+}
+
+impl InteropMessage {
+    pub fn new(sender: Address, message: &[u8]) -> Self {
+        Self {
+            sender,
+            message_hash: H256(keccak256(message)),
+        }
+    }
+
+    /// Returns the L2->L1 log emitted by the L1 messenger for this message.
+    pub fn to_l2_to_l1_log(&self, tx_number_in_batch: u16) -> L2ToL1Log {
+        L2ToL1Log {
+            shard_id: 0,
+            is_service: true,
+            tx_number_in_block: tx_number_in_batch,
+            sender: L1_MESSENGER_ADDRESS,
+            key: address_to_h256(&self.sender),
+            value: self.message_hash,
+        }
+    }
+}
+
+/// Computes the Merkle root of L2->L1 logs from the log leaf, its index and the Merkle path to it.
+/// Returns `None` if the path is too short for the index.
+pub fn compute_l2_to_l1_logs_root(log: &L2ToL1Log, index: u32, path: &[H256]) -> Option<H256> {
+    if path.len() < 32 && index >> path.len() != 0 {
+        return None;
+    }
+
+    let mut hash = H256(keccak256(&log.to_bytes()));
+    for (level, sibling) in path.iter().enumerate() {
+        let mut buffer = [0_u8; 64];
+        if (index >> level) & 1 == 0 {
+            buffer[..32].copy_from_slice(hash.as_bytes());
+            buffer[32..].copy_from_slice(sibling.as_bytes());
+        } else {
+            buffer[..32].copy_from_slice(sibling.as_bytes());
+            buffer[32..].copy_from_slice(hash.as_bytes());
+        }
+        hash = H256(keccak256(&buffer));
+    }
+    Some(hash)
+}
+
+impl InteropMessageProof {
+    /// Verifies that `message` was sent by the chain `expected_chain_id` in the L1 batch with the specified
+    /// root of L2->L1 logs. The root must come from a trusted source; the root included into the proof is not trusted.
+    pub fn verify(
+        &self,
+        message: &InteropMessage,
+        expected_chain_id: L2ChainId,
+        expected_root: H256,
+    ) -> Result<(), InteropVerificationError> {
+        if self.chain_id != expected_chain_id {
+            return Err(InteropVerificationError::ChainMismatch {
+                expected: expected_chain_id,
+                actual: self.chain_id,
+            });
+        }
+
+        let log = message.to_l2_to_l1_log(self.tx_number_in_batch);
+        let index = self.log_proof.id;
+        let path = &self.log_proof.proof;
+        let computed = compute_l2_to_l1_logs_root(&log, index, path).ok_or(
+            InteropVerificationError::InvalidLeafIndex {
+                index,
+                path_len: path.len(),
+            },
+        )?;
+        if computed != expected_root {
+            return Err(InteropVerificationError::RootMismatch {
+                computed,
+                expected: expected_root,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_mini_merkle_tree::MiniMerkleTree;
+
+    use super::*;
+    use crate::{api::L2ToL1LogProof, L1BatchNumber};
+
+    fn create_proof(message: &InteropMessage) -> InteropMessageProof {
+        let other_log = L2ToL1Log {
+            tx_number_in_block: 1,
+            ..L2ToL1Log::default()
+        };
+        let logs = [
+            other_log.clone(),
+            other_log.clone(),
+            message.to_l2_to_l1_log(3),
+            other_log,
+        ];
+        let tree = MiniMerkleTree::new(logs.iter().map(L2ToL1Log::to_bytes), Some(16));
+        let (root, proof) = tree.merkle_root_and_path(2);
+        InteropMessageProof {
+            chain_id: L2ChainId::default(),
+            l1_batch_number: L1BatchNumber(1),
+            tx_number_in_batch: 3,
+            log_proof: L2ToL1LogProof { proof, id: 2, root },
+        }
+    }
+
+    #[test]
+    fn verifying_message_proof() {
+        let message = InteropMessage::new(Address::repeat_byte(1), b"hello");
+        let proof = create_proof(&message);
+        let root = proof.log_proof.root;
+        proof.verify(&message, L2ChainId::default(), root).unwrap();
+
+        let err = proof
+            .verify(&message, L2ChainId::from(271), root)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            InteropVerificationError::ChainMismatch { .. }
+        ));
+
+        let other_message = InteropMessage::new(Address::repeat_byte(1), b"bye");
+        let err = proof
+            .verify(&other_message, L2ChainId::default(), root)
+            .unwrap_err();
+        assert!(matches!(err, InteropVerificationError::RootMismatch { .. }));
+
+        let mut invalid_proof = proof.clone();
+        invalid_proof.log_proof.id = 1 << 10;
+        let err = invalid_proof
+            .verify(&message, L2ChainId::default(), root)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            InteropVerificationError::InvalidLeafIndex { .. }
+        ));
+    }
+}
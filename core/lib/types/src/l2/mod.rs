@@ -106,6 +106,8 @@ impl L2TxCommonData {
         self.input = Some(InputData { hash, data: input })
     }
 
+    /// Extracts the chain ID the transaction was signed for from its raw bytes. Returns `None` if the transaction
+    /// has no input data, or if it is a legacy transaction without replay protection (i.e., pre-EIP-155).
     pub fn extract_chain_id(&self) -> Option<u64> {
         let bytes = self.input_data()?;
         let chain_id = match bytes.first() {
@@ -114,7 +116,7 @@ impl L2TxCommonData {
                 let v = rlp.val_at(6).ok()?;
                 PackedEthSignature::unpack_v(v).ok()?.1?
             }
-            Some(x) if *x == EIP_1559_TX_TYPE => {
+            Some(x) if *x == EIP_1559_TX_TYPE || *x == EIP_2930_TX_TYPE => {
                 let rlp = Rlp::new(&bytes[1..]);
                 rlp.val_at(0).ok()?
             }
@@ -41,6 +41,7 @@ pub mod debug_flat_call;
 pub mod event;
 pub mod fee;
 pub mod fee_model;
+pub mod interop;
 pub mod l1;
 pub mod l2;
 pub mod l2_to_l1_log;
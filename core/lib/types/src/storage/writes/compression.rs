@@ -185,6 +185,52 @@ pub fn compress_with_best_strategy(prev_value: U256, new_value: U256) -> Vec<u8>
         })
 }
 
+/// Value update decoded from the extended compression produced by [`compress_with_best_strategy()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressedValueUpdate {
+    /// New value is the previous value plus the diff (wrapping on overflow).
+    Add(U256),
+    /// New value is the previous value minus the diff (wrapping on underflow).
+    Sub(U256),
+    /// New value is specified directly.
+    Transform(U256),
+}
+
+impl CompressedValueUpdate {
+    /// Applies this update to the previous value.
+    pub fn apply(self, prev_value: U256) -> U256 {
+        match self {
+            Self::Add(diff) => prev_value.overflowing_add(diff).0,
+            Self::Sub(diff) => prev_value.overflowing_sub(diff).0,
+            Self::Transform(new_value) => new_value,
+        }
+    }
+}
+
+/// Decodes a value compressed with [`compress_with_best_strategy()`] from the start of `data`. Returns the update
+/// together with the number of consumed bytes, or `None` if the data is malformed.
+///
+/// This mirrors value decoding in the `Compressor` system contract: the metadata byte contains the value length
+/// and the operation ID, except for the uncompressed operation (ID 0), which always has a full 32-byte value.
+pub fn decompress_value(data: &[u8]) -> Option<(CompressedValueUpdate, usize)> {
+    let (&metadata, data) = data.split_first()?;
+    let operation_id = (metadata & 7) as usize;
+    let len = (metadata >> 3) as usize;
+    let len = match operation_id {
+        0 if len == 0 => 32,
+        // Compressors never produce values of 31+ bytes; see `get_diff_and_size()` implementations.
+        1..=3 if len < 31 => len,
+        _ => return None,
+    };
+    let value = U256::from_big_endian(data.get(..len)?);
+    let update = match operation_id {
+        1 => CompressedValueUpdate::Add(value),
+        2 => CompressedValueUpdate::Sub(value),
+        _ => CompressedValueUpdate::Transform(value),
+    };
+    Some((update, len + 1))
+}
+
 #[cfg(test)]
 mod tests {
     use std::ops::{Add, BitAnd, Shr, Sub};
@@ -350,6 +396,44 @@ mod tests {
         assert!((((compressed_val.bits() as f64) / 8f64).ceil() as usize) == 1);
     }
 
+    #[test]
+    fn decompressing_values() {
+        let values = [
+            U256::zero(),
+            U256::one(),
+            U256::from(255),
+            U256::from(256),
+            U256::from(u64::MAX),
+            U256::one() << 240,
+            (U256::one() << 248) - 1,
+            U256::MAX / 2,
+            U256::MAX - 1,
+            U256::MAX,
+        ];
+        for &prev_value in &values {
+            for &new_value in &values {
+                let compressed = compress_with_best_strategy(prev_value, new_value);
+                let (update, len) = decompress_value(&compressed).unwrap();
+                assert_eq!(len, compressed.len());
+                assert_eq!(
+                    update.apply(prev_value),
+                    new_value,
+                    "{prev_value} -> {new_value}"
+                );
+            }
+        }
+
+        assert_eq!(decompress_value(&[]), None);
+        // Uncompressed value must have 32 bytes.
+        assert_eq!(decompress_value(&[0; 32]), None);
+        // Unknown operation.
+        assert_eq!(decompress_value(&[4, 0]), None);
+        // Value is too long.
+        assert_eq!(decompress_value(&[(31 << 3) | 1; 32]), None);
+        // Truncated value.
+        assert_eq!(decompress_value(&[(2 << 3) | 1, 0]), None);
+    }
+
     fn verify_add_is_none(initial_val: U256, final_val: U256) {
         let compression_add_strategy = CompressionByteAdd {
             prev_value: initial_val,
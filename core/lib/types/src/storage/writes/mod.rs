@@ -4,9 +4,14 @@ use serde::{de, ser::SerializeTuple, Deserialize, Deserializer, Serialize, Seria
 use zksync_basic_types::{Address, U256};
 
 pub(crate) use self::compression::{compress_with_best_strategy, COMPRESSION_VERSION_NUMBER};
+pub use self::state_diff_compression::{
+    state_diff_compression, CompressedStateDiff, CompressedStateDiffKey, StateDiffCompression,
+    StateDiffCompressionV1, StateDiffCompressionV2, StateDiffDecompressionError,
+};
 use crate::H256;
 
 pub mod compression;
+mod state_diff_compression;
 
 /// The number of bytes being used for state diff enumeration indices. Applicable to repeated writes.
 pub const BYTES_PER_ENUMERATION_INDEX: u8 = 4;
@@ -150,42 +155,11 @@ impl StateDiffRecord {
 
 /// Compresses a vector of state diff records according to the following:
 /// num_initial writes (u32) || compressed initial writes || compressed repeated writes
-pub fn compress_state_diffs(mut state_diffs: Vec<StateDiffRecord>) -> Vec<u8> {
-    let mut res = vec![];
-
-    // IMPORTANT: Sorting here is determined by the order expected in the circuits.
-    state_diffs.sort_by_key(|rec| (rec.address, rec.key));
-
-    let (initial_writes, repeated_writes): (Vec<_>, Vec<_>) = state_diffs
-        .iter()
-        .partition(|rec| rec.enumeration_index == 0);
-
-    res.extend((initial_writes.len() as u16).to_be_bytes());
-    for state_diff in initial_writes {
-        res.extend(state_diff.compress());
-    }
-
-    for state_diff in repeated_writes {
-        res.extend(state_diff.compress());
-    }
-
-    prepend_header(res)
-}
-
-/// Adds the header to the beginning of the compressed state diffs so it can be used as part of the overall
-/// pubdata. Need to prepend: compression version || number of compressed state diffs || number of bytes used for
-/// enumeration index.
-fn prepend_header(compressed_state_diffs: Vec<u8>) -> Vec<u8> {
-    let mut res = vec![0u8; 5];
-    res[0] = COMPRESSION_VERSION_NUMBER;
-
-    res[1..4].copy_from_slice(&(compressed_state_diffs.len() as u32).to_be_bytes()[1..4]);
-
-    res[4] = BYTES_PER_ENUMERATION_INDEX;
-
-    res.extend(compressed_state_diffs);
-
-    res.to_vec()
+///
+/// This uses the original compression strategy ([`StateDiffCompressionV1`]); use [`state_diff_compression()`]
+/// to select the strategy based on the protocol version.
+pub fn compress_state_diffs(state_diffs: Vec<StateDiffRecord>) -> Vec<u8> {
+    StateDiffCompressionV1.compress(state_diffs)
 }
 
 /// Struct for storing tree writes in DB.
@@ -0,0 +1,571 @@
+//! Versioned compression of state diffs published as a part of pubdata.
+
+use std::fmt;
+
+use zksync_basic_types::U256;
+
+use super::{
+    compress_with_best_strategy,
+    compression::{decompress_value, CompressedValueUpdate},
+    StateDiffRecord, BYTES_PER_DERIVED_KEY, BYTES_PER_ENUMERATION_INDEX,
+    COMPRESSION_VERSION_NUMBER,
+};
+use crate::ProtocolVersionId;
+
+/// Length of the header prepended to compressed state diffs: compression version (1 byte) || length
+/// of compressed state diffs (3 bytes) || number of bytes used for enumeration indices (1 byte).
+const HEADER_LEN: usize = 5;
+
+/// Error decompressing state diffs.
+#[derive(Debug, thiserror::Error)]
+pub enum StateDiffDecompressionError {
+    #[error("compressed state diffs are truncated")]
+    Truncated,
+    #[error("unexpected compression version: {0}")]
+    UnexpectedVersion(u8),
+    #[error("unexpected number of bytes per enumeration index: {0}")]
+    UnexpectedEnumerationIndexSize(u8),
+    #[error("length in the header ({header}) doesn't match the length of compressed state diffs ({actual})")]
+    LengthMismatch { header: usize, actual: usize },
+    #[error("malformed compressed value at offset {0}")]
+    MalformedValue(usize),
+    #[error("malformed enumeration index at offset {0}")]
+    MalformedEnumerationIndex(usize),
+}
+
+/// Key of a decompressed state diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressedStateDiffKey {
+    /// Derived key of an initial write.
+    Initial([u8; 32]),
+    /// Enumeration index of a repeated write.
+    Repeated(u64),
+}
+
+/// State diff as recovered from compressed pubdata. Since the previous value is not published, the final value
+/// is represented as an update that should be applied to the previous value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressedStateDiff {
+    pub key: CompressedStateDiffKey,
+    pub update: CompressedValueUpdate,
+}
+
+impl CompressedStateDiff {
+    /// Checks whether this diff corresponds to the provided record.
+    pub fn matches(&self, record: &StateDiffRecord) -> bool {
+        let key_matches = match self.key {
+            CompressedStateDiffKey::Initial(derived_key) => {
+                record.enumeration_index == 0 && record.derived_key == derived_key
+            }
+            CompressedStateDiffKey::Repeated(index) => record.enumeration_index == index,
+        };
+        key_matches && self.update.apply(record.initial_value) == record.final_value
+    }
+}
+
+/// Strategy of compressing state diffs. Compressed state diffs are verified by the `Compressor` system contract
+/// and the circuits, so a strategy may only be used starting from the protocol version supporting it.
+pub trait StateDiffCompression: fmt::Debug + Send + Sync {
+    /// Version of the compression format written into the header of compressed state diffs.
+    fn version(&self) -> u8;
+
+    /// Compresses state diffs and prepends the header.
+    fn compress(&self, state_diffs: Vec<StateDiffRecord>) -> Vec<u8>;
+
+    /// Decompresses state diffs produced by [`Self::compress()`], in the order they are published.
+    fn decompress(
+        &self,
+        compressed: &[u8],
+    ) -> Result<Vec<CompressedStateDiff>, StateDiffDecompressionError>;
+}
+
+/// Returns the state diff compression strategy used for L1 batches with the specified protocol version.
+pub fn state_diff_compression(
+    protocol_version: ProtocolVersionId,
+) -> &'static dyn StateDiffCompression {
+    // `StateDiffCompressionV2` requires support in the `Compressor` system contract and the circuits, which
+    // is not deployed for any protocol version yet.
+    let _ = protocol_version;
+    &StateDiffCompressionV1
+}
+
+/// Original compression strategy:
+///
+/// - Initial writes sorted by `(address, key)`, each encoded as derived key (32 bytes) || compressed value
+/// - Repeated writes sorted by `(address, key)`, each encoded as enumeration index (4 bytes) || compressed value
+#[derive(Debug, Clone, Copy)]
+pub struct StateDiffCompressionV1;
+
+impl StateDiffCompression for StateDiffCompressionV1 {
+    fn version(&self) -> u8 {
+        COMPRESSION_VERSION_NUMBER
+    }
+
+    fn compress(&self, mut state_diffs: Vec<StateDiffRecord>) -> Vec<u8> {
+        let mut res = vec![];
+
+        // IMPORTANT: Sorting here is determined by the order expected in the circuits.
+        state_diffs.sort_by_key(|rec| (rec.address, rec.key));
+
+        let (initial_writes, repeated_writes): (Vec<_>, Vec<_>) = state_diffs
+            .iter()
+            .partition(|rec| rec.enumeration_index == 0);
+
+        res.extend((initial_writes.len() as u16).to_be_bytes());
+        for state_diff in initial_writes {
+            res.extend(state_diff.compress());
+        }
+
+        for state_diff in repeated_writes {
+            res.extend(state_diff.compress());
+        }
+
+        prepend_header(self.version(), BYTES_PER_ENUMERATION_INDEX, res)
+    }
+
+    fn decompress(
+        &self,
+        compressed: &[u8],
+    ) -> Result<Vec<CompressedStateDiff>, StateDiffDecompressionError> {
+        let mut reader = Reader::new(compressed, self.version(), BYTES_PER_ENUMERATION_INDEX)?;
+        let mut diffs = reader.read_initial_writes()?;
+        while !reader.is_empty() {
+            let offset = reader.offset;
+            let index_bytes = reader.read_bytes(BYTES_PER_ENUMERATION_INDEX.into())?;
+            let index = u32::from_be_bytes(index_bytes.try_into().unwrap());
+            if index == 0 {
+                return Err(StateDiffDecompressionError::MalformedEnumerationIndex(
+                    offset,
+                ));
+            }
+            diffs.push(CompressedStateDiff {
+                key: CompressedStateDiffKey::Repeated(index.into()),
+                update: reader.read_value()?,
+            });
+        }
+        Ok(diffs)
+    }
+}
+
+/// Improved compression strategy. Differs from [`StateDiffCompressionV1`] in the encoding of repeated writes:
+/// they are clustered by sorting by the enumeration index, and each index is encoded as an unsigned LEB128 varint
+/// of the delta from the previous index (or from 0 for the first write). For a batch touching many slots,
+/// deltas are small, so an index takes 1-2 bytes instead of 4. The header contains 0 as the number of bytes
+/// per enumeration index to signal that indices are variable-length.
+#[derive(Debug, Clone, Copy)]
+pub struct StateDiffCompressionV2;
+
+impl StateDiffCompressionV2 {
+    const VARINT_ENUMERATION_INDEX: u8 = 0;
+}
+
+impl StateDiffCompression for StateDiffCompressionV2 {
+    fn version(&self) -> u8 {
+        2
+    }
+
+    fn compress(&self, mut state_diffs: Vec<StateDiffRecord>) -> Vec<u8> {
+        let mut res = vec![];
+
+        state_diffs.sort_by_key(|rec| (rec.address, rec.key));
+        let (initial_writes, mut repeated_writes): (Vec<_>, Vec<_>) = state_diffs
+            .iter()
+            .partition(|rec| rec.enumeration_index == 0);
+
+        res.extend((initial_writes.len() as u16).to_be_bytes());
+        for state_diff in initial_writes {
+            res.extend(state_diff.compress());
+        }
+
+        repeated_writes.sort_by_key(|rec| rec.enumeration_index);
+        let mut prev_index = 0;
+        for state_diff in repeated_writes {
+            write_varint(&mut res, state_diff.enumeration_index - prev_index);
+            prev_index = state_diff.enumeration_index;
+            res.extend(compress_with_best_strategy(
+                state_diff.initial_value,
+                state_diff.final_value,
+            ));
+        }
+
+        prepend_header(self.version(), Self::VARINT_ENUMERATION_INDEX, res)
+    }
+
+    fn decompress(
+        &self,
+        compressed: &[u8],
+    ) -> Result<Vec<CompressedStateDiff>, StateDiffDecompressionError> {
+        let mut reader = Reader::new(compressed, self.version(), Self::VARINT_ENUMERATION_INDEX)?;
+        let mut diffs = reader.read_initial_writes()?;
+        let mut prev_index = 0_u64;
+        while !reader.is_empty() {
+            let offset = reader.offset;
+            let delta = reader.read_varint()?;
+            // Indices are strictly increasing, so the delta cannot be 0.
+            let index = prev_index.checked_add(delta).filter(|_| delta > 0).ok_or(
+                StateDiffDecompressionError::MalformedEnumerationIndex(offset),
+            )?;
+            prev_index = index;
+            diffs.push(CompressedStateDiff {
+                key: CompressedStateDiffKey::Repeated(index),
+                update: reader.read_value()?,
+            });
+        }
+        Ok(diffs)
+    }
+}
+
+/// Adds the header to the beginning of the compressed state diffs so it can be used as part of the overall
+/// pubdata. Need to prepend: compression version || number of compressed state diffs || number of bytes used for
+/// enumeration index.
+fn prepend_header(
+    version: u8,
+    enumeration_index_size: u8,
+    compressed_state_diffs: Vec<u8>,
+) -> Vec<u8> {
+    let mut res = vec![0u8; HEADER_LEN];
+    res[0] = version;
+
+    res[1..4].copy_from_slice(&(compressed_state_diffs.len() as u32).to_be_bytes()[1..4]);
+
+    res[4] = enumeration_index_size;
+
+    res.extend(compressed_state_diffs);
+
+    res
+}
+
+fn write_varint(buffer: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buffer.push(byte);
+            return;
+        }
+        buffer.push(byte | 0x80);
+    }
+}
+
+/// Reader for compressed state diffs. Performs the same checks as the `Compressor` system contract.
+#[derive(Debug)]
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(
+        compressed: &'a [u8],
+        expected_version: u8,
+        expected_enumeration_index_size: u8,
+    ) -> Result<Self, StateDiffDecompressionError> {
+        if compressed.len() < HEADER_LEN {
+            return Err(StateDiffDecompressionError::Truncated);
+        }
+        let (header, data) = compressed.split_at(HEADER_LEN);
+        if header[0] != expected_version {
+            return Err(StateDiffDecompressionError::UnexpectedVersion(header[0]));
+        }
+        let len = u32::from_be_bytes([0, header[1], header[2], header[3]]) as usize;
+        if len != data.len() {
+            return Err(StateDiffDecompressionError::LengthMismatch {
+                header: len,
+                actual: data.len(),
+            });
+        }
+        if header[4] != expected_enumeration_index_size {
+            return Err(StateDiffDecompressionError::UnexpectedEnumerationIndexSize(
+                header[4],
+            ));
+        }
+        Ok(Self { data, offset: 0 })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.offset == self.data.len()
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], StateDiffDecompressionError> {
+        let bytes = self
+            .data
+            .get(self.offset..self.offset + len)
+            .ok_or(StateDiffDecompressionError::Truncated)?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn read_value(&mut self) -> Result<CompressedValueUpdate, StateDiffDecompressionError> {
+        let (update, len) = decompress_value(&self.data[self.offset..])
+            .ok_or(StateDiffDecompressionError::MalformedValue(self.offset))?;
+        self.offset += len;
+        Ok(update)
+    }
+
+    fn read_varint(&mut self) -> Result<u64, StateDiffDecompressionError> {
+        let start_offset = self.offset;
+        let mut value = 0_u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_bytes(1)?[0];
+            let chunk = u64::from(byte & 0x7f);
+            if shift == 63 && chunk > 1 {
+                break; // overflow
+            }
+            value |= chunk << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(StateDiffDecompressionError::MalformedEnumerationIndex(
+            start_offset,
+        ))
+    }
+
+    fn read_initial_writes(
+        &mut self,
+    ) -> Result<Vec<CompressedStateDiff>, StateDiffDecompressionError> {
+        let count = self.read_bytes(2)?;
+        let count = u16::from_be_bytes([count[0], count[1]]);
+        (0..count)
+            .map(|_| {
+                let derived_key = self.read_bytes(BYTES_PER_DERIVED_KEY.into())?;
+                Ok(CompressedStateDiff {
+                    key: CompressedStateDiffKey::Initial(derived_key.try_into().unwrap()),
+                    update: self.read_value()?,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+    use super::*;
+    use crate::Address;
+
+    const STRATEGIES: [&dyn StateDiffCompression; 2] =
+        [&StateDiffCompressionV1, &StateDiffCompressionV2];
+
+    fn interesting_values() -> [U256; 8] {
+        [
+            U256::zero(),
+            U256::one(),
+            U256::from(64),
+            U256::from(u32::MAX),
+            U256::one() << 200,
+            U256::MAX / 2,
+            U256::MAX - 1,
+            U256::MAX,
+        ]
+    }
+
+    fn mock_state_diffs(rng: &mut impl Rng, count: usize) -> Vec<StateDiffRecord> {
+        let values = interesting_values();
+        let mut next_index = 1;
+        (0..count)
+            .map(|_| {
+                let is_initial = rng.gen_bool(0.3);
+                let enumeration_index = if is_initial {
+                    0
+                } else {
+                    // Mix dense and sparse enumeration indices.
+                    next_index += if rng.gen_bool(0.8) {
+                        rng.gen_range(1..4)
+                    } else {
+                        rng.gen_range(1..u64::from(u32::MAX) / 1_000)
+                    };
+                    next_index
+                };
+                let initial_value = if is_initial {
+                    U256::zero()
+                } else if rng.gen() {
+                    *values.choose(rng).unwrap()
+                } else {
+                    U256(rng.gen())
+                };
+                let final_value = if rng.gen() {
+                    *values.choose(rng).unwrap()
+                } else {
+                    U256(rng.gen())
+                };
+                StateDiffRecord {
+                    address: Address::repeat_byte(rng.gen_range(0..8)),
+                    key: U256(rng.gen()),
+                    derived_key: rng.gen(),
+                    enumeration_index,
+                    initial_value,
+                    final_value,
+                }
+            })
+            .collect()
+    }
+
+    fn assert_roundtrip(strategy: &dyn StateDiffCompression, state_diffs: &[StateDiffRecord]) {
+        let compressed = strategy.compress(state_diffs.to_vec());
+        let decompressed = strategy.decompress(&compressed).unwrap();
+        assert_eq!(decompressed.len(), state_diffs.len(), "{strategy:?}");
+        for record in state_diffs {
+            let matching_diffs = decompressed.iter().filter(|diff| diff.matches(record));
+            assert_eq!(matching_diffs.count(), 1, "{strategy:?}: {record:?}");
+        }
+    }
+
+    #[test]
+    fn v1_matches_legacy_compression() {
+        let mut rng = StdRng::seed_from_u64(123);
+        let state_diffs = mock_state_diffs(&mut rng, 100);
+        assert_eq!(
+            StateDiffCompressionV1.compress(state_diffs.clone()),
+            super::super::compress_state_diffs(state_diffs)
+        );
+    }
+
+    #[test]
+    fn roundtrip_for_all_value_transitions() {
+        let values = interesting_values();
+        let mut state_diffs = vec![];
+        let mut next_index = 1;
+        for (i, &initial_value) in values.iter().enumerate() {
+            for (j, &final_value) in values.iter().enumerate() {
+                let key = U256::from(i * values.len() + j);
+                state_diffs.push(StateDiffRecord {
+                    address: Address::repeat_byte(1),
+                    key,
+                    derived_key: [0; 32],
+                    enumeration_index: next_index,
+                    initial_value,
+                    final_value,
+                });
+                next_index += 1;
+                if initial_value.is_zero() {
+                    let mut derived_key = [0xff; 32];
+                    key.to_big_endian(&mut derived_key);
+                    state_diffs.push(StateDiffRecord {
+                        address: Address::repeat_byte(2),
+                        key,
+                        derived_key,
+                        enumeration_index: 0,
+                        initial_value,
+                        final_value,
+                    });
+                }
+            }
+        }
+
+        for strategy in STRATEGIES {
+            assert_roundtrip(strategy, &state_diffs);
+        }
+    }
+
+    #[test]
+    fn roundtrip_for_random_state_diffs() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for count in [0, 1, 2, 10, 100, 1_000] {
+            let state_diffs = mock_state_diffs(&mut rng, count);
+            for strategy in STRATEGIES {
+                assert_roundtrip(strategy, &state_diffs);
+            }
+        }
+    }
+
+    #[test]
+    fn v2_compresses_dense_repeated_writes_better() {
+        let state_diffs: Vec<_> = (1..=1_000_u64)
+            .map(|index| StateDiffRecord {
+                address: Address::repeat_byte(1),
+                key: U256::from(1_000 - index),
+                derived_key: [0; 32],
+                enumeration_index: index,
+                initial_value: U256::from(index),
+                final_value: U256::from(index + 1),
+            })
+            .collect();
+        let v1_len = StateDiffCompressionV1.compress(state_diffs.clone()).len();
+        let v2_len = StateDiffCompressionV2.compress(state_diffs).len();
+        // Each index takes 1 byte instead of 4.
+        assert_eq!(v1_len - v2_len, 3_000);
+    }
+
+    #[test]
+    fn varint_roundtrip() {
+        for value in [
+            0,
+            1,
+            127,
+            128,
+            300,
+            u64::from(u32::MAX),
+            u64::MAX - 1,
+            u64::MAX,
+        ] {
+            let mut buffer = vec![];
+            write_varint(&mut buffer, value);
+            let mut reader = Reader {
+                data: &buffer,
+                offset: 0,
+            };
+            assert_eq!(reader.read_varint().unwrap(), value);
+            assert!(reader.is_empty());
+        }
+
+        // 11 bytes with the continuation bit set
+        let overlong = [0xff; 11];
+        let mut reader = Reader {
+            data: &overlong,
+            offset: 0,
+        };
+        reader.read_varint().unwrap_err();
+    }
+
+    #[test]
+    fn decompression_errors() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let state_diffs = mock_state_diffs(&mut rng, 10);
+        for strategy in STRATEGIES {
+            let compressed = strategy.compress(state_diffs.clone());
+
+            let err = strategy.decompress(&compressed[..3]).unwrap_err();
+            assert!(
+                matches!(err, StateDiffDecompressionError::Truncated),
+                "{err}"
+            );
+
+            let err = strategy
+                .decompress(&compressed[..compressed.len() - 1])
+                .unwrap_err();
+            assert!(
+                matches!(err, StateDiffDecompressionError::LengthMismatch { .. }),
+                "{err}"
+            );
+
+            let mut invalid = compressed.clone();
+            invalid[0] = 0xff;
+            let err = strategy.decompress(&invalid).unwrap_err();
+            assert!(
+                matches!(err, StateDiffDecompressionError::UnexpectedVersion(0xff)),
+                "{err}"
+            );
+
+            let mut invalid = compressed;
+            invalid[4] = 0xff;
+            let err = strategy.decompress(&invalid).unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    StateDiffDecompressionError::UnexpectedEnumerationIndexSize(0xff)
+                ),
+                "{err}"
+            );
+        }
+
+        let err = StateDiffCompressionV1
+            .decompress(&[1, 0, 0, 0, 4])
+            .unwrap_err();
+        assert!(
+            matches!(err, StateDiffDecompressionError::Truncated),
+            "{err}"
+        );
+    }
+}
@@ -729,7 +729,10 @@ impl TransactionRequest {
                 .chain_id
                 .ok_or(SerializationTransactionError::WrongChainId(None))?;
             Ok(PackedEthSignature::typed_data_to_signed_bytes(
-                &Eip712Domain::new(L2ChainId::try_from(chain_id).unwrap()),
+                &Eip712Domain::new(
+                    L2ChainId::try_from(chain_id)
+                        .map_err(|_| SerializationTransactionError::WrongChainId(Some(chain_id)))?,
+                ),
                 self,
             ))
         } else {
@@ -1170,6 +1173,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn eip712_tx_with_oversized_chain_id_is_rejected() {
+        let private_key = K256PrivateKey::random();
+        let transaction_request = TransactionRequest {
+            nonce: U256::from(1u32),
+            to: Some(Address::random()),
+            from: Some(private_key.address()),
+            gas_price: U256::from(11u32),
+            max_priority_fee_per_gas: Some(U256::from(0u32)),
+            gas: U256::from(12u32),
+            transaction_type: Some(U64::from(EIP_712_TX_TYPE)),
+            eip712_meta: Some(Eip712Meta {
+                gas_per_pubdata: U256::from(4u32),
+                factory_deps: None,
+                custom_signature: Some(vec![]),
+                paymaster_params: None,
+            }),
+            chain_id: Some(u64::MAX),
+            ..Default::default()
+        };
+        // The signature is irrelevant since the chain ID is checked before signature recovery.
+        let domain = Eip712Domain::new(L2ChainId::from(270));
+        let signature =
+            PackedEthSignature::sign_typed_data(&private_key, &domain, &transaction_request)
+                .unwrap();
+        let encoded_tx = transaction_request.get_signed_bytes(&signature).unwrap();
+
+        let decoded_tx = TransactionRequest::from_bytes(&encoded_tx, L2ChainId::from(270));
+        assert_eq!(
+            decoded_tx,
+            Err(SerializationTransactionError::WrongChainId(Some(u64::MAX)))
+        );
+    }
+
     #[test]
     fn check_recovered_public_key_eip1559() {
         let private_key = K256PrivateKey::random();
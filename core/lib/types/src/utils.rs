@@ -90,6 +90,22 @@ pub fn storage_key_for_eth_balance(address: &Address) -> StorageKey {
     storage_key_for_standard_token_balance(AccountTreeId::new(L2_BASE_TOKEN_ADDRESS), address)
 }
 
+/// Create a storage key to access the total supply of the base token. The total supply is stored
+/// in the slot directly following the `balance` mapping of the `L2BaseToken` system contract.
+pub fn storage_key_for_base_token_total_supply() -> StorageKey {
+    StorageKey::new(
+        AccountTreeId::new(L2_BASE_TOKEN_ADDRESS),
+        H256::from_low_u64_be(1),
+    )
+}
+
+/// Create a storage key to access the total supply of a token implemented by the default ERC20 contract
+/// (i.e., a token bridged via the shared bridge). The total supply is stored after the `balanceOf` (slot 51)
+/// and `allowance` (slot 52) mappings.
+pub fn storage_key_for_standard_token_total_supply(token_contract: AccountTreeId) -> StorageKey {
+    StorageKey::new(token_contract, H256::from_low_u64_be(53))
+}
+
 /// Pre-calculated the address of the to-be-deployed contract (via CREATE, not CREATE2).
 pub fn deployed_address_create(sender: Address, deploy_nonce: U256) -> Address {
     let prefix_bytes = keccak256("zksyncCreate".as_bytes());
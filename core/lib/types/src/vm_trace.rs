@@ -8,7 +8,7 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use zksync_system_constants::BOOTLOADER_ADDRESS;
 use zksync_utils::u256_to_h256;
 
-use crate::{zk_evm_types::FarCallOpcode, Address, U256};
+use crate::{api::InternalTransfer, zk_evm_types::FarCallOpcode, Address, U256};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum VmTrace {
@@ -290,6 +290,31 @@ impl Call {
             calls,
         }
     }
+
+    /// Returns value transfers performed by the subcalls of this call, in the execution order.
+    /// Transfers in reverted calls (including transfers in subcalls of reverted calls) and delegate calls are skipped.
+    pub fn internal_transfers(&self) -> Vec<InternalTransfer> {
+        let mut transfers = vec![];
+        let mut stack: Vec<_> = self.calls.iter().rev().collect();
+        while let Some(call) = stack.pop() {
+            if call.error.is_some() || call.revert_reason.is_some() {
+                continue;
+            }
+            let transfers_value = matches!(
+                call.r#type,
+                CallType::Call(FarCallOpcode::Normal | FarCallOpcode::Mimic) | CallType::Create
+            );
+            if transfers_value && !call.value.is_zero() {
+                transfers.push(InternalTransfer {
+                    from: call.from,
+                    to: call.to,
+                    value: call.value,
+                });
+            }
+            stack.extend(call.calls.iter().rev());
+        }
+        transfers
+    }
 }
 
 impl PartialEq for Call {
@@ -432,4 +457,49 @@ mod tests {
         let b = hex::decode("00000000002a000000000000003078303030303030303030303030303030303030303030303030303030303030303030303030303030302a00000000000000307830303030303030303030303030303030303030303030303030303030303030303030303038303031fa59060000000000fa5906000000000057ed0300000000000f00000000000000307833386437656134633638303030000000000000000000000000000000000000010000000000000000000000002a000000000000003078303030303030303030303030303030303030303030303030303030303030303030303030383030312a00000000000000307830303030303030303030303030303030303030303030303030303030303030303030303038303062bae978fb00000000da058bf700000000510f0000000000000300000000000000307830040000000000000030e5ccbd000000000000000000000000000000000000").unwrap();
         let _: Call = bincode::deserialize(&b).unwrap();
     }
+
+    #[test]
+    fn extracting_internal_transfers() {
+        let transfer = |from: u8, to: u8, value: u64, calls: Vec<Call>| Call {
+            from: Address::repeat_byte(from),
+            to: Address::repeat_byte(to),
+            value: value.into(),
+            calls,
+            ..Call::default()
+        };
+        let delegate_call = Call {
+            r#type: CallType::Call(FarCallOpcode::Delegate),
+            ..transfer(2, 5, 100, vec![])
+        };
+        let reverted_call = Call {
+            revert_reason: Some("oops".to_owned()),
+            ..transfer(2, 6, 100, vec![transfer(6, 7, 10, vec![])])
+        };
+        let calls = vec![
+            transfer(
+                1,
+                2,
+                0,
+                vec![transfer(2, 3, 50, vec![transfer(3, 4, 20, vec![])])],
+            ),
+            delegate_call,
+            reverted_call,
+            transfer(1, 8, 5, vec![]),
+        ];
+        let call = Call::new_high_level(100, 50, 70.into(), vec![], vec![], None, calls);
+
+        let transfers: Vec<_> = call
+            .internal_transfers()
+            .into_iter()
+            .map(|transfer| (transfer.from, transfer.to, transfer.value.as_u64()))
+            .collect();
+        assert_eq!(
+            transfers,
+            [
+                (Address::repeat_byte(2), Address::repeat_byte(3), 50),
+                (Address::repeat_byte(3), Address::repeat_byte(4), 20),
+                (Address::repeat_byte(1), Address::repeat_byte(8), 5),
+            ]
+        );
+    }
 }
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, convert::TryInto};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    convert::TryInto,
+};
 
 use itertools::Itertools;
 use zksync_basic_types::{
@@ -30,6 +34,10 @@ pub enum FailedToCompressBytecodeError {
 }
 
 /// Implements, a simple compression algorithm for the bytecode.
+///
+/// The dictionary is embedded into the compressed bytecode. The `Compressor` system contract verifies compressed bytecodes
+/// and L1 reconstructs them from pubdata alone, so dictionaries cannot be shared across bytecodes or batches
+/// without a protocol upgrade. See [`SharedBytecodeDictionary`] for measuring savings from a shared dictionary.
 pub fn compress_bytecode(code: &[u8]) -> Result<Vec<u8>, FailedToCompressBytecodeError> {
     validate_bytecode(code)?;
 
@@ -132,6 +140,141 @@ impl CompressedBytecodeInfo {
     }
 }
 
+/// Dictionary of 8-byte bytecode chunks shared across bytecodes, e.g. ones deployed in different L1 batches.
+///
+/// Chunks are added to the dictionary based on the number of learned bytecodes they occur in, so the dictionary
+/// captures frequently deployed bytecode patterns (proxies, standard tokens etc.). Newly learned bytecodes only
+/// affect compression after the dictionary is [rebuilt](Self::rebuild()).
+#[derive(Debug, Clone)]
+pub struct SharedBytecodeDictionary {
+    capacity: usize,
+    /// Number of learned bytecodes each chunk occurs in.
+    chunk_frequencies: HashMap<u64, u64>,
+    /// Dictionary chunks mapped to their indices.
+    chunks: HashMap<u64, u16>,
+}
+
+impl SharedBytecodeDictionary {
+    /// Multiplier for the capacity of the dictionary determining the number of tracked chunk frequencies.
+    const TRACKED_CHUNKS_MULTIPLIER: usize = 4;
+
+    /// Creates an empty dictionary with the specified maximum number of chunks.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.min(u16::MAX.into()),
+            chunk_frequencies: HashMap::new(),
+            chunks: HashMap::new(),
+        }
+    }
+
+    /// Returns the number of chunks in the dictionary.
+    pub fn len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Checks whether the dictionary is empty.
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Learns chunks from the provided bytecode.
+    pub fn learn(&mut self, code: &[u8]) {
+        let unique_chunks: HashSet<_> = code
+            .chunks_exact(8)
+            .map(|chunk| u64::from_be_bytes(chunk.try_into().unwrap()))
+            .collect();
+        for chunk in unique_chunks {
+            *self.chunk_frequencies.entry(chunk).or_default() += 1;
+        }
+    }
+
+    /// Rebuilds the dictionary from the learned chunks, so that it contains the most frequent ones.
+    /// Chunks occurring in a single bytecode are not added to the dictionary since they cannot be reused.
+    pub fn rebuild(&mut self) {
+        let mut frequencies: Vec<_> = self
+            .chunk_frequencies
+            .iter()
+            .map(|(&chunk, &frequency)| (frequency, chunk))
+            .collect();
+        // Sort by decreasing frequency; chunks are used as a tiebreaker to make the order deterministic.
+        frequencies.sort_unstable_by_key(|&entry| Reverse(entry));
+
+        // Bound the memory used for tracking frequencies.
+        frequencies.truncate(self.capacity * Self::TRACKED_CHUNKS_MULTIPLIER);
+        self.chunk_frequencies = frequencies
+            .iter()
+            .map(|&(frequency, chunk)| (chunk, frequency))
+            .collect();
+
+        self.chunks = frequencies
+            .into_iter()
+            .take_while(|&(frequency, _)| frequency > 1)
+            .take(self.capacity)
+            .enumerate()
+            .map(|(i, (_, chunk))| (chunk, i as u16))
+            .collect();
+    }
+
+    /// Compresses the bytecode using this dictionary. The compressed bytecode has the same format as produced
+    /// by [`compress_bytecode()`], except that indices less than the dictionary length refer to the chunks
+    /// of this dictionary, and only the remaining chunks are embedded.
+    pub fn compress(&self, code: &[u8]) -> Result<Vec<u8>, FailedToCompressBytecodeError> {
+        validate_bytecode(code)?;
+
+        let shared_len = self.chunks.len();
+        let mut embedded_chunks = vec![];
+        let mut embedded_indices = HashMap::new();
+        let mut encoded_data = Vec::with_capacity(code.len() / 4);
+        for chunk_bytes in code.chunks_exact(8) {
+            let chunk = u64::from_be_bytes(chunk_bytes.try_into().unwrap());
+            let index = if let Some(&index) = self.chunks.get(&chunk) {
+                usize::from(index)
+            } else {
+                *embedded_indices.entry(chunk).or_insert_with(|| {
+                    embedded_chunks.push(chunk);
+                    shared_len + embedded_chunks.len() - 1
+                })
+            };
+            let index = u16::try_from(index)
+                .map_err(|_| FailedToCompressBytecodeError::DictionaryOverflow)?;
+            encoded_data.extend(index.to_be_bytes());
+        }
+
+        let mut compressed = Vec::with_capacity(2 + embedded_chunks.len() * 8 + encoded_data.len());
+        compressed.extend((embedded_chunks.len() as u16).to_be_bytes());
+        for chunk in embedded_chunks {
+            compressed.extend(chunk.to_be_bytes());
+        }
+        compressed.extend(encoded_data);
+        Ok(compressed)
+    }
+
+    /// Decompresses a bytecode compressed with [`Self::compress()`] using the same dictionary.
+    pub fn decompress(&self, compressed: &[u8]) -> Option<Vec<u8>> {
+        let mut chunks_by_index = vec![0_u64; self.chunks.len()];
+        for (&chunk, &index) in &self.chunks {
+            chunks_by_index[usize::from(index)] = chunk;
+        }
+
+        let embedded_len = usize::from(u16::from_be_bytes(compressed.get(..2)?.try_into().ok()?));
+        let embedded_end = 2 + embedded_len * 8;
+        let embedded_chunks = compressed.get(2..embedded_end)?.chunks_exact(8);
+        chunks_by_index
+            .extend(embedded_chunks.map(|chunk| u64::from_be_bytes(chunk.try_into().unwrap())));
+
+        let encoded_data = &compressed[embedded_end..];
+        if encoded_data.len() % 2 != 0 {
+            return None;
+        }
+        let mut decompressed = Vec::with_capacity(encoded_data.len() * 4);
+        for index_bytes in encoded_data.chunks_exact(2) {
+            let index = u16::from_be_bytes(index_bytes.try_into().unwrap());
+            decompressed.extend(chunks_by_index.get(usize::from(index))?.to_be_bytes());
+        }
+        Some(decompressed)
+    }
+}
+
 pub fn validate_bytecode(code: &[u8]) -> Result<(), InvalidBytecodeError> {
     let bytecode_len = code.len();
 
@@ -171,6 +314,31 @@ pub fn bytecode_len_in_bytes(bytecodehash: H256) -> usize {
     bytecode_len_in_words(&bytecodehash) as usize * 32
 }
 
+/// Version byte of hashes of EVM bytecodes deployed via the EVM emulator.
+const EVM_BYTECODE_HASH_VERSION: u8 = 2;
+
+/// Checks whether the specified bytecode hash corresponds to an EVM bytecode (as opposed to an EraVM one).
+pub fn is_evm_bytecode_hash(bytecode_hash: &H256) -> bool {
+    bytecode_hash[0] == EVM_BYTECODE_HASH_VERSION
+}
+
+/// Trims EVM bytecode stored in factory deps (i.e., padded to an odd number of 32-byte words)
+/// to its original length, which is encoded in the bytecode hash.
+pub fn trim_padded_evm_bytecode(bytecode_hash: &H256, raw: &[u8]) -> anyhow::Result<&[u8]> {
+    anyhow::ensure!(
+        is_evm_bytecode_hash(bytecode_hash),
+        "bytecode hash {bytecode_hash:?} does not correspond to an EVM bytecode"
+    );
+    // For EVM bytecodes, the length is specified in bytes rather than in words.
+    let len = usize::from(bytecode_len_in_words(bytecode_hash));
+    anyhow::ensure!(
+        len <= raw.len(),
+        "EVM bytecode length {len} encoded in hash {bytecode_hash:?} exceeds the stored bytecode length {}",
+        raw.len()
+    );
+    Ok(&raw[..len])
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -223,4 +391,86 @@ mod test {
 
         assert_eq!(expected_encoding, compress_bytecode(&example_code).unwrap());
     }
+
+    /// Builds a valid bytecode consisting of `prefix` followed by `unique_words` distinct 32-byte words.
+    fn test_bytecode(prefix: &[u8], seed: u8, unique_words: usize) -> Vec<u8> {
+        let mut code = prefix.to_vec();
+        for i in 0..unique_words {
+            let mut word = [seed; 32];
+            word[24..].copy_from_slice(&(i as u64).to_be_bytes());
+            code.extend_from_slice(&word);
+        }
+        if (code.len() / 32) % 2 == 0 {
+            code.extend_from_slice(&[0xff; 32]);
+        }
+        code
+    }
+
+    #[test]
+    fn shared_dictionary_improves_compression_ratio() {
+        // Emulates a proxy / standard token code shared by deployed bytecodes.
+        let common_code: Vec<u8> = (0..=255_u8).flat_map(|i| [i; 8]).collect();
+        let first_bytecode = test_bytecode(&common_code, 1, 4);
+        let second_bytecode = test_bytecode(&common_code, 2, 4);
+        let new_bytecode = test_bytecode(&common_code, 3, 4);
+
+        let mut dictionary = SharedBytecodeDictionary::new(1_000);
+        // An empty dictionary doesn't affect compression.
+        let compressed_without_dictionary = compress_bytecode(&new_bytecode).unwrap();
+        let compressed = dictionary.compress(&new_bytecode).unwrap();
+        assert_eq!(compressed.len(), compressed_without_dictionary.len());
+
+        dictionary.learn(&first_bytecode);
+        dictionary.learn(&second_bytecode);
+        // Learned chunks are not used until the dictionary is rebuilt.
+        assert!(dictionary.is_empty());
+        dictionary.rebuild();
+        assert!(dictionary.len() >= 256, "{}", dictionary.len());
+
+        let compressed = dictionary.compress(&new_bytecode).unwrap();
+        let ratio_without_dictionary =
+            new_bytecode.len() as f64 / compressed_without_dictionary.len() as f64;
+        let ratio_with_dictionary = new_bytecode.len() as f64 / compressed.len() as f64;
+        assert!(
+            ratio_with_dictionary > ratio_without_dictionary * 2.0,
+            "{ratio_with_dictionary} vs {ratio_without_dictionary}"
+        );
+        assert_eq!(dictionary.decompress(&compressed).unwrap(), new_bytecode);
+    }
+
+    #[test]
+    fn shared_dictionary_ignores_unique_chunks() {
+        let bytecode = test_bytecode(&[], 1, 5);
+        let mut dictionary = SharedBytecodeDictionary::new(1_000);
+        dictionary.learn(&bytecode);
+        dictionary.rebuild();
+        assert!(dictionary.is_empty());
+
+        let compressed = dictionary.compress(&bytecode).unwrap();
+        assert_eq!(
+            compressed.len(),
+            compress_bytecode(&bytecode).unwrap().len()
+        );
+        assert_eq!(dictionary.decompress(&compressed).unwrap(), bytecode);
+    }
+
+    #[test]
+    fn trimming_padded_evm_bytecode() {
+        let evm_bytecode = [0x60, 0x80, 0x60, 0x40, 0x52];
+        let mut padded_bytecode = evm_bytecode.to_vec();
+        padded_bytecode.resize(32, 0);
+        let mut bytecode_hash = H256::repeat_byte(0x11);
+        bytecode_hash.0[..4].copy_from_slice(&[2, 0, 0, evm_bytecode.len() as u8]);
+
+        assert!(is_evm_bytecode_hash(&bytecode_hash));
+        let trimmed = trim_padded_evm_bytecode(&bytecode_hash, &padded_bytecode).unwrap();
+        assert_eq!(trimmed, evm_bytecode);
+
+        bytecode_hash.0[3] = 33;
+        trim_padded_evm_bytecode(&bytecode_hash, &padded_bytecode).unwrap_err();
+
+        let era_vm_hash = hash_bytecode(&[0; 32]);
+        assert!(!is_evm_bytecode_hash(&era_vm_hash));
+        trim_padded_evm_bytecode(&era_vm_hash, &padded_bytecode).unwrap_err();
+    }
 }
@@ -27,6 +27,15 @@ pub fn bigdecimal_to_u256(value: BigDecimal) -> U256 {
     biguint_to_u256(bigint.to_biguint().unwrap())
 }
 
+/// Converts `U256` value into the closest `f64` value. Useful for reporting token amounts in metrics.
+pub fn u256_to_f64(value: U256) -> f64 {
+    value
+        .0
+        .iter()
+        .rev()
+        .fold(0.0, |acc, &limb| acc * 2.0_f64.powi(64) + limb as f64)
+}
+
 fn ensure_chunkable(bytes: &[u8]) {
     assert!(
         bytes.len() % 32 == 0,
@@ -172,6 +181,16 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_u256_to_f64() {
+        assert_eq!(u256_to_f64(U256::zero()), 0.0);
+        assert_eq!(u256_to_f64(U256::from(123_456_u64)), 123_456.0);
+        assert_eq!(u256_to_f64(U256::from(u64::MAX) + 1), 2.0_f64.powi(64));
+        assert_eq!(u256_to_f64(U256::one() << 200), 2.0_f64.powi(200));
+        let max = u256_to_f64(U256::MAX);
+        assert!((max / 2.0_f64.powi(256) - 1.0).abs() < 1e-15, "{max}");
+    }
+
     #[test]
     fn test_bigdecimal_to_u256() {
         let value = BigDecimal::from(100u32);
@@ -0,0 +1,253 @@
+//! Harness re-executing a sealed L1 batch with bootloader debugging enabled.
+//!
+//! Unlike the TEE verifier or witness generation, the harness doesn't require any inputs other than Postgres
+//! and doesn't check the resulting state against the Merkle tree. Instead, it records all bootloader hooks
+//! (including bootloader debug logs) and gas remaining at each hook, which helps to debug issues like
+//! "bootloader out of gas" or system log / hash mismatches.
+
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use multivm::{
+    interface::{
+        BootloaderMemory, ExecutionResult, L2BlockEnv, VmExecutionMode, VmExecutionResultAndLogs,
+        VmInterface, VmInterfaceHistoryEnabled,
+    },
+    tracers::bootloader_debug::{BootloaderDebugReport, BootloaderDebugTracer},
+    vm_latest::{HistoryEnabled, ToTracerPointer, Vm},
+};
+use once_cell::sync::OnceCell;
+use tokio::runtime::Handle;
+use zksync_dal::{Connection, Core, CoreDal};
+use zksync_state::{StorageView, StorageViewAt, WriteStorage};
+use zksync_types::{
+    l2_to_l1_log::SystemL2ToL1Log, vm_version::VmVersion, L1BatchNumber, L2BlockNumber, L2ChainId,
+    ProtocolVersionId, Transaction, H256,
+};
+
+use crate::load_l1_batch_env;
+
+/// Debug information for a single transaction in the batch.
+#[derive(Debug)]
+pub struct TxDebugReport {
+    pub tx_hash: H256,
+    pub l2_block_number: L2BlockNumber,
+    /// Whether the transaction had to be re-executed without bytecode compression.
+    pub compression_failed: bool,
+    pub result: ExecutionResult,
+    pub gas_used: u64,
+    pub bootloader: BootloaderDebugReport,
+}
+
+/// Mismatch between a system log produced during re-execution and the log persisted for the batch.
+#[derive(Debug)]
+pub struct SystemLogMismatch {
+    pub index: usize,
+    pub expected: Option<SystemL2ToL1Log>,
+    pub actual: Option<SystemL2ToL1Log>,
+}
+
+/// Debug information for an L1 batch.
+#[derive(Debug)]
+pub struct L1BatchDebugReport {
+    pub l1_batch_number: L1BatchNumber,
+    pub protocol_version: ProtocolVersionId,
+    pub transactions: Vec<TxDebugReport>,
+    /// Result of executing the batch tip (i.e., the bootloader code after the last transaction).
+    pub batch_tip_result: ExecutionResult,
+    pub batch_tip: BootloaderDebugReport,
+    /// Bootloader memory filled in by the operator. Only collected if heap dumping is enabled.
+    pub bootloader_memory: Option<BootloaderMemory>,
+    pub system_log_mismatches: Vec<SystemLogMismatch>,
+}
+
+/// Re-executes the specified sealed L1 batch with [`BootloaderDebugTracer`]. Must be called from a blocking context.
+/// If `dump_heap` is set, the bootloader heap is dumped after each execution; this is slow and memory-intensive.
+///
+/// Only batches executed with VM 1.5.0 are supported.
+pub fn debug_l1_batch(
+    rt_handle: Handle,
+    l1_batch_number: L1BatchNumber,
+    mut connection: Connection<'_, Core>,
+    l2_chain_id: L2ChainId,
+    evm_emulator_hash: Option<H256>,
+    dump_heap: bool,
+) -> anyhow::Result<L1BatchDebugReport> {
+    let l1_batch_header = rt_handle
+        .block_on(connection.blocks_dal().get_l1_batch_header(l1_batch_number))?
+        .with_context(|| format!("header is missing for L1 batch #{l1_batch_number}"))?;
+    let l2_blocks = rt_handle.block_on(
+        connection
+            .transactions_dal()
+            .get_l2_blocks_to_execute_for_l1_batch(l1_batch_number),
+    )?;
+    let (system_env, l1_batch_env, storage_l2_block_number) = load_l1_batch_env(
+        &rt_handle,
+        l1_batch_number,
+        &mut connection,
+        l2_chain_id,
+        evm_emulator_hash,
+    )?;
+
+    let protocol_version = system_env.version;
+    let vm_version = VmVersion::from(protocol_version);
+    anyhow::ensure!(
+        BootloaderDebugTracer::supports(vm_version),
+        "L1 batch #{l1_batch_number} is executed with unsupported VM version {vm_version:?}"
+    );
+
+    let pg_storage = StorageViewAt::l2_block(storage_l2_block_number)
+        .postgres_blocking(rt_handle, connection)?;
+    let storage_view = StorageView::new(pg_storage).to_rc_ptr();
+    let mut vm = Vm::<_, HistoryEnabled>::new(l1_batch_env, system_env, storage_view);
+
+    let mut transactions = vec![];
+    let next_l2_blocks = l2_blocks.iter().skip(1);
+    for (l2_block, next_l2_block) in l2_blocks.iter().zip(next_l2_blocks) {
+        for tx in &l2_block.txs {
+            let tx_report = debug_tx(&mut vm, tx, l2_block.number, vm_version, dump_heap)?;
+            tracing::info!(
+                "Executed tx {:?} in L2 block #{}: {:?}, gas used: {}, bootloader gas remaining: {}",
+                tx_report.tx_hash,
+                tx_report.l2_block_number,
+                tx_report.result,
+                tx_report.gas_used,
+                tx_report.bootloader.gas_remaining
+            );
+            transactions.push(tx_report);
+        }
+        vm.start_new_l2_block(L2BlockEnv::from_l2_block_data(next_l2_block));
+    }
+
+    let (result, batch_tip) = inspect(vm_version, dump_heap, |tracer| {
+        vm.inspect(tracer.into_tracer_pointer().into(), VmExecutionMode::Batch)
+    })?;
+    tracing::info!(
+        "Executed batch tip: {:?}, bootloader gas remaining: {}",
+        result.result,
+        batch_tip.gas_remaining
+    );
+
+    let actual_system_logs = vm.get_current_execution_state().system_logs;
+    let system_log_mismatches =
+        compare_system_logs(&l1_batch_header.system_logs, &actual_system_logs);
+    for mismatch in &system_log_mismatches {
+        tracing::warn!("System log mismatch: {mismatch:?}");
+    }
+
+    Ok(L1BatchDebugReport {
+        l1_batch_number,
+        protocol_version,
+        transactions,
+        batch_tip_result: result.result,
+        batch_tip,
+        bootloader_memory: dump_heap.then(|| vm.get_bootloader_memory()),
+        system_log_mismatches,
+    })
+}
+
+fn debug_tx<S: WriteStorage>(
+    vm: &mut Vm<S, HistoryEnabled>,
+    tx: &Transaction,
+    l2_block_number: L2BlockNumber,
+    vm_version: VmVersion,
+    dump_heap: bool,
+) -> anyhow::Result<TxDebugReport> {
+    // Mirror `execute_tx()`: attempt to run the transaction with bytecode compression, and fall back
+    // to running it without compression.
+    vm.make_snapshot();
+    let mut compression_failed = false;
+    let (mut result, mut bootloader) = inspect(vm_version, dump_heap, |tracer| {
+        let (compression_result, result) = vm.inspect_transaction_with_bytecode_compression(
+            tracer.into_tracer_pointer().into(),
+            tx.clone(),
+            true,
+        );
+        compression_failed = compression_result.is_err();
+        result
+    })?;
+
+    if compression_failed {
+        vm.rollback_to_the_latest_snapshot();
+        (result, bootloader) = inspect(vm_version, dump_heap, |tracer| {
+            vm.inspect_transaction_with_bytecode_compression(
+                tracer.into_tracer_pointer().into(),
+                tx.clone(),
+                false,
+            )
+            .1
+        })?;
+    } else {
+        vm.pop_snapshot_no_rollback();
+    }
+
+    Ok(TxDebugReport {
+        tx_hash: tx.hash(),
+        l2_block_number,
+        compression_failed,
+        result: result.result,
+        gas_used: result.statistics.gas_used,
+        bootloader,
+    })
+}
+
+fn inspect(
+    vm_version: VmVersion,
+    dump_heap: bool,
+    execute: impl FnOnce(BootloaderDebugTracer) -> VmExecutionResultAndLogs,
+) -> anyhow::Result<(VmExecutionResultAndLogs, BootloaderDebugReport)> {
+    let report_cell = Arc::<OnceCell<_>>::default();
+    let tracer = BootloaderDebugTracer::new(vm_version, dump_heap, report_cell.clone());
+    let result = execute(tracer);
+    let report = Arc::into_inner(report_cell)
+        .and_then(OnceCell::into_inner)
+        .context("bootloader debug tracer didn't produce a report")?;
+    Ok((result, report))
+}
+
+fn compare_system_logs(
+    expected: &[SystemL2ToL1Log],
+    actual: &[SystemL2ToL1Log],
+) -> Vec<SystemLogMismatch> {
+    let len = expected.len().max(actual.len());
+    (0..len)
+        .filter_map(|index| {
+            let expected = expected.get(index);
+            let actual = actual.get(index);
+            (expected != actual).then(|| SystemLogMismatch {
+                index,
+                expected: expected.cloned(),
+                actual: actual.cloned(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::l2_to_l1_log::L2ToL1Log;
+
+    use super::*;
+
+    fn system_log(value: u8) -> SystemL2ToL1Log {
+        SystemL2ToL1Log(L2ToL1Log {
+            value: H256::repeat_byte(value),
+            ..L2ToL1Log::default()
+        })
+    }
+
+    #[test]
+    fn comparing_system_logs() {
+        let expected = [system_log(1), system_log(2)];
+        assert!(compare_system_logs(&expected, &expected).is_empty());
+
+        let actual = [system_log(1), system_log(3), system_log(4)];
+        let mismatches = compare_system_logs(&expected, &actual);
+        assert_eq!(mismatches.len(), 2);
+        assert_eq!(mismatches[0].index, 1);
+        assert_eq!(mismatches[0].expected, Some(system_log(2)));
+        assert_eq!(mismatches[0].actual, Some(system_log(3)));
+        assert_eq!(mismatches[1].index, 2);
+        assert_eq!(mismatches[1].expected, None);
+    }
+}
@@ -1,15 +1,16 @@
+pub mod bootloader_debug;
 pub mod storage;
 
 use anyhow::{anyhow, Context};
 use multivm::{
-    interface::{VmInterface, VmInterfaceHistoryEnabled},
+    interface::{L1BatchEnv, SystemEnv, VmInterface, VmInterfaceHistoryEnabled},
     vm_latest::HistoryEnabled,
     VmInstance,
 };
 use tokio::runtime::Handle;
 use zksync_dal::{Connection, Core};
-use zksync_state::{PostgresStorage, StoragePtr, StorageView, WriteStorage};
-use zksync_types::{L1BatchNumber, L2ChainId, Transaction};
+use zksync_state::{PostgresStorage, StoragePtr, StorageView, StorageViewAt, WriteStorage};
+use zksync_types::{L1BatchNumber, L2BlockNumber, L2ChainId, Transaction, H256};
 
 use crate::storage::L1BatchParamsProvider;
 
@@ -23,13 +24,39 @@ pub fn create_vm(
     l1_batch_number: L1BatchNumber,
     mut connection: Connection<'_, Core>,
     l2_chain_id: L2ChainId,
+    evm_emulator_hash: Option<H256>,
 ) -> anyhow::Result<VmAndStorage> {
+    let (system_env, l1_batch_env, storage_l2_block_number) = load_l1_batch_env(
+        &rt_handle,
+        l1_batch_number,
+        &mut connection,
+        l2_chain_id,
+        evm_emulator_hash,
+    )?;
+    let pg_storage = StorageViewAt::l2_block(storage_l2_block_number)
+        .postgres_blocking(rt_handle, connection)?;
+    let storage_view = StorageView::new(pg_storage).to_rc_ptr();
+    let vm = VmInstance::new(l1_batch_env, system_env, storage_view.clone());
+
+    Ok((vm, storage_view))
+}
+
+/// Loads VM environment for an already sealed L1 batch. Returns the number of the L2 block
+/// preceding the batch as the last element, which should be used to initialize the VM storage.
+fn load_l1_batch_env(
+    rt_handle: &Handle,
+    l1_batch_number: L1BatchNumber,
+    connection: &mut Connection<'_, Core>,
+    l2_chain_id: L2ChainId,
+    evm_emulator_hash: Option<H256>,
+) -> anyhow::Result<(SystemEnv, L1BatchEnv, L2BlockNumber)> {
     let l1_batch_params_provider = rt_handle
-        .block_on(L1BatchParamsProvider::new(&mut connection))
-        .context("failed initializing L1 batch params provider")?;
+        .block_on(L1BatchParamsProvider::new(connection))
+        .context("failed initializing L1 batch params provider")?
+        .with_evm_emulator_hash(evm_emulator_hash);
     let first_l2_block_in_batch = rt_handle
         .block_on(
-            l1_batch_params_provider.load_first_l2_block_in_batch(&mut connection, l1_batch_number),
+            l1_batch_params_provider.load_first_l2_block_in_batch(connection, l1_batch_number),
         )
         .with_context(|| format!("failed loading first L2 block in L1 batch #{l1_batch_number}"))?
         .with_context(|| format!("no L2 blocks persisted for L1 batch #{l1_batch_number}"))?;
@@ -41,20 +68,17 @@ pub fn create_vm(
 
     let (system_env, l1_batch_env) = rt_handle
         .block_on(l1_batch_params_provider.load_l1_batch_params(
-            &mut connection,
+            connection,
             &first_l2_block_in_batch,
             validation_computational_gas_limit,
             l2_chain_id,
         ))
         .context("expected L2 block to be executed and sealed")?;
-
-    let storage_l2_block_number = first_l2_block_in_batch.number() - 1;
-    let pg_storage =
-        PostgresStorage::new(rt_handle.clone(), connection, storage_l2_block_number, true);
-    let storage_view = StorageView::new(pg_storage).to_rc_ptr();
-    let vm = VmInstance::new(l1_batch_env, system_env, storage_view.clone());
-
-    Ok((vm, storage_view))
+    Ok((
+        system_env,
+        l1_batch_env,
+        first_l2_block_in_batch.number() - 1,
+    ))
 }
 
 pub fn execute_tx<S: WriteStorage>(
@@ -86,6 +86,7 @@ pub fn l1_batch_params(
 #[derive(Debug)]
 pub struct L1BatchParamsProvider {
     snapshot: Option<SnapshotRecoveryStatus>,
+    evm_emulator_hash: Option<H256>,
 }
 
 impl L1BatchParamsProvider {
@@ -94,7 +95,18 @@ impl L1BatchParamsProvider {
             .snapshot_recovery_dal()
             .get_applied_snapshot_status()
             .await?;
-        Ok(Self { snapshot })
+        Ok(Self {
+            snapshot,
+            evm_emulator_hash: None,
+        })
+    }
+
+    /// Sets the hash of the EVM emulator enabled for the chain (usually, taken from the genesis config).
+    /// The emulator is included into base system contracts for all loaded L1 batches.
+    #[must_use]
+    pub fn with_evm_emulator_hash(mut self, evm_emulator_hash: Option<H256>) -> Self {
+        self.evm_emulator_hash = evm_emulator_hash;
+        self
     }
 
     /// Returns state root hash and timestamp of an L1 batch with the specified number waiting for the hash to be computed
@@ -278,7 +290,11 @@ impl L1BatchParamsProvider {
         let contract_hashes = first_l2_block_in_batch.header.base_system_contracts_hashes;
         let base_system_contracts = storage
             .factory_deps_dal()
-            .get_base_system_contracts(contract_hashes.bootloader, contract_hashes.default_aa)
+            .get_base_system_contracts(
+                contract_hashes.bootloader,
+                contract_hashes.default_aa,
+                self.evm_emulator_hash,
+            )
             .await
             .context("failed getting base system contracts")?;
 
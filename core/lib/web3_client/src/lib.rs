@@ -0,0 +1,50 @@
+//! Rust client for the zkSync Web3 API intended for external integrators.
+//!
+//! # Overview
+//!
+//! - Typed async bindings for `eth_`, `zks_` and other namespaces are provided by the namespace client traits
+//!   (e.g., [`ZksNamespaceClient`]) implemented for [`Client`], [`MockClient`] and [`DynClient`].
+//!   These are the same bindings that are used by the external node and other tools in this repository.
+//! - Clients are built with [`Client::http()`]; rate limiting and metrics are configured
+//!   on the returned [`ClientBuilder`].
+//! - [`RetryingClient`] is a client middleware retrying all calls failing with transient errors (e.g., network failures
+//!   or an overloaded server) according to a [`RetryPolicy`]. The policy can also be applied to individual calls
+//!   via [`RetryPolicy::call()`].
+//! - [`Wallet`] provides helpers for a single account on L1 and L2, e.g. querying balances of the base token,
+//!   which may differ from ETH. If created with a private key, the wallet can also sign and send transfers,
+//!   withdrawals and deposits.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use zksync_web3_client::{Client, L2, RetryPolicy, RetryingClient, Wallet};
+//! # async fn test() -> Result<(), Box<dyn std::error::Error>> {
+//! let client: Client<L2> = Client::http("https://mainnet.era.zksync.io".parse()?)?.build();
+//! let client = RetryingClient::new(Box::new(client), RetryPolicy::default());
+//! let address = "0x0000000000000000000000000000000000008001".parse()?;
+//! let wallet = Wallet::new(address, Box::new(client));
+//! let base_token = wallet.base_token().await?;
+//! let balance = wallet.l2_balance().await?;
+//! println!("Balance of {address:?}: {balance} (base token: {base_token:?})");
+//! # Ok(())
+//! # }
+//! ```
+
+pub use zksync_web3_decl::{
+    client::{Client, ClientBuilder, DynClient, MockClient, Network, L1, L2},
+    error::{ClientRpcContext, EnrichedClientError, EnrichedClientResult},
+    jsonrpsee::core::ClientError,
+    namespaces::{
+        DebugNamespaceClient, EnNamespaceClient, EthNamespaceClient, NetNamespaceClient,
+        SnapshotsNamespaceClient, Web3NamespaceClient, ZksNamespaceClient,
+    },
+    types,
+};
+
+pub use crate::{
+    retry::{RetryPolicy, RetryingClient},
+    wallet::{BaseToken, Wallet, WalletError},
+};
+
+mod retry;
+mod wallet;
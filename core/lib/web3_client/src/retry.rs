@@ -0,0 +1,331 @@
+//! Retries for RPC calls failing with transient errors.
+
+use std::{fmt, future::Future, time::Duration};
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use zksync_web3_decl::{
+    client::{DynClient, ForNetwork, Network, TaggedClient},
+    error::{self, EnrichedClientError, EnrichedClientResult},
+    jsonrpsee::core::{
+        client::{BatchResponse, ClientT},
+        params::BatchRequestBuilder,
+        traits::ToRpcParams,
+        ClientError, JsonRawValue,
+    },
+};
+
+/// Policy for retrying RPC calls that fail with transient errors, as determined by
+/// [`EnrichedClientError::is_transient()`](crate::EnrichedClientError::is_transient()).
+/// Backoff between attempts grows exponentially up to [`Self::max_backoff`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts for a single call, including the first one.
+    pub max_attempts: usize,
+    /// Backoff after the first failed attempt.
+    pub initial_backoff: Duration,
+    /// Upper bound on the backoff between attempts.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy that doesn't retry calls.
+    pub const NO_RETRIES: Self = Self {
+        max_attempts: 1,
+        initial_backoff: Duration::ZERO,
+        max_backoff: Duration::ZERO,
+    };
+
+    /// Performs a call produced by `call_fn`, retrying it according to this policy.
+    pub async fn call<T, F, Fut>(&self, call_fn: F) -> EnrichedClientResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = EnrichedClientResult<T>>,
+    {
+        self.retry(call_fn, EnrichedClientError::is_transient).await
+    }
+
+    async fn retry<T, E, F, Fut>(
+        &self,
+        mut call_fn: F,
+        is_transient: fn(&E) -> bool,
+    ) -> Result<T, E>
+    where
+        E: fmt::Display,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut backoff = self.initial_backoff;
+        let mut attempt = 1;
+        loop {
+            match call_fn().await {
+                Err(err) if is_transient(&err) && attempt < self.max_attempts => {
+                    tracing::warn!(
+                        "Transient error on attempt {attempt}/{}: {err}; retrying in {backoff:?}",
+                        self.max_attempts
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(self.max_backoff);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Params serialized once and reused for all call attempts.
+#[derive(Debug, Clone)]
+struct RawParams(Option<Box<JsonRawValue>>);
+
+impl RawParams {
+    fn new(params: impl ToRpcParams) -> Result<Self, ClientError> {
+        Ok(Self(params.to_rpc_params()?))
+    }
+}
+
+impl ToRpcParams for RawParams {
+    fn to_rpc_params(self) -> Result<Option<Box<JsonRawValue>>, serde_json::Error> {
+        Ok(self.0)
+    }
+}
+
+/// Methods sending transactions.
+const NON_RETRIABLE_METHODS: &[&str] = &[
+    "eth_sendRawTransaction",
+    "eth_sendTransaction",
+    "zks_sendRawTransactionWithDetailedOutput",
+];
+
+/// Client middleware retrying *all* calls that fail with transient errors according to a [`RetryPolicy`].
+///
+/// The middleware wraps a client and implements the same RPC traits, so calls made via namespace client traits
+/// (e.g., [`ZksNamespaceClient`](crate::ZksNamespaceClient)) are retried without any changes on the caller side.
+/// Batch requests are retried as a whole, i.e., only if the entire batch fails with a transient error.
+///
+/// Calls sending transactions (e.g., `eth_sendRawTransaction`) are never retried since a transient error doesn't
+/// guarantee that the transaction wasn't accepted by the server.
+#[derive(Debug, Clone)]
+pub struct RetryingClient<Net: Network> {
+    inner: Box<DynClient<Net>>,
+    policy: RetryPolicy,
+}
+
+impl<Net: Network> RetryingClient<Net> {
+    /// Wraps the provided client.
+    pub fn new(inner: Box<DynClient<Net>>, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    /// Returns the retry policy used by this client.
+    pub fn policy(&self) -> RetryPolicy {
+        self.policy
+    }
+
+    fn policy_for(&self, method: &str) -> RetryPolicy {
+        if NON_RETRIABLE_METHODS.contains(&method) {
+            RetryPolicy::NO_RETRIES
+        } else {
+            self.policy
+        }
+    }
+}
+
+impl<Net: Network> ForNetwork for RetryingClient<Net> {
+    type Net = Net;
+
+    fn network(&self) -> Self::Net {
+        self.inner.network()
+    }
+
+    fn component(&self) -> &'static str {
+        self.inner.component()
+    }
+}
+
+impl<Net: Network> TaggedClient for RetryingClient<Net> {
+    fn set_component(&mut self, component_name: &'static str) {
+        self.inner = self.inner.clone().for_component(component_name);
+    }
+}
+
+#[async_trait]
+impl<Net: Network> ClientT for RetryingClient<Net> {
+    async fn notification<Params>(&self, method: &str, params: Params) -> Result<(), ClientError>
+    where
+        Params: ToRpcParams + Send,
+    {
+        let params = RawParams::new(params)?;
+        self.policy_for(method)
+            .retry(
+                || self.inner.notification(method, params.clone()),
+                error::is_transient,
+            )
+            .await
+    }
+
+    async fn request<R, Params>(&self, method: &str, params: Params) -> Result<R, ClientError>
+    where
+        R: DeserializeOwned,
+        Params: ToRpcParams + Send,
+    {
+        let params = RawParams::new(params)?;
+        self.policy_for(method)
+            .retry(
+                || self.inner.request(method, params.clone()),
+                error::is_transient,
+            )
+            .await
+    }
+
+    async fn batch_request<'a, R>(
+        &self,
+        batch: BatchRequestBuilder<'a>,
+    ) -> Result<BatchResponse<'a, R>, ClientError>
+    where
+        R: DeserializeOwned + fmt::Debug + 'a,
+    {
+        self.policy
+            .retry(
+                || self.inner.batch_request(batch.clone()),
+                error::is_transient,
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use assert_matches::assert_matches;
+    use zksync_types::{web3, H256, U64};
+    use zksync_web3_decl::{
+        client::{MockClient, L2},
+        namespaces::EthNamespaceClient,
+    };
+
+    use super::*;
+
+    async fn flaky_call(
+        calls: &AtomicUsize,
+        failures: usize,
+        transient: bool,
+    ) -> EnrichedClientResult<usize> {
+        let call_idx = calls.fetch_add(1, Ordering::SeqCst);
+        if call_idx < failures {
+            let err = if transient {
+                ClientError::RequestTimeout
+            } else {
+                ClientError::Custom("oops".to_owned())
+            };
+            Err(EnrichedClientError::new(err, "test"))
+        } else {
+            Ok(call_idx)
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retrying_transient_errors() {
+        let policy = RetryPolicy::default();
+        let calls = AtomicUsize::new(0);
+        let result = policy.call(|| flaky_call(&calls, 3, true)).await;
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+        let calls = AtomicUsize::new(0);
+        let err = policy
+            .call(|| flaky_call(&calls, 10, true))
+            .await
+            .unwrap_err();
+        assert_matches!(err.as_ref(), ClientError::RequestTimeout);
+        assert_eq!(calls.load(Ordering::SeqCst), policy.max_attempts);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn not_retrying_non_transient_errors() {
+        let calls = AtomicUsize::new(0);
+        let err = RetryPolicy::default()
+            .call(|| flaky_call(&calls, 1, false))
+            .await
+            .unwrap_err();
+        assert_matches!(err.as_ref(), ClientError::Custom(_));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let calls = AtomicUsize::new(0);
+        RetryPolicy::NO_RETRIES
+            .call(|| flaky_call(&calls, 1, true))
+            .await
+            .unwrap_err();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    fn flaky_client(calls: Arc<AtomicUsize>, failures: usize) -> Box<DynClient<L2>> {
+        let client = MockClient::builder(L2::default())
+            .method("eth_blockNumber", {
+                let calls = calls.clone();
+                move || {
+                    if calls.fetch_add(1, Ordering::SeqCst) < failures {
+                        Err(ClientError::RequestTimeout)
+                    } else {
+                        Ok(U64::from(42))
+                    }
+                }
+            })
+            .method("eth_sendRawTransaction", move |_tx_bytes: web3::Bytes| {
+                if calls.fetch_add(1, Ordering::SeqCst) < failures {
+                    Err(ClientError::RequestTimeout)
+                } else {
+                    Ok(H256::zero())
+                }
+            })
+            .build();
+        Box::new(client)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retrying_client_calls() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let client = RetryingClient::new(flaky_client(calls.clone(), 2), RetryPolicy::default());
+        let client: Box<DynClient<L2>> = Box::new(client).for_component("test");
+        assert_eq!(client.component(), "test");
+
+        let block_number = client.get_block_number().await.unwrap();
+        assert_eq!(block_number, 42.into());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let client = RetryingClient::new(flaky_client(calls.clone(), 10), RetryPolicy::default());
+        let err = client.get_block_number().await.unwrap_err();
+        assert_matches!(err, ClientError::RequestTimeout);
+        assert_eq!(
+            calls.load(Ordering::SeqCst),
+            RetryPolicy::default().max_attempts
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn not_retrying_sent_transactions() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let client = RetryingClient::new(flaky_client(calls.clone(), 1), RetryPolicy::default());
+        let err = client
+            .send_raw_transaction(web3::Bytes(vec![1, 2, 3]))
+            .await
+            .unwrap_err();
+        assert_matches!(err, ClientError::RequestTimeout);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,823 @@
+//! Helpers for a single account on L1 and L2.
+
+use std::collections::HashMap;
+
+use zksync_eth_client::{EthInterface, RawTransactionBytes};
+use zksync_eth_signer::{EthereumSigner, PrivateKeySigner, TransactionParameters};
+use zksync_system_constants::{
+    ETHEREUM_ADDRESS, L2_BASE_TOKEN_ADDRESS, REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE,
+    SHARED_BRIDGE_ETHER_TOKEN_ADDRESS,
+};
+use zksync_types::{
+    api::{BlockIdVariant, BlockNumber},
+    ethabi,
+    fee::Fee,
+    l2::L2Tx,
+    transaction_request::{CallRequest, PaymasterParams, TransactionRequest},
+    web3, Address, Eip712Domain, K256PrivateKey, L2ChainId, Nonce, PackedEthSignature, H256, U256,
+};
+use zksync_web3_decl::{
+    client::{DynClient, L1, L2},
+    error::{ClientRpcContext, EnrichedClientError},
+    jsonrpsee::{core::ClientError, types::error::ErrorCode},
+    namespaces::{EthNamespaceClient, ZksNamespaceClient},
+};
+
+use crate::retry::RetryPolicy;
+
+/// Errors returned by [`Wallet`] methods.
+#[derive(Debug, thiserror::Error)]
+pub enum WalletError {
+    #[error("L1 client is not configured for the wallet")]
+    NoL1Client,
+    #[error("wallet doesn't have a private key and cannot sign transactions")]
+    NoPrivateKey,
+    #[error("failed signing transaction: {0}")]
+    Signing(String),
+    #[error("unexpected response from `{method}`: {message}")]
+    UnexpectedResponse {
+        method: &'static str,
+        message: String,
+    },
+    #[error(transparent)]
+    Rpc(#[from] EnrichedClientError),
+}
+
+/// Base token of a chain, i.e. the token used to pay fees on L2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaseToken {
+    Eth,
+    /// ERC20 token with the specified L1 address.
+    Custom {
+        l1_address: Address,
+    },
+}
+
+impl BaseToken {
+    fn from_l1_address(l1_address: Address) -> Self {
+        if l1_address == SHARED_BRIDGE_ETHER_TOKEN_ADDRESS || l1_address == ETHEREUM_ADDRESS {
+            Self::Eth
+        } else {
+            Self::Custom { l1_address }
+        }
+    }
+
+    /// Returns the L1 address of the base token, or `None` for ETH.
+    pub fn l1_address(&self) -> Option<Address> {
+        match self {
+            Self::Eth => None,
+            Self::Custom { l1_address } => Some(*l1_address),
+        }
+    }
+}
+
+/// Account on a zkSync chain with an optional access to L1.
+///
+/// A wallet created with [`Self::new()`] is read-only. A wallet created with [`Self::from_private_key()`]
+/// can additionally sign and send transactions: transfers and withdrawals on L2, and deposits from L1
+/// (the latter requires an L1 client). All these operations use the base token of the chain, which may differ from ETH.
+#[derive(Debug)]
+pub struct Wallet {
+    address: Address,
+    private_key: Option<K256PrivateKey>,
+    l2_client: Box<DynClient<L2>>,
+    l1_client: Option<Box<DynClient<L1>>>,
+    retry_policy: RetryPolicy,
+}
+
+impl Wallet {
+    /// Creates a wallet for the specified `address`. By default, the wallet doesn't have access to L1
+    /// and doesn't retry failed calls.
+    pub fn new(address: Address, l2_client: Box<DynClient<L2>>) -> Self {
+        Self {
+            address,
+            private_key: None,
+            l2_client: l2_client.for_component("wallet"),
+            l1_client: None,
+            retry_policy: RetryPolicy::NO_RETRIES,
+        }
+    }
+
+    /// Creates a wallet able to sign transactions with the provided `private_key`. The wallet address
+    /// is derived from the key.
+    pub fn from_private_key(private_key: K256PrivateKey, l2_client: Box<DynClient<L2>>) -> Self {
+        let mut this = Self::new(private_key.address(), l2_client);
+        this.private_key = Some(private_key);
+        this
+    }
+
+    /// Sets the L1 client for the wallet.
+    #[must_use]
+    pub fn with_l1_client(mut self, l1_client: Box<DynClient<L1>>) -> Self {
+        self.l1_client = Some(l1_client.for_component("wallet"));
+        self
+    }
+
+    /// Sets the retry policy for all read calls made by the wallet. Calls sending transactions are never retried,
+    /// since a transaction may have been accepted even if the call has failed.
+    #[must_use]
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    pub fn l2_client(&self) -> &DynClient<L2> {
+        self.l2_client.as_ref()
+    }
+
+    fn l1_client(&self) -> Result<&DynClient<L1>, WalletError> {
+        self.l1_client.as_deref().ok_or(WalletError::NoL1Client)
+    }
+
+    fn private_key(&self) -> Result<&K256PrivateKey, WalletError> {
+        self.private_key.as_ref().ok_or(WalletError::NoPrivateKey)
+    }
+
+    /// Returns the base token of the chain. Servers not supporting custom base tokens are assumed to use ETH.
+    pub async fn base_token(&self) -> Result<BaseToken, WalletError> {
+        let result = self
+            .retry_policy
+            .call(|| {
+                self.l2_client
+                    .get_base_token_l1_address()
+                    .rpc_context("get_base_token_l1_address")
+            })
+            .await;
+        match result {
+            Ok(l1_address) => Ok(BaseToken::from_l1_address(l1_address)),
+            Err(err) if is_method_not_found(&err) => Ok(BaseToken::Eth),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Returns the balance of the base token on L2.
+    pub async fn l2_balance(&self) -> Result<U256, WalletError> {
+        let block = Some(BlockIdVariant::BlockNumber(BlockNumber::Latest));
+        let balance = self
+            .retry_policy
+            .call(|| {
+                self.l2_client
+                    .get_balance(self.address, block)
+                    .rpc_context("get_balance")
+                    .with_arg("address", &self.address)
+            })
+            .await?;
+        Ok(balance)
+    }
+
+    /// Returns non-zero balances of all tokens on L2, keyed by the L2 token address. The base token balance
+    /// is keyed by [`ETHEREUM_ADDRESS`] regardless of the base token.
+    pub async fn l2_token_balances(&self) -> Result<HashMap<Address, U256>, WalletError> {
+        let balances = self
+            .retry_policy
+            .call(|| {
+                self.l2_client
+                    .get_all_account_balances(self.address)
+                    .rpc_context("get_all_account_balances")
+                    .with_arg("address", &self.address)
+            })
+            .await?;
+        Ok(balances)
+    }
+
+    /// Returns the committed nonce of the account on L2.
+    pub async fn l2_nonce(&self) -> Result<U256, WalletError> {
+        let block = Some(BlockIdVariant::BlockNumber(BlockNumber::Committed));
+        let nonce = self
+            .retry_policy
+            .call(|| {
+                self.l2_client
+                    .get_transaction_count(self.address, block)
+                    .rpc_context("get_transaction_count")
+                    .with_arg("address", &self.address)
+            })
+            .await?;
+        Ok(nonce)
+    }
+
+    /// Returns the balance of the chain base token on L1.
+    pub async fn l1_base_token_balance(&self) -> Result<U256, WalletError> {
+        match self.base_token().await? {
+            BaseToken::Eth => self.l1_eth_balance().await,
+            BaseToken::Custom { l1_address } => self.l1_erc20_balance(l1_address).await,
+        }
+    }
+
+    /// Returns the ETH balance on L1.
+    pub async fn l1_eth_balance(&self) -> Result<U256, WalletError> {
+        let l1_client = self.l1_client()?;
+        let balance = self
+            .retry_policy
+            .call(|| l1_client.eth_balance(self.address))
+            .await?;
+        Ok(balance)
+    }
+
+    /// Returns the balance of an ERC20 token with the specified L1 address.
+    pub async fn l1_erc20_balance(&self, token: Address) -> Result<U256, WalletError> {
+        const METHOD: &str = "balanceOf";
+
+        let data = encode_function_call(
+            METHOD,
+            &[ethabi::ParamType::Address],
+            &[ethabi::Token::Address(self.address)],
+        );
+        self.call_l1_u256_function(token, METHOD, data).await
+    }
+
+    async fn call_l1_u256_function(
+        &self,
+        contract: Address,
+        method: &'static str,
+        data: Vec<u8>,
+    ) -> Result<U256, WalletError> {
+        let l1_client = self.l1_client()?;
+        let request = web3::CallRequest {
+            to: Some(contract),
+            data: Some(data.into()),
+            ..web3::CallRequest::default()
+        };
+
+        let output = self
+            .retry_policy
+            .call(|| l1_client.call_contract_function(request.clone(), None))
+            .await?;
+        if output.0.len() != 32 {
+            return Err(WalletError::UnexpectedResponse {
+                method,
+                message: format!("expected 32-byte output, got {} bytes", output.0.len()),
+            });
+        }
+        Ok(U256::from_big_endian(&output.0))
+    }
+
+    async fn l2_chain_id(&self) -> Result<L2ChainId, WalletError> {
+        let chain_id = self
+            .retry_policy
+            .call(|| self.l2_client.chain_id().rpc_context("chain_id"))
+            .await?;
+        L2ChainId::try_from(chain_id.as_u64()).map_err(|message| WalletError::UnexpectedResponse {
+            method: "eth_chainId",
+            message,
+        })
+    }
+
+    /// Transfers `amount` of the base token to the `to` address on L2. Returns the hash of the sent L2 transaction.
+    pub async fn transfer(&self, to: Address, amount: U256) -> Result<H256, WalletError> {
+        self.send_l2_transaction(to, vec![], amount).await
+    }
+
+    /// Withdraws `amount` of the base token to the `to` address on L1. Returns the hash of the sent L2 transaction.
+    /// Withdrawn funds must be finalized on L1 once the L1 batch with the transaction is executed.
+    pub async fn withdraw(&self, to: Address, amount: U256) -> Result<H256, WalletError> {
+        let calldata = encode_function_call(
+            "withdraw",
+            &[ethabi::ParamType::Address],
+            &[ethabi::Token::Address(to)],
+        );
+        self.send_l2_transaction(L2_BASE_TOKEN_ADDRESS, calldata, amount)
+            .await
+    }
+
+    /// Signs and sends an L2 transaction, with the fee estimated by the server and paid in the base token.
+    async fn send_l2_transaction(
+        &self,
+        contract_address: Address,
+        calldata: Vec<u8>,
+        value: U256,
+    ) -> Result<H256, WalletError> {
+        let private_key = self.private_key()?;
+        let chain_id = self.l2_chain_id().await?;
+        let block = Some(BlockIdVariant::BlockNumber(BlockNumber::Pending));
+        let nonce = self
+            .retry_policy
+            .call(|| {
+                self.l2_client
+                    .get_transaction_count(self.address, block)
+                    .rpc_context("get_transaction_count")
+                    .with_arg("address", &self.address)
+            })
+            .await?;
+
+        let mut tx = L2Tx::new(
+            contract_address,
+            calldata,
+            Nonce(nonce.as_u32()),
+            Fee::default(),
+            self.address,
+            value,
+            None,
+            PaymasterParams::default(),
+        );
+        let fee_request = CallRequest::from(tx.clone());
+        tx.common_data.fee = self
+            .retry_policy
+            .call(|| {
+                self.l2_client
+                    .estimate_fee(fee_request.clone())
+                    .rpc_context("estimate_fee")
+            })
+            .await?;
+
+        let mut request = TransactionRequest::from(tx);
+        if let Some(meta) = &mut request.eip712_meta {
+            meta.custom_signature = None;
+        }
+        request.from = Some(self.address);
+        request.chain_id = Some(chain_id.as_u64());
+        let signature = PackedEthSignature::sign_typed_data(
+            private_key,
+            &Eip712Domain::new(chain_id),
+            &request,
+        )
+        .map_err(|err| WalletError::Signing(err.to_string()))?;
+        let raw_tx = request
+            .get_signed_bytes(&signature)
+            .map_err(|err| WalletError::Signing(err.to_string()))?;
+
+        let tx_hash = self
+            .l2_client
+            .send_raw_transaction(raw_tx.into())
+            .rpc_context("send_raw_transaction")
+            .await?;
+        Ok(tx_hash)
+    }
+
+    /// Deposits `amount` of the base token from L1 to the `to` address on L2 via the bridgehub contract.
+    /// Returns the hash of the L1 deposit transaction.
+    ///
+    /// For chains with a custom base token, the deposit is preceded by an L1 transaction approving
+    /// the token for the shared bridge. The fee for the L1 -> L2 transaction is paid in the base token
+    /// on top of `amount`.
+    pub async fn deposit(&self, to: Address, amount: U256) -> Result<H256, WalletError> {
+        /// L1 gas limit for the ERC20 `approve` call.
+        const APPROVE_GAS_LIMIT: u64 = 100_000;
+        /// L1 gas limit for the deposit call.
+        const DEPOSIT_GAS_LIMIT: u64 = 600_000;
+
+        let l1_client = self.l1_client()?;
+        let private_key = self.private_key()?;
+        let bridgehub = self
+            .retry_policy
+            .call(|| {
+                self.l2_client
+                    .get_bridgehub_contract()
+                    .rpc_context("get_bridgehub_contract")
+            })
+            .await?
+            .ok_or_else(|| WalletError::UnexpectedResponse {
+                method: "zks_getBridgehubContract",
+                message: "bridgehub contract is not available".to_owned(),
+            })?;
+        let l2_chain_id = self.l2_chain_id().await?;
+
+        let l2_request = CallRequest {
+            from: Some(self.address),
+            to: Some(to),
+            value: Some(amount),
+            ..CallRequest::default()
+        };
+        let l2_gas_limit = self
+            .retry_policy
+            .call(|| {
+                self.l2_client
+                    .estimate_gas_l1_to_l2(l2_request.clone())
+                    .rpc_context("estimate_gas_l1_to_l2")
+            })
+            .await?;
+        let gas_per_pubdata = U256::from(REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE);
+        let gas_price = self.retry_policy.call(|| l1_client.get_gas_price()).await?;
+        let base_cost = self
+            .call_l1_u256_function(
+                bridgehub,
+                "l2TransactionBaseCost",
+                encode_function_call(
+                    "l2TransactionBaseCost",
+                    &[
+                        ethabi::ParamType::Uint(256),
+                        ethabi::ParamType::Uint(256),
+                        ethabi::ParamType::Uint(256),
+                        ethabi::ParamType::Uint(256),
+                    ],
+                    &[
+                        ethabi::Token::Uint(l2_chain_id.as_u64().into()),
+                        ethabi::Token::Uint(gas_price),
+                        ethabi::Token::Uint(l2_gas_limit),
+                        ethabi::Token::Uint(gas_per_pubdata),
+                    ],
+                ),
+            )
+            .await?;
+        let mint_value = base_cost + amount;
+
+        let l1_chain_id = self
+            .retry_policy
+            .call(|| l1_client.fetch_chain_id())
+            .await?;
+        let mut nonce = self
+            .retry_policy
+            .call(|| l1_client.nonce_at_for_account(self.address, web3::BlockNumber::Pending))
+            .await?;
+        let base_params = TransactionParameters {
+            chain_id: l1_chain_id.0,
+            max_fee_per_gas: gas_price,
+            ..TransactionParameters::default()
+        };
+
+        let msg_value = match self.base_token().await? {
+            BaseToken::Eth => mint_value,
+            BaseToken::Custom { l1_address } => {
+                let bridges = self
+                    .retry_policy
+                    .call(|| {
+                        self.l2_client
+                            .get_bridge_contracts()
+                            .rpc_context("get_bridge_contracts")
+                    })
+                    .await?;
+                let shared_bridge = bridges.l1_shared_default_bridge.ok_or_else(|| {
+                    WalletError::UnexpectedResponse {
+                        method: "zks_getBridgeContracts",
+                        message: "L1 shared bridge is not available".to_owned(),
+                    }
+                })?;
+                let data = encode_function_call(
+                    "approve",
+                    &[ethabi::ParamType::Address, ethabi::ParamType::Uint(256)],
+                    &[
+                        ethabi::Token::Address(shared_bridge),
+                        ethabi::Token::Uint(mint_value),
+                    ],
+                );
+                let approve_params = TransactionParameters {
+                    nonce,
+                    to: Some(l1_address),
+                    gas: APPROVE_GAS_LIMIT.into(),
+                    data,
+                    ..base_params.clone()
+                };
+                send_l1_transaction(l1_client, private_key, approve_params).await?;
+                nonce += U256::one();
+                // The base token is transferred by the shared bridge, so no ETH is sent with the deposit.
+                U256::zero()
+            }
+        };
+
+        let request_type = ethabi::ParamType::Tuple(vec![
+            ethabi::ParamType::Uint(256),                                 // chain ID
+            ethabi::ParamType::Uint(256),                                 // mint value
+            ethabi::ParamType::Address,                                   // L2 contract
+            ethabi::ParamType::Uint(256),                                 // L2 value
+            ethabi::ParamType::Bytes,                                     // L2 calldata
+            ethabi::ParamType::Uint(256),                                 // L2 gas limit
+            ethabi::ParamType::Uint(256),                                 // gas per pubdata byte
+            ethabi::ParamType::Array(Box::new(ethabi::ParamType::Bytes)), // factory deps
+            ethabi::ParamType::Address,                                   // refund recipient
+        ]);
+        let request = ethabi::Token::Tuple(vec![
+            ethabi::Token::Uint(l2_chain_id.as_u64().into()),
+            ethabi::Token::Uint(mint_value),
+            ethabi::Token::Address(to),
+            ethabi::Token::Uint(amount),
+            ethabi::Token::Bytes(vec![]),
+            ethabi::Token::Uint(l2_gas_limit),
+            ethabi::Token::Uint(gas_per_pubdata),
+            ethabi::Token::Array(vec![]),
+            ethabi::Token::Address(self.address),
+        ]);
+        let data = encode_function_call("requestL2TransactionDirect", &[request_type], &[request]);
+        let deposit_params = TransactionParameters {
+            nonce,
+            to: Some(bridgehub),
+            gas: DEPOSIT_GAS_LIMIT.into(),
+            value: msg_value,
+            data,
+            ..base_params
+        };
+        send_l1_transaction(l1_client, private_key, deposit_params).await
+    }
+}
+
+fn encode_function_call(
+    name: &str,
+    params: &[ethabi::ParamType],
+    tokens: &[ethabi::Token],
+) -> Vec<u8> {
+    let mut data = ethabi::short_signature(name, params).to_vec();
+    data.extend(ethabi::encode(tokens));
+    data
+}
+
+async fn send_l1_transaction(
+    l1_client: &DynClient<L1>,
+    private_key: &K256PrivateKey,
+    params: TransactionParameters,
+) -> Result<H256, WalletError> {
+    let raw_tx = PrivateKeySigner::new(private_key.clone())
+        .sign_transaction(params)
+        .await
+        .map_err(|err| WalletError::Signing(err.to_string()))?;
+    let tx_hash = l1_client
+        .send_raw_tx(RawTransactionBytes::new_unchecked(raw_tx))
+        .await?;
+    Ok(tx_hash)
+}
+
+fn is_method_not_found(err: &EnrichedClientError) -> bool {
+    matches!(
+        err.as_ref(),
+        ClientError::Call(err) if err.code() == ErrorCode::MethodNotFound.code()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use assert_matches::assert_matches;
+    use zksync_types::{api::BridgeAddresses, U64};
+    use zksync_web3_decl::{client::MockClient, jsonrpsee::types::ErrorObject};
+
+    use super::*;
+
+    const ADDRESS: Address = Address::repeat_byte(0x23);
+    const TOKEN: Address = Address::repeat_byte(0x42);
+    const BRIDGEHUB: Address = Address::repeat_byte(0x01);
+    const SHARED_BRIDGE: Address = Address::repeat_byte(0x02);
+    const L1_CHAIN_ID: u64 = 9;
+    const L2_CHAIN_ID: u64 = 270;
+    const BASE_COST: u64 = 1_000;
+
+    fn l2_client(base_token: Option<Address>) -> Box<DynClient<L2>> {
+        let client = MockClient::builder(L2::default())
+            .method("zks_getBaseTokenL1Address", move || {
+                base_token
+                    .ok_or_else(|| ClientError::Call(ErrorObject::from(ErrorCode::MethodNotFound)))
+            })
+            .method(
+                "eth_getBalance",
+                |address: Address, _block: Option<BlockIdVariant>| {
+                    assert_eq!(address, ADDRESS);
+                    Ok(U256::from(10))
+                },
+            )
+            .build();
+        Box::new(client)
+    }
+
+    fn l1_client() -> Box<DynClient<L1>> {
+        let client = MockClient::builder(L1::default())
+            .method(
+                "eth_getBalance",
+                |address: Address, _block: web3::BlockNumber| {
+                    assert_eq!(address, ADDRESS);
+                    Ok(U256::from(100))
+                },
+            )
+            .method(
+                "eth_call",
+                |req: web3::CallRequest, _block: web3::BlockId| {
+                    assert_eq!(req.to, Some(TOKEN));
+                    let data = req.data.unwrap().0;
+                    assert_eq!(
+                        data[..4],
+                        ethabi::short_signature("balanceOf", &[ethabi::ParamType::Address])
+                    );
+                    Ok(web3::Bytes(ethabi::encode(&[ethabi::Token::Uint(
+                        1_000.into(),
+                    )])))
+                },
+            )
+            .build();
+        Box::new(client)
+    }
+
+    #[tokio::test]
+    async fn getting_base_token() {
+        let wallet = Wallet::new(ADDRESS, l2_client(None));
+        assert_eq!(wallet.base_token().await.unwrap(), BaseToken::Eth);
+        let wallet = Wallet::new(ADDRESS, l2_client(Some(SHARED_BRIDGE_ETHER_TOKEN_ADDRESS)));
+        assert_eq!(wallet.base_token().await.unwrap(), BaseToken::Eth);
+        let wallet = Wallet::new(ADDRESS, l2_client(Some(TOKEN)));
+        assert_eq!(
+            wallet.base_token().await.unwrap(),
+            BaseToken::Custom { l1_address: TOKEN }
+        );
+    }
+
+    #[tokio::test]
+    async fn getting_balances() {
+        let wallet = Wallet::new(ADDRESS, l2_client(None));
+        assert_eq!(wallet.l2_balance().await.unwrap(), 10.into());
+        let err = wallet.l1_base_token_balance().await.unwrap_err();
+        assert_matches!(err, WalletError::NoL1Client);
+
+        let wallet = wallet.with_l1_client(l1_client());
+        assert_eq!(wallet.l1_base_token_balance().await.unwrap(), 100.into());
+
+        let wallet = Wallet::new(ADDRESS, l2_client(Some(TOKEN))).with_l1_client(l1_client());
+        assert_eq!(wallet.l1_base_token_balance().await.unwrap(), 1_000.into());
+    }
+
+    type SentTxs = Arc<Mutex<Vec<web3::Bytes>>>;
+
+    fn signing_l2_client(base_token: Option<Address>, sent_txs: SentTxs) -> Box<DynClient<L2>> {
+        let client = MockClient::builder(L2::default())
+            .method("zks_getBaseTokenL1Address", move || {
+                base_token
+                    .ok_or_else(|| ClientError::Call(ErrorObject::from(ErrorCode::MethodNotFound)))
+            })
+            .method("eth_chainId", || Ok(U64::from(L2_CHAIN_ID)))
+            .method(
+                "eth_getTransactionCount",
+                |_address: Address, block: Option<BlockIdVariant>| {
+                    assert_eq!(
+                        block,
+                        Some(BlockIdVariant::BlockNumber(BlockNumber::Pending))
+                    );
+                    Ok(U256::from(3))
+                },
+            )
+            .method("zks_estimateFee", |req: CallRequest| {
+                assert_eq!(req.from, Some(private_key().address()));
+                Ok(Fee {
+                    gas_limit: 1_000_000.into(),
+                    max_fee_per_gas: 250_000_000.into(),
+                    max_priority_fee_per_gas: 0.into(),
+                    gas_per_pubdata_limit: 50_000.into(),
+                })
+            })
+            .method("eth_sendRawTransaction", move |tx_bytes: web3::Bytes| {
+                sent_txs.lock().unwrap().push(tx_bytes);
+                Ok(H256::repeat_byte(0xff))
+            })
+            .method("zks_getBridgehubContract", || Ok(Some(BRIDGEHUB)))
+            .method("zks_getBridgeContracts", || {
+                Ok(BridgeAddresses {
+                    l1_shared_default_bridge: Some(SHARED_BRIDGE),
+                    l2_shared_default_bridge: None,
+                    l1_erc20_default_bridge: None,
+                    l2_erc20_default_bridge: None,
+                    l1_weth_bridge: None,
+                    l2_weth_bridge: None,
+                })
+            })
+            .method("zks_estimateGasL1ToL2", |req: CallRequest| {
+                assert_eq!(req.from, Some(private_key().address()));
+                Ok(U256::from(500_000))
+            })
+            .build();
+        Box::new(client)
+    }
+
+    fn signing_l1_client(sent_txs: SentTxs) -> Box<DynClient<L1>> {
+        let client = MockClient::builder(L1::default())
+            .method("eth_chainId", || Ok(U64::from(L1_CHAIN_ID)))
+            .method("eth_gasPrice", || Ok(U256::from(10)))
+            .method(
+                "eth_getTransactionCount",
+                |address: Address, block: web3::BlockNumber| {
+                    assert_eq!(address, private_key().address());
+                    assert_eq!(block, web3::BlockNumber::Pending);
+                    Ok(U256::from(5))
+                },
+            )
+            .method(
+                "eth_call",
+                |req: web3::CallRequest, _block: web3::BlockId| {
+                    assert_eq!(req.to, Some(BRIDGEHUB));
+                    let data = req.data.unwrap().0;
+                    assert_eq!(
+                        data[..4],
+                        ethabi::short_signature(
+                            "l2TransactionBaseCost",
+                            &[
+                                ethabi::ParamType::Uint(256),
+                                ethabi::ParamType::Uint(256),
+                                ethabi::ParamType::Uint(256),
+                                ethabi::ParamType::Uint(256),
+                            ]
+                        )
+                    );
+                    Ok(web3::Bytes(ethabi::encode(&[ethabi::Token::Uint(
+                        BASE_COST.into(),
+                    )])))
+                },
+            )
+            .method("eth_sendRawTransaction", move |tx_bytes: web3::Bytes| {
+                sent_txs.lock().unwrap().push(tx_bytes);
+                Ok(H256::repeat_byte(0xee))
+            })
+            .build();
+        Box::new(client)
+    }
+
+    fn private_key() -> K256PrivateKey {
+        K256PrivateKey::from_bytes(H256::repeat_byte(0x11)).unwrap()
+    }
+
+    fn decode_l2_tx(tx_bytes: &web3::Bytes) -> L2Tx {
+        let (request, _) =
+            TransactionRequest::from_bytes(&tx_bytes.0, L2ChainId::from(L2_CHAIN_ID as u32))
+                .unwrap();
+        L2Tx::from_request(request, usize::MAX).unwrap()
+    }
+
+    #[tokio::test]
+    async fn sending_l2_transactions() {
+        let wallet = Wallet::new(ADDRESS, l2_client(None));
+        let err = wallet.transfer(TOKEN, 100.into()).await.unwrap_err();
+        assert_matches!(err, WalletError::NoPrivateKey);
+
+        let sent_txs = SentTxs::default();
+        let private_key = private_key();
+        let address = private_key.address();
+        let client = signing_l2_client(None, sent_txs.clone());
+        let wallet = Wallet::from_private_key(private_key, client);
+        assert_eq!(wallet.address(), address);
+
+        let tx_hash = wallet.transfer(TOKEN, 100.into()).await.unwrap();
+        assert_eq!(tx_hash, H256::repeat_byte(0xff));
+        let tx = decode_l2_tx(&sent_txs.lock().unwrap()[0]);
+        assert_eq!(tx.initiator_account(), address);
+        assert_eq!(tx.recipient_account(), TOKEN);
+        assert_eq!(tx.nonce(), Nonce(3));
+        assert_eq!(tx.execute.value, 100.into());
+        assert!(tx.execute.calldata.is_empty());
+        assert_eq!(tx.common_data.fee.gas_limit, 1_000_000.into());
+
+        wallet.withdraw(ADDRESS, 200.into()).await.unwrap();
+        let tx = decode_l2_tx(&sent_txs.lock().unwrap()[1]);
+        assert_eq!(tx.recipient_account(), L2_BASE_TOKEN_ADDRESS);
+        assert_eq!(tx.execute.value, 200.into());
+        assert_eq!(
+            tx.execute.calldata,
+            encode_function_call(
+                "withdraw",
+                &[ethabi::ParamType::Address],
+                &[ethabi::Token::Address(ADDRESS)]
+            )
+        );
+    }
+
+    fn decode_l1_tx(tx_bytes: &web3::Bytes) -> TransactionRequest {
+        let (request, _) = TransactionRequest::from_bytes_unverified(&tx_bytes.0).unwrap();
+        assert_eq!(request.chain_id, Some(L1_CHAIN_ID));
+        request
+    }
+
+    #[tokio::test]
+    async fn depositing_eth() {
+        let l1_txs = SentTxs::default();
+        let wallet =
+            Wallet::from_private_key(private_key(), signing_l2_client(None, SentTxs::default()));
+        let err = wallet.deposit(ADDRESS, 100.into()).await.unwrap_err();
+        assert_matches!(err, WalletError::NoL1Client);
+
+        let wallet = wallet.with_l1_client(signing_l1_client(l1_txs.clone()));
+        let tx_hash = wallet.deposit(ADDRESS, 100.into()).await.unwrap();
+        assert_eq!(tx_hash, H256::repeat_byte(0xee));
+
+        let l1_txs = l1_txs.lock().unwrap();
+        assert_eq!(l1_txs.len(), 1);
+        let deposit = decode_l1_tx(&l1_txs[0]);
+        assert_eq!(deposit.to, Some(BRIDGEHUB));
+        assert_eq!(deposit.nonce, 5.into());
+        assert_eq!(deposit.value, (BASE_COST + 100).into());
+    }
+
+    #[tokio::test]
+    async fn depositing_custom_base_token() {
+        let l1_txs = SentTxs::default();
+        let wallet = Wallet::from_private_key(
+            private_key(),
+            signing_l2_client(Some(TOKEN), SentTxs::default()),
+        )
+        .with_l1_client(signing_l1_client(l1_txs.clone()));
+        wallet.deposit(ADDRESS, 100.into()).await.unwrap();
+
+        let l1_txs = l1_txs.lock().unwrap();
+        assert_eq!(l1_txs.len(), 2);
+        let approval = decode_l1_tx(&l1_txs[0]);
+        assert_eq!(approval.to, Some(TOKEN));
+        assert_eq!(approval.nonce, 5.into());
+        let expected_data = encode_function_call(
+            "approve",
+            &[ethabi::ParamType::Address, ethabi::ParamType::Uint(256)],
+            &[
+                ethabi::Token::Address(SHARED_BRIDGE),
+                ethabi::Token::Uint((BASE_COST + 100).into()),
+            ],
+        );
+        assert_eq!(approval.input.0, expected_data);
+
+        let deposit = decode_l1_tx(&l1_txs[1]);
+        assert_eq!(deposit.to, Some(BRIDGEHUB));
+        assert_eq!(deposit.nonce, 6.into());
+        assert_eq!(deposit.value, 0.into());
+    }
+}
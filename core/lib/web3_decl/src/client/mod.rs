@@ -27,7 +27,7 @@ use jsonrpsee::{
         params::BatchRequestBuilder,
         traits::ToRpcParams,
     },
-    http_client::{HttpClient, HttpClientBuilder},
+    http_client::{HeaderMap, HttpClient, HttpClientBuilder},
     ws_client,
 };
 use serde::de::DeserializeOwned;
@@ -140,7 +140,17 @@ impl<Net: fmt::Debug, C: 'static> fmt::Debug for Client<Net, C> {
 impl<Net: Network> Client<Net> {
     /// Creates an HTTP-backed client.
     pub fn http(url: SensitiveUrl) -> anyhow::Result<ClientBuilder<Net>> {
-        let client = HttpClientBuilder::default().build(url.expose_str())?;
+        Self::http_with_headers(url, HeaderMap::new())
+    }
+
+    /// Creates an HTTP-backed client sending the specified headers with each request (e.g., for authentication).
+    pub fn http_with_headers(
+        url: SensitiveUrl,
+        headers: HeaderMap,
+    ) -> anyhow::Result<ClientBuilder<Net>> {
+        let client = HttpClientBuilder::default()
+            .set_headers(headers)
+            .build(url.expose_str())?;
         Ok(ClientBuilder::new(client, url))
     }
 }
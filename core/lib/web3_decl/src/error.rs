@@ -14,7 +14,7 @@ use std::{
 use jsonrpsee::{core::ClientError, types::error::ErrorCode};
 use pin_project_lite::pin_project;
 use thiserror::Error;
-use zksync_types::{api::SerializationTransactionError, L1BatchNumber, L2BlockNumber};
+use zksync_types::{api::SerializationTransactionError, L1BatchNumber, L2BlockNumber, H256};
 
 /// Server-side representation of the RPC error.
 #[derive(Debug, Error)]
@@ -37,8 +37,18 @@ pub enum Web3Error {
     FilterNotFound,
     #[error("Query returned more than {0} results. Try with this block range [{1:#x}, {2:#x}].")]
     LogsLimitExceeded(usize, u32, u32),
+    #[error("Query returned more than {0} results. Narrow the block range or the filter.")]
+    ResultTooLarge(usize),
+    #[error("Response exceeds the size limit of {0} bytes")]
+    ResponseTooLarge(usize),
+    #[error("Query timed out. Narrow the block range or the filter.")]
+    QueryTimeout,
     #[error("invalid filter: if blockHash is supplied fromBlock and toBlock must not be")]
     InvalidFilterBlockHash,
+    #[error("invalid transaction batch: {0}")]
+    InvalidTxBatch(String),
+    #[error("Idempotency key was already used for another transaction {0:?}")]
+    IdempotencyKeyReused(H256),
     /// Weaker form of a "method not found" error; the method implementation is technically present,
     /// but the node configuration prevents the method from functioning.
     #[error("Method not implemented")]
@@ -46,6 +56,10 @@ pub enum Web3Error {
     /// Unavailability caused by node configuration is returned as [`Self::MethodNotImplemented`].
     #[error("Tree API is temporarily unavailable")]
     TreeApiUnavailable,
+    #[error(
+        "Unauthorized; provide a valid API key in the `Authorization: Bearer <API key>` header"
+    )]
+    Unauthorized,
     #[error("Internal error")]
     InternalError(#[from] anyhow::Error),
 }
@@ -87,15 +101,21 @@ impl EnrichedClientError {
 
     /// Whether the error should be considered transient.
     pub fn is_transient(&self) -> bool {
-        match self.as_ref() {
-            ClientError::Transport(_) | ClientError::RequestTimeout => true,
-            ClientError::Call(err) => {
-                // At least some RPC providers use "internal error" in case of the server being overloaded
-                err.code() == ErrorCode::ServerIsBusy.code()
-                    || err.code() == ErrorCode::InternalError.code()
-            }
-            _ => false,
+        is_transient(self.as_ref())
+    }
+}
+
+/// Checks whether a client error should be considered transient (e.g., caused by network issues
+/// or an overloaded server), so that the failed call can be retried.
+pub fn is_transient(err: &ClientError) -> bool {
+    match err {
+        ClientError::Transport(_) | ClientError::RequestTimeout => true,
+        ClientError::Call(err) => {
+            // At least some RPC providers use "internal error" in case of the server being overloaded
+            err.code() == ErrorCode::ServerIsBusy.code()
+                || err.code() == ErrorCode::InternalError.code()
         }
+        _ => false,
     }
 }
 
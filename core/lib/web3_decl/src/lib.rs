@@ -10,6 +10,7 @@
 pub mod client;
 pub mod error;
 pub mod namespaces;
+pub mod openrpc;
 pub mod types;
 
 // Re-export to simplify crate usage (especially for server implementations).
@@ -0,0 +1,78 @@
+#[cfg_attr(not(feature = "server"), allow(unused_imports))]
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use zksync_types::{
+    api::{EthSenderStatus, OperatorAuditLogEntry},
+    Address, H256,
+};
+
+use crate::client::{ForNetwork, L2};
+
+/// Operator-facing methods changing node behavior at runtime. Should not be exposed publicly.
+#[cfg_attr(
+    feature = "server",
+    rpc(server, client, namespace = "admin", client_bounds(Self: ForNetwork<Net = L2>))
+)]
+#[cfg_attr(
+    not(feature = "server"),
+    rpc(client, namespace = "admin", client_bounds(Self: ForNetwork<Net = L2>))
+)]
+pub trait AdminNamespace {
+    /// Returns addresses for which call traces of touching transactions are saved by the state keeper.
+    #[method(name = "getTracedAddresses")]
+    async fn get_traced_addresses(&self) -> RpcResult<Vec<Address>>;
+
+    /// Starts saving call traces for transactions touching the specified addresses (i.e., initiating, receiving
+    /// or participating in a nested call). Takes effect from the next L1 batch.
+    ///
+    /// **Important.** Since a traced address can be reached via an arbitrarily deep call, the state keeper
+    /// collects call traces for *all* executed transactions while at least one address is traced, and only then
+    /// filters them. This noticeably slows down transaction execution (comparable to saving call traces globally),
+    /// so traced addresses should be removed once they are no longer needed.
+    #[method(name = "addTracedAddresses")]
+    async fn add_traced_addresses(&self, addresses: Vec<Address>) -> RpcResult<()>;
+
+    /// Stops saving call traces for transactions touching the specified addresses. Takes effect
+    /// from the next L1 batch.
+    #[method(name = "removeTracedAddresses")]
+    async fn remove_traced_addresses(&self, addresses: Vec<Address>) -> RpcResult<()>;
+
+    /// Returns operator audit log entries with IDs greater than `after_id`, ordered by ID. Requires authentication.
+    #[method(name = "getAuditLog")]
+    async fn get_audit_log(
+        &self,
+        after_id: Option<u64>,
+        limit: Option<usize>,
+    ) -> RpcResult<Vec<OperatorAuditLogEntry>>;
+
+    /// Requests the snapshot creator to create a snapshot. Only has an effect if the snapshot creator runs
+    /// in the scheduled mode. Returns the ID of the recorded request.
+    #[method(name = "requestSnapshot")]
+    async fn request_snapshot(&self) -> RpcResult<u64>;
+
+    /// Boosts a pending L2 transaction to the mempool priority lane, so that it's ordered ahead of the general
+    /// fee-ordered queue. Returns `false` if the transaction is not pending or is already boosted.
+    #[method(name = "boostTransaction")]
+    async fn boost_transaction(&self, tx_hash: H256) -> RpcResult<bool>;
+
+    /// Returns a summary of the L1 sender state: pending L1 transactions, fee bids of in-flight transactions,
+    /// nonces of operator keys and up to `failures_limit` recently failed transactions.
+    #[method(name = "getEthSenderStatus")]
+    async fn get_eth_sender_status(
+        &self,
+        failures_limit: Option<usize>,
+    ) -> RpcResult<EthSenderStatus>;
+}
+
+crate::openrpc::rpc_method_specs! {
+    namespace = "admin";
+    server = AdminNamespaceServer;
+    "getTracedAddresses" => get_traced_addresses() -> Vec<Address>;
+    "addTracedAddresses" => add_traced_addresses(addresses: Vec<Address>) -> ();
+    "removeTracedAddresses" => remove_traced_addresses(addresses: Vec<Address>) -> ();
+    "getAuditLog" => get_audit_log(after_id: Option<u64>, limit: Option<usize>)
+        -> Vec<OperatorAuditLogEntry>;
+    "requestSnapshot" => request_snapshot() -> u64;
+    "boostTransaction" => boost_transaction(tx_hash: H256) -> bool;
+    "getEthSenderStatus" => get_eth_sender_status(failures_limit: Option<usize>) -> EthSenderStatus;
+}
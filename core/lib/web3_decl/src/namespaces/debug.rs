@@ -57,3 +57,23 @@ pub trait DebugNamespace {
         options: Option<TracerConfig>,
     ) -> RpcResult<Option<DebugCall>>;
 }
+
+crate::openrpc::rpc_method_specs! {
+    namespace = "debug";
+    server = DebugNamespaceServer;
+    "traceBlockByNumber" => trace_block_by_number(block: BlockNumber, options: Option<TracerConfig>)
+        -> Vec<ResultDebugCall>;
+    "traceBlockByNumber.callFlatTracer" => trace_block_by_number_flat(
+        block: BlockNumber,
+        options: Option<TracerConfig>,
+    ) -> Vec<DebugCallFlat>;
+    "traceBlockByHash" => trace_block_by_hash(hash: H256, options: Option<TracerConfig>)
+        -> Vec<ResultDebugCall>;
+    "traceCall" => trace_call(
+        request: CallRequest,
+        block: Option<BlockId>,
+        options: Option<TracerConfig>,
+    ) -> DebugCall;
+    "traceTransaction" => trace_transaction(tx_hash: H256, options: Option<TracerConfig>)
+        -> Option<DebugCall>;
+}
@@ -42,3 +42,15 @@ pub trait EnNamespace {
     #[method(name = "getEcosystemContracts")]
     async fn get_ecosystem_contracts(&self) -> RpcResult<EcosystemContracts>;
 }
+
+crate::openrpc::rpc_method_specs! {
+    namespace = "en";
+    server = EnNamespaceServer;
+    "syncL2Block" => sync_l2_block(block_number: L2BlockNumber, include_transactions: bool)
+        -> Option<en::SyncBlock>;
+    "consensusGenesis" => consensus_genesis() -> Option<en::ConsensusGenesis>;
+    "syncTokens" => sync_tokens(block_number: Option<L2BlockNumber>) -> Vec<TokenInfo>;
+    "genesisConfig" => genesis_config() -> GenesisConfig;
+    "whitelistedTokensForAA" => whitelisted_tokens_for_aa() -> Vec<Address>;
+    "getEcosystemContracts" => get_ecosystem_contracts() -> EcosystemContracts;
+}
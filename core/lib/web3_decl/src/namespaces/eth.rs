@@ -193,3 +193,67 @@ mod pub_sub {
 
 #[cfg(feature = "server")]
 pub use self::pub_sub::EthPubSubServer;
+
+crate::openrpc::rpc_method_specs! {
+    namespace = "eth";
+    server = EthNamespaceServer;
+    "blockNumber" => get_block_number() -> U64;
+    "chainId" => chain_id() -> U64;
+    "call" => call(req: CallRequest, block: Option<BlockIdVariant>) -> Bytes;
+    "estimateGas" => estimate_gas(req: CallRequest, block: Option<BlockNumber>) -> U256;
+    "gasPrice" => gas_price() -> U256;
+    "newFilter" => new_filter(filter: Filter) -> U256;
+    "newBlockFilter" => new_block_filter() -> U256;
+    "uninstallFilter" => uninstall_filter(idx: U256) -> bool;
+    "newPendingTransactionFilter" => new_pending_transaction_filter() -> U256;
+    "getLogs" => get_logs(filter: Filter) -> Vec<Log>;
+    "getFilterLogs" => get_filter_logs(filter_index: U256) -> FilterChanges;
+    "getFilterChanges" => get_filter_changes(filter_index: U256) -> FilterChanges;
+    "getBalance" => get_balance(address: Address, block: Option<BlockIdVariant>) -> U256;
+    "getBlockByNumber" => get_block_by_number(block_number: BlockNumber, full_transactions: bool)
+        -> Option<Block<TransactionVariant>>;
+    "getBlockByHash" => get_block_by_hash(hash: H256, full_transactions: bool)
+        -> Option<Block<TransactionVariant>>;
+    "getBlockTransactionCountByNumber" => get_block_transaction_count_by_number(
+        block_number: BlockNumber,
+    ) -> Option<U256>;
+    "getBlockReceipts" => get_block_receipts(block_id: BlockId) -> Option<Vec<TransactionReceipt>>;
+    "getBlockTransactionCountByHash" => get_block_transaction_count_by_hash(block_hash: H256)
+        -> Option<U256>;
+    "getCode" => get_code(address: Address, block: Option<BlockIdVariant>) -> Bytes;
+    "getStorageAt" => get_storage_at(address: Address, idx: U256, block: Option<BlockIdVariant>)
+        -> H256;
+    "getTransactionCount" => get_transaction_count(address: Address, block: Option<BlockIdVariant>)
+        -> U256;
+    "getTransactionByHash" => get_transaction_by_hash(hash: H256) -> Option<Transaction>;
+    "getTransactionByBlockHashAndIndex" => get_transaction_by_block_hash_and_index(
+        block_hash: H256,
+        index: Index,
+    ) -> Option<Transaction>;
+    "getTransactionByBlockNumberAndIndex" => get_transaction_by_block_number_and_index(
+        block_number: BlockNumber,
+        index: Index,
+    ) -> Option<Transaction>;
+    "getTransactionReceipt" => get_transaction_receipt(hash: H256) -> Option<TransactionReceipt>;
+    "protocolVersion" => protocol_version() -> String;
+    "sendRawTransaction" => send_raw_transaction(tx_bytes: Bytes) -> H256;
+    "syncing" => syncing() -> SyncState;
+    "accounts" => accounts() -> Vec<Address>;
+    "coinbase" => coinbase() -> Address;
+    "getCompilers" => compilers() -> Vec<String>;
+    "hashrate" => hashrate() -> U256;
+    "getUncleCountByBlockHash" => get_uncle_count_by_block_hash(hash: H256) -> Option<U256>;
+    "getUncleCountByBlockNumber" => get_uncle_count_by_block_number(number: BlockNumber)
+        -> Option<U256>;
+    "mining" => mining() -> bool;
+    "feeHistory" => fee_history(
+        block_count: U64,
+        newest_block: BlockNumber,
+        reward_percentiles: Vec<f32>,
+    ) -> FeeHistory;
+    unchecked {
+        // Subscriptions are declared in `EthPubSub`, and their server methods have a different signature.
+        "subscribe"(sub_type: String, filter: Option<crate::types::PubSubFilter>) -> String;
+        "unsubscribe"(subscription_id: String) -> bool;
+    }
+}
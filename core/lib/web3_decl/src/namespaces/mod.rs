@@ -1,15 +1,16 @@
 pub use self::{
-    debug::DebugNamespaceClient, en::EnNamespaceClient, eth::EthNamespaceClient,
-    net::NetNamespaceClient, snapshots::SnapshotsNamespaceClient, web3::Web3NamespaceClient,
-    zks::ZksNamespaceClient,
+    admin::AdminNamespaceClient, debug::DebugNamespaceClient, en::EnNamespaceClient,
+    eth::EthNamespaceClient, net::NetNamespaceClient, snapshots::SnapshotsNamespaceClient,
+    web3::Web3NamespaceClient, zks::ZksNamespaceClient,
 };
 #[cfg(feature = "server")]
 pub use self::{
-    debug::DebugNamespaceServer, en::EnNamespaceServer, eth::EthNamespaceServer,
-    eth::EthPubSubServer, net::NetNamespaceServer, snapshots::SnapshotsNamespaceServer,
-    web3::Web3NamespaceServer, zks::ZksNamespaceServer,
+    admin::AdminNamespaceServer, debug::DebugNamespaceServer, en::EnNamespaceServer,
+    eth::EthNamespaceServer, eth::EthPubSubServer, net::NetNamespaceServer,
+    snapshots::SnapshotsNamespaceServer, web3::Web3NamespaceServer, zks::ZksNamespaceServer,
 };
 
+mod admin;
 mod debug;
 mod en;
 mod eth;
@@ -17,3 +18,19 @@ mod net;
 mod snapshots;
 mod web3;
 mod zks;
+
+/// Returns specifications of all methods in all namespaces.
+pub(crate) fn method_specs() -> impl Iterator<Item = crate::openrpc::MethodSpec> {
+    [
+        admin::method_specs(),
+        debug::method_specs(),
+        en::method_specs(),
+        eth::method_specs(),
+        net::method_specs(),
+        snapshots::method_specs(),
+        web3::method_specs(),
+        zks::method_specs(),
+    ]
+    .into_iter()
+    .flatten()
+}
@@ -23,3 +23,11 @@ pub trait NetNamespace {
     #[method(name = "listening")]
     fn is_listening(&self) -> RpcResult<bool>;
 }
+
+crate::openrpc::rpc_method_specs! {
+    namespace = "net";
+    server = NetNamespaceServer;
+    "version" => version() -> String;
+    "peerCount" => peer_count() -> U256;
+    "listening" => is_listening() -> bool;
+}
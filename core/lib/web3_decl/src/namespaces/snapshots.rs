@@ -26,3 +26,11 @@ pub trait SnapshotsNamespace {
         l1_batch_number: L1BatchNumber,
     ) -> RpcResult<Option<SnapshotHeader>>;
 }
+
+crate::openrpc::rpc_method_specs! {
+    namespace = "snapshots";
+    server = SnapshotsNamespaceServer;
+    "getAllSnapshots" => get_all_snapshots() -> AllSnapshots;
+    "getSnapshot" => get_snapshot_by_l1_batch_number(l1_batch_number: L1BatchNumber)
+        -> Option<SnapshotHeader>;
+}
@@ -1,8 +1,12 @@
 #[cfg_attr(not(feature = "server"), allow(unused_imports))]
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
+use zksync_types::H256;
 
-use crate::client::{ForNetwork, L2};
+use crate::{
+    client::{ForNetwork, L2},
+    types::Bytes,
+};
 
 #[cfg_attr(
     feature = "server",
@@ -16,6 +20,15 @@ pub trait Web3Namespace {
     #[method(name = "clientVersion")]
     fn client_version(&self) -> RpcResult<String>;
 
-    // `sha3` method is intentionally not implemented for the main server implementation:
-    // it can easily be implemented on the user side.
+    /// Returns Keccak-256 of the provided data. Only supported by servers with geth method compatibility enabled;
+    /// otherwise, it can easily be implemented on the user side.
+    #[method(name = "sha3")]
+    fn sha3(&self, data: Bytes) -> RpcResult<H256>;
+}
+
+crate::openrpc::rpc_method_specs! {
+    namespace = "web3";
+    server = Web3NamespaceServer;
+    "clientVersion" => client_version() -> String;
+    "sha3" => sha3(data: Bytes) -> H256;
 }
@@ -5,13 +5,17 @@ use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
-        TransactionDetailedResult, TransactionDetails,
+        AccountNonceInfo, ApiCapabilities, ApiFeeParams, BlockDetails, BridgeAddresses,
+        ForwardedTransaction, InternalTransfer, InteropMessageProof, L1BatchDetails,
+        L1ToL2ExecutionSimulation, L2BlockOrL1Batch, L2ToL1LogProof, PriorityOpDetails,
+        PriorityQueueInfo, Proof, ProtocolVersion, ProtocolVersionHistoryEntry,
+        RejectedTransaction, TransactionDeadline, TransactionDetailedResult, TransactionDetails,
+        TransactionExpiry, VerificationKeysHashes,
     },
     fee::Fee,
-    fee_model::{FeeParams, PubdataIndependentBatchFeeModelInput},
+    fee_model::{BatchFeeInputParams, PubdataIndependentBatchFeeModelInput},
     transaction_request::CallRequest,
-    Address, L1BatchNumber, L2BlockNumber, H256, U256, U64,
+    Address, L1BatchNumber, L2BlockNumber, PriorityOpId, H256, U256, U64,
 };
 
 use crate::{
@@ -31,9 +35,23 @@ pub trait ZksNamespace {
     #[method(name = "estimateFee")]
     async fn estimate_fee(&self, req: CallRequest) -> RpcResult<Fee>;
 
+    /// Estimates fees for an ordered list of transactions from the same sender. Each transaction is estimated
+    /// assuming that all preceding transactions are executed before it.
+    #[method(name = "estimateFeeBatch")]
+    async fn estimate_fee_batch(&self, reqs: Vec<CallRequest>) -> RpcResult<Vec<Fee>>;
+
     #[method(name = "estimateGasL1ToL2")]
     async fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> RpcResult<U256>;
 
+    /// Simulates the L2 part of an L1 -> L2 transaction against the pending state, including refunds
+    /// and failure behavior. Unlike `zks_estimateGasL1ToL2`, the transaction is executed with the provided gas limit
+    /// (or the maximum batch gas limit if it's not specified).
+    #[method(name = "estimateL1ToL2Execute")]
+    async fn estimate_l1_to_l2_execute(
+        &self,
+        req: CallRequest,
+    ) -> RpcResult<L1ToL2ExecutionSimulation>;
+
     #[method(name = "getBridgehubContract")]
     async fn get_bridgehub_contract(&self) -> RpcResult<Option<Address>>;
 
@@ -75,6 +93,15 @@ pub trait ZksNamespace {
         index: Option<usize>,
     ) -> RpcResult<Option<L2ToL1LogProof>>;
 
+    #[method(name = "getInteropMessageProof")]
+    async fn get_interop_message_proof(
+        &self,
+        block: L2BlockNumber,
+        sender: Address,
+        msg: H256,
+        l2_log_position: Option<usize>,
+    ) -> RpcResult<Option<InteropMessageProof>>;
+
     #[method(name = "L1BatchNumber")]
     async fn get_l1_batch_number(&self) -> RpcResult<U64>;
 
@@ -90,6 +117,20 @@ pub trait ZksNamespace {
     #[method(name = "getTransactionDetails")]
     async fn get_transaction_details(&self, hash: H256) -> RpcResult<Option<TransactionDetails>>;
 
+    /// Returns value transfers performed inside an executed transaction, in the execution order. Transfers are derived
+    /// from call traces, so the list is empty if the node doesn't save call traces.
+    #[method(name = "getInternalTransfers")]
+    async fn get_internal_transfers(&self, tx_hash: H256) -> RpcResult<Vec<InternalTransfer>>;
+
+    /// Returns the committed and the next usable nonce for an account, together with its pending transactions.
+    #[method(name = "getNonceInfo")]
+    async fn get_nonce_info(&self, address: Address) -> RpcResult<AccountNonceInfo>;
+
+    /// Returns the rejection reason for a transaction that was rejected on submission. Only available
+    /// if the rejected transactions journal is enabled on the node, and only within the configured retention period.
+    #[method(name = "getRejectedTransaction")]
+    async fn get_rejected_transaction(&self, hash: H256) -> RpcResult<Option<RejectedTransaction>>;
+
     #[method(name = "getRawBlockTransactions")]
     async fn get_raw_block_transactions(
         &self,
@@ -107,7 +148,14 @@ pub trait ZksNamespace {
     async fn get_l1_gas_price(&self) -> RpcResult<U64>;
 
     #[method(name = "getFeeParams")]
-    async fn get_fee_params(&self) -> RpcResult<FeeParams>;
+    async fn get_fee_params(&self) -> RpcResult<ApiFeeParams>;
+
+    /// Returns fee model params that were in effect for the specified L2 block or L1 batch.
+    #[method(name = "getFeeParamsAt")]
+    async fn get_fee_params_at(
+        &self,
+        at: L2BlockOrL1Batch,
+    ) -> RpcResult<Option<BatchFeeInputParams>>;
 
     #[method(name = "getProtocolVersion")]
     async fn get_protocol_version(
@@ -115,6 +163,16 @@ pub trait ZksNamespace {
         version_id: Option<u16>,
     ) -> RpcResult<Option<ProtocolVersion>>;
 
+    /// Returns all known protocol versions together with their activation L1 batch and L2 block.
+    #[method(name = "getProtocolVersionHistory")]
+    async fn get_protocol_version_history(&self) -> RpcResult<Vec<ProtocolVersionHistoryEntry>>;
+
+    #[method(name = "getVerificationKeysHashes")]
+    async fn get_verification_keys_hashes(
+        &self,
+        version_id: Option<u16>,
+    ) -> RpcResult<Vec<VerificationKeysHashes>>;
+
     #[method(name = "getProof")]
     async fn get_proof(
         &self,
@@ -126,9 +184,112 @@ pub trait ZksNamespace {
     #[method(name = "getBatchFeeInput")]
     async fn get_batch_fee_input(&self) -> RpcResult<PubdataIndependentBatchFeeModelInput>;
 
+    #[method(name = "getPriorityQueueInfo")]
+    async fn get_priority_queue_info(&self, limit: Option<usize>) -> RpcResult<PriorityQueueInfo>;
+
+    #[method(name = "getPriorityOpDetails")]
+    async fn get_priority_op_details(
+        &self,
+        serial_id: PriorityOpId,
+    ) -> RpcResult<Option<PriorityOpDetails>>;
+
+    #[method(name = "getCapabilities")]
+    async fn get_capabilities(&self) -> RpcResult<ApiCapabilities>;
+
     #[method(name = "sendRawTransactionWithDetailedOutput")]
     async fn send_raw_transaction_with_detailed_output(
         &self,
         tx_bytes: Bytes,
     ) -> RpcResult<TransactionDetailedResult>;
+
+    /// Submits a transaction with a client-specified inclusion deadline. If the transaction cannot be included
+    /// into an L2 block before the deadline, it's dropped from the mempool and reported as expired
+    /// by `zks_getTransactionExpiry`. Only supported by the main node.
+    #[method(name = "sendRawTransactionWithDeadline")]
+    async fn send_raw_transaction_with_deadline(
+        &self,
+        tx_bytes: Bytes,
+        deadline: TransactionDeadline,
+    ) -> RpcResult<H256>;
+
+    /// Returns expiry information for a transaction submitted with an inclusion deadline.
+    #[method(name = "getTransactionExpiry")]
+    async fn get_transaction_expiry(&self, hash: H256) -> RpcResult<Option<TransactionExpiry>>;
+
+    /// Returns the forwarding status of a transaction submitted to an external node. Returns `null` if the transaction
+    /// is unknown, or if the node doesn't forward transactions (e.g., for the main node).
+    #[method(name = "getForwardedTransaction")]
+    async fn get_forwarded_transaction(
+        &self,
+        hash: H256,
+    ) -> RpcResult<Option<ForwardedTransaction>>;
+}
+
+crate::openrpc::rpc_method_specs! {
+    namespace = "zks";
+    server = ZksNamespaceServer;
+    "estimateFee" => estimate_fee(req: CallRequest) -> Fee;
+    "estimateFeeBatch" => estimate_fee_batch(reqs: Vec<CallRequest>) -> Vec<Fee>;
+    "estimateGasL1ToL2" => estimate_gas_l1_to_l2(req: CallRequest) -> U256;
+    "estimateL1ToL2Execute" => estimate_l1_to_l2_execute(req: CallRequest)
+        -> L1ToL2ExecutionSimulation;
+    "getBridgehubContract" => get_bridgehub_contract() -> Option<Address>;
+    "getMainContract" => get_main_contract() -> Address;
+    "getTestnetPaymaster" => get_testnet_paymaster() -> Option<Address>;
+    "getBridgeContracts" => get_bridge_contracts() -> BridgeAddresses;
+    "getBaseTokenL1Address" => get_base_token_l1_address() -> Address;
+    "L1ChainId" => l1_chain_id() -> U64;
+    "getConfirmedTokens" => get_confirmed_tokens(from: u32, limit: u8) -> Vec<Token>;
+    "getAllAccountBalances" => get_all_account_balances(address: Address) -> HashMap<Address, U256>;
+    "getL2ToL1MsgProof" => get_l2_to_l1_msg_proof(
+        block: L2BlockNumber,
+        sender: Address,
+        msg: H256,
+        l2_log_position: Option<usize>,
+    ) -> Option<L2ToL1LogProof>;
+    "getL2ToL1LogProof" => get_l2_to_l1_log_proof(tx_hash: H256, index: Option<usize>)
+        -> Option<L2ToL1LogProof>;
+    "getInteropMessageProof" => get_interop_message_proof(
+        block: L2BlockNumber,
+        sender: Address,
+        msg: H256,
+        l2_log_position: Option<usize>,
+    ) -> Option<InteropMessageProof>;
+    "L1BatchNumber" => get_l1_batch_number() -> U64;
+    "getL1BatchBlockRange" => get_l2_block_range(batch: L1BatchNumber) -> Option<(U64, U64)>;
+    "getBlockDetails" => get_block_details(block_number: L2BlockNumber) -> Option<BlockDetails>;
+    "getTransactionDetails" => get_transaction_details(hash: H256) -> Option<TransactionDetails>;
+    "getInternalTransfers" => get_internal_transfers(tx_hash: H256) -> Vec<InternalTransfer>;
+    "getNonceInfo" => get_nonce_info(address: Address) -> AccountNonceInfo;
+    "getRejectedTransaction" => get_rejected_transaction(hash: H256) -> Option<RejectedTransaction>;
+    "getRawBlockTransactions" => get_raw_block_transactions(block_number: L2BlockNumber)
+        -> Vec<zksync_types::Transaction>;
+    "getL1BatchDetails" => get_l1_batch_details(batch: L1BatchNumber) -> Option<L1BatchDetails>;
+    "getBytecodeByHash" => get_bytecode_by_hash(hash: H256) -> Option<Vec<u8>>;
+    "getL1GasPrice" => get_l1_gas_price() -> U64;
+    "getFeeParams" => get_fee_params() -> ApiFeeParams;
+    "getFeeParamsAt" => get_fee_params_at(at: L2BlockOrL1Batch) -> Option<BatchFeeInputParams>;
+    "getProtocolVersion" => get_protocol_version(version_id: Option<u16>)
+        -> Option<ProtocolVersion>;
+    "getProtocolVersionHistory" => get_protocol_version_history()
+        -> Vec<ProtocolVersionHistoryEntry>;
+    "getVerificationKeysHashes" => get_verification_keys_hashes(version_id: Option<u16>)
+        -> Vec<VerificationKeysHashes>;
+    "getProof" => get_proof(address: Address, keys: Vec<H256>, l1_batch_number: L1BatchNumber)
+        -> Option<Proof>;
+    "getBatchFeeInput" => get_batch_fee_input() -> PubdataIndependentBatchFeeModelInput;
+    "getPriorityQueueInfo" => get_priority_queue_info(limit: Option<usize>) -> PriorityQueueInfo;
+    "getPriorityOpDetails" => get_priority_op_details(serial_id: PriorityOpId)
+        -> Option<PriorityOpDetails>;
+    "getCapabilities" => get_capabilities() -> ApiCapabilities;
+    "sendRawTransactionWithDetailedOutput" => send_raw_transaction_with_detailed_output(
+        tx_bytes: Bytes,
+    ) -> TransactionDetailedResult;
+    "sendRawTransactionWithDeadline" => send_raw_transaction_with_deadline(
+        tx_bytes: Bytes,
+        deadline: TransactionDeadline,
+    ) -> H256;
+    "getTransactionExpiry" => get_transaction_expiry(hash: H256) -> Option<TransactionExpiry>;
+    "getForwardedTransaction" => get_forwarded_transaction(hash: H256)
+        -> Option<ForwardedTransaction>;
 }
@@ -0,0 +1,879 @@
+//! [OpenRPC](https://spec.open-rpc.org/) document generation for the Web3 API.
+//!
+//! Each namespace in [`crate::namespaces`] declares specifications of its methods next to the `jsonrpsee` trait
+//! using the `rpc_method_specs!` macro. Param and result schemas are derived from Rust types
+//! via the [`RpcSchema`] trait. The API server builds a document for the methods it has registered
+//! and serves it via the [`DISCOVER_METHOD`] method.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    pin::Pin,
+};
+
+use chrono::{DateTime, Utc};
+use jsonrpsee::core::RpcResult;
+use serde_json::{json, Value};
+use zksync_config::{configs::EcosystemContracts, GenesisConfig};
+use zksync_types::{
+    api::{
+        en, AccountNonceInfo, ApiCapabilities, ApiFeeParams, ApiStorageLog, Block, BlockDetails,
+        BlockId, BlockIdVariant, BlockNumber, BridgeAddresses, DebugCall, EthSenderStatus,
+        ForwardedTransaction, ForwardedTransactionStatus, InternalTransfer, InteropMessageProof,
+        L1BatchDetails, L1ToL2ExecutionSimulation, L2BlockOrL1Batch, L2ToL1LogProof, Log,
+        OperatorAuditLogEntry, PendingTransactionInfo, PriorityOpDetails, PriorityOpStatus,
+        PriorityQueueInfo, Proof, ProtocolVersion, ProtocolVersionHistoryEntry,
+        RejectedTransaction, ResultDebugCall, TracerConfig, Transaction, TransactionDeadline,
+        TransactionDetailedResult, TransactionDetails, TransactionExpiry, TransactionExpiryStatus,
+        TransactionReceipt, TransactionVariant, VerificationKeysHashes,
+    },
+    debug_flat_call::DebugCallFlat,
+    fee::Fee,
+    fee_model::{BatchFeeInputParams, FeeParams, PubdataIndependentBatchFeeModelInput},
+    snapshots::{AllSnapshots, SnapshotHeader},
+    tokens::TokenInfo,
+    transaction_request::CallRequest,
+    web3::{Bytes, FeeHistory, SyncState},
+    Address, L1BatchNumber, L1BlockNumber, L2BlockNumber, PriorityOpId, H256, U256, U64,
+};
+
+use crate::types::{Filter, FilterChanges, PubSubFilter, Token};
+
+/// OpenRPC specification version of generated documents.
+pub const OPENRPC_VERSION: &str = "1.2.6";
+/// Name of the method returning the OpenRPC document.
+pub const DISCOVER_METHOD: &str = "rpc.discover";
+
+type SchemaFn = fn(&mut SchemaComponents) -> Value;
+
+/// Named schemas referenced from method params and results.
+#[derive(Debug, Default)]
+pub struct SchemaComponents(BTreeMap<&'static str, Value>);
+
+impl SchemaComponents {
+    /// Registers a named schema (unless it's already registered) and returns a reference to it.
+    pub fn reference(
+        &mut self,
+        name: &'static str,
+        schema: impl FnOnce(&mut Self) -> Value,
+    ) -> Value {
+        if !self.0.contains_key(name) {
+            // Insert a placeholder first so that recursive schemas terminate.
+            self.0.insert(name, Value::Null);
+            let schema = schema(self);
+            self.0.insert(name, schema);
+        }
+        json!({ "$ref": format!("#/components/schemas/{name}") })
+    }
+}
+
+/// Type that has a JSON schema describing its serialization in the Web3 API.
+pub trait RpcSchema {
+    /// Returns the schema for this type, registering named components if necessary.
+    fn schema(components: &mut SchemaComponents) -> Value;
+
+    /// Returns `true` if the value may be omitted when used as a method param.
+    fn is_optional() -> bool {
+        false
+    }
+}
+
+macro_rules! impl_inline_schemas {
+    ($($ty:ty => $schema:tt,)+) => {
+        $(
+        impl RpcSchema for $ty {
+            fn schema(_components: &mut SchemaComponents) -> Value {
+                json!($schema)
+            }
+        }
+        )+
+    };
+}
+
+const QUANTITY_PATTERN: &str = "^0x(0|[1-9a-fA-F][0-9a-fA-F]*)$";
+
+impl_inline_schemas!(
+    () => { "type": "null" },
+    bool => { "type": "boolean" },
+    String => { "type": "string" },
+    u8 => { "type": "integer", "minimum": 0, "maximum": u8::MAX },
+    u16 => { "type": "integer", "minimum": 0, "maximum": u16::MAX },
+    u32 => { "type": "integer", "minimum": 0, "maximum": u32::MAX },
+    u64 => { "type": "integer", "minimum": 0 },
+    usize => { "type": "integer", "minimum": 0 },
+    f32 => { "type": "number" },
+    f64 => { "type": "number" },
+    DateTime<Utc> => { "type": "string", "format": "date-time" },
+    L1BlockNumber => { "type": "integer", "minimum": 0, "maximum": u32::MAX },
+    L2BlockNumber => { "type": "integer", "minimum": 0, "maximum": u32::MAX },
+    L1BatchNumber => { "type": "integer", "minimum": 0, "maximum": u32::MAX },
+    PriorityOpId => { "type": "integer", "minimum": 0 },
+    U64 => { "type": "string", "pattern": QUANTITY_PATTERN },
+    U256 => { "type": "string", "pattern": QUANTITY_PATTERN },
+    H256 => { "type": "string", "pattern": "^0x[0-9a-fA-F]{64}$" },
+    Address => { "type": "string", "pattern": "^0x[0-9a-fA-F]{40}$" },
+    Bytes => { "type": "string", "pattern": "^0x([0-9a-fA-F]{2})*$" },
+);
+
+impl<T: RpcSchema> RpcSchema for Option<T> {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        json!({ "oneOf": [T::schema(components), { "type": "null" }] })
+    }
+
+    fn is_optional() -> bool {
+        true
+    }
+}
+
+impl<T: RpcSchema> RpcSchema for Vec<T> {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        json!({ "type": "array", "items": T::schema(components) })
+    }
+}
+
+impl<K, V: RpcSchema> RpcSchema for HashMap<K, V> {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        json!({ "type": "object", "additionalProperties": V::schema(components) })
+    }
+}
+
+impl<A: RpcSchema, B: RpcSchema> RpcSchema for (A, B) {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        json!({
+            "type": "array",
+            "prefixItems": [A::schema(components), B::schema(components)],
+            "minItems": 2,
+            "maxItems": 2,
+        })
+    }
+}
+
+impl<T: RpcSchema, E> RpcSchema for Result<T, E> {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        T::schema(components)
+    }
+}
+
+impl RpcSchema for BlockNumber {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        components.reference("BlockNumber", |_| {
+            json!({
+                "oneOf": [
+                    { "type": "string", "enum": ["committed", "finalized", "latest", "earliest", "pending"] },
+                    { "type": "string", "pattern": QUANTITY_PATTERN },
+                ]
+            })
+        })
+    }
+}
+
+impl RpcSchema for BlockId {
+    fn schema(components: &mut SchemaComponents) -> Value {
+        components.reference("BlockId", |components| {
+            json!({ "oneOf": [H256::schema(components), BlockNumber::schema(components)] })
+        })
+    }
+}
+
+/// Implements [`RpcSchema`] for unit enums serialized as strings.
+macro_rules! impl_enum_schemas {
+    ($($ty:ty => $name:literal [$($variant:literal),+],)+) => {
+        $(
+        impl RpcSchema for $ty {
+            fn schema(components: &mut SchemaComponents) -> Value {
+                components.reference($name, |_| json!({ "type": "string", "enum": [$($variant),+] }))
+            }
+        }
+        )+
+    };
+}
+
+impl_enum_schemas!(
+    ForwardedTransactionStatus => "ForwardedTransactionStatus"
+        ["queued", "forwarded", "rejected", "dropped"],
+    PriorityOpStatus => "PriorityOpStatus"
+        ["pending", "included", "committed", "proven", "executed"],
+    TransactionExpiryStatus => "TransactionExpiryStatus"
+        ["pending", "included", "expired", "dropped"],
+);
+
+/// Implements [`RpcSchema`] for object types by listing their serialized fields. Fields with optional types
+/// (i.e., `Option`s) are not required.
+macro_rules! impl_object_schemas {
+    ($($ty:ty => $name:literal { $($field:literal: $field_ty:ty,)+ })+) => {
+        $(
+        impl RpcSchema for $ty {
+            fn schema(components: &mut SchemaComponents) -> Value {
+                components.reference($name, |components| {
+                    let properties: serde_json::Map<String, Value> = [
+                        $(($field.to_owned(), <$field_ty>::schema(components)),)+
+                    ]
+                    .into_iter()
+                    .collect();
+                    let required: Vec<_> = [$(($field, <$field_ty>::is_optional()),)+]
+                        .into_iter()
+                        .filter_map(|(field, is_optional)| (!is_optional).then_some(field))
+                        .collect();
+                    json!({
+                        "type": "object",
+                        "properties": properties,
+                        "required": required,
+                    })
+                })
+            }
+        }
+        )+
+    };
+}
+
+impl_object_schemas!(
+    Fee => "Fee" {
+        "gas_limit": U256,
+        "max_fee_per_gas": U256,
+        "max_priority_fee_per_gas": U256,
+        "gas_per_pubdata_limit": U256,
+    }
+    Token => "Token" {
+        "l1Address": Address,
+        "l2Address": Address,
+        "name": String,
+        "symbol": String,
+        "decimals": u8,
+    }
+    BridgeAddresses => "BridgeAddresses" {
+        "l1SharedDefaultBridge": Option<Address>,
+        "l2SharedDefaultBridge": Option<Address>,
+        "l1Erc20DefaultBridge": Option<Address>,
+        "l2Erc20DefaultBridge": Option<Address>,
+        "l1WethBridge": Option<Address>,
+        "l2WethBridge": Option<Address>,
+    }
+    L2ToL1LogProof => "L2ToL1LogProof" {
+        "proof": Vec<H256>,
+        "id": u32,
+        "root": H256,
+    }
+    BatchFeeInputParams => "BatchFeeInputParams" {
+        "feeParams": FeeParams,
+        "l1GasPriceScaleFactor": f64,
+        "l1PubdataPriceScaleFactor": f64,
+    }
+    PriorityOpDetails => "PriorityOpDetails" {
+        "serialId": PriorityOpId,
+        "txHash": H256,
+        "sender": Address,
+        "l1BlockNumber": Option<L1BlockNumber>,
+        "receivedAt": DateTime<Utc>,
+        "status": PriorityOpStatus,
+        "l2BlockNumber": Option<L2BlockNumber>,
+        "l1BatchNumber": Option<L1BatchNumber>,
+        "error": Option<String>,
+        "queuePosition": Option<u64>,
+        "estimatedInclusionAt": Option<DateTime<Utc>>,
+    }
+    PriorityQueueInfo => "PriorityQueueInfo" {
+        "nextExpectedPriorityOpId": Option<PriorityOpId>,
+        "pendingOpsCount": u64,
+        "oldestPendingOpReceivedAt": Option<DateTime<Utc>>,
+        "averageInclusionDelayMs": Option<u64>,
+        "hasGap": bool,
+        "pendingOps": Vec<PriorityOpDetails>,
+    }
+    PendingTransactionInfo => "PendingTransactionInfo" {
+        "hash": H256,
+        "nonce": U256,
+        "maxFeePerGas": U256,
+        "maxPriorityFeePerGas": U256,
+    }
+    AccountNonceInfo => "AccountNonceInfo" {
+        "committedNonce": U256,
+        "nextNonce": U256,
+        "pendingTransactions": Vec<PendingTransactionInfo>,
+    }
+    RejectedTransaction => "RejectedTransaction" {
+        "hash": H256,
+        "initiatorAddress": Address,
+        "nonce": U256,
+        "errorCode": String,
+        "reason": String,
+        "rejectedAt": DateTime<Utc>,
+    }
+    TransactionDeadline => "TransactionDeadline" {
+        "notAfterL2Block": Option<L2BlockNumber>,
+        "notAfterTimestamp": Option<u64>,
+    }
+    TransactionExpiry => "TransactionExpiry" {
+        "hash": H256,
+        "deadline": TransactionDeadline,
+        "status": TransactionExpiryStatus,
+        "expiredAt": Option<DateTime<Utc>>,
+    }
+    ForwardedTransaction => "ForwardedTransaction" {
+        "hash": H256,
+        "status": ForwardedTransactionStatus,
+        "attempts": u32,
+        "lastError": Option<String>,
+        "receivedAt": DateTime<Utc>,
+        "forwardedAt": Option<DateTime<Utc>>,
+    }
+    InternalTransfer => "InternalTransfer" {
+        "from": Address,
+        "to": Address,
+        "value": U256,
+    }
+    ProtocolVersionHistoryEntry => "ProtocolVersionHistoryEntry" {
+        "minorVersion": u16,
+        "timestamp": u64,
+        "activationL1Batch": Option<L1BatchNumber>,
+        "activationL2Block": Option<L2BlockNumber>,
+        "bootloaderCodeHash": H256,
+        "defaultAccountCodeHash": H256,
+        "verifierAddress": Option<Address>,
+        "l2SystemUpgradeTxHash": Option<H256>,
+    }
+    L1ToL2ExecutionSimulation => "L1ToL2ExecutionSimulation" {
+        "success": bool,
+        "gasLimit": U256,
+        "maxFeePerGas": U256,
+        "gasUsed": U256,
+        "gasRefunded": U256,
+        "refundRecipient": Address,
+        "refundAmount": U256,
+        "output": Bytes,
+        "revertReason": Option<String>,
+        "events": Vec<Log>,
+    }
+    ApiStorageLog => "ApiStorageLog" {
+        "address": Address,
+        "key": U256,
+        "writtenValue": U256,
+    }
+    TransactionDetailedResult => "TransactionDetailedResult" {
+        "transactionHash": H256,
+        "storageLogs": Vec<ApiStorageLog>,
+        "events": Vec<Log>,
+    }
+);
+
+/// Implements [`RpcSchema`] for object types without describing their fields.
+macro_rules! impl_opaque_schemas {
+    ($($ty:ty => $name:literal,)+) => {
+        $(
+        impl RpcSchema for $ty {
+            fn schema(components: &mut SchemaComponents) -> Value {
+                components.reference($name, |_| json!({ "type": "object" }))
+            }
+        }
+        )+
+    };
+}
+
+impl_opaque_schemas!(
+    AllSnapshots => "AllSnapshots",
+    ApiCapabilities => "ApiCapabilities",
+    ApiFeeParams => "ApiFeeParams",
+    Block<TransactionVariant> => "Block",
+    BlockDetails => "BlockDetails",
+    BlockIdVariant => "BlockIdVariant",
+    CallRequest => "CallRequest",
+    DebugCall => "DebugCall",
+    DebugCallFlat => "DebugCallFlat",
+    EcosystemContracts => "EcosystemContracts",
+    EthSenderStatus => "EthSenderStatus",
+    en::ConsensusGenesis => "ConsensusGenesis",
+    en::SyncBlock => "SyncBlock",
+    FeeHistory => "FeeHistory",
+    FeeParams => "FeeParams",
+    Filter => "Filter",
+    FilterChanges => "FilterChanges",
+    GenesisConfig => "GenesisConfig",
+    InteropMessageProof => "InteropMessageProof",
+    L1BatchDetails => "L1BatchDetails",
+    L2BlockOrL1Batch => "L2BlockOrL1Batch",
+    Log => "Log",
+    OperatorAuditLogEntry => "OperatorAuditLogEntry",
+    Proof => "Proof",
+    ProtocolVersion => "ProtocolVersion",
+    PubdataIndependentBatchFeeModelInput => "PubdataIndependentBatchFeeModelInput",
+    PubSubFilter => "PubSubFilter",
+    ResultDebugCall => "ResultDebugCall",
+    SnapshotHeader => "SnapshotHeader",
+    SyncState => "SyncState",
+    TokenInfo => "TokenInfo",
+    TracerConfig => "TracerConfig",
+    Transaction => "Transaction",
+    TransactionDetails => "TransactionDetails",
+    TransactionReceipt => "TransactionReceipt",
+    VerificationKeysHashes => "VerificationKeysHashes",
+    zksync_types::Transaction => "L2Transaction",
+);
+
+/// Specification of a method param.
+#[derive(Debug, Clone, Copy)]
+pub struct ParamSpec {
+    name: &'static str,
+    schema: SchemaFn,
+    optional: bool,
+}
+
+impl ParamSpec {
+    /// Creates a param specification with the schema derived from `T`.
+    pub fn new<T: RpcSchema>(name: &'static str) -> Self {
+        Self {
+            name,
+            schema: T::schema,
+            optional: T::is_optional(),
+        }
+    }
+}
+
+/// Specification of an RPC method.
+#[derive(Debug, Clone)]
+pub struct MethodSpec {
+    name: &'static str,
+    params: Vec<ParamSpec>,
+    result: SchemaFn,
+}
+
+impl MethodSpec {
+    /// Creates a method specification with the result schema derived from `R`.
+    pub fn new<R: RpcSchema>(name: &'static str, params: Vec<ParamSpec>) -> Self {
+        Self {
+            name,
+            params,
+            result: R::schema,
+        }
+    }
+
+    /// Returns the full method name, including the namespace prefix.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn to_json(&self, components: &mut SchemaComponents) -> Value {
+        let params: Vec<_> = self
+            .params
+            .iter()
+            .map(|param| {
+                json!({
+                    "name": param.name,
+                    "required": !param.optional,
+                    "schema": (param.schema)(components),
+                })
+            })
+            .collect();
+        json!({
+            "name": self.name,
+            "params": params,
+            "result": {
+                "name": "result",
+                "schema": (self.result)(components),
+            },
+        })
+    }
+}
+
+/// Output of a server trait method returning `RpcResult<Self::Value>`, either directly or as a future.
+#[doc(hidden)] // only used by `rpc_method_specs!`
+pub trait MethodOutput {
+    type Value;
+}
+
+impl<T> MethodOutput for RpcResult<T> {
+    type Value = T;
+}
+
+impl<T> MethodOutput for Pin<Box<dyn Future<Output = RpcResult<T>> + Send + '_>> {
+    type Value = T;
+}
+
+#[doc(hidden)] // only used by `rpc_method_specs!`
+pub fn assert_method_output<T, O: MethodOutput<Value = T>>(_output: O) {}
+
+/// Declares specifications of methods in a namespace, generating a `method_specs()` function.
+///
+/// Each method is mapped to the corresponding method of the `server` trait. Param and result types are checked
+/// against the trait method signature at compile time, so that specifications cannot diverge from the trait.
+/// Methods that cannot be checked this way (e.g., subscriptions) may be listed in the `unchecked` block.
+macro_rules! rpc_method_specs {
+    (
+        namespace = $namespace:literal;
+        server = $server:ident;
+        $($method:literal => $fn_name:ident($($param:ident: $param_ty:ty),* $(,)?) -> $ret:ty;)+
+        $(unchecked {
+            $($unchecked_method:literal($($unchecked_param:ident: $unchecked_param_ty:ty),* $(,)?)
+                -> $unchecked_ret:ty;)+
+        })?
+    ) => {
+        pub(crate) fn method_specs() -> Vec<$crate::openrpc::MethodSpec> {
+            vec![
+                $(
+                $crate::openrpc::MethodSpec::new::<$ret>(
+                    concat!($namespace, "_", $method),
+                    vec![$($crate::openrpc::ParamSpec::new::<$param_ty>(stringify!($param)),)*],
+                ),
+                )+
+                $($(
+                $crate::openrpc::MethodSpec::new::<$unchecked_ret>(
+                    concat!($namespace, "_", $unchecked_method),
+                    vec![$(
+                        $crate::openrpc::ParamSpec::new::<$unchecked_param_ty>(
+                            stringify!($unchecked_param),
+                        ),
+                    )*],
+                ),
+                )+)?
+            ]
+        }
+
+        /// Never called; only checks that method specifications match the server trait.
+        #[cfg(feature = "server")]
+        #[allow(dead_code)]
+        fn assert_method_signatures<S: $server>(server: &S) {
+            $(
+            let _ = |$($param: $param_ty),*| {
+                $crate::openrpc::assert_method_output::<$ret, _>(server.$fn_name($($param),*));
+            };
+            )+
+        }
+    };
+}
+
+pub(crate) use rpc_method_specs;
+
+/// OpenRPC document describing a set of Web3 API methods.
+#[derive(Debug)]
+pub struct OpenRpcDocument {
+    api_version: String,
+    methods: Vec<MethodSpec>,
+}
+
+impl OpenRpcDocument {
+    /// Creates a document for the specified methods (e.g., methods registered by the server).
+    /// Also returns names of methods that don't have a specification and thus are not included in the document.
+    pub fn new<'a>(
+        api_version: &str,
+        method_names: impl IntoIterator<Item = &'a str>,
+    ) -> (Self, Vec<&'a str>) {
+        let mut all_specs: HashMap<_, _> = crate::namespaces::method_specs()
+            .map(|spec| (spec.name, spec))
+            .collect();
+        let mut methods = vec![];
+        let mut undocumented_methods = vec![];
+        for name in method_names {
+            if let Some(spec) = all_specs.remove(name) {
+                methods.push(spec);
+            } else if name != DISCOVER_METHOD {
+                undocumented_methods.push(name);
+            }
+        }
+        methods.sort_unstable_by_key(|spec| spec.name);
+
+        let this = Self {
+            api_version: api_version.to_owned(),
+            methods,
+        };
+        (this, undocumented_methods)
+    }
+
+    /// Returns specifications of all documented methods, sorted by name.
+    pub fn methods(&self) -> &[MethodSpec] {
+        &self.methods
+    }
+
+    /// Serializes this document to JSON.
+    pub fn to_json(&self) -> Value {
+        let mut components = SchemaComponents::default();
+        let methods: Vec<_> = self
+            .methods
+            .iter()
+            .map(|spec| spec.to_json(&mut components))
+            .collect();
+        json!({
+            "openrpc": OPENRPC_VERSION,
+            "info": {
+                "title": "zkSync Era Web3 API",
+                "version": self.api_version,
+            },
+            "methods": methods,
+            "components": {
+                "schemas": components.0,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use serde::Serialize;
+
+    use super::*;
+
+    /// Checks that the object schema lists exactly the fields produced by serialization, and that all required fields
+    /// are serialized. Optional fields in `value` should be set so that they are serialized.
+    fn assert_object_schema<T: RpcSchema + Serialize>(value: &T) {
+        let mut components = SchemaComponents::default();
+        let schema_ref = T::schema(&mut components);
+        let name = schema_ref["$ref"]
+            .as_str()
+            .unwrap()
+            .strip_prefix("#/components/schemas/")
+            .unwrap();
+        let schema = &components.0[name];
+        let schema_fields: HashSet<_> = schema["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+
+        let serialized = serde_json::to_value(value).unwrap();
+        let serialized_fields: HashSet<_> =
+            serialized.as_object().unwrap().keys().cloned().collect();
+        assert_eq!(schema_fields, serialized_fields, "{name}");
+
+        for required_field in schema["required"].as_array().unwrap() {
+            let required_field = required_field.as_str().unwrap();
+            assert!(
+                serialized_fields.contains(required_field),
+                "{name}.{required_field}"
+            );
+        }
+    }
+
+    fn assert_enum_schema<T: RpcSchema + Serialize>(variants: &[T]) {
+        let mut components = SchemaComponents::default();
+        let schema_ref = T::schema(&mut components);
+        let name = schema_ref["$ref"]
+            .as_str()
+            .unwrap()
+            .strip_prefix("#/components/schemas/")
+            .unwrap();
+        let serialized: Vec<_> = variants
+            .iter()
+            .map(|variant| serde_json::to_value(variant).unwrap())
+            .collect();
+        assert_eq!(
+            components.0[name]["enum"],
+            Value::Array(serialized),
+            "{name}"
+        );
+    }
+
+    fn required_fields<T: RpcSchema>() -> Value {
+        let mut components = SchemaComponents::default();
+        let schema_ref = T::schema(&mut components);
+        let name = schema_ref["$ref"]
+            .as_str()
+            .unwrap()
+            .strip_prefix("#/components/schemas/")
+            .unwrap();
+        components.0[name]["required"].clone()
+    }
+
+    #[test]
+    fn object_schemas_match_serialization() {
+        assert_object_schema(&Fee::default());
+        assert_object_schema(&Token {
+            l1_address: Address::repeat_byte(1),
+            l2_address: Address::repeat_byte(2),
+            name: "Ether".to_owned(),
+            symbol: "ETH".to_owned(),
+            decimals: 18,
+        });
+        assert_object_schema(&BridgeAddresses {
+            l1_shared_default_bridge: None,
+            l2_shared_default_bridge: None,
+            l1_erc20_default_bridge: None,
+            l2_erc20_default_bridge: None,
+            l1_weth_bridge: None,
+            l2_weth_bridge: None,
+        });
+        assert_object_schema(&L2ToL1LogProof {
+            proof: vec![H256::zero()],
+            id: 0,
+            root: H256::zero(),
+        });
+        assert_object_schema(&BatchFeeInputParams::unscaled(
+            FeeParams::sensible_v1_default(),
+        ));
+
+        let priority_op = PriorityOpDetails {
+            serial_id: PriorityOpId(1),
+            tx_hash: H256::repeat_byte(1),
+            sender: Address::repeat_byte(2),
+            l1_block_number: Some(L1BlockNumber(10)),
+            received_at: DateTime::default(),
+            status: PriorityOpStatus::Pending,
+            l2_block_number: Some(L2BlockNumber(5)),
+            l1_batch_number: Some(L1BatchNumber(1)),
+            error: Some("error".to_owned()),
+            queue_position: Some(0),
+            estimated_inclusion_at: Some(DateTime::default()),
+        };
+        assert_object_schema(&priority_op);
+        assert_object_schema(&PriorityQueueInfo {
+            next_expected_priority_op_id: Some(PriorityOpId(1)),
+            pending_ops_count: 1,
+            oldest_pending_op_received_at: Some(DateTime::default()),
+            average_inclusion_delay_ms: Some(100),
+            has_gap: false,
+            pending_ops: vec![priority_op],
+        });
+
+        let pending_tx = PendingTransactionInfo {
+            hash: H256::repeat_byte(1),
+            nonce: 1.into(),
+            max_fee_per_gas: 100.into(),
+            max_priority_fee_per_gas: 10.into(),
+        };
+        assert_object_schema(&pending_tx);
+        assert_object_schema(&AccountNonceInfo {
+            committed_nonce: 1.into(),
+            next_nonce: 2.into(),
+            pending_transactions: vec![pending_tx],
+        });
+        assert_object_schema(&RejectedTransaction {
+            hash: H256::repeat_byte(1),
+            initiator_address: Address::repeat_byte(2),
+            nonce: 0.into(),
+            error_code: "max-fee-per-gas-too-low".to_owned(),
+            reason: "fee too low".to_owned(),
+            rejected_at: DateTime::default(),
+        });
+
+        let deadline = TransactionDeadline {
+            not_after_l2_block: Some(L2BlockNumber(10)),
+            not_after_timestamp: Some(1_000),
+        };
+        assert_object_schema(&deadline);
+        assert_object_schema(&TransactionExpiry {
+            hash: H256::repeat_byte(1),
+            deadline,
+            status: TransactionExpiryStatus::Expired,
+            expired_at: Some(DateTime::default()),
+        });
+        assert_object_schema(&ForwardedTransaction {
+            hash: H256::repeat_byte(1),
+            status: ForwardedTransactionStatus::Rejected,
+            attempts: 2,
+            last_error: Some("error".to_owned()),
+            received_at: DateTime::default(),
+            forwarded_at: Some(DateTime::default()),
+        });
+        assert_object_schema(&InternalTransfer {
+            from: Address::repeat_byte(1),
+            to: Address::repeat_byte(2),
+            value: 1.into(),
+        });
+        assert_object_schema(&ProtocolVersionHistoryEntry {
+            minor_version: 24,
+            timestamp: 0,
+            activation_l1_batch: Some(L1BatchNumber(1)),
+            activation_l2_block: Some(L2BlockNumber(1)),
+            bootloader_code_hash: H256::repeat_byte(1),
+            default_account_code_hash: H256::repeat_byte(2),
+            verifier_address: Some(Address::repeat_byte(3)),
+            l2_system_upgrade_tx_hash: Some(H256::repeat_byte(4)),
+        });
+        assert_object_schema(&L1ToL2ExecutionSimulation {
+            success: false,
+            gas_limit: 1_000_000.into(),
+            max_fee_per_gas: 100.into(),
+            gas_used: 500_000.into(),
+            gas_refunded: 500_000.into(),
+            refund_recipient: Address::repeat_byte(1),
+            refund_amount: 1.into(),
+            output: Bytes::default(),
+            revert_reason: Some("reverted".to_owned()),
+            events: vec![],
+        });
+        let storage_log = ApiStorageLog {
+            address: Address::repeat_byte(1),
+            key: 1.into(),
+            written_value: 2.into(),
+        };
+        assert_object_schema(&storage_log);
+        assert_object_schema(&TransactionDetailedResult {
+            transaction_hash: H256::repeat_byte(1),
+            storage_logs: vec![storage_log],
+            events: vec![],
+        });
+    }
+
+    #[test]
+    fn enum_schemas_match_serialization() {
+        assert_enum_schema(&[
+            ForwardedTransactionStatus::Queued,
+            ForwardedTransactionStatus::Forwarded,
+            ForwardedTransactionStatus::Rejected,
+            ForwardedTransactionStatus::Dropped,
+        ]);
+        assert_enum_schema(&[
+            PriorityOpStatus::Pending,
+            PriorityOpStatus::Included,
+            PriorityOpStatus::Committed,
+            PriorityOpStatus::Proven,
+            PriorityOpStatus::Executed,
+        ]);
+        assert_enum_schema(&[
+            TransactionExpiryStatus::Pending,
+            TransactionExpiryStatus::Included,
+            TransactionExpiryStatus::Expired,
+            TransactionExpiryStatus::Dropped,
+        ]);
+    }
+
+    #[test]
+    fn optional_fields_are_not_required() {
+        assert_eq!(required_fields::<BridgeAddresses>(), json!([]));
+        assert_eq!(
+            required_fields::<TransactionExpiry>(),
+            json!(["hash", "deadline", "status"])
+        );
+        assert_eq!(
+            required_fields::<L2ToL1LogProof>(),
+            json!(["proof", "id", "root"])
+        );
+    }
+
+    #[test]
+    fn method_specs_are_unique() {
+        let mut names = HashSet::new();
+        for spec in crate::namespaces::method_specs() {
+            assert!(names.insert(spec.name), "duplicate spec for {}", spec.name);
+        }
+    }
+
+    #[test]
+    fn generating_document() {
+        let method_names = [
+            "eth_getBalance",
+            "zks_estimateFee",
+            "test_unknown",
+            DISCOVER_METHOD,
+        ];
+        let (document, undocumented) = OpenRpcDocument::new("1.0.0", method_names);
+        assert_eq!(undocumented, ["test_unknown"]);
+        let names: Vec<_> = document.methods().iter().map(MethodSpec::name).collect();
+        assert_eq!(names, ["eth_getBalance", "zks_estimateFee"]);
+
+        let document = document.to_json();
+        assert_eq!(document["openrpc"], OPENRPC_VERSION);
+        let get_balance = &document["methods"][0];
+        assert_eq!(get_balance["params"][0]["name"], "address");
+        assert_eq!(get_balance["params"][0]["required"], true);
+        assert_eq!(get_balance["params"][1]["required"], false);
+        assert_eq!(get_balance["result"]["schema"]["pattern"], QUANTITY_PATTERN);
+        let estimate_fee = &document["methods"][1];
+        assert_eq!(
+            estimate_fee["result"]["schema"]["$ref"],
+            "#/components/schemas/Fee"
+        );
+        let schemas = document["components"]["schemas"].as_object().unwrap();
+        assert!(schemas.contains_key("Fee"));
+        assert!(schemas.contains_key("CallRequest"));
+    }
+}
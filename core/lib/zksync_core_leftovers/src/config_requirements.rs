@@ -0,0 +1,360 @@
+//! Resolution of config sections required by the selected [`Component`]s.
+//!
+//! Each component declares the minimal set of config sections it needs, so that the server
+//! can be started with a partial config (e.g., only with the sections required by the API servers)
+//! and report precisely which sections are missing otherwise.
+
+use std::fmt;
+
+use zksync_config::configs::{
+    consensus::ConsensusConfig, wallets::Wallets, GeneralConfig, Secrets,
+};
+
+use crate::Component;
+
+/// Config section that may be required by a component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RequiredConfig {
+    Postgres,
+    Api,
+    Prometheus,
+    Db,
+    CircuitBreaker,
+    ContractVerifier,
+    Mempool,
+    OperationsManager,
+    StateKeeper,
+    HouseKeeper,
+    Prover,
+    WitnessGenerator,
+    ProverGroup,
+    ProofCompressor,
+    ProofDataHandler,
+    EthSender,
+    EthGasAdjuster,
+    EthWatcher,
+    CoreObjectStore,
+    ProtectiveReadsWriter,
+    BasicWitnessInputProducer,
+    Consensus,
+    DatabaseSecrets,
+    L1Secrets,
+    ConsensusSecrets,
+    EthSenderWallets,
+    StateKeeperWallets,
+}
+
+impl RequiredConfig {
+    /// Config sections required regardless of the selected components.
+    pub const COMMON: &'static [Self] = &[
+        Self::Postgres,
+        Self::Api,
+        Self::Prometheus,
+        Self::DatabaseSecrets,
+    ];
+
+    /// Returns the key of this section as used in YAML configs.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Postgres => "postgres",
+            Self::Api => "api",
+            Self::Prometheus => "prometheus",
+            Self::Db => "db",
+            Self::CircuitBreaker => "circuit_breaker",
+            Self::ContractVerifier => "contract_verifier",
+            Self::Mempool => "mempool",
+            Self::OperationsManager => "operations_manager",
+            Self::StateKeeper => "state_keeper",
+            Self::HouseKeeper => "house_keeper",
+            Self::Prover => "prover",
+            Self::WitnessGenerator => "witness_generator",
+            Self::ProverGroup => "prover_group",
+            Self::ProofCompressor => "proof_compressor",
+            Self::ProofDataHandler => "data_handler",
+            Self::EthSender => "eth.sender",
+            Self::EthGasAdjuster => "eth.gas_adjuster",
+            Self::EthWatcher => "eth.watcher",
+            Self::CoreObjectStore => "core_object_store",
+            Self::ProtectiveReadsWriter => "protective_reads_writer",
+            Self::BasicWitnessInputProducer => "basic_witness_input_producer",
+            Self::Consensus => "consensus",
+            Self::DatabaseSecrets => "secrets.database",
+            Self::L1Secrets => "secrets.l1",
+            Self::ConsensusSecrets => "secrets.consensus",
+            Self::EthSenderWallets => "wallets.eth_sender",
+            Self::StateKeeperWallets => "wallets.state_keeper",
+        }
+    }
+
+    fn is_present(self, configs: &LoadedConfigs<'_>) -> bool {
+        let general = configs.general;
+        let eth = general.eth.as_ref();
+        match self {
+            Self::Postgres => general.postgres_config.is_some(),
+            Self::Api => general.api_config.is_some(),
+            Self::Prometheus => general.prometheus_config.is_some(),
+            Self::Db => general.db_config.is_some(),
+            Self::CircuitBreaker => general.circuit_breaker_config.is_some(),
+            Self::ContractVerifier => general.contract_verifier.is_some(),
+            Self::Mempool => general.mempool_config.is_some(),
+            Self::OperationsManager => general.operations_manager_config.is_some(),
+            Self::StateKeeper => general.state_keeper_config.is_some(),
+            Self::HouseKeeper => general.house_keeper_config.is_some(),
+            Self::Prover => general.prover_config.is_some(),
+            Self::WitnessGenerator => general.witness_generator.is_some(),
+            Self::ProverGroup => general.prover_group_config.is_some(),
+            Self::ProofCompressor => general.proof_compressor_config.is_some(),
+            Self::ProofDataHandler => general.proof_data_handler_config.is_some(),
+            Self::EthSender => eth.map_or(false, |eth| eth.sender.is_some()),
+            Self::EthGasAdjuster => eth.map_or(false, |eth| eth.gas_adjuster.is_some()),
+            Self::EthWatcher => eth.map_or(false, |eth| eth.watcher.is_some()),
+            Self::CoreObjectStore => general.core_object_store.is_some(),
+            Self::ProtectiveReadsWriter => general.protective_reads_writer_config.is_some(),
+            Self::BasicWitnessInputProducer => {
+                general.basic_witness_input_producer_config.is_some()
+            }
+            Self::Consensus => configs.consensus.is_some(),
+            Self::DatabaseSecrets => configs.secrets.database.is_some(),
+            Self::L1Secrets => configs.secrets.l1.is_some(),
+            Self::ConsensusSecrets => configs.secrets.consensus.is_some(),
+            Self::EthSenderWallets => configs.wallets.eth_sender.is_some(),
+            Self::StateKeeperWallets => configs.wallets.state_keeper.is_some(),
+        }
+    }
+}
+
+impl fmt::Display for RequiredConfig {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.key())
+    }
+}
+
+impl Component {
+    /// Returns the name of this component as accepted by the `--components` CLI arg.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::HttpApi => "http_api",
+            Self::WsApi => "ws_api",
+            Self::ContractVerificationApi => "contract_verification_api",
+            Self::Tree => "tree",
+            Self::TreeApi => "tree_api",
+            Self::EthWatcher => "eth_watcher",
+            Self::EthTxAggregator => "eth_tx_aggregator",
+            Self::EthTxManager => "eth_tx_manager",
+            Self::StateKeeper => "state_keeper",
+            Self::TeeVerifierInputProducer => "tee_verifier_input_producer",
+            Self::Housekeeper => "housekeeper",
+            Self::ProofDataHandler => "proof_data_handler",
+            Self::Consensus => "consensus",
+            Self::CommitmentGenerator => "commitment_generator",
+            Self::VmRunnerProtectiveReads => "vm_runner_protective_reads",
+            Self::VmRunnerBwip => "vm_runner_bwip",
+        }
+    }
+
+    /// Returns config sections required by this component in addition to [`RequiredConfig::COMMON`] ones.
+    ///
+    /// The Merkle tree additionally requires [`RequiredConfig::CoreObjectStore`] if it runs in the full mode;
+    /// this is checked when the tree is initialized.
+    pub fn required_configs(self) -> &'static [RequiredConfig] {
+        use RequiredConfig as C;
+
+        match self {
+            Self::HttpApi | Self::WsApi => &[
+                C::StateKeeper,
+                C::StateKeeperWallets,
+                C::CircuitBreaker,
+                C::EthSender,
+                C::EthGasAdjuster,
+                C::L1Secrets,
+            ],
+            Self::ContractVerificationApi => &[
+                C::ContractVerifier,
+                C::StateKeeper,
+                C::StateKeeperWallets,
+                C::CircuitBreaker,
+            ],
+            Self::Tree => &[C::Db, C::OperationsManager],
+            Self::TreeApi => &[],
+            Self::EthWatcher => &[C::EthWatcher, C::L1Secrets],
+            Self::EthTxAggregator => &[
+                C::EthSender,
+                C::EthGasAdjuster,
+                C::EthSenderWallets,
+                C::L1Secrets,
+                C::CircuitBreaker,
+                C::CoreObjectStore,
+            ],
+            Self::EthTxManager => &[
+                C::StateKeeper,
+                C::EthSender,
+                C::EthGasAdjuster,
+                C::EthSenderWallets,
+                C::L1Secrets,
+                C::CircuitBreaker,
+            ],
+            Self::StateKeeper => &[
+                C::Db,
+                C::StateKeeper,
+                C::Mempool,
+                C::StateKeeperWallets,
+                C::EthSender,
+                C::EthGasAdjuster,
+                C::L1Secrets,
+            ],
+            Self::TeeVerifierInputProducer => &[C::CoreObjectStore],
+            Self::Housekeeper => &[
+                C::HouseKeeper,
+                C::Prover,
+                C::WitnessGenerator,
+                C::ProverGroup,
+                C::ProofCompressor,
+            ],
+            Self::ProofDataHandler => &[C::ProofDataHandler, C::CoreObjectStore],
+            Self::Consensus => &[C::Consensus, C::ConsensusSecrets],
+            Self::CommitmentGenerator => &[],
+            Self::VmRunnerProtectiveReads => &[C::ProtectiveReadsWriter],
+            Self::VmRunnerBwip => &[C::BasicWitnessInputProducer, C::CoreObjectStore],
+        }
+    }
+}
+
+/// Checks whether any of the `components` requires the specified config section.
+pub fn is_config_required(components: &[Component], config: RequiredConfig) -> bool {
+    RequiredConfig::COMMON.contains(&config)
+        || components
+            .iter()
+            .any(|component| component.required_configs().contains(&config))
+}
+
+/// References to all configs loaded by the server.
+#[derive(Debug, Clone, Copy)]
+pub struct LoadedConfigs<'a> {
+    pub general: &'a GeneralConfig,
+    pub secrets: &'a Secrets,
+    pub wallets: &'a Wallets,
+    pub consensus: Option<&'a ConsensusConfig>,
+}
+
+impl LoadedConfigs<'_> {
+    /// Checks that all config sections required by the `components` are present.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error listing missing config keys for each of the affected components.
+    pub fn check_required(&self, components: &[Component]) -> anyhow::Result<()> {
+        let missing_common = self.missing(RequiredConfig::COMMON);
+        let mut missing_by_component: Vec<(Component, Vec<RequiredConfig>)> = vec![];
+        for &component in components {
+            if missing_by_component.iter().any(|(c, _)| *c == component) {
+                continue;
+            }
+            let missing = self.missing(component.required_configs());
+            if !missing.is_empty() {
+                missing_by_component.push((component, missing));
+            }
+        }
+
+        if missing_common.is_empty() && missing_by_component.is_empty() {
+            return Ok(());
+        }
+
+        let mut message =
+            "some configs required by the selected components are missing:".to_owned();
+        if !missing_common.is_empty() {
+            message += &format!("\n- all components: {}", join_keys(&missing_common));
+        }
+        for (component, missing) in &missing_by_component {
+            message += &format!("\n- {}: {}", component.name(), join_keys(missing));
+        }
+        Err(anyhow::anyhow!(message))
+    }
+
+    fn missing(&self, configs: &[RequiredConfig]) -> Vec<RequiredConfig> {
+        configs
+            .iter()
+            .copied()
+            .filter(|config| !config.is_present(self))
+            .collect()
+    }
+}
+
+fn join_keys(configs: &[RequiredConfig]) -> String {
+    let keys: Vec<_> = configs.iter().map(|config| config.key()).collect();
+    keys.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use zksync_config::configs::DatabaseSecrets;
+
+    use super::*;
+    use crate::{temp_config_store::TempConfigStore, Components};
+
+    #[test]
+    fn component_names_roundtrip() {
+        let all_components = [
+            Component::HttpApi,
+            Component::WsApi,
+            Component::ContractVerificationApi,
+            Component::Tree,
+            Component::TreeApi,
+            Component::EthWatcher,
+            Component::EthTxAggregator,
+            Component::EthTxManager,
+            Component::StateKeeper,
+            Component::TeeVerifierInputProducer,
+            Component::Housekeeper,
+            Component::ProofDataHandler,
+            Component::Consensus,
+            Component::CommitmentGenerator,
+            Component::VmRunnerProtectiveReads,
+            Component::VmRunnerBwip,
+        ];
+        for component in all_components {
+            let parsed = Components::from_str(component.name()).unwrap();
+            assert_eq!(parsed.0, [component]);
+        }
+    }
+
+    #[test]
+    fn checking_required_configs() {
+        let general = TempConfigStore::default().general();
+        let secrets = Secrets {
+            consensus: None,
+            database: Some(DatabaseSecrets {
+                server_url: None,
+                prover_url: None,
+                server_replica_url: None,
+            }),
+            l1: None,
+        };
+        let wallets = Wallets::for_tests();
+        let configs = LoadedConfigs {
+            general: &general,
+            secrets: &secrets,
+            wallets: &wallets,
+            consensus: None,
+        };
+
+        let err = configs
+            .check_required(&[
+                Component::ContractVerificationApi,
+                Component::Tree,
+                Component::CommitmentGenerator,
+            ])
+            .unwrap_err()
+            .to_string();
+        let expected_lines = [
+            "- all components: postgres, api, prometheus",
+            "- contract_verification_api: contract_verifier, state_keeper, circuit_breaker",
+            "- tree: db, operations_manager",
+        ];
+        for line in expected_lines {
+            assert!(err.lines().any(|err_line| err_line == line), "{err}");
+        }
+        assert!(!err.contains("commitment_generator"), "{err}");
+    }
+}
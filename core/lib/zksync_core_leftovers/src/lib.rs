@@ -16,7 +16,7 @@ use tokio::{
 };
 use zksync_circuit_breaker::{
     l1_txs::FailedL1TransactionChecker, replication_lag::ReplicationLagChecker,
-    CircuitBreakerChecker, CircuitBreakers,
+    supply_invariant::SupplyInvariantChecker, CircuitBreakerChecker, CircuitBreakers,
 };
 use zksync_commitment_generator::{
     validation_task::L1BatchCommitmentModeValidationTask, CommitmentGenerator,
@@ -34,15 +34,24 @@ use zksync_config::{
     },
     ApiConfig, DBConfig, EthWatchConfig, GenesisConfig,
 };
-use zksync_contracts::governance_contract;
-use zksync_dal::{metrics::PostgresMetrics, ConnectionPool, Core, CoreDal};
+use zksync_contracts::{governance_contract, BaseSystemContracts};
+use zksync_dal::{
+    metrics::PostgresMetrics, transactions_dal::CallTraceFormat, ConnectionPool, Core, CoreDal,
+};
 use zksync_db_connection::healthcheck::ConnectionPoolHealthCheck;
 use zksync_eth_client::{clients::PKSigningClient, BoundEthInterface};
-use zksync_eth_sender::{Aggregator, EthTxAggregator, EthTxManager};
+use zksync_eth_sender::{
+    Aggregator, Erc2771ForwarderSubmission, EthTxAggregator, EthTxManager, WithdrawalLimiter,
+};
 use zksync_eth_watch::{EthHttpQueryClient, EthWatch};
+use zksync_feature_flags::FeatureFlagsUpdater;
 use zksync_health_check::{AppHealthCheck, HealthStatus, ReactiveHealthCheck};
 use zksync_house_keeper::{
     blocks_state_reporter::L1BatchMetricsReporter,
+    call_traces_migrator::CallTracesMigrator,
+    disk_space_monitor::DiskSpaceMonitor,
+    eth_txs_history_archiver::EthTxsHistoryArchiver,
+    l2_block_partitions_maintainer::L2BlockPartitionsMaintainer,
     periodic_job::PeriodicJob,
     prover::{
         FriGpuProverArchiver, FriProofCompressorJobRetryManager, FriProofCompressorQueueReporter,
@@ -50,6 +59,7 @@ use zksync_house_keeper::{
         FriWitnessGeneratorJobRetryManager, FriWitnessGeneratorQueueReporter,
         WaitingToQueuedFriWitnessJobMover,
     },
+    rejected_txs_pruner::RejectedTransactionsPruner,
 };
 use zksync_metadata_calculator::{
     api_server::TreeApiHttpClient, MetadataCalculator, MetadataCalculatorConfig,
@@ -73,15 +83,25 @@ use zksync_state_keeper::{
     TreeWritesPersistence,
 };
 use zksync_tee_verifier_input_producer::TeeVerifierInputProducer;
-use zksync_types::{ethabi::Contract, fee_model::FeeModelConfig, Address, L2ChainId};
+use zksync_types::{
+    ethabi::Contract, fee_model::FeeModelConfig, system_contracts::get_system_smart_contracts,
+    Address, L2ChainId, H256,
+};
 use zksync_web3_decl::client::{Client, DynClient, L1};
 
+use crate::config_requirements::{is_config_required, LoadedConfigs, RequiredConfig};
+
+pub mod config_requirements;
+pub mod preflight;
 pub mod temp_config_store;
 
 /// Inserts the initial information about zkSync tokens into the database.
+/// Performs genesis. If `base_system_contracts` are provided, they are used instead of the contracts
+/// in the workspace.
 pub async fn genesis_init(
     genesis_config: GenesisConfig,
     database_secrets: &DatabaseSecrets,
+    base_system_contracts: Option<BaseSystemContracts>,
 ) -> anyhow::Result<()> {
     let db_url = database_secrets.master_url()?;
     let pool = ConnectionPool::<Core>::singleton(db_url)
@@ -90,12 +110,56 @@ pub async fn genesis_init(
         .context("failed to build connection_pool")?;
     let mut storage = pool.connection().await.context("connection()")?;
 
-    let params = GenesisParams::load_genesis_params(genesis_config)?;
+    let params = match base_system_contracts {
+        Some(contracts) => GenesisParams::from_genesis_config(
+            genesis_config,
+            contracts,
+            get_system_smart_contracts(),
+        )?,
+        None => GenesisParams::load_genesis_params(genesis_config)?,
+    };
     ensure_genesis_state(&mut storage, &params).await?;
 
     Ok(())
 }
 
+/// Checks that the provided base system contracts (e.g., overridden ones loaded from a directory)
+/// match the latest protocol version stored in Postgres.
+pub async fn validate_base_system_contracts(
+    database_secrets: &DatabaseSecrets,
+    base_system_contracts: &BaseSystemContracts,
+) -> anyhow::Result<()> {
+    let db_url = database_secrets.master_url()?;
+    let pool = ConnectionPool::<Core>::singleton(db_url)
+        .build()
+        .await
+        .context("failed to build connection_pool")?;
+    let mut storage = pool.connection().await.context("connection()")?;
+
+    let latest_version = storage
+        .protocol_versions_dal()
+        .latest_semantic_version()
+        .await?
+        .context("no protocol versions in Postgres")?;
+    let protocol_version = storage
+        .protocol_versions_dal()
+        .get_protocol_version_with_latest_patch(latest_version.minor)
+        .await?
+        .with_context(|| format!("protocol version {latest_version} is not found"))?;
+
+    let expected_hashes = protocol_version.base_system_contracts_hashes;
+    let actual_hashes = base_system_contracts.hashes();
+    anyhow::ensure!(
+        expected_hashes == actual_hashes,
+        "Base system contracts don't match protocol version {latest_version} stored in Postgres: \
+         expected {expected_hashes:?}, got {actual_hashes:?}; re-run genesis to use the updated contracts"
+    );
+    tracing::info!(
+        "Base system contracts match protocol version {latest_version}: {actual_hashes:?}"
+    );
+    Ok(())
+}
+
 pub async fn is_genesis_needed(database_secrets: &DatabaseSecrets) -> bool {
     let db_url = database_secrets.master_url().unwrap();
     let pool = ConnectionPool::<Core>::singleton(db_url)
@@ -156,6 +220,8 @@ pub enum Component {
     CommitmentGenerator,
     /// VM runner-based component that saves protective reads to Postgres.
     VmRunnerProtectiveReads,
+    /// VM runner-based component that saves basic witness inputs to the object store.
+    VmRunnerBwip,
 }
 
 #[derive(Debug)]
@@ -171,6 +237,7 @@ impl FromStr for Components {
                 Component::WsApi,
                 Component::ContractVerificationApi,
             ])),
+            "web3_api" => Ok(Components(vec![Component::HttpApi, Component::WsApi])),
             "http_api" => Ok(Components(vec![Component::HttpApi])),
             "ws_api" => Ok(Components(vec![Component::WsApi])),
             "contract_verification_api" => Ok(Components(vec![Component::ContractVerificationApi])),
@@ -195,6 +262,7 @@ impl FromStr for Components {
             "vm_runner_protective_reads" => {
                 Ok(Components(vec![Component::VmRunnerProtectiveReads]))
             }
+            "vm_runner_bwip" => Ok(Components(vec![Component::VmRunnerBwip])),
             other => Err(format!("{} is not a valid component name", other)),
         }
     }
@@ -214,8 +282,15 @@ pub async fn initialize_components(
     HealthCheckHandle,
 )> {
     tracing::info!("Starting the components: {components:?}");
+    LoadedConfigs {
+        general: configs,
+        secrets,
+        wallets,
+        consensus: consensus_config.as_ref(),
+    }
+    .check_required(components)?;
+
     let l2_chain_id = genesis_config.l2_chain_id;
-    let db_config = configs.db_config.clone().context("db_config")?;
     let postgres_config = configs.postgres_config.clone().context("postgres_config")?;
     let database_secrets = secrets.database.clone().context("database_secrets")?;
 
@@ -257,44 +332,66 @@ pub async fn initialize_components(
         health_check_config.hard_time_limit(),
     ));
 
-    let eth = configs.eth.clone().context("eth")?;
-    let l1_secrets = secrets.l1.clone().context("l1_secrets")?;
-    let circuit_breaker_config = configs
-        .circuit_breaker_config
-        .clone()
-        .context("circuit_breaker_config")?;
+    // L1-related resources are only initialized if they are required by the selected components.
+    let query_client = if is_config_required(components, RequiredConfig::L1Secrets) {
+        let l1_secrets = secrets.l1.as_ref().context("l1_secrets")?;
+        let query_client = Client::http(l1_secrets.l1_rpc_url.clone())
+            .context("Ethereum client")?
+            .for_network(genesis_config.l1_chain_id.into())
+            .build();
+        Some(Box::new(query_client) as Box<DynClient<L1>>)
+    } else {
+        None
+    };
 
-    let circuit_breaker_checker = CircuitBreakerChecker::new(
-        Arc::new(
-            circuit_breakers_for_components(components, &database_secrets, &circuit_breaker_config)
+    let circuit_breaker_checker = if is_config_required(components, RequiredConfig::CircuitBreaker)
+    {
+        let circuit_breaker_config = configs
+            .circuit_breaker_config
+            .clone()
+            .context("circuit_breaker_config")?;
+        let circuit_breaker_checker = CircuitBreakerChecker::new(
+            Arc::new(
+                circuit_breakers_for_components(
+                    components,
+                    &database_secrets,
+                    &circuit_breaker_config,
+                    contracts_config,
+                    genesis_config.l2_chain_id,
+                    query_client.clone(),
+                )
                 .await
                 .context("circuit_breakers_for_components")?,
-        ),
-        circuit_breaker_config.sync_interval(),
-    );
-    circuit_breaker_checker.check().await.unwrap_or_else(|err| {
-        panic!("Circuit breaker triggered: {}", err);
-    });
+            ),
+            circuit_breaker_config.sync_interval(),
+        );
+        circuit_breaker_checker.check().await.unwrap_or_else(|err| {
+            panic!("Circuit breaker triggered: {}", err);
+        });
+        Some(circuit_breaker_checker)
+    } else {
+        None
+    };
 
-    let query_client = Client::http(l1_secrets.l1_rpc_url.clone())
-        .context("Ethereum client")?
-        .for_network(genesis_config.l1_chain_id.into())
-        .build();
-    let query_client = Box::new(query_client);
-    let gas_adjuster_config = eth.gas_adjuster.context("gas_adjuster")?;
-    let sender = eth.sender.as_ref().context("sender")?;
-
-    let mut gas_adjuster = GasAdjusterSingleton::new(
-        genesis_config.l1_chain_id,
-        l1_secrets.l1_rpc_url.clone(),
-        gas_adjuster_config,
-        sender.pubdata_sending_mode,
-        genesis_config.l1_batch_commit_data_generator_mode,
-    );
+    let mut gas_adjuster = if is_config_required(components, RequiredConfig::EthGasAdjuster) {
+        let eth = configs.eth.as_ref().context("eth")?;
+        let gas_adjuster_config = eth.gas_adjuster.clone().context("gas_adjuster")?;
+        let sender = eth.sender.as_ref().context("sender")?;
+        let l1_secrets = secrets.l1.as_ref().context("l1_secrets")?;
+        Some(GasAdjusterSingleton::new(
+            genesis_config.l1_chain_id,
+            l1_secrets.l1_rpc_url.clone(),
+            gas_adjuster_config,
+            sender.pubdata_sending_mode,
+            genesis_config.l1_batch_commit_data_generator_mode,
+        ))
+    } else {
+        None
+    };
 
     let (stop_sender, stop_receiver) = watch::channel(false);
 
-    // Prometheus exporter and circuit breaker checker should run for every component configuration.
+    // Prometheus exporter should run for every component configuration.
     let prom_config = configs
         .prometheus_config
         .clone()
@@ -312,10 +409,12 @@ pub async fn initialize_components(
         res
     });
 
-    let mut task_futures: Vec<JoinHandle<anyhow::Result<()>>> = vec![
-        prometheus_task,
-        tokio::spawn(circuit_breaker_checker.run(stop_receiver.clone())),
-    ];
+    let mut task_futures: Vec<JoinHandle<anyhow::Result<()>>> = vec![prometheus_task];
+    if let Some(circuit_breaker_checker) = circuit_breaker_checker {
+        task_futures.push(tokio::spawn(
+            circuit_breaker_checker.run(stop_receiver.clone()),
+        ));
+    }
 
     if components.contains(&Component::WsApi)
         || components.contains(&Component::HttpApi)
@@ -336,7 +435,8 @@ pub async fn initialize_components(
                 .fee_account
                 .address(),
             l2_chain_id,
-        );
+        )
+        .with_evm_emulator_hash(genesis_config.evm_emulator_hash);
         let internal_api_config =
             InternalApiConfig::new(&api_config.web3_json_rpc, contracts_config, genesis_config);
 
@@ -369,6 +469,8 @@ pub async fn initialize_components(
             let started_at = Instant::now();
             tracing::info!("Initializing HTTP API");
             let bounded_gas_adjuster = gas_adjuster
+                .as_mut()
+                .context("gas_adjuster")?
                 .get_or_init()
                 .await
                 .context("gas_adjuster.get_or_init()")?;
@@ -418,6 +520,8 @@ pub async fn initialize_components(
             let started_at = Instant::now();
             tracing::info!("initializing WS API");
             let bounded_gas_adjuster = gas_adjuster
+                .as_mut()
+                .context("gas_adjuster")?
                 .get_or_init()
                 .await
                 .context("gas_adjuster.get_or_init()")?;
@@ -471,16 +575,17 @@ pub async fn initialize_components(
         }
     }
 
-    let object_store_config = configs
+    let store_factory = configs
         .core_object_store
         .clone()
-        .context("core_object_store_config")?;
-    let store_factory = ObjectStoreFactory::new(object_store_config);
+        .map(ObjectStoreFactory::new);
 
     if components.contains(&Component::StateKeeper) {
         let started_at = Instant::now();
         tracing::info!("initializing State Keeper");
         let bounded_gas_adjuster = gas_adjuster
+            .as_mut()
+            .context("gas_adjuster")?
             .get_or_init()
             .await
             .context("gas_adjuster.get_or_init()")?;
@@ -488,6 +593,7 @@ pub async fn initialize_components(
             .state_keeper_config
             .clone()
             .context("state_keeper_config")?;
+        let db_config = configs.db_config.clone().context("db_config")?;
         let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
             bounded_gas_adjuster,
             FeeModelConfig::from_state_keeper_config(&state_keeper_config),
@@ -502,9 +608,11 @@ pub async fn initialize_components(
                 .clone()
                 .context("State keeper wallets")?,
             l2_chain_id,
+            genesis_config.evm_emulator_hash,
             &db_config,
             &configs.mempool_config.clone().context("mempool_config")?,
             batch_fee_input_provider,
+            &app_health,
             stop_receiver.clone(),
         )
         .await
@@ -573,7 +681,7 @@ pub async fn initialize_components(
             start_eth_watch(
                 eth_watch_config,
                 eth_watch_pool,
-                query_client.clone(),
+                query_client.clone().context("query_client")?,
                 diamond_proxy_addr,
                 state_transition_manager_addr,
                 governance,
@@ -595,8 +703,12 @@ pub async fn initialize_components(
             .await
             .context("failed to build eth_sender_pool")?;
 
+        let eth = configs.eth.clone().context("eth")?;
+        let query_client = query_client.clone().context("query_client")?;
         let eth_sender_wallets = wallets.eth_sender.clone().context("eth_sender")?;
-        let operator_private_key = eth_sender_wallets.operator.private_key();
+        let forwarder_address = eth.sender.as_ref().and_then(|s| s.l1_tx_forwarder_addr);
+        let operator_private_key =
+            l1_tx_signer_wallet(&eth_sender_wallets, forwarder_address)?.private_key();
         let diamond_proxy_addr = contracts_config.diamond_proxy_addr;
         let default_priority_fee_per_gas = eth
             .gas_adjuster
@@ -629,15 +741,31 @@ pub async fn initialize_components(
         let operator_blobs_address = eth_sender_wallets.blob_operator.map(|x| x.address());
 
         let sender_config = eth.sender.clone().context("eth_sender")?;
+        let mut aggregator = Aggregator::new(
+            sender_config.clone(),
+            store_factory
+                .as_ref()
+                .context("core_object_store")?
+                .create_store()
+                .await?,
+            operator_blobs_address.is_some(),
+            l1_batch_commit_data_generator_mode,
+        );
+        if let Some(withdrawal_limits) = eth.withdrawal_limits.clone() {
+            let l2_bridge_addresses = [
+                contracts_config.l2_shared_bridge_addr,
+                contracts_config.l2_erc20_bridge_addr,
+            ];
+            let l2_bridge_addresses = l2_bridge_addresses.into_iter().flatten().collect();
+            aggregator = aggregator.with_withdrawal_limiter(WithdrawalLimiter::new(
+                withdrawal_limits,
+                l2_bridge_addresses,
+            ));
+        }
         let eth_tx_aggregator_actor = EthTxAggregator::new(
             eth_sender_pool,
             sender_config.clone(),
-            Aggregator::new(
-                sender_config.clone(),
-                store_factory.create_store().await?,
-                operator_blobs_address.is_some(),
-                l1_batch_commit_data_generator_mode,
-            ),
+            aggregator,
             Box::new(eth_client),
             contracts_config.validator_timelock_addr,
             contracts_config.l1_multicall3_addr,
@@ -662,10 +790,16 @@ pub async fn initialize_components(
             .await
             .context("failed to build eth_manager_pool")?;
         let eth_sender = configs.eth.clone().context("eth_sender_config")?;
+        let query_client = query_client.clone().context("query_client")?;
         let eth_sender_wallets = wallets.eth_sender.clone().context("eth_sender")?;
-        let operator_private_key = eth_sender_wallets.operator.private_key();
+        let forwarder_address = eth_sender
+            .sender
+            .as_ref()
+            .and_then(|s| s.l1_tx_forwarder_addr);
+        let operator_private_key =
+            l1_tx_signer_wallet(&eth_sender_wallets, forwarder_address)?.private_key();
         let diamond_proxy_addr = contracts_config.diamond_proxy_addr;
-        let default_priority_fee_per_gas = eth
+        let default_priority_fee_per_gas = eth_sender
             .gas_adjuster
             .as_ref()
             .context("gas_adjuster")?
@@ -694,16 +828,28 @@ pub async fn initialize_components(
             None
         };
 
-        let eth_tx_manager_actor = EthTxManager::new(
+        let mut eth_tx_manager_actor = EthTxManager::new(
             eth_manager_pool,
             eth_sender.sender.clone().context("eth_sender")?,
             gas_adjuster
+                .as_mut()
+                .context("gas_adjuster")?
                 .get_or_init()
                 .await
                 .context("gas_adjuster.get_or_init()")?,
             Box::new(eth_client),
             eth_client_blobs,
         );
+        if let Some(forwarder_address) = forwarder_address {
+            let strategy = Erc2771ForwarderSubmission::new(
+                forwarder_address,
+                eth_sender_wallets.operator.private_key().clone(),
+                l1_chain_id,
+                vec![contracts_config.validator_timelock_addr],
+            );
+            eth_tx_manager_actor =
+                eth_tx_manager_actor.with_submission_strategy(Arc::new(strategy));
+        }
         task_futures.extend([tokio::spawn(
             eth_tx_manager_actor.run(stop_receiver.clone()),
         )]);
@@ -718,7 +864,7 @@ pub async fn initialize_components(
         &mut task_futures,
         &app_health,
         components,
-        &store_factory,
+        store_factory.as_ref(),
         stop_receiver.clone(),
     )
     .await
@@ -733,14 +879,26 @@ pub async fn initialize_components(
         add_tee_verifier_input_producer_to_task_futures(
             &mut task_futures,
             &singleton_connection_pool,
-            &store_factory,
+            store_factory.as_ref().context("core_object_store")?,
             l2_chain_id,
+            genesis_config.evm_emulator_hash,
             stop_receiver.clone(),
         )
         .await
         .context("add_tee_verifier_input_producer_to_task_futures()")?;
     }
 
+    if let Some(disk_space_monitor_config) = configs.disk_space_monitor_config.clone() {
+        let task = DiskSpaceMonitor::new(disk_space_monitor_config).run(stop_receiver.clone());
+        task_futures.push(tokio::spawn(task));
+    }
+
+    if let Some(feature_flags_config) = &configs.feature_flags_config {
+        let updater = FeatureFlagsUpdater::new(feature_flags_config);
+        app_health.insert_component(updater.health_check())?;
+        task_futures.push(tokio::spawn(updater.run(stop_receiver.clone())));
+    }
+
     if components.contains(&Component::Housekeeper) {
         add_house_keeper_to_task_futures(
             configs,
@@ -758,7 +916,11 @@ pub async fn initialize_components(
                 .proof_data_handler_config
                 .clone()
                 .context("proof_data_handler_config")?,
-            store_factory.create_store().await?,
+            store_factory
+                .as_ref()
+                .context("core_object_store")?
+                .create_store()
+                .await?,
             connection_pool.clone(),
             genesis_config.l1_batch_commit_data_generator_mode,
             stop_receiver.clone(),
@@ -788,13 +950,31 @@ pub async fn initialize_components(
     let health_check_handle =
         HealthCheckHandle::spawn_server(health_check_config.bind_addr(), app_health);
 
-    if let Some(task) = gas_adjuster.run_if_initialized(stop_receiver.clone()) {
+    if let Some(task) =
+        gas_adjuster.and_then(|gas_adjuster| gas_adjuster.run_if_initialized(stop_receiver.clone()))
+    {
         task_futures.push(task);
     }
 
     Ok((task_futures, stop_sender, health_check_handle))
 }
 
+/// Returns the wallet signing L1 transactions. If transactions are relayed via a forwarder, they are signed
+/// and paid for by the fee payer; otherwise, they are signed by the operator.
+fn l1_tx_signer_wallet(
+    eth_sender_wallets: &wallets::EthSender,
+    forwarder_address: Option<Address>,
+) -> anyhow::Result<&wallets::Wallet> {
+    if forwarder_address.is_some() {
+        eth_sender_wallets
+            .fee_payer
+            .as_ref()
+            .context("fee payer wallet is required if `l1_tx_forwarder_addr` is set")
+    } else {
+        Ok(&eth_sender_wallets.operator)
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn add_state_keeper_to_task_futures(
     task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
@@ -803,9 +983,11 @@ async fn add_state_keeper_to_task_futures(
     state_keeper_config: StateKeeperConfig,
     state_keeper_wallets: wallets::StateKeeper,
     l2chain_id: L2ChainId,
+    evm_emulator_hash: Option<H256>,
     db_config: &DBConfig,
     mempool_config: &MempoolConfig,
     batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
+    app_health: &AppHealthCheck,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     let state_keeper_pool = ConnectionPool::<Core>::singleton(database_secrets.master_url()?)
@@ -817,7 +999,7 @@ async fn add_state_keeper_to_task_futures(
             .connection()
             .await
             .context("Access storage to build mempool")?;
-        let mempool = MempoolGuard::from_storage(&mut storage, mempool_config.capacity).await;
+        let mempool = MempoolGuard::from_storage(&mut storage, mempool_config).await;
         mempool.register_metrics();
         mempool
     };
@@ -837,6 +1019,12 @@ async fn add_state_keeper_to_task_futures(
             .context("`l2_shared_bridge_addr` config is missing")?,
         state_keeper_config.l2_block_seal_queue_capacity,
     );
+    let call_trace_format = if state_keeper_config.flat_call_traces {
+        CallTraceFormat::Flat
+    } else {
+        CallTraceFormat::Legacy
+    };
+    let persistence = persistence.with_call_trace_format(call_trace_format);
     task_futures.push(tokio::spawn(l2_block_sealer.run()));
 
     // One (potentially held long-term) connection for `AsyncCatchupTask` and another connection
@@ -865,11 +1053,13 @@ async fn add_state_keeper_to_task_futures(
         state_keeper_wallets,
         async_cache,
         l2chain_id,
+        evm_emulator_hash,
         mempool_config,
         state_keeper_pool,
         mempool.clone(),
         batch_fee_input_provider.clone(),
         output_handler,
+        app_health,
         stop_receiver.clone(),
     )
     .await;
@@ -932,7 +1122,7 @@ async fn add_trees_to_task_futures(
     task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
     app_health: &AppHealthCheck,
     components: &[Component],
-    store_factory: &ObjectStoreFactory,
+    store_factory: Option<&ObjectStoreFactory>,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     if !components.contains(&Component::Tree) {
@@ -960,7 +1150,11 @@ async fn add_trees_to_task_futures(
 
     let object_store = match db_config.merkle_tree.mode {
         MerkleTreeMode::Lightweight => None,
-        MerkleTreeMode::Full => Some(store_factory.create_store().await?),
+        MerkleTreeMode::Full => {
+            let store_factory = store_factory
+                .context("core_object_store config is required for the full Merkle tree mode")?;
+            Some(store_factory.create_store().await?)
+        }
     };
 
     run_tree(
@@ -1042,6 +1236,7 @@ async fn add_tee_verifier_input_producer_to_task_futures(
     connection_pool: &ConnectionPool<Core>,
     store_factory: &ObjectStoreFactory,
     l2_chain_id: L2ChainId,
+    evm_emulator_hash: Option<H256>,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
     let started_at = Instant::now();
@@ -1051,7 +1246,8 @@ async fn add_tee_verifier_input_producer_to_task_futures(
         store_factory.create_store().await?,
         l2_chain_id,
     )
-    .await?;
+    .await?
+    .with_evm_emulator_hash(evm_emulator_hash);
     task_futures.push(tokio::spawn(producer.run(stop_receiver, None)));
     tracing::info!(
         "Initialized TeeVerifierInputProducer in {:?}",
@@ -1099,6 +1295,68 @@ async fn add_house_keeper_to_task_futures(
         connection_pool.clone(),
     );
 
+    if let Some((archiving_interval, archive_after)) =
+        house_keeper_config.eth_txs_history_archiver_params()
+    {
+        // Unlike other house keeper tasks, archiving modifies the Postgres state, so it needs the master pool.
+        let master_pool = ConnectionPool::<Core>::singleton(secrets.master_url()?)
+            .build()
+            .await
+            .context("failed to build a master connection pool")?;
+        let eth_txs_history_archiver = EthTxsHistoryArchiver::new(
+            master_pool,
+            archiving_interval,
+            archive_after,
+            house_keeper_config.eth_txs_history_archiver_archive_eth_txs_after_days,
+        );
+        let task = eth_txs_history_archiver.run(stop_receiver.clone());
+        task_futures.push(tokio::spawn(task));
+    }
+
+    if let Some((maintenance_interval, l2_blocks_per_partition)) =
+        house_keeper_config.l2_block_partitions_maintainer_params()
+    {
+        let master_pool = ConnectionPool::<Core>::singleton(secrets.master_url()?)
+            .build()
+            .await
+            .context("failed to build a master connection pool")?;
+        let l2_block_partitions_maintainer = L2BlockPartitionsMaintainer::new(
+            master_pool,
+            maintenance_interval,
+            l2_blocks_per_partition,
+        );
+        let task = l2_block_partitions_maintainer.run(stop_receiver.clone());
+        task_futures.push(tokio::spawn(task));
+    }
+
+    if let Some(migration_interval) = house_keeper_config.call_traces_migration_interval_ms {
+        let master_pool = ConnectionPool::<Core>::singleton(secrets.master_url()?)
+            .build()
+            .await
+            .context("failed to build a master connection pool")?;
+        let call_traces_migrator = CallTracesMigrator::new(master_pool, migration_interval);
+        let task = call_traces_migrator.run(stop_receiver.clone());
+        task_futures.push(tokio::spawn(task));
+    }
+
+    let rejected_txs_retention = configs
+        .api_config
+        .as_ref()
+        .and_then(|config| config.web3_json_rpc.rejected_txs_retention());
+    if let Some(retention) = rejected_txs_retention {
+        let master_pool = ConnectionPool::<Core>::singleton(secrets.master_url()?)
+            .build()
+            .await
+            .context("failed to build a master connection pool")?;
+        let rejected_txs_pruner = RejectedTransactionsPruner::new(
+            master_pool,
+            house_keeper_config.rejected_txs_pruning_interval_ms(),
+            retention,
+        );
+        let task = rejected_txs_pruner.run(stop_receiver.clone());
+        task_futures.push(tokio::spawn(task));
+    }
+
     let prover_connection_pool = ConnectionPool::<Prover>::builder(
         secrets.prover_url()?,
         postgres_config.max_connections()?,
@@ -1249,7 +1507,7 @@ async fn run_http_api(
         &api_config.web3_json_rpc,
         state_keeper_config,
         replica_connection_pool.clone(),
-        master_connection_pool,
+        master_connection_pool.clone(),
         batch_fee_model_input_provider,
         storage_caches,
     )
@@ -1260,6 +1518,9 @@ async fn run_http_api(
         namespaces.push(Namespace::Debug)
     }
     namespaces.push(Namespace::Snapshots);
+    if api_config.web3_json_rpc.admin_namespace_enabled {
+        namespaces.push(Namespace::Admin);
+    }
 
     let updaters_pool = ConnectionPool::<Core>::builder(database_secrets.replica_url()?, 2)
         .build()
@@ -1276,6 +1537,7 @@ async fn run_http_api(
             .with_tx_sender(tx_sender)
             .with_vm_barrier(vm_barrier)
             .with_mempool_cache(mempool_cache)
+            .with_master_pool(master_connection_pool)
             .enable_api_namespaces(namespaces);
     if let Some(tree_api_url) = api_config.web3_json_rpc.tree_api_url() {
         let tree_api = Arc::new(TreeApiHttpClient::new(tree_api_url));
@@ -1365,6 +1627,9 @@ async fn circuit_breakers_for_components(
     components: &[Component],
     database_secrets: &DatabaseSecrets,
     circuit_breaker_config: &CircuitBreakerConfig,
+    contracts_config: &ContractsConfig,
+    l2_chain_id: L2ChainId,
+    query_client: Option<Box<DynClient<L1>>>,
 ) -> anyhow::Result<CircuitBreakers> {
     let circuit_breakers = CircuitBreakers::default();
 
@@ -1381,6 +1646,34 @@ async fn circuit_breakers_for_components(
             .await;
     }
 
+    if let Some(action) = circuit_breaker_config.supply_invariant_action {
+        if components.contains(&Component::EthTxAggregator) {
+            let pool = ConnectionPool::<Core>::singleton(database_secrets.replica_url()?)
+                .build()
+                .await
+                .context("failed to build a connection pool")?;
+            let l1_shared_bridge_addr = contracts_config
+                .l1_shared_bridge_proxy_addr
+                .context("L1 shared bridge address is required to check supply invariant")?;
+            let base_token_addr = contracts_config
+                .base_token_addr
+                .context("base token address is required to check supply invariant")?;
+            let query_client =
+                query_client.context("L1 client is required to check supply invariant")?;
+            circuit_breakers
+                .insert(Box::new(SupplyInvariantChecker::new(
+                    pool,
+                    query_client,
+                    l1_shared_bridge_addr,
+                    base_token_addr,
+                    l2_chain_id,
+                    circuit_breaker_config.supply_invariant_tolerance(),
+                    action,
+                )))
+                .await;
+        }
+    }
+
     if components.iter().any(|c| {
         matches!(
             c,
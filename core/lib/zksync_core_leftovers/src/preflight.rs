@@ -0,0 +1,509 @@
+//! Preflight checks for the server environment.
+//!
+//! The checks validate the environment required by the selected [`Component`]s (Postgres, L1 RPC,
+//! object store, RocksDB directories and config / secret presence) before any component is started.
+//! All checks are run independently of each other, and their outcomes are collected into a single
+//! [`PreflightReport`], so that all issues can be fixed at once rather than discovered one by one at runtime.
+
+use std::{fmt, future::Future, path::Path, time::Duration};
+
+use anyhow::Context as _;
+use zksync_config::{ContractsConfig, GenesisConfig};
+use zksync_dal::{system_dal::MigrationsStatus, ConnectionPool, Core, CoreDal};
+use zksync_eth_client::{
+    clients::{Client, DynClient, L1},
+    CallFunctionArgs, EthInterface,
+};
+use zksync_object_store::{Bucket, ObjectStore, ObjectStoreError, ObjectStoreFactory};
+use zksync_types::Address;
+
+use crate::{
+    config_requirements::{is_config_required, LoadedConfigs, RequiredConfig},
+    Component,
+};
+
+/// Timeout for a single preflight check.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+/// Minimum free disk space for RocksDB directories; if there is less space, the check produces a warning.
+const MIN_FREE_DISK_SPACE: u64 = 1 << 30; // 1 GiB
+/// Key of the object read from the object store to check that it is accessible. The object is not expected to exist;
+/// the checks never write to the store.
+const OBJECT_STORE_CHECK_KEY: &str = "preflight_check.bin";
+
+/// Outcome of a single preflight check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckStatus {
+    /// The check has passed; the string contains details, e.g. the checked values.
+    Passed(String),
+    /// The check has passed, but has found an issue that does not prevent the server from starting.
+    Warning(String),
+    /// The check has failed.
+    Failed(String),
+    /// The check is not applicable to the selected components.
+    Skipped,
+}
+
+impl CheckStatus {
+    fn from_result(result: anyhow::Result<Self>) -> Self {
+        result.unwrap_or_else(|err| Self::Failed(format!("{err:#}")))
+    }
+}
+
+/// Result of a single preflight check.
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+}
+
+/// Consolidated report produced by [`PreflightChecks::run()`].
+#[derive(Debug, Clone, Default)]
+pub struct PreflightReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl PreflightReport {
+    /// Checks whether any of the checks has failed.
+    pub fn has_failures(&self) -> bool {
+        self.checks
+            .iter()
+            .any(|check| matches!(check.status, CheckStatus::Failed(_)))
+    }
+}
+
+impl fmt::Display for PreflightReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, check) in self.checks.iter().enumerate() {
+            if i > 0 {
+                writeln!(formatter)?;
+            }
+            let name = check.name;
+            match &check.status {
+                CheckStatus::Passed(details) => write!(formatter, "[OK] {name}: {details}")?,
+                CheckStatus::Warning(details) => write!(formatter, "[WARN] {name}: {details}")?,
+                CheckStatus::Failed(details) => write!(formatter, "[FAIL] {name}: {details}")?,
+                CheckStatus::Skipped => write!(
+                    formatter,
+                    "[SKIP] {name}: not required by selected components"
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Preflight checks for the server environment.
+#[derive(Debug)]
+pub struct PreflightChecks<'a> {
+    configs: LoadedConfigs<'a>,
+    genesis: &'a GenesisConfig,
+    contracts: &'a ContractsConfig,
+}
+
+impl<'a> PreflightChecks<'a> {
+    pub fn new(
+        configs: LoadedConfigs<'a>,
+        genesis: &'a GenesisConfig,
+        contracts: &'a ContractsConfig,
+    ) -> Self {
+        Self {
+            configs,
+            genesis,
+            contracts,
+        }
+    }
+
+    /// Runs all checks applicable to the `components`. Never returns an error; the failed checks
+    /// are recorded in the returned report instead.
+    pub async fn run(&self, components: &[Component]) -> PreflightReport {
+        let configs_status = match self.configs.check_required(components) {
+            Ok(()) => CheckStatus::Passed("all required configs and secrets are present".into()),
+            Err(err) => CheckStatus::Failed(format!("{err:#}")),
+        };
+
+        let (postgres_status, l1_status, object_store_status, rocksdb_status) = tokio::join!(
+            Self::run_check(self.check_postgres()),
+            Self::run_check_if(
+                is_config_required(components, RequiredConfig::L1Secrets),
+                self.check_l1()
+            ),
+            Self::run_check_if(
+                is_config_required(components, RequiredConfig::CoreObjectStore),
+                self.check_object_store()
+            ),
+            Self::run_check_if(
+                is_config_required(components, RequiredConfig::Db),
+                self.check_rocksdb_dirs()
+            ),
+        );
+
+        let checks = [
+            ("configs", configs_status),
+            ("postgres", postgres_status),
+            ("l1", l1_status),
+            ("object_store", object_store_status),
+            ("rocksdb", rocksdb_status),
+        ];
+        PreflightReport {
+            checks: checks
+                .into_iter()
+                .map(|(name, status)| CheckResult { name, status })
+                .collect(),
+        }
+    }
+
+    async fn run_check(check: impl Future<Output = anyhow::Result<CheckStatus>>) -> CheckStatus {
+        let result = tokio::time::timeout(CHECK_TIMEOUT, check)
+            .await
+            .unwrap_or_else(|_| Err(anyhow::anyhow!("timed out after {CHECK_TIMEOUT:?}")));
+        CheckStatus::from_result(result)
+    }
+
+    async fn run_check_if(
+        is_required: bool,
+        check: impl Future<Output = anyhow::Result<CheckStatus>>,
+    ) -> CheckStatus {
+        if is_required {
+            Self::run_check(check).await
+        } else {
+            CheckStatus::Skipped
+        }
+    }
+
+    async fn check_postgres(&self) -> anyhow::Result<CheckStatus> {
+        let database_secrets = self
+            .configs
+            .secrets
+            .database
+            .as_ref()
+            .context("database secrets are missing")?;
+        let pool = ConnectionPool::<Core>::singleton(database_secrets.master_url()?)
+            .build()
+            .await
+            .context("failed building connection pool")?;
+        let mut storage = pool
+            .connection()
+            .await
+            .context("failed connecting to Postgres")?;
+        let status =
+            storage.system_dal().get_migrations_status().await.context(
+                "failed getting migrations status; were migrations run on the database?",
+            )?;
+        Ok(migrations_check_status(&status))
+    }
+
+    async fn check_l1(&self) -> anyhow::Result<CheckStatus> {
+        let l1_secrets = self
+            .configs
+            .secrets
+            .l1
+            .as_ref()
+            .context("L1 secrets are missing")?;
+        let client = Client::http(l1_secrets.l1_rpc_url.clone())
+            .context("failed creating L1 client")?
+            .for_network(self.genesis.l1_chain_id.into())
+            .build();
+        let client: Box<DynClient<L1>> = Box::new(client);
+
+        let chain_id = client
+            .fetch_chain_id()
+            .await
+            .context("failed fetching L1 chain ID")?;
+        if chain_id != self.genesis.l1_chain_id {
+            return Ok(CheckStatus::Failed(format!(
+                "L1 chain ID returned by RPC ({chain_id}) differs from the configured one ({})",
+                self.genesis.l1_chain_id
+            )));
+        }
+
+        let diamond_proxy_addr = self.contracts.diamond_proxy_addr;
+        let verifier_addr: Address = CallFunctionArgs::new("getVerifier", ())
+            .for_contract(diamond_proxy_addr, &zksync_contracts::hyperchain_contract())
+            .call(client.as_ref())
+            .await
+            .with_context(|| {
+                format!("failed calling diamond proxy contract at {diamond_proxy_addr:?}")
+            })?;
+        // The verifier is legitimately replaced by protocol upgrades, and the server always uses the verifier
+        // reported by the diamond proxy, so a stale config value shouldn't prevent the server from starting.
+        if verifier_addr != self.contracts.verifier_addr {
+            return Ok(CheckStatus::Warning(format!(
+                "verifier address returned by diamond proxy ({verifier_addr:?}) differs from the configured one ({:?}); \
+                 the configured address is probably outdated",
+                self.contracts.verifier_addr
+            )));
+        }
+        Ok(CheckStatus::Passed(format!(
+            "chain ID is {chain_id}; diamond proxy at {diamond_proxy_addr:?} matches config"
+        )))
+    }
+
+    async fn check_object_store(&self) -> anyhow::Result<CheckStatus> {
+        let config = self
+            .configs
+            .general
+            .core_object_store
+            .clone()
+            .context("core object store config is missing")?;
+        let store = ObjectStoreFactory::new(config)
+            .create_store()
+            .await
+            .context("failed creating object store")?;
+        check_object_store_access(store.as_ref()).await
+    }
+
+    async fn check_rocksdb_dirs(&self) -> anyhow::Result<CheckStatus> {
+        let db_config = self
+            .configs
+            .general
+            .db_config
+            .as_ref()
+            .context("DB config is missing")?;
+        let paths = [
+            db_config.state_keeper_db_path.clone(),
+            db_config.merkle_tree.path.clone(),
+        ];
+        tokio::task::spawn_blocking(move || check_dirs(&paths))
+            .await
+            .context("panicked checking RocksDB directories")?
+    }
+}
+
+fn migrations_check_status(status: &MigrationsStatus) -> CheckStatus {
+    if !status.failed_versions.is_empty() {
+        return CheckStatus::Failed(format!(
+            "migrations {:?} have not completed successfully",
+            status.failed_versions
+        ));
+    }
+    if !status.missing_versions.is_empty() {
+        return CheckStatus::Failed(format!(
+            "migrations {:?} are not applied to the database",
+            status.missing_versions
+        ));
+    }
+    if !status.checksum_mismatches.is_empty() {
+        return CheckStatus::Failed(format!(
+            "migrations {:?} were modified after being applied to the database",
+            status.checksum_mismatches
+        ));
+    }
+    let Some(latest_version) = status.latest_version else {
+        return CheckStatus::Failed("no migrations are applied".into());
+    };
+    if !status.unknown_versions.is_empty() {
+        return CheckStatus::Warning(format!(
+            "migrations {:?} applied to the database are unknown to the server; was the database migrated \
+             by a newer server version?",
+            status.unknown_versions
+        ));
+    }
+    CheckStatus::Passed(format!(
+        "connected; {} migrations applied, latest version is {latest_version}",
+        status.applied_count
+    ))
+}
+
+/// Checks that the object store is accessible by reading a (normally missing) object from it.
+async fn check_object_store_access(store: &dyn ObjectStore) -> anyhow::Result<CheckStatus> {
+    let bucket = Bucket::WitnessInput;
+    match store.get_raw(bucket, OBJECT_STORE_CHECK_KEY).await {
+        Ok(_) | Err(ObjectStoreError::KeyNotFound(_)) => Ok(CheckStatus::Passed(format!(
+            "bucket `{bucket}` is readable"
+        ))),
+        Err(err) => Err(anyhow::Error::new(err).context("failed reading from object store")),
+    }
+}
+
+fn check_dirs(paths: &[String]) -> anyhow::Result<CheckStatus> {
+    let mut low_space_dirs = vec![];
+    for path in paths {
+        let path = Path::new(path);
+        let available_space =
+            check_dir(path).with_context(|| format!("directory `{}`", path.display()))?;
+        if available_space < MIN_FREE_DISK_SPACE {
+            low_space_dirs.push(format!(
+                "`{}` ({} MiB available)",
+                path.display(),
+                available_space >> 20
+            ));
+        }
+    }
+
+    Ok(if low_space_dirs.is_empty() {
+        CheckStatus::Passed(format!("{} directories are writable", paths.len()))
+    } else {
+        CheckStatus::Warning(format!(
+            "directories are writable, but are low on disk space: {}",
+            low_space_dirs.join(", ")
+        ))
+    })
+}
+
+/// Checks that the directory can be created and written to. Returns available disk space in bytes.
+fn check_dir(path: &Path) -> anyhow::Result<u64> {
+    std::fs::create_dir_all(path).context("cannot create directory")?;
+    let probe_path = path.join(".preflight_check");
+    std::fs::write(&probe_path, b"preflight").context("directory is not writable")?;
+    std::fs::remove_file(&probe_path).context("cannot remove file from directory")?;
+    fs2::available_space(path).context("cannot get available disk space")
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use zksync_object_store::MockObjectStore;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct UnavailableObjectStore;
+
+    #[async_trait]
+    impl ObjectStore for UnavailableObjectStore {
+        async fn get_raw(&self, _bucket: Bucket, _key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+            Err(ObjectStoreError::Other {
+                source: "access denied".into(),
+                is_transient: false,
+            })
+        }
+
+        async fn put_raw(
+            &self,
+            _bucket: Bucket,
+            _key: &str,
+            _value: Vec<u8>,
+        ) -> Result<(), ObjectStoreError> {
+            panic!("preflight checks must not write to object store");
+        }
+
+        async fn remove_raw(&self, _bucket: Bucket, _key: &str) -> Result<(), ObjectStoreError> {
+            panic!("preflight checks must not write to object store");
+        }
+
+        fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+            bucket.to_string()
+        }
+    }
+
+    fn applied_migrations_status() -> MigrationsStatus {
+        MigrationsStatus {
+            applied_count: 3,
+            latest_version: Some(3),
+            ..MigrationsStatus::default()
+        }
+    }
+
+    #[test]
+    fn checking_migrations_status() {
+        let status = migrations_check_status(&applied_migrations_status());
+        assert!(matches!(status, CheckStatus::Passed(_)), "{status:?}");
+
+        let status = migrations_check_status(&MigrationsStatus::default());
+        assert_eq!(
+            status,
+            CheckStatus::Failed("no migrations are applied".into())
+        );
+
+        let status = migrations_check_status(&MigrationsStatus {
+            missing_versions: vec![4],
+            ..applied_migrations_status()
+        });
+        assert!(
+            matches!(&status, CheckStatus::Failed(msg) if msg.contains("[4] are not applied")),
+            "{status:?}"
+        );
+
+        let status = migrations_check_status(&MigrationsStatus {
+            failed_versions: vec![4],
+            ..applied_migrations_status()
+        });
+        assert!(
+            matches!(&status, CheckStatus::Failed(msg) if msg.contains("[4] have not completed")),
+            "{status:?}"
+        );
+
+        let status = migrations_check_status(&MigrationsStatus {
+            checksum_mismatches: vec![2],
+            ..applied_migrations_status()
+        });
+        assert!(
+            matches!(&status, CheckStatus::Failed(msg) if msg.contains("[2] were modified")),
+            "{status:?}"
+        );
+
+        let status = migrations_check_status(&MigrationsStatus {
+            unknown_versions: vec![4],
+            ..applied_migrations_status()
+        });
+        assert!(
+            matches!(&status, CheckStatus::Warning(msg) if msg.contains("[4] applied")),
+            "{status:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn checking_object_store_access() {
+        let store = MockObjectStore::arc();
+        let status = check_object_store_access(store.as_ref()).await.unwrap();
+        assert!(matches!(status, CheckStatus::Passed(_)), "{status:?}");
+        // The check must not leave any objects in the store.
+        let err = store
+            .get_raw(Bucket::WitnessInput, OBJECT_STORE_CHECK_KEY)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+
+        let err = check_object_store_access(&UnavailableObjectStore)
+            .await
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("access denied"), "{err:#}");
+    }
+
+    #[test]
+    fn checking_dirs() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let paths = [
+            temp_dir.path().join("state_keeper"),
+            temp_dir.path().join("tree"),
+        ];
+        let paths = paths.map(|path| path.to_str().unwrap().to_owned());
+        let status = check_dirs(&paths).unwrap();
+        assert!(
+            matches!(status, CheckStatus::Passed(_) | CheckStatus::Warning(_)),
+            "{status:?}"
+        );
+        assert!(temp_dir.path().join("tree").is_dir());
+
+        let file_path = temp_dir.path().join("file");
+        std::fs::write(&file_path, b"test").unwrap();
+        let err = check_dirs(&[file_path.to_str().unwrap().to_owned()]).unwrap_err();
+        assert!(err.to_string().contains("file"), "{err:#}");
+    }
+
+    #[test]
+    fn report_formatting() {
+        let report = PreflightReport {
+            checks: vec![
+                CheckResult {
+                    name: "configs",
+                    status: CheckStatus::Passed("ok".into()),
+                },
+                CheckResult {
+                    name: "l1",
+                    status: CheckStatus::Failed("chain ID mismatch".into()),
+                },
+                CheckResult {
+                    name: "rocksdb",
+                    status: CheckStatus::Skipped,
+                },
+            ],
+        };
+        assert!(report.has_failures());
+        assert_eq!(
+            report.to_string(),
+            "[OK] configs: ok\n[FAIL] l1: chain ID mismatch\n\
+             [SKIP] rocksdb: not required by selected components"
+        );
+    }
+}
@@ -8,14 +8,17 @@ use zksync_config::{
         fri_prover_group::FriProverGroupConfig,
         house_keeper::HouseKeeperConfig,
         wallets::{AddressWallet, EthSender, StateKeeper, Wallet, Wallets},
+        BasicWitnessInputProducerConfig, DiskSpaceMonitorConfig, FeatureFlagsConfig,
         FriProofCompressorConfig, FriProverConfig, FriProverGatewayConfig,
         FriWitnessGeneratorConfig, FriWitnessVectorGeneratorConfig, GeneralConfig,
         ObservabilityConfig, PrometheusConfig, ProofDataHandlerConfig, ProtectiveReadsWriterConfig,
+        SchedulerConfig,
     },
     ApiConfig, ContractVerifierConfig, DBConfig, EthConfig, EthWatchConfig, GasAdjusterConfig,
     ObjectStoreConfig, PostgresConfig, SnapshotsCreatorConfig,
 };
 use zksync_protobuf::{repr::ProtoRepr, ProtoFmt};
+use zksync_protobuf_config::keystore::{KeystorePassphrase, ResolveKeystores};
 
 pub fn decode_yaml<T: ProtoFmt>(yaml: &str) -> anyhow::Result<T> {
     let d = serde_yaml::Deserializer::from_str(yaml);
@@ -28,6 +31,20 @@ pub fn decode_yaml_repr<T: ProtoRepr>(yaml: &str) -> anyhow::Result<T::Type> {
     let this: T = zksync_protobuf::serde::deserialize_proto_with_options(d, false)?;
     this.read()
 }
+
+/// Decodes a config that may reference encrypted keystores. `passphrase` is only called if the config
+/// references at least one keystore.
+pub fn decode_yaml_repr_with_keystores<T: ProtoRepr + ResolveKeystores>(
+    yaml: &str,
+    passphrase: impl FnOnce() -> anyhow::Result<KeystorePassphrase>,
+) -> anyhow::Result<T::Type> {
+    let d = serde_yaml::Deserializer::from_str(yaml);
+    let mut this: T = zksync_protobuf::serde::deserialize_proto_with_options(d, false)?;
+    if this.has_keystores() {
+        this.resolve_keystores(&passphrase()?)?;
+    }
+    this.read()
+}
 //
 // TODO (QIT-22): This structure is going to be removed when components will be responsible for their own configs.
 /// A temporary config store allowing to pass deserialized configs from `zksync_server` to `zksync_core`.
@@ -62,6 +79,10 @@ pub struct TempConfigStore {
     pub snapshot_creator: Option<SnapshotsCreatorConfig>,
     pub protective_reads_writer_config: Option<ProtectiveReadsWriterConfig>,
     pub core_object_store: Option<ObjectStoreConfig>,
+    pub basic_witness_input_producer_config: Option<BasicWitnessInputProducerConfig>,
+    pub disk_space_monitor_config: Option<DiskSpaceMonitorConfig>,
+    pub scheduler_config: Option<SchedulerConfig>,
+    pub feature_flags_config: Option<FeatureFlagsConfig>,
 }
 
 impl TempConfigStore {
@@ -89,6 +110,10 @@ impl TempConfigStore {
             observability: self.observability.clone(),
             protective_reads_writer_config: self.protective_reads_writer_config.clone(),
             core_object_store: self.core_object_store.clone(),
+            basic_witness_input_producer_config: self.basic_witness_input_producer_config.clone(),
+            disk_space_monitor_config: self.disk_space_monitor_config.clone(),
+            scheduler_config: self.scheduler_config.clone(),
+            feature_flags_config: self.feature_flags_config.clone(),
         }
     }
 
@@ -101,9 +126,13 @@ impl TempConfigStore {
             let blob_operator = sender
                 .private_key_blobs()
                 .and_then(|operator| Wallet::from_private_key_bytes(operator, None).ok());
+            let fee_payer = sender
+                .private_key_fee_payer()
+                .and_then(|fee_payer| Wallet::from_private_key_bytes(fee_payer, None).ok());
             Some(EthSender {
                 operator,
                 blob_operator,
+                fee_payer,
             })
         });
         let state_keeper = self
@@ -115,6 +144,7 @@ impl TempConfigStore {
                         .fee_account_addr
                         .expect("Must be presented in env variables"),
                 ),
+                extra_fee_accounts: vec![],
             });
         Wallets {
             eth_sender,
@@ -6,18 +6,23 @@
 //!
 //! This module is intended to be blocking.
 
-use std::time::{Duration, Instant};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
 use multivm::{
-    interface::{L1BatchEnv, L2BlockEnv, SystemEnv, VmInterface},
+    interface::{ExecutionResult, L1BatchEnv, L2BlockEnv, SystemEnv, VmInterface},
     utils::adjust_pubdata_price_for_tx,
     vm_latest::{constants::BATCH_COMPUTATIONAL_GAS_LIMIT, HistoryDisabled},
     VmInstance,
 };
-use tokio::runtime::Handle;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal, DalError};
-use zksync_state::{PostgresStorage, ReadStorage, StoragePtr, StorageView, WriteStorage};
+use zksync_state::{
+    PostgresStorage, ReadStorage, StoragePtr, StorageView, StorageViewAt, WriteStorage,
+};
 use zksync_system_constants::{
     SYSTEM_CONTEXT_ADDRESS, SYSTEM_CONTEXT_CURRENT_L2_BLOCK_INFO_POSITION,
     SYSTEM_CONTEXT_CURRENT_TX_ROLLING_HASH_POSITION, ZKPORTER_IS_AVAILABLE,
@@ -28,17 +33,52 @@ use zksync_types::{
     fee_model::BatchFeeInput,
     get_nonce_key,
     utils::{decompose_full_nonce, nonces_to_full_nonce, storage_key_for_eth_balance},
-    AccountTreeId, L1BatchNumber, L2BlockNumber, Nonce, ProtocolVersionId, StorageKey, Transaction,
-    H256, U256,
+    AccountTreeId, L1BatchNumber, L2BlockNumber, Nonce, ProtocolVersionId, StorageKey,
+    StorageValue, Transaction, H256, U256,
 };
 use zksync_utils::{h256_to_u256, time::seconds_since_epoch, u256_to_h256};
 
 use super::{
+    execute::PriorTxsSnapshot,
+    vm_memory_pool::PooledVmMemory,
     vm_metrics::{self, SandboxStage, SANDBOX_METRICS},
-    BlockArgs, TxExecutionArgs, TxSharedArgs, VmPermit,
+    BlockArgs, TxExecutionArgs, TxSharedArgs, VmMemoryPool, VmPermit,
 };
 
-type BoxedVm<'a> = Box<VmInstance<StorageView<PostgresStorage<'a>>, HistoryDisabled>>;
+type BoxedVm<'a> = Box<VmInstance<StorageView<SandboxStorage<'a>>, HistoryDisabled>>;
+
+/// Postgres storage supplemented with factory deps published by prior transactions (if any).
+/// Storage writes of prior transactions are applied to the [`StorageView`] on top of this storage.
+#[derive(Debug)]
+pub(super) struct SandboxStorage<'a> {
+    inner: PostgresStorage<'a>,
+    prior_txs: Option<Arc<PriorTxsSnapshot>>,
+}
+
+impl ReadStorage for SandboxStorage<'_> {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        self.inner.read_value(key)
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        self.inner.is_write_initial(key)
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        let prior_dep = self
+            .prior_txs
+            .as_ref()
+            .and_then(|snapshot| snapshot.factory_deps.get(&hash));
+        if let Some(dep) = prior_dep {
+            return Some(dep.clone());
+        }
+        self.inner.load_factory_dep(hash)
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        self.inner.get_enumeration_index(key)
+    }
+}
 
 #[derive(Debug)]
 struct Sandbox<'a> {
@@ -46,7 +86,8 @@ struct Sandbox<'a> {
     l1_batch_env: L1BatchEnv,
     execution_args: &'a TxExecutionArgs,
     l2_block_info_to_reset: Option<StoredL2BlockInfo>,
-    storage_view: StorageView<PostgresStorage<'a>>,
+    storage_view: StorageView<SandboxStorage<'a>>,
+    vm_memory_pool: Option<Arc<VmMemoryPool>>,
 }
 
 impl<'a> Sandbox<'a> {
@@ -80,17 +121,17 @@ impl<'a> Sandbox<'a> {
         )
         .await?;
 
-        let storage = PostgresStorage::new_async(
-            Handle::current(),
-            connection,
-            resolved_block_info.state_l2_block_number,
-            false,
-        )
-        .await
-        .context("cannot create `PostgresStorage`")?
-        .with_caches(shared_args.caches.clone());
-
-        let storage_view = StorageView::new(storage);
+        let storage = StorageViewAt::l2_block(resolved_block_info.state_l2_block_number)
+            .consider_new_l1_batch(false)
+            .with_caches(shared_args.caches.clone())
+            .postgres(connection)
+            .await?;
+
+        let storage_view = StorageView::new(SandboxStorage {
+            inner: storage,
+            prior_txs: execution_args.prior_txs.clone(),
+        });
+        let vm_memory_pool = shared_args.vm_memory_pool.clone();
         let (system_env, l1_batch_env) = Self::prepare_env(
             shared_args,
             execution_args,
@@ -104,6 +145,7 @@ impl<'a> Sandbox<'a> {
             storage_view,
             execution_args,
             l2_block_info_to_reset,
+            vm_memory_pool,
         })
     }
 
@@ -167,6 +209,12 @@ impl<'a> Sandbox<'a> {
     /// This method is blocking.
     fn setup_storage_view(&mut self, tx: &Transaction) {
         let storage_view_setup_started_at = Instant::now();
+        if let Some(prior_txs) = &self.execution_args.prior_txs {
+            for (key, value) in &prior_txs.storage_writes {
+                self.storage_view.set_value(*key, *value);
+            }
+        }
+
         if let Some(nonce) = self.execution_args.enforced_nonce {
             let nonce_key = get_nonce_key(&tx.initiator_account());
             let full_nonce = self.storage_view.read_value(&nonce_key);
@@ -259,7 +307,7 @@ impl<'a> Sandbox<'a> {
         mut self,
         tx: &Transaction,
         adjust_pubdata_price: bool,
-    ) -> (BoxedVm<'a>, StoragePtr<StorageView<PostgresStorage<'a>>>) {
+    ) -> (BoxedVm<'a>, StoragePtr<StorageView<SandboxStorage<'a>>>) {
         self.setup_storage_view(tx);
         let protocol_version = self.system_env.version;
         if adjust_pubdata_price {
@@ -272,17 +320,35 @@ impl<'a> Sandbox<'a> {
         };
 
         let storage_view = self.storage_view.to_rc_ptr();
-        let vm = Box::new(VmInstance::new_with_specific_version(
-            self.l1_batch_env,
-            self.system_env,
-            storage_view.clone(),
-            protocol_version.into_api_vm_version(),
-        ));
-
-        (vm, storage_view)
+        let vm_version = protocol_version.into_api_vm_version();
+        let memory = self
+            .vm_memory_pool
+            .as_ref()
+            .filter(|_| PooledVmMemory::is_supported_by(vm_version))
+            .map(|pool| pool.take(&self.system_env.base_system_smart_contracts));
+        let vm = if let Some(memory) = memory {
+            VmInstance::new_with_reusable_memory(
+                self.l1_batch_env,
+                self.system_env,
+                storage_view.clone(),
+                vm_version,
+                memory,
+            )
+        } else {
+            VmInstance::new_with_specific_version(
+                self.l1_batch_env,
+                self.system_env,
+                storage_view.clone(),
+                vm_version,
+            )
+        };
+
+        (Box::new(vm), storage_view)
     }
 }
 
+type SandboxVm<'a> = VmInstance<StorageView<SandboxStorage<'a>>, HistoryDisabled>;
+
 #[allow(clippy::too_many_arguments)]
 pub(super) fn apply_vm_in_sandbox<T>(
     vm_permit: VmPermit,
@@ -295,8 +361,71 @@ pub(super) fn apply_vm_in_sandbox<T>(
     connection_pool: &ConnectionPool<Core>,
     tx: Transaction,
     block_args: BlockArgs,
+    apply: impl FnOnce(&mut SandboxVm<'_>, Transaction, ProtocolVersionId) -> T,
+) -> anyhow::Result<T> {
+    apply_vm_with_storage_in_sandbox(
+        vm_permit,
+        shared_args,
+        adjust_pubdata_price,
+        execution_args,
+        connection_pool,
+        tx,
+        block_args,
+        |vm, _, tx, protocol_version| apply(vm, tx, protocol_version),
+    )
+}
+
+/// Executes a transaction on top of `execution_args.prior_txs` and returns its execution result together with
+/// storage writes of all executed transactions, excluding writes to the system context (i.e., the L2 block context).
+/// The writes can be used to extend the snapshot of prior transactions.
+pub(super) fn execute_prior_tx_in_sandbox(
+    vm_permit: VmPermit,
+    shared_args: TxSharedArgs,
+    execution_args: &TxExecutionArgs,
+    connection_pool: &ConnectionPool<Core>,
+    tx: Transaction,
+    block_args: BlockArgs,
+) -> anyhow::Result<(ExecutionResult, HashMap<StorageKey, StorageValue>)> {
+    apply_vm_with_storage_in_sandbox(
+        vm_permit,
+        shared_args,
+        true,
+        execution_args,
+        connection_pool,
+        tx,
+        block_args,
+        |vm, storage_view, tx, _| {
+            let tx_hash = tx.hash();
+            let (compression_result, result) =
+                vm.execute_transaction_with_bytecode_compression(tx, true);
+            compression_result.with_context(|| {
+                format!("failed compressing bytecodes for prior transaction {tx_hash:?}")
+            })?;
+
+            let storage_view = storage_view.borrow();
+            let storage_writes = storage_view
+                .modified_storage_keys()
+                .iter()
+                .filter(|(key, _)| *key.address() != SYSTEM_CONTEXT_ADDRESS)
+                .map(|(key, value)| (*key, *value))
+                .collect();
+            Ok((result.result, storage_writes))
+        },
+    )?
+}
+
+#[allow(clippy::too_many_arguments)]
+fn apply_vm_with_storage_in_sandbox<T>(
+    vm_permit: VmPermit,
+    shared_args: TxSharedArgs,
+    adjust_pubdata_price: bool,
+    execution_args: &TxExecutionArgs,
+    connection_pool: &ConnectionPool<Core>,
+    tx: Transaction,
+    block_args: BlockArgs,
     apply: impl FnOnce(
-        &mut VmInstance<StorageView<PostgresStorage<'_>>, HistoryDisabled>,
+        &mut SandboxVm<'_>,
+        &StoragePtr<StorageView<SandboxStorage<'_>>>,
         Transaction,
         ProtocolVersionId,
     ) -> T,
@@ -321,6 +450,7 @@ pub(super) fn apply_vm_in_sandbox<T>(
         block_args,
     ))?;
     let protocol_version = sandbox.system_env.version;
+    let vm_memory_pool = sandbox.vm_memory_pool.clone();
     let (mut vm, storage_view) = sandbox.into_vm(&tx, adjust_pubdata_price);
 
     SANDBOX_METRICS.sandbox[&SandboxStage::Initialization].observe(stage_started_at.elapsed());
@@ -332,7 +462,7 @@ pub(super) fn apply_vm_in_sandbox<T>(
         tx.nonce().unwrap_or(Nonce(0))
     );
     let execution_latency = SANDBOX_METRICS.sandbox[&SandboxStage::Execution].start();
-    let result = apply(&mut vm, tx, protocol_version);
+    let result = apply(&mut vm, &storage_view, tx, protocol_version);
     let vm_execution_took = execution_latency.observe();
 
     let memory_metrics = vm.record_vm_memory_metrics();
@@ -342,6 +472,12 @@ pub(super) fn apply_vm_in_sandbox<T>(
         vm_execution_took,
         storage_view.as_ref().borrow_mut().metrics(),
     );
+
+    if let Some(pool) = &vm_memory_pool {
+        if let Some(memory) = vm.into_reusable_memory() {
+            pool.put(memory);
+        }
+    }
     Ok(result)
 }
 
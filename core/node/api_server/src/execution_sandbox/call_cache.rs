@@ -0,0 +1,184 @@
+//! Short-lived cache for `eth_call` results.
+//!
+//! Front-ends frequently poll the same view methods (e.g., token balances) every second or so. Since the VM state
+//! only changes when a new L2 block is sealed, results of such calls can be reused for a short time without
+//! invoking the VM sandbox.
+
+use std::{
+    num::NonZeroUsize,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use lru::LruCache;
+use zksync_types::{
+    l2::L2Tx, transaction_request::CallOverrides, web3::keccak256, L2BlockNumber, H256,
+};
+
+use super::{
+    vm_metrics::{EthCallCacheOutcome, SANDBOX_METRICS},
+    BlockArgs,
+};
+
+/// Cache key for an `eth_call` request.
+///
+/// The resolved block number serves as the state epoch: the state for a sealed block never changes, and the state
+/// for the pending block changes only when a new block is sealed, which bumps the resolved pending block number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct EthCallCacheKey {
+    block_number: L2BlockNumber,
+    pending: bool,
+    call_hash: H256,
+}
+
+impl EthCallCacheKey {
+    pub fn new(block_args: &BlockArgs, call_overrides: &CallOverrides, tx: &L2Tx) -> Self {
+        // `received_timestamp_ms` is deliberately excluded; it's set to the current time on each call.
+        let call_data = (
+            &tx.execute,
+            &tx.common_data,
+            call_overrides.enforced_base_fee,
+        );
+        let call_data =
+            serde_json::to_vec(&call_data).expect("failed serializing `eth_call` parameters");
+        Self {
+            block_number: block_args.resolved_block_number(),
+            pending: block_args.is_pending(),
+            call_hash: H256(keccak256(&call_data)),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    output: Vec<u8>,
+    inserted_at: Instant,
+}
+
+#[derive(Debug)]
+struct Inner {
+    entries: LruCache<EthCallCacheKey, CacheEntry>,
+    /// Latest observed pending block number. Entries for older pending blocks are stale.
+    pending_block_number: L2BlockNumber,
+}
+
+impl Inner {
+    fn observe_block(&mut self, key: &EthCallCacheKey) {
+        if !key.pending || key.block_number <= self.pending_block_number {
+            return;
+        }
+        self.pending_block_number = key.block_number;
+
+        let stale_keys: Vec<_> = self
+            .entries
+            .iter()
+            .filter_map(|(entry_key, _)| {
+                (entry_key.pending && entry_key.block_number < key.block_number)
+                    .then_some(*entry_key)
+            })
+            .collect();
+        for stale_key in &stale_keys {
+            self.entries.pop(stale_key);
+        }
+    }
+}
+
+/// LRU cache for successful `eth_call` outputs with a time-to-live for each entry.
+#[derive(Debug)]
+pub(crate) struct EthCallCache {
+    ttl: Duration,
+    inner: Mutex<Inner>,
+}
+
+impl EthCallCache {
+    pub fn new(capacity: NonZeroUsize, ttl: Duration) -> Self {
+        Self {
+            ttl,
+            inner: Mutex::new(Inner {
+                entries: LruCache::new(capacity),
+                pending_block_number: L2BlockNumber(0),
+            }),
+        }
+    }
+
+    /// Returns the cached output for the call, if any.
+    pub fn get(&self, key: &EthCallCacheKey) -> Option<Vec<u8>> {
+        let output = self.get_inner(key);
+        let outcome = if output.is_some() {
+            EthCallCacheOutcome::Hit
+        } else {
+            EthCallCacheOutcome::Miss
+        };
+        SANDBOX_METRICS.eth_call_cache[&outcome].inc();
+        output
+    }
+
+    fn get_inner(&self, key: &EthCallCacheKey) -> Option<Vec<u8>> {
+        let mut inner = self.inner.lock().expect("`eth_call` cache is poisoned");
+        inner.observe_block(key);
+        let entry = inner.entries.get(key)?;
+        if entry.inserted_at.elapsed() <= self.ttl {
+            return Some(entry.output.clone());
+        }
+        inner.entries.pop(key);
+        None
+    }
+
+    /// Caches the successful output of the call.
+    pub fn insert(&self, key: EthCallCacheKey, output: Vec<u8>) {
+        let mut inner = self.inner.lock().expect("`eth_call` cache is poisoned");
+        inner.observe_block(&key);
+        if key.pending && key.block_number < inner.pending_block_number {
+            return; // The call was executed on a stale pending block
+        }
+        let entry = CacheEntry {
+            output,
+            inserted_at: Instant::now(),
+        };
+        inner.entries.put(key, entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(block_number: u32, pending: bool, call: u8) -> EthCallCacheKey {
+        EthCallCacheKey {
+            block_number: L2BlockNumber(block_number),
+            pending,
+            call_hash: H256::repeat_byte(call),
+        }
+    }
+
+    #[test]
+    fn cache_entries_expire() {
+        let cache = EthCallCache::new(NonZeroUsize::new(10).unwrap(), Duration::ZERO);
+        cache.insert(key(1, false, 1), vec![1]);
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(cache.get(&key(1, false, 1)), None);
+
+        let cache = EthCallCache::new(NonZeroUsize::new(10).unwrap(), Duration::from_secs(60));
+        cache.insert(key(1, false, 1), vec![1]);
+        assert_eq!(cache.get(&key(1, false, 1)), Some(vec![1]));
+        assert_eq!(cache.get(&key(1, false, 2)), None);
+        assert_eq!(cache.get(&key(2, false, 1)), None);
+    }
+
+    #[test]
+    fn pending_entries_are_invalidated_on_new_block() {
+        let cache = EthCallCache::new(NonZeroUsize::new(10).unwrap(), Duration::from_secs(60));
+        cache.insert(key(5, true, 1), vec![1]);
+        cache.insert(key(4, false, 1), vec![2]);
+        assert_eq!(cache.get(&key(5, true, 1)), Some(vec![1]));
+
+        // A new block is sealed; entries for the old pending block must be evicted.
+        assert_eq!(cache.get(&key(6, true, 1)), None);
+        assert_eq!(cache.get(&key(5, true, 1)), None);
+        assert_eq!(cache.get(&key(4, false, 1)), Some(vec![2]));
+
+        // Outputs computed on a stale pending block should not be cached.
+        cache.insert(key(5, true, 2), vec![3]);
+        assert_eq!(cache.get(&key(5, true, 2)), None);
+    }
+}
@@ -0,0 +1,160 @@
+//! Fair scheduling of VM permits among API clients.
+//!
+//! Each client is identified by an opaque ID set by the HTTP server middleware (e.g., based on an API key
+//! or a forwarded IP address). A client cannot hold more than the configured share of VM permits at the same time;
+//! excessive requests are queued until the client's own requests complete, leaving global permits to other clients.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use zksync_config::configs::api::Web3JsonRpcConfig;
+
+/// Number of tracked clients after which idle clients are pruned.
+const PRUNE_THRESHOLD: usize = 1_000;
+
+tokio::task_local! {
+    static CURRENT_CLIENT: ClientId;
+}
+
+/// Opaque identifier of an API client used for fair VM scheduling.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ClientId(Arc<str>);
+
+impl ClientId {
+    pub fn new(id: &str) -> Self {
+        Self(id.into())
+    }
+
+    /// Runs the provided future with this client as the current one. VM permits acquired inside the future
+    /// will be attributed to the client.
+    pub async fn scope<F: Future>(self, fut: F) -> F::Output {
+        CURRENT_CLIENT.scope(self, fut).await
+    }
+
+    pub(super) fn current() -> Option<Self> {
+        CURRENT_CLIENT.try_with(Clone::clone).ok()
+    }
+}
+
+/// Configuration of fair VM scheduling among API clients.
+#[derive(Debug, Clone, Copy)]
+pub struct VmFairSchedulingConfig {
+    /// Maximum number of VM permits that can be held by a single client at the same time.
+    pub max_concurrency_per_client: NonZeroUsize,
+    /// Maximum time to wait for a VM permit (including waiting for the client share) before the request is rejected.
+    pub acquire_timeout: Duration,
+}
+
+impl VmFairSchedulingConfig {
+    /// Extracts fair scheduling config from the Web3 API config. Returns `None` if fair scheduling is disabled.
+    pub fn from_web3_config(config: &Web3JsonRpcConfig) -> Option<Self> {
+        let max_concurrency_per_client = config
+            .vm_concurrency_limit_per_client
+            .and_then(NonZeroUsize::new)?;
+        Some(Self {
+            max_concurrency_per_client,
+            acquire_timeout: config.vm_permit_timeout(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct ClientLimiter {
+    config: VmFairSchedulingConfig,
+    clients: Mutex<HashMap<ClientId, Arc<Semaphore>>>,
+}
+
+impl ClientLimiter {
+    pub fn new(config: VmFairSchedulingConfig) -> Self {
+        Self {
+            config,
+            clients: Mutex::default(),
+        }
+    }
+
+    pub fn acquire_timeout(&self) -> Duration {
+        self.config.acquire_timeout
+    }
+
+    fn client_semaphore(&self, client: &ClientId) -> Arc<Semaphore> {
+        let mut clients = self.clients.lock().expect("client limiter is poisoned");
+        if clients.len() >= PRUNE_THRESHOLD && !clients.contains_key(client) {
+            // A client semaphore is only referenced from the map if there are no permits issued or awaited for it.
+            clients.retain(|_, semaphore| Arc::strong_count(semaphore) > 1);
+        }
+        let semaphore = clients.entry(client.clone()).or_insert_with(|| {
+            Arc::new(Semaphore::new(self.config.max_concurrency_per_client.get()))
+        });
+        semaphore.clone()
+    }
+
+    /// Waits until the client has a free share. Returns `None` if the share cannot be obtained.
+    pub async fn acquire(&self, client: &ClientId) -> Option<OwnedSemaphorePermit> {
+        let semaphore = self.client_semaphore(client);
+        semaphore.acquire_owned().await.ok()
+    }
+
+    #[cfg(test)]
+    fn tracked_clients(&self) -> usize {
+        self.clients.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(max_concurrency_per_client: usize) -> ClientLimiter {
+        ClientLimiter::new(VmFairSchedulingConfig {
+            max_concurrency_per_client: NonZeroUsize::new(max_concurrency_per_client).unwrap(),
+            acquire_timeout: Duration::from_secs(1),
+        })
+    }
+
+    #[tokio::test]
+    async fn client_shares_are_independent() {
+        let limiter = limiter(1);
+        let alice = ClientId::new("alice");
+        let bob = ClientId::new("bob");
+
+        let alice_permit = limiter.acquire(&alice).await.unwrap();
+        let second_alice_permit =
+            tokio::time::timeout(Duration::from_millis(20), limiter.acquire(&alice)).await;
+        assert!(second_alice_permit.is_err(), "share was not enforced");
+        limiter.acquire(&bob).await.unwrap();
+
+        drop(alice_permit);
+        limiter.acquire(&alice).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn idle_clients_are_pruned() {
+        let limiter = limiter(1);
+        let _permit = limiter.acquire(&ClientId::new("active")).await.unwrap();
+        for i in 1..PRUNE_THRESHOLD {
+            limiter
+                .acquire(&ClientId::new(&i.to_string()))
+                .await
+                .unwrap();
+        }
+        assert_eq!(limiter.tracked_clients(), PRUNE_THRESHOLD);
+
+        limiter.acquire(&ClientId::new("new")).await.unwrap();
+        // Only the active and the new client should remain.
+        assert_eq!(limiter.tracked_clients(), 2);
+    }
+
+    #[tokio::test]
+    async fn current_client_is_scoped() {
+        assert_eq!(ClientId::current(), None);
+        let client = ClientId::new("alice");
+        let current = client.clone().scope(async { ClientId::current() }).await;
+        assert_eq!(current, Some(client));
+    }
+}
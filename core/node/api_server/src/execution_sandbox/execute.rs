@@ -1,8 +1,10 @@
 //! Implementation of "executing" methods, e.g. `eth_call`.
 
+use std::{collections::HashMap, sync::Arc};
+
 use anyhow::Context as _;
 use multivm::{
-    interface::{TxExecutionMode, VmExecutionResultAndLogs, VmInterface},
+    interface::{ExecutionResult, TxExecutionMode, VmExecutionResultAndLogs, VmInterface},
     tracers::StorageInvocations,
     MultiVMTracer,
 };
@@ -10,8 +12,10 @@ use tracing::{span, Level};
 use zksync_dal::{ConnectionPool, Core};
 use zksync_types::{
     fee::TransactionExecutionMetrics, l2::L2Tx, transaction_request::CallOverrides,
-    ExecuteTransactionCommon, Nonce, PackedEthSignature, Transaction, U256,
+    ExecuteTransactionCommon, Nonce, PackedEthSignature, StorageKey, StorageValue, Transaction,
+    H256, U256,
 };
+use zksync_utils::bytecode::hash_bytecode;
 
 use super::{
     apply, testonly::MockTransactionExecutor, vm_metrics, ApiTracer, BlockArgs, TxSharedArgs,
@@ -25,6 +29,8 @@ pub(crate) struct TxExecutionArgs {
     pub added_balance: U256,
     pub enforced_base_fee: Option<u64>,
     pub missed_storage_invocation_limit: usize,
+    /// Effect of transactions executed before the main transaction in the same L2 block.
+    pub prior_txs: Option<Arc<PriorTxsSnapshot>>,
 }
 
 impl TxExecutionArgs {
@@ -35,6 +41,7 @@ impl TxExecutionArgs {
             added_balance: U256::zero(),
             enforced_base_fee: Some(tx.common_data.fee.max_fee_per_gas.as_u64()),
             missed_storage_invocation_limit: usize::MAX,
+            prior_txs: None,
         }
     }
 
@@ -49,6 +56,7 @@ impl TxExecutionArgs {
             added_balance: U256::zero(),
             enforced_base_fee,
             missed_storage_invocation_limit,
+            prior_txs: None,
         }
     }
 
@@ -72,10 +80,53 @@ impl TxExecutionArgs {
             enforced_nonce: tx.nonce(),
             added_balance,
             enforced_base_fee: Some(base_fee),
+            prior_txs: None,
         }
     }
 }
 
+/// Effect of transactions executed before the main transaction in the same L2 block. The snapshot is built
+/// incrementally (one transaction at a time, see [`TransactionExecutor::extend_prior_txs_snapshot()`]) and is then
+/// applied to every execution of the main transaction, so that prior transactions are not re-executed.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PriorTxsSnapshot {
+    /// Number of transactions in the snapshot.
+    pub tx_count: usize,
+    /// Storage writes performed by the transactions, excluding writes to the L2 block context.
+    pub storage_writes: HashMap<StorageKey, StorageValue>,
+    /// Factory deps published by the transactions, keyed by the bytecode hash.
+    pub factory_deps: HashMap<H256, Vec<u8>>,
+}
+
+impl PriorTxsSnapshot {
+    fn with_executed_tx(
+        &self,
+        tx: &Transaction,
+        result: &ExecutionResult,
+        storage_writes: HashMap<StorageKey, StorageValue>,
+    ) -> anyhow::Result<Self> {
+        let tx_hash = tx.hash();
+        match result {
+            ExecutionResult::Success { .. } => {}
+            ExecutionResult::Revert { output } => {
+                anyhow::bail!("prior transaction {tx_hash:?} reverted: {output}");
+            }
+            ExecutionResult::Halt { reason } => {
+                anyhow::bail!("prior transaction {tx_hash:?} halted: {reason}");
+            }
+        }
+
+        let mut extended = self.clone();
+        extended.tx_count += 1;
+        extended.storage_writes.extend(storage_writes);
+        let factory_deps = tx.execute.factory_deps.iter().flatten();
+        extended
+            .factory_deps
+            .extend(factory_deps.map(|bytecode| (hash_bytecode(bytecode), bytecode.clone())));
+        Ok(extended)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct TransactionExecutionOutput {
     /// Output of the VM.
@@ -163,6 +214,42 @@ impl TransactionExecutor {
         })
     }
 
+    /// Executes `tx` on top of `execution_args.prior_txs` and returns the snapshot extended with the effect of `tx`.
+    /// Errors if `tx` doesn't succeed.
+    pub async fn extend_prior_txs_snapshot(
+        &self,
+        vm_permit: VmPermit,
+        shared_args: TxSharedArgs,
+        execution_args: TxExecutionArgs,
+        connection_pool: ConnectionPool<Core>,
+        tx: Transaction,
+        block_args: BlockArgs,
+    ) -> anyhow::Result<PriorTxsSnapshot> {
+        let snapshot = execution_args.prior_txs.clone().unwrap_or_default();
+        let (result, storage_writes) = if let Self::Mock(mock_executor) = self {
+            let output = mock_executor.execute_tx(&tx, &block_args)?;
+            (output.vm.result, HashMap::new())
+        } else {
+            let tx = tx.clone();
+            tokio::task::spawn_blocking(move || {
+                let span = span!(Level::DEBUG, "execute_prior_tx_in_sandbox").entered();
+                let result = apply::execute_prior_tx_in_sandbox(
+                    vm_permit,
+                    shared_args,
+                    &execution_args,
+                    &connection_pool,
+                    tx,
+                    block_args,
+                );
+                span.exit();
+                result
+            })
+            .await
+            .context("prior transaction execution panicked")??
+        };
+        snapshot.with_executed_tx(&tx, &result, storage_writes)
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub async fn execute_tx_eth_call(
         &self,
@@ -12,18 +12,24 @@ use zksync_types::{
     api, fee_model::BatchFeeInput, AccountTreeId, Address, L1BatchNumber, L2BlockNumber, L2ChainId,
 };
 
-use self::vm_metrics::SandboxStage;
+pub use self::client_limiter::VmFairSchedulingConfig;
 pub(super) use self::{
+    call_cache::{EthCallCache, EthCallCacheKey},
+    client_limiter::ClientId,
     error::SandboxExecutionError,
-    execute::{TransactionExecutor, TxExecutionArgs},
+    execute::{PriorTxsSnapshot, TransactionExecutor, TxExecutionArgs},
     tracers::ApiTracer,
     validate::ValidationError,
+    vm_memory_pool::VmMemoryPool,
     vm_metrics::{SubmitTxStage, SANDBOX_METRICS},
 };
+use self::{client_limiter::ClientLimiter, vm_metrics::SandboxStage};
 use super::tx_sender::MultiVMBaseSystemContracts;
 
 // Note: keep the modules private, and instead re-export functions that make public interface.
 mod apply;
+mod call_cache;
+mod client_limiter;
 mod error;
 mod execute;
 pub mod testonly;
@@ -31,6 +37,7 @@ pub mod testonly;
 mod tests;
 mod tracers;
 mod validate;
+mod vm_memory_pool;
 mod vm_metrics;
 
 /// Permit to invoke VM code.
@@ -42,6 +49,7 @@ pub struct VmPermit {
     /// A handle to the runtime that is used to query the VM storage.
     rt_handle: Handle,
     _permit: Arc<tokio::sync::OwnedSemaphorePermit>,
+    _client_permit: Option<Arc<tokio::sync::OwnedSemaphorePermit>>,
 }
 
 impl VmPermit {
@@ -102,9 +110,19 @@ impl VmConcurrencyBarrier {
 pub struct VmConcurrencyLimiter {
     /// Semaphore that limits the number of concurrent VM executions.
     limiter: Arc<tokio::sync::Semaphore>,
+    client_limiter: Option<ClientLimiter>,
     rt_handle: Handle,
 }
 
+/// Error acquiring a [`VmPermit`].
+#[derive(Debug, thiserror::Error)]
+pub enum VmPermitError {
+    #[error("VM concurrency limiter is closed")]
+    Closed,
+    #[error("timed out waiting for a VM permit")]
+    Timeout,
+}
+
 impl VmConcurrencyLimiter {
     /// Creates a limiter together with a barrier allowing to control its shutdown.
     pub fn new(max_concurrency: usize) -> (Self, VmConcurrencyBarrier) {
@@ -115,6 +133,7 @@ impl VmConcurrencyLimiter {
 
         let this = Self {
             limiter: Arc::clone(&limiter),
+            client_limiter: None,
             rt_handle: Handle::current(),
         };
         let barrier = VmConcurrencyBarrier {
@@ -124,16 +143,46 @@ impl VmConcurrencyLimiter {
         (this, barrier)
     }
 
+    /// Enables fair scheduling of VM permits among API clients. Requests not attributed to a client
+    /// are only subject to the global limit.
+    #[must_use]
+    pub fn with_fair_scheduling(mut self, config: VmFairSchedulingConfig) -> Self {
+        tracing::info!("Enabling fair VM scheduling among API clients: {config:?}");
+        self.client_limiter = Some(ClientLimiter::new(config));
+        self
+    }
+
     /// Waits until there is a free slot in the concurrency limiter.
     /// Returns a permit that should be dropped when the VM execution is finished.
-    pub async fn acquire(&self) -> Option<VmPermit> {
+    ///
+    /// If fair scheduling is enabled and the call is attributed to a client, the client must also have
+    /// a free share of permits, and waiting for the permit is bounded by the configured timeout.
+    pub async fn acquire(&self) -> Result<VmPermit, VmPermitError> {
         let available_permits = self.limiter.available_permits();
         SANDBOX_METRICS
             .sandbox_execution_permits
             .observe(available_permits);
 
         let latency = SANDBOX_METRICS.sandbox[&SandboxStage::VmConcurrencyLimiterAcquire].start();
-        let permit = Arc::clone(&self.limiter).acquire_owned().await.ok()?;
+        let client = self.client_limiter.as_ref().zip(ClientId::current());
+        let (client_permit, permit) = if let Some((client_limiter, client)) = client {
+            let acquire = async {
+                let client_permit = client_limiter.acquire(&client).await;
+                let client_permit = client_permit.ok_or(VmPermitError::Closed)?;
+                let permit = Arc::clone(&self.limiter).acquire_owned().await;
+                let permit = permit.map_err(|_| VmPermitError::Closed)?;
+                Ok::<_, VmPermitError>((Some(client_permit), permit))
+            };
+            let timeout = client_limiter.acquire_timeout();
+            tokio::time::timeout(timeout, acquire).await.map_err(|_| {
+                SANDBOX_METRICS.vm_permit_timeouts.inc();
+                tracing::info!("Timed out waiting for a VM permit for client {client:?}");
+                VmPermitError::Timeout
+            })??
+        } else {
+            let permit = Arc::clone(&self.limiter).acquire_owned().await;
+            (None, permit.map_err(|_| VmPermitError::Closed)?)
+        };
         let elapsed = latency.observe();
         // We don't want to emit too many logs.
         if elapsed > Duration::from_millis(10) {
@@ -142,9 +191,10 @@ impl VmConcurrencyLimiter {
             );
         }
 
-        Some(VmPermit {
+        Ok(VmPermit {
             rt_handle: self.rt_handle.clone(),
             _permit: Arc::new(permit),
+            _client_permit: client_permit.map(Arc::new),
         })
     }
 }
@@ -172,6 +222,8 @@ pub(crate) struct TxSharedArgs {
     pub validation_computational_gas_limit: u32,
     pub chain_id: L2ChainId,
     pub whitelisted_tokens_for_aa: Vec<Address>,
+    /// Pool of reusable VM memory. If `None`, memory is allocated anew for each VM.
+    pub vm_memory_pool: Option<Arc<VmMemoryPool>>,
 }
 
 impl TxSharedArgs {
@@ -185,6 +237,7 @@ impl TxSharedArgs {
             validation_computational_gas_limit: u32::MAX,
             chain_id: L2ChainId::default(),
             whitelisted_tokens_for_aa: Vec::new(),
+            vm_memory_pool: None,
         }
     }
 }
@@ -407,6 +460,13 @@ impl BlockArgs {
         self.resolved_block_number
     }
 
+    pub fn is_pending(&self) -> bool {
+        matches!(
+            self.block_id,
+            api::BlockId::Number(api::BlockNumber::Pending)
+        )
+    }
+
     pub fn resolves_to_latest_sealed_l2_block(&self) -> bool {
         matches!(
             self.block_id,
@@ -4,6 +4,12 @@ use assert_matches::assert_matches;
 use zksync_dal::ConnectionPool;
 use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
 use zksync_node_test_utils::{create_l2_block, create_l2_transaction, prepare_recovery_snapshot};
+use zksync_system_constants::{DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE, SYSTEM_CONTEXT_ADDRESS};
+use zksync_types::{
+    fee::Fee, l2::L2Tx, transaction_request::PaymasterParams, utils::storage_key_for_eth_balance,
+    K256PrivateKey, Nonce, Transaction, U256,
+};
+use zksync_utils::h256_to_u256;
 
 use super::*;
 use crate::{execution_sandbox::apply::apply_vm_in_sandbox, tx_sender::ApiContracts};
@@ -204,3 +210,125 @@ async fn test_instantiating_vm(pool: ConnectionPool<Core>, block_args: BlockArgs
     .expect("VM instantiation panicked")
     .expect("VM instantiation errored");
 }
+
+fn create_transfer(from: &K256PrivateKey, to: Address, value: u64) -> Transaction {
+    let fee = Fee {
+        gas_limit: 10_000_000.into(),
+        max_fee_per_gas: 1_000.into(),
+        max_priority_fee_per_gas: 0.into(),
+        gas_per_pubdata_limit: DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE.into(),
+    };
+    L2Tx::new_signed(
+        to,
+        vec![],
+        Nonce(0),
+        fee,
+        value.into(),
+        L2ChainId::default(),
+        from,
+        None,
+        PaymasterParams::default(),
+    )
+    .unwrap()
+    .into()
+}
+
+#[tokio::test]
+async fn executing_transaction_on_top_of_prior_txs() {
+    const BASE_FEE: u64 = 555;
+
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    let block_args = BlockArgs::pending(&mut storage).await.unwrap();
+    drop(storage);
+
+    // Alice transfers value to Bob, who then transfers a part of it further. Bob's transfer can only succeed
+    // if Alice's transfer is taken into account.
+    let alice = K256PrivateKey::random();
+    let bob = K256PrivateKey::random();
+    let transfer_to_bob = create_transfer(&alice, bob.address(), 1_000_000);
+    let recipient = Address::repeat_byte(1);
+    let transfer_from_bob = create_transfer(&bob, recipient, 400_000);
+
+    let executor = TransactionExecutor::Real;
+    let shared_args = || TxSharedArgs::mock(ApiContracts::load_from_disk().estimate_gas);
+    let (vm_concurrency_limiter, _) = VmConcurrencyLimiter::new(1);
+    let vm_permit = vm_concurrency_limiter.acquire().await.unwrap();
+
+    let mut execution_args = TxExecutionArgs::for_gas_estimate(None, &transfer_to_bob, BASE_FEE);
+    execution_args.added_balance += U256::from(1_000_000);
+    let snapshot = executor
+        .extend_prior_txs_snapshot(
+            vm_permit.clone(),
+            shared_args(),
+            execution_args,
+            pool.clone(),
+            transfer_to_bob,
+            block_args,
+        )
+        .await
+        .unwrap();
+    assert_eq!(snapshot.tx_count, 1);
+    let bob_balance = snapshot.storage_writes[&storage_key_for_eth_balance(&bob.address())];
+    assert_eq!(h256_to_u256(bob_balance), 1_000_000.into());
+    // The L2 block context must not be captured.
+    assert!(snapshot
+        .storage_writes
+        .keys()
+        .all(|key| *key.address() != SYSTEM_CONTEXT_ADDRESS));
+
+    let execution_args = TxExecutionArgs::for_gas_estimate(None, &transfer_from_bob, BASE_FEE);
+    let output = executor
+        .execute_tx_in_sandbox(
+            vm_permit.clone(),
+            shared_args(),
+            true,
+            execution_args,
+            pool.clone(),
+            transfer_from_bob.clone(),
+            block_args,
+            vec![],
+        )
+        .await
+        .unwrap();
+    assert!(output.vm.result.is_failed(), "{:?}", output.vm.result);
+
+    let snapshot = Arc::new(snapshot);
+    let mut execution_args = TxExecutionArgs::for_gas_estimate(None, &transfer_from_bob, BASE_FEE);
+    execution_args.prior_txs = Some(snapshot.clone());
+    let output = executor
+        .execute_tx_in_sandbox(
+            vm_permit.clone(),
+            shared_args(),
+            true,
+            execution_args,
+            pool.clone(),
+            transfer_from_bob.clone(),
+            block_args,
+            vec![],
+        )
+        .await
+        .unwrap();
+    assert!(!output.vm.result.is_failed(), "{:?}", output.vm.result);
+
+    // The snapshot can be extended with the dependent transaction.
+    let mut execution_args = TxExecutionArgs::for_gas_estimate(None, &transfer_from_bob, BASE_FEE);
+    execution_args.prior_txs = Some(snapshot);
+    let snapshot = executor
+        .extend_prior_txs_snapshot(
+            vm_permit,
+            shared_args(),
+            execution_args,
+            pool,
+            transfer_from_bob,
+            block_args,
+        )
+        .await
+        .unwrap();
+    assert_eq!(snapshot.tx_count, 2);
+    let recipient_balance = snapshot.storage_writes[&storage_key_for_eth_balance(&recipient)];
+    assert_eq!(h256_to_u256(recipient_balance), 400_000.into());
+}
@@ -0,0 +1,104 @@
+//! Pool of reusable VM memory for sandboxed VM executions.
+//!
+//! Constructing VM memory (i.e., populating the bootloader code page and decommitting the default account)
+//! is a noticeable source of allocations for each `eth_call` and gas estimation iteration. Since the API server
+//! uses only a few sets of base system contracts, VM memory can be recovered after execution and reused.
+
+use std::{collections::HashMap, fmt, num::NonZeroUsize, sync::Mutex};
+
+use multivm::vm_latest::{HistoryDisabled, ReusableVmMemory};
+use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes};
+
+use super::vm_metrics::{VmMemoryPoolOutcome, SANDBOX_METRICS};
+
+pub(super) type PooledVmMemory = ReusableVmMemory<HistoryDisabled>;
+
+/// Pool of idle VM memory instances keyed by base system contract hashes.
+pub(crate) struct VmMemoryPool {
+    max_idle_per_contracts: usize,
+    idle: Mutex<HashMap<BaseSystemContractsHashes, Vec<PooledVmMemory>>>,
+}
+
+impl fmt::Debug for VmMemoryPool {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Memory contents are deliberately not output; they are huge.
+        formatter
+            .debug_struct("VmMemoryPool")
+            .field("max_idle_per_contracts", &self.max_idle_per_contracts)
+            .finish_non_exhaustive()
+    }
+}
+
+impl VmMemoryPool {
+    /// Creates a pool retaining up to `max_idle_per_contracts` idle instances for each set of base system contracts.
+    pub fn new(max_idle_per_contracts: NonZeroUsize) -> Self {
+        Self {
+            max_idle_per_contracts: max_idle_per_contracts.get(),
+            idle: Mutex::default(),
+        }
+    }
+
+    /// Takes memory initialized with the specified contracts from the pool, or allocates new memory
+    /// if there is no idle memory in the pool.
+    pub fn take(&self, base_system_contracts: &BaseSystemContracts) -> PooledVmMemory {
+        let hashes = base_system_contracts.hashes();
+        let memory = self
+            .idle
+            .lock()
+            .expect("VM memory pool is poisoned")
+            .get_mut(&hashes)
+            .and_then(Vec::pop);
+
+        if let Some(memory) = memory {
+            SANDBOX_METRICS.vm_memory_pool[&VmMemoryPoolOutcome::Reused].inc();
+            memory
+        } else {
+            SANDBOX_METRICS.vm_memory_pool[&VmMemoryPoolOutcome::Allocated].inc();
+            PooledVmMemory::new(base_system_contracts)
+        }
+    }
+
+    /// Returns memory recovered from a VM to the pool. If the pool is full, the memory is dropped.
+    pub fn put(&self, memory: PooledVmMemory) {
+        let mut idle = self.idle.lock().expect("VM memory pool is poisoned");
+        let idle_for_contracts = idle
+            .entry(memory.base_system_contracts_hashes())
+            .or_default();
+        if idle_for_contracts.len() < self.max_idle_per_contracts {
+            idle_for_contracts.push(memory);
+        }
+    }
+
+    #[cfg(test)]
+    fn idle_count(&self, hashes: &BaseSystemContractsHashes) -> usize {
+        let idle = self.idle.lock().expect("VM memory pool is poisoned");
+        idle.get(hashes).map_or(0, Vec::len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vm_memory_pool_basics() {
+        let pool = VmMemoryPool::new(NonZeroUsize::new(1).unwrap());
+        let contracts = BaseSystemContracts::load_from_disk();
+        let hashes = contracts.hashes();
+
+        let memory = pool.take(&contracts);
+        assert_eq!(memory.base_system_contracts_hashes(), hashes);
+        let other_memory = pool.take(&contracts);
+        assert_eq!(pool.idle_count(&hashes), 0);
+
+        pool.put(memory);
+        assert_eq!(pool.idle_count(&hashes), 1);
+        // The pool is full, so the memory should be dropped.
+        pool.put(other_memory);
+        assert_eq!(pool.idle_count(&hashes), 1);
+
+        let memory = pool.take(&contracts);
+        assert_eq!(memory.base_system_contracts_hashes(), hashes);
+        assert_eq!(pool.idle_count(&hashes), 0);
+    }
+}
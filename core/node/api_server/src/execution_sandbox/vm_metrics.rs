@@ -2,7 +2,8 @@ use std::time::Duration;
 
 use multivm::interface::{VmExecutionResultAndLogs, VmMemoryMetrics};
 use vise::{
-    Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver, Metrics,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver,
+    Metrics,
 };
 use zksync_shared_metrics::InteractionType;
 use zksync_state::StorageViewMetrics;
@@ -100,6 +101,20 @@ pub(crate) enum SubmitTxStage {
     DbInsert,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "outcome", rename_all = "snake_case")]
+pub(super) enum EthCallCacheOutcome {
+    Hit,
+    Miss,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "outcome", rename_all = "snake_case")]
+pub(super) enum VmMemoryPoolOutcome {
+    Reused,
+    Allocated,
+}
+
 #[must_use = "should be `observe()`d"]
 #[derive(Debug)]
 pub(crate) struct SubmitTxLatencyObserver<'a> {
@@ -154,6 +169,14 @@ pub(crate) struct SandboxMetrics {
     submit_tx: Family<SubmitTxStage, Histogram<Duration>>,
     #[metrics(buckets = Buckets::linear(0.0..=30.0, 3.0))]
     pub estimate_gas_binary_search_iterations: Histogram<usize>,
+    /// Number of `eth_call` cache lookups grouped by the outcome.
+    pub(super) eth_call_cache: Family<EthCallCacheOutcome, Counter>,
+    /// Number of requests that have timed out waiting for a VM permit.
+    pub(super) vm_permit_timeouts: Counter,
+    /// Number of VM memory instances taken from the pool grouped by whether the memory was reused.
+    pub(super) vm_memory_pool: Family<VmMemoryPoolOutcome, Counter>,
+    /// Number of rejected transactions not recorded in the rejected transactions journal because of the rate limit.
+    pub(crate) skipped_rejected_txs: Counter,
 }
 
 impl SandboxMetrics {
@@ -1,18 +1,71 @@
-use std::collections::hash_map::{Entry, HashMap};
+use std::{
+    collections::hash_map::{Entry, HashMap},
+    sync,
+    time::{Duration, Instant},
+};
 
 use tokio::sync::Mutex;
-use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool, Core, CoreDal};
+use zksync_config::configs::api::Web3JsonRpcConfig;
+use zksync_dal::{
+    transactions_dal::{L2TxSubmissionResult, PendingL2TxsSize},
+    Connection, ConnectionPool, Core, CoreDal, DalError,
+};
 use zksync_shared_metrics::{TxStage, APP_METRICS};
-use zksync_types::{fee::TransactionExecutionMetrics, l2::L2Tx, Address, Nonce, H256};
+use zksync_types::{
+    api::{RejectedTransaction, TransactionDeadline},
+    fee::TransactionExecutionMetrics,
+    l2::L2Tx,
+    Address, Nonce, H256,
+};
 
 use super::{tx_sink::TxSink, SubmitTxError};
 use crate::web3::metrics::API_METRICS;
 
+/// Quotas on pending L2 transactions in the mempool.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MempoolQuotas {
+    /// Maximum number of pending transactions from a single sender.
+    pub max_txs_per_sender: Option<u32>,
+    /// Maximum number of pending transactions.
+    pub max_txs: Option<u64>,
+    /// Maximum total size of pending transactions in bytes.
+    pub max_size_bytes: Option<u64>,
+}
+
+impl MempoolQuotas {
+    pub fn from_web3_config(config: &Web3JsonRpcConfig) -> Self {
+        Self {
+            max_txs_per_sender: config.mempool_max_txs_per_sender,
+            max_txs: config.mempool_max_txs,
+            max_size_bytes: config.mempool_max_size_bytes(),
+        }
+    }
+
+    fn has_global_quotas(&self) -> bool {
+        self.max_txs.is_some() || self.max_size_bytes.is_some()
+    }
+}
+
+/// Interval after which the cached mempool size used to check global quotas is reloaded from Postgres.
+const MEMPOOL_SIZE_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Mempool size snapshot used to check global quotas. Between refreshes, the snapshot is adjusted
+/// for transactions inserted and evicted by this server, but not for ones inserted, evicted or executed
+/// via other means.
+#[derive(Debug, Clone, Copy)]
+struct CachedMempoolSize {
+    size: PendingL2TxsSize,
+    refreshed_at: Instant,
+}
+
 /// Wrapper for the master DB pool that allows to submit transactions to the mempool.
 #[derive(Debug)]
 pub struct MasterPoolSink {
     master_pool: ConnectionPool<Core>,
     inflight_requests: Mutex<HashMap<(Address, Nonce), H256>>,
+    replacement_fee_bump_percent: u32,
+    quotas: MempoolQuotas,
+    mempool_size: sync::Mutex<Option<CachedMempoolSize>>,
 }
 
 impl MasterPoolSink {
@@ -20,6 +73,204 @@ impl MasterPoolSink {
         Self {
             master_pool,
             inflight_requests: Mutex::new(HashMap::new()),
+            replacement_fee_bump_percent: 0,
+            quotas: MempoolQuotas::default(),
+            mempool_size: sync::Mutex::new(None),
+        }
+    }
+
+    /// Sets the minimum fee bump (in percent) required to replace a pending transaction with the same nonce.
+    pub fn with_replacement_fee_bump(mut self, percent: u32) -> Self {
+        self.replacement_fee_bump_percent = percent;
+        self
+    }
+
+    /// Sets quotas on pending transactions in the mempool.
+    pub fn with_quotas(mut self, quotas: MempoolQuotas) -> Self {
+        self.quotas = quotas;
+        self
+    }
+
+    async fn insert_tx(
+        &self,
+        connection: &mut Connection<'_, Core>,
+        tx: &L2Tx,
+        execution_metrics: TransactionExecutionMetrics,
+    ) -> Result<L2TxSubmissionResult, SubmitTxError> {
+        let mut transaction = connection
+            .start_transaction()
+            .await
+            .map_err(DalError::generalize)?;
+        // `inflight_requests` only deduplicates submissions to this server. The lock below additionally serializes
+        // submissions with the same initiator and nonce across all API servers sharing the database, so that
+        // a transaction submitted concurrently to several servers is deterministically reported as a duplicate
+        // by all of them except one.
+        transaction
+            .transactions_dal()
+            .lock_l2_tx_submission(tx.initiator_account(), tx.nonce())
+            .await
+            .map_err(DalError::generalize)?;
+        // Check whether the transaction would be rejected before enforcing quotas, so that a rejected submission
+        // doesn't evict other pending transactions.
+        let rejection = transaction
+            .transactions_dal()
+            .check_l2_tx_insertion(tx, self.replacement_fee_bump_percent)
+            .await
+            .map_err(DalError::generalize)?;
+        if let Some(submission_res_handle) = rejection {
+            transaction.rollback().await.map_err(DalError::generalize)?;
+            APP_METRICS.processed_txs[&TxStage::Mempool(submission_res_handle)].inc();
+            return Ok(submission_res_handle);
+        }
+
+        let evicted = self.enforce_quotas(&mut transaction, tx).await?;
+        let submission_res_handle = transaction
+            .transactions_dal()
+            .insert_transaction_l2_with_fee_bump(
+                tx,
+                execution_metrics,
+                self.replacement_fee_bump_percent,
+            )
+            .await
+            .map_err(DalError::generalize)?;
+        let inserted = match submission_res_handle {
+            L2TxSubmissionResult::Added => PendingL2TxsSize {
+                count: 1,
+                size_bytes: Self::tx_size(tx),
+            },
+            L2TxSubmissionResult::Replaced => PendingL2TxsSize::default(),
+            _ => {
+                // Shouldn't normally happen because of the check above, but if it does, evictions must not persist.
+                transaction.rollback().await.map_err(DalError::generalize)?;
+                APP_METRICS.processed_txs[&TxStage::Mempool(submission_res_handle)].inc();
+                return Ok(submission_res_handle);
+            }
+        };
+        transaction.commit().await.map_err(DalError::generalize)?;
+
+        self.adjust_cached_mempool_size(inserted, evicted);
+        APP_METRICS.processed_txs[&TxStage::Mempool(submission_res_handle)].inc();
+        Ok(submission_res_handle)
+    }
+
+    fn tx_size(tx: &L2Tx) -> u64 {
+        tx.common_data
+            .input_data()
+            .map_or(0, |data| data.len() as u64)
+    }
+
+    /// Returns the cached mempool size, reloading it from Postgres if it is stale.
+    async fn mempool_size(
+        &self,
+        connection: &mut Connection<'_, Core>,
+    ) -> Result<PendingL2TxsSize, SubmitTxError> {
+        let cached = *self.mempool_size.lock().unwrap();
+        if let Some(cached) = cached {
+            if cached.refreshed_at.elapsed() < MEMPOOL_SIZE_REFRESH_INTERVAL {
+                return Ok(cached.size);
+            }
+        }
+
+        let size = connection
+            .transactions_dal()
+            .get_pending_l2_txs_size()
+            .await
+            .map_err(DalError::generalize)?;
+        *self.mempool_size.lock().unwrap() = Some(CachedMempoolSize {
+            size,
+            refreshed_at: Instant::now(),
+        });
+        Ok(size)
+    }
+
+    fn adjust_cached_mempool_size(&self, inserted: PendingL2TxsSize, evicted: PendingL2TxsSize) {
+        if let Some(cached) = self.mempool_size.lock().unwrap().as_mut() {
+            let size = &mut cached.size;
+            size.count = (size.count + inserted.count).saturating_sub(evicted.count);
+            size.size_bytes =
+                (size.size_bytes + inserted.size_bytes).saturating_sub(evicted.size_bytes);
+        }
+    }
+
+    /// Checks mempool quotas for the transaction, evicting pending transactions with lower fees if necessary.
+    /// Returns the number and total size of evicted transactions.
+    ///
+    /// The per-sender quota is checked exactly. Global quotas are checked approximately against a mempool size
+    /// snapshot refreshed every [`MEMPOOL_SIZE_REFRESH_INTERVAL`], so that submissions neither contend
+    /// on a global lock nor scan the entire mempool. As a consequence, concurrent submissions via several API servers
+    /// may overflow global quotas by the number of transactions they accept within the refresh interval.
+    async fn enforce_quotas(
+        &self,
+        connection: &mut Connection<'_, Core>,
+        tx: &L2Tx,
+    ) -> Result<PendingL2TxsSize, SubmitTxError> {
+        let mut evicted = PendingL2TxsSize::default();
+
+        // The quota lock makes the check atomic with the following insertion; without it, concurrent submissions
+        // (potentially via different API servers) could all pass the check and overflow the quota.
+        if let Some(max_txs_per_sender) = self.quotas.max_txs_per_sender {
+            connection
+                .transactions_dal()
+                .lock_mempool_quota(tx.initiator_account())
+                .await
+                .map_err(DalError::generalize)?;
+            let pending_txs_count = connection
+                .transactions_dal()
+                .get_pending_l2_txs_count_for_sender(tx.initiator_account(), tx.nonce())
+                .await
+                .map_err(DalError::generalize)?;
+            if pending_txs_count >= u64::from(max_txs_per_sender) {
+                return Err(SubmitTxError::SenderMempoolQuotaExceeded(
+                    max_txs_per_sender,
+                ));
+            }
+        }
+        if !self.quotas.has_global_quotas() {
+            return Ok(evicted);
+        }
+
+        let max_txs = self.quotas.max_txs.unwrap_or(u64::MAX);
+        let max_size_bytes = self.quotas.max_size_bytes.unwrap_or(u64::MAX);
+        let tx_size = Self::tx_size(tx);
+        let size = self.mempool_size(connection).await?;
+        loop {
+            let count = size.count.saturating_sub(evicted.count);
+            let size_bytes = size.size_bytes.saturating_sub(evicted.size_bytes);
+            if count < max_txs && size_bytes.saturating_add(tx_size) <= max_size_bytes {
+                return Ok(evicted);
+            }
+
+            let Some(candidate) = connection
+                .transactions_dal()
+                .get_l2_tx_eviction_candidate()
+                .await
+                .map_err(DalError::generalize)?
+            else {
+                // All pending transactions are either loaded into the state keeper mempool, or cannot be evicted
+                // without producing nonce gaps.
+                return Err(SubmitTxError::MempoolIsFullNoEviction);
+            };
+            if candidate.max_fee_per_gas >= tx.common_data.fee.max_fee_per_gas {
+                let min_fee_per_gas = candidate.max_fee_per_gas.saturating_add(1.into());
+                return Err(SubmitTxError::MempoolIsFull(min_fee_per_gas));
+            }
+            let was_evicted = connection
+                .transactions_dal()
+                .evict_pending_l2_tx(candidate.hash)
+                .await
+                .map_err(DalError::generalize)?;
+            if was_evicted {
+                tracing::info!(
+                    "Evicted pending transaction {:?} with max fee per gas {} from the full mempool \
+                     in favor of transaction {:?}",
+                    candidate.hash,
+                    candidate.max_fee_per_gas,
+                    tx.hash()
+                );
+                API_METRICS.evicted_mempool_txs.inc();
+                evicted.count += 1;
+                evicted.size_bytes += candidate.size_bytes;
+            }
         }
     }
 }
@@ -52,15 +303,7 @@ impl TxSink for MasterPoolSink {
         drop(lock);
 
         let result = match self.master_pool.connection_tagged("api").await {
-            Ok(mut connection) => connection
-                .transactions_dal()
-                .insert_transaction_l2(tx, execution_metrics)
-                .await
-                .map(|submission_res_handle| {
-                    APP_METRICS.processed_txs[&TxStage::Mempool(submission_res_handle)].inc();
-                    submission_res_handle
-                })
-                .map_err(|err| err.generalize().into()),
+            Ok(mut connection) => self.insert_tx(&mut connection, tx, execution_metrics).await,
             Err(err) => Err(err.generalize().into()),
         };
 
@@ -72,4 +315,40 @@ impl TxSink for MasterPoolSink {
 
         result
     }
+
+    async fn record_rejected_tx(&self, tx: &RejectedTransaction) -> anyhow::Result<()> {
+        let mut connection = self.master_pool.connection_tagged("api").await?;
+        connection
+            .rejected_transactions_dal()
+            .insert_rejected_transaction(tx)
+            .await?;
+        Ok(())
+    }
+
+    async fn insert_tx_deadline(
+        &self,
+        tx_hash: H256,
+        deadline: &TransactionDeadline,
+    ) -> Result<bool, SubmitTxError> {
+        let mut connection = self
+            .master_pool
+            .connection_tagged("api")
+            .await
+            .map_err(DalError::generalize)?;
+        let inserted = connection
+            .transaction_deadlines_dal()
+            .insert_deadline(tx_hash, deadline)
+            .await
+            .map_err(DalError::generalize)?;
+        Ok(inserted)
+    }
+
+    async fn remove_tx_deadline(&self, tx_hash: H256) -> anyhow::Result<()> {
+        let mut connection = self.master_pool.connection_tagged("api").await?;
+        connection
+            .transaction_deadlines_dal()
+            .remove_deadline(tx_hash)
+            .await?;
+        Ok(())
+    }
 }
@@ -1,8 +1,19 @@
 //! Helper module to submit transactions into the zkSync Network.
 
-use std::{sync::Arc, time::Instant};
+use std::{
+    num::{NonZeroU32, NonZeroUsize},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
+use chrono::Utc;
+use governor::{
+    clock::DefaultClock,
+    middleware::NoOpMiddleware,
+    state::{InMemoryState, NotKeyed},
+    Quota, RateLimiter,
+};
 use multivm::{
     interface::VmExecutionResultAndLogs,
     utils::{
@@ -13,9 +24,9 @@ use multivm::{
 };
 use tokio::sync::RwLock;
 use zksync_config::configs::{api::Web3JsonRpcConfig, chain::StateKeeperConfig};
-use zksync_contracts::BaseSystemContracts;
+use zksync_contracts::{BaseSystemContracts, SystemContractCode};
 use zksync_dal::{
-    transactions_dal::L2TxSubmissionResult, Connection, ConnectionPool, Core, CoreDal,
+    transactions_dal::L2TxSubmissionResult, Connection, ConnectionPool, Core, CoreDal, DalError,
 };
 use zksync_node_fee_model::{ApiFeeInputProvider, BatchFeeModelInputProvider};
 use zksync_state::PostgresStorageCaches;
@@ -24,24 +35,30 @@ use zksync_state_keeper::{
     SequencerSealer,
 };
 use zksync_types::{
+    api::{RejectedTransaction, TransactionDeadline},
     fee::{Fee, TransactionExecutionMetrics},
     fee_model::BatchFeeInput,
     get_code_key, get_intrinsic_constants,
-    l2::{error::TxCheckError::TxDuplication, L2Tx},
+    l1::L1Tx,
+    l2::{error::TxCheckError::TxDuplication, L2Tx, TransactionType},
     transaction_request::CallOverrides,
     utils::storage_key_for_eth_balance,
-    AccountTreeId, Address, ExecuteTransactionCommon, L2ChainId, Nonce, PackedEthSignature,
-    ProtocolVersionId, Transaction, VmVersion, H160, H256, MAX_L2_TX_GAS_LIMIT,
+    AccountTreeId, Address, ExecuteTransactionCommon, L2BlockNumber, L2ChainId, Nonce,
+    PackedEthSignature, ProtocolVersionId, Transaction, VmVersion, H160, H256, MAX_L2_TX_GAS_LIMIT,
     MAX_NEW_FACTORY_DEPS, U256,
 };
-use zksync_utils::h256_to_u256;
+use zksync_utils::{bytes_to_be_words, h256_to_u256, time::seconds_since_epoch};
 
 pub(super) use self::result::SubmitTxError;
-use self::{master_pool_sink::MasterPoolSink, tx_sink::TxSink};
+use self::{
+    master_pool_sink::{MasterPoolSink, MempoolQuotas},
+    tx_sink::TxSink,
+};
 use crate::{
     execution_sandbox::{
-        BlockArgs, SubmitTxStage, TransactionExecutor, TxExecutionArgs, TxSharedArgs,
-        VmConcurrencyBarrier, VmConcurrencyLimiter, VmPermit, SANDBOX_METRICS,
+        BlockArgs, EthCallCache, EthCallCacheKey, PriorTxsSnapshot, SubmitTxStage,
+        TransactionExecutor, TxExecutionArgs, TxSharedArgs, VmConcurrencyBarrier,
+        VmConcurrencyLimiter, VmFairSchedulingConfig, VmMemoryPool, VmPermit, SANDBOX_METRICS,
     },
     tx_sender::result::ApiCallResult,
 };
@@ -53,6 +70,28 @@ mod result;
 pub(crate) mod tests;
 pub mod tx_sink;
 
+/// Maximum number of rejected transactions recorded in the rejected transactions journal per second. Rejections
+/// above this rate are not recorded, so that spamming invalid transactions doesn't translate into DB writes.
+const MAX_RECORDED_REJECTED_TXS_PER_SECOND: u32 = 50;
+
+type RejectedTxsLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock, NoOpMiddleware>;
+
+/// Computes funds minted for an L1 transaction by the L1 contracts, i.e. `gas_limit * max_fee_per_gas + value`.
+/// Returns `None` on overflow.
+fn required_l1_tx_funds(gas_limit: U256, max_fee_per_gas: U256, value: U256) -> Option<U256> {
+    gas_limit.checked_mul(max_fee_per_gas)?.checked_add(value)
+}
+
+/// Output of [`TxSender::simulate_l1_tx()`].
+#[derive(Debug)]
+pub(crate) struct L1TxSimulationOutput {
+    /// Simulated transaction with the fee params and minted funds filled in.
+    pub tx: L1Tx,
+    /// Base fee used for the simulation.
+    pub base_fee: u64,
+    pub vm: VmExecutionResultAndLogs,
+}
+
 pub async fn build_tx_sender(
     tx_sender_config: &TxSenderConfig,
     web3_json_config: &Web3JsonRpcConfig,
@@ -63,7 +102,9 @@ pub async fn build_tx_sender(
     storage_caches: PostgresStorageCaches,
 ) -> (TxSender, VmConcurrencyBarrier) {
     let sequencer_sealer = SequencerSealer::new(state_keeper_config.clone());
-    let master_pool_sink = MasterPoolSink::new(master_pool);
+    let master_pool_sink = MasterPoolSink::new(master_pool)
+        .with_replacement_fee_bump(web3_json_config.tx_replacement_fee_bump_percent())
+        .with_quotas(MempoolQuotas::from_web3_config(web3_json_config));
     let tx_sender_builder = TxSenderBuilder::new(
         tx_sender_config.clone(),
         replica_pool.clone(),
@@ -72,7 +113,10 @@ pub async fn build_tx_sender(
     .with_sealer(Arc::new(sequencer_sealer));
 
     let max_concurrency = web3_json_config.vm_concurrency_limit();
-    let (vm_concurrency_limiter, vm_barrier) = VmConcurrencyLimiter::new(max_concurrency);
+    let (mut vm_concurrency_limiter, vm_barrier) = VmConcurrencyLimiter::new(max_concurrency);
+    if let Some(config) = VmFairSchedulingConfig::from_web3_config(web3_json_config) {
+        vm_concurrency_limiter = vm_concurrency_limiter.with_fair_scheduling(config);
+    }
 
     let batch_fee_input_provider =
         ApiFeeInputProvider::new(batch_fee_model_input_provider, replica_pool);
@@ -141,6 +185,19 @@ impl MultiVMBaseSystemContracts {
             }
         }
     }
+
+    /// Attaches the EVM emulator to the contracts for protocol versions supporting EVM emulation (i.e., ones
+    /// executed with the latest VM). The state keeper loads the emulator for all batches, so the sandbox must do
+    /// the same to produce consistent gas estimates.
+    pub(crate) fn with_evm_emulator(mut self, evm_emulator: SystemContractCode) -> Self {
+        self.vm_1_5_0_small_memory = self
+            .vm_1_5_0_small_memory
+            .with_evm_emulator(evm_emulator.clone());
+        self.vm_1_5_0_increased_memory = self
+            .vm_1_5_0_increased_memory
+            .with_evm_emulator(evm_emulator);
+        self
+    }
 }
 
 /// Smart contracts to be used in the API sandbox requests, e.g. for estimating gas and
@@ -246,6 +303,18 @@ impl TxSenderBuilder {
             self.whitelisted_tokens_for_aa_cache.unwrap_or_else(|| {
                 Arc::new(RwLock::new(self.config.whitelisted_tokens_for_aa.clone()))
             });
+        let eth_call_cache = self
+            .config
+            .eth_call_cache_size
+            .map(|capacity| EthCallCache::new(capacity, self.config.eth_call_cache_ttl));
+        let vm_memory_pool = self
+            .config
+            .vm_memory_pool_size
+            .map(|size| Arc::new(VmMemoryPool::new(size)));
+        let rejected_txs_limiter = self.config.rejected_txs_retention.map(|_| {
+            let quota = NonZeroU32::new(MAX_RECORDED_REJECTED_TXS_PER_SECOND).unwrap();
+            RateLimiter::direct(Quota::per_second(quota))
+        });
 
         TxSender(Arc::new(TxSenderInner {
             sender_config: self.config,
@@ -258,6 +327,10 @@ impl TxSenderBuilder {
             whitelisted_tokens_for_aa_cache,
             sealer,
             executor: TransactionExecutor::Real,
+            eth_call_cache,
+            vm_memory_pool,
+            evm_emulator: tokio::sync::OnceCell::new(),
+            rejected_txs_limiter,
         }))
     }
 }
@@ -276,6 +349,23 @@ pub struct TxSenderConfig {
     pub validation_computational_gas_limit: u32,
     pub chain_id: L2ChainId,
     pub whitelisted_tokens_for_aa: Vec<Address>,
+    /// Maximum number of cached `eth_call` results. If `None`, `eth_call` results are not cached.
+    pub eth_call_cache_size: Option<NonZeroUsize>,
+    pub eth_call_cache_ttl: Duration,
+    /// Retention period for rejected transactions. If `None`, rejected transactions are not recorded.
+    /// Recorded transactions are pruned by the house keeper rather than the API server.
+    pub rejected_txs_retention: Option<Duration>,
+    /// Minimum `gas_per_pubdata_limit` accepted for submitted transactions.
+    pub min_gas_per_pubdata_limit: Option<u64>,
+    /// Maximum `gas_per_pubdata_limit` accepted for submitted transactions.
+    pub max_gas_per_pubdata_limit: Option<u64>,
+    /// Whether to reject legacy transactions signed without a chain ID (i.e., not protected against replays).
+    pub reject_unprotected_txs: bool,
+    /// Maximum number of idle VM memory instances retained per set of base system contracts. If `None`,
+    /// VM memory is not reused.
+    pub vm_memory_pool_size: Option<NonZeroUsize>,
+    /// Hash of the EVM emulator bytecode. If set, the EVM emulator is used in the API sandbox.
+    pub evm_emulator_hash: Option<H256>,
 }
 
 impl TxSenderConfig {
@@ -295,8 +385,26 @@ impl TxSenderConfig {
                 .validation_computational_gas_limit,
             chain_id,
             whitelisted_tokens_for_aa: web3_json_config.whitelisted_tokens_for_aa.clone(),
+            eth_call_cache_size: web3_json_config
+                .eth_call_cache_size
+                .and_then(NonZeroUsize::new),
+            eth_call_cache_ttl: web3_json_config.eth_call_cache_ttl(),
+            rejected_txs_retention: web3_json_config.rejected_txs_retention(),
+            min_gas_per_pubdata_limit: web3_json_config.min_gas_per_pubdata_limit,
+            max_gas_per_pubdata_limit: web3_json_config.max_gas_per_pubdata_limit,
+            reject_unprotected_txs: web3_json_config.reject_unprotected_txs,
+            vm_memory_pool_size: web3_json_config
+                .vm_memory_pool_size
+                .and_then(NonZeroUsize::new),
+            evm_emulator_hash: None,
         }
     }
+
+    /// Enables the EVM emulator with the specified bytecode hash (usually, taken from the genesis config).
+    pub fn with_evm_emulator_hash(mut self, evm_emulator_hash: Option<H256>) -> Self {
+        self.evm_emulator_hash = evm_emulator_hash;
+        self
+    }
 }
 
 pub struct TxSenderInner {
@@ -316,6 +424,14 @@ pub struct TxSenderInner {
     /// Batch sealer used to check whether transaction can be executed by the sequencer.
     sealer: Arc<dyn ConditionalSealer>,
     pub(super) executor: TransactionExecutor,
+    /// Cache for `eth_call` results.
+    eth_call_cache: Option<EthCallCache>,
+    /// Pool of reusable VM memory.
+    vm_memory_pool: Option<Arc<VmMemoryPool>>,
+    /// EVM emulator code lazily loaded from the storage (only used if the EVM emulator is enabled).
+    evm_emulator: tokio::sync::OnceCell<SystemContractCode>,
+    /// Rate limiter for recording rejected transactions. `None` if rejected transactions are not recorded.
+    rejected_txs_limiter: Option<RejectedTxsLimiter>,
 }
 
 #[derive(Clone)]
@@ -336,6 +452,10 @@ impl TxSender {
         self.0.storage_caches.clone()
     }
 
+    pub(crate) fn vm_memory_pool(&self) -> Option<Arc<VmMemoryPool>> {
+        self.0.vm_memory_pool.clone()
+    }
+
     pub(crate) async fn read_whitelisted_tokens_for_aa_cache(&self) -> Vec<Address> {
         self.0.whitelisted_tokens_for_aa_cache.read().await.clone()
     }
@@ -352,6 +472,81 @@ impl TxSender {
     pub async fn submit_tx(
         &self,
         tx: L2Tx,
+    ) -> Result<(L2TxSubmissionResult, VmExecutionResultAndLogs), SubmitTxError> {
+        let Some(rejected_txs_limiter) = &self.0.rejected_txs_limiter else {
+            return self.submit_tx_inner(tx).await;
+        };
+
+        let (tx_hash, initiator_address, nonce) = (tx.hash(), tx.initiator_account(), tx.nonce());
+        let result = self.submit_tx_inner(tx).await;
+        let Err(err) = &result else {
+            return result;
+        };
+        if !err.is_rejection() {
+            return result;
+        }
+        if rejected_txs_limiter.check().is_err() {
+            SANDBOX_METRICS.skipped_rejected_txs.inc();
+            return result;
+        }
+
+        let rejected_tx = RejectedTransaction {
+            hash: tx_hash,
+            initiator_address,
+            nonce: nonce.0.into(),
+            error_code: err.prom_error_code().to_owned(),
+            reason: err.to_string(),
+            rejected_at: Utc::now(),
+        };
+        if let Err(err) = self.0.tx_sink.record_rejected_tx(&rejected_tx).await {
+            tracing::warn!("Failed recording rejected transaction {tx_hash:?}: {err:#}");
+        }
+        result
+    }
+
+    /// Submits a transaction with the specified inclusion deadline. The deadline is recorded before the transaction
+    /// is propagated to the mempool, so that the state keeper never observes the transaction without its deadline.
+    #[tracing::instrument(level = "debug", skip_all, fields(tx.hash = ?tx.hash()))]
+    pub async fn submit_tx_with_deadline(
+        &self,
+        tx: L2Tx,
+        deadline: TransactionDeadline,
+    ) -> Result<(L2TxSubmissionResult, VmExecutionResultAndLogs), SubmitTxError> {
+        if deadline.is_empty() {
+            return Err(SubmitTxError::EmptyDeadline);
+        }
+        let mut connection = self.acquire_replica_connection().await?;
+        let sealed_l2_block_number = connection
+            .blocks_dal()
+            .get_sealed_l2_block_number()
+            .await
+            .map_err(DalError::generalize)?
+            .unwrap_or(L2BlockNumber(0));
+        drop(connection);
+        // The transaction can be included into the next L2 block at the earliest, and this block cannot have
+        // a timestamp in the past.
+        if !deadline.allows(sealed_l2_block_number + 1, seconds_since_epoch()) {
+            return Err(SubmitTxError::DeadlinePassed);
+        }
+
+        let tx_hash = tx.hash();
+        let inserted = self
+            .0
+            .tx_sink
+            .insert_tx_deadline(tx_hash, &deadline)
+            .await?;
+        let result = self.submit_tx(tx).await;
+        if result.is_err() && inserted {
+            if let Err(err) = self.0.tx_sink.remove_tx_deadline(tx_hash).await {
+                tracing::warn!("Failed removing deadline for transaction {tx_hash:?}: {err:#}");
+            }
+        }
+        result
+    }
+
+    async fn submit_tx_inner(
+        &self,
+        tx: L2Tx,
     ) -> Result<(L2TxSubmissionResult, VmExecutionResultAndLogs), SubmitTxError> {
         let tx_hash = tx.hash();
         let stage_latency = SANDBOX_METRICS.start_tx_submit_stage(tx_hash, SubmitTxStage::Validate);
@@ -363,8 +558,7 @@ impl TxSender {
 
         let stage_latency = SANDBOX_METRICS.start_tx_submit_stage(tx_hash, SubmitTxStage::DryRun);
         let shared_args = self.shared_args().await?;
-        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
-        let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
+        let vm_permit = self.0.vm_concurrency_limiter.acquire().await?;
         let mut connection = self.acquire_replica_connection().await?;
         let block_args = BlockArgs::pending(&mut connection).await?;
         drop(connection);
@@ -441,6 +635,9 @@ impl TxSender {
                 Err(SubmitTxError::IncorrectTx(TxDuplication(tx.hash())))
             }
             L2TxSubmissionResult::InsertionInProgress => Err(SubmitTxError::InsertionInProgress),
+            L2TxSubmissionResult::ReplacementUnderpriced => {
+                Err(SubmitTxError::ReplacementUnderpriced)
+            }
             L2TxSubmissionResult::Proxied => {
                 stage_latency.set_stage(SubmitTxStage::TxProxy);
                 stage_latency.observe();
@@ -465,7 +662,9 @@ impl TxSender {
         Ok(TxSharedArgs {
             operator_account: AccountTreeId::new(self.0.sender_config.fee_account_addr),
             fee_input,
-            base_system_contracts: self.0.api_contracts.eth_call.clone(),
+            base_system_contracts: self
+                .with_evm_emulator(&self.0.api_contracts.eth_call)
+                .await?,
             caches: self.storage_caches(),
             validation_computational_gas_limit: self
                 .0
@@ -473,6 +672,7 @@ impl TxSender {
                 .validation_computational_gas_limit,
             chain_id: self.0.sender_config.chain_id,
             whitelisted_tokens_for_aa: self.read_whitelisted_tokens_for_aa_cache().await,
+            vm_memory_pool: self.vm_memory_pool(),
         })
     }
 
@@ -481,6 +681,7 @@ impl TxSender {
         tx: &L2Tx,
         protocol_version: ProtocolVersionId,
     ) -> Result<(), SubmitTxError> {
+        self.validate_chain_id(tx)?;
         // This check is intended to ensure that the gas-related values will be safe to convert to u64 in the future computations.
         let max_gas = U256::from(u64::MAX);
         if tx.common_data.fee.gas_limit > max_gas
@@ -518,6 +719,7 @@ impl TxSender {
             );
             return Err(SubmitTxError::MaxFeePerGasTooLow);
         }
+        self.validate_gas_per_pubdata_limit(tx, fee_input, protocol_version)?;
         if tx.common_data.fee.max_fee_per_gas < tx.common_data.fee.max_priority_fee_per_gas {
             tracing::info!(
                 "Submitted Tx is Unexecutable {:?} because of MaxPriorityFeeGreaterThanMaxFee {}",
@@ -552,6 +754,87 @@ impl TxSender {
         Ok(())
     }
 
+    /// Checks that the transaction is signed for this chain, so that transactions signed for other chains
+    /// (e.g., other hyperchains sharing the same infrastructure) cannot be replayed here.
+    fn validate_chain_id(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
+        if tx.common_data.input_data().is_none() {
+            // Transactions without raw bytes are not submitted by users (e.g., they're created in tests).
+            return Ok(());
+        }
+
+        let expected = self.0.sender_config.chain_id.as_u64();
+        match tx.common_data.extract_chain_id() {
+            Some(actual) if actual != expected => {
+                tracing::info!(
+                    "Submitted Tx is Unexecutable {:?} because of ChainIdMismatch: expected {expected}, got {actual}",
+                    tx.hash()
+                );
+                Err(SubmitTxError::ChainIdMismatch { expected, actual })
+            }
+            // Typed transactions always specify the chain ID (this is checked when parsing them), but pre-EIP-155
+            // legacy transactions don't; such transactions are accepted unless configured otherwise.
+            None if tx.common_data.transaction_type == TransactionType::LegacyTransaction
+                && self.0.sender_config.reject_unprotected_txs =>
+            {
+                tracing::info!(
+                    "Submitted Tx is Unexecutable {:?} because it has no replay protection",
+                    tx.hash()
+                );
+                Err(SubmitTxError::UnprotectedTransaction)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that the transaction's gas per pubdata limit is within the bounds configured for the server.
+    /// A limit below the currently required gas per pubdata is not rejected by itself; such transactions
+    /// stay in the mempool until the pubdata price drops.
+    fn validate_gas_per_pubdata_limit(
+        &self,
+        tx: &L2Tx,
+        fee_input: BatchFeeInput,
+        protocol_version: ProtocolVersionId,
+    ) -> Result<(), SubmitTxError> {
+        let config = &self.0.sender_config;
+        if config.min_gas_per_pubdata_limit.is_none() && config.max_gas_per_pubdata_limit.is_none()
+        {
+            return Ok(());
+        }
+
+        // Safe to convert since the limit is checked to fit into `u64` beforehand.
+        let limit = tx.common_data.fee.gas_per_pubdata_limit.as_u64();
+        let (_, required) = derive_base_fee_and_gas_per_pubdata(fee_input, protocol_version.into());
+        if let Some(min) = config.min_gas_per_pubdata_limit {
+            if limit < min {
+                tracing::info!(
+                    "Submitted Tx is Unexecutable {:?} because of GasPerPubdataLimitTooLow {limit} \
+                     (min: {min}, required: {required})",
+                    tx.hash()
+                );
+                return Err(SubmitTxError::GasPerPubdataLimitTooLow {
+                    limit,
+                    min,
+                    required,
+                });
+            }
+        }
+        if let Some(max) = config.max_gas_per_pubdata_limit {
+            if limit > max {
+                tracing::info!(
+                    "Submitted Tx is Unexecutable {:?} because of GasPerPubdataLimitTooHigh {limit} \
+                     (max: {max}, required: {required})",
+                    tx.hash()
+                );
+                return Err(SubmitTxError::GasPerPubdataLimitTooHigh {
+                    limit,
+                    max,
+                    required,
+                });
+            }
+        }
+        Ok(())
+    }
+
     async fn validate_account_nonce(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
         let Nonce(expected_nonce) = self
             .get_expected_nonce(tx.initiator_account())
@@ -651,6 +934,7 @@ impl TxSender {
         block_args: BlockArgs,
         base_fee: u64,
         vm_version: VmVersion,
+        prior_txs: Option<&Arc<PriorTxsSnapshot>>,
     ) -> anyhow::Result<(VmExecutionResultAndLogs, TransactionExecutionMetrics)> {
         let gas_limit_with_overhead = tx_gas_limit
             + derive_overhead(
@@ -666,27 +950,32 @@ impl TxSender {
         match &mut tx.common_data {
             ExecuteTransactionCommon::L1(l1_common_data) => {
                 l1_common_data.gas_limit = forced_gas_limit.into();
-                let required_funds =
-                    l1_common_data.gas_limit * l1_common_data.max_fee_per_gas + tx.execute.value;
-                l1_common_data.to_mint = required_funds;
+                l1_common_data.to_mint = required_l1_tx_funds(
+                    l1_common_data.gas_limit,
+                    l1_common_data.max_fee_per_gas,
+                    tx.execute.value,
+                )
+                .context("funds required for transaction overflow uint256")?;
             }
             ExecuteTransactionCommon::L2(l2_common_data) => {
                 l2_common_data.fee.gas_limit = forced_gas_limit.into();
             }
             ExecuteTransactionCommon::ProtocolUpgrade(common_data) => {
                 common_data.gas_limit = forced_gas_limit.into();
-
-                let required_funds =
-                    common_data.gas_limit * common_data.max_fee_per_gas + tx.execute.value;
-
-                common_data.to_mint = required_funds;
+                common_data.to_mint = required_l1_tx_funds(
+                    common_data.gas_limit,
+                    common_data.max_fee_per_gas,
+                    tx.execute.value,
+                )
+                .context("funds required for transaction overflow uint256")?;
             }
         }
 
-        let shared_args = self.shared_args_for_gas_estimate(fee_model_params).await;
+        let shared_args = self.shared_args_for_gas_estimate(fee_model_params).await?;
         let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
-        let execution_args =
+        let mut execution_args =
             TxExecutionArgs::for_gas_estimate(vm_execution_cache_misses_limit, &tx, base_fee);
+        execution_args.prior_txs = prior_txs.cloned();
         let execution_output = self
             .0
             .executor
@@ -704,35 +993,173 @@ impl TxSender {
         Ok((execution_output.vm, execution_output.metrics))
     }
 
-    async fn shared_args_for_gas_estimate(&self, fee_input: BatchFeeInput) -> TxSharedArgs {
+    async fn shared_args_for_gas_estimate(
+        &self,
+        fee_input: BatchFeeInput,
+    ) -> anyhow::Result<TxSharedArgs> {
         let config = &self.0.sender_config;
 
-        TxSharedArgs {
+        Ok(TxSharedArgs {
             operator_account: AccountTreeId::new(config.fee_account_addr),
             fee_input,
             // We want to bypass the computation gas limit check for gas estimation
             validation_computational_gas_limit: BATCH_COMPUTATIONAL_GAS_LIMIT,
-            base_system_contracts: self.0.api_contracts.estimate_gas.clone(),
+            base_system_contracts: self
+                .with_evm_emulator(&self.0.api_contracts.estimate_gas)
+                .await?,
             caches: self.storage_caches(),
             chain_id: config.chain_id,
             whitelisted_tokens_for_aa: self.read_whitelisted_tokens_for_aa_cache().await,
+            vm_memory_pool: self.vm_memory_pool(),
+        })
+    }
+
+    /// Returns `contracts` with the EVM emulator attached if the emulator is enabled for the chain.
+    /// The emulator bytecode is loaded from the storage on the first call.
+    pub(crate) async fn with_evm_emulator(
+        &self,
+        contracts: &MultiVMBaseSystemContracts,
+    ) -> anyhow::Result<MultiVMBaseSystemContracts> {
+        let Some(hash) = self.0.sender_config.evm_emulator_hash else {
+            return Ok(contracts.clone());
+        };
+        let evm_emulator = self
+            .0
+            .evm_emulator
+            .get_or_try_init(|| async {
+                let mut storage = self.acquire_replica_connection().await?;
+                let bytecode = storage
+                    .factory_deps_dal()
+                    .get_sealed_factory_dep(hash)
+                    .await?
+                    .with_context(|| {
+                        format!("EVM emulator bytecode with hash {hash:?} is not in storage")
+                    })?;
+                anyhow::Ok(SystemContractCode {
+                    code: bytes_to_be_words(bytecode),
+                    hash,
+                })
+            })
+            .await?;
+        Ok(contracts.clone().with_evm_emulator(evm_emulator.clone()))
+    }
+
+    pub async fn get_txs_fee_in_wei(
+        &self,
+        tx: Transaction,
+        estimated_fee_scale_factor: f64,
+        acceptable_overestimation: u64,
+    ) -> Result<Fee, SubmitTxError> {
+        let mut connection = self.acquire_replica_connection().await?;
+        let block_args = BlockArgs::pending(&mut connection).await?;
+        drop(connection);
+        self.estimate_fee_after_prior_txs(
+            tx,
+            block_args,
+            None,
+            estimated_fee_scale_factor,
+            acceptable_overestimation,
+        )
+        .await
+    }
+
+    /// Estimates fees for an ordered list of L2 transactions from the same initiator. Each transaction
+    /// is estimated assuming that all preceding transactions are executed before it in the same L2 block,
+    /// with fees set to the estimated values.
+    ///
+    /// Each transaction is executed once more after its fee is estimated; the effect of this execution is snapshotted
+    /// and applied to all executions of the following transactions. Thus, the number of VM executions grows linearly
+    /// with the batch size.
+    pub async fn get_dependent_txs_fees_in_wei(
+        &self,
+        txs: Vec<Transaction>,
+        estimated_fee_scale_factor: f64,
+        acceptable_overestimation: u64,
+    ) -> Result<Vec<Fee>, SubmitTxError> {
+        let mut connection = self.acquire_replica_connection().await?;
+        // All transactions are estimated relative to the same block, so that the snapshot of prior transactions
+        // stays valid.
+        let block_args = BlockArgs::pending(&mut connection).await?;
+        drop(connection);
+
+        let tx_count = txs.len();
+        let mut prior_txs = None;
+        let mut fees = Vec::with_capacity(tx_count);
+        for (i, mut tx) in txs.into_iter().enumerate() {
+            let fee = self
+                .estimate_fee_after_prior_txs(
+                    tx.clone(),
+                    block_args,
+                    prior_txs.clone(),
+                    estimated_fee_scale_factor,
+                    acceptable_overestimation,
+                )
+                .await?;
+            if i + 1 < tx_count {
+                if let ExecuteTransactionCommon::L2(common_data) = &mut tx.common_data {
+                    common_data.fee = fee.clone();
+                    if common_data.signature.is_empty() {
+                        common_data.signature =
+                            PackedEthSignature::default().serialize_packed().into();
+                    }
+                }
+                let snapshot = self
+                    .extend_prior_txs_snapshot(tx, block_args, prior_txs)
+                    .await?;
+                prior_txs = Some(Arc::new(snapshot));
+            }
+            fees.push(fee);
         }
+        Ok(fees)
+    }
+
+    /// Executes `tx` with the estimated fee on top of `prior_txs` and returns the extended snapshot.
+    async fn extend_prior_txs_snapshot(
+        &self,
+        tx: Transaction,
+        block_args: BlockArgs,
+        prior_txs: Option<Arc<PriorTxsSnapshot>>,
+    ) -> Result<PriorTxsSnapshot, SubmitTxError> {
+        let base_fee = tx.max_fee_per_gas().as_u64();
+        let fee_input = self.scaled_batch_fee_input().await?;
+        let shared_args = self.shared_args_for_gas_estimate(fee_input).await?;
+        let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
+        let mut execution_args =
+            TxExecutionArgs::for_gas_estimate(vm_execution_cache_misses_limit, &tx, base_fee);
+        execution_args.prior_txs = prior_txs;
+
+        let vm_permit = self.0.vm_concurrency_limiter.acquire().await?;
+        let snapshot = self
+            .0
+            .executor
+            .extend_prior_txs_snapshot(
+                vm_permit,
+                shared_args,
+                execution_args,
+                self.0.replica_connection_pool.clone(),
+                tx,
+                block_args,
+            )
+            .await?;
+        Ok(snapshot)
     }
 
     #[tracing::instrument(level = "debug", skip_all, fields(
         initiator = ?tx.initiator_account(),
         nonce = ?tx.nonce(),
+        prior_txs = prior_txs.as_ref().map_or(0, |snapshot| snapshot.tx_count),
     ))]
-    pub async fn get_txs_fee_in_wei(
+    async fn estimate_fee_after_prior_txs(
         &self,
         mut tx: Transaction,
+        block_args: BlockArgs,
+        prior_txs: Option<Arc<PriorTxsSnapshot>>,
         estimated_fee_scale_factor: f64,
         acceptable_overestimation: u64,
     ) -> Result<Fee, SubmitTxError> {
         let estimation_started_at = Instant::now();
 
         let mut connection = self.acquire_replica_connection().await?;
-        let block_args = BlockArgs::pending(&mut connection).await?;
         let protocol_version = connection
             .blocks_dal()
             .pending_protocol_version()
@@ -802,8 +1229,7 @@ impl TxSender {
         }
 
         // Acquire the vm token for the whole duration of the binary search.
-        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
-        let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
+        let vm_permit = self.0.vm_concurrency_limiter.acquire().await?;
 
         // When the pubdata cost grows very high, the total gas limit required may become very high as well. If
         // we do binary search over any possible gas limit naively, we may end up with a very high number of iterations,
@@ -831,6 +1257,7 @@ impl TxSender {
                     block_args,
                     base_fee,
                     protocol_version.into(),
+                    prior_txs.as_ref(),
                 )
                 .await
                 .context("estimate_gas step failed")?;
@@ -866,6 +1293,7 @@ impl TxSender {
                     block_args,
                     base_fee,
                     protocol_version.into(),
+                    prior_txs.as_ref(),
                 )
                 .await
                 .context("estimate_gas step failed")?;
@@ -898,6 +1326,7 @@ impl TxSender {
                 block_args,
                 base_fee,
                 protocol_version.into(),
+                prior_txs.as_ref(),
             )
             .await
             .context("final estimate_gas step failed")?;
@@ -952,6 +1381,79 @@ impl TxSender {
         })
     }
 
+    /// Simulates the L2 part of an L1 transaction (aka priority operation) against the pending state.
+    /// Unlike fee estimation, the transaction is executed once with the provided gas limit (or the maximum
+    /// gas limit for a batch if it's not specified); the fee params and minted funds are set as they would be
+    /// by the L1 contracts.
+    pub(crate) async fn simulate_l1_tx(
+        &self,
+        mut tx: L1Tx,
+    ) -> Result<L1TxSimulationOutput, SubmitTxError> {
+        let mut connection = self.acquire_replica_connection().await?;
+        let block_args = BlockArgs::pending(&mut connection).await?;
+        let protocol_version = connection
+            .blocks_dal()
+            .pending_protocol_version()
+            .await
+            .context("failed getting pending protocol version")?;
+        drop(connection);
+
+        let fee_input = adjust_pubdata_price_for_tx(
+            self.scaled_batch_fee_input().await?,
+            tx.common_data.gas_per_pubdata_limit,
+            None,
+            protocol_version.into(),
+        );
+        let (base_fee, _) = derive_base_fee_and_gas_per_pubdata(fee_input, protocol_version.into());
+
+        let common_data = &mut tx.common_data;
+        if common_data.max_fee_per_gas.is_zero() {
+            common_data.max_fee_per_gas = base_fee.into();
+        } else if common_data.max_fee_per_gas < base_fee.into() {
+            return Err(SubmitTxError::MaxFeePerGasTooLow);
+        }
+        let max_gas_limit = U256::from(get_max_batch_gas_limit(protocol_version.into()));
+        if common_data.gas_limit.is_zero() || common_data.gas_limit > max_gas_limit {
+            common_data.gas_limit = max_gas_limit;
+        }
+        if common_data.refund_recipient == Address::zero() {
+            // This mirrors the L1 contracts, which substitute the sender if the refund recipient is not specified.
+            common_data.refund_recipient = common_data.sender;
+        }
+        common_data.to_mint = required_l1_tx_funds(
+            common_data.gas_limit,
+            common_data.max_fee_per_gas,
+            tx.execute.value,
+        )
+        .ok_or(SubmitTxError::MintedAmountOverflow)?;
+
+        let vm_permit = self.0.vm_concurrency_limiter.acquire().await?;
+        let vm_tx = Transaction::from(tx.clone());
+        let shared_args = self.shared_args_for_gas_estimate(fee_input).await?;
+        let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
+        let execution_args =
+            TxExecutionArgs::for_gas_estimate(vm_execution_cache_misses_limit, &vm_tx, base_fee);
+        let execution_output = self
+            .0
+            .executor
+            .execute_tx_in_sandbox(
+                vm_permit,
+                shared_args,
+                true,
+                execution_args,
+                self.0.replica_connection_pool.clone(),
+                vm_tx,
+                block_args,
+                vec![],
+            )
+            .await?;
+        Ok(L1TxSimulationOutput {
+            tx,
+            base_fee,
+            vm: execution_output.vm,
+        })
+    }
+
     // For now, both L1 gas price and pubdata price are scaled with the same coefficient
     async fn scaled_batch_fee_input(&self) -> anyhow::Result<BatchFeeInput> {
         self.0
@@ -969,11 +1471,20 @@ impl TxSender {
         call_overrides: CallOverrides,
         tx: L2Tx,
     ) -> Result<Vec<u8>, SubmitTxError> {
-        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
-        let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
+        let eth_call_cache = self.0.eth_call_cache.as_ref();
+        let cache_key =
+            eth_call_cache.map(|_| EthCallCacheKey::new(&block_args, &call_overrides, &tx));
+        if let (Some(cache), Some(key)) = (eth_call_cache, &cache_key) {
+            if let Some(output) = cache.get(key) {
+                return Ok(output);
+            }
+        }
+
+        let vm_permit = self.0.vm_concurrency_limiter.acquire().await?;
 
         let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
-        self.0
+        let output = self
+            .0
             .executor
             .execute_tx_eth_call(
                 vm_permit,
@@ -986,7 +1497,12 @@ impl TxSender {
                 vec![],
             )
             .await?
-            .into_api_call_result()
+            .into_api_call_result()?;
+
+        if let (Some(cache), Some(key)) = (eth_call_cache, cache_key) {
+            cache.insert(key, output.clone());
+        }
+        Ok(output)
     }
 
     pub async fn gas_price(&self) -> anyhow::Result<u64> {
@@ -1005,6 +1521,28 @@ impl TxSender {
         Ok(base_fee)
     }
 
+    /// Returns the gas per pubdata currently required for transactions to be included into a batch.
+    /// Unlike [`Self::gas_price()`], the value is not scaled.
+    pub async fn required_gas_per_pubdata(&self) -> anyhow::Result<u64> {
+        let mut connection = self.acquire_replica_connection().await?;
+        let protocol_version = connection
+            .blocks_dal()
+            .pending_protocol_version()
+            .await
+            .context("failed obtaining pending protocol version")?;
+        drop(connection);
+
+        let fee_input = self
+            .0
+            .batch_fee_input_provider
+            .get_batch_fee_input()
+            .await
+            .context("cannot get batch fee input")?;
+        let (_, gas_per_pubdata) =
+            derive_base_fee_and_gas_per_pubdata(fee_input, protocol_version.into());
+        Ok(gas_per_pubdata)
+    }
+
     fn ensure_tx_executable(
         &self,
         transaction: &Transaction,
@@ -1,11 +1,12 @@
 use std::{
-    collections::{BTreeSet, HashMap},
+    collections::{BTreeSet, HashMap, VecDeque},
     future::Future,
-    sync::Arc,
-    time::Duration,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 
 use anyhow::Context;
+use chrono::Utc;
 use tokio::sync::{watch, RwLock};
 use zksync_dal::{
     helpers::wait_for_l1_batch, transactions_dal::L2TxSubmissionResult, ConnectionPool, Core,
@@ -13,14 +14,18 @@ use zksync_dal::{
 };
 use zksync_shared_metrics::{TxStage, APP_METRICS};
 use zksync_types::{
-    api::{BlockId, Transaction, TransactionDetails, TransactionId},
+    api::{
+        BlockId, ForwardedTransaction, ForwardedTransactionStatus, Transaction, TransactionDetails,
+        TransactionId,
+    },
     fee::TransactionExecutionMetrics,
     l2::L2Tx,
     Address, Nonce, H256,
 };
 use zksync_web3_decl::{
     client::{DynClient, L2},
-    error::{ClientRpcContext, EnrichedClientResult, Web3Error},
+    error::{ClientRpcContext, EnrichedClientError, EnrichedClientResult, Web3Error},
+    jsonrpsee::core::ClientError,
     namespaces::{EthNamespaceClient, ZksNamespaceClient},
 };
 
@@ -126,12 +131,236 @@ impl TxCache {
     }
 }
 
+async fn forward_tx(client: &DynClient<L2>, tx: &L2Tx) -> EnrichedClientResult<H256> {
+    let input_data = tx.common_data.input_data().expect("raw tx is absent");
+    let raw_tx = zksync_types::web3::Bytes(input_data.to_vec());
+    let tx_hash = tx.hash();
+    tracing::info!("Proxying tx {tx_hash:?}");
+    client
+        .send_raw_transaction(raw_tx)
+        .rpc_context("send_raw_transaction")
+        .with_arg("tx_hash", &tx_hash)
+        .await
+}
+
+/// Checks whether the main node has rejected a transaction because it already knows it. This happens if a previous
+/// forwarding attempt has reached the main node, but its response was lost (e.g., because of a timeout).
+fn is_known_tx_error(err: &EnrichedClientError) -> bool {
+    matches!(
+        err.as_ref(),
+        ClientError::Call(err) if err.message().starts_with("known transaction")
+    )
+}
+
+#[derive(Debug, Clone)]
+struct QueuedTx {
+    tx: L2Tx,
+    queued_at: Instant,
+}
+
+#[derive(Debug, Default)]
+struct ForwardingQueueInner {
+    queued: VecDeque<QueuedTx>,
+    statuses: HashMap<H256, ForwardedTransaction>,
+    /// Hashes of transactions with a final status in the order of finalization; used to evict old statuses.
+    finished: VecDeque<H256>,
+}
+
+/// Queue of transactions waiting to be forwarded to the main node while it's unavailable. Also retains forwarding
+/// statuses for recently processed transactions.
+#[derive(Debug, Clone)]
+struct ForwardingQueue {
+    capacity: usize,
+    timeout: Duration,
+    inner: Arc<Mutex<ForwardingQueueInner>>,
+}
+
+impl ForwardingQueue {
+    /// Maximum number of retained statuses for transactions that have left the queue.
+    const MAX_RETAINED_STATUSES: usize = 10_000;
+
+    fn new(capacity: usize, timeout: Duration) -> Self {
+        Self {
+            capacity,
+            timeout,
+            inner: Arc::default(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ForwardingQueueInner> {
+        self.inner.lock().expect("forwarding queue is poisoned")
+    }
+
+    fn is_empty(&self) -> bool {
+        self.lock().queued.is_empty()
+    }
+
+    /// Returns `false` if the queue is full.
+    fn enqueue(&self, tx: L2Tx, last_error: Option<String>) -> bool {
+        let mut inner = self.lock();
+        if inner.queued.len() >= self.capacity {
+            return false;
+        }
+
+        let tx_hash = tx.hash();
+        let status = ForwardedTransaction {
+            hash: tx_hash,
+            status: ForwardedTransactionStatus::Queued,
+            attempts: last_error.is_some().into(),
+            last_error,
+            received_at: Utc::now(),
+            forwarded_at: None,
+        };
+        inner.statuses.insert(tx_hash, status);
+        inner.queued.push_back(QueuedTx {
+            tx,
+            queued_at: Instant::now(),
+        });
+        true
+    }
+
+    fn front(&self) -> Option<QueuedTx> {
+        self.lock().queued.front().cloned()
+    }
+
+    fn record_failed_attempt(&self, tx_hash: H256, error: String) {
+        if let Some(status) = self.lock().statuses.get_mut(&tx_hash) {
+            status.attempts += 1;
+            status.last_error = Some(error);
+        }
+    }
+
+    /// Records a transaction forwarded without queuing.
+    fn record_forwarded(&self, tx_hash: H256) {
+        let now = Utc::now();
+        let status = ForwardedTransaction {
+            hash: tx_hash,
+            status: ForwardedTransactionStatus::Forwarded,
+            attempts: 1,
+            last_error: None,
+            received_at: now,
+            forwarded_at: Some(now),
+        };
+        let mut inner = self.lock();
+        inner.statuses.insert(tx_hash, status);
+        Self::retain_finished(&mut inner, tx_hash);
+    }
+
+    /// Removes the front transaction from the queue, assigning it a final status.
+    fn finish_front(&self, status: ForwardedTransactionStatus, error: Option<String>) {
+        let mut inner = self.lock();
+        let Some(queued) = inner.queued.pop_front() else {
+            return;
+        };
+        let tx_hash = queued.tx.hash();
+        if let Some(entry) = inner.statuses.get_mut(&tx_hash) {
+            entry.status = status;
+            entry.attempts += 1;
+            if status == ForwardedTransactionStatus::Forwarded {
+                entry.forwarded_at = Some(Utc::now());
+            } else {
+                entry.last_error = error;
+            }
+        }
+        Self::retain_finished(&mut inner, tx_hash);
+    }
+
+    fn retain_finished(inner: &mut ForwardingQueueInner, tx_hash: H256) {
+        inner.finished.push_back(tx_hash);
+        while inner.finished.len() > Self::MAX_RETAINED_STATUSES {
+            if let Some(evicted_hash) = inner.finished.pop_front() {
+                let is_queued = inner.statuses.get(&evicted_hash).map_or(false, |status| {
+                    status.status == ForwardedTransactionStatus::Queued
+                });
+                // The transaction may have been resubmitted and queued again after it was finished.
+                if !is_queued {
+                    inner.statuses.remove(&evicted_hash);
+                }
+            }
+        }
+    }
+
+    fn get(&self, tx_hash: H256) -> Option<ForwardedTransaction> {
+        self.lock().statuses.get(&tx_hash).cloned()
+    }
+}
+
+/// Background task retrying forwarding of queued transactions to the main node.
+#[derive(Debug)]
+struct TxForwarder {
+    client: Box<DynClient<L2>>,
+    tx_cache: TxCache,
+    queue: ForwardingQueue,
+}
+
+impl TxForwarder {
+    const RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// Forwards queued transactions in the FIFO order until the queue is empty or the main node is unavailable.
+    async fn process_queue(&self) {
+        while let Some(queued) = self.queue.front() {
+            let tx_hash = queued.tx.hash();
+            let forward_result = match forward_tx(&self.client, &queued.tx).await {
+                Err(err) if is_known_tx_error(&err) => {
+                    tracing::info!(
+                        "Queued tx {tx_hash:?} is already known to the main node; treating it as forwarded"
+                    );
+                    Ok(tx_hash)
+                }
+                result => result,
+            };
+            match forward_result {
+                Ok(_) => {
+                    tracing::info!("Forwarded queued tx {tx_hash:?} to the main node");
+                    self.queue
+                        .finish_front(ForwardedTransactionStatus::Forwarded, None);
+                    APP_METRICS.processed_txs[&TxStage::Proxied].inc();
+                }
+                Err(err) if err.is_transient() => {
+                    if queued.queued_at.elapsed() < self.queue.timeout {
+                        tracing::debug!("Failed forwarding queued tx {tx_hash:?}: {err}");
+                        self.queue.record_failed_attempt(tx_hash, err.to_string());
+                        return;
+                    }
+                    tracing::warn!(
+                        "Dropping queued tx {tx_hash:?} after it has spent {:?} in the forwarding queue: {err}",
+                        self.queue.timeout
+                    );
+                    self.queue
+                        .finish_front(ForwardedTransactionStatus::Dropped, Some(err.to_string()));
+                }
+                Err(err) => {
+                    tracing::warn!("Queued tx {tx_hash:?} was rejected by the main node: {err}");
+                    self.queue
+                        .finish_front(ForwardedTransactionStatus::Rejected, Some(err.to_string()));
+                }
+            }
+            self.tx_cache.remove_tx(tx_hash).await;
+        }
+    }
+
+    async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow() {
+            self.process_queue().await;
+            if tokio::time::timeout(Self::RETRY_INTERVAL, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, tx forwarder is shutting down");
+        Ok(())
+    }
+}
+
 /// Used by external node to proxy transaction to the main node
 /// and store them while they're not synced back yet
 #[derive(Debug)]
 pub struct TxProxy {
     tx_cache: TxCache,
     client: Box<DynClient<L2>>,
+    forwarding_queue: Option<ForwardingQueue>,
 }
 
 impl TxProxy {
@@ -139,19 +368,17 @@ impl TxProxy {
         Self {
             client: client.for_component("tx_proxy"),
             tx_cache: TxCache::default(),
+            forwarding_queue: None,
         }
     }
 
-    async fn submit_tx_impl(&self, tx: &L2Tx) -> EnrichedClientResult<H256> {
-        let input_data = tx.common_data.input_data().expect("raw tx is absent");
-        let raw_tx = zksync_types::web3::Bytes(input_data.to_vec());
-        let tx_hash = tx.hash();
-        tracing::info!("Proxying tx {tx_hash:?}");
-        self.client
-            .send_raw_transaction(raw_tx)
-            .rpc_context("send_raw_transaction")
-            .with_arg("tx_hash", &tx_hash)
-            .await
+    /// Enables queuing transactions if the main node is unavailable. Up to `capacity` transactions are queued; each
+    /// transaction is retried for at most `timeout`, after which it's dropped. While the queue is non-empty, all new
+    /// transactions are queued (or rejected if the queue is full) to preserve the submission order. Queued transactions
+    /// are forwarded by [`Self::run_forwarder()`], which must be spawned separately.
+    pub fn with_forwarding_queue(mut self, capacity: usize, timeout: Duration) -> Self {
+        self.forwarding_queue = Some(ForwardingQueue::new(capacity, timeout));
+        self
     }
 
     async fn save_tx(&self, tx: L2Tx) {
@@ -232,6 +459,22 @@ impl TxProxy {
         let tx_cache = self.tx_cache.clone();
         tx_cache.run_updates(pool, stop_receiver)
     }
+
+    /// Runs the background task forwarding queued transactions to the main node.
+    pub fn run_forwarder(
+        &self,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> impl Future<Output = anyhow::Result<()>> {
+        let forwarder = self.forwarding_queue.clone().map(|queue| TxForwarder {
+            client: self.client.clone(),
+            tx_cache: self.tx_cache.clone(),
+            queue,
+        });
+        async move {
+            let forwarder = forwarder.context("forwarding queue is not enabled for tx proxy")?;
+            forwarder.run(stop_receiver).await
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -245,11 +488,38 @@ impl TxSink for TxProxy {
         // But before we do that, save the tx to cache in case someone will request it
         // Before it reaches the main node.
         self.save_tx(tx.clone()).await;
-        self.submit_tx_impl(tx).await?;
+        let queue = self.forwarding_queue.as_ref();
+        if let Some(queue) = queue {
+            // Preserve the submission order while there are queued transactions; forwarding the transaction directly
+            // could reorder it with the queued ones.
+            if !queue.is_empty() {
+                if queue.enqueue(tx.clone(), None) {
+                    return Ok(L2TxSubmissionResult::Proxied);
+                }
+                self.forget_tx(tx.hash()).await;
+                return Err(SubmitTxError::ForwardingQueueFull);
+            }
+        }
+
+        if let Err(err) = forward_tx(&self.client, tx).await {
+            if let Some(queue) = queue.filter(|_| err.is_transient()) {
+                if queue.enqueue(tx.clone(), Some(err.to_string())) {
+                    tracing::info!(
+                        "Main node is unavailable ({err}); queued tx {:?} for forwarding",
+                        tx.hash()
+                    );
+                    return Ok(L2TxSubmissionResult::Proxied);
+                }
+            }
+            return Err(err.into());
+        }
         // Now, after we are sure that the tx is on the main node, remove it from cache
         // since we don't want to store txs that might have been replaced or otherwise removed
         // from the mempool.
         self.forget_tx(tx.hash()).await;
+        if let Some(queue) = queue {
+            queue.record_forwarded(tx.hash());
+        }
         APP_METRICS.processed_txs[&TxStage::Proxied].inc();
         Ok(L2TxSubmissionResult::Proxied)
     }
@@ -283,4 +553,210 @@ impl TxSink for TxProxy {
     async fn lookup_tx_details(&self, hash: H256) -> Result<Option<TransactionDetails>, Web3Error> {
         Ok(self.request_tx_details(hash).await?)
     }
+
+    async fn lookup_forwarded_tx(
+        &self,
+        hash: H256,
+    ) -> Result<Option<ForwardedTransaction>, Web3Error> {
+        Ok(self
+            .forwarding_queue
+            .as_ref()
+            .and_then(|queue| queue.get(hash)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use assert_matches::assert_matches;
+    use zksync_node_test_utils::create_l2_transaction;
+    use zksync_web3_decl::{client::MockClient, jsonrpsee::types::ErrorObject};
+
+    use super::*;
+
+    fn mock_main_node(is_available: Arc<AtomicBool>) -> Box<DynClient<L2>> {
+        let client = MockClient::builder(L2::default())
+            .method(
+                "eth_sendRawTransaction",
+                move |_: zksync_types::web3::Bytes| {
+                    if is_available.load(Ordering::SeqCst) {
+                        Ok(H256::zero())
+                    } else {
+                        Err(ClientError::RequestTimeout)
+                    }
+                },
+            )
+            .build();
+        Box::new(client)
+    }
+
+    async fn wait_for_status(
+        proxy: &TxProxy,
+        tx_hash: H256,
+        expected: ForwardedTransactionStatus,
+    ) -> ForwardedTransaction {
+        loop {
+            let status = proxy.lookup_forwarded_tx(tx_hash).await.unwrap().unwrap();
+            if status.status == expected {
+                return status;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn queuing_transactions_while_main_node_is_unavailable() {
+        let is_available = Arc::new(AtomicBool::new(false));
+        let proxy = TxProxy::new(mock_main_node(is_available.clone()))
+            .with_forwarding_queue(2, Duration::from_secs(60));
+        let txs: Vec<_> = (0..3).map(|_| create_l2_transaction(10, 100)).collect();
+
+        for tx in &txs[..2] {
+            let result = proxy
+                .submit_tx(tx, TransactionExecutionMetrics::default())
+                .await
+                .unwrap();
+            assert_matches!(result, L2TxSubmissionResult::Proxied);
+        }
+        // The queue is full, so the transaction should be rejected even if the main node is available;
+        // forwarding it directly would reorder it with the queued transactions.
+        is_available.store(true, Ordering::SeqCst);
+        let err = proxy
+            .submit_tx(&txs[2], TransactionExecutionMetrics::default())
+            .await
+            .unwrap_err();
+        assert_matches!(err, SubmitTxError::ForwardingQueueFull);
+        assert!(proxy
+            .lookup_forwarded_tx(txs[2].hash())
+            .await
+            .unwrap()
+            .is_none());
+        assert!(proxy.find_tx(txs[2].hash()).await.is_none());
+
+        let status = proxy
+            .lookup_forwarded_tx(txs[0].hash())
+            .await
+            .unwrap()
+            .expect("no status for queued tx");
+        assert_eq!(status.status, ForwardedTransactionStatus::Queued);
+        assert_eq!(status.attempts, 1);
+        assert!(status.last_error.is_some());
+        // The second transaction is queued without a forwarding attempt to preserve the submission order.
+        let status = proxy
+            .lookup_forwarded_tx(txs[1].hash())
+            .await
+            .unwrap()
+            .expect("no status for queued tx");
+        assert_eq!(status.status, ForwardedTransactionStatus::Queued);
+        assert_eq!(status.attempts, 0);
+        assert!(proxy.find_tx(txs[0].hash()).await.is_some());
+
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let forwarder_task = tokio::spawn(proxy.run_forwarder(stop_receiver));
+        wait_for_status(&proxy, txs[1].hash(), ForwardedTransactionStatus::Forwarded).await;
+
+        for tx in &txs[..2] {
+            let status = proxy.lookup_forwarded_tx(tx.hash()).await.unwrap().unwrap();
+            assert_eq!(status.status, ForwardedTransactionStatus::Forwarded);
+            assert!(status.forwarded_at.is_some());
+            assert!(proxy.find_tx(tx.hash()).await.is_none());
+        }
+
+        // With the queue drained, transactions are forwarded directly.
+        proxy
+            .submit_tx(&txs[2], TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        let status = proxy
+            .lookup_forwarded_tx(txs[2].hash())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(status.status, ForwardedTransactionStatus::Forwarded);
+        assert_eq!(status.attempts, 1);
+
+        stop_sender.send_replace(true);
+        forwarder_task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn treating_known_queued_transactions_as_forwarded() {
+        let is_available = Arc::new(AtomicBool::new(false));
+        let client = MockClient::builder(L2::default())
+            .method("eth_sendRawTransaction", {
+                let is_available = is_available.clone();
+                move |_: zksync_types::web3::Bytes| {
+                    if is_available.load(Ordering::SeqCst) {
+                        // Emulates a previous forwarding attempt that has reached the main node.
+                        Err::<H256, _>(ClientError::Call(ErrorObject::owned(
+                            3,
+                            "known transaction. transaction with hash 0x00 is already in the system",
+                            None::<()>,
+                        )))
+                    } else {
+                        Err(ClientError::RequestTimeout)
+                    }
+                }
+            })
+            .build();
+        let proxy =
+            TxProxy::new(Box::new(client)).with_forwarding_queue(1, Duration::from_secs(60));
+        let tx = create_l2_transaction(10, 100);
+        proxy
+            .submit_tx(&tx, TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+
+        is_available.store(true, Ordering::SeqCst);
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let forwarder_task = tokio::spawn(proxy.run_forwarder(stop_receiver));
+        let status =
+            wait_for_status(&proxy, tx.hash(), ForwardedTransactionStatus::Forwarded).await;
+        assert!(status.forwarded_at.is_some());
+        assert!(proxy.find_tx(tx.hash()).await.is_none());
+
+        stop_sender.send_replace(true);
+        forwarder_task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn dropping_queued_transactions_after_timeout() {
+        let proxy =
+            TxProxy::new(mock_main_node(Arc::default())).with_forwarding_queue(1, Duration::ZERO);
+        let tx = create_l2_transaction(10, 100);
+        proxy
+            .submit_tx(&tx, TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let forwarder_task = tokio::spawn(proxy.run_forwarder(stop_receiver));
+        let status = wait_for_status(&proxy, tx.hash(), ForwardedTransactionStatus::Dropped).await;
+        assert_eq!(status.attempts, 2);
+        assert!(status.last_error.is_some());
+        assert!(proxy.find_tx(tx.hash()).await.is_none());
+
+        stop_sender.send_replace(true);
+        forwarder_task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn forwarding_without_queue() {
+        let proxy = TxProxy::new(mock_main_node(Arc::default()));
+        let tx = create_l2_transaction(10, 100);
+        let err = proxy
+            .submit_tx(&tx, TransactionExecutionMetrics::default())
+            .await
+            .unwrap_err();
+        assert_matches!(err, SubmitTxError::ProxyError(_));
+        assert!(proxy
+            .lookup_forwarded_tx(tx.hash())
+            .await
+            .unwrap()
+            .is_none());
+
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        proxy.run_forwarder(stop_receiver).await.unwrap_err();
+    }
 }
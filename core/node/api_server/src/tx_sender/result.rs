@@ -3,7 +3,7 @@ use thiserror::Error;
 use zksync_types::{l2::error::TxCheckError, U256};
 use zksync_web3_decl::error::EnrichedClientError;
 
-use crate::execution_sandbox::{SandboxExecutionError, ValidationError};
+use crate::execution_sandbox::{SandboxExecutionError, ValidationError, VmPermitError};
 
 /// Errors that con occur submitting a transaction or estimating gas for its execution.
 #[derive(Debug, Error)]
@@ -14,6 +14,14 @@ pub enum SubmitTxError {
     NonceIsTooLow(u32, u32, u32),
     #[error("insertion of another transaction with the same nonce is in progress")]
     InsertionInProgress,
+    #[error("replacement transaction underpriced")]
+    ReplacementUnderpriced,
+    #[error("too many pending transactions from the sender. at most {0} allowed")]
+    SenderMempoolQuotaExceeded(u32),
+    #[error("mempool is full. max fee per gas must be at least {0} to enter the mempool")]
+    MempoolIsFull(U256),
+    #[error("mempool is full and no pending transactions can be evicted; try again later")]
+    MempoolIsFullNoEviction,
     #[error("{0}")]
     IncorrectTx(#[from] TxCheckError),
     #[error("insufficient funds for gas + value. balance: {0}, fee: {1}, value: {2}")]
@@ -28,6 +36,8 @@ pub enum SubmitTxError {
     RateLimitExceeded,
     #[error("server shutting down")]
     ServerShuttingDown,
+    #[error("too many concurrent VM executions for the client; try again later")]
+    VmPermitTimeout,
     #[error("failed to include transaction in the system. reason: {0}")]
     BootloaderFailure(String),
     #[error("failed to validate the transaction. reason: {0}")]
@@ -59,6 +69,22 @@ pub enum SubmitTxError {
     FeePerGasTooHigh,
     #[error("max fee per pubdata byte higher than 2^32")]
     FeePerPubdataByteTooHigh,
+    #[error("gas limit * max fee per gas + value overflows uint256")]
+    MintedAmountOverflow,
+    #[error(
+        "gas per pubdata limit {limit} is lower than the minimum {min} accepted by the server; \
+         currently required gas per pubdata: {required}"
+    )]
+    GasPerPubdataLimitTooLow { limit: u64, min: u64, required: u64 },
+    #[error(
+        "gas per pubdata limit {limit} is higher than the maximum {max} accepted by the server; \
+         currently required gas per pubdata: {required}"
+    )]
+    GasPerPubdataLimitTooHigh { limit: u64, max: u64, required: u64 },
+    #[error("transaction is signed for chain {actual}, while this chain has ID {expected}")]
+    ChainIdMismatch { expected: u64, actual: u64 },
+    #[error("transactions without replay protection (EIP-155) are not accepted by this node")]
+    UnprotectedTransaction,
     /// InsufficientFundsForTransfer is returned if the transaction sender doesn't
     /// have enough funds for transfer.
     #[error("insufficient balance for transfer")]
@@ -70,8 +96,16 @@ pub enum SubmitTxError {
     /// Error returned from main node
     #[error("{0}")]
     ProxyError(#[from] EnrichedClientError),
+    #[error("main node is unavailable and the forwarding queue is full; try again later")]
+    ForwardingQueueFull,
     #[error("not enough gas to publish compressed bytecodes")]
     FailedToPublishCompressedBytecodes,
+    #[error("inclusion deadline must specify an L2 block or a timestamp")]
+    EmptyDeadline,
+    #[error("inclusion deadline has already passed")]
+    DeadlinePassed,
+    #[error("transactions with inclusion deadlines are not supported by this node")]
+    DeadlinesNotSupported,
     /// Catch-all internal error (e.g., database error) that should not be exposed to the caller.
     #[error("internal error")]
     Internal(#[from] anyhow::Error),
@@ -83,6 +117,9 @@ impl SubmitTxError {
             Self::NonceIsTooHigh(_, _, _) => "nonce-is-too-high",
             Self::NonceIsTooLow(_, _, _) => "nonce-is-too-low",
             Self::InsertionInProgress => "insertion-in-progress",
+            Self::ReplacementUnderpriced => "replacement-underpriced",
+            Self::SenderMempoolQuotaExceeded(_) => "sender-mempool-quota-exceeded",
+            Self::MempoolIsFull(_) | Self::MempoolIsFullNoEviction => "mempool-is-full",
             Self::IncorrectTx(_) => "incorrect-tx",
             Self::NotEnoughBalanceForFeeValue(_, _, _) => "not-enough-balance-for-fee",
             Self::ExecutionReverted(_, _) => "execution-reverted",
@@ -90,6 +127,7 @@ impl SubmitTxError {
             Self::Unexecutable(_) => "unexecutable",
             Self::RateLimitExceeded => "rate-limit-exceeded",
             Self::ServerShuttingDown => "shutting-down",
+            Self::VmPermitTimeout => "vm-permit-timeout",
             Self::BootloaderFailure(_) => "bootloader-failure",
             Self::ValidationFailed(_) => "validation-failed",
             Self::FailedToChargeFee(_) => "failed-too-charge-fee",
@@ -103,14 +141,40 @@ impl SubmitTxError {
             Self::TooManyFactoryDependencies(_, _) => "too-many-factory-dependencies",
             Self::FeePerGasTooHigh => "gas-price-limit-too-high",
             Self::FeePerPubdataByteTooHigh => "pubdata-price-limit-too-high",
+            Self::MintedAmountOverflow => "minted-amount-overflow",
+            Self::GasPerPubdataLimitTooLow { .. } => "gas-per-pubdata-limit-too-low",
+            Self::GasPerPubdataLimitTooHigh { .. } => "gas-per-pubdata-limit-too-high",
+            Self::ChainIdMismatch { .. } => "chain-id-mismatch",
+            Self::UnprotectedTransaction => "unprotected-tx",
             Self::InsufficientFundsForTransfer => "insufficient-funds-for-transfer",
             Self::IntrinsicGas => "intrinsic-gas",
             Self::ProxyError(_) => "proxy-error",
+            Self::ForwardingQueueFull => "forwarding-queue-full",
             Self::FailedToPublishCompressedBytecodes => "failed-to-publish-compressed-bytecodes",
+            Self::EmptyDeadline => "empty-deadline",
+            Self::DeadlinePassed => "deadline-passed",
+            Self::DeadlinesNotSupported => "deadlines-not-supported",
             Self::Internal(_) => "internal",
         }
     }
 
+    /// Checks whether this error means that the transaction was rejected, as opposed to, e.g., an internal error
+    /// or a transient condition. Only such errors are recorded in the rejected transactions journal.
+    pub(super) fn is_rejection(&self) -> bool {
+        !matches!(
+            self,
+            Self::InsertionInProgress
+                | Self::IncorrectTx(TxCheckError::TxDuplication(_))
+                | Self::RateLimitExceeded
+                | Self::ServerShuttingDown
+                | Self::VmPermitTimeout
+                | Self::DeadlinesNotSupported
+                | Self::ProxyError(_)
+                | Self::ForwardingQueueFull
+                | Self::Internal(_)
+        )
+    }
+
     pub fn data(&self) -> Vec<u8> {
         if let Self::ExecutionReverted(_, data) = self {
             data.clone()
@@ -149,6 +213,15 @@ impl From<SandboxExecutionError> for SubmitTxError {
     }
 }
 
+impl From<VmPermitError> for SubmitTxError {
+    fn from(err: VmPermitError) -> Self {
+        match err {
+            VmPermitError::Closed => Self::ServerShuttingDown,
+            VmPermitError::Timeout => Self::VmPermitTimeout,
+        }
+    }
+}
+
 impl From<ValidationError> for SubmitTxError {
     fn from(err: ValidationError) -> Self {
         match err {
@@ -5,7 +5,10 @@ use multivm::interface::ExecutionResult;
 use zksync_node_fee_model::MockBatchFeeParamsProvider;
 use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
 use zksync_node_test_utils::{create_l2_block, create_l2_transaction, prepare_recovery_snapshot};
-use zksync_types::{get_nonce_key, L1BatchNumber, L2BlockNumber, StorageLog};
+use zksync_types::{
+    api, get_nonce_key, K256PrivateKey, L1BatchNumber, L2BlockNumber, StorageLog, EIP_1559_TX_TYPE,
+    EIP_2930_TX_TYPE, U64,
+};
 use zksync_utils::u256_to_h256;
 
 use super::*;
@@ -158,3 +161,295 @@ async fn submitting_tx_requires_one_connection() {
         .unwrap()
         .expect("transaction is not persisted");
 }
+
+#[tokio::test]
+async fn concurrent_submissions_via_different_sinks_are_deduplicated() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    // Emulate API servers sharing the same database.
+    let sinks = [
+        MasterPoolSink::new(pool.clone()),
+        MasterPoolSink::new(pool.clone()),
+    ];
+    let tx = create_l2_transaction(10, 100);
+
+    let submissions = sinks
+        .iter()
+        .map(|sink| sink.submit_tx(&tx, TransactionExecutionMetrics::default()));
+    let mut results: Vec<_> = futures::future::join_all(submissions)
+        .await
+        .into_iter()
+        .map(Result::unwrap)
+        .collect();
+    results.sort_by_key(|result| result.to_string());
+    assert_eq!(
+        results,
+        [L2TxSubmissionResult::Added, L2TxSubmissionResult::Duplicate]
+    );
+}
+
+#[tokio::test]
+async fn per_sender_mempool_quota_is_enforced() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let sink = MasterPoolSink::new(pool).with_quotas(MempoolQuotas {
+        max_txs_per_sender: Some(1),
+        ..MempoolQuotas::default()
+    });
+    let tx = create_l2_transaction(10, 100);
+    let mut next_tx = create_l2_transaction(10, 100);
+    next_tx.common_data.initiator_address = tx.initiator_account();
+    next_tx.common_data.nonce = Nonce(1);
+
+    let result = sink
+        .submit_tx(&tx, TransactionExecutionMetrics::default())
+        .await
+        .unwrap();
+    assert_eq!(result, L2TxSubmissionResult::Added);
+    let err = sink
+        .submit_tx(&next_tx, TransactionExecutionMetrics::default())
+        .await
+        .unwrap_err();
+    assert_matches!(err, SubmitTxError::SenderMempoolQuotaExceeded(1));
+
+    // Other senders are not affected.
+    let other_tx = create_l2_transaction(10, 100);
+    let result = sink
+        .submit_tx(&other_tx, TransactionExecutionMetrics::default())
+        .await
+        .unwrap();
+    assert_eq!(result, L2TxSubmissionResult::Added);
+}
+
+#[tokio::test]
+async fn global_mempool_quota_evicts_cheapest_txs() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let sink = MasterPoolSink::new(pool.clone()).with_quotas(MempoolQuotas {
+        max_txs: Some(2),
+        ..MempoolQuotas::default()
+    });
+    let cheap_tx = create_l2_transaction(10, 100);
+    let tx = create_l2_transaction(20, 100);
+    for tx in [&cheap_tx, &tx] {
+        let result = sink
+            .submit_tx(tx, TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        assert_eq!(result, L2TxSubmissionResult::Added);
+    }
+
+    let cheaper_tx = create_l2_transaction(5, 100);
+    let err = sink
+        .submit_tx(&cheaper_tx, TransactionExecutionMetrics::default())
+        .await
+        .unwrap_err();
+    assert_matches!(err, SubmitTxError::MempoolIsFull(fee) if fee == 11.into());
+
+    let expensive_tx = create_l2_transaction(30, 100);
+    let result = sink
+        .submit_tx(&expensive_tx, TransactionExecutionMetrics::default())
+        .await
+        .unwrap();
+    assert_eq!(result, L2TxSubmissionResult::Added);
+    let mut storage = pool.connection().await.unwrap();
+    let evicted_tx = storage
+        .transactions_web3_dal()
+        .get_transaction_by_hash(cheap_tx.hash(), L2ChainId::default())
+        .await
+        .unwrap();
+    assert!(evicted_tx.is_none(), "{evicted_tx:?}");
+
+    // Transactions loaded into the state keeper mempool cannot be evicted.
+    let loaded_txs = storage
+        .transactions_dal()
+        .sync_mempool(&[], &[], 0, 0, 1000)
+        .await
+        .unwrap();
+    assert_eq!(loaded_txs.len(), 2);
+    let err = sink
+        .submit_tx(
+            &create_l2_transaction(100, 100),
+            TransactionExecutionMetrics::default(),
+        )
+        .await
+        .unwrap_err();
+    assert_matches!(err, SubmitTxError::MempoolIsFullNoEviction);
+}
+
+#[tokio::test]
+async fn rejected_submissions_do_not_evict_txs() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let sink = MasterPoolSink::new(pool.clone())
+        .with_replacement_fee_bump(10)
+        .with_quotas(MempoolQuotas {
+            max_txs: Some(2),
+            ..MempoolQuotas::default()
+        });
+    let cheap_tx = create_l2_transaction(10, 100);
+    let tx = create_l2_transaction(20, 100);
+    for tx in [&cheap_tx, &tx] {
+        let result = sink
+            .submit_tx(tx, TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        assert_eq!(result, L2TxSubmissionResult::Added);
+    }
+
+    let result = sink
+        .submit_tx(&tx, TransactionExecutionMetrics::default())
+        .await
+        .unwrap();
+    assert_eq!(result, L2TxSubmissionResult::Duplicate);
+
+    let mut underpriced_tx = create_l2_transaction(21, 100);
+    underpriced_tx.common_data.initiator_address = tx.initiator_account();
+    underpriced_tx.common_data.nonce = tx.nonce();
+    let result = sink
+        .submit_tx(&underpriced_tx, TransactionExecutionMetrics::default())
+        .await
+        .unwrap();
+    assert_eq!(result, L2TxSubmissionResult::ReplacementUnderpriced);
+
+    let mut storage = pool.connection().await.unwrap();
+    for tx in [&cheap_tx, &tx] {
+        storage
+            .transactions_web3_dal()
+            .get_transaction_by_hash(tx.hash(), L2ChainId::default())
+            .await
+            .unwrap()
+            .expect("transaction was evicted");
+    }
+}
+
+#[tokio::test]
+async fn gas_per_pubdata_limit_bounds_are_enforced() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    drop(storage);
+
+    let fee_input = MockBatchFeeParamsProvider::default()
+        .get_batch_fee_input()
+        .await
+        .unwrap();
+    let protocol_version = ProtocolVersionId::latest();
+    let (base_fee, required) =
+        derive_base_fee_and_gas_per_pubdata(fee_input, protocol_version.into());
+
+    let tx_executor = MockTransactionExecutor::default().into();
+    let (mut tx_sender, _) =
+        create_test_tx_sender(pool.clone(), L2ChainId::default(), tx_executor).await;
+    let sender_config = &mut Arc::get_mut(&mut tx_sender.0).unwrap().sender_config;
+    sender_config.min_gas_per_pubdata_limit = Some(required + 1);
+    sender_config.max_gas_per_pubdata_limit = Some(required + 100);
+
+    let tx = create_l2_transaction(base_fee, required);
+    let err = tx_sender
+        .validate_tx(&tx, protocol_version)
+        .await
+        .unwrap_err();
+    assert_matches!(
+        err,
+        SubmitTxError::GasPerPubdataLimitTooLow { limit, min, required: err_required }
+            if limit == required && min == required + 1 && err_required == required
+    );
+    assert!(err.to_string().contains(&required.to_string()), "{err}");
+
+    let tx = create_l2_transaction(base_fee, required + 101);
+    let err = tx_sender
+        .validate_tx(&tx, protocol_version)
+        .await
+        .unwrap_err();
+    assert_matches!(
+        err,
+        SubmitTxError::GasPerPubdataLimitTooHigh { max, .. } if max == required + 100
+    );
+
+    assert_eq!(
+        tx_sender.required_gas_per_pubdata().await.unwrap(),
+        required
+    );
+}
+
+/// Creates a transaction with raw bytes signed for the specified chain, as it would be submitted via the API.
+fn create_raw_transaction(tx_type: Option<u8>, chain_id: Option<u64>) -> L2Tx {
+    let private_key = K256PrivateKey::random();
+    let mut request = api::TransactionRequest {
+        to: Some(Address::random()),
+        from: Some(private_key.address()),
+        gas_price: 1_000.into(),
+        gas: 1_000_000.into(),
+        transaction_type: tx_type.map(U64::from),
+        chain_id,
+        ..api::TransactionRequest::default()
+    };
+    if tx_type.is_some() {
+        request.max_priority_fee_per_gas = Some(0.into());
+        request.access_list = Some(vec![]);
+    }
+    let mut unsigned_bytes = request.get_rlp().unwrap();
+    if let Some(tx_type) = tx_type {
+        unsigned_bytes.insert(0, tx_type);
+    }
+    let message = PackedEthSignature::message_to_signed_bytes(&unsigned_bytes);
+    let signature = PackedEthSignature::sign_raw(&private_key, &message).unwrap();
+    let raw_bytes = request.get_signed_bytes(&signature).unwrap();
+
+    let (request, hash) = api::TransactionRequest::from_bytes_unverified(&raw_bytes).unwrap();
+    let mut tx = L2Tx::from_request(request, usize::MAX).unwrap();
+    tx.set_input(raw_bytes, hash);
+    tx
+}
+
+#[tokio::test]
+async fn transactions_signed_for_other_chains_are_rejected() {
+    let pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    // Emulates hyperchains sharing the same infrastructure (and thus potentially the same accounts).
+    let chain_id = L2ChainId::from(270);
+    let other_chain_id = 271;
+    let tx_executor = MockTransactionExecutor::default().into();
+    let (mut tx_sender, _) = create_test_tx_sender(pool.clone(), chain_id, tx_executor).await;
+
+    for tx_type in [None, Some(EIP_2930_TX_TYPE), Some(EIP_1559_TX_TYPE)] {
+        let tx = create_raw_transaction(tx_type, Some(chain_id.as_u64()));
+        tx_sender.validate_chain_id(&tx).unwrap();
+
+        let tx = create_raw_transaction(tx_type, Some(other_chain_id));
+        let err = tx_sender
+            .validate_tx(&tx, ProtocolVersionId::latest())
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            SubmitTxError::ChainIdMismatch {
+                expected: 270,
+                actual: 271
+            },
+            "{tx_type:?}"
+        );
+        assert!(err.is_rejection());
+    }
+
+    // Legacy transactions without replay protection are accepted by default...
+    let unprotected_tx = create_raw_transaction(None, None);
+    assert_eq!(unprotected_tx.common_data.extract_chain_id(), None);
+    tx_sender.validate_chain_id(&unprotected_tx).unwrap();
+
+    // ...but can be rejected based on the config.
+    Arc::get_mut(&mut tx_sender.0)
+        .unwrap()
+        .sender_config
+        .reject_unprotected_txs = true;
+    let err = tx_sender
+        .validate_tx(&unprotected_tx, ProtocolVersionId::latest())
+        .await
+        .unwrap_err();
+    assert_matches!(err, SubmitTxError::UnprotectedTransaction);
+    let tx = create_raw_transaction(None, Some(chain_id.as_u64()));
+    tx_sender.validate_chain_id(&tx).unwrap();
+}
@@ -1,6 +1,9 @@
 use zksync_dal::transactions_dal::L2TxSubmissionResult;
 use zksync_types::{
-    api::{Transaction, TransactionDetails, TransactionId},
+    api::{
+        ForwardedTransaction, RejectedTransaction, Transaction, TransactionDeadline,
+        TransactionDetails, TransactionId,
+    },
     fee::TransactionExecutionMetrics,
     l2::L2Tx,
     Address, Nonce, H256,
@@ -54,4 +57,36 @@ pub trait TxSink: std::fmt::Debug + Send + Sync + 'static {
     ) -> Result<Option<TransactionDetails>, Web3Error> {
         Ok(None)
     }
+
+    /// Attempts to look up the forwarding status of a transaction propagated to another node (e.g., the main node).
+    /// By default, returns `Ok(None)`.
+    async fn lookup_forwarded_tx(
+        &self,
+        _hash: H256,
+    ) -> Result<Option<ForwardedTransaction>, Web3Error> {
+        Ok(None)
+    }
+
+    /// Records a rejected transaction in the rejected transactions journal. Old records are pruned separately
+    /// (by the house keeper). By default, this is a no-op.
+    async fn record_rejected_tx(&self, _tx: &RejectedTransaction) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Records an inclusion deadline for a transaction that is about to be submitted. Returns `false` if the transaction
+    /// already has a deadline, which is left intact.
+    /// By default, returns [`SubmitTxError::DeadlinesNotSupported`].
+    async fn insert_tx_deadline(
+        &self,
+        _tx_hash: H256,
+        _deadline: &TransactionDeadline,
+    ) -> Result<bool, SubmitTxError> {
+        Err(SubmitTxError::DeadlinesNotSupported)
+    }
+
+    /// Removes the inclusion deadline recorded by [`Self::insert_tx_deadline()`] if the transaction submission has failed.
+    /// By default, this is a no-op.
+    async fn remove_tx_deadline(&self, _tx_hash: H256) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
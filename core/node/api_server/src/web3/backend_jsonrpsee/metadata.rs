@@ -62,11 +62,21 @@ impl Drop for CurrentMethodGuard<'_> {
 #[derive(Debug, Default)]
 pub struct MethodTracer {
     inner: ThreadLocal<CurrentMethodInner>,
+    /// Whether errors should be mapped to the error codes used by geth.
+    pub(super) geth_compatible_errors: bool,
     #[cfg(test)]
     recorder: RecordedMethodCalls,
 }
 
 impl MethodTracer {
+    /// Creates a tracer mapping errors to the error codes used by geth.
+    pub(crate) fn geth_compatible() -> Self {
+        Self {
+            geth_compatible_errors: true,
+            ..Self::default()
+        }
+    }
+
     /// Sets the block ID for the current JSON-RPC method call. It will be used as a metric label for method latency etc.
     ///
     /// This should be called inside JSON-RPC method handlers; otherwise, this method is a no-op.
@@ -88,6 +98,13 @@ impl MethodTracer {
         }
     }
 
+    /// Returns the name of the current JSON-RPC method call, or `None` if called outside JSON-RPC method handlers.
+    pub(crate) fn method_name(&self) -> Option<&'static str> {
+        let cell = self.inner.get_or_default();
+        let name = cell.borrow().as_ref().map(|metadata| metadata.name);
+        name
+    }
+
     pub(super) fn new_call<'a>(
         self: &Arc<Self>,
         name: &'static str,
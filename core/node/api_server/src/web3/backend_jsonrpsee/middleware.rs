@@ -9,6 +9,8 @@ use std::{
     time::{Duration, Instant},
 };
 
+use anyhow::Context as _;
+use futures::future::BoxFuture;
 use governor::{
     clock::DefaultClock,
     middleware::NoOpMiddleware,
@@ -30,7 +32,14 @@ use zksync_web3_decl::jsonrpsee::{
 };
 
 use super::metadata::{MethodCall, MethodTracer};
-use crate::web3::metrics::{ObservedRpcParams, API_METRICS};
+use crate::{
+    execution_sandbox::ClientId,
+    web3::{
+        idempotency::{IdempotencyKey, IDEMPOTENCY_KEY_HEADER, MAX_IDEMPOTENCY_KEY_LEN},
+        metrics::{ObservedRpcParams, API_METRICS},
+        operator_auth::OperatorAuthenticator,
+    },
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "transport", rename_all = "snake_case")]
@@ -330,6 +339,195 @@ where
     }
 }
 
+/// HTTP-level middleware attributing requests to API clients for fair VM scheduling. The configured header is treated
+/// as a comma-separated list of hops (like `x-forwarded-for`), to which each of `trusted_proxies` reverse proxies
+/// appends an entry. The client is identified by the rightmost hop not appended by a trusted proxy; entries to the left
+/// of it are controlled by the client and thus ignored.
+///
+/// The client ID is set for the entire HTTP request processing, so it only has effect for the HTTP server;
+/// WebSocket connections are processed in separate tasks and are only subject to the global VM limit.
+#[derive(Debug, Clone)]
+pub(crate) struct ClientIdLayer {
+    header: http::HeaderName,
+    trusted_proxies: usize,
+}
+
+impl ClientIdLayer {
+    pub fn new(header: &str, trusted_proxies: usize) -> anyhow::Result<Self> {
+        let header = header
+            .parse()
+            .with_context(|| format!("invalid client ID header `{header}`"))?;
+        if trusted_proxies == 0 {
+            tracing::warn!(
+                "No trusted proxies are configured for client ID header `{header}`; requests will not be attributed \
+                 to API clients"
+            );
+        }
+        Ok(Self {
+            header,
+            trusted_proxies,
+        })
+    }
+}
+
+impl<S> tower::Layer<S> for ClientIdLayer {
+    type Service = ClientIdMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ClientIdMiddleware {
+            inner,
+            header: self.header.clone(),
+            trusted_proxies: self.trusted_proxies,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ClientIdMiddleware<S> {
+    inner: S,
+    header: http::HeaderName,
+    trusted_proxies: usize,
+}
+
+impl<S> ClientIdMiddleware<S> {
+    fn client_id(&self, headers: &http::HeaderMap) -> Option<ClientId> {
+        // Proxies may either append to an existing header or add a new one, so all header values are considered.
+        let mut hops = vec![];
+        for value in headers.get_all(&self.header) {
+            hops.extend(value.to_str().ok()?.split(',').map(str::trim));
+        }
+        let idx = hops.len().checked_sub(self.trusted_proxies)?;
+        let id = *hops.get(idx)?;
+        (!id.is_empty()).then(|| ClientId::new(id))
+    }
+}
+
+impl<S, B> tower::Service<http::Request<B>> for ClientIdMiddleware<S>
+where
+    S: tower::Service<http::Request<B>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let client_id = self.client_id(request.headers());
+        let response = self.inner.call(request);
+        match client_id {
+            Some(client_id) => Box::pin(client_id.scope(response)),
+            None => Box::pin(response),
+        }
+    }
+}
+
+/// HTTP-level middleware extracting idempotency keys for transaction submissions from the [`IDEMPOTENCY_KEY_HEADER`].
+/// Like [`ClientIdMiddleware`], it only has effect for the HTTP server.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct IdempotencyKeyLayer;
+
+impl<S> tower::Layer<S> for IdempotencyKeyLayer {
+    type Service = IdempotencyKeyMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        IdempotencyKeyMiddleware { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct IdempotencyKeyMiddleware<S> {
+    inner: S,
+}
+
+impl<S> IdempotencyKeyMiddleware<S> {
+    fn idempotency_key(headers: &http::HeaderMap) -> Option<IdempotencyKey> {
+        let key = headers.get(IDEMPOTENCY_KEY_HEADER)?.to_str().ok()?.trim();
+        (!key.is_empty() && key.len() <= MAX_IDEMPOTENCY_KEY_LEN).then(|| IdempotencyKey::new(key))
+    }
+}
+
+impl<S, B> tower::Service<http::Request<B>> for IdempotencyKeyMiddleware<S>
+where
+    S: tower::Service<http::Request<B>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let key = Self::idempotency_key(request.headers());
+        let response = self.inner.call(request);
+        match key {
+            Some(key) => Box::pin(key.scope(response)),
+            None => Box::pin(response),
+        }
+    }
+}
+
+/// HTTP-level middleware authenticating operators accessing the `admin` namespace by their API keys.
+/// Like [`ClientIdMiddleware`], it only has effect for the HTTP server.
+#[derive(Debug, Clone)]
+pub(crate) struct OperatorAuthLayer {
+    authenticator: Arc<OperatorAuthenticator>,
+}
+
+impl OperatorAuthLayer {
+    pub fn new(authenticator: OperatorAuthenticator) -> Self {
+        Self {
+            authenticator: Arc::new(authenticator),
+        }
+    }
+}
+
+impl<S> tower::Layer<S> for OperatorAuthLayer {
+    type Service = OperatorAuthMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        OperatorAuthMiddleware {
+            inner,
+            authenticator: self.authenticator.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct OperatorAuthMiddleware<S> {
+    inner: S,
+    authenticator: Arc<OperatorAuthenticator>,
+}
+
+impl<S, B> tower::Service<http::Request<B>> for OperatorAuthMiddleware<S>
+where
+    S: tower::Service<http::Request<B>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let operator = self.authenticator.authenticate(request.headers());
+        let response = self.inner.call(request);
+        match operator {
+            Some(operator) => Box::pin(operator.scope(response)),
+            None => Box::pin(response),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
@@ -412,4 +610,77 @@ mod tests {
         let elapsed = now.elapsed();
         assert!(elapsed >= Duration::from_millis(15), "{elapsed:?}");
     }
+
+    #[test]
+    fn extracting_client_id() {
+        let layer = ClientIdLayer::new("x-forwarded-for", 1).unwrap();
+        let middleware = tower::Layer::layer(&layer, ());
+        let mut headers = http::HeaderMap::new();
+        assert_eq!(middleware.client_id(&headers), None);
+
+        headers.insert("x-forwarded-for", "10.0.0.1".parse().unwrap());
+        assert_eq!(
+            middleware.client_id(&headers),
+            Some(ClientId::new("10.0.0.1"))
+        );
+        // The leftmost hop is supplied by the client and must be ignored.
+        headers.insert("x-forwarded-for", " 10.0.0.2, 10.0.0.1".parse().unwrap());
+        assert_eq!(
+            middleware.client_id(&headers),
+            Some(ClientId::new("10.0.0.1"))
+        );
+        // Hops in multiple header values are concatenated.
+        headers.append("x-forwarded-for", "10.0.0.3".parse().unwrap());
+        assert_eq!(
+            middleware.client_id(&headers),
+            Some(ClientId::new("10.0.0.3"))
+        );
+        headers.insert("x-forwarded-for", "".parse().unwrap());
+        assert_eq!(middleware.client_id(&headers), None);
+
+        ClientIdLayer::new("invalid header", 1).unwrap_err();
+    }
+
+    #[test]
+    fn extracting_client_id_behind_multiple_proxies() {
+        let layer = ClientIdLayer::new("x-forwarded-for", 2).unwrap();
+        let middleware = tower::Layer::layer(&layer, ());
+        let mut headers = http::HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            "spoofed, 10.0.0.1, 192.168.0.1".parse().unwrap(),
+        );
+        assert_eq!(
+            middleware.client_id(&headers),
+            Some(ClientId::new("10.0.0.1"))
+        );
+        // The request has not passed through all trusted proxies.
+        headers.insert("x-forwarded-for", "10.0.0.1".parse().unwrap());
+        assert_eq!(middleware.client_id(&headers), None);
+
+        let layer = ClientIdLayer::new("x-forwarded-for", 0).unwrap();
+        let middleware = tower::Layer::layer(&layer, ());
+        assert_eq!(middleware.client_id(&headers), None);
+    }
+
+    #[test]
+    fn extracting_idempotency_key() {
+        let mut headers = http::HeaderMap::new();
+        assert_eq!(
+            IdempotencyKeyMiddleware::<()>::idempotency_key(&headers),
+            None
+        );
+
+        headers.insert(IDEMPOTENCY_KEY_HEADER, " retry-42 ".parse().unwrap());
+        assert_eq!(
+            IdempotencyKeyMiddleware::<()>::idempotency_key(&headers),
+            Some(IdempotencyKey::new("retry-42"))
+        );
+        let long_key = "a".repeat(MAX_IDEMPOTENCY_KEY_LEN + 1);
+        headers.insert(IDEMPOTENCY_KEY_HEADER, long_key.parse().unwrap());
+        assert_eq!(
+            IdempotencyKeyMiddleware::<()>::idempotency_key(&headers),
+            None
+        );
+    }
 }
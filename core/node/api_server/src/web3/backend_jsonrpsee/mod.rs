@@ -4,13 +4,17 @@
 
 use zksync_web3_decl::{
     error::Web3Error,
-    jsonrpsee::types::{error::ErrorCode, ErrorObjectOwned},
+    jsonrpsee::types::{
+        error::{ErrorCode, OVERSIZED_RESPONSE_CODE},
+        ErrorObjectOwned,
+    },
 };
 
 pub(crate) use self::{
     metadata::{MethodMetadata, MethodTracer},
     middleware::{
-        CorrelationMiddleware, LimitMiddleware, MetadataLayer, ShutdownMiddleware, TrafficTracker,
+        ClientIdLayer, CorrelationMiddleware, IdempotencyKeyLayer, LimitMiddleware, MetadataLayer,
+        OperatorAuthLayer, ShutdownMiddleware, TrafficTracker,
     },
 };
 use crate::tx_sender::SubmitTxError;
@@ -30,6 +34,12 @@ impl MethodTracer {
             Web3Error::ProxyError(_) => Some("0x".to_owned()),
             _ => None,
         };
+        if self.geth_compatible_errors {
+            if let Some((code, message)) = geth_compatible_error(&err) {
+                return ErrorObjectOwned::owned(code, message, data);
+            }
+        }
+
         let code = match err {
             Web3Error::MethodNotImplemented => ErrorCode::MethodNotFound.code(),
             Web3Error::InternalError(_) => ErrorCode::InternalError.code(),
@@ -39,7 +49,14 @@ impl MethodTracer {
             | Web3Error::TooManyTopics
             | Web3Error::FilterNotFound
             | Web3Error::InvalidFilterBlockHash
-            | Web3Error::LogsLimitExceeded(_, _, _) => ErrorCode::InvalidParams.code(),
+            | Web3Error::InvalidTxBatch(_)
+            | Web3Error::IdempotencyKeyReused(_)
+            | Web3Error::LogsLimitExceeded(_, _, _)
+            | Web3Error::ResultTooLarge(_)
+            | Web3Error::QueryTimeout => ErrorCode::InvalidParams.code(),
+            Web3Error::Unauthorized => ErrorCode::InvalidRequest.code(),
+            // Use the same code as `jsonrpsee` uses for responses exceeding the size limit during serialization.
+            Web3Error::ResponseTooLarge(_) => OVERSIZED_RESPONSE_CODE,
             Web3Error::SubmitTransactionError(_, _)
             | Web3Error::SerializationError(_)
             | Web3Error::ProxyError(_) => 3,
@@ -57,11 +74,30 @@ impl MethodTracer {
     }
 }
 
+/// Maps errors to the code / message pairs returned by geth, for the errors where the default mapping differs.
+/// geth uses the generic server error code (-32000) for most app-level errors, with the exception
+/// of reverted transactions (code 3).
+fn geth_compatible_error(err: &Web3Error) -> Option<(i32, String)> {
+    const GETH_SERVER_ERROR: i32 = -32000;
+
+    match err {
+        Web3Error::NoBlock => Some((GETH_SERVER_ERROR, "header not found".to_owned())),
+        Web3Error::SubmitTransactionError(message, _)
+            if !message.starts_with("execution reverted") =>
+        {
+            Some((GETH_SERVER_ERROR, message.clone()))
+        }
+        Web3Error::SerializationError(_) => Some((GETH_SERVER_ERROR, err.to_string())),
+        _ => None,
+    }
+}
+
 impl From<SubmitTxError> for Web3Error {
     fn from(err: SubmitTxError) -> Self {
         match err {
             SubmitTxError::Internal(err) => Self::InternalError(err),
             SubmitTxError::ProxyError(err) => Self::ProxyError(err),
+            SubmitTxError::DeadlinesNotSupported => Self::MethodNotImplemented,
             _ => Self::SubmitTransactionError(err.to_string(), err.data()),
         }
     }
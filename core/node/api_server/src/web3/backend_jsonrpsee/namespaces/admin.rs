@@ -0,0 +1,60 @@
+use async_trait::async_trait;
+use zksync_types::{
+    api::{EthSenderStatus, OperatorAuditLogEntry},
+    Address, H256,
+};
+use zksync_web3_decl::{jsonrpsee::core::RpcResult, namespaces::AdminNamespaceServer};
+
+use crate::web3::namespaces::AdminNamespace;
+
+#[async_trait]
+impl AdminNamespaceServer for AdminNamespace {
+    async fn get_traced_addresses(&self) -> RpcResult<Vec<Address>> {
+        self.get_traced_addresses_impl()
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn add_traced_addresses(&self, addresses: Vec<Address>) -> RpcResult<()> {
+        self.add_traced_addresses_impl(&addresses)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn remove_traced_addresses(&self, addresses: Vec<Address>) -> RpcResult<()> {
+        self.remove_traced_addresses_impl(&addresses)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_audit_log(
+        &self,
+        after_id: Option<u64>,
+        limit: Option<usize>,
+    ) -> RpcResult<Vec<OperatorAuditLogEntry>> {
+        self.get_audit_log_impl(after_id, limit)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn request_snapshot(&self) -> RpcResult<u64> {
+        self.request_snapshot_impl()
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn boost_transaction(&self, tx_hash: H256) -> RpcResult<bool> {
+        self.boost_transaction_impl(tx_hash)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_eth_sender_status(
+        &self,
+        failures_limit: Option<usize>,
+    ) -> RpcResult<EthSenderStatus> {
+        self.get_eth_sender_status_impl(failures_limit)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+}
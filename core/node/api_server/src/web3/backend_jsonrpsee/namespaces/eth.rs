@@ -1,3 +1,4 @@
+use anyhow::Context as _;
 use zksync_types::{
     api::{
         Block, BlockId, BlockIdVariant, BlockNumber, Log, Transaction, TransactionId,
@@ -8,7 +9,11 @@ use zksync_types::{
     Address, H256, U256, U64,
 };
 use zksync_web3_decl::{
-    jsonrpsee::core::{async_trait, RpcResult},
+    error::Web3Error,
+    jsonrpsee::{
+        core::{async_trait, RpcResult},
+        RpcModule,
+    },
     namespaces::EthNamespaceServer,
     types::{Filter, FilterChanges},
 };
@@ -259,3 +264,58 @@ impl EthNamespaceServer for EthNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 }
+
+/// Block fields that are not returned by geth.
+const NON_GETH_BLOCK_FIELDS: &[&str] = &["l1BatchNumber", "l1BatchTimestamp", "sealFields"];
+
+impl EthNamespace {
+    /// Replaces `eth_getBlockByNumber` and `eth_getBlockByHash` methods in the module with ones returning blocks
+    /// in the geth shape (i.e., without [`NON_GETH_BLOCK_FIELDS`]). Used if the `block_fields` geth compatibility shim
+    /// is enabled.
+    pub(crate) fn use_geth_block_fields(rpc: &mut RpcModule<Self>) -> anyhow::Result<()> {
+        rpc.remove_method("eth_getBlockByNumber");
+        rpc.register_async_method("eth_getBlockByNumber", |params, this| async move {
+            let mut params = params.sequence();
+            let block_number: BlockNumber = params.next()?;
+            let full_transactions: bool = params.next()?;
+            this.get_geth_block(BlockId::Number(block_number), full_transactions)
+                .await
+        })
+        .context("cannot register `eth_getBlockByNumber`")?;
+
+        rpc.remove_method("eth_getBlockByHash");
+        rpc.register_async_method("eth_getBlockByHash", |params, this| async move {
+            let mut params = params.sequence();
+            let hash: H256 = params.next()?;
+            let full_transactions: bool = params.next()?;
+            this.get_geth_block(BlockId::Hash(hash), full_transactions)
+                .await
+        })
+        .context("cannot register `eth_getBlockByHash`")?;
+        Ok(())
+    }
+
+    async fn get_geth_block(
+        &self,
+        block_id: BlockId,
+        full_transactions: bool,
+    ) -> RpcResult<Option<serde_json::Value>> {
+        let block = self
+            .get_block_impl(block_id, full_transactions)
+            .await
+            .map_err(|err| self.current_method().map_err(err))?;
+        let Some(block) = block else {
+            return Ok(None);
+        };
+        let mut block = serde_json::to_value(block).map_err(|err| {
+            self.current_method()
+                .map_err(Web3Error::InternalError(err.into()))
+        })?;
+        if let Some(fields) = block.as_object_mut() {
+            for &field in NON_GETH_BLOCK_FIELDS {
+                fields.remove(field);
+            }
+        }
+        Ok(Some(block))
+    }
+}
@@ -1,4 +1,5 @@
-use zksync_web3_decl::{jsonrpsee::core::RpcResult, namespaces::Web3NamespaceServer};
+use zksync_types::H256;
+use zksync_web3_decl::{jsonrpsee::core::RpcResult, namespaces::Web3NamespaceServer, types::Bytes};
 
 use crate::web3::Web3Namespace;
 
@@ -6,4 +7,8 @@ impl Web3NamespaceServer for Web3Namespace {
     fn client_version(&self) -> RpcResult<String> {
         Ok(self.client_version_impl())
     }
+
+    fn sha3(&self, data: Bytes) -> RpcResult<H256> {
+        Ok(self.sha3_impl(data))
+    }
 }
@@ -1,16 +1,19 @@
 use std::collections::HashMap;
 
-use itertools::Itertools;
 use zksync_types::{
     api::{
-        ApiStorageLog, BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Log, Proof,
-        ProtocolVersion, TransactionDetailedResult, TransactionDetails,
+        AccountNonceInfo, ApiCapabilities, ApiFeeParams, BlockDetails, BridgeAddresses,
+        ForwardedTransaction, InternalTransfer, InteropMessageProof, L1BatchDetails,
+        L1ToL2ExecutionSimulation, L2BlockOrL1Batch, L2ToL1LogProof, PriorityOpDetails,
+        PriorityQueueInfo, Proof, ProtocolVersion, ProtocolVersionHistoryEntry,
+        RejectedTransaction, TransactionDeadline, TransactionDetailedResult, TransactionDetails,
+        TransactionExpiry, VerificationKeysHashes,
     },
     fee::Fee,
-    fee_model::{FeeParams, PubdataIndependentBatchFeeModelInput},
+    fee_model::{BatchFeeInputParams, PubdataIndependentBatchFeeModelInput},
     transaction_request::CallRequest,
     web3::Bytes,
-    Address, L1BatchNumber, L2BlockNumber, StorageLogQueryType, H256, U256, U64,
+    Address, L1BatchNumber, L2BlockNumber, PriorityOpId, H256, U256, U64,
 };
 use zksync_web3_decl::{
     jsonrpsee::core::{async_trait, RpcResult},
@@ -28,12 +31,27 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn estimate_fee_batch(&self, reqs: Vec<CallRequest>) -> RpcResult<Vec<Fee>> {
+        self.estimate_fee_batch_impl(reqs)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> RpcResult<U256> {
         self.estimate_l1_to_l2_gas_impl(req)
             .await
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn estimate_l1_to_l2_execute(
+        &self,
+        req: CallRequest,
+    ) -> RpcResult<L1ToL2ExecutionSimulation> {
+        self.estimate_l1_to_l2_execute_impl(req)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn get_bridgehub_contract(&self) -> RpcResult<Option<Address>> {
         Ok(self.get_bridgehub_contract_impl())
     }
@@ -91,6 +109,18 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn get_interop_message_proof(
+        &self,
+        block: L2BlockNumber,
+        sender: Address,
+        msg: H256,
+        l2_log_position: Option<usize>,
+    ) -> RpcResult<Option<InteropMessageProof>> {
+        self.get_interop_message_proof_impl(block, sender, msg, l2_log_position)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn get_l1_batch_number(&self) -> RpcResult<U64> {
         self.get_l1_batch_number_impl()
             .await
@@ -118,6 +148,24 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn get_internal_transfers(&self, tx_hash: H256) -> RpcResult<Vec<InternalTransfer>> {
+        self.get_internal_transfers_impl(tx_hash)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_nonce_info(&self, address: Address) -> RpcResult<AccountNonceInfo> {
+        self.get_nonce_info_impl(address)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_rejected_transaction(&self, hash: H256) -> RpcResult<Option<RejectedTransaction>> {
+        self.get_rejected_transaction_impl(hash)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn get_raw_block_transactions(
         &self,
         block_number: L2BlockNumber,
@@ -150,8 +198,19 @@ impl ZksNamespaceServer for ZksNamespace {
         }
     }
 
-    async fn get_fee_params(&self) -> RpcResult<FeeParams> {
-        Ok(self.get_fee_params_impl())
+    async fn get_fee_params(&self) -> RpcResult<ApiFeeParams> {
+        self.get_fee_params_impl()
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_fee_params_at(
+        &self,
+        at: L2BlockOrL1Batch,
+    ) -> RpcResult<Option<BatchFeeInputParams>> {
+        self.get_fee_params_at_impl(at)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
     }
 
     async fn get_batch_fee_input(&self) -> RpcResult<PubdataIndependentBatchFeeModelInput> {
@@ -169,6 +228,21 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn get_protocol_version_history(&self) -> RpcResult<Vec<ProtocolVersionHistoryEntry>> {
+        self.get_protocol_version_history_impl()
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_verification_keys_hashes(
+        &self,
+        version_id: Option<u16>,
+    ) -> RpcResult<Vec<VerificationKeysHashes>> {
+        self.get_verification_keys_hashes_impl(version_id)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn get_proof(
         &self,
         address: Address,
@@ -185,34 +259,58 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn get_priority_queue_info(&self, limit: Option<usize>) -> RpcResult<PriorityQueueInfo> {
+        self.get_priority_queue_info_impl(limit)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_priority_op_details(
+        &self,
+        serial_id: PriorityOpId,
+    ) -> RpcResult<Option<PriorityOpDetails>> {
+        self.get_priority_op_details_impl(serial_id)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_capabilities(&self) -> RpcResult<ApiCapabilities> {
+        self.get_capabilities_impl()
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn send_raw_transaction_with_detailed_output(
         &self,
         tx_bytes: Bytes,
     ) -> RpcResult<TransactionDetailedResult> {
         self.send_raw_transaction_with_detailed_output_impl(tx_bytes)
             .await
-            .map(|result| TransactionDetailedResult {
-                transaction_hash: result.0,
-                storage_logs: result
-                    .1
-                    .logs
-                    .storage_logs
-                    .iter()
-                    .filter(|x| x.log_type != StorageLogQueryType::Read)
-                    .map(ApiStorageLog::from)
-                    .collect_vec(),
-                events: result
-                    .1
-                    .logs
-                    .events
-                    .iter()
-                    .map(|x| {
-                        let mut l = Log::from(x);
-                        l.transaction_hash = Some(result.0);
-                        l
-                    })
-                    .collect_vec(),
-            })
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn send_raw_transaction_with_deadline(
+        &self,
+        tx_bytes: Bytes,
+        deadline: TransactionDeadline,
+    ) -> RpcResult<H256> {
+        self.send_raw_transaction_with_deadline_impl(tx_bytes, deadline)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_transaction_expiry(&self, hash: H256) -> RpcResult<Option<TransactionExpiry>> {
+        self.get_transaction_expiry_impl(hash)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_forwarded_transaction(
+        &self,
+        hash: H256,
+    ) -> RpcResult<Option<ForwardedTransaction>> {
+        self.get_forwarded_transaction_impl(hash)
+            .await
             .map_err(|err| self.current_method().map_err(err))
     }
 }
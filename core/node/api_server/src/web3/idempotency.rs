@@ -0,0 +1,160 @@
+//! Idempotency keys for transaction submissions.
+//!
+//! A client retrying `eth_sendRawTransaction` or `zks_sendRawTransactionWithDetailedOutput` after a network failure
+//! doesn't know whether the original submission succeeded; if it did, the retry fails with a "known transaction" error.
+//! If a submission is accompanied by the [`IDEMPOTENCY_KEY_HEADER`], its successful result is remembered for a limited time,
+//! and retries with the same key and the same transaction receive the original result.
+
+use std::{
+    future::Future,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use lru::LruCache;
+use zksync_types::{api::TransactionDetailedResult, H256};
+use zksync_web3_decl::error::Web3Error;
+
+use super::metrics::API_METRICS;
+
+/// HTTP header containing the idempotency key.
+pub(crate) const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+/// Maximum supported length of idempotency keys. Longer keys are ignored.
+pub(crate) const MAX_IDEMPOTENCY_KEY_LEN: usize = 256;
+
+tokio::task_local! {
+    static CURRENT_KEY: IdempotencyKey;
+}
+
+/// Client-provided idempotency key. Set for the entire HTTP request processing by the server middleware.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct IdempotencyKey(Arc<str>);
+
+impl IdempotencyKey {
+    pub fn new(key: &str) -> Self {
+        Self(key.into())
+    }
+
+    /// Runs the provided future with this key as the current one.
+    pub async fn scope<F: Future>(self, fut: F) -> F::Output {
+        CURRENT_KEY.scope(self, fut).await
+    }
+
+    pub fn current() -> Option<Self> {
+        CURRENT_KEY.try_with(Clone::clone).ok()
+    }
+}
+
+#[derive(Debug)]
+struct CacheEntry<T> {
+    tx_hash: H256,
+    output: T,
+    inserted_at: Instant,
+}
+
+/// LRU cache of successful submission outputs keyed by idempotency keys, with a time-to-live for each entry.
+///
+/// Only completed submissions are remembered; if a retry arrives while the original submission is still processed,
+/// it is processed as a separate submission.
+#[derive(Debug)]
+pub(crate) struct IdempotencyCache<T> {
+    ttl: Duration,
+    entries: Mutex<LruCache<IdempotencyKey, CacheEntry<T>>>,
+}
+
+impl<T: Clone> IdempotencyCache<T> {
+    pub fn new(capacity: NonZeroUsize, ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Returns the remembered output for the key. Errors if the key was used for a different transaction.
+    pub fn get(&self, key: &IdempotencyKey, tx_hash: H256) -> Result<Option<T>, Web3Error> {
+        let mut entries = self.entries.lock().expect("idempotency cache is poisoned");
+        let Some(entry) = entries.get(key) else {
+            return Ok(None);
+        };
+        if entry.inserted_at.elapsed() > self.ttl {
+            entries.pop(key);
+            return Ok(None);
+        }
+        if entry.tx_hash != tx_hash {
+            return Err(Web3Error::IdempotencyKeyReused(entry.tx_hash));
+        }
+        API_METRICS.idempotent_submission_replays.inc();
+        Ok(Some(entry.output.clone()))
+    }
+
+    /// Remembers the output of a successful submission.
+    pub fn insert(&self, key: IdempotencyKey, tx_hash: H256, output: T) {
+        let entry = CacheEntry {
+            tx_hash,
+            output,
+            inserted_at: Instant::now(),
+        };
+        self.entries
+            .lock()
+            .expect("idempotency cache is poisoned")
+            .put(key, entry);
+    }
+}
+
+/// Idempotency caches for all supported submission methods.
+#[derive(Debug)]
+pub(crate) struct IdempotencyCaches {
+    /// Cache for `eth_sendRawTransaction`. The output is the transaction hash, so it's not stored separately.
+    pub send_raw_transaction: IdempotencyCache<()>,
+    /// Cache for `zks_sendRawTransactionWithDetailedOutput`.
+    pub send_raw_transaction_detailed: IdempotencyCache<TransactionDetailedResult>,
+}
+
+impl IdempotencyCaches {
+    pub fn new(capacity: NonZeroUsize, ttl: Duration) -> Self {
+        Self {
+            send_raw_transaction: IdempotencyCache::new(capacity, ttl),
+            send_raw_transaction_detailed: IdempotencyCache::new(capacity, ttl),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn idempotency_cache_basics() {
+        let cache = IdempotencyCache::new(NonZeroUsize::new(10).unwrap(), Duration::from_secs(60));
+        let key = IdempotencyKey::new("test");
+        let tx_hash = H256::repeat_byte(1);
+        assert_eq!(cache.get(&key, tx_hash).unwrap(), None);
+
+        cache.insert(key.clone(), tx_hash, 42_u32);
+        assert_eq!(cache.get(&key, tx_hash).unwrap(), Some(42));
+        let err = cache.get(&key, H256::repeat_byte(2)).unwrap_err();
+        assert_matches!(err, Web3Error::IdempotencyKeyReused(hash) if hash == tx_hash);
+        let other_key = IdempotencyKey::new("other");
+        assert_eq!(cache.get(&other_key, tx_hash).unwrap(), None);
+    }
+
+    #[test]
+    fn idempotency_cache_entries_expire() {
+        let cache = IdempotencyCache::new(NonZeroUsize::new(10).unwrap(), Duration::ZERO);
+        let key = IdempotencyKey::new("test");
+        cache.insert(key.clone(), H256::repeat_byte(1), 42_u32);
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(cache.get(&key, H256::repeat_byte(2)).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn current_idempotency_key() {
+        assert_eq!(IdempotencyKey::current(), None);
+        let key = IdempotencyKey::new("test");
+        let current = key.clone().scope(async { IdempotencyKey::current() }).await;
+        assert_eq!(current, Some(key));
+    }
+}
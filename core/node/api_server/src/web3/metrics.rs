@@ -167,8 +167,14 @@ enum Web3ErrorKind {
     TooManyTopics,
     FilterNotFound,
     LogsLimitExceeded,
+    ResultTooLarge,
+    ResponseTooLarge,
+    QueryTimeout,
     InvalidFilterBlockHash,
+    InvalidTxBatch,
+    IdempotencyKeyReused,
     TreeApiUnavailable,
+    Unauthorized,
     Internal,
 }
 
@@ -183,8 +189,14 @@ impl Web3ErrorKind {
             Web3Error::TooManyTopics => Self::TooManyTopics,
             Web3Error::FilterNotFound => Self::FilterNotFound,
             Web3Error::LogsLimitExceeded(..) => Self::LogsLimitExceeded,
+            Web3Error::ResultTooLarge(_) => Self::ResultTooLarge,
+            Web3Error::ResponseTooLarge(_) => Self::ResponseTooLarge,
+            Web3Error::QueryTimeout => Self::QueryTimeout,
             Web3Error::InvalidFilterBlockHash => Self::InvalidFilterBlockHash,
+            Web3Error::InvalidTxBatch(_) => Self::InvalidTxBatch,
+            Web3Error::IdempotencyKeyReused(_) => Self::IdempotencyKeyReused,
             Web3Error::TreeApiUnavailable => Self::TreeApiUnavailable,
+            Web3Error::Unauthorized => Self::Unauthorized,
             Web3Error::InternalError(_) | Web3Error::MethodNotImplemented => Self::Internal,
         }
     }
@@ -267,6 +279,10 @@ pub(crate) struct ApiMetrics {
     pub ws_open_sessions: Gauge,
     /// Number of currently inserted into DB transactions.
     pub inflight_tx_submissions: Gauge,
+    /// Number of pending transactions evicted from the mempool to make space for transactions with higher fees.
+    pub evicted_mempool_txs: Counter,
+    /// Number of transaction submissions answered with a remembered result based on the idempotency key.
+    pub idempotent_submission_replays: Counter,
 }
 
 impl ApiMetrics {
@@ -9,7 +9,9 @@ use tokio::{
     task::JoinHandle,
 };
 use tower_http::{cors::CorsLayer, metrics::InFlightRequestsLayer};
-use zksync_config::configs::api::{MaxResponseSize, MaxResponseSizeOverrides};
+use zksync_config::configs::api::{
+    GethCompatibilityShim, MaxResponseSize, MaxResponseSizeOverrides,
+};
 use zksync_dal::{helpers::wait_for_l1_batch, ConnectionPool, Core};
 use zksync_health_check::{HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_metadata_calculator::api_server::TreeApiClient;
@@ -20,26 +22,31 @@ use zksync_web3_decl::{
         server::{
             middleware::rpc::either::Either, BatchRequestConfig, RpcServiceBuilder, ServerBuilder,
         },
+        types::ErrorObjectOwned,
         MethodCallback, Methods, RpcModule,
     },
     namespaces::{
-        DebugNamespaceServer, EnNamespaceServer, EthNamespaceServer, EthPubSubServer,
-        NetNamespaceServer, SnapshotsNamespaceServer, Web3NamespaceServer, ZksNamespaceServer,
+        AdminNamespaceServer, DebugNamespaceServer, EnNamespaceServer, EthNamespaceServer,
+        EthPubSubServer, NetNamespaceServer, SnapshotsNamespaceServer, Web3NamespaceServer,
+        ZksNamespaceServer,
     },
+    openrpc::{OpenRpcDocument, DISCOVER_METHOD},
     types::Filter,
 };
 
 use self::{
     backend_jsonrpsee::{
-        CorrelationMiddleware, LimitMiddleware, MetadataLayer, MethodTracer, ShutdownMiddleware,
-        TrafficTracker,
+        ClientIdLayer, CorrelationMiddleware, IdempotencyKeyLayer, LimitMiddleware, MetadataLayer,
+        MethodTracer, OperatorAuthLayer, ShutdownMiddleware, TrafficTracker,
     },
+    idempotency::{IdempotencyCaches, IDEMPOTENCY_KEY_HEADER},
     mempool_cache::MempoolCache,
     metrics::API_METRICS,
     namespaces::{
-        DebugNamespace, EnNamespace, EthNamespace, NetNamespace, SnapshotsNamespace, Web3Namespace,
-        ZksNamespace,
+        AdminNamespace, DebugNamespace, EnNamespace, EthNamespace, NetNamespace,
+        SnapshotsNamespace, Web3Namespace, ZksNamespace, ZKS_API_VERSION,
     },
+    operator_auth::OperatorAuthenticator,
     pubsub::{EthSubscribe, EthSubscriptionIdProvider, PubSubEvent},
     state::{Filters, InternalApiConfig, RpcState, SealedL2BlockNumber},
 };
@@ -49,9 +56,11 @@ use crate::{
 };
 
 pub mod backend_jsonrpsee;
+mod idempotency;
 pub mod mempool_cache;
 pub(super) mod metrics;
 pub mod namespaces;
+mod operator_auth;
 mod pubsub;
 pub mod state;
 pub mod testonly;
@@ -97,6 +106,9 @@ pub enum Namespace {
     En,
     Pubsub,
     Snapshots,
+    /// Operator-facing methods changing node behavior at runtime. Requires a master connection pool
+    /// to be set via [`ApiBuilder::with_master_pool()`].
+    Admin,
 }
 
 impl Namespace {
@@ -132,6 +144,7 @@ struct OptionalApiParams {
     tree_api: Option<Arc<dyn TreeApiClient>>,
     mempool_cache: Option<MempoolCache>,
     extended_tracing: bool,
+    master_pool: Option<ConnectionPool<Core>>,
     pub_sub_events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
 }
 
@@ -174,6 +187,14 @@ impl ApiBuilder {
     const DEFAULT_PRUNING_INFO_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
 
     pub fn jsonrpsee_backend(config: InternalApiConfig, pool: ConnectionPool<Core>) -> Self {
+        let method_tracer = if config
+            .geth_compatibility
+            .contains(&GethCompatibilityShim::ErrorCodes)
+        {
+            MethodTracer::geth_compatible()
+        } else {
+            MethodTracer::default()
+        };
         Self {
             updaters_pool: pool.clone(),
             pool,
@@ -183,7 +204,7 @@ impl ApiBuilder {
             transport: None,
             tx_sender: None,
             namespaces: None,
-            method_tracer: Arc::new(MethodTracer::default()),
+            method_tracer: Arc::new(method_tracer),
             optional: OptionalApiParams::default(),
         }
     }
@@ -282,6 +303,12 @@ impl ApiBuilder {
         self
     }
 
+    /// Configures a pool connected to the master DB. Required for the `admin` namespace, which modifies node state.
+    pub fn with_master_pool(mut self, pool: ConnectionPool<Core>) -> Self {
+        self.optional.master_pool = Some(pool);
+        self
+    }
+
     // Intended for tests only.
     #[doc(hidden)]
     fn with_pub_sub_events(mut self, sender: mpsc::UnboundedSender<PubSubEvent>) -> Self {
@@ -306,6 +333,26 @@ impl ApiBuilder {
         };
         let (_, health_updater) = ReactiveHealthCheck::new(health_check_name);
 
+        let mut namespaces = self.namespaces.unwrap_or_else(|| {
+            tracing::warn!(
+                "debug_ and snapshots_ API namespace will be disabled by default in ApiBuilder"
+            );
+            Namespace::DEFAULT.to_vec()
+        });
+        if namespaces.contains(&Namespace::Admin) {
+            // The admin namespace must never be served without authentication.
+            anyhow::ensure!(
+                !self.config.admin_api_keys.is_empty(),
+                "admin API namespace requires admin API keys to be configured"
+            );
+            if matches!(transport, ApiTransport::WebSocket(_)) {
+                tracing::warn!(
+                    "admin API namespace is only served by the HTTP server since WebSocket connections are not authenticated"
+                );
+                namespaces.retain(|namespace| *namespace != Namespace::Admin);
+            }
+        }
+
         Ok(ApiServer {
             pool: self.pool,
             health_updater: Arc::new(health_updater),
@@ -315,12 +362,7 @@ impl ApiBuilder {
             tx_sender: self.tx_sender.context("Transaction sender not set")?,
             polling_interval: self.polling_interval,
             pruning_info_refresh_interval: self.pruning_info_refresh_interval,
-            namespaces: self.namespaces.unwrap_or_else(|| {
-                tracing::warn!(
-                    "debug_ and snapshots_ API namespace will be disabled by default in ApiBuilder"
-                );
-                Namespace::DEFAULT.to_vec()
-            }),
+            namespaces,
             method_tracer: self.method_tracer,
             optional: self.optional,
         })
@@ -351,6 +393,17 @@ impl ApiServer {
                 ))))
             };
 
+        let idempotency_caches = if matches!(self.transport, ApiTransport::Http(_)) {
+            self.config.idempotency_keys_cache_size.map(|capacity| {
+                Arc::new(IdempotencyCaches::new(
+                    capacity,
+                    self.config.idempotency_key_ttl,
+                ))
+            })
+        } else {
+            None
+        };
+
         Ok(RpcState {
             current_method: self.method_tracer,
             installed_filters,
@@ -362,6 +415,9 @@ impl ApiServer {
             mempool_cache: self.optional.mempool_cache,
             last_sealed_l2_block,
             tree_api: self.optional.tree_api,
+            rpc_method_names: Arc::default(),
+            response_size_limit: self.optional.response_body_size_limit.map(Arc::new),
+            idempotency_caches,
         })
     }
 
@@ -372,7 +428,17 @@ impl ApiServer {
     ) -> anyhow::Result<RpcModule<()>> {
         let namespaces = self.namespaces.clone();
         let zksync_network_id = self.config.l2_chain_id;
+        let net_web3_geth_compatible = self
+            .config
+            .geth_compatibility
+            .contains(&GethCompatibilityShim::NetWeb3Methods);
+        let geth_block_fields = self
+            .config
+            .geth_compatibility
+            .contains(&GethCompatibilityShim::BlockFields);
+        let master_pool = self.optional.master_pool.clone();
         let rpc_state = self.build_rpc_state(last_sealed_l2_block).await?;
+        let rpc_method_names = rpc_state.rpc_method_names.clone();
 
         // Collect all the methods into a single RPC module.
         let mut rpc = RpcModule::new(());
@@ -386,16 +452,23 @@ impl ApiServer {
                 .context("cannot merge debug namespace")?;
         }
         if namespaces.contains(&Namespace::Eth) {
-            rpc.merge(EthNamespace::new(rpc_state.clone()).into_rpc())
-                .context("cannot merge eth namespace")?;
+            let mut eth_rpc = EthNamespace::new(rpc_state.clone()).into_rpc();
+            if geth_block_fields {
+                EthNamespace::use_geth_block_fields(&mut eth_rpc)?;
+            }
+            rpc.merge(eth_rpc).context("cannot merge eth namespace")?;
         }
         if namespaces.contains(&Namespace::Net) {
-            rpc.merge(NetNamespace::new(zksync_network_id).into_rpc())
+            rpc.merge(NetNamespace::new(zksync_network_id, net_web3_geth_compatible).into_rpc())
                 .context("cannot merge net namespace")?;
         }
         if namespaces.contains(&Namespace::Web3) {
-            rpc.merge(Web3Namespace.into_rpc())
-                .context("cannot merge web3 namespace")?;
+            let mut web3_rpc = Web3Namespace.into_rpc();
+            if !net_web3_geth_compatible {
+                // Unregister the method, so that it's not advertised by `zks_getCapabilities` and `rpc.discover`.
+                web3_rpc.remove_method("web3_sha3");
+            }
+            rpc.merge(web3_rpc).context("cannot merge web3 namespace")?;
         }
         if namespaces.contains(&Namespace::Zks) {
             rpc.merge(ZksNamespace::new(rpc_state.clone()).into_rpc())
@@ -405,10 +478,36 @@ impl ApiServer {
             rpc.merge(EnNamespace::new(rpc_state.clone()).into_rpc())
                 .context("cannot merge en namespace")?;
         }
+        if namespaces.contains(&Namespace::Admin) {
+            let master_pool =
+                master_pool.context("admin namespace requires a master connection pool")?;
+            rpc.merge(AdminNamespace::new(rpc_state.clone(), master_pool).into_rpc())
+                .context("cannot merge admin namespace")?;
+        }
         if namespaces.contains(&Namespace::Snapshots) {
             rpc.merge(SnapshotsNamespace::new(rpc_state).into_rpc())
                 .context("cannot merge snapshots namespace")?;
         }
+
+        let (document, undocumented_methods) =
+            OpenRpcDocument::new(ZKS_API_VERSION, rpc.method_names());
+        if !undocumented_methods.is_empty() {
+            tracing::warn!(
+                "Methods {undocumented_methods:?} don't have specifications and are not included in the OpenRPC document"
+            );
+        }
+        let document = document.to_json();
+        rpc.register_method(DISCOVER_METHOD, move |_params, _ctx| {
+            Ok::<_, ErrorObjectOwned>(document.clone())
+        })
+        .context("cannot register OpenRPC discovery method")?;
+
+        let mut method_names: Vec<_> = rpc.method_names().map(str::to_owned).collect();
+        method_names.sort_unstable();
+        rpc_method_names
+            .set(method_names)
+            .ok()
+            .context("RPC method names are already set")?;
         Ok(rpc)
     }
 
@@ -627,6 +726,22 @@ impl ApiServer {
         let vm_barrier = self.optional.vm_barrier.clone();
         let health_updater = self.health_updater.clone();
         let method_tracer = self.method_tracer.clone();
+        let client_id_layer = self
+            .config
+            .vm_client_id_header
+            .as_deref()
+            .filter(|_| is_http)
+            .map(|header| ClientIdLayer::new(header, self.config.vm_client_id_trusted_proxies))
+            .transpose()?;
+        let idempotency_key_layer = (is_http && self.config.idempotency_keys_cache_size.is_some())
+            .then_some(IdempotencyKeyLayer);
+        let operator_auth_layer = if is_http && self.namespaces.contains(&Namespace::Admin) {
+            let authenticator = OperatorAuthenticator::new(&self.config.admin_api_keys)
+                .context("invalid admin API keys")?;
+            Some(OperatorAuthLayer::new(authenticator))
+        } else {
+            None
+        };
 
         let extended_tracing = self.optional.extended_tracing;
         if extended_tracing {
@@ -648,7 +763,10 @@ impl ApiServer {
                 .allow_methods([http::Method::POST])
                 // Allow requests from any origin
                 .allow_origin(tower_http::cors::Any)
-                .allow_headers([http::header::CONTENT_TYPE])
+                .allow_headers([
+                    http::header::CONTENT_TYPE,
+                    http::HeaderName::from_static(IDEMPOTENCY_KEY_HEADER),
+                ])
         });
         // Setup metrics for the number of in-flight requests.
         let (in_flight_requests, counter) = InFlightRequestsLayer::pair();
@@ -661,7 +779,10 @@ impl ApiServer {
         // Assemble server middleware.
         let middleware = tower::ServiceBuilder::new()
             .layer(in_flight_requests)
-            .option_layer(cors);
+            .option_layer(cors)
+            .option_layer(client_id_layer)
+            .option_layer(idempotency_key_layer)
+            .option_layer(operator_auth_layer);
 
         // Settings shared by HTTP and WS servers.
         let max_connections = !is_http
@@ -0,0 +1,201 @@
+use serde_json::json;
+use zksync_dal::{ConnectionPool, Core, CoreDal, DalError};
+use zksync_types::{
+    api::{EthSenderStatus, OperatorAuditLogEntry},
+    Address, H256,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use crate::web3::{backend_jsonrpsee::MethodTracer, operator_auth::OperatorId, state::RpcState};
+
+/// Default number of recently failed L1 transactions returned by `admin_getEthSenderStatus`.
+const DEFAULT_ETH_SENDER_FAILURES_LIMIT: usize = 10;
+
+#[derive(Debug, Clone)]
+pub(crate) struct AdminNamespace {
+    state: RpcState,
+    /// Pool used for writes; the pool in `state` may point to a read replica.
+    master_pool: ConnectionPool<Core>,
+}
+
+impl AdminNamespace {
+    pub fn new(state: RpcState, master_pool: ConnectionPool<Core>) -> Self {
+        Self { state, master_pool }
+    }
+
+    pub(crate) fn current_method(&self) -> &MethodTracer {
+        &self.state.current_method
+    }
+
+    /// Returns the operator performing the current call. Errors if the caller is not authenticated.
+    fn current_actor(&self) -> Result<String, Web3Error> {
+        let operator = OperatorId::current().ok_or(Web3Error::Unauthorized)?;
+        Ok(operator.to_string())
+    }
+
+    pub async fn get_traced_addresses_impl(&self) -> Result<Vec<Address>, Web3Error> {
+        self.current_actor()?;
+        let mut storage = self.state.acquire_connection().await?;
+        Ok(storage
+            .traced_addresses_dal()
+            .get_traced_addresses()
+            .await
+            .map_err(DalError::generalize)?)
+    }
+
+    pub async fn add_traced_addresses_impl(&self, addresses: &[Address]) -> Result<(), Web3Error> {
+        let actor = self.current_actor()?;
+        let mut storage = self
+            .master_pool
+            .connection_tagged("api")
+            .await
+            .map_err(DalError::generalize)?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(DalError::generalize)?;
+        transaction
+            .traced_addresses_dal()
+            .add_traced_addresses(addresses)
+            .await
+            .map_err(DalError::generalize)?;
+        transaction
+            .operator_audit_log_dal()
+            .record_action(
+                &actor,
+                "add_traced_addresses",
+                json!({ "addresses": addresses }),
+            )
+            .await
+            .map_err(DalError::generalize)?;
+        transaction.commit().await.map_err(DalError::generalize)?;
+        tracing::info!("Enabled call tracing for addresses {addresses:?}");
+        Ok(())
+    }
+
+    pub async fn remove_traced_addresses_impl(
+        &self,
+        addresses: &[Address],
+    ) -> Result<(), Web3Error> {
+        let actor = self.current_actor()?;
+        let mut storage = self
+            .master_pool
+            .connection_tagged("api")
+            .await
+            .map_err(DalError::generalize)?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(DalError::generalize)?;
+        transaction
+            .traced_addresses_dal()
+            .remove_traced_addresses(addresses)
+            .await
+            .map_err(DalError::generalize)?;
+        transaction
+            .operator_audit_log_dal()
+            .record_action(
+                &actor,
+                "remove_traced_addresses",
+                json!({ "addresses": addresses }),
+            )
+            .await
+            .map_err(DalError::generalize)?;
+        transaction.commit().await.map_err(DalError::generalize)?;
+        tracing::info!("Disabled call tracing for addresses {addresses:?}");
+        Ok(())
+    }
+
+    pub async fn request_snapshot_impl(&self) -> Result<u64, Web3Error> {
+        let actor = self.current_actor()?;
+        let mut storage = self
+            .master_pool
+            .connection_tagged("api")
+            .await
+            .map_err(DalError::generalize)?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(DalError::generalize)?;
+        let request_id = transaction
+            .snapshots_dal()
+            .request_snapshot(&actor)
+            .await
+            .map_err(DalError::generalize)?;
+        transaction
+            .operator_audit_log_dal()
+            .record_action(
+                &actor,
+                "request_snapshot",
+                json!({ "request_id": request_id }),
+            )
+            .await
+            .map_err(DalError::generalize)?;
+        transaction.commit().await.map_err(DalError::generalize)?;
+        tracing::info!("Recorded snapshot creation request #{request_id}");
+        Ok(request_id)
+    }
+
+    pub async fn boost_transaction_impl(&self, tx_hash: H256) -> Result<bool, Web3Error> {
+        let actor = self.current_actor()?;
+        let mut storage = self
+            .master_pool
+            .connection_tagged("api")
+            .await
+            .map_err(DalError::generalize)?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .map_err(DalError::generalize)?;
+        let boosted = transaction
+            .boosted_transactions_dal()
+            .boost_transaction(tx_hash)
+            .await
+            .map_err(DalError::generalize)?;
+        if boosted {
+            transaction
+                .operator_audit_log_dal()
+                .record_action(&actor, "boost_transaction", json!({ "tx_hash": tx_hash }))
+                .await
+                .map_err(DalError::generalize)?;
+        }
+        transaction.commit().await.map_err(DalError::generalize)?;
+        if boosted {
+            tracing::info!("Boosted transaction {tx_hash:?} to the mempool priority lane");
+        }
+        Ok(boosted)
+    }
+
+    pub async fn get_eth_sender_status_impl(
+        &self,
+        failures_limit: Option<usize>,
+    ) -> Result<EthSenderStatus, Web3Error> {
+        self.current_actor()?;
+        let max_limit = self.state.api_config.req_entities_limit;
+        let failures_limit = failures_limit
+            .unwrap_or(DEFAULT_ETH_SENDER_FAILURES_LIMIT)
+            .min(max_limit);
+        let mut storage = self.state.acquire_connection().await?;
+        Ok(storage
+            .eth_sender_dal()
+            .get_status(failures_limit)
+            .await
+            .map_err(DalError::generalize)?)
+    }
+
+    pub async fn get_audit_log_impl(
+        &self,
+        after_id: Option<u64>,
+        limit: Option<usize>,
+    ) -> Result<Vec<OperatorAuditLogEntry>, Web3Error> {
+        self.current_actor()?;
+        let max_limit = self.state.api_config.req_entities_limit;
+        let limit = limit.unwrap_or(max_limit).min(max_limit);
+        let mut storage = self.state.acquire_connection().await?;
+        Ok(storage
+            .operator_audit_log_dal()
+            .get_entries(after_id, limit)
+            .await
+            .map_err(DalError::generalize)?)
+    }
+}
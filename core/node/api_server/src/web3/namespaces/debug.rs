@@ -3,7 +3,7 @@ use std::sync::Arc;
 use anyhow::Context as _;
 use multivm::{interface::ExecutionResult, vm_latest::constants::BATCH_COMPUTATIONAL_GAS_LIMIT};
 use once_cell::sync::OnceCell;
-use zksync_dal::{CoreDal, DalError};
+use zksync_dal::{transactions_dal::CallTraceFilter, CoreDal, DalError};
 use zksync_system_constants::MAX_ENCODED_TX_SIZE;
 use zksync_types::{
     api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
@@ -18,8 +18,11 @@ use zksync_web3_decl::error::Web3Error;
 
 use crate::{
     execution_sandbox::{ApiTracer, TxSharedArgs},
-    tx_sender::{ApiContracts, TxSenderConfig},
-    web3::{backend_jsonrpsee::MethodTracer, state::RpcState},
+    tx_sender::{ApiContracts, SubmitTxError, TxSenderConfig},
+    web3::{
+        backend_jsonrpsee::MethodTracer,
+        state::{map_read_query_error, RpcState},
+    },
 };
 
 #[derive(Debug, Clone)]
@@ -76,11 +79,19 @@ impl DebugNamespace {
         self.current_method()
             .set_block_diff(self.state.last_sealed_l2_block.diff(block_number));
 
+        let limit = self.state.api_config.req_entities_limit;
         let call_traces = connection
             .blocks_web3_dal()
-            .get_traces_for_l2_block(block_number)
+            .get_traces_for_l2_block(
+                block_number,
+                limit + 1,
+                self.state.api_config.query_statement_timeout,
+            )
             .await
-            .map_err(DalError::generalize)?;
+            .map_err(map_read_query_error)?;
+        if call_traces.len() > limit {
+            return Err(Web3Error::ResultTooLarge(limit));
+        }
         let call_trace = call_traces
             .into_iter()
             .map(|call_trace| {
@@ -109,22 +120,25 @@ impl DebugNamespace {
         tx_hash: H256,
         options: Option<TracerConfig>,
     ) -> Result<Option<DebugCall>, Web3Error> {
-        let only_top_call = options
-            .map(|options| options.tracer_config.only_top_call)
-            .unwrap_or(false);
+        let tracer_config = options
+            .map(|options| options.tracer_config)
+            .unwrap_or_default();
+        let filter = CallTraceFilter {
+            top_calls_offset: tracer_config.top_calls_offset,
+            top_calls_limit: tracer_config.top_calls_limit,
+            max_frames: if tracer_config.only_top_call {
+                Some(1)
+            } else {
+                tracer_config.max_frames
+            },
+        };
         let mut connection = self.state.acquire_connection().await?;
         let call_trace = connection
             .transactions_dal()
-            .get_call_trace(tx_hash)
+            .get_call_trace(tx_hash, &filter)
             .await
             .map_err(DalError::generalize)?;
-        Ok(call_trace.map(|call_trace| {
-            let mut result: DebugCall = call_trace.into();
-            if only_top_call {
-                result.calls = vec![];
-            }
-            result
-        }))
+        Ok(call_trace.map(Into::into))
     }
 
     pub async fn debug_trace_call_impl(
@@ -167,14 +181,14 @@ impl DebugNamespace {
         let call_overrides = request.get_call_overrides()?;
         let tx = L2Tx::from_request(request.into(), MAX_ENCODED_TX_SIZE)?;
 
-        let shared_args = self.shared_args().await;
+        let shared_args = self.shared_args().await.map_err(Web3Error::InternalError)?;
         let vm_permit = self
             .state
             .tx_sender
             .vm_concurrency_limiter()
             .acquire()
-            .await;
-        let vm_permit = vm_permit.context("cannot acquire VM permit")?;
+            .await
+            .map_err(SubmitTxError::from)?;
 
         // We don't need properly trace if we only need top call
         let call_tracer_result = Arc::new(OnceCell::default());
@@ -226,12 +240,16 @@ impl DebugNamespace {
         Ok(call.into())
     }
 
-    async fn shared_args(&self) -> TxSharedArgs {
+    async fn shared_args(&self) -> anyhow::Result<TxSharedArgs> {
         let sender_config = self.sender_config();
-        TxSharedArgs {
+        Ok(TxSharedArgs {
             operator_account: AccountTreeId::default(),
             fee_input: self.batch_fee_input,
-            base_system_contracts: self.api_contracts.eth_call.clone(),
+            base_system_contracts: self
+                .state
+                .tx_sender
+                .with_evm_emulator(&self.api_contracts.eth_call)
+                .await?,
             caches: self.state.tx_sender.storage_caches().clone(),
             validation_computational_gas_limit: BATCH_COMPUTATIONAL_GAS_LIMIT,
             chain_id: sender_config.chain_id,
@@ -240,6 +258,7 @@ impl DebugNamespace {
                 .tx_sender
                 .read_whitelisted_tokens_for_aa_cache()
                 .await,
-        }
+            vm_memory_pool: self.state.tx_sender.vm_memory_pool(),
+        })
     }
 }
@@ -127,6 +127,7 @@ impl EnNamespace {
             genesis_commitment: Some(genesis_batch.metadata.commitment),
             bootloader_hash: Some(genesis_batch.header.base_system_contracts_hashes.bootloader),
             default_aa_hash: Some(genesis_batch.header.base_system_contracts_hashes.default_aa),
+            evm_emulator_hash: self.state.api_config.evm_emulator_hash,
             l1_chain_id: self.state.api_config.l1_chain_id,
             l2_chain_id: self.state.api_config.l2_chain_id,
             recursion_node_level_vk_hash: verifier_config.params.recursion_node_level_vk_hash,
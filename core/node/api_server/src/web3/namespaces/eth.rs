@@ -1,6 +1,7 @@
 use anyhow::Context as _;
+use zksync_config::configs::api::GethCompatibilityShim;
 use zksync_dal::{CoreDal, DalError};
-use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
+use zksync_system_constants::{DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE, EMPTY_TRIE_ROOT_HASH};
 use zksync_types::{
     api::{
         BlockId, BlockNumber, GetLogsFilter, Transaction, TransactionId, TransactionReceipt,
@@ -12,14 +13,23 @@ use zksync_types::{
     web3::{self, Bytes, FeeHistory, SyncInfo, SyncState},
     AccountTreeId, L2BlockNumber, StorageKey, H256, L2_BASE_TOKEN_ADDRESS, U256,
 };
-use zksync_utils::u256_to_h256;
+use zksync_utils::{
+    bytecode::{is_evm_bytecode_hash, trim_padded_evm_bytecode},
+    u256_to_h256,
+};
 use zksync_web3_decl::{
     error::Web3Error,
     types::{Address, Block, Filter, FilterChanges, Log, U64},
 };
 
 use crate::web3::{
-    backend_jsonrpsee::MethodTracer, metrics::API_METRICS, state::RpcState, TypedFilter,
+    backend_jsonrpsee::MethodTracer,
+    idempotency::IdempotencyKey,
+    metrics::API_METRICS,
+    state::{
+        map_read_query_error, max_serialized_logs_size, min_serialized_transactions_size, RpcState,
+    },
+    TypedFilter,
 };
 
 pub const EVENT_TOPIC_NUMBER_LIMIT: usize = 4;
@@ -244,7 +254,7 @@ impl EthNamespace {
         else {
             return Ok(None);
         };
-        let Some(block) = storage
+        let Some(mut block) = storage
             .blocks_web3_dal()
             .get_api_block(block_number)
             .await
@@ -254,6 +264,26 @@ impl EthNamespace {
         };
         self.set_block_diff(block_number);
 
+        let geth_compatibility = &self.state.api_config.geth_compatibility;
+        if geth_compatibility.contains(&GethCompatibilityShim::BlockMiner) {
+            // Some tooling expects `miner` to be set to the fee recipient, like in geth.
+            if let Some(fee_address) = storage
+                .blocks_dal()
+                .get_fee_address_for_l2_block(block_number)
+                .await
+                .map_err(DalError::generalize)?
+            {
+                block.author = fee_address;
+            }
+        }
+        if geth_compatibility.contains(&GethCompatibilityShim::BlockFields)
+            && block.transactions.is_empty()
+        {
+            // geth clients check that the transactions root of a block without transactions is the empty trie root.
+            block.transactions_root = EMPTY_TRIE_ROOT_HASH;
+            block.receipts_root = EMPTY_TRIE_ROOT_HASH;
+        }
+
         let transactions = if full_transactions {
             let mut transactions = storage
                 .transactions_web3_dal()
@@ -270,6 +300,10 @@ impl EthNamespace {
                 );
                 return Err(err.into());
             }
+            // The server enforces the exact response size limit when serializing the response, so a lower bound
+            // is enough to reject oversized blocks early.
+            let min_response_size = min_serialized_transactions_size(&transactions);
+            self.state.ensure_response_may_fit(min_response_size)?;
             // We need to sort `transactions` by their index in block since `get_transactions()` returns
             // transactions in an arbitrary order.
             transactions.sort_unstable_by_key(|tx| tx.transaction_index);
@@ -384,7 +418,17 @@ impl EthNamespace {
             .get_contract_code_unchecked(address, block_number)
             .await
             .map_err(DalError::generalize)?;
-        Ok(contract_code.unwrap_or_default().into())
+        let Some((bytecode_hash, bytecode)) = contract_code else {
+            return Ok(Bytes::default());
+        };
+        // EVM contracts are stored as padded bytecodes interpreted by the EVM emulator; return the original
+        // EVM bytecode rather than the padded one.
+        if is_evm_bytecode_hash(&bytecode_hash) {
+            let bytecode = trim_padded_evm_bytecode(&bytecode_hash, &bytecode)
+                .map_err(Web3Error::InternalError)?;
+            return Ok(bytecode.to_vec().into());
+        }
+        Ok(bytecode.into())
     }
 
     pub fn chain_id_impl(&self) -> U64 {
@@ -593,6 +637,8 @@ impl EthNamespace {
                 installed_filters.lock().await.remove(idx);
                 Err(Web3Error::FilterNotFound)
             }
+            // Other errors (e.g., a statement timeout or an oversized response) may be transient,
+            // so the filter is retained and can be polled again.
             Err(err) => Err(err),
         }
     }
@@ -613,14 +659,28 @@ impl EthNamespace {
 
     pub async fn send_raw_transaction_impl(&self, tx_bytes: Bytes) -> Result<H256, Web3Error> {
         let (mut tx, hash) = self.state.parse_transaction_bytes(&tx_bytes.0)?;
+        let idempotency = self
+            .state
+            .idempotency_caches
+            .as_deref()
+            .zip(IdempotencyKey::current());
+        if let Some((caches, key)) = &idempotency {
+            if caches.send_raw_transaction.get(key, hash)?.is_some() {
+                return Ok(hash);
+            }
+        }
         tx.set_input(tx_bytes.0, hash);
 
         let submit_result = self.state.tx_sender.submit_tx(tx).await;
-        submit_result.map(|_| hash).map_err(|err| {
+        submit_result.map_err(|err| {
             tracing::debug!("Send raw transaction error: {err}");
             API_METRICS.submit_tx_error[&err.prom_error_code()].inc();
-            err.into()
-        })
+            Web3Error::from(err)
+        })?;
+        if let Some((caches, key)) = idempotency {
+            caches.send_raw_transaction.insert(key, hash, ());
+        }
+        Ok(hash)
     }
 
     pub fn accounts_impl(&self) -> Vec<Address> {
@@ -787,32 +847,45 @@ impl EthNamespace {
                 };
 
                 let mut storage = self.state.acquire_connection().await?;
+                let limit = self.state.api_config.req_entities_limit;
+                let statement_timeout = self.state.api_config.query_statement_timeout;
 
                 // Check if there is more than one block in range and there are more than `req_entities_limit` logs that satisfies filter.
                 // In this case we should return error and suggest requesting logs with smaller block range.
                 if *from_block != to_block {
                     if let Some(l2_block_number) = storage
                         .events_web3_dal()
-                        .get_log_block_number(
-                            &get_logs_filter,
-                            self.state.api_config.req_entities_limit,
-                        )
+                        .get_log_block_number(&get_logs_filter, limit, statement_timeout)
                         .await
-                        .map_err(DalError::generalize)?
+                        .map_err(map_read_query_error)?
                     {
                         return Err(Web3Error::LogsLimitExceeded(
-                            self.state.api_config.req_entities_limit,
+                            limit,
                             from_block.0,
                             from_block.0.max(l2_block_number.0 - 1),
                         ));
                     }
                 }
 
+                // Logs from a single L2 block cannot be split across responses, so if the block contains
+                // more than `req_entities_limit` matching logs, the filter has to be narrowed. Fetching
+                // an extra log is enough to detect this case.
                 let logs = storage
                     .events_web3_dal()
-                    .get_logs(get_logs_filter, i32::MAX as usize)
+                    .get_logs(get_logs_filter, limit + 1, statement_timeout)
                     .await
-                    .map_err(DalError::generalize)?;
+                    .map_err(map_read_query_error)?;
+                if logs.len() > limit {
+                    return Err(Web3Error::LogsLimitExceeded(
+                        limit,
+                        from_block.0,
+                        from_block.0,
+                    ));
+                }
+                // Must be checked before the filter is advanced; otherwise, the logs would be lost if the server
+                // rejects the response.
+                self.state
+                    .ensure_response_may_fit(max_serialized_logs_size(&logs))?;
                 *from_block = to_block + 1;
                 FilterChanges::Logs(logs)
             }
@@ -3,11 +3,15 @@ use zksync_types::{L2ChainId, U256};
 #[derive(Debug, Clone)]
 pub struct NetNamespace {
     zksync_network_id: L2ChainId,
+    geth_compatible: bool,
 }
 
 impl NetNamespace {
-    pub fn new(zksync_network_id: L2ChainId) -> Self {
-        Self { zksync_network_id }
+    pub fn new(zksync_network_id: L2ChainId, geth_compatible: bool) -> Self {
+        Self {
+            zksync_network_id,
+            geth_compatible,
+        }
     }
 
     pub fn version_impl(&self) -> String {
@@ -19,6 +23,7 @@ impl NetNamespace {
     }
 
     pub fn is_listening_impl(&self) -> bool {
-        false
+        // geth reports `true` for all nodes listening to p2p connections, which some tooling uses as a liveness check.
+        self.geth_compatible
     }
 }
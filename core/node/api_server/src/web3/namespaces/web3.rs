@@ -1,3 +1,6 @@
+use zksync_types::{web3::keccak256, H256};
+use zksync_web3_decl::types::Bytes;
+
 #[derive(Debug, Clone)]
 pub struct Web3Namespace;
 
@@ -5,7 +8,9 @@ impl Web3Namespace {
     pub fn client_version_impl(&self) -> String {
         "zkSync/v2.0".to_string()
     }
-}
 
-// `sha3` method is intentionally not implemented for the main server implementation:
-// it can easily be implemented on the user side.
+    /// `sha3` method is intentionally not registered by default: it can easily be implemented on the user side.
+    pub fn sha3_impl(&self, data: Bytes) -> H256 {
+        H256(keccak256(&data.0))
+    }
+}
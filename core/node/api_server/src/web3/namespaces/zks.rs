@@ -1,27 +1,38 @@
-use std::{collections::HashMap, convert::TryInto};
+use std::{collections::HashMap, convert::TryInto, time::Duration};
 
 use anyhow::Context as _;
-use multivm::interface::VmExecutionResultAndLogs;
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use multivm::interface::{ExecutionResult, VmExecutionResultAndLogs};
 use zksync_dal::{Connection, Core, CoreDal, DalError};
 use zksync_metadata_calculator::api_server::TreeApiError;
 use zksync_mini_merkle_tree::MiniMerkleTree;
-use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
+use zksync_system_constants::{
+    DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE, SHARED_BRIDGE_ETHER_TOKEN_ADDRESS,
+};
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, GetLogsFilter, L1BatchDetails, L2ToL1LogProof, Proof,
-        ProtocolVersion, StorageProof, TransactionDetails,
+        AccountNonceInfo, ApiCapabilities, ApiFeeParams, ApiStorageLog, BlockDetails, BlockId,
+        BlockNumber, BridgeAddresses, ChainFeatures, ForwardedTransaction, GetLogsFilter,
+        InternalTransfer, InteropMessageProof, L1BatchDetails, L1ToL2ExecutionSimulation,
+        L2BlockOrL1Batch, L2ToL1LogProof, Log, PriorityOpDetails, PriorityOpStatus,
+        PriorityQueueInfo, Proof, ProtocolVersion, ProtocolVersionHistoryEntry,
+        RejectedTransaction, StorageProof, TransactionDeadline, TransactionDetailedResult,
+        TransactionDetails, TransactionExpiry, VerificationKeysHashes,
     },
+    commitment::L1BatchCommitmentMode,
     fee::Fee,
-    fee_model::{FeeParams, PubdataIndependentBatchFeeModelInput},
+    fee_model::{BatchFeeInputParams, PubdataIndependentBatchFeeModelInput},
     l1::L1Tx,
     l2::L2Tx,
     l2_to_l1_log::{l2_to_l1_logs_tree_size, L2ToL1Log},
     tokens::ETHEREUM_ADDRESS,
     transaction_request::CallRequest,
-    utils::storage_key_for_standard_token_balance,
+    utils::{decompose_full_nonce, storage_key_for_standard_token_balance},
     web3::Bytes,
-    AccountTreeId, L1BatchNumber, L2BlockNumber, ProtocolVersionId, StorageKey, Transaction,
-    L1_MESSENGER_ADDRESS, L2_BASE_TOKEN_ADDRESS, REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256, U64,
+    AccountTreeId, L1BatchNumber, L2BlockNumber, PriorityOpId, ProtocolVersionId, StorageKey,
+    StorageLogQueryType, Transaction, L1_MESSENGER_ADDRESS, L2_BASE_TOKEN_ADDRESS,
+    REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256, U64,
 };
 use zksync_utils::{address_to_h256, h256_to_u256};
 use zksync_web3_decl::{
@@ -29,7 +40,20 @@ use zksync_web3_decl::{
     types::{Address, Token, H256},
 };
 
-use crate::web3::{backend_jsonrpsee::MethodTracer, metrics::API_METRICS, RpcState};
+use crate::{
+    tx_sender::L1TxSimulationOutput,
+    web3::{
+        backend_jsonrpsee::MethodTracer, idempotency::IdempotencyKey, metrics::API_METRICS,
+        state::map_read_query_error, RpcState,
+    },
+};
+
+/// Semantic version of the API reported by `zks_getCapabilities` and `rpc.discover`. Must be bumped together with changes to the API.
+pub(crate) const ZKS_API_VERSION: &str = "1.0.0";
+/// Default number of pending priority operations returned by `zks_getPriorityQueueInfo`.
+const DEFAULT_PENDING_PRIORITY_OPS_LIMIT: usize = 100;
+/// Number of recently included priority operations used to estimate inclusion delays.
+const PRIORITY_OP_DELAY_SAMPLE_SIZE: usize = 20;
 
 #[derive(Debug)]
 pub(crate) struct ZksNamespace {
@@ -50,21 +74,66 @@ impl ZksNamespace {
         self.state
             .set_nonce_for_call_request(&mut request_with_gas_per_pubdata_overridden)
             .await?;
+        let tx = self.l2_tx_for_fee_estimation(request_with_gas_per_pubdata_overridden)?;
+        self.estimate_fee(tx.into()).await
+    }
 
-        if let Some(ref mut eip712_meta) = request_with_gas_per_pubdata_overridden.eip712_meta {
+    pub async fn estimate_fee_batch_impl(
+        &self,
+        requests: Vec<CallRequest>,
+    ) -> Result<Vec<Fee>, Web3Error> {
+        let max_batch_size = self.state.api_config.estimate_fee_batch_max_size;
+        let Some(first_request) = requests.first() else {
+            return Err(Web3Error::InvalidTxBatch("batch is empty".to_owned()));
+        };
+        if requests.len() > max_batch_size {
+            return Err(Web3Error::InvalidTxBatch(format!(
+                "batch contains {} transactions, while at most {max_batch_size} are allowed",
+                requests.len()
+            )));
+        }
+        let sender = first_request.from;
+        if requests.iter().any(|request| request.from != sender) {
+            return Err(Web3Error::InvalidTxBatch(
+                "all transactions must have the same sender".to_owned(),
+            ));
+        }
+
+        let mut txs = Vec::with_capacity(requests.len());
+        let mut next_nonce = None;
+        for mut request in requests {
+            // Nonces not specified explicitly are carried forward from the previous transaction.
+            match next_nonce {
+                Some(nonce) if request.nonce.is_none() => request.nonce = Some(nonce),
+                _ => self.state.set_nonce_for_call_request(&mut request).await?,
+            }
+            next_nonce = request.nonce.map(|nonce| nonce + 1);
+            let tx = self.l2_tx_for_fee_estimation(request)?;
+            txs.push(tx.into());
+        }
+
+        let scale_factor = self.state.api_config.estimate_gas_scale_factor;
+        let acceptable_overestimation =
+            self.state.api_config.estimate_gas_acceptable_overestimation;
+        Ok(self
+            .state
+            .tx_sender
+            .get_dependent_txs_fees_in_wei(txs, scale_factor, acceptable_overestimation as u64)
+            .await?)
+    }
+
+    fn l2_tx_for_fee_estimation(&self, mut request: CallRequest) -> Result<L2Tx, Web3Error> {
+        if let Some(ref mut eip712_meta) = request.eip712_meta {
             eip712_meta.gas_per_pubdata = U256::from(DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE);
         }
 
-        let mut tx = L2Tx::from_request(
-            request_with_gas_per_pubdata_overridden.into(),
-            self.state.api_config.max_tx_size,
-        )?;
+        let mut tx = L2Tx::from_request(request.into(), self.state.api_config.max_tx_size)?;
 
         // When we're estimating fee, we are trying to deduce values related to fee, so we should
         // not consider provided ones.
         tx.common_data.fee.max_priority_fee_per_gas = 0u64.into();
         tx.common_data.fee.gas_per_pubdata_limit = U256::from(DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE);
-        self.estimate_fee(tx.into()).await
+        Ok(tx)
     }
 
     pub async fn estimate_l1_to_l2_gas_impl(
@@ -88,6 +157,54 @@ impl ZksNamespace {
         Ok(fee.gas_limit)
     }
 
+    pub async fn estimate_l1_to_l2_execute_impl(
+        &self,
+        mut request: CallRequest,
+    ) -> Result<L1ToL2ExecutionSimulation, Web3Error> {
+        if let Some(ref mut eip712_meta) = request.eip712_meta {
+            if eip712_meta.gas_per_pubdata == U256::zero() {
+                eip712_meta.gas_per_pubdata = REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE.into();
+            }
+        }
+        let tx: L1Tx = request.try_into().map_err(Web3Error::SerializationError)?;
+        let output = self.state.tx_sender.simulate_l1_tx(tx).await?;
+        Ok(Self::l1_to_l2_simulation(output))
+    }
+
+    fn l1_to_l2_simulation(output: L1TxSimulationOutput) -> L1ToL2ExecutionSimulation {
+        let L1TxSimulationOutput { tx, base_fee, vm } = output;
+        let common_data = &tx.common_data;
+        let gas_refunded = U256::from(vm.refunds.gas_refunded);
+        let gas_used = common_data.gas_limit.saturating_sub(gas_refunded);
+        let success = !vm.result.is_failed();
+        // The operator is paid for the charged gas at the base fee; the remaining minted funds
+        // (including the transferred value if the transaction fails) go to the refund recipient.
+        let mut refund_amount = common_data
+            .to_mint
+            .saturating_sub(gas_used * U256::from(base_fee));
+        if success {
+            refund_amount = refund_amount.saturating_sub(tx.execute.value);
+        }
+        let (output, revert_reason) = match &vm.result {
+            ExecutionResult::Success { output } => (output.clone(), None),
+            ExecutionResult::Revert { output } => (output.encoded_data(), Some(output.to_string())),
+            ExecutionResult::Halt { reason } => (vec![], Some(reason.to_string())),
+        };
+
+        L1ToL2ExecutionSimulation {
+            success,
+            gas_limit: common_data.gas_limit,
+            max_fee_per_gas: common_data.max_fee_per_gas,
+            gas_used,
+            gas_refunded,
+            refund_recipient: common_data.refund_recipient,
+            refund_amount,
+            output: output.into(),
+            revert_reason,
+            events: vm.logs.events.iter().map(Log::from).collect(),
+        }
+    }
+
     async fn estimate_fee(&self, tx: Transaction) -> Result<Fee, Web3Error> {
         let scale_factor = self.state.api_config.estimate_gas_scale_factor;
         let acceptable_overestimation =
@@ -196,6 +313,40 @@ impl ZksNamespace {
         msg: H256,
         l2_log_position: Option<usize>,
     ) -> Result<Option<L2ToL1LogProof>, Web3Error> {
+        let proof = self
+            .get_l2_to_l1_msg_proof_inner(block_number, sender, msg, l2_log_position)
+            .await?;
+        Ok(proof.map(|(_, proof, _)| proof))
+    }
+
+    pub async fn get_interop_message_proof_impl(
+        &self,
+        block_number: L2BlockNumber,
+        sender: Address,
+        msg: H256,
+        l2_log_position: Option<usize>,
+    ) -> Result<Option<InteropMessageProof>, Web3Error> {
+        let proof = self
+            .get_l2_to_l1_msg_proof_inner(block_number, sender, msg, l2_log_position)
+            .await?;
+        Ok(
+            proof.map(|(l1_batch_number, log_proof, log)| InteropMessageProof {
+                chain_id: self.state.api_config.l2_chain_id,
+                l1_batch_number,
+                tx_number_in_batch: log.tx_number_in_block,
+                log_proof,
+            }),
+        )
+    }
+
+    /// Returns the L1 batch containing the message, the proof for the message log and the log itself.
+    async fn get_l2_to_l1_msg_proof_inner(
+        &self,
+        block_number: L2BlockNumber,
+        sender: Address,
+        msg: H256,
+        l2_log_position: Option<usize>,
+    ) -> Result<Option<(L1BatchNumber, L2ToL1LogProof, L2ToL1Log)>, Web3Error> {
         let mut storage = self.state.acquire_connection().await?;
         self.state
             .start_info
@@ -229,9 +380,10 @@ impl ZksNamespace {
                         topics: vec![(2, vec![address_to_h256(&sender)]), (3, vec![msg])],
                     },
                     self.state.api_config.req_entities_limit,
+                    self.state.api_config.query_statement_timeout,
                 )
                 .await
-                .map_err(DalError::generalize)?;
+                .map_err(map_read_query_error)?;
             let maybe_pos = logs.iter().position(|event| {
                 event.block_number == Some(block_number.0.into())
                     && event.log_index == Some(l2_log_position.into())
@@ -256,7 +408,7 @@ impl ZksNamespace {
                 },
             )
             .await?;
-        Ok(log_proof)
+        Ok(log_proof.map(|(proof, log)| (l1_batch_number, proof, log)))
     }
 
     async fn get_l2_to_l1_log_proof_inner(
@@ -265,14 +417,14 @@ impl ZksNamespace {
         l1_batch_number: L1BatchNumber,
         index_in_filtered_logs: usize,
         log_filter: impl Fn(&L2ToL1Log) -> bool,
-    ) -> Result<Option<L2ToL1LogProof>, Web3Error> {
+    ) -> Result<Option<(L2ToL1LogProof, L2ToL1Log)>, Web3Error> {
         let all_l1_logs_in_batch = storage
             .blocks_web3_dal()
             .get_l2_to_l1_logs(l1_batch_number)
             .await
             .map_err(DalError::generalize)?;
 
-        let Some((l1_log_index, _)) = all_l1_logs_in_batch
+        let Some((l1_log_index, log)) = all_l1_logs_in_batch
             .iter()
             .enumerate()
             .filter(|(_, log)| log_filter(log))
@@ -299,11 +451,12 @@ impl ZksNamespace {
 
         let (root, proof) = MiniMerkleTree::new(merkle_tree_leaves, Some(tree_size))
             .merkle_root_and_path(l1_log_index);
-        Ok(Some(L2ToL1LogProof {
+        let proof = L2ToL1LogProof {
             proof,
             root,
             id: l1_log_index as u32,
-        }))
+        };
+        Ok(Some((proof, log.clone())))
     }
 
     pub async fn get_l2_to_l1_log_proof_impl(
@@ -329,7 +482,7 @@ impl ZksNamespace {
                 |log| log.tx_number_in_block == l1_batch_tx_index,
             )
             .await?;
-        Ok(log_proof)
+        Ok(log_proof.map(|(proof, _)| proof))
     }
 
     pub async fn get_l1_batch_number_impl(&self) -> Result<U64, Web3Error> {
@@ -412,6 +565,74 @@ impl ZksNamespace {
         Ok(tx_details)
     }
 
+    pub async fn get_internal_transfers_impl(
+        &self,
+        tx_hash: H256,
+    ) -> Result<Vec<InternalTransfer>, Web3Error> {
+        let mut storage = self.state.acquire_connection().await?;
+        Ok(storage
+            .transactions_web3_dal()
+            .get_internal_transfers(tx_hash)
+            .await
+            .map_err(DalError::generalize)?)
+    }
+
+    pub async fn get_nonce_info_impl(
+        &self,
+        address: Address,
+    ) -> Result<AccountNonceInfo, Web3Error> {
+        let mut connection = self.state.acquire_connection().await?;
+        let block_id = BlockId::Number(BlockNumber::Latest);
+        let block_number = self.state.resolve_block(&mut connection, block_id).await?;
+        let full_nonce = connection
+            .storage_web3_dal()
+            .get_address_historical_nonce(address, block_number)
+            .await
+            .map_err(DalError::generalize)?;
+        let (committed_nonce, _) = decompose_full_nonce(full_nonce);
+        let committed_nonce_u64 = u64::try_from(committed_nonce)
+            .map_err(|err| anyhow::anyhow!("nonce conversion failed: {err}"))?;
+
+        let pending_transactions = connection
+            .transactions_web3_dal()
+            .get_pending_transactions_by_initiator_account(address, committed_nonce_u64)
+            .await
+            .map_err(DalError::generalize)?;
+        // Mirrors nonce resolution in `eth_getTransactionCount` for the pending block.
+        let next_nonce = if let Some(nonce) = self
+            .state
+            .tx_sink()
+            .lookup_pending_nonce(address, committed_nonce_u64 as u32)
+            .await?
+        {
+            nonce.0.into()
+        } else {
+            connection
+                .transactions_web3_dal()
+                .next_nonce_by_initiator_account(address, committed_nonce_u64)
+                .await
+                .map_err(DalError::generalize)?
+        };
+
+        Ok(AccountNonceInfo {
+            committed_nonce,
+            next_nonce,
+            pending_transactions,
+        })
+    }
+
+    pub async fn get_rejected_transaction_impl(
+        &self,
+        hash: H256,
+    ) -> Result<Option<RejectedTransaction>, Web3Error> {
+        let mut storage = self.state.acquire_connection().await?;
+        Ok(storage
+            .rejected_transactions_dal()
+            .get_rejected_transaction(hash)
+            .await
+            .map_err(DalError::generalize)?)
+    }
+
     pub async fn get_l1_batch_details_impl(
         &self,
         batch_number: L1BatchNumber,
@@ -422,11 +643,20 @@ impl ZksNamespace {
             .ensure_not_pruned(batch_number, &mut storage)
             .await?;
 
-        Ok(storage
+        let Some(mut details) = storage
             .blocks_web3_dal()
             .get_l1_batch_details(batch_number)
             .await
-            .map_err(DalError::generalize)?)
+            .map_err(DalError::generalize)?
+        else {
+            return Ok(None);
+        };
+        details.custom_metadata = storage
+            .l1_batches_custom_metadata_dal()
+            .get_metadata(batch_number)
+            .await
+            .map_err(DalError::generalize)?;
+        Ok(Some(details))
     }
 
     pub async fn get_bytecode_by_hash_impl(
@@ -442,12 +672,55 @@ impl ZksNamespace {
     }
 
     #[tracing::instrument(skip(self))]
-    pub fn get_fee_params_impl(&self) -> FeeParams {
-        self.state
+    pub async fn get_fee_params_impl(&self) -> Result<ApiFeeParams, Web3Error> {
+        let params = self
+            .state
             .tx_sender
             .0
             .batch_fee_input_provider
-            .get_fee_model_params()
+            .get_fee_model_params();
+        let required_gas_per_pubdata = self.state.tx_sender.required_gas_per_pubdata().await?;
+        Ok(ApiFeeParams::new(params, Some(required_gas_per_pubdata)))
+    }
+
+    pub async fn get_fee_params_at_impl(
+        &self,
+        at: L2BlockOrL1Batch,
+    ) -> Result<Option<BatchFeeInputParams>, Web3Error> {
+        let mut storage = self.state.acquire_connection().await?;
+        let l1_batch_number = match at {
+            L2BlockOrL1Batch::L1Batch(number) => number,
+            L2BlockOrL1Batch::L2Block(number) => {
+                self.state
+                    .start_info
+                    .ensure_not_pruned(number, &mut storage)
+                    .await?;
+                let sealed_l2_block = storage
+                    .blocks_dal()
+                    .get_sealed_l2_block_number()
+                    .await
+                    .map_err(DalError::generalize)?;
+                if sealed_l2_block.map_or(true, |sealed| number > sealed) {
+                    return Ok(None);
+                }
+                // Fee params are persisted when a batch is opened, so they are available for the pending batch as well.
+                storage
+                    .storage_web3_dal()
+                    .resolve_l1_batch_number_of_l2_block(number)
+                    .await
+                    .map_err(DalError::generalize)?
+                    .expected_l1_batch()
+            }
+        };
+        self.state
+            .start_info
+            .ensure_not_pruned(l1_batch_number, &mut storage)
+            .await?;
+
+        Ok(storage
+            .blocks_dal()
+            .get_l1_batch_fee_params(l1_batch_number)
+            .await?)
     }
 
     pub async fn get_protocol_version_impl(
@@ -473,6 +746,29 @@ impl ZksNamespace {
         Ok(protocol_version)
     }
 
+    pub async fn get_protocol_version_history_impl(
+        &self,
+    ) -> Result<Vec<ProtocolVersionHistoryEntry>, Web3Error> {
+        let mut storage = self.state.acquire_connection().await?;
+        Ok(storage
+            .protocol_versions_web3_dal()
+            .get_protocol_version_history()
+            .await
+            .map_err(DalError::generalize)?)
+    }
+
+    pub async fn get_verification_keys_hashes_impl(
+        &self,
+        version_id: Option<u16>,
+    ) -> Result<Vec<VerificationKeysHashes>, Web3Error> {
+        let mut storage = self.state.acquire_connection().await?;
+        Ok(storage
+            .protocol_versions_web3_dal()
+            .get_verification_keys_hashes(version_id)
+            .await
+            .map_err(DalError::generalize)?)
+    }
+
     pub async fn get_proofs_impl(
         &self,
         address: Address,
@@ -553,19 +849,241 @@ impl ZksNamespace {
             .into_pubdata_independent())
     }
 
+    async fn average_priority_op_inclusion_delay(
+        storage: &mut Connection<'_, Core>,
+    ) -> Result<Option<Duration>, Web3Error> {
+        let delays = storage
+            .transactions_web3_dal()
+            .get_recent_priority_op_inclusion_delays(PRIORITY_OP_DELAY_SAMPLE_SIZE)
+            .await
+            .map_err(DalError::generalize)?;
+        if delays.is_empty() {
+            return Ok(None);
+        }
+        let total_delay: Duration = delays.iter().sum();
+        Ok(Some(total_delay / delays.len() as u32))
+    }
+
+    fn estimate_inclusion_time(received_at: DateTime<Utc>, delay: Duration) -> DateTime<Utc> {
+        let delay = chrono::Duration::from_std(delay).unwrap_or_else(|_| chrono::Duration::zero());
+        // If the operation has already spent more time in the queue than expected, it's expected to be included ASAP.
+        (received_at + delay).max(Utc::now())
+    }
+
+    pub async fn get_priority_queue_info_impl(
+        &self,
+        limit: Option<usize>,
+    ) -> Result<PriorityQueueInfo, Web3Error> {
+        let limit = limit
+            .unwrap_or(DEFAULT_PENDING_PRIORITY_OPS_LIMIT)
+            .min(self.state.api_config.req_entities_limit);
+        let mut storage = self.state.acquire_connection().await?;
+        let next_expected_priority_op_id = storage
+            .transactions_web3_dal()
+            .get_next_expected_priority_op_id()
+            .await
+            .map_err(DalError::generalize)?;
+        let (pending_ops_count, oldest_pending_op_received_at) = storage
+            .transactions_web3_dal()
+            .get_pending_priority_ops_stats()
+            .await
+            .map_err(DalError::generalize)?;
+        let mut pending_ops = storage
+            .transactions_web3_dal()
+            .get_pending_priority_ops(limit)
+            .await
+            .map_err(DalError::generalize)?;
+        let average_inclusion_delay =
+            Self::average_priority_op_inclusion_delay(&mut storage).await?;
+        drop(storage);
+
+        for (position, op) in pending_ops.iter_mut().enumerate() {
+            op.queue_position = Some(position as u64);
+            op.estimated_inclusion_at = average_inclusion_delay
+                .map(|delay| Self::estimate_inclusion_time(op.received_at, delay));
+        }
+        let has_gap = match (next_expected_priority_op_id, pending_ops.first()) {
+            (Some(next_id), Some(first_pending_op)) => first_pending_op.serial_id != next_id,
+            _ => false,
+        };
+
+        Ok(PriorityQueueInfo {
+            next_expected_priority_op_id,
+            pending_ops_count,
+            oldest_pending_op_received_at: oldest_pending_op_received_at
+                .map(|time| DateTime::<Utc>::from_naive_utc_and_offset(time, Utc)),
+            average_inclusion_delay_ms: average_inclusion_delay
+                .map(|delay| delay.as_millis() as u64),
+            has_gap,
+            pending_ops,
+        })
+    }
+
+    pub async fn get_priority_op_details_impl(
+        &self,
+        serial_id: PriorityOpId,
+    ) -> Result<Option<PriorityOpDetails>, Web3Error> {
+        let mut storage = self.state.acquire_connection().await?;
+        let Some(mut details) = storage
+            .transactions_web3_dal()
+            .get_priority_op_details(serial_id)
+            .await
+            .map_err(DalError::generalize)?
+        else {
+            return Ok(None);
+        };
+
+        if details.status == PriorityOpStatus::Pending {
+            let queue_position = storage
+                .transactions_web3_dal()
+                .get_pending_priority_ops_count_before(serial_id)
+                .await
+                .map_err(DalError::generalize)?;
+            let average_inclusion_delay =
+                Self::average_priority_op_inclusion_delay(&mut storage).await?;
+            details.queue_position = Some(queue_position);
+            details.estimated_inclusion_at = average_inclusion_delay
+                .map(|delay| Self::estimate_inclusion_time(details.received_at, delay));
+        }
+        Ok(Some(details))
+    }
+
+    pub async fn get_capabilities_impl(&self) -> Result<ApiCapabilities, Web3Error> {
+        let methods = self
+            .state
+            .rpc_method_names
+            .get()
+            .cloned()
+            .unwrap_or_default();
+        let namespaces = methods
+            .iter()
+            .filter_map(|method| method.split_once('_').map(|(namespace, _)| namespace))
+            .dedup() // `methods` are sorted, so all methods in a namespace are adjacent
+            .map(str::to_owned)
+            .collect();
+
+        let api_config = &self.state.api_config;
+        let features = ChainFeatures {
+            validium: api_config.l1_batch_commit_data_generator_mode
+                == L1BatchCommitmentMode::Validium,
+            custom_base_token: api_config
+                .base_token_address
+                .is_some_and(|address| address != SHARED_BRIDGE_ETHER_TOKEN_ADDRESS),
+            evm_emulator: api_config.evm_emulator_hash.is_some(),
+        };
+
+        let mut storage = self.state.acquire_connection().await?;
+        let protocol_version = storage
+            .protocol_versions_dal()
+            .latest_semantic_version()
+            .await
+            .map_err(DalError::generalize)?;
+
+        Ok(ApiCapabilities {
+            api_version: ZKS_API_VERSION.to_owned(),
+            namespaces,
+            methods,
+            features,
+            protocol_version,
+        })
+    }
+
     #[tracing::instrument(skip(self, tx_bytes))]
     pub async fn send_raw_transaction_with_detailed_output_impl(
         &self,
         tx_bytes: Bytes,
-    ) -> Result<(H256, VmExecutionResultAndLogs), Web3Error> {
+    ) -> Result<TransactionDetailedResult, Web3Error> {
         let (mut tx, hash) = self.state.parse_transaction_bytes(&tx_bytes.0)?;
+        let idempotency = self
+            .state
+            .idempotency_caches
+            .as_deref()
+            .zip(IdempotencyKey::current());
+        if let Some((caches, key)) = &idempotency {
+            if let Some(result) = caches.send_raw_transaction_detailed.get(key, hash)? {
+                return Ok(result);
+            }
+        }
         tx.set_input(tx_bytes.0, hash);
 
         let submit_result = self.state.tx_sender.submit_tx(tx).await;
-        submit_result.map(|result| (hash, result.1)).map_err(|err| {
+        let (_, execution_result) = submit_result.map_err(|err| {
             tracing::debug!("Send raw transaction error: {err}");
             API_METRICS.submit_tx_error[&err.prom_error_code()].inc();
-            err.into()
-        })
+            Web3Error::from(err)
+        })?;
+        let result = Self::detailed_result(hash, &execution_result);
+        if let Some((caches, key)) = idempotency {
+            caches
+                .send_raw_transaction_detailed
+                .insert(key, hash, result.clone());
+        }
+        Ok(result)
+    }
+
+    pub async fn send_raw_transaction_with_deadline_impl(
+        &self,
+        tx_bytes: Bytes,
+        deadline: TransactionDeadline,
+    ) -> Result<H256, Web3Error> {
+        let (mut tx, hash) = self.state.parse_transaction_bytes(&tx_bytes.0)?;
+        tx.set_input(tx_bytes.0, hash);
+
+        let submit_result = self
+            .state
+            .tx_sender
+            .submit_tx_with_deadline(tx, deadline)
+            .await;
+        submit_result.map_err(|err| {
+            tracing::debug!("Send raw transaction with deadline error: {err}");
+            API_METRICS.submit_tx_error[&err.prom_error_code()].inc();
+            Web3Error::from(err)
+        })?;
+        Ok(hash)
+    }
+
+    pub async fn get_transaction_expiry_impl(
+        &self,
+        hash: H256,
+    ) -> Result<Option<TransactionExpiry>, Web3Error> {
+        let mut storage = self.state.acquire_connection().await?;
+        Ok(storage
+            .transaction_deadlines_dal()
+            .get_transaction_expiry(hash)
+            .await
+            .map_err(DalError::generalize)?)
+    }
+
+    pub async fn get_forwarded_transaction_impl(
+        &self,
+        hash: H256,
+    ) -> Result<Option<ForwardedTransaction>, Web3Error> {
+        self.state.tx_sink().lookup_forwarded_tx(hash).await
+    }
+
+    fn detailed_result(
+        hash: H256,
+        execution_result: &VmExecutionResultAndLogs,
+    ) -> TransactionDetailedResult {
+        TransactionDetailedResult {
+            transaction_hash: hash,
+            storage_logs: execution_result
+                .logs
+                .storage_logs
+                .iter()
+                .filter(|log| log.log_type != StorageLogQueryType::Read)
+                .map(ApiStorageLog::from)
+                .collect(),
+            events: execution_result
+                .logs
+                .events
+                .iter()
+                .map(|event| {
+                    let mut log = Log::from(event);
+                    log.transaction_hash = Some(hash);
+                    log
+                })
+                .collect(),
+        }
     }
 }
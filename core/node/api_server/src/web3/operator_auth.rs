@@ -0,0 +1,123 @@
+//! Authentication of operators accessing the `admin` namespace.
+//!
+//! Operators authenticate with an API key passed in the `Authorization: Bearer <API key>` header.
+//! The server only stores keccak256 hashes of API keys mapped to operator IDs.
+
+use std::{collections::HashMap, fmt, future::Future, sync::Arc};
+
+use anyhow::Context as _;
+use zksync_types::{web3::keccak256, H256};
+
+tokio::task_local! {
+    static CURRENT_OPERATOR: OperatorId;
+}
+
+/// ID of an authenticated operator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OperatorId(Arc<str>);
+
+impl OperatorId {
+    fn new(id: &str) -> Self {
+        Self(id.into())
+    }
+
+    /// Runs the provided future with this operator as the authenticated one.
+    pub async fn scope<F: Future>(self, fut: F) -> F::Output {
+        CURRENT_OPERATOR.scope(self, fut).await
+    }
+
+    /// Returns the operator authenticated for the current request, if any.
+    pub fn current() -> Option<Self> {
+        CURRENT_OPERATOR.try_with(Clone::clone).ok()
+    }
+}
+
+impl fmt::Display for OperatorId {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.0)
+    }
+}
+
+/// Authenticates operators by their API keys.
+#[derive(Debug, Default)]
+pub(crate) struct OperatorAuthenticator {
+    /// Operator IDs keyed by API key hashes.
+    operators: HashMap<H256, OperatorId>,
+}
+
+impl OperatorAuthenticator {
+    /// Parses authorized operators from the `<operator_id>:<API key hash>` format used in the config.
+    pub fn new(admin_api_keys: &[String]) -> anyhow::Result<Self> {
+        let mut operators = HashMap::with_capacity(admin_api_keys.len());
+        for entry in admin_api_keys {
+            let (operator_id, key_hash) = entry
+                .rsplit_once(':')
+                .with_context(|| format!("admin API key entry `{entry}` has no API key hash"))?;
+            anyhow::ensure!(!operator_id.is_empty(), "empty operator ID in `{entry}`");
+            let key_hash: H256 = key_hash
+                .parse()
+                .with_context(|| format!("invalid API key hash for operator `{operator_id}`"))?;
+            if let Some(prev_id) = operators.insert(key_hash, OperatorId::new(operator_id)) {
+                anyhow::bail!("operators `{prev_id}` and `{operator_id}` have the same API key");
+            }
+        }
+        Ok(Self { operators })
+    }
+
+    /// Returns the operator authenticated by the `Authorization` header, or `None` if the header is missing
+    /// or contains an unknown API key.
+    pub fn authenticate(&self, headers: &http::HeaderMap) -> Option<OperatorId> {
+        let api_key = headers
+            .get(http::header::AUTHORIZATION)?
+            .to_str()
+            .ok()?
+            .strip_prefix("Bearer ")?;
+        let key_hash = H256(keccak256(api_key.trim().as_bytes()));
+        self.operators.get(&key_hash).cloned()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn admin_api_key(operator_id: &str, api_key: &str) -> String {
+        format!("{operator_id}:{:?}", H256(keccak256(api_key.as_bytes())))
+    }
+
+    fn headers(api_key: &str) -> http::HeaderMap {
+        let mut headers = http::HeaderMap::new();
+        let value = format!("Bearer {api_key}").parse().unwrap();
+        headers.insert(http::header::AUTHORIZATION, value);
+        headers
+    }
+
+    #[test]
+    fn authenticating_operators() {
+        let authenticator = OperatorAuthenticator::new(&[
+            admin_api_key("alice", "key-1"),
+            admin_api_key("bob", "key-2"),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            authenticator.authenticate(&headers("key-1")),
+            Some(OperatorId::new("alice"))
+        );
+        assert_eq!(
+            authenticator.authenticate(&headers("key-2")),
+            Some(OperatorId::new("bob"))
+        );
+        assert_eq!(authenticator.authenticate(&headers("key-3")), None);
+        assert_eq!(authenticator.authenticate(&http::HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn invalid_admin_api_keys_are_rejected() {
+        OperatorAuthenticator::new(&["alice".to_owned()]).unwrap_err();
+        OperatorAuthenticator::new(&[":0x01".to_owned()]).unwrap_err();
+        OperatorAuthenticator::new(&["alice:0x01".to_owned()]).unwrap_err();
+        OperatorAuthenticator::new(&[admin_api_key("alice", "key"), admin_api_key("bob", "key")])
+            .unwrap_err();
+    }
+}
@@ -1,5 +1,7 @@
 use std::{
+    collections::HashSet,
     future::Future,
+    num::NonZeroUsize,
     sync::{
         atomic::{AtomicU32, Ordering},
         Arc,
@@ -10,10 +12,14 @@ use std::{
 use anyhow::Context as _;
 use futures::TryFutureExt;
 use lru::LruCache;
+use once_cell::sync::OnceCell;
 use tokio::sync::{watch, Mutex};
 use vise::GaugeGuard;
 use zksync_config::{
-    configs::{api::Web3JsonRpcConfig, ContractsConfig},
+    configs::{
+        api::{GethCompatibilityShim, MaxResponseSize, Web3JsonRpcConfig},
+        ContractsConfig,
+    },
     GenesisConfig,
 };
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal, DalError};
@@ -27,6 +33,7 @@ use zksync_web3_decl::{error::Web3Error, types::Filter};
 
 use super::{
     backend_jsonrpsee::MethodTracer,
+    idempotency::IdempotencyCaches,
     mempool_cache::MempoolCache,
     metrics::{FilterType, FILTER_METRICS},
     TypedFilter,
@@ -70,6 +77,16 @@ impl From<BlockArgsError> for Web3Error {
     }
 }
 
+/// Maps an error of an expensive read query. Statement timeouts are reported to the client, while other errors
+/// are considered internal.
+pub(super) fn map_read_query_error(err: DalError) -> Web3Error {
+    if err.is_statement_timeout() {
+        Web3Error::QueryTimeout
+    } else {
+        err.generalize().into()
+    }
+}
+
 impl BlockStartInfo {
     pub(super) async fn ensure_not_pruned(
         &self,
@@ -100,6 +117,7 @@ pub struct InternalApiConfig {
     pub max_tx_size: usize,
     pub estimate_gas_scale_factor: f64,
     pub estimate_gas_acceptable_overestimation: u32,
+    pub estimate_fee_batch_max_size: usize,
     pub bridge_addresses: api::BridgeAddresses,
     pub bridgehub_proxy_addr: Option<Address>,
     pub state_transition_proxy_addr: Option<Address>,
@@ -112,6 +130,22 @@ pub struct InternalApiConfig {
     pub filters_disabled: bool,
     pub dummy_verifier: bool,
     pub l1_batch_commit_data_generator_mode: L1BatchCommitmentMode,
+    /// Hash of the EVM emulator bytecode. `None` if the EVM emulator is disabled for the chain.
+    pub evm_emulator_hash: Option<H256>,
+    pub geth_compatibility: HashSet<GethCompatibilityShim>,
+    /// HTTP header identifying API clients for fair VM scheduling. `None` if fair scheduling is disabled.
+    pub vm_client_id_header: Option<String>,
+    /// Number of trusted proxies appending hops to `vm_client_id_header`.
+    pub vm_client_id_trusted_proxies: usize,
+    /// Statement timeout for expensive read queries (e.g., for `eth_getLogs`).
+    pub query_statement_timeout: Option<Duration>,
+    /// Maximum number of remembered idempotency keys for transaction submissions. `None` if idempotency keys
+    /// are not supported.
+    pub idempotency_keys_cache_size: Option<NonZeroUsize>,
+    pub idempotency_key_ttl: Duration,
+    /// Operators authorized to access the `admin` namespace in the `<operator_id>:<API key hash>` format.
+    /// If empty, the namespace is accessible without authentication.
+    pub admin_api_keys: Vec<String>,
 }
 
 impl InternalApiConfig {
@@ -127,6 +161,7 @@ impl InternalApiConfig {
             estimate_gas_scale_factor: web3_config.estimate_gas_scale_factor,
             estimate_gas_acceptable_overestimation: web3_config
                 .estimate_gas_acceptable_overestimation,
+            estimate_fee_batch_max_size: web3_config.estimate_fee_batch_max_size(),
             bridge_addresses: api::BridgeAddresses {
                 l1_erc20_default_bridge: contracts_config.l1_erc20_bridge_proxy_addr,
                 l2_erc20_default_bridge: contracts_config.l2_erc20_bridge_addr,
@@ -155,6 +190,18 @@ impl InternalApiConfig {
             filters_disabled: web3_config.filters_disabled,
             dummy_verifier: genesis_config.dummy_verifier,
             l1_batch_commit_data_generator_mode: genesis_config.l1_batch_commit_data_generator_mode,
+            evm_emulator_hash: genesis_config.evm_emulator_hash,
+            geth_compatibility: web3_config.geth_compatibility.iter().copied().collect(),
+            vm_client_id_header: web3_config
+                .vm_concurrency_limit_per_client
+                .map(|_| web3_config.vm_client_id_header().to_owned()),
+            vm_client_id_trusted_proxies: web3_config.vm_client_id_trusted_proxies(),
+            query_statement_timeout: web3_config.query_statement_timeout(),
+            idempotency_keys_cache_size: web3_config
+                .idempotency_keys_cache_size
+                .and_then(NonZeroUsize::new),
+            idempotency_key_ttl: web3_config.idempotency_key_ttl(),
+            admin_api_keys: web3_config.admin_api_keys.clone(),
         }
     }
 }
@@ -247,6 +294,52 @@ pub(crate) struct RpcState {
     pub(super) start_info: BlockStartInfo,
     pub(super) mempool_cache: Option<MempoolCache>,
     pub(super) last_sealed_l2_block: SealedL2BlockNumber,
+    /// Names of all RPC methods served by the API server. Set once the RPC module is built.
+    pub(super) rpc_method_names: Arc<OnceCell<Vec<String>>>,
+    /// Response size limits. `None` if response sizes are not limited.
+    pub(super) response_size_limit: Option<Arc<MaxResponseSize>>,
+    /// Caches of transaction submission results keyed by idempotency keys. `None` if idempotency keys are not supported.
+    pub(super) idempotency_caches: Option<Arc<IdempotencyCaches>>,
+}
+
+/// Upper bound for the serialized size of an [`api::Log`] without topics, data and log type, including
+/// the separator from the next log in the response.
+const MAX_LOG_SIZE_OVERHEAD: usize = 563;
+/// Upper bound for the serialized size of a log topic (a quoted hex-encoded 32-byte value), including the separator.
+const MAX_LOG_TOPIC_SIZE: usize = 69;
+/// Lower bound for the serialized size of an [`api::Transaction`] without calldata.
+const MIN_TRANSACTION_SIZE: usize = 232;
+
+/// Returns an upper bound for the serialized size of `logs`. The bound is computed from the number of logs and topics
+/// and the length of their data, without serializing logs.
+pub(crate) fn max_serialized_logs_size(logs: &[api::Log]) -> usize {
+    let logs_size = logs.iter().fold(0_usize, |acc, log| {
+        // Each char of the log type takes at most 6 bytes when escaped, plus 2 bytes for quotes.
+        let log_type_size = log.log_type.as_ref().map_or(0, |ty| ty.len() * 6 + 2);
+        acc.saturating_add(MAX_LOG_SIZE_OVERHEAD)
+            .saturating_add(log.topics.len().saturating_mul(MAX_LOG_TOPIC_SIZE))
+            .saturating_add(log.data.0.len().saturating_mul(2)) // each byte is encoded as 2 hex chars
+            .saturating_add(log_type_size)
+    });
+    logs_size.saturating_add(2) // enclosing brackets
+}
+
+/// Returns a lower bound for the serialized size of `transactions`. The bound is computed from the number
+/// of transactions and the length of their calldata, without serializing transactions.
+pub(crate) fn min_serialized_transactions_size(transactions: &[api::Transaction]) -> usize {
+    transactions.iter().fold(0_usize, |acc, tx| {
+        acc.saturating_add(MIN_TRANSACTION_SIZE)
+            .saturating_add(tx.input.0.len().saturating_mul(2)) // each byte is encoded as 2 hex chars
+    })
+}
+
+/// Checks that a response with the specified estimated serialized size (in bytes) fits into the `limit`.
+fn check_response_size(estimated_size: usize, limit: usize) -> Result<(), Web3Error> {
+    if estimated_size > limit {
+        Err(Web3Error::ResponseTooLarge(limit))
+    } else {
+        Ok(())
+    }
 }
 
 impl RpcState {
@@ -272,6 +365,30 @@ impl RpcState {
         self.tx_sender.0.tx_sink.as_ref()
     }
 
+    fn response_size_limit(&self) -> Option<usize> {
+        let limits = self.response_size_limit.as_ref()?;
+        let method_limit = self
+            .current_method
+            .method_name()
+            .and_then(|name| limits.overrides.get(name));
+        Some(method_limit.unwrap_or(limits.global))
+    }
+
+    /// Checks that a response with the specified estimated serialized size (in bytes) fits into the response size limit
+    /// for the current method. The size should be estimated from the number of returned entities and the length
+    /// of their binary payloads (e.g., using [`max_serialized_logs_size()`]), so that the response isn't serialized twice.
+    ///
+    /// This allows rejecting oversized responses before they are fully loaded or before any state is changed
+    /// (e.g., a filter is advanced). The exact limit is enforced by the server when serializing the response
+    /// into the response body; serialization is aborted as soon as the limit is exceeded. Thus, for stateful methods,
+    /// the estimate must be an upper bound for the response size.
+    pub(crate) fn ensure_response_may_fit(&self, estimated_size: usize) -> Result<(), Web3Error> {
+        match self.response_size_limit() {
+            Some(limit) => check_response_size(estimated_size, limit),
+            None => Ok(()),
+        }
+    }
+
     /// Acquires a DB connection mapping possible errors.
     // `track_caller` is necessary to correctly record call location. `async fn`s don't support it yet,
     // thus manual de-sugaring.
@@ -556,4 +673,88 @@ mod tests {
         assert!(filters.0.contains(&idx2));
         assert!(!filters.0.contains(&idx3));
     }
+
+    #[test]
+    fn checking_response_size() {
+        use assert_matches::assert_matches;
+
+        use super::*;
+
+        check_response_size(0, 0).unwrap();
+        check_response_size(1_024, 1_024).unwrap();
+        let err = check_response_size(1_025, 1_024).unwrap_err();
+        assert_matches!(err, Web3Error::ResponseTooLarge(1_024));
+        let err = check_response_size(usize::MAX, 1_024).unwrap_err();
+        assert_matches!(err, Web3Error::ResponseTooLarge(1_024));
+    }
+
+    #[test]
+    fn estimating_serialized_logs_size() {
+        use zksync_types::web3::Bytes;
+
+        use super::*;
+
+        assert_eq!(max_serialized_logs_size(&[]), 2);
+
+        // Log with all fields set to values with the longest serialization.
+        let log = api::Log {
+            address: Address::repeat_byte(0xff),
+            topics: vec![H256::repeat_byte(0xff); 4],
+            data: Bytes(vec![0xaa; 100]),
+            block_hash: Some(H256::repeat_byte(0xff)),
+            block_number: Some(U64::MAX),
+            l1_batch_number: Some(U64::MAX),
+            transaction_hash: Some(H256::repeat_byte(0xff)),
+            transaction_index: Some(U64::MAX),
+            log_index: Some(U256::MAX),
+            transaction_log_index: Some(U256::MAX),
+            log_type: None,
+            removed: Some(false),
+        };
+        for logs in [vec![log.clone()], vec![log.clone(); 10]] {
+            let actual_size = serde_json::to_vec(&logs).unwrap().len();
+            let estimated_size = max_serialized_logs_size(&logs);
+            assert!(
+                estimated_size >= actual_size,
+                "{estimated_size} < {actual_size}"
+            );
+            // The estimate is tight: it only overcounts the last separator and the separator of the first topic.
+            assert!(
+                estimated_size - actual_size <= 2 * logs.len(),
+                "{estimated_size} vs {actual_size}"
+            );
+        }
+
+        let log = api::Log {
+            log_type: Some("\"removed\"".to_owned()),
+            ..log
+        };
+        let logs = [log];
+        let actual_size = serde_json::to_vec(&logs).unwrap().len();
+        assert!(max_serialized_logs_size(&logs) >= actual_size);
+    }
+
+    #[test]
+    fn estimating_serialized_transactions_size() {
+        use zksync_types::web3::Bytes;
+
+        use super::*;
+
+        let tx = api::Transaction::default();
+        assert_eq!(serde_json::to_vec(&tx).unwrap().len(), MIN_TRANSACTION_SIZE);
+
+        let tx = api::Transaction {
+            from: Some(Address::repeat_byte(1)),
+            to: Some(Address::repeat_byte(2)),
+            input: Bytes(vec![0xaa; 100]),
+            ..api::Transaction::default()
+        };
+        let transactions = vec![tx; 10];
+        let actual_size = serde_json::to_vec(&transactions).unwrap().len();
+        let estimated_size = min_serialized_transactions_size(&transactions);
+        assert!(
+            estimated_size <= actual_size,
+            "{estimated_size} > {actual_size}"
+        );
+    }
 }
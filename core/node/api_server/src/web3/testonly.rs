@@ -101,6 +101,25 @@ pub async fn spawn_http_server(
     tx_executor: MockTransactionExecutor,
     method_tracer: Arc<MethodTracer>,
     stop_receiver: watch::Receiver<bool>,
+) -> ApiServerHandles {
+    spawn_http_server_with_response_size_limit(
+        api_config,
+        pool,
+        tx_executor,
+        method_tracer,
+        None,
+        stop_receiver,
+    )
+    .await
+}
+
+pub(crate) async fn spawn_http_server_with_response_size_limit(
+    api_config: InternalApiConfig,
+    pool: ConnectionPool<Core>,
+    tx_executor: MockTransactionExecutor,
+    method_tracer: Arc<MethodTracer>,
+    response_size_limit: Option<MaxResponseSize>,
+    stop_receiver: watch::Receiver<bool>,
 ) -> ApiServerHandles {
     spawn_server(
         ApiTransportLabel::Http,
@@ -109,6 +128,7 @@ pub async fn spawn_http_server(
         None,
         tx_executor,
         method_tracer,
+        response_size_limit,
         stop_receiver,
     )
     .await
@@ -128,6 +148,7 @@ pub async fn spawn_ws_server(
         websocket_requests_per_minute_limit,
         MockTransactionExecutor::default(),
         Arc::default(),
+        None,
         stop_receiver,
     )
     .await
@@ -140,6 +161,7 @@ async fn spawn_server(
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
     tx_executor: MockTransactionExecutor,
     method_tracer: Arc<MethodTracer>,
+    response_size_limit: Option<MaxResponseSize>,
     stop_receiver: watch::Receiver<bool>,
 ) -> (ApiServerHandles, mpsc::UnboundedReceiver<PubSubEvent>) {
     let (tx_sender, vm_barrier) =
@@ -148,7 +170,12 @@ async fn spawn_server(
 
     let mut namespaces = Namespace::DEFAULT.to_vec();
     namespaces.extend([Namespace::Debug, Namespace::Snapshots]);
+    // The admin namespace cannot be served without authentication.
+    if !api_config.admin_api_keys.is_empty() {
+        namespaces.push(Namespace::Admin);
+    }
 
+    let master_pool = pool.clone();
     let server_builder = match transport {
         ApiTransportLabel::Http => ApiBuilder::jsonrpsee_backend(api_config, pool).http(0),
         ApiTransportLabel::Ws => {
@@ -162,12 +189,17 @@ async fn spawn_server(
             builder
         }
     };
+    let mut server_builder = server_builder;
+    if let Some(limit) = response_size_limit {
+        server_builder = server_builder.with_response_body_size_limit(limit);
+    }
     let server_handles = server_builder
         .with_polling_interval(POLL_INTERVAL)
         .with_tx_sender(tx_sender)
         .with_vm_barrier(vm_barrier)
         .with_pub_sub_events(pub_sub_events_sender)
         .with_method_tracer(method_tracer)
+        .with_master_pool(master_pool)
         .enable_api_namespaces(namespaces)
         .build()
         .expect("Unable to build API server")
@@ -3,7 +3,7 @@
 use zksync_types::{tx::TransactionExecutionResult, vm_trace::Call, BOOTLOADER_ADDRESS};
 use zksync_web3_decl::{
     client::{DynClient, L2},
-    namespaces::DebugNamespaceClient,
+    namespaces::{DebugNamespaceClient, ZksNamespaceClient},
 };
 
 use super::*;
@@ -209,6 +209,43 @@ impl HttpTest for TraceTransactionTest {
         assert_eq!(result.gas, tx_results[0].transaction.gas_limit());
         assert_eq!(result.calls, expected_calls);
 
+        for (offset, limit) in [(0, 1), (1, 1), (1, 10), (2, 1)] {
+            let tracer_config = api::CallTracerConfig {
+                top_calls_offset: offset,
+                top_calls_limit: Some(limit),
+                ..api::CallTracerConfig::default()
+            };
+            let options = api::TracerConfig {
+                tracer: api::SupportedTracers::CallTracer,
+                tracer_config,
+            };
+            let result = client
+                .trace_transaction(tx_results[0].hash, Some(options))
+                .await?
+                .context("no transaction traces")?;
+            let expected_page: Vec<_> = expected_calls
+                .iter()
+                .skip(offset)
+                .take(limit)
+                .cloned()
+                .collect();
+            assert_eq!(result.calls, expected_page);
+        }
+
+        let tracer_config = api::CallTracerConfig {
+            max_frames: Some(2),
+            ..api::CallTracerConfig::default()
+        };
+        let options = api::TracerConfig {
+            tracer: api::SupportedTracers::CallTracer,
+            tracer_config,
+        };
+        let result = client
+            .trace_transaction(tx_results[0].hash, Some(options))
+            .await?
+            .context("no transaction traces")?;
+        assert_eq!(result.calls, expected_calls[..1]);
+
         Ok(())
     }
 }
@@ -218,6 +255,42 @@ async fn tracing_transaction() {
     test_http_server(TraceTransactionTest).await;
 }
 
+#[derive(Debug)]
+struct InternalTransfersTest;
+
+#[async_trait]
+impl HttpTest for InternalTransfersTest {
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let tx_results = [execute_l2_transaction_with_traces(0)];
+        let mut storage = pool.connection().await?;
+        store_l2_block(&mut storage, L2BlockNumber(1), &tx_results).await?;
+        drop(storage);
+
+        let transfers = client.get_internal_transfers(tx_results[0].hash).await?;
+        // Only the second call in the trace transfers value.
+        let expected_call = &tx_results[0].call_traces[1];
+        let expected_transfer = api::InternalTransfer {
+            from: expected_call.from,
+            to: expected_call.to,
+            value: expected_call.value,
+        };
+        assert_eq!(transfers, [expected_transfer]);
+
+        let transfers = client.get_internal_transfers(H256::zero()).await?;
+        assert!(transfers.is_empty());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_internal_transfers() {
+    test_http_server(InternalTransfersTest).await;
+}
+
 #[derive(Debug)]
 struct TraceBlockTestWithSnapshotRecovery;
 
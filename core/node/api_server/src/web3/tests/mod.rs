@@ -11,24 +11,32 @@ use multivm::zk_evm_latest::ethereum_types::U256;
 use tokio::sync::watch;
 use zksync_config::{
     configs::{
-        api::Web3JsonRpcConfig,
+        api::{GethCompatibilityShim, Web3JsonRpcConfig},
         chain::{NetworkConfig, StateKeeperConfig},
         ContractsConfig,
     },
     GenesisConfig,
 };
-use zksync_dal::{transactions_dal::L2TxSubmissionResult, Connection, ConnectionPool, CoreDal};
+use zksync_dal::{
+    transactions_dal::{CallTraceFormat, L2TxSubmissionResult},
+    Connection, ConnectionPool, CoreDal,
+};
 use zksync_node_genesis::{insert_genesis_batch, mock_genesis_config, GenesisParams};
 use zksync_node_test_utils::{
     create_l1_batch, create_l1_batch_metadata, create_l2_block, create_l2_transaction,
     l1_batch_metadata_to_commitment_artifacts, prepare_recovery_snapshot,
 };
+use zksync_system_constants::EMPTY_TRIE_ROOT_HASH;
 use zksync_types::{
+    aggregated_operations::AggregatedActionType,
     api,
     block::L2BlockHeader,
     fee::TransactionExecutionMetrics,
+    fee_model::{BatchFeeInputParams, FeeParams},
     get_nonce_key,
     l2::L2Tx,
+    protocol_upgrade::ProtocolVersion,
+    protocol_version::ProtocolSemanticVersion,
     storage::get_code_key,
     tokens::{TokenInfo, TokenMetadata},
     tx::{
@@ -36,6 +44,7 @@ use zksync_types::{
         TransactionExecutionResult,
     },
     utils::{storage_key_for_eth_balance, storage_key_for_standard_token_balance},
+    web3::keccak256,
     AccountTreeId, Address, L1BatchNumber, Nonce, ProtocolVersionId, StorageKey, StorageLog,
     VmEvent, H256, U64,
 };
@@ -51,13 +60,22 @@ use zksync_web3_decl::{
             ErrorObjectOwned,
         },
     },
-    namespaces::{EnNamespaceClient, EthNamespaceClient, ZksNamespaceClient},
+    namespaces::{
+        AdminNamespaceClient, EnNamespaceClient, EthNamespaceClient, NetNamespaceClient,
+        Web3NamespaceClient, ZksNamespaceClient,
+    },
 };
 
 use super::*;
 use crate::{
     execution_sandbox::testonly::MockTransactionExecutor,
-    web3::testonly::{spawn_http_server, spawn_ws_server},
+    web3::{
+        operator_auth::tests::admin_api_key,
+        testonly::{
+            create_test_tx_sender, spawn_http_server, spawn_http_server_with_response_size_limit,
+            spawn_ws_server,
+        },
+    },
 };
 
 mod debug;
@@ -151,6 +169,26 @@ trait HttpTest: Send + Sync {
     fn filters_disabled(&self) -> bool {
         false
     }
+
+    /// Overrides the `geth_compatibility` configuration parameter for HTTP server startup
+    fn geth_compatibility(&self) -> HashSet<GethCompatibilityShim> {
+        HashSet::new()
+    }
+
+    /// Overrides the EVM emulator hash for HTTP server startup
+    fn evm_emulator_hash(&self) -> Option<H256> {
+        None
+    }
+
+    /// Sets response size limits for HTTP server startup
+    fn response_size_limit(&self) -> Option<MaxResponseSize> {
+        None
+    }
+
+    /// Overrides the `req_entities_limit` configuration parameter for HTTP server startup
+    fn req_entities_limit(&self) -> Option<usize> {
+        None
+    }
 }
 
 /// Storage initialization strategy.
@@ -212,6 +250,10 @@ impl StorageInitialization {
     }
 }
 
+/// Operator authenticated by HTTP test clients.
+const TEST_OPERATOR: &str = "test-operator";
+const TEST_ADMIN_API_KEY: &str = "test-admin-key";
+
 async fn test_http_server(test: impl HttpTest) {
     let pool = ConnectionPool::<Core>::test_pool().await;
     let network_config = NetworkConfig::for_tests();
@@ -228,19 +270,30 @@ async fn test_http_server(test: impl HttpTest) {
     let genesis = GenesisConfig::for_tests();
     let mut api_config = InternalApiConfig::new(&web3_config, &contracts_config, &genesis);
     api_config.filters_disabled = test.filters_disabled();
-    let mut server_handles = spawn_http_server(
+    api_config.geth_compatibility = test.geth_compatibility();
+    api_config.evm_emulator_hash = test.evm_emulator_hash();
+    if let Some(req_entities_limit) = test.req_entities_limit() {
+        api_config.req_entities_limit = req_entities_limit;
+    }
+    api_config.admin_api_keys = vec![admin_api_key(TEST_OPERATOR, TEST_ADMIN_API_KEY)];
+    let mut server_handles = spawn_http_server_with_response_size_limit(
         api_config,
         pool.clone(),
         test.transaction_executor(),
         test.method_tracer(),
+        test.response_size_limit(),
         stop_receiver,
     )
     .await;
 
     let local_addr = server_handles.wait_until_ready().await;
-    let client = Client::http(format!("http://{local_addr}/").parse().unwrap())
-        .unwrap()
-        .build();
+    let mut headers = http::HeaderMap::new();
+    let auth_header = format!("Bearer {TEST_ADMIN_API_KEY}").parse().unwrap();
+    headers.insert(http::header::AUTHORIZATION, auth_header);
+    let client =
+        Client::http_with_headers(format!("http://{local_addr}/").parse().unwrap(), headers)
+            .unwrap()
+            .build();
     test.test(&client, &pool).await.unwrap();
 
     stop_sender.send_replace(true);
@@ -308,6 +361,7 @@ async fn store_l2_block(
             transaction_results,
             1.into(),
             ProtocolVersionId::latest(),
+            CallTraceFormat::Legacy,
             false,
         )
         .await?;
@@ -584,6 +638,16 @@ async fn l1_batch_methods_with_snapshot_recovery() {
 #[derive(Debug)]
 struct StorageAccessWithSnapshotRecovery;
 
+impl StorageAccessWithSnapshotRecovery {
+    const EVM_CODE: &'static [u8] = &[0x60, 0x80, 0x60, 0x40, 0x52];
+
+    fn evm_code_hash() -> H256 {
+        let mut hash = H256::repeat_byte(5);
+        hash.0[..4].copy_from_slice(&[2, 0, 0, Self::EVM_CODE.len() as u8]);
+        hash
+    }
+}
+
 #[async_trait]
 impl HttpTest for StorageAccessWithSnapshotRecovery {
     fn storage_initialization(&self) -> StorageInitialization {
@@ -591,15 +655,24 @@ impl HttpTest for StorageAccessWithSnapshotRecovery {
         let code_key = get_code_key(&address);
         let code_hash = H256::repeat_byte(2);
         let balance_key = storage_key_for_eth_balance(&address);
+        let evm_address = Address::repeat_byte(3);
+        let evm_code_hash = Self::evm_code_hash();
+        let mut padded_evm_code = Self::EVM_CODE.to_vec();
+        padded_evm_code.resize(32, 0);
         let logs = vec![
             StorageLog::new_write_log(code_key, code_hash),
+            StorageLog::new_write_log(get_code_key(&evm_address), evm_code_hash),
             StorageLog::new_write_log(balance_key, H256::from_low_u64_be(123)),
             StorageLog::new_write_log(
                 StorageKey::new(AccountTreeId::new(address), H256::zero()),
                 H256::repeat_byte(0xff),
             ),
         ];
-        let factory_deps = [(code_hash, b"code".to_vec())].into();
+        let factory_deps = [
+            (code_hash, b"code".to_vec()),
+            (evm_code_hash, padded_evm_code),
+        ]
+        .into();
         StorageInitialization::Recovery { logs, factory_deps }
     }
 
@@ -627,6 +700,10 @@ impl HttpTest for StorageAccessWithSnapshotRecovery {
             let number = api::BlockIdVariant::BlockNumber(number);
             let code = client.get_code(address, Some(number)).await?;
             assert_eq!(code.0, b"code");
+            let evm_code = client
+                .get_code(Address::repeat_byte(3), Some(number))
+                .await?;
+            assert_eq!(evm_code.0, Self::EVM_CODE);
             let balance = client.get_balance(address, Some(number)).await?;
             assert_eq!(balance, 123.into());
             let storage_value = client
@@ -643,6 +720,125 @@ async fn storage_access_with_snapshot_recovery() {
     test_http_server(StorageAccessWithSnapshotRecovery).await;
 }
 
+#[derive(Debug)]
+struct OversizedLogsTest;
+
+impl OversizedLogsTest {
+    const RESPONSE_SIZE_LIMIT: usize = 1_024;
+}
+
+#[async_trait]
+impl HttpTest for OversizedLogsTest {
+    fn response_size_limit(&self) -> Option<MaxResponseSize> {
+        Some(MaxResponseSize {
+            global: Self::RESPONSE_SIZE_LIMIT,
+            overrides: MaxResponseSizeOverrides::empty(),
+        })
+    }
+
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let filter_id = client.new_filter(Filter::default()).await?;
+        let mut storage = pool.connection().await?;
+        let (_, events) = store_events(&mut storage, 1, 0).await?;
+        drop(storage);
+
+        // A single log fits into the limit.
+        let filter = Filter {
+            from_block: Some(api::BlockNumber::Number(1.into())),
+            to_block: Some(api::BlockNumber::Number(1.into())),
+            topics: Some(vec![None, Some(H256::repeat_byte(111).into())]),
+            ..Filter::default()
+        };
+        let logs = client.get_logs(filter).await?;
+        assert_logs_match(&logs, &[&events[3]]);
+
+        // All logs don't fit. The response size is estimated from the number of logs, so the response is rejected
+        // even though the log data alone would fit.
+        let data_len: usize = events.iter().map(|event| event.value.len()).sum();
+        assert!(data_len * 2 < Self::RESPONSE_SIZE_LIMIT);
+        let filter = Filter {
+            from_block: Some(api::BlockNumber::Number(1.into())),
+            to_block: Some(api::BlockNumber::Number(1.into())),
+            ..Filter::default()
+        };
+        let err = client.get_logs(filter).await.unwrap_err();
+        assert_matches!(
+            err,
+            ClientError::Call(err) if err.code() == OVERSIZED_RESPONSE_CODE
+                && err.message().contains("exceeds the size limit of 1024 bytes")
+        );
+
+        // The filter is retained after an oversized response, so it can be polled again.
+        for _ in 0..2 {
+            let err = client.get_filter_changes(filter_id).await.unwrap_err();
+            assert_matches!(
+                err,
+                ClientError::Call(err) if err.code() == OVERSIZED_RESPONSE_CODE
+            );
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn oversized_logs_are_rejected() {
+    test_http_server(OversizedLogsTest).await;
+}
+
+#[derive(Debug)]
+struct SingleBlockLogsLimitTest;
+
+#[async_trait]
+impl HttpTest for SingleBlockLogsLimitTest {
+    fn req_entities_limit(&self) -> Option<usize> {
+        Some(3)
+    }
+
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let mut storage = pool.connection().await?;
+        let (_, events) = store_events(&mut storage, 1, 0).await?;
+        drop(storage);
+        assert!(events.len() > 3);
+
+        let filter = Filter {
+            from_block: Some(api::BlockNumber::Number(1.into())),
+            to_block: Some(api::BlockNumber::Number(1.into())),
+            address: Some(Address::repeat_byte(23).into()),
+            ..Filter::default()
+        };
+        let logs = client.get_logs(filter).await?;
+        assert_logs_match(&logs, &[&events[0], &events[3]]);
+
+        // Logs from a single block cannot be split, so the request is rejected even though the block range
+        // cannot be narrowed.
+        let filter = Filter {
+            from_block: Some(api::BlockNumber::Number(1.into())),
+            to_block: Some(api::BlockNumber::Number(1.into())),
+            ..Filter::default()
+        };
+        let err = client.get_logs(filter).await.unwrap_err();
+        assert_matches!(
+            err,
+            ClientError::Call(err) if err.code() == ErrorCode::InvalidParams.code()
+                && err.message().contains("Query returned more than 3 results")
+        );
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn single_block_logs_limit() {
+    test_http_server(SingleBlockLogsLimitTest).await;
+}
+
 #[derive(Debug)]
 struct TransactionCountTest;
 
@@ -1075,3 +1271,715 @@ impl HttpTest for GenesisConfigTest {
 async fn tracing_genesis_config() {
     test_http_server(GenesisConfigTest).await;
 }
+
+#[derive(Debug)]
+struct CapabilitiesTest {
+    evm_emulator: bool,
+}
+
+#[async_trait]
+impl HttpTest for CapabilitiesTest {
+    fn evm_emulator_hash(&self) -> Option<H256> {
+        self.evm_emulator.then(|| H256::repeat_byte(4))
+    }
+
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        _pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let capabilities = client.get_capabilities().await?;
+        assert!(capabilities.api_version.starts_with("1."));
+        for namespace in [
+            "admin",
+            "debug",
+            "en",
+            "eth",
+            "net",
+            "snapshots",
+            "web3",
+            "zks",
+        ] {
+            assert!(
+                capabilities.namespaces.iter().any(|ns| ns == namespace),
+                "{capabilities:?}"
+            );
+        }
+        for method in ["eth_blockNumber", "zks_getCapabilities", "zks_L1ChainId"] {
+            assert!(
+                capabilities.methods.iter().any(|name| name == method),
+                "{capabilities:?}"
+            );
+        }
+        assert!(capabilities
+            .methods
+            .windows(2)
+            .all(|pair| pair[0] < pair[1]));
+        assert_eq!(capabilities.features.evm_emulator, self.evm_emulator);
+        assert!(capabilities.protocol_version.is_some());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_api_capabilities() {
+    test_http_server(CapabilitiesTest {
+        evm_emulator: false,
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn getting_api_capabilities_with_evm_emulator() {
+    test_http_server(CapabilitiesTest { evm_emulator: true }).await;
+}
+
+#[derive(Debug)]
+struct OpenRpcDocumentTest;
+
+#[async_trait]
+impl HttpTest for OpenRpcDocumentTest {
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        _pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let document: serde_json::Value =
+            ClientT::request(&client, DISCOVER_METHOD, rpc_params![]).await?;
+        assert_eq!(document["info"]["version"], ZKS_API_VERSION);
+
+        let documented_methods: Vec<_> = document["methods"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|method| method["name"].as_str().unwrap().to_owned())
+            .collect();
+        let capabilities = client.get_capabilities().await?;
+        let registered_methods: Vec<_> = capabilities
+            .methods
+            .into_iter()
+            .filter(|name| name != DISCOVER_METHOD)
+            .collect();
+        // All registered methods must be documented.
+        assert_eq!(documented_methods, registered_methods);
+
+        let estimate_fee = document["methods"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|method| method["name"] == "zks_estimateFee")
+            .unwrap();
+        assert_eq!(estimate_fee["params"][0]["name"], "req");
+        assert_eq!(
+            estimate_fee["result"]["schema"]["$ref"],
+            "#/components/schemas/Fee"
+        );
+        let schemas = document["components"]["schemas"].as_object().unwrap();
+        for name in ["Fee", "BridgeAddresses", "L2ToL1LogProof", "BlockDetails"] {
+            assert!(schemas.contains_key(name), "{schemas:?}");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_openrpc_document() {
+    test_http_server(OpenRpcDocumentTest).await;
+}
+
+#[derive(Debug)]
+struct VerificationKeysHashesTest;
+
+#[async_trait]
+impl HttpTest for VerificationKeysHashesTest {
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let genesis_version = GenesisParams::mock().protocol_version();
+        let expected_config = pool
+            .connection()
+            .await?
+            .protocol_versions_dal()
+            .l1_verifier_config_for_version(genesis_version)
+            .await
+            .unwrap();
+
+        let all_hashes = client.get_verification_keys_hashes(None).await?;
+        assert_eq!(all_hashes.len(), 1, "{all_hashes:?}");
+        assert_eq!(all_hashes[0].protocol_version, genesis_version);
+        assert_eq!(all_hashes[0].verification_keys_hashes, expected_config);
+
+        let version_hashes = client
+            .get_verification_keys_hashes(Some(genesis_version.minor as u16))
+            .await?;
+        assert_eq!(version_hashes, all_hashes);
+
+        let missing_hashes = client
+            .get_verification_keys_hashes(Some(ProtocolVersionId::next() as u16))
+            .await?;
+        assert!(missing_hashes.is_empty(), "{missing_hashes:?}");
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_verification_keys_hashes() {
+    test_http_server(VerificationKeysHashesTest).await;
+}
+
+#[derive(Debug)]
+struct ProtocolVersionHistoryTest;
+
+#[async_trait]
+impl HttpTest for ProtocolVersionHistoryTest {
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let genesis_version = GenesisParams::mock().protocol_version();
+        let history = client.get_protocol_version_history().await?;
+        assert_eq!(history.len(), 1, "{history:?}");
+        assert_eq!(history[0].minor_version, genesis_version.minor as u16);
+        assert_eq!(history[0].activation_l1_batch, Some(L1BatchNumber(0)));
+        assert_eq!(history[0].activation_l2_block, Some(L2BlockNumber(0)));
+        assert_eq!(history[0].verifier_address, None);
+
+        let verifier_address = Address::repeat_byte(0x23);
+        let mut storage = pool.connection().await?;
+        storage
+            .protocol_versions_dal()
+            .save_protocol_version_with_tx(&ProtocolVersion {
+                version: ProtocolSemanticVersion {
+                    minor: ProtocolVersionId::next(),
+                    patch: 0.into(),
+                },
+                timestamp: 1_000,
+                ..ProtocolVersion::default()
+            })
+            .await?;
+        storage
+            .protocol_versions_dal()
+            .save_verifier_address(ProtocolVersionId::next(), verifier_address)
+            .await?;
+        drop(storage);
+
+        let history = client.get_protocol_version_history().await?;
+        assert_eq!(history.len(), 2, "{history:?}");
+        let next_version = &history[1];
+        assert_eq!(next_version.minor_version, ProtocolVersionId::next() as u16);
+        assert_eq!(next_version.timestamp, 1_000);
+        assert_eq!(next_version.activation_l1_batch, None);
+        assert_eq!(next_version.activation_l2_block, None);
+        assert_eq!(next_version.verifier_address, Some(verifier_address));
+        assert_eq!(next_version.l2_system_upgrade_tx_hash, None);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_protocol_version_history() {
+    test_http_server(ProtocolVersionHistoryTest).await;
+}
+
+#[derive(Debug)]
+struct TracedAddressesTest;
+
+#[async_trait]
+impl HttpTest for TracedAddressesTest {
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let addresses = client.get_traced_addresses().await?;
+        assert!(addresses.is_empty(), "{addresses:?}");
+
+        let traced_addresses = [Address::repeat_byte(1), Address::repeat_byte(2)];
+        client
+            .add_traced_addresses(traced_addresses.to_vec())
+            .await?;
+        let addresses = client.get_traced_addresses().await?;
+        assert_eq!(addresses, traced_addresses);
+        let stored_addresses = pool
+            .connection()
+            .await?
+            .traced_addresses_dal()
+            .get_traced_addresses()
+            .await?;
+        assert_eq!(stored_addresses, traced_addresses);
+
+        client
+            .remove_traced_addresses(vec![traced_addresses[0]])
+            .await?;
+        let addresses = client.get_traced_addresses().await?;
+        assert_eq!(addresses, [traced_addresses[1]]);
+
+        let audit_log = pool
+            .connection()
+            .await?
+            .operator_audit_log_dal()
+            .get_entries(None, 10)
+            .await?;
+        let actions: Vec<_> = audit_log
+            .iter()
+            .map(|entry| (entry.actor.as_str(), entry.action.as_str()))
+            .collect();
+        assert_eq!(
+            actions,
+            [
+                (TEST_OPERATOR, "add_traced_addresses"),
+                (TEST_OPERATOR, "remove_traced_addresses")
+            ]
+        );
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn managing_traced_addresses() {
+    test_http_server(TracedAddressesTest).await;
+}
+
+#[derive(Debug)]
+struct SnapshotRequestTest;
+
+#[async_trait]
+impl HttpTest for SnapshotRequestTest {
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let request_id = client.request_snapshot().await?;
+        let mut storage = pool.connection().await?;
+        let pending_request_id = storage
+            .snapshots_dal()
+            .get_latest_pending_snapshot_request()
+            .await?;
+        assert_eq!(pending_request_id, Some(request_id));
+
+        let audit_log = storage
+            .operator_audit_log_dal()
+            .get_entries(None, 10)
+            .await?;
+        assert_eq!(audit_log.len(), 1);
+        assert_eq!(audit_log[0].action, "request_snapshot");
+        assert_eq!(
+            audit_log[0].details,
+            serde_json::json!({ "request_id": request_id })
+        );
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn requesting_snapshot() {
+    test_http_server(SnapshotRequestTest).await;
+}
+
+#[derive(Debug)]
+struct BoostTransactionTest;
+
+#[async_trait]
+impl HttpTest for BoostTransactionTest {
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let tx = create_l2_transaction(10, 200);
+        let boosted = client.boost_transaction(tx.hash()).await?;
+        assert!(!boosted, "unknown transaction was boosted");
+
+        let mut storage = pool.connection().await?;
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(&tx, TransactionExecutionMetrics::default())
+            .await?;
+        let boosted = client.boost_transaction(tx.hash()).await?;
+        assert!(boosted);
+        let boosted_hashes = storage
+            .boosted_transactions_dal()
+            .get_boosted_transactions(&[tx.hash()])
+            .await?;
+        assert!(boosted_hashes.contains(&tx.hash()));
+
+        let audit_log = storage
+            .operator_audit_log_dal()
+            .get_entries(None, 10)
+            .await?;
+        assert_eq!(audit_log.len(), 1);
+        assert_eq!(audit_log[0].action, "boost_transaction");
+        assert_eq!(
+            audit_log[0].details,
+            serde_json::json!({ "tx_hash": tx.hash() })
+        );
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn boosting_transaction() {
+    test_http_server(BoostTransactionTest).await;
+}
+
+#[derive(Debug)]
+struct EthSenderStatusTest;
+
+#[async_trait]
+impl HttpTest for EthSenderStatusTest {
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let status = client.get_eth_sender_status(None).await?;
+        assert!(status.pending.is_empty(), "{status:?}");
+        assert!(status.operator_nonces.is_empty(), "{status:?}");
+
+        let mut storage = pool.connection().await?;
+        let mut eth_txs = vec![];
+        for nonce in 0..3 {
+            let eth_tx = storage
+                .eth_sender_dal()
+                .save_eth_tx(
+                    nonce,
+                    vec![],
+                    AggregatedActionType::Commit,
+                    Address::zero(),
+                    100,
+                    None,
+                    None,
+                )
+                .await?;
+            eth_txs.push(eth_tx);
+        }
+        let tx_hash = H256::repeat_byte(1);
+        let history_id = storage
+            .eth_sender_dal()
+            .insert_tx_history(eth_txs[0].id, 100, 10, None, tx_hash, &[])
+            .await?
+            .unwrap();
+        storage
+            .eth_sender_dal()
+            .set_sent_at_block(history_id, 5)
+            .await?;
+        storage
+            .eth_sender_dal()
+            .mark_failed_transaction(eth_txs[2].id, Some("execution reverted"))
+            .await?;
+
+        let status = client.get_eth_sender_status(None).await?;
+        assert_eq!(status.pending.len(), 1, "{status:?}");
+        let pending = &status.pending[0];
+        assert_eq!(pending.tx_type, "CommitBlocks");
+        assert_eq!((pending.count, pending.sent_count), (2, 1));
+
+        assert_eq!(status.in_flight.len(), 1, "{status:?}");
+        let in_flight = &status.in_flight[0];
+        assert_eq!(in_flight.id, eth_txs[0].id);
+        assert_eq!(in_flight.tx_hash, tx_hash);
+        assert_eq!(in_flight.attempts, 1);
+        assert_eq!(
+            (in_flight.base_fee_per_gas, in_flight.priority_fee_per_gas),
+            (100, 10)
+        );
+        assert_eq!(in_flight.first_sent_at_block, Some(5));
+        assert_eq!(in_flight.last_sent_at_block, Some(5));
+
+        assert_eq!(
+            status.operator_nonces,
+            [api::OperatorNonceStatus {
+                from_addr: None,
+                next_nonce: 3,
+                last_confirmed_nonce: None,
+            }]
+        );
+        assert_eq!(status.recent_failures.len(), 1, "{status:?}");
+        let failure = &status.recent_failures[0];
+        assert_eq!(failure.id, eth_txs[2].id);
+        assert_eq!(
+            failure.failure_reason.as_deref(),
+            Some("execution reverted")
+        );
+
+        let status = client.get_eth_sender_status(Some(0)).await?;
+        assert!(status.recent_failures.is_empty(), "{status:?}");
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_eth_sender_status() {
+    test_http_server(EthSenderStatusTest).await;
+}
+
+#[tokio::test]
+async fn admin_namespace_with_authentication() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    drop(storage);
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let mut api_config = InternalApiConfig::new(
+        &Web3JsonRpcConfig::for_tests(),
+        &ContractsConfig::for_tests(),
+        &GenesisConfig::for_tests(),
+    );
+    api_config.admin_api_keys = vec![admin_api_key("alice", "alice-key")];
+    let mut server_handles = spawn_http_server(
+        api_config,
+        pool,
+        MockTransactionExecutor::default(),
+        Arc::default(),
+        stop_receiver,
+    )
+    .await;
+    let local_addr = server_handles.wait_until_ready().await;
+
+    let unauthenticated_client = <HttpClient>::builder()
+        .build(format!("http://{local_addr}/"))
+        .unwrap();
+    for method in ["admin_getTracedAddresses", "admin_getAuditLog"] {
+        let err = unauthenticated_client
+            .request::<serde_json::Value, _>(method, rpc_params![])
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            ClientError::Call(err) if err.code() == ErrorCode::InvalidRequest.code()
+        );
+    }
+    let err = unauthenticated_client
+        .request::<(), _>(
+            "admin_addTracedAddresses",
+            rpc_params![[Address::repeat_byte(1)]],
+        )
+        .await
+        .unwrap_err();
+    assert_matches!(
+        err,
+        ClientError::Call(err) if err.code() == ErrorCode::InvalidRequest.code()
+    );
+
+    let mut headers = http::HeaderMap::new();
+    headers.insert(
+        http::header::AUTHORIZATION,
+        "Bearer alice-key".parse().unwrap(),
+    );
+    let client = <HttpClient>::builder()
+        .set_headers(headers)
+        .build(format!("http://{local_addr}/"))
+        .unwrap();
+    let traced_addresses = vec![Address::repeat_byte(1)];
+    client
+        .request::<(), _>("admin_addTracedAddresses", rpc_params![&traced_addresses])
+        .await
+        .unwrap();
+    let audit_log: Vec<api::OperatorAuditLogEntry> = client
+        .request("admin_getAuditLog", rpc_params![])
+        .await
+        .unwrap();
+    assert_eq!(audit_log.len(), 1);
+    assert_eq!(audit_log[0].actor, "alice");
+    assert_eq!(audit_log[0].action, "add_traced_addresses");
+
+    let audit_log: Vec<api::OperatorAuditLogEntry> = client
+        .request("admin_getAuditLog", rpc_params![audit_log[0].id])
+        .await
+        .unwrap();
+    assert!(audit_log.is_empty(), "{audit_log:?}");
+
+    stop_sender.send_replace(true);
+    server_handles.shutdown().await;
+}
+
+#[tokio::test]
+async fn admin_namespace_requires_api_keys() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let api_config = InternalApiConfig::new(
+        &Web3JsonRpcConfig::for_tests(),
+        &ContractsConfig::for_tests(),
+        &GenesisConfig::for_tests(),
+    );
+    let (tx_sender, _) = create_test_tx_sender(
+        pool.clone(),
+        api_config.l2_chain_id,
+        MockTransactionExecutor::default().into(),
+    )
+    .await;
+    let err = ApiBuilder::jsonrpsee_backend(api_config, pool.clone())
+        .http(0)
+        .with_tx_sender(tx_sender)
+        .with_master_pool(pool)
+        .enable_api_namespaces(vec![Namespace::Eth, Namespace::Admin])
+        .build()
+        .unwrap_err();
+    assert!(err.to_string().contains("admin API keys"), "{err:#}");
+}
+
+#[derive(Debug)]
+struct FeeParamsAtTest;
+
+#[async_trait]
+impl HttpTest for FeeParamsAtTest {
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let fee_params = client
+            .get_fee_params_at(api::L2BlockOrL1Batch::L1Batch(L1BatchNumber(0)))
+            .await?;
+        assert!(fee_params.is_none(), "{fee_params:?}");
+
+        pool.connection()
+            .await?
+            .blocks_dal()
+            .insert_l1_batch_fee_params(
+                L1BatchNumber(0),
+                &BatchFeeInputParams::unscaled(FeeParams::sensible_v1_default()),
+            )
+            .await?;
+
+        for at in [
+            api::L2BlockOrL1Batch::L1Batch(L1BatchNumber(0)),
+            api::L2BlockOrL1Batch::L2Block(L2BlockNumber(0)),
+        ] {
+            let fee_params = client.get_fee_params_at(at).await?;
+            assert_matches!(
+                fee_params,
+                Some(BatchFeeInputParams {
+                    fee_params: FeeParams::V1(_),
+                    ..
+                })
+            );
+        }
+        for at in [
+            api::L2BlockOrL1Batch::L1Batch(L1BatchNumber(1)),
+            api::L2BlockOrL1Batch::L2Block(L2BlockNumber(1)),
+        ] {
+            let fee_params = client.get_fee_params_at(at).await?;
+            assert!(fee_params.is_none(), "{fee_params:?}");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_fee_params_at_block() {
+    test_http_server(FeeParamsAtTest).await;
+}
+
+#[derive(Debug)]
+struct GethCompatibilityTest {
+    enabled: bool,
+}
+
+#[async_trait]
+impl HttpTest for GethCompatibilityTest {
+    fn method_tracer(&self) -> Arc<MethodTracer> {
+        if self.enabled {
+            Arc::new(MethodTracer::geth_compatible())
+        } else {
+            Arc::default()
+        }
+    }
+
+    fn geth_compatibility(&self) -> HashSet<GethCompatibilityShim> {
+        if self.enabled {
+            [
+                GethCompatibilityShim::BlockMiner,
+                GethCompatibilityShim::ErrorCodes,
+                GethCompatibilityShim::NetWeb3Methods,
+                GethCompatibilityShim::BlockFields,
+            ]
+            .into()
+        } else {
+            HashSet::new()
+        }
+    }
+
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        _pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let is_listening = client.is_listening().await?;
+        assert_eq!(is_listening, self.enabled);
+
+        let sha3_result = client.sha3(b"test".to_vec().into()).await;
+        if self.enabled {
+            assert_eq!(sha3_result?, H256(keccak256(b"test")));
+        } else {
+            let error = sha3_result.unwrap_err();
+            assert_matches!(
+                error,
+                ClientError::Call(err) if err.code() == ErrorCode::MethodNotFound.code()
+            );
+        }
+        let capabilities = client.get_capabilities().await?;
+        assert_eq!(
+            capabilities.methods.iter().any(|name| name == "web3_sha3"),
+            self.enabled,
+            "{capabilities:?}"
+        );
+
+        let missing_block = api::BlockIdVariant::BlockNumber(100.into());
+        let error = client
+            .get_balance(Address::repeat_byte(1), Some(missing_block))
+            .await
+            .unwrap_err();
+        let ClientError::Call(error) = error else {
+            panic!("Unexpected error: {error:?}");
+        };
+        if self.enabled {
+            assert_eq!(error.code(), -32000);
+            assert_eq!(error.message(), "header not found");
+        } else {
+            assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+        }
+
+        // The genesis block doesn't have transactions.
+        let block: serde_json::Value = client
+            .request("eth_getBlockByNumber", rpc_params!["0x0", false])
+            .await?;
+        let block_by_hash: serde_json::Value = client
+            .request("eth_getBlockByHash", rpc_params![&block["hash"], false])
+            .await?;
+        assert_eq!(block_by_hash, block);
+        let fields = block.as_object().unwrap();
+        for field in ["l1BatchNumber", "l1BatchTimestamp", "sealFields"] {
+            assert_eq!(fields.contains_key(field), !self.enabled, "{block:#}");
+        }
+        let expected_root = if self.enabled {
+            EMPTY_TRIE_ROOT_HASH
+        } else {
+            H256::zero()
+        };
+        let expected_root = serde_json::to_value(expected_root).unwrap();
+        assert_eq!(fields["transactionsRoot"], expected_root, "{block:#}");
+        assert_eq!(fields["receiptsRoot"], expected_root, "{block:#}");
+
+        // Typed clients should be able to parse blocks in both shapes.
+        let block = client
+            .get_block_by_number(api::BlockNumber::Number(0.into()), false)
+            .await?
+            .context("no genesis block")?;
+        assert_eq!(block.number, 0.into());
+        assert_eq!(block.l1_batch_number.is_some(), !self.enabled);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn geth_compatibility_shims() {
+    test_http_server(GethCompatibilityTest { enabled: false }).await;
+    test_http_server(GethCompatibilityTest { enabled: true }).await;
+}
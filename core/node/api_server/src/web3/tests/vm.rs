@@ -1,23 +1,32 @@
 //! Tests for the VM-instantiating methods (e.g., `eth_call`).
 
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::{
+    num::NonZeroUsize,
+    sync::atomic::{AtomicU32, Ordering},
+};
 
 use itertools::Itertools;
 use multivm::{
-    interface::{ExecutionResult, VmRevertReason},
+    interface::{ExecutionResult, Refunds, VmRevertReason},
     vm_latest::{VmExecutionLogs, VmExecutionResultAndLogs},
 };
+use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
+use zksync_test_account::{ExpectedOutcome, RpcScenarioExecutor, Scenario, TxOutcome};
 use zksync_types::{
-    api::{ApiStorageLog, Log},
+    api::{ApiStorageLog, Log, TransactionDeadline, TransactionExpiryStatus},
+    fee::Fee,
     get_intrinsic_constants,
     transaction_request::CallRequest,
+    web3::Bytes,
     zk_evm_types::{LogQuery, Timestamp},
-    K256PrivateKey, L2ChainId, PackedEthSignature, StorageLogQuery, StorageLogQueryType, U256,
+    ExecuteTransactionCommon, K256PrivateKey, L2ChainId, PackedEthSignature, StorageLogQuery,
+    StorageLogQueryType, U256,
 };
 use zksync_utils::u256_to_h256;
 use zksync_web3_decl::namespaces::DebugNamespaceClient;
 
 use super::*;
+use crate::web3::idempotency::IDEMPOTENCY_KEY_HEADER;
 
 #[derive(Debug)]
 struct CallTest;
@@ -269,6 +278,187 @@ async fn send_raw_transaction_after_snapshot_recovery() {
     .await;
 }
 
+#[tokio::test]
+async fn send_raw_transaction_with_idempotency_key() {
+    let test = SendRawTransactionTest {
+        snapshot_recovery: false,
+    };
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    test.storage_initialization()
+        .prepare_storage(&NetworkConfig::for_tests(), &mut storage)
+        .await
+        .unwrap();
+    storage
+        .storage_logs_dal()
+        .append_storage_logs(
+            L2BlockNumber(0),
+            &[(
+                H256::zero(),
+                vec![SendRawTransactionTest::balance_storage_log()],
+            )],
+        )
+        .await
+        .unwrap();
+    drop(storage);
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let mut api_config = InternalApiConfig::new(
+        &Web3JsonRpcConfig::for_tests(),
+        &ContractsConfig::for_tests(),
+        &GenesisConfig::for_tests(),
+    );
+    api_config.idempotency_keys_cache_size = NonZeroUsize::new(100);
+    let mut server_handles = spawn_http_server(
+        api_config,
+        pool,
+        test.transaction_executor(),
+        Arc::default(),
+        stop_receiver,
+    )
+    .await;
+    let local_addr = server_handles.wait_until_ready().await;
+
+    let mut headers = http::HeaderMap::new();
+    headers.insert(IDEMPOTENCY_KEY_HEADER, "retry-1".parse().unwrap());
+    let client = <HttpClient>::builder()
+        .set_headers(headers)
+        .build(format!("http://{local_addr}/"))
+        .unwrap();
+
+    let (tx_bytes, tx_hash) = SendRawTransactionTest::transaction_bytes_and_hash();
+    let tx_bytes = Bytes(tx_bytes);
+    for _ in 0..3 {
+        // Retries must return the original result rather than a "known transaction" error.
+        let send_result: H256 = client
+            .request("eth_sendRawTransaction", rpc_params![&tx_bytes])
+            .await
+            .unwrap();
+        assert_eq!(send_result, tx_hash);
+    }
+
+    stop_sender.send_replace(true);
+    server_handles.shutdown().await;
+}
+
+/// Checks that transactions rejected by the server are reported as rejections by the scenario RPC executor.
+#[derive(Debug)]
+struct ScenarioRejectionsTest;
+
+#[async_trait]
+impl HttpTest for ScenarioRejectionsTest {
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        _pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let fee = Fee {
+            gas_limit: (get_intrinsic_constants().l2_tx_intrinsic_gas * 2).into(),
+            max_fee_per_gas: StateKeeperConfig::for_tests().minimal_l2_gas_price.into(),
+            max_priority_fee_per_gas: 0.into(),
+            gas_per_pubdata_limit: DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE.into(),
+        };
+        // The scenario account is not funded.
+        let mut scenario = Scenario::with_deterministic_accounts(1)
+            .with_l2_fee(fee)
+            .transfer(0, Address::repeat_byte(1), 1.into())
+            .expecting(ExpectedOutcome::Rejection)
+            .transfer(0, Address::repeat_byte(2), 0.into())
+            .expecting(ExpectedOutcome::Rejection);
+        let mut executor = RpcScenarioExecutor::new(client.clone_boxed());
+        let report = scenario.run(&mut executor).await?;
+
+        for (tx, outcome) in &report.transactions {
+            assert_eq!(tx.nonce(), Some(Nonce(0)));
+            assert_matches!(
+                outcome,
+                TxOutcome::Rejected { reason } if reason.contains("insufficient funds")
+            );
+        }
+        assert_eq!(scenario.accounts()[0].nonce, Nonce(0));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn scenario_rejections_via_rpc() {
+    test_http_server(ScenarioRejectionsTest).await;
+}
+
+#[derive(Debug)]
+struct SendRawTransactionWithDeadlineTest;
+
+#[async_trait]
+impl HttpTest for SendRawTransactionWithDeadlineTest {
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        let test = SendRawTransactionTest {
+            snapshot_recovery: false,
+        };
+        test.transaction_executor()
+    }
+
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let mut storage = pool.connection().await?;
+        storage
+            .storage_logs_dal()
+            .append_storage_logs(
+                L2BlockNumber(0),
+                &[(
+                    H256::zero(),
+                    vec![SendRawTransactionTest::balance_storage_log()],
+                )],
+            )
+            .await?;
+        drop(storage);
+
+        let (tx_bytes, tx_hash) = SendRawTransactionTest::transaction_bytes_and_hash();
+        let tx_bytes = Bytes(tx_bytes);
+        let error = client
+            .send_raw_transaction_with_deadline(tx_bytes.clone(), TransactionDeadline::default())
+            .await
+            .unwrap_err();
+        assert_matches!(error, ClientError::Call(error) if error.message().contains("must specify"));
+        let passed_deadline = TransactionDeadline {
+            not_after_l2_block: Some(L2BlockNumber(0)),
+            not_after_timestamp: None,
+        };
+        let error = client
+            .send_raw_transaction_with_deadline(tx_bytes.clone(), passed_deadline)
+            .await
+            .unwrap_err();
+        assert_matches!(error, ClientError::Call(error) if error.message().contains("already passed"));
+        assert!(client.get_transaction_expiry(tx_hash).await?.is_none());
+
+        let deadline = TransactionDeadline {
+            not_after_l2_block: Some(L2BlockNumber(10)),
+            not_after_timestamp: None,
+        };
+        let send_result = client
+            .send_raw_transaction_with_deadline(tx_bytes, deadline)
+            .await?;
+        assert_eq!(send_result, tx_hash);
+
+        let expiry = client
+            .get_transaction_expiry(tx_hash)
+            .await?
+            .context("no expiry info")?;
+        assert_eq!(expiry.hash, tx_hash);
+        assert_eq!(expiry.deadline, deadline);
+        assert_eq!(expiry.status, TransactionExpiryStatus::Pending);
+        assert_eq!(expiry.expired_at, None);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn send_raw_transaction_with_deadline() {
+    test_http_server(SendRawTransactionWithDeadlineTest).await;
+}
+
 #[derive(Debug)]
 struct SendTransactionWithDetailedOutputTest;
 
@@ -641,3 +831,163 @@ async fn estimate_gas_basics() {
 async fn estimate_gas_after_snapshot_recovery() {
     test_http_server(EstimateGasTest::new(true)).await;
 }
+
+#[derive(Debug, Default)]
+struct EstimateFeeBatchTest {
+    estimated_nonces: Arc<Mutex<HashSet<Nonce>>>,
+}
+
+#[async_trait]
+impl HttpTest for EstimateFeeBatchTest {
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        let mut tx_executor = MockTransactionExecutor::default();
+        let estimated_nonces = self.estimated_nonces.clone();
+        tx_executor.set_call_responses(move |tx, _| {
+            estimated_nonces.lock().unwrap().insert(tx.nonce().unwrap());
+            ExecutionResult::Success { output: vec![] }
+        });
+        tx_executor
+    }
+
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        _pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let mut call_request = CallRequest::from(create_l2_transaction(10, 100));
+        call_request.nonce = None;
+        let fees = client
+            .estimate_fee_batch(vec![call_request.clone(), call_request.clone()])
+            .await?;
+        assert_eq!(fees.len(), 2);
+        // Nonces must be carried forward.
+        let estimated_nonces = self.estimated_nonces.lock().unwrap().clone();
+        assert_eq!(estimated_nonces, HashSet::from([Nonce(0), Nonce(1)]));
+
+        let error = client.estimate_fee_batch(vec![]).await.unwrap_err();
+        assert_matches!(error, ClientError::Call(error) if error.message().contains("batch is empty"));
+        let error = client
+            .estimate_fee_batch(vec![call_request.clone(); 17])
+            .await
+            .unwrap_err();
+        assert_matches!(error, ClientError::Call(error) if error.message().contains("at most 16"));
+
+        let mut other_call_request = call_request.clone();
+        other_call_request.from = Some(Address::repeat_byte(0xff));
+        let error = client
+            .estimate_fee_batch(vec![call_request, other_call_request])
+            .await
+            .unwrap_err();
+        assert_matches!(error, ClientError::Call(error) if error.message().contains("same sender"));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn estimating_fee_for_batch() {
+    test_http_server(EstimateFeeBatchTest::default()).await;
+}
+
+#[derive(Debug)]
+struct EstimateL1ToL2ExecuteTest;
+
+impl EstimateL1ToL2ExecuteTest {
+    const GAS_LIMIT: u64 = 1_000_000;
+    const GAS_REFUNDED: u64 = 400_000;
+}
+
+#[async_trait]
+impl HttpTest for EstimateL1ToL2ExecuteTest {
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        let mut tx_executor = MockTransactionExecutor::default();
+        tx_executor.set_tx_responses_with_logs(|tx, _| {
+            let ExecuteTransactionCommon::L1(data) = &tx.common_data else {
+                panic!("Unexpected transaction: {tx:?}");
+            };
+            assert_eq!(data.refund_recipient, data.sender);
+            assert_eq!(data.gas_limit, Self::GAS_LIMIT.into());
+            assert!(!data.max_fee_per_gas.is_zero());
+            assert_eq!(
+                data.to_mint,
+                data.gas_limit * data.max_fee_per_gas + tx.execute.value
+            );
+
+            let result = if tx.execute.calldata().is_empty() {
+                ExecutionResult::Success {
+                    output: b"done".to_vec(),
+                }
+            } else {
+                ExecutionResult::Revert {
+                    output: VmRevertReason::General {
+                        msg: "insufficient allowance".to_owned(),
+                        data: b"revert data".to_vec(),
+                    },
+                }
+            };
+            VmExecutionResultAndLogs {
+                result,
+                logs: Default::default(),
+                statistics: Default::default(),
+                refunds: Refunds {
+                    gas_refunded: Self::GAS_REFUNDED,
+                    operator_suggested_refund: Self::GAS_REFUNDED,
+                },
+            }
+        });
+        tx_executor
+    }
+
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        _pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let mut call_request = CallRequest {
+            from: Some(Address::repeat_byte(1)),
+            to: Some(Address::repeat_byte(2)),
+            gas: Some(Self::GAS_LIMIT.into()),
+            value: Some(1_000.into()),
+            ..CallRequest::default()
+        };
+        let simulation = client
+            .estimate_l1_to_l2_execute(call_request.clone())
+            .await?;
+        assert!(simulation.success);
+        assert_eq!(simulation.output.0, b"done");
+        assert_eq!(simulation.revert_reason, None);
+        assert_eq!(simulation.gas_limit, Self::GAS_LIMIT.into());
+        assert_eq!(simulation.gas_refunded, Self::GAS_REFUNDED.into());
+        assert_eq!(
+            simulation.gas_used,
+            (Self::GAS_LIMIT - Self::GAS_REFUNDED).into()
+        );
+        assert_eq!(simulation.refund_recipient, Address::repeat_byte(1));
+        let gas_refund = U256::from(Self::GAS_REFUNDED) * simulation.max_fee_per_gas;
+        assert_eq!(simulation.refund_amount, gas_refund);
+
+        call_request.data = Some(b"fail".to_vec().into());
+        let simulation = client.estimate_l1_to_l2_execute(call_request).await?;
+        assert!(!simulation.success);
+        assert_eq!(simulation.output.0, b"revert data");
+        assert_eq!(
+            simulation.revert_reason.as_deref(),
+            Some("insufficient allowance")
+        );
+        // The transferred value is refunded for failed transactions.
+        assert_eq!(simulation.refund_amount, gas_refund + U256::from(1_000));
+
+        call_request.data = None;
+        call_request.value = Some(U256::MAX);
+        let error = client
+            .estimate_l1_to_l2_execute(call_request)
+            .await
+            .unwrap_err();
+        assert_matches!(error, ClientError::Call(error) if error.message().contains("overflows uint256"));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn estimating_l1_to_l2_execution() {
+    test_http_server(EstimateL1ToL2ExecuteTest).await;
+}
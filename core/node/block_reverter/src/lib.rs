@@ -1,7 +1,8 @@
-use std::{path::Path, sync::Arc, time::Duration};
+use std::{ops::RangeInclusive, path::Path, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use serde::Serialize;
+use serde_json::json;
 use tokio::{fs, sync::Semaphore};
 use zksync_config::{configs::chain::NetworkConfig, ContractsConfig, EthConfig};
 use zksync_contracts::hyperchain_contract;
@@ -97,9 +98,13 @@ pub struct BlockReverter {
     state_keeper_cache_path: Option<String>,
     merkle_tree_path: Option<String>,
     snapshots_object_store: Option<Arc<dyn ObjectStore>>,
+    audit_actor: String,
 }
 
 impl BlockReverter {
+    /// Actor recorded in the operator audit log if it wasn't set explicitly.
+    const DEFAULT_AUDIT_ACTOR: &'static str = "block_reverter";
+
     pub fn new(node_role: NodeRole, connection_pool: ConnectionPool<Core>) -> Self {
         Self {
             node_role,
@@ -109,9 +114,16 @@ impl BlockReverter {
             state_keeper_cache_path: None,
             merkle_tree_path: None,
             snapshots_object_store: None,
+            audit_actor: Self::DEFAULT_AUDIT_ACTOR.to_owned(),
         }
     }
 
+    /// Sets the operator recorded in the operator audit log for the performed actions.
+    pub fn set_audit_actor(&mut self, actor: String) -> &mut Self {
+        self.audit_actor = actor;
+        self
+    }
+
     /// Allows rolling back the state past the last batch finalized on L1. If this is disallowed (which is the default),
     /// block reverter will error upon such an attempt.
     ///
@@ -177,6 +189,28 @@ impl BlockReverter {
             );
         }
 
+        let details = json!({
+            "last_l1_batch_to_keep": last_l1_batch_to_keep.0,
+            "postgres": self.should_roll_back_postgres,
+            "merkle_tree": self.merkle_tree_path.is_some(),
+            "state_keeper_cache": self.state_keeper_cache_path.is_some(),
+            "snapshot_objects": self.snapshots_object_store.is_some(),
+        });
+        self.record_audit_entry("roll_back_l1_batches", details)
+            .await
+    }
+
+    async fn record_audit_entry(
+        &self,
+        action: &str,
+        details: serde_json::Value,
+    ) -> anyhow::Result<()> {
+        self.connection_pool
+            .connection()
+            .await?
+            .operator_audit_log_dal()
+            .record_action(&self.audit_actor, action, details)
+            .await?;
         Ok(())
     }
 
@@ -353,6 +387,18 @@ impl BlockReverter {
             .delete_snapshots_after(last_l1_batch_to_keep)
             .await?;
 
+        tracing::info!("Rolling back component watermarks");
+        transaction
+            .watermarks_dal()
+            .roll_back_watermarks(last_l1_batch_to_keep)
+            .await?;
+
+        tracing::info!("Rolling back L1 batch fee params");
+        transaction
+            .blocks_dal()
+            .delete_l1_batch_fee_params(last_l1_batch_to_keep)
+            .await?;
+
         // Remove data from main tables (L2 blocks and L1 batches).
         tracing::info!("Rolling back L1 batches");
         transaction
@@ -536,7 +582,14 @@ impl BlockReverter {
                     receipt.status
                 );
                 tracing::info!("Revert transaction has completed");
-                return Ok(());
+                let details = json!({
+                    "last_l1_batch_to_keep": last_l1_batch_to_keep.0,
+                    "nonce": nonce,
+                    "tx_hash": hash,
+                });
+                return self
+                    .record_audit_entry("send_ethereum_revert_transaction", details)
+                    .await;
             } else {
                 tracing::info!("waiting for L1 transaction confirmation...");
                 tokio::time::sleep(Duration::from_secs(5)).await;
@@ -623,7 +676,35 @@ impl BlockReverter {
             .eth_sender_dal()
             .clear_failed_transactions()
             .await?;
-        Ok(())
+        self.record_audit_entry("clear_failed_l1_transactions", json!({}))
+            .await
+    }
+
+    /// Restores L1 transactions and their sending attempts archived by the house keeper for the specified range
+    /// of L1 transaction IDs. Returns the number of restored L1 transactions and sending attempts.
+    pub async fn restore_archived_eth_txs_history(
+        &self,
+        eth_tx_ids: RangeInclusive<u32>,
+    ) -> anyhow::Result<(usize, usize)> {
+        tracing::info!("Restoring archived L1 transactions {eth_tx_ids:?}");
+        let (restored_eth_tx_count, restored_count) = self
+            .connection_pool
+            .connection()
+            .await?
+            .eth_sender_dal()
+            .restore_archived_eth_txs_history(eth_tx_ids.clone())
+            .await?;
+        tracing::info!(
+            "Restored {restored_eth_tx_count} L1 transactions and {restored_count} sending attempts"
+        );
+        let details = json!({
+            "eth_tx_ids": [eth_tx_ids.start(), eth_tx_ids.end()],
+            "restored_eth_tx_count": restored_eth_tx_count,
+            "restored_count": restored_count,
+        });
+        self.record_audit_entry("restore_archived_eth_txs_history", details)
+            .await?;
+        Ok((restored_eth_tx_count, restored_count))
     }
 }
 
@@ -12,6 +12,7 @@ use zksync_object_store::{Bucket, MockObjectStore};
 use zksync_state::ReadStorage;
 use zksync_types::{
     block::{L1BatchHeader, L2BlockHeader},
+    fee_model::{BatchFeeInputParams, FeeParams},
     snapshots::SnapshotVersion,
     AccountTreeId, L2BlockNumber, ProtocolVersion, ProtocolVersionId, StorageKey, StorageLog,
 };
@@ -93,6 +94,14 @@ async fn setup_storage(storage: &mut Connection<'_, Core>, storage_logs: &[Stora
             .mark_l2_blocks_as_executed_in_l1_batch(l1_batch_header.number)
             .await
             .unwrap();
+        storage
+            .blocks_dal()
+            .insert_l1_batch_fee_params(
+                l1_batch_header.number,
+                &BatchFeeInputParams::unscaled(FeeParams::sensible_v1_default()),
+            )
+            .await
+            .unwrap();
 
         storage
             .storage_logs_dal()
@@ -146,6 +155,7 @@ async fn block_reverter_basics(sync_merkle_tree: bool) {
         .enable_rolling_back_postgres()
         .enable_rolling_back_merkle_tree(merkle_tree_path.to_str().unwrap().to_owned())
         .enable_rolling_back_state_keeper_cache(sk_cache_path.to_str().unwrap().to_owned())
+        .set_audit_actor("alice".to_owned())
         .roll_back(L1BatchNumber(5))
         .await
         .unwrap();
@@ -163,6 +173,19 @@ async fn block_reverter_basics(sync_merkle_tree: bool) {
         .unwrap();
     assert_eq!(last_l2_block_number, Some(L2BlockNumber(5)));
 
+    let fee_params = storage
+        .blocks_dal()
+        .get_l1_batch_fee_params(L1BatchNumber(5))
+        .await
+        .unwrap();
+    assert!(fee_params.is_some());
+    let fee_params = storage
+        .blocks_dal()
+        .get_l1_batch_fee_params(L1BatchNumber(6))
+        .await
+        .unwrap();
+    assert!(fee_params.is_none());
+
     let all_storage_logs = storage
         .storage_logs_dal()
         .dump_all_storage_logs_for_tests()
@@ -198,6 +221,17 @@ async fn block_reverter_basics(sync_merkle_tree: bool) {
         let expected_value = if i <= 5 { log.value } else { H256::zero() };
         assert_eq!(sk_cache.read_value(&log.key), expected_value);
     }
+
+    let audit_log = storage
+        .operator_audit_log_dal()
+        .get_entries(None, 10)
+        .await
+        .unwrap();
+    assert_eq!(audit_log.len(), 1);
+    assert_eq!(audit_log[0].actor, "alice");
+    assert_eq!(audit_log[0].action, "roll_back_l1_batches");
+    assert_eq!(audit_log[0].details["last_l1_batch_to_keep"], 5);
+    assert_eq!(audit_log[0].details["postgres"], true);
 }
 
 async fn create_mock_snapshot(
@@ -4,7 +4,7 @@ use anyhow::Context;
 use itertools::Itertools;
 use multivm::zk_evm_latest::ethereum_types::U256;
 use tokio::{sync::watch, task::JoinHandle};
-use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_dal::{watermarks_dal::WatermarkComponent, ConnectionPool, Core, CoreDal};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_l1_contract_interface::i_executor::commit::kzg::pubdata_to_blob_commitments;
 use zksync_types::{
@@ -15,6 +15,7 @@ use zksync_types::{
     },
     event::convert_vm_events_to_log_queries,
     writes::{InitialStorageWrite, RepeatedStorageWrite, StateDiffRecord},
+    zk_evm_types::LogQuery,
     L1BatchNumber, ProtocolVersionId, StorageKey, H256,
 };
 use zksync_utils::h256_to_u256;
@@ -25,6 +26,7 @@ use crate::{
 };
 
 mod metrics;
+pub mod recalculation;
 #[cfg(test)]
 mod tests;
 mod utils;
@@ -104,6 +106,28 @@ impl CommitmentGenerator {
             })?;
         drop(connection);
 
+        self.calculate_aux_commitments_from(
+            events_queue,
+            initial_bootloader_contents,
+            l1_batch_number,
+            protocol_version,
+        )
+        .await
+    }
+
+    /// Calculates auxiliary commitments for an L1 batch from the provided events queue and initial bootloader memory
+    /// (e.g., ones obtained by re-executing the batch).
+    ///
+    /// # Errors
+    ///
+    /// Propagates calculation errors.
+    pub async fn calculate_aux_commitments_from(
+        &self,
+        events_queue: Vec<LogQuery>,
+        initial_bootloader_contents: Vec<(usize, U256)>,
+        l1_batch_number: L1BatchNumber,
+        protocol_version: ProtocolVersionId,
+    ) -> anyhow::Result<AuxCommitments> {
         let computer = self.computer.clone();
         let events_commitment_task: JoinHandle<anyhow::Result<H256>> =
             tokio::task::spawn_blocking(move || {
@@ -262,15 +286,11 @@ impl CommitmentGenerator {
             }
             state_diffs.sort_unstable_by_key(|rec| (rec.address, rec.key));
 
-            let blob_commitments = if protocol_version.is_post_1_4_2() {
-                let pubdata_input = header.pubdata_input.with_context(|| {
-                    format!("`pubdata_input` is missing for L1 batch #{l1_batch_number}")
-                })?;
-
-                pubdata_to_blob_commitments(num_blobs_required(&protocol_version), &pubdata_input)
-            } else {
-                vec![H256::zero(); num_blobs_required(&protocol_version)]
-            };
+            let blob_commitments =
+                Self::blob_commitments(protocol_version, header.pubdata_input.as_deref())
+                    .with_context(|| {
+                        format!("`pubdata_input` is missing for L1 batch #{l1_batch_number}")
+                    })?;
 
             CommitmentInput::PostBoojum {
                 common,
@@ -285,6 +305,29 @@ impl CommitmentGenerator {
         Ok(input)
     }
 
+    /// Calculates blob commitments for a post-boojum L1 batch. Returns `None` if `pubdata_input` is required
+    /// for the protocol version, but is not provided.
+    pub fn blob_commitments(
+        protocol_version: ProtocolVersionId,
+        pubdata_input: Option<&[u8]>,
+    ) -> Option<Vec<H256>> {
+        let blob_count = num_blobs_required(&protocol_version);
+        Some(if protocol_version.is_post_1_4_2() {
+            pubdata_to_blob_commitments(blob_count, pubdata_input?)
+        } else {
+            vec![H256::zero(); blob_count]
+        })
+    }
+
+    /// Calculates commitment artifacts for the provided input, accounting for the commitment mode
+    /// of this generator.
+    pub fn calculate_artifacts(&self, mut input: CommitmentInput) -> L1BatchCommitmentArtifacts {
+        self.tweak_input(&mut input);
+        let mut commitment = L1BatchCommitment::new(input);
+        self.post_process_commitment(&mut commitment);
+        commitment.artifacts()
+    }
+
     async fn process_batch(
         &self,
         l1_batch_number: L1BatchNumber,
@@ -326,15 +369,23 @@ impl CommitmentGenerator {
             .connection_pool
             .connection_tagged("commitment_generator")
             .await?;
-        // Saving changes atomically is not required here; since we save batches in order, if we encounter a DB error,
-        // the commitment generator will be able to recover gracefully.
+        // Saving changes for all batches atomically is not required here; since we save batches in order,
+        // if we encounter a DB error, the commitment generator will be able to recover gracefully.
+        // Artifacts for each batch are saved together with the watermark, so that the watermark never runs ahead
+        // of the saved data.
         for (l1_batch_number, artifacts) in artifacts {
             let latency =
                 METRICS.generate_commitment_latency_stage[&CommitmentStage::SaveResults].start();
-            connection
+            let mut transaction = connection.start_transaction().await?;
+            transaction
                 .blocks_dal()
                 .save_l1_batch_commitment_artifacts(l1_batch_number, &artifacts)
                 .await?;
+            transaction
+                .watermarks_dal()
+                .set_watermark(WatermarkComponent::Commitments, l1_batch_number)
+                .await?;
+            transaction.commit().await?;
             let latency = latency.observe();
             tracing::debug!(
                 "Stored commitment artifacts for L1 batch #{l1_batch_number} in {latency:?}"
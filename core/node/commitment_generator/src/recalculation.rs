@@ -0,0 +1,329 @@
+//! Recalculation of L1 batch metadata, used by maintenance tooling (e.g., after restoring the Merkle tree
+//! or if metadata corruption is suspected).
+
+use std::fmt;
+
+use anyhow::Context as _;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_types::{
+    block::L1BatchTreeData,
+    commitment::{L1BatchCommitmentArtifacts, L1BatchCommitmentMode, L1BatchMetadata},
+    web3::keccak256,
+    L1BatchNumber, H256,
+};
+
+use crate::CommitmentGenerator;
+
+/// Mismatch between a stored and recalculated metadata field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataMismatch {
+    /// Name of the mismatched field.
+    pub field: &'static str,
+    /// Human-readable stored value.
+    pub stored: String,
+    /// Human-readable recalculated value.
+    pub recalculated: String,
+}
+
+impl MetadataMismatch {
+    /// Compares `Debug`-formattable values, returning a mismatch if they differ.
+    pub fn compare_debug<T: PartialEq + fmt::Debug>(
+        field: &'static str,
+        stored: &T,
+        recalculated: &T,
+    ) -> Option<Self> {
+        (stored != recalculated).then(|| Self {
+            field,
+            stored: format!("{stored:?}"),
+            recalculated: format!("{recalculated:?}"),
+        })
+    }
+
+    /// Compares byte sequences, returning a mismatch if they differ. Values in the mismatch are summarized
+    /// by their length and keccak256 digest.
+    pub fn compare_bytes(
+        field: &'static str,
+        stored: Option<&[u8]>,
+        recalculated: Option<&[u8]>,
+    ) -> Option<Self> {
+        fn summarize(bytes: Option<&[u8]>) -> String {
+            match bytes {
+                Some(bytes) => format!(
+                    "{} bytes with keccak256 {:?}",
+                    bytes.len(),
+                    H256(keccak256(bytes))
+                ),
+                None => "None".to_owned(),
+            }
+        }
+
+        (stored != recalculated).then(|| Self {
+            field,
+            stored: summarize(stored),
+            recalculated: summarize(recalculated),
+        })
+    }
+}
+
+impl fmt::Display for MetadataMismatch {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "`{}`: stored {}, recalculated {}",
+            self.field, self.stored, self.recalculated
+        )
+    }
+}
+
+/// Outcome of checking metadata for a single L1 batch.
+#[derive(Debug, Clone)]
+pub struct L1BatchMetadataCheck {
+    /// Number of the checked L1 batch.
+    pub l1_batch_number: L1BatchNumber,
+    /// If set, metadata for the batch is incomplete, so it wasn't checked. Contains the reason.
+    pub incomplete_reason: Option<String>,
+    /// Mismatches between the stored tree data and the Merkle tree.
+    pub tree_mismatches: Vec<MetadataMismatch>,
+    /// Mismatches between the stored and recalculated commitment artifacts.
+    pub commitment_mismatches: Vec<MetadataMismatch>,
+    /// Whether the batch is committed on L1.
+    pub committed_on_l1: bool,
+    /// Whether the stored commitment artifacts were overwritten with the recalculated ones.
+    pub repaired: bool,
+}
+
+impl L1BatchMetadataCheck {
+    /// Checks whether the batch metadata is complete and doesn't have mismatches.
+    pub fn is_ok(&self) -> bool {
+        self.incomplete_reason.is_none()
+            && self.tree_mismatches.is_empty()
+            && self.commitment_mismatches.is_empty()
+    }
+}
+
+/// Recalculates metadata (commitments, auxiliary commitments and compressed pubdata) for sealed L1 batches
+/// and compares it with the values stored in Postgres. Optionally, the stored commitment artifacts
+/// are repaired, i.e., overwritten with the recalculated ones.
+///
+/// Commitments are recalculated based on the tree data stored in Postgres. The stored tree data itself
+/// can be compared against the Merkle tree, but it is never repaired by the recalculator; a mismatch
+/// there requires reverting the node state.
+#[derive(Debug)]
+pub struct MetadataRecalculator {
+    generator: CommitmentGenerator,
+    pool: ConnectionPool<Core>,
+    repair: bool,
+}
+
+impl MetadataRecalculator {
+    /// Creates a recalculator with the provided commitment mode. Repair mode is disabled by default.
+    pub fn new(pool: ConnectionPool<Core>, commitment_mode: L1BatchCommitmentMode) -> Self {
+        Self::with_generator(CommitmentGenerator::new(pool, commitment_mode))
+    }
+
+    pub(crate) fn with_generator(generator: CommitmentGenerator) -> Self {
+        Self {
+            pool: generator.connection_pool.clone(),
+            generator,
+            repair: false,
+        }
+    }
+
+    /// Enables or disables repair mode. In repair mode, mismatched commitment artifacts are overwritten
+    /// with the recalculated ones, unless the batch is already committed on L1.
+    pub fn set_repair(&mut self, repair: bool) {
+        self.repair = repair;
+    }
+
+    /// Checks metadata of the specified L1 batch. If `tree_data` is provided, it is compared
+    /// against the tree data stored in Postgres.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the batch is not sealed, or on DB / recalculation errors.
+    pub async fn check_batch(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        tree_data: Option<L1BatchTreeData>,
+    ) -> anyhow::Result<L1BatchMetadataCheck> {
+        let mut connection = self.pool.connection_tagged("metadata_recalculator").await?;
+        let batch = connection
+            .blocks_dal()
+            .get_optional_l1_batch_metadata(l1_batch_number)
+            .await?
+            .with_context(|| format!("L1 batch #{l1_batch_number} is not sealed"))?;
+        let committed_on_l1 = connection
+            .blocks_dal()
+            .get_eth_commit_tx_id(l1_batch_number)
+            .await?
+            .is_some();
+        drop(connection);
+
+        let mut check = L1BatchMetadataCheck {
+            l1_batch_number,
+            incomplete_reason: None,
+            tree_mismatches: vec![],
+            commitment_mismatches: vec![],
+            committed_on_l1,
+            repaired: false,
+        };
+        let metadata = match batch.metadata {
+            Ok(metadata) => metadata,
+            Err(err) => {
+                check.incomplete_reason = Some(err.to_string());
+                return Ok(check);
+            }
+        };
+
+        if let Some(tree_data) = tree_data {
+            check.tree_mismatches = Self::compare_tree_data(&metadata, &tree_data);
+        }
+        let artifacts = self
+            .generator
+            .process_batch(l1_batch_number)
+            .await
+            .with_context(|| {
+                format!("failed recalculating metadata for L1 batch #{l1_batch_number}")
+            })?;
+        check.commitment_mismatches = Self::compare_artifacts(&metadata, &artifacts);
+
+        if self.repair && !check.commitment_mismatches.is_empty() {
+            if committed_on_l1 {
+                tracing::warn!(
+                    "L1 batch #{l1_batch_number} is committed on L1; not repairing its metadata"
+                );
+            } else {
+                tracing::info!("Overwriting commitment artifacts for L1 batch #{l1_batch_number}");
+                let mut connection = self.pool.connection_tagged("metadata_recalculator").await?;
+                connection
+                    .blocks_dal()
+                    .overwrite_l1_batch_commitment_artifacts(l1_batch_number, &artifacts)
+                    .await?;
+                check.repaired = true;
+            }
+        }
+        Ok(check)
+    }
+
+    fn compare_tree_data(
+        metadata: &L1BatchMetadata,
+        tree_data: &L1BatchTreeData,
+    ) -> Vec<MetadataMismatch> {
+        let mut mismatches = vec![];
+        compare_debug(
+            &mut mismatches,
+            "root_hash",
+            &metadata.root_hash,
+            &tree_data.hash,
+        );
+        compare_debug(
+            &mut mismatches,
+            "rollup_last_leaf_index",
+            &metadata.rollup_last_leaf_index,
+            &tree_data.rollup_last_leaf_index,
+        );
+        mismatches
+    }
+
+    /// Compares commitment artifacts stored in `metadata` with the recalculated ones.
+    pub fn compare_artifacts(
+        metadata: &L1BatchMetadata,
+        artifacts: &L1BatchCommitmentArtifacts,
+    ) -> Vec<MetadataMismatch> {
+        let mut mismatches = vec![];
+        let hashes = &artifacts.commitment_hash;
+        compare_debug(
+            &mut mismatches,
+            "commitment",
+            &metadata.commitment,
+            &hashes.commitment,
+        );
+        compare_debug(
+            &mut mismatches,
+            "aux_data_hash",
+            &metadata.aux_data_hash,
+            &hashes.aux_output,
+        );
+        compare_debug(
+            &mut mismatches,
+            "meta_parameters_hash",
+            &metadata.meta_parameters_hash,
+            &hashes.meta_parameters,
+        );
+        compare_debug(
+            &mut mismatches,
+            "pass_through_data_hash",
+            &metadata.pass_through_data_hash,
+            &hashes.pass_through_data,
+        );
+        compare_debug(
+            &mut mismatches,
+            "l2_l1_merkle_root",
+            &metadata.l2_l1_merkle_root,
+            &artifacts.l2_l1_merkle_root,
+        );
+        compare_debug(
+            &mut mismatches,
+            "zkporter_is_available",
+            &metadata.block_meta_params.zkporter_is_available,
+            &artifacts.zkporter_is_available,
+        );
+        compare_bytes(
+            &mut mismatches,
+            "compressed_state_diffs",
+            Some(&metadata.state_diffs_compressed),
+            // Missing state diffs are read from Postgres as an empty vector.
+            Some(
+                artifacts
+                    .compressed_state_diffs
+                    .as_deref()
+                    .unwrap_or_default(),
+            ),
+        );
+        compare_bytes(
+            &mut mismatches,
+            "compressed_initial_writes",
+            metadata.initial_writes_compressed.as_deref(),
+            artifacts.compressed_initial_writes.as_deref(),
+        );
+        compare_bytes(
+            &mut mismatches,
+            "compressed_repeated_writes",
+            metadata.repeated_writes_compressed.as_deref(),
+            artifacts.compressed_repeated_writes.as_deref(),
+        );
+
+        let aux_commitments = artifacts.aux_commitments.as_ref();
+        compare_debug(
+            &mut mismatches,
+            "events_queue_commitment",
+            &metadata.events_queue_commitment,
+            &aux_commitments.map(|aux| aux.events_queue_commitment),
+        );
+        compare_debug(
+            &mut mismatches,
+            "bootloader_initial_content_commitment",
+            &metadata.bootloader_initial_content_commitment,
+            &aux_commitments.map(|aux| aux.bootloader_initial_content_commitment),
+        );
+        mismatches
+    }
+}
+
+fn compare_debug<T: PartialEq + fmt::Debug>(
+    mismatches: &mut Vec<MetadataMismatch>,
+    field: &'static str,
+    stored: &T,
+    recalculated: &T,
+) {
+    mismatches.extend(MetadataMismatch::compare_debug(field, stored, recalculated));
+}
+
+fn compare_bytes(
+    mismatches: &mut Vec<MetadataMismatch>,
+    field: &'static str,
+    stored: Option<&[u8]>,
+    recalculated: Option<&[u8]>,
+) {
+    mismatches.extend(MetadataMismatch::compare_bytes(field, stored, recalculated));
+}
@@ -11,6 +11,7 @@ use zksync_types::{
 };
 
 use super::*;
+use crate::recalculation::MetadataRecalculator;
 
 async fn seal_l1_batch(storage: &mut Connection<'_, Core>, number: L1BatchNumber) {
     let l2_block = create_l2_block(number.0);
@@ -299,3 +300,65 @@ async fn commitment_generator_with_tree_emulation() {
     stop_sender.send_replace(true);
     generator_handle.await.unwrap().unwrap();
 }
+
+#[tokio::test]
+async fn recalculating_metadata() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    let number = L1BatchNumber(1);
+    seal_l1_batch(&mut storage, number).await;
+    save_l1_batch_tree_data(&mut storage, number).await;
+
+    let mut recalculator =
+        MetadataRecalculator::with_generator(create_commitment_generator(pool.clone()));
+    let check = recalculator.check_batch(number, None).await.unwrap();
+    assert!(!check.is_ok());
+    assert!(check.incomplete_reason.is_some(), "{check:?}");
+
+    let generator = create_commitment_generator(pool.clone());
+    generator.step(number..=number).await.unwrap();
+    let check = recalculator.check_batch(number, None).await.unwrap();
+    assert!(check.is_ok(), "{check:?}");
+    assert!(!check.committed_on_l1);
+
+    let tree_data = L1BatchTreeData {
+        hash: H256::repeat_byte(0x23),
+        rollup_last_leaf_index: 30,
+    };
+    let check = recalculator
+        .check_batch(number, Some(tree_data))
+        .await
+        .unwrap();
+    let mismatched_fields: Vec<_> = check.tree_mismatches.iter().map(|m| m.field).collect();
+    assert_eq!(mismatched_fields, ["root_hash"]);
+    assert!(check.commitment_mismatches.is_empty());
+
+    // Corrupt the stored metadata.
+    let mut artifacts = generator.process_batch(number).await.unwrap();
+    artifacts.commitment_hash.commitment = H256::repeat_byte(0xff);
+    artifacts.compressed_state_diffs = Some(vec![1, 2, 3]);
+    storage
+        .blocks_dal()
+        .overwrite_l1_batch_commitment_artifacts(number, &artifacts)
+        .await
+        .unwrap();
+    let check = recalculator.check_batch(number, None).await.unwrap();
+    let mismatched_fields: Vec<_> = check
+        .commitment_mismatches
+        .iter()
+        .map(|m| m.field)
+        .collect();
+    assert_eq!(mismatched_fields, ["commitment", "compressed_state_diffs"]);
+    assert!(!check.repaired);
+
+    recalculator.set_repair(true);
+    let check = recalculator.check_batch(number, None).await.unwrap();
+    assert_eq!(check.commitment_mismatches.len(), 2);
+    assert!(check.repaired);
+    let check = recalculator.check_batch(number, None).await.unwrap();
+    assert!(check.is_ok(), "{check:?}");
+    assert!(!check.repaired);
+}
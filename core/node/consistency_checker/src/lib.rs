@@ -11,7 +11,10 @@ use zksync_eth_client::{
 };
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_l1_contract_interface::{
-    i_executor::{commit::kzg::ZK_SYNC_BYTES_PER_BLOB, structures::CommitBatchInfo},
+    i_executor::{
+        commit::kzg::ZK_SYNC_BYTES_PER_BLOB,
+        structures::{CommitBatchInfo, StoredBatchInfo},
+    },
     Tokenizable,
 };
 use zksync_shared_metrics::{CheckerComponent, EN_METRICS};
@@ -38,6 +41,9 @@ enum CheckError {
     /// Error that is caused by violating invariants internal to *this* node (e.g., not having expected data in Postgres).
     #[error("internal error")]
     Internal(anyhow::Error),
+    /// The batch is not executed on L1 yet, so it cannot be verified in the verify-on-sync mode.
+    #[error("L1 batch is not executed on L1 yet (last executed batch: #{0})")]
+    NotExecutedOnL1(L1BatchNumber),
 }
 
 impl CheckError {
@@ -130,10 +136,9 @@ impl HandleConsistencyCheckerEvent for ConsistencyCheckerHealthUpdater {
 /// Consistency checker behavior when L1 commit data divergence is detected.
 // This is a temporary workaround for a bug that sometimes leads to incorrect L1 batch data returned by the server
 // (and thus persisted by external nodes). Eventually, we want to go back to bailing on L1 data mismatch;
-// for now, it's only enabled for the unit tests.
+// for now, it's only enabled for the unit tests and the verify-on-sync mode.
 #[derive(Debug)]
 enum L1DataMismatchBehavior {
-    #[cfg(test)]
     Bail,
     Log,
 }
@@ -206,6 +211,13 @@ impl LocalL1BatchCommitData {
             .map_or(true, |version| version.is_pre_boojum())
     }
 
+    /// Returns the hash of `StoredBatchInfo` for this batch as it should be persisted by the L1 diamond proxy contract.
+    /// The hash covers the state root hash and the batch commitment (which in turn covers pubdata commitments),
+    /// so matching it against L1 proves that the locally recomputed state is the one committed on L1.
+    fn stored_batch_hash(&self) -> H256 {
+        StoredBatchInfo::from(&self.l1_batch).hash()
+    }
+
     fn is_pre_shared_bridge(&self) -> bool {
         self.l1_batch
             .header
@@ -315,6 +327,8 @@ pub struct ConsistencyChecker {
     pool: ConnectionPool<Core>,
     health_check: ReactiveHealthCheck,
     commitment_mode: L1BatchCommitmentMode,
+    /// Whether the checker runs in the verify-on-sync mode; see [`Self::with_verify_on_sync()`].
+    verify_on_sync: bool,
 }
 
 impl ConsistencyChecker {
@@ -338,6 +352,7 @@ impl ConsistencyChecker {
             pool,
             health_check,
             commitment_mode,
+            verify_on_sync: false,
         })
     }
 
@@ -346,6 +361,23 @@ impl ConsistencyChecker {
         self
     }
 
+    /// Enables the verify-on-sync mode. In this mode, the checker additionally compares the batch hash stored
+    /// by the L1 diamond proxy contract (which covers the state root hash and pubdata commitments) with the hash
+    /// recomputed from local data, and stops with an error on any L1 data mismatch instead of skipping
+    /// the inconsistent batch. Since the last checked batch is persisted, it can be used to gate batch finality
+    /// locally (e.g., in the batch status updater).
+    ///
+    /// To make this gating sound, batches are checked sequentially starting from the batch after the last processed
+    /// one (or from the earliest local batch), rather than from a fixed number of batches before the last committed
+    /// batch. Only batches executed on L1 are checked, since committed batches may be reverted.
+    ///
+    /// Requires the diamond proxy address to be set.
+    pub fn with_verify_on_sync(mut self) -> Self {
+        self.verify_on_sync = true;
+        self.l1_data_mismatch_behavior = L1DataMismatchBehavior::Bail;
+        self
+    }
+
     /// Returns health check associated with this checker.
     pub fn health_check(&self) -> &ReactiveHealthCheck {
         &self.health_check
@@ -356,6 +388,13 @@ impl ConsistencyChecker {
         batch_number: L1BatchNumber,
         local: &LocalL1BatchCommitData,
     ) -> Result<(), CheckError> {
+        if self.verify_on_sync {
+            let last_executed_batch = self.last_executed_batch_on_l1().await?;
+            if batch_number > last_executed_batch {
+                return Err(CheckError::NotExecutedOnL1(last_executed_batch));
+            }
+        }
+
         let commit_tx_hash = local.commit_tx_hash;
         tracing::info!("Checking commit tx {commit_tx_hash} for L1 batch #{batch_number}");
 
@@ -440,7 +479,62 @@ impl ConsistencyChecker {
                 .map_err(CheckError::Validation)?;
         local
             .verify_commitment(&commitment)
-            .map_err(CheckError::Validation)
+            .map_err(CheckError::Validation)?;
+
+        if self.verify_on_sync {
+            self.check_stored_batch_hash(batch_number, local).await?;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of the last L1 batch executed on L1.
+    async fn last_executed_batch_on_l1(&self) -> Result<L1BatchNumber, CheckError> {
+        let diamond_proxy_addr = self
+            .diamond_proxy_addr
+            .context("diamond proxy address is required for verify-on-sync mode")
+            .map_err(CheckError::Internal)?;
+        let executed_batch_count: U256 = CallFunctionArgs::new("getTotalBatchesExecuted", ())
+            .for_contract(diamond_proxy_addr, &self.contract)
+            .call(self.l1_client.as_ref())
+            .await?;
+        let executed_batch_count = u32::try_from(executed_batch_count)
+            .map_err(|err| anyhow::anyhow!("executed L1 batch count overflow: {err}"))
+            .map_err(CheckError::Validation)?;
+        // Since the genesis batch is not committed on L1, the count coincides with the last executed batch number.
+        Ok(L1BatchNumber(executed_batch_count))
+    }
+
+    /// Checks that the batch hash stored by the L1 diamond proxy contract matches the locally recomputed one.
+    async fn check_stored_batch_hash(
+        &self,
+        batch_number: L1BatchNumber,
+        local: &LocalL1BatchCommitData,
+    ) -> Result<(), CheckError> {
+        if local.is_pre_boojum() {
+            tracing::debug!(
+                "Skipping stored batch hash check for pre-boojum L1 batch #{batch_number}"
+            );
+            return Ok(());
+        }
+        let diamond_proxy_addr = self
+            .diamond_proxy_addr
+            .context("diamond proxy address is required for verify-on-sync mode")
+            .map_err(CheckError::Internal)?;
+
+        let reference_hash: H256 =
+            CallFunctionArgs::new("storedBatchHash", U256::from(batch_number.0))
+                .for_contract(diamond_proxy_addr, &self.contract)
+                .call(self.l1_client.as_ref())
+                .await?;
+        let local_hash = local.stored_batch_hash();
+        if local_hash != reference_hash {
+            let err = anyhow::anyhow!(
+                "Locally recomputed stored batch hash differs from the one persisted on L1; \
+                 local: {local_hash:?}, reference: {reference_hash:?}"
+            );
+            return Err(CheckError::Validation(err));
+        }
+        Ok(())
     }
 
     /// All returned errors are validation errors.
@@ -525,10 +619,15 @@ impl ConsistencyChecker {
     pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         tracing::info!(
             "Starting consistency checker with diamond proxy contract: {:?}, sleep interval: {:?}, \
-             max historic L1 batches to check: {}",
+             max historic L1 batches to check: {}, verify-on-sync: {}",
             self.diamond_proxy_addr,
             self.sleep_interval,
-            self.max_batches_to_recheck
+            self.max_batches_to_recheck,
+            self.verify_on_sync
+        );
+        anyhow::ensure!(
+            !self.verify_on_sync || self.diamond_proxy_addr.is_some(),
+            "Diamond proxy address must be specified for verify-on-sync mode"
         );
         self.event_handler.initialize();
 
@@ -564,10 +663,15 @@ impl ConsistencyChecker {
             .last_committed_batch()
             .await?
             .unwrap_or(earliest_l1_batch_number);
-        let first_batch_to_check: L1BatchNumber = last_committed_batch
-            .0
-            .saturating_sub(self.max_batches_to_recheck)
-            .into();
+        let first_batch_to_check: L1BatchNumber = if self.verify_on_sync {
+            // All batches must be checked in order for the last processed batch to gate batch finality.
+            earliest_l1_batch_number
+        } else {
+            last_committed_batch
+                .0
+                .saturating_sub(self.max_batches_to_recheck)
+                .into()
+        };
 
         let last_processed_batch = self
             .pool
@@ -622,7 +726,6 @@ impl ConsistencyChecker {
                     self.event_handler
                         .report_inconsistent_batch(batch_number, &err);
                     match &self.l1_data_mismatch_behavior {
-                        #[cfg(test)]
                         L1DataMismatchBehavior::Bail => {
                             let context =
                                 format!("L1 batch #{batch_number} is inconsistent with L1");
@@ -633,6 +736,18 @@ impl ConsistencyChecker {
                         }
                     }
                 }
+                Err(CheckError::NotExecutedOnL1(last_executed_batch)) => {
+                    tracing::debug!(
+                        "L1 batch #{batch_number} is not executed on L1 yet (last executed batch: #{last_executed_batch}); \
+                         will retry after a delay"
+                    );
+                    if tokio::time::timeout(self.sleep_interval, stop_receiver.changed())
+                        .await
+                        .is_ok()
+                    {
+                        break;
+                    }
+                }
                 Err(err) if err.is_transient() => {
                     tracing::warn!(
                         "Transient error while verifying L1 batch #{batch_number}; will retry after a delay: {:#}",
@@ -1,5 +1,12 @@
 //! Tests for the consistency checker component.
-use std::{collections::HashMap, slice};
+use std::{
+    collections::HashMap,
+    slice,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+};
 
 use assert_matches::assert_matches;
 use once_cell::sync::Lazy;
@@ -107,6 +114,7 @@ pub(crate) fn create_mock_checker(
         pool,
         commitment_mode,
         health_check,
+        verify_on_sync: false,
     }
 }
 
@@ -795,3 +803,196 @@ async fn checker_detects_incorrect_tx_data_after_snapshot_recovery(
     )
     .await;
 }
+
+fn create_mock_ethereum_with_stored_batch_hashes(
+    stored_batch_hashes: HashMap<L1BatchNumber, H256>,
+    executed_batch_count: Arc<AtomicU32>,
+) -> MockEthereum {
+    let contract = zksync_contracts::hyperchain_contract();
+    let get_protocol_version_selector = contract
+        .function("getProtocolVersion")
+        .unwrap()
+        .short_signature();
+    let get_total_batches_executed_selector = contract
+        .function("getTotalBatchesExecuted")
+        .unwrap()
+        .short_signature();
+    let stored_batch_hash_fn = contract.function("storedBatchHash").unwrap().clone();
+
+    let mock = MockEthereum::builder().with_call_handler(move |call, _block_id| {
+        assert_eq!(call.to, Some(DIAMOND_PROXY_ADDR));
+        let call_data = &call.data.as_ref().unwrap().0;
+        if call_data[..4] == get_protocol_version_selector {
+            let packed_semver = ProtocolVersionId::latest().into_packed_semver_with_patch(0);
+            return ethabi::Token::Uint(packed_semver);
+        }
+        if call_data[..4] == get_total_batches_executed_selector {
+            let count = executed_batch_count.load(Ordering::SeqCst);
+            return ethabi::Token::Uint(count.into());
+        }
+
+        assert_eq!(call_data[..4], stored_batch_hash_fn.short_signature());
+        let args = stored_batch_hash_fn.decode_input(&call_data[4..]).unwrap();
+        let batch_number = args[0].clone().into_uint().unwrap().as_u32();
+        let hash = stored_batch_hashes[&L1BatchNumber(batch_number)];
+        ethabi::Token::FixedBytes(hash.as_bytes().to_vec())
+    });
+    mock.build()
+}
+
+#[test_casing(4, Product(([false, true], COMMITMENT_MODES)))]
+#[tokio::test]
+async fn checker_in_verify_on_sync_mode(
+    correct_stored_hashes: bool,
+    commitment_mode: L1BatchCommitmentMode,
+) {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let l1_batches: Vec<_> = (1..=3).map(create_l1_batch_with_metadata).collect();
+    let mut stored_batch_hashes: HashMap<_, _> = l1_batches
+        .iter()
+        .map(|batch| (batch.header.number, StoredBatchInfo::from(batch).hash()))
+        .collect();
+    if !correct_stored_hashes {
+        stored_batch_hashes.insert(L1BatchNumber(2), H256::repeat_byte(0xff));
+    }
+    let client =
+        create_mock_ethereum_with_stored_batch_hashes(stored_batch_hashes, Arc::new(3.into()));
+    prepare_batches_for_verify_on_sync(&mut storage, &client, &l1_batches, commitment_mode).await;
+
+    let (l1_batch_updates_sender, mut l1_batch_updates_receiver) = mpsc::unbounded_channel();
+    let checker = ConsistencyChecker {
+        event_handler: Box::new(l1_batch_updates_sender),
+        l1_data_mismatch_behavior: L1DataMismatchBehavior::Log,
+        ..create_mock_checker(client, pool.clone(), commitment_mode)
+    }
+    .with_verify_on_sync();
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let checker_task = tokio::spawn(checker.run(stop_receiver));
+
+    if correct_stored_hashes {
+        loop {
+            let checked_batch = l1_batch_updates_receiver.recv().await.unwrap();
+            if checked_batch == L1BatchNumber(3) {
+                break;
+            }
+        }
+        stop_sender.send_replace(true);
+        checker_task.await.unwrap().unwrap();
+    } else {
+        // The checker must stop with an error on the batch with the mismatched hash.
+        let err = tokio::time::timeout(Duration::from_secs(30), checker_task)
+            .await
+            .expect("Timed out waiting for checker to stop")
+            .unwrap()
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("#2"), "{err:#}");
+    }
+
+    let last_processed_batch = storage
+        .blocks_dal()
+        .get_consistency_checker_last_processed_l1_batch()
+        .await
+        .unwrap();
+    let expected_last_processed_batch = if correct_stored_hashes { 3 } else { 1 };
+    assert_eq!(
+        last_processed_batch,
+        L1BatchNumber(expected_last_processed_batch)
+    );
+}
+
+/// Commits all provided batches on L1 in a single transaction and persists them locally.
+async fn prepare_batches_for_verify_on_sync(
+    storage: &mut Connection<'_, Core>,
+    client: &MockEthereum,
+    l1_batches: &[L1BatchWithMetadata],
+    commitment_mode: L1BatchCommitmentMode,
+) {
+    let input_data = build_commit_tx_input_data(l1_batches, commitment_mode);
+    let signed_tx = client
+        .sign_prepared_tx(
+            input_data,
+            VALIDATOR_TIMELOCK_ADDR,
+            Options {
+                nonce: Some(0.into()),
+                ..Options::default()
+            },
+        )
+        .unwrap();
+    client.as_ref().send_raw_tx(signed_tx.raw_tx).await.unwrap();
+    client
+        .execute_tx(signed_tx.hash, true, 1)
+        .with_logs(l1_batches.iter().map(l1_batch_commit_log).collect());
+    let commit_tx_hash_by_l1_batch: HashMap<_, _> = l1_batches
+        .iter()
+        .map(|batch| (batch.header.number, signed_tx.hash))
+        .collect();
+
+    for save_action in SAVE_ACTION_MAPPERS[0].1(l1_batches) {
+        save_action
+            .apply(storage, &commit_tx_hash_by_l1_batch)
+            .await;
+    }
+}
+
+#[test_casing(2, COMMITMENT_MODES)]
+#[tokio::test]
+async fn checker_in_verify_on_sync_mode_checks_all_executed_batches(
+    commitment_mode: L1BatchCommitmentMode,
+) {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    // The last committed batch is far ahead of `max_batches_to_recheck`.
+    let l1_batches: Vec<_> = (1..=15).map(create_l1_batch_with_metadata).collect();
+    let stored_batch_hashes = l1_batches
+        .iter()
+        .map(|batch| (batch.header.number, StoredBatchInfo::from(batch).hash()))
+        .collect();
+    let executed_batch_count = Arc::new(AtomicU32::new(12));
+    let client = create_mock_ethereum_with_stored_batch_hashes(
+        stored_batch_hashes,
+        executed_batch_count.clone(),
+    );
+    prepare_batches_for_verify_on_sync(&mut storage, &client, &l1_batches, commitment_mode).await;
+
+    let (l1_batch_updates_sender, mut l1_batch_updates_receiver) = mpsc::unbounded_channel();
+    let checker = ConsistencyChecker {
+        event_handler: Box::new(l1_batch_updates_sender),
+        max_batches_to_recheck: 10,
+        ..create_mock_checker(client, pool.clone(), commitment_mode)
+    }
+    .with_verify_on_sync();
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let checker_task = tokio::spawn(checker.run(stop_receiver));
+
+    // All batches must be checked, starting from the earliest one.
+    for expected_batch in 1..=12 {
+        let checked_batch = l1_batch_updates_receiver.recv().await.unwrap();
+        assert_eq!(checked_batch, L1BatchNumber(expected_batch));
+    }
+    // Batches not executed on L1 must not be marked as checked.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(l1_batch_updates_receiver.try_recv().is_err());
+    let last_processed_batch = storage
+        .blocks_dal()
+        .get_consistency_checker_last_processed_l1_batch()
+        .await
+        .unwrap();
+    assert_eq!(last_processed_batch, L1BatchNumber(12));
+
+    executed_batch_count.store(15, Ordering::SeqCst);
+    for expected_batch in 13..=15 {
+        let checked_batch = l1_batch_updates_receiver.recv().await.unwrap();
+        assert_eq!(checked_batch, L1BatchNumber(expected_batch));
+    }
+    stop_sender.send_replace(true);
+    checker_task.await.unwrap().unwrap();
+}
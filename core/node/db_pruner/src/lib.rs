@@ -5,15 +5,19 @@ use std::{sync::Arc, time::Duration};
 use anyhow::Context as _;
 use serde::{Deserialize, Serialize};
 use tokio::sync::watch;
-use zksync_dal::{pruning_dal::PruningInfo, Connection, ConnectionPool, Core, CoreDal};
+use zksync_dal::{
+    pruning_dal::PruningInfo, watermarks_dal::WatermarkComponent, Connection, ConnectionPool, Core,
+    CoreDal,
+};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_types::{L1BatchNumber, L2BlockNumber};
 
 use self::{
     metrics::{MetricPruneType, METRICS},
     prune_conditions::{
-        ConsistencyCheckerProcessedBatch, L1BatchExistsCondition, L1BatchOlderThanPruneCondition,
-        NextL1BatchHasMetadataCondition, NextL1BatchWasExecutedCondition, PruneCondition,
+        ComponentWatermarksReachedCondition, ConsistencyCheckerProcessedBatch,
+        L1BatchExistsCondition, L1BatchOlderThanPruneCondition, NextL1BatchHasMetadataCondition,
+        NextL1BatchWasExecutedCondition, PruneCondition,
     },
 };
 
@@ -33,6 +37,10 @@ pub struct DbPrunerConfig {
     /// Minimum age of an L1 batch in order for it to be eligible for pruning. Setting this to zero
     /// will effectively disable this pruning criterion.
     pub minimum_l1_batch_age: Duration,
+    /// Components that must publish a watermark (i.e., process an L1 batch) before the batch can be pruned.
+    /// Components missing from this list are ignored even if they have published watermarks before, so that
+    /// a disabled component doesn't block pruning.
+    pub watermark_components: Vec<WatermarkComponent>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -94,6 +102,12 @@ impl DbPruner {
                 pool: connection_pool.clone(),
             }),
         ];
+        if !config.watermark_components.is_empty() {
+            conditions.push(Arc::new(ComponentWatermarksReachedCondition {
+                pool: connection_pool.clone(),
+                components: config.watermark_components.clone(),
+            }));
+        }
         if config.minimum_l1_batch_age > Duration::ZERO {
             // Do not add a condition if it's trivial in order to not clutter logs.
             conditions.push(Arc::new(L1BatchOlderThanPruneCondition {
@@ -20,6 +20,7 @@ enum PrunedEntityType {
     Event,
     L2ToL1Log,
     CallTrace,
+    InternalTransfer,
 }
 
 const ENTITY_COUNT_BUCKETS: Buckets = Buckets::values(&[
@@ -48,6 +49,7 @@ impl DbPrunerMetrics {
             deleted_storage_logs_from_pruned_batches,
             deleted_events,
             deleted_call_traces,
+            deleted_internal_transfers,
             deleted_l2_to_l1_logs,
         } = stats;
         let deleted_storage_logs =
@@ -56,7 +58,8 @@ impl DbPrunerMetrics {
             "Performed pruning of database, deleted {deleted_l1_batches} L1 batches, {deleted_l2_blocks} L2 blocks, \
              {deleted_storage_logs} storage logs ({deleted_storage_logs_from_pruned_batches} from pruned batches + \
              {deleted_storage_logs_from_past_batches} from past batches), \
-             {deleted_events} events, {deleted_call_traces} call traces, {deleted_l2_to_l1_logs} L2-to-L1 logs"
+             {deleted_events} events, {deleted_call_traces} call traces, {deleted_internal_transfers} internal transfers, \
+             {deleted_l2_to_l1_logs} L2-to-L1 logs"
         );
 
         self.deleted_entities[&PrunedEntityType::L1Batch].observe(deleted_l1_batches);
@@ -68,6 +71,8 @@ impl DbPrunerMetrics {
         self.deleted_entities[&PrunedEntityType::Event].observe(deleted_events);
         self.deleted_entities[&PrunedEntityType::L2ToL1Log].observe(deleted_l2_to_l1_logs);
         self.deleted_entities[&PrunedEntityType::CallTrace].observe(deleted_call_traces);
+        self.deleted_entities[&PrunedEntityType::InternalTransfer]
+            .observe(deleted_internal_transfers);
     }
 }
 
@@ -2,7 +2,7 @@ use std::{fmt, time::Duration};
 
 use async_trait::async_trait;
 use chrono::Utc;
-use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_dal::{watermarks_dal::WatermarkComponent, ConnectionPool, Core, CoreDal};
 use zksync_types::L1BatchNumber;
 
 #[async_trait]
@@ -127,6 +127,35 @@ impl PruneCondition for L1BatchExistsCondition {
     }
 }
 
+#[derive(Debug)]
+pub(super) struct ComponentWatermarksReachedCondition {
+    pub pool: ConnectionPool<Core>,
+    pub components: Vec<WatermarkComponent>,
+}
+
+impl fmt::Display for ComponentWatermarksReachedCondition {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "L1 batch was processed by components {:?}",
+            self.components
+        )
+    }
+}
+
+#[async_trait]
+impl PruneCondition for ComponentWatermarksReachedCondition {
+    async fn is_batch_prunable(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<bool> {
+        let mut storage = self.pool.connection_tagged("db_pruner").await?;
+        let min_watermark = storage
+            .watermarks_dal()
+            .get_min_watermark(&self.components)
+            .await?;
+        // A component that hasn't published its watermark yet hasn't processed any batches.
+        Ok(min_watermark.map_or(false, |watermark| l1_batch_number <= watermark))
+    }
+}
+
 #[derive(Debug)]
 pub(super) struct ConsistencyCheckerProcessedBatch {
     pub pool: ConnectionPool<Core>,
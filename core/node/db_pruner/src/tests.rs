@@ -75,6 +75,7 @@ async fn is_l1_batch_prunable_works() {
             removal_delay: Duration::ZERO,
             pruned_batch_chunk_size: 1,
             minimum_l1_batch_age: Duration::ZERO,
+            watermark_components: vec![],
         },
         ConnectionPool::test_pool().await,
         vec![failing_check, other_failing_check],
@@ -148,6 +149,7 @@ async fn hard_pruning_ignores_conditions_checks() {
             removal_delay: Duration::ZERO,
             pruned_batch_chunk_size: 5,
             minimum_l1_batch_age: Duration::ZERO,
+            watermark_components: vec![],
         },
         pool.clone(),
         vec![nothing_prunable_check],
@@ -187,6 +189,7 @@ async fn pruner_catches_up_with_hard_pruning_up_to_soft_pruning_boundary_ignorin
             removal_delay: Duration::ZERO,
             pruned_batch_chunk_size: 5,
             minimum_l1_batch_age: Duration::ZERO,
+            watermark_components: vec![],
         },
         pool.clone(),
         vec![], //No checks, so every batch is prunable
@@ -235,6 +238,7 @@ async fn unconstrained_pruner_with_fresh_database() {
             removal_delay: Duration::ZERO,
             pruned_batch_chunk_size: 3,
             minimum_l1_batch_age: Duration::ZERO,
+            watermark_components: vec![],
         },
         pool.clone(),
         vec![], //No checks, so every batch is prunable
@@ -285,6 +289,7 @@ async fn pruning_blocked_after_first_chunk() {
             removal_delay: Duration::ZERO,
             pruned_batch_chunk_size: 3,
             minimum_l1_batch_age: Duration::ZERO,
+            watermark_components: vec![],
         },
         pool.clone(),
         vec![first_chunk_prunable_check],
@@ -335,6 +340,7 @@ async fn pruner_is_resistant_to_errors() {
             removal_delay: Duration::ZERO,
             pruned_batch_chunk_size: 3,
             minimum_l1_batch_age: Duration::ZERO,
+            watermark_components: vec![],
         },
         pool.clone(),
         vec![erroneous_condition],
@@ -492,6 +498,43 @@ async fn real_conditions_work_as_expected() {
     );
 }
 
+#[tokio::test]
+async fn component_watermarks_condition() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    let condition = ComponentWatermarksReachedCondition {
+        pool: pool.clone(),
+        components: vec![
+            WatermarkComponent::Tree,
+            WatermarkComponent::ProtectiveReads,
+        ],
+    };
+
+    // Components that haven't published their watermarks block pruning.
+    assert!(!condition.is_batch_prunable(L1BatchNumber(1)).await.unwrap());
+    storage
+        .watermarks_dal()
+        .set_watermark(WatermarkComponent::Tree, L1BatchNumber(3))
+        .await
+        .unwrap();
+    assert!(!condition.is_batch_prunable(L1BatchNumber(1)).await.unwrap());
+
+    storage
+        .watermarks_dal()
+        .set_watermark(WatermarkComponent::ProtectiveReads, L1BatchNumber(2))
+        .await
+        .unwrap();
+    // Components not in the list must be ignored.
+    storage
+        .watermarks_dal()
+        .set_watermark(WatermarkComponent::Commitments, L1BatchNumber(0))
+        .await
+        .unwrap();
+    assert!(condition.is_batch_prunable(L1BatchNumber(1)).await.unwrap());
+    assert!(condition.is_batch_prunable(L1BatchNumber(2)).await.unwrap());
+    assert!(!condition.is_batch_prunable(L1BatchNumber(3)).await.unwrap());
+}
+
 #[tokio::test]
 async fn pruner_with_real_conditions() {
     let pool = ConnectionPool::<Core>::test_pool().await;
@@ -504,6 +547,7 @@ async fn pruner_with_real_conditions() {
         removal_delay: Duration::from_millis(10), // non-zero to not have a tight loop in `DbPruner::run()`
         pruned_batch_chunk_size: 1,
         minimum_l1_batch_age: Duration::ZERO,
+        watermark_components: vec![],
     };
     let pruner = DbPruner::new(config, pool.clone());
     let mut health_check = pruner.health_check();
@@ -560,6 +604,7 @@ async fn pruning_iteration_timely_shuts_down() {
             removal_delay: Duration::MAX, // intentionally chosen so that pruning iterations stuck
             pruned_batch_chunk_size: 3,
             minimum_l1_batch_age: Duration::ZERO,
+            watermark_components: vec![],
         },
         pool.clone(),
         vec![], //No checks, so every batch is prunable
@@ -589,6 +634,7 @@ async fn pruner_timely_shuts_down() {
             removal_delay: Duration::MAX, // intentionally chosen so that pruning iterations stuck
             pruned_batch_chunk_size: 3,
             minimum_l1_batch_age: Duration::ZERO,
+            watermark_components: vec![],
         },
         pool.clone(),
         vec![], //No checks, so every batch is prunable
@@ -21,6 +21,7 @@ use super::{
         DataSizeCriterion, GasCriterion, L1BatchPublishCriterion, NumberCriterion,
         TimestampDeadlineCriterion,
     },
+    withdrawal_limiter::WithdrawalLimiter,
 };
 
 #[derive(Debug)]
@@ -38,6 +39,7 @@ pub struct Aggregator {
     operate_4844_mode: bool,
     pubdata_da: PubdataDA,
     commitment_mode: L1BatchCommitmentMode,
+    withdrawal_limiter: Option<WithdrawalLimiter>,
 }
 
 impl Aggregator {
@@ -109,9 +111,16 @@ impl Aggregator {
             operate_4844_mode,
             pubdata_da,
             commitment_mode,
+            withdrawal_limiter: None,
         }
     }
 
+    /// Restricts execution of L1 batches with the provided withdrawal limiter.
+    pub fn with_withdrawal_limiter(mut self, limiter: WithdrawalLimiter) -> Self {
+        self.withdrawal_limiter = Some(limiter);
+        self
+    }
+
     pub async fn get_next_ready_operation(
         &mut self,
         storage: &mut Connection<'_, Core>,
@@ -169,11 +178,16 @@ impl Aggregator {
             .config
             .l1_batch_min_age_before_execute_seconds
             .map(|age| unix_timestamp_ms() - age * 1_000);
-        let ready_for_execute_batches = storage
+        let mut ready_for_execute_batches = storage
             .blocks_dal()
             .get_ready_for_execute_l1_batches(limit, max_l1_batch_timestamp_millis)
             .await
             .unwrap();
+        if let Some(limiter) = &mut self.withdrawal_limiter {
+            ready_for_execute_batches = limiter
+                .limit_l1_batches_to_execute(storage, ready_for_execute_batches)
+                .await;
+        }
         let l1_batches = extract_ready_subrange(
             storage,
             &mut self.execute_criteria,
@@ -1,12 +1,28 @@
+use zksync_dal::DalError;
 use zksync_eth_client::{ContractCallError, EnrichedClientError};
-use zksync_types::web3::contract;
+use zksync_types::{web3::contract, L1BatchNumber};
 
 #[derive(Debug, thiserror::Error)]
 pub enum EthSenderError {
+    #[error("Database error: {0}")]
+    Dal(#[from] DalError),
     #[error("Ethereum gateway error: {0}")]
     EthereumGateway(#[from] EnrichedClientError),
     #[error("Contract call error: {0}")]
     ContractCall(#[from] ContractCallError),
     #[error("Token parsing error: {0}")]
     Parse(#[from] contract::Error),
+    #[error("Signing error: {0}")]
+    Signing(String),
+    #[error("gas limit {gas_limit} is insufficient to forward a call requiring {min_gas} gas")]
+    InsufficientForwardedGas { gas_limit: u32, min_gas: u32 },
+    #[error(
+        "timestamp of L1 batch #{l1_batch_number} is {drift_sec}s ahead of the latest L1 block timestamp, \
+         which exceeds the allowed drift of {max_drift_sec}s"
+    )]
+    TimestampDrift {
+        l1_batch_number: L1BatchNumber,
+        drift_sec: u64,
+        max_drift_sec: u64,
+    },
 }
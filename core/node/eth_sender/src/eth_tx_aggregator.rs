@@ -62,6 +62,9 @@ pub struct EthTxAggregator {
     /// address.
     custom_commit_sender_addr: Option<Address>,
     pool: ConnectionPool<Core>,
+    /// L1 verifier config and protocol version for which a verifier config mismatch was last reported.
+    /// Used to report each mismatch once rather than on every loop iteration.
+    reported_l1_verifier_mismatch: Option<(ProtocolVersionId, L1VerifierConfig)>,
 }
 
 struct TxData {
@@ -110,6 +113,7 @@ impl EthTxAggregator {
             rollup_chain_id,
             custom_commit_sender_addr,
             pool,
+            reported_l1_verifier_mismatch: None,
         }
     }
 
@@ -367,6 +371,9 @@ impl EthTxAggregator {
             params: verifier_params,
             recursion_scheduler_level_vk_hash,
         };
+        self.check_l1_verifier_config(storage, protocol_version_id, l1_verifier_config)
+            .await?;
+
         if let Some(agg_op) = self
             .aggregator
             .get_next_ready_operation(
@@ -377,6 +384,9 @@ impl EthTxAggregator {
             )
             .await
         {
+            if let AggregatedOperation::Commit(_, l1_batches, _) = &agg_op {
+                self.check_l1_batch_timestamps(l1_batches).await?;
+            }
             let tx = self
                 .save_eth_tx(storage, &agg_op, contracts_are_pre_shared_bridge)
                 .await?;
@@ -385,6 +395,74 @@ impl EthTxAggregator {
         Ok(())
     }
 
+    /// Checks that verification keys used by the L1 verifier correspond to one of the patches of the current
+    /// protocol version. A mismatch means that proofs produced by provers will be rejected on L1.
+    /// The mismatch is logged once when it's first observed and after it's resolved.
+    async fn check_l1_verifier_config(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        protocol_version_id: ProtocolVersionId,
+        l1_verifier_config: L1VerifierConfig,
+    ) -> Result<(), EthSenderError> {
+        let known_keys = storage
+            .protocol_versions_web3_dal()
+            .get_verification_keys_hashes(Some(protocol_version_id as u16))
+            .await?;
+        let is_known = known_keys
+            .iter()
+            .any(|keys| keys.verification_keys_hashes == l1_verifier_config);
+        if is_known {
+            if let Some((_, prev_config)) = self.reported_l1_verifier_mismatch.take() {
+                tracing::info!(
+                    "Verification keys used by the L1 verifier ({l1_verifier_config:?}) match keys known \
+                     for protocol version {protocol_version_id:?}; previously mismatched config: {prev_config:?}"
+                );
+            }
+            METRICS.l1_verifier_config_mismatch.set(0);
+        } else {
+            let mismatch = (protocol_version_id, l1_verifier_config);
+            if self.reported_l1_verifier_mismatch != Some(mismatch) {
+                tracing::error!(
+                    "Verification keys used by the L1 verifier ({l1_verifier_config:?}) don't match any keys \
+                     known for protocol version {protocol_version_id:?}: {known_keys:?}"
+                );
+                self.reported_l1_verifier_mismatch = Some(mismatch);
+            }
+            METRICS.l1_verifier_config_mismatch.set(1);
+        }
+        Ok(())
+    }
+
+    /// Checks that timestamps of committed L1 batches are not too far ahead of the latest L1 block timestamp.
+    /// Otherwise, the commitment would be rejected by the L1 contract.
+    async fn check_l1_batch_timestamps(
+        &self,
+        l1_batches: &[L1BatchWithMetadata],
+    ) -> Result<(), EthSenderError> {
+        let Some(max_drift_sec) = self.config.max_timestamp_drift_from_l1_sec else {
+            return Ok(());
+        };
+        let Some(latest_block) = self.eth_client.block(BlockNumber::Latest.into()).await? else {
+            tracing::warn!("Latest L1 block is missing; skipping L1 batch timestamp checks");
+            return Ok(());
+        };
+        let l1_timestamp = latest_block.timestamp.as_u64();
+
+        for batch in l1_batches {
+            let drift_sec = batch.header.timestamp.saturating_sub(l1_timestamp);
+            if drift_sec > max_drift_sec {
+                let err = EthSenderError::TimestampDrift {
+                    l1_batch_number: batch.header.number,
+                    drift_sec,
+                    max_drift_sec,
+                };
+                tracing::error!("Not committing L1 batches: {err}");
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
     async fn report_eth_tx_saving(
         storage: &mut Connection<'_, Core>,
         aggregated_op: &AggregatedOperation,
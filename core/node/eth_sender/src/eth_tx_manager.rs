@@ -19,7 +19,11 @@ use zksync_types::{
 };
 use zksync_utils::time::seconds_since_epoch;
 
-use super::{metrics::METRICS, EthSenderError};
+use super::{
+    metrics::METRICS,
+    submission::{DirectSubmission, L1Call, L1TxSubmissionStrategy},
+    EthSenderError,
+};
 
 #[derive(Debug)]
 struct EthFee {
@@ -55,6 +59,9 @@ pub struct EthTxManager {
     /// If the operator is in 4844 mode this is sent to `Some` and used to send
     /// commit transactions.
     ethereum_gateway_blobs: Option<Box<dyn BoundEthInterface>>,
+    /// Strategy for submitting transactions via `ethereum_gateway`. Transactions sent via `ethereum_gateway_blobs`
+    /// are always submitted directly.
+    submission_strategy: Arc<dyn L1TxSubmissionStrategy>,
     config: SenderConfig,
     gas_adjuster: Arc<dyn L1TxParamsProvider>,
     pool: ConnectionPool<Core>,
@@ -72,12 +79,21 @@ impl EthTxManager {
             ethereum_gateway: ethereum_gateway.for_component("eth_tx_manager"),
             ethereum_gateway_blobs: ethereum_gateway_blobs
                 .map(|eth| eth.for_component("eth_tx_manager")),
+            submission_strategy: Arc::new(DirectSubmission),
             config,
             gas_adjuster,
             pool,
         }
     }
 
+    /// Sets the strategy for submitting L1 transactions. By default, transactions are submitted directly.
+    #[must_use]
+    pub fn with_submission_strategy(mut self, strategy: Arc<dyn L1TxSubmissionStrategy>) -> Self {
+        tracing::info!("Using L1 transaction submission strategy: {strategy:?}");
+        self.submission_strategy = strategy;
+        self
+    }
+
     pub(crate) fn query_client(&self) -> &DynClient<L1> {
         (*self.ethereum_gateway).as_ref()
     }
@@ -264,8 +280,18 @@ impl EthTxManager {
             None
         };
 
+        let call = self.prepare_call(tx).await.map_err(|err| {
+            tracing::warn!("Failed preparing L1 call for tx {}: {err}", tx.id);
+            err
+        })?;
         let mut signed_tx = self
-            .sign_tx(tx, base_fee_per_gas, priority_fee_per_gas, blob_gas_price)
+            .sign_tx(
+                tx,
+                call,
+                base_fee_per_gas,
+                priority_fee_per_gas,
+                blob_gas_price,
+            )
             .await;
 
         if let Some(blob_sidecar) = &tx.blob_sidecar {
@@ -538,31 +564,43 @@ impl EthTxManager {
         Ok(None)
     }
 
+    /// Chooses the signing gateway. Uses a custom one in case the operator is in 4844 mode
+    /// and the operation at hand is Commit; then the optional gateway is used to send this transaction
+    /// from a custom sender account.
+    fn signing_gateway(&self, tx: &EthTx) -> (&dyn BoundEthInterface, bool) {
+        match &self.ethereum_gateway_blobs {
+            Some(blobs_gateway) if tx.tx_type == AggregatedActionType::Commit => {
+                (blobs_gateway.as_ref(), true)
+            }
+            _ => (self.ethereum_gateway.as_ref(), false),
+        }
+    }
+
+    async fn prepare_call(&self, tx: &EthTx) -> Result<L1Call, EthSenderError> {
+        let (gateway, is_blobs_gateway) = self.signing_gateway(tx);
+        let gas_limit = self.config.max_aggregated_tx_gas;
+        if is_blobs_gateway {
+            DirectSubmission.prepare_call(gateway, tx, gas_limit).await
+        } else {
+            self.submission_strategy
+                .prepare_call(gateway, tx, gas_limit)
+                .await
+        }
+    }
+
     async fn sign_tx(
         &self,
         tx: &EthTx,
+        call: L1Call,
         base_fee_per_gas: u64,
         priority_fee_per_gas: u64,
         blob_gas_price: Option<U256>,
     ) -> SignedCallResult {
-        // Chose the signing gateway. Use a custom one in case
-        // the operator is in 4844 mode and the operation at hand is Commit.
-        // then the optional gateway is used to send this transaction from a
-        // custom sender account.
-        let signing_gateway = if let Some(blobs_gateway) = self.ethereum_gateway_blobs.as_ref() {
-            if tx.tx_type == AggregatedActionType::Commit {
-                blobs_gateway
-            } else {
-                &self.ethereum_gateway
-            }
-        } else {
-            &self.ethereum_gateway
-        };
-
+        let (signing_gateway, _) = self.signing_gateway(tx);
         signing_gateway
             .sign_prepared_tx_for_addr(
-                tx.raw_tx.clone(),
-                tx.contract_address,
+                call.calldata,
+                call.contract_address,
                 Options::with(|opt| {
                     // TODO Calculate gas for every operation SMA-1436
                     opt.gas = Some(self.config.max_aggregated_tx_gas.into());
@@ -659,11 +697,6 @@ impl EthTxManager {
         tx: &EthTx,
         tx_status: ExecutedTxStatus,
     ) {
-        storage
-            .eth_sender_dal()
-            .mark_failed_transaction(tx.id)
-            .await
-            .unwrap();
         let failure_reason = self
             .query_client()
             .failure_reason(tx_status.receipt.transaction_hash)
@@ -671,6 +704,15 @@ impl EthTxManager {
             .expect(
                 "Tx is already failed, it's safe to fail here and apply the status on the next run",
             );
+        // The reason is persisted so that it's available to operators via the admin API.
+        let revert_reason = failure_reason
+            .as_ref()
+            .map(|info| info.revert_reason.as_str());
+        storage
+            .eth_sender_dal()
+            .mark_failed_transaction(tx.id, revert_reason)
+            .await
+            .unwrap();
 
         tracing::error!(
             "Eth tx failed {:?}, {:?}, failure reason {:?}",
@@ -734,6 +776,10 @@ impl EthTxManager {
 
     pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         let pool = self.pool.clone();
+        self.submission_strategy
+            .validate(self.ethereum_gateway.as_ref())
+            .await
+            .context("L1 transaction submission strategy is misconfigured")?;
         {
             let l1_block_numbers = self
                 .get_l1_block_numbers()
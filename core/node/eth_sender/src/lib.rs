@@ -5,13 +5,19 @@ mod eth_tx_aggregator;
 mod eth_tx_manager;
 mod metrics;
 mod publish_criterion;
+mod submission;
 mod utils;
+mod withdrawal_limiter;
 mod zksync_functions;
 
 #[cfg(test)]
 mod tests;
 
 pub use self::{
-    aggregator::Aggregator, error::EthSenderError, eth_tx_aggregator::EthTxAggregator,
+    aggregator::Aggregator,
+    error::EthSenderError,
+    eth_tx_aggregator::EthTxAggregator,
     eth_tx_manager::EthTxManager,
+    submission::{DirectSubmission, Erc2771ForwarderSubmission, L1Call, L1TxSubmissionStrategy},
+    withdrawal_limiter::WithdrawalLimiter,
 };
@@ -2,7 +2,10 @@
 
 use std::{fmt, time::Duration};
 
-use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
+    Metrics,
+};
 use zksync_dal::{Connection, Core, CoreDal};
 use zksync_shared_metrics::{BlockL1Stage, BlockStage, APP_METRICS};
 use zksync_types::{aggregated_operations::AggregatedActionType, eth_sender::EthTx};
@@ -98,6 +101,15 @@ pub(super) struct EthSenderMetrics {
     pub l1_blocks_waited_in_mempool: Family<ActionTypeLabel, Histogram<u64>>,
     /// Number of L1 batches aggregated for publishing with a specific reason.
     pub block_aggregation_reason: Family<AggregationReasonLabels, Counter>,
+    /// Share of the withdrawal limit used by L1 batches executed within the limiter window, per L2 token.
+    #[metrics(labels = ["token"])]
+    pub withdrawal_limit_usage: LabeledFamily<String, Gauge<f64>>,
+    /// Number of times an L1 batch was found to exceed the withdrawal limit for a certain L2 token.
+    #[metrics(labels = ["token"])]
+    pub withdrawal_limit_exceeded: LabeledFamily<String, Counter>,
+    /// Set to 1 if verification keys used by the L1 verifier don't match any keys known for the current
+    /// protocol version, and to 0 otherwise.
+    pub l1_verifier_config_mismatch: Gauge<u64>,
 }
 
 impl EthSenderMetrics {
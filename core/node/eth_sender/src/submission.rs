@@ -0,0 +1,639 @@
+//! Strategies for submitting L1 transactions prepared by [`EthTxManager`](crate::EthTxManager).
+
+use std::{fmt, time::Duration};
+
+use async_trait::async_trait;
+use tokio::sync::OnceCell;
+use zksync_eth_client::{
+    clients::{DynClient, L1},
+    BoundEthInterface, EthInterface,
+};
+use zksync_types::{
+    eth_sender::EthTx,
+    ethabi::{self, ParamType, Token},
+    web3::{self, contract, keccak256, BlockNumber},
+    Address, K256PrivateKey, L1ChainId, PackedEthSignature, H256, U256,
+};
+use zksync_utils::time::seconds_since_epoch;
+
+use crate::EthSenderError;
+
+/// Call sent in an L1 transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct L1Call {
+    pub contract_address: Address,
+    pub calldata: Vec<u8>,
+}
+
+/// Strategy for submitting L1 transactions. For each sending attempt of an [`EthTx`], the strategy determines
+/// the call sent from the account of the Ethereum gateway, which pays for L1 gas.
+#[async_trait]
+pub trait L1TxSubmissionStrategy: 'static + fmt::Debug + Send + Sync {
+    /// Checks that the strategy is configured correctly, e.g. that L1 contracts accept calls submitted with it.
+    /// Called once before [`EthTxManager`](crate::EthTxManager) starts sending transactions; if this method
+    /// returns an error, the manager doesn't start.
+    async fn validate(&self, _gateway: &dyn BoundEthInterface) -> Result<(), EthSenderError> {
+        Ok(())
+    }
+
+    /// Prepares the call for the specified transaction. `gas_limit` is the gas limit of the L1 transaction.
+    async fn prepare_call(
+        &self,
+        gateway: &dyn BoundEthInterface,
+        tx: &EthTx,
+        gas_limit: u32,
+    ) -> Result<L1Call, EthSenderError>;
+}
+
+/// Default submission strategy: the gateway account calls the target contract directly.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DirectSubmission;
+
+#[async_trait]
+impl L1TxSubmissionStrategy for DirectSubmission {
+    async fn prepare_call(
+        &self,
+        _gateway: &dyn BoundEthInterface,
+        tx: &EthTx,
+        _gas_limit: u32,
+    ) -> Result<L1Call, EthSenderError> {
+        Ok(L1Call {
+            contract_address: tx.contract_address,
+            calldata: tx.raw_tx.clone(),
+        })
+    }
+}
+
+/// Submission strategy relaying calls via an [ERC-2771] forwarder contract compatible with OpenZeppelin's
+/// `ERC2771Forwarder`. Each call is wrapped into a forward request signed by the operator, while the L1 transaction
+/// is sent and paid for by the gateway account (the fee payer). This allows the operator account, which is authorized
+/// in L1 contracts, to hold minimal ETH. Target contracts must trust the forwarder.
+///
+/// Forward request nonces are assigned assuming that the fee payer account only sends transactions
+/// created by this strategy. Calls can only be forwarded to the target contracts specified on creation;
+/// these contracts are checked to trust the forwarder when the strategy is validated.
+///
+/// [ERC-2771]: https://eips.ethereum.org/EIPS/eip-2771
+#[derive(Debug)]
+pub struct Erc2771ForwarderSubmission {
+    forwarder_address: Address,
+    signer: K256PrivateKey,
+    l1_chain_id: L1ChainId,
+    target_contracts: Vec<Address>,
+    /// EIP-712 domain separator of the forwarder, fetched from the forwarder on first use.
+    domain_separator: OnceCell<H256>,
+}
+
+impl Erc2771ForwarderSubmission {
+    const FORWARD_REQUEST_TYPE: &'static str =
+        "ForwardRequest(address from,address to,uint256 value,\
+         uint256 gas,uint256 nonce,uint48 deadline,bytes data)";
+    const EXECUTE_SIGNATURE: &'static str =
+        "execute((address,address,uint256,uint256,uint48,bytes,bytes))";
+    /// Gas spent by the forwarder in addition to the forwarded call (signature verification, nonce update etc.).
+    const FORWARDER_GAS_OVERHEAD: u32 = 50_000;
+    /// Validity period of forward requests. Each sending attempt creates a new request, so this only needs to cover
+    /// the time a transaction spends in the mempool.
+    const REQUEST_TTL: Duration = Duration::from_secs(24 * 3_600);
+
+    /// Creates a strategy forwarding calls to the specified `target_contracts` (normally, the validator timelock).
+    pub fn new(
+        forwarder_address: Address,
+        signer: K256PrivateKey,
+        l1_chain_id: L1ChainId,
+        target_contracts: Vec<Address>,
+    ) -> Self {
+        Self {
+            forwarder_address,
+            signer,
+            l1_chain_id,
+            target_contracts,
+            domain_separator: OnceCell::new(),
+        }
+    }
+
+    /// Returns the address signing forward requests.
+    pub fn signer_address(&self) -> Address {
+        self.signer.address()
+    }
+
+    async fn call_forwarder(
+        &self,
+        client: &DynClient<L1>,
+        signature: &str,
+        args: &[Token],
+    ) -> Result<Vec<u8>, EthSenderError> {
+        let mut data = function_selector(signature).to_vec();
+        data.extend(ethabi::encode(args));
+        let request = web3::CallRequest {
+            to: Some(self.forwarder_address),
+            data: Some(data.into()),
+            ..web3::CallRequest::default()
+        };
+        let output = client.call_contract_function(request, None).await?;
+        Ok(output.0)
+    }
+
+    /// Checks that `target` trusts the forwarder via ERC-2771 `isTrustedForwarder(address)`. Target contracts
+    /// authorize operators using the message sender, so if the forwarder isn't trusted, all forwarded calls revert.
+    async fn check_trusted_forwarder(
+        &self,
+        client: &DynClient<L1>,
+        target: Address,
+    ) -> Result<(), EthSenderError> {
+        let mut data = function_selector("isTrustedForwarder(address)").to_vec();
+        data.extend(ethabi::encode(&[Token::Address(self.forwarder_address)]));
+        let request = web3::CallRequest {
+            to: Some(target),
+            data: Some(data.into()),
+            ..web3::CallRequest::default()
+        };
+        let output = client.call_contract_function(request, None).await?;
+        let is_trusted = ethabi::decode(&[ParamType::Bool], &output.0)
+            .ok()
+            .and_then(|tokens| tokens.into_iter().next()?.into_bool());
+        match is_trusted {
+            Some(true) => Ok(()),
+            Some(false) => {
+                let err = format!(
+                    "forwarder {:?} is not trusted by target contract {target:?}",
+                    self.forwarder_address
+                );
+                Err(contract::Error::Other(err).into())
+            }
+            None => {
+                let err = format!(
+                    "unexpected `isTrustedForwarder(address)` output from {target:?}: {output:?}"
+                );
+                Err(contract::Error::InvalidOutputType(err).into())
+            }
+        }
+    }
+
+    async fn domain_separator(&self, client: &DynClient<L1>) -> Result<H256, EthSenderError> {
+        self.domain_separator
+            .get_or_try_init(|| async {
+                // Uses EIP-5267 domain retrieval supported by OpenZeppelin's `EIP712` base contract.
+                let output = self.call_forwarder(client, "eip712Domain()", &[]).await?;
+                let (name, version, chain_id, verifying_contract) = decode_eip712_domain(&output)?;
+                if chain_id != U256::from(self.l1_chain_id.0) {
+                    let err = format!(
+                        "forwarder {:?} is deployed on chain {chain_id}, expected {}",
+                        self.forwarder_address, self.l1_chain_id.0
+                    );
+                    return Err(contract::Error::Other(err).into());
+                }
+                Ok(eip712_domain_separator(
+                    &name,
+                    &version,
+                    chain_id,
+                    verifying_contract,
+                ))
+            })
+            .await
+            .copied()
+    }
+
+    /// Returns the nonce of the forward request for the specified transaction. Since all transactions of the fee payer
+    /// contain forward requests, the forwarder nonce is offset from its current value by the number of fee payer
+    /// transactions pending before the specified one.
+    async fn forward_request_nonce(
+        &self,
+        gateway: &dyn BoundEthInterface,
+        tx: &EthTx,
+    ) -> Result<U256, EthSenderError> {
+        let client = gateway.as_ref();
+        let output = self
+            .call_forwarder(
+                client,
+                "nonces(address)",
+                &[Token::Address(self.signer_address())],
+            )
+            .await?;
+        if output.len() != 32 {
+            let err = format!(
+                "unexpected `nonces(address)` output length: {}",
+                output.len()
+            );
+            return Err(contract::Error::InvalidOutputType(err).into());
+        }
+        let forwarder_nonce = U256::from_big_endian(&output);
+        let fee_payer_nonce = client
+            .nonce_at_for_account(gateway.sender_account(), BlockNumber::Latest)
+            .await?;
+        let pending_tx_count = U256::from(tx.nonce.0).saturating_sub(fee_payer_nonce);
+        Ok(forwarder_nonce + pending_tx_count)
+    }
+}
+
+#[async_trait]
+impl L1TxSubmissionStrategy for Erc2771ForwarderSubmission {
+    async fn validate(&self, gateway: &dyn BoundEthInterface) -> Result<(), EthSenderError> {
+        let client = gateway.as_ref();
+        for &target in &self.target_contracts {
+            self.check_trusted_forwarder(client, target).await?;
+        }
+        self.domain_separator(client).await?;
+        Ok(())
+    }
+
+    async fn prepare_call(
+        &self,
+        gateway: &dyn BoundEthInterface,
+        tx: &EthTx,
+        gas_limit: u32,
+    ) -> Result<L1Call, EthSenderError> {
+        if !self.target_contracts.contains(&tx.contract_address) {
+            let err = format!(
+                "cannot forward call to {:?}: only calls to {:?} are forwarded",
+                tx.contract_address, self.target_contracts
+            );
+            return Err(contract::Error::Other(err).into());
+        }
+
+        let domain_separator = self.domain_separator(gateway.as_ref()).await?;
+        let request = ForwardRequest {
+            from: self.signer_address(),
+            to: tx.contract_address,
+            gas: U256::from(forwarded_gas(gas_limit, &tx.raw_tx)?),
+            nonce: self.forward_request_nonce(gateway, tx).await?,
+            deadline: seconds_since_epoch() + Self::REQUEST_TTL.as_secs(),
+            data: tx.raw_tx.clone(),
+        };
+        let signature =
+            PackedEthSignature::sign_raw(&self.signer, &request.digest(domain_separator))
+                .map_err(|err| EthSenderError::Signing(err.to_string()))?;
+
+        let mut calldata = function_selector(Self::EXECUTE_SIGNATURE).to_vec();
+        calldata.extend(ethabi::encode(&[request.into_token(&signature)]));
+        Ok(L1Call {
+            contract_address: self.forwarder_address,
+            calldata,
+        })
+    }
+}
+
+/// Forward request as defined by OpenZeppelin's `ERC2771Forwarder`. Value transfers are not supported.
+#[derive(Debug)]
+struct ForwardRequest {
+    from: Address,
+    to: Address,
+    gas: U256,
+    nonce: U256,
+    deadline: u64,
+    data: Vec<u8>,
+}
+
+impl ForwardRequest {
+    fn digest(&self, domain_separator: H256) -> H256 {
+        let struct_hash = keccak256(&ethabi::encode(&[
+            Token::FixedBytes(
+                keccak256(Erc2771ForwarderSubmission::FORWARD_REQUEST_TYPE.as_bytes()).to_vec(),
+            ),
+            Token::Address(self.from),
+            Token::Address(self.to),
+            Token::Uint(U256::zero()),
+            Token::Uint(self.gas),
+            Token::Uint(self.nonce),
+            Token::Uint(self.deadline.into()),
+            Token::FixedBytes(keccak256(&self.data).to_vec()),
+        ]));
+
+        let mut bytes = b"\x19\x01".to_vec();
+        bytes.extend_from_slice(domain_separator.as_bytes());
+        bytes.extend_from_slice(&struct_hash);
+        H256(keccak256(&bytes))
+    }
+
+    fn into_token(self, signature: &PackedEthSignature) -> Token {
+        Token::Tuple(vec![
+            Token::Address(self.from),
+            Token::Address(self.to),
+            Token::Uint(U256::zero()),
+            Token::Uint(self.gas),
+            Token::Uint(self.deadline.into()),
+            Token::Bytes(self.data),
+            Token::Bytes(signature.serialize_packed().to_vec()),
+        ])
+    }
+}
+
+fn function_selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Decodes the output of `eip712Domain()` into the domain name, version, chain ID and verifying contract.
+fn decode_eip712_domain(output: &[u8]) -> Result<(String, String, U256, Address), contract::Error> {
+    let output_types = [
+        ParamType::FixedBytes(1),
+        ParamType::String,
+        ParamType::String,
+        ParamType::Uint(256),
+        ParamType::Address,
+        ParamType::FixedBytes(32),
+        ParamType::Array(Box::new(ParamType::Uint(256))),
+    ];
+    let tokens = ethabi::decode(&output_types, output).map_err(|err| {
+        contract::Error::InvalidOutputType(format!(
+            "failed decoding `eip712Domain()` output: {err}"
+        ))
+    })?;
+    let mut tokens = tokens.into_iter().skip(1);
+    // The token types are checked by `ethabi` when decoding, so the conversions below cannot fail.
+    let name = tokens.next().and_then(Token::into_string).unwrap();
+    let version = tokens.next().and_then(Token::into_string).unwrap();
+    let chain_id = tokens.next().and_then(Token::into_uint).unwrap();
+    let verifying_contract = tokens.next().and_then(Token::into_address).unwrap();
+    Ok((name, version, chain_id, verifying_contract))
+}
+
+fn eip712_domain_separator(
+    name: &str,
+    version: &str,
+    chain_id: U256,
+    verifying_contract: Address,
+) -> H256 {
+    const DOMAIN_TYPE: &str =
+        "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+
+    H256(keccak256(&ethabi::encode(&[
+        Token::FixedBytes(keccak256(DOMAIN_TYPE.as_bytes()).to_vec()),
+        Token::FixedBytes(keccak256(name.as_bytes()).to_vec()),
+        Token::FixedBytes(keccak256(version.as_bytes()).to_vec()),
+        Token::Uint(chain_id),
+        Token::Address(verifying_contract),
+    ])))
+}
+
+/// Computes gas available for the forwarded call given the gas limit of the L1 transaction. Returns an error
+/// if the gas limit doesn't leave any gas for the call; such a transaction would revert, burning the fee payer's gas.
+fn forwarded_gas(gas_limit: u32, data: &[u8]) -> Result<u32, EthSenderError> {
+    const TX_BASE_GAS: u32 = 21_000;
+
+    let calldata_gas: u32 = data
+        .iter()
+        .map(|&byte| if byte == 0 { 4 } else { 16 })
+        .sum();
+    let overhead = TX_BASE_GAS + calldata_gas + Erc2771ForwarderSubmission::FORWARDER_GAS_OVERHEAD;
+    match gas_limit.checked_sub(overhead) {
+        Some(gas) if gas > 0 => Ok(gas),
+        _ => Err(EthSenderError::InsufficientForwardedGas {
+            gas_limit,
+            min_gas: overhead + 1,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use zksync_eth_client::clients::MockEthereum;
+    use zksync_types::{aggregated_operations::AggregatedActionType, Nonce};
+
+    use super::*;
+
+    const FORWARDER_ADDRESS: Address = Address::repeat_byte(0xf0);
+    const TARGET_ADDRESS: Address = Address::repeat_byte(0x33);
+
+    fn mock_forwarder_client(forwarder_nonce: u64, is_trusted: bool) -> MockEthereum {
+        MockEthereum::builder()
+            .with_call_handler(move |call, _| {
+                let data = call.data.as_ref().unwrap().0.as_slice();
+                let selector: [u8; 4] = data[..4].try_into().unwrap();
+                if call.to == Some(FORWARDER_ADDRESS)
+                    && selector == function_selector("nonces(address)")
+                {
+                    Token::Uint(forwarder_nonce.into())
+                } else if call.to == Some(TARGET_ADDRESS)
+                    && selector == function_selector("isTrustedForwarder(address)")
+                {
+                    assert_eq!(
+                        data[4..],
+                        ethabi::encode(&[Token::Address(FORWARDER_ADDRESS)])
+                    );
+                    Token::Bool(is_trusted)
+                } else {
+                    panic!("unexpected call: {call:?}");
+                }
+            })
+            .build()
+    }
+
+    fn test_strategy() -> Erc2771ForwarderSubmission {
+        let signer = K256PrivateKey::from_bytes(H256::repeat_byte(1)).unwrap();
+        let strategy = Erc2771ForwarderSubmission::new(
+            FORWARDER_ADDRESS,
+            signer,
+            L1ChainId(9),
+            vec![TARGET_ADDRESS],
+        );
+        // `eip712Domain()` output cannot be mocked since it consists of multiple values.
+        strategy
+            .domain_separator
+            .set(eip712_domain_separator(
+                "Forwarder",
+                "1",
+                9.into(),
+                FORWARDER_ADDRESS,
+            ))
+            .unwrap();
+        strategy
+    }
+
+    fn test_eth_tx(nonce: u32, contract_address: Address) -> EthTx {
+        EthTx {
+            id: 1,
+            nonce: Nonce(nonce),
+            contract_address,
+            raw_tx: vec![1, 2, 3],
+            tx_type: AggregatedActionType::Execute,
+            created_at_timestamp: 0,
+            predicted_gas_cost: 0,
+            from_addr: None,
+            blob_sidecar: None,
+        }
+    }
+
+    #[test]
+    fn computing_function_selectors() {
+        assert_eq!(
+            function_selector("nonces(address)"),
+            [0x7e, 0xce, 0xbe, 0x00]
+        );
+        assert_eq!(
+            function_selector("eip712Domain()"),
+            [0x84, 0xb0, 0x19, 0x6e]
+        );
+    }
+
+    #[test]
+    fn computing_forwarded_gas() {
+        assert_eq!(forwarded_gas(1_000_000, &[]).unwrap(), 929_000);
+        assert_eq!(forwarded_gas(1_000_000, &[0, 1, 0]).unwrap(), 928_976);
+        assert_eq!(forwarded_gas(71_001, &[]).unwrap(), 1);
+
+        for gas_limit in [50_000, 71_000] {
+            let err = forwarded_gas(gas_limit, &[1; 100]).unwrap_err();
+            assert_matches!(
+                err,
+                EthSenderError::InsufficientForwardedGas {
+                    gas_limit: limit,
+                    min_gas: 72_601,
+                } if limit == gas_limit
+            );
+        }
+    }
+
+    #[test]
+    fn decoding_eip712_domain() {
+        let output = ethabi::encode(&[
+            Token::FixedBytes(vec![0x0f]),
+            Token::String("Forwarder".to_owned()),
+            Token::String("1".to_owned()),
+            Token::Uint(9.into()),
+            Token::Address(FORWARDER_ADDRESS),
+            Token::FixedBytes(vec![0; 32]),
+            Token::Array(vec![]),
+        ]);
+        let domain = decode_eip712_domain(&output).unwrap();
+        assert_eq!(
+            domain,
+            (
+                "Forwarder".to_owned(),
+                "1".to_owned(),
+                U256::from(9),
+                FORWARDER_ADDRESS
+            )
+        );
+
+        let err = decode_eip712_domain(&output[..64]).unwrap_err();
+        assert_matches!(err, contract::Error::InvalidOutputType(_));
+    }
+
+    #[tokio::test]
+    async fn computing_forward_request_nonce() {
+        let client = mock_forwarder_client(7, true);
+        let strategy = test_strategy();
+        // The fee payer account has no sent transactions, so all transactions with lesser nonces are pending.
+        for (tx_nonce, expected_nonce) in [(0, 7_u64), (3, 10)] {
+            let tx = test_eth_tx(tx_nonce, TARGET_ADDRESS);
+            let nonce = strategy.forward_request_nonce(&client, &tx).await.unwrap();
+            assert_eq!(nonce, U256::from(expected_nonce));
+        }
+    }
+
+    #[tokio::test]
+    async fn validating_trusted_forwarder() {
+        let strategy = test_strategy();
+        strategy
+            .validate(&mock_forwarder_client(0, true))
+            .await
+            .unwrap();
+
+        let err = strategy
+            .validate(&mock_forwarder_client(0, false))
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("is not trusted by target contract"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn preparing_forwarded_call() {
+        let client = mock_forwarder_client(7, true);
+        let strategy = test_strategy();
+        let tx = test_eth_tx(2, TARGET_ADDRESS);
+        let call = strategy
+            .prepare_call(&client, &tx, 1_000_000)
+            .await
+            .unwrap();
+        assert_eq!(call.contract_address, FORWARDER_ADDRESS);
+        assert_eq!(
+            call.calldata[..4],
+            function_selector(Erc2771ForwarderSubmission::EXECUTE_SIGNATURE)
+        );
+
+        let request_type = ParamType::Tuple(vec![
+            ParamType::Address,
+            ParamType::Address,
+            ParamType::Uint(256),
+            ParamType::Uint(256),
+            ParamType::Uint(48),
+            ParamType::Bytes,
+            ParamType::Bytes,
+        ]);
+        let tokens = ethabi::decode(&[request_type], &call.calldata[4..]).unwrap();
+        let Some(Token::Tuple(tokens)) = tokens.into_iter().next() else {
+            unreachable!();
+        };
+        let request = ForwardRequest {
+            from: tokens[0].clone().into_address().unwrap(),
+            to: tokens[1].clone().into_address().unwrap(),
+            gas: tokens[3].clone().into_uint().unwrap(),
+            nonce: 9.into(), // forwarder nonce + 2 pending fee payer transactions
+            deadline: tokens[4].clone().into_uint().unwrap().as_u64(),
+            data: tokens[5].clone().into_bytes().unwrap(),
+        };
+        assert_eq!(request.from, strategy.signer_address());
+        assert_eq!(request.to, TARGET_ADDRESS);
+        assert_eq!(request.gas, 928_952.into());
+        assert_eq!(request.data, tx.raw_tx);
+
+        let signature = tokens[6].clone().into_bytes().unwrap();
+        let signature = PackedEthSignature::deserialize_packed(&signature).unwrap();
+        let digest = request.digest(strategy.domain_separator.get().copied().unwrap());
+        let recovered = signature.signature_recover_signer(&digest).unwrap();
+        assert_eq!(recovered, strategy.signer_address());
+    }
+
+    #[tokio::test]
+    async fn forwarded_calls_are_restricted() {
+        let client = mock_forwarder_client(7, true);
+        let strategy = test_strategy();
+        let tx = test_eth_tx(0, Address::repeat_byte(0x44));
+        let err = strategy
+            .prepare_call(&client, &tx, 1_000_000)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("cannot forward call"), "{err}");
+
+        let tx = test_eth_tx(0, TARGET_ADDRESS);
+        let err = strategy
+            .prepare_call(&client, &tx, 50_000)
+            .await
+            .unwrap_err();
+        assert_matches!(err, EthSenderError::InsufficientForwardedGas { .. });
+    }
+
+    #[test]
+    fn forward_request_signature_is_recoverable() {
+        let signer = K256PrivateKey::from_bytes(H256::repeat_byte(1)).unwrap();
+        let domain_separator =
+            eip712_domain_separator("Forwarder", "1", 1.into(), Address::repeat_byte(2));
+        let request = ForwardRequest {
+            from: signer.address(),
+            to: Address::repeat_byte(3),
+            gas: 100_000.into(),
+            nonce: 5.into(),
+            deadline: 1_700_000_000,
+            data: vec![1, 2, 3],
+        };
+        let digest = request.digest(domain_separator);
+        let signature = PackedEthSignature::sign_raw(&signer, &digest).unwrap();
+        let recovered = signature.signature_recover_signer(&digest).unwrap();
+        assert_eq!(recovered, signer.address());
+
+        let Token::Tuple(tokens) = request.into_token(&signature) else {
+            unreachable!();
+        };
+        assert_eq!(tokens.len(), 7);
+        assert_eq!(
+            tokens[6],
+            Token::Bytes(signature.serialize_packed().to_vec())
+        );
+    }
+}
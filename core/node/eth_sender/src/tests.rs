@@ -9,7 +9,7 @@ use zksync_config::{
 };
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
-use zksync_eth_client::{clients::MockEthereum, EthInterface};
+use zksync_eth_client::{clients::MockEthereum, BoundEthInterface, EthInterface};
 use zksync_l1_contract_interface::i_executor::methods::{ExecuteBatches, ProveBatches};
 use zksync_node_fee_model::l1_gas_price::GasAdjuster;
 use zksync_node_test_utils::{create_l1_batch, l1_batch_metadata_to_commitment_artifacts};
@@ -19,6 +19,7 @@ use zksync_types::{
     commitment::{
         L1BatchCommitmentMode, L1BatchMetaParameters, L1BatchMetadata, L1BatchWithMetadata,
     },
+    eth_sender::EthTx,
     ethabi::Token,
     helpers::unix_timestamp_ms,
     l2_to_l1_log::{L2ToL1Log, UserL2ToL1Log},
@@ -29,7 +30,7 @@ use zksync_types::{
 
 use crate::{
     aggregated_operations::AggregatedOperation, eth_tx_manager::L1BlockNumbers, Aggregator,
-    EthSenderError, EthTxAggregator, EthTxManager,
+    EthSenderError, EthTxAggregator, EthTxManager, L1Call, L1TxSubmissionStrategy,
 };
 
 // Alias to conveniently call static methods of `ETHSender`.
@@ -497,6 +498,83 @@ async fn resend_each_block(commitment_mode: L1BatchCommitmentMode) -> anyhow::Re
 
 // Tests that if transaction was mined, but not enough blocks has been mined since,
 // we won't mark it as confirmed but also won't resend it.
+/// Strategy relaying all transactions via a mock contract that expects the original calldata to be prefixed.
+#[derive(Debug)]
+struct MockRelaySubmission;
+
+impl MockRelaySubmission {
+    const RELAY_ADDRESS: Address = Address::repeat_byte(0x42);
+    const CALLDATA_PREFIX: [u8; 4] = [0xde, 0xad, 0xbe, 0xef];
+}
+
+#[async_trait::async_trait]
+impl L1TxSubmissionStrategy for MockRelaySubmission {
+    async fn prepare_call(
+        &self,
+        _gateway: &dyn BoundEthInterface,
+        tx: &EthTx,
+        _gas_limit: u32,
+    ) -> Result<L1Call, EthSenderError> {
+        let mut calldata = Self::CALLDATA_PREFIX.to_vec();
+        calldata.extend_from_slice(&tx.raw_tx);
+        Ok(L1Call {
+            contract_address: Self::RELAY_ADDRESS,
+            calldata,
+        })
+    }
+}
+
+#[tokio::test]
+async fn sending_tx_with_custom_submission_strategy() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let mut tester = EthSenderTester::new(
+        connection_pool.clone(),
+        vec![100; 100],
+        false,
+        false,
+        L1BatchCommitmentMode::Rollup,
+    )
+    .await;
+    tester.manager = EthTxManager::new(
+        connection_pool.clone(),
+        EthConfig::for_tests().sender.unwrap(),
+        tester.gas_adjuster.clone(),
+        tester.gateway.clone(),
+        None,
+    )
+    .with_submission_strategy(Arc::new(MockRelaySubmission));
+    insert_genesis_protocol_version(&tester).await;
+    insert_l1_batch(&tester, L1BatchNumber(1)).await;
+
+    let tx = tester
+        .aggregator
+        .save_eth_tx(
+            &mut tester.conn.connection().await.unwrap(),
+            &get_dummy_operation(1),
+            false,
+        )
+        .await
+        .unwrap();
+    let block = tester.get_block_numbers().await.latest;
+    let hash = tester
+        .manager
+        .send_eth_tx(&mut tester.conn.connection().await.unwrap(), &tx, 0, block)
+        .await
+        .unwrap();
+
+    let sent_tx = tester
+        .manager
+        .query_client()
+        .get_tx(hash)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(sent_tx.to, Some(MockRelaySubmission::RELAY_ADDRESS));
+    let (prefix, calldata) = sent_tx.input.0.split_at(4);
+    assert_eq!(prefix, MockRelaySubmission::CALLDATA_PREFIX);
+    assert_eq!(calldata, tx.raw_tx);
+}
+
 #[test_casing(2, COMMITMENT_MODES)]
 #[tokio::test]
 async fn dont_resend_already_mined(commitment_mode: L1BatchCommitmentMode) -> anyhow::Result<()> {
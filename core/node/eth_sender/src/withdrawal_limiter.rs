@@ -0,0 +1,261 @@
+//! Withdrawal limiter restricting the aggregate value of withdrawals executed on L1 within a time window.
+
+use std::collections::{HashMap, HashSet};
+
+use zksync_config::configs::eth_sender::{WithdrawalLimitAction, WithdrawalLimitsConfig};
+use zksync_dal::{Connection, Core, CoreDal};
+use zksync_types::{
+    commitment::L1BatchWithMetadata, event::extract_withdrawals, Address, L1BatchNumber, U256,
+};
+use zksync_utils::u256_to_f64;
+
+use crate::metrics::METRICS;
+
+/// Aggregated withdrawn amounts keyed by the L2 token address.
+type Withdrawals = HashMap<Address, U256>;
+
+/// Tracks the aggregate value of withdrawals in L1 batches executed within a sliding time window
+/// and restricts execution of new L1 batches if it would exceed the configured per-token limits.
+///
+/// Withdrawals of an L1 batch are accounted for in the window once an execute transaction
+/// for the batch is created.
+#[derive(Debug)]
+pub struct WithdrawalLimiter {
+    config: WithdrawalLimitsConfig,
+    l2_bridge_addresses: Vec<Address>,
+    /// Withdrawals for L1 batches that are either in the window or are candidates for execution.
+    withdrawals_cache: HashMap<L1BatchNumber, Withdrawals>,
+}
+
+impl WithdrawalLimiter {
+    /// Creates a new limiter. ERC20 withdrawals are only accounted for if they are initiated
+    /// by one of `l2_bridge_addresses`; base token withdrawals are always accounted for.
+    pub fn new(config: WithdrawalLimitsConfig, l2_bridge_addresses: Vec<Address>) -> Self {
+        Self {
+            config,
+            l2_bridge_addresses,
+            withdrawals_cache: HashMap::new(),
+        }
+    }
+
+    async fn withdrawals(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> Withdrawals {
+        if let Some(withdrawals) = self.withdrawals_cache.get(&l1_batch_number) {
+            return withdrawals.clone();
+        }
+
+        let events = storage
+            .events_dal()
+            .get_vm_events_for_l1_batch(l1_batch_number)
+            .await
+            .unwrap()
+            .unwrap_or_default();
+        let mut withdrawals = Withdrawals::new();
+        for (token, amount) in extract_withdrawals(&self.l2_bridge_addresses, &events) {
+            let total = withdrawals.entry(token).or_default();
+            *total = total.saturating_add(amount);
+        }
+        self.withdrawals_cache
+            .insert(l1_batch_number, withdrawals.clone());
+        withdrawals
+    }
+
+    /// Returns the longest prefix of `l1_batches` that can be executed without exceeding withdrawal limits.
+    pub(crate) async fn limit_l1_batches_to_execute(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        mut l1_batches: Vec<L1BatchWithMetadata>,
+    ) -> Vec<L1BatchWithMetadata> {
+        let executed_l1_batches = storage
+            .blocks_dal()
+            .get_l1_batches_executed_within(self.config.window())
+            .await
+            .unwrap();
+
+        let mut usage = Withdrawals::new();
+        for &l1_batch_number in &executed_l1_batches {
+            add_withdrawals(
+                &mut usage,
+                &self.withdrawals(storage, l1_batch_number).await,
+            );
+        }
+        self.report_usage(&usage);
+
+        let mut candidates = Vec::with_capacity(l1_batches.len());
+        for l1_batch in &l1_batches {
+            let number = l1_batch.header.number;
+            candidates.push((number, self.withdrawals(storage, number).await));
+        }
+
+        let retained_l1_batches: HashSet<_> = executed_l1_batches
+            .into_iter()
+            .chain(candidates.iter().map(|(number, _)| *number))
+            .collect();
+        self.withdrawals_cache
+            .retain(|number, _| retained_l1_batches.contains(number));
+
+        let executable_count = self.executable_prefix_len(usage, &candidates);
+        l1_batches.truncate(executable_count);
+        l1_batches
+    }
+
+    /// Returns the number of leading `candidates` that can be executed given the current `usage` in the window.
+    fn executable_prefix_len(
+        &self,
+        mut usage: Withdrawals,
+        candidates: &[(L1BatchNumber, Withdrawals)],
+    ) -> usize {
+        for (i, (l1_batch_number, withdrawals)) in candidates.iter().enumerate() {
+            add_withdrawals(&mut usage, withdrawals);
+            let exceeded_tokens: Vec<_> = self
+                .config
+                .limits
+                .iter()
+                .filter(|limit| {
+                    usage.get(&limit.token).copied().unwrap_or_default() > limit.max_amount
+                })
+                .map(|limit| limit.token)
+                .collect();
+            if exceeded_tokens.is_empty() {
+                continue;
+            }
+
+            for token in &exceeded_tokens {
+                METRICS.withdrawal_limit_exceeded[&format!("{token:?}")].inc();
+            }
+            let is_overridden = self
+                .config
+                .override_up_to_l1_batch
+                .is_some_and(|last_overridden| *l1_batch_number <= last_overridden);
+            if is_overridden {
+                tracing::warn!(
+                    "Executing L1 batch #{l1_batch_number} exceeds withdrawal limits for tokens {exceeded_tokens:?}; \
+                     proceeding because of the admin override"
+                );
+                continue;
+            }
+
+            match self.config.action {
+                WithdrawalLimitAction::Alert => {
+                    tracing::error!(
+                        "Executing L1 batch #{l1_batch_number} exceeds withdrawal limits for tokens {exceeded_tokens:?}"
+                    );
+                }
+                WithdrawalLimitAction::DelayExecution => {
+                    tracing::warn!(
+                        "Delaying execution of L1 batch #{l1_batch_number} since it exceeds withdrawal limits \
+                         for tokens {exceeded_tokens:?}; it will be executed once the limits allow it, \
+                         or after an admin override"
+                    );
+                    return i;
+                }
+            }
+        }
+        candidates.len()
+    }
+
+    fn report_usage(&self, usage: &Withdrawals) {
+        for limit in &self.config.limits {
+            let used = usage.get(&limit.token).copied().unwrap_or_default();
+            let ratio = if limit.max_amount.is_zero() {
+                if used.is_zero() {
+                    0.0
+                } else {
+                    f64::INFINITY
+                }
+            } else {
+                u256_to_f64(used) / u256_to_f64(limit.max_amount)
+            };
+            METRICS.withdrawal_limit_usage[&format!("{:?}", limit.token)].set(ratio);
+        }
+    }
+}
+
+fn add_withdrawals(usage: &mut Withdrawals, withdrawals: &Withdrawals) {
+    for (&token, &amount) in withdrawals {
+        let total = usage.entry(token).or_default();
+        *total = total.saturating_add(amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_config::configs::eth_sender::TokenWithdrawalLimit;
+
+    use super::*;
+
+    const TOKEN: Address = Address::repeat_byte(1);
+    const OTHER_TOKEN: Address = Address::repeat_byte(2);
+
+    fn create_limiter(action: WithdrawalLimitAction) -> WithdrawalLimiter {
+        let config = WithdrawalLimitsConfig {
+            window_sec: 3_600,
+            limits: vec![TokenWithdrawalLimit {
+                token: TOKEN,
+                max_amount: U256::from(100),
+            }],
+            action,
+            override_up_to_l1_batch: None,
+        };
+        WithdrawalLimiter::new(config, vec![])
+    }
+
+    fn candidate(number: u32, withdrawals: &[(Address, u64)]) -> (L1BatchNumber, Withdrawals) {
+        let withdrawals = withdrawals
+            .iter()
+            .map(|&(token, amount)| (token, U256::from(amount)))
+            .collect();
+        (L1BatchNumber(number), withdrawals)
+    }
+
+    #[test]
+    fn delaying_execution_when_limit_is_exceeded() {
+        let limiter = create_limiter(WithdrawalLimitAction::DelayExecution);
+        let candidates = [
+            candidate(1, &[(TOKEN, 30), (OTHER_TOKEN, 1_000)]),
+            candidate(2, &[(TOKEN, 40)]),
+            candidate(3, &[(TOKEN, 40)]),
+            candidate(4, &[]),
+        ];
+
+        assert_eq!(
+            limiter.executable_prefix_len(Withdrawals::new(), &candidates),
+            2
+        );
+        let usage = Withdrawals::from([(TOKEN, U256::from(80))]);
+        assert_eq!(limiter.executable_prefix_len(usage, &candidates), 0);
+        let usage = Withdrawals::from([(OTHER_TOKEN, U256::from(80))]);
+        assert_eq!(limiter.executable_prefix_len(usage, &candidates), 2);
+    }
+
+    #[test]
+    fn alerting_when_limit_is_exceeded() {
+        let limiter = create_limiter(WithdrawalLimitAction::Alert);
+        let candidates = [candidate(1, &[(TOKEN, 80)]), candidate(2, &[(TOKEN, 80)])];
+
+        assert_eq!(
+            limiter.executable_prefix_len(Withdrawals::new(), &candidates),
+            2
+        );
+    }
+
+    #[test]
+    fn admin_override_for_withdrawal_limits() {
+        let mut limiter = create_limiter(WithdrawalLimitAction::DelayExecution);
+        limiter.config.override_up_to_l1_batch = Some(L1BatchNumber(2));
+        let candidates = [
+            candidate(1, &[(TOKEN, 80)]),
+            candidate(2, &[(TOKEN, 80)]),
+            candidate(3, &[(TOKEN, 1)]),
+        ];
+
+        // Withdrawals in overridden L1 batches are still accounted for.
+        assert_eq!(
+            limiter.executable_prefix_len(Withdrawals::new(), &candidates),
+            2
+        );
+    }
+}
@@ -0,0 +1,55 @@
+//! Detection of drift between contract ABIs compiled into the node and contracts deployed on L1.
+
+use zksync_types::{Address, H256};
+
+use crate::{client::EthClient, event_processors::EventProcessorError};
+
+/// Opcode pushing a 32-byte constant onto the stack. Solidity emits topics of non-anonymous events as `PUSH32` constants,
+/// so the topic of each event emitted by a contract is present in its bytecode prefixed by this opcode.
+const PUSH32_OPCODE: u8 = 0x7f;
+
+fn bytecode_emits_topic(bytecode: &[u8], topic: H256) -> bool {
+    let needle_len = 1 + topic.as_bytes().len();
+    bytecode
+        .windows(needle_len)
+        .any(|window| window[0] == PUSH32_OPCODE && window[1..] == *topic.as_bytes())
+}
+
+/// Checks that each of the provided events, identified by the name and topic from the compiled-in ABI, can be emitted
+/// by at least one of the L1 contracts watched by the client.
+///
+/// If this check fails, the event signature has changed in the deployed contracts (or the node is configured with
+/// wrong contract addresses). In this case, the watcher would silently skip the corresponding events, so the error
+/// is considered fatal.
+pub(crate) async fn check_event_signatures(
+    client: &dyn EthClient,
+    events: &[(&'static str, H256)],
+) -> Result<(), EventProcessorError> {
+    let bytecodes = client.emitter_bytecodes().await?;
+    for (address, bytecode) in &bytecodes {
+        if bytecode.is_empty() {
+            tracing::warn!("No contract is deployed at watched L1 address {address:?}");
+        }
+    }
+
+    for &(event_name, topic) in events {
+        let is_emitted = bytecodes
+            .iter()
+            .any(|(_, bytecode)| bytecode_emits_topic(bytecode, topic));
+        if !is_emitted {
+            let addresses: Vec<Address> = bytecodes.iter().map(|(address, _)| *address).collect();
+            let err = anyhow::anyhow!(
+                "event `{event_name}` with topic {topic:?} is not emitted by any of watched L1 contracts {addresses:?}"
+            );
+            return Err(EventProcessorError::Internal(err.context(
+                "L1 contract ABI drift detected: contract ABIs compiled into the node don't match deployed contracts",
+            )));
+        }
+    }
+    tracing::info!(
+        "Checked signatures of events {:?} against {} deployed L1 contracts",
+        events.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+        bytecodes.len()
+    );
+    Ok(())
+}
@@ -1,13 +1,13 @@
 use std::fmt;
 
-use zksync_contracts::verifier_contract;
+use zksync_contracts::{hyperchain_contract, verifier_contract};
 use zksync_eth_client::{
     clients::{DynClient, L1},
     CallFunctionArgs, ClientError, ContractCallError, EnrichedClientError, EnrichedClientResult,
     EthInterface,
 };
 use zksync_types::{
-    ethabi::Contract,
+    ethabi::{Contract, Token},
     web3::{BlockId, BlockNumber, FilterBuilder, Log},
     Address, H256,
 };
@@ -27,6 +27,9 @@ pub trait EthClient: 'static + fmt::Debug + Send + Sync {
     /// Returns scheduler verification key hash by verifier address.
     async fn scheduler_vk_hash(&self, verifier_address: Address)
         -> Result<H256, ContractCallError>;
+    /// Returns bytecodes of all L1 contracts that can emit events watched by the client, i.e. governance,
+    /// state transition manager (if present) and all facets of the diamond proxy.
+    async fn emitter_bytecodes(&self) -> Result<Vec<(Address, Vec<u8>)>, ContractCallError>;
     /// Sets list of topics to return events for.
     fn set_topics(&mut self, topics: Vec<H256>);
 }
@@ -45,6 +48,7 @@ pub struct EthHttpQueryClient {
     // Only present for post-shared bridge chains.
     state_transition_manager_address: Option<Address>,
     verifier_contract_abi: Contract,
+    getters_facet_contract_abi: Contract,
     confirmations_for_eth_event: Option<u64>,
 }
 
@@ -68,6 +72,7 @@ impl EthHttpQueryClient {
             state_transition_manager_address,
             governance_address,
             verifier_contract_abi: verifier_contract(),
+            getters_facet_contract_abi: hyperchain_contract(),
             confirmations_for_eth_event,
         }
     }
@@ -110,6 +115,29 @@ impl EthClient for EthHttpQueryClient {
             .await
     }
 
+    async fn emitter_bytecodes(&self) -> Result<Vec<(Address, Vec<u8>)>, ContractCallError> {
+        // Events emitted by the diamond proxy are actually emitted by its facets via `delegatecall`.
+        let facet_addresses: Vec<Token> = CallFunctionArgs::new("facetAddresses", ())
+            .for_contract(self.diamond_proxy_addr, &self.getters_facet_contract_abi)
+            .call(self.client.as_ref())
+            .await?;
+        let facet_addresses = facet_addresses.into_iter().filter_map(Token::into_address);
+        let addresses = [
+            Some(self.governance_address),
+            self.state_transition_manager_address,
+        ]
+        .into_iter()
+        .flatten()
+        .chain(facet_addresses);
+
+        let mut bytecodes = vec![];
+        for address in addresses {
+            let bytecode = self.client.contract_code(address).await?;
+            bytecodes.push((address, bytecode));
+        }
+        Ok(bytecodes)
+    }
+
     async fn get_events(
         &self,
         from: BlockNumber,
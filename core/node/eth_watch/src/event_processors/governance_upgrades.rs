@@ -114,6 +114,7 @@ impl EventProcessor for GovernanceUpgradesEventProcessor {
                         )
                     })?;
 
+                let verifier_address = upgrade.verifier_address;
                 let new_version = latest_version.apply_upgrade(upgrade, scheduler_vk_hash);
                 if new_version.version.minor == latest_semantic_version.minor {
                     // Only verification parameters may change if only patch is bumped.
@@ -128,6 +129,13 @@ impl EventProcessor for GovernanceUpgradesEventProcessor {
                     .save_protocol_version_with_tx(&new_version)
                     .await
                     .map_err(DalError::generalize)?;
+                if let Some(verifier_address) = verifier_address {
+                    storage
+                        .protocol_versions_dal()
+                        .save_verifier_address(new_version.version.minor, verifier_address)
+                        .await
+                        .map_err(DalError::generalize)?;
+                }
             }
         }
         stage_latency.observe();
@@ -139,4 +147,8 @@ impl EventProcessor for GovernanceUpgradesEventProcessor {
     fn relevant_topic(&self) -> H256 {
         self.upgrade_proposal_signature
     }
+
+    fn relevant_event_name(&self) -> &'static str {
+        "TransparentOperationScheduled"
+    }
 }
@@ -53,4 +53,7 @@ pub(super) trait EventProcessor: 'static + fmt::Debug + Send + Sync {
 
     /// Relevant topic which defines what events to be processed
     fn relevant_topic(&self) -> H256;
+
+    /// Name of the event with [`Self::relevant_topic()`] in the contract ABI.
+    fn relevant_event_name(&self) -> &'static str;
 }
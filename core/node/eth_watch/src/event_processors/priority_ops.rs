@@ -99,4 +99,8 @@ impl EventProcessor for PriorityOpsEventProcessor {
     fn relevant_topic(&self) -> H256 {
         self.new_priority_request_signature
     }
+
+    fn relevant_event_name(&self) -> &'static str {
+        "NewPriorityRequest"
+    }
 }
@@ -2,7 +2,7 @@
 //! protocol upgrades etc.
 //! New events are accepted to the zkSync network once they have the sufficient amount of L1 confirmations.
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::Context as _;
 use tokio::sync::watch;
@@ -23,12 +23,16 @@ use self::{
     metrics::{PollStage, METRICS},
 };
 
+mod abi_check;
 mod client;
 mod event_processors;
 mod metrics;
 #[cfg(test)]
 mod tests;
 
+/// Interval between checks that event signatures in compiled-in contract ABIs match the deployed L1 contracts.
+const ABI_CHECK_INTERVAL: Duration = Duration::from_secs(3_600);
+
 #[derive(Debug)]
 struct EthWatchState {
     last_seen_protocol_version: ProtocolSemanticVersion,
@@ -43,6 +47,7 @@ pub struct EthWatch {
     poll_interval: Duration,
     event_processors: Vec<Box<dyn EventProcessor>>,
     last_processed_ethereum_block: u64,
+    last_abi_check: Option<Instant>,
     pool: ConnectionPool<Core>,
 }
 
@@ -82,6 +87,7 @@ impl EthWatch {
             poll_interval,
             event_processors,
             last_processed_ethereum_block: state.last_processed_ethereum_block,
+            last_abi_check: None,
             pool,
         })
     }
@@ -164,6 +170,21 @@ impl EthWatch {
         &mut self,
         storage: &mut Connection<'_, Core>,
     ) -> Result<(), EventProcessorError> {
+        // The check is performed on the first iteration and then periodically, since L1 contracts can be upgraded
+        // while the node is running. If the check fails because of a transient error, it is retried on the next iteration.
+        let should_check_abi = self.last_abi_check.map_or(true, |checked_at| {
+            checked_at.elapsed() >= ABI_CHECK_INTERVAL
+        });
+        if should_check_abi {
+            let events: Vec<_> = self
+                .event_processors
+                .iter()
+                .map(|processor| (processor.relevant_event_name(), processor.relevant_topic()))
+                .collect();
+            abi_check::check_event_signatures(&*self.client, &events).await?;
+            self.last_abi_check = Some(Instant::now());
+        }
+
         let stage_latency = METRICS.poll_eth_node[&PollStage::Request].start();
         let to_block = self.client.finalized_block_number().await?;
         if to_block <= self.last_processed_ethereum_block {
@@ -15,7 +15,7 @@ use zksync_types::{
     ProtocolVersionId, Transaction, H256, U256,
 };
 
-use crate::{client::EthClient, EthWatch};
+use crate::{client::EthClient, event_processors::EventProcessorError, EthWatch};
 
 #[derive(Debug)]
 struct FakeEthClientData {
@@ -23,6 +23,7 @@ struct FakeEthClientData {
     diamond_upgrades: HashMap<u64, Vec<Log>>,
     governance_upgrades: HashMap<u64, Vec<Log>>,
     last_finalized_block_number: u64,
+    emitter_bytecodes: Vec<(Address, Vec<u8>)>,
 }
 
 impl FakeEthClientData {
@@ -32,6 +33,19 @@ impl FakeEthClientData {
             diamond_upgrades: Default::default(),
             governance_upgrades: Default::default(),
             last_finalized_block_number: 0,
+            emitter_bytecodes: vec![(
+                Address::repeat_byte(1),
+                mock_emitter_bytecode(&[
+                    hyperchain_contract()
+                        .event("NewPriorityRequest")
+                        .unwrap()
+                        .signature(),
+                    governance_contract()
+                        .event("TransparentOperationScheduled")
+                        .unwrap()
+                        .signature(),
+                ]),
+            )],
         }
     }
 
@@ -79,6 +93,10 @@ impl MockEthClient {
         self.inner.write().await.add_governance_upgrades(upgrades);
     }
 
+    async fn set_emitter_bytecodes(&mut self, bytecodes: Vec<(Address, Vec<u8>)>) {
+        self.inner.write().await.emitter_bytecodes = bytecodes;
+    }
+
     async fn set_last_finalized_block_number(&mut self, number: u64) {
         self.inner
             .write()
@@ -125,6 +143,10 @@ impl EthClient for MockEthClient {
 
     fn set_topics(&mut self, _topics: Vec<Hash>) {}
 
+    async fn emitter_bytecodes(&self) -> Result<Vec<(Address, Vec<u8>)>, ContractCallError> {
+        Ok(self.inner.read().await.emitter_bytecodes.clone())
+    }
+
     async fn scheduler_vk_hash(
         &self,
         _verifier_address: Address,
@@ -137,6 +159,17 @@ impl EthClient for MockEthClient {
     }
 }
 
+/// Creates a bytecode emitting events with the specified topics (via `PUSH32 <topic>; ...; LOG*` opcode sequences).
+fn mock_emitter_bytecode(topics: &[H256]) -> Vec<u8> {
+    let mut bytecode = vec![0x60, 0x80, 0x60, 0x40, 0x52]; // `PUSH1 0x80 PUSH1 0x40 MSTORE`
+    for topic in topics {
+        bytecode.push(0x7f); // `PUSH32`
+        bytecode.extend_from_slice(topic.as_bytes());
+        bytecode.extend_from_slice(&[0x60, 0x20, 0x60, 0x00, 0xa1]); // `PUSH1 0x20 PUSH1 0x00 LOG1`
+    }
+    bytecode
+}
+
 fn build_l1_tx(serial_id: u64, eth_block: u64) -> L1Tx {
     let tx = L1Tx {
         execute: Execute {
@@ -458,6 +491,64 @@ async fn test_overlapping_batches() {
     assert_eq!(tx.common_data.serial_id.0, 4);
 }
 
+#[tokio::test]
+async fn detecting_abi_drift() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+    let (mut watcher, mut client) = create_test_watcher(connection_pool.clone()).await;
+
+    // The deployed contract emits a priority op event with a changed signature.
+    let changed_topic = H256::repeat_byte(0xff);
+    let governance_topic = governance_contract()
+        .event("TransparentOperationScheduled")
+        .unwrap()
+        .signature();
+    client
+        .set_emitter_bytecodes(vec![
+            (
+                Address::repeat_byte(1),
+                mock_emitter_bytecode(&[governance_topic]),
+            ),
+            (
+                Address::repeat_byte(2),
+                mock_emitter_bytecode(&[changed_topic]),
+            ),
+        ])
+        .await;
+    client.add_transactions(&[build_l1_tx(0, 10)]).await;
+    client.set_last_finalized_block_number(15).await;
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    let err = watcher.loop_iteration(&mut storage).await.unwrap_err();
+    let EventProcessorError::Internal(err) = err else {
+        panic!("unexpected error: {err:?}");
+    };
+    let err = format!("{err:#}");
+    assert!(err.contains("ABI drift"), "{err}");
+    assert!(err.contains("NewPriorityRequest"), "{err}");
+    // No events must be processed.
+    assert!(get_all_db_txs(&mut storage).await.is_empty());
+}
+
+#[tokio::test]
+async fn abi_check_is_not_repeated_on_each_iteration() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+    let (mut watcher, mut client) = create_test_watcher(connection_pool.clone()).await;
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    client.set_last_finalized_block_number(5).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    assert!(watcher.last_abi_check.is_some());
+
+    // Since the check has just been performed, the watcher shouldn't repeat it.
+    client.set_emitter_bytecodes(vec![]).await;
+    client.add_transactions(&[build_l1_tx(0, 10)]).await;
+    client.set_last_finalized_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    assert_eq!(get_all_db_txs(&mut storage).await.len(), 1);
+}
+
 async fn get_all_db_txs(storage: &mut Connection<'_, Core>) -> Vec<Transaction> {
     storage.transactions_dal().reset_mempool().await.unwrap();
     storage
@@ -43,7 +43,7 @@ impl MainNodeFeeParamsFetcher {
                 .rpc_context("get_fee_params")
                 .await;
             let main_node_fee_params = match fetch_result {
-                Ok(price) => price,
+                Ok(params) => params.into(),
                 Err(err) => {
                     tracing::warn!("Unable to get the gas price: {}", err);
                     // A delay to avoid spamming the main node with requests.
@@ -4,8 +4,8 @@ use anyhow::Context as _;
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_types::{
     fee_model::{
-        BatchFeeInput, FeeModelConfig, FeeModelConfigV2, FeeParams, FeeParamsV1, FeeParamsV2,
-        L1PeggedBatchFeeModelInput, PubdataIndependentBatchFeeModelInput,
+        BatchFeeInput, BatchFeeInputParams, FeeModelConfig, FeeModelConfigV2, FeeParams,
+        FeeParamsV1, FeeParamsV2, L1PeggedBatchFeeModelInput, PubdataIndependentBatchFeeModelInput,
     },
     U256,
 };
@@ -25,21 +25,29 @@ pub trait BatchFeeModelInputProvider: fmt::Debug + 'static + Send + Sync {
         l1_gas_price_scale_factor: f64,
         l1_pubdata_price_scale_factor: f64,
     ) -> anyhow::Result<BatchFeeInput> {
-        let params = self.get_fee_model_params();
-
-        Ok(match params {
-            FeeParams::V1(params) => BatchFeeInput::L1Pegged(compute_batch_fee_model_input_v1(
-                params,
+        let (fee_input, _) = self
+            .get_batch_fee_input_with_params_scaled(
                 l1_gas_price_scale_factor,
-            )),
-            FeeParams::V2(params) => {
-                BatchFeeInput::PubdataIndependent(compute_batch_fee_model_input_v2(
-                    params,
-                    l1_gas_price_scale_factor,
-                    l1_pubdata_price_scale_factor,
-                ))
-            }
-        })
+                l1_pubdata_price_scale_factor,
+            )
+            .await?;
+        Ok(fee_input)
+    }
+
+    /// Returns the batch fee input computed from the fee model parameters together with the exact inputs
+    /// of the computation. Unlike separate calls to [`Self::get_fee_model_params()`] and [`Self::get_batch_fee_input_scaled()`],
+    /// the returned values are always consistent with each other.
+    async fn get_batch_fee_input_with_params_scaled(
+        &self,
+        l1_gas_price_scale_factor: f64,
+        l1_pubdata_price_scale_factor: f64,
+    ) -> anyhow::Result<(BatchFeeInput, BatchFeeInputParams)> {
+        let params = BatchFeeInputParams {
+            fee_params: self.get_fee_model_params(),
+            l1_gas_price_scale_factor,
+            l1_pubdata_price_scale_factor,
+        };
+        Ok((compute_batch_fee_input(params), params))
     }
 
     /// Returns the fee model parameters.
@@ -51,6 +59,30 @@ impl dyn BatchFeeModelInputProvider {
     pub async fn get_batch_fee_input(&self) -> anyhow::Result<BatchFeeInput> {
         self.get_batch_fee_input_scaled(1.0, 1.0).await
     }
+
+    /// Same as [`Self::get_batch_fee_input()`], but also returns the inputs used to compute the fee input.
+    pub async fn get_batch_fee_input_with_params(
+        &self,
+    ) -> anyhow::Result<(BatchFeeInput, BatchFeeInputParams)> {
+        self.get_batch_fee_input_with_params_scaled(1.0, 1.0).await
+    }
+}
+
+/// Computes the batch fee input from the provided fee model inputs. The result is fully determined by the inputs.
+pub fn compute_batch_fee_input(params: BatchFeeInputParams) -> BatchFeeInput {
+    match params.fee_params {
+        FeeParams::V1(fee_params) => BatchFeeInput::L1Pegged(compute_batch_fee_model_input_v1(
+            fee_params,
+            params.l1_gas_price_scale_factor,
+        )),
+        FeeParams::V2(fee_params) => {
+            BatchFeeInput::PubdataIndependent(compute_batch_fee_model_input_v2(
+                fee_params,
+                params.l1_gas_price_scale_factor,
+                params.l1_pubdata_price_scale_factor,
+            ))
+        }
+    }
 }
 
 /// The struct that represents the batch fee input provider to be used in the main node of the server, i.e.
@@ -0,0 +1,80 @@
+use anyhow::Context as _;
+use vise::{Counter, Metrics};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_types::H256;
+
+use crate::periodic_job::PeriodicJob;
+
+/// Maximum number of call traces converted in a single DB transaction.
+const MIGRATION_CHUNK_SIZE: usize = 1_000;
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "house_keeper")]
+struct CallTracesMigratorMetrics {
+    call_traces_migrated: Counter,
+    call_traces_skipped: Counter,
+}
+
+#[vise::register]
+static METRICS: vise::Global<CallTracesMigratorMetrics> = vise::Global::new();
+
+/// `CallTracesMigrator` is a task that periodically converts call traces stored in the legacy format
+/// to the flat format, which supports partial decoding. New traces are written in the flat format; legacy traces
+/// may still be written by older node versions during an upgrade, so the task converts them on subsequent runs.
+///
+/// Traces are iterated over in the order of transaction hashes; the cursor is reset to the start once
+/// all traces after it are processed. Traces that cannot be decoded are skipped.
+#[derive(Debug)]
+pub struct CallTracesMigrator {
+    pool: ConnectionPool<Core>,
+    migration_interval_ms: u64,
+    cursor: Option<H256>,
+}
+
+impl CallTracesMigrator {
+    /// `pool` must point to the master DB.
+    pub fn new(pool: ConnectionPool<Core>, migration_interval_ms: u64) -> Self {
+        Self {
+            pool,
+            migration_interval_ms,
+            cursor: None,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PeriodicJob for CallTracesMigrator {
+    const SERVICE_NAME: &'static str = "CallTracesMigrator";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        let mut conn = self.pool.connection().await?;
+        let mut total_migrated_count = 0;
+        loop {
+            let migrated = conn
+                .transactions_dal()
+                .migrate_legacy_call_traces(self.cursor, MIGRATION_CHUNK_SIZE)
+                .await
+                .context("migrate_legacy_call_traces()")?;
+            let Some(last_hash) = migrated.last_processed() else {
+                self.cursor = None;
+                break;
+            };
+            METRICS
+                .call_traces_migrated
+                .inc_by(migrated.migrated.len() as u64);
+            METRICS
+                .call_traces_skipped
+                .inc_by(migrated.skipped.len() as u64);
+            total_migrated_count += migrated.migrated.len();
+            self.cursor = Some(last_hash);
+        }
+        if total_migrated_count > 0 {
+            tracing::info!("Migrated {total_migrated_count} call traces to the flat format");
+        }
+        Ok(())
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.migration_interval_ms
+    }
+}
@@ -0,0 +1,93 @@
+use anyhow::Context as _;
+use async_trait::async_trait;
+use vise::{Gauge, LabeledFamily, Metrics, Unit};
+use zksync_config::configs::{disk_space_monitor::MonitoredPathConfig, DiskSpaceMonitorConfig};
+
+use crate::periodic_job::PeriodicJob;
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "disk_space_monitor")]
+struct DiskSpaceMonitorMetrics {
+    /// Free disk space available to the node on the volume containing the monitored path.
+    #[metrics(labels = ["path"], unit = Unit::Bytes)]
+    available_space: LabeledFamily<String, Gauge<u64>>,
+    /// Total disk space on the volume containing the monitored path.
+    #[metrics(labels = ["path"], unit = Unit::Bytes)]
+    total_space: LabeledFamily<String, Gauge<u64>>,
+    /// Set to 1 if free disk space for the monitored path is below the warning threshold.
+    #[metrics(labels = ["path"])]
+    low_space: LabeledFamily<String, Gauge<u64>>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<DiskSpaceMonitorMetrics> = vise::Global::new();
+
+/// Periodically checks free disk space on volumes containing the configured paths (e.g., RocksDB directories
+/// for the state keeper cache and the Merkle tree, or the Postgres data volume) and reports it as metrics.
+///
+/// If free space drops below the warning threshold for a path, a warning is logged. If it drops below
+/// the stop threshold, the monitor exits with an error, which stops the node gracefully; this prevents
+/// state-advancing components from corrupting RocksDB instances by running out of disk space.
+#[derive(Debug)]
+pub struct DiskSpaceMonitor {
+    config: DiskSpaceMonitorConfig,
+}
+
+impl DiskSpaceMonitor {
+    pub fn new(config: DiskSpaceMonitorConfig) -> Self {
+        Self { config }
+    }
+
+    fn check_path(path_config: &MonitoredPathConfig) -> anyhow::Result<()> {
+        let path = &path_config.path;
+        let available_space = match fs2::available_space(path) {
+            Ok(space) => space,
+            Err(err) => {
+                // The path may not be created yet (e.g., if the corresponding component hasn't started yet).
+                tracing::warn!("Cannot get available disk space for `{path}`: {err}");
+                return Ok(());
+            }
+        };
+        METRICS.available_space[path].set(available_space);
+        if let Ok(total_space) = fs2::total_space(path) {
+            METRICS.total_space[path].set(total_space);
+        }
+
+        let is_low_space = available_space < path_config.warn_threshold();
+        METRICS.low_space[path].set(is_low_space.into());
+        if let Some(stop_threshold) = path_config.stop_threshold() {
+            anyhow::ensure!(
+                available_space >= stop_threshold,
+                "free disk space for `{path}` ({} MiB) is below the stop threshold ({} MiB); \
+                 stopping the node to prevent data corruption",
+                available_space >> 20,
+                stop_threshold >> 20
+            );
+        }
+        if is_low_space {
+            tracing::warn!(
+                "Free disk space for `{path}` ({} MiB) is below the warning threshold ({} MiB)",
+                available_space >> 20,
+                path_config.warn_threshold_mb
+            );
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for DiskSpaceMonitor {
+    const SERVICE_NAME: &'static str = "DiskSpaceMonitor";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        for path_config in &self.config.paths {
+            Self::check_path(path_config)
+                .with_context(|| format!("path `{}`", path_config.path))?;
+        }
+        Ok(())
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.config.check_interval_ms
+    }
+}
@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use anyhow::Context as _;
+use vise::{Counter, Metrics};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+
+use crate::periodic_job::PeriodicJob;
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "house_keeper")]
+struct EthTxsHistoryArchiverMetrics {
+    eth_txs_history_archived: Counter,
+    eth_txs_archived: Counter,
+}
+
+#[vise::register]
+static METRICS: vise::Global<EthTxsHistoryArchiverMetrics> = vise::Global::new();
+
+/// Maximum number of rows archived by a single DB query.
+const ARCHIVING_CHUNK_SIZE: usize = 1_000;
+
+/// `EthTxsHistoryArchiver` is a task that periodically archives sending attempts for L1 transactions
+/// that were superseded by another attempt, once the L1 transaction has been confirmed for a certain amount of time.
+/// If configured, it also archives mined L1 transactions (together with all their sending attempts)
+/// older than the specified number of days. Archiving is performed in bounded chunks.
+/// Archived data can be restored using the block reverter CLI.
+#[derive(Debug)]
+pub struct EthTxsHistoryArchiver {
+    pool: ConnectionPool<Core>,
+    archiving_interval_ms: u64,
+    archive_after_secs: u64,
+    archive_eth_txs_after_days: Option<u32>,
+}
+
+impl EthTxsHistoryArchiver {
+    /// `pool` must point to the master DB.
+    pub fn new(
+        pool: ConnectionPool<Core>,
+        archiving_interval_ms: u64,
+        archive_after_secs: u64,
+        archive_eth_txs_after_days: Option<u32>,
+    ) -> Self {
+        Self {
+            pool,
+            archiving_interval_ms,
+            archive_after_secs,
+            archive_eth_txs_after_days,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PeriodicJob for EthTxsHistoryArchiver {
+    const SERVICE_NAME: &'static str = "EthTxsHistoryArchiver";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        let mut connection = self.pool.connection().await?;
+        let archive_after = Duration::from_secs(self.archive_after_secs);
+        let mut archived_count = 0;
+        loop {
+            let chunk_count = connection
+                .eth_sender_dal()
+                .archive_eth_txs_history(archive_after, ARCHIVING_CHUNK_SIZE)
+                .await
+                .context("archive_eth_txs_history()")?;
+            archived_count += chunk_count;
+            METRICS.eth_txs_history_archived.inc_by(chunk_count as u64);
+            if chunk_count < ARCHIVING_CHUNK_SIZE {
+                break;
+            }
+        }
+        tracing::info!("Archived {archived_count} L1 transaction sending attempts");
+
+        let Some(archive_eth_txs_after_days) = self.archive_eth_txs_after_days else {
+            return Ok(());
+        };
+        let archive_after = Duration::from_secs(u64::from(archive_eth_txs_after_days) * 86_400);
+        let mut archived_count = 0;
+        loop {
+            let chunk_count = connection
+                .eth_sender_dal()
+                .archive_eth_txs(archive_after, ARCHIVING_CHUNK_SIZE)
+                .await
+                .context("archive_eth_txs()")?;
+            archived_count += chunk_count;
+            METRICS.eth_txs_archived.inc_by(chunk_count as u64);
+            if chunk_count < ARCHIVING_CHUNK_SIZE {
+                break;
+            }
+        }
+        tracing::info!("Archived {archived_count} mined L1 transactions");
+        Ok(())
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.archiving_interval_ms
+    }
+}
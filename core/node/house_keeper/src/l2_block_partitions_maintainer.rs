@@ -0,0 +1,70 @@
+use anyhow::Context as _;
+use zksync_dal::{partitioning_dal::PartitionedTable, ConnectionPool, Core, CoreDal};
+use zksync_types::L2BlockNumber;
+
+use crate::periodic_job::PeriodicJob;
+
+/// Number of partitions created ahead of the sealed L2 block, so that new rows never end up in the default partition.
+const PARTITIONS_AHEAD: u32 = 2;
+
+/// `L2BlockPartitionsMaintainer` is a task that periodically creates partitions for tables partitioned
+/// by L2 block ranges (see the `table_partitioner` tool). Tables that aren't partitioned are skipped.
+#[derive(Debug)]
+pub struct L2BlockPartitionsMaintainer {
+    pool: ConnectionPool<Core>,
+    maintenance_interval_ms: u64,
+    l2_blocks_per_partition: u32,
+}
+
+impl L2BlockPartitionsMaintainer {
+    /// `pool` must point to the master DB.
+    pub fn new(
+        pool: ConnectionPool<Core>,
+        maintenance_interval_ms: u64,
+        l2_blocks_per_partition: u32,
+    ) -> Self {
+        Self {
+            pool,
+            maintenance_interval_ms,
+            l2_blocks_per_partition,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PeriodicJob for L2BlockPartitionsMaintainer {
+    const SERVICE_NAME: &'static str = "L2BlockPartitionsMaintainer";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        let mut conn = self.pool.connection().await?;
+        let sealed_l2_block = conn
+            .blocks_dal()
+            .get_sealed_l2_block_number()
+            .await?
+            .unwrap_or_default();
+        let up_to = L2BlockNumber(
+            sealed_l2_block.0.saturating_add(
+                self.l2_blocks_per_partition
+                    .saturating_mul(PARTITIONS_AHEAD),
+            ),
+        );
+
+        for table in PartitionedTable::ALL {
+            let created_count = conn
+                .partitioning_dal()
+                .ensure_partitions(table, up_to, self.l2_blocks_per_partition)
+                .await
+                .with_context(|| format!("ensure_partitions({table})"))?;
+            if created_count > 0 {
+                tracing::info!(
+                    "Created {created_count} partitions for table `{table}` up to L2 block #{up_to}"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.maintenance_interval_ms
+    }
+}
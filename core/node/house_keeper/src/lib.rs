@@ -1,3 +1,8 @@
 pub mod blocks_state_reporter;
+pub mod call_traces_migrator;
+pub mod disk_space_monitor;
+pub mod eth_txs_history_archiver;
+pub mod l2_block_partitions_maintainer;
 pub mod periodic_job;
 pub mod prover;
+pub mod rejected_txs_pruner;
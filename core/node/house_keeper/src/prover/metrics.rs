@@ -29,6 +29,9 @@ pub(crate) struct ProverFriMetrics {
     #[metrics(labels = ["type", "protocol_version"])]
     pub proof_compressor_jobs: LabeledFamily<(JobStatus, String), Gauge<u64>, 2>,
     pub proof_compressor_oldest_uncompressed_batch: Gauge<u64>,
+    /// Number of jobs in each lifecycle state for all prover job queues.
+    #[metrics(labels = ["queue", "state"])]
+    pub jobs_by_state: LabeledFamily<(&'static str, &'static str), Gauge<u64>, 2>,
 }
 
 #[vise::register]
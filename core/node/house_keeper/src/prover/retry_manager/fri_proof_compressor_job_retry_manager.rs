@@ -1,7 +1,8 @@
 use std::time::Duration;
 
+use anyhow::Context as _;
 use async_trait::async_trait;
-use prover_dal::{Prover, ProverDal};
+use prover_dal::{fri_jobs_dal::FriJobQueue, Prover, ProverDal};
 use zksync_dal::ConnectionPool;
 
 use crate::{periodic_job::PeriodicJob, prover::metrics::PROVER_FRI_METRICS};
@@ -36,14 +37,21 @@ impl PeriodicJob for FriProofCompressorJobRetryManager {
     const SERVICE_NAME: &'static str = "FriProofCompressorJobRetryManager";
 
     async fn run_routine_task(&mut self) -> anyhow::Result<()> {
-        let stuck_jobs = self
-            .pool
-            .connection()
+        let mut conn = self.pool.connection().await?;
+        let stuck_jobs = conn
+            .fri_jobs_dal()
+            .requeue_stuck_jobs(
+                FriJobQueue::ProofCompressor,
+                self.processing_timeout,
+                self.max_attempts,
+            )
             .await
-            .unwrap()
-            .fri_proof_compressor_dal()
-            .requeue_stuck_jobs(self.processing_timeout, self.max_attempts)
-            .await;
+            .with_context(|| {
+                format!(
+                    "failed requeuing stuck jobs in {}",
+                    FriJobQueue::ProofCompressor
+                )
+            })?;
         let job_len = stuck_jobs.len();
         for stuck_job in stuck_jobs {
             tracing::info!("re-queuing fri proof compressor job {:?}", stuck_job);
@@ -51,6 +59,8 @@ impl PeriodicJob for FriProofCompressorJobRetryManager {
         PROVER_FRI_METRICS
             .proof_compressor_requeued_jobs
             .inc_by(job_len as u64);
+        super::report_job_states(&mut conn, FriJobQueue::ProofCompressor, self.max_attempts)
+            .await?;
         Ok(())
     }
 
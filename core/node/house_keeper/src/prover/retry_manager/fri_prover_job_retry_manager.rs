@@ -1,7 +1,8 @@
 use std::time::Duration;
 
+use anyhow::Context as _;
 use async_trait::async_trait;
-use prover_dal::{Prover, ProverDal};
+use prover_dal::{fri_jobs_dal::FriJobQueue, Prover, ProverDal};
 use zksync_dal::ConnectionPool;
 
 use crate::{periodic_job::PeriodicJob, prover::metrics::SERVER_METRICS};
@@ -36,14 +37,16 @@ impl PeriodicJob for FriProverJobRetryManager {
     const SERVICE_NAME: &'static str = "FriProverJobRetryManager";
 
     async fn run_routine_task(&mut self) -> anyhow::Result<()> {
-        let stuck_jobs = self
-            .pool
-            .connection()
+        let mut conn = self.pool.connection().await?;
+        let stuck_jobs = conn
+            .fri_jobs_dal()
+            .requeue_stuck_jobs(
+                FriJobQueue::Prover,
+                self.processing_timeout,
+                self.max_attempts,
+            )
             .await
-            .unwrap()
-            .fri_prover_jobs_dal()
-            .requeue_stuck_jobs(self.processing_timeout, self.max_attempts)
-            .await;
+            .with_context(|| format!("failed requeuing stuck jobs in {}", FriJobQueue::Prover))?;
         let job_len = stuck_jobs.len();
         for stuck_job in stuck_jobs {
             tracing::info!("re-queuing fri prover job {:?}", stuck_job);
@@ -51,6 +54,7 @@ impl PeriodicJob for FriProverJobRetryManager {
         SERVER_METRICS
             .prover_fri_requeued_jobs
             .inc_by(job_len as u64);
+        super::report_job_states(&mut conn, FriJobQueue::Prover, self.max_attempts).await?;
         Ok(())
     }
 
@@ -1,5 +1,8 @@
+use std::time::Duration;
+
+use anyhow::Context as _;
 use async_trait::async_trait;
-use prover_dal::{Prover, ProverDal};
+use prover_dal::{fri_jobs_dal::FriJobQueue, Prover, ProverDal};
 use zksync_config::configs::fri_witness_generator::WitnessGenerationTimeouts;
 use zksync_dal::ConnectionPool;
 use zksync_types::prover_dal::StuckJobs;
@@ -41,73 +44,53 @@ impl FriWitnessGeneratorJobRetryManager {
             .inc_by(stuck_jobs.len() as u64);
     }
 
-    pub async fn requeue_stuck_witness_inputs_jobs(&mut self) {
-        let stuck_jobs = self
-            .pool
-            .connection()
-            .await
-            .unwrap()
-            .fri_witness_generator_dal()
-            .requeue_stuck_jobs(self.processing_timeouts.basic(), self.max_attempts)
-            .await;
-        self.emit_telemetry("witness_inputs_fri", &stuck_jobs);
-    }
-
-    pub async fn requeue_stuck_leaf_aggregations_jobs(&mut self) {
-        let stuck_jobs = self
-            .pool
-            .connection()
-            .await
-            .unwrap()
-            .fri_witness_generator_dal()
-            .requeue_stuck_leaf_aggregations_jobs(
-                self.processing_timeouts.leaf(),
-                self.max_attempts,
-            )
-            .await;
-        self.emit_telemetry("leaf_aggregations_jobs_fri", &stuck_jobs);
-    }
-
-    pub async fn requeue_stuck_node_aggregations_jobs(&mut self) {
-        let stuck_jobs = self
-            .pool
-            .connection()
-            .await
-            .unwrap()
-            .fri_witness_generator_dal()
-            .requeue_stuck_node_aggregations_jobs(
-                self.processing_timeouts.node(),
-                self.max_attempts,
-            )
-            .await;
-        self.emit_telemetry("node_aggregations_jobs_fri", &stuck_jobs);
-    }
-
-    pub async fn requeue_stuck_recursion_tip_jobs(&mut self) {
-        let stuck_jobs = self
-            .pool
-            .connection()
-            .await
-            .unwrap()
-            .fri_witness_generator_dal()
-            .requeue_stuck_recursion_tip_jobs(
-                self.processing_timeouts.recursion_tip(),
-                self.max_attempts,
-            )
-            .await;
-        self.emit_telemetry("recursion_tip_jobs_fri", &stuck_jobs);
+    /// Returns witness generator job queues together with their witness types used in metrics
+    /// and processing timeouts.
+    fn queues(&self) -> [(FriJobQueue, &'static str, Duration); 5] {
+        let timeouts = &self.processing_timeouts;
+        [
+            (
+                FriJobQueue::WitnessInputs,
+                "witness_inputs_fri",
+                timeouts.basic(),
+            ),
+            (
+                FriJobQueue::LeafAggregation,
+                "leaf_aggregations_jobs_fri",
+                timeouts.leaf(),
+            ),
+            (
+                FriJobQueue::NodeAggregation,
+                "node_aggregations_jobs_fri",
+                timeouts.node(),
+            ),
+            (
+                FriJobQueue::RecursionTip,
+                "recursion_tip_jobs_fri",
+                timeouts.recursion_tip(),
+            ),
+            (
+                FriJobQueue::Scheduler,
+                "scheduler_jobs_fri",
+                timeouts.scheduler(),
+            ),
+        ]
     }
 
-    pub async fn requeue_stuck_scheduler_jobs(&mut self) {
-        let stuck_jobs = self
-            .pool
-            .connection()
+    pub async fn requeue_stuck_jobs(
+        &mut self,
+        queue: FriJobQueue,
+        witness_type: &str,
+        processing_timeout: Duration,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.pool.connection().await?;
+        let stuck_jobs = conn
+            .fri_jobs_dal()
+            .requeue_stuck_jobs(queue, processing_timeout, self.max_attempts)
             .await
-            .unwrap()
-            .fri_witness_generator_dal()
-            .requeue_stuck_scheduler_jobs(self.processing_timeouts.scheduler(), self.max_attempts)
-            .await;
-        self.emit_telemetry("scheduler_jobs_fri", &stuck_jobs);
+            .with_context(|| format!("failed requeuing stuck jobs in {queue}"))?;
+        self.emit_telemetry(witness_type, &stuck_jobs);
+        super::report_job_states(&mut conn, queue, self.max_attempts).await
     }
 }
 
@@ -116,11 +99,10 @@ impl PeriodicJob for FriWitnessGeneratorJobRetryManager {
     const SERVICE_NAME: &'static str = "FriWitnessGeneratorJobRetryManager";
 
     async fn run_routine_task(&mut self) -> anyhow::Result<()> {
-        self.requeue_stuck_witness_inputs_jobs().await;
-        self.requeue_stuck_leaf_aggregations_jobs().await;
-        self.requeue_stuck_node_aggregations_jobs().await;
-        self.requeue_stuck_recursion_tip_jobs().await;
-        self.requeue_stuck_scheduler_jobs().await;
+        for (queue, witness_type, processing_timeout) in self.queues() {
+            self.requeue_stuck_jobs(queue, witness_type, processing_timeout)
+                .await?;
+        }
         Ok(())
     }
 
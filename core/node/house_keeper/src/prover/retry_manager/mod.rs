@@ -1,3 +1,9 @@
+use anyhow::Context as _;
+use prover_dal::{fri_jobs_dal::FriJobQueue, Connection, Prover, ProverDal};
+use zksync_types::prover_dal::JobStateCounts;
+
+use crate::prover::metrics::PROVER_FRI_METRICS;
+
 mod fri_proof_compressor_job_retry_manager;
 mod fri_prover_job_retry_manager;
 mod fri_witness_generator_jobs_retry_manager;
@@ -5,3 +11,34 @@ mod fri_witness_generator_jobs_retry_manager;
 pub use fri_proof_compressor_job_retry_manager::FriProofCompressorJobRetryManager;
 pub use fri_prover_job_retry_manager::FriProverJobRetryManager;
 pub use fri_witness_generator_jobs_retry_manager::FriWitnessGeneratorJobRetryManager;
+
+/// Reports the number of jobs in each lifecycle state for the queue. Retry managers report these metrics since
+/// dead jobs are defined by `max_attempts` they are configured with.
+async fn report_job_states(
+    conn: &mut Connection<'_, Prover>,
+    queue: FriJobQueue,
+    max_attempts: u32,
+) -> anyhow::Result<()> {
+    let JobStateCounts {
+        queued,
+        in_progress,
+        failed,
+        successful,
+        dead,
+    } = conn
+        .fri_jobs_dal()
+        .get_job_state_counts(queue, max_attempts)
+        .await
+        .with_context(|| format!("failed getting job state counts for {queue}"))?;
+    let queue = queue.table_name();
+    for (state, count) in [
+        ("queued", queued),
+        ("in_progress", in_progress),
+        ("failed", failed),
+        ("successful", successful),
+        ("dead", dead),
+    ] {
+        PROVER_FRI_METRICS.jobs_by_state[&(queue, state)].set(count as u64);
+    }
+    Ok(())
+}
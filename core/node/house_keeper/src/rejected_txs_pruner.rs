@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use anyhow::Context as _;
+use chrono::Utc;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+
+use crate::periodic_job::PeriodicJob;
+
+/// `RejectedTransactionsPruner` is a task that periodically removes transactions older than the retention period
+/// from the journal of transactions rejected by the API server on submission.
+#[derive(Debug)]
+pub struct RejectedTransactionsPruner {
+    pool: ConnectionPool<Core>,
+    pruning_interval_ms: u64,
+    retention: Duration,
+}
+
+impl RejectedTransactionsPruner {
+    /// `pool` must point to the master DB.
+    pub fn new(pool: ConnectionPool<Core>, pruning_interval_ms: u64, retention: Duration) -> Self {
+        Self {
+            pool,
+            pruning_interval_ms,
+            retention,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PeriodicJob for RejectedTransactionsPruner {
+    const SERVICE_NAME: &'static str = "RejectedTransactionsPruner";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        let retention = chrono::Duration::from_std(self.retention).context("invalid retention")?;
+        let rejected_before = Utc::now() - retention;
+        let pruned_count = self
+            .pool
+            .connection()
+            .await?
+            .rejected_transactions_dal()
+            .prune_rejected_transactions(rejected_before)
+            .await?;
+        if pruned_count > 0 {
+            tracing::info!(
+                "Pruned {pruned_count} transactions rejected before {rejected_before} from the journal"
+            );
+        }
+        Ok(())
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.pruning_interval_ms
+    }
+}
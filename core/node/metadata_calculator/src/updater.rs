@@ -5,7 +5,10 @@ use std::{ops, sync::Arc, time::Instant};
 use anyhow::Context as _;
 use futures::{future, FutureExt};
 use tokio::sync::watch;
-use zksync_dal::{helpers::wait_for_l1_batch, Connection, ConnectionPool, Core, CoreDal};
+use zksync_dal::{
+    helpers::wait_for_l1_batch, watermarks_dal::WatermarkComponent, Connection, ConnectionPool,
+    Core, CoreDal,
+};
 use zksync_merkle_tree::domain::TreeMetadata;
 use zksync_object_store::ObjectStore;
 use zksync_types::{
@@ -130,11 +133,18 @@ impl TreeUpdater {
                 hash: metadata.root_hash,
                 rollup_last_leaf_index: metadata.rollup_last_leaf_index,
             };
-            storage
+            let mut transaction = storage.start_transaction().await?;
+            transaction
                 .blocks_dal()
                 .save_l1_batch_tree_data(l1_batch_number, &tree_data)
                 .await
                 .context("failed saving tree data")?;
+            transaction
+                .watermarks_dal()
+                .set_watermark(WatermarkComponent::Tree, l1_batch_number)
+                .await
+                .context("failed publishing tree watermark")?;
+            transaction.commit().await?;
             // ^ Note that `save_l1_batch_tree_data()` will not blindly overwrite changes if L1 batch
             // metadata already exists; instead, it'll check that the old and new metadata match.
             // That is, if we run multiple tree instances, we'll get metadata correspondence
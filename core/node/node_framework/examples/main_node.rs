@@ -21,7 +21,7 @@ use zksync_config::{
 use zksync_env_config::FromEnv;
 use zksync_metadata_calculator::MetadataCalculatorConfig;
 use zksync_node_api_server::{
-    tx_sender::{ApiContracts, TxSenderConfig},
+    tx_sender::{master_pool_sink::MempoolQuotas, ApiContracts, TxSenderConfig},
     web3::{state::InternalApiConfig, Namespace},
 };
 use zksync_node_framework::{
@@ -197,7 +197,10 @@ impl MainNodeBuilder {
         let wallets = Wallets::from_env()?;
 
         // On main node we always use master pool sink.
-        self.node.add_layer(TxSinkLayer::MasterPoolSink);
+        self.node.add_layer(TxSinkLayer::MasterPoolSink {
+            replacement_fee_bump_percent: rpc_config.tx_replacement_fee_bump_percent(),
+            quotas: MempoolQuotas::from_web3_config(&rpc_config),
+        });
         self.node.add_layer(TxSenderLayer::new(
             TxSenderConfig::new(
                 &state_keeper_config,
@@ -0,0 +1,55 @@
+use zksync_config::configs::DiskSpaceMonitorConfig;
+use zksync_house_keeper::{disk_space_monitor::DiskSpaceMonitor, periodic_job::PeriodicJob};
+
+use crate::{
+    service::{ServiceContext, StopReceiver},
+    task::{Task, TaskId},
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Builder for the disk space monitor.
+///
+/// ## Effects
+///
+/// - Adds `disk_space_monitor` to the node. The task exits with an error (thus stopping the node)
+///   if free disk space for any of the monitored paths drops below the configured stop threshold.
+#[derive(Debug)]
+pub struct DiskSpaceMonitorLayer {
+    config: DiskSpaceMonitorConfig,
+}
+
+impl DiskSpaceMonitorLayer {
+    pub fn new(config: DiskSpaceMonitorConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for DiskSpaceMonitorLayer {
+    fn layer_name(&self) -> &'static str {
+        "disk_space_monitor_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        context.add_task(Box::new(DiskSpaceMonitorTask {
+            monitor: DiskSpaceMonitor::new(self.config),
+        }));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct DiskSpaceMonitorTask {
+    monitor: DiskSpaceMonitor,
+}
+
+#[async_trait::async_trait]
+impl Task for DiskSpaceMonitorTask {
+    fn id(&self) -> TaskId {
+        "disk_space_monitor".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.monitor.run(stop_receiver.0).await
+    }
+}
@@ -2,13 +2,16 @@ use anyhow::Context;
 use zksync_circuit_breaker::l1_txs::FailedL1TransactionChecker;
 use zksync_config::configs::{eth_sender::EthConfig, ContractsConfig};
 use zksync_eth_client::BoundEthInterface;
-use zksync_eth_sender::{Aggregator, EthTxAggregator, EthTxManager};
+use zksync_eth_sender::{Aggregator, EthTxAggregator, EthTxManager, WithdrawalLimiter};
 use zksync_types::{commitment::L1BatchCommitmentMode, L2ChainId};
 
 use crate::{
     implementations::resources::{
         circuit_breakers::CircuitBreakersResource,
-        eth_interface::{BoundEthInterfaceForBlobsResource, BoundEthInterfaceResource},
+        eth_interface::{
+            BoundEthInterfaceForBlobsResource, BoundEthInterfaceResource,
+            L1TxSubmissionStrategyResource,
+        },
         l1_tx_params::L1TxParamsResource,
         object_store::ObjectStoreResource,
         pools::{MasterPool, PoolResource, ReplicaPool},
@@ -52,17 +55,29 @@ impl WiringLayer for EthTxManagerLayer {
             Err(err) => return Err(err),
         };
 
+        let submission_strategy = match context
+            .get_resource::<L1TxSubmissionStrategyResource>()
+            .await
+        {
+            Ok(L1TxSubmissionStrategyResource(strategy)) => Some(strategy),
+            Err(WiringError::ResourceLacking { .. }) => None,
+            Err(err) => return Err(err),
+        };
+
         let config = self.eth_sender_config.sender.context("sender")?;
 
         let gas_adjuster = context.get_resource::<L1TxParamsResource>().await?.0;
 
-        let eth_tx_manager_actor = EthTxManager::new(
+        let mut eth_tx_manager_actor = EthTxManager::new(
             master_pool,
             config,
             gas_adjuster,
             eth_client,
             eth_client_blobs,
         );
+        if let Some(strategy) = submission_strategy {
+            eth_tx_manager_actor = eth_tx_manager_actor.with_submission_strategy(strategy);
+        }
 
         context.add_task(Box::new(EthTxManagerTask {
             eth_tx_manager_actor,
@@ -132,12 +147,23 @@ impl WiringLayer for EthTxAggregatorLayer {
             .map(BoundEthInterface::sender_account);
 
         let config = self.eth_sender_config.sender.context("sender")?;
-        let aggregator = Aggregator::new(
+        let mut aggregator = Aggregator::new(
             config.clone(),
             object_store,
             eth_client_blobs_addr.is_some(),
             self.l1_batch_commit_data_generator_mode,
         );
+        if let Some(withdrawal_limits) = self.eth_sender_config.withdrawal_limits {
+            let l2_bridge_addresses = [
+                self.contracts_config.l2_shared_bridge_addr,
+                self.contracts_config.l2_erc20_bridge_addr,
+            ];
+            let l2_bridge_addresses = l2_bridge_addresses.into_iter().flatten().collect();
+            aggregator = aggregator.with_withdrawal_limiter(WithdrawalLimiter::new(
+                withdrawal_limits,
+                l2_bridge_addresses,
+            ));
+        }
 
         let eth_tx_aggregator_actor = EthTxAggregator::new(
             master_pool.clone(),
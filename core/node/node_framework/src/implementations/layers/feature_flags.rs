@@ -0,0 +1,68 @@
+use zksync_config::configs::FeatureFlagsConfig;
+use zksync_feature_flags::FeatureFlagsUpdater;
+
+use crate::{
+    implementations::resources::{
+        feature_flags::FeatureFlagsResource, healthcheck::AppHealthCheckResource,
+    },
+    service::{ServiceContext, StopReceiver},
+    task::{Task, TaskId},
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Builder for runtime-resolved feature flags.
+///
+/// ## Adds resources
+///
+/// - `FeatureFlagsResource`. Layers consulting feature flags should be added after this layer.
+///
+/// ## Effects
+///
+/// - Adds the `feature_flags` health check to the `AppHealthCheckResource`; its details contain resolved flags.
+/// - Adds `feature_flags_updater` to the node. The task polls the remote source of flags, if one is configured.
+#[derive(Debug)]
+pub struct FeatureFlagsLayer {
+    config: FeatureFlagsConfig,
+}
+
+impl FeatureFlagsLayer {
+    pub fn new(config: FeatureFlagsConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for FeatureFlagsLayer {
+    fn layer_name(&self) -> &'static str {
+        "feature_flags_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let updater = FeatureFlagsUpdater::new(&self.config);
+        context.insert_resource(FeatureFlagsResource(updater.flags()))?;
+
+        let AppHealthCheckResource(app_health) = context.get_resource_or_default().await;
+        app_health
+            .insert_component(updater.health_check())
+            .map_err(WiringError::internal)?;
+
+        context.add_task(Box::new(FeatureFlagsUpdaterTask { updater }));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct FeatureFlagsUpdaterTask {
+    updater: FeatureFlagsUpdater,
+}
+
+#[async_trait::async_trait]
+impl Task for FeatureFlagsUpdaterTask {
+    fn id(&self) -> TaskId {
+        "feature_flags_updater".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.updater.run(stop_receiver.0).await
+    }
+}
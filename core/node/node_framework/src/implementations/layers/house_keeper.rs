@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{fmt, time::Duration};
 
 use zksync_config::configs::{
     fri_prover_group::FriProverGroupConfig, house_keeper::HouseKeeperConfig,
@@ -7,6 +7,9 @@ use zksync_config::configs::{
 use zksync_dal::{metrics::PostgresMetrics, ConnectionPool, Core};
 use zksync_house_keeper::{
     blocks_state_reporter::L1BatchMetricsReporter,
+    call_traces_migrator::CallTracesMigrator,
+    eth_txs_history_archiver::EthTxsHistoryArchiver,
+    l2_block_partitions_maintainer::L2BlockPartitionsMaintainer,
     periodic_job::PeriodicJob,
     prover::{
         FriGpuProverArchiver, FriProofCompressorJobRetryManager, FriProofCompressorQueueReporter,
@@ -14,10 +17,14 @@ use zksync_house_keeper::{
         FriWitnessGeneratorJobRetryManager, FriWitnessGeneratorQueueReporter,
         WaitingToQueuedFriWitnessJobMover,
     },
+    rejected_txs_pruner::RejectedTransactionsPruner,
 };
 
 use crate::{
-    implementations::resources::pools::{PoolResource, ProverPool, ReplicaPool},
+    implementations::resources::{
+        pools::{MasterPool, PoolResource, ProverPool, ReplicaPool},
+        scheduler::{ScheduledJobs, ScheduledJobsResource},
+    },
     service::{ServiceContext, StopReceiver},
     task::{Task, TaskId},
     wiring_layer::{WiringError, WiringLayer},
@@ -25,6 +32,17 @@ use crate::{
 
 const SCRAPE_INTERVAL: Duration = Duration::from_secs(60);
 
+/// Wiring layer for house keeper tasks.
+///
+/// ## Requests resources
+///
+/// - `PoolResource<ReplicaPool>`
+/// - `PoolResource<ProverPool>`
+/// - `PoolResource<MasterPool>` (only if jobs that modify the main DB are enabled, including the rejected transactions
+///   pruner)
+/// - `ScheduledJobsResource` (optional). If present, archivers, the L2 block partitions maintainer and
+///   the call traces migrator that have a schedule in the scheduler config are run by the scheduler
+///   instead of their own interval loops.
 #[derive(Debug)]
 pub struct HouseKeeperLayer {
     house_keeper_config: HouseKeeperConfig,
@@ -32,6 +50,7 @@ pub struct HouseKeeperLayer {
     fri_witness_generator_config: FriWitnessGeneratorConfig,
     fri_prover_group_config: FriProverGroupConfig,
     fri_proof_compressor_config: FriProofCompressorConfig,
+    rejected_txs_retention: Option<Duration>,
 }
 
 impl HouseKeeperLayer {
@@ -48,8 +67,15 @@ impl HouseKeeperLayer {
             fri_witness_generator_config,
             fri_prover_group_config,
             fri_proof_compressor_config,
+            rejected_txs_retention: None,
         }
     }
+
+    /// Enables pruning the journal of transactions rejected by the API server, with the specified retention period.
+    pub fn with_rejected_txs_retention(mut self, retention: Option<Duration>) -> Self {
+        self.rejected_txs_retention = retention;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -66,6 +92,13 @@ impl WiringLayer for HouseKeeperLayer {
         let prover_pool_resource = context.get_resource::<PoolResource<ProverPool>>().await?;
         let prover_pool = prover_pool_resource.get().await?;
 
+        let scheduled_jobs = match context.get_resource::<ScheduledJobsResource>().await {
+            Ok(resource) => Some(resource.jobs),
+            Err(WiringError::ResourceLacking { .. }) => None,
+            Err(err) => return Err(err),
+        };
+        let scheduled_jobs = scheduled_jobs.as_deref();
+
         // initialize and add tasks
         let pool_for_metrics = replica_pool_resource.get_singleton().await?;
         context.add_task(Box::new(PostgresMetricsScrapingTask { pool_for_metrics }));
@@ -79,6 +112,84 @@ impl WiringLayer for HouseKeeperLayer {
             l1_batch_metrics_reporter,
         }));
 
+        if let Some((archiving_interval, archive_after)) =
+            self.house_keeper_config.eth_txs_history_archiver_params()
+        {
+            let master_pool = context
+                .get_resource::<PoolResource<MasterPool>>()
+                .await?
+                .get()
+                .await?;
+            let eth_txs_history_archiver = EthTxsHistoryArchiver::new(
+                master_pool,
+                archiving_interval,
+                archive_after,
+                self.house_keeper_config
+                    .eth_txs_history_archiver_archive_eth_txs_after_days,
+            );
+            if let Some(eth_txs_history_archiver) =
+                schedule_job(scheduled_jobs, eth_txs_history_archiver)
+            {
+                context.add_task(Box::new(EthTxsHistoryArchiverTask {
+                    eth_txs_history_archiver,
+                }));
+            }
+        }
+
+        if let Some((maintenance_interval, l2_blocks_per_partition)) = self
+            .house_keeper_config
+            .l2_block_partitions_maintainer_params()
+        {
+            let master_pool = context
+                .get_resource::<PoolResource<MasterPool>>()
+                .await?
+                .get()
+                .await?;
+            let l2_block_partitions_maintainer = L2BlockPartitionsMaintainer::new(
+                master_pool,
+                maintenance_interval,
+                l2_blocks_per_partition,
+            );
+            if let Some(l2_block_partitions_maintainer) =
+                schedule_job(scheduled_jobs, l2_block_partitions_maintainer)
+            {
+                context.add_task(Box::new(L2BlockPartitionsMaintainerTask {
+                    l2_block_partitions_maintainer,
+                }));
+            }
+        }
+
+        if let Some(migration_interval) = self.house_keeper_config.call_traces_migration_interval_ms
+        {
+            let master_pool = context
+                .get_resource::<PoolResource<MasterPool>>()
+                .await?
+                .get()
+                .await?;
+            let call_traces_migrator = CallTracesMigrator::new(master_pool, migration_interval);
+            if let Some(call_traces_migrator) = schedule_job(scheduled_jobs, call_traces_migrator) {
+                context.add_task(Box::new(CallTracesMigratorTask {
+                    call_traces_migrator,
+                }));
+            }
+        }
+
+        if let Some(retention) = self.rejected_txs_retention {
+            let master_pool = context
+                .get_resource::<PoolResource<MasterPool>>()
+                .await?
+                .get()
+                .await?;
+            let rejected_txs_pruner = RejectedTransactionsPruner::new(
+                master_pool,
+                self.house_keeper_config.rejected_txs_pruning_interval_ms(),
+                retention,
+            );
+            context.add_task(Box::new(RejectedTransactionsPrunerTask {
+                rejected_txs_pruner,
+            }));
+        }
+
         let fri_prover_job_retry_manager = FriProverJobRetryManager::new(
             self.fri_prover_config.max_attempts,
             self.fri_prover_config.proof_generation_timeout(),
@@ -114,9 +225,13 @@ impl WiringLayer for HouseKeeperLayer {
         {
             let fri_prover_job_archiver =
                 FriProverJobsArchiver::new(prover_pool.clone(), archiving_interval, archive_after);
-            context.add_task(Box::new(FriProverJobArchiverTask {
-                fri_prover_job_archiver,
-            }));
+            if let Some(fri_prover_job_archiver) =
+                schedule_job(scheduled_jobs, fri_prover_job_archiver)
+            {
+                context.add_task(Box::new(FriProverJobArchiverTask {
+                    fri_prover_job_archiver,
+                }));
+            }
         }
 
         if let Some((archiving_interval, archive_after)) =
@@ -124,9 +239,13 @@ impl WiringLayer for HouseKeeperLayer {
         {
             let fri_prover_gpu_archiver =
                 FriGpuProverArchiver::new(prover_pool.clone(), archiving_interval, archive_after);
-            context.add_task(Box::new(FriProverGpuArchiverTask {
-                fri_prover_gpu_archiver,
-            }));
+            if let Some(fri_prover_gpu_archiver) =
+                schedule_job(scheduled_jobs, fri_prover_gpu_archiver)
+            {
+                context.add_task(Box::new(FriProverGpuArchiverTask {
+                    fri_prover_gpu_archiver,
+                }));
+            }
         }
 
         let fri_witness_generator_stats_reporter = FriWitnessGeneratorQueueReporter::new(
@@ -172,6 +291,18 @@ impl WiringLayer for HouseKeeperLayer {
     }
 }
 
+/// Registers `job` in the scheduler if it has a schedule. Otherwise, returns the job back, so that it can be run
+/// in its own interval loop.
+fn schedule_job<J: PeriodicJob + fmt::Debug + 'static>(
+    scheduled_jobs: Option<&ScheduledJobs>,
+    job: J,
+) -> Option<J> {
+    match scheduled_jobs {
+        Some(jobs) => jobs.insert_periodic(job),
+        None => Some(job),
+    }
+}
+
 #[derive(Debug)]
 struct PostgresMetricsScrapingTask {
     pool_for_metrics: ConnectionPool<Core>,
@@ -212,6 +343,22 @@ impl Task for L1BatchMetricsReporterTask {
     }
 }
 
+#[derive(Debug)]
+struct RejectedTransactionsPrunerTask {
+    rejected_txs_pruner: RejectedTransactionsPruner,
+}
+
+#[async_trait::async_trait]
+impl Task for RejectedTransactionsPrunerTask {
+    fn id(&self) -> TaskId {
+        "rejected_txs_pruner".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.rejected_txs_pruner.run(stop_receiver.0).await
+    }
+}
+
 #[derive(Debug)]
 struct FriProverJobRetryManagerTask {
     fri_prover_job_retry_manager: FriProverJobRetryManager,
@@ -350,6 +497,53 @@ impl Task for FriProverJobArchiverTask {
     }
 }
 
+struct EthTxsHistoryArchiverTask {
+    eth_txs_history_archiver: EthTxsHistoryArchiver,
+}
+
+#[async_trait::async_trait]
+impl Task for EthTxsHistoryArchiverTask {
+    fn id(&self) -> TaskId {
+        "eth_txs_history_archiver".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.eth_txs_history_archiver.run(stop_receiver.0).await
+    }
+}
+
+struct L2BlockPartitionsMaintainerTask {
+    l2_block_partitions_maintainer: L2BlockPartitionsMaintainer,
+}
+
+#[async_trait::async_trait]
+impl Task for L2BlockPartitionsMaintainerTask {
+    fn id(&self) -> TaskId {
+        "l2_block_partitions_maintainer".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.l2_block_partitions_maintainer
+            .run(stop_receiver.0)
+            .await
+    }
+}
+
+struct CallTracesMigratorTask {
+    call_traces_migrator: CallTracesMigrator,
+}
+
+#[async_trait::async_trait]
+impl Task for CallTracesMigratorTask {
+    fn id(&self) -> TaskId {
+        "call_traces_migrator".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.call_traces_migrator.run(stop_receiver.0).await
+    }
+}
+
 struct FriProverGpuArchiverTask {
     fri_prover_gpu_archiver: FriGpuProverArchiver,
 }
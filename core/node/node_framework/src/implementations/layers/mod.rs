@@ -3,8 +3,10 @@ pub mod commitment_generator;
 pub mod consensus;
 pub mod consistency_checker;
 pub mod contract_verification_api;
+pub mod disk_space_monitor;
 pub mod eth_sender;
 pub mod eth_watch;
+pub mod feature_flags;
 pub mod healtcheck_server;
 pub mod house_keeper;
 pub mod l1_gas;
@@ -18,8 +20,10 @@ pub mod proof_data_handler;
 pub mod query_eth_client;
 pub mod reorg_detector_checker;
 pub mod reorg_detector_runner;
+pub mod scheduler;
 pub mod sigint;
 pub mod state_keeper;
+pub mod supply_invariant_checker;
 pub mod tee_verifier_input_producer;
 pub mod vm_runner;
 pub mod web3_api;
@@ -1,14 +1,18 @@
+use std::sync::Arc;
+
 use anyhow::Context as _;
 use zksync_config::{
     configs::{wallets, ContractsConfig},
     EthConfig,
 };
 use zksync_eth_client::clients::PKSigningClient;
+use zksync_eth_sender::Erc2771ForwarderSubmission;
 use zksync_types::L1ChainId;
 
 use crate::{
     implementations::resources::eth_interface::{
         BoundEthInterfaceForBlobsResource, BoundEthInterfaceResource, EthInterfaceResource,
+        L1TxSubmissionStrategyResource,
     },
     service::ServiceContext,
     wiring_layer::{WiringError, WiringLayer},
@@ -45,7 +49,22 @@ impl WiringLayer for PKSigningEthClientLayer {
     }
 
     async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
-        let private_key = self.wallets.operator.private_key();
+        let forwarder_address = self
+            .eth_sender_config
+            .sender
+            .as_ref()
+            .and_then(|sender| sender.l1_tx_forwarder_addr);
+        // If L1 transactions are relayed via a forwarder, they are signed and paid for by the fee payer,
+        // while the operator only signs forwarded requests.
+        let private_key = if forwarder_address.is_some() {
+            self.wallets
+                .fee_payer
+                .as_ref()
+                .context("fee payer wallet is required if `l1_tx_forwarder_addr` is set")?
+                .private_key()
+        } else {
+            self.wallets.operator.private_key()
+        };
         let gas_adjuster_config = self
             .eth_sender_config
             .gas_adjuster
@@ -62,6 +81,16 @@ impl WiringLayer for PKSigningEthClientLayer {
         );
         context.insert_resource(BoundEthInterfaceResource(Box::new(signing_client)))?;
 
+        if let Some(forwarder_address) = forwarder_address {
+            let strategy = Erc2771ForwarderSubmission::new(
+                forwarder_address,
+                self.wallets.operator.private_key().clone(),
+                self.l1_chain_id,
+                vec![self.contracts_config.validator_timelock_addr],
+            );
+            context.insert_resource(L1TxSubmissionStrategyResource(Arc::new(strategy)))?;
+        }
+
         if let Some(blob_operator) = &self.wallets.blob_operator {
             let private_key = blob_operator.private_key();
             let signing_client_for_blobs = PKSigningClient::new_raw(
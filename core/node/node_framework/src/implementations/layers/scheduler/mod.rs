@@ -0,0 +1,337 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use chrono::Utc;
+use rand::Rng;
+use tokio::sync::{watch, Mutex};
+use zksync_config::configs::SchedulerConfig;
+
+use self::schedule::Schedule;
+use crate::{
+    implementations::resources::scheduler::{ScheduledJob, ScheduledJobs, ScheduledJobsResource},
+    service::{ServiceContext, StopReceiver},
+    task::{Task, TaskId},
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+mod schedule;
+
+/// Builder for the scheduler of periodic maintenance jobs.
+///
+/// ## Adds resources
+///
+/// - `ScheduledJobsResource`. Jobs should be registered in this resource by the layers added after this one;
+///   jobs without a schedule in the config are not run.
+///
+/// ## Effects
+///
+/// - Adds `scheduler` to the node. A failed job run is logged and does not stop the node.
+#[derive(Debug)]
+pub struct SchedulerLayer {
+    config: SchedulerConfig,
+}
+
+impl SchedulerLayer {
+    pub fn new(config: SchedulerConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for SchedulerLayer {
+    fn layer_name(&self) -> &'static str {
+        "scheduler_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let mut schedules = HashMap::with_capacity(self.config.jobs.len());
+        for job_config in &self.config.jobs {
+            let schedule = job_config.schedule.parse().map_err(|err| {
+                WiringError::Configuration(format!(
+                    "invalid schedule `{}` for job `{}`: {err:#}",
+                    job_config.schedule, job_config.name
+                ))
+            })?;
+            let job_schedule = JobSchedule {
+                cron: job_config.schedule.clone(),
+                schedule,
+                jitter: job_config.jitter(),
+                exclusion_group: job_config.exclusion_group.clone(),
+            };
+            if schedules
+                .insert(job_config.name.clone(), job_schedule)
+                .is_some()
+            {
+                return Err(WiringError::Configuration(format!(
+                    "job `{}` is scheduled multiple times",
+                    job_config.name
+                )));
+            }
+        }
+
+        let jobs = Arc::new(ScheduledJobs::new(schedules.keys().cloned().collect()));
+        context.insert_resource(ScheduledJobsResource { jobs: jobs.clone() })?;
+        context.add_task(Box::new(SchedulerTask { schedules, jobs }));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct JobSchedule {
+    cron: String,
+    schedule: Schedule,
+    jitter: Duration,
+    exclusion_group: Option<String>,
+}
+
+#[derive(Debug)]
+struct SchedulerTask {
+    schedules: HashMap<String, JobSchedule>,
+    jobs: Arc<ScheduledJobs>,
+}
+
+#[async_trait::async_trait]
+impl Task for SchedulerTask {
+    fn id(&self) -> TaskId {
+        "scheduler".into()
+    }
+
+    async fn run(mut self: Box<Self>, mut stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        // All wiring layers have registered their jobs at this point.
+        let jobs = self.jobs.take();
+        let mut job_names = HashSet::with_capacity(jobs.len());
+        let mut exclusion_groups = HashMap::<_, Arc<Mutex<()>>>::new();
+        let mut job_futures = vec![];
+        for job in jobs {
+            let name = job.name();
+            anyhow::ensure!(
+                job_names.insert(name),
+                "job `{name}` is registered multiple times"
+            );
+            let Some(schedule) = self.schedules.remove(name) else {
+                tracing::warn!("Job `{name}` has no schedule configured; it will not be run");
+                continue;
+            };
+            let exclusion_group = schedule
+                .exclusion_group
+                .as_ref()
+                .map(|group| exclusion_groups.entry(group.clone()).or_default().clone());
+            tracing::info!(
+                "Scheduling job `{name}` with schedule `{}`, jitter {:?} and exclusion group {:?}",
+                schedule.cron,
+                schedule.jitter,
+                schedule.exclusion_group
+            );
+            job_futures.push(run_job(
+                job,
+                schedule,
+                exclusion_group,
+                stop_receiver.0.clone(),
+            ));
+        }
+        for name in self.schedules.keys() {
+            tracing::warn!("Job `{name}` is scheduled, but not registered by any component");
+        }
+
+        futures::future::join_all(job_futures).await;
+        // Jobs may exit before the stop signal (e.g., if their schedules cannot be satisfied); the task must not
+        // return in this case, since this would stop the node.
+        stop_receiver.0.changed().await?;
+        tracing::info!("Stop signal received, scheduler is shut down");
+        Ok(())
+    }
+}
+
+async fn run_job(
+    mut job: Box<dyn ScheduledJob>,
+    schedule: JobSchedule,
+    exclusion_group: Option<Arc<Mutex<()>>>,
+    mut stop_receiver: watch::Receiver<bool>,
+) {
+    let name = job.name();
+    loop {
+        let now = Utc::now();
+        let Some(next_run) = schedule.schedule.next_after(now) else {
+            tracing::warn!(
+                "Schedule for job `{name}` cannot be satisfied; the job will not be run"
+            );
+            return;
+        };
+        let jitter_ms = rand::thread_rng().gen_range(0..=schedule.jitter.as_millis() as u64);
+        let delay =
+            (next_run - now).to_std().unwrap_or_default() + Duration::from_millis(jitter_ms);
+        tracing::debug!("Next run of job `{name}` is in {delay:?}");
+        if tokio::time::timeout(delay, stop_receiver.changed())
+            .await
+            .is_ok()
+        {
+            return;
+        }
+
+        let _guard = if let Some(exclusion_group) = &exclusion_group {
+            tokio::select! {
+                guard = exclusion_group.lock() => Some(guard),
+                _ = stop_receiver.changed() => return,
+            }
+        } else {
+            None
+        };
+
+        tracing::info!("Running scheduled job `{name}`");
+        let started_at = Instant::now();
+        match job.run(stop_receiver.clone()).await {
+            Ok(()) => {
+                tracing::info!(
+                    "Scheduled job `{name}` finished in {:?}",
+                    started_at.elapsed()
+                );
+            }
+            Err(err) => {
+                tracing::error!(
+                    "Scheduled job `{name}` failed after {:?}: {err:#}",
+                    started_at.elapsed()
+                );
+            }
+        }
+        if *stop_receiver.borrow() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct JobStats {
+        runs: AtomicUsize,
+        running: AtomicUsize,
+        max_running: AtomicUsize,
+    }
+
+    #[derive(Debug)]
+    struct MockJob {
+        name: &'static str,
+        is_failing: bool,
+        /// Stats for this job.
+        stats: Arc<JobStats>,
+        /// Stats shared by all jobs in the same exclusion group.
+        group_stats: Arc<JobStats>,
+    }
+
+    impl MockJob {
+        fn new(name: &'static str, group_stats: &Arc<JobStats>) -> Self {
+            Self {
+                name,
+                is_failing: false,
+                stats: Arc::default(),
+                group_stats: group_stats.clone(),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ScheduledJob for MockJob {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        async fn run(&mut self, _stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+            self.stats.runs.fetch_add(1, Ordering::SeqCst);
+            let running = self.group_stats.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.group_stats
+                .max_running
+                .fetch_max(running, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            self.group_stats.running.fetch_sub(1, Ordering::SeqCst);
+
+            if self.is_failing {
+                anyhow::bail!("emulated job failure");
+            }
+            Ok(())
+        }
+    }
+
+    fn every_minute(exclusion_group: Option<&str>) -> JobSchedule {
+        JobSchedule {
+            cron: "* * * * *".to_owned(),
+            schedule: "* * * * *".parse().unwrap(),
+            jitter: Duration::ZERO,
+            exclusion_group: exclusion_group.map(str::to_owned),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn running_scheduled_jobs() {
+        let db_group_stats = Arc::<JobStats>::default();
+        let first_db_job = MockJob::new("first_db_job", &db_group_stats);
+        let first_db_job_stats = first_db_job.stats.clone();
+        let second_db_job = MockJob::new("second_db_job", &db_group_stats);
+        let second_db_job_stats = second_db_job.stats.clone();
+
+        let failing_group_stats = Arc::<JobStats>::default();
+        let failing_job = MockJob {
+            is_failing: true,
+            ..MockJob::new("failing_job", &failing_group_stats)
+        };
+        let failing_job_stats = failing_job.stats.clone();
+        let unscheduled_job = MockJob::new("unscheduled_job", &failing_group_stats);
+        let unscheduled_job_stats = unscheduled_job.stats.clone();
+
+        let schedules = HashMap::from([
+            ("first_db_job".to_owned(), every_minute(Some("db"))),
+            ("second_db_job".to_owned(), every_minute(Some("db"))),
+            ("failing_job".to_owned(), every_minute(None)),
+            // Scheduled jobs that are not registered must be ignored.
+            ("unknown_job".to_owned(), every_minute(None)),
+        ]);
+        let jobs = Arc::new(ScheduledJobs::new(schedules.keys().cloned().collect()));
+        assert!(jobs.is_scheduled("first_db_job"));
+        assert!(!jobs.is_scheduled("unscheduled_job"));
+        for job in [first_db_job, second_db_job, failing_job, unscheduled_job] {
+            jobs.insert(Box::new(job));
+        }
+
+        let task = Box::new(SchedulerTask { schedules, jobs });
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let task_handle = tokio::spawn(task.run(StopReceiver(stop_receiver)));
+
+        while first_db_job_stats.runs.load(Ordering::SeqCst) < 3
+            || second_db_job_stats.runs.load(Ordering::SeqCst) < 3
+            || failing_job_stats.runs.load(Ordering::SeqCst) < 3
+        {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+        }
+        assert!(!task_handle.is_finished());
+        stop_sender.send_replace(true);
+        task_handle.await.unwrap().unwrap();
+
+        // Jobs in the same exclusion group must not run concurrently.
+        assert_eq!(db_group_stats.max_running.load(Ordering::SeqCst), 1);
+        assert_eq!(unscheduled_job_stats.runs.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn scheduler_task_rejects_duplicate_jobs() {
+        let stats = Arc::<JobStats>::default();
+        let schedules = HashMap::from([("job".to_owned(), every_minute(None))]);
+        let jobs = Arc::new(ScheduledJobs::new(schedules.keys().cloned().collect()));
+        jobs.insert(Box::new(MockJob::new("job", &stats)));
+        jobs.insert(Box::new(MockJob::new("job", &stats)));
+
+        let task = Box::new(SchedulerTask { schedules, jobs });
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        let err = task
+            .run(StopReceiver(stop_receiver))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("registered multiple times"), "{err}");
+    }
+}
@@ -0,0 +1,224 @@
+//! Cron-like schedules for the scheduler.
+
+use std::{ops::RangeInclusive, str::FromStr};
+
+use anyhow::Context as _;
+use chrono::{DateTime, Datelike, Days, Duration, TimeZone, Timelike, Utc};
+
+/// Maximum time span searched for the next scheduled run. Schedules that cannot be satisfied (e.g., `0 0 30 2 *`)
+/// are detected this way.
+const MAX_SEARCH_SPAN_DAYS: i64 = 5 * 366;
+
+/// Set of allowed values for a single cron field, represented as a bitmask.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FieldSet(u64);
+
+impl FieldSet {
+    fn parse(field: &str, range: RangeInclusive<u32>) -> anyhow::Result<Self> {
+        let mut set = 0_u64;
+        for part in field.split(',') {
+            let (values, step) = match part.split_once('/') {
+                Some((values, step)) => {
+                    let step: u32 = step.parse().context("invalid step")?;
+                    anyhow::ensure!(step > 0, "step must be positive");
+                    (values, step)
+                }
+                None => (part, 1),
+            };
+            let (start, end) = if values == "*" {
+                (*range.start(), *range.end())
+            } else if let Some((start, end)) = values.split_once('-') {
+                let start = start.parse().context("invalid range start")?;
+                let end = end.parse().context("invalid range end")?;
+                (start, end)
+            } else {
+                let value = values.parse().context("invalid value")?;
+                // `5/10` is interpreted as `5-<max>/10`, as in most cron implementations.
+                let end = if part.contains('/') {
+                    *range.end()
+                } else {
+                    value
+                };
+                (value, end)
+            };
+            anyhow::ensure!(
+                range.contains(&start) && range.contains(&end) && start <= end,
+                "values must be within {range:?}"
+            );
+            for value in (start..=end).step_by(step as usize) {
+                set |= 1 << value;
+            }
+        }
+        Ok(Self(set))
+    }
+
+    fn contains(self, value: u32) -> bool {
+        self.0 & (1 << value) != 0
+    }
+}
+
+/// Cron-like schedule with 5 fields: minute, hour, day of month, month and day of week. All times are in UTC.
+///
+/// As in standard cron, if both day of month and day of week are restricted (i.e., not `*`),
+/// a day matches the schedule if it matches *either* of these fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Schedule {
+    minutes: FieldSet,
+    hours: FieldSet,
+    days_of_month: FieldSet,
+    months: FieldSet,
+    days_of_week: FieldSet,
+    days_of_month_restricted: bool,
+    days_of_week_restricted: bool,
+}
+
+impl FromStr for Schedule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<_> = s.split_whitespace().collect();
+        let [minutes, hours, days_of_month, months, days_of_week] = fields.as_slice() else {
+            anyhow::bail!("schedule must have 5 fields, got {}", fields.len());
+        };
+
+        let mut days_of_week_set =
+            FieldSet::parse(days_of_week, 0..=7).context("invalid day of week")?;
+        // Both 0 and 7 denote Sunday.
+        if days_of_week_set.contains(7) {
+            days_of_week_set.0 = (days_of_week_set.0 & !(1 << 7)) | 1;
+        }
+        Ok(Self {
+            minutes: FieldSet::parse(minutes, 0..=59).context("invalid minute")?,
+            hours: FieldSet::parse(hours, 0..=23).context("invalid hour")?,
+            days_of_month: FieldSet::parse(days_of_month, 1..=31)
+                .context("invalid day of month")?,
+            months: FieldSet::parse(months, 1..=12).context("invalid month")?,
+            days_of_week: days_of_week_set,
+            days_of_month_restricted: !days_of_month.starts_with('*'),
+            days_of_week_restricted: !days_of_week.starts_with('*'),
+        })
+    }
+}
+
+impl Schedule {
+    fn matches_day(&self, time: DateTime<Utc>) -> bool {
+        let day_of_month_matches = self.days_of_month.contains(time.day());
+        let day_of_week_matches = self
+            .days_of_week
+            .contains(time.weekday().num_days_from_sunday());
+        if self.days_of_month_restricted && self.days_of_week_restricted {
+            day_of_month_matches || day_of_week_matches
+        } else {
+            day_of_month_matches && day_of_week_matches
+        }
+    }
+
+    /// Returns the first time strictly after `time` matching this schedule, or `None` if the schedule
+    /// cannot be satisfied.
+    pub fn next_after(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut time = time.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = time + Duration::days(MAX_SEARCH_SPAN_DAYS);
+        while time < limit {
+            if !self.months.contains(time.month()) {
+                let (year, month) = if time.month() == 12 {
+                    (time.year() + 1, 1)
+                } else {
+                    (time.year(), time.month() + 1)
+                };
+                time = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+            } else if !self.matches_day(time) {
+                let next_day = time.date_naive().checked_add_days(Days::new(1))?;
+                time = Utc.from_utc_datetime(&next_day.and_hms_opt(0, 0, 0)?);
+            } else if !self.hours.contains(time.hour()) {
+                time = time.with_minute(0)? + Duration::hours(1);
+            } else if !self.minutes.contains(time.minute()) {
+                time += Duration::minutes(1);
+            } else {
+                return Some(time);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn parsing_schedules() {
+        let schedule: Schedule = "*/15 0-6/2 * * *".parse().unwrap();
+        for minute in 0..60 {
+            assert_eq!(schedule.minutes.contains(minute), minute % 15 == 0);
+        }
+        for hour in 0..24 {
+            assert_eq!(schedule.hours.contains(hour), hour <= 6 && hour % 2 == 0);
+        }
+        assert!(!schedule.days_of_month_restricted);
+        assert!(!schedule.days_of_week_restricted);
+
+        let schedule: Schedule = "0 0 * * 5-7".parse().unwrap();
+        assert_eq!(schedule.days_of_week, FieldSet(0b110_0001));
+
+        for invalid_schedule in [
+            "",
+            "* * * *",
+            "* * * * * *",
+            "60 * * * *",
+            "* * 0 * *",
+            "5-1 * * * *",
+            "*/0 * * * *",
+            "a * * * *",
+        ] {
+            invalid_schedule.parse::<Schedule>().unwrap_err();
+        }
+    }
+
+    #[test]
+    fn computing_next_run() {
+        let schedule: Schedule = "*/15 * * * *".parse().unwrap();
+        assert_eq!(
+            schedule.next_after(time("2024-06-24T10:07:30Z")),
+            Some(time("2024-06-24T10:15:00Z"))
+        );
+        assert_eq!(
+            schedule.next_after(time("2024-06-24T10:15:00Z")),
+            Some(time("2024-06-24T10:30:00Z"))
+        );
+        assert_eq!(
+            schedule.next_after(time("2024-06-24T23:50:00Z")),
+            Some(time("2024-06-25T00:00:00Z"))
+        );
+
+        let schedule: Schedule = "30 3 1 * *".parse().unwrap();
+        assert_eq!(
+            schedule.next_after(time("2024-12-24T10:00:00Z")),
+            Some(time("2025-01-01T03:30:00Z"))
+        );
+
+        // 2024-06-24 is Monday.
+        let schedule: Schedule = "0 12 * * 0".parse().unwrap();
+        assert_eq!(
+            schedule.next_after(time("2024-06-24T10:00:00Z")),
+            Some(time("2024-06-30T12:00:00Z"))
+        );
+        // Either day of month or day of week must match.
+        let schedule: Schedule = "0 12 26 * 0".parse().unwrap();
+        assert_eq!(
+            schedule.next_after(time("2024-06-24T10:00:00Z")),
+            Some(time("2024-06-26T12:00:00Z"))
+        );
+
+        let schedule: Schedule = "0 0 29 2 *".parse().unwrap();
+        assert_eq!(
+            schedule.next_after(time("2024-06-24T10:00:00Z")),
+            Some(time("2028-02-29T00:00:00Z"))
+        );
+        let schedule: Schedule = "0 0 30 2 *".parse().unwrap();
+        assert_eq!(schedule.next_after(time("2024-06-24T10:00:00Z")), None);
+    }
+}
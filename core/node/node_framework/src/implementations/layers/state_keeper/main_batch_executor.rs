@@ -1,8 +1,12 @@
 use zksync_config::configs::chain::StateKeeperConfig;
-use zksync_state_keeper::MainBatchExecutor;
+use zksync_state_keeper::{BatchResourceBudget, MainBatchExecutor};
 
 use crate::{
-    implementations::resources::state_keeper::BatchExecutorResource,
+    implementations::resources::{
+        healthcheck::AppHealthCheckResource,
+        pools::{MasterPool, PoolResource},
+        state_keeper::BatchExecutorResource,
+    },
     resource::Unique,
     service::ServiceContext,
     wiring_layer::{WiringError, WiringLayer},
@@ -28,7 +32,21 @@ impl WiringLayer for MainBatchExecutorLayer {
     }
 
     async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
-        let builder = MainBatchExecutor::new(self.state_keeper_config.save_call_traces, false);
+        let master_pool = context.get_resource::<PoolResource<MasterPool>>().await?;
+        let mut builder = MainBatchExecutor::new(self.state_keeper_config.save_call_traces, false)
+            .with_traced_addresses(master_pool.get_singleton().await?)
+            .with_resource_budget(BatchResourceBudget::from_config(&self.state_keeper_config));
+        if let Some(size) = self
+            .state_keeper_config
+            .bytecode_compression_dictionary_size
+        {
+            builder = builder.with_bytecode_compression_dictionary(size);
+        }
+
+        let AppHealthCheckResource(app_health) = context.get_resource_or_default().await;
+        app_health
+            .insert_component(builder.health_check())
+            .map_err(WiringError::internal)?;
 
         context.insert_resource(BatchExecutorResource(Unique::new(Box::new(builder))))?;
         Ok(())
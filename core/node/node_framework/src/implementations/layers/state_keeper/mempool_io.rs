@@ -8,15 +8,18 @@ use zksync_config::{
     },
     ContractsConfig,
 };
+use zksync_dal::transactions_dal::CallTraceFormat;
 use zksync_state_keeper::{
-    io::seal_logic::l2_block_seal_subtasks::L2BlockSealProcess, MempoolFetcher, MempoolGuard,
-    MempoolIO, OutputHandler, SequencerSealer, StateKeeperPersistence, TreeWritesPersistence,
+    io::seal_logic::l2_block_seal_subtasks::L2BlockSealProcess, L1BatchMetadataPersistence,
+    L1BatchMetadataSource, MempoolFetcher, MempoolGuard, MempoolIO, OutputHandler, SequencerSealer,
+    StateKeeperPersistence, TransactionFilter, TreeWritesPersistence,
 };
-use zksync_types::L2ChainId;
+use zksync_types::{L2ChainId, H256};
 
 use crate::{
     implementations::resources::{
         fee_input::FeeInputResource,
+        healthcheck::AppHealthCheckResource,
         pools::{MasterPool, PoolResource},
         state_keeper::{ConditionalSealerResource, OutputHandlerResource, StateKeeperIOResource},
     },
@@ -33,6 +36,9 @@ pub struct MempoolIOLayer {
     state_keeper_config: StateKeeperConfig,
     mempool_config: MempoolConfig,
     wallets: wallets::StateKeeper,
+    evm_emulator_hash: Option<H256>,
+    tx_filters: Vec<Arc<dyn TransactionFilter>>,
+    l1_batch_metadata_sources: Vec<Arc<dyn L1BatchMetadataSource>>,
 }
 
 impl MempoolIOLayer {
@@ -49,9 +55,32 @@ impl MempoolIOLayer {
             state_keeper_config,
             mempool_config,
             wallets,
+            evm_emulator_hash: None,
+            tx_filters: Vec::new(),
+            l1_batch_metadata_sources: Vec::new(),
         }
     }
 
+    /// Enables the EVM emulator with the specified bytecode hash (usually, taken from the genesis config).
+    pub fn with_evm_emulator_hash(mut self, evm_emulator_hash: Option<H256>) -> Self {
+        self.evm_emulator_hash = evm_emulator_hash;
+        self
+    }
+
+    /// Adds a filter for L2 transactions applied by the mempool IO before transactions are included into a batch.
+    /// Can be called multiple times; a transaction is rejected if any of the filters denies it.
+    pub fn with_transaction_filter(mut self, filter: Arc<dyn TransactionFilter>) -> Self {
+        self.tx_filters.push(filter);
+        self
+    }
+
+    /// Adds a source of custom metadata persisted for each sealed L1 batch. Can be called multiple times;
+    /// if several sources return the same key, the value from the source added last wins.
+    pub fn with_l1_batch_metadata_source(mut self, source: Arc<dyn L1BatchMetadataSource>) -> Self {
+        self.l1_batch_metadata_sources.push(source);
+        self
+    }
+
     async fn build_mempool_guard(
         &self,
         master_pool: &PoolResource<MasterPool>,
@@ -64,7 +93,7 @@ impl MempoolIOLayer {
             .connection()
             .await
             .context("Access storage to build mempool")?;
-        let mempool = MempoolGuard::from_storage(&mut storage, self.mempool_config.capacity).await;
+        let mempool = MempoolGuard::from_storage(&mut storage, &self.mempool_config).await;
         mempool.register_metrics();
         Ok(mempool)
     }
@@ -92,9 +121,22 @@ impl WiringLayer for MempoolIOLayer {
             self.contracts_config.l2_shared_bridge_addr.unwrap(),
             self.state_keeper_config.l2_block_seal_queue_capacity,
         );
-        let tree_writes_persistence = TreeWritesPersistence::new(persistence_pool);
-        let output_handler = OutputHandler::new(Box::new(persistence))
+        let call_trace_format = if self.state_keeper_config.flat_call_traces {
+            CallTraceFormat::Flat
+        } else {
+            CallTraceFormat::Legacy
+        };
+        let persistence = persistence.with_call_trace_format(call_trace_format);
+        let tree_writes_persistence = TreeWritesPersistence::new(persistence_pool.clone());
+        let mut output_handler = OutputHandler::new(Box::new(persistence))
             .with_handler(Box::new(tree_writes_persistence));
+        if !self.l1_batch_metadata_sources.is_empty() {
+            let mut metadata_persistence = L1BatchMetadataPersistence::new(persistence_pool);
+            for source in &self.l1_batch_metadata_sources {
+                metadata_persistence = metadata_persistence.with_source(source.clone());
+            }
+            output_handler = output_handler.with_handler(Box::new(metadata_persistence));
+        }
         context.insert_resource(OutputHandlerResource(Unique::new(output_handler)))?;
         context.add_task(Box::new(L2BlockSealerTask(l2_block_sealer)));
 
@@ -117,7 +159,7 @@ impl WiringLayer for MempoolIOLayer {
             .get_singleton()
             .await
             .context("Get master pool")?;
-        let io = MempoolIO::new(
+        let mut io = MempoolIO::new(
             mempool_guard,
             batch_fee_input_provider,
             mempool_db_pool,
@@ -126,11 +168,25 @@ impl WiringLayer for MempoolIOLayer {
             self.mempool_config.delay_interval(),
             self.zksync_network_id,
         )
-        .await?;
+        .await?
+        .with_extra_fee_accounts(
+            self.wallets
+                .extra_fee_accounts
+                .iter()
+                .map(wallets::AddressWallet::address),
+        )
+        .with_evm_emulator_hash(self.evm_emulator_hash);
+        for filter in self.tx_filters {
+            io = io.with_transaction_filter(filter);
+        }
         context.insert_resource(StateKeeperIOResource(Unique::new(Box::new(io))))?;
 
         // Create sealer.
         let sealer = SequencerSealer::new(self.state_keeper_config);
+        let AppHealthCheckResource(app_health) = context.get_resource_or_default().await;
+        app_health
+            .insert_component(sealer.health_check())
+            .map_err(WiringError::internal)?;
         context.insert_resource(ConditionalSealerResource(Arc::new(sealer)))?;
 
         Ok(())
@@ -5,7 +5,7 @@ use zksync_config::DBConfig;
 use zksync_state::{AsyncCatchupTask, ReadStorageFactory, RocksdbStorageOptions};
 use zksync_state_keeper::{
     seal_criteria::ConditionalSealer, AsyncRocksdbCache, BatchExecutor, OutputHandler,
-    StateKeeperIO, ZkSyncStateKeeper,
+    ShutdownMode, StateKeeperIO, ZkSyncStateKeeper,
 };
 use zksync_storage::RocksDB;
 
@@ -33,11 +33,21 @@ use crate::{
 #[derive(Debug)]
 pub struct StateKeeperLayer {
     db_config: DBConfig,
+    shutdown_mode: ShutdownMode,
 }
 
 impl StateKeeperLayer {
     pub fn new(db_config: DBConfig) -> Self {
-        Self { db_config }
+        Self {
+            db_config,
+            shutdown_mode: ShutdownMode::default(),
+        }
+    }
+
+    /// Sets the state keeper behavior on shutdown. By default, the state keeper stops immediately.
+    pub fn with_shutdown_mode(mut self, shutdown_mode: ShutdownMode) -> Self {
+        self.shutdown_mode = shutdown_mode;
+        self
     }
 }
 
@@ -89,6 +99,7 @@ impl WiringLayer for StateKeeperLayer {
             output_handler,
             sealer,
             storage_factory: Arc::new(storage_factory),
+            shutdown_mode: self.shutdown_mode,
         }));
         Ok(())
     }
@@ -101,6 +112,7 @@ struct StateKeeperTask {
     output_handler: OutputHandler,
     sealer: Arc<dyn ConditionalSealer>,
     storage_factory: Arc<dyn ReadStorageFactory>,
+    shutdown_mode: ShutdownMode,
 }
 
 #[async_trait::async_trait]
@@ -117,7 +129,8 @@ impl Task for StateKeeperTask {
             self.output_handler,
             self.sealer,
             self.storage_factory,
-        );
+        )
+        .with_shutdown_mode(self.shutdown_mode);
         let result = state_keeper.run().await;
 
         // Wait for all the instances of RocksDB to be destroyed.
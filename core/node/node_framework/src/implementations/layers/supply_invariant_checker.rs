@@ -0,0 +1,78 @@
+use anyhow::Context as _;
+use zksync_circuit_breaker::supply_invariant::SupplyInvariantChecker;
+use zksync_config::{configs::chain::CircuitBreakerConfig, ContractsConfig};
+use zksync_types::L2ChainId;
+
+use crate::{
+    implementations::resources::{
+        circuit_breakers::CircuitBreakersResource,
+        eth_interface::EthInterfaceResource,
+        pools::{PoolResource, ReplicaPool},
+    },
+    service::ServiceContext,
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Inserts a circuit breaker checking that the L2 base token supply doesn't exceed the funds
+/// locked for the chain on L1. Does nothing if the supply invariant action is not configured.
+#[derive(Debug)]
+pub struct SupplyInvariantCheckerLayer {
+    circuit_breaker_config: CircuitBreakerConfig,
+    contracts_config: ContractsConfig,
+    l2_chain_id: L2ChainId,
+}
+
+impl SupplyInvariantCheckerLayer {
+    pub fn new(
+        circuit_breaker_config: CircuitBreakerConfig,
+        contracts_config: ContractsConfig,
+        l2_chain_id: L2ChainId,
+    ) -> Self {
+        Self {
+            circuit_breaker_config,
+            contracts_config,
+            l2_chain_id,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for SupplyInvariantCheckerLayer {
+    fn layer_name(&self) -> &'static str {
+        "supply_invariant_checker_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let Some(action) = self.circuit_breaker_config.supply_invariant_action else {
+            return Ok(());
+        };
+
+        let l1_shared_bridge_addr = self
+            .contracts_config
+            .l1_shared_bridge_proxy_addr
+            .context("L1 shared bridge address is required to check supply invariant")?;
+        let base_token_addr = self
+            .contracts_config
+            .base_token_addr
+            .context("base token address is required to check supply invariant")?;
+
+        let pool_resource = context.get_resource::<PoolResource<ReplicaPool>>().await?;
+        let pool = pool_resource.get_singleton().await?;
+        let eth_client = context.get_resource::<EthInterfaceResource>().await?.0;
+
+        let CircuitBreakersResource { breakers } = context.get_resource_or_default().await;
+        breakers
+            .insert(Box::new(SupplyInvariantChecker::new(
+                pool,
+                eth_client,
+                l1_shared_bridge_addr,
+                base_token_addr,
+                self.l2_chain_id,
+                self.circuit_breaker_config.supply_invariant_tolerance(),
+                action,
+            )))
+            .await;
+
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use zksync_queued_job_processor::JobProcessor;
 use zksync_tee_verifier_input_producer::TeeVerifierInputProducer;
-use zksync_types::L2ChainId;
+use zksync_types::{L2ChainId, H256};
 
 use crate::{
     implementations::resources::{
@@ -15,11 +15,21 @@ use crate::{
 #[derive(Debug)]
 pub struct TeeVerifierInputProducerLayer {
     l2_chain_id: L2ChainId,
+    evm_emulator_hash: Option<H256>,
 }
 
 impl TeeVerifierInputProducerLayer {
     pub fn new(l2_chain_id: L2ChainId) -> Self {
-        Self { l2_chain_id }
+        Self {
+            l2_chain_id,
+            evm_emulator_hash: None,
+        }
+    }
+
+    /// Enables the EVM emulator with the specified bytecode hash (usually, taken from the genesis config).
+    pub fn with_evm_emulator_hash(mut self, evm_emulator_hash: Option<H256>) -> Self {
+        self.evm_emulator_hash = evm_emulator_hash;
+        self
     }
 }
 
@@ -37,8 +47,9 @@ impl WiringLayer for TeeVerifierInputProducerLayer {
             .get()
             .await?;
         let object_store = context.get_resource::<ObjectStoreResource>().await?;
-        let tee =
-            TeeVerifierInputProducer::new(pool_resource, object_store.0, self.l2_chain_id).await?;
+        let tee = TeeVerifierInputProducer::new(pool_resource, object_store.0, self.l2_chain_id)
+            .await?
+            .with_evm_emulator_hash(self.evm_emulator_hash);
 
         context.add_task(Box::new(TeeVerifierInputProducerTask { tee }));
 
@@ -0,0 +1,142 @@
+use anyhow::Context as _;
+use zksync_config::configs::vm_runner::BasicWitnessInputProducerConfig;
+use zksync_types::{L2ChainId, H256};
+use zksync_vm_runner::BasicWitnessInputProducer;
+
+use crate::{
+    implementations::resources::{
+        object_store::ObjectStoreResource,
+        pools::{MasterPool, PoolResource},
+        vm_runner::{SealedBatchesResource, SharedVmRunnerStorageResource},
+    },
+    service::{ServiceContext, StopReceiver},
+    task::{Task, TaskId},
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Wiring layer for [`BasicWitnessInputProducer`].
+///
+/// If [`SharedVmRunnerStorageResource`] is present, the producer uses the shared storage instead of its own
+/// RocksDB cache at `db_path`. If [`SealedBatchesResource`] is present, the producer reacts to sealed batches
+/// instead of polling Postgres.
+#[derive(Debug)]
+pub struct BasicWitnessInputProducerLayer {
+    basic_witness_input_producer_config: BasicWitnessInputProducerConfig,
+    zksync_network_id: L2ChainId,
+    evm_emulator_hash: Option<H256>,
+}
+
+impl BasicWitnessInputProducerLayer {
+    pub fn new(
+        basic_witness_input_producer_config: BasicWitnessInputProducerConfig,
+        zksync_network_id: L2ChainId,
+    ) -> Self {
+        Self {
+            basic_witness_input_producer_config,
+            zksync_network_id,
+            evm_emulator_hash: None,
+        }
+    }
+
+    /// Enables the EVM emulator with the specified bytecode hash (usually, taken from the genesis config).
+    pub fn with_evm_emulator_hash(mut self, evm_emulator_hash: Option<H256>) -> Self {
+        self.evm_emulator_hash = evm_emulator_hash;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for BasicWitnessInputProducerLayer {
+    fn layer_name(&self) -> &'static str {
+        "vm_runner_bwip"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let master_pool = context.get_resource::<PoolResource<MasterPool>>().await?;
+        let object_store = context.get_resource::<ObjectStoreResource>().await?;
+        let sealed_batches = match context.get_resource::<SealedBatchesResource>().await {
+            Ok(resource) => Some(resource.0),
+            Err(WiringError::ResourceLacking { .. }) => None,
+            Err(err) => return Err(err),
+        };
+        let shared_storage = match context
+            .get_resource::<SharedVmRunnerStorageResource>()
+            .await
+        {
+            Ok(resource) => Some(resource),
+            Err(WiringError::ResourceLacking { .. }) => None,
+            Err(err) => return Err(err),
+        };
+        if let Some(shared_storage) = shared_storage {
+            let config = self.basic_witness_input_producer_config;
+            // One for `ConcurrentOutputHandlerFactoryTask`/`VmRunner`, and `window_size` connections
+            // for output handlers; storage is synchronized by the shared storage task.
+            let pool = master_pool.get_custom(config.window_size + 1).await?;
+            let (basic_witness_input_producer, output_handler_factory_task) = shared_storage
+                .with_storage(|storage| {
+                    BasicWitnessInputProducer::with_shared_storage(
+                        pool,
+                        object_store.0,
+                        storage,
+                        config.first_processed_batch,
+                        config.window_size,
+                        sealed_batches,
+                    )
+                })
+                .context("shared VM runner storage is already consumed")?;
+            context.add_task(Box::new(output_handler_factory_task));
+            context.add_task(Box::new(BasicWitnessInputProducerTask {
+                basic_witness_input_producer,
+            }));
+            return Ok(());
+        }
+
+        let (basic_witness_input_producer, tasks) = BasicWitnessInputProducer::new(
+            // One for `StorageSyncTask` which can hold a long-term connection in case it needs to
+            // catch up cache.
+            //
+            // One for `ConcurrentOutputHandlerFactoryTask`/`VmRunner` as they need occasional access
+            // to DB for querying last processed batch and last ready to be loaded batch.
+            //
+            // `window_size` connections for `BasicWitnessInputProducerOutputHandlerFactory`
+            // as there can be multiple output handlers loading factory deps concurrently.
+            master_pool
+                .get_custom(self.basic_witness_input_producer_config.window_size + 2)
+                .await?,
+            object_store.0,
+            self.basic_witness_input_producer_config.db_path,
+            self.zksync_network_id,
+            self.evm_emulator_hash,
+            self.basic_witness_input_producer_config
+                .first_processed_batch,
+            self.basic_witness_input_producer_config.window_size,
+            sealed_batches,
+        )
+        .await?;
+
+        context.add_task(Box::new(tasks.loader_task));
+        context.add_task(Box::new(tasks.output_handler_factory_task));
+        context.add_task(Box::new(BasicWitnessInputProducerTask {
+            basic_witness_input_producer,
+        }));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct BasicWitnessInputProducerTask {
+    basic_witness_input_producer: BasicWitnessInputProducer,
+}
+
+#[async_trait::async_trait]
+impl Task for BasicWitnessInputProducerTask {
+    fn id(&self) -> TaskId {
+        "vm_runner/bwip".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.basic_witness_input_producer
+            .run(&stop_receiver.0)
+            .await
+    }
+}
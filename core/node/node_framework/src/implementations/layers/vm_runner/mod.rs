@@ -5,7 +5,12 @@ use crate::{
     task::{Task, TaskId},
 };
 
+pub mod bwip;
+pub mod presimulation;
 pub mod protective_reads;
+pub mod sealed_batches;
+pub mod shared_storage;
+pub mod upgrade_canary;
 
 #[async_trait::async_trait]
 impl<Io: VmRunnerIo> Task for StorageSyncTask<Io> {
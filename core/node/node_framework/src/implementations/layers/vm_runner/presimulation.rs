@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use zksync_state_keeper::TransactionFilter;
+use zksync_types::{L2ChainId, H256};
+use zksync_vm_runner::{PresimulationCache, TransactionPresimulator};
+
+use crate::{
+    implementations::resources::pools::{MasterPool, PoolResource},
+    service::{ServiceContext, StopReceiver},
+    task::{Task, TaskId},
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Wiring layer for [`TransactionPresimulator`], which pre-executes pending L2 transactions against
+/// the last sealed L1 batch. The pre-simulator reads storage from Postgres and doesn't require a RocksDB cache.
+///
+/// Pre-simulation outcomes only have effect if the [filter](Self::transaction_filter()) provided by the layer
+/// is passed to the state keeper (e.g., using [`MempoolIOLayer::with_transaction_filter()`]).
+///
+/// [`MempoolIOLayer::with_transaction_filter()`]: crate::implementations::layers::state_keeper::mempool_io::MempoolIOLayer::with_transaction_filter()
+#[derive(Debug)]
+pub struct TxPresimulatorLayer {
+    batch_size: usize,
+    validation_computational_gas_limit: u32,
+    zksync_network_id: L2ChainId,
+    evm_emulator_hash: Option<H256>,
+    cache: Arc<PresimulationCache>,
+}
+
+impl TxPresimulatorLayer {
+    pub fn new(
+        batch_size: usize,
+        validation_computational_gas_limit: u32,
+        zksync_network_id: L2ChainId,
+    ) -> Self {
+        Self {
+            batch_size,
+            validation_computational_gas_limit,
+            zksync_network_id,
+            evm_emulator_hash: None,
+            cache: Arc::default(),
+        }
+    }
+
+    /// Enables the EVM emulator with the specified bytecode hash (usually, taken from the genesis config).
+    pub fn with_evm_emulator_hash(mut self, evm_emulator_hash: Option<H256>) -> Self {
+        self.evm_emulator_hash = evm_emulator_hash;
+        self
+    }
+
+    /// Returns the transaction filter rejecting transactions that have failed validation during pre-simulation.
+    pub fn transaction_filter(&self) -> Arc<dyn TransactionFilter> {
+        self.cache.clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for TxPresimulatorLayer {
+    fn layer_name(&self) -> &'static str {
+        "vm_runner_tx_presimulator"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let master_pool = context.get_resource::<PoolResource<MasterPool>>().await?;
+
+        let presimulator = TransactionPresimulator::new(
+            // One connection for the pre-simulator itself and one for Postgres-backed storage.
+            master_pool.get_custom(2).await?,
+            self.zksync_network_id,
+            self.evm_emulator_hash,
+            self.validation_computational_gas_limit,
+            self.batch_size,
+            self.cache,
+        )
+        .await?;
+
+        context.add_task(Box::new(TxPresimulatorTask { presimulator }));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct TxPresimulatorTask {
+    presimulator: TransactionPresimulator,
+}
+
+#[async_trait::async_trait]
+impl Task for TxPresimulatorTask {
+    fn id(&self) -> TaskId {
+        "vm_runner/tx_presimulator".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.presimulator.run(&stop_receiver.0).await
+    }
+}
@@ -1,18 +1,28 @@
+use anyhow::Context as _;
 use zksync_config::configs::vm_runner::ProtectiveReadsWriterConfig;
-use zksync_types::L2ChainId;
+use zksync_types::{L2ChainId, H256};
 use zksync_vm_runner::ProtectiveReadsWriter;
 
 use crate::{
-    implementations::resources::pools::{MasterPool, PoolResource},
+    implementations::resources::{
+        pools::{MasterPool, PoolResource},
+        vm_runner::{SealedBatchesResource, SharedVmRunnerStorageResource},
+    },
     service::{ServiceContext, StopReceiver},
     task::{Task, TaskId},
     wiring_layer::{WiringError, WiringLayer},
 };
 
+/// Wiring layer for [`ProtectiveReadsWriter`].
+///
+/// If [`SharedVmRunnerStorageResource`] is present, the writer uses the shared storage instead of its own
+/// RocksDB cache at `db_path`. If [`SealedBatchesResource`] is present, the writer reacts to sealed batches
+/// instead of polling Postgres.
 #[derive(Debug)]
 pub struct ProtectiveReadsWriterLayer {
     protective_reads_writer_config: ProtectiveReadsWriterConfig,
     zksync_network_id: L2ChainId,
+    evm_emulator_hash: Option<H256>,
 }
 
 impl ProtectiveReadsWriterLayer {
@@ -23,8 +33,15 @@ impl ProtectiveReadsWriterLayer {
         Self {
             protective_reads_writer_config,
             zksync_network_id,
+            evm_emulator_hash: None,
         }
     }
+
+    /// Enables the EVM emulator with the specified bytecode hash (usually, taken from the genesis config).
+    pub fn with_evm_emulator_hash(mut self, evm_emulator_hash: Option<H256>) -> Self {
+        self.evm_emulator_hash = evm_emulator_hash;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -35,6 +52,41 @@ impl WiringLayer for ProtectiveReadsWriterLayer {
 
     async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
         let master_pool = context.get_resource::<PoolResource<MasterPool>>().await?;
+        let sealed_batches = match context.get_resource::<SealedBatchesResource>().await {
+            Ok(resource) => Some(resource.0),
+            Err(WiringError::ResourceLacking { .. }) => None,
+            Err(err) => return Err(err),
+        };
+        let shared_storage = match context
+            .get_resource::<SharedVmRunnerStorageResource>()
+            .await
+        {
+            Ok(resource) => Some(resource),
+            Err(WiringError::ResourceLacking { .. }) => None,
+            Err(err) => return Err(err),
+        };
+        if let Some(shared_storage) = shared_storage {
+            let config = self.protective_reads_writer_config;
+            // One for `ConcurrentOutputHandlerFactoryTask`/`VmRunner`, and `window_size` connections
+            // for output handlers; storage is synchronized by the shared storage task.
+            let pool = master_pool.get_custom(config.window_size + 1).await?;
+            let (protective_reads_writer, output_handler_factory_task) = shared_storage
+                .with_storage(|storage| {
+                    ProtectiveReadsWriter::with_shared_storage(
+                        pool,
+                        storage,
+                        config.first_processed_batch,
+                        config.window_size,
+                        sealed_batches,
+                    )
+                })
+                .context("shared VM runner storage is already consumed")?;
+            context.add_task(Box::new(output_handler_factory_task));
+            context.add_task(Box::new(ProtectiveReadsWriterTask {
+                protective_reads_writer,
+            }));
+            return Ok(());
+        }
 
         let (protective_reads_writer, tasks) = ProtectiveReadsWriter::new(
             // One for `StorageSyncTask` which can hold a long-term connection in case it needs to
@@ -51,8 +103,10 @@ impl WiringLayer for ProtectiveReadsWriterLayer {
                 .await?,
             self.protective_reads_writer_config.db_path,
             self.zksync_network_id,
+            self.evm_emulator_hash,
             self.protective_reads_writer_config.first_processed_batch,
             self.protective_reads_writer_config.window_size,
+            sealed_batches,
         )
         .await?;
 
@@ -0,0 +1,50 @@
+use zksync_vm_runner::SealedBatchesListener;
+
+use crate::{
+    implementations::resources::{
+        pools::{MasterPool, PoolResource},
+        vm_runner::SealedBatchesResource,
+    },
+    service::{ServiceContext, StopReceiver},
+    task::{Task, TaskId},
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Wiring layer for [`SealedBatchesListener`], which listens to Postgres notifications about sealed L1 batches.
+///
+/// ## Adds resources
+///
+/// - [`SealedBatchesResource`]. The layer must be added before the VM runner layers using it.
+///
+/// ## Adds tasks
+///
+/// - Listener task.
+#[derive(Debug, Default)]
+pub struct SealedBatchesListenerLayer;
+
+#[async_trait::async_trait]
+impl WiringLayer for SealedBatchesListenerLayer {
+    fn layer_name(&self) -> &'static str {
+        "vm_runner_sealed_batches_listener"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let master_pool = context.get_resource::<PoolResource<MasterPool>>().await?;
+        // One connection is held by the listener, and another one is used to query the latest sealed batch on start.
+        let listener = SealedBatchesListener::new(master_pool.get_custom(2).await?);
+        context.insert_resource(SealedBatchesResource(listener.subscribe()))?;
+        context.add_task(Box::new(listener));
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for SealedBatchesListener {
+    fn id(&self) -> TaskId {
+        "vm_runner/sealed_batches_listener".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        SealedBatchesListener::run(*self, stop_receiver.0).await
+    }
+}
@@ -0,0 +1,97 @@
+use anyhow::Context as _;
+use zksync_types::{L2ChainId, H256};
+use zksync_vm_runner::SharedVmRunnerStorage;
+
+use crate::{
+    implementations::resources::{
+        pools::{MasterPool, PoolResource},
+        vm_runner::SharedVmRunnerStorageResource,
+    },
+    service::{ServiceContext, StopReceiver},
+    task::{Task, TaskId},
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Wiring layer for [`SharedVmRunnerStorage`], which allows VM runners in the same process (e.g., the protective
+/// reads writer and the basic witness input producer) to use a single RocksDB cache and a single task catching it up
+/// with Postgres.
+///
+/// ## Adds resources
+///
+/// - [`SharedVmRunnerStorageResource`]. The layer must be added before the VM runner layers using it.
+///
+/// ## Adds tasks
+///
+/// - Storage sync task for all VM runners added to the storage.
+#[derive(Debug)]
+pub struct SharedVmRunnerStorageLayer {
+    rocksdb_path: String,
+    zksync_network_id: L2ChainId,
+    evm_emulator_hash: Option<H256>,
+}
+
+impl SharedVmRunnerStorageLayer {
+    pub fn new(rocksdb_path: String, zksync_network_id: L2ChainId) -> Self {
+        Self {
+            rocksdb_path,
+            zksync_network_id,
+            evm_emulator_hash: None,
+        }
+    }
+
+    /// Enables the EVM emulator with the specified bytecode hash (usually, taken from the genesis config).
+    pub fn with_evm_emulator_hash(mut self, evm_emulator_hash: Option<H256>) -> Self {
+        self.evm_emulator_hash = evm_emulator_hash;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for SharedVmRunnerStorageLayer {
+    fn layer_name(&self) -> &'static str {
+        "vm_runner_shared_storage"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let master_pool = context.get_resource::<PoolResource<MasterPool>>().await?;
+
+        let storage = SharedVmRunnerStorage::new(
+            // One for `StorageSyncTask` which can hold a long-term connection in case it needs to
+            // catch up cache, and one for VM runners loading batches from Postgres before the cache is ready.
+            master_pool.get_custom(2).await?,
+            self.zksync_network_id,
+            self.evm_emulator_hash,
+        )
+        .await?;
+        let storage = SharedVmRunnerStorageResource::new(storage);
+        context.insert_resource(storage.clone())?;
+        context.add_task(Box::new(SharedStorageSyncTask {
+            storage,
+            rocksdb_path: self.rocksdb_path,
+        }));
+        Ok(())
+    }
+}
+
+/// Creates the storage sync task once all VM runners are added to the storage (i.e., after wiring) and runs it.
+#[derive(Debug)]
+struct SharedStorageSyncTask {
+    storage: SharedVmRunnerStorageResource,
+    rocksdb_path: String,
+}
+
+#[async_trait::async_trait]
+impl Task for SharedStorageSyncTask {
+    fn id(&self) -> TaskId {
+        "vm_runner/shared_storage_sync".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        let storage = self
+            .storage
+            .take()
+            .context("shared VM runner storage is already consumed")?;
+        let sync_task = storage.into_sync_task(self.rocksdb_path).await?;
+        Task::run(Box::new(sync_task), stop_receiver).await
+    }
+}
@@ -0,0 +1,84 @@
+use zksync_types::{L2ChainId, H256};
+use zksync_vm_runner::{UpgradeCanary, VmRunnerStorageBackend};
+
+use crate::{
+    implementations::resources::pools::{MasterPool, PoolResource},
+    service::{ServiceContext, StopReceiver},
+    task::{Task, TaskId},
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Wiring layer for [`UpgradeCanary`], which shadow-executes L1 batches under the next protocol version
+/// once a protocol upgrade is scheduled.
+#[derive(Debug)]
+pub struct UpgradeCanaryLayer {
+    storage_backend: VmRunnerStorageBackend,
+    canary_batches: u32,
+    zksync_network_id: L2ChainId,
+    evm_emulator_hash: Option<H256>,
+}
+
+impl UpgradeCanaryLayer {
+    pub fn new(
+        storage_backend: VmRunnerStorageBackend,
+        canary_batches: u32,
+        zksync_network_id: L2ChainId,
+    ) -> Self {
+        Self {
+            storage_backend,
+            canary_batches,
+            zksync_network_id,
+            evm_emulator_hash: None,
+        }
+    }
+
+    /// Enables the EVM emulator with the specified bytecode hash (usually, taken from the genesis config).
+    pub fn with_evm_emulator_hash(mut self, evm_emulator_hash: Option<H256>) -> Self {
+        self.evm_emulator_hash = evm_emulator_hash;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for UpgradeCanaryLayer {
+    fn layer_name(&self) -> &'static str {
+        "vm_runner_upgrade_canary"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let master_pool = context.get_resource::<PoolResource<MasterPool>>().await?;
+
+        let (upgrade_canary, tasks) = UpgradeCanary::new(
+            // One for `StorageSyncTask`, one for `ConcurrentOutputHandlerFactoryTask`/`VmRunner`,
+            // and one for the output handler comparing execution results with Postgres.
+            master_pool.get_custom(3).await?,
+            self.storage_backend,
+            self.zksync_network_id,
+            self.evm_emulator_hash,
+            self.canary_batches,
+            None,
+        )
+        .await?;
+
+        context.add_task(Box::new(tasks.loader_task));
+        context.add_task(Box::new(tasks.output_handler_factory_task));
+        context.add_task(Box::new(UpgradeCanaryTask { upgrade_canary }));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct UpgradeCanaryTask {
+    upgrade_canary: UpgradeCanary,
+}
+
+#[async_trait::async_trait]
+impl Task for UpgradeCanaryTask {
+    fn id(&self) -> TaskId {
+        "vm_runner/upgrade_canary".into()
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.upgrade_canary.run(&stop_receiver.0).await
+    }
+}
@@ -9,12 +9,12 @@ use crate::{
     implementations::resources::{
         circuit_breakers::CircuitBreakersResource,
         healthcheck::AppHealthCheckResource,
-        pools::{PoolResource, ReplicaPool},
+        pools::{MasterPool, PoolResource, ReplicaPool},
         sync_state::SyncStateResource,
         web3_api::{MempoolCacheResource, TreeApiClientResource, TxSenderResource},
     },
     service::{ServiceContext, StopReceiver},
-    task::{Task, TaskId},
+    task::{Task, TaskId, TaskRuntime},
     wiring_layer::{WiringError, WiringLayer},
 };
 
@@ -147,6 +147,15 @@ impl WiringLayer for Web3ServerLayer {
         if let Some(sync_state) = sync_state {
             api_builder = api_builder.with_sync_state(sync_state);
         }
+        let admin_namespace_enabled = self
+            .optional_config
+            .namespaces
+            .as_ref()
+            .is_some_and(|namespaces| namespaces.contains(&Namespace::Admin));
+        if admin_namespace_enabled {
+            let master_pool = context.get_resource::<PoolResource<MasterPool>>().await?;
+            api_builder = api_builder.with_master_pool(master_pool.get_singleton().await?);
+        }
         let replication_lag_limit = self.optional_config.replication_lag_limit;
         api_builder = self.optional_config.apply(api_builder);
         let server = api_builder.build()?;
@@ -213,6 +222,10 @@ impl Task for Web3ApiTask {
         }
     }
 
+    fn runtime(&self) -> TaskRuntime {
+        TaskRuntime::LatencySensitive
+    }
+
     async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
         let tasks = self.server.run(stop_receiver.0).await?;
         // Wait for the first task to finish to be able to signal the service.
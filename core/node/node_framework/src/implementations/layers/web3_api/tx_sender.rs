@@ -1,7 +1,7 @@
 use std::{fmt, sync::Arc};
 
 use zksync_node_api_server::{
-    execution_sandbox::{VmConcurrencyBarrier, VmConcurrencyLimiter},
+    execution_sandbox::{VmConcurrencyBarrier, VmConcurrencyLimiter, VmFairSchedulingConfig},
     tx_sender::{ApiContracts, TxSenderBuilder, TxSenderConfig},
 };
 use zksync_state::PostgresStorageCaches;
@@ -30,6 +30,7 @@ pub struct TxSenderLayer {
     tx_sender_config: TxSenderConfig,
     postgres_storage_caches_config: PostgresStorageCachesConfig,
     max_vm_concurrency: usize,
+    vm_fair_scheduling: Option<VmFairSchedulingConfig>,
     api_contracts: ApiContracts,
 }
 
@@ -44,9 +45,16 @@ impl TxSenderLayer {
             tx_sender_config,
             postgres_storage_caches_config,
             max_vm_concurrency,
+            vm_fair_scheduling: None,
             api_contracts,
         }
     }
+
+    /// Enables fair scheduling of VM permits among API clients.
+    pub fn with_vm_fair_scheduling(mut self, config: Option<VmFairSchedulingConfig>) -> Self {
+        self.vm_fair_scheduling = config;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -85,8 +93,11 @@ impl WiringLayer for TxSenderLayer {
         }
 
         // Initialize `VmConcurrencyLimiter`.
-        let (vm_concurrency_limiter, vm_concurrency_barrier) =
+        let (mut vm_concurrency_limiter, vm_concurrency_barrier) =
             VmConcurrencyLimiter::new(self.max_vm_concurrency);
+        if let Some(config) = self.vm_fair_scheduling {
+            vm_concurrency_limiter = vm_concurrency_limiter.with_fair_scheduling(config);
+        }
         context.add_task(Box::new(VmConcurrencyBarrierTask {
             barrier: vm_concurrency_barrier,
         }));
@@ -1,6 +1,9 @@
 use std::sync::Arc;
 
-use zksync_node_api_server::tx_sender::{master_pool_sink::MasterPoolSink, proxy::TxProxy};
+use zksync_node_api_server::tx_sender::{
+    master_pool_sink::{MasterPoolSink, MempoolQuotas},
+    proxy::TxProxy,
+};
 
 use crate::{
     implementations::resources::{
@@ -15,7 +18,12 @@ use crate::{
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum TxSinkLayer {
-    MasterPoolSink,
+    MasterPoolSink {
+        /// Minimum fee bump (in percent) required to replace a pending transaction with the same nonce.
+        replacement_fee_bump_percent: u32,
+        /// Quotas on pending transactions in the mempool.
+        quotas: MempoolQuotas,
+    },
     ProxySink,
 }
 
@@ -27,13 +35,19 @@ impl WiringLayer for TxSinkLayer {
 
     async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
         let tx_sink = match self.as_ref() {
-            TxSinkLayer::MasterPoolSink => {
+            TxSinkLayer::MasterPoolSink {
+                replacement_fee_bump_percent,
+                quotas,
+            } => {
                 let pool = context
                     .get_resource::<PoolResource<MasterPool>>()
                     .await?
                     .get()
                     .await?;
-                TxSinkResource(Arc::new(MasterPoolSink::new(pool)))
+                let sink = MasterPoolSink::new(pool)
+                    .with_replacement_fee_bump(*replacement_fee_bump_percent)
+                    .with_quotas(*quotas);
+                TxSinkResource(Arc::new(sink))
             }
             TxSinkLayer::ProxySink => {
                 let MainNodeClientResource(client) = context.get_resource().await?;
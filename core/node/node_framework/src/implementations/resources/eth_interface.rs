@@ -1,4 +1,7 @@
+use std::sync::Arc;
+
 use zksync_eth_client::BoundEthInterface;
+use zksync_eth_sender::L1TxSubmissionStrategy;
 use zksync_web3_decl::client::{DynClient, L1};
 
 use crate::resource::Resource;
@@ -29,3 +32,14 @@ impl Resource for BoundEthInterfaceForBlobsResource {
         "common/bound_eth_interface_for_blobs".into()
     }
 }
+
+/// Strategy used by the Ethereum transaction manager to prepare L1 calls. If not provided,
+/// transactions are sent directly to their target contracts.
+#[derive(Debug, Clone)]
+pub struct L1TxSubmissionStrategyResource(pub Arc<dyn L1TxSubmissionStrategy>);
+
+impl Resource for L1TxSubmissionStrategyResource {
+    fn name() -> String {
+        "common/l1_tx_submission_strategy".into()
+    }
+}
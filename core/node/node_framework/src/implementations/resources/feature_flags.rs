@@ -0,0 +1,14 @@
+use zksync_feature_flags::FeatureFlags;
+
+use crate::resource::Resource;
+
+/// A resource that provides [`FeatureFlags`] to the service. If the feature flags layer is not added,
+/// the default resource with all features disabled is used.
+#[derive(Debug, Clone, Default)]
+pub struct FeatureFlagsResource(pub FeatureFlags);
+
+impl Resource for FeatureFlagsResource {
+    fn name() -> String {
+        "common/feature_flags".into()
+    }
+}
@@ -1,6 +1,7 @@
 pub mod action_queue;
 pub mod circuit_breakers;
 pub mod eth_interface;
+pub mod feature_flags;
 pub mod fee_input;
 pub mod healthcheck;
 pub mod l1_tx_params;
@@ -8,6 +9,8 @@ pub mod main_node_client;
 pub mod object_store;
 pub mod pools;
 pub mod reverter;
+pub mod scheduler;
 pub mod state_keeper;
 pub mod sync_state;
+pub mod vm_runner;
 pub mod web3_api;
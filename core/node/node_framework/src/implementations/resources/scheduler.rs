@@ -0,0 +1,95 @@
+use std::{
+    collections::HashSet,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::watch;
+use zksync_house_keeper::periodic_job::PeriodicJob;
+
+use crate::resource::Resource;
+
+/// Periodic maintenance job (e.g., pruning, GC or a consistency check) run by the scheduler
+/// according to the schedule configured for the job.
+#[async_trait::async_trait]
+pub trait ScheduledJob: 'static + Send + fmt::Debug {
+    /// Unique name of the job. Used to match the job with its schedule in the scheduler config.
+    fn name(&self) -> &'static str;
+
+    /// Performs a single run of the job. Long-running jobs should check `stop_receiver`
+    /// and return early once a stop signal is received.
+    async fn run(&mut self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()>;
+}
+
+/// Adapter allowing to run a [`PeriodicJob`] by the scheduler rather than in its own interval loop.
+/// The polling interval of the wrapped job is ignored.
+#[derive(Debug)]
+pub struct PeriodicJobAdapter<J>(pub J);
+
+#[async_trait::async_trait]
+impl<J: PeriodicJob + fmt::Debug + 'static> ScheduledJob for PeriodicJobAdapter<J> {
+    fn name(&self) -> &'static str {
+        J::SERVICE_NAME
+    }
+
+    async fn run(&mut self, _stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.0.run_routine_task().await
+    }
+}
+
+/// Collection of jobs registered in the scheduler.
+#[derive(Debug, Default)]
+pub struct ScheduledJobs {
+    /// Names of jobs that have a schedule in the scheduler config.
+    scheduled_names: HashSet<String>,
+    jobs: Mutex<Vec<Box<dyn ScheduledJob>>>,
+}
+
+impl ScheduledJobs {
+    pub(crate) fn new(scheduled_names: HashSet<String>) -> Self {
+        Self {
+            scheduled_names,
+            jobs: Mutex::default(),
+        }
+    }
+
+    /// Checks whether the scheduler config contains a schedule for the job with the specified name.
+    pub fn is_scheduled(&self, name: &str) -> bool {
+        self.scheduled_names.contains(name)
+    }
+
+    /// Registers a job. The job is only run if the scheduler config contains a schedule for it.
+    pub fn insert(&self, job: Box<dyn ScheduledJob>) {
+        self.jobs.lock().unwrap().push(job);
+    }
+
+    /// Registers a periodic job if the scheduler config contains a schedule for it. Otherwise, returns the job
+    /// back to the caller, so that it can be run in its own interval loop.
+    pub fn insert_periodic<J: PeriodicJob + fmt::Debug + 'static>(&self, job: J) -> Option<J> {
+        if self.is_scheduled(J::SERVICE_NAME) {
+            self.insert(Box::new(PeriodicJobAdapter(job)));
+            None
+        } else {
+            Some(job)
+        }
+    }
+
+    pub(crate) fn take(&self) -> Vec<Box<dyn ScheduledJob>> {
+        std::mem::take(&mut *self.jobs.lock().unwrap())
+    }
+}
+
+/// A resource that provides [`ScheduledJobs`] to the service. The resource is inserted by
+/// the [scheduler](crate::implementations::layers::scheduler::SchedulerLayer), so the scheduler must be added
+/// to the node before layers registering maintenance jobs. If the resource is missing, layers should run their jobs
+/// in their own interval loops.
+#[derive(Debug, Clone, Default)]
+pub struct ScheduledJobsResource {
+    pub jobs: Arc<ScheduledJobs>,
+}
+
+impl Resource for ScheduledJobsResource {
+    fn name() -> String {
+        "common/scheduled_jobs".into()
+    }
+}
@@ -0,0 +1,54 @@
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::watch;
+use zksync_types::L1BatchNumber;
+use zksync_vm_runner::SharedVmRunnerStorage;
+
+use crate::resource::Resource;
+
+/// A resource that provides [`SharedVmRunnerStorage`] for VM runners co-located in the same process.
+/// The resource is inserted by [`SharedVmRunnerStorageLayer`], which must be added to the node before the VM runner
+/// layers using it. VM runners add themselves to the storage during wiring; the storage is consumed once the service
+/// starts.
+///
+/// [`SharedVmRunnerStorageLayer`]: crate::implementations::layers::vm_runner::shared_storage::SharedVmRunnerStorageLayer
+#[derive(Debug, Clone)]
+pub struct SharedVmRunnerStorageResource(Arc<Mutex<Option<SharedVmRunnerStorage>>>);
+
+impl Resource for SharedVmRunnerStorageResource {
+    fn name() -> String {
+        "vm_runner/shared_storage".into()
+    }
+}
+
+impl SharedVmRunnerStorageResource {
+    pub fn new(storage: SharedVmRunnerStorage) -> Self {
+        Self(Arc::new(Mutex::new(Some(storage))))
+    }
+
+    /// Provides mutable access to the storage, e.g. to add a VM runner instance. Returns `None` if the storage
+    /// was already consumed.
+    pub fn with_storage<R>(
+        &self,
+        action: impl FnOnce(&mut SharedVmRunnerStorage) -> R,
+    ) -> Option<R> {
+        self.0.lock().unwrap().as_mut().map(action)
+    }
+
+    pub(crate) fn take(&self) -> Option<SharedVmRunnerStorage> {
+        self.0.lock().unwrap().take()
+    }
+}
+
+/// A resource that provides the latest sealed L1 batch number as reported by Postgres notifications. Inserted by
+/// [`SealedBatchesListenerLayer`]; VM runner layers use it to react to sealed batches instead of polling.
+///
+/// [`SealedBatchesListenerLayer`]: crate::implementations::layers::vm_runner::sealed_batches::SealedBatchesListenerLayer
+#[derive(Debug, Clone)]
+pub struct SealedBatchesResource(pub watch::Receiver<L1BatchNumber>);
+
+impl Resource for SealedBatchesResource {
+    fn name() -> String {
+        "vm_runner/sealed_batches".into()
+    }
+}
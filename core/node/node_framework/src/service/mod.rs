@@ -23,16 +23,38 @@ mod tests;
 // A reasonable amount of time for any task to finish the shutdown process
 const TASK_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// Configuration of tokio runtimes created by [`ZkStackService`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RuntimeConfig {
+    /// Number of worker threads of the main runtime. If not set, the tokio default (the number of CPU cores) is used.
+    pub main_worker_threads: Option<usize>,
+    /// Number of worker threads of the dedicated runtime for [latency-sensitive](crate::task::TaskRuntime::LatencySensitive)
+    /// tasks, such as API servers. If not set, the dedicated runtime is not created, and all tasks are spawned
+    /// on the main runtime.
+    pub latency_sensitive_worker_threads: Option<usize>,
+}
+
 /// A builder for [`ZkStackService`].
 #[derive(Default, Debug)]
 pub struct ZkStackServiceBuilder {
     /// List of wiring layers.
     layers: Vec<Box<dyn WiringLayer>>,
+    /// Configuration of tokio runtimes.
+    runtime_config: RuntimeConfig,
 }
 
 impl ZkStackServiceBuilder {
     pub fn new() -> Self {
-        Self { layers: Vec::new() }
+        Self {
+            layers: Vec::new(),
+            runtime_config: RuntimeConfig::default(),
+        }
+    }
+
+    /// Sets the configuration of tokio runtimes used by the service.
+    pub fn with_runtime_config(&mut self, runtime_config: RuntimeConfig) -> &mut Self {
+        self.runtime_config = runtime_config;
+        self
     }
 
     /// Adds a wiring layer.
@@ -58,10 +80,22 @@ impl ZkStackServiceBuilder {
         if tokio::runtime::Handle::try_current().is_ok() {
             return Err(ZkStackServiceError::RuntimeDetected);
         }
-        let runtime = tokio::runtime::Builder::new_multi_thread()
-            .enable_all()
-            .build()
-            .unwrap();
+        let mut runtime_builder = tokio::runtime::Builder::new_multi_thread();
+        if let Some(worker_threads) = self.runtime_config.main_worker_threads {
+            runtime_builder.worker_threads(worker_threads);
+        }
+        let runtime = runtime_builder.enable_all().build().unwrap();
+        let latency_sensitive_runtime =
+            self.runtime_config
+                .latency_sensitive_worker_threads
+                .map(|worker_threads| {
+                    tokio::runtime::Builder::new_multi_thread()
+                        .worker_threads(worker_threads)
+                        .thread_name("latency-sensitive-worker")
+                        .enable_all()
+                        .build()
+                        .unwrap()
+                });
 
         let (stop_sender, _stop_receiver) = watch::channel(false);
 
@@ -71,6 +105,7 @@ impl ZkStackServiceBuilder {
             runnables: Default::default(),
             stop_sender,
             runtime,
+            latency_sensitive_runtime,
         })
     }
 }
@@ -90,6 +125,12 @@ pub struct ZkStackService {
     stop_sender: watch::Sender<bool>,
     /// Tokio runtime used to spawn tasks.
     runtime: Runtime,
+    /// Dedicated tokio runtime used to spawn latency-sensitive tasks. If not set, such tasks are spawned
+    /// on the main runtime.
+    ///
+    /// Wiring is performed on the main runtime, so I/O resources created during wiring (e.g., DB connections
+    /// established eagerly) are driven by the main runtime reactor even if used by latency-sensitive tasks.
+    latency_sensitive_runtime: Option<Runtime>,
 }
 
 impl ZkStackService {
@@ -137,6 +178,7 @@ impl ZkStackService {
         let stop_receiver = StopReceiver(self.stop_sender.subscribe());
         let TaskReprs {
             mut long_running_tasks,
+            latency_sensitive_tasks,
             oneshot_tasks,
         } = self
             .runnables
@@ -157,9 +199,18 @@ impl ZkStackService {
 
         // Prepare tasks for running.
         let rt_handle = self.runtime.handle().clone();
+        let latency_sensitive_rt_handle = self
+            .latency_sensitive_runtime
+            .as_ref()
+            .map_or_else(|| rt_handle.clone(), |runtime| runtime.handle().clone());
         let join_handles: Vec<_> = long_running_tasks
             .into_iter()
             .map(|task| rt_handle.spawn(task).fuse())
+            .chain(
+                latency_sensitive_tasks
+                    .into_iter()
+                    .map(|task| latency_sensitive_rt_handle.spawn(task).fuse()),
+            )
             .collect();
 
         // Run the tasks until one of them exits.
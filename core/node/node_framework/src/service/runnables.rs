@@ -7,7 +7,7 @@ use tokio::sync::Barrier;
 use super::StopReceiver;
 use crate::{
     precondition::Precondition,
-    task::{OneshotTask, Task, UnconstrainedOneshotTask, UnconstrainedTask},
+    task::{OneshotTask, Task, TaskRuntime, UnconstrainedOneshotTask, UnconstrainedTask},
 };
 
 /// A collection of different flavors of tasks.
@@ -51,6 +51,8 @@ impl fmt::Debug for Runnables {
 /// A unified representation of tasks that can be run by the service.
 pub(super) struct TaskReprs {
     pub(super) long_running_tasks: Vec<BoxFuture<'static, anyhow::Result<()>>>,
+    /// Long-running tasks that requested to be spawned on the latency-sensitive runtime.
+    pub(super) latency_sensitive_tasks: Vec<BoxFuture<'static, anyhow::Result<()>>>,
     pub(super) oneshot_tasks: Vec<BoxFuture<'static, anyhow::Result<()>>>,
 }
 
@@ -58,6 +60,10 @@ impl fmt::Debug for TaskReprs {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("TaskReprs")
             .field("long_running_tasks", &self.long_running_tasks.len())
+            .field(
+                "latency_sensitive_tasks",
+                &self.latency_sensitive_tasks.len(),
+            )
             .field("oneshot_tasks", &self.oneshot_tasks.len())
             .finish()
     }
@@ -95,9 +101,15 @@ impl Runnables {
         stop_receiver: StopReceiver,
     ) -> TaskReprs {
         let mut long_running_tasks = Vec::new();
-        self.collect_unconstrained_tasks(&mut long_running_tasks, stop_receiver.clone());
+        let mut latency_sensitive_tasks = Vec::new();
+        self.collect_unconstrained_tasks(
+            &mut long_running_tasks,
+            &mut latency_sensitive_tasks,
+            stop_receiver.clone(),
+        );
         self.collect_tasks(
             &mut long_running_tasks,
+            &mut latency_sensitive_tasks,
             task_barrier.clone(),
             stop_receiver.clone(),
         );
@@ -117,6 +129,7 @@ impl Runnables {
 
         TaskReprs {
             long_running_tasks,
+            latency_sensitive_tasks,
             oneshot_tasks,
         }
     }
@@ -124,28 +137,35 @@ impl Runnables {
     fn collect_unconstrained_tasks(
         &mut self,
         tasks: &mut Vec<BoxFuture<'static, anyhow::Result<()>>>,
+        latency_sensitive_tasks: &mut Vec<BoxFuture<'static, anyhow::Result<()>>>,
         stop_receiver: StopReceiver,
     ) {
         for task in std::mem::take(&mut self.unconstrained_tasks) {
             let name = task.id();
+            let runtime = task.runtime();
             let stop_receiver = stop_receiver.clone();
             let task_future = Box::pin(async move {
                 task.run_unconstrained(stop_receiver)
                     .await
                     .with_context(|| format!("Task {name} failed"))
             });
-            tasks.push(task_future);
+            match runtime {
+                TaskRuntime::Main => tasks.push(task_future),
+                TaskRuntime::LatencySensitive => latency_sensitive_tasks.push(task_future),
+            }
         }
     }
 
     fn collect_tasks(
         &mut self,
         tasks: &mut Vec<BoxFuture<'static, anyhow::Result<()>>>,
+        latency_sensitive_tasks: &mut Vec<BoxFuture<'static, anyhow::Result<()>>>,
         task_barrier: Arc<Barrier>,
         stop_receiver: StopReceiver,
     ) {
         for task in std::mem::take(&mut self.tasks) {
             let name = task.id();
+            let runtime = task.runtime();
             let stop_receiver = stop_receiver.clone();
             let task_barrier = task_barrier.clone();
             let task_future = Box::pin(async move {
@@ -153,7 +173,10 @@ impl Runnables {
                     .await
                     .with_context(|| format!("Task {name} failed"))
             });
-            tasks.push(task_future);
+            match runtime {
+                TaskRuntime::Main => tasks.push(task_future),
+                TaskRuntime::LatencySensitive => latency_sensitive_tasks.push(task_future),
+            }
         }
     }
 
@@ -6,10 +6,10 @@ use tokio::{runtime::Runtime, sync::Barrier};
 
 use crate::{
     service::{
-        ServiceContext, StopReceiver, WiringError, WiringLayer, ZkStackServiceBuilder,
-        ZkStackServiceError,
+        RuntimeConfig, ServiceContext, StopReceiver, WiringError, WiringLayer,
+        ZkStackServiceBuilder, ZkStackServiceError,
     },
-    task::{Task, TaskId},
+    task::{Task, TaskId, TaskRuntime},
 };
 
 // `ZkStack` Service's `new()` method has to have a check for nested runtime.
@@ -232,3 +232,79 @@ fn test_task_run() {
     let res2 = *remaining_task_was_run.lock().unwrap();
     assert!(res2, "Incorrect resource value");
 }
+
+#[derive(Debug)]
+struct ThreadNameTask {
+    runtime: TaskRuntime,
+    thread_name: Arc<Mutex<Option<String>>>,
+}
+
+#[async_trait::async_trait]
+impl Task for ThreadNameTask {
+    fn id(&self) -> TaskId {
+        format!("thread_name_task_{:?}", self.runtime).into()
+    }
+
+    fn runtime(&self) -> TaskRuntime {
+        self.runtime
+    }
+
+    async fn run(self: Box<Self>, _stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        let thread_name = std::thread::current().name().map(str::to_owned);
+        *self.thread_name.lock().unwrap() = thread_name;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct ThreadNameLayer {
+    runtime: TaskRuntime,
+    thread_name: Arc<Mutex<Option<String>>>,
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ThreadNameLayer {
+    fn layer_name(&self) -> &'static str {
+        "thread_name_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut node: ServiceContext<'_>) -> Result<(), WiringError> {
+        node.add_task(Box::new(ThreadNameTask {
+            runtime: self.runtime,
+            thread_name: self.thread_name,
+        }));
+        Ok(())
+    }
+}
+
+fn run_thread_name_task(runtime_config: RuntimeConfig, runtime: TaskRuntime) -> Option<String> {
+    let thread_name = Arc::<Mutex<Option<String>>>::default();
+    let mut zk_stack_service = ZkStackServiceBuilder::new();
+    zk_stack_service
+        .with_runtime_config(runtime_config)
+        .add_layer(ThreadNameLayer {
+            runtime,
+            thread_name: thread_name.clone(),
+        });
+    zk_stack_service.build().unwrap().run().unwrap();
+    let thread_name = thread_name.lock().unwrap().clone();
+    thread_name
+}
+
+// Latency-sensitive tasks must be spawned on the dedicated runtime if it is configured.
+#[test]
+fn test_latency_sensitive_tasks_use_dedicated_runtime() {
+    let runtime_config = RuntimeConfig {
+        main_worker_threads: Some(1),
+        latency_sensitive_worker_threads: Some(1),
+    };
+    let thread_name = run_thread_name_task(runtime_config, TaskRuntime::LatencySensitive);
+    assert_eq!(thread_name.as_deref(), Some("latency-sensitive-worker"));
+
+    let thread_name = run_thread_name_task(runtime_config, TaskRuntime::Main);
+    assert_ne!(thread_name.as_deref(), Some("latency-sensitive-worker"));
+
+    // Without a dedicated runtime, latency-sensitive tasks fall back to the main runtime.
+    let thread_name = run_thread_name_task(RuntimeConfig::default(), TaskRuntime::LatencySensitive);
+    assert_ne!(thread_name.as_deref(), Some("latency-sensitive-worker"));
+}
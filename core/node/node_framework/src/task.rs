@@ -74,6 +74,22 @@ impl Deref for TaskId {
     }
 }
 
+/// Tokio runtime that a long-running task should be spawned on.
+///
+/// By default, all tasks are spawned on the main runtime of the service. If the service is configured with
+/// a dedicated runtime for latency-sensitive tasks (see [`RuntimeConfig`](crate::service::RuntimeConfig)),
+/// tasks returning [`Self::LatencySensitive`] are spawned on it instead, so that CPU-heavy background tasks
+/// (e.g., Merkle tree or VM runner) cannot starve them of worker threads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TaskRuntime {
+    /// Main runtime of the service.
+    #[default]
+    Main,
+    /// Dedicated runtime for latency-sensitive tasks, such as API servers. Falls back to the main runtime
+    /// if the dedicated runtime is not configured.
+    LatencySensitive,
+}
+
 /// A task implementation.
 ///
 /// Note: any `Task` added to the service will only start after all the [preconditions](crate::precondition::Precondition)
@@ -83,6 +99,11 @@ pub trait Task: 'static + Send {
     /// Unique name of the task.
     fn id(&self) -> TaskId;
 
+    /// Runtime the task should be spawned on. Tasks are spawned on the main runtime by default.
+    fn runtime(&self) -> TaskRuntime {
+        TaskRuntime::Main
+    }
+
     /// Runs the task.
     ///
     /// Once any of the task returns, the node will shutdown.
@@ -172,6 +193,11 @@ pub trait UnconstrainedTask: 'static + Send {
     /// Unique name of the task.
     fn id(&self) -> TaskId;
 
+    /// Runtime the task should be spawned on. See [`Task::runtime()`] for details.
+    fn runtime(&self) -> TaskRuntime {
+        TaskRuntime::Main
+    }
+
     /// Runs the task without waiting for any precondition to be met.
     async fn run_unconstrained(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()>;
 }
@@ -172,16 +172,28 @@ impl UpdaterCursor {
         }
     }
 
+    /// Updates the cursor with the batch status. If `last_verified_l1_batch` is specified, batches after it
+    /// are never marked as executed.
     fn update(
         &mut self,
         status_changes: &mut StatusChanges,
         batch_info: &api::BlockDetails,
+        last_verified_l1_batch: Option<L1BatchNumber>,
     ) -> anyhow::Result<()> {
         for stage in [
             AggregatedActionType::Commit,
             AggregatedActionType::PublishProofOnchain,
             AggregatedActionType::Execute,
         ] {
+            if matches!(stage, AggregatedActionType::Execute)
+                && last_verified_l1_batch.is_some_and(|last| batch_info.l1_batch_number > last)
+            {
+                tracing::debug!(
+                    "Batch {} is not verified against L1 yet; postponing updating its execution status",
+                    batch_info.l1_batch_number
+                );
+                continue;
+            }
             self.update_stage(status_changes, batch_info, stage)?;
         }
         Ok(())
@@ -246,6 +258,8 @@ pub struct BatchStatusUpdater {
     pool: ConnectionPool<Core>,
     health_updater: HealthUpdater,
     sleep_interval: Duration,
+    /// Whether executed batches must be verified by the consistency checker before being marked as executed locally.
+    verify_on_sync: bool,
     /// Test-only sender of status changes each time they are produced and applied to the storage.
     #[cfg(test)]
     changes_sender: mpsc::UnboundedSender<StatusChanges>,
@@ -272,11 +286,20 @@ impl BatchStatusUpdater {
             pool,
             health_updater: ReactiveHealthCheck::new("batch_status_updater").1,
             sleep_interval,
+            verify_on_sync: false,
             #[cfg(test)]
             changes_sender: mpsc::unbounded_channel().0,
         }
     }
 
+    /// Enables the verify-on-sync mode. In this mode, a batch is marked as executed (i.e., final) locally only after
+    /// the consistency checker has verified it against L1; the consistency checker must run in the verify-on-sync mode
+    /// as well.
+    pub fn with_verify_on_sync(mut self) -> Self {
+        self.verify_on_sync = true;
+        self
+    }
+
     pub fn health_check(&self) -> ReactiveHealthCheck {
         self.health_updater.subscribe()
     }
@@ -332,16 +355,22 @@ impl BatchStatusUpdater {
         mut cursor: UpdaterCursor,
     ) -> Result<(), UpdaterError> {
         let total_latency = EN_METRICS.update_batch_statuses.start();
-        let Some(last_sealed_batch) = self
-            .pool
-            .connection_tagged("sync_layer")
-            .await?
-            .blocks_dal()
-            .get_sealed_l1_batch_number()
-            .await?
+        let mut storage = self.pool.connection_tagged("sync_layer").await?;
+        let Some(last_sealed_batch) = storage.blocks_dal().get_sealed_l1_batch_number().await?
         else {
             return Ok(()); // No L1 batches in the storage yet; do nothing.
         };
+        let last_verified_batch = if self.verify_on_sync {
+            Some(
+                storage
+                    .blocks_dal()
+                    .get_consistency_checker_last_processed_l1_batch()
+                    .await?,
+            )
+        } else {
+            None
+        };
+        drop(storage);
 
         let mut batch = cursor.last_executed_l1_batch.next();
         // In this loop we try to progress on the batch statuses, utilizing the same request to the node to potentially
@@ -364,7 +393,7 @@ impl BatchStatusUpdater {
                 return Err(err.into());
             };
 
-            cursor.update(status_changes, &batch_info)?;
+            cursor.update(status_changes, &batch_info, last_verified_batch)?;
 
             // Check whether we can skip a part of the range.
             if batch_info.base.commit_tx_hash.is_none() {
@@ -375,8 +404,12 @@ impl BatchStatusUpdater {
             {
                 // The interval between this batch and the last committed one is not proven.
                 batch = cursor.last_committed_l1_batch.next();
-            } else if batch_info.base.executed_at.is_none() && batch < cursor.last_proven_l1_batch {
-                // The interval between this batch and the last proven one is not executed.
+            } else if (batch_info.base.executed_at.is_none()
+                || last_verified_batch.is_some_and(|last| batch > last))
+                && batch < cursor.last_proven_l1_batch
+            {
+                // The interval between this batch and the last proven one is not executed (or cannot be marked
+                // as executed since it's not verified yet).
                 batch = cursor.last_proven_l1_batch.next();
             } else {
                 batch += 1;
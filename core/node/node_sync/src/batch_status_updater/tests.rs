@@ -433,3 +433,58 @@ async fn test_resuming_updater(pool: ConnectionPool<Core>, initial_batch_stages:
     stop_sender.send_replace(true);
     updater_task.await.unwrap().expect("updater failed");
 }
+
+#[tokio::test]
+async fn updater_in_verify_on_sync_mode() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    let target_batch_stages =
+        L1BatchStagesMap::new(L1BatchNumber(1), vec![L1BatchStage::Executed; 3]);
+    for (number, _) in target_batch_stages.iter() {
+        seal_l1_batch(&mut storage, number).await;
+    }
+
+    let client = MockMainNodeClient::from(target_batch_stages.clone());
+    let (updater, _) = mock_updater(client, pool.clone());
+    let updater = updater.with_verify_on_sync();
+    let mut cursor = UpdaterCursor::new(&mut storage).await.unwrap();
+
+    // No batches are verified yet, so none of them should be marked as executed.
+    let mut changes = StatusChanges::default();
+    updater
+        .get_status_changes(&mut changes, cursor)
+        .await
+        .unwrap();
+    assert_eq!(changes.commit.len(), 3);
+    assert_eq!(changes.prove.len(), 3);
+    assert!(changes.execute.is_empty());
+    updater
+        .apply_status_changes(&mut cursor, changes)
+        .await
+        .unwrap();
+    assert_eq!(cursor.last_proven_l1_batch, L1BatchNumber(3));
+    assert_eq!(cursor.last_executed_l1_batch, L1BatchNumber(0));
+
+    storage
+        .blocks_dal()
+        .set_consistency_checker_last_processed_l1_batch(L1BatchNumber(2))
+        .await
+        .unwrap();
+    let mut changes = StatusChanges::default();
+    updater
+        .get_status_changes(&mut changes, cursor)
+        .await
+        .unwrap();
+    assert!(changes.commit.is_empty());
+    assert!(changes.prove.is_empty());
+    let executed_batches: Vec<_> = changes.execute.iter().map(|change| change.number).collect();
+    assert_eq!(executed_batches, [L1BatchNumber(1), L1BatchNumber(2)]);
+    updater
+        .apply_status_changes(&mut cursor, changes)
+        .await
+        .unwrap();
+    assert_eq!(cursor.last_executed_l1_batch, L1BatchNumber(2));
+}
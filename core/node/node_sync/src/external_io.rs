@@ -40,6 +40,7 @@ pub struct ExternalIO {
     actions: ActionQueue,
     main_node_client: Box<dyn MainNodeClient>,
     chain_id: L2ChainId,
+    evm_emulator_hash: Option<H256>,
 }
 
 impl ExternalIO {
@@ -61,9 +62,21 @@ impl ExternalIO {
             actions,
             main_node_client,
             chain_id,
+            evm_emulator_hash: None,
         })
     }
 
+    /// Enables the EVM emulator with the specified bytecode hash (usually, taken from the main node genesis config)
+    /// for all executed L1 batches.
+    #[must_use]
+    pub fn with_evm_emulator_hash(mut self, evm_emulator_hash: Option<H256>) -> Self {
+        self.evm_emulator_hash = evm_emulator_hash;
+        self.l1_batch_params_provider = self
+            .l1_batch_params_provider
+            .with_evm_emulator_hash(evm_emulator_hash);
+        self
+    }
+
     async fn get_base_system_contract(
         &self,
         hash: H256,
@@ -322,7 +335,10 @@ impl StateKeeperIO for ExternalIO {
             .connection_tagged("sync_layer")
             .await?
             .protocol_versions_dal()
-            .load_base_system_contracts_by_version_id(protocol_version as u16)
+            .load_base_system_contracts_by_version_id(
+                protocol_version as u16,
+                self.evm_emulator_hash,
+            )
             .await
             .context("failed loading base system contracts")?;
 
@@ -376,9 +392,19 @@ impl StateKeeperIO for ExternalIO {
             .get_base_system_contract(default_account_code_hash, cursor.next_l2_block)
             .await
             .with_context(|| format!("cannot fetch default AA code for {protocol_version:?}"))?;
+        let evm_emulator = if let Some(evm_emulator_hash) = self.evm_emulator_hash {
+            let evm_emulator = self
+                .get_base_system_contract(evm_emulator_hash, cursor.next_l2_block)
+                .await
+                .context("cannot fetch EVM emulator code")?;
+            Some(evm_emulator)
+        } else {
+            None
+        };
         Ok(BaseSystemContracts {
             bootloader,
             default_aa,
+            evm_emulator,
         })
     }
 
@@ -2,7 +2,10 @@ use zksync_dal::{Connection, Core, CoreDal};
 use zksync_shared_metrics::{TxStage, APP_METRICS};
 use zksync_state_keeper::io::{common::IoCursor, L1BatchParams, L2BlockParams};
 use zksync_types::{
-    api::en::SyncBlock, block::L2BlockHasher, fee_model::BatchFeeInput, helpers::unix_timestamp_ms,
+    api::en::SyncBlock,
+    block::{L2BlockHasher, L2BlockHashes},
+    fee_model::BatchFeeInput,
+    helpers::unix_timestamp_ms,
     Address, L1BatchNumber, L2BlockNumber, ProtocolVersionId, H256,
 };
 
@@ -54,12 +57,12 @@ pub struct FetchedBlock {
 }
 
 impl FetchedBlock {
-    fn compute_hash(&self, prev_l2_block_hash: H256) -> H256 {
+    fn compute_hashes(&self, prev_l2_block_hash: H256) -> L2BlockHashes {
         let mut hasher = L2BlockHasher::new(self.number, self.timestamp, prev_l2_block_hash);
         for tx in &self.transactions {
             hasher.push_tx_hash(tx.hash());
         }
-        hasher.finalize(self.protocol_version)
+        hasher.finalize_for_migration(self.protocol_version)
     }
 }
 
@@ -123,9 +126,20 @@ impl IoCursorExt for IoCursor {
 
     fn advance(&mut self, block: FetchedBlock) -> Vec<SyncAction> {
         assert_eq!(block.number, self.next_l2_block);
-        let local_block_hash = block.compute_hash(self.prev_l2_block_hash);
+        let local_block_hashes = block.compute_hashes(self.prev_l2_block_hash);
+        // The cursor always uses the locally computed hash since it's the hash persisted by the state keeper.
+        let local_block_hash = local_block_hashes.hash;
         if let Some(reference_hash) = block.reference_hash {
-            if local_block_hash != reference_hash {
+            let matched_version = local_block_hashes.verify(reference_hash);
+            if let Some(version) = matched_version.filter(|&v| v != local_block_hashes.version) {
+                // The block is on the hash version upgrade boundary and was hashed by the main node
+                // using the previous version.
+                tracing::info!(
+                    "L2 block #{} is hashed by the main node using pre-upgrade hash version {version:?}; \
+                     local_block_hash = {local_block_hash:?}, reference_hash = {reference_hash:?}",
+                    block.number
+                );
+            } else if matched_version.is_none() {
                 // This is a warning, not an assertion because hash mismatch may occur after a reorg.
                 // Indeed, `self.prev_l2_block_hash` may differ from the hash of the updated previous L2 block.
                 tracing::warn!(
@@ -3,7 +3,8 @@ use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes, SystemCon
 use zksync_dal::{Connection, Core, CoreDal};
 use zksync_node_genesis::{ensure_genesis_state, GenesisParams};
 use zksync_types::{
-    block::DeployedContract, system_contracts::get_system_smart_contracts, AccountTreeId, L2ChainId,
+    block::DeployedContract, system_contracts::get_system_smart_contracts, AccountTreeId,
+    L2ChainId, H256,
 };
 
 use super::client::MainNodeClient;
@@ -50,9 +51,13 @@ async fn create_genesis_params(
 
     // These have to be *initial* base contract hashes of main node
     // (those that were used during genesis), not necessarily the current ones.
-    let base_system_contracts = fetch_base_system_contracts(client, base_system_contracts_hashes)
-        .await
-        .context("Failed to fetch base system contracts from main node")?;
+    let base_system_contracts = fetch_base_system_contracts(
+        client,
+        base_system_contracts_hashes,
+        config.evm_emulator_hash,
+    )
+    .await
+    .context("Failed to fetch base system contracts from main node")?;
 
     // In EN, we don't know what were the system contracts at the genesis state.
     // We know the list of addresses where these contracts *may have been* deployed.
@@ -90,6 +95,7 @@ async fn create_genesis_params(
 async fn fetch_base_system_contracts(
     client: &dyn MainNodeClient,
     contract_hashes: BaseSystemContractsHashes,
+    evm_emulator_hash: Option<H256>,
 ) -> anyhow::Result<BaseSystemContracts> {
     let bootloader_bytecode = client
         .fetch_system_contract_by_hash(contract_hashes.bootloader)
@@ -99,6 +105,18 @@ async fn fetch_base_system_contracts(
         .fetch_system_contract_by_hash(contract_hashes.default_aa)
         .await?
         .context("default AA bytecode is missing on main node")?;
+    let evm_emulator = if let Some(evm_emulator_hash) = evm_emulator_hash {
+        let evm_emulator_bytecode = client
+            .fetch_system_contract_by_hash(evm_emulator_hash)
+            .await?
+            .context("EVM emulator bytecode is missing on main node")?;
+        Some(SystemContractCode {
+            code: zksync_utils::bytes_to_be_words(evm_emulator_bytecode),
+            hash: evm_emulator_hash,
+        })
+    } else {
+        None
+    };
     Ok(BaseSystemContracts {
         bootloader: SystemContractCode {
             code: zksync_utils::bytes_to_be_words(bootloader_bytecode),
@@ -108,5 +126,6 @@ async fn fetch_base_system_contracts(
             code: zksync_utils::bytes_to_be_words(default_aa_bytecode),
             hash: contract_hashes.default_aa,
         },
+        evm_emulator,
     })
 }
@@ -11,7 +11,7 @@ use zksync_node_test_utils::{
     create_l1_batch_metadata, create_l2_transaction, prepare_recovery_snapshot,
 };
 use zksync_state_keeper::{
-    io::{L1BatchParams, L2BlockParams},
+    io::{common::IoCursor, L1BatchParams, L2BlockParams},
     seal_criteria::NoopSealer,
     testonly::test_batch_executor::{MockReadStorageFactory, TestBatchExecutorBuilder},
     OutputHandler, StateKeeperPersistence, TreeWritesPersistence, ZkSyncStateKeeper,
@@ -25,7 +25,10 @@ use zksync_types::{
 };
 
 use super::{
-    fetcher::FetchedTransaction, sync_action::SyncAction, testonly::MockMainNodeClient, *,
+    fetcher::{FetchedBlock, FetchedTransaction, IoCursorExt},
+    sync_action::SyncAction,
+    testonly::MockMainNodeClient,
+    *,
 };
 
 const TEST_TIMEOUT: Duration = Duration::from_secs(10);
@@ -630,3 +633,51 @@ async fn external_io_with_multiple_l1_batches() {
     assert_eq!(fictive_l2_block.timestamp, 2);
     assert_eq!(fictive_l2_block.l2_tx_count, 0);
 }
+
+#[test]
+fn fetcher_cursor_uses_locally_computed_block_hashes() {
+    let prev_l2_block_hash = H256::repeat_byte(1);
+    let hasher = || L2BlockHasher::new(L2BlockNumber(5), 12, prev_l2_block_hash);
+    let local_hash = hasher().finalize(ProtocolVersionId::Version13);
+    let legacy_hash = L2BlockHasher::legacy_hash(L2BlockNumber(5));
+    assert_ne!(local_hash, legacy_hash);
+
+    // The block on the hash version upgrade boundary may be hashed by the main node using either
+    // of hash versions; mismatching or missing reference hashes must not influence the cursor as well.
+    for reference_hash in [
+        Some(local_hash),
+        Some(legacy_hash),
+        Some(H256::repeat_byte(0xff)),
+        None,
+    ] {
+        let mut cursor = IoCursor {
+            next_l2_block: L2BlockNumber(5),
+            prev_l2_block_hash,
+            prev_l2_block_timestamp: 10,
+            l1_batch: L1BatchNumber(1),
+        };
+        let block = FetchedBlock {
+            number: L2BlockNumber(5),
+            l1_batch_number: L1BatchNumber(1),
+            last_in_batch: false,
+            protocol_version: ProtocolVersionId::Version13,
+            timestamp: 12,
+            reference_hash,
+            l1_gas_price: 1,
+            l2_fair_gas_price: 1,
+            fair_pubdata_price: None,
+            virtual_blocks: 1,
+            operator_address: OPERATOR_ADDRESS,
+            transactions: vec![],
+        };
+        let actions = cursor.advance(block);
+
+        assert_eq!(
+            cursor.prev_l2_block_hash, local_hash,
+            "reference_hash={reference_hash:?}"
+        );
+        assert_eq!(cursor.next_l2_block, L2BlockNumber(6));
+        assert_eq!(actions.len(), 2, "{actions:?}");
+        assert!(matches!(actions[1], SyncAction::SealL2Block), "{actions:?}");
+    }
+}
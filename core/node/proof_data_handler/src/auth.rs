@@ -0,0 +1,128 @@
+//! Authentication of provers accessing the proof data handler API.
+//!
+//! Provers authenticate with an API key passed in the `Authorization: Bearer <API key>` header.
+//! The server only stores keccak256 hashes of API keys mapped to prover IDs.
+
+use std::collections::HashMap;
+
+use anyhow::Context as _;
+use axum::http::{header, HeaderMap};
+use zksync_types::{web3::keccak256, H256};
+
+/// Authenticates provers by their API keys.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ProverAuthenticator {
+    /// Prover IDs keyed by API key hashes. If empty, authentication is disabled.
+    provers: HashMap<H256, String>,
+}
+
+impl ProverAuthenticator {
+    /// Parses authorized provers from the `<prover_id>:<API key hash>` format used in the config.
+    pub fn new(authorized_provers: &[String]) -> anyhow::Result<Self> {
+        let mut provers = HashMap::with_capacity(authorized_provers.len());
+        for entry in authorized_provers {
+            let (prover_id, key_hash) = entry
+                .rsplit_once(':')
+                .with_context(|| format!("authorized prover `{entry}` has no API key hash"))?;
+            anyhow::ensure!(!prover_id.is_empty(), "empty prover ID in `{entry}`");
+            let key_hash: H256 = key_hash
+                .parse()
+                .with_context(|| format!("invalid API key hash for prover `{prover_id}`"))?;
+            if let Some(prev_id) = provers.insert(key_hash, prover_id.to_owned()) {
+                anyhow::bail!("provers `{prev_id}` and `{prover_id}` have the same API key");
+            }
+        }
+        Ok(Self { provers })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.provers.is_empty()
+    }
+
+    /// Returns the ID of the authenticated prover, or `Ok(None)` if authentication is disabled.
+    pub fn authenticate(&self, headers: &HeaderMap) -> Result<Option<&str>, AuthError> {
+        if !self.is_enabled() {
+            return Ok(None);
+        }
+        let api_key = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or(AuthError::MissingApiKey)?;
+        let key_hash = H256(keccak256(api_key.trim().as_bytes()));
+        let prover_id = self
+            .provers
+            .get(&key_hash)
+            .ok_or(AuthError::UnknownApiKey)?;
+        Ok(Some(prover_id))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AuthError {
+    MissingApiKey,
+    UnknownApiKey,
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn authorized_prover(prover_id: &str, api_key: &str) -> String {
+        format!("{prover_id}:{:?}", H256(keccak256(api_key.as_bytes())))
+    }
+
+    fn headers(api_key: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let value = HeaderValue::from_str(&format!("Bearer {api_key}")).unwrap();
+        headers.insert(header::AUTHORIZATION, value);
+        headers
+    }
+
+    #[test]
+    fn authenticating_provers() {
+        let authenticator = ProverAuthenticator::new(&[
+            authorized_prover("prover-1", "key-1"),
+            authorized_prover("prover-2", "key-2"),
+        ])
+        .unwrap();
+        assert!(authenticator.is_enabled());
+
+        assert_eq!(
+            authenticator.authenticate(&headers("key-1")),
+            Ok(Some("prover-1"))
+        );
+        assert_eq!(
+            authenticator.authenticate(&headers("key-2")),
+            Ok(Some("prover-2"))
+        );
+        assert_eq!(
+            authenticator.authenticate(&headers("key-3")),
+            Err(AuthError::UnknownApiKey)
+        );
+        assert_eq!(
+            authenticator.authenticate(&HeaderMap::new()),
+            Err(AuthError::MissingApiKey)
+        );
+    }
+
+    #[test]
+    fn authentication_is_disabled_without_provers() {
+        let authenticator = ProverAuthenticator::new(&[]).unwrap();
+        assert!(!authenticator.is_enabled());
+        assert_eq!(authenticator.authenticate(&HeaderMap::new()), Ok(None));
+    }
+
+    #[test]
+    fn invalid_authorized_provers_are_rejected() {
+        ProverAuthenticator::new(&["prover-1".to_owned()]).unwrap_err();
+        ProverAuthenticator::new(&["prover-1:0x01".to_owned()]).unwrap_err();
+        ProverAuthenticator::new(&[
+            authorized_prover("prover-1", "key"),
+            authorized_prover("prover-2", "key"),
+        ])
+        .unwrap_err();
+    }
+}
@@ -1,7 +1,7 @@
 use std::{net::SocketAddr, sync::Arc};
 
 use anyhow::Context as _;
-use axum::{extract::Path, routing::post, Json, Router};
+use axum::{extract::Path, http::HeaderMap, routing::post, Json, Router};
 use tokio::sync::watch;
 use zksync_config::configs::ProofDataHandlerConfig;
 use zksync_dal::{ConnectionPool, Core};
@@ -9,8 +9,9 @@ use zksync_object_store::ObjectStore;
 use zksync_prover_interface::api::{ProofGenerationDataRequest, SubmitProofRequest};
 use zksync_types::commitment::L1BatchCommitmentMode;
 
-use crate::request_processor::RequestProcessor;
+use crate::{auth::ProverAuthenticator, request_processor::RequestProcessor};
 
+mod auth;
 mod request_processor;
 
 pub async fn run_server(
@@ -22,7 +23,20 @@ pub async fn run_server(
 ) -> anyhow::Result<()> {
     let bind_address = SocketAddr::from(([0, 0, 0, 0], config.http_port));
     tracing::debug!("Starting proof data handler server on {bind_address}");
-    let get_proof_gen_processor = RequestProcessor::new(blob_store, pool, config, commitment_mode);
+    let authenticator = ProverAuthenticator::new(&config.authorized_provers)
+        .context("invalid authorized provers")?;
+    if !authenticator.is_enabled() {
+        anyhow::ensure!(
+            config.allow_unauthenticated_access,
+            "no authorized provers are configured; set `allow_unauthenticated_access` to run \
+             the proof data handler API without authentication"
+        );
+        tracing::warn!(
+            "No authorized provers are configured; proof data handler API is unauthenticated"
+        );
+    }
+    let get_proof_gen_processor =
+        RequestProcessor::new(blob_store, pool, config, commitment_mode, authenticator);
     let submit_proof_processor = get_proof_gen_processor.clone();
     let app = Router::new()
         .route(
@@ -30,9 +44,9 @@ pub async fn run_server(
             post(
                 // we use post method because the returned data is not idempotent,
                 // i.e we return different result on each call.
-                move |payload: Json<ProofGenerationDataRequest>| async move {
+                move |headers: HeaderMap, payload: Json<ProofGenerationDataRequest>| async move {
                     get_proof_gen_processor
-                        .get_proof_generation_data(payload)
+                        .get_proof_generation_data(headers, payload)
                         .await
                 },
             ),
@@ -40,9 +54,11 @@ pub async fn run_server(
         .route(
             "/submit_proof/:l1_batch_number",
             post(
-                move |l1_batch_number: Path<u32>, payload: Json<SubmitProofRequest>| async move {
+                move |l1_batch_number: Path<u32>,
+                      headers: HeaderMap,
+                      payload: Json<SubmitProofRequest>| async move {
                     submit_proof_processor
-                        .submit_proof(l1_batch_number, payload)
+                        .submit_proof(l1_batch_number, headers, payload)
                         .await
                 },
             ),
@@ -2,12 +2,12 @@ use std::sync::Arc;
 
 use axum::{
     extract::Path,
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
 use zksync_config::configs::ProofDataHandlerConfig;
-use zksync_dal::{ConnectionPool, Core, CoreDal, SqlxError};
+use zksync_dal::{ConnectionPool, Core, CoreDal, DalError, SqlxError};
 use zksync_object_store::{ObjectStore, ObjectStoreError};
 use zksync_prover_interface::api::{
     ProofGenerationData, ProofGenerationDataRequest, ProofGenerationDataResponse,
@@ -20,17 +20,27 @@ use zksync_types::{
     L1BatchNumber, H256,
 };
 
+use crate::auth::{AuthError, ProverAuthenticator};
+
 #[derive(Clone)]
 pub(crate) struct RequestProcessor {
     blob_store: Arc<dyn ObjectStore>,
     pool: ConnectionPool<Core>,
     config: ProofDataHandlerConfig,
     commitment_mode: L1BatchCommitmentMode,
+    authenticator: ProverAuthenticator,
 }
 
 pub(crate) enum RequestProcessorError {
     ObjectStore(ObjectStoreError),
     Sqlx(SqlxError),
+    Dal(DalError),
+    Unauthorized(AuthError),
+    /// Prover attempted to submit a proof for a batch picked by another prover.
+    Forbidden {
+        prover_id: String,
+        l1_batch_number: L1BatchNumber,
+    },
 }
 
 impl IntoResponse for RequestProcessorError {
@@ -55,6 +65,33 @@ impl IntoResponse for RequestProcessorError {
                     ),
                 }
             }
+            RequestProcessorError::Dal(err) => {
+                tracing::error!("DAL error: {:?}", err);
+                (
+                    StatusCode::BAD_GATEWAY,
+                    "Failed fetching/saving from db".to_owned(),
+                )
+            }
+            RequestProcessorError::Unauthorized(err) => {
+                tracing::warn!("Rejected unauthenticated request: {err:?}");
+                let message = match err {
+                    AuthError::MissingApiKey => "Missing API key",
+                    AuthError::UnknownApiKey => "Unknown API key",
+                };
+                (StatusCode::UNAUTHORIZED, message.to_owned())
+            }
+            RequestProcessorError::Forbidden {
+                prover_id,
+                l1_batch_number,
+            } => {
+                tracing::warn!(
+                    "Rejected submission from prover `{prover_id}` for L1 batch #{l1_batch_number} picked by another prover"
+                );
+                (
+                    StatusCode::FORBIDDEN,
+                    format!("L1 batch #{l1_batch_number} is not assigned to the prover"),
+                )
+            }
         };
         (status_code, message).into_response()
     }
@@ -66,20 +103,58 @@ impl RequestProcessor {
         pool: ConnectionPool<Core>,
         config: ProofDataHandlerConfig,
         commitment_mode: L1BatchCommitmentMode,
+        authenticator: ProverAuthenticator,
     ) -> Self {
         Self {
             blob_store,
             pool,
             config,
             commitment_mode,
+            authenticator,
+        }
+    }
+
+    /// Checks that the prover submitting a proof for the batch is the one that has picked it.
+    async fn authorize_submission(
+        &self,
+        prover_id: Option<&str>,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<(), RequestProcessorError> {
+        let Some(prover_id) = prover_id else {
+            return Ok(()); // Authentication is disabled
+        };
+        let picked_by = self
+            .pool
+            .connection()
+            .await
+            .map_err(RequestProcessorError::Dal)?
+            .proof_generation_dal()
+            .get_prover_id_for_batch(l1_batch_number)
+            .await
+            .map_err(RequestProcessorError::Sqlx)?;
+        if picked_by.as_deref() != Some(prover_id) {
+            return Err(RequestProcessorError::Forbidden {
+                prover_id: prover_id.to_owned(),
+                l1_batch_number,
+            });
         }
+        Ok(())
     }
 
     pub(crate) async fn get_proof_generation_data(
         &self,
+        headers: HeaderMap,
         request: Json<ProofGenerationDataRequest>,
     ) -> Result<Json<ProofGenerationDataResponse>, RequestProcessorError> {
-        tracing::info!("Received request for proof generation data: {:?}", request);
+        let prover_id = self
+            .authenticator
+            .authenticate(&headers)
+            .map_err(RequestProcessorError::Unauthorized)?;
+        tracing::info!(
+            prover_id,
+            "Received request for proof generation data: {:?}",
+            request
+        );
 
         let l1_batch_number_result = self
             .pool
@@ -87,13 +162,17 @@ impl RequestProcessor {
             .await
             .unwrap()
             .proof_generation_dal()
-            .get_next_block_to_be_proven(self.config.proof_generation_timeout())
+            .get_next_block_to_be_proven(self.config.proof_generation_timeout(), prover_id)
             .await;
 
         let l1_batch_number = match l1_batch_number_result {
             Some(number) => number,
             None => return Ok(Json(ProofGenerationDataResponse::Success(None))), // no batches pending to be proven
         };
+        tracing::info!(
+            prover_id,
+            "L1 batch #{l1_batch_number} was picked for proof generation"
+        );
 
         let blob = self
             .blob_store
@@ -164,10 +243,21 @@ impl RequestProcessor {
     pub(crate) async fn submit_proof(
         &self,
         Path(l1_batch_number): Path<u32>,
+        headers: HeaderMap,
         Json(payload): Json<SubmitProofRequest>,
     ) -> Result<Json<SubmitProofResponse>, RequestProcessorError> {
-        tracing::info!("Received proof for block number: {:?}", l1_batch_number);
+        let prover_id = self
+            .authenticator
+            .authenticate(&headers)
+            .map_err(RequestProcessorError::Unauthorized)?;
+        tracing::info!(
+            prover_id,
+            "Received proof for block number: {:?}",
+            l1_batch_number
+        );
         let l1_batch_number = L1BatchNumber(l1_batch_number);
+        self.authorize_submission(prover_id, l1_batch_number)
+            .await?;
         match payload {
             SubmitProofRequest::Proof(proof) => {
                 let blob_url = self
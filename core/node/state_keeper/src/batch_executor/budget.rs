@@ -0,0 +1,103 @@
+//! Resource budget enforced by [`MainBatchExecutor`](super::main_executor::MainBatchExecutor) for each L1 batch.
+
+use std::time::Duration;
+
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_types::L1BatchNumber;
+
+/// Resource budget for executing a single L1 batch. By default, resources are not limited.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BatchResourceBudget {
+    /// Maximum total time spent executing batch commands in the VM. Time spent waiting for commands
+    /// (e.g., while the state keeper waits for new transactions) is not included.
+    pub max_execution_time: Option<Duration>,
+    /// Maximum memory used by the VM, as reported by [`VmMemoryMetrics`](multivm::interface::VmMemoryMetrics).
+    pub max_vm_memory_bytes: Option<usize>,
+}
+
+impl BatchResourceBudget {
+    pub fn from_config(config: &StateKeeperConfig) -> Self {
+        Self {
+            max_execution_time: config.max_batch_execution_time(),
+            max_vm_memory_bytes: config.max_batch_vm_memory_bytes(),
+        }
+    }
+
+    pub(super) fn is_unlimited(&self) -> bool {
+        self.max_execution_time.is_none() && self.max_vm_memory_bytes.is_none()
+    }
+
+    /// Returns the remaining execution time given the time already spent on the batch, or `None`
+    /// if the execution time is not limited.
+    pub(super) fn remaining_execution_time(&self, execution_time: Duration) -> Option<Duration> {
+        self.max_execution_time
+            .map(|budget| budget.saturating_sub(execution_time))
+    }
+
+    pub(super) fn check_execution_time(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        execution_time: Duration,
+    ) -> Result<(), BatchBudgetExceeded> {
+        match self.max_execution_time {
+            // Transactions are interrupted once the budget is reached, so the budget is considered exceeded
+            // even if the execution time is exactly equal to it.
+            Some(budget) if execution_time >= budget => Err(BatchBudgetExceeded::ExecutionTime {
+                l1_batch_number,
+                execution_time,
+                budget,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    pub(super) fn check_vm_memory(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        used_bytes: usize,
+    ) -> Result<(), BatchBudgetExceeded> {
+        match self.max_vm_memory_bytes {
+            Some(budget_bytes) if used_bytes > budget_bytes => Err(BatchBudgetExceeded::VmMemory {
+                l1_batch_number,
+                used_bytes,
+                budget_bytes,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Reason of excluding a transaction by the batch executor because the transaction made an L1 batch exceed
+/// its [`BatchResourceBudget`].
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum BatchBudgetExceeded {
+    #[error(
+        "L1 batch #{l1_batch_number} exceeded execution time budget: spent {execution_time:?}, budget is {budget:?}"
+    )]
+    ExecutionTime {
+        l1_batch_number: L1BatchNumber,
+        execution_time: Duration,
+        budget: Duration,
+    },
+    #[error(
+        "L1 batch #{l1_batch_number} exceeded VM memory budget: used {used_bytes} bytes, budget is {budget_bytes} bytes"
+    )]
+    VmMemory {
+        l1_batch_number: L1BatchNumber,
+        used_bytes: usize,
+        budget_bytes: usize,
+    },
+}
+
+impl BatchBudgetExceeded {
+    pub fn l1_batch_number(&self) -> L1BatchNumber {
+        match self {
+            Self::ExecutionTime {
+                l1_batch_number, ..
+            }
+            | Self::VmMemory {
+                l1_batch_number, ..
+            } => *l1_batch_number,
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Hooks allowing external code to observe transactions executed by [`MainBatchExecutor`](super::main_executor::MainBatchExecutor).
+
+use std::fmt;
+
+use multivm::interface::{L1BatchEnv, VmExecutionResultAndLogs};
+use zksync_types::{StorageLogQuery, Transaction};
+
+/// Transaction executed by the batch executor, as passed to [`TxExecutionHook::after_tx()`].
+#[derive(Debug, Clone, Copy)]
+pub struct ExecutedTx<'a> {
+    /// Executed transaction.
+    pub tx: &'a Transaction,
+    /// Result of executing the transaction in the VM. Note that the transaction may still be rejected
+    /// by the state keeper based on this result (e.g., if the VM halted).
+    pub result: &'a VmExecutionResultAndLogs,
+}
+
+impl<'a> ExecutedTx<'a> {
+    /// Returns storage writes performed by the transaction.
+    pub fn storage_writes(&self) -> impl Iterator<Item = &'a StorageLogQuery> + 'a {
+        self.result
+            .logs
+            .storage_logs
+            .iter()
+            .filter(|log| log.log_query.rw_flag)
+    }
+}
+
+/// Hook invoked by the batch executor before and after executing each transaction. This allows
+/// to subscribe to executed transactions (e.g., for custom tracing, accounting or collecting fraud proof data)
+/// without changing the state keeper.
+///
+/// Hooks are invoked synchronously on the VM thread, so they should be fast. If an expensive processing is
+/// required, it should be offloaded to a separate task (e.g., by sending data over a channel).
+pub trait TxExecutionHook: 'static + Send + Sync + fmt::Debug {
+    /// Called when a new L1 batch is started. The default implementation does nothing.
+    fn start_batch(&self, _l1_batch_env: &L1BatchEnv) {}
+
+    /// Called before executing a transaction. The default implementation does nothing.
+    fn before_tx(&self, _tx: &Transaction) {}
+
+    /// Called after executing a transaction.
+    fn after_tx(&self, executed_tx: ExecutedTx<'_>);
+
+    /// Called when the last executed transaction is rolled back by the state keeper, e.g. because it doesn't fit
+    /// into the current L1 batch and will be re-executed in the next one. The default implementation does nothing.
+    fn rollback_last_tx(&self) {}
+}
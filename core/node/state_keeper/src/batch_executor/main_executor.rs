@@ -1,4 +1,8 @@
-use std::sync::Arc;
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
 use async_trait::async_trait;
@@ -7,41 +11,143 @@ use multivm::{
         ExecutionResult, FinishedL1Batch, Halt, L1BatchEnv, L2BlockEnv, SystemEnv,
         VmExecutionResultAndLogs, VmInterface, VmInterfaceHistoryEnabled,
     },
-    tracers::CallTracer,
+    tracers::{CallTracer, ExecutionDeadline},
     vm_latest::HistoryEnabled,
     MultiVMTracer, VmInstance,
 };
 use once_cell::sync::OnceCell;
+use serde::Serialize;
 use tokio::{
     runtime::Handle,
     sync::{mpsc, watch},
 };
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_shared_metrics::{InteractionType, TxStage, APP_METRICS};
-use zksync_state::{ReadStorage, ReadStorageFactory, StorageView, WriteStorage};
-use zksync_types::{vm_trace::Call, Transaction};
-use zksync_utils::bytecode::CompressedBytecodeInfo;
-
-use super::{BatchExecutor, BatchExecutorHandle, Command, TxExecutionResult};
+use zksync_state::{PgOrRocksdbStorage, ReadStorageFactory, StorageView, WriteStorage};
+use zksync_types::{vm_trace::Call, Address, L1BatchNumber, Transaction, VmVersion};
+use zksync_utils::bytecode::{CompressedBytecodeInfo, SharedBytecodeDictionary};
+
+use super::{
+    budget::{BatchBudgetExceeded, BatchResourceBudget},
+    hooks::{ExecutedTx, TxExecutionHook},
+    tracers::{BatchTracer, BatchTracerFactory, BatchVmStorage},
+    BatchExecutor, BatchExecutorHandle, Command, TxExecutionResult,
+};
 use crate::{
-    metrics::{TxExecutionStage, BATCH_TIP_METRICS, EXECUTOR_METRICS, KEEPER_METRICS},
+    channel::ObservedReceiver,
+    metrics::{
+        StageChannel, TxExecutionStage, BATCH_TIP_METRICS, EXECUTOR_METRICS, KEEPER_METRICS,
+    },
     types::ExecutionMetricsForCriteria,
 };
 
+/// Health details of [`MainBatchExecutor`] reported if an L1 batch exceeds the resource budget.
+#[derive(Debug, Serialize)]
+struct BatchExecutorHealthDetails {
+    l1_batch_number: L1BatchNumber,
+    error: String,
+}
+
 /// The default implementation of [`BatchExecutor`].
 /// Creates a "real" batch executor which maintains the VM (as opposed to the test builder which doesn't use the VM).
 #[derive(Debug, Clone)]
 pub struct MainBatchExecutor {
     save_call_traces: bool,
     optional_bytecode_compression: bool,
+    traced_addresses_pool: Option<ConnectionPool<Core>>,
+    hooks: Vec<Arc<dyn TxExecutionHook>>,
+    tracer_factory: Option<Arc<dyn BatchTracerFactory>>,
+    budget: BatchResourceBudget,
+    health_updater: Arc<HealthUpdater>,
+    bytecode_dictionary: Option<Arc<Mutex<SharedBytecodeDictionary>>>,
+    vm_version: Option<VmVersion>,
 }
 
 impl MainBatchExecutor {
+    /// Creates a new executor. If `save_call_traces` is set, call traces are collected for all executed transactions
+    /// and are attached to execution results, and thus to the [`UpdatesManager`](crate::UpdatesManager) handled
+    /// by output handlers ([`UpdatesManager::call_traces()`](crate::UpdatesManager::call_traces())). Otherwise, traces
+    /// are only collected for [traced addresses](Self::with_traced_addresses()).
     pub fn new(save_call_traces: bool, optional_bytecode_compression: bool) -> Self {
+        let (_, health_updater) = ReactiveHealthCheck::new("batch_executor");
+        health_updater.update(HealthStatus::Ready.into());
         Self {
             save_call_traces,
             optional_bytecode_compression,
+            traced_addresses_pool: None,
+            hooks: Vec::new(),
+            tracer_factory: None,
+            budget: BatchResourceBudget::default(),
+            health_updater: Arc::new(health_updater),
+            bytecode_dictionary: None,
+            vm_version: None,
         }
     }
+
+    /// Overrides the VM version used to execute all L1 batches. By default, the VM version is determined by the protocol
+    /// version of each batch. The overriding VM must be compatible with the base system contracts of executed batches;
+    /// this is mostly useful for benchmarking and testing VM changes.
+    #[must_use]
+    pub fn with_vm_version(mut self, vm_version: VmVersion) -> Self {
+        self.vm_version = Some(vm_version);
+        self
+    }
+
+    /// Enables measuring pubdata savings from compressing published bytecodes with a dictionary of frequently
+    /// deployed bytecode chunks shared across L1 batches. The dictionary is learned from bytecodes published
+    /// in previous batches and has at most `capacity` chunks. Published bytecodes are not affected.
+    #[must_use]
+    pub fn with_bytecode_compression_dictionary(mut self, capacity: usize) -> Self {
+        let dictionary = SharedBytecodeDictionary::new(capacity);
+        self.bytecode_dictionary = Some(Arc::new(Mutex::new(dictionary)));
+        self
+    }
+
+    /// Sets the resource budget for each executed L1 batch. A transaction that makes the batch exceed the budget
+    /// is interrupted (if it exceeds the execution time budget) and is reported as [`TxExecutionResult::BatchBudgetExceeded`],
+    /// so that the state keeper seals the batch without it. The error is reported via the [health check](Self::health_check())
+    /// until a subsequent batch is executed within the budget.
+    ///
+    /// The budget is not enforced for transactions [re-executed](BatchExecutorHandle::reexecute_tx()) from a pending batch,
+    /// and for the first transaction in a batch after the previous first transaction has exceeded the execution time budget.
+    #[must_use]
+    pub fn with_resource_budget(mut self, budget: BatchResourceBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Returns a health check for this executor. The executor is affected if the last executed L1 batch has exceeded
+    /// the resource budget.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    /// Adds a hook invoked for each transaction executed by this executor. Hooks are invoked in the order
+    /// they were added.
+    #[must_use]
+    pub fn with_hook(mut self, hook: Arc<dyn TxExecutionHook>) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+
+    /// Attaches custom VM tracers created by the provided factory to each executed transaction.
+    #[must_use]
+    pub fn with_tracer_factory(mut self, factory: Arc<dyn BatchTracerFactory>) -> Self {
+        self.tracer_factory = Some(factory);
+        self
+    }
+
+    /// Enables saving call traces for transactions touching traced addresses (i.e., ones initiated by
+    /// or sent to these addresses, or calling them in a nested call), even if call traces are not saved globally.
+    /// Traced addresses are loaded from Postgres when each L1 batch is started, so changes take effect from the next batch.
+    ///
+    /// While any addresses are traced, the call tracer is installed for all transactions (traced addresses
+    /// may be called arbitrarily deep), so execution is as slow as with call traces saved globally.
+    pub fn with_traced_addresses(mut self, pool: ConnectionPool<Core>) -> Self {
+        self.traced_addresses_pool = Some(pool);
+        self
+    }
 }
 
 #[async_trait]
@@ -56,14 +162,37 @@ impl BatchExecutor for MainBatchExecutor {
         // Since we process `BatchExecutor` commands one-by-one (the next command is never enqueued
         // until a previous command is processed), capacity 1 is enough for the commands channel.
         let (commands_sender, commands_receiver) = mpsc::channel(1);
-        let executor = CommandReceiver {
+        let mut executor = CommandReceiver {
             save_call_traces: self.save_call_traces,
+            traced_addresses: HashSet::new(),
             optional_bytecode_compression: self.optional_bytecode_compression,
-            commands: commands_receiver,
+            hooks: self.hooks.clone(),
+            tracer_factory: self.tracer_factory.clone(),
+            l1_batch_number: l1_batch_params.number,
+            budget: self.budget,
+            execution_time: Duration::ZERO,
+            last_tx_execution_time: Duration::ZERO,
+            tx_count: 0,
+            is_first_tx_interrupted: false,
+            is_budget_exceeded: false,
+            health_updater: self.health_updater.clone(),
+            bytecode_dictionary: self.bytecode_dictionary.clone(),
+            vm_version: self.vm_version,
+            commands: ObservedReceiver::new(
+                StageChannel::BatchExecutorCommands,
+                commands_receiver,
+                &commands_sender,
+            ),
         };
 
+        let traced_addresses_pool = self.traced_addresses_pool.clone();
         let stop_receiver = stop_receiver.clone();
         let handle = tokio::task::spawn_blocking(move || {
+            if let Some(pool) = traced_addresses_pool {
+                executor.traced_addresses = Handle::current()
+                    .block_on(load_traced_addresses(&pool))
+                    .context("failed loading traced addresses")?;
+            }
             if let Some(storage) = Handle::current()
                 .block_on(
                     storage_factory.access_storage(&stop_receiver, l1_batch_params.number - 1),
@@ -78,6 +207,20 @@ impl BatchExecutor for MainBatchExecutor {
         });
         Some(BatchExecutorHandle::from_raw(handle, commands_sender))
     }
+
+    fn set_tracer_factory(&mut self, factory: Arc<dyn BatchTracerFactory>) -> anyhow::Result<()> {
+        self.tracer_factory = Some(factory);
+        Ok(())
+    }
+}
+
+async fn load_traced_addresses(pool: &ConnectionPool<Core>) -> anyhow::Result<HashSet<Address>> {
+    let mut conn = pool.connection_tagged("state_keeper").await?;
+    let addresses = conn.traced_addresses_dal().get_traced_addresses().await?;
+    if !addresses.is_empty() {
+        tracing::info!("Saving call traces for transactions touching addresses {addresses:?}");
+    }
+    Ok(addresses.into_iter().collect())
 }
 
 /// Implementation of the "primary" (non-test) batch executor.
@@ -89,45 +232,94 @@ impl BatchExecutor for MainBatchExecutor {
 #[derive(Debug)]
 struct CommandReceiver {
     save_call_traces: bool,
+    traced_addresses: HashSet<Address>,
     optional_bytecode_compression: bool,
-    commands: mpsc::Receiver<Command>,
+    hooks: Vec<Arc<dyn TxExecutionHook>>,
+    tracer_factory: Option<Arc<dyn BatchTracerFactory>>,
+    l1_batch_number: L1BatchNumber,
+    budget: BatchResourceBudget,
+    /// Total time spent executing commands for the current batch.
+    execution_time: Duration,
+    /// Time spent executing the last transaction. Used to not account for rolled back transactions.
+    last_tx_execution_time: Duration,
+    /// Number of executed transactions in the batch, excluding rolled back ones.
+    tx_count: usize,
+    /// Whether the first transaction in the batch has exceeded the execution time budget.
+    is_first_tx_interrupted: bool,
+    is_budget_exceeded: bool,
+    health_updater: Arc<HealthUpdater>,
+    bytecode_dictionary: Option<Arc<Mutex<SharedBytecodeDictionary>>>,
+    vm_version: Option<VmVersion>,
+    commands: ObservedReceiver<Command>,
 }
 
 impl CommandReceiver {
-    pub(super) fn run<S: ReadStorage>(
+    pub(super) fn run(
         mut self,
-        secondary_storage: S,
+        secondary_storage: PgOrRocksdbStorage<'_>,
         l1_batch_params: L1BatchEnv,
         system_env: SystemEnv,
     ) {
         tracing::info!("Starting executing L1 batch #{}", &l1_batch_params.number);
+        for hook in &self.hooks {
+            hook.start_batch(&l1_batch_params);
+        }
 
         let storage_view = StorageView::new(secondary_storage).to_rc_ptr();
 
-        let mut vm = VmInstance::new(l1_batch_params, system_env, storage_view.clone());
+        let vm_version = self.vm_version.unwrap_or_else(|| system_env.version.into());
+        let mut vm = VmInstance::new_with_specific_version(
+            l1_batch_params,
+            system_env,
+            storage_view.clone(),
+            vm_version,
+        );
 
         while let Some(cmd) = self.commands.blocking_recv() {
+            let started_at = Instant::now();
             match cmd {
                 Command::ExecuteTx(tx, resp) => {
-                    let result = self.execute_tx(&tx, &mut vm);
+                    // Execution time depends on the machine load, so if the first transaction in the batch
+                    // has exceeded the execution time budget, the next first transaction is executed without
+                    // enforcing the budget. Otherwise, a transaction taking longer than the entire budget
+                    // could never be included into a batch.
+                    let enforce_budget = self.tx_count > 0 || !self.is_first_tx_interrupted;
+                    let result =
+                        self.execute_tx_with_budget(&tx, &mut vm, started_at, enforce_budget);
+                    if resp.send(result).is_err() {
+                        break;
+                    }
+                }
+                Command::ReexecuteTx(tx, resp) => {
+                    // Re-executed transactions are already included into the batch, so the budget is not enforced.
+                    let result = self.execute_tx_with_budget(&tx, &mut vm, started_at, false);
                     if resp.send(result).is_err() {
                         break;
                     }
                 }
                 Command::RollbackLastTx(resp) => {
                     self.rollback_last_tx(&mut vm);
+                    self.tx_count = self.tx_count.saturating_sub(1);
+                    self.execution_time = self
+                        .execution_time
+                        .saturating_sub(self.last_tx_execution_time);
+                    self.last_tx_execution_time = Duration::ZERO;
                     if resp.send(()).is_err() {
                         break;
                     }
                 }
                 Command::StartNextL2Block(l2_block_env, resp) => {
                     self.start_next_l2_block(l2_block_env, &mut vm);
+                    self.execution_time += started_at.elapsed();
                     if resp.send(()).is_err() {
                         break;
                     }
                 }
                 Command::FinishBatch(resp) => {
                     let vm_block_result = self.finish_batch(&mut vm);
+                    if !self.is_budget_exceeded {
+                        self.health_updater.update(HealthStatus::Ready.into());
+                    }
                     if resp.send(vm_block_result).is_err() {
                         break;
                     }
@@ -147,25 +339,101 @@ impl CommandReceiver {
         tracing::info!("State keeper exited with an unfinished L1 batch");
     }
 
-    fn execute_tx<S: WriteStorage>(
+    /// Executes a transaction and accounts for the time spent. If `enforce_budget` is set, the transaction
+    /// is interrupted once the batch execution time budget is reached, and the batch is checked to be within
+    /// its resource budget after the execution.
+    fn execute_tx_with_budget(
+        &mut self,
+        tx: &Transaction,
+        vm: &mut VmInstance<BatchVmStorage<'_>, HistoryEnabled>,
+        started_at: Instant,
+        enforce_budget: bool,
+    ) -> TxExecutionResult {
+        let deadline = if enforce_budget {
+            self.budget
+                .remaining_execution_time(self.execution_time)
+                .map(|remaining_time| started_at + remaining_time)
+        } else {
+            None
+        };
+        let mut result = self.execute_tx(tx, vm, deadline);
+        self.last_tx_execution_time = started_at.elapsed();
+        self.execution_time += self.last_tx_execution_time;
+        self.tx_count += 1;
+        if enforce_budget {
+            if let Err(err) = self.check_budget(vm) {
+                if self.tx_count == 1 && matches!(err, BatchBudgetExceeded::ExecutionTime { .. }) {
+                    self.is_first_tx_interrupted = true;
+                }
+                result = TxExecutionResult::BatchBudgetExceeded(err);
+            }
+        }
+        result
+    }
+
+    /// Checks whether the batch is within its resource budget after executing a transaction.
+    fn check_budget<S: WriteStorage>(
+        &mut self,
+        vm: &VmInstance<S, HistoryEnabled>,
+    ) -> Result<(), BatchBudgetExceeded> {
+        if self.budget.is_unlimited() {
+            return Ok(());
+        }
+
+        let l1_batch_number = self.l1_batch_number;
+        let mut result = self
+            .budget
+            .check_execution_time(l1_batch_number, self.execution_time);
+        if result.is_ok() && self.budget.max_vm_memory_bytes.is_some() {
+            let used_bytes = vm.record_vm_memory_metrics().full_size();
+            result = self.budget.check_vm_memory(l1_batch_number, used_bytes);
+        }
+
+        if let Err(err) = &result {
+            tracing::warn!("Excluding the last transaction from the batch: {err}");
+            self.is_budget_exceeded = true;
+            let details = BatchExecutorHealthDetails {
+                l1_batch_number,
+                error: err.to_string(),
+            };
+            self.health_updater
+                .update(Health::from(HealthStatus::Affected).with_details(details));
+        }
+        result
+    }
+
+    fn execute_tx(
         &self,
         tx: &Transaction,
-        vm: &mut VmInstance<S, HistoryEnabled>,
+        vm: &mut VmInstance<BatchVmStorage<'_>, HistoryEnabled>,
+        deadline: Option<Instant>,
     ) -> TxExecutionResult {
         // Save pre-`execute_next_tx` VM snapshot.
         vm.make_snapshot();
+        for hook in &self.hooks {
+            hook.before_tx(tx);
+        }
 
         // Execute the transaction.
         let latency = KEEPER_METRICS.tx_execution_time[&TxExecutionStage::Execution].start();
-        let (tx_result, compressed_bytecodes, call_tracer_result) =
+        let (tx_result, compressed_bytecodes, mut call_tracer_result) =
             if self.optional_bytecode_compression {
-                self.execute_tx_in_vm_with_optional_compression(tx, vm)
+                self.execute_tx_in_vm_with_optional_compression(tx, vm, deadline)
             } else {
-                self.execute_tx_in_vm(tx, vm)
+                self.execute_tx_in_vm(tx, vm, deadline)
             };
         latency.observe();
+        if !self.should_save_call_trace(tx, &call_tracer_result) {
+            call_tracer_result = vec![];
+        }
         APP_METRICS.processed_txs[&TxStage::StateKeeper].inc();
         APP_METRICS.processed_l1_txs[&TxStage::StateKeeper].inc_by(tx.is_l1().into());
+        for hook in &self.hooks {
+            hook.after_tx(ExecutedTx {
+                tx,
+                result: &tx_result,
+            });
+        }
 
         if let ExecutionResult::Halt { reason } = tx_result.result {
             return match reason {
@@ -174,6 +442,18 @@ impl CommandReceiver {
             };
         }
 
+        for bytecode in &compressed_bytecodes {
+            EXECUTOR_METRICS
+                .compressed_bytecodes_original_len
+                .inc_by(bytecode.original.len() as u64);
+            EXECUTOR_METRICS
+                .compressed_bytecodes_len
+                .inc_by(bytecode.compressed.len() as u64);
+        }
+        if let Some(dictionary) = &self.bytecode_dictionary {
+            Self::measure_dictionary_compression(dictionary, &compressed_bytecodes);
+        }
+
         let tx_metrics = ExecutionMetricsForCriteria::new(Some(tx), &tx_result);
         let gas_remaining = vm.gas_remaining();
 
@@ -186,10 +466,84 @@ impl CommandReceiver {
         }
     }
 
+    fn measure_dictionary_compression(
+        dictionary: &Mutex<SharedBytecodeDictionary>,
+        compressed_bytecodes: &[CompressedBytecodeInfo],
+    ) {
+        let mut dictionary = dictionary.lock().expect("bytecode dictionary is poisoned");
+        for bytecode in compressed_bytecodes {
+            match dictionary.compress(&bytecode.original) {
+                Ok(compressed) => {
+                    EXECUTOR_METRICS
+                        .dictionary_compressed_bytecodes_len
+                        .inc_by(compressed.len() as u64);
+                }
+                Err(err) => {
+                    tracing::warn!("Failed compressing bytecode with the shared dictionary: {err}");
+                }
+            }
+            dictionary.learn(&bytecode.original);
+        }
+    }
+
+    fn create_tracers<'a>(
+        &self,
+        tx: &Transaction,
+        call_tracer_result: &Arc<OnceCell<Vec<Call>>>,
+        deadline: Option<Instant>,
+    ) -> Vec<BatchTracer<'a>> {
+        let mut tracers = if self.should_collect_call_trace() {
+            vec![CallTracer::new(call_tracer_result.clone()).into_tracer_pointer()]
+        } else {
+            vec![]
+        };
+        if let Some(deadline) = deadline {
+            tracers.push(ExecutionDeadline::new(deadline).into_tracer_pointer());
+        }
+        if let Some(factory) = &self.tracer_factory {
+            tracers.extend(factory.create_tracers(self.l1_batch_number, tx));
+        }
+        tracers
+    }
+
+    /// Checks whether the call tracer should be installed for a transaction. Since calls to traced addresses
+    /// may be nested arbitrarily deep, there's no cheap way to pre-filter transactions (e.g., by the initiator
+    /// or recipient); the trace is collected for all transactions if any addresses are traced, and is then
+    /// filtered by [`Self::should_save_call_trace()`]. This cost is documented for the `admin_addTracedAddresses`
+    /// method, so that operators keep the traced address set empty when it's not needed.
+    fn should_collect_call_trace(&self) -> bool {
+        self.save_call_traces || !self.traced_addresses.is_empty()
+    }
+
+    fn should_save_call_trace(&self, tx: &Transaction, trace: &[Call]) -> bool {
+        if self.save_call_traces {
+            return true;
+        }
+        if self.traced_addresses.contains(&tx.initiator_account())
+            || self.traced_addresses.contains(&tx.recipient_account())
+        {
+            return true;
+        }
+
+        let mut pending_calls: Vec<_> = trace.iter().collect();
+        while let Some(call) = pending_calls.pop() {
+            if self.traced_addresses.contains(&call.from)
+                || self.traced_addresses.contains(&call.to)
+            {
+                return true;
+            }
+            pending_calls.extend(&call.calls);
+        }
+        false
+    }
+
     fn rollback_last_tx<S: WriteStorage>(&self, vm: &mut VmInstance<S, HistoryEnabled>) {
         let latency = KEEPER_METRICS.tx_execution_time[&TxExecutionStage::TxRollback].start();
         vm.rollback_to_the_latest_snapshot();
         latency.observe();
+        for hook in &self.hooks {
+            hook.rollback_last_tx();
+        }
     }
 
     fn start_next_l2_block<S: WriteStorage>(
@@ -215,13 +569,22 @@ impl CommandReceiver {
         }
 
         BATCH_TIP_METRICS.observe(&result.block_tip_execution_result);
+        if let Some(dictionary) = &self.bytecode_dictionary {
+            // Bytecodes learned in this batch will be used for the following batches.
+            let mut dictionary = dictionary.lock().expect("bytecode dictionary is poisoned");
+            dictionary.rebuild();
+            EXECUTOR_METRICS
+                .bytecode_dictionary_len
+                .set(dictionary.len() as u64);
+        }
         result
     }
 
-    fn execute_tx_in_vm_with_optional_compression<S: WriteStorage>(
+    fn execute_tx_in_vm_with_optional_compression(
         &self,
         tx: &Transaction,
-        vm: &mut VmInstance<S, HistoryEnabled>,
+        vm: &mut VmInstance<BatchVmStorage<'_>, HistoryEnabled>,
+        deadline: Option<Instant>,
     ) -> (
         VmExecutionResultAndLogs,
         Vec<CompressedBytecodeInfo>,
@@ -240,11 +603,7 @@ impl CommandReceiver {
         vm.make_snapshot();
 
         let call_tracer_result = Arc::new(OnceCell::default());
-        let tracer = if self.save_call_traces {
-            vec![CallTracer::new(call_tracer_result.clone()).into_tracer_pointer()]
-        } else {
-            vec![]
-        };
+        let tracer = self.create_tracers(tx, &call_tracer_result, deadline);
 
         if let (Ok(()), result) =
             vm.inspect_transaction_with_bytecode_compression(tracer.into(), tx.clone(), true)
@@ -261,11 +620,7 @@ impl CommandReceiver {
         vm.rollback_to_the_latest_snapshot();
 
         let call_tracer_result = Arc::new(OnceCell::default());
-        let tracer = if self.save_call_traces {
-            vec![CallTracer::new(call_tracer_result.clone()).into_tracer_pointer()]
-        } else {
-            vec![]
-        };
+        let tracer = self.create_tracers(tx, &call_tracer_result, deadline);
 
         let result =
             vm.inspect_transaction_with_bytecode_compression(tracer.into(), tx.clone(), false);
@@ -287,21 +642,18 @@ impl CommandReceiver {
     // `Ok(TxExecutionStatus::Success)` when the transaction succeeded
     // `Ok(TxExecutionStatus::Failure)` when the transaction failed.
     // Note that failed transactions are considered properly processed and are included in blocks
-    fn execute_tx_in_vm<S: WriteStorage>(
+    fn execute_tx_in_vm(
         &self,
         tx: &Transaction,
-        vm: &mut VmInstance<S, HistoryEnabled>,
+        vm: &mut VmInstance<BatchVmStorage<'_>, HistoryEnabled>,
+        deadline: Option<Instant>,
     ) -> (
         VmExecutionResultAndLogs,
         Vec<CompressedBytecodeInfo>,
         Vec<Call>,
     ) {
         let call_tracer_result = Arc::new(OnceCell::default());
-        let tracer = if self.save_call_traces {
-            vec![CallTracer::new(call_tracer_result.clone()).into_tracer_pointer()]
-        } else {
-            vec![]
-        };
+        let tracer = self.create_tracers(tx, &call_tracer_result, deadline);
 
         let (published_bytecodes, mut result) =
             vm.inspect_transaction_with_bytecode_compression(tracer.into(), tx.clone(), true);
@@ -13,15 +13,20 @@ use zksync_state::ReadStorageFactory;
 use zksync_types::{vm_trace::Call, Transaction};
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
+use self::{budget::BatchBudgetExceeded, tracers::BatchTracerFactory};
 use crate::{
-    metrics::{ExecutorCommand, EXECUTOR_METRICS},
+    channel::ObservedSender,
+    metrics::{ExecutorCommand, StageChannel, EXECUTOR_METRICS},
     types::ExecutionMetricsForCriteria,
 };
 
 #[cfg(test)]
 mod tests;
 
+pub mod budget;
+pub mod hooks;
 pub mod main_executor;
+pub mod tracers;
 
 /// Representation of a transaction executed in the virtual machine.
 #[derive(Debug, Clone)]
@@ -38,6 +43,9 @@ pub enum TxExecutionResult {
     RejectedByVm { reason: Halt },
     /// Bootloader gas limit is not enough to execute the tx.
     BootloaderOutOfGasForTx,
+    /// Executing the tx made the L1 batch exceed its resource budget, or the tx was interrupted because of the budget.
+    /// The tx should be excluded from the batch, and the batch should be sealed.
+    BatchBudgetExceeded(BatchBudgetExceeded),
 }
 
 impl TxExecutionResult {
@@ -49,6 +57,7 @@ impl TxExecutionResult {
                 reason: rejection_reason,
             } => Some(rejection_reason),
             Self::BootloaderOutOfGasForTx => Some(&Halt::BootloaderOutOfGas),
+            Self::BatchBudgetExceeded(_) => None,
         }
     }
 }
@@ -65,6 +74,15 @@ pub trait BatchExecutor: 'static + Send + Sync + fmt::Debug {
         system_env: SystemEnv,
         stop_receiver: &watch::Receiver<bool>,
     ) -> Option<BatchExecutorHandle>;
+
+    /// Attaches custom VM tracers to transactions executed in subsequently initialized batches.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the executor doesn't support custom tracers, which is the default.
+    fn set_tracer_factory(&mut self, _factory: Arc<dyn BatchTracerFactory>) -> anyhow::Result<()> {
+        anyhow::bail!("{self:?} doesn't support custom VM tracers")
+    }
 }
 
 #[derive(Debug)]
@@ -106,7 +124,7 @@ impl HandleOrError {
 #[derive(Debug)]
 pub struct BatchExecutorHandle {
     handle: HandleOrError,
-    commands: mpsc::Sender<Command>,
+    commands: ObservedSender<Command>,
 }
 
 impl BatchExecutorHandle {
@@ -119,19 +137,36 @@ impl BatchExecutorHandle {
     ) -> Self {
         Self {
             handle: HandleOrError::Handle(handle),
-            commands,
+            commands: ObservedSender::new(StageChannel::BatchExecutorCommands, commands),
         }
     }
 
     pub async fn execute_tx(&mut self, tx: Transaction) -> anyhow::Result<TxExecutionResult> {
+        self.send_tx(tx, false).await
+    }
+
+    /// Re-executes a transaction from a pending L1 batch restored after a restart. Unlike [`Self::execute_tx()`],
+    /// the executor doesn't enforce its resource budget (if any) for the transaction: the transaction is already
+    /// included into the batch, so it must not be interrupted because of the wall-clock time or VM memory.
+    pub async fn reexecute_tx(&mut self, tx: Transaction) -> anyhow::Result<TxExecutionResult> {
+        self.send_tx(tx, true).await
+    }
+
+    async fn send_tx(
+        &mut self,
+        tx: Transaction,
+        is_reexecution: bool,
+    ) -> anyhow::Result<TxExecutionResult> {
         let tx_gas_limit = tx.gas_limit().as_u64();
 
         let (response_sender, response_receiver) = oneshot::channel();
-        let send_failed = self
-            .commands
-            .send(Command::ExecuteTx(Box::new(tx), response_sender))
-            .await
-            .is_err();
+        let tx = Box::new(tx);
+        let command = if is_reexecution {
+            Command::ReexecuteTx(tx, response_sender)
+        } else {
+            Command::ExecuteTx(tx, response_sender)
+        };
+        let send_failed = self.commands.send(command).await.is_err();
         if send_failed {
             return Err(self.handle.wait_for_error().await);
         }
@@ -235,6 +270,7 @@ impl BatchExecutorHandle {
 #[derive(Debug)]
 pub(super) enum Command {
     ExecuteTx(Box<Transaction>, oneshot::Sender<TxExecutionResult>),
+    ReexecuteTx(Box<Transaction>, oneshot::Sender<TxExecutionResult>),
     StartNextL2Block(L2BlockEnv, oneshot::Sender<()>),
     RollbackLastTx(oneshot::Sender<()>),
     FinishBatch(oneshot::Sender<FinishedL1Batch>),
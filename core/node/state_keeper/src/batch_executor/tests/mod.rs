@@ -1,11 +1,27 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
 use assert_matches::assert_matches;
+use multivm::interface::L1BatchEnv;
 use test_casing::{test_casing, Product};
+use zksync_contracts::{load_contract, read_bytecode};
 use zksync_dal::{ConnectionPool, Core};
-use zksync_test_account::Account;
-use zksync_types::{get_nonce_key, utils::storage_key_for_eth_balance, PriorityOpId};
+use zksync_health_check::{CheckHealth, HealthStatus};
+use zksync_system_constants::NONCE_HOLDER_ADDRESS;
+use zksync_test_account::{Account, ExpectedOutcome, Scenario};
+use zksync_types::{
+    ethabi::Token, get_nonce_key, utils::storage_key_for_eth_balance, Address, Execute,
+    L1BatchNumber, Nonce, PriorityOpId, Transaction, VmVersion, H256,
+};
 
 use self::tester::{AccountLoadNextExecutable, StorageSnapshot, TestConfig, Tester};
-use super::TxExecutionResult;
+use super::{
+    budget::{BatchBudgetExceeded, BatchResourceBudget},
+    hooks::{ExecutedTx, TxExecutionHook},
+    TxExecutionResult,
+};
 
 mod read_storage_factory;
 mod tester;
@@ -57,6 +73,82 @@ async fn execute_l2_tx(storage_type: StorageType) {
     executor.finish_batch().await.unwrap();
 }
 
+#[tokio::test]
+async fn execute_l2_tx_with_vm_version_override() {
+    let connection_pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+    let mut alice = Account::random();
+    let mut tester = Tester::new(connection_pool);
+    tester.genesis().await;
+    tester.fund(&[alice.address()]).await;
+    let mut batch_executor = tester
+        .main_batch_executor()
+        .with_vm_version(VmVersion::latest());
+    let mut executor = tester.init_batch(&mut batch_executor).await;
+
+    let res = executor.execute_tx(alice.execute()).await.unwrap();
+    assert_executed(&res);
+    executor.finish_batch().await.unwrap();
+}
+
+/// Checks that call traces are saved only for transactions touching traced addresses.
+#[tokio::test]
+async fn saving_call_traces_for_traced_addresses() {
+    let connection_pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+    let mut alice = Account::random();
+    let mut bob = Account::random();
+    let mut tester = Tester::new(connection_pool);
+    tester.genesis().await;
+    tester.fund(&[alice.address(), bob.address()]).await;
+    tester.trace_addresses(&[alice.address()]).await;
+    let mut executor = tester
+        .create_batch_executor(StorageType::AsyncRocksdbCache)
+        .await;
+
+    let res = executor.execute_tx(alice.execute()).await.unwrap();
+    let TxExecutionResult::Success {
+        call_tracer_result, ..
+    } = res
+    else {
+        panic!("Unexpected execution result: {res:?}");
+    };
+    assert!(!call_tracer_result.is_empty());
+
+    let res = executor.execute_tx(bob.execute()).await.unwrap();
+    let TxExecutionResult::Success {
+        call_tracer_result, ..
+    } = res
+    else {
+        panic!("Unexpected execution result: {res:?}");
+    };
+    assert!(call_tracer_result.is_empty());
+    executor.finish_batch().await.unwrap();
+}
+
+/// Checks that call traces are saved for transactions touching traced addresses in nested calls.
+#[tokio::test]
+async fn saving_call_traces_for_nested_calls_to_traced_addresses() {
+    let connection_pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+    let mut alice = Account::random();
+    let mut tester = Tester::new(connection_pool);
+    tester.genesis().await;
+    tester.fund(&[alice.address()]).await;
+    // The nonce holder is called by the account during validation, i.e., in a nested call.
+    tester.trace_addresses(&[NONCE_HOLDER_ADDRESS]).await;
+    let mut executor = tester
+        .create_batch_executor(StorageType::AsyncRocksdbCache)
+        .await;
+
+    let res = executor.execute_tx(alice.execute()).await.unwrap();
+    let TxExecutionResult::Success {
+        call_tracer_result, ..
+    } = res
+    else {
+        panic!("Unexpected execution result: {res:?}");
+    };
+    assert!(!call_tracer_result.is_empty());
+    executor.finish_batch().await.unwrap();
+}
+
 #[derive(Debug, Clone, Copy)]
 enum SnapshotRecoveryMutation {
     RemoveNonce,
@@ -212,6 +304,79 @@ async fn rollback() {
     executor.finish_batch().await.unwrap();
 }
 
+#[derive(Debug, Clone, PartialEq)]
+enum HookEvent {
+    StartBatch(L1BatchNumber),
+    BeforeTx(H256),
+    AfterTx { tx_hash: H256, has_writes: bool },
+    RollbackLastTx,
+}
+
+#[derive(Debug, Default)]
+struct RecordingHook(Mutex<Vec<HookEvent>>);
+
+impl TxExecutionHook for RecordingHook {
+    fn start_batch(&self, l1_batch_env: &L1BatchEnv) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(HookEvent::StartBatch(l1_batch_env.number));
+    }
+
+    fn before_tx(&self, tx: &Transaction) {
+        self.0.lock().unwrap().push(HookEvent::BeforeTx(tx.hash()));
+    }
+
+    fn after_tx(&self, executed_tx: ExecutedTx<'_>) {
+        self.0.lock().unwrap().push(HookEvent::AfterTx {
+            tx_hash: executed_tx.tx.hash(),
+            has_writes: executed_tx.storage_writes().next().is_some(),
+        });
+    }
+
+    fn rollback_last_tx(&self) {
+        self.0.lock().unwrap().push(HookEvent::RollbackLastTx);
+    }
+}
+
+/// Checks that transaction execution hooks are invoked by the batch executor.
+#[tokio::test]
+async fn tx_execution_hooks() {
+    let connection_pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+    let mut alice = Account::random();
+    let hook = Arc::new(RecordingHook::default());
+    let mut config = TestConfig::new();
+    config.hooks.push(hook.clone());
+    let mut tester = Tester::with_config(connection_pool, config);
+
+    tester.genesis().await;
+    tester.fund(&[alice.address()]).await;
+    let mut executor = tester
+        .create_batch_executor(StorageType::AsyncRocksdbCache)
+        .await;
+
+    let tx = alice.execute();
+    let tx_hash = tx.hash();
+    assert_executed(&executor.execute_tx(tx.clone()).await.unwrap());
+    executor.rollback_last_tx().await.unwrap();
+    assert_executed(&executor.execute_tx(tx).await.unwrap());
+    executor.finish_batch().await.unwrap();
+
+    let events = hook.0.lock().unwrap().clone();
+    let executed_tx_events = [
+        HookEvent::BeforeTx(tx_hash),
+        HookEvent::AfterTx {
+            tx_hash,
+            has_writes: true,
+        },
+    ];
+    let mut expected_events = vec![HookEvent::StartBatch(L1BatchNumber(1))];
+    expected_events.extend(executed_tx_events.clone());
+    expected_events.push(HookEvent::RollbackLastTx);
+    expected_events.extend(executed_tx_events);
+    assert_eq!(events, expected_events);
+}
+
 /// Checks that incorrect transactions are marked as rejected.
 #[tokio::test]
 async fn reject_tx() {
@@ -230,6 +395,119 @@ async fn reject_tx() {
     assert_rejected(&res);
 }
 
+/// Checks that a transaction making the batch exceed its memory budget is excluded from the batch.
+#[tokio::test]
+async fn excluding_tx_exceeding_memory_budget() {
+    let connection_pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+    let mut alice = Account::random();
+
+    let mut tester = Tester::new(connection_pool);
+    tester.genesis().await;
+    tester.fund(&[alice.address()]).await;
+    let mut config = TestConfig::new();
+    config.budget = BatchResourceBudget {
+        max_execution_time: None,
+        max_vm_memory_bytes: Some(1),
+    };
+    tester.set_config(config);
+    let mut executor = tester
+        .create_batch_executor(StorageType::AsyncRocksdbCache)
+        .await;
+
+    let res = executor.execute_tx(alice.execute()).await.unwrap();
+    assert_matches!(
+        res,
+        TxExecutionResult::BatchBudgetExceeded(BatchBudgetExceeded::VmMemory {
+            l1_batch_number: L1BatchNumber(1),
+            ..
+        })
+    );
+    // The executor must remain operational, so that the batch can be sealed without the transaction.
+    executor.rollback_last_tx().await.unwrap();
+    executor.finish_batch().await.unwrap();
+}
+
+/// Checks that a long transaction is interrupted once the batch exceeds its execution time budget,
+/// and that the executor health reflects the budget overrun.
+#[tokio::test]
+async fn interrupting_tx_exceeding_execution_time_budget() {
+    let connection_pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+    let mut alice = Account::random();
+
+    let mut tester = Tester::new(connection_pool);
+    tester.genesis().await;
+    tester.fund(&[alice.address()]).await;
+    let mut config = TestConfig::new();
+    config.budget = BatchResourceBudget {
+        max_execution_time: Some(Duration::ZERO),
+        max_vm_memory_bytes: None,
+    };
+    tester.set_config(config);
+    let mut batch_executor = tester.main_batch_executor();
+    let health_check = batch_executor.health_check();
+    assert_matches!(
+        health_check.check_health().await.status(),
+        HealthStatus::Ready
+    );
+
+    let mut executor = tester.init_batch(&mut batch_executor).await;
+    let tx = alice.execute();
+    let res = executor.execute_tx(tx.clone()).await.unwrap();
+    assert_matches!(
+        res,
+        TxExecutionResult::BatchBudgetExceeded(BatchBudgetExceeded::ExecutionTime { .. })
+    );
+    executor.rollback_last_tx().await.unwrap();
+    // The interrupted first transaction is retried without the budget...
+    let res = executor.execute_tx(tx).await.unwrap();
+    assert_executed(&res);
+    // ...but the budget is still enforced for the following transactions.
+    let res = executor.execute_tx(alice.execute()).await.unwrap();
+    assert_matches!(
+        res,
+        TxExecutionResult::BatchBudgetExceeded(BatchBudgetExceeded::ExecutionTime { .. })
+    );
+    executor.rollback_last_tx().await.unwrap();
+    executor.finish_batch().await.unwrap();
+    assert_matches!(
+        health_check.check_health().await.status(),
+        HealthStatus::Affected
+    );
+
+    // Health should be reset once a batch is executed within the budget.
+    let executor = tester.init_batch(&mut batch_executor).await;
+    executor.finish_batch().await.unwrap();
+    assert_matches!(
+        health_check.check_health().await.status(),
+        HealthStatus::Ready
+    );
+}
+
+/// Checks that the resource budget is not enforced for transactions re-executed from a pending batch.
+#[tokio::test]
+async fn reexecuting_tx_ignores_budget() {
+    let connection_pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+    let mut alice = Account::random();
+
+    let mut tester = Tester::new(connection_pool);
+    tester.genesis().await;
+    tester.fund(&[alice.address()]).await;
+    let mut config = TestConfig::new();
+    config.budget = BatchResourceBudget {
+        max_execution_time: Some(Duration::ZERO),
+        max_vm_memory_bytes: Some(0),
+    };
+    tester.set_config(config);
+    let mut batch_executor = tester.main_batch_executor();
+    let mut executor = tester.init_batch(&mut batch_executor).await;
+
+    for _ in 0..2 {
+        let res = executor.reexecute_tx(alice.execute()).await.unwrap();
+        assert_executed(&res);
+    }
+    executor.finish_batch().await.unwrap();
+}
+
 /// Checks that tx with too big gas limit is correctly processed.
 /// When processed in the bootloader, no more than 80M gas can be used within the execution context.
 #[tokio::test]
@@ -394,6 +672,61 @@ async fn execute_realistic_scenario() {
     executor.finish_batch().await.unwrap();
 }
 
+/// Checks that a declarative scenario can be executed by the batch executor.
+#[tokio::test]
+async fn executing_scenario() {
+    const COUNTER_PATH: &str =
+        "etc/contracts-test-data/artifacts-zk/contracts/counter/counter.sol/Counter.json";
+
+    let connection_pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+    let mut tester = Tester::new(connection_pool);
+    tester.genesis().await;
+    let scenario = Scenario::with_deterministic_accounts(2);
+    let addresses: Vec<_> = scenario.accounts().iter().map(Account::address).collect();
+    tester.fund(&addresses).await;
+    let mut executor = tester
+        .create_batch_executor(StorageType::AsyncRocksdbCache)
+        .await;
+
+    let empty_execute = Execute {
+        contract_address: Address::repeat_byte(0x23),
+        calldata: vec![],
+        value: 0.into(),
+        factory_deps: None,
+    };
+    let mut scenario = scenario
+        .transfer(0, addresses[1], 1_000.into())
+        .transfer(1, addresses[0], 500.into())
+        .l1_execute(1, empty_execute)
+        .deploy(0, read_bytecode(COUNTER_PATH), None);
+    let report = scenario.run(&mut executor).await.unwrap();
+    assert_eq!(report.transactions.len(), 4);
+    assert_eq!(report.deployed_contracts.len(), 1);
+    assert_eq!(scenario.accounts()[0].nonce, Nonce(2));
+
+    let counter_address = report.deployed_contracts[0];
+    let increment_with_revert = load_contract(COUNTER_PATH)
+        .function("incrementWithRevert")
+        .unwrap()
+        .encode_input(&[Token::Uint(1.into()), Token::Bool(true)])
+        .unwrap();
+    let mut scenario = scenario
+        .execute(
+            0,
+            Execute {
+                contract_address: counter_address,
+                calldata: increment_with_revert,
+                value: 0.into(),
+                factory_deps: None,
+            },
+        )
+        .expecting(ExpectedOutcome::Revert);
+    let report = scenario.run(&mut executor).await.unwrap();
+    assert_eq!(report.transactions.len(), 1);
+
+    executor.finish_batch().await.unwrap();
+}
+
 /// Checks that we handle the bootloader out of gas error on execution phase.
 #[tokio::test]
 async fn bootloader_out_of_gas_for_any_tx() {
@@ -406,6 +739,8 @@ async fn bootloader_out_of_gas_for_any_tx() {
             save_call_traces: false,
             vm_gas_limit: Some(10),
             validation_computational_gas_limit: u32::MAX,
+            hooks: vec![],
+            budget: BatchResourceBudget::default(),
         },
     );
 
@@ -451,6 +786,8 @@ async fn bootloader_tip_out_of_gas() {
                 - 10,
         ),
         validation_computational_gas_limit: u32::MAX,
+        hooks: vec![],
+        budget: BatchResourceBudget::default(),
     });
 
     let mut second_executor = tester
@@ -3,8 +3,9 @@
 
 use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
+use async_trait::async_trait;
 use multivm::{
-    interface::{L1BatchEnv, L2BlockEnv, SystemEnv},
+    interface::{ExecutionResult, Halt, L1BatchEnv, L2BlockEnv, SystemEnv},
     vm_latest::constants::INITIAL_STORAGE_WRITE_PUBDATA_BYTES,
 };
 use tempfile::TempDir;
@@ -15,7 +16,7 @@ use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_node_genesis::create_genesis_l1_batch;
 use zksync_node_test_utils::prepare_recovery_snapshot;
 use zksync_state::{ReadStorageFactory, RocksdbStorageOptions};
-use zksync_test_account::{Account, DeployContractsTx, TxType};
+use zksync_test_account::{Account, DeployContractsTx, ScenarioExecutor, TxOutcome, TxType};
 use zksync_types::{
     block::L2BlockHasher, ethabi::Token, fee::Fee, protocol_version::ProtocolSemanticVersion,
     snapshots::SnapshotRecoveryStatus, storage_writes_deduplicator::StorageWritesDeduplicator,
@@ -31,7 +32,9 @@ use super::{
     StorageType,
 };
 use crate::{
-    batch_executor::{BatchExecutorHandle, TxExecutionResult},
+    batch_executor::{
+        budget::BatchResourceBudget, hooks::TxExecutionHook, BatchExecutorHandle, TxExecutionResult,
+    },
     testonly::BASE_SYSTEM_CONTRACTS,
     tests::{default_l1_batch_env, default_system_env},
     AsyncRocksdbCache, BatchExecutor, MainBatchExecutor,
@@ -46,6 +49,8 @@ pub(super) struct TestConfig {
     pub(super) save_call_traces: bool,
     pub(super) vm_gas_limit: Option<u32>,
     pub(super) validation_computational_gas_limit: u32,
+    pub(super) hooks: Vec<Arc<dyn TxExecutionHook>>,
+    pub(super) budget: BatchResourceBudget,
 }
 
 impl TestConfig {
@@ -56,6 +61,8 @@ impl TestConfig {
             vm_gas_limit: None,
             save_call_traces: false,
             validation_computational_gas_limit: config.validation_computational_gas_limit,
+            hooks: vec![],
+            budget: BatchResourceBudget::default(),
         }
     }
 }
@@ -145,7 +152,7 @@ impl Tester {
         l1_batch_env: L1BatchEnv,
         system_env: SystemEnv,
     ) -> BatchExecutorHandle {
-        let mut batch_executor = MainBatchExecutor::new(self.config.save_call_traces, false);
+        let mut batch_executor = self.main_batch_executor();
         let (_stop_sender, stop_receiver) = watch::channel(false);
         batch_executor
             .init_batch(storage_factory, l1_batch_env, system_env, &stop_receiver)
@@ -153,6 +160,35 @@ impl Tester {
             .expect("Batch executor was interrupted")
     }
 
+    /// Creates a main batch executor configured according to the test config.
+    pub(super) fn main_batch_executor(&self) -> MainBatchExecutor {
+        let mut batch_executor = MainBatchExecutor::new(self.config.save_call_traces, false)
+            .with_traced_addresses(self.pool.clone())
+            .with_resource_budget(self.config.budget);
+        for hook in &self.config.hooks {
+            batch_executor = batch_executor.with_hook(hook.clone());
+        }
+        batch_executor
+    }
+
+    /// Starts the first L1 batch with the provided executor and Postgres storage.
+    pub(super) async fn init_batch(
+        &self,
+        batch_executor: &mut MainBatchExecutor,
+    ) -> BatchExecutorHandle {
+        let (l1_batch_env, system_env) = self.default_batch_params();
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        batch_executor
+            .init_batch(
+                Arc::new(PostgresFactory::new(self.pool())),
+                l1_batch_env,
+                system_env,
+                &stop_receiver,
+            )
+            .await
+            .expect("Batch executor was interrupted")
+    }
+
     pub(super) async fn recover_batch_executor(
         &mut self,
         snapshot: &SnapshotRecoveryStatus,
@@ -257,6 +293,16 @@ impl Tester {
         }
     }
 
+    /// Enables saving call traces for transactions touching the specified addresses.
+    pub(super) async fn trace_addresses(&self, addresses: &[Address]) {
+        let mut storage = self.pool.connection().await.unwrap();
+        storage
+            .traced_addresses_dal()
+            .add_traced_addresses(addresses)
+            .await
+            .unwrap();
+    }
+
     /// Adds funds for specified account list.
     /// Expects genesis to be performed (i.e. `setup_storage` called beforehand).
     pub(super) async fn fund(&self, addresses: &[Address]) {
@@ -435,6 +481,39 @@ impl AccountLoadNextExecutable for Account {
     }
 }
 
+#[async_trait]
+impl ScenarioExecutor for BatchExecutorHandle {
+    async fn execute_transaction(&mut self, tx: Transaction) -> anyhow::Result<TxOutcome> {
+        let tx_result = match self.execute_tx(tx).await? {
+            TxExecutionResult::Success { tx_result, .. } => tx_result,
+            TxExecutionResult::RejectedByVm { reason } => {
+                return Ok(TxOutcome::Rejected {
+                    reason: reason.to_string(),
+                });
+            }
+            TxExecutionResult::BootloaderOutOfGasForTx => {
+                return Ok(TxOutcome::Rejected {
+                    reason: Halt::BootloaderOutOfGas.to_string(),
+                });
+            }
+            TxExecutionResult::BatchBudgetExceeded(budget) => {
+                return Ok(TxOutcome::Rejected {
+                    reason: budget.to_string(),
+                });
+            }
+        };
+        Ok(match &tx_result.result {
+            ExecutionResult::Success { .. } => TxOutcome::Success,
+            ExecutionResult::Revert { output } => TxOutcome::Reverted {
+                reason: output.to_string(),
+            },
+            ExecutionResult::Halt { reason } => TxOutcome::Rejected {
+                reason: reason.to_string(),
+            },
+        })
+    }
+}
+
 fn fee(gas_limit: u32) -> Fee {
     Fee {
         gas_limit: U256::from(gas_limit),
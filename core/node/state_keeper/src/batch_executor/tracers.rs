@@ -0,0 +1,33 @@
+//! Custom VM tracers attached by [`MainBatchExecutor`](super::main_executor::MainBatchExecutor) to executed transactions.
+
+use std::{any::Any, fmt};
+
+use multivm::{vm_latest::HistoryEnabled, MultiVmTracerPointer};
+use zksync_state::{PgOrRocksdbStorage, StorageView};
+use zksync_types::{L1BatchNumber, Transaction};
+
+/// Storage used by the VM in [`MainBatchExecutor`](super::main_executor::MainBatchExecutor).
+pub type BatchVmStorage<'a> = StorageView<PgOrRocksdbStorage<'a>>;
+
+/// Custom tracer that can be attached to transactions executed by [`MainBatchExecutor`](super::main_executor::MainBatchExecutor).
+pub type BatchTracer<'a> = MultiVmTracerPointer<BatchVmStorage<'a>, HistoryEnabled>;
+
+/// Type-erased output of custom tracers for an L1 batch, e.g. a storage access profile or an opcode histogram.
+pub type BatchTracerOutput = Box<dyn Any + Send + Sync>;
+
+/// Factory of custom tracers attached to each transaction executed by [`MainBatchExecutor`](super::main_executor::MainBatchExecutor).
+///
+/// Tracers are created on the VM thread, so creation should be fast. Like [`CallTracer`](multivm::tracers::CallTracer),
+/// tracers should report their results via shared state (e.g., an `Arc<OnceCell<_>>`) owned by the factory.
+pub trait BatchTracerFactory: 'static + Send + Sync + fmt::Debug {
+    /// Creates tracers for executing the specified transaction in the specified L1 batch.
+    ///
+    /// This method is called for each VM execution of the transaction. A transaction may be executed more than once
+    /// (e.g., re-executed without bytecode compression if compression fails), and an executed transaction
+    /// may be rolled back by the caller; tracers should account for this if necessary.
+    fn create_tracers<'a>(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        tx: &Transaction,
+    ) -> Vec<BatchTracer<'a>>;
+}
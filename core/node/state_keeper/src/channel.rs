@@ -0,0 +1,153 @@
+//! Bounded channels between state keeper stages with standardized metrics.
+//!
+//! All channels between stages (IO, batch execution and persistence) must be bounded, so that a lagging stage
+//! exerts backpressure on the previous ones instead of causing unbounded memory growth. The wrappers
+//! in this module report the channel depth, saturation and the time sends are blocked on a full channel.
+
+use tokio::sync::mpsc::{
+    self,
+    error::{SendError, TrySendError},
+};
+
+use crate::metrics::{StageChannel, CHANNEL_METRICS};
+
+/// Creates a bounded channel with the specified capacity.
+pub(crate) fn bounded<T>(
+    channel: StageChannel,
+    capacity: usize,
+) -> (ObservedSender<T>, ObservedReceiver<T>) {
+    let (sender, receiver) = mpsc::channel(capacity);
+    let receiver = ObservedReceiver::new(channel, receiver, &sender);
+    (ObservedSender::new(channel, sender), receiver)
+}
+
+/// Sending half of a bounded channel reporting [`CHANNEL_METRICS`].
+#[derive(Debug)]
+pub(crate) struct ObservedSender<T> {
+    channel: StageChannel,
+    inner: mpsc::Sender<T>,
+}
+
+impl<T> ObservedSender<T> {
+    pub fn new(channel: StageChannel, inner: mpsc::Sender<T>) -> Self {
+        CHANNEL_METRICS.capacity[&channel].set(inner.max_capacity());
+        Self { channel, inner }
+    }
+
+    /// Returns the number of messages that can be sent without blocking.
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    pub fn max_capacity(&self) -> usize {
+        self.inner.max_capacity()
+    }
+
+    /// Sends a message, waiting for free capacity if the channel is full.
+    pub async fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let permit = match self.inner.try_reserve() {
+            Ok(permit) => permit,
+            Err(TrySendError::Closed(())) => return Err(SendError(value)),
+            Err(TrySendError::Full(())) => {
+                CHANNEL_METRICS.blocked_sends[&self.channel].inc();
+                let latency = CHANNEL_METRICS.send_blocked_time[&self.channel].start();
+                let Ok(permit) = self.inner.reserve().await else {
+                    return Err(SendError(value));
+                };
+                latency.observe();
+                permit
+            }
+        };
+        permit.send(value);
+        self.report_depth();
+        Ok(())
+    }
+
+    fn report_depth(&self) {
+        let max_capacity = self.inner.max_capacity();
+        let depth = max_capacity - self.inner.capacity();
+        CHANNEL_METRICS.report_depth(self.channel, depth, max_capacity);
+    }
+}
+
+/// Receiving half of a bounded channel reporting [`CHANNEL_METRICS`].
+#[derive(Debug)]
+pub(crate) struct ObservedReceiver<T> {
+    channel: StageChannel,
+    inner: mpsc::Receiver<T>,
+    // Weak sender handle to get the channel depth.
+    sender: mpsc::WeakSender<T>,
+}
+
+impl<T> ObservedReceiver<T> {
+    pub fn new(channel: StageChannel, inner: mpsc::Receiver<T>, sender: &mpsc::Sender<T>) -> Self {
+        Self {
+            channel,
+            inner,
+            sender: sender.downgrade(),
+        }
+    }
+
+    /// Returns the maximum channel capacity, or `None` if all senders are dropped.
+    pub fn max_capacity(&self) -> Option<usize> {
+        Some(self.sender.upgrade()?.max_capacity())
+    }
+
+    /// Returns the number of messages that can be sent without blocking, or `None` if all senders are dropped.
+    pub fn capacity(&self) -> Option<usize> {
+        Some(self.sender.upgrade()?.capacity())
+    }
+
+    pub async fn recv(&mut self) -> Option<T> {
+        let value = self.inner.recv().await;
+        self.report_depth();
+        value
+    }
+
+    /// Blocking version of [`Self::recv()`]. Must not be called from an async context.
+    pub fn blocking_recv(&mut self) -> Option<T> {
+        let value = self.inner.blocking_recv();
+        self.report_depth();
+        value
+    }
+
+    fn report_depth(&self) {
+        if let Some(sender) = self.sender.upgrade() {
+            let max_capacity = sender.max_capacity();
+            let depth = max_capacity - sender.capacity();
+            CHANNEL_METRICS.report_depth(self.channel, depth, max_capacity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn observed_channel_basics() {
+        let (sender, mut receiver) = bounded::<u32>(StageChannel::L2BlockSealCommands, 1);
+        assert_eq!(receiver.max_capacity(), Some(1));
+
+        sender.send(1).await.unwrap();
+        assert_eq!(sender.capacity(), 0);
+        assert_eq!(receiver.capacity(), Some(0));
+
+        // The channel is full, so the send must be blocked until a message is received.
+        let send_future = sender.send(2);
+        tokio::pin!(send_future);
+        let timeout_result =
+            tokio::time::timeout(Duration::from_millis(50), &mut send_future).await;
+        assert!(timeout_result.is_err());
+
+        assert_eq!(receiver.recv().await, Some(1));
+        send_future.await.unwrap();
+        assert_eq!(receiver.recv().await, Some(2));
+
+        drop(receiver);
+        let err = sender.send(3).await.unwrap_err();
+        assert_eq!(err.0, 3);
+    }
+}
@@ -0,0 +1,94 @@
+//! Custom metadata attached to L1 batches by operators.
+
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+
+use super::StateKeeperOutputHandler;
+use crate::updates::UpdatesManager;
+
+/// Source of custom key / value metadata for sealed L1 batches (e.g., compliance tags or build info).
+/// Metadata is persisted by [`L1BatchMetadataPersistence`] and is returned by the `zks_getL1BatchDetails`
+/// RPC method.
+pub trait L1BatchMetadataSource: 'static + Send + Sync + fmt::Debug {
+    /// Returns metadata for the L1 batch. Errors are treated as fatal for the state keeper.
+    fn l1_batch_metadata(
+        &self,
+        updates_manager: &UpdatesManager,
+    ) -> anyhow::Result<BTreeMap<String, String>>;
+}
+
+/// [`L1BatchMetadataSource`] attaching the same metadata to all batches.
+#[derive(Debug, Clone, Default)]
+pub struct StaticL1BatchMetadata(pub BTreeMap<String, String>);
+
+impl L1BatchMetadataSource for StaticL1BatchMetadata {
+    fn l1_batch_metadata(
+        &self,
+        _updates_manager: &UpdatesManager,
+    ) -> anyhow::Result<BTreeMap<String, String>> {
+        Ok(self.0.clone())
+    }
+}
+
+/// Output handler persisting custom metadata for sealed L1 batches. Must be placed after
+/// [`StateKeeperPersistence`](super::StateKeeperPersistence) in the output handler, since metadata refers
+/// to the persisted batch.
+#[derive(Debug)]
+pub struct L1BatchMetadataPersistence {
+    pool: ConnectionPool<Core>,
+    sources: Vec<Arc<dyn L1BatchMetadataSource>>,
+}
+
+impl L1BatchMetadataPersistence {
+    pub fn new(pool: ConnectionPool<Core>) -> Self {
+        Self {
+            pool,
+            sources: Vec::new(),
+        }
+    }
+
+    /// Adds a metadata source. If several sources return the same key, the value from the source added last wins.
+    pub fn with_source(mut self, source: Arc<dyn L1BatchMetadataSource>) -> Self {
+        self.sources.push(source);
+        self
+    }
+}
+
+#[async_trait]
+impl StateKeeperOutputHandler for L1BatchMetadataPersistence {
+    async fn handle_l2_block(&mut self, _updates_manager: &UpdatesManager) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_l1_batch(
+        &mut self,
+        updates_manager: Arc<UpdatesManager>,
+    ) -> anyhow::Result<()> {
+        let l1_batch_number = updates_manager.l1_batch.number;
+        let mut metadata = BTreeMap::new();
+        for source in &self.sources {
+            let source_metadata =
+                source
+                    .l1_batch_metadata(&updates_manager)
+                    .with_context(|| {
+                        format!(
+                        "failed getting metadata for L1 batch #{l1_batch_number} from {source:?}"
+                    )
+                    })?;
+            metadata.extend(source_metadata);
+        }
+        if metadata.is_empty() {
+            return Ok(());
+        }
+
+        let mut connection = self.pool.connection_tagged("state_keeper").await?;
+        connection
+            .l1_batches_custom_metadata_dal()
+            .insert_metadata(l1_batch_number, &metadata)
+            .await?;
+        Ok(())
+    }
+}
@@ -9,7 +9,7 @@ use futures::FutureExt;
 use vm_utils::storage::L1BatchParamsProvider;
 use zksync_config::GenesisConfig;
 use zksync_contracts::BaseSystemContractsHashes;
-use zksync_dal::{ConnectionPool, Core};
+use zksync_dal::{transactions_dal::CallTraceFormat, ConnectionPool, Core};
 use zksync_node_genesis::{insert_genesis_batch, mock_genesis_config, GenesisParams};
 use zksync_node_test_utils::{
     create_l1_batch, create_l2_block, create_l2_transaction, execute_l2_transaction,
@@ -375,6 +375,7 @@ async fn store_pending_l2_blocks(
                 &[tx_result],
                 1.into(),
                 ProtocolVersionId::latest(),
+                CallTraceFormat::Legacy,
                 false,
             )
             .await
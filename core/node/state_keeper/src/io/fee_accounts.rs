@@ -0,0 +1,95 @@
+//! Rotation of fee accounts across L1 batches.
+
+use std::num::NonZeroU64;
+
+use anyhow::Context as _;
+use zksync_types::{Address, L1BatchNumber};
+
+/// Chooses the fee account for each new L1 batch among the configured accounts.
+///
+/// The choice is stateless (depends only on the batch number or timestamp), so it's stable across restarts;
+/// the chosen account is persisted as a part of the batch env (i.e., as the fee account address of the batch L2 blocks).
+#[derive(Debug, Clone)]
+pub(crate) struct FeeAccountRotation {
+    accounts: Vec<Address>,
+    /// If set, each account is active for this period based on the batch timestamp. Otherwise, accounts
+    /// are rotated round-robin for each batch.
+    period_sec: Option<NonZeroU64>,
+}
+
+impl FeeAccountRotation {
+    pub fn new(accounts: Vec<Address>, period_sec: Option<u64>) -> anyhow::Result<Self> {
+        anyhow::ensure!(!accounts.is_empty(), "no fee accounts provided");
+        let period_sec = period_sec
+            .map(|period| NonZeroU64::new(period).context("fee account rotation period is zero"))
+            .transpose()?;
+        Ok(Self {
+            accounts,
+            period_sec,
+        })
+    }
+
+    /// Adds accounts to the rotation.
+    pub fn extend(&mut self, accounts: impl IntoIterator<Item = Address>) {
+        self.accounts.extend(accounts);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.accounts.len() > 1
+    }
+
+    /// Returns the fee account for a new L1 batch with the specified number and timestamp.
+    pub fn select(&self, l1_batch_number: L1BatchNumber, timestamp: u64) -> Address {
+        let slot = match self.period_sec {
+            Some(period_sec) => timestamp / period_sec.get(),
+            None => l1_batch_number.0.into(),
+        };
+        self.accounts[(slot % self.accounts.len() as u64) as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_robin_rotation() {
+        let accounts: Vec<_> = (1..=3).map(Address::repeat_byte).collect();
+        let mut rotation = FeeAccountRotation::new(vec![accounts[0]], None).unwrap();
+        rotation.extend(accounts[1..].iter().copied());
+        assert!(rotation.is_enabled());
+
+        let selected: Vec<_> = (0..6)
+            .map(|number| rotation.select(L1BatchNumber(number), 1_000))
+            .collect();
+        let expected: Vec<_> = accounts.iter().chain(&accounts).copied().collect();
+        assert_eq!(selected, expected);
+    }
+
+    #[test]
+    fn scheduled_rotation() {
+        let accounts: Vec<_> = (1..=2).map(Address::repeat_byte).collect();
+        let rotation = FeeAccountRotation::new(accounts.clone(), Some(60)).unwrap();
+
+        assert_eq!(rotation.select(L1BatchNumber(1), 0), accounts[0]);
+        assert_eq!(rotation.select(L1BatchNumber(2), 59), accounts[0]);
+        assert_eq!(rotation.select(L1BatchNumber(3), 60), accounts[1]);
+        assert_eq!(rotation.select(L1BatchNumber(4), 119), accounts[1]);
+        assert_eq!(rotation.select(L1BatchNumber(5), 120), accounts[0]);
+    }
+
+    #[test]
+    fn single_account() {
+        let rotation = FeeAccountRotation::new(vec![Address::repeat_byte(1)], Some(60)).unwrap();
+        assert!(!rotation.is_enabled());
+        for number in 0..3 {
+            assert_eq!(
+                rotation.select(L1BatchNumber(number), number.into()),
+                Address::repeat_byte(1)
+            );
+        }
+
+        FeeAccountRotation::new(vec![], None).unwrap_err();
+        FeeAccountRotation::new(vec![Address::repeat_byte(1)], Some(0)).unwrap_err();
+    }
+}
@@ -11,12 +11,13 @@ use multivm::{interface::Halt, utils::derive_base_fee_and_gas_per_pubdata};
 use vm_utils::storage::L1BatchParamsProvider;
 use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_contracts::BaseSystemContracts;
-use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_mempool::L2TxFilter;
 use zksync_node_fee_model::BatchFeeModelInputProvider;
 use zksync_types::{
-    protocol_upgrade::ProtocolUpgradeTx, utils::display_timestamp, Address, L1BatchNumber,
-    L2BlockNumber, L2ChainId, ProtocolVersionId, Transaction, H256, U256,
+    api::TransactionDeadline, protocol_upgrade::ProtocolUpgradeTx, utils::display_timestamp,
+    Address, ExecuteTransactionCommon, L1BatchNumber, L2BlockNumber, L2ChainId, ProtocolVersionId,
+    Transaction, H256, U256,
 };
 // TODO (SMA-1206): use seconds instead of milliseconds.
 use zksync_utils::time::millis_since_epoch;
@@ -24,13 +25,16 @@ use zksync_utils::time::millis_since_epoch;
 use crate::{
     io::{
         common::{load_pending_batch, poll_iters, IoCursor},
+        fee_accounts::FeeAccountRotation,
         seal_logic::l2_block_seal_subtasks::L2BlockSealProcess,
-        L1BatchParams, L2BlockParams, PendingBatchData, StateKeeperIO,
+        L1BatchParams, L2BlockParams, PendingBatchData, StateKeeperIO, TransactionFilter,
+        TxFilterDecision,
     },
-    mempool_actor::l2_tx_filter,
+    mempool_actor::l2_tx_filter_for_fee_input,
     metrics::KEEPER_METRICS,
     seal_criteria::{
-        IoSealCriteria, L2BlockMaxPayloadSizeSealer, TimeoutSealer, UnexecutableReason,
+        IoSealCriteria, L1GasPriceTrendSealer, L2BlockMaxPayloadSizeSealer,
+        L2BlockMaxPubdataSealer, TimeoutSealer, UnexecutableReason,
     },
     updates::UpdatesManager,
     MempoolGuard,
@@ -45,29 +49,60 @@ pub struct MempoolIO {
     mempool: MempoolGuard,
     pool: ConnectionPool<Core>,
     timeout_sealer: TimeoutSealer,
+    l1_gas_price_trend_sealer: L1GasPriceTrendSealer,
     l2_block_max_payload_size_sealer: L2BlockMaxPayloadSizeSealer,
+    l2_block_max_pubdata_sealer: L2BlockMaxPubdataSealer,
     filter: L2TxFilter,
     l1_batch_params_provider: L1BatchParamsProvider,
-    fee_account: Address,
+    fee_accounts: FeeAccountRotation,
     validation_computational_gas_limit: u32,
     max_allowed_tx_gas_limit: U256,
     delay_interval: Duration,
+    max_timestamp_drift_sec: Option<u64>,
+    discard_pending_l1_batch_on_restart: bool,
     // Used to keep track of gas prices to set accepted price per pubdata byte in blocks.
     batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     chain_id: L2ChainId,
+    evm_emulator_hash: Option<H256>,
+    /// Number and timestamp of the L2 block being filled with transactions. Used to check transaction inclusion deadlines.
+    current_l2_block: Option<(L2BlockNumber, u64)>,
+    /// Deadline of the last returned transaction. It's returned to the mempool if the transaction is rolled back.
+    last_tx_deadline: Option<(H256, TransactionDeadline)>,
+    /// Filters applied to L2 transactions before they are included into a batch.
+    tx_filters: Vec<Arc<dyn TransactionFilter>>,
 }
 
 impl IoSealCriteria for MempoolIO {
     fn should_seal_l1_batch_unconditionally(&mut self, manager: &UpdatesManager) -> bool {
+        if !self.l1_gas_price_trend_sealer.is_enabled() {
+            return self
+                .timeout_sealer
+                .should_seal_l1_batch_unconditionally(manager);
+        }
+
+        let current_l1_gas_price = self
+            .batch_fee_input_provider
+            .get_fee_model_params()
+            .l1_gas_price();
+        let deadline_factor = self.l1_gas_price_trend_sealer.deadline_factor(
+            manager.batch_fee_input().l1_gas_price(),
+            current_l1_gas_price,
+        );
         self.timeout_sealer
-            .should_seal_l1_batch_unconditionally(manager)
+            .should_seal_l1_batch_with_deadline_factor(manager, deadline_factor)
     }
 
     fn should_seal_l2_block(&mut self, manager: &UpdatesManager) -> bool {
         if self.timeout_sealer.should_seal_l2_block(manager) {
             return true;
         }
-        self.l2_block_max_payload_size_sealer
+        if self
+            .l2_block_max_payload_size_sealer
+            .should_seal_l2_block(manager)
+        {
+            return true;
+        }
+        self.l2_block_max_pubdata_sealer
             .should_seal_l2_block(manager)
     }
 }
@@ -97,6 +132,7 @@ impl StateKeeperIO for MempoolIO {
         let Some(pending_l2_block_header) = pending_l2_block_header else {
             return Ok((cursor, None));
         };
+        let first_pending_l2_block = pending_l2_block_header.number();
 
         let (system_env, l1_batch_env) = self
             .l1_batch_params_provider
@@ -117,6 +153,24 @@ impl StateKeeperIO for MempoolIO {
                 )
             })?;
 
+        if self.discard_pending_l1_batch_on_restart {
+            let discarded_cursor = Self::discard_pending_l2_blocks(
+                &mut storage,
+                &pending_batch_data,
+                first_pending_l2_block,
+            )
+            .await
+            .with_context(|| {
+                format!(
+                    "failed discarding pending L2 blocks for L1 batch #{}",
+                    cursor.l1_batch
+                )
+            })?;
+            if let Some(cursor) = discarded_cursor {
+                return Ok((cursor, None));
+            }
+        }
+
         let PendingBatchData {
             l1_batch_env,
             system_env,
@@ -148,6 +202,7 @@ impl StateKeeperIO for MempoolIO {
         max_wait: Duration,
     ) -> anyhow::Result<Option<L1BatchParams>> {
         let deadline = Instant::now() + max_wait;
+        self.check_timestamp_drift(cursor)?;
 
         // Block until at least one transaction in the mempool can match the filter (or timeout happens).
         // This is needed to ensure that block timestamp is not too old.
@@ -178,22 +233,40 @@ impl StateKeeperIO for MempoolIO {
 
             // We create a new filter each time, since parameters may change and a previously
             // ignored transaction in the mempool may be scheduled for the execution.
-            self.filter = l2_tx_filter(
-                self.batch_fee_input_provider.as_ref(),
-                protocol_version.into(),
-            )
-            .await
-            .context("failed creating L2 transaction filter")?;
+            // Fee input and its inputs are sampled at once, so that the persisted inputs match the batch fee input.
+            let (fee_input, fee_input_params) = self
+                .batch_fee_input_provider
+                .get_batch_fee_input_with_params()
+                .await
+                .context("failed getting batch fee input")?;
+            self.filter = l2_tx_filter_for_fee_input(fee_input, protocol_version.into());
 
             if !self.mempool.has_next(&self.filter) {
                 tokio::time::sleep(self.delay_interval).await;
                 continue;
             }
 
+            // Persist fee model inputs so that fees in the batch can be audited / reconciled later.
+            self.pool
+                .connection_tagged("state_keeper")
+                .await?
+                .blocks_dal()
+                .insert_l1_batch_fee_params(cursor.l1_batch, &fee_input_params)
+                .await?;
+
+            let fee_account = self.fee_accounts.select(cursor.l1_batch, timestamp);
+            if self.fee_accounts.is_enabled() {
+                tracing::info!(
+                    "Using fee account {fee_account:?} for L1 batch #{}",
+                    cursor.l1_batch
+                );
+            }
+
+            self.current_l2_block = Some((cursor.next_l2_block, timestamp));
             return Ok(Some(L1BatchParams {
                 protocol_version,
                 validation_computational_gas_limit: self.validation_computational_gas_limit,
-                operator_address: self.fee_account,
+                operator_address: fee_account,
                 fee_input: self.filter.fee_input,
                 first_l2_block: L2BlockParams {
                     timestamp,
@@ -210,6 +283,7 @@ impl StateKeeperIO for MempoolIO {
         cursor: &IoCursor,
         max_wait: Duration,
     ) -> anyhow::Result<Option<L2BlockParams>> {
+        self.check_timestamp_drift(cursor)?;
         // We must provide different timestamps for each L2 block.
         // If L2 block sealing interval is greater than 1 second then `sleep_past` won't actually sleep.
         let timeout_result = tokio::time::timeout(
@@ -221,6 +295,7 @@ impl StateKeeperIO for MempoolIO {
             return Ok(None);
         };
 
+        self.current_l2_block = Some((cursor.next_l2_block, timestamp));
         Ok(Some(L2BlockParams {
             timestamp,
             // This value is effectively ignored by the protocol.
@@ -239,6 +314,7 @@ impl StateKeeperIO for MempoolIO {
             get_latency.observe();
 
             if let Some(tx) = maybe_tx {
+                let deadline = self.mempool.take_deadline(tx.hash());
                 // Reject transactions with too big gas limit. They are also rejected on the API level, but
                 // we need to secure ourselves in case some tx will somehow get into mempool.
                 if tx.gas_limit() > self.max_allowed_tx_gas_limit {
@@ -251,6 +327,27 @@ impl StateKeeperIO for MempoolIO {
                         .await?;
                     continue;
                 }
+                if let (Some(deadline), Some((l2_block_number, timestamp))) =
+                    (deadline, self.current_l2_block)
+                {
+                    if !deadline.allows(l2_block_number, timestamp) {
+                        tracing::info!(
+                            "Transaction {:?} cannot be included into L2 block #{l2_block_number} with timestamp {} \
+                             because of its inclusion deadline {deadline:?}",
+                            tx.hash(),
+                            display_timestamp(timestamp)
+                        );
+                        self.reject(&tx, UnexecutableReason::DeadlineExpired)
+                            .await?;
+                        continue;
+                    }
+                }
+                if let Some(reason) = self.find_filter_denial(&tx).await? {
+                    self.reject(&tx, UnexecutableReason::DeniedByFilter(reason))
+                        .await?;
+                    continue;
+                }
+                self.last_tx_deadline = deadline.map(|deadline| (tx.hash(), deadline));
                 return Ok(Some(tx));
             } else {
                 tokio::time::sleep(self.delay_interval).await;
@@ -263,7 +360,13 @@ impl StateKeeperIO for MempoolIO {
     async fn rollback(&mut self, tx: Transaction) -> anyhow::Result<()> {
         // Reset nonces in the mempool.
         self.mempool.rollback(&tx);
-        // Insert the transaction back.
+        // Insert the transaction back, together with its deadline.
+        if let Some((tx_hash, deadline)) = self.last_tx_deadline.take() {
+            if tx_hash == tx.hash() {
+                self.mempool
+                    .insert_deadlines(HashMap::from([(tx_hash, deadline)]));
+            }
+        }
         self.mempool.insert(vec![tx], HashMap::new());
         Ok(())
     }
@@ -294,6 +397,12 @@ impl StateKeeperIO for MempoolIO {
             .transactions_dal()
             .mark_tx_as_rejected(rejected.hash(), &format!("rejected: {reason}"))
             .await?;
+        if matches!(reason, UnexecutableReason::DeadlineExpired) {
+            storage
+                .transaction_deadlines_dal()
+                .mark_deadline_expired(rejected.hash())
+                .await?;
+        }
         Ok(())
     }
 
@@ -306,7 +415,10 @@ impl StateKeeperIO for MempoolIO {
             .connection_tagged("state_keeper")
             .await?
             .protocol_versions_dal()
-            .load_base_system_contracts_by_version_id(protocol_version as u16)
+            .load_base_system_contracts_by_version_id(
+                protocol_version as u16,
+                self.evm_emulator_hash,
+            )
             .await
             .context("failed loading base system contracts")?
             .with_context(|| {
@@ -421,23 +533,150 @@ impl MempoolIO {
             .await
             .context("failed initializing L1 batch params provider")?;
         drop(storage);
+        let fee_accounts =
+            FeeAccountRotation::new(vec![fee_account], config.fee_account_rotation_period_sec)?;
 
         Ok(Self {
             mempool,
             pool,
             timeout_sealer: TimeoutSealer::new(config),
+            l1_gas_price_trend_sealer: L1GasPriceTrendSealer::new(config),
             l2_block_max_payload_size_sealer: L2BlockMaxPayloadSizeSealer::new(config),
+            l2_block_max_pubdata_sealer: L2BlockMaxPubdataSealer::new(config),
             filter: L2TxFilter::default(),
             // ^ Will be initialized properly on the first newly opened batch
             l1_batch_params_provider,
-            fee_account,
+            fee_accounts,
             validation_computational_gas_limit: config.validation_computational_gas_limit,
             max_allowed_tx_gas_limit: config.max_allowed_l2_tx_gas_limit.into(),
             delay_interval,
+            max_timestamp_drift_sec: config.max_timestamp_drift_sec,
+            discard_pending_l1_batch_on_restart: config.discard_pending_l1_batch_on_restart,
             batch_fee_input_provider,
             chain_id,
+            evm_emulator_hash: None,
+            current_l2_block: None,
+            last_tx_deadline: None,
+            tx_filters: vec![],
         })
     }
+
+    /// Discards pending L2 blocks of the open L1 batch (starting from `first_l2_block`) from Postgres,
+    /// returning their transactions to the mempool. Returns the updated cursor, or `None` if pending L2 blocks
+    /// cannot be discarded and must be re-executed.
+    async fn discard_pending_l2_blocks(
+        storage: &mut Connection<'_, Core>,
+        pending_batch_data: &PendingBatchData,
+        first_l2_block: L2BlockNumber,
+    ) -> anyhow::Result<Option<IoCursor>> {
+        let l1_batch_number = pending_batch_data.l1_batch_env.number;
+        // L1 and upgrade transactions must be executed in order, so we never return them to the mempool.
+        let has_non_l2_txs = pending_batch_data
+            .pending_l2_blocks
+            .iter()
+            .flat_map(|l2_block| &l2_block.txs)
+            .any(|tx| !matches!(tx.common_data, ExecuteTransactionCommon::L2(_)));
+        if has_non_l2_txs {
+            tracing::warn!(
+                "Pending L1 batch #{l1_batch_number} contains L1 or upgrade transactions; it will be re-executed \
+                 instead of being discarded"
+            );
+            return Ok(None);
+        }
+        // If consensus is enabled, pending L2 blocks may have already been certified or distributed to other nodes,
+        // so discarding them would fork the chain.
+        if storage.consensus_dal().genesis().await?.is_some() {
+            tracing::warn!(
+                "Consensus is enabled; pending L1 batch #{l1_batch_number} will be re-executed instead of \
+                 being discarded"
+            );
+            return Ok(None);
+        }
+
+        let last_l2_block_to_keep = first_l2_block - 1;
+        tracing::info!(
+            "Discarding {} pending L2 blocks starting from #{first_l2_block} in L1 batch #{l1_batch_number}",
+            pending_batch_data.pending_l2_blocks.len()
+        );
+        let mut transaction = storage.start_transaction().await?;
+        L2BlockSealProcess::clear_pending_l2_block(&mut transaction, last_l2_block_to_keep).await?;
+        transaction
+            .blocks_dal()
+            .delete_l2_blocks(last_l2_block_to_keep)
+            .await?;
+        // The mempool fetcher may have already loaded transactions from Postgres, so we need to mark
+        // discarded transactions as not being in the mempool for them to be fetched again.
+        transaction.transactions_dal().reset_mempool().await?;
+        let cursor = IoCursor::new(&mut transaction).await?;
+        transaction.commit().await?;
+        Ok(Some(cursor))
+    }
+
+    /// Adds fee accounts rotated with the primary fee account across new L1 batches;
+    /// see [`StateKeeperConfig::fee_account_rotation_period_sec`] for the rotation schedule.
+    #[must_use]
+    pub fn with_extra_fee_accounts(mut self, accounts: impl IntoIterator<Item = Address>) -> Self {
+        self.fee_accounts.extend(accounts);
+        self
+    }
+
+    /// Enables the EVM emulator with the specified bytecode hash (usually, taken from the genesis config)
+    /// for all executed L1 batches.
+    #[must_use]
+    pub fn with_evm_emulator_hash(mut self, evm_emulator_hash: Option<H256>) -> Self {
+        self.evm_emulator_hash = evm_emulator_hash;
+        self.l1_batch_params_provider = self
+            .l1_batch_params_provider
+            .with_evm_emulator_hash(evm_emulator_hash);
+        self
+    }
+
+    /// Adds a filter for L2 transactions. Filters are applied in the order they were added;
+    /// a transaction is rejected if any filter denies it.
+    #[must_use]
+    pub fn with_transaction_filter(mut self, filter: Arc<dyn TransactionFilter>) -> Self {
+        self.tx_filters.push(filter);
+        self
+    }
+
+    /// Returns the denial reason reported by the first filter that denies the transaction, if any.
+    async fn find_filter_denial(&self, tx: &Transaction) -> anyhow::Result<Option<String>> {
+        if tx.is_l1() {
+            return Ok(None);
+        }
+        for filter in &self.tx_filters {
+            let decision = filter
+                .filter(tx)
+                .await
+                .with_context(|| format!("failed filtering transaction {:?}", tx.hash()))?;
+            if let TxFilterDecision::Deny(reason) = decision {
+                return Ok(Some(reason));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Checks that the previous L2 block timestamp isn't too far in the future compared to the wall clock.
+    /// Otherwise, `sleep_past()` would wait for the wall clock to catch up for an unreasonably long time,
+    /// and the produced timestamps would be skewed.
+    fn check_timestamp_drift(&self, cursor: &IoCursor) -> anyhow::Result<()> {
+        let Some(max_drift_sec) = self.max_timestamp_drift_sec else {
+            return Ok(());
+        };
+        let current_timestamp = (millis_since_epoch() / 1_000) as u64;
+        let drift_sec = cursor
+            .prev_l2_block_timestamp
+            .saturating_sub(current_timestamp);
+        anyhow::ensure!(
+            drift_sec <= max_drift_sec,
+            "Previous L2 block timestamp {} is {drift_sec}s ahead of the current timestamp {} for L2 block #{}, \
+             which exceeds the allowed drift of {max_drift_sec}s; check the system clock",
+            display_timestamp(cursor.prev_l2_block_timestamp),
+            display_timestamp(current_timestamp),
+            cursor.next_l2_block
+        );
+        Ok(())
+    }
 }
 
 /// Getters required for testing the MempoolIO.
@@ -10,19 +10,26 @@ use zksync_types::{
 };
 
 pub use self::{
+    batch_metadata::{L1BatchMetadataPersistence, L1BatchMetadataSource, StaticL1BatchMetadata},
     common::IoCursor,
-    output_handler::{OutputHandler, StateKeeperOutputHandler},
+    output_handler::{
+        CompositeOutputHandler, HandlerFailurePolicy, OutputHandler, StateKeeperOutputHandler,
+    },
     persistence::{L2BlockSealerTask, StateKeeperPersistence, TreeWritesPersistence},
+    tx_filter::{AddressTransactionFilter, TransactionFilter, TxFilterDecision},
 };
 use super::seal_criteria::{IoSealCriteria, UnexecutableReason};
 
+mod batch_metadata;
 pub mod common;
+mod fee_accounts;
 pub(crate) mod mempool;
 mod output_handler;
 mod persistence;
 pub mod seal_logic;
 #[cfg(test)]
 mod tests;
+mod tx_filter;
 
 /// Contains information about the un-synced execution state:
 /// Batch data and transactions that were executed before and are marked as so in the DB,
@@ -26,6 +26,12 @@ pub trait StateKeeperOutputHandler: 'static + Send + fmt::Debug {
     ) -> anyhow::Result<()> {
         Ok(())
     }
+
+    /// Waits until all previously handled outputs are fully processed. This method is called on graceful state keeper shutdown.
+    /// The default implementation does nothing.
+    async fn flush(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 /// Compound output handler plugged into the state keeper.
@@ -99,4 +105,207 @@ impl OutputHandler {
         }
         Ok(())
     }
+
+    pub(crate) async fn flush(&mut self) -> anyhow::Result<()> {
+        for handler in &mut self.inner {
+            handler
+                .flush()
+                .await
+                .with_context(|| format!("failed flushing handler {handler:?}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Policy applied by [`CompositeOutputHandler`] when one of its handlers fails.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HandlerFailurePolicy {
+    /// Propagate the error, so that subsequent handlers aren't run and the state keeper stops.
+    /// This is the only sensible policy for handlers that other components depend on (e.g., persistence).
+    #[default]
+    FailFast,
+    /// Log the error and continue with subsequent handlers. Suitable for auxiliary consumers of state keeper
+    /// outputs (e.g., metrics or notifications) which shouldn't be able to stop the state keeper.
+    LogAndContinue,
+}
+
+/// [`StateKeeperOutputHandler`] fanning out all hooks to multiple registered handlers, with a configurable
+/// [failure policy](HandlerFailurePolicy) for each of them.
+///
+/// Handlers are executed sequentially in the order they were registered. Since the composite handler
+/// is a [`StateKeeperOutputHandler`] itself, it can be plugged into the [`OutputHandler`] with a single call.
+#[derive(Debug, Default)]
+pub struct CompositeOutputHandler {
+    handlers: Vec<(Box<dyn StateKeeperOutputHandler>, HandlerFailurePolicy)>,
+}
+
+impl CompositeOutputHandler {
+    /// Creates a composite handler without any handlers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a new handler with the specified failure policy. Its hooks will be executed after all handlers
+    /// registered previously.
+    #[must_use]
+    pub fn with_handler(
+        mut self,
+        handler: Box<dyn StateKeeperOutputHandler>,
+        policy: HandlerFailurePolicy,
+    ) -> Self {
+        self.handlers.push((handler, policy));
+        self
+    }
+
+    /// Returns the number of registered handlers.
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    /// Checks whether there are no registered handlers.
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    fn handle_result(
+        result: anyhow::Result<()>,
+        handler: &dyn StateKeeperOutputHandler,
+        policy: HandlerFailurePolicy,
+        action: impl FnOnce() -> String,
+    ) -> anyhow::Result<()> {
+        let Err(err) = result else {
+            return Ok(());
+        };
+        match policy {
+            HandlerFailurePolicy::FailFast => {
+                Err(err.context(format!("failed {} on handler {handler:?}", action())))
+            }
+            HandlerFailurePolicy::LogAndContinue => {
+                tracing::warn!(
+                    "Failed {} on handler {handler:?}, continuing with other handlers: {err:#}",
+                    action()
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl StateKeeperOutputHandler for CompositeOutputHandler {
+    async fn initialize(&mut self, cursor: &IoCursor) -> anyhow::Result<()> {
+        for (handler, policy) in &mut self.handlers {
+            let result = handler.initialize(cursor).await;
+            Self::handle_result(result, handler.as_ref(), *policy, || {
+                "initializing".to_owned()
+            })?;
+        }
+        Ok(())
+    }
+
+    async fn handle_l2_block(&mut self, updates_manager: &UpdatesManager) -> anyhow::Result<()> {
+        for (handler, policy) in &mut self.handlers {
+            let result = handler.handle_l2_block(updates_manager).await;
+            Self::handle_result(result, handler.as_ref(), *policy, || {
+                format!("handling L2 block {:?}", updates_manager.l2_block)
+            })?;
+        }
+        Ok(())
+    }
+
+    async fn handle_l1_batch(
+        &mut self,
+        updates_manager: Arc<UpdatesManager>,
+    ) -> anyhow::Result<()> {
+        for (handler, policy) in &mut self.handlers {
+            let result = handler.handle_l1_batch(updates_manager.clone()).await;
+            Self::handle_result(result, handler.as_ref(), *policy, || {
+                format!("handling L1 batch #{}", updates_manager.l1_batch.number)
+            })?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> anyhow::Result<()> {
+        for (handler, policy) in &mut self.handlers {
+            let result = handler.flush().await;
+            Self::handle_result(result, handler.as_ref(), *policy, || "flushing".to_owned())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::tests::create_updates_manager;
+
+    #[derive(Debug)]
+    struct RecordingHandler {
+        name: &'static str,
+        should_fail: bool,
+        calls: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    #[async_trait]
+    impl StateKeeperOutputHandler for RecordingHandler {
+        async fn handle_l2_block(
+            &mut self,
+            _updates_manager: &UpdatesManager,
+        ) -> anyhow::Result<()> {
+            self.calls.lock().unwrap().push(self.name);
+            if self.should_fail {
+                anyhow::bail!("handler {} failed", self.name);
+            }
+            Ok(())
+        }
+    }
+
+    fn composite_handler(
+        first_policy: HandlerFailurePolicy,
+    ) -> (CompositeOutputHandler, Arc<Mutex<Vec<&'static str>>>) {
+        let calls = Arc::default();
+        let handler = CompositeOutputHandler::new()
+            .with_handler(
+                Box::new(RecordingHandler {
+                    name: "first",
+                    should_fail: true,
+                    calls: Arc::clone(&calls),
+                }),
+                first_policy,
+            )
+            .with_handler(
+                Box::new(RecordingHandler {
+                    name: "second",
+                    should_fail: false,
+                    calls: Arc::clone(&calls),
+                }),
+                HandlerFailurePolicy::FailFast,
+            );
+        (handler, calls)
+    }
+
+    #[tokio::test]
+    async fn composite_handler_with_fail_fast_policy() {
+        let (mut handler, calls) = composite_handler(HandlerFailurePolicy::FailFast);
+        let err = handler
+            .handle_l2_block(&create_updates_manager())
+            .await
+            .unwrap_err();
+        let err = format!("{err:#}");
+        assert!(err.contains("handler first failed"), "{err}");
+        assert_eq!(*calls.lock().unwrap(), ["first"]);
+    }
+
+    #[tokio::test]
+    async fn composite_handler_with_log_and_continue_policy() {
+        let (mut handler, calls) = composite_handler(HandlerFailurePolicy::LogAndContinue);
+        handler
+            .handle_l2_block(&create_updates_manager())
+            .await
+            .unwrap();
+        assert_eq!(*calls.lock().unwrap(), ["first", "second"]);
+    }
 }
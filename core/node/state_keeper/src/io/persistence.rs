@@ -5,17 +5,18 @@ use std::{sync::Arc, time::Instant};
 use anyhow::Context as _;
 use async_trait::async_trait;
 use multivm::zk_evm_latest::ethereum_types::H256;
-use tokio::sync::{mpsc, oneshot};
-use zksync_dal::{ConnectionPool, Core, CoreDal};
+use tokio::sync::oneshot;
+use zksync_dal::{transactions_dal::CallTraceFormat, ConnectionPool, Core, CoreDal};
 use zksync_shared_metrics::{BlockStage, APP_METRICS};
 use zksync_types::{writes::TreeWrite, AccountTreeId, Address, StorageKey};
 use zksync_utils::u256_to_h256;
 
 use crate::{
+    channel::{self, ObservedReceiver, ObservedSender},
     io::{
         seal_logic::l2_block_seal_subtasks::L2BlockSealProcess, IoCursor, StateKeeperOutputHandler,
     },
-    metrics::{L2BlockQueueStage, L2_BLOCK_METRICS},
+    metrics::{L2BlockQueueStage, StageChannel, L2_BLOCK_METRICS},
     updates::{L2BlockSealCommand, UpdatesManager},
 };
 
@@ -33,7 +34,8 @@ pub struct StateKeeperPersistence {
     l2_shared_bridge_addr: Address,
     pre_insert_txs: bool,
     insert_protective_reads: bool,
-    commands_sender: mpsc::Sender<Completable<L2BlockSealCommand>>,
+    call_trace_format: CallTraceFormat,
+    commands_sender: ObservedSender<Completable<L2BlockSealCommand>>,
     latest_completion_receiver: Option<oneshot::Receiver<()>>,
     // If true, `submit_l2_block()` will wait for the operation to complete.
     is_sync: bool,
@@ -52,11 +54,11 @@ impl StateKeeperPersistence {
         let is_sync = command_capacity == 0;
         command_capacity = command_capacity.max(1);
 
-        let (commands_sender, commands_receiver) = mpsc::channel(command_capacity);
+        let (commands_sender, commands_receiver) =
+            channel::bounded(StageChannel::L2BlockSealCommands, command_capacity);
         let sealer = L2BlockSealerTask {
             pool: pool.clone(),
             is_sync,
-            commands_sender: commands_sender.downgrade(),
             commands_receiver,
         };
         let this = Self {
@@ -64,6 +66,7 @@ impl StateKeeperPersistence {
             l2_shared_bridge_addr,
             pre_insert_txs: false,
             insert_protective_reads: true,
+            call_trace_format: CallTraceFormat::default(),
             commands_sender,
             latest_completion_receiver: None,
             is_sync,
@@ -83,6 +86,14 @@ impl StateKeeperPersistence {
         self
     }
 
+    /// Sets the format used to persist call traces. By default, call traces are persisted in the legacy format
+    /// understood by all node versions; the flat format should only be enabled once all nodes reading traces
+    /// from the same database are updated.
+    pub fn with_call_trace_format(mut self, format: CallTraceFormat) -> Self {
+        self.call_trace_format = format;
+        self
+    }
+
     /// Submits a new sealing `command` to the sealer that this handle is attached to.
     ///
     /// If there are currently too many unprocessed commands, this method will wait until
@@ -158,8 +169,11 @@ impl StateKeeperOutputHandler for StateKeeperPersistence {
     }
 
     async fn handle_l2_block(&mut self, updates_manager: &UpdatesManager) -> anyhow::Result<()> {
-        let command =
-            updates_manager.seal_l2_block_command(self.l2_shared_bridge_addr, self.pre_insert_txs);
+        let command = updates_manager.seal_l2_block_command(
+            self.l2_shared_bridge_addr,
+            self.pre_insert_txs,
+            self.call_trace_format,
+        );
         self.submit_l2_block(command).await;
         Ok(())
     }
@@ -183,6 +197,11 @@ impl StateKeeperOutputHandler for StateKeeperPersistence {
         APP_METRICS.block_number[&BlockStage::Sealed].set(batch_number.0.into());
         Ok(())
     }
+
+    async fn flush(&mut self) -> anyhow::Result<()> {
+        self.wait_for_all_commands().await;
+        Ok(())
+    }
 }
 
 /// Component responsible for sealing L2 blocks (i.e., storing their data to Postgres).
@@ -190,9 +209,7 @@ impl StateKeeperOutputHandler for StateKeeperPersistence {
 pub struct L2BlockSealerTask {
     pool: ConnectionPool<Core>,
     is_sync: bool,
-    // Weak sender handle to get queue capacity stats.
-    commands_sender: mpsc::WeakSender<Completable<L2BlockSealCommand>>,
-    commands_receiver: mpsc::Receiver<Completable<L2BlockSealCommand>>,
+    commands_receiver: ObservedReceiver<Completable<L2BlockSealCommand>>,
 }
 
 impl L2BlockSealerTask {
@@ -201,11 +218,8 @@ impl L2BlockSealerTask {
     pub async fn run(mut self) -> anyhow::Result<()> {
         if self.is_sync {
             tracing::info!("Starting synchronous L2 block sealer");
-        } else if let Some(sender) = self.commands_sender.upgrade() {
-            tracing::info!(
-                "Starting async L2 block sealer with queue capacity {}",
-                sender.max_capacity()
-            );
+        } else if let Some(max_capacity) = self.commands_receiver.max_capacity() {
+            tracing::info!("Starting async L2 block sealer with queue capacity {max_capacity}");
         } else {
             tracing::warn!("L2 block sealer not started, since its handle is already dropped");
         }
@@ -241,8 +255,8 @@ impl L2BlockSealerTask {
 
         if !self.is_sync {
             L2_BLOCK_METRICS.seal_queue_latency[&L2BlockQueueStage::NextCommand].observe(elapsed);
-            if let Some(sender) = self.commands_sender.upgrade() {
-                L2_BLOCK_METRICS.seal_queue_capacity.set(sender.capacity());
+            if let Some(capacity) = self.commands_receiver.capacity() {
+                L2_BLOCK_METRICS.seal_queue_capacity.set(capacity);
             }
         }
         command
@@ -354,7 +368,7 @@ impl StateKeeperOutputHandler for TreeWritesPersistence {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use std::collections::{BTreeMap, HashSet};
 
     use assert_matches::assert_matches;
     use futures::FutureExt;
@@ -370,7 +384,7 @@ mod tests {
 
     use super::*;
     use crate::{
-        io::L2BlockParams,
+        io::{L1BatchMetadataPersistence, L2BlockParams, StaticL1BatchMetadata},
         testonly::default_vm_batch_result,
         tests::{
             create_execution_result, create_transaction, create_updates_manager,
@@ -579,6 +593,49 @@ mod tests {
         assert_eq!(protective_reads, HashSet::new());
     }
 
+    #[tokio::test]
+    async fn persisting_custom_l1_batch_metadata() {
+        let pool = ConnectionPool::constrained_test_pool(1).await;
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+        storage
+            .blocks_dal()
+            .set_l1_batch_hash(L1BatchNumber(0), H256::zero())
+            .await
+            .unwrap();
+        drop(storage);
+
+        let (persistence, l2_block_sealer) =
+            StateKeeperPersistence::new(pool.clone(), Address::default(), 1);
+        let build_info = StaticL1BatchMetadata(BTreeMap::from([
+            ("build".to_owned(), "v1.0.0".to_owned()),
+            ("region".to_owned(), "eu".to_owned()),
+        ]));
+        let compliance_tags =
+            StaticL1BatchMetadata(BTreeMap::from([("region".to_owned(), "us".to_owned())]));
+        let metadata_persistence = L1BatchMetadataPersistence::new(pool.clone())
+            .with_source(Arc::new(build_info))
+            .with_source(Arc::new(compliance_tags));
+        let mut output_handler =
+            OutputHandler::new(Box::new(persistence)).with_handler(Box::new(metadata_persistence));
+        tokio::spawn(l2_block_sealer.run());
+        execute_mock_batch(&mut output_handler).await;
+
+        let mut storage = pool.connection().await.unwrap();
+        let metadata = storage
+            .l1_batches_custom_metadata_dal()
+            .get_metadata(L1BatchNumber(1))
+            .await
+            .unwrap();
+        let expected_metadata = BTreeMap::from([
+            ("build".to_owned(), "v1.0.0".to_owned()),
+            ("region".to_owned(), "us".to_owned()),
+        ]);
+        assert_eq!(metadata, expected_metadata);
+    }
+
     #[tokio::test]
     async fn l2_block_sealer_handle_blocking() {
         let pool = ConnectionPool::constrained_test_pool(1).await;
@@ -587,7 +644,11 @@ mod tests {
 
         // The first command should be successfully submitted immediately.
         let mut updates_manager = create_updates_manager();
-        let seal_command = updates_manager.seal_l2_block_command(Address::default(), false);
+        let seal_command = updates_manager.seal_l2_block_command(
+            Address::default(),
+            false,
+            CallTraceFormat::Legacy,
+        );
         persistence.submit_l2_block(seal_command).await;
 
         // The second command should lead to blocking
@@ -595,7 +656,11 @@ mod tests {
             timestamp: 2,
             virtual_blocks: 1,
         });
-        let seal_command = updates_manager.seal_l2_block_command(Address::default(), false);
+        let seal_command = updates_manager.seal_l2_block_command(
+            Address::default(),
+            false,
+            CallTraceFormat::Legacy,
+        );
         {
             let submit_future = persistence.submit_l2_block(seal_command);
             futures::pin_mut!(submit_future);
@@ -623,7 +688,11 @@ mod tests {
             timestamp: 3,
             virtual_blocks: 1,
         });
-        let seal_command = updates_manager.seal_l2_block_command(Address::default(), false);
+        let seal_command = updates_manager.seal_l2_block_command(
+            Address::default(),
+            false,
+            CallTraceFormat::Legacy,
+        );
         persistence.submit_l2_block(seal_command).await;
         let command = sealer.commands_receiver.recv().await.unwrap();
         command.completion_sender.send(()).unwrap();
@@ -639,7 +708,11 @@ mod tests {
         // 5 L2 block sealing commands can be submitted without blocking.
         let mut updates_manager = create_updates_manager();
         for i in 1..=5 {
-            let seal_command = updates_manager.seal_l2_block_command(Address::default(), false);
+            let seal_command = updates_manager.seal_l2_block_command(
+                Address::default(),
+                false,
+                CallTraceFormat::Legacy,
+            );
             updates_manager.push_l2_block(L2BlockParams {
                 timestamp: i,
                 virtual_blocks: 1,
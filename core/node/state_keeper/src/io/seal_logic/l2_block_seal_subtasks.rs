@@ -124,6 +124,7 @@ impl L2BlockSealSubtask for MarkTransactionsInL2BlockSubtask {
                 &command.l2_block.executed_transactions,
                 command.base_fee_per_gas.into(),
                 command.l2_block.protocol_version,
+                command.call_trace_format,
                 command.pre_insert_txs,
             )
             .await?;
@@ -371,7 +372,7 @@ mod tests {
         zk_evm_latest::ethereum_types::H256,
         VmVersion,
     };
-    use zksync_dal::{ConnectionPool, Core};
+    use zksync_dal::{transactions_dal::CallTraceFormat, ConnectionPool, Core};
     use zksync_node_test_utils::create_l2_transaction;
     use zksync_types::{
         block::L2BlockHeader,
@@ -461,6 +462,7 @@ mod tests {
                 block_execution_metrics: Default::default(),
                 txs_encoding_size: Default::default(),
                 payload_encoding_size: Default::default(),
+                events_encoding_size: Default::default(),
                 timestamp: 1,
                 number: L2BlockNumber(1),
                 prev_block_hash: Default::default(),
@@ -475,6 +477,7 @@ mod tests {
             protocol_version: Some(ProtocolVersionId::latest()),
             l2_shared_bridge_addr: Default::default(),
             pre_insert_txs: false,
+            call_trace_format: CallTraceFormat::Legacy,
         };
 
         // Run.
@@ -9,7 +9,7 @@ use std::{
 use anyhow::Context as _;
 use itertools::Itertools;
 use multivm::utils::{get_max_batch_gas_limit, get_max_gas_per_pubdata_byte};
-use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_dal::{transactions_dal::CallTraceFormat, Connection, ConnectionPool, Core, CoreDal};
 use zksync_shared_metrics::{BlockStage, L2BlockStage, APP_METRICS};
 use zksync_types::{
     block::{L1BatchHeader, L2BlockHeader},
@@ -61,6 +61,7 @@ impl UpdatesManager {
         let l2_block_command = self.seal_l2_block_command(
             l2_shared_bridge_addr,
             false, // fictive L2 blocks don't have txs, so it's fine to pass `false` here.
+            CallTraceFormat::default(),
         );
 
         let mut connection = pool.connection_tagged("state_keeper").await?;
@@ -1,19 +1,23 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use multivm::utils::derive_base_fee_and_gas_per_pubdata;
 use test_casing::test_casing;
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_consensus_roles::validator;
 use zksync_contracts::BaseSystemContractsHashes;
-use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_dal::{transactions_dal::CallTraceFormat, ConnectionPool, Core, CoreDal};
 use zksync_mempool::L2TxFilter;
+use zksync_node_fee_model::compute_batch_fee_input;
 use zksync_node_test_utils::prepare_recovery_snapshot;
 use zksync_types::{
+    api::{TransactionDeadline, TransactionExpiryStatus},
     block::{BlockGasCount, L2BlockHasher},
     commitment::L1BatchCommitmentMode,
     fee::TransactionExecutionMetrics,
     fee_model::{BatchFeeInput, PubdataIndependentBatchFeeModelInput},
     tx::ExecutionMetrics,
     AccountTreeId, Address, L1BatchNumber, L2BlockNumber, L2ChainId, ProtocolVersion,
-    ProtocolVersionId, StorageKey, VmEvent, H256, U256,
+    ProtocolVersionId, StorageKey, Transaction, VmEvent, H256, U256,
 };
 use zksync_utils::time::seconds_since_epoch;
 
@@ -24,7 +28,7 @@ use crate::{
     testonly::BASE_SYSTEM_CONTRACTS,
     tests::{create_execution_result, create_transaction, Query},
     updates::{L2BlockSealCommand, L2BlockUpdates, UpdatesManager},
-    StateKeeperOutputHandler, StateKeeperPersistence,
+    AddressTransactionFilter, StateKeeperOutputHandler, StateKeeperPersistence,
 };
 
 mod tester;
@@ -94,6 +98,102 @@ async fn test_filter_with_pending_batch(commitment_mode: L1BatchCommitmentMode)
     assert_eq!(mempool.filter(), &want_filter);
 }
 
+#[tokio::test]
+async fn discarding_pending_batch_on_restart() {
+    let connection_pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+    let mut tester = Tester::new(L1BatchCommitmentMode::Rollup);
+    tester.genesis(&connection_pool).await;
+    let fee_input = BatchFeeInput::l1_pegged(55, 555);
+    let tx_result = tester
+        .insert_l2_block(&connection_pool, 1, 5, fee_input)
+        .await;
+    tester
+        .insert_sealed_batch(&connection_pool, 1, &[tx_result])
+        .await;
+    tester.set_timestamp(2);
+    let pending_tx_result = tester
+        .insert_l2_block(&connection_pool, 2, 5, fee_input)
+        .await;
+    tester.set_timestamp(3);
+    tester
+        .insert_l2_block(&connection_pool, 3, 5, fee_input)
+        .await;
+
+    let config = StateKeeperConfig {
+        discard_pending_l1_batch_on_restart: true,
+        ..tester.state_keeper_config()
+    };
+    let (mut mempool, _) = tester
+        .create_test_mempool_io_with_config(connection_pool.clone(), &config)
+        .await;
+    let (cursor, maybe_pending_batch) = mempool.initialize().await.unwrap();
+    assert!(maybe_pending_batch.is_none());
+    assert_eq!(cursor.next_l2_block, L2BlockNumber(2));
+    assert_eq!(cursor.l1_batch, L1BatchNumber(2));
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    let last_l2_block = storage
+        .blocks_dal()
+        .get_sealed_l2_block_number()
+        .await
+        .unwrap();
+    assert_eq!(last_l2_block, Some(L2BlockNumber(1)));
+    // Discarded transactions should be returned to the mempool.
+    let mempool_txs = storage
+        .transactions_dal()
+        .sync_mempool(&[], &[], 0, 0, 10)
+        .await
+        .unwrap();
+    let mempool_tx_hashes: Vec<_> = mempool_txs.iter().map(Transaction::hash).collect();
+    assert_eq!(mempool_tx_hashes.len(), 2);
+    assert!(mempool_tx_hashes.contains(&pending_tx_result.hash));
+
+    // On the next restart, there should be nothing to re-execute.
+    let (mut mempool, _) = tester.create_test_mempool_io(connection_pool).await;
+    let (cursor, maybe_pending_batch) = mempool.initialize().await.unwrap();
+    assert!(maybe_pending_batch.is_none());
+    assert_eq!(cursor.next_l2_block, L2BlockNumber(2));
+}
+
+#[tokio::test]
+async fn pending_batch_is_not_discarded_with_consensus() {
+    let connection_pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+    let mut tester = Tester::new(L1BatchCommitmentMode::Rollup);
+    tester.genesis(&connection_pool).await;
+    let fee_input = BatchFeeInput::l1_pegged(55, 555);
+    let tx_result = tester
+        .insert_l2_block(&connection_pool, 1, 5, fee_input)
+        .await;
+    tester
+        .insert_sealed_batch(&connection_pool, 1, &[tx_result])
+        .await;
+    tester.set_timestamp(2);
+    tester
+        .insert_l2_block(&connection_pool, 2, 5, fee_input)
+        .await;
+
+    let consensus_setup = validator::testonly::Setup::new(&mut rand::thread_rng(), 1);
+    let mut storage = connection_pool.connection().await.unwrap();
+    storage
+        .consensus_dal()
+        .try_update_genesis(&consensus_setup.genesis)
+        .await
+        .unwrap();
+    drop(storage);
+
+    let config = StateKeeperConfig {
+        discard_pending_l1_batch_on_restart: true,
+        ..tester.state_keeper_config()
+    };
+    let (mut mempool, _) = tester
+        .create_test_mempool_io_with_config(connection_pool, &config)
+        .await;
+    let (cursor, maybe_pending_batch) = mempool.initialize().await.unwrap();
+    let pending_batch = maybe_pending_batch.expect("pending batch should be re-executed");
+    assert_eq!(pending_batch.pending_l2_blocks.len(), 1);
+    assert_eq!(cursor.next_l2_block, L2BlockNumber(3));
+}
+
 /// Ensure that `MempoolIO.filter` is modified correctly if there is no pending batch.
 #[test_casing(2, COMMITMENT_MODES)]
 #[tokio::test]
@@ -120,7 +220,7 @@ async fn test_filter_with_no_pending_batch(commitment_mode: L1BatchCommitmentMod
     .unwrap();
 
     // Create a mempool without pending batch and ensure that filter is not initialized just yet.
-    let (mut mempool, mut guard) = tester.create_test_mempool_io(connection_pool).await;
+    let (mut mempool, mut guard) = tester.create_test_mempool_io(connection_pool.clone()).await;
     let (io_cursor, _) = mempool.initialize().await.unwrap();
     assert_eq!(mempool.filter(), &L2TxFilter::default());
 
@@ -133,11 +233,29 @@ async fn test_filter_with_no_pending_batch(commitment_mode: L1BatchCommitmentMod
 
     // Now, given that there is a transaction matching the expected filter, waiting for the new batch params
     // should succeed and initialize the filter.
-    mempool
+    let batch_params = mempool
         .wait_for_new_batch_params(&io_cursor, Duration::from_secs(10))
         .await
+        .unwrap()
         .expect("No batch params in the test mempool");
     assert_eq!(mempool.filter(), &want_filter);
+
+    // Persisted fee model inputs must reproduce the batch fee input exactly.
+    let fee_input_params = connection_pool
+        .connection()
+        .await
+        .unwrap()
+        .blocks_dal()
+        .get_l1_batch_fee_params(io_cursor.l1_batch)
+        .await
+        .unwrap()
+        .expect("fee params are not persisted");
+    assert_eq!(fee_input_params.l1_gas_price_scale_factor, 1.0);
+    assert_eq!(fee_input_params.l1_pubdata_price_scale_factor, 1.0);
+    assert_eq!(
+        compute_batch_fee_input(fee_input_params),
+        batch_params.fee_input
+    );
 }
 
 async fn test_timestamps_are_distinct(
@@ -285,6 +403,7 @@ async fn processing_storage_logs_when_sealing_l2_block() {
         protocol_version: Some(ProtocolVersionId::latest()),
         l2_shared_bridge_addr: Address::default(),
         pre_insert_txs: false,
+        call_trace_format: CallTraceFormat::Legacy,
     };
     connection_pool
         .connection()
@@ -374,6 +493,7 @@ async fn processing_events_when_sealing_l2_block() {
         protocol_version: Some(ProtocolVersionId::latest()),
         l2_shared_bridge_addr: Address::default(),
         pre_insert_txs: false,
+        call_trace_format: CallTraceFormat::Legacy,
     };
     pool.connection()
         .await
@@ -551,3 +671,163 @@ async fn different_timestamp_for_l2_blocks_in_same_batch(commitment_mode: L1Batc
         .expect("no new L2 block params");
     assert!(l2_block_params.timestamp > current_timestamp);
 }
+
+#[tokio::test]
+async fn excessive_clock_skew_is_an_error() {
+    let connection_pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+    let tester = Tester::new(L1BatchCommitmentMode::Rollup);
+    tester.genesis(&connection_pool).await;
+    let (mut mempool, _) = tester.create_test_mempool_io(connection_pool).await;
+    let (mut io_cursor, _) = mempool.initialize().await.unwrap();
+    io_cursor.prev_l2_block_timestamp = seconds_since_epoch() + 3_600;
+
+    let err = mempool
+        .wait_for_new_l2_block_params(&io_cursor, Duration::from_secs(10))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("allowed drift"), "{err}");
+    let err = mempool
+        .wait_for_new_batch_params(&io_cursor, Duration::from_secs(10))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("allowed drift"), "{err}");
+}
+
+#[tokio::test]
+async fn transactions_with_expired_deadline_are_rejected() {
+    let connection_pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+    let tester = Tester::new(L1BatchCommitmentMode::Rollup);
+    tester.genesis(&connection_pool).await;
+    let (mut mempool, mut guard) = tester.create_test_mempool_io(connection_pool.clone()).await;
+    let (io_cursor, _) = mempool.initialize().await.unwrap();
+    assert_eq!(io_cursor.next_l2_block, L2BlockNumber(1));
+    let tx_filter = l2_tx_filter(
+        &tester.create_batch_fee_input_provider().await,
+        ProtocolVersionId::latest().into(),
+    )
+    .await
+    .unwrap();
+
+    let expired_deadline = TransactionDeadline {
+        not_after_l2_block: Some(L2BlockNumber(0)),
+        not_after_timestamp: None,
+    };
+    let expired_tx = tester.insert_tx_with_deadline(
+        &mut guard,
+        tx_filter.fee_per_gas,
+        tx_filter.gas_per_pubdata,
+        expired_deadline,
+    );
+    let mut storage = connection_pool.connection().await.unwrap();
+    storage
+        .transaction_deadlines_dal()
+        .insert_deadline(expired_tx.hash(), &expired_deadline)
+        .await
+        .unwrap();
+    drop(storage);
+
+    mempool
+        .wait_for_new_batch_params(&io_cursor, Duration::from_secs(10))
+        .await
+        .unwrap()
+        .expect("no batch params");
+    let tx = mempool
+        .wait_for_next_tx(Duration::from_millis(100))
+        .await
+        .unwrap();
+    assert!(tx.is_none(), "{tx:?}");
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    let expiry = storage
+        .transaction_deadlines_dal()
+        .get_transaction_expiry(expired_tx.hash())
+        .await
+        .unwrap()
+        .expect("no expiry info");
+    assert_eq!(expiry.status, TransactionExpiryStatus::Expired);
+    drop(storage);
+
+    let timely_tx = tester.insert_tx_with_deadline(
+        &mut guard,
+        tx_filter.fee_per_gas,
+        tx_filter.gas_per_pubdata,
+        TransactionDeadline {
+            not_after_l2_block: Some(L2BlockNumber(1)),
+            not_after_timestamp: Some(seconds_since_epoch() + 3_600),
+        },
+    );
+    let tx = mempool
+        .wait_for_next_tx(Duration::from_millis(100))
+        .await
+        .unwrap()
+        .expect("no transaction");
+    assert_eq!(tx.hash(), timely_tx.hash());
+}
+
+#[tokio::test]
+async fn transactions_denied_by_filter_are_rejected() {
+    let connection_pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+    let tester = Tester::new(L1BatchCommitmentMode::Rollup);
+    tester.genesis(&connection_pool).await;
+    let (mempool, mut guard) = tester.create_test_mempool_io(connection_pool.clone()).await;
+    let tx_filter = l2_tx_filter(
+        &tester.create_batch_fee_input_provider().await,
+        ProtocolVersionId::latest().into(),
+    )
+    .await
+    .unwrap();
+
+    let denied_tx = tester.insert_tx(&mut guard, tx_filter.fee_per_gas, tx_filter.gas_per_pubdata);
+    let filter =
+        AddressTransactionFilter::default().with_denied_senders([denied_tx.initiator_account()]);
+    let mut mempool = mempool.with_transaction_filter(Arc::new(filter));
+    let (io_cursor, _) = mempool.initialize().await.unwrap();
+    mempool
+        .wait_for_new_batch_params(&io_cursor, Duration::from_secs(10))
+        .await
+        .unwrap()
+        .expect("no batch params");
+
+    let tx = mempool
+        .wait_for_next_tx(Duration::from_millis(100))
+        .await
+        .unwrap();
+    assert!(tx.is_none(), "{tx:?}");
+
+    let allowed_tx = tester.insert_tx(&mut guard, tx_filter.fee_per_gas, tx_filter.gas_per_pubdata);
+    let tx = mempool
+        .wait_for_next_tx(Duration::from_millis(100))
+        .await
+        .unwrap()
+        .expect("no transaction");
+    assert_eq!(tx.hash(), allowed_tx.hash());
+}
+
+#[tokio::test]
+async fn fee_accounts_are_rotated_for_new_batches() {
+    let connection_pool = ConnectionPool::<Core>::constrained_test_pool(1).await;
+    let tester = Tester::new(L1BatchCommitmentMode::Rollup);
+    tester.genesis(&connection_pool).await;
+    let (mempool, mut guard) = tester.create_test_mempool_io(connection_pool).await;
+    let extra_fee_accounts = [Address::repeat_byte(0xaa), Address::repeat_byte(0xbb)];
+    let mut mempool = mempool.with_extra_fee_accounts(extra_fee_accounts);
+
+    let tx_filter = l2_tx_filter(
+        &tester.create_batch_fee_input_provider().await,
+        ProtocolVersionId::latest().into(),
+    )
+    .await
+    .unwrap();
+    tester.insert_tx(&mut guard, tx_filter.fee_per_gas, tx_filter.gas_per_pubdata);
+
+    let (io_cursor, _) = mempool.initialize().await.unwrap();
+    assert_eq!(io_cursor.l1_batch, L1BatchNumber(1));
+    let l1_batch_params = mempool
+        .wait_for_new_batch_params(&io_cursor, Duration::from_secs(10))
+        .await
+        .unwrap()
+        .expect("no batch params");
+    // Accounts are rotated round-robin by default: the primary account is used for batches with numbers
+    // divisible by 3, the 1st extra account for batch #1 etc.
+    assert_eq!(l1_batch_params.operator_address, extra_fee_accounts[0]);
+}
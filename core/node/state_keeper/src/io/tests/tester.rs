@@ -1,6 +1,6 @@
 //! Testing harness for the IO.
 
-use std::{slice, sync::Arc, time::Duration};
+use std::{collections::HashMap, slice, sync::Arc, time::Duration};
 
 use multivm::vm_latest::constants::BATCH_COMPUTATIONAL_GAS_LIMIT;
 use zksync_config::{
@@ -8,7 +8,7 @@ use zksync_config::{
     GasAdjusterConfig,
 };
 use zksync_contracts::BaseSystemContracts;
-use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_dal::{transactions_dal::CallTraceFormat, ConnectionPool, Core, CoreDal};
 use zksync_eth_client::clients::MockEthereum;
 use zksync_node_fee_model::{l1_gas_price::GasAdjuster, MainNodeFeeInputProvider};
 use zksync_node_genesis::create_genesis_l1_batch;
@@ -16,6 +16,7 @@ use zksync_node_test_utils::{
     create_l1_batch, create_l2_block, create_l2_transaction, execute_l2_transaction,
 };
 use zksync_types::{
+    api::TransactionDeadline,
     block::L2BlockHeader,
     commitment::L1BatchCommitmentMode,
     fee::TransactionExecutionMetrics,
@@ -91,9 +92,27 @@ impl Tester {
         100
     }
 
+    pub(super) fn state_keeper_config(&self) -> StateKeeperConfig {
+        StateKeeperConfig {
+            minimal_l2_gas_price: self.minimal_l2_gas_price(),
+            validation_computational_gas_limit: BATCH_COMPUTATIONAL_GAS_LIMIT,
+            max_timestamp_drift_sec: Some(60),
+            ..StateKeeperConfig::for_tests()
+        }
+    }
+
     pub(super) async fn create_test_mempool_io(
         &self,
         pool: ConnectionPool<Core>,
+    ) -> (MempoolIO, MempoolGuard) {
+        self.create_test_mempool_io_with_config(pool, &self.state_keeper_config())
+            .await
+    }
+
+    pub(super) async fn create_test_mempool_io_with_config(
+        &self,
+        pool: ConnectionPool<Core>,
+        config: &StateKeeperConfig,
     ) -> (MempoolIO, MempoolGuard) {
         let gas_adjuster = Arc::new(self.create_gas_adjuster().await);
         let batch_fee_input_provider = MainNodeFeeInputProvider::new(
@@ -104,17 +123,12 @@ impl Tester {
         );
 
         let mempool = MempoolGuard::new(PriorityOpId(0), 100);
-        let config = StateKeeperConfig {
-            minimal_l2_gas_price: self.minimal_l2_gas_price(),
-            validation_computational_gas_limit: BATCH_COMPUTATIONAL_GAS_LIMIT,
-            ..StateKeeperConfig::for_tests()
-        };
         let wallets = Wallets::for_tests();
         let io = MempoolIO::new(
             mempool.clone(),
             Arc::new(batch_fee_input_provider),
             pool,
-            &config,
+            config,
             wallets.state_keeper.unwrap().fee_account.address(),
             Duration::from_secs(1),
             L2ChainId::from(270),
@@ -180,6 +194,7 @@ impl Tester {
                 slice::from_ref(&tx_result),
                 1.into(),
                 ProtocolVersionId::latest(),
+                CallTraceFormat::Legacy,
                 false,
             )
             .await
@@ -227,4 +242,17 @@ impl Tester {
         guard.insert(vec![tx.clone().into()], Default::default());
         tx
     }
+
+    pub(super) fn insert_tx_with_deadline(
+        &self,
+        guard: &mut MempoolGuard,
+        fee_per_gas: u64,
+        gas_per_pubdata: u32,
+        deadline: TransactionDeadline,
+    ) -> L2Tx {
+        let tx = create_l2_transaction(fee_per_gas, gas_per_pubdata.into());
+        guard.insert_deadlines(HashMap::from([(tx.hash(), deadline)]));
+        guard.insert(vec![tx.clone().into()], Default::default());
+        tx
+    }
 }
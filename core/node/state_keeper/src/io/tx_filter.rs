@@ -0,0 +1,125 @@
+//! Filtering of transactions at sequencing time.
+
+use std::{collections::HashSet, fmt};
+
+use async_trait::async_trait;
+use zksync_types::{Address, Transaction};
+
+/// Decision of a [`TransactionFilter`] regarding a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxFilterDecision {
+    /// Transaction may be included into a batch.
+    Allow,
+    /// Transaction must not be included into a batch and should be rejected with the specified reason.
+    Deny(String),
+}
+
+/// Filter for L2 transactions called by the [`MempoolIO`](super::mempool::MempoolIO) before a transaction
+/// is included into a batch. Can be used e.g. to implement sender / contract allowlists for permissioned chains.
+///
+/// Denied transactions are rejected (i.e., removed from the mempool and marked as rejected in Postgres).
+/// L1 transactions cannot be rejected and are thus never passed to filters.
+#[async_trait]
+pub trait TransactionFilter: 'static + Send + Sync + fmt::Debug {
+    /// Decides whether the transaction may be included into a batch. Errors are treated as fatal
+    /// for the state keeper.
+    async fn filter(&self, tx: &Transaction) -> anyhow::Result<TxFilterDecision>;
+}
+
+/// [`TransactionFilter`] based on static lists of transaction senders and target contracts.
+///
+/// A transaction is allowed if its sender and target are not denylisted, and if they are present
+/// in the corresponding allowlists (if the allowlists are set).
+#[derive(Debug, Default)]
+pub struct AddressTransactionFilter {
+    allowed_senders: Option<HashSet<Address>>,
+    denied_senders: HashSet<Address>,
+    allowed_contracts: Option<HashSet<Address>>,
+    denied_contracts: HashSet<Address>,
+}
+
+impl AddressTransactionFilter {
+    /// Only allows transactions from the specified senders.
+    pub fn with_allowed_senders(mut self, senders: impl IntoIterator<Item = Address>) -> Self {
+        self.allowed_senders
+            .get_or_insert_with(HashSet::new)
+            .extend(senders);
+        self
+    }
+
+    /// Denies transactions from the specified senders.
+    pub fn with_denied_senders(mut self, senders: impl IntoIterator<Item = Address>) -> Self {
+        self.denied_senders.extend(senders);
+        self
+    }
+
+    /// Only allows transactions targeting the specified contracts.
+    pub fn with_allowed_contracts(mut self, contracts: impl IntoIterator<Item = Address>) -> Self {
+        self.allowed_contracts
+            .get_or_insert_with(HashSet::new)
+            .extend(contracts);
+        self
+    }
+
+    /// Denies transactions targeting the specified contracts.
+    pub fn with_denied_contracts(mut self, contracts: impl IntoIterator<Item = Address>) -> Self {
+        self.denied_contracts.extend(contracts);
+        self
+    }
+
+    fn decide(&self, sender: Address, target: Address) -> TxFilterDecision {
+        if self.denied_senders.contains(&sender) {
+            return TxFilterDecision::Deny(format!("sender {sender:?} is denylisted"));
+        }
+        if let Some(allowed_senders) = &self.allowed_senders {
+            if !allowed_senders.contains(&sender) {
+                return TxFilterDecision::Deny(format!("sender {sender:?} is not allowlisted"));
+            }
+        }
+        if self.denied_contracts.contains(&target) {
+            return TxFilterDecision::Deny(format!("contract {target:?} is denylisted"));
+        }
+        if let Some(allowed_contracts) = &self.allowed_contracts {
+            if !allowed_contracts.contains(&target) {
+                return TxFilterDecision::Deny(format!("contract {target:?} is not allowlisted"));
+            }
+        }
+        TxFilterDecision::Allow
+    }
+}
+
+#[async_trait]
+impl TransactionFilter for AddressTransactionFilter {
+    async fn filter(&self, tx: &Transaction) -> anyhow::Result<TxFilterDecision> {
+        Ok(self.decide(tx.initiator_account(), tx.recipient_account()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn address_filter_decisions() {
+        let [alice, bob, token, dex] = [1, 2, 3, 4].map(Address::repeat_byte);
+
+        let filter = AddressTransactionFilter::default();
+        assert_eq!(filter.decide(alice, token), TxFilterDecision::Allow);
+
+        let filter = AddressTransactionFilter::default()
+            .with_denied_senders([bob])
+            .with_denied_contracts([dex]);
+        assert_eq!(filter.decide(alice, token), TxFilterDecision::Allow);
+        assert_matches!(filter.decide(bob, token), TxFilterDecision::Deny(_));
+        assert_matches!(filter.decide(alice, dex), TxFilterDecision::Deny(_));
+
+        let filter = AddressTransactionFilter::default()
+            .with_allowed_senders([alice])
+            .with_allowed_contracts([token]);
+        assert_eq!(filter.decide(alice, token), TxFilterDecision::Allow);
+        assert_matches!(filter.decide(bob, token), TxFilterDecision::Deny(_));
+        assert_matches!(filter.decide(alice, dex), TxFilterDecision::Deny(_));
+    }
+}
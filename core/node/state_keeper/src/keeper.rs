@@ -7,6 +7,7 @@ use std::{
 use anyhow::Context as _;
 use multivm::interface::{Halt, L1BatchEnv, SystemEnv};
 use tokio::sync::watch;
+use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_state::ReadStorageFactory;
 use zksync_types::{
     block::L2BlockExecutionData, l2::TransactionType, protocol_upgrade::ProtocolUpgradeTx,
@@ -15,7 +16,9 @@ use zksync_types::{
 };
 
 use super::{
-    batch_executor::{BatchExecutor, BatchExecutorHandle, TxExecutionResult},
+    batch_executor::{
+        budget::BatchBudgetExceeded, BatchExecutor, BatchExecutorHandle, TxExecutionResult,
+    },
     io::{IoCursor, L1BatchParams, L2BlockParams, OutputHandler, PendingBatchData, StateKeeperIO},
     metrics::{AGGREGATION_METRICS, KEEPER_METRICS, L1_BATCH_METRICS},
     seal_criteria::{ConditionalSealer, SealData, SealResolution},
@@ -47,6 +50,33 @@ impl Error {
     }
 }
 
+/// Behavior of the state keeper upon receiving a stop signal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ShutdownMode {
+    /// Stop as soon as possible. The pending L2 block is discarded and, together with the rest
+    /// of the pending L1 batch, re-executed after the restart.
+    #[default]
+    Immediate,
+    /// Seal the current L2 block (if it's not empty) and flush all output handlers before stopping.
+    Graceful {
+        /// If set, the state keeper will additionally try to seal the current L1 batch, provided that
+        /// sealing can be started within this timeout.
+        l1_batch_seal_timeout: Option<Duration>,
+    },
+}
+
+impl ShutdownMode {
+    pub fn from_config(config: &StateKeeperConfig) -> Self {
+        if config.graceful_shutdown {
+            Self::Graceful {
+                l1_batch_seal_timeout: config.graceful_shutdown_l1_batch_seal_timeout(),
+            }
+        } else {
+            Self::Immediate
+        }
+    }
+}
+
 /// State keeper represents a logic layer of L1 batch / L2 block processing flow.
 /// It's responsible for taking all the data from the `StateKeeperIO`, feeding it into `BatchExecutor` objects
 /// and calling `SealManager` to decide whether an L2 block or L1 batch should be sealed.
@@ -64,6 +94,7 @@ pub struct ZkSyncStateKeeper {
     batch_executor_base: Box<dyn BatchExecutor>,
     sealer: Arc<dyn ConditionalSealer>,
     storage_factory: Arc<dyn ReadStorageFactory>,
+    shutdown_mode: ShutdownMode,
 }
 
 impl ZkSyncStateKeeper {
@@ -82,15 +113,31 @@ impl ZkSyncStateKeeper {
             output_handler,
             sealer,
             storage_factory,
+            shutdown_mode: ShutdownMode::default(),
         }
     }
 
+    /// Sets the behavior of the state keeper upon receiving a stop signal. By default, the state keeper
+    /// stops immediately.
+    #[must_use]
+    pub fn with_shutdown_mode(mut self, shutdown_mode: ShutdownMode) -> Self {
+        self.shutdown_mode = shutdown_mode;
+        self
+    }
+
     pub async fn run(mut self) -> anyhow::Result<()> {
         match self.run_inner().await {
             Ok(_) => unreachable!(),
             Err(Error::Fatal(err)) => Err(err).context("state_keeper failed"),
             Err(Error::Canceled) => {
                 tracing::info!("Stop signal received, state keeper is shutting down");
+                if matches!(self.shutdown_mode, ShutdownMode::Graceful { .. }) {
+                    self.output_handler
+                        .flush()
+                        .await
+                        .context("failed flushing state keeper outputs on shutdown")?;
+                    tracing::info!("Flushed all state keeper outputs");
+                }
                 Ok(())
             }
         }
@@ -161,12 +208,25 @@ impl ZkSyncStateKeeper {
         let mut l1_batch_seal_delta: Option<Instant> = None;
         while !self.is_canceled() {
             // This function will run until the batch can be sealed.
-            self.process_l1_batch(
-                &mut batch_executor,
-                &mut updates_manager,
-                protocol_upgrade_tx,
-            )
-            .await?;
+            let process_result = self
+                .process_l1_batch(
+                    &mut batch_executor,
+                    &mut updates_manager,
+                    protocol_upgrade_tx,
+                )
+                .await;
+            if let (
+                Err(Error::Canceled),
+                ShutdownMode::Graceful {
+                    l1_batch_seal_timeout: Some(timeout),
+                },
+            ) = (&process_result, self.shutdown_mode)
+            {
+                self.seal_l1_batch_on_shutdown(batch_executor, updates_manager, timeout)
+                    .await?;
+                return Err(Error::Canceled);
+            }
+            process_result?;
 
             // Finish current batch.
             if !updates_manager.l2_block.executed_transactions.is_empty() {
@@ -275,6 +335,90 @@ impl ZkSyncStateKeeper {
         *self.stop_receiver.borrow()
     }
 
+    /// Seals the current L2 block on shutdown if graceful shutdown is enabled. The current L2 block must not be sealed yet.
+    async fn seal_l2_block_on_shutdown(
+        &mut self,
+        updates_manager: &UpdatesManager,
+    ) -> anyhow::Result<()> {
+        if self.shutdown_mode == ShutdownMode::Immediate
+            || updates_manager.l2_block.executed_transactions.is_empty()
+        {
+            return Ok(());
+        }
+        tracing::info!(
+            "Sealing L2 block #{} (L1 batch #{}) on shutdown",
+            updates_manager.l2_block.number,
+            updates_manager.l1_batch.number
+        );
+        self.seal_l2_block(updates_manager).await
+    }
+
+    /// Tries to seal the current L1 batch on shutdown. Expects that the current L2 block is either sealed,
+    /// or is empty and not sealed.
+    ///
+    /// The timeout only applies to preparing the batch for sealing (i.e., waiting for the fictive L2 block params
+    /// and finishing batch execution); once persistence is started, it runs to completion so that output handlers
+    /// are never interrupted midway.
+    async fn seal_l1_batch_on_shutdown(
+        &mut self,
+        mut batch_executor: BatchExecutorHandle,
+        mut updates_manager: UpdatesManager,
+        timeout: Duration,
+    ) -> Result<(), Error> {
+        let l1_batch_number = updates_manager.l1_batch.number;
+        if updates_manager.pending_executed_transactions_len() == 0 {
+            tracing::info!("L1 batch #{l1_batch_number} is empty; not sealing it on shutdown");
+            return Ok(());
+        }
+
+        tracing::info!(
+            "Trying to seal L1 batch #{l1_batch_number} on shutdown (timeout: {timeout:?})"
+        );
+        let started_at = Instant::now();
+        let prepare_batch = async {
+            if !updates_manager.l2_block.executed_transactions.is_empty() {
+                // The last L2 block is sealed, so we need to start the fictive L2 block. We cannot use
+                // `wait_for_new_l2_block_params()` since it returns immediately on cancellation.
+                let cursor = updates_manager.io_cursor();
+                let params = loop {
+                    if let Some(params) = self
+                        .io
+                        .wait_for_new_l2_block_params(&cursor, POLL_WAIT_DURATION)
+                        .await
+                        .context("error waiting for new L2 block params")?
+                    {
+                        Self::check_l2_block_timestamp(&cursor, params.timestamp)?;
+                        break params;
+                    }
+                };
+                Self::start_next_l2_block(params, &mut updates_manager, &mut batch_executor)
+                    .await?;
+            }
+            batch_executor.finish_batch().await
+        };
+        let finished_batch = match tokio::time::timeout(timeout, prepare_batch).await {
+            Ok(finished_batch) => finished_batch?,
+            Err(_) => {
+                tracing::info!(
+                    "L1 batch #{l1_batch_number} cannot be sealed within {timeout:?}; \
+                     it will be re-executed after the restart"
+                );
+                return Ok(());
+            }
+        };
+
+        updates_manager.finish_batch(finished_batch);
+        self.output_handler
+            .handle_l1_batch(Arc::new(updates_manager))
+            .await
+            .with_context(|| format!("failed sealing L1 batch #{l1_batch_number} on shutdown"))?;
+        tracing::info!(
+            "Sealed L1 batch #{l1_batch_number} on shutdown in {:?}",
+            started_at.elapsed()
+        );
+        Ok(())
+    }
+
     async fn load_upgrade_tx(
         &mut self,
         protocol_version: ProtocolVersionId,
@@ -295,6 +439,7 @@ impl ZkSyncStateKeeper {
                 .wait_for_new_batch_params(cursor, POLL_WAIT_DURATION)
                 .await?
             {
+                Self::check_l2_block_timestamp(cursor, params.first_l2_block.timestamp)?;
                 return Ok(params);
             }
         }
@@ -341,12 +486,27 @@ impl ZkSyncStateKeeper {
                 .await
                 .context("error waiting for new L2 block params")?
             {
+                Self::check_l2_block_timestamp(&cursor, params.timestamp)?;
                 return Ok(params);
             }
         }
         Err(Error::Canceled)
     }
 
+    /// Ensures that L2 block timestamps are strictly increasing, including across state keeper restarts
+    /// (the previous L2 block timestamp in `cursor` is loaded from storage on initialization).
+    /// Non-monotonic timestamps are rejected by the bootloader and would lead to L1 commitment failures.
+    fn check_l2_block_timestamp(cursor: &IoCursor, timestamp: u64) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            timestamp > cursor.prev_l2_block_timestamp,
+            "Timestamp {} provided by I/O for L2 block #{} is not greater than the previous L2 block timestamp {}",
+            display_timestamp(timestamp),
+            cursor.next_l2_block,
+            display_timestamp(cursor.prev_l2_block_timestamp)
+        );
+        Ok(())
+    }
+
     async fn start_next_l2_block(
         params: L2BlockParams,
         updates_manager: &mut UpdatesManager,
@@ -409,7 +569,7 @@ impl ZkSyncStateKeeper {
             );
             for tx in l2_block.txs {
                 let result = batch_executor
-                    .execute_tx(tx.clone())
+                    .reexecute_tx(tx.clone())
                     .await
                     .with_context(|| format!("failed re-executing transaction {:?}", tx.hash()))?;
 
@@ -543,6 +703,18 @@ impl ZkSyncStateKeeper {
                 .await?;
 
             match &seal_resolution {
+                SealResolution::NoSeal
+                    if matches!(exec_result, TxExecutionResult::BatchBudgetExceeded(_)) =>
+                {
+                    // The first transaction in the batch has exceeded the execution time budget. Execution time
+                    // depends on the machine load, so the transaction is returned to the mempool rather than rejected.
+                    batch_executor.rollback_last_tx().await.with_context(|| {
+                        format!("failed rolling back transaction {tx_hash:?} in batch executor")
+                    })?;
+                    self.io.rollback(tx).await.with_context(|| {
+                        format!("failed rolling back transaction {tx_hash:?} in I/O")
+                    })?;
+                }
                 SealResolution::NoSeal | SealResolution::IncludeAndSeal => {
                     let TxExecutionResult::Success {
                         tx_result,
@@ -597,6 +769,8 @@ impl ZkSyncStateKeeper {
                 return Ok(());
             }
         }
+        // The loop above is only exited on cancellation, in which case the current L2 block is not sealed.
+        self.seal_l2_block_on_shutdown(updates_manager).await?;
         Err(Error::Canceled)
     }
 
@@ -682,12 +856,18 @@ impl ZkSyncStateKeeper {
         // - `Halt::NotEnoughGasProvided`: there are checks in bootloader in some places (search for `checkEnoughGas` calls).
         //      They check if there is more gas in the frame than bootloader estimates it will need.
         //      This error is returned when such a check fails. Basically, bootloader doesn't continue execution but panics prematurely instead.
+        // `TxExecutionResult::BatchBudgetExceeded` is handled in the same way; it means that the batch has exceeded
+        // its resource budget (e.g., the execution time budget) because of the transaction.
         // If some transaction fails with any of these errors and is the first transaction in L1 batch, then it's marked as unexecutable.
         // Otherwise, `ExcludeAndSeal` resolution is returned, i.e. batch will be sealed and transaction will be included in the next L1 batch.
+        // The only exception is the first transaction exceeding the execution time budget: since execution time depends
+        // on the machine load, the transaction is returned to the mempool (`NoSeal` resolution), and the batch executor
+        // doesn't enforce the budget for the next first transaction in the batch.
 
         let is_first_tx = updates_manager.pending_executed_transactions_len() == 0;
         let resolution = match &exec_result {
             TxExecutionResult::BootloaderOutOfGasForTx
+            | TxExecutionResult::BatchBudgetExceeded(_)
             | TxExecutionResult::RejectedByVm {
                 reason: Halt::NotEnoughGasProvided,
             } => {
@@ -702,10 +882,21 @@ impl ZkSyncStateKeeper {
                         UnexecutableReason::NotEnoughGasProvided,
                         "not_enough_gas_provided_to_start_tx",
                     ),
+                    TxExecutionResult::BatchBudgetExceeded(_) => (
+                        UnexecutableReason::BatchBudgetExceeded,
+                        "batch_resource_budget",
+                    ),
                     _ => unreachable!(),
                 };
                 let resolution = if is_first_tx {
-                    SealResolution::Unexecutable(reason)
+                    if let TxExecutionResult::BatchBudgetExceeded(
+                        BatchBudgetExceeded::ExecutionTime { .. },
+                    ) = &exec_result
+                    {
+                        SealResolution::NoSeal
+                    } else {
+                        SealResolution::Unexecutable(reason)
+                    }
                 } else {
                     SealResolution::ExcludeAndSeal
                 };
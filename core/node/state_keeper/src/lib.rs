@@ -6,30 +6,39 @@ use zksync_config::configs::{
     wallets,
 };
 use zksync_dal::{ConnectionPool, Core};
+use zksync_health_check::AppHealthCheck;
 use zksync_node_fee_model::BatchFeeModelInputProvider;
-use zksync_types::L2ChainId;
+use zksync_types::{L2ChainId, H256};
 
 pub use self::{
     batch_executor::{
-        main_executor::MainBatchExecutor, BatchExecutor, BatchExecutorHandle, TxExecutionResult,
+        budget::{BatchBudgetExceeded, BatchResourceBudget},
+        hooks::{ExecutedTx, TxExecutionHook},
+        main_executor::MainBatchExecutor,
+        tracers::{BatchTracer, BatchTracerFactory, BatchTracerOutput, BatchVmStorage},
+        BatchExecutor, BatchExecutorHandle, TxExecutionResult,
     },
     io::{
-        mempool::MempoolIO, L2BlockParams, L2BlockSealerTask, OutputHandler, StateKeeperIO,
-        StateKeeperOutputHandler, StateKeeperPersistence, TreeWritesPersistence,
+        mempool::MempoolIO, AddressTransactionFilter, CompositeOutputHandler, HandlerFailurePolicy,
+        L1BatchMetadataPersistence, L1BatchMetadataSource, L2BlockParams, L2BlockSealerTask,
+        OutputHandler, StateKeeperIO, StateKeeperOutputHandler, StateKeeperPersistence,
+        StaticL1BatchMetadata, TransactionFilter, TreeWritesPersistence, TxFilterDecision,
     },
-    keeper::ZkSyncStateKeeper,
+    keeper::{ShutdownMode, ZkSyncStateKeeper},
     mempool_actor::MempoolFetcher,
     seal_criteria::SequencerSealer,
     state_keeper_storage::AsyncRocksdbCache,
     types::{ExecutionMetricsForCriteria, MempoolGuard},
-    updates::UpdatesManager,
+    updates::{PayloadSize, UpdatesManager},
 };
 
 mod batch_executor;
+mod channel;
 pub mod io;
 mod keeper;
 mod mempool_actor;
 pub mod metrics;
+pub mod replay;
 pub mod seal_criteria;
 mod state_keeper_storage;
 pub mod testonly;
@@ -45,14 +54,25 @@ pub async fn create_state_keeper(
     wallets: wallets::StateKeeper,
     async_cache: AsyncRocksdbCache,
     l2chain_id: L2ChainId,
+    evm_emulator_hash: Option<H256>,
     mempool_config: &MempoolConfig,
     pool: ConnectionPool<Core>,
     mempool: MempoolGuard,
     batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     output_handler: OutputHandler,
+    app_health: &AppHealthCheck,
     stop_receiver: watch::Receiver<bool>,
 ) -> ZkSyncStateKeeper {
-    let batch_executor_base = MainBatchExecutor::new(state_keeper_config.save_call_traces, false);
+    let mut batch_executor_base =
+        MainBatchExecutor::new(state_keeper_config.save_call_traces, false)
+            .with_traced_addresses(pool.clone())
+            .with_resource_budget(BatchResourceBudget::from_config(&state_keeper_config));
+    if let Some(size) = state_keeper_config.bytecode_compression_dictionary_size {
+        batch_executor_base = batch_executor_base.with_bytecode_compression_dictionary(size);
+    }
+    app_health
+        .insert_component(batch_executor_base.health_check())
+        .expect("Failed registering batch executor health check");
 
     let io = MempoolIO::new(
         mempool,
@@ -64,8 +84,16 @@ pub async fn create_state_keeper(
         l2chain_id,
     )
     .await
-    .expect("Failed initializing main node I/O for state keeper");
+    .expect("Failed initializing main node I/O for state keeper")
+    .with_extra_fee_accounts(
+        wallets
+            .extra_fee_accounts
+            .iter()
+            .map(wallets::AddressWallet::address),
+    )
+    .with_evm_emulator_hash(evm_emulator_hash);
 
+    let shutdown_mode = ShutdownMode::from_config(&state_keeper_config);
     let sealer = SequencerSealer::new(state_keeper_config);
 
     ZkSyncStateKeeper::new(
@@ -76,4 +104,5 @@ pub async fn create_state_keeper(
         Arc::new(sealer),
         Arc::new(async_cache),
     )
+    .with_shutdown_mode(shutdown_mode)
 }
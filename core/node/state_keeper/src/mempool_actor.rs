@@ -1,4 +1,8 @@
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
 use multivm::utils::derive_base_fee_and_gas_per_pubdata;
@@ -11,9 +15,14 @@ use zksync_mempool::L2TxFilter;
 use zksync_node_fee_model::BatchFeeModelInputProvider;
 #[cfg(test)]
 use zksync_types::H256;
-use zksync_types::{get_nonce_key, Address, Nonce, Transaction, VmVersion};
+use zksync_types::{
+    fee_model::BatchFeeInput, get_nonce_key, Address, Nonce, Transaction, VmVersion,
+};
 
-use super::{metrics::KEEPER_METRICS, types::MempoolGuard};
+use super::{metrics::KEEPER_METRICS, seal_criteria::UnexecutableReason, types::MempoolGuard};
+
+/// Interval between sweeps expiring transactions with overdue inclusion deadlines.
+const DEADLINES_SWEEP_INTERVAL: Duration = Duration::from_secs(10);
 
 /// Creates a mempool filter for L2 transactions based on the current L1 gas price.
 /// The filter is used to filter out transactions from the mempool that do not cover expenses
@@ -23,12 +32,19 @@ pub async fn l2_tx_filter(
     vm_version: VmVersion,
 ) -> anyhow::Result<L2TxFilter> {
     let fee_input = batch_fee_input_provider.get_batch_fee_input().await?;
+    Ok(l2_tx_filter_for_fee_input(fee_input, vm_version))
+}
+
+pub(crate) fn l2_tx_filter_for_fee_input(
+    fee_input: BatchFeeInput,
+    vm_version: VmVersion,
+) -> L2TxFilter {
     let (base_fee, gas_per_pubdata) = derive_base_fee_and_gas_per_pubdata(fee_input, vm_version);
-    Ok(L2TxFilter {
+    L2TxFilter {
         fee_input,
         fee_per_gas: base_fee,
         gas_per_pubdata: gas_per_pubdata as u32,
-    })
+    }
 }
 
 #[derive(Debug)]
@@ -39,6 +55,8 @@ pub struct MempoolFetcher {
     sync_interval: Duration,
     sync_batch_size: usize,
     stuck_tx_timeout: Option<Duration>,
+    deadlines_retention: Duration,
+    last_deadlines_sweep: Option<Instant>,
     #[cfg(test)]
     transaction_hashes_sender: mpsc::UnboundedSender<Vec<H256>>,
 }
@@ -57,6 +75,8 @@ impl MempoolFetcher {
             sync_interval: config.sync_interval(),
             sync_batch_size: config.sync_batch_size,
             stuck_tx_timeout: config.remove_stuck_txs.then(|| config.stuck_tx_timeout()),
+            deadlines_retention: config.deadlines_retention(),
+            last_deadlines_sweep: None,
             #[cfg(test)]
             transaction_hashes_sender: mpsc::unbounded_channel().0,
         }
@@ -82,6 +102,13 @@ impl MempoolFetcher {
             }
             let latency = KEEPER_METRICS.mempool_sync.start();
             let mut storage = self.pool.connection_tagged("state_keeper").await?;
+            let should_sweep_deadlines = self.last_deadlines_sweep.map_or(true, |swept_at| {
+                swept_at.elapsed() >= DEADLINES_SWEEP_INTERVAL
+            });
+            if should_sweep_deadlines {
+                self.sweep_deadlines(&mut storage).await?;
+                self.last_deadlines_sweep = Some(Instant::now());
+            }
             let mempool_info = self.mempool.get_mempool_info();
             let protocol_version = storage
                 .blocks_dal()
@@ -108,14 +135,26 @@ impl MempoolFetcher {
                 .await
                 .context("failed syncing mempool")?;
             let nonces = get_transaction_nonces(&mut storage, &transactions).await?;
+            let transaction_hashes: Vec<_> = transactions.iter().map(Transaction::hash).collect();
+            let deadlines = storage
+                .transaction_deadlines_dal()
+                .get_pending_deadlines(&transaction_hashes)
+                .await
+                .context("failed loading transaction deadlines")?;
+            let boosted_transactions = storage
+                .boosted_transactions_dal()
+                .get_boosted_transactions(&transaction_hashes)
+                .await
+                .context("failed loading boosted transactions")?;
             drop(storage);
 
             #[cfg(test)]
             {
-                let transaction_hashes = transactions.iter().map(Transaction::hash).collect();
                 self.transaction_hashes_sender.send(transaction_hashes).ok();
             }
             let all_transactions_loaded = transactions.len() < self.sync_batch_size;
+            self.mempool.insert_deadlines(deadlines);
+            self.mempool.boost_transactions(boosted_transactions);
             self.mempool.insert(transactions, nonces);
             latency.observe();
 
@@ -125,6 +164,44 @@ impl MempoolFetcher {
         }
         Ok(())
     }
+
+    /// Expires pending transactions which inclusion deadlines have passed, and prunes deadlines
+    /// that are no longer needed both from the storage and from the mempool.
+    ///
+    /// Expiration is based on the last *sealed* L2 block: all L2 blocks that can include transactions from now on
+    /// have greater numbers and not lesser timestamps, so expired transactions can never be included.
+    async fn sweep_deadlines(&mut self, storage: &mut Connection<'_, Core>) -> anyhow::Result<()> {
+        let last_l2_block = storage
+            .blocks_dal()
+            .get_last_sealed_l2_block_header()
+            .await
+            .context("failed getting last sealed L2 block")?;
+        let Some(last_l2_block) = last_l2_block else {
+            return Ok(());
+        };
+
+        let error = format!("rejected: {}", UnexecutableReason::DeadlineExpired);
+        let expired_tx_hashes = storage
+            .transaction_deadlines_dal()
+            .expire_pending_transactions(last_l2_block.number + 1, last_l2_block.timestamp, &error)
+            .await
+            .context("failed expiring pending transactions")?;
+        let pruned_count = storage
+            .transaction_deadlines_dal()
+            .prune_deadlines(self.deadlines_retention)
+            .await
+            .context("failed pruning transaction deadlines")?;
+        let pruned_in_memory_count = self.mempool.prune_deadlines();
+
+        if !expired_tx_hashes.is_empty() || pruned_count > 0 || pruned_in_memory_count > 0 {
+            tracing::info!(
+                "Expired {} transactions with overdue inclusion deadlines: {expired_tx_hashes:?}; \
+                 pruned {pruned_count} stored and {pruned_in_memory_count} in-memory deadlines",
+                expired_tx_hashes.len()
+            );
+        }
+        Ok(())
+    }
 }
 
 /// Loads nonces for all distinct `transactions` initiators from the storage.
@@ -162,21 +239,31 @@ mod tests {
     use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
     use zksync_node_test_utils::create_l2_transaction;
     use zksync_types::{
-        fee::TransactionExecutionMetrics, L2BlockNumber, PriorityOpId, ProtocolVersionId,
-        StorageLog, H256,
+        api::{TransactionDeadline, TransactionExpiryStatus},
+        fee::TransactionExecutionMetrics,
+        L2BlockNumber, PriorityOpId, ProtocolVersionId, StorageLog, H256,
     };
     use zksync_utils::u256_to_h256;
 
     use super::*;
 
-    const TEST_MEMPOOL_CONFIG: MempoolConfig = MempoolConfig {
-        sync_interval_ms: 10,
-        sync_batch_size: 100,
-        capacity: 100,
-        stuck_tx_timeout: 0,
-        remove_stuck_txs: false,
-        delay_interval: 10,
-    };
+    fn test_mempool_config() -> MempoolConfig {
+        MempoolConfig {
+            sync_interval_ms: 10,
+            sync_batch_size: 100,
+            capacity: 100,
+            stuck_tx_timeout: 0,
+            remove_stuck_txs: false,
+            delay_interval: 10,
+            priority_fee_ordering: false,
+            priority_fee_ordering_window_ms: MempoolConfig::default_priority_fee_ordering_window_ms(
+            ),
+            priority_fee_ordering_max_fee: MempoolConfig::default_priority_fee_ordering_max_fee(),
+            priority_lane_addresses: Vec::new(),
+            priority_lane_capacity: 1_000,
+            deadlines_retention_sec: 0,
+        }
+    }
 
     #[tokio::test]
     async fn getting_transaction_nonces() {
@@ -234,7 +321,7 @@ mod tests {
         let mut fetcher = MempoolFetcher::new(
             mempool.clone(),
             fee_params_provider,
-            &TEST_MEMPOOL_CONFIG,
+            &test_mempool_config(),
             pool.clone(),
         );
         let (tx_hashes_sender, mut tx_hashes_receiver) = mpsc::unbounded_channel();
@@ -262,6 +349,161 @@ mod tests {
         fetcher_task.await.unwrap().expect("fetcher errored");
     }
 
+    #[tokio::test]
+    async fn syncing_transaction_deadlines() {
+        let pool = ConnectionPool::constrained_test_pool(1).await;
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+        drop(storage);
+
+        let mut mempool = MempoolGuard::new(PriorityOpId(0), 100);
+        let fee_params_provider: Arc<dyn BatchFeeModelInputProvider> =
+            Arc::new(MockBatchFeeParamsProvider::default());
+        let fee_input = fee_params_provider.get_batch_fee_input().await.unwrap();
+        let (base_fee, gas_per_pubdata) =
+            derive_base_fee_and_gas_per_pubdata(fee_input, ProtocolVersionId::latest().into());
+
+        let mut fetcher = MempoolFetcher::new(
+            mempool.clone(),
+            fee_params_provider,
+            &test_mempool_config(),
+            pool.clone(),
+        );
+        let (tx_hashes_sender, mut tx_hashes_receiver) = mpsc::unbounded_channel();
+        fetcher.transaction_hashes_sender = tx_hashes_sender;
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let fetcher_task = tokio::spawn(fetcher.run(stop_receiver));
+
+        let transaction = create_l2_transaction(base_fee, gas_per_pubdata);
+        let transaction_hash = transaction.hash();
+        let deadline = TransactionDeadline {
+            not_after_l2_block: Some(L2BlockNumber(10)),
+            not_after_timestamp: None,
+        };
+        let mut storage = pool.connection().await.unwrap();
+        storage
+            .transaction_deadlines_dal()
+            .insert_deadline(transaction_hash, &deadline)
+            .await
+            .unwrap();
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(&transaction, TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+        drop(storage);
+
+        let tx_hashes = wait_for_new_transactions(&mut tx_hashes_receiver).await;
+        assert_eq!(tx_hashes, [transaction_hash]);
+        assert_eq!(mempool.take_deadline(transaction_hash), Some(deadline));
+
+        stop_sender.send_replace(true);
+        fetcher_task.await.unwrap().expect("fetcher errored");
+    }
+
+    #[tokio::test]
+    async fn expiring_overdue_transactions() {
+        let pool = ConnectionPool::constrained_test_pool(1).await;
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+
+        let mempool = MempoolGuard::new(PriorityOpId(0), 100);
+        let fee_params_provider: Arc<dyn BatchFeeModelInputProvider> =
+            Arc::new(MockBatchFeeParamsProvider::default());
+        let fee_input = fee_params_provider.get_batch_fee_input().await.unwrap();
+        let (base_fee, gas_per_pubdata) =
+            derive_base_fee_and_gas_per_pubdata(fee_input, ProtocolVersionId::latest().into());
+
+        // The genesis L2 block is sealed, so the first deadline has already passed.
+        let deadlines = [L2BlockNumber(0), L2BlockNumber(10)];
+        let transactions = deadlines.map(|not_after_l2_block| {
+            let transaction = create_l2_transaction(base_fee, gas_per_pubdata);
+            let deadline = TransactionDeadline {
+                not_after_l2_block: Some(not_after_l2_block),
+                not_after_timestamp: None,
+            };
+            (transaction, deadline)
+        });
+        for (transaction, deadline) in &transactions {
+            storage
+                .transaction_deadlines_dal()
+                .insert_deadline(transaction.hash(), deadline)
+                .await
+                .unwrap();
+            storage
+                .transactions_dal()
+                .insert_transaction_l2(transaction, TransactionExecutionMetrics::default())
+                .await
+                .unwrap();
+        }
+        drop(storage);
+        let expired_tx_hash = transactions[0].0.hash();
+        let pending_tx_hash = transactions[1].0.hash();
+
+        let mut fetcher = MempoolFetcher::new(
+            mempool.clone(),
+            fee_params_provider,
+            &test_mempool_config(),
+            pool.clone(),
+        );
+        let (tx_hashes_sender, mut tx_hashes_receiver) = mpsc::unbounded_channel();
+        fetcher.transaction_hashes_sender = tx_hashes_sender;
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let fetcher_task = tokio::spawn(fetcher.run(stop_receiver));
+
+        // The expired transaction must not be loaded into the mempool.
+        let tx_hashes = wait_for_new_transactions(&mut tx_hashes_receiver).await;
+        assert_eq!(tx_hashes, [pending_tx_hash]);
+        assert_eq!(mempool.stats().l2_transaction_count, 1);
+        stop_sender.send_replace(true);
+        fetcher_task.await.unwrap().expect("fetcher errored");
+
+        let mut storage = pool.connection().await.unwrap();
+        let expiry = storage
+            .transaction_deadlines_dal()
+            .get_transaction_expiry(expired_tx_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(expiry.status, TransactionExpiryStatus::Expired);
+        let expiry = storage
+            .transaction_deadlines_dal()
+            .get_transaction_expiry(pending_tx_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(expiry.status, TransactionExpiryStatus::Pending);
+    }
+
+    #[test]
+    fn pruning_in_memory_deadlines() {
+        let mut mempool = MempoolGuard::new(PriorityOpId(0), 100);
+        let transaction = create_l2_transaction(10, 100);
+        let transaction_hash = transaction.hash();
+        let transaction_initiator = transaction.initiator_account();
+        let deadline = TransactionDeadline {
+            not_after_l2_block: Some(L2BlockNumber(10)),
+            not_after_timestamp: None,
+        };
+        let missing_tx_hash = H256::repeat_byte(1);
+        mempool.insert_deadlines(HashMap::from([
+            (transaction_hash, deadline),
+            (missing_tx_hash, deadline),
+        ]));
+        mempool.insert(
+            vec![transaction.into()],
+            HashMap::from([(transaction_initiator, Nonce(0))]),
+        );
+
+        assert_eq!(mempool.prune_deadlines(), 1);
+        assert_eq!(mempool.take_deadline(missing_tx_hash), None);
+        assert_eq!(mempool.take_deadline(transaction_hash), Some(deadline));
+    }
+
     async fn wait_for_new_transactions(
         tx_hashes_receiver: &mut mpsc::UnboundedReceiver<Vec<H256>>,
     ) -> Vec<H256> {
@@ -293,7 +535,7 @@ mod tests {
         let fetcher = MempoolFetcher::new(
             mempool.clone(),
             fee_params_provider,
-            &TEST_MEMPOOL_CONFIG,
+            &test_mempool_config(),
             pool.clone(),
         );
         let (stop_sender, stop_receiver) = watch::channel(false);
@@ -309,7 +551,7 @@ mod tests {
             .unwrap();
         drop(storage);
 
-        tokio::time::sleep(TEST_MEMPOOL_CONFIG.sync_interval() * 5).await;
+        tokio::time::sleep(test_mempool_config().sync_interval() * 5).await;
         assert_eq!(mempool.stats().l2_transaction_count, 0);
 
         stop_sender.send_replace(true);
@@ -335,7 +577,7 @@ mod tests {
         let mut fetcher = MempoolFetcher::new(
             mempool.clone(),
             fee_params_provider,
-            &TEST_MEMPOOL_CONFIG,
+            &test_mempool_config(),
             pool.clone(),
         );
         let (tx_hashes_sender, mut tx_hashes_receiver) = mpsc::unbounded_channel();
@@ -7,8 +7,8 @@ use std::{
 
 use multivm::interface::{VmExecutionResultAndLogs, VmRevertReason};
 use vise::{
-    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver,
-    Metrics,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
+    LatencyObserver, Metrics, Unit,
 };
 use zksync_mempool::MempoolStore;
 use zksync_shared_metrics::InteractionType;
@@ -142,6 +142,8 @@ pub(super) struct StateKeeperGauges {
     mempool_l2_size: Gauge<u64>,
     /// Current size of the L2 priority queue.
     l2_priority_queue_size: Gauge<usize>,
+    /// Current size of the L2 priority lane.
+    l2_priority_lane_size: Gauge<usize>,
 }
 
 impl StateKeeperGauges {
@@ -161,6 +163,9 @@ impl StateKeeperGauges {
                     .l2_priority_queue_size
                     .set(stats.l2_priority_queue_size);
                 gauges
+                    .l2_priority_lane_size
+                    .set(stats.l2_priority_lane_size);
+                gauges
             })
         });
         if res.is_err() {
@@ -201,6 +206,10 @@ struct TxAggregationLabels {
 #[metrics(prefix = "server_tx_aggregation")]
 pub(super) struct TxAggregationMetrics {
     reason: Family<TxAggregationLabels, Counter>,
+    /// Filled share of the L1 batch capacity tracked by a certain seal criterion for the currently open batch.
+    /// Values `>= 1` mean that the criterion seals the batch.
+    #[metrics(labels = ["criterion"])]
+    pub capacity_filled: LabeledFamily<&'static str, Gauge<f64>>,
 }
 
 impl TxAggregationMetrics {
@@ -438,6 +447,19 @@ pub(super) struct ExecutorMetrics {
     pub computational_gas_per_nanosecond: Histogram<f64>,
     #[metrics(buckets = GAS_PER_NANOSECOND_BUCKETS)]
     pub failed_tx_gas_limit_per_nanosecond: Histogram<f64>,
+    /// Total length of original bytecodes published in the compressed form.
+    #[metrics(unit = Unit::Bytes)]
+    pub compressed_bytecodes_original_len: Counter,
+    /// Total length of compressed bytecodes. Together with `compressed_bytecodes_original_len`,
+    /// allows estimating pubdata savings from bytecode compression.
+    #[metrics(unit = Unit::Bytes)]
+    pub compressed_bytecodes_len: Counter,
+    /// Total length of published bytecodes compressed with the dictionary shared across L1 batches
+    /// (only measured if the shared dictionary is enabled).
+    #[metrics(unit = Unit::Bytes)]
+    pub dictionary_compressed_bytecodes_len: Counter,
+    /// Number of chunks in the bytecode dictionary shared across L1 batches.
+    pub bytecode_dictionary_len: Gauge<u64>,
 }
 
 #[vise::register]
@@ -493,3 +515,40 @@ impl BatchTipMetrics {
 
 #[vise::register]
 pub(crate) static BATCH_TIP_METRICS: vise::Global<BatchTipMetrics> = vise::Global::new();
+
+/// Channel between state keeper stages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "channel", rename_all = "snake_case")]
+pub(crate) enum StageChannel {
+    /// Commands sent by the state keeper to the batch executor.
+    BatchExecutorCommands,
+    /// L2 block seal commands sent by the persistence output handler to the L2 block sealer.
+    L2BlockSealCommands,
+}
+
+/// Standardized metrics for bounded channels between state keeper stages.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_state_keeper_channel")]
+pub(crate) struct ChannelMetrics {
+    /// Maximum number of messages that can be buffered in the channel.
+    pub capacity: Family<StageChannel, Gauge<usize>>,
+    /// Current number of buffered messages in the channel.
+    pub depth: Family<StageChannel, Gauge<usize>>,
+    /// Ratio of buffered messages to the channel capacity.
+    pub saturation: Family<StageChannel, Gauge<f64>>,
+    /// Number of sends that were blocked because the channel was full.
+    pub blocked_sends: Family<StageChannel, Counter>,
+    /// Time spent waiting for free capacity in the channel by blocked sends.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub send_blocked_time: Family<StageChannel, Histogram<Duration>>,
+}
+
+impl ChannelMetrics {
+    pub(crate) fn report_depth(&self, channel: StageChannel, depth: usize, capacity: usize) {
+        self.depth[&channel].set(depth);
+        self.saturation[&channel].set(depth as f64 / capacity as f64);
+    }
+}
+
+#[vise::register]
+pub(crate) static CHANNEL_METRICS: vise::Global<ChannelMetrics> = vise::Global::new();
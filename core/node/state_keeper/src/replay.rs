@@ -0,0 +1,875 @@
+//! Deterministic replay of sealed L1 batches with the state keeper.
+//!
+//! The replay reconstructs the environment and the transaction stream of an L1 batch from Postgres, feeds them
+//! to the state keeper and checks whether the state keeper produces the same L2 blocks and seals the batch after
+//! the same transaction. This allows validating changes to the seal criteria against the chain history.
+//!
+//! L2 block boundaries are taken from the recorded history since they depend on the wall clock. L1 batch boundaries
+//! are decided by the [`ConditionalSealer`] under test: transactions from the following sealed batches are fed
+//! to the state keeper after the replayed batch's transactions (see [`L1BatchReplayer::with_lookahead_batches()`]),
+//! so that a sealer accommodating more transactions per batch is detected as well. Consequently, batches sealed
+//! unconditionally (e.g., by a timeout) are reported as diverging unless the lookahead is disabled, in which case
+//! the replayed batch is sealed unconditionally after its last transaction.
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use multivm::interface::{L1BatchEnv, SystemEnv};
+use tokio::sync::watch;
+use vm_utils::storage::L1BatchParamsProvider;
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_contracts::BaseSystemContracts;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_state::{PgOrRocksdbStorage, ReadStorageFactory};
+use zksync_types::{
+    block::L2BlockExecutionData, l2::TransactionType, protocol_upgrade::ProtocolUpgradeTx,
+    L1BatchNumber, L2BlockNumber, L2ChainId, ProtocolVersionId, Transaction, H256,
+};
+
+use crate::{
+    io::{
+        IoCursor, L1BatchParams, L2BlockParams, OutputHandler, PendingBatchData, StateKeeperIO,
+        StateKeeperOutputHandler,
+    },
+    seal_criteria::{ConditionalSealer, IoSealCriteria, SequencerSealer, UnexecutableReason},
+    updates::UpdatesManager,
+    MainBatchExecutor, ZkSyncStateKeeper,
+};
+
+/// L2 block produced during the replay or recorded in Postgres.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayedL2Block {
+    pub number: L2BlockNumber,
+    pub tx_hashes: Vec<H256>,
+}
+
+/// Outcome of replaying an L1 batch.
+#[derive(Debug)]
+pub struct L1BatchReplay {
+    pub l1_batch_number: L1BatchNumber,
+    /// Non-fictive L2 blocks of the batch as recorded in Postgres.
+    pub expected_l2_blocks: Vec<ReplayedL2Block>,
+    /// Non-fictive L2 blocks of the batch as produced by the state keeper during the replay.
+    pub actual_l2_blocks: Vec<ReplayedL2Block>,
+    /// Transactions rejected by the state keeper during the replay together with the rejection reasons.
+    pub rejected_txs: Vec<(H256, String)>,
+}
+
+impl L1BatchReplay {
+    /// Checks whether the replay produced the same L2 blocks and L1 batch seal as recorded in Postgres.
+    pub fn is_identical(&self) -> bool {
+        self.rejected_txs.is_empty() && self.expected_l2_blocks == self.actual_l2_blocks
+    }
+
+    /// Returns an error describing the first divergence between the replay and the recorded batch, if any.
+    pub fn ensure_identical(&self) -> anyhow::Result<()> {
+        let l1_batch_number = self.l1_batch_number;
+        if let Some((tx_hash, reason)) = self.rejected_txs.first() {
+            anyhow::bail!(
+                "Transaction {tx_hash:?} from L1 batch #{l1_batch_number} was rejected during replay: {reason}"
+            );
+        }
+
+        let blocks = self.expected_l2_blocks.iter().zip(&self.actual_l2_blocks);
+        for (expected, actual) in blocks {
+            anyhow::ensure!(
+                expected == actual,
+                "L2 block #{} in L1 batch #{l1_batch_number} diverges on replay: expected transactions {:?}, \
+                 got {:?}",
+                expected.number,
+                expected.tx_hashes,
+                actual.tx_hashes
+            );
+        }
+        anyhow::ensure!(
+            self.expected_l2_blocks.len() == self.actual_l2_blocks.len(),
+            "L1 batch #{l1_batch_number} is sealed after {} L2 blocks on replay, while {} L2 blocks are expected",
+            self.actual_l2_blocks.len(),
+            self.expected_l2_blocks.len()
+        );
+        Ok(())
+    }
+}
+
+/// Replays sealed L1 batches with the state keeper. The state before the batch is read from Postgres,
+/// so the replayed batches must not be pruned.
+#[derive(Debug)]
+pub struct L1BatchReplayer {
+    pool: ConnectionPool<Core>,
+    sealer: Arc<dyn ConditionalSealer>,
+    validation_computational_gas_limit: u32,
+    chain_id: L2ChainId,
+    evm_emulator_hash: Option<H256>,
+    lookahead_batches: u32,
+}
+
+impl L1BatchReplayer {
+    const DEFAULT_LOOKAHEAD_BATCHES: u32 = 1;
+
+    /// Creates a replayer using the [`SequencerSealer`] configured from the provided config.
+    pub fn new(
+        pool: ConnectionPool<Core>,
+        config: &StateKeeperConfig,
+        chain_id: L2ChainId,
+    ) -> Self {
+        Self {
+            pool,
+            sealer: Arc::new(SequencerSealer::new(config.clone())),
+            validation_computational_gas_limit: config.validation_computational_gas_limit,
+            chain_id,
+            evm_emulator_hash: None,
+            lookahead_batches: Self::DEFAULT_LOOKAHEAD_BATCHES,
+        }
+    }
+
+    /// Sets the hash of the EVM emulator enabled for the chain (usually, taken from the genesis config).
+    #[must_use]
+    pub fn with_evm_emulator_hash(mut self, evm_emulator_hash: Option<H256>) -> Self {
+        self.evm_emulator_hash = evm_emulator_hash;
+        self
+    }
+
+    /// Sets the sealer used during the replay, e.g. one with updated seal criteria.
+    #[must_use]
+    pub fn with_sealer(mut self, sealer: Arc<dyn ConditionalSealer>) -> Self {
+        self.sealer = sealer;
+        self
+    }
+
+    /// Sets the maximum number of sealed L1 batches following the replayed one, transactions from which are fed
+    /// to the state keeper once the replayed batch's transactions are exhausted. Batches with another protocol version
+    /// are never used. By default, a single batch is used. If set to 0, the replayed batch is sealed unconditionally
+    /// after its last transaction, so only earlier seals can be detected.
+    #[must_use]
+    pub fn with_lookahead_batches(mut self, count: u32) -> Self {
+        self.lookahead_batches = count;
+        self
+    }
+
+    /// Replays the specified sealed L1 batch.
+    pub async fn replay(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<L1BatchReplay> {
+        let io = ReplayIO::load(
+            &self.pool,
+            l1_batch_number,
+            self.validation_computational_gas_limit,
+            self.chain_id,
+            self.evm_emulator_hash,
+            self.lookahead_batches,
+        )
+        .await?;
+        let expected_l2_blocks = io.expected_l2_blocks();
+        let rejected_txs = io.rejected_txs.clone();
+
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let recorder = ReplayRecorder {
+            l2_blocks: Arc::default(),
+            stop_sender,
+        };
+        let actual_l2_blocks = recorder.l2_blocks.clone();
+        let state_keeper = ZkSyncStateKeeper::new(
+            stop_receiver,
+            Box::new(io),
+            Box::new(MainBatchExecutor::new(false, false)),
+            OutputHandler::new(Box::new(recorder)),
+            self.sealer.clone(),
+            Arc::new(PostgresStorageFactory(self.pool.clone())),
+        );
+        state_keeper
+            .run()
+            .await
+            .with_context(|| format!("failed replaying L1 batch #{l1_batch_number}"))?;
+
+        let actual_l2_blocks = std::mem::take(&mut *actual_l2_blocks.lock().unwrap());
+        let rejected_txs = std::mem::take(&mut *rejected_txs.lock().unwrap());
+        Ok(L1BatchReplay {
+            l1_batch_number,
+            expected_l2_blocks,
+            actual_l2_blocks,
+            rejected_txs,
+        })
+    }
+}
+
+/// Storage factory reading the state from Postgres.
+#[derive(Debug)]
+struct PostgresStorageFactory(ConnectionPool<Core>);
+
+#[async_trait]
+impl ReadStorageFactory for PostgresStorageFactory {
+    async fn access_storage(
+        &self,
+        _stop_receiver: &watch::Receiver<bool>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<PgOrRocksdbStorage<'_>>> {
+        Ok(Some(
+            PgOrRocksdbStorage::access_storage_pg(&self.0, l1_batch_number).await?,
+        ))
+    }
+}
+
+/// I/O feeding the recorded L1 batch to the state keeper.
+#[derive(Debug)]
+struct ReplayIO {
+    pool: ConnectionPool<Core>,
+    l1_batch_params_provider: L1BatchParamsProvider,
+    /// Cursor at the start of the replayed batch; taken on initialization.
+    cursor: Option<IoCursor>,
+    system_env: SystemEnv,
+    l1_batch_env: L1BatchEnv,
+    /// Whether batch params were already returned to the state keeper.
+    batch_started: bool,
+    /// Recorded non-fictive L2 blocks that were not started yet, including ones from lookahead batches.
+    l2_blocks: VecDeque<L2BlockExecutionData>,
+    /// Recorded fictive L2 block of the replayed batch.
+    fictive_l2_block: L2BlockParams,
+    /// All recorded non-fictive L2 blocks of the replayed batch.
+    expected_l2_blocks: Vec<ReplayedL2Block>,
+    /// Transactions from the current L2 block that were not returned to the state keeper yet.
+    current_txs: VecDeque<Transaction>,
+    /// Timestamp of the last started L2 block.
+    last_l2_block_timestamp: u64,
+    /// Whether the state keeper is going to seal the current L2 block and request params for the next one.
+    /// Otherwise, the next requested L2 block is the fictive one.
+    l2_block_seal_requested: bool,
+    rejected_txs: Arc<Mutex<Vec<(H256, String)>>>,
+}
+
+impl ReplayIO {
+    async fn load(
+        pool: &ConnectionPool<Core>,
+        l1_batch_number: L1BatchNumber,
+        validation_computational_gas_limit: u32,
+        chain_id: L2ChainId,
+        evm_emulator_hash: Option<H256>,
+        lookahead_batches: u32,
+    ) -> anyhow::Result<Self> {
+        let mut storage = pool.connection_tagged("state_keeper").await?;
+        let sealed_l1_batch = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .context("no sealed L1 batches")?;
+        anyhow::ensure!(
+            l1_batch_number <= sealed_l1_batch,
+            "L1 batch #{l1_batch_number} is not sealed (last sealed batch: #{sealed_l1_batch})"
+        );
+
+        let l1_batch_params_provider = L1BatchParamsProvider::new(&mut storage)
+            .await
+            .context("failed initializing L1 batch params provider")?
+            .with_evm_emulator_hash(evm_emulator_hash);
+        let first_l2_block_in_batch = l1_batch_params_provider
+            .load_first_l2_block_in_batch(&mut storage, l1_batch_number)
+            .await
+            .with_context(|| {
+                format!("failed loading first L2 block for L1 batch #{l1_batch_number}")
+            })?
+            .with_context(|| format!("no L2 blocks persisted for L1 batch #{l1_batch_number}"))?;
+        let (system_env, l1_batch_env) = l1_batch_params_provider
+            .load_l1_batch_params(
+                &mut storage,
+                &first_l2_block_in_batch,
+                validation_computational_gas_limit,
+                chain_id,
+            )
+            .await
+            .with_context(|| format!("failed loading params for L1 batch #{l1_batch_number}"))?;
+
+        let mut l2_blocks = storage
+            .transactions_dal()
+            .get_l2_blocks_to_execute_for_l1_batch(l1_batch_number)
+            .await?;
+        let fictive_l2_block = l2_blocks
+            .pop()
+            .with_context(|| format!("no L2 blocks persisted for L1 batch #{l1_batch_number}"))?;
+        anyhow::ensure!(
+            fictive_l2_block.txs.is_empty(),
+            "L1 batch #{l1_batch_number} has no fictive L2 block"
+        );
+        let fictive_l2_block = L2BlockParams {
+            timestamp: fictive_l2_block.timestamp,
+            virtual_blocks: fictive_l2_block.virtual_blocks,
+        };
+
+        let prev_l2_block_number = first_l2_block_in_batch.header.number - 1;
+        let prev_l2_block_header = storage
+            .blocks_dal()
+            .get_l2_block_header(prev_l2_block_number)
+            .await?
+            .with_context(|| {
+                format!("L2 block #{prev_l2_block_number} preceding L1 batch #{l1_batch_number} is not persisted")
+            })?;
+        let cursor = IoCursor {
+            next_l2_block: first_l2_block_in_batch.header.number,
+            prev_l2_block_hash: l1_batch_env.first_l2_block.prev_block_hash,
+            prev_l2_block_timestamp: prev_l2_block_header.timestamp,
+            l1_batch: l1_batch_number,
+        };
+
+        let expected_l2_blocks = l2_blocks
+            .iter()
+            .map(|block| ReplayedL2Block {
+                number: block.number,
+                tx_hashes: block.txs.iter().map(Transaction::hash).collect(),
+            })
+            .collect();
+
+        let last_lookahead_batch = sealed_l1_batch.min(l1_batch_number + lookahead_batches);
+        for lookahead_batch in (l1_batch_number.0 + 1)..=last_lookahead_batch.0 {
+            let lookahead_batch = L1BatchNumber(lookahead_batch);
+            let protocol_version = l1_batch_params_provider
+                .load_l1_batch_protocol_version(&mut storage, lookahead_batch)
+                .await
+                .with_context(|| {
+                    format!("failed loading protocol version for L1 batch #{lookahead_batch}")
+                })?;
+            if protocol_version != Some(system_env.version) {
+                // Transactions from batches with another protocol version cannot be executed in the replayed batch.
+                break;
+            }
+            let mut lookahead_l2_blocks = storage
+                .transactions_dal()
+                .get_l2_blocks_to_execute_for_l1_batch(lookahead_batch)
+                .await?;
+            lookahead_l2_blocks.pop(); // Remove the fictive L2 block
+            l2_blocks.extend(lookahead_l2_blocks);
+        }
+
+        Ok(Self {
+            pool: pool.clone(),
+            l1_batch_params_provider,
+            cursor: Some(cursor),
+            system_env,
+            l1_batch_env,
+            batch_started: false,
+            l2_blocks: l2_blocks.into(),
+            fictive_l2_block,
+            expected_l2_blocks,
+            current_txs: VecDeque::new(),
+            last_l2_block_timestamp: prev_l2_block_header.timestamp,
+            l2_block_seal_requested: false,
+            rejected_txs: Arc::default(),
+        })
+    }
+
+    fn expected_l2_blocks(&self) -> Vec<ReplayedL2Block> {
+        self.expected_l2_blocks.clone()
+    }
+
+    fn start_next_l2_block(&mut self) -> anyhow::Result<L2BlockParams> {
+        let block = self
+            .l2_blocks
+            .pop_front()
+            .context("no more L2 blocks to replay")?;
+        // Protocol upgrade transactions are loaded by the state keeper separately via `load_upgrade_tx()`.
+        self.current_txs = block
+            .txs
+            .into_iter()
+            .filter(|tx| tx.tx_format() != TransactionType::ProtocolUpgradeTransaction)
+            .collect();
+        self.last_l2_block_timestamp = block.timestamp;
+        Ok(L2BlockParams {
+            timestamp: block.timestamp,
+            virtual_blocks: block.virtual_blocks,
+        })
+    }
+}
+
+impl IoSealCriteria for ReplayIO {
+    fn should_seal_l1_batch_unconditionally(&mut self, _manager: &UpdatesManager) -> bool {
+        // All recorded transactions are exhausted without the sealer deciding to seal the batch.
+        self.current_txs.is_empty() && self.l2_blocks.is_empty()
+    }
+
+    fn should_seal_l2_block(&mut self, _manager: &UpdatesManager) -> bool {
+        let should_seal = self.current_txs.is_empty() && !self.l2_blocks.is_empty();
+        self.l2_block_seal_requested |= should_seal;
+        should_seal
+    }
+}
+
+#[async_trait]
+impl StateKeeperIO for ReplayIO {
+    fn chain_id(&self) -> L2ChainId {
+        self.system_env.chain_id
+    }
+
+    async fn initialize(&mut self) -> anyhow::Result<(IoCursor, Option<PendingBatchData>)> {
+        let cursor = self
+            .cursor
+            .take()
+            .context("replay I/O is already initialized")?;
+        Ok((cursor, None))
+    }
+
+    async fn wait_for_new_batch_params(
+        &mut self,
+        _cursor: &IoCursor,
+        max_wait: Duration,
+    ) -> anyhow::Result<Option<L1BatchParams>> {
+        if self.batch_started {
+            // The replayed batch is the only one provided by this I/O.
+            tokio::time::sleep(max_wait).await;
+            return Ok(None);
+        }
+        self.batch_started = true;
+
+        let first_l2_block = self.start_next_l2_block()?;
+        Ok(Some(L1BatchParams {
+            protocol_version: self.system_env.version,
+            validation_computational_gas_limit: self
+                .system_env
+                .default_validation_computational_gas_limit,
+            operator_address: self.l1_batch_env.fee_account,
+            fee_input: self.l1_batch_env.fee_input,
+            first_l2_block,
+        }))
+    }
+
+    async fn wait_for_new_l2_block_params(
+        &mut self,
+        _cursor: &IoCursor,
+        _max_wait: Duration,
+    ) -> anyhow::Result<Option<L2BlockParams>> {
+        if std::mem::take(&mut self.l2_block_seal_requested) {
+            return self.start_next_l2_block().map(Some);
+        }
+        // The batch is being sealed. If it's sealed later than recorded, the recorded fictive block timestamp
+        // may be outdated.
+        Ok(Some(L2BlockParams {
+            timestamp: self
+                .fictive_l2_block
+                .timestamp
+                .max(self.last_l2_block_timestamp + 1),
+            virtual_blocks: self.fictive_l2_block.virtual_blocks,
+        }))
+    }
+
+    async fn wait_for_next_tx(
+        &mut self,
+        _max_wait: Duration,
+    ) -> anyhow::Result<Option<Transaction>> {
+        // The state keeper always seals the current L2 block or L1 batch once the current L2 block is exhausted.
+        self.current_txs
+            .pop_front()
+            .context("replayed L2 block has no more transactions")
+            .map(Some)
+    }
+
+    async fn rollback(&mut self, tx: Transaction) -> anyhow::Result<()> {
+        self.current_txs.push_front(tx);
+        Ok(())
+    }
+
+    async fn reject(&mut self, tx: &Transaction, reason: UnexecutableReason) -> anyhow::Result<()> {
+        tracing::warn!(
+            "Transaction {:?} is rejected during replay: {reason}",
+            tx.hash()
+        );
+        self.rejected_txs
+            .lock()
+            .unwrap()
+            .push((tx.hash(), reason.to_string()));
+        Ok(())
+    }
+
+    async fn load_base_system_contracts(
+        &self,
+        protocol_version: ProtocolVersionId,
+        _cursor: &IoCursor,
+    ) -> anyhow::Result<BaseSystemContracts> {
+        anyhow::ensure!(
+            protocol_version == self.system_env.version,
+            "unexpected protocol version requested: {protocol_version:?}"
+        );
+        Ok(self.system_env.base_system_smart_contracts.clone())
+    }
+
+    async fn load_batch_version_id(
+        &self,
+        number: L1BatchNumber,
+    ) -> anyhow::Result<ProtocolVersionId> {
+        let mut storage = self.pool.connection_tagged("state_keeper").await?;
+        self.l1_batch_params_provider
+            .load_l1_batch_protocol_version(&mut storage, number)
+            .await
+            .with_context(|| format!("failed loading protocol version for L1 batch #{number}"))?
+            .with_context(|| format!("L1 batch #{number} misses protocol version"))
+    }
+
+    async fn load_upgrade_tx(
+        &self,
+        version_id: ProtocolVersionId,
+    ) -> anyhow::Result<Option<ProtocolUpgradeTx>> {
+        let mut storage = self.pool.connection_tagged("state_keeper").await?;
+        storage
+            .protocol_versions_dal()
+            .get_protocol_upgrade_tx(version_id)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn load_batch_state_hash(&self, number: L1BatchNumber) -> anyhow::Result<H256> {
+        anyhow::ensure!(
+            number + 1 == self.l1_batch_env.number,
+            "unexpected L1 batch state hash requested: #{number}"
+        );
+        self.l1_batch_env
+            .previous_batch_hash
+            .context("previous L1 batch hash is not set")
+    }
+}
+
+/// Output handler recording L2 blocks produced during the replay. Stops the state keeper once the replayed
+/// L1 batch is sealed.
+#[derive(Debug)]
+struct ReplayRecorder {
+    l2_blocks: Arc<Mutex<Vec<ReplayedL2Block>>>,
+    stop_sender: watch::Sender<bool>,
+}
+
+#[async_trait]
+impl StateKeeperOutputHandler for ReplayRecorder {
+    async fn handle_l2_block(&mut self, updates_manager: &UpdatesManager) -> anyhow::Result<()> {
+        let l2_block = &updates_manager.l2_block;
+        self.l2_blocks.lock().unwrap().push(ReplayedL2Block {
+            number: l2_block.number,
+            tx_hashes: l2_block
+                .executed_transactions
+                .iter()
+                .map(|tx| tx.hash)
+                .collect(),
+        });
+        Ok(())
+    }
+
+    async fn handle_l1_batch(
+        &mut self,
+        _updates_manager: Arc<UpdatesManager>,
+    ) -> anyhow::Result<()> {
+        self.stop_sender.send_replace(true);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
+    use zksync_test_account::Account;
+    use zksync_types::{
+        fee_model::BatchFeeInput, utils::storage_key_for_standard_token_balance, AccountTreeId,
+        Address, Execute, StorageLog, L2_BASE_TOKEN_ADDRESS, U256,
+    };
+    use zksync_utils::u256_to_h256;
+
+    use super::*;
+    use crate::{io::StateKeeperPersistence, seal_criteria::NoopSealer};
+
+    fn l2_block(number: u32, tx_hashes: &[u8]) -> ReplayedL2Block {
+        ReplayedL2Block {
+            number: L2BlockNumber(number),
+            tx_hashes: tx_hashes.iter().copied().map(H256::repeat_byte).collect(),
+        }
+    }
+
+    #[test]
+    fn comparing_replayed_batches() {
+        let expected_l2_blocks = vec![l2_block(1, &[1, 2]), l2_block(2, &[3])];
+        let mut replay = L1BatchReplay {
+            l1_batch_number: L1BatchNumber(1),
+            expected_l2_blocks: expected_l2_blocks.clone(),
+            actual_l2_blocks: expected_l2_blocks,
+            rejected_txs: vec![],
+        };
+        assert!(replay.is_identical());
+        replay.ensure_identical().unwrap();
+
+        // Batch is sealed early.
+        replay.actual_l2_blocks = vec![l2_block(1, &[1])];
+        assert!(!replay.is_identical());
+        let err = replay.ensure_identical().unwrap_err().to_string();
+        assert!(err.contains("L2 block #1"), "{err}");
+
+        replay.actual_l2_blocks = vec![l2_block(1, &[1, 2])];
+        let err = replay.ensure_identical().unwrap_err().to_string();
+        assert!(err.contains("sealed after 1 L2 blocks"), "{err}");
+
+        replay.actual_l2_blocks = replay.expected_l2_blocks.clone();
+        replay.rejected_txs = vec![(H256::repeat_byte(3), "Too much gas".to_owned())];
+        assert!(!replay.is_identical());
+        let err = replay.ensure_identical().unwrap_err().to_string();
+        assert!(err.contains("rejected during replay"), "{err}");
+    }
+
+    /// I/O sealing L2 blocks and L1 batches as specified by the script. Used to record L1 batches for replay.
+    #[derive(Debug)]
+    struct ScriptedIO {
+        pool: ConnectionPool<Core>,
+        genesis_params: GenesisParams,
+        /// Transactions in each L2 block of each L1 batch that was not started yet.
+        l1_batches: VecDeque<VecDeque<Vec<Transaction>>>,
+        /// Remaining L2 blocks in the current L1 batch.
+        l2_blocks: VecDeque<Vec<Transaction>>,
+        current_txs: VecDeque<Transaction>,
+        timestamp: u64,
+        stop_sender: watch::Sender<bool>,
+    }
+
+    impl ScriptedIO {
+        fn next_l2_block(&mut self) -> L2BlockParams {
+            // The fictive L2 block is started once all L2 blocks in the batch are exhausted.
+            self.current_txs = self.l2_blocks.pop_front().unwrap_or_default().into();
+            self.timestamp += 1;
+            L2BlockParams {
+                timestamp: self.timestamp,
+                virtual_blocks: 1,
+            }
+        }
+    }
+
+    impl IoSealCriteria for ScriptedIO {
+        fn should_seal_l1_batch_unconditionally(&mut self, _manager: &UpdatesManager) -> bool {
+            self.current_txs.is_empty() && self.l2_blocks.is_empty()
+        }
+
+        fn should_seal_l2_block(&mut self, _manager: &UpdatesManager) -> bool {
+            self.current_txs.is_empty() && !self.l2_blocks.is_empty()
+        }
+    }
+
+    #[async_trait]
+    impl StateKeeperIO for ScriptedIO {
+        fn chain_id(&self) -> L2ChainId {
+            L2ChainId::default()
+        }
+
+        async fn initialize(&mut self) -> anyhow::Result<(IoCursor, Option<PendingBatchData>)> {
+            let mut storage = self.pool.connection().await?;
+            Ok((IoCursor::new(&mut storage).await?, None))
+        }
+
+        async fn wait_for_new_batch_params(
+            &mut self,
+            _cursor: &IoCursor,
+            _max_wait: Duration,
+        ) -> anyhow::Result<Option<L1BatchParams>> {
+            let Some(l2_blocks) = self.l1_batches.pop_front() else {
+                self.stop_sender.send_replace(true);
+                return Ok(None);
+            };
+            self.l2_blocks = l2_blocks;
+            Ok(Some(L1BatchParams {
+                protocol_version: self.genesis_params.minor_protocol_version(),
+                validation_computational_gas_limit: u32::MAX,
+                operator_address: Address::repeat_byte(1),
+                fee_input: BatchFeeInput::pubdata_independent(1, 1, 1),
+                first_l2_block: self.next_l2_block(),
+            }))
+        }
+
+        async fn wait_for_new_l2_block_params(
+            &mut self,
+            _cursor: &IoCursor,
+            _max_wait: Duration,
+        ) -> anyhow::Result<Option<L2BlockParams>> {
+            Ok(Some(self.next_l2_block()))
+        }
+
+        async fn wait_for_next_tx(
+            &mut self,
+            _max_wait: Duration,
+        ) -> anyhow::Result<Option<Transaction>> {
+            Ok(self.current_txs.pop_front())
+        }
+
+        async fn rollback(&mut self, tx: Transaction) -> anyhow::Result<()> {
+            self.current_txs.push_front(tx);
+            Ok(())
+        }
+
+        async fn reject(
+            &mut self,
+            tx: &Transaction,
+            reason: UnexecutableReason,
+        ) -> anyhow::Result<()> {
+            anyhow::bail!(
+                "transaction {:?} is unexpectedly rejected: {reason}",
+                tx.hash()
+            );
+        }
+
+        async fn load_base_system_contracts(
+            &self,
+            _protocol_version: ProtocolVersionId,
+            _cursor: &IoCursor,
+        ) -> anyhow::Result<BaseSystemContracts> {
+            Ok(self.genesis_params.base_system_contracts().clone())
+        }
+
+        async fn load_batch_version_id(
+            &self,
+            _number: L1BatchNumber,
+        ) -> anyhow::Result<ProtocolVersionId> {
+            Ok(self.genesis_params.minor_protocol_version())
+        }
+
+        async fn load_upgrade_tx(
+            &self,
+            _version_id: ProtocolVersionId,
+        ) -> anyhow::Result<Option<ProtocolUpgradeTx>> {
+            Ok(None)
+        }
+
+        async fn load_batch_state_hash(&self, number: L1BatchNumber) -> anyhow::Result<H256> {
+            // Only the genesis batch has a state hash since the Merkle tree isn't run.
+            let mut storage = self.pool.connection().await?;
+            let hash = storage.blocks_dal().get_l1_batch_state_root(number).await?;
+            Ok(hash.unwrap_or_default())
+        }
+    }
+
+    async fn fund(pool: &ConnectionPool<Core>, account: &Account) {
+        let mut storage = pool.connection().await.unwrap();
+        let key = storage_key_for_standard_token_balance(
+            AccountTreeId::new(L2_BASE_TOKEN_ADDRESS),
+            &account.address,
+        );
+        let eth_amount = U256::from(10).pow(U256::from(32));
+        let storage_log = StorageLog::new_write_log(key, u256_to_h256(eth_amount));
+        storage
+            .storage_logs_dal()
+            .append_storage_logs(L2BlockNumber(0), &[(H256::zero(), vec![storage_log])])
+            .await
+            .unwrap();
+        storage
+            .storage_logs_dedup_dal()
+            .insert_initial_writes(L1BatchNumber(0), &[storage_log.key])
+            .await
+            .unwrap();
+    }
+
+    /// Records L1 batches with the specified L2 blocks by running the state keeper.
+    async fn record_l1_batches(
+        pool: &ConnectionPool<Core>,
+        genesis_params: GenesisParams,
+        l1_batches: Vec<Vec<Vec<Transaction>>>,
+    ) {
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let io = ScriptedIO {
+            pool: pool.clone(),
+            genesis_params,
+            l1_batches: l1_batches.into_iter().map(Into::into).collect(),
+            l2_blocks: VecDeque::new(),
+            current_txs: VecDeque::new(),
+            timestamp: 0,
+            stop_sender,
+        };
+        let (persistence, l2_block_sealer) =
+            StateKeeperPersistence::new(pool.clone(), Address::default(), 0);
+        let l2_block_sealer = tokio::spawn(l2_block_sealer.run());
+        let state_keeper = ZkSyncStateKeeper::new(
+            stop_receiver,
+            Box::new(io),
+            Box::new(MainBatchExecutor::new(false, false)),
+            OutputHandler::new(Box::new(persistence.with_tx_insertion())),
+            Arc::new(NoopSealer),
+            Arc::new(PostgresStorageFactory(pool.clone())),
+        );
+        state_keeper.run().await.unwrap();
+        // The sealer task terminates once the persistence is dropped together with the state keeper.
+        l2_block_sealer.await.unwrap().unwrap();
+    }
+
+    fn transfer(account: &mut Account) -> Transaction {
+        let execute = Execute {
+            contract_address: Address::random(),
+            calldata: vec![],
+            value: 1.into(),
+            factory_deps: None,
+        };
+        account.get_l2_tx_for_execute(execute, None)
+    }
+
+    #[tokio::test]
+    async fn replaying_recorded_l1_batches() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let genesis_params = GenesisParams::mock();
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &genesis_params)
+            .await
+            .unwrap();
+        drop(storage);
+        let mut alice = Account::random();
+        fund(&pool, &alice).await;
+
+        let txs: Vec<_> = (0..5).map(|_| transfer(&mut alice)).collect();
+        let tx_hashes: Vec<_> = txs.iter().map(Transaction::hash).collect();
+        let l1_batches = vec![
+            vec![txs[..2].to_vec(), txs[2..3].to_vec()],
+            vec![txs[3..].to_vec()],
+        ];
+        record_l1_batches(&pool, genesis_params, l1_batches).await;
+
+        let replayer = |transaction_slots| {
+            let config = StateKeeperConfig {
+                transaction_slots,
+                ..StateKeeperConfig::for_tests()
+            };
+            L1BatchReplayer::new(pool.clone(), &config, L2ChainId::default())
+        };
+        let expected_l2_blocks = vec![
+            ReplayedL2Block {
+                number: L2BlockNumber(1),
+                tx_hashes: tx_hashes[..2].to_vec(),
+            },
+            ReplayedL2Block {
+                number: L2BlockNumber(2),
+                tx_hashes: tx_hashes[2..3].to_vec(),
+            },
+        ];
+
+        // The sealer seals the batch at the recorded boundary.
+        let replay = replayer(3).replay(L1BatchNumber(1)).await.unwrap();
+        assert_eq!(replay.expected_l2_blocks, expected_l2_blocks);
+        replay.ensure_identical().unwrap();
+
+        // The sealer allows more transactions per batch, so transactions from the next batch are included.
+        let replay = replayer(10).replay(L1BatchNumber(1)).await.unwrap();
+        assert!(!replay.is_identical());
+        assert_eq!(replay.actual_l2_blocks.len(), 3);
+        assert_eq!(replay.actual_l2_blocks[2].tx_hashes, &tx_hashes[3..]);
+        let err = replay.ensure_identical().unwrap_err().to_string();
+        assert!(err.contains("sealed after 3 L2 blocks"), "{err}");
+
+        // Without the lookahead, only earlier seals can be detected.
+        let replay = replayer(10)
+            .with_lookahead_batches(0)
+            .replay(L1BatchNumber(1))
+            .await
+            .unwrap();
+        replay.ensure_identical().unwrap();
+
+        // The sealer seals the batch early.
+        let replay = replayer(2).replay(L1BatchNumber(1)).await.unwrap();
+        assert_eq!(replay.actual_l2_blocks, expected_l2_blocks[..1]);
+        let err = replay.ensure_identical().unwrap_err().to_string();
+        assert!(err.contains("sealed after 1 L2 blocks"), "{err}");
+
+        // The Merkle tree isn't run, so the hash of the previous batch needs to be set manually.
+        let mut storage = pool.connection().await.unwrap();
+        storage
+            .blocks_dal()
+            .set_l1_batch_hash(L1BatchNumber(1), H256::repeat_byte(1))
+            .await
+            .unwrap();
+        drop(storage);
+        // The last batch has no lookahead batches.
+        let replay = replayer(10).replay(L1BatchNumber(2)).await.unwrap();
+        replay.ensure_identical().unwrap();
+    }
+}
@@ -4,12 +4,17 @@
 //! The conditional sealer abstraction allows to implement different sealing strategies, e.g. the actual
 //! sealing strategy for the main node or noop sealer for the external node.
 
-use std::fmt;
+use std::{collections::BTreeMap, fmt};
 
+use serde::Serialize;
 use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_types::ProtocolVersionId;
+use zksync_utils::time::millis_since_epoch;
 
-use super::{criteria, SealCriterion, SealData, SealResolution, AGGREGATION_METRICS};
+use super::{
+    criteria, SealCriterion, SealData, SealResolution, TimeoutSealer, AGGREGATION_METRICS,
+};
 
 /// Checks if an L1 batch should be sealed after executing a transaction.
 pub trait ConditionalSealer: 'static + fmt::Debug + Send + Sync {
@@ -39,10 +44,29 @@ pub trait ConditionalSealer: 'static + fmt::Debug + Send + Sync {
 ///
 /// The checks are deterministic, i.e., should depend solely on execution metrics and [`StateKeeperConfig`].
 /// Non-deterministic seal criteria are expressed using [`IoSealCriteria`](super::IoSealCriteria).
-#[derive(Debug, Default)]
+///
+/// The sealer reports the filled capacity of each criterion for the currently open L1 batch as metrics
+/// and as details of its [health check](Self::health_check()).
+#[derive(Debug)]
 pub struct SequencerSealer {
     config: StateKeeperConfig,
     sealers: Vec<Box<dyn SealCriterion>>,
+    health_updater: HealthUpdater,
+}
+
+impl Default for SequencerSealer {
+    fn default() -> Self {
+        Self::from_parts(StateKeeperConfig::default(), Vec::new())
+    }
+}
+
+/// Health details of [`SequencerSealer`].
+#[derive(Debug, Serialize)]
+struct SealCriteriaHealthDetails {
+    l1_batch_number: u32,
+    tx_count: usize,
+    /// Filled share of the capacity for each seal criterion; see [`SealCriterion::capacity_filled()`].
+    capacity_filled: BTreeMap<&'static str, f64>,
 }
 
 impl ConditionalSealer for SequencerSealer {
@@ -110,6 +134,14 @@ impl ConditionalSealer for SequencerSealer {
 
             final_seal_resolution = final_seal_resolution.stricter(seal_resolution);
         }
+
+        self.report_capacity_filled(
+            l1_batch_number,
+            block_open_timestamp_ms,
+            tx_count,
+            block_data,
+            protocol_version,
+        );
         final_seal_resolution
     }
 }
@@ -117,7 +149,7 @@ impl ConditionalSealer for SequencerSealer {
 impl SequencerSealer {
     pub fn new(config: StateKeeperConfig) -> Self {
         let sealers = Self::default_sealers(&config);
-        Self { config, sealers }
+        Self::from_parts(config, sealers)
     }
 
     #[cfg(test)]
@@ -125,11 +157,65 @@ impl SequencerSealer {
         config: StateKeeperConfig,
         sealers: Vec<Box<dyn SealCriterion>>,
     ) -> Self {
-        Self { config, sealers }
+        Self::from_parts(config, sealers)
+    }
+
+    fn from_parts(config: StateKeeperConfig, sealers: Vec<Box<dyn SealCriterion>>) -> Self {
+        let (_, health_updater) = ReactiveHealthCheck::new("seal_criteria");
+        // The sealer is functional immediately; details are added once the first transaction is processed.
+        health_updater.update(HealthStatus::Ready.into());
+        Self {
+            config,
+            sealers,
+            health_updater,
+        }
+    }
+
+    /// Returns a health check for this sealer. Health details contain the filled capacity of each seal criterion
+    /// for the currently open L1 batch.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    /// Reports capacity filled by the currently open L1 batch (including the latest executed transaction)
+    /// for each seal criterion.
+    fn report_capacity_filled(
+        &self,
+        l1_batch_number: u32,
+        block_open_timestamp_ms: u128,
+        tx_count: usize,
+        block_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) {
+        let mut capacity_filled: BTreeMap<_, _> = self
+            .sealers
+            .iter()
+            .filter_map(|sealer| {
+                let filled =
+                    sealer.capacity_filled(&self.config, tx_count, block_data, protocol_version)?;
+                Some((sealer.prom_criterion_name(), filled))
+            })
+            .collect();
+        let batch_age_ms = millis_since_epoch().saturating_sub(block_open_timestamp_ms);
+        capacity_filled.insert(
+            TimeoutSealer::RULE_NAME,
+            batch_age_ms as f64 / self.config.block_commit_deadline_ms as f64,
+        );
+
+        for (&criterion, &filled) in &capacity_filled {
+            AGGREGATION_METRICS.capacity_filled[&criterion].set(filled);
+        }
+        let details = SealCriteriaHealthDetails {
+            l1_batch_number,
+            tx_count,
+            capacity_filled,
+        };
+        self.health_updater
+            .update(Health::from(HealthStatus::Ready).with_details(details));
     }
 
     fn default_sealers(config: &StateKeeperConfig) -> Vec<Box<dyn SealCriterion>> {
-        vec![
+        let mut sealers: Vec<Box<dyn SealCriterion>> = vec![
             Box::new(criteria::SlotsCriterion),
             Box::new(criteria::GasCriterion),
             Box::new(criteria::PubDataBytesCriterion {
@@ -138,7 +224,13 @@ impl SequencerSealer {
             Box::new(criteria::CircuitsCriterion),
             Box::new(criteria::TxEncodingSizeCriterion),
             Box::new(criteria::GasForBatchTipCriterion),
-        ]
+        ];
+        if let Some(max_pubdata_per_l2_block) = config.l2_block_max_pubdata {
+            sealers.push(Box::new(criteria::L2BlockPubdataCriterion {
+                max_pubdata_per_l2_block,
+            }));
+        }
+        sealers
     }
 }
 
@@ -169,3 +261,56 @@ impl ConditionalSealer for NoopSealer {
         SealResolution::NoSeal
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use zksync_health_check::CheckHealth;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn sequencer_sealer_reports_capacity_filled() {
+        let config = StateKeeperConfig {
+            transaction_slots: 4,
+            block_commit_deadline_ms: 1_000_000,
+            ..StateKeeperConfig::default()
+        };
+        let sealer = SequencerSealer::with_sealers(
+            config,
+            vec![
+                Box::new(criteria::SlotsCriterion),
+                Box::new(criteria::GasForBatchTipCriterion),
+            ],
+        );
+        let health_check = sealer.health_check();
+        assert_eq!(
+            health_check.check_health().await.status(),
+            HealthStatus::Ready
+        );
+
+        let tx_data = SealData {
+            gas_remaining: u32::MAX,
+            ..SealData::default()
+        };
+        let resolution = sealer.should_seal_l1_batch(
+            1,
+            millis_since_epoch(),
+            2,
+            &SealData::default(),
+            &tx_data,
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(resolution, SealResolution::NoSeal);
+
+        let health = health_check.check_health().await;
+        assert_eq!(health.status(), HealthStatus::Ready);
+        let details = health.details().unwrap();
+        assert_eq!(details["l1_batch_number"], 1);
+        assert_eq!(details["tx_count"], 2);
+        let capacity_filled = details["capacity_filled"].as_object().unwrap();
+        assert_eq!(capacity_filled["slots"], 0.5);
+        assert!(!capacity_filled.contains_key("gas_for_batch_tip"));
+        let timeout_filled = capacity_filled[TimeoutSealer::RULE_NAME].as_f64().unwrap();
+        assert!(timeout_filled < 1.0, "{timeout_filled}");
+    }
+}
@@ -45,6 +45,19 @@ impl SealCriterion for GasCriterion {
         }
     }
 
+    fn capacity_filled(
+        &self,
+        config: &StateKeeperConfig,
+        _tx_count: usize,
+        block_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> Option<f64> {
+        let block_bound = config.max_single_tx_gas as f64 * config.close_block_at_gas_percentage;
+        let gas_count = &block_data.gas_count;
+        let used_gas = gas_count.commit.max(gas_count.prove).max(gas_count.execute);
+        Some(used_gas as f64 / block_bound)
+    }
+
     fn prom_criterion_name(&self) -> &'static str {
         "gas"
     }
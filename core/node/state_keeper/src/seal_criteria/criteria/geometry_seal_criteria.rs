@@ -63,6 +63,21 @@ impl SealCriterion for CircuitsCriterion {
         }
     }
 
+    fn capacity_filled(
+        &self,
+        config: &StateKeeperConfig,
+        _tx_count: usize,
+        block_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> Option<f64> {
+        let batch_tip_circuit_overhead =
+            circuit_statistics_bootloader_batch_tip_overhead(protocol_version.into());
+        let include_and_seal_bound =
+            config.max_circuits_per_batch as f64 * config.close_block_at_geometry_percentage;
+        let used_circuits_batch = block_data.execution_metrics.circuit_statistic.total();
+        Some((used_circuits_batch + batch_tip_circuit_overhead) as f64 / include_and_seal_bound)
+    }
+
     fn prom_criterion_name(&self) -> &'static str {
         "circuits_criterion"
     }
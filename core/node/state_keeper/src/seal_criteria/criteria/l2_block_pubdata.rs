@@ -0,0 +1,79 @@
+use zksync_types::ProtocolVersionId;
+
+use super::pubdata_bytes::tx_pubdata_size;
+use crate::seal_criteria::{
+    SealCriterion, SealData, SealResolution, StateKeeperConfig, UnexecutableReason,
+};
+
+/// Rejects transactions that publish more pubdata than allowed for a single L2 block. Pubdata of L2 blocks
+/// is accumulated by [`L2BlockMaxPubdataSealer`](crate::seal_criteria::L2BlockMaxPubdataSealer), which seals
+/// L2 blocks once the limit is reached.
+#[derive(Debug)]
+pub struct L2BlockPubdataCriterion {
+    pub max_pubdata_per_l2_block: u64,
+}
+
+impl SealCriterion for L2BlockPubdataCriterion {
+    fn should_seal(
+        &self,
+        _config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        _tx_count: usize,
+        _block_data: &SealData,
+        tx_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> SealResolution {
+        if tx_pubdata_size(tx_data, protocol_version) > self.max_pubdata_per_l2_block as usize {
+            UnexecutableReason::PubdataLimit.into()
+        } else {
+            SealResolution::NoSeal
+        }
+    }
+
+    fn prom_criterion_name(&self) -> &'static str {
+        "l2_block_pub_data_size"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::tx::ExecutionMetrics;
+
+    use super::*;
+
+    #[test]
+    fn seal_criterion() {
+        let config = StateKeeperConfig::default();
+        let criterion = L2BlockPubdataCriterion {
+            max_pubdata_per_l2_block: 1_000,
+        };
+        let tx_data = |pubdata_published| SealData {
+            execution_metrics: ExecutionMetrics {
+                pubdata_published,
+                ..ExecutionMetrics::default()
+            },
+            ..SealData::default()
+        };
+
+        let resolution = criterion.should_seal(
+            &config,
+            0,
+            1,
+            &tx_data(2_000),
+            &tx_data(1_000),
+            ProtocolVersionId::latest(),
+        );
+        // Pubdata of the entire batch must not influence the resolution.
+        assert_eq!(resolution, SealResolution::NoSeal);
+
+        let resolution = criterion.should_seal(
+            &config,
+            0,
+            1,
+            &tx_data(1_001),
+            &tx_data(1_001),
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(resolution, UnexecutableReason::PubdataLimit.into());
+    }
+}
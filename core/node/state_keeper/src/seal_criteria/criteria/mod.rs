@@ -1,12 +1,14 @@
 mod gas;
 mod gas_for_batch_tip;
 mod geometry_seal_criteria;
+mod l2_block_pubdata;
 mod pubdata_bytes;
 mod slots;
 mod tx_encoding_size;
 
 pub(crate) use self::{
     gas::GasCriterion, gas_for_batch_tip::GasForBatchTipCriterion,
-    geometry_seal_criteria::CircuitsCriterion, pubdata_bytes::PubDataBytesCriterion,
-    slots::SlotsCriterion, tx_encoding_size::TxEncodingSizeCriterion,
+    geometry_seal_criteria::CircuitsCriterion, l2_block_pubdata::L2BlockPubdataCriterion,
+    pubdata_bytes::PubDataBytesCriterion, slots::SlotsCriterion,
+    tx_encoding_size::TxEncodingSizeCriterion,
 };
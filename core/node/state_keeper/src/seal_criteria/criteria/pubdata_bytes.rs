@@ -5,6 +5,18 @@ use crate::seal_criteria::{
     SealCriterion, SealData, SealResolution, StateKeeperConfig, UnexecutableReason,
 };
 
+/// Returns the amount of pubdata published by a transaction.
+pub(super) fn tx_pubdata_size(tx_data: &SealData, protocol_version: ProtocolVersionId) -> usize {
+    // For backward compatibility, we need to keep calculating the size of the pubdata based
+    // `StorageDeduplication` metrics. All vm versions
+    // after vm with virtual blocks will provide the size of the pubdata in the execution metrics.
+    if tx_data.execution_metrics.pubdata_published == 0 {
+        tx_data.execution_metrics.size() + tx_data.writes_metrics.size(protocol_version)
+    } else {
+        tx_data.execution_metrics.pubdata_published as usize
+    }
+}
+
 #[derive(Debug)]
 pub struct PubDataBytesCriterion {
     /// This value changes based on the DA solution.
@@ -32,14 +44,7 @@ impl SealCriterion for PubDataBytesCriterion {
 
         let block_size =
             block_data.execution_metrics.size() + block_data.writes_metrics.size(protocol_version);
-        // For backward compatibility, we need to keep calculating the size of the pubdata based
-        // `StorageDeduplication` metrics. All vm versions
-        // after vm with virtual blocks will provide the size of the pubdata in the execution metrics.
-        let tx_size = if tx_data.execution_metrics.pubdata_published == 0 {
-            tx_data.execution_metrics.size() + tx_data.writes_metrics.size(protocol_version)
-        } else {
-            tx_data.execution_metrics.pubdata_published as usize
-        };
+        let tx_size = tx_pubdata_size(tx_data, protocol_version);
         if tx_size + execution_metrics_bootloader_batch_tip_overhead(protocol_version.into())
             > reject_bound as usize
         {
@@ -59,6 +64,21 @@ impl SealCriterion for PubDataBytesCriterion {
         }
     }
 
+    fn capacity_filled(
+        &self,
+        config: &StateKeeperConfig,
+        _tx_count: usize,
+        block_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> Option<f64> {
+        let include_and_seal_bound =
+            self.max_pubdata_per_batch as f64 * config.close_block_at_eth_params_percentage;
+        let block_size = block_data.execution_metrics.size()
+            + block_data.writes_metrics.size(protocol_version)
+            + execution_metrics_bootloader_batch_tip_overhead(protocol_version.into());
+        Some(block_size as f64 / include_and_seal_bound)
+    }
+
     fn prom_criterion_name(&self) -> &'static str {
         "pub_data_size"
     }
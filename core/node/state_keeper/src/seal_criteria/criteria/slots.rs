@@ -31,6 +31,16 @@ impl SealCriterion for SlotsCriterion {
         }
     }
 
+    fn capacity_filled(
+        &self,
+        config: &StateKeeperConfig,
+        tx_count: usize,
+        _block_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> Option<f64> {
+        Some(tx_count as f64 / config.transaction_slots as f64)
+    }
+
     fn prom_criterion_name(&self) -> &'static str {
         "slots"
     }
@@ -69,5 +79,13 @@ mod tests {
             ProtocolVersionId::latest(),
         );
         assert_eq!(full_block_resolution, SealResolution::IncludeAndSeal);
+
+        let capacity_filled = criterion.capacity_filled(
+            &config,
+            config.transaction_slots - 1,
+            &SealData::default(),
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(capacity_filled, Some(0.5));
     }
 }
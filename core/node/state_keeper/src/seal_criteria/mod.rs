@@ -69,6 +69,9 @@ pub enum UnexecutableReason {
     OutOfGasForBatchTip,
     BootloaderOutOfGas,
     NotEnoughGasProvided,
+    DeadlineExpired,
+    DeniedByFilter(String),
+    BatchBudgetExceeded,
 }
 
 impl UnexecutableReason {
@@ -83,6 +86,9 @@ impl UnexecutableReason {
             UnexecutableReason::OutOfGasForBatchTip => "OutOfGasForBatchTip",
             UnexecutableReason::BootloaderOutOfGas => "BootloaderOutOfGas",
             UnexecutableReason::NotEnoughGasProvided => "NotEnoughGasProvided",
+            UnexecutableReason::DeadlineExpired => "DeadlineExpired",
+            UnexecutableReason::DeniedByFilter(_) => "DeniedByFilter",
+            UnexecutableReason::BatchBudgetExceeded => "BatchBudgetExceeded",
         }
     }
 }
@@ -107,6 +113,13 @@ impl fmt::Display for UnexecutableReason {
             UnexecutableReason::OutOfGasForBatchTip => write!(f, "Out of gas for batch tip"),
             UnexecutableReason::BootloaderOutOfGas => write!(f, "Bootloader out of gas"),
             UnexecutableReason::NotEnoughGasProvided => write!(f, "Not enough gas provided"),
+            UnexecutableReason::DeadlineExpired => write!(f, "Inclusion deadline expired"),
+            UnexecutableReason::DeniedByFilter(reason) => {
+                write!(f, "Denied by transaction filter: {reason}")
+            }
+            UnexecutableReason::BatchBudgetExceeded => {
+                write!(f, "Transaction exceeds L1 batch resource budget")
+            }
         }
     }
 }
@@ -199,6 +212,19 @@ pub(super) trait SealCriterion: fmt::Debug + Send + Sync + 'static {
         protocol_version: ProtocolVersionId,
     ) -> SealResolution;
 
+    /// Returns the filled share of the L1 batch capacity tracked by this criterion. The capacity corresponds
+    /// to the bound at which the batch is sealed, so values `>= 1.0` mean that the criterion seals the batch.
+    /// Returns `None` if the criterion doesn't track a batch-wide resource.
+    fn capacity_filled(
+        &self,
+        _config: &StateKeeperConfig,
+        _tx_count: usize,
+        _block_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> Option<f64> {
+        None
+    }
+
     // We need self here only for rust restrictions for creating an object from trait
     // https://doc.rust-lang.org/reference/items/traits.html#object-safety
     fn prom_criterion_name(&self) -> &'static str;
@@ -228,16 +254,25 @@ impl TimeoutSealer {
     }
 }
 
-impl IoSealCriteria for TimeoutSealer {
-    fn should_seal_l1_batch_unconditionally(&mut self, manager: &UpdatesManager) -> bool {
-        const RULE_NAME: &str = "no_txs_timeout";
+impl TimeoutSealer {
+    pub(super) const RULE_NAME: &'static str = "no_txs_timeout";
+
+    /// Same as [`IoSealCriteria::should_seal_l1_batch_unconditionally()`], but with the L1 batch commit deadline
+    /// multiplied by `deadline_factor` (e.g., as returned by [`L1GasPriceTrendSealer`]).
+    pub(super) fn should_seal_l1_batch_with_deadline_factor(
+        &self,
+        manager: &UpdatesManager,
+        deadline_factor: f64,
+    ) -> bool {
+        const RULE_NAME: &str = TimeoutSealer::RULE_NAME;
 
         if manager.pending_executed_transactions_len() == 0 {
             // Regardless of which sealers are provided, we never want to seal an empty batch.
             return false;
         }
 
-        let block_commit_deadline_ms = self.block_commit_deadline_ms;
+        let block_commit_deadline_ms =
+            (self.block_commit_deadline_ms as f64 * deadline_factor) as u64;
         // Verify timestamp
         let should_seal_timeout =
             millis_since(manager.batch_timestamp()) > block_commit_deadline_ms;
@@ -252,6 +287,12 @@ impl IoSealCriteria for TimeoutSealer {
         }
         should_seal_timeout
     }
+}
+
+impl IoSealCriteria for TimeoutSealer {
+    fn should_seal_l1_batch_unconditionally(&mut self, manager: &UpdatesManager) -> bool {
+        self.should_seal_l1_batch_with_deadline_factor(manager, 1.0)
+    }
 
     fn should_seal_l2_block(&mut self, manager: &UpdatesManager) -> bool {
         !manager.l2_block.executed_transactions.is_empty()
@@ -259,6 +300,69 @@ impl IoSealCriteria for TimeoutSealer {
     }
 }
 
+/// Adjusts the L1 batch commit deadline used by [`TimeoutSealer`] based on the L1 gas price trend since
+/// the batch was opened. If L1 gas price is rising, the deadline is shortened so that the batch is committed
+/// before prices rise further; if the price is falling, the deadline is extended allowing longer batches.
+///
+/// Since this sealer depends on the L1 gas price oracle, it's non-deterministic and thus cannot be expressed
+/// as a [`SealCriterion`].
+#[derive(Debug, Clone, Copy)]
+pub(super) struct L1GasPriceTrendSealer {
+    rise_threshold: Option<f64>,
+    rise_deadline_factor: f64,
+    fall_threshold: Option<f64>,
+    fall_deadline_factor: f64,
+}
+
+impl L1GasPriceTrendSealer {
+    const DEFAULT_RISE_DEADLINE_FACTOR: f64 = 0.5;
+    const DEFAULT_FALL_DEADLINE_FACTOR: f64 = 2.0;
+
+    pub fn new(config: &StateKeeperConfig) -> Self {
+        Self {
+            rise_threshold: config.l1_gas_price_rise_threshold,
+            rise_deadline_factor: config
+                .l1_gas_price_rise_deadline_factor
+                .unwrap_or(Self::DEFAULT_RISE_DEADLINE_FACTOR),
+            fall_threshold: config.l1_gas_price_fall_threshold,
+            fall_deadline_factor: config
+                .l1_gas_price_fall_deadline_factor
+                .unwrap_or(Self::DEFAULT_FALL_DEADLINE_FACTOR),
+        }
+    }
+
+    /// Returns `true` if the sealer may adjust the deadline, i.e. if at least one of thresholds is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.rise_threshold.is_some() || self.fall_threshold.is_some()
+    }
+
+    /// Returns the factor to apply to the L1 batch commit deadline given the L1 gas price used by the batch
+    /// and the current L1 gas price.
+    pub fn deadline_factor(&self, batch_l1_gas_price: u64, current_l1_gas_price: u64) -> f64 {
+        if batch_l1_gas_price == 0 {
+            return 1.0;
+        }
+        let relative_change = current_l1_gas_price as f64 / batch_l1_gas_price as f64 - 1.0;
+        if matches!(self.rise_threshold, Some(threshold) if relative_change >= threshold) {
+            tracing::trace!(
+                "L1 gas price rose from {batch_l1_gas_price} to {current_l1_gas_price} since L1 batch was opened; \
+                 applying commit deadline factor {}",
+                self.rise_deadline_factor
+            );
+            self.rise_deadline_factor
+        } else if matches!(self.fall_threshold, Some(threshold) if relative_change <= -threshold) {
+            tracing::trace!(
+                "L1 gas price fell from {batch_l1_gas_price} to {current_l1_gas_price} since L1 batch was opened; \
+                 applying commit deadline factor {}",
+                self.fall_deadline_factor
+            );
+            self.fall_deadline_factor
+        } else {
+            1.0
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub(super) struct L2BlockMaxPayloadSizeSealer {
     max_payload_size: usize,
@@ -276,20 +380,61 @@ impl L2BlockMaxPayloadSizeSealer {
     }
 }
 
+/// Seals L2 blocks once the pubdata published by their transactions reaches the configured threshold.
+/// Transactions exceeding the threshold on their own are rejected by [`criteria::L2BlockPubdataCriterion`].
+#[derive(Debug, Clone, Copy)]
+pub(super) struct L2BlockMaxPubdataSealer {
+    max_pubdata: Option<u64>,
+}
+
+impl L2BlockMaxPubdataSealer {
+    pub fn new(config: &StateKeeperConfig) -> Self {
+        Self {
+            max_pubdata: config.l2_block_max_pubdata,
+        }
+    }
+
+    pub fn should_seal_l2_block(&mut self, manager: &UpdatesManager) -> bool {
+        let Some(max_pubdata) = self.max_pubdata else {
+            return false;
+        };
+        let metrics = &manager.l2_block.block_execution_metrics;
+        // Older VM versions don't report published pubdata; use an estimate based on execution metrics for them.
+        let pubdata = if metrics.pubdata_published == 0 {
+            metrics.size() as u64
+        } else {
+            metrics.pubdata_published.into()
+        };
+        pubdata >= max_pubdata
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use zksync_types::Address;
     use zksync_utils::time::seconds_since_epoch;
 
     use super::*;
-    use crate::tests::{create_execution_result, create_transaction, create_updates_manager};
+    use crate::tests::{
+        create_execution_result, create_transaction, create_updates_manager, default_l1_batch_env,
+        default_system_env,
+    };
 
     fn apply_tx_to_manager(tx: Transaction, manager: &mut UpdatesManager) {
+        apply_tx_with_metrics_to_manager(tx, ExecutionMetrics::default(), manager);
+    }
+
+    fn apply_tx_with_metrics_to_manager(
+        tx: Transaction,
+        execution_metrics: ExecutionMetrics,
+        manager: &mut UpdatesManager,
+    ) {
         manager.extend_from_executed_transaction(
             tx,
             create_execution_result(0, []),
             vec![],
             BlockGasCount::default(),
-            ExecutionMetrics::default(),
+            execution_metrics,
             vec![],
         );
     }
@@ -349,4 +494,79 @@ mod tests {
             "L2 block with payload encoding size equal or greater than max payload size should be sealed"
         );
     }
+
+    #[test]
+    fn max_pubdata_l2_block_sealer() {
+        let tx_metrics = ExecutionMetrics {
+            pubdata_published: 600,
+            ..ExecutionMetrics::default()
+        };
+        let mut max_pubdata_sealer = L2BlockMaxPubdataSealer {
+            max_pubdata: Some(1_000),
+        };
+        let mut disabled_sealer = L2BlockMaxPubdataSealer { max_pubdata: None };
+
+        let mut manager = create_updates_manager();
+        assert!(
+            !max_pubdata_sealer.should_seal_l2_block(&manager),
+            "Empty L2 block shouldn't be sealed"
+        );
+
+        apply_tx_with_metrics_to_manager(create_transaction(10, 100), tx_metrics, &mut manager);
+        assert!(
+            !max_pubdata_sealer.should_seal_l2_block(&manager),
+            "L2 block with pubdata below the threshold shouldn't be sealed"
+        );
+
+        apply_tx_with_metrics_to_manager(create_transaction(10, 100), tx_metrics, &mut manager);
+        assert!(
+            max_pubdata_sealer.should_seal_l2_block(&manager),
+            "L2 block with pubdata exceeding the threshold should be sealed"
+        );
+        assert!(!disabled_sealer.should_seal_l2_block(&manager));
+    }
+
+    #[test]
+    fn timeout_sealer_with_deadline_factor() {
+        let timeout_sealer = TimeoutSealer {
+            block_commit_deadline_ms: 10_000,
+            l2_block_commit_deadline_ms: 1_000,
+        };
+        // This relies on the fact that the test shouldn't run for more than 4 seconds.
+        let l1_batch_env = default_l1_batch_env(1, seconds_since_epoch() - 6, Address::default());
+        let mut manager = UpdatesManager::new(&l1_batch_env, &default_system_env());
+        assert!(
+            !timeout_sealer.should_seal_l1_batch_with_deadline_factor(&manager, 0.5),
+            "Empty L1 batch shouldn't be sealed"
+        );
+
+        apply_tx_to_manager(create_transaction(10, 100), &mut manager);
+        assert!(!timeout_sealer.should_seal_l1_batch_with_deadline_factor(&manager, 1.0));
+        assert!(!timeout_sealer.should_seal_l1_batch_with_deadline_factor(&manager, 2.0));
+        assert!(timeout_sealer.should_seal_l1_batch_with_deadline_factor(&manager, 0.5));
+    }
+
+    #[test]
+    fn l1_gas_price_trend_sealer() {
+        let config = StateKeeperConfig {
+            l1_gas_price_rise_threshold: Some(0.2),
+            l1_gas_price_fall_threshold: Some(0.5),
+            l1_gas_price_fall_deadline_factor: Some(3.0),
+            ..StateKeeperConfig::for_tests()
+        };
+        let sealer = L1GasPriceTrendSealer::new(&config);
+        assert!(sealer.is_enabled());
+        assert_eq!(sealer.deadline_factor(1_000, 1_000), 1.0);
+        assert_eq!(sealer.deadline_factor(1_000, 1_100), 1.0);
+        assert_eq!(sealer.deadline_factor(1_000, 1_300), 0.5);
+        assert_eq!(sealer.deadline_factor(1_000, 5_000), 0.5);
+        assert_eq!(sealer.deadline_factor(1_000, 600), 1.0);
+        assert_eq!(sealer.deadline_factor(1_000, 500), 3.0);
+        assert_eq!(sealer.deadline_factor(0, 1_000), 1.0);
+
+        let sealer = L1GasPriceTrendSealer::new(&StateKeeperConfig::for_tests());
+        assert!(!sealer.is_enabled());
+        assert_eq!(sealer.deadline_factor(1_000, 5_000), 1.0);
+        assert_eq!(sealer.deadline_factor(1_000, 100), 1.0);
+    }
 }
@@ -89,7 +89,9 @@ impl BatchExecutor for MockBatchExecutor {
             let mut recv = recv;
             while let Some(cmd) = recv.recv().await {
                 match cmd {
-                    Command::ExecuteTx(_, resp) => resp.send(successful_exec()).unwrap(),
+                    Command::ExecuteTx(_, resp) | Command::ReexecuteTx(_, resp) => {
+                        resp.send(successful_exec()).unwrap();
+                    }
                     Command::StartNextL2Block(_, resp) => resp.send(()).unwrap(),
                     Command::RollbackLastTx(_) => panic!("unexpected rollback"),
                     Command::FinishBatch(resp) => {
@@ -33,7 +33,7 @@ use crate::{
     testonly::{default_vm_batch_result, successful_exec, BASE_SYSTEM_CONTRACTS},
     types::ExecutionMetricsForCriteria,
     updates::UpdatesManager,
-    OutputHandler, StateKeeperOutputHandler, ZkSyncStateKeeper,
+    OutputHandler, ShutdownMode, StateKeeperOutputHandler, ZkSyncStateKeeper,
 };
 
 pub const FEE_ACCOUNT: Address = Address::repeat_byte(0x11);
@@ -55,6 +55,7 @@ pub(crate) struct TestScenario {
     pending_batch: Option<PendingBatchData>,
     l1_batch_seal_fn: Box<SealFn>,
     l2_block_seal_fn: Box<SealFn>,
+    shutdown_mode: ShutdownMode,
 }
 
 type SealFn = dyn FnMut(&UpdatesManager) -> bool + Send + Sync;
@@ -76,9 +77,23 @@ impl TestScenario {
             pending_batch: None,
             l1_batch_seal_fn: Box::new(|_| false),
             l2_block_seal_fn: Box::new(|_| false),
+            shutdown_mode: ShutdownMode::Immediate,
         }
     }
 
+    /// Sets the shutdown mode for the tested state keeper.
+    pub(crate) fn with_shutdown_mode(mut self, shutdown_mode: ShutdownMode) -> Self {
+        self.shutdown_mode = shutdown_mode;
+        self
+    }
+
+    /// Sends a stop signal to the state keeper. Tx requests after the signal are answered with `None`.
+    /// Must be followed by at least one other action (e.g., sealing an L2 block on shutdown).
+    pub(crate) fn stop(mut self, description: &'static str) -> Self {
+        self.actions.push_back(ScenarioItem::Stop(description));
+        self
+    }
+
     /// Adds a pending batch data that would be fed into the state keeper.
     /// Note that during processing pending batch, state keeper do *not* call `seal_l2_block` method on the IO (since
     /// it only recovers the temporary state).
@@ -197,6 +212,7 @@ impl TestScenario {
         assert!(!self.actions.is_empty(), "Test scenario can't be empty");
 
         let batch_executor_base = TestBatchExecutorBuilder::new(&self);
+        let shutdown_mode = self.shutdown_mode;
         let (stop_sender, stop_receiver) = watch::channel(false);
         let (io, output_handler) = TestIO::new(stop_sender, self);
         let state_keeper = ZkSyncStateKeeper::new(
@@ -206,7 +222,8 @@ impl TestScenario {
             output_handler,
             Arc::new(sealer),
             Arc::new(MockReadStorageFactory),
-        );
+        )
+        .with_shutdown_mode(shutdown_mode);
         let sk_thread = tokio::spawn(state_keeper.run());
 
         // We must assume that *theoretically* state keeper may ignore the stop signal from IO once scenario is
@@ -281,6 +298,8 @@ enum ScenarioItem {
     NoTxsUntilNextAction(&'static str),
     /// Increments protocol version in IO state.
     IncrementProtocolVersion(&'static str),
+    /// Sends a stop signal to the state keeper.
+    Stop(&'static str),
     Tx(&'static str, Transaction, TxExecutionResult),
     Rollback(&'static str, Transaction),
     Reject(&'static str, Transaction, UnexecutableReason),
@@ -305,6 +324,7 @@ impl fmt::Debug for ScenarioItem {
                 .debug_tuple("IncrementProtocolVersion")
                 .field(descr)
                 .finish(),
+            Self::Stop(descr) => formatter.debug_tuple("Stop").field(descr).finish(),
             Self::Tx(descr, tx, result) => formatter
                 .debug_tuple("Tx")
                 .field(descr)
@@ -461,7 +481,7 @@ impl TestBatchExecutor {
     pub(super) fn run(mut self) {
         while let Some(cmd) = self.commands.blocking_recv() {
             match cmd {
-                Command::ExecuteTx(tx, resp) => {
+                Command::ExecuteTx(tx, resp) | Command::ReexecuteTx(tx, resp) => {
                     let result = self
                         .txs
                         .get_mut(&tx.hash())
@@ -650,6 +670,12 @@ impl TestIO {
                     // This is a mock item, so pop an actual one for the IO to process.
                     continue;
                 }
+                ScenarioItem::Stop(_) => {
+                    self.stop_sender.send_replace(true);
+                    self.skipping_txs = true;
+                    // This is a mock item, so pop an actual one for the IO to process.
+                    continue;
+                }
                 _ => break action,
             }
         }
@@ -819,7 +845,9 @@ impl BatchExecutor for MockBatchExecutor {
             let mut recv = recv;
             while let Some(cmd) = recv.recv().await {
                 match cmd {
-                    Command::ExecuteTx(_, resp) => resp.send(successful_exec()).unwrap(),
+                    Command::ExecuteTx(_, resp) | Command::ReexecuteTx(_, resp) => {
+                        resp.send(successful_exec()).unwrap();
+                    }
                     Command::StartNextL2Block(_, resp) => resp.send(()).unwrap(),
                     Command::RollbackLastTx(_) => panic!("unexpected rollback"),
                     Command::FinishBatch(resp) => {
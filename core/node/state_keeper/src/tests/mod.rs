@@ -3,7 +3,7 @@ use std::{
         atomic::{AtomicBool, AtomicU64, Ordering},
         Arc,
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use multivm::{
@@ -27,7 +27,7 @@ use zksync_types::{
 };
 
 use crate::{
-    batch_executor::TxExecutionResult,
+    batch_executor::{budget::BatchBudgetExceeded, TxExecutionResult},
     io::PendingBatchData,
     keeper::POLL_WAIT_DURATION,
     seal_criteria::{
@@ -45,7 +45,7 @@ use crate::{
     types::ExecutionMetricsForCriteria,
     updates::UpdatesManager,
     utils::l1_batch_base_cost,
-    ZkSyncStateKeeper,
+    ShutdownMode, ZkSyncStateKeeper,
 };
 
 /// Creates a mock `PendingBatchData` object containing the provided sequence of L2 blocks.
@@ -380,6 +380,67 @@ async fn bootloader_tip_out_of_gas_flow() {
         .await;
 }
 
+#[tokio::test]
+async fn batch_budget_exceeded_flow() {
+    let config = StateKeeperConfig {
+        transaction_slots: 2,
+        ..StateKeeperConfig::default()
+    };
+    let sealer = SequencerSealer::with_sealers(config, vec![Box::new(SlotsCriterion)]);
+
+    let budget_exceeded = |l1_batch_number| {
+        TxExecutionResult::BatchBudgetExceeded(BatchBudgetExceeded::ExecutionTime {
+            l1_batch_number: L1BatchNumber(l1_batch_number),
+            execution_time: Duration::from_secs(2),
+            budget: Duration::from_secs(1),
+        })
+    };
+    let memory_heavy_tx = random_tx(1);
+    let long_tx = random_tx(2);
+    let excluded_tx = random_tx(3);
+    TestScenario::new()
+        .seal_l2_block_when(|updates| updates.l2_block.executed_transactions.len() == 1)
+        // A transaction exceeding the memory budget on its own cannot be executed.
+        .next_tx(
+            "Memory-heavy tx",
+            memory_heavy_tx.clone(),
+            TxExecutionResult::BatchBudgetExceeded(BatchBudgetExceeded::VmMemory {
+                l1_batch_number: L1BatchNumber(1),
+                used_bytes: 2_048,
+                budget_bytes: 1_024,
+            }),
+        )
+        .tx_rejected(
+            "Memory-heavy tx rejected",
+            memory_heavy_tx,
+            UnexecutableReason::BatchBudgetExceeded,
+        )
+        // A transaction exceeding the execution time budget on its own is returned to the mempool
+        // and is retried without the budget.
+        .next_tx("Long tx", long_tx.clone(), budget_exceeded(1))
+        .tx_rollback("Long tx returned to mempool", long_tx.clone())
+        .next_tx("Long tx retried", long_tx, successful_exec())
+        .l2_block_sealed("L2 block with 1st tx")
+        .next_tx(
+            "Tx -> Budget exceeded",
+            excluded_tx.clone(),
+            budget_exceeded(1),
+        )
+        .tx_rollback("Last tx rolled back to seal the batch", excluded_tx.clone())
+        .batch_sealed("Batch sealed with 1 tx")
+        .next_tx("Same tx now succeeds", excluded_tx, successful_exec())
+        .l2_block_sealed("L2 block with this tx sealed")
+        .next_tx(
+            "Second tx of the 2nd batch",
+            random_tx(4),
+            successful_exec(),
+        )
+        .l2_block_sealed("L2 block with 2nd tx")
+        .batch_sealed("2nd batch sealed")
+        .run(sealer)
+        .await;
+}
+
 #[tokio::test]
 async fn pending_batch_is_applied() {
     let config = StateKeeperConfig {
@@ -511,6 +572,40 @@ async fn unconditional_sealing() {
         .await;
 }
 
+#[tokio::test]
+async fn graceful_shutdown_seals_l2_block() {
+    TestScenario::new()
+        .with_shutdown_mode(ShutdownMode::Graceful {
+            l1_batch_seal_timeout: None,
+        })
+        .next_tx("The only tx", random_tx(1), successful_exec())
+        .stop("Stop signal is sent")
+        .l2_block_sealed_with("L2 block is sealed on shutdown", |updates| {
+            assert_eq!(updates.l2_block.executed_transactions.len(), 1);
+        })
+        .run(SequencerSealer::default())
+        .await;
+}
+
+#[tokio::test]
+async fn graceful_shutdown_seals_l1_batch() {
+    TestScenario::new()
+        .with_shutdown_mode(ShutdownMode::Graceful {
+            l1_batch_seal_timeout: Some(Duration::from_secs(10)),
+        })
+        .next_tx("First tx", random_tx(1), successful_exec())
+        .next_tx("Second tx", random_tx(2), successful_exec())
+        .stop("Stop signal is sent")
+        .l2_block_sealed("L2 block is sealed on shutdown")
+        .batch_sealed_with("L1 batch is sealed on shutdown", |updates| {
+            assert_eq!(updates.l1_batch.executed_transactions.len(), 2);
+            // The last L2 block in the batch is the fictive one.
+            assert!(updates.l2_block.executed_transactions.is_empty());
+        })
+        .run(SequencerSealer::default())
+        .await;
+}
+
 /// Checks the next L2 block sealed after pending batch has a correct timestamp
 #[tokio::test]
 async fn l2_block_timestamp_after_pending_batch() {
@@ -1,13 +1,15 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{Arc, Mutex},
 };
 
 use multivm::interface::VmExecutionResultAndLogs;
+use zksync_config::configs::chain::MempoolConfig;
 use zksync_dal::{Connection, Core, CoreDal};
-use zksync_mempool::{L2TxFilter, MempoolInfo, MempoolStore};
+use zksync_mempool::{L2TxFilter, L2TxOrdering, MempoolInfo, MempoolStore};
 use zksync_types::{
-    block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce, PriorityOpId, Transaction,
+    api::TransactionDeadline, block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce,
+    PriorityOpId, Transaction, H256,
 };
 
 use super::{
@@ -16,52 +18,129 @@ use super::{
 };
 
 #[derive(Debug, Clone)]
-pub struct MempoolGuard(Arc<Mutex<MempoolStore>>);
+pub struct MempoolGuard {
+    store: Arc<Mutex<MempoolStore>>,
+    /// Inclusion deadlines for transactions in the mempool. Only contains transactions submitted with a deadline.
+    deadlines: Arc<Mutex<HashMap<H256, TransactionDeadline>>>,
+}
 
 impl MempoolGuard {
-    pub async fn from_storage(storage_processor: &mut Connection<'_, Core>, capacity: u64) -> Self {
+    pub async fn from_storage(
+        storage_processor: &mut Connection<'_, Core>,
+        config: &MempoolConfig,
+    ) -> Self {
         let next_priority_id = storage_processor
             .transactions_dal()
             .next_priority_id()
             .await;
-        Self::new(next_priority_id, capacity)
+        let ordering = if config.priority_fee_ordering {
+            L2TxOrdering::PriorityFee {
+                window_ms: config.priority_fee_ordering_window_ms,
+                max_priority_fee: config.priority_fee_ordering_max_fee,
+            }
+        } else {
+            L2TxOrdering::Fifo
+        };
+        let store = MempoolStore::new(next_priority_id, config.capacity)
+            .with_ordering(ordering)
+            .with_priority_lane(
+                config.priority_lane_addresses.iter().copied().collect(),
+                config.priority_lane_capacity,
+            );
+        Self::from_store(store)
     }
 
     pub(super) fn new(next_priority_id: PriorityOpId, capacity: u64) -> Self {
-        let store = MempoolStore::new(next_priority_id, capacity);
-        Self(Arc::new(Mutex::new(store)))
+        Self::from_store(MempoolStore::new(next_priority_id, capacity))
+    }
+
+    fn from_store(store: MempoolStore) -> Self {
+        Self {
+            store: Arc::new(Mutex::new(store)),
+            deadlines: Arc::default(),
+        }
     }
 
     pub fn insert(&mut self, transactions: Vec<Transaction>, nonces: HashMap<Address, Nonce>) {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .insert(transactions, nonces);
     }
 
     pub fn has_next(&self, filter: &L2TxFilter) -> bool {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .has_next(filter)
     }
 
     pub fn next_transaction(&mut self, filter: &L2TxFilter) -> Option<Transaction> {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .next_transaction(filter)
     }
 
     pub fn rollback(&mut self, rejected: &Transaction) {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .rollback(rejected);
     }
 
+    /// Remembers inclusion deadlines for transactions. Must be called before the transactions are inserted
+    /// into the mempool.
+    pub fn insert_deadlines(&mut self, deadlines: HashMap<H256, TransactionDeadline>) {
+        if deadlines.is_empty() {
+            return;
+        }
+        self.deadlines
+            .lock()
+            .expect("failed to acquire mempool deadlines lock")
+            .extend(deadlines);
+    }
+
+    /// Boosts transactions to the priority lane. Must be called before the transactions are inserted
+    /// into the mempool.
+    pub fn boost_transactions(&mut self, tx_hashes: HashSet<H256>) {
+        if tx_hashes.is_empty() {
+            return;
+        }
+        self.store
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .boost_transactions(tx_hashes);
+    }
+
+    /// Removes and returns the inclusion deadline for a transaction, if it has one.
+    pub fn take_deadline(&mut self, tx_hash: H256) -> Option<TransactionDeadline> {
+        self.deadlines
+            .lock()
+            .expect("failed to acquire mempool deadlines lock")
+            .remove(&tx_hash)
+    }
+
+    /// Forgets inclusion deadlines for transactions that are not in the mempool (e.g., were not inserted
+    /// because of an outdated nonce, or were evicted from the mempool). Returns the number of removed deadlines.
+    pub fn prune_deadlines(&mut self) -> usize {
+        let tx_hashes: HashSet<_> = self
+            .store
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .l2_transaction_hashes()
+            .collect();
+        let mut deadlines = self
+            .deadlines
+            .lock()
+            .expect("failed to acquire mempool deadlines lock");
+        let original_len = deadlines.len();
+        deadlines.retain(|tx_hash, _| tx_hashes.contains(tx_hash));
+        original_len - deadlines.len()
+    }
+
     pub fn get_mempool_info(&mut self) -> MempoolInfo {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .get_mempool_info()
@@ -69,14 +148,14 @@ impl MempoolGuard {
 
     #[cfg(test)]
     pub fn stats(&self) -> zksync_mempool::MempoolStats {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .stats()
     }
 
     pub fn register_metrics(&self) {
-        StateKeeperGauges::register(Arc::downgrade(&self.0));
+        StateKeeperGauges::register(Arc::downgrade(&self.store));
     }
 }
 
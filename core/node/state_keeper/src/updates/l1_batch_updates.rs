@@ -6,7 +6,10 @@ use zksync_types::{
     ExecuteTransactionCommon, L1BatchNumber,
 };
 
-use crate::{updates::l2_block_updates::L2BlockUpdates, utils::new_block_gas_count};
+use crate::{
+    updates::{l2_block_updates::L2BlockUpdates, PayloadSize},
+    utils::new_block_gas_count,
+};
 
 #[derive(Debug)]
 pub struct L1BatchUpdates {
@@ -17,7 +20,11 @@ pub struct L1BatchUpdates {
     // how much L1 gas will it take to submit this block?
     pub l1_gas_count: BlockGasCount,
     pub txs_encoding_size: usize,
+    pub payload_encoding_size: usize,
+    pub events_encoding_size: usize,
     pub finished: Option<FinishedL1Batch>,
+    /// Whether to keep factory deps of transactions from sealed L2 blocks.
+    retain_factory_deps: bool,
 }
 
 impl L1BatchUpdates {
@@ -29,16 +36,26 @@ impl L1BatchUpdates {
             block_execution_metrics: Default::default(),
             l1_gas_count: new_block_gas_count(),
             txs_encoding_size: 0,
+            payload_encoding_size: 0,
+            events_encoding_size: 0,
             finished: None,
+            retain_factory_deps: true,
         }
     }
 
-    pub(crate) fn extend_from_sealed_l2_block(&mut self, l2_block_updates: L2BlockUpdates) {
-        for tx in &l2_block_updates.executed_transactions {
+    pub(crate) fn discard_factory_deps(&mut self) {
+        self.retain_factory_deps = false;
+    }
+
+    pub(crate) fn extend_from_sealed_l2_block(&mut self, mut l2_block_updates: L2BlockUpdates) {
+        for tx in &mut l2_block_updates.executed_transactions {
             if let ExecuteTransactionCommon::L1(data) = &tx.transaction.common_data {
                 let onchain_metadata = data.onchain_metadata().onchain_data;
                 self.priority_ops_onchain_data.push(onchain_metadata);
             }
+            if !self.retain_factory_deps {
+                tx.transaction.execute.factory_deps = None;
+            }
         }
         self.executed_transactions
             .extend(l2_block_updates.executed_transactions);
@@ -46,6 +63,17 @@ impl L1BatchUpdates {
         self.l1_gas_count += l2_block_updates.l1_gas_count;
         self.block_execution_metrics += l2_block_updates.block_execution_metrics;
         self.txs_encoding_size += l2_block_updates.txs_encoding_size;
+        self.payload_encoding_size += l2_block_updates.payload_encoding_size;
+        self.events_encoding_size += l2_block_updates.events_encoding_size;
+    }
+
+    /// Returns the cumulative serialized payload size of the sealed L2 blocks in this batch.
+    pub fn payload_size(&self) -> PayloadSize {
+        PayloadSize {
+            txs: self.payload_encoding_size,
+            events: self.events_encoding_size,
+            pubdata: self.block_execution_metrics.pubdata_published as usize,
+        }
     }
 }
 
@@ -71,6 +99,8 @@ mod tests {
         );
         let tx = create_transaction(10, 100);
         let expected_tx_size = tx.bootloader_encoding_size();
+        let expected_payload_size =
+            zksync_protobuf::repr::encode::<zksync_dal::consensus::proto::Transaction>(&tx).len();
 
         l2_block_accumulator.extend_from_executed_transaction(
             tx,
@@ -92,5 +122,9 @@ mod tests {
             0
         );
         assert_eq!(l1_batch_accumulator.txs_encoding_size, expected_tx_size);
+        assert_eq!(
+            l1_batch_accumulator.payload_size().txs,
+            expected_payload_size
+        );
     }
 }
@@ -10,11 +10,11 @@ use zksync_types::{
     l2_to_l1_log::{SystemL2ToL1Log, UserL2ToL1Log},
     tx::{tx_execution_info::TxExecutionStatus, ExecutionMetrics, TransactionExecutionResult},
     vm_trace::Call,
-    L2BlockNumber, ProtocolVersionId, StorageLogQuery, Transaction, VmEvent, H256,
+    Address, L2BlockNumber, ProtocolVersionId, StorageLogQuery, Transaction, VmEvent, H256,
 };
 use zksync_utils::bytecode::{hash_bytecode, CompressedBytecodeInfo};
 
-use crate::metrics::KEEPER_METRICS;
+use crate::{metrics::KEEPER_METRICS, updates::PayloadSize};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct L2BlockUpdates {
@@ -29,6 +29,8 @@ pub struct L2BlockUpdates {
     pub block_execution_metrics: ExecutionMetrics,
     pub txs_encoding_size: usize,
     pub payload_encoding_size: usize,
+    /// Total serialized size of `events` (emitter address, indexed topics and data).
+    pub events_encoding_size: usize,
     pub timestamp: u64,
     pub number: L2BlockNumber,
     pub prev_block_hash: H256,
@@ -55,6 +57,7 @@ impl L2BlockUpdates {
             block_execution_metrics: ExecutionMetrics::default(),
             txs_encoding_size: 0,
             payload_encoding_size: 0,
+            events_encoding_size: 0,
             timestamp,
             number,
             prev_block_hash,
@@ -69,6 +72,7 @@ impl L2BlockUpdates {
         l1_gas_count: BlockGasCount,
         execution_metrics: ExecutionMetrics,
     ) {
+        self.events_encoding_size += events_encoding_size(&result.logs.events);
         self.events.extend(result.logs.events);
         self.storage_logs.extend(result.logs.storage_logs);
         self.user_l2_to_l1_logs
@@ -91,6 +95,7 @@ impl L2BlockUpdates {
     ) {
         let saved_factory_deps =
             extract_bytecodes_marked_as_known(&tx_execution_result.logs.events);
+        self.events_encoding_size += events_encoding_size(&tx_execution_result.logs.events);
         self.events.extend(tx_execution_result.logs.events);
         self.user_l2_to_l1_logs
             .extend(tx_execution_result.logs.user_l2_to_l1_logs);
@@ -160,6 +165,15 @@ impl L2BlockUpdates {
         });
     }
 
+    /// Returns the cumulative serialized payload size of this L2 block.
+    pub fn payload_size(&self) -> PayloadSize {
+        PayloadSize {
+            txs: self.payload_encoding_size,
+            events: self.events_encoding_size,
+            pubdata: self.block_execution_metrics.pubdata_published as usize,
+        }
+    }
+
     /// Calculates L2 block hash based on the protocol version.
     pub(crate) fn get_l2_block_hash(&self) -> H256 {
         let mut digest = L2BlockHasher::new(self.number, self.timestamp, self.prev_block_hash);
@@ -179,6 +193,17 @@ impl L2BlockUpdates {
     }
 }
 
+fn events_encoding_size(events: &[VmEvent]) -> usize {
+    events
+        .iter()
+        .map(|event| {
+            Address::len_bytes()
+                + event.indexed_topics.len() * H256::len_bytes()
+                + event.value.len()
+        })
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use multivm::vm_latest::TransactionVmExt;
@@ -219,5 +244,48 @@ mod tests {
         assert_eq!(accumulator.block_execution_metrics.l2_to_l1_logs, 0);
         assert_eq!(accumulator.txs_encoding_size, bootloader_encoding_size);
         assert_eq!(accumulator.payload_encoding_size, payload_encoding_size);
+        assert_eq!(
+            accumulator.payload_size(),
+            PayloadSize {
+                txs: payload_encoding_size,
+                events: 0,
+                pubdata: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn events_payload_size() {
+        let mut accumulator = L2BlockUpdates::new(
+            0,
+            L2BlockNumber(0),
+            H256::random(),
+            0,
+            ProtocolVersionId::latest(),
+        );
+        let mut tx_result = create_execution_result(0, []);
+        tx_result.logs.events = vec![VmEvent {
+            location: Default::default(),
+            address: Address::repeat_byte(1),
+            indexed_topics: vec![H256::repeat_byte(2), H256::repeat_byte(3)],
+            value: vec![0; 64],
+        }];
+        let execution_metrics = ExecutionMetrics {
+            pubdata_published: 100,
+            ..ExecutionMetrics::default()
+        };
+
+        accumulator.extend_from_executed_transaction(
+            create_transaction(10, 100),
+            tx_result,
+            BlockGasCount::default(),
+            execution_metrics,
+            vec![],
+            vec![],
+        );
+
+        let payload_size = accumulator.payload_size();
+        assert_eq!(payload_size.events, 20 + 2 * 32 + 64);
+        assert_eq!(payload_size.pubdata, 100);
     }
 }
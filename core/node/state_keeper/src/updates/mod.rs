@@ -1,13 +1,16 @@
+use std::ops;
+
 use multivm::{
     interface::{FinishedL1Batch, L1BatchEnv, SystemEnv, VmExecutionResultAndLogs},
     utils::get_batch_base_fee,
 };
 use zksync_contracts::BaseSystemContractsHashes;
+use zksync_dal::transactions_dal::CallTraceFormat;
 use zksync_types::{
     block::BlockGasCount, fee_model::BatchFeeInput,
     storage_writes_deduplicator::StorageWritesDeduplicator,
     tx::tx_execution_info::ExecutionMetrics, vm_trace::Call, Address, L1BatchNumber, L2BlockNumber,
-    ProtocolVersionId, Transaction,
+    ProtocolVersionId, Transaction, H256,
 };
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
@@ -16,11 +19,44 @@ use super::{
     io::{IoCursor, L2BlockParams},
     metrics::BATCH_TIP_METRICS,
 };
-use crate::types::ExecutionMetricsForCriteria;
+use crate::{batch_executor::tracers::BatchTracerOutput, types::ExecutionMetricsForCriteria};
 
 pub mod l1_batch_updates;
 pub mod l2_block_updates;
 
+/// Cumulative serialized size of the data produced by executed transactions, in bytes.
+///
+/// Can be used by output handlers and seal criteria to make size-based decisions without re-encoding
+/// transactions or events.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PayloadSize {
+    /// Size of transactions encoded in the consensus payload.
+    pub txs: usize,
+    /// Size of emitted events (emitter address, indexed topics and data).
+    pub events: usize,
+    /// Size of published pubdata.
+    pub pubdata: usize,
+}
+
+impl PayloadSize {
+    /// Returns the total size across all kinds of data.
+    pub fn total(&self) -> usize {
+        self.txs + self.events + self.pubdata
+    }
+}
+
+impl ops::Add for PayloadSize {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            txs: self.txs + rhs.txs,
+            events: self.events + rhs.events,
+            pubdata: self.pubdata + rhs.pubdata,
+        }
+    }
+}
+
 /// Most of the information needed to seal the l1 batch / L2 block is contained within the VM,
 /// things that are not captured there are accumulated externally.
 /// `L2BlockUpdates` keeps updates for the pending L2 block.
@@ -38,6 +74,7 @@ pub struct UpdatesManager {
     pub l1_batch: L1BatchUpdates,
     pub l2_block: L2BlockUpdates,
     pub storage_writes_deduplicator: StorageWritesDeduplicator,
+    tracer_output: Option<BatchTracerOutput>,
 }
 
 impl UpdatesManager {
@@ -59,14 +96,32 @@ impl UpdatesManager {
                 protocol_version,
             ),
             storage_writes_deduplicator: StorageWritesDeduplicator::new(),
+            tracer_output: None,
         }
     }
 
+    /// Makes this manager discard factory deps of transactions once the containing L2 block is sealed
+    /// (i.e., [`Self::push_l2_block()`] is called), so that bytecodes deployed in the batch are not held
+    /// in memory until the batch is finished. Factory deps of the in-progress L2 block (including bytecodes
+    /// published in it) are still available via [`Self::l2_block`].
+    ///
+    /// This must only be used by consumers that don't access factory deps in [`Self::l1_batch`], e.g.,
+    /// to seal the batch.
+    #[must_use]
+    pub fn without_sealed_factory_deps(mut self) -> Self {
+        self.l1_batch.discard_factory_deps();
+        self
+    }
+
     pub(crate) fn batch_timestamp(&self) -> u64 {
         self.batch_timestamp
     }
 
-    pub(crate) fn base_system_contract_hashes(&self) -> BaseSystemContractsHashes {
+    pub(crate) fn batch_fee_input(&self) -> BatchFeeInput {
+        self.batch_fee_input
+    }
+
+    pub fn base_system_contract_hashes(&self) -> BaseSystemContractsHashes {
         self.base_system_contract_hashes
     }
 
@@ -83,6 +138,7 @@ impl UpdatesManager {
         &self,
         l2_shared_bridge_addr: Address,
         pre_insert_txs: bool,
+        call_trace_format: CallTraceFormat,
     ) -> L2BlockSealCommand {
         L2BlockSealCommand {
             l1_batch_number: self.l1_batch.number,
@@ -95,10 +151,21 @@ impl UpdatesManager {
             protocol_version: Some(self.protocol_version),
             l2_shared_bridge_addr,
             pre_insert_txs,
+            call_trace_format,
         }
     }
 
-    pub(crate) fn protocol_version(&self) -> ProtocolVersionId {
+    /// Sets the output of custom VM tracers attached to the batch execution.
+    pub fn set_tracer_output(&mut self, output: BatchTracerOutput) {
+        self.tracer_output = Some(output);
+    }
+
+    /// Returns the output of custom VM tracers attached to the batch execution, provided that it has the specified type.
+    pub fn tracer_output<T: 'static>(&self) -> Option<&T> {
+        self.tracer_output.as_ref()?.downcast_ref()
+    }
+
+    pub fn protocol_version(&self) -> ProtocolVersionId {
         self.protocol_version
     }
 
@@ -176,6 +243,31 @@ impl UpdatesManager {
     pub(crate) fn pending_txs_encoding_size(&self) -> usize {
         self.l1_batch.txs_encoding_size + self.l2_block.txs_encoding_size
     }
+
+    /// Returns the cumulative payload size of the in-progress L2 block.
+    pub fn l2_block_payload_size(&self) -> PayloadSize {
+        self.l2_block.payload_size()
+    }
+
+    /// Returns the cumulative payload size of the in-progress L1 batch, including the in-progress L2 block.
+    pub fn pending_payload_size(&self) -> PayloadSize {
+        self.l1_batch.payload_size() + self.l2_block.payload_size()
+    }
+
+    /// Returns call traces of transactions executed in the in-progress L1 batch (including the in-progress L2 block)
+    /// in the execution order, together with transaction hashes. Transactions without call traces are skipped.
+    ///
+    /// Call traces are only collected if the batch executor is configured to save them
+    /// (see [`MainBatchExecutor::new()`](crate::MainBatchExecutor::new())), so that output handlers can persist
+    /// or analyze traces without re-executing transactions.
+    pub fn call_traces(&self) -> impl Iterator<Item = (H256, &[Call])> + '_ {
+        let l1_batch_txs = self.l1_batch.executed_transactions.iter();
+        let l2_block_txs = self.l2_block.executed_transactions.iter();
+        l1_batch_txs
+            .chain(l2_block_txs)
+            .filter(|tx| !tx.call_traces.is_empty())
+            .map(|tx| (tx.hash, tx.call_traces.as_slice()))
+    }
 }
 
 /// Command to seal an L2 block containing all necessary data for it.
@@ -194,6 +286,8 @@ pub struct L2BlockSealCommand {
     /// Should be set to `true` for EN's IO as EN doesn't store transactions in DB
     /// before they are included into L2 blocks.
     pub pre_insert_txs: bool,
+    /// Format used to persist call traces of the executed transactions.
+    pub call_trace_format: CallTraceFormat,
 }
 
 #[cfg(test)]
@@ -238,4 +332,113 @@ mod tests {
         assert_eq!(updates_manager.l2_block.executed_transactions.len(), 0);
         assert_eq!(updates_manager.l1_batch.executed_transactions.len(), 1);
     }
+
+    #[test]
+    fn discarding_sealed_factory_deps() {
+        for retain_factory_deps in [false, true] {
+            let mut updates_manager = create_updates_manager();
+            if !retain_factory_deps {
+                updates_manager = updates_manager.without_sealed_factory_deps();
+            }
+            let mut tx = create_transaction(10, 100);
+            tx.execute.factory_deps = Some(vec![vec![1; 32]]);
+            updates_manager.extend_from_executed_transaction(
+                tx,
+                create_execution_result(0, []),
+                vec![],
+                new_block_gas_count(),
+                ExecutionMetrics::default(),
+                vec![],
+            );
+            let tx = &updates_manager.l2_block.executed_transactions[0].transaction;
+            assert!(tx.execute.factory_deps.is_some());
+
+            updates_manager.push_l2_block(L2BlockParams {
+                timestamp: 2,
+                virtual_blocks: 1,
+            });
+            let tx = &updates_manager.l1_batch.executed_transactions[0].transaction;
+            assert_eq!(
+                tx.execute.factory_deps.is_some(),
+                retain_factory_deps,
+                "{tx:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn payload_size_accounting() {
+        let mut updates_manager = create_updates_manager();
+        assert_eq!(
+            updates_manager.pending_payload_size(),
+            PayloadSize::default()
+        );
+
+        let tx = create_transaction(10, 100);
+        let tx_payload_size =
+            zksync_protobuf::repr::encode::<zksync_dal::consensus::proto::Transaction>(&tx).len();
+        let execution_metrics = ExecutionMetrics {
+            pubdata_published: 50,
+            ..ExecutionMetrics::default()
+        };
+        updates_manager.extend_from_executed_transaction(
+            tx,
+            create_execution_result(0, []),
+            vec![],
+            new_block_gas_count(),
+            execution_metrics,
+            vec![],
+        );
+        let expected_size = PayloadSize {
+            txs: tx_payload_size,
+            events: 0,
+            pubdata: 50,
+        };
+        assert_eq!(updates_manager.l2_block_payload_size(), expected_size);
+        assert_eq!(updates_manager.pending_payload_size(), expected_size);
+
+        updates_manager.push_l2_block(L2BlockParams {
+            timestamp: 2,
+            virtual_blocks: 1,
+        });
+        assert_eq!(
+            updates_manager.l2_block_payload_size(),
+            PayloadSize::default()
+        );
+        assert_eq!(updates_manager.pending_payload_size(), expected_size);
+    }
+
+    #[test]
+    fn call_traces_accessor() {
+        let mut updates_manager = create_updates_manager();
+        assert_eq!(updates_manager.call_traces().count(), 0);
+
+        let traced_tx = create_transaction(10, 100);
+        let traced_tx_hash = traced_tx.hash();
+        updates_manager.extend_from_executed_transaction(
+            traced_tx,
+            create_execution_result(0, []),
+            vec![],
+            new_block_gas_count(),
+            ExecutionMetrics::default(),
+            vec![Call::default()],
+        );
+        updates_manager.push_l2_block(L2BlockParams {
+            timestamp: 2,
+            virtual_blocks: 1,
+        });
+        updates_manager.extend_from_executed_transaction(
+            create_transaction(10, 100),
+            create_execution_result(1, []),
+            vec![],
+            new_block_gas_count(),
+            ExecutionMetrics::default(),
+            vec![],
+        );
+
+        let call_traces: Vec<_> = updates_manager.call_traces().collect();
+        assert_eq!(call_traces.len(), 1);
+        assert_eq!(call_traces[0].0, traced_tx_hash);
+        assert_eq!(call_traces[0].1.len(), 1);
+    }
 }
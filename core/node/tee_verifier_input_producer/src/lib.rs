@@ -18,9 +18,9 @@ use zksync_dal::{tee_verifier_input_producer_dal::JOB_MAX_ATTEMPT, ConnectionPoo
 use zksync_object_store::ObjectStore;
 use zksync_prover_interface::inputs::PrepareBasicCircuitsJob;
 use zksync_queued_job_processor::JobProcessor;
-use zksync_state::{PostgresStorage, ReadStorage};
+use zksync_state::{ReadStorage, StorageViewAt};
 use zksync_tee_verifier::TeeVerifierInput;
-use zksync_types::{block::L1BatchHeader, L1BatchNumber, L2BlockNumber, L2ChainId};
+use zksync_types::{block::L1BatchHeader, L1BatchNumber, L2BlockNumber, L2ChainId, H256};
 use zksync_utils::u256_to_h256;
 
 use self::metrics::METRICS;
@@ -32,6 +32,7 @@ mod metrics;
 pub struct TeeVerifierInputProducer {
     connection_pool: ConnectionPool<Core>,
     l2_chain_id: L2ChainId,
+    evm_emulator_hash: Option<H256>,
     object_store: Arc<dyn ObjectStore>,
 }
 
@@ -45,9 +46,17 @@ impl TeeVerifierInputProducer {
             connection_pool,
             object_store,
             l2_chain_id,
+            evm_emulator_hash: None,
         })
     }
 
+    /// Sets the hash of the EVM emulator enabled for the chain (usually, taken from the genesis config).
+    #[must_use]
+    pub fn with_evm_emulator_hash(mut self, evm_emulator_hash: Option<H256>) -> Self {
+        self.evm_emulator_hash = evm_emulator_hash;
+        self
+    }
+
     async fn process_job_impl(
         rt_handle: Handle,
         l1_batch_number: L1BatchNumber,
@@ -55,6 +64,7 @@ impl TeeVerifierInputProducer {
         connection_pool: ConnectionPool<Core>,
         object_store: Arc<dyn ObjectStore>,
         l2_chain_id: L2ChainId,
+        evm_emulator_hash: Option<H256>,
     ) -> anyhow::Result<TeeVerifierInput> {
         let prepare_basic_circuits_job: PrepareBasicCircuitsJob = object_store
             .get(l1_batch_number)
@@ -82,7 +92,8 @@ impl TeeVerifierInputProducer {
 
         let l1_batch_params_provider = L1BatchParamsProvider::new(&mut connection)
             .await
-            .context("failed initializing L1 batch params provider")?;
+            .context("failed initializing L1 batch params provider")?
+            .with_evm_emulator_hash(evm_emulator_hash);
 
         let first_miniblock_in_batch = l1_batch_params_provider
             .load_first_l2_block_in_batch(&mut connection, l1_batch_number)
@@ -158,8 +169,8 @@ impl TeeVerifierInputProducer {
             .block_on(connection_pool.connection())
             .context("failed to get connection for TeeVerifierInputProducer")?;
 
-        let mut pg_storage =
-            PostgresStorage::new(rt_handle, connection, last_batch_miniblock_number, true);
+        let mut pg_storage = StorageViewAt::l2_block(last_batch_miniblock_number)
+            .postgres_blocking(rt_handle, connection)?;
 
         Ok(l1_batch_header
             .used_contract_hashes
@@ -214,6 +225,7 @@ impl JobProcessor for TeeVerifierInputProducer {
         started_at: Instant,
     ) -> JoinHandle<anyhow::Result<Self::JobArtifacts>> {
         let l2_chain_id = self.l2_chain_id;
+        let evm_emulator_hash = self.evm_emulator_hash;
         let connection_pool = self.connection_pool.clone();
         let object_store = self.object_store.clone();
         tokio::task::spawn(async move {
@@ -225,6 +237,7 @@ impl JobProcessor for TeeVerifierInputProducer {
                 connection_pool.clone(),
                 object_store,
                 l2_chain_id,
+                evm_emulator_hash,
             )
             .await
         })
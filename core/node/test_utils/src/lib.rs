@@ -129,8 +129,11 @@ pub fn create_l2_transaction(fee_per_gas: u64, gas_per_pubdata: u64) -> L2Tx {
     .unwrap();
     // Input means all transaction data (NOT calldata, but all tx fields) that came from the API.
     // This input will be used for the derivation of the tx hash, so put some random to it to be sure
-    // that the transaction hash is unique.
-    tx.set_input(H256::random().0.to_vec(), H256::random());
+    // that the transaction hash is unique. The first byte is zeroed so that the input cannot be parsed
+    // as a typed transaction (e.g., by the chain ID checks in the API server).
+    let mut input = H256::random().0.to_vec();
+    input[0] = 0;
+    tx.set_input(input, H256::random());
     tx
 }
 
@@ -0,0 +1,476 @@
+//! Comparative benchmark for the VM runner.
+//!
+//! Replays a range of L1 batches from a Postgres database (e.g., a restored dump of a real network)
+//! through the VM runner with different VM versions and window sizes, and reports transaction and VM instruction
+//! throughput together with the share of batch storage accesses served by RocksDB vs Postgres. Outputs are discarded,
+//! so the benchmark doesn't modify the database.
+//!
+//! Should be compiled with the release profile, otherwise VM execution would be prohibitively slow.
+
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use clap::{Parser, ValueEnum};
+use tempfile::TempDir;
+use tokio::{sync::watch, task::JoinHandle};
+use tracing_subscriber::EnvFilter;
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_state::{PgOrRocksdbStorage, ReadStorageFactory};
+use zksync_state_keeper::{MainBatchExecutor, StateKeeperOutputHandler, UpdatesManager};
+use zksync_types::{url::SensitiveUrl, L1BatchNumber, L2BlockNumber, L2ChainId, VmVersion, H256};
+use zksync_vm_runner::{
+    BatchExecuteData, ConcurrentOutputHandlerFactory, ConcurrentOutputHandlerOptions,
+    L2BlockCheckpoint, OutputHandlerFactory, StorageLoader, VmRunner, VmRunnerIo, VmRunnerStorage,
+    VmRunnerStorageBackend,
+};
+
+/// Name of the benchmarked VM runner instance.
+const BENCHMARK_NAME: &str = "vm_runner_benchmark";
+
+/// VM to execute batches with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum VmKind {
+    /// VM version determined by the protocol version of each batch, as in production.
+    Protocol,
+    /// Latest VM version, regardless of the protocol version of batches.
+    Latest,
+    /// VM 1.5.0 with small bootloader memory.
+    #[value(name = "1.5.0-small-memory")]
+    Vm1_5_0SmallBootloaderMemory,
+    /// VM 1.4.2.
+    #[value(name = "1.4.2")]
+    Vm1_4_2,
+}
+
+impl VmKind {
+    fn create_executor(self, save_call_traces: bool) -> MainBatchExecutor {
+        let executor = MainBatchExecutor::new(save_call_traces, false);
+        let vm_version = match self {
+            Self::Protocol => return executor,
+            Self::Latest => VmVersion::latest(),
+            Self::Vm1_5_0SmallBootloaderMemory => VmVersion::Vm1_5_0SmallBootloaderMemory,
+            Self::Vm1_4_2 => VmVersion::Vm1_4_2,
+        };
+        executor.with_vm_version(vm_version)
+    }
+}
+
+/// CLI for the comparative VM runner benchmark.
+#[derive(Debug, Parser)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    /// Postgres URL of the database to replay batches from.
+    #[arg(long = "database-url")]
+    database_url: SensitiveUrl,
+    /// Maximum number of Postgres connections.
+    #[arg(long = "pool-size", default_value = "10")]
+    pool_size: u32,
+    /// L2 chain ID of the replayed chain.
+    #[arg(long = "chain-id", default_value = "270")]
+    chain_id: L2ChainId,
+    /// Hash of the EVM emulator bytecode, if the EVM emulator is enabled on the replayed chain.
+    #[arg(long = "evm-emulator-hash")]
+    evm_emulator_hash: Option<H256>,
+    /// First L1 batch to replay.
+    #[arg(long = "first-batch")]
+    first_batch: u32,
+    /// Last L1 batch to replay (inclusive).
+    #[arg(long = "last-batch")]
+    last_batch: u32,
+    /// VMs to benchmark. Can be specified multiple times. VMs other than `protocol` must be compatible
+    /// with the base system contracts of the replayed batches.
+    #[arg(long = "vm", value_enum, default_value = "protocol")]
+    vms: Vec<VmKind>,
+    /// Collect call traces for all executed transactions.
+    #[arg(long = "call-traces")]
+    call_traces: bool,
+    /// VM runner window sizes to benchmark. Can be specified multiple times.
+    #[arg(long = "window-size", default_value = "1")]
+    window_sizes: Vec<u32>,
+    /// Directory to create RocksDB caches in. Each run uses a fresh cache, which catches up
+    /// with Postgres while batches are being executed. By default, caches are created in a temporary directory.
+    #[arg(long = "rocksdb-dir", conflicts_with = "postgres_only")]
+    rocksdb_dir: Option<PathBuf>,
+    /// Read storage directly from Postgres instead of using RocksDB caches.
+    #[arg(long = "postgres-only")]
+    postgres_only: bool,
+}
+
+impl Cli {
+    fn init_logging() {
+        tracing_subscriber::fmt()
+            .pretty()
+            .with_env_filter(EnvFilter::from_default_env())
+            .init();
+    }
+
+    async fn run(self) -> anyhow::Result<()> {
+        Self::init_logging();
+        anyhow::ensure!(
+            self.first_batch > 0 && self.first_batch <= self.last_batch,
+            "Invalid L1 batch range: #{}..=#{}",
+            self.first_batch,
+            self.last_batch
+        );
+        let pool = ConnectionPool::<Core>::builder(self.database_url.clone(), self.pool_size)
+            .build()
+            .await
+            .context("failed building connection pool")?;
+
+        let mut reports = vec![];
+        for &vm in &self.vms {
+            for &window_size in &self.window_sizes {
+                tracing::info!(
+                    ?vm,
+                    window_size,
+                    "Replaying L1 batches #{}..=#{}",
+                    self.first_batch,
+                    self.last_batch
+                );
+                let report = self.run_once(&pool, vm, window_size).await?;
+                tracing::info!(?report, "Finished run");
+                reports.push(report);
+            }
+        }
+
+        println!(
+            "{:<24} {:>6} {:>12} {:>12} {:>16} {:>10}",
+            "vm", "window", "elapsed, s", "txs/s", "instructions/s", "rocksdb, %"
+        );
+        for report in &reports {
+            report.print();
+        }
+        Ok(())
+    }
+
+    async fn run_once(
+        &self,
+        pool: &ConnectionPool<Core>,
+        vm: VmKind,
+        window_size: u32,
+    ) -> anyhow::Result<RunReport> {
+        anyhow::ensure!(window_size > 0, "window size must be positive");
+        let rocksdb_dir = if self.postgres_only {
+            None
+        } else {
+            let dir = match &self.rocksdb_dir {
+                Some(dir) => TempDir::new_in(dir),
+                None => TempDir::new(),
+            };
+            Some(dir.context("failed creating RocksDB directory")?)
+        };
+        let storage_backend = match &rocksdb_dir {
+            Some(dir) => {
+                let rocksdb_path = dir.path().to_str().context("RocksDB path is not UTF-8")?;
+                VmRunnerStorageBackend::Rocksdb(rocksdb_path.to_owned())
+            }
+            None => VmRunnerStorageBackend::PostgresOnly,
+        };
+
+        let io = BenchmarkIo {
+            latest_processed_batch: Arc::new(AtomicU32::new(self.first_batch - 1)),
+            last_batch: L1BatchNumber(self.last_batch),
+            window_size,
+        };
+        let stats = Arc::<ExecutionStats>::default();
+        let accesses = Arc::<StorageAccesses>::default();
+        let (stop_sender, stop_receiver) = watch::channel(false);
+
+        let (storage, storage_task) = VmRunnerStorage::with_backend(
+            pool.clone(),
+            storage_backend,
+            io.clone(),
+            self.chain_id,
+            self.evm_emulator_hash,
+        )
+        .await?;
+        let storage = CountingStorage {
+            inner: Arc::new(storage),
+            accesses: accesses.clone(),
+        };
+        let (output_factory, output_task) = ConcurrentOutputHandlerFactory::new(
+            pool.clone(),
+            io.clone(),
+            BenchmarkOutputHandlerFactory(stats.clone()),
+            ConcurrentOutputHandlerOptions::default(),
+        );
+        let vm_runner = VmRunner::new(
+            pool.clone(),
+            Box::new(io.clone()),
+            Arc::new(storage),
+            Box::new(output_factory),
+            Box::new(vm.create_executor(self.call_traces)),
+            window_size,
+        );
+
+        let started_at = Instant::now();
+        let tasks = [
+            tokio::spawn(storage_task.run(stop_receiver.clone())),
+            tokio::spawn(output_task.run(stop_receiver.clone())),
+            tokio::spawn(async move { vm_runner.run(&stop_receiver).await }),
+        ];
+        while io.latest_processed_batch.load(Ordering::SeqCst) < self.last_batch {
+            if tasks.iter().any(JoinHandle::is_finished) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let elapsed = started_at.elapsed();
+
+        stop_sender.send_replace(true);
+        for task in tasks {
+            task.await.context("VM runner task panicked")??;
+        }
+        let latest_processed_batch = io.latest_processed_batch.load(Ordering::SeqCst);
+        anyhow::ensure!(
+            latest_processed_batch >= self.last_batch,
+            "VM runner has terminated prematurely after L1 batch #{latest_processed_batch}"
+        );
+
+        Ok(RunReport {
+            vm,
+            window_size,
+            elapsed,
+            tx_count: stats.tx_count.load(Ordering::Relaxed),
+            instruction_count: stats.instruction_count.load(Ordering::Relaxed),
+            rocksdb_accesses: accesses.rocksdb.load(Ordering::Relaxed),
+            postgres_accesses: accesses.postgres.load(Ordering::Relaxed),
+        })
+    }
+}
+
+/// Results of a single benchmark run.
+#[derive(Debug)]
+struct RunReport {
+    vm: VmKind,
+    window_size: u32,
+    elapsed: Duration,
+    tx_count: u64,
+    instruction_count: u64,
+    rocksdb_accesses: u64,
+    postgres_accesses: u64,
+}
+
+impl RunReport {
+    fn rocksdb_share(&self) -> f64 {
+        let total = self.rocksdb_accesses + self.postgres_accesses;
+        if total == 0 {
+            0.0
+        } else {
+            self.rocksdb_accesses as f64 / total as f64
+        }
+    }
+
+    fn print(&self) {
+        let elapsed = self.elapsed.as_secs_f64();
+        let vm = self.vm.to_possible_value().map_or_else(
+            || format!("{:?}", self.vm),
+            |value| value.get_name().to_owned(),
+        );
+        println!(
+            "{vm:<24} {:>6} {elapsed:>12.3} {:>12.1} {:>16.0} {:>10.1}",
+            self.window_size,
+            self.tx_count as f64 / elapsed,
+            self.instruction_count as f64 / elapsed,
+            self.rocksdb_share() * 100.0
+        );
+    }
+}
+
+/// Number of storage accesses (one per executed batch) by the backend serving them.
+#[derive(Debug, Default)]
+struct StorageAccesses {
+    rocksdb: AtomicU64,
+    postgres: AtomicU64,
+}
+
+/// Storage loader recording [`StorageAccesses`].
+#[derive(Debug)]
+struct CountingStorage {
+    inner: Arc<dyn StorageLoader>,
+    accesses: Arc<StorageAccesses>,
+}
+
+#[async_trait]
+impl ReadStorageFactory for CountingStorage {
+    async fn access_storage(
+        &self,
+        stop_receiver: &watch::Receiver<bool>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<PgOrRocksdbStorage<'_>>> {
+        let storage = self
+            .inner
+            .access_storage(stop_receiver, l1_batch_number)
+            .await?;
+        let counter = match &storage {
+            Some(PgOrRocksdbStorage::Postgres(_)) => Some(&self.accesses.postgres),
+            Some(PgOrRocksdbStorage::Rocksdb(_) | PgOrRocksdbStorage::RocksdbWithMemory(_)) => {
+                Some(&self.accesses.rocksdb)
+            }
+            Some(PgOrRocksdbStorage::InMemory(_)) | None => None,
+        };
+        if let Some(counter) = counter {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(storage)
+    }
+}
+
+#[async_trait]
+impl StorageLoader for CountingStorage {
+    async fn load_batch(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<BatchExecuteData>> {
+        self.inner.load_batch(l1_batch_number).await
+    }
+
+    fn upcast(self: Arc<Self>) -> Arc<dyn ReadStorageFactory> {
+        self
+    }
+}
+
+/// IO replaying a fixed range of L1 batches. Progress is kept in memory.
+#[derive(Debug, Clone)]
+struct BenchmarkIo {
+    latest_processed_batch: Arc<AtomicU32>,
+    last_batch: L1BatchNumber,
+    window_size: u32,
+}
+
+#[async_trait]
+impl VmRunnerIo for BenchmarkIo {
+    fn name(&self) -> &'static str {
+        BENCHMARK_NAME
+    }
+
+    async fn latest_processed_batch(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        Ok(L1BatchNumber(
+            self.latest_processed_batch.load(Ordering::SeqCst),
+        ))
+    }
+
+    async fn last_ready_to_be_loaded_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        let latest_processed_batch = self.latest_processed_batch(conn).await?;
+        let sealed_batch = conn
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .unwrap_or_default();
+        let last_ready_batch = sealed_batch
+            .min(latest_processed_batch + self.window_size)
+            .min(self.last_batch);
+        Ok(last_ready_batch.max(latest_processed_batch))
+    }
+
+    async fn mark_l1_batch_as_completed(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        self.latest_processed_batch
+            .store(l1_batch_number.0, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn load_l2_block_checkpoint(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<L2BlockCheckpoint>> {
+        Ok(None)
+    }
+
+    async fn save_l2_block_checkpoint(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+        _l2_block_number: L2BlockNumber,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn mark_l1_batch_as_handled(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+        _last_l2_block_number: L2BlockNumber,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn remove_l2_block_checkpoints(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct ExecutionStats {
+    tx_count: AtomicU64,
+    instruction_count: AtomicU64,
+}
+
+/// Output handler recording execution statistics of replayed batches.
+#[derive(Debug)]
+struct BenchmarkOutputHandler(Arc<ExecutionStats>);
+
+#[async_trait]
+impl StateKeeperOutputHandler for BenchmarkOutputHandler {
+    async fn handle_l2_block(&mut self, _updates_manager: &UpdatesManager) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_l1_batch(
+        &mut self,
+        updates_manager: Arc<UpdatesManager>,
+    ) -> anyhow::Result<()> {
+        let transactions = &updates_manager.l1_batch.executed_transactions;
+        let instruction_count: u64 = transactions
+            .iter()
+            .map(|tx| u64::from(tx.execution_info.cycles_used))
+            .sum();
+        self.0
+            .tx_count
+            .fetch_add(transactions.len() as u64, Ordering::Relaxed);
+        self.0
+            .instruction_count
+            .fetch_add(instruction_count, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct BenchmarkOutputHandlerFactory(Arc<ExecutionStats>);
+
+#[async_trait]
+impl OutputHandlerFactory for BenchmarkOutputHandlerFactory {
+    async fn create_handler(
+        &mut self,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Box<dyn StateKeeperOutputHandler>> {
+        Ok(Box::new(BenchmarkOutputHandler(self.0.clone())))
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    Cli::parse().run().await
+}
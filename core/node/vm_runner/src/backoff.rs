@@ -0,0 +1,71 @@
+//! Backoff policies used when polling for VM runner progress.
+
+use std::{fmt::Debug, time::Duration};
+
+use rand::Rng;
+
+/// Policy determining the delay between consecutive unsuccessful polls, e.g. when
+/// [`ConcurrentOutputHandlerFactoryTask`](crate::ConcurrentOutputHandlerFactoryTask) waits for an output handler
+/// for the next batch to be created.
+pub trait BackoffPolicy: Debug + Send + Sync + 'static {
+    /// Returns the delay before the next poll. `attempt` is the number of consecutive unsuccessful polls so far
+    /// (i.e., it's 0 for the first poll); it's reset once a poll succeeds.
+    fn delay(&self, attempt: u32) -> Duration;
+}
+
+/// Backoff policy with a constant delay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstantBackoff(pub Duration);
+
+impl BackoffPolicy for ConstantBackoff {
+    fn delay(&self, _attempt: u32) -> Duration {
+        self.0
+    }
+}
+
+/// Backoff policy with the delay doubling after each unsuccessful poll, up to the specified maximum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExponentialBackoff {
+    /// Delay after the first unsuccessful poll.
+    pub initial: Duration,
+    /// Maximum delay.
+    pub max: Duration,
+}
+
+impl BackoffPolicy for ExponentialBackoff {
+    fn delay(&self, attempt: u32) -> Duration {
+        let multiplier = 1_u32.checked_shl(attempt).unwrap_or(u32::MAX);
+        self.initial
+            .checked_mul(multiplier)
+            .map_or(self.max, |delay| delay.min(self.max))
+    }
+}
+
+/// Backoff policy randomly reducing delays of the wrapped policy, so that multiple pollers don't poll in lockstep.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JitteredBackoff<P> {
+    inner: P,
+    jitter: f64,
+}
+
+impl<P: BackoffPolicy> JitteredBackoff<P> {
+    /// Wraps the provided policy. `jitter` is the maximum fraction of a delay that can be subtracted from it;
+    /// it is clamped to the `[0, 1]` range.
+    pub fn new(inner: P, jitter: f64) -> Self {
+        Self {
+            inner,
+            jitter: jitter.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl<P: BackoffPolicy> BackoffPolicy for JitteredBackoff<P> {
+    fn delay(&self, attempt: u32) -> Duration {
+        let delay = self.inner.delay(attempt);
+        if self.jitter == 0.0 {
+            return delay;
+        }
+        let factor = 1.0 - rand::thread_rng().gen_range(0.0..=self.jitter);
+        delay.mul_f64(factor)
+    }
+}
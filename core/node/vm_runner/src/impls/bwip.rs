@@ -0,0 +1,476 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use tokio::sync::watch;
+use zksync_dal::{watermarks_dal::WatermarkComponent, Connection, ConnectionPool, Core, CoreDal};
+use zksync_object_store::ObjectStore;
+use zksync_prover_interface::inputs::{StreamedVmRunWitnessInputData, VmRunWitnessInputChunk};
+use zksync_state_keeper::{MainBatchExecutor, StateKeeperOutputHandler, UpdatesManager};
+use zksync_types::{L1BatchNumber, L2BlockNumber, L2ChainId, H256, U256};
+use zksync_utils::{bytes_to_chunks, h256_to_u256, u256_to_h256};
+
+use crate::{
+    storage::StorageSyncTask, ConcurrentOutputHandlerFactory, ConcurrentOutputHandlerFactoryTask,
+    ConcurrentOutputHandlerOptions, NotifiedIo, OutputHandlerFactory, SharedVmRunnerStorage,
+    VmRunner, VmRunnerIo, VmRunnerStorage,
+};
+
+/// A standalone component that produces basic witness inputs for L1 batches asynchronously to state keeper
+/// and saves them to the object store.
+///
+/// Witness inputs are streamed to the object store as the batch is executed, as a sequence of
+/// [`VmRunWitnessInputChunk`]s followed by a [`StreamedVmRunWitnessInputData`] header, so that the producer
+/// doesn't need to hold bytecodes for the entire batch in memory.
+#[derive(Debug)]
+pub struct BasicWitnessInputProducer {
+    vm_runner: VmRunner,
+}
+
+impl BasicWitnessInputProducer {
+    /// Create a new basic witness input producer from the provided DB parameters, object store and window size
+    /// which regulates how many batches this component can handle at the same time. If `sealed_batches`
+    /// (obtained from [`SealedBatchesListener::subscribe()`](crate::SealedBatchesListener::subscribe())) are provided,
+    /// the producer reacts to sealed batches instead of polling Postgres.
+    pub async fn new(
+        pool: ConnectionPool<Core>,
+        object_store: Arc<dyn ObjectStore>,
+        rocksdb_path: String,
+        chain_id: L2ChainId,
+        evm_emulator_hash: Option<H256>,
+        first_processed_batch: L1BatchNumber,
+        window_size: u32,
+        sealed_batches: Option<watch::Receiver<L1BatchNumber>>,
+    ) -> anyhow::Result<(Self, BasicWitnessInputProducerTasks)> {
+        let io = NotifiedIo::with_sealed_batches(
+            BasicWitnessInputProducerIo {
+                first_processed_batch,
+                window_size,
+                processing_times: Arc::default(),
+            },
+            sealed_batches,
+        );
+        let (loader, loader_task) = VmRunnerStorage::new(
+            pool.clone(),
+            rocksdb_path,
+            io.clone(),
+            chain_id,
+            evm_emulator_hash,
+        )
+        .await?;
+        let (this, output_handler_factory_task) = Self::with_loader(pool, object_store, io, loader);
+        Ok((
+            this,
+            BasicWitnessInputProducerTasks {
+                loader_task,
+                output_handler_factory_task,
+            },
+        ))
+    }
+
+    /// Creates a new basic witness input producer using storage shared with other VM runners. Unlike with
+    /// [`Self::new()`], storage is synchronized by the task created by [`SharedVmRunnerStorage::into_sync_task()`],
+    /// so only the returned output handler task needs to be run.
+    pub fn with_shared_storage(
+        pool: ConnectionPool<Core>,
+        object_store: Arc<dyn ObjectStore>,
+        shared_storage: &mut SharedVmRunnerStorage,
+        first_processed_batch: L1BatchNumber,
+        window_size: u32,
+        sealed_batches: Option<watch::Receiver<L1BatchNumber>>,
+    ) -> (
+        Self,
+        ConcurrentOutputHandlerFactoryTask<NotifiedBasicWitnessInputProducerIo>,
+    ) {
+        let io = NotifiedIo::with_sealed_batches(
+            BasicWitnessInputProducerIo {
+                first_processed_batch,
+                window_size,
+                processing_times: Arc::default(),
+            },
+            sealed_batches,
+        );
+        let loader = shared_storage.add_io(io.clone());
+        Self::with_loader(pool, object_store, io, loader)
+    }
+
+    fn with_loader(
+        pool: ConnectionPool<Core>,
+        object_store: Arc<dyn ObjectStore>,
+        io: NotifiedBasicWitnessInputProducerIo,
+        loader: VmRunnerStorage<NotifiedBasicWitnessInputProducerIo>,
+    ) -> (
+        Self,
+        ConcurrentOutputHandlerFactoryTask<NotifiedBasicWitnessInputProducerIo>,
+    ) {
+        let window_size = io.inner().window_size;
+        let output_handler_factory = BasicWitnessInputProducerOutputHandlerFactory::new(
+            pool.clone(),
+            object_store,
+            io.inner().processing_times.clone(),
+        );
+        let (output_handler_factory, output_handler_factory_task) =
+            ConcurrentOutputHandlerFactory::new(
+                pool.clone(),
+                io.clone(),
+                output_handler_factory,
+                ConcurrentOutputHandlerOptions::default(),
+            );
+        let batch_processor = MainBatchExecutor::new(false, false);
+        // Bytecodes are streamed to the object store after each L2 block, so there's no need to keep them
+        // until the batch is finished.
+        let vm_runner = VmRunner::new(
+            pool,
+            Box::new(io),
+            Arc::new(loader),
+            Box::new(output_handler_factory),
+            Box::new(batch_processor),
+            window_size,
+        )
+        .without_sealed_factory_deps();
+        (Self { vm_runner }, output_handler_factory_task)
+    }
+
+    /// Continuously loads new available batches and saves the corresponding witness inputs
+    /// to the object store.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB, Postgres and object store errors.
+    pub async fn run(self, stop_receiver: &watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.vm_runner.run(stop_receiver).await
+    }
+}
+
+/// A collections of tasks that need to be run in order for basic witness input producer to work as
+/// intended.
+#[derive(Debug)]
+pub struct BasicWitnessInputProducerTasks {
+    /// Task that synchronizes storage with new available batches.
+    pub loader_task: StorageSyncTask<NotifiedBasicWitnessInputProducerIo>,
+    /// Task that handles output from processed batches.
+    pub output_handler_factory_task:
+        ConcurrentOutputHandlerFactoryTask<NotifiedBasicWitnessInputProducerIo>,
+}
+
+/// IO used by [`BasicWitnessInputProducer`].
+pub type NotifiedBasicWitnessInputProducerIo = NotifiedIo<BasicWitnessInputProducerIo>;
+
+/// Times taken to produce witness inputs for L1 batches, keyed by the batch number. Entries are added
+/// by output handlers once witness inputs are saved, and are removed when batches are marked as completed.
+type ProcessingTimes = Arc<Mutex<HashMap<L1BatchNumber, Duration>>>;
+
+#[derive(Debug, Clone)]
+pub struct BasicWitnessInputProducerIo {
+    first_processed_batch: L1BatchNumber,
+    window_size: u32,
+    processing_times: ProcessingTimes,
+}
+
+#[async_trait]
+impl VmRunnerIo for BasicWitnessInputProducerIo {
+    fn name(&self) -> &'static str {
+        "basic_witness_input_producer"
+    }
+
+    async fn latest_processed_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        Ok(conn
+            .vm_runner_dal()
+            .get_bwip_latest_processed_batch(self.first_processed_batch)
+            .await?)
+    }
+
+    async fn last_ready_to_be_loaded_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        Ok(conn
+            .vm_runner_dal()
+            .get_bwip_last_ready_batch(self.window_size)
+            .await?)
+    }
+
+    async fn mark_l1_batch_as_completed(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        let time_taken = self
+            .processing_times
+            .lock()
+            .expect("processing times are poisoned")
+            .remove(&l1_batch_number);
+        let mut transaction = conn.start_transaction().await?;
+        transaction
+            .vm_runner_dal()
+            .mark_bwip_batch_as_completed(l1_batch_number, time_taken)
+            .await?;
+        transaction
+            .watermarks_dal()
+            .set_watermark(
+                WatermarkComponent::BasicWitnessInputProducer,
+                l1_batch_number,
+            )
+            .await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+}
+
+/// Maximum number of bytecodes loaded from Postgres and persisted in a single witness input chunk
+/// when finalizing an L1 batch.
+const MAX_BYTECODES_PER_CHUNK: usize = 64;
+
+/// Output handler streaming witness inputs to the object store. Bytecodes published in each L2 block
+/// are persisted in a separate chunk once the block is handled; the remaining used bytecodes are loaded
+/// from Postgres in bounded chunks after the batch is finished, followed by the header that references all chunks.
+#[derive(Debug)]
+struct BasicWitnessInputProducerOutputHandler {
+    pool: ConnectionPool<Core>,
+    object_store: Arc<dyn ObjectStore>,
+    l1_batch_number: L1BatchNumber,
+    l2_block_range: (L2BlockNumber, L2BlockNumber),
+    started_at: Instant,
+    processing_times: ProcessingTimes,
+    /// Hashes of bytecodes already persisted in chunks. Isn't restored after a restart, in which case
+    /// some bytecodes may be persisted in multiple chunks.
+    streamed_bytecode_hashes: HashSet<U256>,
+}
+
+impl BasicWitnessInputProducerOutputHandler {
+    fn chunk_index(&self, l2_block_number: L2BlockNumber) -> anyhow::Result<u32> {
+        let (first_l2_block, last_l2_block) = self.l2_block_range;
+        anyhow::ensure!(
+            (first_l2_block..=last_l2_block).contains(&l2_block_number),
+            "L2 block #{l2_block_number} doesn't belong to L1 batch #{} with L2 blocks {first_l2_block}..={last_l2_block}",
+            self.l1_batch_number
+        );
+        Ok(l2_block_number.0 - first_l2_block.0)
+    }
+
+    async fn save_chunk(
+        &mut self,
+        chunk_index: u32,
+        used_bytecodes: HashMap<U256, Vec<[u8; 32]>>,
+    ) -> anyhow::Result<()> {
+        self.streamed_bytecode_hashes
+            .extend(used_bytecodes.keys().copied());
+        let chunk = VmRunWitnessInputChunk {
+            l1_batch_number: self.l1_batch_number,
+            chunk_index,
+            used_bytecodes,
+        };
+        let blob_key = self
+            .object_store
+            .put((self.l1_batch_number, chunk_index), &chunk)
+            .await
+            .context("cannot save witness input chunk to object store")?;
+        tracing::debug!(
+            "Saved witness input chunk #{chunk_index} for L1 batch #{} to object store at `{blob_key}`",
+            self.l1_batch_number
+        );
+        Ok(())
+    }
+
+    /// Persists chunks with used bytecodes not streamed during L2 block handling. Returns the total number of chunks.
+    async fn save_remaining_bytecodes(
+        &mut self,
+        updates_manager: &UpdatesManager,
+    ) -> anyhow::Result<u32> {
+        let l1_batch_number = self.l1_batch_number;
+        let finished_batch = updates_manager
+            .l1_batch
+            .finished
+            .as_ref()
+            .context("L1 batch is not actually finished")?;
+        let execution_state = &finished_batch.final_execution_state;
+        let base_system_contracts = updates_manager.base_system_contract_hashes();
+        let bootloader_code_hash = h256_to_u256(base_system_contracts.bootloader);
+        let default_account_code_hash = h256_to_u256(base_system_contracts.default_aa);
+
+        let mut remaining_hashes: Vec<_> = execution_state
+            .used_contract_hashes
+            .iter()
+            .filter(|&&hash| {
+                hash != bootloader_code_hash && !self.streamed_bytecode_hashes.contains(&hash)
+            })
+            .copied()
+            .collect();
+        remaining_hashes.sort_unstable();
+        remaining_hashes.dedup();
+
+        let mut chunk_index = self.chunk_index(self.l2_block_range.1)? + 1;
+        for hashes in remaining_hashes.chunks(MAX_BYTECODES_PER_CHUNK) {
+            let mut connection = self
+                .pool
+                .connection_tagged("basic_witness_input_producer")
+                .await?;
+            let (default_account_hash, hashes): (HashSet<_>, HashSet<_>) = hashes
+                .iter()
+                .map(|&hash| u256_to_h256(hash))
+                .partition(|&hash| hash == base_system_contracts.default_aa);
+            let mut used_bytecodes = connection
+                .factory_deps_dal()
+                .get_factory_deps(&hashes)
+                .await;
+            anyhow::ensure!(
+                hashes.len() == used_bytecodes.len(),
+                "{} factory deps used in L1 batch #{l1_batch_number} are not found in Postgres",
+                hashes.len().saturating_sub(used_bytecodes.len())
+            );
+            if !default_account_hash.is_empty() {
+                let default_account_code = connection
+                    .factory_deps_dal()
+                    .get_sealed_factory_dep(base_system_contracts.default_aa)
+                    .await?
+                    .context("default account bytecode is missing in Postgres")?;
+                used_bytecodes.insert(
+                    default_account_code_hash,
+                    bytes_to_chunks(&default_account_code),
+                );
+            }
+            drop(connection);
+
+            self.save_chunk(chunk_index, used_bytecodes).await?;
+            chunk_index += 1;
+        }
+        Ok(chunk_index)
+    }
+
+    async fn get_witness_input_header(
+        &self,
+        updates_manager: &UpdatesManager,
+        chunk_count: u32,
+    ) -> anyhow::Result<StreamedVmRunWitnessInputData> {
+        let finished_batch = updates_manager
+            .l1_batch
+            .finished
+            .as_ref()
+            .context("L1 batch is not actually finished")?;
+        let execution_state = &finished_batch.final_execution_state;
+        let initial_heap_content = finished_batch
+            .final_bootloader_memory
+            .clone()
+            .context("bootloader memory is not produced by the VM")?;
+        let base_system_contracts = updates_manager.base_system_contract_hashes();
+
+        let mut connection = self
+            .pool
+            .connection_tagged("basic_witness_input_producer")
+            .await?;
+        let bootloader_code = connection
+            .factory_deps_dal()
+            .get_sealed_factory_dep(base_system_contracts.bootloader)
+            .await?
+            .context("bootloader bytecode is missing in Postgres")?;
+
+        Ok(StreamedVmRunWitnessInputData {
+            l1_batch_number: self.l1_batch_number,
+            protocol_version: updates_manager.protocol_version(),
+            chunk_count,
+            initial_heap_content,
+            bootloader_code: bytes_to_chunks(&bootloader_code),
+            default_account_code_hash: h256_to_u256(base_system_contracts.default_aa),
+            storage_refunds: execution_state.storage_refunds.clone(),
+            pubdata_costs: execution_state.pubdata_costs.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl StateKeeperOutputHandler for BasicWitnessInputProducerOutputHandler {
+    async fn handle_l2_block(&mut self, updates_manager: &UpdatesManager) -> anyhow::Result<()> {
+        let l2_block = &updates_manager.l2_block;
+        let chunk_index = self.chunk_index(l2_block.number)?;
+        // Chunks are persisted for all L2 blocks (including ones without new bytecodes), so that chunk indices
+        // do not depend on whether the VM runner was restarted in the middle of the batch.
+        let used_bytecodes = l2_block
+            .new_factory_deps
+            .iter()
+            .map(|(hash, bytecode)| (h256_to_u256(*hash), bytecode))
+            .filter(|(hash, _)| !self.streamed_bytecode_hashes.contains(hash))
+            .map(|(hash, bytecode)| (hash, bytes_to_chunks(bytecode)))
+            .collect();
+        self.save_chunk(chunk_index, used_bytecodes).await
+    }
+
+    async fn handle_l1_batch(
+        &mut self,
+        updates_manager: Arc<UpdatesManager>,
+    ) -> anyhow::Result<()> {
+        let l1_batch_number = self.l1_batch_number;
+        let chunk_count = self.save_remaining_bytecodes(&updates_manager).await?;
+        let header = self
+            .get_witness_input_header(&updates_manager, chunk_count)
+            .await?;
+        let blob_key = self
+            .object_store
+            .put(l1_batch_number, &header)
+            .await
+            .context("cannot save witness input to object store")?;
+        let time_taken = self.started_at.elapsed();
+        tracing::info!(
+            "Saved witness input for L1 batch #{l1_batch_number} ({chunk_count} chunks) to object store at `{blob_key}`; \
+             took {time_taken:?}"
+        );
+        self.processing_times
+            .lock()
+            .expect("processing times are poisoned")
+            .insert(l1_batch_number, time_taken);
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct BasicWitnessInputProducerOutputHandlerFactory {
+    pool: ConnectionPool<Core>,
+    object_store: Arc<dyn ObjectStore>,
+    processing_times: ProcessingTimes,
+}
+
+impl BasicWitnessInputProducerOutputHandlerFactory {
+    pub(crate) fn new(
+        pool: ConnectionPool<Core>,
+        object_store: Arc<dyn ObjectStore>,
+        processing_times: ProcessingTimes,
+    ) -> Self {
+        Self {
+            pool,
+            object_store,
+            processing_times,
+        }
+    }
+}
+
+#[async_trait]
+impl OutputHandlerFactory for BasicWitnessInputProducerOutputHandlerFactory {
+    async fn create_handler(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Box<dyn StateKeeperOutputHandler>> {
+        let l2_block_range = self
+            .pool
+            .connection_tagged("basic_witness_input_producer")
+            .await?
+            .blocks_dal()
+            .get_l2_block_range_of_l1_batch(l1_batch_number)
+            .await?
+            .with_context(|| format!("L1 batch #{l1_batch_number} doesn't have L2 blocks"))?;
+        Ok(Box::new(BasicWitnessInputProducerOutputHandler {
+            pool: self.pool.clone(),
+            object_store: self.object_store.clone(),
+            l1_batch_number,
+            l2_block_range,
+            started_at: Instant::now(),
+            processing_times: self.processing_times.clone(),
+            streamed_bytecode_hashes: HashSet::new(),
+        }))
+    }
+}
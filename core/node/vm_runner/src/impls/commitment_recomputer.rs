@@ -0,0 +1,502 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use multivm::interface::FinishedL1Batch;
+use tokio::sync::{mpsc, watch};
+use zksync_commitment_generator::{
+    recalculation::{MetadataMismatch, MetadataRecalculator},
+    CommitmentGenerator,
+};
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_state_keeper::{MainBatchExecutor, StateKeeperOutputHandler, UpdatesManager};
+use zksync_types::{
+    block::L1BatchHeader,
+    commitment::{
+        CommitmentCommonInput, CommitmentInput, L1BatchCommitmentArtifacts, L1BatchCommitmentMode,
+        L1BatchMetadata,
+    },
+    event::convert_vm_events_to_log_queries,
+    L1BatchNumber, L2ChainId, ProtocolVersionId, H256,
+};
+
+use crate::{
+    metrics::METRICS, storage::StorageSyncTask, ConcurrentOutputHandlerFactory,
+    ConcurrentOutputHandlerFactoryTask, ConcurrentOutputHandlerOptions, OutputHandlerFactory,
+    VmRunner, VmRunnerIo, VmRunnerStorage, VmRunnerStorageBackend,
+};
+
+/// A standalone component that re-executes a range of historical L1 batches and recomputes their commitments,
+/// bootloader memory and auxiliary outputs (L2-to-L1 logs, used contract hashes and pubdata) from the VM outputs.
+/// Recomputed values are compared to the ones stored in the `l1_batches` table, and a
+/// [`CommitmentRecomputationReport`] is emitted for each batch. Only the progress of the component is written
+/// to Postgres.
+///
+/// Unlike [`MetadataRecalculator`], which recalculates commitments from the data stored in Postgres, this component
+/// doesn't trust the stored batch outputs, so it's useful for audits before protocol upgrades. Tree data
+/// (the root hash and the last leaf index) is still taken from Postgres.
+///
+/// Progress of the component is persisted in Postgres, so after a restart, processing resumes from the first
+/// batch in the range that wasn't processed yet. Use [`Self::reset_progress()`] to process the range from scratch.
+#[derive(Debug)]
+pub struct CommitmentRecomputer {
+    vm_runner: VmRunner,
+    io: CommitmentRecomputerIo,
+}
+
+impl CommitmentRecomputer {
+    /// Creates a new component processing batches after `first_processed_batch` up to and including `last_batch`.
+    /// Reports are logged and, if `report_sender` is provided, sent through it.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn new(
+        pool: ConnectionPool<Core>,
+        storage_backend: VmRunnerStorageBackend,
+        chain_id: L2ChainId,
+        evm_emulator_hash: Option<H256>,
+        commitment_mode: L1BatchCommitmentMode,
+        first_processed_batch: L1BatchNumber,
+        last_batch: L1BatchNumber,
+        window_size: u32,
+        report_sender: Option<mpsc::UnboundedSender<CommitmentRecomputationReport>>,
+    ) -> anyhow::Result<(Self, CommitmentRecomputerTasks)> {
+        anyhow::ensure!(
+            first_processed_batch < last_batch,
+            "invalid L1 batch range for commitment recomputation: #{}..=#{last_batch}",
+            first_processed_batch + 1
+        );
+        let mut conn = pool.connection_tagged("commitment_recomputer").await?;
+        let latest_processed_batch = conn
+            .vm_runner_dal()
+            .get_commitment_recomputer_latest_processed_batch(first_processed_batch, last_batch)
+            .await?;
+        drop(conn);
+        if latest_processed_batch > first_processed_batch {
+            tracing::info!(
+                "Resuming commitment recomputation after L1 batch #{latest_processed_batch}"
+            );
+        }
+
+        let io = CommitmentRecomputerIo {
+            latest_processed_batch: Arc::new(AtomicU32::new(latest_processed_batch.0)),
+            last_batch,
+            window_size,
+        };
+        let (loader, loader_task) = VmRunnerStorage::with_backend(
+            pool.clone(),
+            storage_backend,
+            io.clone(),
+            chain_id,
+            evm_emulator_hash,
+        )
+        .await?;
+        let output_handler_factory = CommitmentRecomputerOutputHandlerFactory {
+            pool: pool.clone(),
+            generator: Arc::new(CommitmentGenerator::new(pool.clone(), commitment_mode)),
+            report_sender,
+        };
+        let (output_handler_factory, output_handler_factory_task) =
+            ConcurrentOutputHandlerFactory::new(
+                pool.clone(),
+                io.clone(),
+                output_handler_factory,
+                ConcurrentOutputHandlerOptions::default(),
+            );
+        let batch_processor = MainBatchExecutor::new(false, false);
+        let vm_runner = VmRunner::new(
+            pool,
+            Box::new(io.clone()),
+            Arc::new(loader),
+            Box::new(output_handler_factory),
+            Box::new(batch_processor),
+            window_size,
+        );
+        Ok((
+            Self { vm_runner, io },
+            CommitmentRecomputerTasks {
+                loader_task,
+                output_handler_factory_task,
+            },
+        ))
+    }
+
+    /// Removes persisted progress of the component. Should be called before [`Self::new()`].
+    ///
+    /// # Errors
+    ///
+    /// Propagates Postgres errors.
+    pub async fn reset_progress(pool: &ConnectionPool<Core>) -> anyhow::Result<()> {
+        let mut conn = pool.connection_tagged("commitment_recomputer").await?;
+        conn.vm_runner_dal()
+            .reset_commitment_recomputer_progress()
+            .await?;
+        Ok(())
+    }
+
+    /// Loads batches in the configured range and compares recomputed data with the stored one.
+    /// Returns once the last batch in the range is processed, or when a stop signal is received.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB and Postgres errors.
+    pub async fn run(self, stop_receiver: &watch::Receiver<bool>) -> anyhow::Result<()> {
+        const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+        let mut stop_receiver = stop_receiver.clone();
+        let io = self.io;
+        let vm_runner_stop_receiver = stop_receiver.clone();
+        let vm_runner = self.vm_runner.run(&vm_runner_stop_receiver);
+        tokio::pin!(vm_runner);
+        loop {
+            let latest_processed_batch = io.latest_processed_batch.load(Ordering::SeqCst);
+            if latest_processed_batch >= io.last_batch.0 {
+                tracing::info!(
+                    "Finished commitment recomputation for L1 batches up to and including #{}",
+                    io.last_batch
+                );
+                return Ok(());
+            }
+
+            tokio::select! {
+                res = &mut vm_runner => return res,
+                _ = stop_receiver.changed() => {
+                    tracing::info!(
+                        "Stop signal received, commitment recomputer is shutting down; \
+                         processed L1 batches up to and including #{latest_processed_batch}"
+                    );
+                    return Ok(());
+                }
+                () = tokio::time::sleep(POLL_INTERVAL) => { /* continue polling */ }
+            }
+        }
+    }
+}
+
+/// A collections of tasks that need to be run in order for [`CommitmentRecomputer`] to work as intended.
+#[derive(Debug)]
+pub struct CommitmentRecomputerTasks {
+    /// Task that synchronizes storage with new available batches.
+    pub loader_task: StorageSyncTask<CommitmentRecomputerIo>,
+    /// Task that handles output from processed batches.
+    pub output_handler_factory_task: ConcurrentOutputHandlerFactoryTask<CommitmentRecomputerIo>,
+}
+
+/// IO for [`CommitmentRecomputer`]. Persists progress to Postgres.
+#[derive(Debug, Clone)]
+pub struct CommitmentRecomputerIo {
+    /// Mirrors the progress persisted in Postgres.
+    latest_processed_batch: Arc<AtomicU32>,
+    last_batch: L1BatchNumber,
+    window_size: u32,
+}
+
+#[async_trait]
+impl VmRunnerIo for CommitmentRecomputerIo {
+    fn name(&self) -> &'static str {
+        "commitment_recomputer"
+    }
+
+    async fn latest_processed_batch(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        Ok(L1BatchNumber(
+            self.latest_processed_batch.load(Ordering::SeqCst),
+        ))
+    }
+
+    async fn last_ready_to_be_loaded_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        let latest_processed_batch = self.latest_processed_batch(conn).await?;
+        let sealed_batch = conn
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .unwrap_or_default();
+        let last_ready_batch = sealed_batch
+            .min(latest_processed_batch + self.window_size)
+            .min(self.last_batch);
+        Ok(last_ready_batch.max(latest_processed_batch))
+    }
+
+    async fn mark_l1_batch_as_completed(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        conn.vm_runner_dal()
+            .mark_commitment_recomputer_batch_as_completed(l1_batch_number)
+            .await?;
+        self.latest_processed_batch
+            .store(l1_batch_number.0, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+/// Report on mismatches between the stored and recomputed data for a single L1 batch.
+#[derive(Debug, Clone)]
+pub struct CommitmentRecomputationReport {
+    /// Number of the checked L1 batch.
+    pub l1_batch_number: L1BatchNumber,
+    /// If set, commitments for the batch were not recomputed. Contains the reason (e.g., the batch metadata
+    /// is incomplete).
+    pub skipped_commitment_reason: Option<String>,
+    /// Found mismatches. Empty if the recomputed data matches the stored one.
+    pub mismatches: Vec<MetadataMismatch>,
+}
+
+impl CommitmentRecomputationReport {
+    /// Checks whether the recomputed data matches the stored one.
+    pub fn is_empty(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Compares sequences, returning a mismatch if they differ. Values in the mismatch are summarized
+/// by the sequence length and the first differing entry.
+pub(crate) fn compare_sequences<T: PartialEq + fmt::Debug>(
+    field: &'static str,
+    stored: &[T],
+    recomputed: &[T],
+) -> Option<MetadataMismatch> {
+    if stored == recomputed {
+        return None;
+    }
+
+    let first_diff_idx = stored
+        .iter()
+        .zip(recomputed)
+        .position(|(stored, recomputed)| stored != recomputed)
+        .unwrap_or_else(|| stored.len().min(recomputed.len()));
+    let summarize = |items: &[T]| match items.get(first_diff_idx) {
+        Some(item) => format!("{} entries, #{first_diff_idx}: {item:?}", items.len()),
+        None => format!("{} entries", items.len()),
+    };
+    Some(MetadataMismatch {
+        field,
+        stored: summarize(stored),
+        recalculated: summarize(recomputed),
+    })
+}
+
+#[derive(Debug)]
+struct CommitmentRecomputerOutputHandler {
+    pool: ConnectionPool<Core>,
+    generator: Arc<CommitmentGenerator>,
+    report_sender: Option<mpsc::UnboundedSender<CommitmentRecomputationReport>>,
+}
+
+impl CommitmentRecomputerOutputHandler {
+    async fn recompute_artifacts(
+        &self,
+        header: &L1BatchHeader,
+        metadata: &L1BatchMetadata,
+        finished_batch: &FinishedL1Batch,
+        protocol_version: ProtocolVersionId,
+    ) -> anyhow::Result<L1BatchCommitmentArtifacts> {
+        let execution_state = &finished_batch.final_execution_state;
+        let events_queue = convert_vm_events_to_log_queries(&execution_state.events);
+        let initial_bootloader_contents = finished_batch
+            .final_bootloader_memory
+            .clone()
+            .unwrap_or_default();
+        let aux_commitments = self
+            .generator
+            .calculate_aux_commitments_from(
+                events_queue,
+                initial_bootloader_contents,
+                header.number,
+                protocol_version,
+            )
+            .await?;
+
+        let mut state_diffs = finished_batch
+            .state_diffs
+            .clone()
+            .context("VM didn't output state diffs")?;
+        state_diffs.sort_unstable_by_key(|rec| (rec.address, rec.key));
+        let blob_commitments = CommitmentGenerator::blob_commitments(
+            protocol_version,
+            finished_batch.pubdata_input.as_deref(),
+        )
+        .context("VM didn't output `pubdata_input`")?;
+
+        let common = CommitmentCommonInput {
+            l2_to_l1_logs: execution_state.user_l2_to_l1_logs.clone(),
+            rollup_last_leaf_index: metadata.rollup_last_leaf_index,
+            rollup_root_hash: metadata.root_hash,
+            bootloader_code_hash: header.base_system_contracts_hashes.bootloader,
+            default_aa_code_hash: header.base_system_contracts_hashes.default_aa,
+            protocol_version,
+        };
+        let input = CommitmentInput::PostBoojum {
+            common,
+            system_logs: execution_state.system_logs.clone(),
+            state_diffs,
+            aux_commitments,
+            blob_commitments,
+        };
+        Ok(self.generator.calculate_artifacts(input))
+    }
+
+    async fn check_batch(
+        &self,
+        updates_manager: &UpdatesManager,
+    ) -> anyhow::Result<CommitmentRecomputationReport> {
+        let l1_batch_number = updates_manager.l1_batch.number;
+        let finished_batch = updates_manager
+            .l1_batch
+            .finished
+            .as_ref()
+            .context("L1 batch is not actually finished")?;
+        let execution_state = &finished_batch.final_execution_state;
+
+        let mut conn = self.pool.connection_tagged("commitment_recomputer").await?;
+        let batch = conn
+            .blocks_dal()
+            .get_optional_l1_batch_metadata(l1_batch_number)
+            .await?
+            .with_context(|| format!("L1 batch #{l1_batch_number} is not sealed"))?;
+        let stored_bootloader_heap = conn
+            .blocks_dal()
+            .get_initial_bootloader_heap(l1_batch_number)
+            .await?
+            .with_context(|| {
+                format!("Bootloader initial heap is missing for L1 batch #{l1_batch_number}")
+            })?;
+        drop(conn);
+
+        let header = &batch.header;
+        let recomputed_bootloader_heap = finished_batch
+            .final_bootloader_memory
+            .as_deref()
+            .unwrap_or_default();
+        let mut mismatches: Vec<_> = [
+            compare_sequences(
+                "initial_bootloader_heap",
+                &stored_bootloader_heap,
+                recomputed_bootloader_heap,
+            ),
+            compare_sequences(
+                "system_logs",
+                &header.system_logs,
+                &execution_state.system_logs,
+            ),
+            compare_sequences(
+                "l2_to_l1_logs",
+                &header.l2_to_l1_logs,
+                &execution_state.user_l2_to_l1_logs,
+            ),
+            compare_sequences(
+                "used_contract_hashes",
+                &header.used_contract_hashes,
+                &execution_state.used_contract_hashes,
+            ),
+            MetadataMismatch::compare_bytes(
+                "pubdata_input",
+                header.pubdata_input.as_deref(),
+                finished_batch.pubdata_input.as_deref(),
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        // TODO(PLA-731): ensure that the protocol version is always available.
+        let protocol_version = header
+            .protocol_version
+            .unwrap_or_else(ProtocolVersionId::last_potentially_undefined);
+        let skipped_commitment_reason = match &batch.metadata {
+            Err(err) => Some(err.to_string()),
+            Ok(_) if protocol_version.is_pre_boojum() => {
+                Some("commitments are not recomputed for pre-boojum L1 batches".to_owned())
+            }
+            Ok(metadata) => {
+                let artifacts = self
+                    .recompute_artifacts(header, metadata, finished_batch, protocol_version)
+                    .await
+                    .with_context(|| {
+                        format!("failed recomputing commitments for L1 batch #{l1_batch_number}")
+                    })?;
+                mismatches.extend(MetadataRecalculator::compare_artifacts(
+                    metadata, &artifacts,
+                ));
+                None
+            }
+        };
+
+        Ok(CommitmentRecomputationReport {
+            l1_batch_number,
+            skipped_commitment_reason,
+            mismatches,
+        })
+    }
+}
+
+#[async_trait]
+impl StateKeeperOutputHandler for CommitmentRecomputerOutputHandler {
+    async fn handle_l2_block(&mut self, _updates_manager: &UpdatesManager) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_l1_batch(
+        &mut self,
+        updates_manager: Arc<UpdatesManager>,
+    ) -> anyhow::Result<()> {
+        let report = self.check_batch(&updates_manager).await?;
+        if let Some(reason) = &report.skipped_commitment_reason {
+            tracing::warn!(
+                l1_batch_number = %report.l1_batch_number,
+                "Commitments were not recomputed for L1 batch: {reason}"
+            );
+        }
+        if report.is_empty() {
+            tracing::info!(
+                l1_batch_number = %report.l1_batch_number,
+                "Recomputed L1 batch data matches data in Postgres"
+            );
+        }
+        for mismatch in &report.mismatches {
+            METRICS.commitment_mismatches[&mismatch.field].inc();
+            tracing::error!(
+                l1_batch_number = %report.l1_batch_number,
+                field = mismatch.field,
+                "Recomputed L1 batch data diverges from data in Postgres: {mismatch}"
+            );
+        }
+        if let Some(sender) = &self.report_sender {
+            // The receiver may be dropped if the reports are no longer of interest.
+            sender.send(report).ok();
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct CommitmentRecomputerOutputHandlerFactory {
+    pool: ConnectionPool<Core>,
+    generator: Arc<CommitmentGenerator>,
+    report_sender: Option<mpsc::UnboundedSender<CommitmentRecomputationReport>>,
+}
+
+#[async_trait]
+impl OutputHandlerFactory for CommitmentRecomputerOutputHandlerFactory {
+    async fn create_handler(
+        &mut self,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Box<dyn StateKeeperOutputHandler>> {
+        Ok(Box::new(CommitmentRecomputerOutputHandler {
+            pool: self.pool.clone(),
+            generator: self.generator.clone(),
+            report_sender: self.report_sender.clone(),
+        }))
+    }
+}
@@ -0,0 +1,499 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use tokio::sync::{mpsc, watch};
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_state_keeper::{MainBatchExecutor, StateKeeperOutputHandler, UpdatesManager};
+use zksync_types::{
+    tx::tx_execution_info::TxExecutionStatus, AccountTreeId, L1BatchNumber, L2BlockNumber,
+    L2ChainId, StorageKey, VmEvent, H256, U256,
+};
+use zksync_utils::u256_to_h256;
+
+use crate::{
+    metrics::METRICS, storage::StorageSyncTask, ConcurrentOutputHandlerFactory,
+    ConcurrentOutputHandlerFactoryTask, ConcurrentOutputHandlerOptions, L2BlockCheckpoint,
+    OutputHandlerFactory, VmRunner, VmRunnerIo, VmRunnerStorage, VmRunnerStorageBackend,
+};
+
+/// A standalone component that re-executes L1 batches in the dry-run mode: execution results (storage writes,
+/// events and transaction results) are compared against the data stored in Postgres, and a [`DivergenceReport`]
+/// is emitted for each batch instead of writing any outputs. Useful to audit DB integrity after incidents.
+///
+/// Progress of the runner is kept in memory, so processing starts from `first_processed_batch + 1` after each restart.
+#[derive(Debug)]
+pub struct DryRunVmRunner {
+    vm_runner: VmRunner,
+    io: DryRunIo,
+}
+
+impl DryRunVmRunner {
+    /// Creates a new dry-run VM runner processing batches after `first_processed_batch` up to and including
+    /// `last_batch` (if not specified, the runner keeps processing new batches). Reports are logged and,
+    /// if `report_sender` is provided, sent through it.
+    pub async fn new(
+        pool: ConnectionPool<Core>,
+        storage_backend: VmRunnerStorageBackend,
+        chain_id: L2ChainId,
+        evm_emulator_hash: Option<H256>,
+        first_processed_batch: L1BatchNumber,
+        last_batch: Option<L1BatchNumber>,
+        window_size: u32,
+        report_sender: Option<mpsc::UnboundedSender<DivergenceReport>>,
+    ) -> anyhow::Result<(Self, DryRunVmRunnerTasks)> {
+        let io = DryRunIo {
+            latest_processed_batch: Arc::new(AtomicU32::new(first_processed_batch.0)),
+            last_batch,
+            window_size,
+        };
+        let (loader, loader_task) = VmRunnerStorage::with_backend(
+            pool.clone(),
+            storage_backend,
+            io.clone(),
+            chain_id,
+            evm_emulator_hash,
+        )
+        .await?;
+        let output_handler_factory = DryRunOutputHandlerFactory {
+            pool: pool.clone(),
+            report_sender,
+        };
+        let (output_handler_factory, output_handler_factory_task) =
+            ConcurrentOutputHandlerFactory::new(
+                pool.clone(),
+                io.clone(),
+                output_handler_factory,
+                ConcurrentOutputHandlerOptions::default(),
+            );
+        let batch_processor = MainBatchExecutor::new(false, false);
+        let vm_runner = VmRunner::new(
+            pool,
+            Box::new(io.clone()),
+            Arc::new(loader),
+            Box::new(output_handler_factory),
+            Box::new(batch_processor),
+            window_size,
+        );
+        Ok((
+            Self { vm_runner, io },
+            DryRunVmRunnerTasks {
+                loader_task,
+                output_handler_factory_task,
+            },
+        ))
+    }
+
+    /// Continuously loads new available batches and compares their execution results with the stored ones.
+    /// Returns once `last_batch` is processed (if it was specified), or when a stop signal is received.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB and Postgres errors.
+    pub async fn run(self, stop_receiver: &watch::Receiver<bool>) -> anyhow::Result<()> {
+        const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+        let Some(last_batch) = self.io.last_batch else {
+            return self.vm_runner.run(stop_receiver).await;
+        };
+        let mut stop_receiver = stop_receiver.clone();
+        let io = self.io;
+        let vm_runner_stop_receiver = stop_receiver.clone();
+        let vm_runner = self.vm_runner.run(&vm_runner_stop_receiver);
+        tokio::pin!(vm_runner);
+        loop {
+            let latest_processed_batch = io.latest_processed_batch.load(Ordering::SeqCst);
+            if latest_processed_batch >= last_batch.0 {
+                tracing::info!("Finished dry run for L1 batches up to and including #{last_batch}");
+                return Ok(());
+            }
+
+            tokio::select! {
+                res = &mut vm_runner => return res,
+                _ = stop_receiver.changed() => {
+                    tracing::info!(
+                        "Stop signal received, dry-run VM runner is shutting down; \
+                         processed L1 batches up to and including #{latest_processed_batch}"
+                    );
+                    return Ok(());
+                }
+                () = tokio::time::sleep(POLL_INTERVAL) => { /* continue polling */ }
+            }
+        }
+    }
+}
+
+/// A collections of tasks that need to be run in order for dry-run VM runner to work as intended.
+#[derive(Debug)]
+pub struct DryRunVmRunnerTasks {
+    /// Task that synchronizes storage with new available batches.
+    pub loader_task: StorageSyncTask<DryRunIo>,
+    /// Task that handles output from processed batches.
+    pub output_handler_factory_task: ConcurrentOutputHandlerFactoryTask<DryRunIo>,
+}
+
+/// IO for [`DryRunVmRunner`]. Doesn't persist anything to Postgres.
+#[derive(Debug, Clone)]
+pub struct DryRunIo {
+    latest_processed_batch: Arc<AtomicU32>,
+    last_batch: Option<L1BatchNumber>,
+    window_size: u32,
+}
+
+#[async_trait]
+impl VmRunnerIo for DryRunIo {
+    fn name(&self) -> &'static str {
+        "dry_run_vm_runner"
+    }
+
+    async fn latest_processed_batch(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        Ok(L1BatchNumber(
+            self.latest_processed_batch.load(Ordering::SeqCst),
+        ))
+    }
+
+    async fn last_ready_to_be_loaded_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        let latest_processed_batch = self.latest_processed_batch(conn).await?;
+        let sealed_batch = conn
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .unwrap_or_default();
+        let mut last_ready_batch = sealed_batch.min(latest_processed_batch + self.window_size);
+        if let Some(last_batch) = self.last_batch {
+            last_ready_batch = last_ready_batch.min(last_batch);
+        }
+        Ok(last_ready_batch.max(latest_processed_batch))
+    }
+
+    async fn mark_l1_batch_as_completed(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        self.latest_processed_batch
+            .store(l1_batch_number.0, Ordering::SeqCst);
+        Ok(())
+    }
+
+    // Checkpoints are not persisted since progress of the runner isn't persisted either.
+
+    async fn load_l2_block_checkpoint(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<L2BlockCheckpoint>> {
+        Ok(None)
+    }
+
+    async fn save_l2_block_checkpoint(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+        _l2_block_number: L2BlockNumber,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn mark_l1_batch_as_handled(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+        _last_l2_block_number: L2BlockNumber,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn remove_l2_block_checkpoints(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Outcome of a transaction execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionOutcome {
+    /// Whether the transaction succeeded.
+    pub success: bool,
+    /// Gas used by the transaction.
+    pub gas_used: U256,
+}
+
+/// Divergence between the execution results stored in Postgres (`expected`) and ones produced
+/// by re-executing the batch (`actual`). `None` means that the corresponding entity is missing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Divergence {
+    /// Final value of a storage slot written to in the batch.
+    StorageWrite {
+        /// Written storage slot.
+        key: StorageKey,
+        /// Value stored in Postgres.
+        expected: Option<H256>,
+        /// Value produced by the VM.
+        actual: Option<H256>,
+    },
+    /// Event emitted in the batch.
+    Event {
+        /// Zero-based index of the event in the batch.
+        index: usize,
+        /// Event stored in Postgres.
+        expected: Option<VmEvent>,
+        /// Event produced by the VM.
+        actual: Option<VmEvent>,
+    },
+    /// Transaction execution result.
+    TransactionResult {
+        /// Transaction hash.
+        hash: H256,
+        /// Outcome stored in Postgres.
+        expected: Option<TransactionOutcome>,
+        /// Outcome produced by the VM.
+        actual: Option<TransactionOutcome>,
+    },
+}
+
+impl Divergence {
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Self::StorageWrite { .. } => "storage_write",
+            Self::Event { .. } => "event",
+            Self::TransactionResult { .. } => "transaction_result",
+        }
+    }
+}
+
+/// Report on divergences found for a single L1 batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DivergenceReport {
+    /// Number of the checked L1 batch.
+    pub l1_batch_number: L1BatchNumber,
+    /// Found divergences. Empty if the batch execution matches the stored data.
+    pub divergences: Vec<Divergence>,
+}
+
+impl DivergenceReport {
+    /// Checks whether the batch execution matches the stored data.
+    pub fn is_empty(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+pub(crate) fn compare_storage_writes(
+    expected: &HashMap<StorageKey, H256>,
+    actual: &HashMap<StorageKey, H256>,
+) -> Vec<Divergence> {
+    let keys: BTreeSet<_> = expected.keys().chain(actual.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let expected = expected.get(key).copied();
+            let actual = actual.get(key).copied();
+            (expected != actual).then_some(Divergence::StorageWrite {
+                key: *key,
+                expected,
+                actual,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn compare_events(expected: &[VmEvent], actual: &[VmEvent]) -> Vec<Divergence> {
+    (0..expected.len().max(actual.len()))
+        .filter_map(|index| {
+            let expected = expected.get(index);
+            let actual = actual.get(index);
+            (expected != actual).then(|| Divergence::Event {
+                index,
+                expected: expected.cloned(),
+                actual: actual.cloned(),
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn compare_transaction_outcomes(
+    expected: &HashMap<H256, TransactionOutcome>,
+    actual: &[(H256, TransactionOutcome)],
+) -> Vec<Divergence> {
+    let mut divergences: Vec<_> = actual
+        .iter()
+        .filter_map(|(hash, actual)| {
+            let expected = expected.get(hash).copied();
+            (expected != Some(*actual)).then_some(Divergence::TransactionResult {
+                hash: *hash,
+                expected,
+                actual: Some(*actual),
+            })
+        })
+        .collect();
+    let actual_hashes: HashSet<_> = actual.iter().map(|(hash, _)| *hash).collect();
+    let mut missing: Vec<_> = expected
+        .iter()
+        .filter(|(hash, _)| !actual_hashes.contains(*hash))
+        .collect();
+    missing.sort_unstable_by_key(|(hash, _)| **hash);
+    divergences.extend(
+        missing
+            .into_iter()
+            .map(|(hash, expected)| Divergence::TransactionResult {
+                hash: *hash,
+                expected: Some(*expected),
+                actual: None,
+            }),
+    );
+    divergences
+}
+
+/// Compares execution results of a finished L1 batch with the data stored in Postgres.
+pub(crate) async fn check_batch(
+    pool: &ConnectionPool<Core>,
+    tag: &'static str,
+    updates_manager: &UpdatesManager,
+) -> anyhow::Result<DivergenceReport> {
+    let l1_batch_number = updates_manager.l1_batch.number;
+    let finished_batch = updates_manager
+        .l1_batch
+        .finished
+        .as_ref()
+        .context("L1 batch is not actually finished")?;
+    let execution_state = &finished_batch.final_execution_state;
+
+    let actual_writes: HashMap<_, _> = execution_state
+        .deduplicated_storage_log_queries
+        .iter()
+        .filter(|log_query| log_query.rw_flag)
+        .map(|log_query| {
+            let key = StorageKey::new(
+                AccountTreeId::new(log_query.address),
+                u256_to_h256(log_query.key),
+            );
+            (key, u256_to_h256(log_query.written_value))
+        })
+        .collect();
+    let actual_outcomes: Vec<_> = updates_manager
+        .l1_batch
+        .executed_transactions
+        .iter()
+        .map(|tx| {
+            let outcome = TransactionOutcome {
+                success: tx.execution_status == TxExecutionStatus::Success,
+                gas_used: tx
+                    .transaction
+                    .gas_limit()
+                    .saturating_sub(tx.refunded_gas.into()),
+            };
+            (tx.hash, outcome)
+        })
+        .collect();
+    let tx_hashes: Vec<_> = actual_outcomes.iter().map(|(hash, _)| *hash).collect();
+
+    let mut conn = pool.connection_tagged(tag).await?;
+    let expected_writes = conn
+        .storage_logs_dal()
+        .get_touched_slots_for_l1_batch(l1_batch_number)
+        .await?;
+    let expected_events = conn
+        .events_dal()
+        .get_vm_events_for_l1_batch(l1_batch_number)
+        .await?
+        .with_context(|| format!("L1 batch #{l1_batch_number} is not found"))?;
+    let expected_outcomes: HashMap<_, _> = conn
+        .transactions_web3_dal()
+        .get_transaction_receipts(&tx_hashes)
+        .await?
+        .into_iter()
+        .map(|receipt| {
+            let outcome = TransactionOutcome {
+                success: receipt.status.as_u64() == 1,
+                gas_used: receipt.gas_used.unwrap_or_default(),
+            };
+            (receipt.transaction_hash, outcome)
+        })
+        .collect();
+    drop(conn);
+
+    let mut divergences = compare_storage_writes(&expected_writes, &actual_writes);
+    divergences.extend(compare_events(&expected_events, &execution_state.events));
+    divergences.extend(compare_transaction_outcomes(
+        &expected_outcomes,
+        &actual_outcomes,
+    ));
+    Ok(DivergenceReport {
+        l1_batch_number,
+        divergences,
+    })
+}
+
+#[derive(Debug)]
+struct DryRunOutputHandler {
+    pool: ConnectionPool<Core>,
+    report_sender: Option<mpsc::UnboundedSender<DivergenceReport>>,
+}
+
+#[async_trait]
+impl StateKeeperOutputHandler for DryRunOutputHandler {
+    async fn handle_l2_block(&mut self, _updates_manager: &UpdatesManager) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_l1_batch(
+        &mut self,
+        updates_manager: Arc<UpdatesManager>,
+    ) -> anyhow::Result<()> {
+        let report = check_batch(&self.pool, "dry_run_vm_runner", &updates_manager).await?;
+        if report.is_empty() {
+            tracing::info!(
+                l1_batch_number = %report.l1_batch_number,
+                "Re-executed L1 batch matches data in Postgres"
+            );
+        }
+        for divergence in &report.divergences {
+            METRICS.divergences[&divergence.kind()].inc();
+            tracing::error!(
+                l1_batch_number = %report.l1_batch_number,
+                kind = divergence.kind(),
+                ?divergence,
+                "Re-executed L1 batch diverges from data in Postgres"
+            );
+        }
+        if let Some(sender) = &self.report_sender {
+            // The receiver may be dropped if the reports are no longer of interest.
+            sender.send(report).ok();
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct DryRunOutputHandlerFactory {
+    pool: ConnectionPool<Core>,
+    report_sender: Option<mpsc::UnboundedSender<DivergenceReport>>,
+}
+
+#[async_trait]
+impl OutputHandlerFactory for DryRunOutputHandlerFactory {
+    async fn create_handler(
+        &mut self,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Box<dyn StateKeeperOutputHandler>> {
+        Ok(Box::new(DryRunOutputHandler {
+            pool: self.pool.clone(),
+            report_sender: self.report_sender.clone(),
+        }))
+    }
+}
@@ -1,3 +1,23 @@
-mod protective_reads;
+pub(crate) mod bwip;
+pub(crate) mod commitment_recomputer;
+pub(crate) mod dry_run;
+mod presimulation;
+pub(crate) mod protective_reads;
+pub(crate) mod upgrade_canary;
 
-pub use protective_reads::{ProtectiveReadsWriter, ProtectiveReadsWriterTasks};
+pub use bwip::{BasicWitnessInputProducer, BasicWitnessInputProducerTasks};
+pub use commitment_recomputer::{
+    CommitmentRecomputationReport, CommitmentRecomputer, CommitmentRecomputerIo,
+    CommitmentRecomputerTasks,
+};
+pub use dry_run::{
+    Divergence, DivergenceReport, DryRunIo, DryRunVmRunner, DryRunVmRunnerTasks, TransactionOutcome,
+};
+pub use presimulation::{
+    PresimulationCache, PresimulationIo, PresimulationOutcome, TransactionPresimulator,
+};
+pub use protective_reads::{
+    ProtectiveReadsBackfill, ProtectiveReadsBackfillIo, ProtectiveReadsBackfillTasks,
+    ProtectiveReadsWriter, ProtectiveReadsWriterTasks,
+};
+pub use upgrade_canary::{UpgradeCanary, UpgradeCanaryIo, UpgradeCanaryReport, UpgradeCanaryTasks};
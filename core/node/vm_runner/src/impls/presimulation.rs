@@ -0,0 +1,492 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use multivm::interface::{Halt, L1BatchEnv, SystemEnv, VmRevertReason};
+use tokio::sync::watch;
+use vm_utils::storage::l1_batch_params;
+use zksync_dal::{transactions_dal::PendingL2TxCursor, Connection, ConnectionPool, Core, CoreDal};
+use zksync_state::{ReadStorage, ReadStorageFactory};
+use zksync_state_keeper::{
+    BatchExecutor, MainBatchExecutor, TransactionFilter, TxExecutionResult, TxFilterDecision,
+};
+use zksync_types::{
+    get_code_key, get_nonce_key, L1BatchNumber, L2BlockNumber, L2ChainId, Nonce, Transaction, H256,
+};
+use zksync_utils::h256_to_u32;
+
+use crate::{metrics::METRICS, L2BlockCheckpoint, VmRunnerIo, VmRunnerStorage};
+
+/// A component pre-executing pending L2 transactions in a throwaway VM against the state as of the last
+/// sealed L1 batch. Execution outcomes are stored in a [`PresimulationCache`], which is consulted by the state keeper
+/// as a [`TransactionFilter`]: transactions known to fail validation are rejected without being executed
+/// by the state keeper VM, so that they don't occupy the sequencer at the expense of valid transactions.
+///
+/// Each pending transaction is pre-simulated once, after it's received. Only transactions which nonce matches
+/// the committed nonce of the sender are pre-simulated; other transactions depend on preceding transactions
+/// and are left to the state keeper.
+///
+/// Pre-simulation doesn't take into account the pending (not yet sealed) L1 batch. Thus, most failures are only
+/// a prediction; a transaction failing validation against the sealed state may become valid because of
+/// transactions in the pending batch (e.g., if the sender is funded in the same batch). Hence, only
+/// [state-independent failures](PresimulationOutcome::state_independent_failure()) lead to rejecting transactions.
+///
+/// The pre-simulator reads storage directly from Postgres (see [`VmRunnerStorage::postgres_only()`]): it only
+/// executes a few transactions per sealed batch, so maintaining a separate RocksDB cache isn't worth it.
+#[derive(Debug)]
+pub struct TransactionPresimulator {
+    pool: ConnectionPool<Core>,
+    io: PresimulationIo,
+    storage: Arc<VmRunnerStorage<PresimulationIo>>,
+    batch_executor: Box<dyn BatchExecutor>,
+    cache: Arc<PresimulationCache>,
+    chain_id: L2ChainId,
+    evm_emulator_hash: Option<H256>,
+    validation_computational_gas_limit: u32,
+    batch_size: usize,
+    /// Cursor pointing at the last pre-simulated transaction.
+    cursor: PendingL2TxCursor,
+}
+
+impl TransactionPresimulator {
+    /// Interval between polling Postgres for new pending transactions.
+    const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Creates a new pre-simulator executing at most `batch_size` transactions in a single VM instance.
+    /// Outcomes are recorded to the provided `cache`.
+    pub async fn new(
+        pool: ConnectionPool<Core>,
+        chain_id: L2ChainId,
+        evm_emulator_hash: Option<H256>,
+        validation_computational_gas_limit: u32,
+        batch_size: usize,
+        cache: Arc<PresimulationCache>,
+    ) -> anyhow::Result<Self> {
+        let mut conn = pool.connection_tagged(PresimulationIo::NAME).await?;
+        let last_sealed_batch = conn
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .unwrap_or_default();
+        drop(conn);
+
+        let io = PresimulationIo::new(last_sealed_batch);
+        let storage =
+            VmRunnerStorage::postgres_only(pool.clone(), io.clone(), chain_id, evm_emulator_hash)
+                .await?;
+        Ok(Self {
+            pool,
+            io,
+            storage: Arc::new(storage),
+            batch_executor: Box::new(MainBatchExecutor::new(false, false)),
+            cache,
+            chain_id,
+            evm_emulator_hash,
+            validation_computational_gas_limit,
+            batch_size,
+            // Transactions received before the start are not pre-simulated; they are likely to be picked
+            // by the state keeper soon anyway.
+            cursor: PendingL2TxCursor::received_after_ms(
+                zksync_utils::time::millis_since_epoch() as u64
+            ),
+        })
+    }
+
+    /// Continuously pre-simulates new pending transactions.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB and Postgres errors.
+    pub async fn run(mut self, stop_receiver: &watch::Receiver<bool>) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow() {
+            let processed_count = self.presimulate_pending_transactions(stop_receiver).await?;
+            if processed_count == 0 {
+                tokio::time::sleep(Self::POLL_INTERVAL).await;
+            }
+        }
+        tracing::info!("Stop signal received, transaction pre-simulator is shutting down");
+        Ok(())
+    }
+
+    /// Returns the number of processed pending transactions, including ones that were not pre-simulated.
+    pub(crate) async fn presimulate_pending_transactions(
+        &mut self,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<usize> {
+        let mut conn = self.pool.connection_tagged(PresimulationIo::NAME).await?;
+        let Some(base_batch) = conn.blocks_dal().get_sealed_l1_batch_number().await? else {
+            return Ok(0);
+        };
+        // Outcomes for the previous base batch become stale.
+        self.io.set_base_batch(base_batch);
+        self.cache.set_base_batch(base_batch);
+
+        let (transactions, next_cursor) = conn
+            .transactions_dal()
+            .get_pending_l2_txs_received_after(self.cursor, self.batch_size)
+            .await?;
+        if transactions.is_empty() {
+            return Ok(0);
+        }
+        let processed_count = transactions.len();
+
+        let (system_env, l1_batch_env) = self.load_batch_env(&mut conn, base_batch).await?;
+        drop(conn);
+
+        // Postgres-backed storage is always available, but we handle the general case for robustness;
+        // unprocessed transactions will be pre-simulated on the next iteration.
+        let Some(mut storage) = self
+            .storage
+            .access_storage(stop_receiver, base_batch)
+            .await?
+        else {
+            return Ok(0);
+        };
+        let transactions: Vec<_> = transactions
+            .into_iter()
+            .filter_map(|tx| {
+                let initiator = tx.initiator_account();
+                let committed_nonce =
+                    Nonce(h256_to_u32(storage.read_value(&get_nonce_key(&initiator))));
+                // Accounts without deployed code are served by the default account, which validation
+                // doesn't depend on the account storage.
+                let is_default_account = storage.read_value(&get_code_key(&initiator)).is_zero();
+                (tx.nonce() == Some(committed_nonce)).then_some((tx, is_default_account))
+            })
+            .collect();
+        drop(storage);
+        if transactions.is_empty() {
+            self.cursor = next_cursor;
+            return Ok(processed_count);
+        }
+
+        let Some(mut handle) = self
+            .batch_executor
+            .init_batch(
+                self.storage.clone(),
+                l1_batch_env,
+                system_env,
+                stop_receiver,
+            )
+            .await
+        else {
+            return Ok(0);
+        };
+
+        for (tx, is_default_account) in transactions {
+            let tx_hash = tx.hash();
+            let result = handle.execute_tx(tx).await?;
+            // Transactions are executed independently of each other, i.e., all against the base batch state.
+            handle.rollback_last_tx().await?;
+            let outcome = PresimulationOutcome::new(result, is_default_account);
+            METRICS.presimulated_transactions[&outcome.label()].inc();
+            self.cache.insert(tx_hash, base_batch, outcome);
+        }
+        // Finishing the batch ensures that the executor has released its storage connection.
+        handle.finish_batch().await?;
+
+        self.cursor = next_cursor;
+        Ok(processed_count)
+    }
+
+    /// Loads VM params for the batch following the base batch. The loaded params are similar to ones
+    /// used by the state keeper for the pending batch.
+    async fn load_batch_env(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        base_batch: L1BatchNumber,
+    ) -> anyhow::Result<(SystemEnv, L1BatchEnv)> {
+        let (_, last_l2_block_number) = conn
+            .blocks_dal()
+            .get_l2_block_range_of_l1_batch(base_batch)
+            .await?
+            .with_context(|| format!("L1 batch #{base_batch} has no L2 blocks"))?;
+        let last_l2_block = conn
+            .blocks_dal()
+            .get_l2_block_header(last_l2_block_number)
+            .await?
+            .with_context(|| format!("L2 block #{last_l2_block_number} disappeared"))?;
+        let contract_hashes = last_l2_block.base_system_contracts_hashes;
+        let base_system_contracts = conn
+            .factory_deps_dal()
+            .get_base_system_contracts(
+                contract_hashes.bootloader,
+                contract_hashes.default_aa,
+                self.evm_emulator_hash,
+            )
+            .await
+            .context("failed getting base system contracts")?;
+        let protocol_version = last_l2_block
+            .protocol_version
+            .with_context(|| format!("L2 block #{last_l2_block_number} has no protocol version"))?;
+
+        Ok(l1_batch_params(
+            base_batch + 1,
+            last_l2_block.fee_account_address,
+            last_l2_block.timestamp + 1,
+            // The previous batch hash is only recorded by the bootloader; it doesn't influence transaction execution.
+            H256::zero(),
+            last_l2_block.batch_fee_input,
+            last_l2_block_number + 1,
+            last_l2_block.hash,
+            base_system_contracts,
+            self.validation_computational_gas_limit,
+            protocol_version,
+            last_l2_block.virtual_blocks,
+            self.chain_id,
+        ))
+    }
+}
+
+/// IO for [`TransactionPresimulator`]. The latest processed batch is the base batch for pre-simulation,
+/// i.e., the last sealed batch observed by the pre-simulator. Doesn't persist anything to Postgres.
+#[derive(Debug, Clone)]
+pub struct PresimulationIo {
+    base_batch: Arc<AtomicU32>,
+}
+
+impl PresimulationIo {
+    const NAME: &'static str = "tx_presimulator";
+
+    fn new(base_batch: L1BatchNumber) -> Self {
+        Self {
+            base_batch: Arc::new(AtomicU32::new(base_batch.0)),
+        }
+    }
+
+    fn set_base_batch(&self, base_batch: L1BatchNumber) {
+        self.base_batch.store(base_batch.0, Ordering::SeqCst);
+    }
+}
+
+#[async_trait]
+impl VmRunnerIo for PresimulationIo {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    async fn latest_processed_batch(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        Ok(L1BatchNumber(self.base_batch.load(Ordering::SeqCst)))
+    }
+
+    async fn last_ready_to_be_loaded_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        // Batches are never executed by the pre-simulator, so there's no need to load them.
+        self.latest_processed_batch(conn).await
+    }
+
+    async fn mark_l1_batch_as_completed(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    // Checkpoints are not used since the pre-simulator doesn't execute batches.
+
+    async fn load_l2_block_checkpoint(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<L2BlockCheckpoint>> {
+        Ok(None)
+    }
+
+    async fn save_l2_block_checkpoint(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+        _l2_block_number: L2BlockNumber,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn mark_l1_batch_as_handled(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+        _last_l2_block_number: L2BlockNumber,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn remove_l2_block_checkpoints(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Outcome of pre-simulating a transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PresimulationOutcome {
+    /// Transaction was successfully executed (it may still have reverted).
+    Executed,
+    /// Transaction was rejected by the VM.
+    Rejected {
+        reason: Halt,
+        /// Whether the transaction initiator is served by the default account (i.e., has no deployed code).
+        is_default_account: bool,
+    },
+    /// Transaction didn't fit into the bootloader gas limit.
+    BootloaderOutOfGas,
+}
+
+impl PresimulationOutcome {
+    fn new(result: TxExecutionResult, is_default_account: bool) -> Self {
+        match result {
+            TxExecutionResult::Success { .. } => Self::Executed,
+            TxExecutionResult::RejectedByVm { reason } => Self::Rejected {
+                reason,
+                is_default_account,
+            },
+            // Like running out of bootloader gas, exceeding the batch budget depends on other transactions in the batch.
+            TxExecutionResult::BootloaderOutOfGasForTx
+            | TxExecutionResult::BatchBudgetExceeded(_) => Self::BootloaderOutOfGas,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Executed => "executed",
+            Self::Rejected { .. } if self.state_independent_failure().is_some() => "invalid",
+            Self::Rejected { .. } => "rejected",
+            Self::BootloaderOutOfGas => "bootloader_out_of_gas",
+        }
+    }
+
+    /// Message the bootloader reverts with if account validation returns an invalid magic value, i.e.,
+    /// if the transaction signature is incorrect.
+    const INVALID_SIGNATURE_MSG: &'static str =
+        "Account validation returned invalid magic value. Most often this means that the signature is incorrect";
+
+    /// Returns the halt reason if the transaction has failed in a way that doesn't depend on the pending batch,
+    /// i.e., if it will be rejected by the state keeper regardless of transactions executed before it. These are
+    /// an excessive gas limit and an invalid signature for a default account.
+    ///
+    /// Other kinds of validation failures (e.g., failing to charge fee or paymaster validation failures) depend
+    /// on the storage state, which may be changed by the pending batch not observed during pre-simulation;
+    /// thus, such transactions are left to the state keeper. The same applies to signature checks in custom
+    /// accounts, which may depend on the account storage (e.g., on the set of account owners).
+    pub fn state_independent_failure(&self) -> Option<&Halt> {
+        let Self::Rejected {
+            reason,
+            is_default_account,
+        } = self
+        else {
+            return None;
+        };
+        match reason {
+            Halt::TooBigGasLimit => Some(reason),
+            Halt::ValidationFailed(VmRevertReason::General { msg, .. })
+                if *is_default_account && msg == Self::INVALID_SIGNATURE_MSG =>
+            {
+                Some(reason)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CachedOutcome {
+    base_batch: L1BatchNumber,
+    outcome: PresimulationOutcome,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    base_batch: L1BatchNumber,
+    outcomes: HashMap<H256, CachedOutcome>,
+}
+
+/// Cache of [`TransactionPresimulator`] outcomes shared with the state keeper.
+///
+/// Outcomes are only considered fresh while the last sealed batch doesn't change, i.e., while the state keeper
+/// executes the batch following the base batch of pre-simulation. Stale outcomes are pruned once the base batch
+/// changes, and their transactions are not pre-simulated again.
+#[derive(Debug, Default)]
+pub struct PresimulationCache {
+    state: Mutex<CacheState>,
+}
+
+impl PresimulationCache {
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        self.state.lock().expect("pre-simulation cache is poisoned")
+    }
+
+    pub(crate) fn set_base_batch(&self, base_batch: L1BatchNumber) {
+        let mut state = self.lock();
+        if state.base_batch != base_batch {
+            state.base_batch = base_batch;
+            state
+                .outcomes
+                .retain(|_, cached| cached.base_batch >= base_batch);
+        }
+    }
+
+    pub(crate) fn insert(
+        &self,
+        tx_hash: H256,
+        base_batch: L1BatchNumber,
+        outcome: PresimulationOutcome,
+    ) {
+        let mut state = self.lock();
+        if base_batch >= state.base_batch {
+            state.outcomes.insert(
+                tx_hash,
+                CachedOutcome {
+                    base_batch,
+                    outcome,
+                },
+            );
+        }
+    }
+
+    /// Takes the outcome for the specified transaction if it's fresh.
+    pub fn take_fresh_outcome(&self, tx_hash: H256) -> Option<PresimulationOutcome> {
+        let mut state = self.lock();
+        let cached = state.outcomes.remove(&tx_hash);
+        let Some(cached) = cached else {
+            METRICS.presimulation_cache_lookups[&"miss"].inc();
+            return None;
+        };
+        if cached.base_batch == state.base_batch {
+            Some(cached.outcome)
+        } else {
+            METRICS.presimulation_cache_lookups[&"stale"].inc();
+            None
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionFilter for PresimulationCache {
+    async fn filter(&self, tx: &Transaction) -> anyhow::Result<TxFilterDecision> {
+        let Some(outcome) = self.take_fresh_outcome(tx.hash()) else {
+            return Ok(TxFilterDecision::Allow);
+        };
+        Ok(if let Some(halt) = outcome.state_independent_failure() {
+            METRICS.presimulation_cache_lookups[&"denied"].inc();
+            TxFilterDecision::Deny(format!("pre-simulation: {halt}"))
+        } else {
+            METRICS.presimulation_cache_lookups[&"allowed"].inc();
+            TxFilterDecision::Allow
+        })
+    }
+}
@@ -1,16 +1,29 @@
-use std::sync::Arc;
+use std::{
+    num::NonZeroU32,
+    ops,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
 use anyhow::Context;
 use async_trait::async_trait;
 use tokio::sync::watch;
-use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_dal::{watermarks_dal::WatermarkComponent, Connection, ConnectionPool, Core, CoreDal};
 use zksync_state_keeper::{MainBatchExecutor, StateKeeperOutputHandler, UpdatesManager};
-use zksync_types::{zk_evm_types::LogQuery, AccountTreeId, L1BatchNumber, L2ChainId, StorageKey};
+use zksync_types::{
+    zk_evm_types::LogQuery, AccountTreeId, L1BatchNumber, L2BlockNumber, L2ChainId, StorageKey,
+    H256,
+};
 use zksync_utils::u256_to_h256;
 
 use crate::{
     storage::StorageSyncTask, ConcurrentOutputHandlerFactory, ConcurrentOutputHandlerFactoryTask,
-    OutputHandlerFactory, VmRunner, VmRunnerIo, VmRunnerStorage,
+    ConcurrentOutputHandlerOptions, ExponentialBackoff, L2BlockCheckpoint, NotifiedIo,
+    OutputHandlerFactory, SharedVmRunnerStorage, VmRunner, VmRunnerIo, VmRunnerStorage,
+    VmRunnerStorageBackend,
 };
 
 /// A standalone component that writes protective reads asynchronously to state keeper.
@@ -21,23 +34,93 @@ pub struct ProtectiveReadsWriter {
 
 impl ProtectiveReadsWriter {
     /// Create a new protective reads writer from the provided DB parameters and window size which
-    /// regulates how many batches this component can handle at the same time.
+    /// regulates how many batches this component can handle at the same time. If `sealed_batches`
+    /// (obtained from [`SealedBatchesListener::subscribe()`](crate::SealedBatchesListener::subscribe())) are provided, the writer reacts to sealed batches
+    /// instead of polling Postgres.
     pub async fn new(
         pool: ConnectionPool<Core>,
         rocksdb_path: String,
         chain_id: L2ChainId,
+        evm_emulator_hash: Option<H256>,
         first_processed_batch: L1BatchNumber,
         window_size: u32,
+        sealed_batches: Option<watch::Receiver<L1BatchNumber>>,
     ) -> anyhow::Result<(Self, ProtectiveReadsWriterTasks)> {
-        let io = ProtectiveReadsIo {
-            first_processed_batch,
-            window_size,
-        };
-        let (loader, loader_task) =
-            VmRunnerStorage::new(pool.clone(), rocksdb_path, io.clone(), chain_id).await?;
+        let io = NotifiedIo::with_sealed_batches(
+            ProtectiveReadsIo {
+                first_processed_batch,
+                window_size,
+            },
+            sealed_batches,
+        );
+        let (loader, loader_task) = VmRunnerStorage::new(
+            pool.clone(),
+            rocksdb_path,
+            io.clone(),
+            chain_id,
+            evm_emulator_hash,
+        )
+        .await?;
+        let (this, output_handler_factory_task) = Self::with_loader(pool, io, loader);
+        Ok((
+            this,
+            ProtectiveReadsWriterTasks {
+                loader_task,
+                output_handler_factory_task,
+            },
+        ))
+    }
+
+    /// Creates a new protective reads writer using storage shared with other VM runners. Unlike with [`Self::new()`],
+    /// storage is synchronized by the task created by [`SharedVmRunnerStorage::into_sync_task()`], so only the returned
+    /// output handler task needs to be run.
+    pub fn with_shared_storage(
+        pool: ConnectionPool<Core>,
+        shared_storage: &mut SharedVmRunnerStorage,
+        first_processed_batch: L1BatchNumber,
+        window_size: u32,
+        sealed_batches: Option<watch::Receiver<L1BatchNumber>>,
+    ) -> (
+        Self,
+        ConcurrentOutputHandlerFactoryTask<NotifiedProtectiveReadsIo>,
+    ) {
+        let io = NotifiedIo::with_sealed_batches(
+            ProtectiveReadsIo {
+                first_processed_batch,
+                window_size,
+            },
+            sealed_batches,
+        );
+        let loader = shared_storage.add_io(io.clone());
+        Self::with_loader(pool, io, loader)
+    }
+
+    fn with_loader(
+        pool: ConnectionPool<Core>,
+        io: NotifiedProtectiveReadsIo,
+        loader: VmRunnerStorage<NotifiedProtectiveReadsIo>,
+    ) -> (
+        Self,
+        ConcurrentOutputHandlerFactoryTask<NotifiedProtectiveReadsIo>,
+    ) {
+        let window_size = io.inner().window_size;
         let output_handler_factory = ProtectiveReadsOutputHandlerFactory { pool: pool.clone() };
+        // Protective reads are written in bulk, so there's no need to poll for new handlers frequently
+        // once the writer has caught up with the state keeper.
+        let options = ConcurrentOutputHandlerOptions {
+            backoff: Arc::new(ExponentialBackoff {
+                initial: Duration::from_millis(50),
+                max: Duration::from_secs(1),
+            }),
+            ..ConcurrentOutputHandlerOptions::default()
+        };
         let (output_handler_factory, output_handler_factory_task) =
-            ConcurrentOutputHandlerFactory::new(pool.clone(), io.clone(), output_handler_factory);
+            ConcurrentOutputHandlerFactory::new(
+                pool.clone(),
+                io.clone(),
+                output_handler_factory,
+                options,
+            );
         let batch_processor = MainBatchExecutor::new(false, false);
         let vm_runner = VmRunner::new(
             pool,
@@ -45,14 +128,9 @@ impl ProtectiveReadsWriter {
             Arc::new(loader),
             Box::new(output_handler_factory),
             Box::new(batch_processor),
+            window_size,
         );
-        Ok((
-            Self { vm_runner },
-            ProtectiveReadsWriterTasks {
-                loader_task,
-                output_handler_factory_task,
-            },
-        ))
+        (Self { vm_runner }, output_handler_factory_task)
     }
 
     /// Continuously loads new available batches and writes the corresponding protective reads
@@ -71,11 +149,14 @@ impl ProtectiveReadsWriter {
 #[derive(Debug)]
 pub struct ProtectiveReadsWriterTasks {
     /// Task that synchronizes storage with new available batches.
-    pub loader_task: StorageSyncTask<ProtectiveReadsIo>,
+    pub loader_task: StorageSyncTask<NotifiedProtectiveReadsIo>,
     /// Task that handles output from processed batches.
-    pub output_handler_factory_task: ConcurrentOutputHandlerFactoryTask<ProtectiveReadsIo>,
+    pub output_handler_factory_task: ConcurrentOutputHandlerFactoryTask<NotifiedProtectiveReadsIo>,
 }
 
+/// IO used by [`ProtectiveReadsWriter`].
+pub type NotifiedProtectiveReadsIo = NotifiedIo<ProtectiveReadsIo>;
+
 #[derive(Debug, Clone)]
 pub struct ProtectiveReadsIo {
     first_processed_batch: L1BatchNumber,
@@ -113,10 +194,251 @@ impl VmRunnerIo for ProtectiveReadsIo {
         conn: &mut Connection<'_, Core>,
         l1_batch_number: L1BatchNumber,
     ) -> anyhow::Result<()> {
-        Ok(conn
-            .vm_runner_dal()
-            .mark_protective_reads_batch_as_completed(l1_batch_number)
-            .await?)
+        conn.watermarks_dal()
+            .set_watermark(WatermarkComponent::ProtectiveReads, l1_batch_number)
+            .await?;
+        Ok(())
+    }
+}
+
+/// One-off component processing a historical range of L1 batches with the same output handler as
+/// [`ProtectiveReadsWriter`], e.g. batches sealed before the writer was enabled.
+///
+/// The backfill is intended to be run against a production replica, so it can be rate-limited to avoid starving
+/// other components of Postgres connections. Progress is kept in memory and isn't visible to [`ProtectiveReadsWriter`];
+/// in particular, the backfill doesn't affect the batch the writer will process next.
+#[derive(Debug)]
+pub struct ProtectiveReadsBackfill {
+    vm_runner: VmRunner,
+    io: ProtectiveReadsBackfillIo,
+}
+
+impl ProtectiveReadsBackfill {
+    /// Creates a backfill processing batches in the specified range. If `max_batches_per_minute` is set, batches
+    /// are loaded at most at the specified rate (the first batch is loaded immediately).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the batch range is empty, or propagates RocksDB and Postgres errors.
+    pub async fn new(
+        pool: ConnectionPool<Core>,
+        storage_backend: VmRunnerStorageBackend,
+        chain_id: L2ChainId,
+        evm_emulator_hash: Option<H256>,
+        batch_range: ops::RangeInclusive<L1BatchNumber>,
+        window_size: u32,
+        max_batches_per_minute: Option<NonZeroU32>,
+    ) -> anyhow::Result<(Self, ProtectiveReadsBackfillTasks)> {
+        let (first_batch, last_batch) = batch_range.into_inner();
+        anyhow::ensure!(
+            first_batch <= last_batch && first_batch > L1BatchNumber(0),
+            "invalid L1 batch range for backfill: #{first_batch}..=#{last_batch}"
+        );
+        let first_processed_batch = first_batch - 1;
+        let io = ProtectiveReadsBackfillIo {
+            first_processed_batch,
+            latest_processed_batch: Arc::new(AtomicU32::new(first_processed_batch.0)),
+            last_batch,
+            window_size,
+            rate_limiter: max_batches_per_minute.map(BatchRateLimiter::new),
+        };
+        let (loader, loader_task) = VmRunnerStorage::with_backend(
+            pool.clone(),
+            storage_backend,
+            io.clone(),
+            chain_id,
+            evm_emulator_hash,
+        )
+        .await?;
+        let output_handler_factory = ProtectiveReadsOutputHandlerFactory { pool: pool.clone() };
+        let (output_handler_factory, output_handler_factory_task) =
+            ConcurrentOutputHandlerFactory::new(
+                pool.clone(),
+                io.clone(),
+                output_handler_factory,
+                ConcurrentOutputHandlerOptions::default(),
+            );
+        let batch_processor = MainBatchExecutor::new(false, false);
+        let vm_runner = VmRunner::new(
+            pool,
+            Box::new(io.clone()),
+            Arc::new(loader),
+            Box::new(output_handler_factory),
+            Box::new(batch_processor),
+            window_size,
+        );
+        Ok((
+            Self { vm_runner, io },
+            ProtectiveReadsBackfillTasks {
+                loader_task,
+                output_handler_factory_task,
+            },
+        ))
+    }
+
+    /// Processes all batches in the configured range. Returns once the last batch in the range is processed,
+    /// or when a stop signal is received.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB and Postgres errors.
+    pub async fn run(self, stop_receiver: &watch::Receiver<bool>) -> anyhow::Result<()> {
+        const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+        let mut stop_receiver = stop_receiver.clone();
+        let io = self.io;
+        let vm_runner_stop_receiver = stop_receiver.clone();
+        let vm_runner = self.vm_runner.run(&vm_runner_stop_receiver);
+        tokio::pin!(vm_runner);
+        loop {
+            let latest_processed_batch = io.latest_processed_batch.load(Ordering::SeqCst);
+            if latest_processed_batch >= io.last_batch.0 {
+                tracing::info!(
+                    "Finished backfilling protective reads for L1 batches #{}..=#{}",
+                    io.first_processed_batch + 1,
+                    io.last_batch
+                );
+                return Ok(());
+            }
+
+            tokio::select! {
+                res = &mut vm_runner => return res,
+                _ = stop_receiver.changed() => {
+                    tracing::info!(
+                        "Stop signal received, protective reads backfill is shutting down; \
+                         processed L1 batches up to and including #{latest_processed_batch}"
+                    );
+                    return Ok(());
+                }
+                () = tokio::time::sleep(POLL_INTERVAL) => { /* continue polling */ }
+            }
+        }
+    }
+}
+
+/// A collections of tasks that need to be run in order for [`ProtectiveReadsBackfill`] to work as intended.
+#[derive(Debug)]
+pub struct ProtectiveReadsBackfillTasks {
+    /// Task that synchronizes storage with new available batches.
+    pub loader_task: StorageSyncTask<ProtectiveReadsBackfillIo>,
+    /// Task that handles output from processed batches.
+    pub output_handler_factory_task: ConcurrentOutputHandlerFactoryTask<ProtectiveReadsBackfillIo>,
+}
+
+/// Limits the rate at which [`ProtectiveReadsBackfill`] loads batches.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BatchRateLimiter {
+    started_at: Instant,
+    max_batches_per_minute: NonZeroU32,
+}
+
+impl BatchRateLimiter {
+    pub(crate) fn new(max_batches_per_minute: NonZeroU32) -> Self {
+        Self {
+            started_at: Instant::now(),
+            max_batches_per_minute,
+        }
+    }
+
+    /// Returns the total number of batches allowed to be loaded after `elapsed` time since the start.
+    pub(crate) fn allowed_batch_count(&self, elapsed: Duration) -> u32 {
+        let rate = f64::from(self.max_batches_per_minute.get());
+        // Float-to-int conversion saturates, so this cannot overflow.
+        let count = (elapsed.as_secs_f64() * rate / 60.0) as u32;
+        count.saturating_add(1)
+    }
+}
+
+/// IO for [`ProtectiveReadsBackfill`]. Doesn't persist progress or L2 block checkpoints to Postgres.
+#[derive(Debug, Clone)]
+pub struct ProtectiveReadsBackfillIo {
+    first_processed_batch: L1BatchNumber,
+    latest_processed_batch: Arc<AtomicU32>,
+    last_batch: L1BatchNumber,
+    window_size: u32,
+    rate_limiter: Option<BatchRateLimiter>,
+}
+
+#[async_trait]
+impl VmRunnerIo for ProtectiveReadsBackfillIo {
+    fn name(&self) -> &'static str {
+        "protective_reads_backfill"
+    }
+
+    async fn latest_processed_batch(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        Ok(L1BatchNumber(
+            self.latest_processed_batch.load(Ordering::SeqCst),
+        ))
+    }
+
+    async fn last_ready_to_be_loaded_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        let latest_processed_batch = self.latest_processed_batch(conn).await?;
+        let sealed_batch = conn
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .unwrap_or_default();
+        let mut last_ready_batch = sealed_batch
+            .min(latest_processed_batch + self.window_size)
+            .min(self.last_batch);
+        if let Some(rate_limiter) = &self.rate_limiter {
+            let allowed_count = rate_limiter.allowed_batch_count(rate_limiter.started_at.elapsed());
+            let allowed_batch = self.first_processed_batch.0.saturating_add(allowed_count);
+            last_ready_batch = last_ready_batch.min(L1BatchNumber(allowed_batch));
+        }
+        Ok(last_ready_batch.max(latest_processed_batch))
+    }
+
+    async fn mark_l1_batch_as_completed(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        self.latest_processed_batch
+            .store(l1_batch_number.0, Ordering::SeqCst);
+        Ok(())
+    }
+
+    // Checkpoints are not persisted since progress of the backfill isn't persisted either.
+
+    async fn load_l2_block_checkpoint(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<L2BlockCheckpoint>> {
+        Ok(None)
+    }
+
+    async fn save_l2_block_checkpoint(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+        _l2_block_number: L2BlockNumber,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn mark_l1_batch_as_handled(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+        _last_l2_block_number: L2BlockNumber,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn remove_l2_block_checkpoints(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        Ok(())
     }
 }
 
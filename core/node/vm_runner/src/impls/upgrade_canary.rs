@@ -0,0 +1,397 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use tokio::sync::{mpsc, watch};
+use zksync_dal::{watermarks_dal::WatermarkComponent, Connection, ConnectionPool, Core, CoreDal};
+use zksync_state::{PgOrRocksdbStorage, ReadStorageFactory};
+use zksync_state_keeper::{MainBatchExecutor, StateKeeperOutputHandler, UpdatesManager};
+use zksync_types::{L1BatchNumber, L2ChainId, ProtocolVersionId, H256};
+
+use super::dry_run::{check_batch, DivergenceReport};
+use crate::{
+    metrics::METRICS,
+    storage::{StorageLoader, StorageSyncTask},
+    BatchExecuteData, ConcurrentOutputHandlerFactory, ConcurrentOutputHandlerFactoryTask,
+    ConcurrentOutputHandlerOptions, OutputHandlerFactory, VmRunner, VmRunnerIo, VmRunnerStorage,
+    VmRunnerStorageBackend,
+};
+
+/// A component validating a scheduled protocol upgrade on real traffic before the upgrade is activated.
+/// Once an upgrade is scheduled (i.e., the next protocol version is stored in Postgres, but its timestamp
+/// is not reached yet), the canary shadow-executes the next `canary_batches` sealed L1 batches under the VM
+/// of the next protocol version and compares the results with the ones produced by the state keeper
+/// under the current version. An [`UpgradeCanaryReport`] is emitted for each executed batch.
+///
+/// Batches are executed with the base system contracts of the next version (if they are known),
+/// but without the protocol upgrade transaction, so some divergences may be expected for upgrades
+/// changing system contracts' state.
+///
+/// Selected batches are persisted in Postgres, and progress of the canary is published as the
+/// [`WatermarkComponent::UpgradeCanary`] watermark. When the canary is started for the first time,
+/// processing starts from the last sealed batch.
+#[derive(Debug)]
+pub struct UpgradeCanary {
+    vm_runner: VmRunner,
+}
+
+impl UpgradeCanary {
+    /// Maximum number of batches executed concurrently. The canary is not latency-sensitive, so it's kept low
+    /// in order to not compete with the state keeper for resources.
+    const WINDOW_SIZE: u32 = 1;
+
+    /// Creates a new upgrade canary executing at most `canary_batches` batches for each scheduled upgrade.
+    /// Reports are logged and, if `report_sender` is provided, sent through it.
+    ///
+    /// Since the canary only executes a few batches per upgrade, [`VmRunnerStorageBackend::PostgresOnly`]
+    /// is usually preferable to a RocksDB cache, which would need to be kept in sync with Postgres all the time.
+    pub async fn new(
+        pool: ConnectionPool<Core>,
+        storage_backend: VmRunnerStorageBackend,
+        chain_id: L2ChainId,
+        evm_emulator_hash: Option<H256>,
+        canary_batches: u32,
+        report_sender: Option<mpsc::UnboundedSender<UpgradeCanaryReport>>,
+    ) -> anyhow::Result<(Self, UpgradeCanaryTasks)> {
+        let mut conn = pool.connection_tagged(UpgradeCanaryIo::NAME).await?;
+        let last_sealed_batch = conn
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .unwrap_or_default();
+        drop(conn);
+
+        let io = UpgradeCanaryIo::new(last_sealed_batch, canary_batches);
+        let (loader, loader_task) = VmRunnerStorage::with_backend(
+            pool.clone(),
+            storage_backend,
+            io.clone(),
+            chain_id,
+            evm_emulator_hash,
+        )
+        .await?;
+        let loader = UpgradeCanaryLoader::new(Arc::new(loader), pool.clone());
+        let output_handler_factory = UpgradeCanaryOutputHandlerFactory {
+            pool: pool.clone(),
+            report_sender,
+        };
+        let (output_handler_factory, output_handler_factory_task) =
+            ConcurrentOutputHandlerFactory::new(
+                pool.clone(),
+                io.clone(),
+                output_handler_factory,
+                ConcurrentOutputHandlerOptions::default(),
+            );
+        let batch_processor = MainBatchExecutor::new(false, false);
+        let vm_runner = VmRunner::new(
+            pool,
+            Box::new(io),
+            Arc::new(loader),
+            Box::new(output_handler_factory),
+            Box::new(batch_processor),
+            Self::WINDOW_SIZE,
+        );
+        Ok((
+            Self { vm_runner },
+            UpgradeCanaryTasks {
+                loader_task,
+                output_handler_factory_task,
+            },
+        ))
+    }
+
+    /// Continuously loads new sealed batches and shadow-executes them if a protocol upgrade is scheduled.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB and Postgres errors.
+    pub async fn run(self, stop_receiver: &watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.vm_runner.run(stop_receiver).await
+    }
+}
+
+/// A collections of tasks that need to be run in order for upgrade canary to work as intended.
+#[derive(Debug)]
+pub struct UpgradeCanaryTasks {
+    /// Task that synchronizes storage with new available batches.
+    pub loader_task: StorageSyncTask<UpgradeCanaryIo>,
+    /// Task that handles output from processed batches.
+    pub output_handler_factory_task: ConcurrentOutputHandlerFactoryTask<UpgradeCanaryIo>,
+}
+
+/// Report on divergences found by [`UpgradeCanary`] for a single L1 batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpgradeCanaryReport {
+    /// Protocol version the batch was shadow-executed under.
+    pub protocol_version: ProtocolVersionId,
+    /// Divergences between the shadow execution (`actual`) and the execution under the current
+    /// protocol version (`expected`).
+    pub divergences: DivergenceReport,
+}
+
+/// IO for [`UpgradeCanary`].
+#[derive(Debug, Clone)]
+pub struct UpgradeCanaryIo {
+    /// Batch treated as the latest processed one if the canary hasn't published its watermark yet.
+    first_processed_batch: L1BatchNumber,
+    canary_batches: u32,
+}
+
+impl UpgradeCanaryIo {
+    const NAME: &'static str = "upgrade_canary";
+
+    pub(crate) fn new(first_processed_batch: L1BatchNumber, canary_batches: u32) -> Self {
+        Self {
+            first_processed_batch,
+            canary_batches,
+        }
+    }
+
+    /// Returns the next protocol version if its upgrade is scheduled, but not activated for the specified batch.
+    async fn scheduled_upgrade(
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<ProtocolVersionId>> {
+        let header = conn
+            .blocks_dal()
+            .get_l1_batch_header(l1_batch_number)
+            .await?
+            .with_context(|| format!("L1 batch #{l1_batch_number} is not found"))?;
+        let current_version = header
+            .protocol_version
+            .with_context(|| format!("L1 batch #{l1_batch_number} has no protocol version"))?;
+        let next_version = conn
+            .protocol_versions_dal()
+            .get_next_protocol_version(current_version)
+            .await?;
+        Ok(
+            next_version.and_then(|(next_version, activation_timestamp)| {
+                (activation_timestamp > header.timestamp).then_some(next_version)
+            }),
+        )
+    }
+}
+
+#[async_trait]
+impl VmRunnerIo for UpgradeCanaryIo {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    async fn latest_processed_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        Ok(conn
+            .vm_runner_dal()
+            .get_upgrade_canary_latest_processed_batch(self.first_processed_batch)
+            .await?)
+    }
+
+    async fn last_ready_to_be_loaded_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        let latest_processed_batch = self.latest_processed_batch(conn).await?;
+        let sealed_batch = conn
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .unwrap_or_default();
+        let last_ready_batch =
+            sealed_batch.min(latest_processed_batch + UpgradeCanary::WINDOW_SIZE);
+        Ok(last_ready_batch.max(latest_processed_batch))
+    }
+
+    async fn should_process_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<bool> {
+        // The method may be called multiple times for the same batch if the batch isn't loaded yet,
+        // or after a restart.
+        let selected_version = conn
+            .vm_runner_dal()
+            .get_upgrade_canary_batch_version(l1_batch_number)
+            .await?;
+        if selected_version.is_some() {
+            return Ok(true);
+        }
+        let Some(next_version) = Self::scheduled_upgrade(conn, l1_batch_number).await? else {
+            return Ok(false);
+        };
+
+        let is_selected = conn
+            .vm_runner_dal()
+            .select_upgrade_canary_batch(l1_batch_number, next_version, self.canary_batches)
+            .await?;
+        if is_selected {
+            tracing::info!(
+                %l1_batch_number,
+                ?next_version,
+                "Selected L1 batch for shadow execution under the next protocol version"
+            );
+        }
+        Ok(is_selected)
+    }
+
+    async fn mark_l1_batch_as_completed(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        conn.watermarks_dal()
+            .set_watermark(WatermarkComponent::UpgradeCanary, l1_batch_number)
+            .await?;
+        Ok(())
+    }
+}
+
+async fn selected_batch_version(
+    conn: &mut Connection<'_, Core>,
+    l1_batch_number: L1BatchNumber,
+) -> anyhow::Result<ProtocolVersionId> {
+    conn.vm_runner_dal()
+        .get_upgrade_canary_batch_version(l1_batch_number)
+        .await?
+        .with_context(|| {
+            format!("L1 batch #{l1_batch_number} is not selected for shadow execution")
+        })
+}
+
+/// Loader patching the system environment of loaded batches to use the next protocol version.
+#[derive(Debug)]
+pub(crate) struct UpgradeCanaryLoader {
+    inner: Arc<dyn StorageLoader>,
+    pool: ConnectionPool<Core>,
+}
+
+impl UpgradeCanaryLoader {
+    pub(crate) fn new(inner: Arc<dyn StorageLoader>, pool: ConnectionPool<Core>) -> Self {
+        Self { inner, pool }
+    }
+}
+
+#[async_trait]
+impl ReadStorageFactory for UpgradeCanaryLoader {
+    async fn access_storage(
+        &self,
+        stop_receiver: &watch::Receiver<bool>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<PgOrRocksdbStorage<'_>>> {
+        self.inner
+            .access_storage(stop_receiver, l1_batch_number)
+            .await
+    }
+}
+
+#[async_trait]
+impl StorageLoader for UpgradeCanaryLoader {
+    async fn load_batch(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<BatchExecuteData>> {
+        let Some(mut batch_data) = self.inner.load_batch(l1_batch_number).await? else {
+            return Ok(None);
+        };
+        let mut conn = self.pool.connection_tagged(UpgradeCanaryIo::NAME).await?;
+        let next_version = selected_batch_version(&mut conn, l1_batch_number).await?;
+        // The EVM emulator is chain-wide, so it's carried over from the base system contracts of the loaded batch.
+        let evm_emulator_hash = batch_data
+            .system_env
+            .base_system_smart_contracts
+            .evm_emulator_hash();
+        let base_system_contracts = conn
+            .protocol_versions_dal()
+            .load_base_system_contracts_by_version_id(next_version as u16, evm_emulator_hash)
+            .await?;
+        drop(conn);
+        if let Some(base_system_contracts) = base_system_contracts {
+            batch_data.system_env.base_system_smart_contracts = base_system_contracts;
+        } else {
+            tracing::warn!(
+                %l1_batch_number,
+                ?next_version,
+                "Base system contracts for the next protocol version are unknown; using current ones"
+            );
+        }
+        batch_data.system_env.version = next_version;
+        Ok(Some(batch_data))
+    }
+
+    fn upcast(self: Arc<Self>) -> Arc<dyn ReadStorageFactory> {
+        self
+    }
+}
+
+#[derive(Debug)]
+struct UpgradeCanaryOutputHandler {
+    pool: ConnectionPool<Core>,
+    protocol_version: ProtocolVersionId,
+    report_sender: Option<mpsc::UnboundedSender<UpgradeCanaryReport>>,
+}
+
+#[async_trait]
+impl StateKeeperOutputHandler for UpgradeCanaryOutputHandler {
+    async fn handle_l2_block(&mut self, _updates_manager: &UpdatesManager) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_l1_batch(
+        &mut self,
+        updates_manager: Arc<UpdatesManager>,
+    ) -> anyhow::Result<()> {
+        let divergences = check_batch(&self.pool, UpgradeCanaryIo::NAME, &updates_manager).await?;
+        let protocol_version = self.protocol_version;
+        if divergences.is_empty() {
+            tracing::info!(
+                l1_batch_number = %divergences.l1_batch_number,
+                ?protocol_version,
+                "Execution of L1 batch under the next protocol version matches the actual execution"
+            );
+        }
+        for divergence in &divergences.divergences {
+            METRICS.upgrade_canary_divergences[&divergence.kind()].inc();
+            tracing::warn!(
+                l1_batch_number = %divergences.l1_batch_number,
+                ?protocol_version,
+                kind = divergence.kind(),
+                ?divergence,
+                "Execution of L1 batch under the next protocol version diverges from the actual execution"
+            );
+        }
+        if let Some(sender) = &self.report_sender {
+            // The receiver may be dropped if the reports are no longer of interest.
+            sender
+                .send(UpgradeCanaryReport {
+                    protocol_version,
+                    divergences,
+                })
+                .ok();
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct UpgradeCanaryOutputHandlerFactory {
+    pool: ConnectionPool<Core>,
+    report_sender: Option<mpsc::UnboundedSender<UpgradeCanaryReport>>,
+}
+
+#[async_trait]
+impl OutputHandlerFactory for UpgradeCanaryOutputHandlerFactory {
+    async fn create_handler(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Box<dyn StateKeeperOutputHandler>> {
+        let mut conn = self.pool.connection_tagged(UpgradeCanaryIo::NAME).await?;
+        let protocol_version = selected_batch_version(&mut conn, l1_batch_number).await?;
+        drop(conn);
+        Ok(Box::new(UpgradeCanaryOutputHandler {
+            pool: self.pool.clone(),
+            protocol_version,
+            report_sender: self.report_sender.clone(),
+        }))
+    }
+}
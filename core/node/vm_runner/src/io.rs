@@ -1,8 +1,21 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, time::Duration};
 
 use async_trait::async_trait;
-use zksync_dal::{Connection, Core};
-use zksync_types::L1BatchNumber;
+use zksync_dal::{Connection, Core, CoreDal};
+use zksync_types::{L1BatchNumber, L2BlockNumber};
+
+/// Interval to poll IO in [`VmRunnerIo::wait_for_ready_batches()`] by default.
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Progress of a VM runner within a partially processed L1 batch, as persisted by [`VmRunnerIo`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct L2BlockCheckpoint {
+    /// Last L2 block in the batch that has been executed and handled by the output handler.
+    pub l2_block_number: L2BlockNumber,
+    /// Whether the output handler has finished handling the entire batch (i.e., `handle_l1_batch()` has succeeded).
+    /// Such batches are not executed again.
+    pub is_l1_batch_handled: bool,
+}
 
 /// Functionality to fetch/save data about processed/unprocessed batches for a particular VM runner
 /// instance.
@@ -31,6 +44,34 @@ pub trait VmRunnerIo: Debug + Send + Sync + 'static {
         conn: &mut Connection<'_, Core>,
     ) -> anyhow::Result<L1BatchNumber>;
 
+    /// Waits until new batches may be ready to be loaded, i.e., until [`Self::last_ready_to_be_loaded_batch()`]
+    /// may return a greater value. Spurious wake-ups are allowed.
+    ///
+    /// The default implementation sleeps for a short fixed interval, i.e., the IO is polled. See
+    /// [`NotifiedIo`](crate::NotifiedIo) for an implementation reacting to sealed L1 batches instead.
+    async fn wait_for_ready_batches(&self) {
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    /// Checks whether the specified batch should be processed by this VM runner instance. Batches for which
+    /// this method returns `false` are skipped: they are neither executed nor passed to the output handler,
+    /// but are still marked as completed in order, so that the processing can continue past them.
+    ///
+    /// Together with [`Self::latest_processed_batch()`] and [`Self::last_ready_to_be_loaded_batch()`]
+    /// bounding the processed batches, this allows processing an arbitrary set of batches, e.g. re-running
+    /// the VM over a historical range of batches with gaps. By default, all batches are processed.
+    ///
+    /// # Errors
+    ///
+    /// Propagates DB errors.
+    async fn should_process_batch(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<bool> {
+        Ok(true)
+    }
+
     /// Marks the specified batch as the latest completed batch. All earlier batches are considered
     /// to be completed too. No guarantees about later batches.
     ///
@@ -42,4 +83,79 @@ pub trait VmRunnerIo: Debug + Send + Sync + 'static {
         conn: &mut Connection<'_, Core>,
         l1_batch_number: L1BatchNumber,
     ) -> anyhow::Result<()>;
+
+    /// Returns the checkpoint within the specified batch, or `None` if the batch wasn't partially processed before.
+    ///
+    /// # Errors
+    ///
+    /// Propagates DB errors.
+    async fn load_l2_block_checkpoint(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<L2BlockCheckpoint>> {
+        let checkpoint = conn
+            .vm_runner_dal()
+            .get_l2_block_checkpoint(self.name(), l1_batch_number)
+            .await?;
+        Ok(
+            checkpoint.map(|(l2_block_number, is_l1_batch_handled)| L2BlockCheckpoint {
+                l2_block_number,
+                is_l1_batch_handled,
+            }),
+        )
+    }
+
+    /// Records the last L2 block within the specified batch that has been executed and handled
+    /// by the output handler.
+    ///
+    /// # Errors
+    ///
+    /// Propagates DB errors.
+    async fn save_l2_block_checkpoint(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+        l2_block_number: L2BlockNumber,
+    ) -> anyhow::Result<()> {
+        Ok(conn
+            .vm_runner_dal()
+            .save_l2_block_checkpoint(self.name(), l1_batch_number, l2_block_number)
+            .await?)
+    }
+
+    /// Records that the output handler has finished handling the specified batch, so that the batch is not executed
+    /// again if it's re-processed after a restart. `last_l2_block_number` is the last (fictive) L2 block in the batch.
+    ///
+    /// # Errors
+    ///
+    /// Propagates DB errors.
+    async fn mark_l1_batch_as_handled(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+        last_l2_block_number: L2BlockNumber,
+    ) -> anyhow::Result<()> {
+        Ok(conn
+            .vm_runner_dal()
+            .mark_l1_batch_as_handled(self.name(), l1_batch_number, last_l2_block_number)
+            .await?)
+    }
+
+    /// Removes L2 block checkpoints for all batches up to and including the specified one. Called
+    /// once the batch is marked as completed.
+    ///
+    /// # Errors
+    ///
+    /// Propagates DB errors.
+    async fn remove_l2_block_checkpoints(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        Ok(conn
+            .vm_runner_dal()
+            .remove_l2_block_checkpoints(self.name(), l1_batch_number)
+            .await?)
+    }
 }
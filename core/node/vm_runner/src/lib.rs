@@ -3,19 +3,38 @@
 
 #![warn(missing_debug_implementations, missing_docs)]
 
+mod backoff;
 mod impls;
 mod io;
+mod metrics;
+mod notify;
 mod output_handler;
 mod process;
 mod storage;
+mod tracers;
 
 #[cfg(test)]
 mod tests;
 
-pub use impls::{ProtectiveReadsWriter, ProtectiveReadsWriterTasks};
-pub use io::VmRunnerIo;
+pub use backoff::{BackoffPolicy, ConstantBackoff, ExponentialBackoff, JitteredBackoff};
+pub use impls::{
+    BasicWitnessInputProducer, BasicWitnessInputProducerTasks, CommitmentRecomputationReport,
+    CommitmentRecomputer, CommitmentRecomputerIo, CommitmentRecomputerTasks, Divergence,
+    DivergenceReport, DryRunIo, DryRunVmRunner, DryRunVmRunnerTasks, PresimulationCache,
+    PresimulationIo, PresimulationOutcome, ProtectiveReadsBackfill, ProtectiveReadsBackfillIo,
+    ProtectiveReadsBackfillTasks, ProtectiveReadsWriter, ProtectiveReadsWriterTasks,
+    TransactionOutcome, TransactionPresimulator, UpgradeCanary, UpgradeCanaryIo,
+    UpgradeCanaryReport, UpgradeCanaryTasks,
+};
+pub use io::{L2BlockCheckpoint, VmRunnerIo};
+pub use notify::{NotifiedIo, SealedBatchesListener};
 pub use output_handler::{
-    ConcurrentOutputHandlerFactory, ConcurrentOutputHandlerFactoryTask, OutputHandlerFactory,
+    ConcurrentOutputHandlerFactory, ConcurrentOutputHandlerFactoryTask,
+    ConcurrentOutputHandlerOptions, OutputHandlerFactory,
 };
 pub use process::VmRunner;
-pub use storage::{BatchExecuteData, StorageSyncTask, VmRunnerStorage};
+pub use storage::{
+    BatchExecuteData, InMemoryVmRunnerStorage, SharedVmRunnerIo, SharedVmRunnerStorage,
+    StorageLoader, StorageSyncTask, VmRunnerStorage, VmRunnerStorageBackend,
+};
+pub use tracers::VmRunnerTracers;
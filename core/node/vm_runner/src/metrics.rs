@@ -0,0 +1,74 @@
+//! Metrics for VM runner instances.
+
+use std::time::Duration;
+
+use vise::{Buckets, Counter, EncodeLabelValue, Gauge, Histogram, LabeledFamily, Metrics};
+
+/// Kind of storage used to execute an L1 batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub(crate) enum StorageKind {
+    /// RocksDB cache (a hit).
+    Rocksdb,
+    /// Postgres, used while RocksDB cache is catching up (a miss), or if the storage is Postgres-only.
+    Postgres,
+    /// The requested L1 batch is not available in RocksDB cache yet.
+    Unavailable,
+}
+
+/// Output handler method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub(crate) enum OutputHandlerMethod {
+    HandleL2Block,
+    HandleL1Batch,
+}
+
+/// Metrics shared by all VM runner instances, labeled by the instance name (see [`VmRunnerIo::name()`]).
+///
+/// [`VmRunnerIo::name()`]: crate::VmRunnerIo::name()
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "vm_runner")]
+pub(crate) struct VmRunnerMetrics {
+    /// Latest L1 batch marked as processed by the VM runner instance.
+    #[metrics(labels = ["name"])]
+    pub last_processed_batch: LabeledFamily<&'static str, Gauge<u64>>,
+    /// Number of sealed L1 batches that are not processed by the VM runner instance yet.
+    #[metrics(labels = ["name"])]
+    pub batch_lag: LabeledFamily<&'static str, Gauge<u64>>,
+    /// Number of L1 batches currently executed by the VM runner instance.
+    #[metrics(labels = ["name"])]
+    pub in_flight_batches: LabeledFamily<&'static str, Gauge<usize>>,
+    /// Time spent executing an L1 batch in the VM (excluding output handling).
+    #[metrics(labels = ["name"], buckets = Buckets::LATENCIES)]
+    pub batch_execution_time: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Time spent loading data needed to execute an L1 batch.
+    #[metrics(labels = ["name"], buckets = Buckets::LATENCIES)]
+    pub storage_load_time: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Number of storage accesses by the storage kind. The RocksDB cache hit rate can be computed
+    /// as the ratio of `rocksdb` accesses to all accesses.
+    #[metrics(labels = ["name", "storage"])]
+    pub storage_accesses: LabeledFamily<(&'static str, StorageKind), Counter, 2>,
+    /// Latency of output handler methods.
+    #[metrics(labels = ["name", "method"], buckets = Buckets::LATENCIES)]
+    pub output_handler_latency:
+        LabeledFamily<(&'static str, OutputHandlerMethod), Histogram<Duration>, 2>,
+    /// Number of divergences from the data in Postgres found by the dry-run VM runner, labeled by the divergence kind.
+    #[metrics(labels = ["kind"])]
+    pub divergences: LabeledFamily<&'static str, Counter>,
+    /// Number of divergences from the actual execution results found by the upgrade canary, labeled by the divergence kind.
+    #[metrics(labels = ["kind"])]
+    pub upgrade_canary_divergences: LabeledFamily<&'static str, Counter>,
+    /// Number of mismatches with the data in Postgres found by the commitment recomputer, labeled by the mismatched field.
+    #[metrics(labels = ["field"])]
+    pub commitment_mismatches: LabeledFamily<&'static str, Counter>,
+    /// Number of transactions pre-simulated by the transaction pre-simulator, labeled by the outcome.
+    #[metrics(labels = ["outcome"])]
+    pub presimulated_transactions: LabeledFamily<&'static str, Counter>,
+    /// Number of lookups in the pre-simulation cache performed by the state keeper, labeled by the result.
+    #[metrics(labels = ["result"])]
+    pub presimulation_cache_lookups: LabeledFamily<&'static str, Counter>,
+}
+
+#[vise::register]
+pub(crate) static METRICS: vise::Global<VmRunnerMetrics> = vise::Global::new();
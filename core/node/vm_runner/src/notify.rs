@@ -0,0 +1,265 @@
+//! Event-driven VM runner IO reacting to Postgres notifications.
+
+use std::{future, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use tokio::sync::{watch, Mutex};
+use zksync_dal::{blocks_dal::L1_BATCH_SEALED_CHANNEL, Connection, ConnectionPool, Core, CoreDal};
+use zksync_types::{L1BatchNumber, L2BlockNumber};
+
+use crate::{io::POLL_INTERVAL, L2BlockCheckpoint, VmRunnerIo};
+
+/// Task listening to Postgres notifications about sealed L1 batches (see [`L1_BATCH_SEALED_CHANNEL`])
+/// and broadcasting the latest sealed batch number to subscribed [`NotifiedIo`] instances. A single listener
+/// can be shared by all VM runner instances in the process.
+#[derive(Debug)]
+pub struct SealedBatchesListener {
+    pool: ConnectionPool<Core>,
+    sender: watch::Sender<L1BatchNumber>,
+}
+
+impl SealedBatchesListener {
+    /// Creates a new listener. It uses a dedicated connection from the provided pool once run.
+    pub fn new(pool: ConnectionPool<Core>) -> Self {
+        Self {
+            pool,
+            sender: watch::channel(L1BatchNumber(0)).0,
+        }
+    }
+
+    /// Subscribes to updates of the latest sealed L1 batch number.
+    pub fn subscribe(&self) -> watch::Receiver<L1BatchNumber> {
+        self.sender.subscribe()
+    }
+
+    /// Runs the listener until a stop signal is received.
+    ///
+    /// # Errors
+    ///
+    /// Propagates Postgres errors.
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let mut listener = self.pool.listen(L1_BATCH_SEALED_CHANNEL).await?;
+        // Notifications sent before the listener was created are lost, so we query the latest sealed batch explicitly.
+        let mut conn = self.pool.connection_tagged("vm_runner").await?;
+        let sealed_batch = conn
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .unwrap_or_default();
+        drop(conn);
+        self.update(sealed_batch);
+
+        loop {
+            tokio::select! {
+                _ = stop_receiver.changed() => {
+                    tracing::info!("Stop signal received, sealed batches listener is shutting down");
+                    return Ok(());
+                }
+                payload = listener.recv() => {
+                    let payload = payload?;
+                    let l1_batch_number: u32 = payload.parse().with_context(|| {
+                        format!("invalid payload for sealed L1 batch notification: {payload:?}")
+                    })?;
+                    tracing::debug!("Received notification about sealed L1 batch #{l1_batch_number}");
+                    self.update(L1BatchNumber(l1_batch_number));
+                }
+            }
+        }
+    }
+
+    fn update(&self, sealed_batch: L1BatchNumber) {
+        self.sender.send_if_modified(|latest_sealed_batch| {
+            let is_newer = sealed_batch > *latest_sealed_batch;
+            if is_newer {
+                *latest_sealed_batch = sealed_batch;
+            }
+            is_newer
+        });
+    }
+}
+
+/// [`VmRunnerIo`] wrapper that waits for sealed L1 batches (as reported by [`SealedBatchesListener`]) and for
+/// batches completed by the VM runner instead of polling the wrapped IO. All other methods are delegated
+/// to the wrapped IO.
+///
+/// Waiting is still bounded by a (relatively large) poll interval, so that the IO stays responsive if notifications
+/// are lost (e.g., while the listener reconnects to Postgres) or new batches become ready for other reasons.
+#[derive(Debug)]
+pub struct NotifiedIo<Io> {
+    inner: Io,
+    poll_interval: Duration,
+    /// Never polled; only used to create receivers for clones.
+    sealed_batches_template: watch::Receiver<L1BatchNumber>,
+    sealed_batches: Mutex<watch::Receiver<L1BatchNumber>>,
+    completed_batches_sender: Arc<watch::Sender<L1BatchNumber>>,
+    completed_batches: Mutex<watch::Receiver<L1BatchNumber>>,
+}
+
+impl<Io: Clone> Clone for NotifiedIo<Io> {
+    fn clone(&self) -> Self {
+        // Each clone has its own receivers, so that waking up one clone doesn't affect the others.
+        let completed_batches = self.completed_batches_sender.subscribe();
+        Self {
+            inner: self.inner.clone(),
+            poll_interval: self.poll_interval,
+            sealed_batches_template: self.sealed_batches_template.clone(),
+            sealed_batches: Mutex::new(self.sealed_batches_template.clone()),
+            completed_batches_sender: self.completed_batches_sender.clone(),
+            completed_batches: Mutex::new(completed_batches),
+        }
+    }
+}
+
+impl<Io: VmRunnerIo> NotifiedIo<Io> {
+    /// Default interval to poll the wrapped IO if no notifications are received.
+    pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// Wraps the provided IO. `sealed_batches` should be obtained from [`SealedBatchesListener::subscribe()`].
+    pub fn new(inner: Io, sealed_batches: watch::Receiver<L1BatchNumber>) -> Self {
+        let (completed_batches_sender, completed_batches) = watch::channel(L1BatchNumber(0));
+        Self {
+            inner,
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+            sealed_batches_template: sealed_batches.clone(),
+            sealed_batches: Mutex::new(sealed_batches),
+            completed_batches_sender: Arc::new(completed_batches_sender),
+            completed_batches: Mutex::new(completed_batches),
+        }
+    }
+
+    /// Wraps the provided IO without sealed batch notifications. The returned IO polls the wrapped IO as frequently
+    /// as [`VmRunnerIo`] does by default, but still wakes up immediately once a batch is marked as completed.
+    pub fn polling(inner: Io) -> Self {
+        // The sender is dropped immediately, so the sealed batches receiver never resolves.
+        let sealed_batches = watch::channel(L1BatchNumber(0)).1;
+        Self::new(inner, sealed_batches).with_poll_interval(POLL_INTERVAL)
+    }
+
+    /// Wraps the provided IO, using sealed batch notifications if they are available.
+    pub fn with_sealed_batches(
+        inner: Io,
+        sealed_batches: Option<watch::Receiver<L1BatchNumber>>,
+    ) -> Self {
+        match sealed_batches {
+            Some(sealed_batches) => Self::new(inner, sealed_batches),
+            None => Self::polling(inner),
+        }
+    }
+
+    /// Sets the interval to poll the wrapped IO if no notifications are received.
+    #[must_use]
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Returns a reference to the wrapped IO.
+    pub fn inner(&self) -> &Io {
+        &self.inner
+    }
+}
+
+/// Waits until the watched value changes. If the sender is dropped, never resolves.
+async fn changed<T>(receiver: &mut watch::Receiver<T>) {
+    if receiver.changed().await.is_err() {
+        future::pending::<()>().await;
+    }
+}
+
+#[async_trait]
+impl<Io: VmRunnerIo> VmRunnerIo for NotifiedIo<Io> {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    async fn latest_processed_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        self.inner.latest_processed_batch(conn).await
+    }
+
+    async fn last_ready_to_be_loaded_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        // Mark all notifications received so far as seen *before* querying the wrapped IO, so that
+        // no notifications are missed by `wait_for_ready_batches()`.
+        self.sealed_batches.lock().await.borrow_and_update();
+        self.completed_batches.lock().await.borrow_and_update();
+        self.inner.last_ready_to_be_loaded_batch(conn).await
+    }
+
+    async fn wait_for_ready_batches(&self) {
+        let mut sealed_batches = self.sealed_batches.lock().await;
+        let mut completed_batches = self.completed_batches.lock().await;
+        tokio::select! {
+            () = changed(&mut sealed_batches) => {}
+            () = changed(&mut completed_batches) => {}
+            () = tokio::time::sleep(self.poll_interval) => {}
+        }
+    }
+
+    async fn should_process_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<bool> {
+        self.inner.should_process_batch(conn, l1_batch_number).await
+    }
+
+    async fn mark_l1_batch_as_completed(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        self.inner
+            .mark_l1_batch_as_completed(conn, l1_batch_number)
+            .await?;
+        self.completed_batches_sender.send_replace(l1_batch_number);
+        Ok(())
+    }
+
+    async fn load_l2_block_checkpoint(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<L2BlockCheckpoint>> {
+        self.inner
+            .load_l2_block_checkpoint(conn, l1_batch_number)
+            .await
+    }
+
+    async fn save_l2_block_checkpoint(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+        l2_block_number: L2BlockNumber,
+    ) -> anyhow::Result<()> {
+        self.inner
+            .save_l2_block_checkpoint(conn, l1_batch_number, l2_block_number)
+            .await
+    }
+
+    async fn mark_l1_batch_as_handled(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+        last_l2_block_number: L2BlockNumber,
+    ) -> anyhow::Result<()> {
+        self.inner
+            .mark_l1_batch_as_handled(conn, l1_batch_number, last_l2_block_number)
+            .await
+    }
+
+    async fn remove_l2_block_checkpoints(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        self.inner
+            .remove_l2_block_checkpoints(conn, l1_batch_number)
+            .await
+    }
+}
@@ -1,8 +1,9 @@
 use std::{
+    collections::HashMap,
     fmt::{Debug, Formatter},
     mem,
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use anyhow::Context;
@@ -12,11 +13,15 @@ use tokio::{
     sync::{oneshot, watch},
     task::JoinHandle,
 };
-use zksync_dal::{ConnectionPool, Core};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_state_keeper::{StateKeeperOutputHandler, UpdatesManager};
 use zksync_types::L1BatchNumber;
 
-use crate::VmRunnerIo;
+use crate::{
+    backoff::{BackoffPolicy, ConstantBackoff},
+    metrics::METRICS,
+    VmRunnerIo,
+};
 
 type BatchReceiver = oneshot::Receiver<JoinHandle<anyhow::Result<()>>>;
 
@@ -39,6 +44,54 @@ pub trait OutputHandlerFactory: Debug + Send {
         &mut self,
         l1_batch_number: L1BatchNumber,
     ) -> anyhow::Result<Box<dyn StateKeeperOutputHandler>>;
+
+    /// Signals that the provided L1 batch is skipped by the VM runner (see [`VmRunnerIo::should_process_batch()`]),
+    /// i.e., no handler will be created for it. By default, does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Propagates DB errors.
+    async fn skip_batch(&mut self, _l1_batch_number: L1BatchNumber) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Options for [`ConcurrentOutputHandlerFactoryTask`].
+#[derive(Debug, Clone)]
+pub struct ConcurrentOutputHandlerOptions {
+    /// Policy for delays between polls while the output handler for the next batch is not created yet.
+    /// By default, polls are performed every 50ms.
+    pub backoff: Arc<dyn BackoffPolicy>,
+    /// Default timeout for a single batch to be processed after `handle_l1_batch` is called on its handler.
+    /// If not set (which is the default), batch processing is awaited indefinitely.
+    pub batch_timeout: Option<Duration>,
+    /// Overrides of [`Self::batch_timeout`] for specific L1 batches.
+    pub batch_timeout_overrides: HashMap<L1BatchNumber, Duration>,
+    /// If set, enables the drain mode: once the stop signal is received, the task waits up to this deadline
+    /// for output handlers of already started batches to finish, and marks the finished batches as completed.
+    /// Batches not finished by the deadline are abandoned and will be reprocessed from scratch after a restart.
+    /// If not set (which is the default), in-flight output handlers are abandoned immediately.
+    pub drain_timeout: Option<Duration>,
+}
+
+impl Default for ConcurrentOutputHandlerOptions {
+    fn default() -> Self {
+        Self {
+            backoff: Arc::new(ConstantBackoff(Duration::from_millis(50))),
+            batch_timeout: None,
+            batch_timeout_overrides: HashMap::new(),
+            drain_timeout: None,
+        }
+    }
+}
+
+impl ConcurrentOutputHandlerOptions {
+    fn batch_timeout(&self, l1_batch_number: L1BatchNumber) -> Option<Duration> {
+        self.batch_timeout_overrides
+            .get(&l1_batch_number)
+            .copied()
+            .or(self.batch_timeout)
+    }
 }
 
 /// A delegator factory that requires an underlying factory `F` that does the actual work, however
@@ -67,21 +120,49 @@ impl<Io: VmRunnerIo, F: OutputHandlerFactory> Debug for ConcurrentOutputHandlerF
     }
 }
 
+impl<Io: VmRunnerIo, F: OutputHandlerFactory> ConcurrentOutputHandlerFactory<Io, F> {
+    async fn ensure_batch_can_be_handled(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.pool.connection_tagged(self.io.name()).await?;
+        let latest_processed_batch = self.io.latest_processed_batch(&mut conn).await?;
+        let last_processable_batch = self.io.last_ready_to_be_loaded_batch(&mut conn).await?;
+        drop(conn);
+        anyhow::ensure!(
+            l1_batch_number > latest_processed_batch,
+            "Cannot handle an already processed batch #{} (latest is #{})",
+            l1_batch_number,
+            latest_processed_batch
+        );
+        anyhow::ensure!(
+            l1_batch_number <= last_processable_batch,
+            "Cannot handle batch #{} as it is too far away from latest batch #{} (last processable batch is #{})",
+            l1_batch_number,
+            latest_processed_batch,
+            last_processable_batch
+        );
+        Ok(())
+    }
+}
+
 impl<Io: VmRunnerIo + Clone, F: OutputHandlerFactory> ConcurrentOutputHandlerFactory<Io, F> {
-    /// Creates a new concurrent delegator factory using provided Postgres pool, VM runner IO
-    /// and underlying output handler factory.
+    /// Creates a new concurrent delegator factory using provided Postgres pool, VM runner IO,
+    /// underlying output handler factory and task options.
     ///
     /// Returns a [`ConcurrentOutputHandlerFactoryTask`] which is supposed to be run by the caller.
     pub fn new(
         pool: ConnectionPool<Core>,
         io: Io,
         factory: F,
+        options: ConcurrentOutputHandlerOptions,
     ) -> (Self, ConcurrentOutputHandlerFactoryTask<Io>) {
         let state = Arc::new(DashMap::new());
         let task = ConcurrentOutputHandlerFactoryTask {
             pool: pool.clone(),
             io: io.clone(),
             state: state.clone(),
+            options,
         };
         (
             Self {
@@ -96,41 +177,40 @@ impl<Io: VmRunnerIo + Clone, F: OutputHandlerFactory> ConcurrentOutputHandlerFac
 }
 
 #[async_trait]
-impl<Io: VmRunnerIo, F: OutputHandlerFactory> OutputHandlerFactory
+impl<Io: VmRunnerIo + Clone, F: OutputHandlerFactory> OutputHandlerFactory
     for ConcurrentOutputHandlerFactory<Io, F>
 {
     async fn create_handler(
         &mut self,
         l1_batch_number: L1BatchNumber,
     ) -> anyhow::Result<Box<dyn StateKeeperOutputHandler>> {
-        let mut conn = self.pool.connection_tagged(self.io.name()).await?;
-        let latest_processed_batch = self.io.latest_processed_batch(&mut conn).await?;
-        let last_processable_batch = self.io.last_ready_to_be_loaded_batch(&mut conn).await?;
-        drop(conn);
-        anyhow::ensure!(
-            l1_batch_number > latest_processed_batch,
-            "Cannot handle an already processed batch #{} (latest is #{})",
-            l1_batch_number,
-            latest_processed_batch
-        );
-        anyhow::ensure!(
-            l1_batch_number <= last_processable_batch,
-            "Cannot handle batch #{} as it is too far away from latest batch #{} (last processable batch is #{})",
-            l1_batch_number,
-            latest_processed_batch,
-            last_processable_batch
-        );
-
+        self.ensure_batch_can_be_handled(l1_batch_number).await?;
         let handler = self.factory.create_handler(l1_batch_number).await?;
         let (sender, receiver) = oneshot::channel();
         self.state.insert(l1_batch_number, receiver);
-        Ok(Box::new(AsyncOutputHandler::Running { handler, sender }))
+        Ok(Box::new(AsyncOutputHandler::Running {
+            handler,
+            pool: self.pool.clone(),
+            io: Box::new(self.io.clone()),
+            sender,
+        }))
+    }
+
+    async fn skip_batch(&mut self, l1_batch_number: L1BatchNumber) -> anyhow::Result<()> {
+        self.ensure_batch_can_be_handled(l1_batch_number).await?;
+        // The batch is immediately considered to be processed, so that it's marked as completed in order.
+        let (sender, receiver) = oneshot::channel();
+        sender.send(tokio::task::spawn(async { Ok(()) })).ok();
+        self.state.insert(l1_batch_number, receiver);
+        Ok(())
     }
 }
 
 enum AsyncOutputHandler {
     Running {
         handler: Box<dyn StateKeeperOutputHandler>,
+        pool: ConnectionPool<Core>,
+        io: Box<dyn VmRunnerIo>,
         sender: oneshot::Sender<JoinHandle<anyhow::Result<()>>>,
     },
     Finished,
@@ -169,11 +249,24 @@ impl StateKeeperOutputHandler for AsyncOutputHandler {
         match state {
             AsyncOutputHandler::Running {
                 mut handler,
+                pool,
+                io,
                 sender,
             } => {
+                let l1_batch_number = updates_manager.l1_batch.number;
+                let last_l2_block_number = updates_manager.l2_block.number;
                 sender
                     .send(tokio::task::spawn(async move {
-                        handler.handle_l1_batch(updates_manager).await
+                        handler.handle_l1_batch(updates_manager).await?;
+                        // Allows to skip executing the batch if it's re-processed before being marked as completed
+                        // (e.g., if preceding batches are still being handled when the node restarts).
+                        let mut conn = pool.connection_tagged(io.name()).await?;
+                        io.mark_l1_batch_as_handled(
+                            &mut conn,
+                            l1_batch_number,
+                            last_l2_block_number,
+                        )
+                        .await
                     }))
                     .ok();
                 Ok(())
@@ -191,6 +284,7 @@ pub struct ConcurrentOutputHandlerFactoryTask<Io: VmRunnerIo> {
     pool: ConnectionPool<Core>,
     io: Io,
     state: Arc<DashMap<L1BatchNumber, BatchReceiver>>,
+    options: ConcurrentOutputHandlerOptions,
 }
 
 impl<Io: VmRunnerIo> Debug for ConcurrentOutputHandlerFactoryTask<Io> {
@@ -198,6 +292,7 @@ impl<Io: VmRunnerIo> Debug for ConcurrentOutputHandlerFactoryTask<Io> {
         f.debug_struct("ConcurrentOutputHandlerFactoryTask")
             .field("pool", &self.pool)
             .field("io", &self.io)
+            .field("options", &self.options)
             .finish()
     }
 }
@@ -208,48 +303,174 @@ impl<Io: VmRunnerIo> ConcurrentOutputHandlerFactoryTask<Io> {
         &self.io
     }
 
+    /// Reports the latest processed batch and the lag behind the sealed batch to metrics.
+    async fn report_progress(&self, latest_processed_batch: L1BatchNumber) -> anyhow::Result<()> {
+        let mut conn = self.pool.connection_tagged(self.io.name()).await?;
+        let sealed_batch = conn
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .unwrap_or_default();
+        METRICS.last_processed_batch[&self.io.name()].set(latest_processed_batch.0.into());
+        METRICS.batch_lag[&self.io.name()].set(
+            sealed_batch
+                .0
+                .saturating_sub(latest_processed_batch.0)
+                .into(),
+        );
+        Ok(())
+    }
+
     /// Starts running the task which is supposed to last until the end of the node's lifetime.
     ///
     /// # Errors
     ///
-    /// Propagates DB errors.
+    /// Propagates DB errors. Errors if processing a batch exceeds the configured timeout.
     pub async fn run(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
-        const SLEEP_INTERVAL: Duration = Duration::from_millis(50);
+        const PROGRESS_REPORT_INTERVAL: Duration = Duration::from_secs(5);
 
         let mut conn = self.pool.connection_tagged(self.io.name()).await?;
         let mut latest_processed_batch = self.io.latest_processed_batch(&mut conn).await?;
         drop(conn);
+        self.report_progress(latest_processed_batch).await?;
+        let mut last_progress_report = Instant::now();
+        let mut poll_attempt = 0_u32;
         loop {
             if *stop_receiver.borrow() {
                 tracing::info!("`ConcurrentOutputHandlerFactoryTask` was interrupted");
+                if let Some(drain_timeout) = self.options.drain_timeout {
+                    self.drain(latest_processed_batch, drain_timeout).await?;
+                }
                 return Ok(());
             }
+            // Report progress periodically, so that the lag is updated even if no batches are processed.
+            if last_progress_report.elapsed() >= PROGRESS_REPORT_INTERVAL {
+                self.report_progress(latest_processed_batch).await?;
+                last_progress_report = Instant::now();
+            }
             match self.state.remove(&(latest_processed_batch + 1)) {
                 None => {
                     tracing::debug!(
                         "Output handler for batch #{} has not been created yet",
                         latest_processed_batch + 1
                     );
-                    tokio::time::sleep(SLEEP_INTERVAL).await;
+                    tokio::time::sleep(self.options.backoff.delay(poll_attempt)).await;
+                    poll_attempt = poll_attempt.saturating_add(1);
                 }
                 Some((_, receiver)) => {
+                    poll_attempt = 0;
+                    let l1_batch_number = latest_processed_batch + 1;
                     // Wait until the `JoinHandle` is sent through the receiver, happens when
                     // `handle_l1_batch` is called on the corresponding output handler
-                    let handle = receiver
-                        .await
-                        .context("handler was dropped before the batch was fully processed")?;
+                    let handle = match receiver.await {
+                        Ok(handle) => handle,
+                        Err(_) if *stop_receiver.borrow() => {
+                            // The VM runner was stopped mid-batch; the batch will be reprocessed after a restart.
+                            tracing::info!(
+                                "Output handler for batch #{l1_batch_number} was dropped after the stop signal"
+                            );
+                            return Ok(());
+                        }
+                        Err(_) => anyhow::bail!(
+                            "handler was dropped before the batch was fully processed"
+                        ),
+                    };
                     // Wait until the handle is resolved, meaning that the `handle_l1_batch`
                     // computation has finished, and we can consider this batch to be completed
-                    handle
-                        .await
-                        .context("failed to await for batch to be processed")??;
+                    Self::await_batch(
+                        handle,
+                        l1_batch_number,
+                        self.options.batch_timeout(l1_batch_number),
+                    )
+                    .await?;
                     latest_processed_batch += 1;
-                    let mut conn = self.pool.connection_tagged(self.io.name()).await?;
-                    self.io
-                        .mark_l1_batch_as_completed(&mut conn, latest_processed_batch)
-                        .await?;
+                    self.complete_batch(latest_processed_batch).await?;
                 }
             }
         }
     }
+
+    async fn complete_batch(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<()> {
+        let mut conn = self.pool.connection_tagged(self.io.name()).await?;
+        // Checkpoints are removed first, so that they are not observable for completed batches.
+        // If the node restarts before the batch is marked as completed, the batch is simply
+        // reprocessed from scratch.
+        self.io
+            .remove_l2_block_checkpoints(&mut conn, l1_batch_number)
+            .await?;
+        self.io
+            .mark_l1_batch_as_completed(&mut conn, l1_batch_number)
+            .await?;
+        drop(conn);
+        METRICS.last_processed_batch[&self.io.name()].set(l1_batch_number.0.into());
+        Ok(())
+    }
+
+    /// Waits for output handlers of already started batches to finish and marks them as completed in order.
+    /// Stops on the first batch without a handler or with a handler dropped before `handle_l1_batch` is called
+    /// (e.g., because the VM runner was stopped mid-batch), or once `timeout` elapses.
+    async fn drain(
+        &self,
+        latest_processed_batch: L1BatchNumber,
+        timeout: Duration,
+    ) -> anyhow::Result<()> {
+        let mut drained_batch = latest_processed_batch;
+        let drain = async {
+            while let Some((_, receiver)) = self.state.remove(&(drained_batch + 1)) {
+                let l1_batch_number = drained_batch + 1;
+                let Ok(handle) = receiver.await else {
+                    tracing::info!(
+                        "Output handler for batch #{l1_batch_number} was dropped before the batch was fully processed"
+                    );
+                    break;
+                };
+                Self::await_batch(
+                    handle,
+                    l1_batch_number,
+                    self.options.batch_timeout(l1_batch_number),
+                )
+                .await?;
+                self.complete_batch(l1_batch_number).await?;
+                drained_batch = l1_batch_number;
+            }
+            anyhow::Ok(())
+        };
+
+        tracing::info!(
+            "Draining output handlers for batches after #{latest_processed_batch} with {timeout:?} deadline"
+        );
+        match tokio::time::timeout(timeout, drain).await {
+            Ok(result) => {
+                result?;
+                tracing::info!("Drained output handlers up to batch #{drained_batch}");
+            }
+            Err(_) => {
+                tracing::warn!(
+                    "Draining output handlers timed out after {timeout:?}; handlers for batches after #{drained_batch} are abandoned"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn await_batch(
+        mut handle: JoinHandle<anyhow::Result<()>>,
+        l1_batch_number: L1BatchNumber,
+        timeout: Option<Duration>,
+    ) -> anyhow::Result<()> {
+        let result = if let Some(timeout) = timeout {
+            match tokio::time::timeout(timeout, &mut handle).await {
+                Ok(result) => result,
+                Err(_) => {
+                    handle.abort();
+                    anyhow::bail!(
+                        "processing L1 batch #{l1_batch_number} timed out after {timeout:?}"
+                    );
+                }
+            }
+        } else {
+            handle.await
+        };
+        result.context("failed to await for batch to be processed")?
+    }
 }
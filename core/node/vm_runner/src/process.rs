@@ -1,4 +1,7 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context;
 use multivm::interface::L2BlockEnv;
@@ -8,9 +11,13 @@ use zksync_state_keeper::{
     BatchExecutor, BatchExecutorHandle, ExecutionMetricsForCriteria, L2BlockParams,
     StateKeeperOutputHandler, TxExecutionResult, UpdatesManager,
 };
-use zksync_types::{block::L2BlockExecutionData, L1BatchNumber};
+use zksync_types::{block::L2BlockExecutionData, L1BatchNumber, L2BlockNumber};
 
-use crate::{storage::StorageLoader, OutputHandlerFactory, VmRunnerIo};
+use crate::{
+    metrics::{OutputHandlerMethod, METRICS},
+    storage::StorageLoader,
+    OutputHandlerFactory, VmRunnerIo, VmRunnerTracers,
+};
 
 /// VM runner represents a logic layer of L1 batch / L2 block processing flow akin to that of state
 /// keeper. The difference is that VM runner is designed to be run on batches/blocks that have
@@ -23,45 +30,116 @@ use crate::{storage::StorageLoader, OutputHandlerFactory, VmRunnerIo};
 ///
 /// You can think of VM runner as a concurrent processor of a continuous stream of newly committed
 /// batches/blocks.
+///
+/// Progress within a batch is checkpointed via [`VmRunnerIo`] periodically (every several handled L2 blocks),
+/// and once the output handler has finished handling the entire batch. If a batch is re-processed
+/// after a restart, fully handled batches are not executed at all. For partially handled batches, L2 blocks up to
+/// the checkpoint are still executed (the VM cannot start mid-batch), but they are not passed to the output handler
+/// again. Since checkpoints are only saved periodically, output handlers must be idempotent w.r.t. handling L2 blocks.
+///
+/// Batches can be skipped via [`VmRunnerIo::should_process_batch()`].
+///
+/// Custom VM tracers can be attached to batch executions via [`Self::with_tracers()`].
+///
+/// Up to `window_size` batches are executed concurrently, each on its own storage view provided by
+/// [`StorageLoader`]. Results of the execution are still committed in order by the output handlers
+/// (see [`ConcurrentOutputHandlerFactory`](crate::ConcurrentOutputHandlerFactory)).
 #[derive(Debug)]
 pub struct VmRunner {
     pool: ConnectionPool<Core>,
-    io: Box<dyn VmRunnerIo>,
+    io: Arc<dyn VmRunnerIo>,
     loader: Arc<dyn StorageLoader>,
     output_handler_factory: Box<dyn OutputHandlerFactory>,
     batch_processor: Box<dyn BatchExecutor>,
+    tracers: Option<Arc<dyn VmRunnerTracers>>,
+    window_size: usize,
+    retain_sealed_factory_deps: bool,
 }
 
 impl VmRunner {
+    /// Number of handled L2 blocks after which a checkpoint is saved.
+    const L2_BLOCKS_PER_CHECKPOINT: usize = 10;
+
     /// Initializes VM runner with its constituents. In order to make VM runner concurrent each
     /// parameter here needs to support concurrent execution mode. See
     /// [`ConcurrentOutputHandlerFactory`], [`VmRunnerStorage`].
     ///
     /// Caller is expected to provide a component-specific implementation of [`VmRunnerIo`] and
     /// an underlying implementation of [`OutputHandlerFactory`].
+    ///
+    /// `window_size` is the maximum number of L1 batches executed concurrently.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window_size` is zero.
     pub fn new(
         pool: ConnectionPool<Core>,
         io: Box<dyn VmRunnerIo>,
         loader: Arc<dyn StorageLoader>,
         output_handler_factory: Box<dyn OutputHandlerFactory>,
         batch_processor: Box<dyn BatchExecutor>,
+        window_size: u32,
     ) -> Self {
+        assert!(window_size > 0, "VM runner window size must be positive");
         Self {
             pool,
-            io,
+            io: io.into(),
             loader,
             output_handler_factory,
             batch_processor,
+            tracers: None,
+            window_size: window_size as usize,
+            retain_sealed_factory_deps: true,
         }
     }
 
+    /// Makes this runner discard factory deps of transactions in sealed L2 blocks of a batch being processed
+    /// (see [`UpdatesManager::without_sealed_factory_deps()`]). This bounds memory usage for output handlers
+    /// that process bytecodes on an L2 block basis.
+    #[must_use]
+    pub fn without_sealed_factory_deps(mut self) -> Self {
+        self.retain_sealed_factory_deps = false;
+        self
+    }
+
+    /// Attaches custom VM tracers to all transactions in executed batches. Tracer outputs are passed to output handlers
+    /// together with the finished batch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the batch executor doesn't support custom tracers.
+    pub fn with_tracers(mut self, tracers: Arc<dyn VmRunnerTracers>) -> anyhow::Result<Self> {
+        self.batch_processor
+            .set_tracer_factory(tracers.clone().upcast())
+            .context("batch executor doesn't support custom tracers")?;
+        self.tracers = Some(tracers);
+        Ok(self)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     async fn process_batch(
+        pool: ConnectionPool<Core>,
+        io: Arc<dyn VmRunnerIo>,
+        tracers: Option<Arc<dyn VmRunnerTracers>>,
+        l1_batch_number: L1BatchNumber,
+        checkpoint: Option<L2BlockNumber>,
         mut batch_executor: BatchExecutorHandle,
         l2_blocks: Vec<L2BlockExecutionData>,
         mut updates_manager: UpdatesManager,
         mut output_handler: Box<dyn StateKeeperOutputHandler>,
     ) -> anyhow::Result<()> {
+        if let Some(checkpoint) = checkpoint {
+            tracing::info!(
+                "Resuming processing L1 batch #{l1_batch_number} after L2 block #{checkpoint}"
+            );
+        }
+
+        // Execution time is accumulated separately from output handling.
+        let mut execution_time = Duration::ZERO;
+        let mut blocks_since_checkpoint = 0;
         for (i, l2_block) in l2_blocks.into_iter().enumerate() {
+            let l2_block_number = l2_block.number;
+            let started_at = Instant::now();
             if i > 0 {
                 // First L2 block in every batch is already preloaded
                 updates_manager.push_l2_block(L2BlockParams {
@@ -104,20 +182,48 @@ impl VmRunner {
                     call_tracer_result,
                 );
             }
+            execution_time += started_at.elapsed();
+            if checkpoint.is_some_and(|checkpoint| l2_block_number <= checkpoint) {
+                // The block was handled before the restart.
+                continue;
+            }
+            let latency = METRICS.output_handler_latency
+                [&(io.name(), OutputHandlerMethod::HandleL2Block)]
+                .start();
             output_handler
                 .handle_l2_block(&updates_manager)
                 .await
                 .context("VM runner failed to handle L2 block")?;
+            latency.observe();
+
+            blocks_since_checkpoint += 1;
+            if blocks_since_checkpoint >= Self::L2_BLOCKS_PER_CHECKPOINT {
+                let mut conn = pool.connection_tagged(io.name()).await?;
+                io.save_l2_block_checkpoint(&mut conn, l1_batch_number, l2_block_number)
+                    .await?;
+                blocks_since_checkpoint = 0;
+            }
         }
+        let started_at = Instant::now();
         let finished_batch = batch_executor
             .finish_batch()
             .await
             .context("failed finishing L1 batch in executor")?;
+        execution_time += started_at.elapsed();
+        METRICS.batch_execution_time[&io.name()].observe(execution_time);
+
         updates_manager.finish_batch(finished_batch);
+        if let Some(output) = tracers.and_then(|tracers| tracers.take_output(l1_batch_number)) {
+            updates_manager.set_tracer_output(output);
+        }
+        let latency = METRICS.output_handler_latency
+            [&(io.name(), OutputHandlerMethod::HandleL1Batch)]
+            .start();
         output_handler
             .handle_l1_batch(Arc::new(updates_manager))
             .await
             .context("VM runner failed to handle L1 batch")?;
+        latency.observe();
         Ok(())
     }
 
@@ -148,6 +254,12 @@ impl VmRunner {
                 }
             }
             task_handles = retained_handles;
+            METRICS.in_flight_batches[&self.io.name()].set(task_handles.len());
+            if task_handles.len() >= self.window_size {
+                // All slots in the execution window are taken
+                tokio::time::sleep(SLEEP_INTERVAL).await;
+                continue;
+            }
 
             let last_ready_batch = self
                 .io
@@ -155,16 +267,44 @@ impl VmRunner {
                 .await?;
             if next_batch > last_ready_batch {
                 // Next batch is not ready to be processed yet
-                tokio::time::sleep(SLEEP_INTERVAL).await;
+                self.io.wait_for_ready_batches().await;
                 continue;
             }
+            let should_process = self
+                .io
+                .should_process_batch(&mut self.pool.connection().await?, next_batch)
+                .await?;
+            if !should_process {
+                // Components may skip most batches (e.g., the upgrade canary), so this is not logged at the info level.
+                tracing::debug!("Skipping L1 batch #{next_batch}");
+                self.output_handler_factory.skip_batch(next_batch).await?;
+                next_batch += 1;
+                continue;
+            }
+            let checkpoint = self
+                .io
+                .load_l2_block_checkpoint(&mut self.pool.connection().await?, next_batch)
+                .await?;
+            if checkpoint.is_some_and(|checkpoint| checkpoint.is_l1_batch_handled) {
+                tracing::info!(
+                    "L1 batch #{next_batch} was fully handled before the restart; skipping its execution"
+                );
+                self.output_handler_factory.skip_batch(next_batch).await?;
+                next_batch += 1;
+                continue;
+            }
+            let started_at = Instant::now();
             let Some(batch_data) = self.loader.load_batch(next_batch).await? else {
                 // Next batch has not been loaded yet
                 tokio::time::sleep(SLEEP_INTERVAL).await;
                 continue;
             };
-            let updates_manager =
+            METRICS.storage_load_time[&self.io.name()].observe(started_at.elapsed());
+            let mut updates_manager =
                 UpdatesManager::new(&batch_data.l1_batch_env, &batch_data.system_env);
+            if !self.retain_sealed_factory_deps {
+                updates_manager = updates_manager.without_sealed_factory_deps();
+            }
             let Some(batch_executor) = self
                 .batch_processor
                 .init_batch(
@@ -184,6 +324,11 @@ impl VmRunner {
                 .await?;
 
             let handle = tokio::task::spawn(Self::process_batch(
+                self.pool.clone(),
+                self.io.clone(),
+                self.tracers.clone(),
+                next_batch,
+                checkpoint.map(|checkpoint| checkpoint.l2_block_number),
                 batch_executor,
                 batch_data.l2_blocks,
                 updates_manager,
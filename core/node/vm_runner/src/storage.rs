@@ -13,13 +13,17 @@ use tokio::sync::{watch, RwLock};
 use vm_utils::storage::L1BatchParamsProvider;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_state::{
-    AsyncCatchupTask, BatchDiff, PgOrRocksdbStorage, ReadStorageFactory, RocksdbStorage,
-    RocksdbStorageBuilder, RocksdbStorageOptions, RocksdbWithMemory, StateKeeperColumnFamily,
+    AsyncCatchupTask, BatchDiff, InMemoryStorage, PgOrRocksdbStorage, ReadStorageFactory,
+    RocksdbStorage, RocksdbStorageBuilder, RocksdbStorageOptions, RocksdbWithMemory,
+    StateKeeperColumnFamily,
 };
 use zksync_storage::RocksDB;
-use zksync_types::{block::L2BlockExecutionData, L1BatchNumber, L2ChainId};
+use zksync_types::{block::L2BlockExecutionData, L1BatchNumber, L2ChainId, H256};
 
-use crate::VmRunnerIo;
+use crate::{
+    metrics::{StorageKind, METRICS},
+    VmRunnerIo,
+};
 
 #[async_trait]
 pub trait StorageLoader: ReadStorageFactory {
@@ -56,6 +60,17 @@ pub struct BatchExecuteData {
     pub l2_blocks: Vec<L2BlockExecutionData>,
 }
 
+/// Storage backend for [`VmRunnerStorage`] selected by VM runner components.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VmRunnerStorageBackend {
+    /// RocksDB cache at the specified path, caught up with Postgres by [`StorageSyncTask`].
+    /// Recommended for long-running components.
+    Rocksdb(String),
+    /// All data is read from Postgres, so no disk needs to be provisioned. Storage access is slower,
+    /// so this backend is best suited for short-lived runs, e.g., in CI or when re-running a few batches.
+    PostgresOnly,
+}
+
 #[derive(Debug, Clone)]
 struct BatchData {
     execute_data: BatchExecuteData,
@@ -71,10 +86,14 @@ struct BatchData {
 ///
 /// Users of `VmRunnerStorage` are not supposed to retain storage access to batches that are less
 /// than `L::latest_processed_batch`. Holding one is considered to be an undefined behavior.
+///
+/// Storage created with [`Self::postgres_only()`] (or with [`VmRunnerStorageBackend::PostgresOnly`]) never
+/// initializes RocksDB and serves all data from Postgres. See also [`InMemoryVmRunnerStorage`] for a storage
+/// not requiring Postgres.
 #[derive(Debug)]
 pub struct VmRunnerStorage<Io: VmRunnerIo> {
     pool: ConnectionPool<Core>,
-    l1_batch_params_provider: L1BatchParamsProvider,
+    l1_batch_params_provider: Arc<L1BatchParamsProvider>,
     chain_id: L2ChainId,
     state: Arc<RwLock<State>>,
     io: Io,
@@ -96,16 +115,20 @@ impl State {
 
 impl<Io: VmRunnerIo + Clone> VmRunnerStorage<Io> {
     /// Creates a new VM runner storage using provided Postgres pool and RocksDB path.
+    /// `evm_emulator_hash` is the chain-wide EVM emulator hash (if any) included into base system contracts
+    /// of loaded batches.
     pub async fn new(
         pool: ConnectionPool<Core>,
         rocksdb_path: String,
         io: Io,
         chain_id: L2ChainId,
+        evm_emulator_hash: Option<H256>,
     ) -> anyhow::Result<(Self, StorageSyncTask<Io>)> {
         let mut conn = pool.connection_tagged(io.name()).await?;
         let l1_batch_params_provider = L1BatchParamsProvider::new(&mut conn)
             .await
-            .context("Failed initializing L1 batch params provider")?;
+            .context("Failed initializing L1 batch params provider")?
+            .with_evm_emulator_hash(evm_emulator_hash);
         drop(conn);
         let state = Arc::new(RwLock::new(State {
             rocksdb: None,
@@ -115,6 +138,7 @@ impl<Io: VmRunnerIo + Clone> VmRunnerStorage<Io> {
         let task = StorageSyncTask::new(
             pool.clone(),
             chain_id,
+            evm_emulator_hash,
             rocksdb_path,
             io.clone(),
             state.clone(),
@@ -123,7 +147,7 @@ impl<Io: VmRunnerIo + Clone> VmRunnerStorage<Io> {
         Ok((
             Self {
                 pool,
-                l1_batch_params_provider,
+                l1_batch_params_provider: Arc::new(l1_batch_params_provider),
                 chain_id,
                 state,
                 io,
@@ -131,6 +155,267 @@ impl<Io: VmRunnerIo + Clone> VmRunnerStorage<Io> {
             task,
         ))
     }
+
+    /// Creates a new VM runner storage with the specified backend. For [`VmRunnerStorageBackend::PostgresOnly`],
+    /// the returned sync task does nothing and returns immediately.
+    pub async fn with_backend(
+        pool: ConnectionPool<Core>,
+        backend: VmRunnerStorageBackend,
+        io: Io,
+        chain_id: L2ChainId,
+        evm_emulator_hash: Option<H256>,
+    ) -> anyhow::Result<(Self, StorageSyncTask<Io>)> {
+        match backend {
+            VmRunnerStorageBackend::Rocksdb(rocksdb_path) => {
+                Self::new(pool, rocksdb_path, io, chain_id, evm_emulator_hash).await
+            }
+            VmRunnerStorageBackend::PostgresOnly => {
+                let storage =
+                    Self::postgres_only(pool.clone(), io.clone(), chain_id, evm_emulator_hash)
+                        .await?;
+                let task = StorageSyncTask::postgres_only(
+                    pool,
+                    chain_id,
+                    evm_emulator_hash,
+                    io,
+                    storage.state.clone(),
+                )
+                .await?;
+                Ok((storage, task))
+            }
+        }
+    }
+
+    /// Creates a new VM runner storage that reads all data from Postgres. Unlike [`Self::new()`], this doesn't
+    /// require provisioning disk for a RocksDB cache or running a [`StorageSyncTask`], at the cost of slower
+    /// storage access during batch execution. Useful for short-lived runs, e.g., in CI or when re-running
+    /// a few batches.
+    pub async fn postgres_only(
+        pool: ConnectionPool<Core>,
+        io: Io,
+        chain_id: L2ChainId,
+        evm_emulator_hash: Option<H256>,
+    ) -> anyhow::Result<Self> {
+        let mut conn = pool.connection_tagged(io.name()).await?;
+        let l1_batch_params_provider = L1BatchParamsProvider::new(&mut conn)
+            .await
+            .context("Failed initializing L1 batch params provider")?
+            .with_evm_emulator_hash(evm_emulator_hash);
+        drop(conn);
+        // Since RocksDB is never initialized, the storage always falls back to Postgres.
+        let state = Arc::new(RwLock::new(State {
+            rocksdb: None,
+            l1_batch_number: L1BatchNumber(0),
+            storage: BTreeMap::new(),
+        }));
+        Ok(Self {
+            pool,
+            l1_batch_params_provider: Arc::new(l1_batch_params_provider),
+            chain_id,
+            state,
+            io,
+        })
+    }
+}
+
+/// Fully in-memory VM runner storage, mostly useful for tests. Batches to execute are added together with
+/// the storage state at the start of each batch using [`Self::insert_batch()`].
+#[derive(Debug, Default)]
+pub struct InMemoryVmRunnerStorage {
+    batches: std::sync::RwLock<BTreeMap<L1BatchNumber, (BatchExecuteData, Arc<InMemoryStorage>)>>,
+}
+
+impl InMemoryVmRunnerStorage {
+    /// Adds an L1 batch together with the storage state at the start of the batch. If the batch is already present,
+    /// it is overwritten.
+    pub fn insert_batch(&self, execute_data: BatchExecuteData, storage: InMemoryStorage) {
+        let l1_batch_number = execute_data.l1_batch_env.number;
+        self.batches
+            .write()
+            .expect("in-memory VM runner storage is poisoned")
+            .insert(l1_batch_number, (execute_data, Arc::new(storage)));
+    }
+
+    /// Removes batches with numbers up to and including the specified one.
+    pub fn remove_batches_up_to(&self, l1_batch_number: L1BatchNumber) {
+        self.batches
+            .write()
+            .expect("in-memory VM runner storage is poisoned")
+            .retain(|&number, _| number > l1_batch_number);
+    }
+}
+
+#[async_trait]
+impl StorageLoader for InMemoryVmRunnerStorage {
+    async fn load_batch(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<BatchExecuteData>> {
+        let batches = self
+            .batches
+            .read()
+            .expect("in-memory VM runner storage is poisoned");
+        Ok(batches
+            .get(&l1_batch_number)
+            .map(|(execute_data, _)| execute_data.clone()))
+    }
+
+    fn upcast(self: Arc<Self>) -> Arc<dyn ReadStorageFactory> {
+        self
+    }
+}
+
+#[async_trait]
+impl ReadStorageFactory for InMemoryVmRunnerStorage {
+    async fn access_storage(
+        &self,
+        _stop_receiver: &watch::Receiver<bool>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<PgOrRocksdbStorage<'_>>> {
+        let batches = self
+            .batches
+            .read()
+            .expect("in-memory VM runner storage is poisoned");
+        Ok(batches
+            .get(&l1_batch_number)
+            .map(|(_, storage)| PgOrRocksdbStorage::InMemory(storage.clone())))
+    }
+}
+
+/// [`VmRunnerStorage`] shared among several VM runner instances running in the same process. All instances
+/// share a single RocksDB cache and a single [`StorageSyncTask`] catching it up with Postgres.
+///
+/// The RocksDB cache is kept at the latest batch processed by *all* instances, and data for batches up to
+/// the last batch ready to be loaded by *any* instance is kept in memory. Hence, sharing storage is most efficient
+/// for instances with overlapping batch windows; if one of the instances lags far behind the others,
+/// data for all batches in between is retained in memory.
+///
+/// Instances are added with [`Self::add_io()`], which returns [`VmRunnerStorage`] to pass to the corresponding
+/// [`VmRunner`](crate::VmRunner). Once all instances are added, the sync task is created with [`Self::into_sync_task()`].
+#[derive(Debug)]
+pub struct SharedVmRunnerStorage {
+    pool: ConnectionPool<Core>,
+    l1_batch_params_provider: Arc<L1BatchParamsProvider>,
+    chain_id: L2ChainId,
+    evm_emulator_hash: Option<H256>,
+    state: Arc<RwLock<State>>,
+    io: SharedVmRunnerIo,
+}
+
+impl SharedVmRunnerStorage {
+    /// Creates a new shared storage using provided Postgres pool. VM runner instances using the storage
+    /// should be added with [`Self::add_io()`].
+    pub async fn new(
+        pool: ConnectionPool<Core>,
+        chain_id: L2ChainId,
+        evm_emulator_hash: Option<H256>,
+    ) -> anyhow::Result<Self> {
+        let mut conn = pool.connection_tagged(SharedVmRunnerIo::NAME).await?;
+        let l1_batch_params_provider = L1BatchParamsProvider::new(&mut conn)
+            .await
+            .context("Failed initializing L1 batch params provider")?
+            .with_evm_emulator_hash(evm_emulator_hash);
+        drop(conn);
+        let state = Arc::new(RwLock::new(State {
+            rocksdb: None,
+            l1_batch_number: L1BatchNumber(0),
+            storage: BTreeMap::new(),
+        }));
+        Ok(Self {
+            pool,
+            l1_batch_params_provider: Arc::new(l1_batch_params_provider),
+            chain_id,
+            evm_emulator_hash,
+            state,
+            io: SharedVmRunnerIo { ios: vec![] },
+        })
+    }
+
+    /// Adds a VM runner instance with the specified IO and returns storage for it.
+    pub fn add_io<Io: VmRunnerIo + Clone>(&mut self, io: Io) -> VmRunnerStorage<Io> {
+        self.io.ios.push(Arc::new(io.clone()));
+        VmRunnerStorage {
+            pool: self.pool.clone(),
+            l1_batch_params_provider: self.l1_batch_params_provider.clone(),
+            chain_id: self.chain_id,
+            state: self.state.clone(),
+            io,
+        }
+    }
+
+    /// Creates a task synchronizing the RocksDB cache at the specified path for all added VM runner instances.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no VM runner instances were added. Propagates DB errors.
+    pub async fn into_sync_task(
+        self,
+        rocksdb_path: String,
+    ) -> anyhow::Result<StorageSyncTask<SharedVmRunnerIo>> {
+        anyhow::ensure!(
+            !self.io.ios.is_empty(),
+            "No VM runner instances were added to shared storage"
+        );
+        StorageSyncTask::new(
+            self.pool,
+            self.chain_id,
+            self.evm_emulator_hash,
+            rocksdb_path,
+            self.io,
+            self.state,
+        )
+        .await
+    }
+}
+
+/// [`VmRunnerIo`] combining IOs of all VM runner instances using [`SharedVmRunnerStorage`]. Only used
+/// to synchronize storage; cannot mark batches as completed.
+#[derive(Debug, Clone)]
+pub struct SharedVmRunnerIo {
+    ios: Vec<Arc<dyn VmRunnerIo>>,
+}
+
+impl SharedVmRunnerIo {
+    const NAME: &'static str = "shared_vm_runner_storage";
+}
+
+#[async_trait]
+impl VmRunnerIo for SharedVmRunnerIo {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    async fn latest_processed_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        let mut min_batch = None;
+        for io in &self.ios {
+            let batch = io.latest_processed_batch(conn).await?;
+            min_batch = Some(min_batch.map_or(batch, |min: L1BatchNumber| min.min(batch)));
+        }
+        min_batch.context("no VM runner instances use shared storage")
+    }
+
+    async fn last_ready_to_be_loaded_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        let mut max_batch = None;
+        for io in &self.ios {
+            let batch = io.last_ready_to_be_loaded_batch(conn).await?;
+            max_batch = Some(max_batch.map_or(batch, |max: L1BatchNumber| max.max(batch)));
+        }
+        max_batch.context("no VM runner instances use shared storage")
+    }
+
+    async fn mark_l1_batch_as_completed(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!("L1 batches cannot be marked as completed for shared storage")
+    }
 }
 
 impl<Io: VmRunnerIo> VmRunnerStorage<Io> {
@@ -141,6 +426,7 @@ impl<Io: VmRunnerIo> VmRunnerStorage<Io> {
     ) -> anyhow::Result<Option<PgOrRocksdbStorage<'_>>> {
         let state = self.state.read().await;
         let Some(rocksdb) = &state.rocksdb else {
+            METRICS.storage_accesses[&(self.io.name(), StorageKind::Postgres)].inc();
             return Ok(Some(
                 PgOrRocksdbStorage::access_storage_pg(&self.pool, l1_batch_number)
                     .await
@@ -154,6 +440,7 @@ impl<Io: VmRunnerIo> VmRunnerStorage<Io> {
                 max_l1_batch = %state.storage.last_key_value().map(|(k, _)| *k).unwrap_or(state.l1_batch_number),
                 "Trying to access VM runner storage with L1 batch that is not available",
             );
+            METRICS.storage_accesses[&(self.io.name(), StorageKind::Unavailable)].inc();
             return Ok(None);
         }
         let batch_diffs = state
@@ -167,6 +454,7 @@ impl<Io: VmRunnerIo> VmRunnerStorage<Io> {
                 }
             })
             .collect::<Vec<_>>();
+        METRICS.storage_accesses[&(self.io.name(), StorageKind::Rocksdb)].inc();
         Ok(Some(PgOrRocksdbStorage::RocksdbWithMemory(
             RocksdbWithMemory {
                 rocksdb: rocksdb.clone(),
@@ -228,21 +516,31 @@ impl<Io: VmRunnerIo> ReadStorageFactory for VmRunnerStorage<Io> {
 /// batch and then continuously makes sure that this invariant is held for the foreseeable future.
 /// In the meanwhile, `StorageSyncTask` also loads the next `max_batches_to_load` batches in memory
 /// so that they are immediately accessible by [`VmRunnerStorage`].
+///
+/// For storage with the [`VmRunnerStorageBackend::PostgresOnly`] backend, there is nothing to synchronize,
+/// so the task returns immediately.
 #[derive(Debug)]
 pub struct StorageSyncTask<Io: VmRunnerIo> {
     pool: ConnectionPool<Core>,
     l1_batch_params_provider: L1BatchParamsProvider,
     chain_id: L2ChainId,
-    rocksdb_cell: Arc<OnceCell<RocksDB<StateKeeperColumnFamily>>>,
+    /// `None` for the Postgres-only backend.
+    catchup: Option<RocksdbCatchup>,
     io: Io,
     state: Arc<RwLock<State>>,
-    catchup_task: AsyncCatchupTask,
+}
+
+#[derive(Debug)]
+struct RocksdbCatchup {
+    rocksdb_cell: Arc<OnceCell<RocksDB<StateKeeperColumnFamily>>>,
+    task: AsyncCatchupTask,
 }
 
 impl<Io: VmRunnerIo> StorageSyncTask<Io> {
     async fn new(
         pool: ConnectionPool<Core>,
         chain_id: L2ChainId,
+        evm_emulator_hash: Option<H256>,
         rocksdb_path: String,
         io: Io,
         state: Arc<RwLock<State>>,
@@ -250,7 +548,8 @@ impl<Io: VmRunnerIo> StorageSyncTask<Io> {
         let mut conn = pool.connection_tagged(io.name()).await?;
         let l1_batch_params_provider = L1BatchParamsProvider::new(&mut conn)
             .await
-            .context("Failed initializing L1 batch params provider")?;
+            .context("Failed initializing L1 batch params provider")?
+            .with_evm_emulator_hash(evm_emulator_hash);
         let rocksdb_cell = Arc::new(OnceCell::new());
         let catchup_task = AsyncCatchupTask::new(
             pool.clone(),
@@ -264,10 +563,35 @@ impl<Io: VmRunnerIo> StorageSyncTask<Io> {
             pool,
             l1_batch_params_provider,
             chain_id,
-            rocksdb_cell,
+            catchup: Some(RocksdbCatchup {
+                rocksdb_cell,
+                task: catchup_task,
+            }),
+            io,
+            state,
+        })
+    }
+
+    async fn postgres_only(
+        pool: ConnectionPool<Core>,
+        chain_id: L2ChainId,
+        evm_emulator_hash: Option<H256>,
+        io: Io,
+        state: Arc<RwLock<State>>,
+    ) -> anyhow::Result<Self> {
+        let mut conn = pool.connection_tagged(io.name()).await?;
+        let l1_batch_params_provider = L1BatchParamsProvider::new(&mut conn)
+            .await
+            .context("Failed initializing L1 batch params provider")?
+            .with_evm_emulator_hash(evm_emulator_hash);
+        drop(conn);
+        Ok(Self {
+            pool,
+            l1_batch_params_provider,
+            chain_id,
+            catchup: None,
             io,
             state,
-            catchup_task,
         })
     }
 
@@ -285,8 +609,14 @@ impl<Io: VmRunnerIo> StorageSyncTask<Io> {
     pub async fn run(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         const SLEEP_INTERVAL: Duration = Duration::from_millis(50);
 
-        self.catchup_task.run(stop_receiver.clone()).await?;
-        let rocksdb = self.rocksdb_cell.get().ok_or_else(|| {
+        let Some(catchup) = self.catchup else {
+            tracing::info!(
+                "VM runner storage reads data from Postgres; no synchronization is required"
+            );
+            return Ok(());
+        };
+        catchup.task.run(stop_receiver.clone()).await?;
+        let rocksdb = catchup.rocksdb_cell.get().ok_or_else(|| {
             anyhow::anyhow!("Expected RocksDB to be initialized by `AsyncCatchupTask`")
         })?;
         loop {
@@ -0,0 +1,358 @@
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use multivm::{
+    interface::{
+        CurrentExecutionState, ExecutionResult, FinishedL1Batch, Refunds, VmExecutionResultAndLogs,
+        VmExecutionStatistics,
+    },
+    vm_latest::VmExecutionLogs,
+};
+use tempfile::TempDir;
+use tokio::sync::{watch, RwLock};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
+use zksync_object_store::MockObjectStore;
+use zksync_prover_interface::inputs::{StreamedVmRunWitnessInputData, VmRunWitnessInputChunk};
+use zksync_state_keeper::{L2BlockParams, UpdatesManager};
+use zksync_test_account::Account;
+use zksync_types::{L1BatchNumber, L2ChainId, H256, U256};
+use zksync_utils::{bytes_to_chunks, h256_to_u256};
+
+use crate::{
+    impls::bwip::BasicWitnessInputProducerOutputHandlerFactory,
+    storage::StorageLoader,
+    tests::{fund, store_l1_batches, IoMock},
+    BasicWitnessInputProducer, OutputHandlerFactory, ProtectiveReadsWriter, SealedBatchesListener,
+    SharedVmRunnerStorage, VmRunnerStorage,
+};
+
+async fn prepare_batch(pool: &ConnectionPool<Core>) {
+    let mut conn = pool.connection().await.unwrap();
+    let genesis_params = GenesisParams::mock();
+    insert_genesis_batch(&mut conn, &genesis_params)
+        .await
+        .unwrap();
+    let mut accounts = vec![Account::random(), Account::random()];
+    fund(pool, &accounts).await;
+    store_l1_batches(
+        &mut conn,
+        1..=1,
+        genesis_params.base_system_contracts().hashes(),
+        &mut accounts,
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn producing_basic_witness_inputs() {
+    let rocksdb_dir = TempDir::new().unwrap();
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_batch(&pool).await;
+
+    let object_store = MockObjectStore::arc();
+    let (producer, tasks) = BasicWitnessInputProducer::new(
+        pool.clone(),
+        object_store.clone(),
+        rocksdb_dir.path().to_str().unwrap().to_owned(),
+        L2ChainId::default(),
+        None,
+        L1BatchNumber(0),
+        1,
+        None,
+    )
+    .await
+    .unwrap();
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let loader_task = tokio::spawn(tasks.loader_task.run(stop_receiver.clone()));
+    let output_handler_task =
+        tokio::spawn(tasks.output_handler_factory_task.run(stop_receiver.clone()));
+    let producer_task = tokio::spawn(async move { producer.run(&stop_receiver).await });
+
+    let mut conn = pool.connection().await.unwrap();
+    tokio::time::timeout(Duration::from_secs(30), async {
+        loop {
+            let processed_batch = conn
+                .vm_runner_dal()
+                .get_bwip_latest_processed_batch(L1BatchNumber(0))
+                .await
+                .unwrap();
+            if processed_batch >= L1BatchNumber(1) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("timed out waiting for L1 batch to be processed");
+
+    let time_taken = conn
+        .vm_runner_dal()
+        .get_bwip_time_taken(L1BatchNumber(1))
+        .await
+        .unwrap();
+    assert!(time_taken.is_some());
+
+    let header: StreamedVmRunWitnessInputData = object_store.get(L1BatchNumber(1)).await.unwrap();
+    assert!(header.chunk_count > 0);
+    let witness_input = StreamedVmRunWitnessInputData::load(&*object_store, L1BatchNumber(1))
+        .await
+        .unwrap();
+    assert_eq!(witness_input.l1_batch_number, L1BatchNumber(1));
+    assert!(witness_input
+        .used_bytecodes
+        .contains_key(&header.default_account_code_hash));
+
+    stop_sender.send_replace(true);
+    producer_task.await.unwrap().unwrap();
+    output_handler_task.await.unwrap().unwrap();
+    loader_task.await.unwrap().unwrap();
+}
+
+fn finished_batch(used_contract_hashes: Vec<U256>) -> FinishedL1Batch {
+    FinishedL1Batch {
+        block_tip_execution_result: VmExecutionResultAndLogs {
+            result: ExecutionResult::Success { output: vec![] },
+            logs: VmExecutionLogs::default(),
+            statistics: VmExecutionStatistics::default(),
+            refunds: Refunds::default(),
+        },
+        final_execution_state: CurrentExecutionState {
+            events: vec![],
+            deduplicated_storage_log_queries: vec![],
+            used_contract_hashes,
+            user_l2_to_l1_logs: vec![],
+            system_logs: vec![],
+            total_log_queries: 0,
+            cycles_used: 0,
+            deduplicated_events_logs: vec![],
+            storage_refunds: vec![1, 2],
+            pubdata_costs: vec![3, -4],
+        },
+        final_bootloader_memory: Some(vec![(0, U256::one())]),
+        pubdata_input: Some(vec![]),
+        state_diffs: Some(vec![]),
+    }
+}
+
+#[tokio::test]
+async fn streaming_basic_witness_inputs_in_output_handler() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_batch(&pool).await;
+    let stored_factory_deps = pool
+        .connection()
+        .await
+        .unwrap()
+        .factory_deps_dal()
+        .dump_all_factory_deps_for_tests()
+        .await;
+
+    let io = Arc::new(RwLock::new(IoMock {
+        current: L1BatchNumber(0),
+        max: 1,
+    }));
+    let storage = VmRunnerStorage::postgres_only(pool.clone(), io, L2ChainId::default(), None)
+        .await
+        .unwrap();
+    let batch_data = storage.load_batch(L1BatchNumber(1)).await.unwrap().unwrap();
+    let base_system_contracts = batch_data.system_env.base_system_smart_contracts.hashes();
+    // Sanity check: the batch consists of a regular and a fictive L2 block.
+    assert_eq!(batch_data.l2_blocks.len(), 2);
+
+    let object_store = MockObjectStore::arc();
+    let processing_times = Arc::default();
+    let mut factory = BasicWitnessInputProducerOutputHandlerFactory::new(
+        pool.clone(),
+        object_store.clone(),
+        Arc::clone(&processing_times),
+    );
+    let mut output_handler = factory.create_handler(L1BatchNumber(1)).await.unwrap();
+    let mut updates_manager = UpdatesManager::new(&batch_data.l1_batch_env, &batch_data.system_env);
+    let published_bytecode_hash = H256::repeat_byte(1);
+    let published_bytecode = vec![1_u8; 32];
+    updates_manager
+        .l2_block
+        .new_factory_deps
+        .insert(published_bytecode_hash, published_bytecode.clone());
+    output_handler
+        .handle_l2_block(&updates_manager)
+        .await
+        .unwrap();
+
+    // Bytecodes already streamed must not be persisted again, but a chunk must still be created
+    // for the L2 block.
+    updates_manager.push_l2_block(L2BlockParams {
+        timestamp: batch_data.l2_blocks[1].timestamp,
+        virtual_blocks: 0,
+    });
+    updates_manager
+        .l2_block
+        .new_factory_deps
+        .insert(published_bytecode_hash, published_bytecode.clone());
+    output_handler
+        .handle_l2_block(&updates_manager)
+        .await
+        .unwrap();
+    let chunk: VmRunWitnessInputChunk = object_store.get((L1BatchNumber(1), 1)).await.unwrap();
+    assert!(chunk.used_bytecodes.is_empty(), "{chunk:?}");
+
+    let (&stored_bytecode_hash, stored_bytecode) = stored_factory_deps
+        .iter()
+        .find(|(hash, _)| {
+            **hash != base_system_contracts.bootloader && **hash != base_system_contracts.default_aa
+        })
+        .unwrap();
+    let used_contract_hashes = [
+        base_system_contracts.bootloader,
+        base_system_contracts.default_aa,
+        published_bytecode_hash,
+        stored_bytecode_hash,
+    ];
+    let used_contract_hashes = used_contract_hashes.into_iter().map(h256_to_u256).collect();
+    updates_manager.finish_batch(finished_batch(used_contract_hashes));
+    output_handler
+        .handle_l1_batch(Arc::new(updates_manager))
+        .await
+        .unwrap();
+    assert!(processing_times
+        .lock()
+        .unwrap()
+        .contains_key(&L1BatchNumber(1)));
+
+    let header: StreamedVmRunWitnessInputData = object_store.get(L1BatchNumber(1)).await.unwrap();
+    assert_eq!(header.chunk_count, 3);
+    assert_eq!(header.storage_refunds, [1, 2]);
+    assert_eq!(header.pubdata_costs, [3, -4]);
+    assert_eq!(
+        header.bootloader_code,
+        bytes_to_chunks(&stored_factory_deps[&base_system_contracts.bootloader])
+    );
+    let witness_input = StreamedVmRunWitnessInputData::load(&*object_store, L1BatchNumber(1))
+        .await
+        .unwrap();
+    assert_eq!(witness_input.initial_heap_content, [(0, U256::one())]);
+    let used_bytecode_hashes: HashSet<_> = witness_input.used_bytecodes.keys().copied().collect();
+    let expected_hashes = [
+        base_system_contracts.default_aa,
+        published_bytecode_hash,
+        stored_bytecode_hash,
+    ];
+    let expected_hashes = expected_hashes.into_iter().map(h256_to_u256).collect();
+    assert_eq!(used_bytecode_hashes, expected_hashes);
+    assert_eq!(
+        witness_input.used_bytecodes[&h256_to_u256(published_bytecode_hash)],
+        bytes_to_chunks(&published_bytecode)
+    );
+    assert_eq!(
+        witness_input.used_bytecodes[&h256_to_u256(stored_bytecode_hash)],
+        bytes_to_chunks(stored_bytecode)
+    );
+}
+
+#[tokio::test]
+async fn output_handler_rejects_l2_blocks_from_other_batch() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_batch(&pool).await;
+    let io = Arc::new(RwLock::new(IoMock {
+        current: L1BatchNumber(0),
+        max: 1,
+    }));
+    let storage = VmRunnerStorage::postgres_only(pool.clone(), io, L2ChainId::default(), None)
+        .await
+        .unwrap();
+    let batch_data = storage.load_batch(L1BatchNumber(1)).await.unwrap().unwrap();
+
+    let mut factory = BasicWitnessInputProducerOutputHandlerFactory::new(
+        pool,
+        MockObjectStore::arc(),
+        Arc::default(),
+    );
+    let mut output_handler = factory.create_handler(L1BatchNumber(1)).await.unwrap();
+    let mut updates_manager = UpdatesManager::new(&batch_data.l1_batch_env, &batch_data.system_env);
+    for _ in 0..2 {
+        updates_manager.push_l2_block(L2BlockParams {
+            timestamp: batch_data.l1_batch_env.timestamp,
+            virtual_blocks: 0,
+        });
+    }
+    let err = output_handler
+        .handle_l2_block(&updates_manager)
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("doesn't belong to L1 batch #1"),
+        "{err:#}"
+    );
+}
+
+#[tokio::test]
+async fn co_located_vm_runners_with_shared_storage() {
+    let rocksdb_dir = TempDir::new().unwrap();
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    prepare_batch(&pool).await;
+
+    let listener = SealedBatchesListener::new(pool.clone());
+    let sealed_batches = listener.subscribe();
+    let mut shared_storage = SharedVmRunnerStorage::new(pool.clone(), L2ChainId::default(), None)
+        .await
+        .unwrap();
+    let (producer, producer_output_task) = BasicWitnessInputProducer::with_shared_storage(
+        pool.clone(),
+        MockObjectStore::arc(),
+        &mut shared_storage,
+        L1BatchNumber(0),
+        1,
+        Some(sealed_batches.clone()),
+    );
+    let (writer, writer_output_task) = ProtectiveReadsWriter::with_shared_storage(
+        pool.clone(),
+        &mut shared_storage,
+        L1BatchNumber(0),
+        1,
+        Some(sealed_batches),
+    );
+    let sync_task = shared_storage
+        .into_sync_task(rocksdb_dir.path().to_str().unwrap().to_owned())
+        .await
+        .unwrap();
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let tasks = [
+        tokio::spawn(listener.run(stop_receiver.clone())),
+        tokio::spawn(sync_task.run(stop_receiver.clone())),
+        tokio::spawn(producer_output_task.run(stop_receiver.clone())),
+        tokio::spawn(writer_output_task.run(stop_receiver.clone())),
+        tokio::spawn({
+            let stop_receiver = stop_receiver.clone();
+            async move { producer.run(&stop_receiver).await }
+        }),
+        tokio::spawn(async move { writer.run(&stop_receiver).await }),
+    ];
+
+    let mut conn = pool.connection().await.unwrap();
+    tokio::time::timeout(Duration::from_secs(30), async {
+        loop {
+            let bwip_batch = conn
+                .vm_runner_dal()
+                .get_bwip_latest_processed_batch(L1BatchNumber(0))
+                .await
+                .unwrap();
+            let protective_reads_batch = conn
+                .vm_runner_dal()
+                .get_protective_reads_latest_processed_batch(L1BatchNumber(0))
+                .await
+                .unwrap();
+            if bwip_batch >= L1BatchNumber(1) && protective_reads_batch >= L1BatchNumber(1) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("timed out waiting for L1 batch to be processed by both VM runners");
+
+    stop_sender.send_replace(true);
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use tempfile::TempDir;
+use tokio::sync::{mpsc, watch};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
+use zksync_test_account::Account;
+use zksync_types::{commitment::L1BatchCommitmentMode, L1BatchNumber, L2ChainId, H256};
+
+use crate::{
+    impls::commitment_recomputer::compare_sequences,
+    tests::{fund, store_l1_batches},
+    CommitmentRecomputationReport, CommitmentRecomputer, VmRunnerStorageBackend,
+};
+
+#[test]
+fn comparing_sequences() {
+    let stored = [H256::repeat_byte(1), H256::repeat_byte(2)];
+    assert!(compare_sequences("hashes", &stored, &stored).is_none());
+
+    let recomputed = [H256::repeat_byte(1), H256::repeat_byte(3)];
+    let mismatch = compare_sequences("hashes", &stored, &recomputed).unwrap();
+    assert_eq!(mismatch.field, "hashes");
+    assert_eq!(
+        mismatch.stored,
+        format!("2 entries, #1: {:?}", H256::repeat_byte(2))
+    );
+    assert_eq!(
+        mismatch.recalculated,
+        format!("2 entries, #1: {:?}", H256::repeat_byte(3))
+    );
+
+    let mismatch = compare_sequences("hashes", &stored, &stored[..1]).unwrap();
+    assert_eq!(
+        mismatch.stored,
+        format!("2 entries, #1: {:?}", H256::repeat_byte(2))
+    );
+    assert_eq!(mismatch.recalculated, "1 entries");
+}
+
+async fn recompute_commitments(
+    pool: &ConnectionPool<Core>,
+    last_batch: L1BatchNumber,
+) -> Vec<CommitmentRecomputationReport> {
+    let rocksdb_dir = TempDir::new().unwrap();
+    let (report_sender, mut report_receiver) = mpsc::unbounded_channel();
+    let (recomputer, tasks) = CommitmentRecomputer::new(
+        pool.clone(),
+        VmRunnerStorageBackend::Rocksdb(rocksdb_dir.path().to_str().unwrap().to_owned()),
+        L2ChainId::default(),
+        None,
+        L1BatchCommitmentMode::Rollup,
+        L1BatchNumber(0),
+        last_batch,
+        1,
+        Some(report_sender),
+    )
+    .await
+    .unwrap();
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let loader_task = tokio::spawn(tasks.loader_task.run(stop_receiver.clone()));
+    let output_handler_task =
+        tokio::spawn(tasks.output_handler_factory_task.run(stop_receiver.clone()));
+
+    tokio::time::timeout(Duration::from_secs(30), recomputer.run(&stop_receiver))
+        .await
+        .expect("timed out waiting for commitment recomputation")
+        .unwrap();
+    stop_sender.send_replace(true);
+    output_handler_task.await.unwrap().unwrap();
+    loader_task.await.unwrap().unwrap();
+
+    let mut reports = vec![];
+    while let Ok(report) = report_receiver.try_recv() {
+        reports.push(report);
+    }
+    reports
+}
+
+#[tokio::test]
+async fn recomputing_commitments_with_persisted_progress() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    let genesis_params = GenesisParams::mock();
+    insert_genesis_batch(&mut conn, &genesis_params)
+        .await
+        .unwrap();
+    let mut accounts = vec![Account::random(), Account::random()];
+    fund(&pool, &accounts).await;
+    // Stored batches have mock headers and metadata, which cannot match the recomputed ones.
+    store_l1_batches(
+        &mut conn,
+        1..=3,
+        genesis_params.base_system_contracts().hashes(),
+        &mut accounts,
+    )
+    .await
+    .unwrap();
+
+    let reports = recompute_commitments(&pool, L1BatchNumber(2)).await;
+    let checked_batches: Vec<_> = reports
+        .iter()
+        .map(|report| report.l1_batch_number)
+        .collect();
+    assert_eq!(checked_batches, [L1BatchNumber(1), L1BatchNumber(2)]);
+    for report in &reports {
+        let mismatched_fields: Vec<_> = report.mismatches.iter().map(|m| m.field).collect();
+        // The VM always outputs system logs, and mock headers don't contain any.
+        assert!(mismatched_fields.contains(&"system_logs"), "{report:?}");
+    }
+    let latest_processed_batch = conn
+        .vm_runner_dal()
+        .get_commitment_recomputer_latest_processed_batch(L1BatchNumber(0), L1BatchNumber(3))
+        .await
+        .unwrap();
+    assert_eq!(latest_processed_batch, L1BatchNumber(2));
+
+    // Processing must resume after the persisted progress.
+    let reports = recompute_commitments(&pool, L1BatchNumber(3)).await;
+    let checked_batches: Vec<_> = reports
+        .iter()
+        .map(|report| report.l1_batch_number)
+        .collect();
+    assert_eq!(checked_batches, [L1BatchNumber(3)]);
+
+    // ...unless progress is reset.
+    CommitmentRecomputer::reset_progress(&pool).await.unwrap();
+    let reports = recompute_commitments(&pool, L1BatchNumber(1)).await;
+    let checked_batches: Vec<_> = reports
+        .iter()
+        .map(|report| report.l1_batch_number)
+        .collect();
+    assert_eq!(checked_batches, [L1BatchNumber(1)]);
+}
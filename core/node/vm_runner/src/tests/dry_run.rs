@@ -0,0 +1,199 @@
+use std::{collections::HashMap, time::Duration};
+
+use tokio::sync::{mpsc, watch};
+use zksync_dal::{ConnectionPool, Core};
+use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
+use zksync_test_account::Account;
+use zksync_types::{Address, L1BatchNumber, L2ChainId, StorageKey, VmEvent, H256, U256};
+
+use crate::{
+    impls::dry_run::{
+        compare_events, compare_storage_writes, compare_transaction_outcomes, Divergence,
+        TransactionOutcome,
+    },
+    tests::{fund, store_l1_batches},
+    DryRunVmRunner, VmRunnerStorageBackend,
+};
+
+fn mock_event(index_in_block: u32) -> VmEvent {
+    VmEvent {
+        location: (L1BatchNumber(1), index_in_block),
+        address: Address::repeat_byte(1),
+        indexed_topics: vec![H256::repeat_byte(index_in_block as u8)],
+        value: vec![],
+    }
+}
+
+#[test]
+fn comparing_storage_writes() {
+    let key = StorageKey::new(Default::default(), H256::repeat_byte(1));
+    let other_key = StorageKey::new(Default::default(), H256::repeat_byte(2));
+    let expected = HashMap::from([(key, H256::repeat_byte(0xff))]);
+
+    assert_eq!(compare_storage_writes(&expected, &expected.clone()), []);
+
+    let actual = HashMap::from([
+        (key, H256::repeat_byte(0xfe)),
+        (other_key, H256::repeat_byte(0xff)),
+    ]);
+    let divergences = compare_storage_writes(&expected, &actual);
+    assert_eq!(
+        divergences,
+        [
+            Divergence::StorageWrite {
+                key,
+                expected: Some(H256::repeat_byte(0xff)),
+                actual: Some(H256::repeat_byte(0xfe)),
+            },
+            Divergence::StorageWrite {
+                key: other_key,
+                expected: None,
+                actual: Some(H256::repeat_byte(0xff)),
+            },
+        ]
+    );
+}
+
+#[test]
+fn comparing_events() {
+    let expected = [mock_event(0), mock_event(1)];
+    assert_eq!(compare_events(&expected, &expected), []);
+
+    let actual = [mock_event(0), mock_event(2), mock_event(3)];
+    let divergences = compare_events(&expected, &actual);
+    assert_eq!(
+        divergences,
+        [
+            Divergence::Event {
+                index: 1,
+                expected: Some(mock_event(1)),
+                actual: Some(mock_event(2)),
+            },
+            Divergence::Event {
+                index: 2,
+                expected: None,
+                actual: Some(mock_event(3)),
+            },
+        ]
+    );
+}
+
+#[test]
+fn comparing_transaction_outcomes() {
+    let outcome = TransactionOutcome {
+        success: true,
+        gas_used: U256::from(100_000),
+    };
+    let failed_outcome = TransactionOutcome {
+        success: false,
+        ..outcome
+    };
+    let expected = HashMap::from([
+        (H256::repeat_byte(1), outcome),
+        (H256::repeat_byte(2), outcome),
+    ]);
+    let actual = [
+        (H256::repeat_byte(1), outcome),
+        (H256::repeat_byte(2), outcome),
+    ];
+    assert_eq!(compare_transaction_outcomes(&expected, &actual), []);
+
+    let actual = [
+        (H256::repeat_byte(1), failed_outcome),
+        (H256::repeat_byte(3), outcome),
+    ];
+    let divergences = compare_transaction_outcomes(&expected, &actual);
+    assert_eq!(
+        divergences,
+        [
+            Divergence::TransactionResult {
+                hash: H256::repeat_byte(1),
+                expected: Some(outcome),
+                actual: Some(failed_outcome),
+            },
+            Divergence::TransactionResult {
+                hash: H256::repeat_byte(3),
+                expected: None,
+                actual: Some(outcome),
+            },
+            Divergence::TransactionResult {
+                hash: H256::repeat_byte(2),
+                expected: Some(outcome),
+                actual: None,
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn dry_run_reports_divergences_and_stops_at_last_batch() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    let genesis_params = GenesisParams::mock();
+    insert_genesis_batch(&mut conn, &genesis_params)
+        .await
+        .unwrap();
+    let mut accounts = vec![Account::random(), Account::random()];
+    fund(&pool, &accounts).await;
+    // Stored batches contain random storage writes that cannot be produced by executing their transactions.
+    store_l1_batches(
+        &mut conn,
+        1..=3,
+        genesis_params.base_system_contracts().hashes(),
+        &mut accounts,
+    )
+    .await
+    .unwrap();
+    drop(conn);
+
+    let (report_sender, mut report_receiver) = mpsc::unbounded_channel();
+    let (dry_runner, tasks) = DryRunVmRunner::new(
+        pool.clone(),
+        // The dry run is short-lived, so it doesn't need a RocksDB cache.
+        VmRunnerStorageBackend::PostgresOnly,
+        L2ChainId::default(),
+        None,
+        L1BatchNumber(0),
+        Some(L1BatchNumber(2)),
+        1,
+        Some(report_sender),
+    )
+    .await
+    .unwrap();
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let loader_task = tokio::spawn(tasks.loader_task.run(stop_receiver.clone()));
+    let output_handler_task =
+        tokio::spawn(tasks.output_handler_factory_task.run(stop_receiver.clone()));
+
+    // The runner must return on its own once the last batch is processed.
+    tokio::time::timeout(Duration::from_secs(30), dry_runner.run(&stop_receiver))
+        .await
+        .expect("timed out waiting for dry run to finish")
+        .unwrap();
+    stop_sender.send_replace(true);
+    output_handler_task.await.unwrap().unwrap();
+    loader_task.await.unwrap().unwrap();
+
+    let mut reports = vec![];
+    while let Ok(report) = report_receiver.try_recv() {
+        reports.push(report);
+    }
+    let checked_batches: Vec<_> = reports
+        .iter()
+        .map(|report| report.l1_batch_number)
+        .collect();
+    assert_eq!(checked_batches, [L1BatchNumber(1), L1BatchNumber(2)]);
+    for report in &reports {
+        assert!(
+            report.divergences.iter().any(|divergence| matches!(
+                divergence,
+                Divergence::StorageWrite {
+                    expected: Some(_),
+                    actual: None,
+                    ..
+                }
+            )),
+            "{report:?}"
+        );
+    }
+}
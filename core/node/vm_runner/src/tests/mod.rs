@@ -1,16 +1,16 @@
 use std::{collections::HashMap, ops, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
-use rand::{prelude::SliceRandom, Rng};
+use rand::Rng;
 use tokio::sync::RwLock;
 use zksync_contracts::BaseSystemContractsHashes;
-use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_dal::{transactions_dal::CallTraceFormat, Connection, ConnectionPool, Core, CoreDal};
 use zksync_node_test_utils::{
     create_l1_batch_metadata, create_l2_block, execute_l2_transaction,
     l1_batch_metadata_to_commitment_artifacts,
 };
 use zksync_state_keeper::{StateKeeperOutputHandler, UpdatesManager};
-use zksync_test_account::Account;
+use zksync_test_account::{Account, Scenario, ScenarioExecutor, TxOutcome};
 use zksync_types::{
     block::{BlockGasCount, L1BatchHeader, L2BlockHasher},
     fee::{Fee, TransactionExecutionMetrics},
@@ -18,15 +18,22 @@ use zksync_types::{
     l2::L2Tx,
     utils::storage_key_for_standard_token_balance,
     AccountTreeId, Address, Execute, L1BatchNumber, L2BlockNumber, ProtocolVersionId, StorageKey,
-    StorageLog, StorageLogKind, StorageValue, H160, H256, L2_BASE_TOKEN_ADDRESS, U256,
+    StorageLog, StorageLogKind, StorageValue, Transaction, H160, H256, L2_BASE_TOKEN_ADDRESS, U256,
 };
 use zksync_utils::u256_to_h256;
 
 use super::{OutputHandlerFactory, VmRunnerIo};
 
+mod bwip;
+mod commitment_recomputer;
+mod dry_run;
+mod notify;
 mod output_handler;
+mod presimulation;
 mod process;
+mod protective_reads;
 mod storage;
+mod upgrade_canary;
 
 #[derive(Debug, Default)]
 struct IoMock {
@@ -172,18 +179,21 @@ impl OutputHandlerFactory for TestOutputFactory {
     }
 }
 
+fn test_fee(fee_per_gas: u64, gas_per_pubdata: u64) -> Fee {
+    Fee {
+        gas_limit: (get_intrinsic_constants().l2_tx_intrinsic_gas * 10).into(),
+        max_fee_per_gas: fee_per_gas.into(),
+        max_priority_fee_per_gas: 0_u64.into(),
+        gas_per_pubdata_limit: gas_per_pubdata.into(),
+    }
+}
+
 /// Creates an L2 transaction with randomized parameters.
 pub fn create_l2_transaction(
     account: &mut Account,
     fee_per_gas: u64,
     gas_per_pubdata: u64,
 ) -> L2Tx {
-    let fee = Fee {
-        gas_limit: (get_intrinsic_constants().l2_tx_intrinsic_gas * 10).into(),
-        max_fee_per_gas: fee_per_gas.into(),
-        max_priority_fee_per_gas: 0_u64.into(),
-        gas_per_pubdata_limit: gas_per_pubdata.into(),
-    };
     let tx = account.get_l2_tx_for_execute(
         Execute {
             contract_address: Address::random(),
@@ -191,40 +201,59 @@ pub fn create_l2_transaction(
             value: Default::default(),
             factory_deps: None,
         },
-        Some(fee),
+        Some(test_fee(fee_per_gas, gas_per_pubdata)),
     );
     L2Tx::try_from(tx).unwrap()
 }
 
-async fn store_l1_batches(
-    conn: &mut Connection<'_, Core>,
-    numbers: ops::RangeInclusive<u32>,
+/// [`ScenarioExecutor`] storing each transaction in a separate L1 batch in Postgres together with random
+/// storage logs and factory deps. Transaction execution results are mocked, so all transactions succeed.
+struct L1BatchesWriter<'a, 'c> {
+    conn: &'a mut Connection<'c, Core>,
     contract_hashes: BaseSystemContractsHashes,
-    accounts: &mut [Account],
-) -> anyhow::Result<Vec<L1BatchHeader>> {
-    let mut rng = rand::thread_rng();
-    let mut batches = Vec::new();
-    let mut l2_block_number = conn
-        .blocks_dal()
-        .get_last_sealed_l2_block_header()
-        .await?
-        .map(|m| m.number)
-        .unwrap_or_default()
-        + 1;
-    let mut last_l2_block_hash = if l2_block_number == 1.into() {
-        // First L2 block ever has a special `prev_l2_block_hash`
-        L2BlockHasher::legacy_hash(L2BlockNumber(0))
-    } else {
-        conn.blocks_dal()
-            .get_l2_block_header(l2_block_number - 1)
+    next_l1_batch_number: L1BatchNumber,
+    next_l2_block_number: L2BlockNumber,
+    last_l2_block_hash: H256,
+    batches: Vec<L1BatchHeader>,
+}
+
+impl<'a, 'c> L1BatchesWriter<'a, 'c> {
+    async fn new(
+        conn: &'a mut Connection<'c, Core>,
+        first_l1_batch_number: L1BatchNumber,
+        contract_hashes: BaseSystemContractsHashes,
+    ) -> anyhow::Result<Self> {
+        let next_l2_block_number = conn
+            .blocks_dal()
+            .get_last_sealed_l2_block_header()
             .await?
-            .unwrap()
-            .hash
-    };
-    for l1_batch_number in numbers {
-        let l1_batch_number = L1BatchNumber(l1_batch_number);
-        let account = accounts.choose_mut(&mut rng).unwrap();
-        let tx = create_l2_transaction(account, 1000000, 100);
+            .map(|m| m.number)
+            .unwrap_or_default()
+            + 1;
+        let last_l2_block_hash = if next_l2_block_number == 1.into() {
+            // First L2 block ever has a special `prev_l2_block_hash`
+            L2BlockHasher::legacy_hash(L2BlockNumber(0))
+        } else {
+            conn.blocks_dal()
+                .get_l2_block_header(next_l2_block_number - 1)
+                .await?
+                .unwrap()
+                .hash
+        };
+        Ok(Self {
+            conn,
+            contract_hashes,
+            next_l1_batch_number: first_l1_batch_number,
+            next_l2_block_number,
+            last_l2_block_hash,
+            batches: vec![],
+        })
+    }
+
+    async fn store_l1_batch(&mut self, tx: L2Tx) -> anyhow::Result<()> {
+        let conn = &mut *self.conn;
+        let l1_batch_number = self.next_l1_batch_number;
+        let l2_block_number = self.next_l2_block_number;
         conn.transactions_dal()
             .insert_transaction_l2(&tx, TransactionExecutionMetrics::default())
             .await?;
@@ -240,10 +269,9 @@ async fn store_l1_batches(
                 value,
             });
         }
-        let mut factory_deps = HashMap::new();
-        for _ in 0..10 {
-            factory_deps.insert(H256::random(), rng.gen::<[u8; 32]>().into());
-        }
+        let factory_deps: HashMap<_, _> = (0..10)
+            .map(|_| (H256::random(), rand::thread_rng().gen::<[u8; 32]>().into()))
+            .collect();
         conn.storage_logs_dal()
             .insert_storage_logs(l2_block_number, &[(tx.hash(), logs)])
             .await?;
@@ -258,16 +286,14 @@ async fn store_l1_batches(
         let mut digest = L2BlockHasher::new(
             new_l2_block.number,
             new_l2_block.timestamp,
-            last_l2_block_hash,
+            self.last_l2_block_hash,
         );
         digest.push_tx_hash(tx.hash());
         new_l2_block.hash = digest.finalize(ProtocolVersionId::latest());
 
-        l2_block_number += 1;
-        new_l2_block.base_system_contracts_hashes = contract_hashes;
+        new_l2_block.base_system_contracts_hashes = self.contract_hashes;
         new_l2_block.l2_tx_count = 1;
         conn.blocks_dal().insert_l2_block(&new_l2_block).await?;
-        last_l2_block_hash = new_l2_block.hash;
         let tx_result = execute_l2_transaction(tx.clone());
         conn.transactions_dal()
             .mark_txs_as_executed_in_l2_block(
@@ -275,26 +301,27 @@ async fn store_l1_batches(
                 &[tx_result.clone()],
                 1.into(),
                 ProtocolVersionId::latest(),
+                CallTraceFormat::Legacy,
                 false,
             )
             .await?;
 
         // Insert a fictive L2 block at the end of the batch
-        let mut fictive_l2_block = create_l2_block(l2_block_number.0);
+        let mut fictive_l2_block = create_l2_block(l2_block_number.0 + 1);
         let mut digest = L2BlockHasher::new(
             fictive_l2_block.number,
             fictive_l2_block.timestamp,
-            last_l2_block_hash,
+            new_l2_block.hash,
         );
         digest.push_tx_hash(tx.hash());
         fictive_l2_block.hash = digest.finalize(ProtocolVersionId::latest());
-        l2_block_number += 1;
         conn.blocks_dal().insert_l2_block(&fictive_l2_block).await?;
-        last_l2_block_hash = fictive_l2_block.hash;
+        self.last_l2_block_hash = fictive_l2_block.hash;
+        self.next_l2_block_number = l2_block_number + 2;
 
         let header = L1BatchHeader::new(
             l1_batch_number,
-            l2_block_number.0 as u64 - 2, // Matches the first L2 block in the batch
+            l2_block_number.0.into(), // Matches the first L2 block in the batch
             BaseSystemContractsHashes::default(),
             ProtocolVersionId::default(),
         );
@@ -323,10 +350,39 @@ async fn store_l1_batches(
                 &l1_batch_metadata_to_commitment_artifacts(&metadata),
             )
             .await?;
-        batches.push(header);
+        self.batches.push(header);
+        self.next_l1_batch_number += 1;
+        Ok(())
     }
+}
 
-    Ok(batches)
+#[async_trait]
+impl ScenarioExecutor for L1BatchesWriter<'_, '_> {
+    async fn execute_transaction(&mut self, tx: Transaction) -> anyhow::Result<TxOutcome> {
+        let tx = L2Tx::try_from(tx).map_err(anyhow::Error::msg)?;
+        self.store_l1_batch(tx).await?;
+        Ok(TxOutcome::Success)
+    }
+}
+
+/// Stores L1 batches with the specified numbers, each containing a single transfer from a random account.
+/// Account nonces are updated accordingly.
+async fn store_l1_batches(
+    conn: &mut Connection<'_, Core>,
+    numbers: ops::RangeInclusive<u32>,
+    contract_hashes: BaseSystemContractsHashes,
+    accounts: &mut [Account],
+) -> anyhow::Result<Vec<L1BatchHeader>> {
+    let mut scenario = Scenario::new(accounts.to_vec()).with_l2_fee(test_fee(1_000_000, 100));
+    for _ in numbers.clone() {
+        let from = rand::thread_rng().gen_range(0..accounts.len());
+        scenario = scenario.transfer(from, Address::random(), U256::zero());
+    }
+    let mut writer =
+        L1BatchesWriter::new(conn, L1BatchNumber(*numbers.start()), contract_hashes).await?;
+    scenario.run(&mut writer).await?;
+    accounts.clone_from_slice(scenario.accounts());
+    Ok(writer.batches)
 }
 
 async fn fund(pool: &ConnectionPool<Core>, accounts: &[Account]) {
@@ -0,0 +1,74 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::sync::{watch, RwLock};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
+use zksync_node_test_utils::create_l1_batch;
+use zksync_types::L1BatchNumber;
+
+use crate::{tests::IoMock, NotifiedIo, SealedBatchesListener, VmRunnerIo};
+
+const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[tokio::test]
+async fn sealed_batches_listener_receives_notifications() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut conn, &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let listener = SealedBatchesListener::new(pool.clone());
+    let mut sealed_batches = listener.subscribe();
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    tokio::spawn(listener.run(stop_receiver));
+
+    for number in 1..=3 {
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&create_l1_batch(number))
+            .await
+            .unwrap();
+        tokio::time::timeout(
+            TEST_TIMEOUT,
+            sealed_batches.wait_for(|sealed| *sealed >= L1BatchNumber(number)),
+        )
+        .await
+        .expect("timed out waiting for notification")
+        .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn notified_io_wakes_up_on_sealed_and_completed_batches() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    let (sealed_sender, sealed_batches) = watch::channel(L1BatchNumber(0));
+    let io_mock = Arc::new(RwLock::new(IoMock::default()));
+    let io =
+        NotifiedIo::new(io_mock, sealed_batches).with_poll_interval(Duration::from_secs(3_600));
+
+    io.last_ready_to_be_loaded_batch(&mut conn).await.unwrap();
+    let wait_task = {
+        let io = io.clone();
+        tokio::spawn(async move { io.wait_for_ready_batches().await })
+    };
+    sealed_sender.send_replace(L1BatchNumber(1));
+    tokio::time::timeout(TEST_TIMEOUT, wait_task)
+        .await
+        .expect("timed out waiting for sealed batch")
+        .unwrap();
+
+    io.last_ready_to_be_loaded_batch(&mut conn).await.unwrap();
+    io.mark_l1_batch_as_completed(&mut conn, L1BatchNumber(1))
+        .await
+        .unwrap();
+    tokio::time::timeout(TEST_TIMEOUT, io.wait_for_ready_batches())
+        .await
+        .expect("timed out waiting for completed batch");
+
+    // No new notifications after the latest query => waiting should not resolve.
+    io.last_ready_to_be_loaded_batch(&mut conn).await.unwrap();
+    let wait_result =
+        tokio::time::timeout(Duration::from_millis(100), io.wait_for_ready_batches()).await;
+    assert!(wait_result.is_err());
+}
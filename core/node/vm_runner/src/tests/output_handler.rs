@@ -12,11 +12,13 @@ use zksync_types::L1BatchNumber;
 
 use crate::{
     tests::{wait, IoMock, TestOutputFactory},
-    ConcurrentOutputHandlerFactory, OutputHandlerFactory,
+    BackoffPolicy, ConcurrentOutputHandlerFactory, ConcurrentOutputHandlerOptions, ConstantBackoff,
+    ExponentialBackoff, JitteredBackoff, OutputHandlerFactory,
 };
 
 struct OutputHandlerTester {
     output_factory: ConcurrentOutputHandlerFactory<Arc<RwLock<IoMock>>, TestOutputFactory>,
+    factory_task: JoinHandle<anyhow::Result<()>>,
     tasks: Vec<JoinHandle<()>>,
     stop_sender: watch::Sender<bool>,
 }
@@ -26,15 +28,25 @@ impl OutputHandlerTester {
         io: Arc<RwLock<IoMock>>,
         pool: ConnectionPool<Core>,
         delays: HashMap<L1BatchNumber, Duration>,
+    ) -> Self {
+        Self::with_options(io, pool, delays, ConcurrentOutputHandlerOptions::default())
+    }
+
+    fn with_options(
+        io: Arc<RwLock<IoMock>>,
+        pool: ConnectionPool<Core>,
+        delays: HashMap<L1BatchNumber, Duration>,
+        options: ConcurrentOutputHandlerOptions,
     ) -> Self {
         let test_factory = TestOutputFactory { delays };
-        let (output_factory, task) = ConcurrentOutputHandlerFactory::new(pool, io, test_factory);
+        let (output_factory, task) =
+            ConcurrentOutputHandlerFactory::new(pool, io, test_factory, options);
         let (stop_sender, stop_receiver) = watch::channel(false);
-        let join_handle = tokio::task::spawn(async move { task.run(stop_receiver).await.unwrap() });
-        let tasks = vec![join_handle];
+        let factory_task = tokio::task::spawn(task.run(stop_receiver));
         Self {
             output_factory,
-            tasks,
+            factory_task,
+            tasks: vec![],
             stop_sender,
         }
     }
@@ -68,6 +80,7 @@ impl OutputHandlerTester {
                         code: vec![],
                         hash: Default::default(),
                     },
+                    evm_emulator: None,
                 },
                 bootloader_gas_limit: 0,
                 execution_mode: TxExecutionMode::VerifyExecute,
@@ -91,10 +104,37 @@ impl OutputHandlerTester {
     async fn stop_and_wait_for_all_tasks(self) -> anyhow::Result<()> {
         self.stop_sender.send(true)?;
         futures::future::join_all(self.tasks).await;
+        self.factory_task.await??;
         Ok(())
     }
 }
 
+#[test]
+fn backoff_policies() {
+    let constant = ConstantBackoff(Duration::from_millis(50));
+    assert_eq!(constant.delay(0), Duration::from_millis(50));
+    assert_eq!(constant.delay(100), Duration::from_millis(50));
+
+    let exponential = ExponentialBackoff {
+        initial: Duration::from_millis(50),
+        max: Duration::from_secs(1),
+    };
+    let delays: Vec<_> = (0..7).map(|attempt| exponential.delay(attempt)).collect();
+    let expected_delays = [50, 100, 200, 400, 800, 1_000, 1_000].map(Duration::from_millis);
+    assert_eq!(delays, expected_delays);
+    assert_eq!(exponential.delay(u32::MAX), Duration::from_secs(1));
+
+    let jittered = JitteredBackoff::new(exponential, 0.5);
+    for attempt in 0..10 {
+        let delay = jittered.delay(attempt);
+        let max_delay = exponential.delay(attempt);
+        assert!(delay <= max_delay, "{delay:?}");
+        assert!(delay >= max_delay / 2, "{delay:?}");
+    }
+    let not_jittered = JitteredBackoff::new(constant, 0.0);
+    assert_eq!(not_jittered.delay(3), Duration::from_millis(50));
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
 async fn monotonically_progress_processed_batches() -> anyhow::Result<()> {
     let pool = ConnectionPool::<Core>::test_pool().await;
@@ -144,3 +184,128 @@ async fn do_not_progress_with_gaps() -> anyhow::Result<()> {
     assert_eq!(io.read().await.current, L1BatchNumber(9));
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn skipped_batches_are_marked_as_completed() -> anyhow::Result<()> {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let io = Arc::new(RwLock::new(IoMock {
+        current: 0.into(),
+        max: 10,
+    }));
+    let mut tester = OutputHandlerTester::new(io.clone(), pool, HashMap::new());
+    for i in 1..6 {
+        if i % 2 == 0 {
+            tester.output_factory.skip_batch(i.into()).await?;
+        } else {
+            tester.spawn_test_task(i.into()).await?;
+        }
+    }
+    wait::for_batch(io.clone(), L1BatchNumber(5), Duration::from_secs(10)).await?;
+    // Already processed batches cannot be skipped.
+    tester
+        .output_factory
+        .skip_batch(L1BatchNumber(3))
+        .await
+        .unwrap_err();
+    tester.stop_and_wait_for_all_tasks().await?;
+    assert_eq!(io.read().await.current, L1BatchNumber(5));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn batch_processing_timeout() -> anyhow::Result<()> {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let io = Arc::new(RwLock::new(IoMock {
+        current: 0.into(),
+        max: 10,
+    }));
+    let delays = HashMap::from([
+        (L1BatchNumber(1), Duration::from_millis(100)),
+        (L1BatchNumber(2), Duration::from_secs(3_600)),
+    ]);
+    let options = ConcurrentOutputHandlerOptions {
+        backoff: Arc::new(ExponentialBackoff {
+            initial: Duration::from_millis(10),
+            max: Duration::from_millis(100),
+        }),
+        batch_timeout: Some(Duration::from_millis(50)),
+        // Batch #1 would time out with the default timeout.
+        batch_timeout_overrides: HashMap::from([(L1BatchNumber(1), Duration::from_secs(10))]),
+        drain_timeout: None,
+    };
+    let mut tester = OutputHandlerTester::with_options(io.clone(), pool, delays, options);
+    tester.spawn_test_task(L1BatchNumber(1)).await?;
+    tester.spawn_test_task(L1BatchNumber(2)).await?;
+
+    let err = tokio::time::timeout(Duration::from_secs(10), tester.factory_task)
+        .await?
+        .unwrap()
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("L1 batch #2 timed out"), "{err}");
+    assert_eq!(io.read().await.current, L1BatchNumber(1));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn draining_started_batches_on_stop() -> anyhow::Result<()> {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let io = Arc::new(RwLock::new(IoMock {
+        current: 0.into(),
+        max: 10,
+    }));
+    let delays = HashMap::from([
+        (L1BatchNumber(1), Duration::from_millis(500)),
+        (L1BatchNumber(2), Duration::from_millis(500)),
+        (L1BatchNumber(3), Duration::from_secs(3_600)),
+    ]);
+    let options = ConcurrentOutputHandlerOptions {
+        drain_timeout: Some(Duration::from_secs(5)),
+        ..ConcurrentOutputHandlerOptions::default()
+    };
+    let mut tester = OutputHandlerTester::with_options(io.clone(), pool, delays, options);
+    for i in 1..=3 {
+        tester.spawn_test_task(i.into()).await?;
+    }
+    // Handler for batch #4 is created, but `handle_l1_batch` is never called on it.
+    let _pending_handler = tester
+        .output_factory
+        .create_handler(L1BatchNumber(4))
+        .await?;
+
+    let started_at = std::time::Instant::now();
+    tester.stop_sender.send_replace(true);
+    tokio::time::timeout(Duration::from_secs(10), tester.factory_task)
+        .await?
+        .unwrap()?;
+    // Batches #1 and #2 should be drained, while batch #3 should be abandoned after the deadline.
+    assert_eq!(io.read().await.current, L1BatchNumber(2));
+    assert!(started_at.elapsed() >= Duration::from_secs(5));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+async fn draining_stops_on_dropped_handler() -> anyhow::Result<()> {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let io = Arc::new(RwLock::new(IoMock {
+        current: 0.into(),
+        max: 10,
+    }));
+    let options = ConcurrentOutputHandlerOptions {
+        drain_timeout: Some(Duration::from_secs(3_600)),
+        ..ConcurrentOutputHandlerOptions::default()
+    };
+    let mut tester = OutputHandlerTester::with_options(io.clone(), pool, HashMap::new(), options);
+    let handler = tester
+        .output_factory
+        .create_handler(L1BatchNumber(1))
+        .await?;
+    // Emulate the VM runner being stopped mid-batch: the handler is dropped without handling the batch.
+    tester.stop_sender.send_replace(true);
+    drop(handler);
+    tokio::time::timeout(Duration::from_secs(10), tester.factory_task)
+        .await?
+        .unwrap()?;
+    assert_eq!(io.read().await.current, L1BatchNumber(0));
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use std::sync::Arc;
+
+use multivm::interface::{Halt, VmRevertReason};
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
+use zksync_state_keeper::{TransactionFilter, TxFilterDecision};
+use zksync_test_account::Account;
+use zksync_types::{fee::TransactionExecutionMetrics, L1BatchNumber, L2ChainId, Transaction};
+
+use super::{create_l2_transaction, fund};
+use crate::{PresimulationCache, PresimulationOutcome, TransactionPresimulator};
+
+fn invalid_signature_for(is_default_account: bool) -> PresimulationOutcome {
+    PresimulationOutcome::Rejected {
+        reason: Halt::ValidationFailed(VmRevertReason::General {
+            msg: "Account validation returned invalid magic value. Most often this means that the signature is incorrect".to_owned(),
+            data: vec![],
+        }),
+        is_default_account,
+    }
+}
+
+fn invalid_signature() -> PresimulationOutcome {
+    invalid_signature_for(true)
+}
+
+fn not_enough_balance() -> PresimulationOutcome {
+    PresimulationOutcome::Rejected {
+        reason: Halt::ValidationFailed(VmRevertReason::General {
+            msg: "Not enough balance for fee + value".to_owned(),
+            data: vec![],
+        }),
+        is_default_account: true,
+    }
+}
+
+#[tokio::test]
+async fn presimulation_cache_as_transaction_filter() {
+    let mut alice = Account::random();
+    let [valid_tx, invalid_tx, unfunded_tx, stale_tx, unknown_tx]: [Transaction; 5] =
+        [(); 5].map(|()| create_l2_transaction(&mut alice, 1_000_000, 100).into());
+
+    let cache = PresimulationCache::default();
+    cache.set_base_batch(L1BatchNumber(1));
+    cache.insert(
+        valid_tx.hash(),
+        L1BatchNumber(1),
+        PresimulationOutcome::Executed,
+    );
+    cache.insert(invalid_tx.hash(), L1BatchNumber(1), invalid_signature());
+    cache.insert(unfunded_tx.hash(), L1BatchNumber(1), not_enough_balance());
+    // Outcomes for outdated base batches must not be recorded.
+    cache.insert(unknown_tx.hash(), L1BatchNumber(0), invalid_signature());
+
+    let decision = cache.filter(&valid_tx).await.unwrap();
+    assert_eq!(decision, TxFilterDecision::Allow);
+    let decision = cache.filter(&invalid_tx).await.unwrap();
+    assert!(
+        matches!(&decision, TxFilterDecision::Deny(reason) if reason.contains("signature is incorrect")),
+        "{decision:?}"
+    );
+    // State-dependent failures must not lead to rejection.
+    let decision = cache.filter(&unfunded_tx).await.unwrap();
+    assert_eq!(decision, TxFilterDecision::Allow);
+    // Outcomes are consumed by the filter.
+    let decision = cache.filter(&invalid_tx).await.unwrap();
+    assert_eq!(decision, TxFilterDecision::Allow);
+    let decision = cache.filter(&unknown_tx).await.unwrap();
+    assert_eq!(decision, TxFilterDecision::Allow);
+
+    // Outcomes become stale once a new batch is sealed.
+    cache.insert(stale_tx.hash(), L1BatchNumber(1), invalid_signature());
+    cache.set_base_batch(L1BatchNumber(2));
+    assert_eq!(cache.take_fresh_outcome(stale_tx.hash()), None);
+}
+
+#[test]
+fn classifying_presimulation_outcomes() {
+    assert!(invalid_signature().state_independent_failure().is_some());
+    assert!(invalid_signature_for(false)
+        .state_independent_failure()
+        .is_none());
+    assert!(not_enough_balance().state_independent_failure().is_none());
+
+    let too_big_gas_limit = PresimulationOutcome::Rejected {
+        reason: Halt::TooBigGasLimit,
+        is_default_account: false,
+    };
+    assert!(too_big_gas_limit.state_independent_failure().is_some());
+    for reason in [
+        Halt::ValidationOutOfGas,
+        Halt::FromIsNotAnAccount,
+        Halt::NotEnoughGasProvided,
+    ] {
+        let outcome = PresimulationOutcome::Rejected {
+            reason,
+            is_default_account: true,
+        };
+        assert!(outcome.state_independent_failure().is_none(), "{outcome:?}");
+    }
+    assert!(PresimulationOutcome::Executed
+        .state_independent_failure()
+        .is_none());
+    assert!(PresimulationOutcome::BootloaderOutOfGas
+        .state_independent_failure()
+        .is_none());
+}
+
+#[tokio::test]
+async fn presimulating_pending_transactions() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut conn, &GenesisParams::mock())
+        .await
+        .unwrap();
+    let mut alice = Account::random();
+    let mut bob = Account::random();
+    fund(&pool, &[alice.clone(), bob.clone()]).await;
+    // Carol is not funded, so her transaction should fail validation, but this failure depends on the state.
+    let mut carol = Account::random();
+
+    let cache = Arc::<PresimulationCache>::default();
+    let mut presimulator = TransactionPresimulator::new(
+        pool.clone(),
+        L2ChainId::default(),
+        None,
+        u32::MAX,
+        10,
+        cache.clone(),
+    )
+    .await
+    .unwrap();
+
+    let valid_tx = create_l2_transaction(&mut alice, 1_000_000, 100);
+    let mut invalid_tx = create_l2_transaction(&mut bob, 1_000_000, 100);
+    invalid_tx.common_data.signature = vec![27; 65];
+    let unfunded_tx = create_l2_transaction(&mut carol, 1_000_000, 100);
+    // Alice's second transaction depends on the first one and thus must not be pre-simulated.
+    let dependent_tx = create_l2_transaction(&mut alice, 1_000_000, 100);
+    for tx in [&valid_tx, &invalid_tx, &unfunded_tx, &dependent_tx] {
+        conn.transactions_dal()
+            .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+    }
+    drop(conn);
+
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let processed_count = presimulator
+        .presimulate_pending_transactions(&stop_receiver)
+        .await
+        .unwrap();
+    assert_eq!(processed_count, 4);
+    // All transactions are already processed.
+    let processed_count = presimulator
+        .presimulate_pending_transactions(&stop_receiver)
+        .await
+        .unwrap();
+    assert_eq!(processed_count, 0);
+
+    let outcome = cache.take_fresh_outcome(valid_tx.hash());
+    assert_eq!(outcome, Some(PresimulationOutcome::Executed));
+    let invalid_tx = Transaction::from(invalid_tx);
+    let decision = cache.filter(&invalid_tx).await.unwrap();
+    assert!(
+        matches!(&decision, TxFilterDecision::Deny(reason) if reason.contains("signature is incorrect")),
+        "{decision:?}"
+    );
+
+    let outcome = cache.take_fresh_outcome(unfunded_tx.hash()).unwrap();
+    assert!(
+        matches!(
+            &outcome,
+            PresimulationOutcome::Rejected {
+                is_default_account: true,
+                ..
+            }
+        ),
+        "{outcome:?}"
+    );
+    assert!(outcome.state_independent_failure().is_none());
+    assert_eq!(cache.take_fresh_outcome(dependent_tx.hash()), None);
+}
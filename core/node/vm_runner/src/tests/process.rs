@@ -1,28 +1,36 @@
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
+use anyhow::Context as _;
+use async_trait::async_trait;
+use multivm::{tracers::CallTracer, MultiVMTracer};
+use once_cell::sync::OnceCell;
 use tempfile::TempDir;
 use tokio::sync::{watch, RwLock};
-use zksync_dal::{ConnectionPool, Core};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
-use zksync_state_keeper::MainBatchExecutor;
+use zksync_state::InMemoryStorage;
+use zksync_state_keeper::{
+    BatchTracer, BatchTracerFactory, BatchTracerOutput, MainBatchExecutor,
+    StateKeeperOutputHandler, UpdatesManager,
+};
 use zksync_test_account::Account;
-use zksync_types::L2ChainId;
+use zksync_types::{
+    block::L1BatchHeader, vm_trace::Call, AccountTreeId, L1BatchNumber, L2BlockNumber, L2ChainId,
+    StorageKey, Transaction,
+};
 
 use crate::{
+    storage::StorageLoader,
     tests::{fund, store_l1_batches, wait, IoMock, TestOutputFactory},
-    ConcurrentOutputHandlerFactory, VmRunner, VmRunnerStorage,
+    ConcurrentOutputHandlerFactory, ConcurrentOutputHandlerOptions, InMemoryVmRunnerStorage,
+    OutputHandlerFactory, VmRunner, VmRunnerIo, VmRunnerStorage, VmRunnerTracers,
 };
 
-// Testing more than a one-batch scenario is pretty difficult as that requires storage to have
-// completely valid state after each L2 block execution (current block number, hash, rolling txs
-// hash etc written to the correct places). To achieve this we could run state keeper e2e but that
-// is pretty difficult to set up.
-//
-// Instead, we rely on integration tests to verify the correctness of VM runner main process.
-#[tokio::test]
-async fn process_one_batch() -> anyhow::Result<()> {
-    let rocksdb_dir = TempDir::new()?;
-    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+async fn prepare_batches(connection_pool: &ConnectionPool<Core>) -> Vec<L1BatchHeader> {
     let mut conn = connection_pool.connection().await.unwrap();
     let genesis_params = GenesisParams::mock();
     insert_genesis_batch(&mut conn, &genesis_params)
@@ -31,49 +39,161 @@ async fn process_one_batch() -> anyhow::Result<()> {
     let alice = Account::random();
     let bob = Account::random();
     let mut accounts = vec![alice, bob];
-    fund(&connection_pool, &accounts).await;
+    fund(connection_pool, &accounts).await;
 
-    let batches = store_l1_batches(
+    store_l1_batches(
         &mut conn,
         1..=1,
         genesis_params.base_system_contracts().hashes(),
         &mut accounts,
     )
-    .await?;
-    drop(conn);
+    .await
+    .unwrap()
+}
 
-    let io = Arc::new(RwLock::new(IoMock {
-        current: 0.into(),
-        max: 1,
-    }));
+async fn run_vm_runner(
+    connection_pool: ConnectionPool<Core>,
+    rocksdb_dir: &TempDir,
+    io: Arc<RwLock<IoMock>>,
+    factory: impl OutputHandlerFactory + 'static,
+    tracers: Option<Arc<dyn VmRunnerTracers>>,
+) -> anyhow::Result<()> {
     let (storage, task) = VmRunnerStorage::new(
         connection_pool.clone(),
         rocksdb_dir.path().to_str().unwrap().to_owned(),
         io.clone(),
         L2ChainId::default(),
+        None,
     )
     .await?;
     let (_, stop_receiver) = watch::channel(false);
-    let storage_stop_receiver = stop_receiver.clone();
-    tokio::task::spawn(async move { task.run(storage_stop_receiver).await.unwrap() });
-    let test_factory = TestOutputFactory {
-        delays: HashMap::new(),
-    };
-    let (output_factory, task) =
-        ConcurrentOutputHandlerFactory::new(connection_pool.clone(), io.clone(), test_factory);
+    tokio::task::spawn(async move { task.run(stop_receiver).await.unwrap() });
+    spawn_vm_runner(connection_pool, Arc::new(storage), io, factory, tracers)
+}
+
+/// Spawns a VM runner using the provided storage, which is assumed to be kept up to date externally.
+fn spawn_vm_runner(
+    connection_pool: ConnectionPool<Core>,
+    storage: Arc<dyn StorageLoader>,
+    io: Arc<RwLock<IoMock>>,
+    factory: impl OutputHandlerFactory + 'static,
+    tracers: Option<Arc<dyn VmRunnerTracers>>,
+) -> anyhow::Result<()> {
+    let (_, stop_receiver) = watch::channel(false);
+    let (output_factory, task) = ConcurrentOutputHandlerFactory::new(
+        connection_pool.clone(),
+        io.clone(),
+        factory,
+        ConcurrentOutputHandlerOptions::default(),
+    );
     let output_stop_receiver = stop_receiver.clone();
     tokio::task::spawn(async move { task.run(output_stop_receiver).await.unwrap() });
 
-    let storage = Arc::new(storage);
     let batch_executor = MainBatchExecutor::new(false, false);
-    let vm_runner = VmRunner::new(
+    let mut vm_runner = VmRunner::new(
         connection_pool,
-        Box::new(io.clone()),
+        Box::new(io),
         storage,
         Box::new(output_factory),
         Box::new(batch_executor),
+        1,
     );
+    if let Some(tracers) = tracers {
+        vm_runner = vm_runner.with_tracers(tracers)?;
+    }
     tokio::task::spawn(async move { vm_runner.run(&stop_receiver).await.unwrap() });
+    Ok(())
+}
+
+/// Creates in-memory storage with the provided batches, seeding the state at the start of each batch
+/// from Postgres.
+async fn in_memory_storage(
+    connection_pool: &ConnectionPool<Core>,
+    io: Arc<RwLock<IoMock>>,
+    batches: &[L1BatchHeader],
+) -> anyhow::Result<InMemoryVmRunnerStorage> {
+    let pg_storage =
+        VmRunnerStorage::postgres_only(connection_pool.clone(), io, L2ChainId::default(), None)
+            .await?;
+    let mut conn = connection_pool.connection().await?;
+    let storage_logs = conn
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await;
+    let initial_writes = conn
+        .storage_logs_dedup_dal()
+        .dump_all_initial_writes_for_tests()
+        .await;
+    let factory_deps = conn
+        .factory_deps_dal()
+        .dump_all_factory_deps_for_tests()
+        .await;
+
+    let vm_runner_storage = InMemoryVmRunnerStorage::default();
+    for batch in batches {
+        let (first_l2_block, _) = conn
+            .blocks_dal()
+            .get_l2_block_range_of_l1_batch(batch.number)
+            .await?
+            .context("missing L2 blocks for batch")?;
+        let enum_indices: HashMap<_, _> = initial_writes
+            .iter()
+            .filter(|write| write.l1_batch_number < batch.number)
+            .map(|write| (write.hashed_key, write.index))
+            .collect();
+
+        let mut storage = InMemoryStorage::default();
+        // Logs are ordered by L2 block and operation number, so later writes override earlier ones.
+        for log in storage_logs
+            .iter()
+            .filter(|log| log.l2_block_number < first_l2_block)
+        {
+            if let Some(&enum_index) = enum_indices.get(&log.hashed_key) {
+                storage.set_value_hashed_enum(log.hashed_key, enum_index, log.value);
+            } else {
+                let key = StorageKey::new(AccountTreeId::new(log.address), log.key);
+                storage.set_value(key, log.value);
+            }
+        }
+        for (hash, bytecode) in &factory_deps {
+            storage.store_factory_dep(*hash, bytecode.clone());
+        }
+
+        let batch_data = pg_storage
+            .load_batch(batch.number)
+            .await?
+            .context("missing batch data")?;
+        vm_runner_storage.insert_batch(batch_data, storage);
+    }
+    Ok(vm_runner_storage)
+}
+
+// Testing more than a one-batch scenario is pretty difficult as that requires storage to have
+// completely valid state after each L2 block execution (current block number, hash, rolling txs
+// hash etc written to the correct places). To achieve this we could run state keeper e2e but that
+// is pretty difficult to set up.
+//
+// Instead, we rely on integration tests to verify the correctness of VM runner main process.
+#[tokio::test]
+async fn process_one_batch() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let batches = prepare_batches(&connection_pool).await;
+
+    let io = Arc::new(RwLock::new(IoMock {
+        current: 0.into(),
+        max: 1,
+    }));
+    let test_factory = TestOutputFactory {
+        delays: HashMap::new(),
+    };
+    let storage = in_memory_storage(&connection_pool, io.clone(), &batches).await?;
+    spawn_vm_runner(
+        connection_pool,
+        Arc::new(storage),
+        io.clone(),
+        test_factory,
+        None,
+    )?;
 
     for batch in batches {
         wait::for_batch(io.clone(), batch.number, Duration::from_secs(1)).await?;
@@ -81,3 +201,240 @@ async fn process_one_batch() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Output handler factory recording the L2 blocks passed to the created handlers.
+#[derive(Debug, Default)]
+struct RecordingOutputFactory {
+    handled_l2_blocks: Arc<Mutex<Vec<L2BlockNumber>>>,
+}
+
+#[async_trait]
+impl OutputHandlerFactory for RecordingOutputFactory {
+    async fn create_handler(
+        &mut self,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Box<dyn StateKeeperOutputHandler>> {
+        #[derive(Debug)]
+        struct RecordingOutputHandler(Arc<Mutex<Vec<L2BlockNumber>>>);
+
+        #[async_trait]
+        impl StateKeeperOutputHandler for RecordingOutputHandler {
+            async fn handle_l2_block(
+                &mut self,
+                updates_manager: &UpdatesManager,
+            ) -> anyhow::Result<()> {
+                self.0.lock().unwrap().push(updates_manager.l2_block.number);
+                Ok(())
+            }
+
+            async fn handle_l1_batch(
+                &mut self,
+                _updates_manager: Arc<UpdatesManager>,
+            ) -> anyhow::Result<()> {
+                Ok(())
+            }
+        }
+
+        Ok(Box::new(RecordingOutputHandler(
+            self.handled_l2_blocks.clone(),
+        )))
+    }
+}
+
+#[tokio::test]
+async fn resuming_batch_processing_from_checkpoint() -> anyhow::Result<()> {
+    let rocksdb_dir = TempDir::new()?;
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let batches = prepare_batches(&connection_pool).await;
+    assert_eq!(batches.len(), 1);
+
+    let io = Arc::new(RwLock::new(IoMock {
+        current: 0.into(),
+        max: 1,
+    }));
+    // Emulate a restart after handling the first L2 block in the batch.
+    let mut conn = connection_pool.connection().await?;
+    io.save_l2_block_checkpoint(&mut conn, L1BatchNumber(1), L2BlockNumber(1))
+        .await?;
+    drop(conn);
+
+    let factory = RecordingOutputFactory::default();
+    let handled_l2_blocks = factory.handled_l2_blocks.clone();
+    run_vm_runner(
+        connection_pool.clone(),
+        &rocksdb_dir,
+        io.clone(),
+        factory,
+        None,
+    )
+    .await?;
+    wait::for_batch(io.clone(), L1BatchNumber(1), Duration::from_secs(1)).await?;
+
+    // Only the (fictive) L2 block after the checkpoint should be handled.
+    assert_eq!(*handled_l2_blocks.lock().unwrap(), [L2BlockNumber(2)]);
+    // The checkpoint should be removed once the batch is completed.
+    let mut conn = connection_pool.connection().await?;
+    let checkpoint = conn
+        .vm_runner_dal()
+        .get_l2_block_checkpoint(io.name(), L1BatchNumber(1))
+        .await?;
+    assert_eq!(checkpoint, None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn skipping_fully_handled_batch() -> anyhow::Result<()> {
+    let rocksdb_dir = TempDir::new()?;
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let batches = prepare_batches(&connection_pool).await;
+    assert_eq!(batches.len(), 1);
+
+    let io = Arc::new(RwLock::new(IoMock {
+        current: 0.into(),
+        max: 1,
+    }));
+    // Emulate a restart after the batch was handled, but before it was marked as completed.
+    let mut conn = connection_pool.connection().await?;
+    io.mark_l1_batch_as_handled(&mut conn, L1BatchNumber(1), L2BlockNumber(2))
+        .await?;
+    drop(conn);
+
+    let factory = RecordingOutputFactory::default();
+    let handled_l2_blocks = factory.handled_l2_blocks.clone();
+    run_vm_runner(
+        connection_pool.clone(),
+        &rocksdb_dir,
+        io.clone(),
+        factory,
+        None,
+    )
+    .await?;
+    wait::for_batch(io.clone(), L1BatchNumber(1), Duration::from_secs(1)).await?;
+
+    // The batch should not be executed or handled again.
+    assert_eq!(*handled_l2_blocks.lock().unwrap(), []);
+    let mut conn = connection_pool.connection().await?;
+    let checkpoint = conn
+        .vm_runner_dal()
+        .get_l2_block_checkpoint(io.name(), L1BatchNumber(1))
+        .await?;
+    assert_eq!(checkpoint, None);
+    Ok(())
+}
+
+/// Tracers collecting call traces for all transactions in a batch.
+#[derive(Debug, Default)]
+struct CallTracers {
+    traces: Mutex<HashMap<L1BatchNumber, Vec<Arc<OnceCell<Vec<Call>>>>>>,
+}
+
+impl BatchTracerFactory for CallTracers {
+    fn create_tracers<'a>(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        _tx: &Transaction,
+    ) -> Vec<BatchTracer<'a>> {
+        let result = Arc::<OnceCell<_>>::default();
+        let mut traces = self.traces.lock().unwrap();
+        traces
+            .entry(l1_batch_number)
+            .or_default()
+            .push(result.clone());
+        vec![CallTracer::new(result).into_tracer_pointer()]
+    }
+}
+
+impl VmRunnerTracers for CallTracers {
+    fn take_output(&self, l1_batch_number: L1BatchNumber) -> Option<BatchTracerOutput> {
+        let traces = self.traces.lock().unwrap().remove(&l1_batch_number)?;
+        let traces: Vec<Vec<Call>> = traces
+            .into_iter()
+            .map(|cell| cell.get().cloned().unwrap_or_default())
+            .collect();
+        Some(Box::new(traces))
+    }
+
+    fn upcast(self: Arc<Self>) -> Arc<dyn BatchTracerFactory> {
+        self
+    }
+}
+
+type TracerOutputs = Arc<Mutex<Vec<(usize, Vec<Vec<Call>>)>>>;
+
+/// Output handler factory recording the number of executed transactions and tracer outputs for each batch.
+#[derive(Debug, Default)]
+struct TracerOutputFactory {
+    outputs: TracerOutputs,
+}
+
+#[async_trait]
+impl OutputHandlerFactory for TracerOutputFactory {
+    async fn create_handler(
+        &mut self,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Box<dyn StateKeeperOutputHandler>> {
+        #[derive(Debug)]
+        struct TracerOutputHandler(TracerOutputs);
+
+        #[async_trait]
+        impl StateKeeperOutputHandler for TracerOutputHandler {
+            async fn handle_l2_block(
+                &mut self,
+                _updates_manager: &UpdatesManager,
+            ) -> anyhow::Result<()> {
+                Ok(())
+            }
+
+            async fn handle_l1_batch(
+                &mut self,
+                updates_manager: Arc<UpdatesManager>,
+            ) -> anyhow::Result<()> {
+                let tx_count = updates_manager.l1_batch.executed_transactions.len()
+                    + updates_manager.l2_block.executed_transactions.len();
+                let traces = updates_manager
+                    .tracer_output::<Vec<Vec<Call>>>()
+                    .context("no tracer output")?;
+                self.0.lock().unwrap().push((tx_count, traces.clone()));
+                Ok(())
+            }
+        }
+
+        Ok(Box::new(TracerOutputHandler(self.outputs.clone())))
+    }
+}
+
+#[tokio::test]
+async fn delivering_custom_tracer_outputs() -> anyhow::Result<()> {
+    let rocksdb_dir = TempDir::new()?;
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let batches = prepare_batches(&connection_pool).await;
+    assert_eq!(batches.len(), 1);
+
+    let io = Arc::new(RwLock::new(IoMock {
+        current: 0.into(),
+        max: 1,
+    }));
+    let factory = TracerOutputFactory::default();
+    let outputs = factory.outputs.clone();
+    let tracers = Arc::new(CallTracers::default());
+    run_vm_runner(
+        connection_pool,
+        &rocksdb_dir,
+        io.clone(),
+        factory,
+        Some(tracers.clone()),
+    )
+    .await?;
+    wait::for_batch(io, L1BatchNumber(1), Duration::from_secs(1)).await?;
+
+    let outputs = outputs.lock().unwrap();
+    let [(tx_count, traces)] = outputs.as_slice() else {
+        panic!("unexpected outputs: {outputs:?}");
+    };
+    assert!(*tx_count > 0);
+    assert_eq!(traces.len(), *tx_count);
+    assert!(traces.iter().all(|calls| !calls.is_empty()), "{traces:?}");
+    // Outputs should be removed from tracers once they are delivered.
+    assert!(tracers.traces.lock().unwrap().is_empty());
+    Ok(())
+}
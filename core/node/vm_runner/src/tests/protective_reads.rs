@@ -0,0 +1,25 @@
+use std::{num::NonZeroU32, time::Duration};
+
+use crate::impls::protective_reads::BatchRateLimiter;
+
+#[test]
+fn limiting_backfill_rate() {
+    let rate_limiter = BatchRateLimiter::new(NonZeroU32::new(10).unwrap());
+    assert_eq!(rate_limiter.allowed_batch_count(Duration::ZERO), 1);
+    assert_eq!(rate_limiter.allowed_batch_count(Duration::from_secs(5)), 1);
+    assert_eq!(rate_limiter.allowed_batch_count(Duration::from_secs(6)), 2);
+    assert_eq!(
+        rate_limiter.allowed_batch_count(Duration::from_secs(60)),
+        11
+    );
+    assert_eq!(
+        rate_limiter.allowed_batch_count(Duration::from_secs(90)),
+        16
+    );
+
+    let rate_limiter = BatchRateLimiter::new(NonZeroU32::MAX);
+    assert_eq!(
+        rate_limiter.allowed_batch_count(Duration::from_secs(3_600)),
+        u32::MAX
+    );
+}
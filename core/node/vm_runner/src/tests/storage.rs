@@ -9,14 +9,16 @@ use tokio::{
 };
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
-use zksync_state::{PgOrRocksdbStorage, PostgresStorage, ReadStorage, ReadStorageFactory};
+use zksync_state::{
+    InMemoryStorage, PgOrRocksdbStorage, PostgresStorage, ReadStorage, ReadStorageFactory,
+};
 use zksync_test_account::Account;
-use zksync_types::{AccountTreeId, L1BatchNumber, L2ChainId, StorageKey};
+use zksync_types::{AccountTreeId, L1BatchNumber, L2ChainId, StorageKey, H256};
 
 use crate::{
     storage::StorageLoader,
     tests::{fund, store_l1_batches, IoMock},
-    BatchExecuteData, VmRunnerIo, VmRunnerStorage,
+    BatchExecuteData, InMemoryVmRunnerStorage, SharedVmRunnerStorage, VmRunnerIo, VmRunnerStorage,
 };
 
 #[derive(Debug)]
@@ -44,6 +46,7 @@ impl StorageTester {
             self.db_dir.path().to_str().unwrap().to_owned(),
             io_mock,
             L2ChainId::default(),
+            None,
         )
         .await?;
         let handle = tokio::task::spawn(async move {
@@ -367,3 +370,213 @@ async fn access_vm_runner_storage() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn shared_storage_for_multiple_vm_runners() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = connection_pool.connection().await.unwrap();
+    let genesis_params = GenesisParams::mock();
+    insert_genesis_batch(&mut conn, &genesis_params)
+        .await
+        .unwrap();
+    drop(conn);
+    let alice = Account::random();
+    let bob = Account::random();
+    let mut accounts = vec![alice, bob];
+    fund(&connection_pool, &accounts).await;
+
+    let batches = store_l1_batches(
+        &mut connection_pool.connection().await?,
+        1..=5,
+        genesis_params.base_system_contracts().hashes(),
+        &mut accounts,
+    )
+    .await?;
+
+    let db_dir = TempDir::new()?;
+    let fast_io = Arc::new(RwLock::new(IoMock {
+        current: 0.into(),
+        max: 5,
+    }));
+    let slow_io = Arc::new(RwLock::new(IoMock {
+        current: 0.into(),
+        max: 5,
+    }));
+    let mut shared_storage =
+        SharedVmRunnerStorage::new(connection_pool.clone(), L2ChainId::default(), None).await?;
+    let fast_storage = shared_storage.add_io(fast_io.clone());
+    let slow_storage = shared_storage.add_io(slow_io.clone());
+    let task = shared_storage
+        .into_sync_task(db_dir.path().to_str().unwrap().to_owned())
+        .await?;
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    tokio::task::spawn(async move { task.run(stop_receiver).await.unwrap() });
+
+    for batch in &batches {
+        let fast_data = fast_storage.load_batch_eventually(batch.number).await?;
+        let slow_data = slow_storage.load_batch_eventually(batch.number).await?;
+        assert_eq!(fast_data.l1_batch_env.number, batch.number);
+        assert_eq!(slow_data.l2_blocks, fast_data.l2_blocks);
+    }
+
+    // Batches should be retained while one of the VM runners hasn't processed them.
+    fast_io.write().await.current += batches.len() as u32;
+    assert!(!slow_storage.batch_stays_unloaded(L1BatchNumber(1)).await);
+
+    slow_io.write().await.current += batches.len() as u32;
+    for batch in &batches {
+        fast_storage
+            .ensure_batch_unloads_eventually(batch.number)
+            .await?;
+        slow_storage
+            .ensure_batch_unloads_eventually(batch.number)
+            .await?;
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn postgres_only_vm_runner_storage() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = connection_pool.connection().await.unwrap();
+    let genesis_params = GenesisParams::mock();
+    insert_genesis_batch(&mut conn, &genesis_params)
+        .await
+        .unwrap();
+    drop(conn);
+    let alice = Account::random();
+    let bob = Account::random();
+    let mut accounts = vec![alice, bob];
+    fund(&connection_pool, &accounts).await;
+
+    let batches = store_l1_batches(
+        &mut connection_pool.connection().await?,
+        1..=3,
+        genesis_params.base_system_contracts().hashes(),
+        &mut accounts,
+    )
+    .await?;
+    let storage_logs = connection_pool
+        .connection()
+        .await?
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await;
+
+    let io_mock = Arc::new(RwLock::new(IoMock {
+        current: 0.into(),
+        max: 3,
+    }));
+    let vm_runner_storage = VmRunnerStorage::postgres_only(
+        connection_pool.clone(),
+        io_mock,
+        L2ChainId::default(),
+        None,
+    )
+    .await?;
+    for batch in &batches {
+        let batch_data = vm_runner_storage.load_batch(batch.number).await?.unwrap();
+        assert_eq!(batch_data.l1_batch_env.number, batch.number);
+        assert_eq!(batch_data.l1_batch_env.timestamp, batch.timestamp);
+    }
+
+    let (_sender, receiver) = watch::channel(false);
+    let rt_handle = Handle::current();
+    let handle = tokio::task::spawn_blocking(move || {
+        for i in 1..=3 {
+            let mut conn = rt_handle.block_on(connection_pool.connection()).unwrap();
+            let (_, last_l2_block_number) = rt_handle
+                .block_on(
+                    conn.blocks_dal()
+                        .get_l2_block_range_of_l1_batch(L1BatchNumber(i)),
+                )?
+                .unwrap();
+            let mut pg_storage =
+                PostgresStorage::new(rt_handle.clone(), conn, last_l2_block_number, true);
+            let mut vm_storage = rt_handle
+                .block_on(vm_runner_storage.access_storage(&receiver, L1BatchNumber(i)))?
+                .unwrap();
+            assert!(matches!(vm_storage, PgOrRocksdbStorage::Postgres(_)));
+            for storage_log in &storage_logs {
+                let storage_key =
+                    StorageKey::new(AccountTreeId::new(storage_log.address), storage_log.key);
+                assert_eq!(
+                    pg_storage.read_value(&storage_key),
+                    vm_storage.read_value(&storage_key)
+                );
+            }
+        }
+        anyhow::Ok(())
+    });
+    handle.await??;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn in_memory_vm_runner_storage() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = connection_pool.connection().await.unwrap();
+    let genesis_params = GenesisParams::mock();
+    insert_genesis_batch(&mut conn, &genesis_params)
+        .await
+        .unwrap();
+    drop(conn);
+    let mut accounts = vec![Account::random()];
+    fund(&connection_pool, &accounts).await;
+    store_l1_batches(
+        &mut connection_pool.connection().await?,
+        1..=2,
+        genesis_params.base_system_contracts().hashes(),
+        &mut accounts,
+    )
+    .await?;
+
+    let io_mock = Arc::new(RwLock::new(IoMock {
+        current: 0.into(),
+        max: 2,
+    }));
+    let pg_storage =
+        VmRunnerStorage::postgres_only(connection_pool, io_mock, L2ChainId::default(), None)
+            .await?;
+    let storage_key = StorageKey::new(AccountTreeId::default(), H256::repeat_byte(1));
+    let vm_runner_storage = InMemoryVmRunnerStorage::default();
+    for i in 1..=2 {
+        let batch_data = pg_storage.load_batch(L1BatchNumber(i)).await?.unwrap();
+        let mut storage = InMemoryStorage::default();
+        storage.set_value(storage_key, H256::from_low_u64_be(i.into()));
+        vm_runner_storage.insert_batch(batch_data, storage);
+    }
+
+    let (_sender, receiver) = watch::channel(false);
+    for i in 1..=2 {
+        let batch_data = vm_runner_storage
+            .load_batch(L1BatchNumber(i))
+            .await?
+            .unwrap();
+        assert_eq!(batch_data.l1_batch_env.number, L1BatchNumber(i));
+        let mut storage = vm_runner_storage
+            .access_storage(&receiver, L1BatchNumber(i))
+            .await?
+            .unwrap();
+        assert_eq!(
+            storage.read_value(&storage_key),
+            H256::from_low_u64_be(i.into())
+        );
+    }
+    assert!(vm_runner_storage
+        .access_storage(&receiver, L1BatchNumber(3))
+        .await?
+        .is_none());
+
+    vm_runner_storage.remove_batches_up_to(L1BatchNumber(1));
+    assert!(vm_runner_storage
+        .load_batch(L1BatchNumber(1))
+        .await?
+        .is_none());
+    assert!(vm_runner_storage
+        .load_batch(L1BatchNumber(2))
+        .await?
+        .is_some());
+    Ok(())
+}
@@ -0,0 +1,244 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::sync::{mpsc, watch, RwLock};
+use zksync_contracts::BaseSystemContractsHashes;
+use zksync_dal::{watermarks_dal::WatermarkComponent, ConnectionPool, Core, CoreDal};
+use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
+use zksync_node_test_utils::create_l1_batch;
+use zksync_test_account::Account;
+use zksync_types::{
+    protocol_upgrade::ProtocolVersion, protocol_version::ProtocolSemanticVersion, L1BatchNumber,
+    L2ChainId, ProtocolVersionId,
+};
+
+use crate::{
+    impls::upgrade_canary::UpgradeCanaryLoader,
+    storage::StorageLoader,
+    tests::{fund, store_l1_batches, IoMock},
+    UpgradeCanary, UpgradeCanaryIo, VmRunnerIo, VmRunnerStorage, VmRunnerStorageBackend,
+};
+
+fn next_protocol_version(
+    timestamp: u64,
+    base_system_contracts_hashes: BaseSystemContractsHashes,
+) -> ProtocolVersion {
+    ProtocolVersion {
+        version: ProtocolSemanticVersion {
+            minor: ProtocolVersionId::next(),
+            patch: 0.into(),
+        },
+        timestamp,
+        base_system_contracts_hashes,
+        ..ProtocolVersion::default()
+    }
+}
+
+#[tokio::test]
+async fn selecting_batches_for_upgrade_canary() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut conn, &GenesisParams::mock())
+        .await
+        .unwrap();
+    for number in 1..=4 {
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&create_l1_batch(number))
+            .await
+            .unwrap();
+    }
+
+    let io = UpgradeCanaryIo::new(L1BatchNumber(0), 2);
+    assert_eq!(
+        io.latest_processed_batch(&mut conn).await.unwrap(),
+        L1BatchNumber(0)
+    );
+    // No upgrade is scheduled yet.
+    assert!(!io
+        .should_process_batch(&mut conn, L1BatchNumber(1))
+        .await
+        .unwrap());
+
+    // Schedule an upgrade activated after batch #3 (mock batch timestamps equal batch numbers).
+    conn.protocol_versions_dal()
+        .save_protocol_version_with_tx(&next_protocol_version(4, Default::default()))
+        .await
+        .unwrap();
+
+    let mut selected_batches = vec![];
+    for number in 1..=4 {
+        let number = L1BatchNumber(number);
+        if io.should_process_batch(&mut conn, number).await.unwrap() {
+            selected_batches.push(number);
+        }
+    }
+    assert_eq!(selected_batches, [L1BatchNumber(1), L1BatchNumber(2)]);
+    // Selection is idempotent.
+    assert!(io
+        .should_process_batch(&mut conn, L1BatchNumber(2))
+        .await
+        .unwrap());
+
+    io.mark_l1_batch_as_completed(&mut conn, L1BatchNumber(2))
+        .await
+        .unwrap();
+
+    // Emulate a restart: selection and progress must be retained.
+    let io = UpgradeCanaryIo::new(L1BatchNumber(4), 2);
+    assert_eq!(
+        io.latest_processed_batch(&mut conn).await.unwrap(),
+        L1BatchNumber(2)
+    );
+    assert!(io
+        .should_process_batch(&mut conn, L1BatchNumber(1))
+        .await
+        .unwrap());
+    assert!(!io
+        .should_process_batch(&mut conn, L1BatchNumber(3))
+        .await
+        .unwrap());
+    let version = conn
+        .vm_runner_dal()
+        .get_upgrade_canary_batch_version(L1BatchNumber(2))
+        .await
+        .unwrap();
+    assert_eq!(version, Some(ProtocolVersionId::next()));
+}
+
+#[tokio::test]
+async fn loader_patches_protocol_version_and_base_system_contracts() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    let genesis_params = GenesisParams::mock();
+    insert_genesis_batch(&mut conn, &genesis_params)
+        .await
+        .unwrap();
+    let mut accounts = vec![Account::random()];
+    fund(&pool, &accounts).await;
+    let genesis_hashes = genesis_params.base_system_contracts().hashes();
+    store_l1_batches(&mut conn, 1..=2, genesis_hashes, &mut accounts)
+        .await
+        .unwrap();
+
+    // Swap genesis contracts, so that the patched contracts differ from the current ones, but are known to Postgres.
+    let next_hashes = BaseSystemContractsHashes {
+        bootloader: genesis_hashes.default_aa,
+        default_aa: genesis_hashes.bootloader,
+    };
+    conn.protocol_versions_dal()
+        .save_protocol_version_with_tx(&next_protocol_version(u32::MAX.into(), next_hashes))
+        .await
+        .unwrap();
+    let is_selected = conn
+        .vm_runner_dal()
+        .select_upgrade_canary_batch(L1BatchNumber(1), ProtocolVersionId::next(), 1)
+        .await
+        .unwrap();
+    assert!(is_selected);
+
+    let io = Arc::new(RwLock::new(IoMock {
+        current: L1BatchNumber(0),
+        max: 2,
+    }));
+    let storage = VmRunnerStorage::postgres_only(pool.clone(), io, L2ChainId::default(), None)
+        .await
+        .unwrap();
+    let loader = UpgradeCanaryLoader::new(Arc::new(storage), pool.clone());
+
+    let batch_data = loader.load_batch(L1BatchNumber(1)).await.unwrap().unwrap();
+    assert_eq!(batch_data.l1_batch_env.number, L1BatchNumber(1));
+    assert_eq!(batch_data.system_env.version, ProtocolVersionId::next());
+    let contracts = &batch_data.system_env.base_system_smart_contracts;
+    assert_eq!(contracts.hashes(), next_hashes);
+
+    // Batches not selected for shadow execution must not be loaded.
+    let err = loader
+        .load_batch(L1BatchNumber(2))
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("not selected"), "{err}");
+}
+
+#[tokio::test]
+async fn upgrade_canary_shadow_executes_batches_and_reports_divergences() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    let genesis_params = GenesisParams::mock();
+    insert_genesis_batch(&mut conn, &genesis_params)
+        .await
+        .unwrap();
+    let mut accounts = vec![Account::random(), Account::random()];
+    fund(&pool, &accounts).await;
+
+    let (report_sender, mut report_receiver) = mpsc::unbounded_channel();
+    // The canary starts from the last sealed batch, i.e., genesis.
+    let (upgrade_canary, tasks) = UpgradeCanary::new(
+        pool.clone(),
+        VmRunnerStorageBackend::PostgresOnly,
+        L2ChainId::default(),
+        None,
+        2,
+        Some(report_sender),
+    )
+    .await
+    .unwrap();
+
+    let genesis_hashes = genesis_params.base_system_contracts().hashes();
+    conn.protocol_versions_dal()
+        .save_protocol_version_with_tx(&next_protocol_version(u32::MAX.into(), genesis_hashes))
+        .await
+        .unwrap();
+    // Stored batches contain random storage writes that cannot be produced by executing their transactions.
+    store_l1_batches(&mut conn, 1..=4, genesis_hashes, &mut accounts)
+        .await
+        .unwrap();
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let loader_task = tokio::spawn(tasks.loader_task.run(stop_receiver.clone()));
+    let output_handler_task =
+        tokio::spawn(tasks.output_handler_factory_task.run(stop_receiver.clone()));
+    let canary_stop_receiver = stop_receiver.clone();
+    let canary_task = tokio::spawn(async move { upgrade_canary.run(&canary_stop_receiver).await });
+
+    let mut reports = vec![];
+    for _ in 0..2 {
+        let report = tokio::time::timeout(Duration::from_secs(30), report_receiver.recv())
+            .await
+            .expect("timed out waiting for upgrade canary report")
+            .unwrap();
+        reports.push(report);
+    }
+
+    // Batches not selected for shadow execution must be skipped, yet marked as processed.
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            let watermark = conn
+                .watermarks_dal()
+                .get_watermark(WatermarkComponent::UpgradeCanary)
+                .await
+                .unwrap();
+            if watermark == Some(L1BatchNumber(4)) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("timed out waiting for upgrade canary to process all batches");
+
+    stop_sender.send_replace(true);
+    canary_task.await.unwrap().unwrap();
+    output_handler_task.await.unwrap().unwrap();
+    loader_task.await.unwrap().unwrap();
+
+    let checked_batches: Vec<_> = reports
+        .iter()
+        .map(|report| report.divergences.l1_batch_number)
+        .collect();
+    assert_eq!(checked_batches, [L1BatchNumber(1), L1BatchNumber(2)]);
+    for report in &reports {
+        assert_eq!(report.protocol_version, ProtocolVersionId::next());
+        assert!(!report.divergences.is_empty(), "{report:?}");
+    }
+    assert!(report_receiver.try_recv().is_err());
+}
@@ -0,0 +1,22 @@
+use std::sync::Arc;
+
+use zksync_state_keeper::{BatchTracerFactory, BatchTracerOutput};
+use zksync_types::L1BatchNumber;
+
+/// Custom VM tracers attached by [`VmRunner`](crate::VmRunner) to all transactions in executed L1 batches,
+/// e.g. a storage access profiler or an opcode histogram tracer.
+///
+/// Since multiple batches can be executed concurrently, implementations should keep tracer results
+/// separately for each L1 batch.
+pub trait VmRunnerTracers: BatchTracerFactory {
+    /// Takes the output of tracers for the specified L1 batch. Called once the batch is finished, right before
+    /// it's passed to the output handler. The output is available to the handler via
+    /// [`UpdatesManager::tracer_output()`](zksync_state_keeper::UpdatesManager::tracer_output()).
+    fn take_output(&self, l1_batch_number: L1BatchNumber) -> Option<BatchTracerOutput>;
+
+    /// A workaround for Rust's limitations on upcasting coercion. See
+    /// https://github.com/rust-lang/rust/issues/65991.
+    ///
+    /// Should always be implementable as [`VmRunnerTracers`] requires [`BatchTracerFactory`].
+    fn upcast(self: Arc<Self>) -> Arc<dyn BatchTracerFactory>;
+}
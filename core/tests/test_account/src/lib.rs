@@ -13,11 +13,19 @@ use zksync_types::{
     l1::{OpProcessingType, PriorityQueueType},
     l2::L2Tx,
     utils::deployed_address_create,
+    web3::keccak256,
     Address, Execute, ExecuteTransactionCommon, K256PrivateKey, L1TxCommonData, L2ChainId, Nonce,
     PriorityOpId, Transaction, H256, U256,
 };
 use zksync_utils::bytecode::hash_bytecode;
 
+pub use crate::scenario::{
+    ExpectedOutcome, RpcScenarioExecutor, Scenario, ScenarioExecutor, ScenarioReport, ScenarioStep,
+    TxOutcome,
+};
+
+mod scenario;
+
 pub const L1_TEST_GAS_PER_PUBDATA_BYTE: u32 = 800;
 const BASE_FEE: u64 = 2_000_000_000;
 
@@ -37,6 +45,7 @@ pub enum TxType {
 #[derive(Debug, Clone)]
 pub struct Account {
     private_key: K256PrivateKey,
+    chain_id: L2ChainId,
     pub address: Address,
     pub nonce: Nonce,
 }
@@ -46,6 +55,7 @@ impl Account {
         let address = private_key.address();
         Self {
             private_key,
+            chain_id: L2ChainId::default(),
             address,
             nonce: Nonce(0),
         }
@@ -55,6 +65,26 @@ impl Account {
         Self::new(K256PrivateKey::random())
     }
 
+    /// Creates an account with the private key derived from the provided index. Unlike [`Self::random()`],
+    /// the account is the same across test runs, which makes test data (e.g., transaction hashes) reproducible.
+    pub fn deterministic(index: u32) -> Self {
+        let seed = [b"zksync_test_account".as_slice(), &index.to_be_bytes()].concat();
+        let private_key = K256PrivateKey::from_bytes(H256(keccak256(&seed)))
+            .expect("derived private key is invalid");
+        Self::new(private_key)
+    }
+
+    /// Sets the chain ID used to sign L2 transactions. By default, [`L2ChainId::default()`] is used.
+    #[must_use]
+    pub fn with_chain_id(mut self, chain_id: L2ChainId) -> Self {
+        self.chain_id = chain_id;
+        self
+    }
+
+    pub fn chain_id(&self) -> L2ChainId {
+        self.chain_id
+    }
+
     pub fn get_l2_tx_for_execute(&mut self, execute: Execute, fee: Option<Fee>) -> Transaction {
         let tx = self.get_l2_tx_for_execute_with_nonce(execute, fee, self.nonce);
         self.nonce += 1;
@@ -79,7 +109,7 @@ impl Account {
             nonce,
             fee.unwrap_or_else(|| self.default_fee()),
             value,
-            L2ChainId::default(),
+            self.chain_id,
             &self.private_key,
             factory_deps,
             Default::default(),
@@ -88,7 +118,7 @@ impl Account {
 
         // Set the real transaction hash, which is necessary for transaction execution in VM to function properly.
         let mut tx_request = api::TransactionRequest::from(tx.clone());
-        tx_request.chain_id = Some(L2ChainId::default().as_u64());
+        tx_request.chain_id = Some(self.chain_id.as_u64());
         let tx_hash = tx_request.get_tx_hash().unwrap();
         tx.set_input(H256::random().0.to_vec(), tx_hash);
         tx.into()
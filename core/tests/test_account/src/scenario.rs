@@ -0,0 +1,592 @@
+//! Declarative test scenarios, i.e. sequences of transactions together with their expected outcomes.
+
+use std::{collections::VecDeque, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use ethabi::Token;
+use zksync_types::{
+    api::TransactionRequest, fee::Fee, l2::L2Tx, web3::Bytes, Address, Execute,
+    ExecuteTransactionCommon, PackedEthSignature, Transaction, U256,
+};
+use zksync_web3_decl::{
+    client::{DynClient, L2},
+    jsonrpsee::core::ClientError,
+    namespaces::EthNamespaceClient,
+};
+
+use crate::{Account, TxType};
+
+/// Expected outcome of a [`ScenarioStep`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpectedOutcome {
+    /// Transaction is included and executed successfully.
+    Success,
+    /// Transaction is included, but reverted.
+    Revert,
+    /// Transaction is not included (e.g., rejected by the VM or the RPC server).
+    Rejection,
+}
+
+/// Actual outcome of executing a transaction by a [`ScenarioExecutor`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TxOutcome {
+    /// Transaction is included and executed successfully.
+    Success,
+    /// Transaction is included, but reverted.
+    Reverted { reason: String },
+    /// Transaction is not included.
+    Rejected { reason: String },
+}
+
+impl TxOutcome {
+    /// Checks whether this outcome corresponds to the expected one.
+    pub fn matches(&self, expected: ExpectedOutcome) -> bool {
+        matches!(
+            (self, expected),
+            (Self::Success, ExpectedOutcome::Success)
+                | (Self::Reverted { .. }, ExpectedOutcome::Revert)
+                | (Self::Rejected { .. }, ExpectedOutcome::Rejection)
+        )
+    }
+}
+
+/// Executor of scenario transactions, e.g. an in-process batch executor or a live RPC endpoint.
+#[async_trait]
+pub trait ScenarioExecutor: Send {
+    /// Executes a single transaction and returns its outcome.
+    ///
+    /// # Errors
+    ///
+    /// Should return an error only if the outcome cannot be determined (e.g., because of a network error);
+    /// transaction rejections should be returned as [`TxOutcome::Rejected`].
+    async fn execute_transaction(&mut self, tx: Transaction) -> anyhow::Result<TxOutcome>;
+}
+
+/// Step of a [`Scenario`]. Accounts are referenced by their index in the scenario.
+#[derive(Debug, Clone)]
+pub enum ScenarioStep {
+    /// Transfers base token from an account to the specified address.
+    Transfer {
+        from: usize,
+        to: Address,
+        value: U256,
+    },
+    /// Deploys a contract from an account.
+    Deploy {
+        from: usize,
+        bytecode: Vec<u8>,
+        constructor_args: Option<Vec<Token>>,
+    },
+    /// Executes an arbitrary L2 transaction from an account.
+    Execute { from: usize, execute: Execute },
+    /// Executes an L1-to-L2 transaction from an account.
+    L1Execute { from: usize, execute: Execute },
+}
+
+impl ScenarioStep {
+    /// Returns the index of the account sending the step transaction.
+    pub fn sender(&self) -> usize {
+        match self {
+            Self::Transfer { from, .. }
+            | Self::Deploy { from, .. }
+            | Self::Execute { from, .. }
+            | Self::L1Execute { from, .. } => *from,
+        }
+    }
+}
+
+/// Declarative sequence of transactions together with their expected outcomes.
+///
+/// Scenarios are built using a fluent interface; each added step is expected to succeed by default,
+/// which can be overridden using [`Self::expecting()`].
+#[derive(Debug, Clone)]
+pub struct Scenario {
+    accounts: Vec<Account>,
+    steps: VecDeque<(ScenarioStep, ExpectedOutcome)>,
+    next_priority_op_id: u64,
+    l2_fee: Option<Fee>,
+}
+
+impl Scenario {
+    /// Creates an empty scenario with the specified accounts.
+    pub fn new(accounts: Vec<Account>) -> Self {
+        Self {
+            accounts,
+            steps: VecDeque::new(),
+            next_priority_op_id: 0,
+            l2_fee: None,
+        }
+    }
+
+    /// Creates an empty scenario with the specified number of [deterministic](Account::deterministic()) accounts.
+    pub fn with_deterministic_accounts(count: u32) -> Self {
+        Self::new((0..count).map(Account::deterministic).collect())
+    }
+
+    /// Sets the serial ID of the first L1-to-L2 transaction in the scenario. By default, it's 0.
+    #[must_use]
+    pub fn with_first_priority_op_id(mut self, serial_id: u64) -> Self {
+        self.next_priority_op_id = serial_id;
+        self
+    }
+
+    /// Sets the fee for L2 transfers and executions in the scenario. By default, the [`Account`] default fee is used.
+    /// Deployments always use the default fee.
+    #[must_use]
+    pub fn with_l2_fee(mut self, fee: Fee) -> Self {
+        self.l2_fee = Some(fee);
+        self
+    }
+
+    /// Returns accounts participating in the scenario.
+    pub fn accounts(&self) -> &[Account] {
+        &self.accounts
+    }
+
+    /// Returns the address of the account with the specified index.
+    pub fn address(&self, account: usize) -> Address {
+        self.accounts[account].address
+    }
+
+    /// Returns steps that were added, but haven't been run yet.
+    pub fn pending_steps(&self) -> impl Iterator<Item = &ScenarioStep> + '_ {
+        self.steps.iter().map(|(step, _)| step)
+    }
+
+    fn push(mut self, step: ScenarioStep) -> Self {
+        let from = step.sender();
+        assert!(
+            from < self.accounts.len(),
+            "account #{from} is not defined in the scenario"
+        );
+        self.steps.push_back((step, ExpectedOutcome::Success));
+        self
+    }
+
+    /// Adds a base token transfer.
+    #[must_use]
+    pub fn transfer(self, from: usize, to: Address, value: U256) -> Self {
+        self.push(ScenarioStep::Transfer { from, to, value })
+    }
+
+    /// Adds a contract deployment.
+    #[must_use]
+    pub fn deploy(
+        self,
+        from: usize,
+        bytecode: Vec<u8>,
+        constructor_args: Option<Vec<Token>>,
+    ) -> Self {
+        self.push(ScenarioStep::Deploy {
+            from,
+            bytecode,
+            constructor_args,
+        })
+    }
+
+    /// Adds an arbitrary L2 transaction.
+    #[must_use]
+    pub fn execute(self, from: usize, execute: Execute) -> Self {
+        self.push(ScenarioStep::Execute { from, execute })
+    }
+
+    /// Adds an L1-to-L2 transaction.
+    #[must_use]
+    pub fn l1_execute(self, from: usize, execute: Execute) -> Self {
+        self.push(ScenarioStep::L1Execute { from, execute })
+    }
+
+    /// Sets the expected outcome for the last added step.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scenario has no steps.
+    #[must_use]
+    pub fn expecting(mut self, outcome: ExpectedOutcome) -> Self {
+        let (_, expected) = self
+            .steps
+            .back_mut()
+            .expect("scenario has no steps to set expectation for");
+        *expected = outcome;
+        self
+    }
+
+    fn create_transaction(&mut self, step: &ScenarioStep) -> (Transaction, Option<Address>) {
+        match step {
+            ScenarioStep::Transfer { from, to, value } => {
+                let execute = Execute {
+                    contract_address: *to,
+                    calldata: vec![],
+                    value: *value,
+                    factory_deps: None,
+                };
+                let tx = self.accounts[*from].get_l2_tx_for_execute(execute, self.l2_fee.clone());
+                (tx, None)
+            }
+            ScenarioStep::Deploy {
+                from,
+                bytecode,
+                constructor_args,
+            } => {
+                let deploy_tx = self.accounts[*from].get_deploy_tx(
+                    bytecode,
+                    constructor_args.as_deref(),
+                    TxType::L2,
+                );
+                (deploy_tx.tx, Some(deploy_tx.address))
+            }
+            ScenarioStep::Execute { from, execute } => {
+                let tx = self.accounts[*from]
+                    .get_l2_tx_for_execute(execute.clone(), self.l2_fee.clone());
+                (tx, None)
+            }
+            ScenarioStep::L1Execute { from, execute } => {
+                let serial_id = self.next_priority_op_id;
+                self.next_priority_op_id += 1;
+                let tx = self.accounts[*from].get_l1_tx(execute.clone(), serial_id);
+                (tx, None)
+            }
+        }
+    }
+
+    /// Runs all pending steps of this scenario on the provided executor, checking that outcomes match expectations.
+    /// Account nonces are updated as the scenario progresses, so the scenario can be extended and run again
+    /// to continue from the reached state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the executor fails or if an outcome doesn't match the expectation. The failed step
+    /// is consumed (its transaction may have been executed), while the following steps remain
+    /// [pending](Self::pending_steps()).
+    pub async fn run(
+        &mut self,
+        executor: &mut dyn ScenarioExecutor,
+    ) -> anyhow::Result<ScenarioReport> {
+        let mut report = ScenarioReport::default();
+        while let Some((step, expected)) = self.steps.pop_front() {
+            let i = report.transactions.len();
+            let (tx, deployed_address) = self.create_transaction(&step);
+            let outcome = executor
+                .execute_transaction(tx.clone())
+                .await
+                .with_context(|| format!("failed executing scenario step #{i}: {step:?}"))?;
+            let is_rejected = matches!(outcome, TxOutcome::Rejected { .. });
+            if is_rejected {
+                // Rejected L2 transactions don't consume the account nonce.
+                if let ExecuteTransactionCommon::L2(_) = &tx.common_data {
+                    self.accounts[step.sender()].nonce -= 1;
+                }
+            }
+            anyhow::ensure!(
+                outcome.matches(expected),
+                "unexpected outcome of scenario step #{i} ({step:?}): expected {expected:?}, got {outcome:?}"
+            );
+
+            if let Some(address) = deployed_address.filter(|_| !is_rejected) {
+                report.deployed_contracts.push(address);
+            }
+            report.transactions.push((tx, outcome));
+        }
+        Ok(report)
+    }
+}
+
+/// Report produced by [`Scenario::run()`].
+#[derive(Debug, Default)]
+pub struct ScenarioReport {
+    /// Executed transactions together with their outcomes, in the execution order.
+    pub transactions: Vec<(Transaction, TxOutcome)>,
+    /// Addresses of contracts deployed by non-rejected deployment steps.
+    pub deployed_contracts: Vec<Address>,
+}
+
+/// [`ScenarioExecutor`] submitting transactions to a live RPC endpoint and waiting for their receipts.
+/// L1-to-L2 transactions are not supported since they cannot be submitted via L2 RPC.
+#[derive(Debug)]
+pub struct RpcScenarioExecutor {
+    client: Box<DynClient<L2>>,
+    poll_interval: Duration,
+    receipt_timeout: Duration,
+}
+
+impl RpcScenarioExecutor {
+    /// Creates an executor with the specified client. Receipts are polled every 100ms for up to 60s.
+    pub fn new(client: Box<DynClient<L2>>) -> Self {
+        Self {
+            client,
+            poll_interval: Duration::from_millis(100),
+            receipt_timeout: Duration::from_secs(60),
+        }
+    }
+
+    /// Sets the timeout for waiting for a transaction receipt.
+    #[must_use]
+    pub fn with_receipt_timeout(mut self, timeout: Duration) -> Self {
+        self.receipt_timeout = timeout;
+        self
+    }
+
+    fn encode_transaction(tx: Transaction) -> anyhow::Result<Vec<u8>> {
+        let tx = L2Tx::try_from(tx).map_err(anyhow::Error::msg)?;
+        let chain_id = tx.common_data.extract_chain_id();
+        let signature = PackedEthSignature::deserialize_packed(&tx.common_data.signature)
+            .context("malformed transaction signature")?;
+        let mut tx_request = TransactionRequest::from(tx);
+        tx_request.chain_id = chain_id;
+        tx_request
+            .get_signed_bytes(&signature)
+            .context("failed encoding transaction")
+    }
+}
+
+#[async_trait]
+impl ScenarioExecutor for RpcScenarioExecutor {
+    async fn execute_transaction(&mut self, tx: Transaction) -> anyhow::Result<TxOutcome> {
+        anyhow::ensure!(
+            matches!(tx.common_data, ExecuteTransactionCommon::L2(_)),
+            "only L2 transactions can be submitted via RPC"
+        );
+        let raw_tx = Self::encode_transaction(tx)?;
+        let tx_hash = match self.client.send_raw_transaction(Bytes(raw_tx)).await {
+            Ok(hash) => hash,
+            // Only errors returned by the server signal transaction rejection; transport errors etc. leave
+            // the transaction status unknown.
+            Err(ClientError::Call(err)) => {
+                return Ok(TxOutcome::Rejected {
+                    reason: err.message().to_owned(),
+                })
+            }
+            Err(err) => return Err(anyhow::Error::new(err).context("failed sending transaction")),
+        };
+
+        let receipt = tokio::time::timeout(self.receipt_timeout, async {
+            loop {
+                if let Some(receipt) = self.client.get_transaction_receipt(tx_hash).await? {
+                    return anyhow::Ok(receipt);
+                }
+                tokio::time::sleep(self.poll_interval).await;
+            }
+        })
+        .await
+        .with_context(|| format!("timed out waiting for receipt of transaction {tx_hash:?}"))??;
+
+        Ok(if receipt.status.as_u64() == 1 {
+            TxOutcome::Success
+        } else {
+            TxOutcome::Reverted {
+                reason: format!("transaction {tx_hash:?} has failed status"),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use zksync_types::{api, Nonce, H256, U64};
+    use zksync_web3_decl::{
+        client::MockClient,
+        jsonrpsee::types::{error::ErrorCode, ErrorObject},
+    };
+
+    use super::*;
+
+    /// Executor returning predefined outcomes (or an error, if outcomes are exhausted).
+    #[derive(Debug, Default)]
+    struct MockExecutor {
+        outcomes: VecDeque<TxOutcome>,
+        executed_transactions: Vec<Transaction>,
+    }
+
+    #[async_trait]
+    impl ScenarioExecutor for MockExecutor {
+        async fn execute_transaction(&mut self, tx: Transaction) -> anyhow::Result<TxOutcome> {
+            let outcome = self
+                .outcomes
+                .pop_front()
+                .context("emulated executor error")?;
+            self.executed_transactions.push(tx);
+            Ok(outcome)
+        }
+    }
+
+    fn transfer_scenario() -> Scenario {
+        let scenario = Scenario::with_deterministic_accounts(2);
+        let recipient = scenario.address(1);
+        scenario
+            .transfer(0, recipient, 1.into())
+            .transfer(0, recipient, 2.into())
+            .expecting(ExpectedOutcome::Rejection)
+            .transfer(0, recipient, 3.into())
+            .transfer(1, Address::repeat_byte(1), 4.into())
+    }
+
+    #[tokio::test]
+    async fn running_scenario() {
+        let mut scenario = transfer_scenario();
+        let mut executor = MockExecutor {
+            outcomes: VecDeque::from([
+                TxOutcome::Success,
+                TxOutcome::Rejected {
+                    reason: "oops".to_owned(),
+                },
+                TxOutcome::Success,
+                TxOutcome::Success,
+            ]),
+            ..MockExecutor::default()
+        };
+        let report = scenario.run(&mut executor).await.unwrap();
+
+        assert_eq!(report.transactions.len(), 4);
+        assert!(report.deployed_contracts.is_empty());
+        assert_eq!(scenario.pending_steps().count(), 0);
+        // The rejected transaction must not consume the nonce.
+        let nonces: Vec<_> = executor
+            .executed_transactions
+            .iter()
+            .map(|tx| tx.nonce().unwrap())
+            .collect();
+        assert_eq!(nonces, [Nonce(0), Nonce(1), Nonce(1), Nonce(0)]);
+        assert_eq!(scenario.accounts()[0].nonce, Nonce(2));
+        assert_eq!(scenario.accounts()[1].nonce, Nonce(1));
+    }
+
+    #[tokio::test]
+    async fn scenario_steps_are_retained_on_error() {
+        let mut scenario = transfer_scenario();
+        let mut executor = MockExecutor {
+            outcomes: VecDeque::from([TxOutcome::Success, TxOutcome::Success]),
+            ..MockExecutor::default()
+        };
+        let err = scenario.run(&mut executor).await.unwrap_err();
+        assert!(err.to_string().contains("step #1"), "{err:#}");
+        // The failed step is consumed; the following ones are retained.
+        assert_eq!(scenario.pending_steps().count(), 2);
+
+        executor.outcomes = VecDeque::from([TxOutcome::Success, TxOutcome::Success]);
+        let report = scenario.run(&mut executor).await.unwrap();
+        assert_eq!(report.transactions.len(), 2);
+        assert_eq!(scenario.pending_steps().count(), 0);
+
+        // Errors from the executor also retain the following steps.
+        let recipient = scenario.address(1);
+        let mut scenario =
+            scenario
+                .transfer(0, recipient, 1.into())
+                .transfer(0, recipient, 2.into());
+        let err = scenario.run(&mut executor).await.unwrap_err();
+        assert!(
+            format!("{err:#}").contains("emulated executor error"),
+            "{err:#}"
+        );
+        assert_eq!(scenario.pending_steps().count(), 1);
+    }
+
+    fn mock_client(
+        send_result: impl Fn() -> Result<H256, ClientError> + Send + Sync + 'static,
+        receipt_status: u64,
+    ) -> (Box<DynClient<L2>>, Arc<Mutex<Vec<H256>>>) {
+        let requested_receipts = Arc::<Mutex<Vec<H256>>>::default();
+        let receipts = requested_receipts.clone();
+        let client = MockClient::builder(L2::default())
+            .method("eth_sendRawTransaction", move |_: Bytes| send_result())
+            .method("eth_getTransactionReceipt", move |hash: H256| {
+                let mut receipts = receipts.lock().unwrap();
+                receipts.push(hash);
+                // Emulate a transaction included on the second poll.
+                Ok((receipts.len() > 1).then(|| api::TransactionReceipt {
+                    transaction_hash: hash,
+                    status: U64::from(receipt_status),
+                    ..api::TransactionReceipt::default()
+                }))
+            })
+            .build();
+        (Box::new(client), requested_receipts)
+    }
+
+    fn rpc_executor(client: Box<DynClient<L2>>) -> RpcScenarioExecutor {
+        let mut executor =
+            RpcScenarioExecutor::new(client).with_receipt_timeout(Duration::from_secs(5));
+        executor.poll_interval = Duration::from_millis(10);
+        executor
+    }
+
+    #[tokio::test]
+    async fn rpc_executor_basics() {
+        let tx_hash = H256::repeat_byte(0x11);
+        let (client, requested_receipts) = mock_client(move || Ok(tx_hash), 1);
+        let mut scenario = transfer_scenario();
+        let tx = scenario.create_transaction(&ScenarioStep::Transfer {
+            from: 0,
+            to: Address::repeat_byte(1),
+            value: 1.into(),
+        });
+
+        let outcome = rpc_executor(client)
+            .execute_transaction(tx.0)
+            .await
+            .unwrap();
+        assert_eq!(outcome, TxOutcome::Success);
+        assert_eq!(*requested_receipts.lock().unwrap(), [tx_hash; 2]);
+
+        let (client, _) = mock_client(move || Ok(tx_hash), 0);
+        let tx = scenario.create_transaction(&ScenarioStep::Transfer {
+            from: 0,
+            to: Address::repeat_byte(1),
+            value: 1.into(),
+        });
+        let outcome = rpc_executor(client)
+            .execute_transaction(tx.0)
+            .await
+            .unwrap();
+        assert!(matches!(outcome, TxOutcome::Reverted { .. }), "{outcome:?}");
+    }
+
+    #[tokio::test]
+    async fn rpc_executor_errors() {
+        let mut scenario = transfer_scenario();
+        let step = ScenarioStep::Transfer {
+            from: 0,
+            to: Address::repeat_byte(1),
+            value: 1.into(),
+        };
+
+        let (client, requested_receipts) = mock_client(
+            || {
+                Err(ClientError::Call(ErrorObject::owned(
+                    ErrorCode::InvalidParams.code(),
+                    "insufficient balance",
+                    None::<()>,
+                )))
+            },
+            1,
+        );
+        let (tx, _) = scenario.create_transaction(&step);
+        let outcome = rpc_executor(client).execute_transaction(tx).await.unwrap();
+        assert_eq!(
+            outcome,
+            TxOutcome::Rejected {
+                reason: "insufficient balance".to_owned()
+            }
+        );
+        assert!(requested_receipts.lock().unwrap().is_empty());
+
+        // Transport errors must not be treated as rejections.
+        let (client, _) = mock_client(|| Err(ClientError::RequestTimeout), 1);
+        let (tx, _) = scenario.create_transaction(&step);
+        rpc_executor(client)
+            .execute_transaction(tx)
+            .await
+            .unwrap_err();
+
+        let (client, _) = mock_client(|| Ok(H256::zero()), 1);
+        let l1_tx = scenario.accounts[0].get_l1_tx(Execute::default(), 0);
+        let err = rpc_executor(client)
+            .execute_transaction(l1_tx)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("only L2 transactions"), "{err:#}");
+    }
+}
@@ -8,17 +8,20 @@ use zksync_config::{
         },
         fri_prover_group::FriProverGroupConfig,
         house_keeper::HouseKeeperConfig,
-        DatabaseSecrets, FriProofCompressorConfig, FriProverConfig, FriProverGatewayConfig,
+        BasicWitnessInputProducerConfig, DatabaseSecrets, DiskSpaceMonitorConfig,
+        FeatureFlagsConfig, FriProofCompressorConfig, FriProverConfig, FriProverGatewayConfig,
         FriWitnessGeneratorConfig, FriWitnessVectorGeneratorConfig, GeneralConfig,
         ObjectStoreConfig, ObservabilityConfig, PrometheusConfig, ProofDataHandlerConfig,
-        ProtectiveReadsWriterConfig,
+        ProtectiveReadsWriterConfig, SchedulerConfig,
     },
     ApiConfig, ContractVerifierConfig, DBConfig, EthConfig, EthWatchConfig, GasAdjusterConfig,
     PostgresConfig, SnapshotsCreatorConfig,
 };
-use zksync_core_leftovers::temp_config_store::{decode_yaml_repr, TempConfigStore};
+use zksync_core_leftovers::temp_config_store::{
+    decode_yaml_repr, decode_yaml_repr_with_keystores, TempConfigStore,
+};
 use zksync_env_config::FromEnv;
-use zksync_protobuf_config::proto::secrets::Secrets;
+use zksync_protobuf_config::{keystore::KeystorePassphrase, proto::secrets::Secrets};
 
 fn load_env_config() -> anyhow::Result<TempConfigStore> {
     Ok(TempConfigStore {
@@ -50,6 +53,10 @@ fn load_env_config() -> anyhow::Result<TempConfigStore> {
         snapshot_creator: SnapshotsCreatorConfig::from_env().ok(),
         protective_reads_writer_config: ProtectiveReadsWriterConfig::from_env().ok(),
         core_object_store: ObjectStoreConfig::from_env().ok(),
+        basic_witness_input_producer_config: BasicWitnessInputProducerConfig::from_env().ok(),
+        disk_space_monitor_config: DiskSpaceMonitorConfig::from_env().ok(),
+        scheduler_config: SchedulerConfig::from_env().ok(),
+        feature_flags_config: FeatureFlagsConfig::from_env().ok(),
     })
 }
 
@@ -69,7 +76,9 @@ pub fn load_database_secrets(path: Option<std::path::PathBuf>) -> anyhow::Result
     match path {
         Some(path) => {
             let yaml = std::fs::read_to_string(path).context("Failed to read secrets")?;
-            let secrets = decode_yaml_repr::<Secrets>(&yaml).context("Failed to parse secrets")?;
+            let secrets =
+                decode_yaml_repr_with_keystores::<Secrets>(&yaml, KeystorePassphrase::from_env)
+                    .context("Failed to parse secrets")?;
             Ok(secrets
                 .database
                 .context("failed to parse database secrets")?)
@@ -3,7 +3,7 @@ use std::{sync::Arc, time::Instant};
 use anyhow::Context as _;
 use async_trait::async_trait;
 use circuit_sequencer_api::proof::FinalProof;
-use prover_dal::{ConnectionPool, Prover, ProverDal};
+use prover_dal::{fri_jobs_dal::FriJobQueue, ConnectionPool, Prover, ProverDal};
 use tokio::task::JoinHandle;
 #[cfg(feature = "gpu")]
 use wrapper_prover::{Bn256, GPUWrapperConfigs, WrapperProver, DEFAULT_WRAPPER_CONFIG};
@@ -33,8 +33,9 @@ use zksync_prover_fri_types::{
     },
     get_current_pod_name, AuxOutputWitnessWrapper, FriProofWrapper,
 };
+use zksync_prover_fri_utils::job_queue::FriJobQueueStorage;
 use zksync_prover_interface::outputs::L1BatchProofForL1;
-use zksync_queued_job_processor::JobProcessor;
+use zksync_queued_job_processor::QueuedJobProcessor;
 use zksync_types::{protocol_version::ProtocolSemanticVersion, L1BatchNumber};
 use zksync_vk_setup_data_server_fri::keystore::Keystore;
 
@@ -43,6 +44,7 @@ use crate::metrics::METRICS;
 pub struct ProofCompressor {
     blob_store: Arc<dyn ObjectStore>,
     pool: ConnectionPool<Prover>,
+    job_queue: FriJobQueueStorage<L1BatchNumber>,
     compression_mode: u8,
     verify_wrapper_proof: bool,
     max_attempts: u32,
@@ -60,6 +62,7 @@ impl ProofCompressor {
     ) -> Self {
         Self {
             blob_store,
+            job_queue: FriJobQueueStorage::new(pool.clone(), FriJobQueue::ProofCompressor),
             pool,
             compression_mode,
             verify_wrapper_proof,
@@ -155,12 +158,17 @@ impl ProofCompressor {
 }
 
 #[async_trait]
-impl JobProcessor for ProofCompressor {
+impl QueuedJobProcessor for ProofCompressor {
     type Job = ZkSyncRecursionLayerProof;
     type JobId = L1BatchNumber;
     type JobArtifacts = FinalProof;
+    type Queue = FriJobQueueStorage<Self::JobId>;
     const SERVICE_NAME: &'static str = "ProofCompressor";
 
+    fn job_queue(&self) -> &Self::Queue {
+        &self.job_queue
+    }
+
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
         let mut conn = self.pool.connection().await.unwrap();
         let pod_name = get_current_pod_name();
@@ -196,16 +204,6 @@ impl JobProcessor for ProofCompressor {
         Ok(Some((l1_batch_number, scheduler_proof)))
     }
 
-    async fn save_failure(&self, job_id: Self::JobId, _started_at: Instant, error: String) {
-        self.pool
-            .connection()
-            .await
-            .unwrap()
-            .fri_proof_compressor_dal()
-            .mark_proof_compression_job_failed(&error, job_id)
-            .await;
-    }
-
     async fn process_job(
         &self,
         job_id: &L1BatchNumber,
@@ -268,18 +266,4 @@ impl JobProcessor for ProofCompressor {
     fn max_attempts(&self) -> u32 {
         self.max_attempts
     }
-
-    async fn get_job_attempts(&self, job_id: &L1BatchNumber) -> anyhow::Result<u32> {
-        let mut prover_storage = self
-            .pool
-            .connection()
-            .await
-            .context("failed to acquire DB connection for ProofCompressor")?;
-        prover_storage
-            .fri_proof_compressor_dal()
-            .get_proof_compression_job_attempts(*job_id)
-            .await
-            .map(|attempts| attempts.unwrap_or(0))
-            .context("failed to get job attempts for ProofCompressor")
-    }
 }
@@ -1,7 +1,9 @@
 use clap::{command, Args, Parser, Subcommand};
 use zksync_types::url::SensitiveUrl;
 
-use crate::commands::{self, config, debug_proof, delete, get_file_info, requeue, restart};
+use crate::commands::{
+    self, config, debug_proof, delete, get_file_info, requeue, restart, restore_archived,
+};
 
 pub const VERSION_STRING: &str = env!("CARGO_PKG_VERSION");
 
@@ -35,6 +37,7 @@ enum ProverCommand {
     Status(commands::StatusCommand),
     Requeue(requeue::Args),
     Restart(restart::Args),
+    RestoreArchived(restore_archived::Args),
 }
 
 pub async fn start() -> anyhow::Result<()> {
@@ -46,6 +49,7 @@ pub async fn start() -> anyhow::Result<()> {
         ProverCommand::Status(cmd) => cmd.run(config).await?,
         ProverCommand::Requeue(args) => requeue::run(args, config).await?,
         ProverCommand::Restart(args) => restart::run(args).await?,
+        ProverCommand::RestoreArchived(args) => restore_archived::run(args, config).await?,
         ProverCommand::DebugProof(args) => debug_proof::run(args).await?,
     };
 
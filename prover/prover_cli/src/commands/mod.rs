@@ -4,5 +4,6 @@ pub(crate) mod delete;
 pub(crate) mod get_file_info;
 pub(crate) mod requeue;
 pub(crate) mod restart;
+pub(crate) mod restore_archived;
 pub(crate) mod status;
 pub(crate) use status::StatusCommand;
@@ -0,0 +1,45 @@
+use anyhow::Context;
+use clap::Args as ClapArgs;
+use prover_dal::{ConnectionPool, Prover, ProverDal};
+use zksync_types::L1BatchNumber;
+
+use crate::cli::ProverCLIConfig;
+
+#[derive(ClapArgs)]
+pub struct Args {
+    /// First batch (inclusive) to restore archived prover jobs for.
+    #[clap(long)]
+    from_batch: L1BatchNumber,
+    /// Last batch (inclusive) to restore archived prover jobs for. Defaults to `from_batch`.
+    #[clap(long)]
+    to_batch: Option<L1BatchNumber>,
+}
+
+pub async fn run(args: Args, config: ProverCLIConfig) -> anyhow::Result<()> {
+    let to_batch = args.to_batch.unwrap_or(args.from_batch);
+    anyhow::ensure!(
+        args.from_batch <= to_batch,
+        "invalid batch range: {}..={to_batch}",
+        args.from_batch
+    );
+
+    let pool = ConnectionPool::<Prover>::singleton(config.db_url)
+        .build()
+        .await
+        .context("failed to build a prover_connection_pool")?;
+    let mut conn = pool
+        .connection()
+        .await
+        .context("failed to acquire a connection")?;
+
+    let restored_jobs = conn
+        .fri_prover_jobs_dal()
+        .restore_archived_jobs(args.from_batch..=to_batch)
+        .await
+        .context("restore_archived_jobs()")?;
+    println!(
+        "Restored {restored_jobs} archived prover jobs for batches {}..={to_batch}",
+        args.from_batch
+    );
+    Ok(())
+}
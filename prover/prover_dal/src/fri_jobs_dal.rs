@@ -0,0 +1,1069 @@
+#![doc = include_str!("../doc/FriJobsDal.md")]
+use std::{fmt, time::Duration};
+
+use zksync_basic_types::prover_dal::{JobState, JobStateCounts, StuckJobs};
+use zksync_db_connection::connection::Connection;
+
+use crate::{pg_interval_from_duration, Prover};
+
+/// Queue of jobs processed by one of the prover components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FriJobQueue {
+    WitnessInputs,
+    LeafAggregation,
+    NodeAggregation,
+    RecursionTip,
+    Scheduler,
+    Prover,
+    ProofCompressor,
+}
+
+impl FriJobQueue {
+    pub const ALL: [Self; 7] = [
+        Self::WitnessInputs,
+        Self::LeafAggregation,
+        Self::NodeAggregation,
+        Self::RecursionTip,
+        Self::Scheduler,
+        Self::Prover,
+        Self::ProofCompressor,
+    ];
+
+    pub fn table_name(self) -> &'static str {
+        match self {
+            Self::WitnessInputs => "witness_inputs_fri",
+            Self::LeafAggregation => "leaf_aggregation_witness_jobs_fri",
+            Self::NodeAggregation => "node_aggregation_witness_jobs_fri",
+            Self::RecursionTip => "recursion_tip_witness_jobs_fri",
+            Self::Scheduler => "scheduler_witness_jobs_fri",
+            Self::Prover => "prover_jobs_fri",
+            Self::ProofCompressor => "proof_compression_jobs_fri",
+        }
+    }
+}
+
+impl fmt::Display for FriJobQueue {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.table_name())
+    }
+}
+
+/// Claim on an in-progress job in one of [`FriJobQueue`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FriJobClaim {
+    pub picked_by: Option<String>,
+    pub attempts: u32,
+}
+
+#[derive(Debug)]
+pub struct FriJobsDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Prover>,
+}
+
+impl FriJobsDal<'_, '_> {
+    /// Marks the job as failed. `id` is either the job ID or the L1 batch number, depending on the queue.
+    pub async fn mark_job_failed(
+        &mut self,
+        queue: FriJobQueue,
+        id: u64,
+        error: &str,
+    ) -> sqlx::Result<()> {
+        let id = id as i64;
+        match queue {
+            FriJobQueue::WitnessInputs => {
+                sqlx::query!(
+                    r#"
+                    UPDATE witness_inputs_fri
+                    SET
+                        status = 'failed',
+                        error = $1,
+                        updated_at = NOW()
+                    WHERE
+                        l1_batch_number = $2
+                    "#,
+                    error,
+                    id
+                )
+                .execute(self.storage.conn())
+                .await?
+            }
+            FriJobQueue::LeafAggregation => {
+                sqlx::query!(
+                    r#"
+                    UPDATE leaf_aggregation_witness_jobs_fri
+                    SET
+                        status = 'failed',
+                        error = $1,
+                        updated_at = NOW()
+                    WHERE
+                        id = $2
+                    "#,
+                    error,
+                    id
+                )
+                .execute(self.storage.conn())
+                .await?
+            }
+            FriJobQueue::NodeAggregation => {
+                sqlx::query!(
+                    r#"
+                    UPDATE node_aggregation_witness_jobs_fri
+                    SET
+                        status = 'failed',
+                        error = $1,
+                        updated_at = NOW()
+                    WHERE
+                        id = $2
+                    "#,
+                    error,
+                    id
+                )
+                .execute(self.storage.conn())
+                .await?
+            }
+            FriJobQueue::RecursionTip => {
+                sqlx::query!(
+                    r#"
+                    UPDATE recursion_tip_witness_jobs_fri
+                    SET
+                        status = 'failed',
+                        error = $1,
+                        updated_at = NOW()
+                    WHERE
+                        l1_batch_number = $2
+                    "#,
+                    error,
+                    id
+                )
+                .execute(self.storage.conn())
+                .await?
+            }
+            FriJobQueue::Scheduler => {
+                sqlx::query!(
+                    r#"
+                    UPDATE scheduler_witness_jobs_fri
+                    SET
+                        status = 'failed',
+                        error = $1,
+                        updated_at = NOW()
+                    WHERE
+                        l1_batch_number = $2
+                    "#,
+                    error,
+                    id
+                )
+                .execute(self.storage.conn())
+                .await?
+            }
+            FriJobQueue::Prover => {
+                sqlx::query!(
+                    r#"
+                    UPDATE prover_jobs_fri
+                    SET
+                        status = 'failed',
+                        error = $1,
+                        updated_at = NOW()
+                    WHERE
+                        id = $2
+                    "#,
+                    error,
+                    id
+                )
+                .execute(self.storage.conn())
+                .await?
+            }
+            FriJobQueue::ProofCompressor => {
+                sqlx::query!(
+                    r#"
+                    UPDATE proof_compression_jobs_fri
+                    SET
+                        status = 'failed',
+                        error = $1,
+                        updated_at = NOW()
+                    WHERE
+                        l1_batch_number = $2
+                    "#,
+                    error,
+                    id
+                )
+                .execute(self.storage.conn())
+                .await?
+            }
+        };
+        Ok(())
+    }
+
+    pub async fn get_job_attempts(
+        &mut self,
+        queue: FriJobQueue,
+        id: u64,
+    ) -> sqlx::Result<Option<u32>> {
+        let id = id as i64;
+        let attempts = match queue {
+            FriJobQueue::WitnessInputs => sqlx::query!(
+                r#"
+                SELECT
+                    attempts
+                FROM
+                    witness_inputs_fri
+                WHERE
+                    l1_batch_number = $1
+                "#,
+                id
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .map(|row| row.attempts),
+            FriJobQueue::LeafAggregation => sqlx::query!(
+                r#"
+                SELECT
+                    attempts
+                FROM
+                    leaf_aggregation_witness_jobs_fri
+                WHERE
+                    id = $1
+                "#,
+                id
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .map(|row| row.attempts),
+            FriJobQueue::NodeAggregation => sqlx::query!(
+                r#"
+                SELECT
+                    attempts
+                FROM
+                    node_aggregation_witness_jobs_fri
+                WHERE
+                    id = $1
+                "#,
+                id
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .map(|row| row.attempts),
+            FriJobQueue::RecursionTip => sqlx::query!(
+                r#"
+                SELECT
+                    attempts
+                FROM
+                    recursion_tip_witness_jobs_fri
+                WHERE
+                    l1_batch_number = $1
+                "#,
+                id
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .map(|row| row.attempts),
+            FriJobQueue::Scheduler => sqlx::query!(
+                r#"
+                SELECT
+                    attempts
+                FROM
+                    scheduler_witness_jobs_fri
+                WHERE
+                    l1_batch_number = $1
+                "#,
+                id
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .map(|row| row.attempts),
+            FriJobQueue::Prover => sqlx::query!(
+                r#"
+                SELECT
+                    attempts
+                FROM
+                    prover_jobs_fri
+                WHERE
+                    id = $1
+                "#,
+                id
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .map(|row| row.attempts),
+            FriJobQueue::ProofCompressor => sqlx::query!(
+                r#"
+                SELECT
+                    attempts
+                FROM
+                    proof_compression_jobs_fri
+                WHERE
+                    l1_batch_number = $1
+                "#,
+                id
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .map(|row| row.attempts),
+        };
+        Ok(attempts.map(|attempts| attempts as u32))
+    }
+
+    /// Returns the claim on an in-progress job, or `None` if the job is not in progress.
+    pub async fn get_job_claim(
+        &mut self,
+        queue: FriJobQueue,
+        id: u64,
+    ) -> sqlx::Result<Option<FriJobClaim>> {
+        let id = id as i64;
+        let claim = match queue {
+            FriJobQueue::WitnessInputs => sqlx::query!(
+                r#"
+                SELECT
+                    picked_by,
+                    attempts
+                FROM
+                    witness_inputs_fri
+                WHERE
+                    l1_batch_number = $1
+                    AND status = 'in_progress'
+                "#,
+                id
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .map(|row| (row.picked_by, row.attempts)),
+            FriJobQueue::LeafAggregation => sqlx::query!(
+                r#"
+                SELECT
+                    picked_by,
+                    attempts
+                FROM
+                    leaf_aggregation_witness_jobs_fri
+                WHERE
+                    id = $1
+                    AND status = 'in_progress'
+                "#,
+                id
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .map(|row| (row.picked_by, row.attempts)),
+            FriJobQueue::NodeAggregation => sqlx::query!(
+                r#"
+                SELECT
+                    picked_by,
+                    attempts
+                FROM
+                    node_aggregation_witness_jobs_fri
+                WHERE
+                    id = $1
+                    AND status = 'in_progress'
+                "#,
+                id
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .map(|row| (row.picked_by, row.attempts)),
+            FriJobQueue::RecursionTip => sqlx::query!(
+                r#"
+                SELECT
+                    picked_by,
+                    attempts
+                FROM
+                    recursion_tip_witness_jobs_fri
+                WHERE
+                    l1_batch_number = $1
+                    AND status = 'in_progress'
+                "#,
+                id
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .map(|row| (row.picked_by, row.attempts)),
+            FriJobQueue::Scheduler => sqlx::query!(
+                r#"
+                SELECT
+                    picked_by,
+                    attempts
+                FROM
+                    scheduler_witness_jobs_fri
+                WHERE
+                    l1_batch_number = $1
+                    AND status = 'in_progress'
+                "#,
+                id
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .map(|row| (row.picked_by, row.attempts)),
+            FriJobQueue::Prover => sqlx::query!(
+                r#"
+                SELECT
+                    picked_by,
+                    attempts
+                FROM
+                    prover_jobs_fri
+                WHERE
+                    id = $1
+                    AND status = 'in_progress'
+                "#,
+                id
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .map(|row| (row.picked_by, row.attempts)),
+            FriJobQueue::ProofCompressor => sqlx::query!(
+                r#"
+                SELECT
+                    picked_by,
+                    attempts
+                FROM
+                    proof_compression_jobs_fri
+                WHERE
+                    l1_batch_number = $1
+                    AND status = 'in_progress'
+                "#,
+                id
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .map(|row| (row.picked_by, row.attempts)),
+        };
+        Ok(claim.map(|(picked_by, attempts)| FriJobClaim {
+            picked_by,
+            attempts: attempts as u32,
+        }))
+    }
+
+    /// Resets the processing start of an in-progress job to the current time, so that the job is not
+    /// considered stuck by [`Self::requeue_stuck_jobs()`]. Used for jobs claimed in advance (i.e., prefetched).
+    /// Returns `false` if the job is no longer held by the specified `claim` (e.g., it was re-queued, and possibly
+    /// picked again by another component).
+    pub async fn refresh_job_claim(
+        &mut self,
+        queue: FriJobQueue,
+        id: u64,
+        claim: &FriJobClaim,
+    ) -> sqlx::Result<bool> {
+        let id = id as i64;
+        let picked_by = claim.picked_by.as_deref();
+        let attempts = claim.attempts as i16;
+        let result = match queue {
+            FriJobQueue::WitnessInputs => {
+                sqlx::query!(
+                    r#"
+                    UPDATE witness_inputs_fri
+                    SET
+                        updated_at = NOW(),
+                        processing_started_at = NOW()
+                    WHERE
+                        l1_batch_number = $1
+                        AND status = 'in_progress'
+                        AND picked_by IS NOT DISTINCT FROM $2
+                        AND attempts = $3
+                    "#,
+                    id,
+                    picked_by,
+                    attempts
+                )
+                .execute(self.storage.conn())
+                .await?
+            }
+            FriJobQueue::LeafAggregation => {
+                sqlx::query!(
+                    r#"
+                    UPDATE leaf_aggregation_witness_jobs_fri
+                    SET
+                        updated_at = NOW(),
+                        processing_started_at = NOW()
+                    WHERE
+                        id = $1
+                        AND status = 'in_progress'
+                        AND picked_by IS NOT DISTINCT FROM $2
+                        AND attempts = $3
+                    "#,
+                    id,
+                    picked_by,
+                    attempts
+                )
+                .execute(self.storage.conn())
+                .await?
+            }
+            FriJobQueue::NodeAggregation => {
+                sqlx::query!(
+                    r#"
+                    UPDATE node_aggregation_witness_jobs_fri
+                    SET
+                        updated_at = NOW(),
+                        processing_started_at = NOW()
+                    WHERE
+                        id = $1
+                        AND status = 'in_progress'
+                        AND picked_by IS NOT DISTINCT FROM $2
+                        AND attempts = $3
+                    "#,
+                    id,
+                    picked_by,
+                    attempts
+                )
+                .execute(self.storage.conn())
+                .await?
+            }
+            FriJobQueue::RecursionTip => {
+                sqlx::query!(
+                    r#"
+                    UPDATE recursion_tip_witness_jobs_fri
+                    SET
+                        updated_at = NOW(),
+                        processing_started_at = NOW()
+                    WHERE
+                        l1_batch_number = $1
+                        AND status = 'in_progress'
+                        AND picked_by IS NOT DISTINCT FROM $2
+                        AND attempts = $3
+                    "#,
+                    id,
+                    picked_by,
+                    attempts
+                )
+                .execute(self.storage.conn())
+                .await?
+            }
+            FriJobQueue::Scheduler => {
+                sqlx::query!(
+                    r#"
+                    UPDATE scheduler_witness_jobs_fri
+                    SET
+                        updated_at = NOW(),
+                        processing_started_at = NOW()
+                    WHERE
+                        l1_batch_number = $1
+                        AND status = 'in_progress'
+                        AND picked_by IS NOT DISTINCT FROM $2
+                        AND attempts = $3
+                    "#,
+                    id,
+                    picked_by,
+                    attempts
+                )
+                .execute(self.storage.conn())
+                .await?
+            }
+            FriJobQueue::Prover => {
+                sqlx::query!(
+                    r#"
+                    UPDATE prover_jobs_fri
+                    SET
+                        updated_at = NOW(),
+                        processing_started_at = NOW()
+                    WHERE
+                        id = $1
+                        AND status = 'in_progress'
+                        AND picked_by IS NOT DISTINCT FROM $2
+                        AND attempts = $3
+                    "#,
+                    id,
+                    picked_by,
+                    attempts
+                )
+                .execute(self.storage.conn())
+                .await?
+            }
+            FriJobQueue::ProofCompressor => {
+                sqlx::query!(
+                    r#"
+                    UPDATE proof_compression_jobs_fri
+                    SET
+                        updated_at = NOW(),
+                        processing_started_at = NOW()
+                    WHERE
+                        l1_batch_number = $1
+                        AND status = 'in_progress'
+                        AND picked_by IS NOT DISTINCT FROM $2
+                        AND attempts = $3
+                    "#,
+                    id,
+                    picked_by,
+                    attempts
+                )
+                .execute(self.storage.conn())
+                .await?
+            }
+        };
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Re-queues jobs that have been in progress for longer than `processing_timeout`, and failed jobs
+    /// that have attempts left.
+    pub async fn requeue_stuck_jobs(
+        &mut self,
+        queue: FriJobQueue,
+        processing_timeout: Duration,
+        max_attempts: u32,
+    ) -> sqlx::Result<Vec<StuckJobs>> {
+        let processing_timeout = pg_interval_from_duration(processing_timeout);
+        let max_attempts = max_attempts as i32;
+        let stuck_jobs = match queue {
+            FriJobQueue::WitnessInputs => sqlx::query!(
+                r#"
+                    UPDATE witness_inputs_fri
+                    SET
+                        status = 'queued',
+                        updated_at = NOW(),
+                        processing_started_at = NOW()
+                    WHERE
+                        l1_batch_number IN (
+                            SELECT
+                                l1_batch_number
+                            FROM
+                                witness_inputs_fri
+                            WHERE
+                                (
+                                    status IN ('in_progress', 'in_gpu_proof')
+                                    AND processing_started_at <= NOW() - $1::INTERVAL
+                                    AND attempts < $2
+                                )
+                                OR (
+                                    status = 'failed'
+                                    AND attempts < $2
+                                )
+                            FOR UPDATE
+                                SKIP LOCKED
+                        )
+                    RETURNING
+                        l1_batch_number,
+                        status,
+                        attempts
+                    "#,
+                &processing_timeout,
+                max_attempts,
+            )
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| StuckJobs {
+                id: row.l1_batch_number as u64,
+                status: row.status,
+                attempts: row.attempts as u64,
+                circuit_id: None,
+            })
+            .collect(),
+            FriJobQueue::LeafAggregation => sqlx::query!(
+                r#"
+                    UPDATE leaf_aggregation_witness_jobs_fri
+                    SET
+                        status = 'queued',
+                        updated_at = NOW(),
+                        processing_started_at = NOW()
+                    WHERE
+                        id IN (
+                            SELECT
+                                id
+                            FROM
+                                leaf_aggregation_witness_jobs_fri
+                            WHERE
+                                (
+                                    status IN ('in_progress', 'in_gpu_proof')
+                                    AND processing_started_at <= NOW() - $1::INTERVAL
+                                    AND attempts < $2
+                                )
+                                OR (
+                                    status = 'failed'
+                                    AND attempts < $2
+                                )
+                            FOR UPDATE
+                                SKIP LOCKED
+                        )
+                    RETURNING
+                        id,
+                        status,
+                        attempts,
+                        circuit_id
+                    "#,
+                &processing_timeout,
+                max_attempts,
+            )
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| StuckJobs {
+                id: row.id as u64,
+                status: row.status,
+                attempts: row.attempts as u64,
+                circuit_id: Some(row.circuit_id as u32),
+            })
+            .collect(),
+            FriJobQueue::NodeAggregation => sqlx::query!(
+                r#"
+                    UPDATE node_aggregation_witness_jobs_fri
+                    SET
+                        status = 'queued',
+                        updated_at = NOW(),
+                        processing_started_at = NOW()
+                    WHERE
+                        id IN (
+                            SELECT
+                                id
+                            FROM
+                                node_aggregation_witness_jobs_fri
+                            WHERE
+                                (
+                                    status IN ('in_progress', 'in_gpu_proof')
+                                    AND processing_started_at <= NOW() - $1::INTERVAL
+                                    AND attempts < $2
+                                )
+                                OR (
+                                    status = 'failed'
+                                    AND attempts < $2
+                                )
+                            FOR UPDATE
+                                SKIP LOCKED
+                        )
+                    RETURNING
+                        id,
+                        status,
+                        attempts,
+                        circuit_id
+                    "#,
+                &processing_timeout,
+                max_attempts,
+            )
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| StuckJobs {
+                id: row.id as u64,
+                status: row.status,
+                attempts: row.attempts as u64,
+                circuit_id: Some(row.circuit_id as u32),
+            })
+            .collect(),
+            FriJobQueue::RecursionTip => sqlx::query!(
+                r#"
+                    UPDATE recursion_tip_witness_jobs_fri
+                    SET
+                        status = 'queued',
+                        updated_at = NOW(),
+                        processing_started_at = NOW()
+                    WHERE
+                        l1_batch_number IN (
+                            SELECT
+                                l1_batch_number
+                            FROM
+                                recursion_tip_witness_jobs_fri
+                            WHERE
+                                (
+                                    status IN ('in_progress', 'in_gpu_proof')
+                                    AND processing_started_at <= NOW() - $1::INTERVAL
+                                    AND attempts < $2
+                                )
+                                OR (
+                                    status = 'failed'
+                                    AND attempts < $2
+                                )
+                            FOR UPDATE
+                                SKIP LOCKED
+                        )
+                    RETURNING
+                        l1_batch_number,
+                        status,
+                        attempts
+                    "#,
+                &processing_timeout,
+                max_attempts,
+            )
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| StuckJobs {
+                id: row.l1_batch_number as u64,
+                status: row.status,
+                attempts: row.attempts as u64,
+                circuit_id: None,
+            })
+            .collect(),
+            FriJobQueue::Scheduler => sqlx::query!(
+                r#"
+                    UPDATE scheduler_witness_jobs_fri
+                    SET
+                        status = 'queued',
+                        updated_at = NOW(),
+                        processing_started_at = NOW()
+                    WHERE
+                        l1_batch_number IN (
+                            SELECT
+                                l1_batch_number
+                            FROM
+                                scheduler_witness_jobs_fri
+                            WHERE
+                                (
+                                    status IN ('in_progress', 'in_gpu_proof')
+                                    AND processing_started_at <= NOW() - $1::INTERVAL
+                                    AND attempts < $2
+                                )
+                                OR (
+                                    status = 'failed'
+                                    AND attempts < $2
+                                )
+                            FOR UPDATE
+                                SKIP LOCKED
+                        )
+                    RETURNING
+                        l1_batch_number,
+                        status,
+                        attempts
+                    "#,
+                &processing_timeout,
+                max_attempts,
+            )
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| StuckJobs {
+                id: row.l1_batch_number as u64,
+                status: row.status,
+                attempts: row.attempts as u64,
+                circuit_id: None,
+            })
+            .collect(),
+            FriJobQueue::Prover => sqlx::query!(
+                r#"
+                    UPDATE prover_jobs_fri
+                    SET
+                        status = 'queued',
+                        updated_at = NOW(),
+                        processing_started_at = NOW()
+                    WHERE
+                        id IN (
+                            SELECT
+                                id
+                            FROM
+                                prover_jobs_fri
+                            WHERE
+                                (
+                                    status IN ('in_progress', 'in_gpu_proof')
+                                    AND processing_started_at <= NOW() - $1::INTERVAL
+                                    AND attempts < $2
+                                )
+                                OR (
+                                    status = 'failed'
+                                    AND attempts < $2
+                                )
+                            FOR UPDATE
+                                SKIP LOCKED
+                        )
+                    RETURNING
+                        id,
+                        status,
+                        attempts,
+                        circuit_id
+                    "#,
+                &processing_timeout,
+                max_attempts,
+            )
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| StuckJobs {
+                id: row.id as u64,
+                status: row.status,
+                attempts: row.attempts as u64,
+                circuit_id: Some(row.circuit_id as u32),
+            })
+            .collect(),
+            FriJobQueue::ProofCompressor => sqlx::query!(
+                r#"
+                    UPDATE proof_compression_jobs_fri
+                    SET
+                        status = 'queued',
+                        updated_at = NOW(),
+                        processing_started_at = NOW()
+                    WHERE
+                        l1_batch_number IN (
+                            SELECT
+                                l1_batch_number
+                            FROM
+                                proof_compression_jobs_fri
+                            WHERE
+                                (
+                                    status IN ('in_progress', 'in_gpu_proof')
+                                    AND processing_started_at <= NOW() - $1::INTERVAL
+                                    AND attempts < $2
+                                )
+                                OR (
+                                    status = 'failed'
+                                    AND attempts < $2
+                                )
+                            FOR UPDATE
+                                SKIP LOCKED
+                        )
+                    RETURNING
+                        l1_batch_number,
+                        status,
+                        attempts
+                    "#,
+                &processing_timeout,
+                max_attempts,
+            )
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| StuckJobs {
+                id: row.l1_batch_number as u64,
+                status: row.status,
+                attempts: row.attempts as u64,
+                circuit_id: None,
+            })
+            .collect(),
+        };
+        Ok(stuck_jobs)
+    }
+
+    /// Counts jobs in the queue by their [`JobState`]. Failed jobs with at least `max_attempts` attempts
+    /// are considered dead.
+    pub async fn get_job_state_counts(
+        &mut self,
+        queue: FriJobQueue,
+        max_attempts: u32,
+    ) -> sqlx::Result<JobStateCounts> {
+        let rows: Vec<(String, bool, i64)> = match queue {
+            FriJobQueue::WitnessInputs => sqlx::query!(
+                r#"
+                SELECT
+                    status,
+                    attempts >= $1 AS "no_attempts_left!",
+                    COUNT(*) AS "count!"
+                FROM
+                    witness_inputs_fri
+                GROUP BY
+                    status,
+                    attempts >= $1
+                "#,
+                max_attempts as i32
+            )
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| (row.status, row.no_attempts_left, row.count))
+            .collect(),
+            FriJobQueue::LeafAggregation => sqlx::query!(
+                r#"
+                SELECT
+                    status,
+                    attempts >= $1 AS "no_attempts_left!",
+                    COUNT(*) AS "count!"
+                FROM
+                    leaf_aggregation_witness_jobs_fri
+                GROUP BY
+                    status,
+                    attempts >= $1
+                "#,
+                max_attempts as i32
+            )
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| (row.status, row.no_attempts_left, row.count))
+            .collect(),
+            FriJobQueue::NodeAggregation => sqlx::query!(
+                r#"
+                SELECT
+                    status,
+                    attempts >= $1 AS "no_attempts_left!",
+                    COUNT(*) AS "count!"
+                FROM
+                    node_aggregation_witness_jobs_fri
+                GROUP BY
+                    status,
+                    attempts >= $1
+                "#,
+                max_attempts as i32
+            )
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| (row.status, row.no_attempts_left, row.count))
+            .collect(),
+            FriJobQueue::RecursionTip => sqlx::query!(
+                r#"
+                SELECT
+                    status,
+                    attempts >= $1 AS "no_attempts_left!",
+                    COUNT(*) AS "count!"
+                FROM
+                    recursion_tip_witness_jobs_fri
+                GROUP BY
+                    status,
+                    attempts >= $1
+                "#,
+                max_attempts as i32
+            )
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| (row.status, row.no_attempts_left, row.count))
+            .collect(),
+            FriJobQueue::Scheduler => sqlx::query!(
+                r#"
+                SELECT
+                    status,
+                    attempts >= $1 AS "no_attempts_left!",
+                    COUNT(*) AS "count!"
+                FROM
+                    scheduler_witness_jobs_fri
+                GROUP BY
+                    status,
+                    attempts >= $1
+                "#,
+                max_attempts as i32
+            )
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| (row.status, row.no_attempts_left, row.count))
+            .collect(),
+            FriJobQueue::Prover => sqlx::query!(
+                r#"
+                SELECT
+                    status,
+                    attempts >= $1 AS "no_attempts_left!",
+                    COUNT(*) AS "count!"
+                FROM
+                    prover_jobs_fri
+                GROUP BY
+                    status,
+                    attempts >= $1
+                "#,
+                max_attempts as i32
+            )
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| (row.status, row.no_attempts_left, row.count))
+            .collect(),
+            FriJobQueue::ProofCompressor => sqlx::query!(
+                r#"
+                SELECT
+                    status,
+                    attempts >= $1 AS "no_attempts_left!",
+                    COUNT(*) AS "count!"
+                FROM
+                    proof_compression_jobs_fri
+                GROUP BY
+                    status,
+                    attempts >= $1
+                "#,
+                max_attempts as i32
+            )
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| (row.status, row.no_attempts_left, row.count))
+            .collect(),
+        };
+
+        let mut counts = JobStateCounts::default();
+        for (status, no_attempts_left, count) in rows {
+            // Exact attempts aren't queried, so dead jobs are distinguished by the `no_attempts_left` flag.
+            let attempts = if no_attempts_left { max_attempts } else { 0 };
+            if let Some(state) = JobState::from_db(&status, attempts, max_attempts) {
+                counts.add(state, count as usize);
+            }
+        }
+        Ok(counts)
+    }
+}
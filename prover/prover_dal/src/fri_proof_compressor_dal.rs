@@ -10,7 +10,7 @@ use zksync_basic_types::{
 };
 use zksync_db_connection::connection::Connection;
 
-use crate::{duration_to_naive_time, pg_interval_from_duration, Prover};
+use crate::{duration_to_naive_time, Prover};
 
 #[derive(Debug)]
 pub struct FriProofCompressorDal<'a, 'c> {
@@ -114,28 +114,6 @@ impl FriProofCompressorDal<'_, '_> {
         .map(|row| L1BatchNumber(row.l1_batch_number as u32))
     }
 
-    pub async fn get_proof_compression_job_attempts(
-        &mut self,
-        l1_batch_number: L1BatchNumber,
-    ) -> sqlx::Result<Option<u32>> {
-        let attempts = sqlx::query!(
-            r#"
-            SELECT
-                attempts
-            FROM
-                proof_compression_jobs_fri
-            WHERE
-                l1_batch_number = $1
-            "#,
-            i64::from(l1_batch_number.0)
-        )
-        .fetch_optional(self.storage.conn())
-        .await?
-        .map(|row| row.attempts as u32);
-
-        Ok(attempts)
-    }
-
     pub async fn mark_proof_compression_job_successful(
         &mut self,
         block_number: L1BatchNumber,
@@ -163,30 +141,6 @@ impl FriProofCompressorDal<'_, '_> {
         .unwrap();
     }
 
-    pub async fn mark_proof_compression_job_failed(
-        &mut self,
-        error: &str,
-        block_number: L1BatchNumber,
-    ) {
-        sqlx::query!(
-            r#"
-            UPDATE proof_compression_jobs_fri
-            SET
-                status = $1,
-                error = $2,
-                updated_at = NOW()
-            WHERE
-                l1_batch_number = $3
-            "#,
-            ProofCompressionJobStatus::Failed.to_string(),
-            error,
-            i64::from(block_number.0)
-        )
-        .execute(self.storage.conn())
-        .await
-        .unwrap();
-    }
-
     pub async fn get_least_proven_block_not_sent_to_server(
         &mut self,
     ) -> Option<(
@@ -315,52 +269,6 @@ impl FriProofCompressorDal<'_, '_> {
         result
     }
 
-    pub async fn requeue_stuck_jobs(
-        &mut self,
-        processing_timeout: Duration,
-        max_attempts: u32,
-    ) -> Vec<StuckJobs> {
-        let processing_timeout = pg_interval_from_duration(processing_timeout);
-        {
-            sqlx::query!(
-                r#"
-                UPDATE proof_compression_jobs_fri
-                SET
-                    status = 'queued',
-                    updated_at = NOW(),
-                    processing_started_at = NOW()
-                WHERE
-                    (
-                        status = 'in_progress'
-                        AND processing_started_at <= NOW() - $1::INTERVAL
-                        AND attempts < $2
-                    )
-                    OR (
-                        status = 'failed'
-                        AND attempts < $2
-                    )
-                RETURNING
-                    l1_batch_number,
-                    status,
-                    attempts
-                "#,
-                &processing_timeout,
-                max_attempts as i32,
-            )
-            .fetch_all(self.storage.conn())
-            .await
-            .unwrap()
-            .into_iter()
-            .map(|row| StuckJobs {
-                id: row.l1_batch_number as u64,
-                status: row.status,
-                attempts: row.attempts as u64,
-                circuit_id: None,
-            })
-            .collect()
-        }
-    }
-
     pub async fn get_proof_compression_job_for_batch(
         &mut self,
         block_number: L1BatchNumber,
@@ -1,5 +1,7 @@
 #![doc = include_str!("../doc/FriProverDal.md")]
-use std::{collections::HashMap, convert::TryFrom, str::FromStr, time::Duration};
+use std::{
+    collections::HashMap, convert::TryFrom, ops::RangeInclusive, str::FromStr, time::Duration,
+};
 
 use zksync_basic_types::{
     basic_fri_types::{AggregationRound, CircuitIdRoundTuple, JobIdentifiers},
@@ -200,46 +202,6 @@ impl FriProverDal<'_, '_> {
         })
     }
 
-    pub async fn save_proof_error(&mut self, id: u32, error: String) {
-        {
-            sqlx::query!(
-                r#"
-                UPDATE prover_jobs_fri
-                SET
-                    status = 'failed',
-                    error = $1,
-                    updated_at = NOW()
-                WHERE
-                    id = $2
-                "#,
-                error,
-                i64::from(id)
-            )
-            .execute(self.storage.conn())
-            .await
-            .unwrap();
-        }
-    }
-
-    pub async fn get_prover_job_attempts(&mut self, id: u32) -> sqlx::Result<Option<u32>> {
-        let attempts = sqlx::query!(
-            r#"
-            SELECT
-                attempts
-            FROM
-                prover_jobs_fri
-            WHERE
-                id = $1
-            "#,
-            i64::from(id)
-        )
-        .fetch_optional(self.storage.conn())
-        .await?
-        .map(|row| row.attempts as u32);
-
-        Ok(attempts)
-    }
-
     pub async fn save_proof(
         &mut self,
         id: u32,
@@ -288,67 +250,6 @@ impl FriProverDal<'_, '_> {
         .unwrap()
     }
 
-    pub async fn requeue_stuck_jobs(
-        &mut self,
-        processing_timeout: Duration,
-        max_attempts: u32,
-    ) -> Vec<StuckJobs> {
-        let processing_timeout = pg_interval_from_duration(processing_timeout);
-        {
-            sqlx::query!(
-                r#"
-                UPDATE prover_jobs_fri
-                SET
-                    status = 'queued',
-                    updated_at = NOW(),
-                    processing_started_at = NOW()
-                WHERE
-                    id IN (
-                        SELECT
-                            id
-                        FROM
-                            prover_jobs_fri
-                        WHERE
-                            (
-                                status = 'in_progress'
-                                AND processing_started_at <= NOW() - $1::INTERVAL
-                                AND attempts < $2
-                            )
-                            OR (
-                                status = 'in_gpu_proof'
-                                AND processing_started_at <= NOW() - $1::INTERVAL
-                                AND attempts < $2
-                            )
-                            OR (
-                                status = 'failed'
-                                AND attempts < $2
-                            )
-                        FOR UPDATE
-                            SKIP LOCKED
-                    )
-                RETURNING
-                    id,
-                    status,
-                    attempts,
-                    circuit_id
-                "#,
-                &processing_timeout,
-                max_attempts as i32,
-            )
-            .fetch_all(self.storage.conn())
-            .await
-            .unwrap()
-            .into_iter()
-            .map(|row| StuckJobs {
-                id: row.id as u64,
-                status: row.status,
-                attempts: row.attempts as u64,
-                circuit_id: Some(row.circuit_id as u32),
-            })
-            .collect()
-        }
-    }
-
     #[allow(clippy::too_many_arguments)]
     pub async fn insert_prover_job(
         &mut self,
@@ -621,6 +522,102 @@ impl FriProverDal<'_, '_> {
         .unwrap_or(0) as usize
     }
 
+    /// Moves archived prover jobs for the specified range of L1 batches back to `prover_jobs_fri`.
+    /// Returns the number of restored jobs.
+    pub async fn restore_archived_jobs(
+        &mut self,
+        l1_batch_numbers: RangeInclusive<L1BatchNumber>,
+    ) -> sqlx::Result<usize> {
+        let restored_count = sqlx::query_scalar!(
+            r#"
+            WITH
+                restored AS (
+                    DELETE FROM prover_jobs_fri_archive
+                    WHERE
+                        l1_batch_number BETWEEN $1 AND $2
+                    RETURNING
+                        id,
+                        l1_batch_number,
+                        circuit_id,
+                        circuit_blob_url,
+                        aggregation_round,
+                        sequence_number,
+                        status,
+                        error,
+                        attempts,
+                        processing_started_at,
+                        created_at,
+                        updated_at,
+                        time_taken,
+                        is_blob_cleaned,
+                        depth,
+                        is_node_final_proof,
+                        proof_blob_url,
+                        protocol_version,
+                        picked_by,
+                        protocol_version_patch
+                ),
+                inserted_count AS (
+                    INSERT INTO
+                        prover_jobs_fri (
+                            id,
+                            l1_batch_number,
+                            circuit_id,
+                            circuit_blob_url,
+                            aggregation_round,
+                            sequence_number,
+                            status,
+                            error,
+                            attempts,
+                            processing_started_at,
+                            created_at,
+                            updated_at,
+                            time_taken,
+                            is_blob_cleaned,
+                            depth,
+                            is_node_final_proof,
+                            proof_blob_url,
+                            protocol_version,
+                            picked_by,
+                            protocol_version_patch
+                        )
+                    SELECT
+                        id,
+                        l1_batch_number,
+                        circuit_id,
+                        circuit_blob_url,
+                        aggregation_round,
+                        sequence_number,
+                        status,
+                        error,
+                        attempts,
+                        processing_started_at,
+                        created_at,
+                        updated_at,
+                        time_taken,
+                        is_blob_cleaned,
+                        depth,
+                        is_node_final_proof,
+                        proof_blob_url,
+                        protocol_version,
+                        picked_by,
+                        protocol_version_patch
+                    FROM
+                        restored
+                )
+            SELECT
+                COUNT(*)
+            FROM
+                restored
+            "#,
+            i64::from(l1_batch_numbers.start().0),
+            i64::from(l1_batch_numbers.end().0)
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(restored_count.unwrap_or(0) as usize)
+    }
+
     pub async fn get_final_node_proof_job_ids_for(
         &mut self,
         l1_batch_number: L1BatchNumber,
@@ -15,7 +15,7 @@ use zksync_basic_types::{
 };
 use zksync_db_connection::{connection::Connection, metrics::MethodLatency};
 
-use crate::{duration_to_naive_time, pg_interval_from_duration, Prover};
+use crate::{duration_to_naive_time, Prover};
 
 #[derive(Debug)]
 pub struct FriWitnessGeneratorDal<'a, 'c> {
@@ -136,28 +136,6 @@ impl FriWitnessGeneratorDal<'_, '_> {
         })
     }
 
-    pub async fn get_basic_circuit_witness_job_attempts(
-        &mut self,
-        l1_batch_number: L1BatchNumber,
-    ) -> sqlx::Result<Option<u32>> {
-        let attempts = sqlx::query!(
-            r#"
-            SELECT
-                attempts
-            FROM
-                witness_inputs_fri
-            WHERE
-                l1_batch_number = $1
-            "#,
-            i64::from(l1_batch_number.0)
-        )
-        .fetch_optional(self.storage.conn())
-        .await?
-        .map(|row| row.attempts as u32);
-
-        Ok(attempts)
-    }
-
     pub async fn mark_witness_job(
         &mut self,
         status: FriWitnessJobStatus,
@@ -203,44 +181,6 @@ impl FriWitnessGeneratorDal<'_, '_> {
         .unwrap();
     }
 
-    pub async fn mark_witness_job_failed(&mut self, error: &str, block_number: L1BatchNumber) {
-        sqlx::query!(
-            r#"
-            UPDATE witness_inputs_fri
-            SET
-                status = 'failed',
-                error = $1,
-                updated_at = NOW()
-            WHERE
-                l1_batch_number = $2
-            "#,
-            error,
-            i64::from(block_number.0)
-        )
-        .execute(self.storage.conn())
-        .await
-        .unwrap();
-    }
-
-    pub async fn mark_leaf_aggregation_job_failed(&mut self, error: &str, id: u32) {
-        sqlx::query!(
-            r#"
-            UPDATE leaf_aggregation_witness_jobs_fri
-            SET
-                status = 'failed',
-                error = $1,
-                updated_at = NOW()
-            WHERE
-                id = $2
-            "#,
-            error,
-            i64::from(id)
-        )
-        .execute(self.storage.conn())
-        .await
-        .unwrap();
-    }
-
     pub async fn mark_leaf_aggregation_as_successful(&mut self, id: u32, time_taken: Duration) {
         sqlx::query!(
             r#"
@@ -260,55 +200,6 @@ impl FriWitnessGeneratorDal<'_, '_> {
         .unwrap();
     }
 
-    pub async fn requeue_stuck_jobs(
-        &mut self,
-        processing_timeout: Duration,
-        max_attempts: u32,
-    ) -> Vec<StuckJobs> {
-        let processing_timeout = pg_interval_from_duration(processing_timeout);
-        sqlx::query!(
-            r#"
-            UPDATE witness_inputs_fri
-            SET
-                status = 'queued',
-                updated_at = NOW(),
-                processing_started_at = NOW()
-            WHERE
-                (
-                    status = 'in_progress'
-                    AND processing_started_at <= NOW() - $1::INTERVAL
-                    AND attempts < $2
-                )
-                OR (
-                    status = 'in_gpu_proof'
-                    AND processing_started_at <= NOW() - $1::INTERVAL
-                    AND attempts < $2
-                )
-                OR (
-                    status = 'failed'
-                    AND attempts < $2
-                )
-            RETURNING
-                l1_batch_number,
-                status,
-                attempts
-            "#,
-            &processing_timeout,
-            max_attempts as i32,
-        )
-        .fetch_all(self.storage.conn())
-        .await
-        .unwrap()
-        .into_iter()
-        .map(|row| StuckJobs {
-            id: row.l1_batch_number as u64,
-            status: row.status,
-            attempts: row.attempts as u64,
-            circuit_id: None,
-        })
-        .collect()
-    }
-
     /// Responsible for creating the jobs to be processed, after a basic witness generator run.
     /// It will create as follows:
     /// - all prover jobs for aggregation round 0 identified in the basic witness generator run
@@ -493,29 +384,6 @@ impl FriWitnessGeneratorDal<'_, '_> {
         })
     }
 
-    pub async fn get_leaf_aggregation_job_attempts(
-        &mut self,
-        id: u32,
-    ) -> sqlx::Result<Option<u32>> {
-        let attempts = sqlx::query!(
-            r#"
-            SELECT
-                attempts
-            FROM
-                leaf_aggregation_witness_jobs_fri
-            WHERE
-                id = $1
-            "#,
-            i64::from(id)
-        )
-        .fetch_optional(self.storage.conn())
-        .await
-        .unwrap()
-        .map(|row| row.attempts as u32);
-
-        Ok(attempts)
-    }
-
     async fn prover_job_ids_for(
         &mut self,
         block_number: L1BatchNumber,
@@ -686,48 +554,6 @@ impl FriWitnessGeneratorDal<'_, '_> {
         })
     }
 
-    pub async fn get_node_aggregation_job_attempts(
-        &mut self,
-        id: u32,
-    ) -> sqlx::Result<Option<u32>> {
-        let attempts = sqlx::query!(
-            r#"
-            SELECT
-                attempts
-            FROM
-                node_aggregation_witness_jobs_fri
-            WHERE
-                id = $1
-            "#,
-            i64::from(id)
-        )
-        .fetch_optional(self.storage.conn())
-        .await
-        .unwrap()
-        .map(|row| row.attempts as u32);
-
-        Ok(attempts)
-    }
-
-    pub async fn mark_node_aggregation_job_failed(&mut self, error: &str, id: u32) {
-        sqlx::query!(
-            r#"
-            UPDATE node_aggregation_witness_jobs_fri
-            SET
-                status = 'failed',
-                error = $1,
-                updated_at = NOW()
-            WHERE
-                id = $2
-            "#,
-            error,
-            i64::from(id)
-        )
-        .execute(self.storage.conn())
-        .await
-        .unwrap();
-    }
-
     pub async fn mark_node_aggregation_as_successful(&mut self, id: u32, time_taken: Duration) {
         sqlx::query!(
             r#"
@@ -946,140 +772,6 @@ impl FriWitnessGeneratorDal<'_, '_> {
         .collect()
     }
 
-    pub async fn requeue_stuck_leaf_aggregations_jobs(
-        &mut self,
-        processing_timeout: Duration,
-        max_attempts: u32,
-    ) -> Vec<StuckJobs> {
-        let processing_timeout = pg_interval_from_duration(processing_timeout);
-        sqlx::query!(
-            r#"
-            UPDATE leaf_aggregation_witness_jobs_fri
-            SET
-                status = 'queued',
-                updated_at = NOW(),
-                processing_started_at = NOW()
-            WHERE
-                (
-                    status = 'in_progress'
-                    AND processing_started_at <= NOW() - $1::INTERVAL
-                    AND attempts < $2
-                )
-                OR (
-                    status = 'failed'
-                    AND attempts < $2
-                )
-            RETURNING
-                id,
-                status,
-                attempts,
-                circuit_id
-            "#,
-            &processing_timeout,
-            max_attempts as i32,
-        )
-        .fetch_all(self.storage.conn())
-        .await
-        .unwrap()
-        .into_iter()
-        .map(|row| StuckJobs {
-            id: row.id as u64,
-            status: row.status,
-            attempts: row.attempts as u64,
-            circuit_id: Some(row.circuit_id as u32),
-        })
-        .collect()
-    }
-
-    pub async fn requeue_stuck_node_aggregations_jobs(
-        &mut self,
-        processing_timeout: Duration,
-        max_attempts: u32,
-    ) -> Vec<StuckJobs> {
-        let processing_timeout = pg_interval_from_duration(processing_timeout);
-        sqlx::query!(
-            r#"
-            UPDATE node_aggregation_witness_jobs_fri
-            SET
-                status = 'queued',
-                updated_at = NOW(),
-                processing_started_at = NOW()
-            WHERE
-                (
-                    status = 'in_progress'
-                    AND processing_started_at <= NOW() - $1::INTERVAL
-                    AND attempts < $2
-                )
-                OR (
-                    status = 'failed'
-                    AND attempts < $2
-                )
-            RETURNING
-                id,
-                status,
-                attempts,
-                circuit_id
-            "#,
-            &processing_timeout,
-            max_attempts as i32,
-        )
-        .fetch_all(self.storage.conn())
-        .await
-        .unwrap()
-        .into_iter()
-        .map(|row| StuckJobs {
-            id: row.id as u64,
-            status: row.status,
-            attempts: row.attempts as u64,
-            circuit_id: Some(row.circuit_id as u32),
-        })
-        .collect()
-    }
-
-    pub async fn requeue_stuck_recursion_tip_jobs(
-        &mut self,
-        processing_timeout: Duration,
-        max_attempts: u32,
-    ) -> Vec<StuckJobs> {
-        let processing_timeout = pg_interval_from_duration(processing_timeout);
-        sqlx::query!(
-            r#"
-            UPDATE recursion_tip_witness_jobs_fri
-            SET
-                status = 'queued',
-                updated_at = NOW(),
-                processing_started_at = NOW()
-            WHERE
-                (
-                    status = 'in_progress'
-                    AND processing_started_at <= NOW() - $1::INTERVAL
-                    AND attempts < $2
-                )
-                OR (
-                    status = 'failed'
-                    AND attempts < $2
-                )
-            RETURNING
-                l1_batch_number,
-                status,
-                attempts
-            "#,
-            &processing_timeout,
-            max_attempts as i32,
-        )
-        .fetch_all(self.storage.conn())
-        .await
-        .unwrap()
-        .into_iter()
-        .map(|row| StuckJobs {
-            id: row.l1_batch_number as u64,
-            status: row.status,
-            attempts: row.attempts as u64,
-            circuit_id: None,
-        })
-        .collect()
-    }
-
     pub async fn get_next_recursion_tip_witness_job(
         &mut self,
         protocol_version: ProtocolSemanticVersion,
@@ -1142,50 +834,6 @@ impl FriWitnessGeneratorDal<'_, '_> {
         .unwrap();
     }
 
-    pub async fn requeue_stuck_scheduler_jobs(
-        &mut self,
-        processing_timeout: Duration,
-        max_attempts: u32,
-    ) -> Vec<StuckJobs> {
-        let processing_timeout = pg_interval_from_duration(processing_timeout);
-        sqlx::query!(
-            r#"
-            UPDATE scheduler_witness_jobs_fri
-            SET
-                status = 'queued',
-                updated_at = NOW(),
-                processing_started_at = NOW()
-            WHERE
-                (
-                    status = 'in_progress'
-                    AND processing_started_at <= NOW() - $1::INTERVAL
-                    AND attempts < $2
-                )
-                OR (
-                    status = 'failed'
-                    AND attempts < $2
-                )
-            RETURNING
-                l1_batch_number,
-                status,
-                attempts
-            "#,
-            &processing_timeout,
-            max_attempts as i32,
-        )
-        .fetch_all(self.storage.conn())
-        .await
-        .unwrap()
-        .into_iter()
-        .map(|row| StuckJobs {
-            id: row.l1_batch_number as u64,
-            status: row.status,
-            attempts: row.attempts as u64,
-            circuit_id: None,
-        })
-        .collect()
-    }
-
     pub async fn get_next_scheduler_witness_job(
         &mut self,
         protocol_version: ProtocolSemanticVersion,
@@ -1230,50 +878,6 @@ impl FriWitnessGeneratorDal<'_, '_> {
         .map(|row| L1BatchNumber(row.l1_batch_number as u32))
     }
 
-    pub async fn get_recursion_tip_witness_job_attempts(
-        &mut self,
-        l1_batch_number: L1BatchNumber,
-    ) -> sqlx::Result<Option<u32>> {
-        let attempts = sqlx::query!(
-            r#"
-            SELECT
-                attempts
-            FROM
-                recursion_tip_witness_jobs_fri
-            WHERE
-                l1_batch_number = $1
-            "#,
-            l1_batch_number.0 as i64
-        )
-        .fetch_optional(self.storage.conn())
-        .await?
-        .map(|row| row.attempts as u32);
-
-        Ok(attempts)
-    }
-
-    pub async fn get_scheduler_witness_job_attempts(
-        &mut self,
-        l1_batch_number: L1BatchNumber,
-    ) -> sqlx::Result<Option<u32>> {
-        let attempts = sqlx::query!(
-            r#"
-            SELECT
-                attempts
-            FROM
-                scheduler_witness_jobs_fri
-            WHERE
-                l1_batch_number = $1
-            "#,
-            i64::from(l1_batch_number.0)
-        )
-        .fetch_optional(self.storage.conn())
-        .await?
-        .map(|row| row.attempts as u32);
-
-        Ok(attempts)
-    }
-
     pub async fn mark_recursion_tip_job_as_successful(
         &mut self,
         l1_batch_number: L1BatchNumber,
@@ -1320,48 +924,6 @@ impl FriWitnessGeneratorDal<'_, '_> {
         .unwrap();
     }
 
-    pub async fn mark_recursion_tip_job_failed(
-        &mut self,
-        error: &str,
-        l1_batch_number: L1BatchNumber,
-    ) {
-        sqlx::query!(
-            r#"
-            UPDATE recursion_tip_witness_jobs_fri
-            SET
-                status = 'failed',
-                error = $1,
-                updated_at = NOW()
-            WHERE
-                l1_batch_number = $2
-            "#,
-            error,
-            l1_batch_number.0 as i64
-        )
-        .execute(self.storage.conn())
-        .await
-        .unwrap();
-    }
-
-    pub async fn mark_scheduler_job_failed(&mut self, error: &str, block_number: L1BatchNumber) {
-        sqlx::query!(
-            r#"
-            UPDATE scheduler_witness_jobs_fri
-            SET
-                status = 'failed',
-                error = $1,
-                updated_at = NOW()
-            WHERE
-                l1_batch_number = $2
-            "#,
-            error,
-            i64::from(block_number.0)
-        )
-        .execute(self.storage.conn())
-        .await
-        .unwrap();
-    }
-
     pub async fn get_witness_jobs_stats(
         &mut self,
         aggregation_round: AggregationRound,
@@ -6,13 +6,14 @@ pub use zksync_db_connection::{
 };
 
 use crate::{
-    fri_gpu_prover_queue_dal::FriGpuProverQueueDal,
+    fri_gpu_prover_queue_dal::FriGpuProverQueueDal, fri_jobs_dal::FriJobsDal,
     fri_proof_compressor_dal::FriProofCompressorDal,
     fri_protocol_versions_dal::FriProtocolVersionsDal, fri_prover_dal::FriProverDal,
     fri_witness_generator_dal::FriWitnessGeneratorDal,
 };
 
 pub mod fri_gpu_prover_queue_dal;
+pub mod fri_jobs_dal;
 pub mod fri_proof_compressor_dal;
 pub mod fri_protocol_versions_dal;
 pub mod fri_prover_dal;
@@ -38,6 +39,8 @@ where
     fn fri_protocol_versions_dal(&mut self) -> FriProtocolVersionsDal<'_, 'a>;
 
     fn fri_proof_compressor_dal(&mut self) -> FriProofCompressorDal<'_, 'a>;
+
+    fn fri_jobs_dal(&mut self) -> FriJobsDal<'_, 'a>;
 }
 
 #[derive(Clone, Debug)]
@@ -68,4 +71,8 @@ impl<'a> ProverDal<'a> for Connection<'a, Prover> {
     fn fri_proof_compressor_dal(&mut self) -> FriProofCompressorDal<'_, 'a> {
         FriProofCompressorDal { storage: self }
     }
+
+    fn fri_jobs_dal(&mut self) -> FriJobsDal<'_, 'a> {
+        FriJobsDal { storage: self }
+    }
 }
@@ -3,7 +3,7 @@ pub mod gpu_prover {
     use std::{collections::HashMap, sync::Arc, time::Instant};
 
     use anyhow::Context as _;
-    use prover_dal::{ConnectionPool, ProverDal};
+    use prover_dal::{fri_jobs_dal::FriJobQueue, ConnectionPool, ProverDal};
     use shivini::{
         gpu_proof_config::GpuProofConfig, gpu_prove_from_external_witness_data, ProverContext,
     };
@@ -28,7 +28,8 @@ pub mod gpu_prover {
         },
         CircuitWrapper, FriProofWrapper, ProverServiceDataKey, WitnessVectorArtifacts,
     };
-    use zksync_queued_job_processor::{async_trait, JobProcessor};
+    use zksync_prover_fri_utils::job_queue::FriJobQueueStorage;
+    use zksync_queued_job_processor::{async_trait, QueuedJobProcessor};
     use zksync_types::{
         basic_fri_types::CircuitIdRoundTuple, protocol_version::ProtocolSemanticVersion,
         prover_dal::SocketAddress,
@@ -57,6 +58,7 @@ pub mod gpu_prover {
         public_blob_store: Option<Arc<dyn ObjectStore>>,
         config: Arc<FriProverConfig>,
         prover_connection_pool: ConnectionPool<prover_dal::Prover>,
+        job_queue: FriJobQueueStorage<u32>,
         setup_load_mode: SetupLoadMode,
         // Only pick jobs for the configured circuit id and aggregation rounds.
         // Empty means all jobs are picked.
@@ -86,6 +88,10 @@ pub mod gpu_prover {
                 blob_store,
                 public_blob_store,
                 config: Arc::new(config),
+                job_queue: FriJobQueueStorage::new(
+                    prover_connection_pool.clone(),
+                    FriJobQueue::Prover,
+                ),
                 prover_connection_pool,
                 setup_load_mode,
                 circuit_ids_for_round_to_be_proven,
@@ -195,16 +201,21 @@ pub mod gpu_prover {
     }
 
     #[async_trait]
-    impl JobProcessor for Prover {
+    impl QueuedJobProcessor for Prover {
         type Job = GpuProverJob;
         type JobId = u32;
         type JobArtifacts = ProverArtifacts;
+        type Queue = FriJobQueueStorage<Self::JobId>;
 
         // we use smaller number here as the polling in done from the in-memory queue not DB
         const POLLING_INTERVAL_MS: u64 = 200;
         const MAX_BACKOFF_MS: u64 = 1_000;
         const SERVICE_NAME: &'static str = "FriGpuProver";
 
+        fn job_queue(&self) -> &Self::Queue {
+            &self.job_queue
+        }
+
         async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
             let now = Instant::now();
             tracing::info!("Attempting to get new job from assembly queue.");
@@ -247,16 +258,6 @@ pub mod gpu_prover {
             }
         }
 
-        async fn save_failure(&self, job_id: Self::JobId, _started_at: Instant, error: String) {
-            self.prover_connection_pool
-                .connection()
-                .await
-                .unwrap()
-                .fri_prover_jobs_dal()
-                .save_proof_error(job_id, error)
-                .await;
-        }
-
         async fn process_job(
             &self,
             _job_id: &Self::JobId,
@@ -302,20 +303,6 @@ pub mod gpu_prover {
         fn max_attempts(&self) -> u32 {
             self.config.max_attempts
         }
-
-        async fn get_job_attempts(&self, job_id: &u32) -> anyhow::Result<u32> {
-            let mut prover_storage = self
-                .prover_connection_pool
-                .connection()
-                .await
-                .context("failed to acquire DB connection for Prover")?;
-            prover_storage
-                .fri_prover_jobs_dal()
-                .get_prover_job_attempts(*job_id)
-                .await
-                .map(|attempts| attempts.unwrap_or(0))
-                .context("failed to get job attempts for Prover")
-        }
     }
 
     pub fn load_setup_data_cache(config: &FriProverConfig) -> anyhow::Result<SetupLoadMode> {
@@ -49,6 +49,14 @@ pub(crate) struct ProverFriMetrics {
     #[metrics(buckets = Buckets::LATENCIES, labels = ["circuit_type"])]
     pub blob_save_time: LabeledFamily<String, Histogram<Duration>>,
     pub zombie_prover_instances_count: Family<KillingReason, Counter>,
+    /// Number of times setup data was loaded from disk because the previous job used another circuit.
+    #[metrics(labels = ["circuit_type"])]
+    pub setup_data_reloads: LabeledFamily<String, Counter>,
+    /// Number of times setup data loaded for the previous job was reused.
+    #[metrics(labels = ["circuit_type"])]
+    pub setup_data_reuses: LabeledFamily<String, Counter>,
+    /// Number of jobs picked because they use the same circuit as the previous job.
+    pub sticky_job_picks: Counter,
 }
 
 #[vise::register]
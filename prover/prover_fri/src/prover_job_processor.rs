@@ -1,7 +1,11 @@
-use std::{collections::HashMap, sync::Arc, time::Instant};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
 
 use anyhow::Context as _;
-use prover_dal::{ConnectionPool, ProverDal};
+use prover_dal::{fri_jobs_dal::FriJobQueue, ConnectionPool};
 use tokio::task::JoinHandle;
 use zkevm_test_harness::prover_utils::{prove_base_layer_circuit, prove_recursion_layer_circuit};
 use zksync_config::configs::{fri_prover_group::FriProverGroupConfig, FriProverConfig};
@@ -19,8 +23,11 @@ use zksync_prover_fri_types::{
     },
     CircuitWrapper, FriProofWrapper, ProverJob, ProverServiceDataKey,
 };
-use zksync_prover_fri_utils::fetch_next_circuit;
-use zksync_queued_job_processor::{async_trait, JobProcessor};
+use zksync_prover_fri_utils::{
+    fetch_next_circuit_with_affinity, get_all_circuit_id_round_tuples_for,
+    job_queue::FriJobQueueStorage,
+};
+use zksync_queued_job_processor::{async_trait, JobPrefetchConfig, QueuedJobProcessor};
 use zksync_types::{
     basic_fri_types::CircuitIdRoundTuple, protocol_version::ProtocolSemanticVersion,
 };
@@ -39,16 +46,51 @@ pub enum SetupLoadMode {
     FromDisk,
 }
 
+/// Tracks the circuit of the last picked jobs, so that jobs for the same circuit can be picked consecutively
+/// without reloading setup data.
+#[derive(Debug, Default)]
+struct CircuitAffinity {
+    last_circuit: Option<CircuitIdRoundTuple>,
+    consecutive_sticky_jobs: u32,
+}
+
+impl CircuitAffinity {
+    /// Returns circuits the next job should preferably be picked for. Once `max_sticky_jobs` consecutive jobs
+    /// were picked this way, the next job is picked in the regular order, so that other circuits aren't starved.
+    fn preferred_circuits(&self, max_sticky_jobs: u32) -> Vec<CircuitIdRoundTuple> {
+        match &self.last_circuit {
+            Some(circuit) if self.consecutive_sticky_jobs < max_sticky_jobs => {
+                // All node aggregation circuits share the same setup data.
+                get_all_circuit_id_round_tuples_for(vec![circuit.clone()])
+            }
+            _ => vec![],
+        }
+    }
+
+    fn record_job(&mut self, key: &ProverServiceDataKey, is_sticky: bool) {
+        if is_sticky {
+            self.consecutive_sticky_jobs += 1;
+        } else {
+            self.consecutive_sticky_jobs = 0;
+        }
+        self.last_circuit = Some(CircuitIdRoundTuple::new(key.circuit_id, key.round as u8));
+    }
+}
+
 pub struct Prover {
     blob_store: Arc<dyn ObjectStore>,
     public_blob_store: Option<Arc<dyn ObjectStore>>,
     config: Arc<FriProverConfig>,
     prover_connection_pool: ConnectionPool<prover_dal::Prover>,
+    job_queue: FriJobQueueStorage<u32>,
     setup_load_mode: SetupLoadMode,
     // Only pick jobs for the configured circuit id and aggregation rounds.
     // Empty means all jobs are picked.
     circuit_ids_for_round_to_be_proven: Vec<CircuitIdRoundTuple>,
     protocol_version: ProtocolSemanticVersion,
+    circuit_affinity: Mutex<CircuitAffinity>,
+    // Setup data used by the previous job; only retained in `FromDisk` mode.
+    last_setup_data: Mutex<Option<(ProverServiceDataKey, Arc<GoldilocksProverSetupData>)>>,
 }
 
 impl Prover {
@@ -66,10 +108,13 @@ impl Prover {
             blob_store,
             public_blob_store,
             config: Arc::new(config),
+            job_queue: FriJobQueueStorage::new(prover_connection_pool.clone(), FriJobQueue::Prover),
             prover_connection_pool,
             setup_load_mode,
             circuit_ids_for_round_to_be_proven,
             protocol_version,
+            circuit_affinity: Mutex::default(),
+            last_setup_data: Mutex::default(),
         }
     }
 
@@ -84,15 +129,28 @@ impl Prover {
                 .context("Setup data not found in cache")?
                 .clone(),
             SetupLoadMode::FromDisk => {
+                let mut last_setup_data = self.last_setup_data.lock().unwrap();
+                let circuit_type = key.circuit_id.to_string();
+                if let Some((last_key, setup_data)) = &*last_setup_data {
+                    if *last_key == key {
+                        METRICS.setup_data_reuses[&circuit_type].inc();
+                        return Ok(setup_data.clone());
+                    }
+                }
+                // Drop the previous setup data before loading the new one to limit memory usage.
+                *last_setup_data = None;
+
                 let started_at = Instant::now();
                 let keystore = Keystore::default();
                 let artifact: GoldilocksProverSetupData = keystore
                     .load_cpu_setup_data_for_circuit_type(key.clone())
                     .context("get_cpu_setup_data_for_circuit_type()")?;
-                METRICS.gpu_setup_data_load_time[&key.circuit_id.to_string()]
-                    .observe(started_at.elapsed());
+                METRICS.gpu_setup_data_load_time[&circuit_type].observe(started_at.elapsed());
+                METRICS.setup_data_reloads[&circuit_type].inc();
 
-                Arc::new(artifact)
+                let artifact = Arc::new(artifact);
+                *last_setup_data = Some((key, artifact.clone()));
+                artifact
             }
         })
     }
@@ -184,17 +242,28 @@ impl Prover {
 }
 
 #[async_trait]
-impl JobProcessor for Prover {
+impl QueuedJobProcessor for Prover {
     type Job = ProverJob;
     type JobId = u32;
     type JobArtifacts = ProverArtifacts;
+    type Queue = FriJobQueueStorage<Self::JobId>;
     const SERVICE_NAME: &'static str = "FriCpuProver";
 
+    fn job_queue(&self) -> &Self::Queue {
+        &self.job_queue
+    }
+
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
         let mut storage = self.prover_connection_pool.connection().await.unwrap();
-        let Some(prover_job) = fetch_next_circuit(
+        let preferred_circuits = self
+            .circuit_affinity
+            .lock()
+            .unwrap()
+            .preferred_circuits(self.config.max_sticky_jobs());
+        let Some((prover_job, is_sticky)) = fetch_next_circuit_with_affinity(
             &mut storage,
             &*self.blob_store,
+            &preferred_circuits,
             &self.circuit_ids_for_round_to_be_proven,
             &self.protocol_version,
         )
@@ -202,19 +271,16 @@ impl JobProcessor for Prover {
         else {
             return Ok(None);
         };
+        if is_sticky {
+            METRICS.sticky_job_picks.inc();
+        }
+        self.circuit_affinity.lock().unwrap().record_job(
+            &get_setup_data_key(prover_job.setup_data_key.clone()),
+            is_sticky,
+        );
         Ok(Some((prover_job.job_id, prover_job)))
     }
 
-    async fn save_failure(&self, job_id: Self::JobId, _started_at: Instant, error: String) {
-        self.prover_connection_pool
-            .connection()
-            .await
-            .unwrap()
-            .fri_prover_jobs_dal()
-            .save_proof_error(job_id, error)
-            .await;
-    }
-
     async fn process_job(
         &self,
         _job_id: &Self::JobId,
@@ -261,18 +327,16 @@ impl JobProcessor for Prover {
         self.config.max_attempts
     }
 
-    async fn get_job_attempts(&self, job_id: &u32) -> anyhow::Result<u32> {
-        let mut prover_storage = self
-            .prover_connection_pool
-            .connection()
-            .await
-            .context("failed to acquire DB connection for Prover")?;
-        prover_storage
-            .fri_prover_jobs_dal()
-            .get_prover_job_attempts(*job_id)
-            .await
-            .map(|attempts| attempts.unwrap_or(0))
-            .context("failed to get job attempts for Prover")
+    fn prefetch_config(&self) -> Option<JobPrefetchConfig> {
+        let depth = self.config.prefetch_depth();
+        (depth > 0).then(|| JobPrefetchConfig {
+            depth,
+            max_memory_bytes: self.config.prefetch_max_memory_bytes(),
+        })
+    }
+
+    fn job_size_in_bytes(&self, job: &Self::Job) -> usize {
+        bincode::serialized_size(&job.circuit_wrapper).map_or(0, |size| size as usize)
     }
 }
 
@@ -19,6 +19,8 @@ pub(crate) struct PeriodicApiStruct {
     pub(crate) blob_store: Arc<dyn ObjectStore>,
     pub(crate) pool: ConnectionPool<Prover>,
     pub(crate) api_url: String,
+    /// API key for the proof data handler API; `None` if the API is unauthenticated.
+    pub(crate) api_key: Option<String>,
     pub(crate) poll_duration: Duration,
     pub(crate) client: Client,
 }
@@ -35,9 +37,11 @@ impl PeriodicApiStruct {
     {
         tracing::info!("Sending request to {}", endpoint);
 
-        self.client
-            .post(endpoint)
-            .json(&request)
+        let mut request_builder = self.client.post(endpoint).json(&request);
+        if let Some(api_key) = &self.api_key {
+            request_builder = request_builder.bearer_auth(api_key);
+        }
+        request_builder
             .send()
             .await?
             .error_for_status()?
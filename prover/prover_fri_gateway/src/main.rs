@@ -70,6 +70,7 @@ async fn main() -> anyhow::Result<()> {
         pool: pool.clone(),
         api_url: format!("{}{SUBMIT_PROOF_PATH}", config.api_url),
         poll_duration: config.api_poll_duration(),
+        api_key: config.api_key.clone(),
         client: Client::new(),
     };
     let proof_gen_data_fetcher = PeriodicApiStruct {
@@ -77,6 +78,7 @@ async fn main() -> anyhow::Result<()> {
         pool,
         api_url: format!("{}{PROOF_GENERATION_DATA_PATH}", config.api_url),
         poll_duration: config.api_poll_duration(),
+        api_key: config.api_key.clone(),
         client: Client::new(),
     };
 
@@ -0,0 +1,122 @@
+//! [`JobQueue`] implementation backed by the prover Postgres database.
+
+use std::{fmt, marker::PhantomData};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use prover_dal::{
+    fri_jobs_dal::{FriJobClaim, FriJobQueue},
+    ConnectionPool, Prover, ProverDal,
+};
+use zksync_queued_job_processor::{JobClaim, JobQueue};
+use zksync_types::L1BatchNumber;
+
+/// Identifier of a job in one of [`FriJobQueue`]s.
+pub trait FriJobId: fmt::Debug + Send + Sync + 'static {
+    /// Converts this ID to the value stored in the ID column of the queue table.
+    fn to_db_id(&self) -> u64;
+}
+
+impl FriJobId for u32 {
+    fn to_db_id(&self) -> u64 {
+        (*self).into()
+    }
+}
+
+impl FriJobId for L1BatchNumber {
+    fn to_db_id(&self) -> u64 {
+        self.0.into()
+    }
+}
+
+/// [`JobQueue`] for jobs stored in one of [`FriJobQueue`]s.
+pub struct FriJobQueueStorage<Id> {
+    pool: ConnectionPool<Prover>,
+    queue: FriJobQueue,
+    _id: PhantomData<fn(Id)>,
+}
+
+impl<Id> fmt::Debug for FriJobQueueStorage<Id> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("FriJobQueueStorage")
+            .field("queue", &self.queue)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Id: FriJobId> FriJobQueueStorage<Id> {
+    pub fn new(pool: ConnectionPool<Prover>, queue: FriJobQueue) -> Self {
+        Self {
+            pool,
+            queue,
+            _id: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<Id: FriJobId> JobQueue for FriJobQueueStorage<Id> {
+    type JobId = Id;
+
+    async fn mark_job_failed(&self, job_id: &Id, error: &str) -> anyhow::Result<()> {
+        let mut storage = self
+            .pool
+            .connection()
+            .await
+            .with_context(|| format!("failed to acquire DB connection for {}", self.queue))?;
+        storage
+            .fri_jobs_dal()
+            .mark_job_failed(self.queue, job_id.to_db_id(), error)
+            .await
+            .with_context(|| format!("failed to mark {} job {job_id:?} as failed", self.queue))
+    }
+
+    async fn get_job_attempts(&self, job_id: &Id) -> anyhow::Result<u32> {
+        let mut storage = self
+            .pool
+            .connection()
+            .await
+            .with_context(|| format!("failed to acquire DB connection for {}", self.queue))?;
+        let attempts = storage
+            .fri_jobs_dal()
+            .get_job_attempts(self.queue, job_id.to_db_id())
+            .await
+            .with_context(|| format!("failed to get attempts for {} job {job_id:?}", self.queue))?;
+        Ok(attempts.unwrap_or(0))
+    }
+
+    async fn get_job_claim(&self, job_id: &Id) -> anyhow::Result<Option<JobClaim>> {
+        let mut storage = self
+            .pool
+            .connection()
+            .await
+            .with_context(|| format!("failed to acquire DB connection for {}", self.queue))?;
+        let claim = storage
+            .fri_jobs_dal()
+            .get_job_claim(self.queue, job_id.to_db_id())
+            .await
+            .with_context(|| format!("failed to get claim on {} job {job_id:?}", self.queue))?;
+        Ok(claim.map(|claim| JobClaim {
+            picked_by: claim.picked_by,
+            attempts: claim.attempts,
+        }))
+    }
+
+    async fn refresh_job_claim(&self, job_id: &Id, claim: &JobClaim) -> anyhow::Result<bool> {
+        let mut storage = self
+            .pool
+            .connection()
+            .await
+            .with_context(|| format!("failed to acquire DB connection for {}", self.queue))?;
+        let claim = FriJobClaim {
+            picked_by: claim.picked_by.clone(),
+            attempts: claim.attempts,
+        };
+        storage
+            .fri_jobs_dal()
+            .refresh_job_claim(self.queue, job_id.to_db_id(), &claim)
+            .await
+            .with_context(|| format!("failed to refresh claim on {} job {job_id:?}", self.queue))
+    }
+}
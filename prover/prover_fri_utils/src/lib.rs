@@ -20,6 +20,7 @@ use zksync_types::{
 
 use crate::metrics::{CircuitLabels, PROVER_FRI_UTILS_METRICS};
 
+pub mod job_queue;
 pub mod metrics;
 pub mod region_fetcher;
 pub mod socket_utils;
@@ -84,6 +85,33 @@ pub async fn fetch_next_circuit(
     ))
 }
 
+/// Fetches the next job for one of `preferred_circuits` (e.g., circuits using the setup data already loaded
+/// by the prover), falling back to [`fetch_next_circuit()`] if there are no such jobs queued.
+/// Returns the job together with a flag whether it was picked for the preferred circuits.
+pub async fn fetch_next_circuit_with_affinity(
+    storage: &mut Connection<'_, Prover>,
+    blob_store: &dyn ObjectStore,
+    preferred_circuits: &[CircuitIdRoundTuple],
+    circuit_ids_for_round_to_be_proven: &[CircuitIdRoundTuple],
+    protocol_version: &ProtocolSemanticVersion,
+) -> Option<(ProverJob, bool)> {
+    if !preferred_circuits.is_empty() {
+        let preferred_job =
+            fetch_next_circuit(storage, blob_store, preferred_circuits, protocol_version).await;
+        if let Some(job) = preferred_job {
+            return Some((job, true));
+        }
+    }
+    let job = fetch_next_circuit(
+        storage,
+        blob_store,
+        circuit_ids_for_round_to_be_proven,
+        protocol_version,
+    )
+    .await?;
+    Some((job, false))
+}
+
 pub fn get_recursive_layer_circuit_id_for_base_layer(base_layer_circuit_id: u8) -> u8 {
     let recursive_circuit_type = base_circuit_type_into_recursive_leaf_circuit_type(
         BaseLayerCircuitType::from_numeric_value(base_layer_circuit_id),
@@ -5,7 +5,6 @@ use std::{
     time::Instant,
 };
 
-use anyhow::Context as _;
 use async_trait::async_trait;
 use circuit_definitions::{
     circuit_definitions::base_layer::ZkSyncBaseLayerStorage,
@@ -15,7 +14,7 @@ use circuit_definitions::{
 use multivm::vm_latest::{
     constants::MAX_CYCLES_FOR_TX, HistoryDisabled, StorageOracle as VmStorageOracle,
 };
-use prover_dal::{ConnectionPool, Prover, ProverDal};
+use prover_dal::{fri_jobs_dal::FriJobQueue, ConnectionPool, Prover, ProverDal};
 use tracing::Instrument;
 use zkevm_test_harness::geometry_config::get_geometry_config;
 use zksync_config::configs::FriWitnessGeneratorConfig;
@@ -35,9 +34,11 @@ use zksync_prover_fri_types::{
     keys::ClosedFormInputKey,
     AuxOutputWitnessWrapper,
 };
-use zksync_prover_fri_utils::get_recursive_layer_circuit_id_for_base_layer;
+use zksync_prover_fri_utils::{
+    get_recursive_layer_circuit_id_for_base_layer, job_queue::FriJobQueueStorage,
+};
 use zksync_prover_interface::inputs::{BasicCircuitWitnessGeneratorInput, PrepareBasicCircuitsJob};
-use zksync_queued_job_processor::JobProcessor;
+use zksync_queued_job_processor::{JobPrefetchConfig, QueuedJobProcessor};
 use zksync_state::{PostgresStorage, StorageView};
 use zksync_types::{
     basic_fri_types::{AggregationRound, Eip4844Blobs},
@@ -52,8 +53,8 @@ use crate::{
     precalculated_merkle_paths_provider::PrecalculatedMerklePathsProvider,
     storage_oracle::StorageOracle,
     utils::{
-        expand_bootloader_contents, save_circuit, ClosedFormInputWrapper,
-        SchedulerPartialInputWrapper, KZG_TRUSTED_SETUP_FILE,
+        expand_bootloader_contents, prefetch_config, save_circuit, serialized_size,
+        ClosedFormInputWrapper, SchedulerPartialInputWrapper, KZG_TRUSTED_SETUP_FILE,
     },
 };
 
@@ -89,6 +90,7 @@ pub struct BasicWitnessGenerator {
     public_blob_store: Option<Arc<dyn ObjectStore>>,
     connection_pool: ConnectionPool<Core>,
     prover_connection_pool: ConnectionPool<Prover>,
+    job_queue: FriJobQueueStorage<L1BatchNumber>,
     protocol_version: ProtocolSemanticVersion,
 }
 
@@ -106,6 +108,10 @@ impl BasicWitnessGenerator {
             object_store,
             public_blob_store,
             connection_pool,
+            job_queue: FriJobQueueStorage::new(
+                prover_connection_pool.clone(),
+                FriJobQueue::WitnessInputs,
+            ),
             prover_connection_pool,
             protocol_version,
         }
@@ -144,14 +150,19 @@ impl BasicWitnessGenerator {
 }
 
 #[async_trait]
-impl JobProcessor for BasicWitnessGenerator {
+impl QueuedJobProcessor for BasicWitnessGenerator {
     type Job = BasicWitnessGeneratorJob;
     type JobId = L1BatchNumber;
     // The artifact is optional to support skipping blocks when sampling is enabled.
     type JobArtifacts = Option<BasicCircuitArtifacts>;
+    type Queue = FriJobQueueStorage<Self::JobId>;
 
     const SERVICE_NAME: &'static str = "fri_basic_circuit_witness_generator";
 
+    fn job_queue(&self) -> &Self::Queue {
+        &self.job_queue
+    }
+
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
         let mut prover_connection = self.prover_connection_pool.connection().await?;
         let last_l1_batch_to_process = self.config.last_l1_batch_to_process();
@@ -182,16 +193,6 @@ impl JobProcessor for BasicWitnessGenerator {
         }
     }
 
-    async fn save_failure(&self, job_id: L1BatchNumber, _started_at: Instant, error: String) -> () {
-        self.prover_connection_pool
-            .connection()
-            .await
-            .unwrap()
-            .fri_witness_generator_dal()
-            .mark_witness_job_failed(&error, job_id)
-            .await;
-    }
-
     #[allow(clippy::async_yields_async)]
     async fn process_job(
         &self,
@@ -254,18 +255,12 @@ impl JobProcessor for BasicWitnessGenerator {
         self.config.max_attempts
     }
 
-    async fn get_job_attempts(&self, job_id: &L1BatchNumber) -> anyhow::Result<u32> {
-        let mut prover_storage = self
-            .prover_connection_pool
-            .connection()
-            .await
-            .context("failed to acquire DB connection for BasicWitnessGenerator")?;
-        prover_storage
-            .fri_witness_generator_dal()
-            .get_basic_circuit_witness_job_attempts(*job_id)
-            .await
-            .map(|attempts| attempts.unwrap_or(0))
-            .context("failed to get job attempts for BasicWitnessGenerator")
+    fn prefetch_config(&self) -> Option<JobPrefetchConfig> {
+        prefetch_config(&self.config)
+    }
+
+    fn job_size_in_bytes(&self, job: &Self::Job) -> usize {
+        serialized_size(&job.job)
     }
 }
 
@@ -3,7 +3,7 @@ use std::{sync::Arc, time::Instant};
 use anyhow::Context as _;
 use async_trait::async_trait;
 use circuit_definitions::circuit_definitions::recursion_layer::base_circuit_type_into_recursive_leaf_circuit_type;
-use prover_dal::{Prover, ProverDal};
+use prover_dal::{fri_jobs_dal::FriJobQueue, Prover, ProverDal};
 use zkevm_test_harness::{
     witness::recursive_aggregation::{compute_leaf_params, create_leaf_witnesses},
     zkevm_circuits::scheduler::aux::BaseLayerCircuitType,
@@ -28,8 +28,10 @@ use zksync_prover_fri_types::{
     keys::ClosedFormInputKey,
     FriProofWrapper,
 };
-use zksync_prover_fri_utils::get_recursive_layer_circuit_id_for_base_layer;
-use zksync_queued_job_processor::JobProcessor;
+use zksync_prover_fri_utils::{
+    get_recursive_layer_circuit_id_for_base_layer, job_queue::FriJobQueueStorage,
+};
+use zksync_queued_job_processor::{JobPrefetchConfig, QueuedJobProcessor};
 use zksync_types::{
     basic_fri_types::AggregationRound, protocol_version::ProtocolSemanticVersion,
     prover_dal::LeafAggregationJobMetadata, L1BatchNumber,
@@ -39,8 +41,8 @@ use zksync_vk_setup_data_server_fri::keystore::Keystore;
 use crate::{
     metrics::WITNESS_GENERATOR_METRICS,
     utils::{
-        load_proofs_for_job_ids, save_node_aggregations_artifacts,
-        save_recursive_layer_prover_input_artifacts, ClosedFormInputWrapper,
+        load_proofs_for_job_ids, prefetch_config, save_node_aggregations_artifacts,
+        save_recursive_layer_prover_input_artifacts, serialized_size, ClosedFormInputWrapper,
     },
 };
 
@@ -76,6 +78,7 @@ pub struct LeafAggregationWitnessGenerator {
     config: FriWitnessGeneratorConfig,
     object_store: Arc<dyn ObjectStore>,
     prover_connection_pool: ConnectionPool<Prover>,
+    job_queue: FriJobQueueStorage<u32>,
     protocol_version: ProtocolSemanticVersion,
 }
 
@@ -89,6 +92,10 @@ impl LeafAggregationWitnessGenerator {
         Self {
             config,
             object_store,
+            job_queue: FriJobQueueStorage::new(
+                prover_connection_pool.clone(),
+                FriJobQueue::LeafAggregation,
+            ),
             prover_connection_pool,
             protocol_version,
         }
@@ -109,13 +116,18 @@ impl LeafAggregationWitnessGenerator {
 }
 
 #[async_trait]
-impl JobProcessor for LeafAggregationWitnessGenerator {
+impl QueuedJobProcessor for LeafAggregationWitnessGenerator {
     type Job = LeafAggregationWitnessGeneratorJob;
     type JobId = u32;
     type JobArtifacts = LeafAggregationArtifacts;
+    type Queue = FriJobQueueStorage<Self::JobId>;
 
     const SERVICE_NAME: &'static str = "fri_leaf_aggregation_witness_generator";
 
+    fn job_queue(&self) -> &Self::Queue {
+        &self.job_queue
+    }
+
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
         let mut prover_connection = self.prover_connection_pool.connection().await?;
         let pod_name = get_current_pod_name();
@@ -135,16 +147,6 @@ impl JobProcessor for LeafAggregationWitnessGenerator {
         )))
     }
 
-    async fn save_failure(&self, job_id: u32, _started_at: Instant, error: String) -> () {
-        self.prover_connection_pool
-            .connection()
-            .await
-            .unwrap()
-            .fri_witness_generator_dal()
-            .mark_leaf_aggregation_job_failed(&error, job_id)
-            .await;
-    }
-
     #[allow(clippy::async_yields_async)]
     async fn process_job(
         &self,
@@ -195,18 +197,12 @@ impl JobProcessor for LeafAggregationWitnessGenerator {
         self.config.max_attempts
     }
 
-    async fn get_job_attempts(&self, job_id: &u32) -> anyhow::Result<u32> {
-        let mut prover_storage = self
-            .prover_connection_pool
-            .connection()
-            .await
-            .context("failed to acquire DB connection for LeafAggregationWitnessGenerator")?;
-        prover_storage
-            .fri_witness_generator_dal()
-            .get_leaf_aggregation_job_attempts(*job_id)
-            .await
-            .map(|attempts| attempts.unwrap_or(0))
-            .context("failed to get job attempts for LeafAggregationWitnessGenerator")
+    fn prefetch_config(&self) -> Option<JobPrefetchConfig> {
+        prefetch_config(&self.config)
+    }
+
+    fn job_size_in_bytes(&self, job: &Self::Job) -> usize {
+        serialized_size(&job.proofs)
     }
 }
 
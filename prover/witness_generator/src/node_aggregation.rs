@@ -2,7 +2,7 @@ use std::{sync::Arc, time::Instant};
 
 use anyhow::Context as _;
 use async_trait::async_trait;
-use prover_dal::{Prover, ProverDal};
+use prover_dal::{fri_jobs_dal::FriJobQueue, Prover, ProverDal};
 use zkevm_test_harness::witness::recursive_aggregation::{
     compute_node_vk_commitment, create_node_witnesses,
 };
@@ -23,7 +23,8 @@ use zksync_prover_fri_types::{
     keys::AggregationsKey,
     FriProofWrapper,
 };
-use zksync_queued_job_processor::JobProcessor;
+use zksync_prover_fri_utils::job_queue::FriJobQueueStorage;
+use zksync_queued_job_processor::{JobPrefetchConfig, QueuedJobProcessor};
 use zksync_types::{
     basic_fri_types::AggregationRound, protocol_version::ProtocolSemanticVersion,
     prover_dal::NodeAggregationJobMetadata, L1BatchNumber,
@@ -33,8 +34,8 @@ use zksync_vk_setup_data_server_fri::{keystore::Keystore, utils::get_leaf_vk_par
 use crate::{
     metrics::WITNESS_GENERATOR_METRICS,
     utils::{
-        load_proofs_for_job_ids, save_node_aggregations_artifacts,
-        save_recursive_layer_prover_input_artifacts, AggregationWrapper,
+        load_proofs_for_job_ids, prefetch_config, save_node_aggregations_artifacts,
+        save_recursive_layer_prover_input_artifacts, serialized_size, AggregationWrapper,
     },
 };
 
@@ -76,6 +77,7 @@ pub struct NodeAggregationWitnessGenerator {
     config: FriWitnessGeneratorConfig,
     object_store: Arc<dyn ObjectStore>,
     prover_connection_pool: ConnectionPool<Prover>,
+    job_queue: FriJobQueueStorage<u32>,
     protocol_version: ProtocolSemanticVersion,
 }
 
@@ -89,6 +91,10 @@ impl NodeAggregationWitnessGenerator {
         Self {
             config,
             object_store,
+            job_queue: FriJobQueueStorage::new(
+                prover_connection_pool.clone(),
+                FriJobQueue::NodeAggregation,
+            ),
             prover_connection_pool,
             protocol_version,
         }
@@ -140,13 +146,18 @@ impl NodeAggregationWitnessGenerator {
 }
 
 #[async_trait]
-impl JobProcessor for NodeAggregationWitnessGenerator {
+impl QueuedJobProcessor for NodeAggregationWitnessGenerator {
     type Job = NodeAggregationWitnessGeneratorJob;
     type JobId = u32;
     type JobArtifacts = NodeAggregationArtifacts;
+    type Queue = FriJobQueueStorage<Self::JobId>;
 
     const SERVICE_NAME: &'static str = "fri_node_aggregation_witness_generator";
 
+    fn job_queue(&self) -> &Self::Queue {
+        &self.job_queue
+    }
+
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
         let mut prover_connection = self.prover_connection_pool.connection().await?;
         let pod_name = get_current_pod_name();
@@ -166,16 +177,6 @@ impl JobProcessor for NodeAggregationWitnessGenerator {
         )))
     }
 
-    async fn save_failure(&self, job_id: u32, _started_at: Instant, error: String) -> () {
-        self.prover_connection_pool
-            .connection()
-            .await
-            .unwrap()
-            .fri_witness_generator_dal()
-            .mark_node_aggregation_job_failed(&error, job_id)
-            .await;
-    }
-
     #[allow(clippy::async_yields_async)]
     async fn process_job(
         &self,
@@ -219,18 +220,12 @@ impl JobProcessor for NodeAggregationWitnessGenerator {
         self.config.max_attempts
     }
 
-    async fn get_job_attempts(&self, job_id: &u32) -> anyhow::Result<u32> {
-        let mut prover_storage = self
-            .prover_connection_pool
-            .connection()
-            .await
-            .context("failed to acquire DB connection for NodeAggregationWitnessGenerator")?;
-        prover_storage
-            .fri_witness_generator_dal()
-            .get_node_aggregation_job_attempts(*job_id)
-            .await
-            .map(|attempts| attempts.unwrap_or(0))
-            .context("failed to get job attempts for NodeAggregationWitnessGenerator")
+    fn prefetch_config(&self) -> Option<JobPrefetchConfig> {
+        prefetch_config(&self.config)
+    }
+
+    fn job_size_in_bytes(&self, job: &Self::Job) -> usize {
+        serialized_size(&job.aggregations) + serialized_size(&job.proofs)
     }
 }
 
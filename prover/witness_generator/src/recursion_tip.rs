@@ -9,7 +9,7 @@ use circuit_definitions::{
     },
     recursion_layer_proof_config,
 };
-use prover_dal::{Prover, ProverDal};
+use prover_dal::{fri_jobs_dal::FriJobQueue, Prover, ProverDal};
 use zkevm_test_harness::{
     boojum::{
         field::{
@@ -44,7 +44,8 @@ use zksync_prover_fri_types::{
     keys::{ClosedFormInputKey, FriCircuitKey},
     CircuitWrapper,
 };
-use zksync_queued_job_processor::JobProcessor;
+use zksync_prover_fri_utils::job_queue::FriJobQueueStorage;
+use zksync_queued_job_processor::{JobPrefetchConfig, QueuedJobProcessor};
 use zksync_types::{
     basic_fri_types::AggregationRound, protocol_version::ProtocolSemanticVersion, L1BatchNumber,
 };
@@ -52,7 +53,7 @@ use zksync_vk_setup_data_server_fri::{keystore::Keystore, utils::get_leaf_vk_par
 
 use crate::{
     metrics::WITNESS_GENERATOR_METRICS,
-    utils::{load_proofs_for_recursion_tip, ClosedFormInputWrapper},
+    utils::{load_proofs_for_recursion_tip, prefetch_config, ClosedFormInputWrapper},
 };
 
 #[derive(Clone)]
@@ -75,6 +76,7 @@ pub struct RecursionTipWitnessGenerator {
     config: FriWitnessGeneratorConfig,
     object_store: Arc<dyn ObjectStore>,
     prover_connection_pool: ConnectionPool<Prover>,
+    job_queue: FriJobQueueStorage<L1BatchNumber>,
     protocol_version: ProtocolSemanticVersion,
 }
 
@@ -88,6 +90,10 @@ impl RecursionTipWitnessGenerator {
         Self {
             config,
             object_store,
+            job_queue: FriJobQueueStorage::new(
+                prover_connection_pool.clone(),
+                FriJobQueue::RecursionTip,
+            ),
             prover_connection_pool,
             protocol_version,
         }
@@ -133,13 +139,18 @@ impl RecursionTipWitnessGenerator {
 }
 
 #[async_trait]
-impl JobProcessor for RecursionTipWitnessGenerator {
+impl QueuedJobProcessor for RecursionTipWitnessGenerator {
     type Job = RecursionTipWitnessGeneratorJob;
     type JobId = L1BatchNumber;
     type JobArtifacts = RecursionTipArtifacts;
+    type Queue = FriJobQueueStorage<Self::JobId>;
 
     const SERVICE_NAME: &'static str = "recursion_tip_witness_generator";
 
+    fn job_queue(&self) -> &Self::Queue {
+        &self.job_queue
+    }
+
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
         let mut prover_connection = self.prover_connection_pool.connection().await?;
         let pod_name = get_current_pod_name();
@@ -168,16 +179,6 @@ impl JobProcessor for RecursionTipWitnessGenerator {
         )))
     }
 
-    async fn save_failure(&self, job_id: L1BatchNumber, _started_at: Instant, error: String) -> () {
-        self.prover_connection_pool
-            .connection()
-            .await
-            .unwrap()
-            .fri_witness_generator_dal()
-            .mark_recursion_tip_job_failed(&error, job_id)
-            .await;
-    }
-
     #[allow(clippy::async_yields_async)]
     async fn process_job(
         &self,
@@ -251,18 +252,8 @@ impl JobProcessor for RecursionTipWitnessGenerator {
         self.config.max_attempts
     }
 
-    async fn get_job_attempts(&self, job_id: &L1BatchNumber) -> anyhow::Result<u32> {
-        let mut prover_storage = self
-            .prover_connection_pool
-            .connection()
-            .await
-            .context("failed to acquire DB connection for RecursionTipWitnessGenerator")?;
-        prover_storage
-            .fri_witness_generator_dal()
-            .get_recursion_tip_witness_job_attempts(*job_id)
-            .await
-            .map(|attempts| attempts.unwrap_or(0))
-            .context("failed to get job attempts for RecursionTipWitnessGenerator")
+    fn prefetch_config(&self) -> Option<JobPrefetchConfig> {
+        prefetch_config(&self.config)
     }
 }
 
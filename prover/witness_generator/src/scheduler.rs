@@ -2,7 +2,7 @@ use std::{convert::TryInto, sync::Arc, time::Instant};
 
 use anyhow::Context as _;
 use async_trait::async_trait;
-use prover_dal::{Prover, ProverDal};
+use prover_dal::{fri_jobs_dal::FriJobQueue, Prover, ProverDal};
 use zkevm_test_harness::zkevm_circuits::recursion::{
     leaf_layer::input::RecursionLeafParametersWitness, NUM_BASE_LAYER_CIRCUITS,
 };
@@ -26,13 +26,17 @@ use zksync_prover_fri_types::{
     keys::FriCircuitKey,
     CircuitWrapper, FriProofWrapper,
 };
-use zksync_queued_job_processor::JobProcessor;
+use zksync_prover_fri_utils::job_queue::FriJobQueueStorage;
+use zksync_queued_job_processor::{JobPrefetchConfig, QueuedJobProcessor};
 use zksync_types::{
     basic_fri_types::AggregationRound, protocol_version::ProtocolSemanticVersion, L1BatchNumber,
 };
 use zksync_vk_setup_data_server_fri::{keystore::Keystore, utils::get_leaf_vk_params};
 
-use crate::{metrics::WITNESS_GENERATOR_METRICS, utils::SchedulerPartialInputWrapper};
+use crate::{
+    metrics::WITNESS_GENERATOR_METRICS,
+    utils::{prefetch_config, SchedulerPartialInputWrapper},
+};
 
 pub struct SchedulerArtifacts {
     pub scheduler_circuit: ZkSyncRecursiveLayerCircuit,
@@ -57,6 +61,7 @@ pub struct SchedulerWitnessGenerator {
     config: FriWitnessGeneratorConfig,
     object_store: Arc<dyn ObjectStore>,
     prover_connection_pool: ConnectionPool<Prover>,
+    job_queue: FriJobQueueStorage<L1BatchNumber>,
     protocol_version: ProtocolSemanticVersion,
 }
 
@@ -70,6 +75,10 @@ impl SchedulerWitnessGenerator {
         Self {
             config,
             object_store,
+            job_queue: FriJobQueueStorage::new(
+                prover_connection_pool.clone(),
+                FriJobQueue::Scheduler,
+            ),
             prover_connection_pool,
             protocol_version,
         }
@@ -116,13 +125,18 @@ impl SchedulerWitnessGenerator {
 }
 
 #[async_trait]
-impl JobProcessor for SchedulerWitnessGenerator {
+impl QueuedJobProcessor for SchedulerWitnessGenerator {
     type Job = SchedulerWitnessGeneratorJob;
     type JobId = L1BatchNumber;
     type JobArtifacts = SchedulerArtifacts;
+    type Queue = FriJobQueueStorage<Self::JobId>;
 
     const SERVICE_NAME: &'static str = "fri_scheduler_witness_generator";
 
+    fn job_queue(&self) -> &Self::Queue {
+        &self.job_queue
+    }
+
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
         let mut prover_connection = self.prover_connection_pool.connection().await?;
         let pod_name = get_current_pod_name();
@@ -150,16 +164,6 @@ impl JobProcessor for SchedulerWitnessGenerator {
         )))
     }
 
-    async fn save_failure(&self, job_id: L1BatchNumber, _started_at: Instant, error: String) -> () {
-        self.prover_connection_pool
-            .connection()
-            .await
-            .unwrap()
-            .fri_witness_generator_dal()
-            .mark_scheduler_job_failed(&error, job_id)
-            .await;
-    }
-
     #[allow(clippy::async_yields_async)]
     async fn process_job(
         &self,
@@ -228,18 +232,8 @@ impl JobProcessor for SchedulerWitnessGenerator {
         self.config.max_attempts
     }
 
-    async fn get_job_attempts(&self, job_id: &L1BatchNumber) -> anyhow::Result<u32> {
-        let mut prover_storage = self
-            .prover_connection_pool
-            .connection()
-            .await
-            .context("failed to acquire DB connection for SchedulerWitnessGenerator")?;
-        prover_storage
-            .fri_witness_generator_dal()
-            .get_scheduler_witness_job_attempts(*job_id)
-            .await
-            .map(|attempts| attempts.unwrap_or(0))
-            .context("failed to get job attempts for SchedulerWitnessGenerator")
+    fn prefetch_config(&self) -> Option<JobPrefetchConfig> {
+        prefetch_config(&self.config)
     }
 }
 
@@ -13,6 +13,7 @@ use zkevm_test_harness::{
     boojum::field::goldilocks::GoldilocksField, empty_node_proof,
     zkevm_circuits::scheduler::aux::BaseLayerCircuitType,
 };
+use zksync_config::configs::FriWitnessGeneratorConfig;
 use zksync_object_store::{serialize_using_bincode, Bucket, ObjectStore, StoredObject};
 use zksync_prover_fri_types::{
     circuit_definitions::{
@@ -30,8 +31,23 @@ use zksync_prover_fri_types::{
     keys::{AggregationsKey, ClosedFormInputKey, FriCircuitKey},
     CircuitWrapper, FriProofWrapper,
 };
+use zksync_queued_job_processor::JobPrefetchConfig;
 use zksync_types::{basic_fri_types::AggregationRound, L1BatchNumber, ProtocolVersionId, U256};
 
+/// Returns job prefetching configuration shared by all witness generators.
+pub(crate) fn prefetch_config(config: &FriWitnessGeneratorConfig) -> Option<JobPrefetchConfig> {
+    let depth = config.prefetch_depth();
+    (depth > 0).then(|| JobPrefetchConfig {
+        depth,
+        max_memory_bytes: config.prefetch_max_memory_bytes(),
+    })
+}
+
+/// Estimates memory used by a job input by its serialized size.
+pub(crate) fn serialized_size<T: serde::Serialize>(value: &T) -> usize {
+    bincode::serialized_size(value).map_or(0, |size| size as usize)
+}
+
 // Creates a temporary file with the serialized KZG setup usable by `zkevm_test_harness` functions.
 pub(crate) static KZG_TRUSTED_SETUP_FILE: Lazy<tempfile::NamedTempFile> = Lazy::new(|| {
     let mut file = tempfile::NamedTempFile::new().expect("cannot create file for KZG setup");
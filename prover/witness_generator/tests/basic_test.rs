@@ -31,6 +31,7 @@ async fn test_leaf_witness_gen() {
         },
         max_retries: 5,
         local_mirror_path: None,
+        replica: None,
     };
     let object_store = ObjectStoreFactory::new(object_store_config)
         .create_store()
@@ -73,6 +74,7 @@ async fn test_node_witness_gen() {
         },
         max_retries: 5,
         local_mirror_path: None,
+        replica: None,
     };
     let object_store = ObjectStoreFactory::new(object_store_config)
         .create_store()
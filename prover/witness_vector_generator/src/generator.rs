@@ -6,7 +6,7 @@ use std::{
 
 use anyhow::Context as _;
 use async_trait::async_trait;
-use prover_dal::{ConnectionPool, Prover, ProverDal};
+use prover_dal::{fri_jobs_dal::FriJobQueue, ConnectionPool, Prover, ProverDal};
 use tokio::{task::JoinHandle, time::sleep};
 use zksync_config::configs::FriWitnessVectorGeneratorConfig;
 use zksync_object_store::ObjectStore;
@@ -15,9 +15,10 @@ use zksync_prover_fri_types::{
     WitnessVectorArtifacts,
 };
 use zksync_prover_fri_utils::{
-    fetch_next_circuit, get_numeric_circuit_id, socket_utils::send_assembly,
+    fetch_next_circuit, get_numeric_circuit_id, job_queue::FriJobQueueStorage,
+    socket_utils::send_assembly,
 };
-use zksync_queued_job_processor::JobProcessor;
+use zksync_queued_job_processor::{JobQueue, QueuedJobProcessor};
 use zksync_types::{
     basic_fri_types::CircuitIdRoundTuple, protocol_version::ProtocolSemanticVersion,
     prover_dal::GpuProverInstanceStatus,
@@ -29,6 +30,7 @@ use crate::metrics::METRICS;
 pub struct WitnessVectorGenerator {
     object_store: Arc<dyn ObjectStore>,
     pool: ConnectionPool<Prover>,
+    job_queue: FriJobQueueStorage<u32>,
     circuit_ids_for_round_to_be_proven: Vec<CircuitIdRoundTuple>,
     zone: String,
     config: FriWitnessVectorGeneratorConfig,
@@ -48,6 +50,7 @@ impl WitnessVectorGenerator {
     ) -> Self {
         Self {
             object_store,
+            job_queue: FriJobQueueStorage::new(prover_connection_pool.clone(), FriJobQueue::Prover),
             pool: prover_connection_pool,
             circuit_ids_for_round_to_be_proven,
             zone,
@@ -77,14 +80,19 @@ impl WitnessVectorGenerator {
 }
 
 #[async_trait]
-impl JobProcessor for WitnessVectorGenerator {
+impl QueuedJobProcessor for WitnessVectorGenerator {
     type Job = ProverJob;
     type JobId = u32;
     type JobArtifacts = WitnessVectorArtifacts;
+    type Queue = FriJobQueueStorage<Self::JobId>;
 
     const POLLING_INTERVAL_MS: u64 = 15000;
     const SERVICE_NAME: &'static str = "WitnessVectorGenerator";
 
+    fn job_queue(&self) -> &Self::Queue {
+        &self.job_queue
+    }
+
     async fn get_next_job(&self) -> anyhow::Result<Option<(Self::JobId, Self::Job)>> {
         let mut storage = self.pool.connection().await.unwrap();
         let Some(job) = fetch_next_circuit(
@@ -100,16 +108,6 @@ impl JobProcessor for WitnessVectorGenerator {
         Ok(Some((job.job_id, job)))
     }
 
-    async fn save_failure(&self, job_id: Self::JobId, _started_at: Instant, error: String) {
-        self.pool
-            .connection()
-            .await
-            .unwrap()
-            .fri_prover_jobs_dal()
-            .save_proof_error(job_id, error)
-            .await;
-    }
-
     async fn process_job(
         &self,
         _job_id: &Self::JobId,
@@ -168,7 +166,16 @@ impl JobProcessor for WitnessVectorGenerator {
                     now.elapsed()
                 );
                 let result = send_assembly(job_id, &serialized, &address);
-                handle_send_result(&result, job_id, &address, &self.pool, self.zone.clone()).await;
+                handle_send_result(
+                    &result,
+                    job_id,
+                    &address,
+                    &self.pool,
+                    &self.job_queue,
+                    self.zone.clone(),
+                )
+                .await
+                .context("handle_send_result()")?;
 
                 if result.is_ok() {
                     METRICS.prover_waiting_time[&circuit_type].observe(now.elapsed());
@@ -205,20 +212,6 @@ impl JobProcessor for WitnessVectorGenerator {
     fn max_attempts(&self) -> u32 {
         self.max_attempts
     }
-
-    async fn get_job_attempts(&self, job_id: &u32) -> anyhow::Result<u32> {
-        let mut prover_storage = self
-            .pool
-            .connection()
-            .await
-            .context("failed to acquire DB connection for WitnessVectorGenerator")?;
-        prover_storage
-            .fri_prover_jobs_dal()
-            .get_prover_job_attempts(*job_id)
-            .await
-            .map(|attempts| attempts.unwrap_or(0))
-            .context("failed to get job attempts for WitnessVectorGenerator")
-    }
 }
 
 async fn handle_send_result(
@@ -226,8 +219,9 @@ async fn handle_send_result(
     job_id: u32,
     address: &SocketAddr,
     pool: &ConnectionPool<Prover>,
+    job_queue: &FriJobQueueStorage<u32>,
     zone: String,
-) {
+) -> anyhow::Result<()> {
     match result {
         Ok((elapsed, len)) => {
             let blob_size_in_mb = len / (1024 * 1024);
@@ -266,12 +260,10 @@ async fn handle_send_result(
                 .await;
 
             // mark the job as failed
-            pool.connection()
-                .await
-                .unwrap()
-                .fri_prover_jobs_dal()
-                .save_proof_error(job_id, "prover instance unreachable".to_string())
-                .await;
+            job_queue
+                .mark_job_failed(&job_id, "prover instance unreachable")
+                .await?;
         }
     }
+    Ok(())
 }
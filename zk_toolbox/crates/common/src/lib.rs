@@ -12,6 +12,6 @@ mod term;
 pub mod wallets;
 
 pub use prerequisites::check_prerequisites;
-pub use prompt::{init_prompt_theme, Prompt, PromptConfirm, PromptSelect};
+pub use prompt::{init_prompt_theme, Prompt, PromptConfirm, PromptPassword, PromptSelect};
 pub use slugify::slugify;
 pub use term::{logger, spinner};
@@ -1,11 +1,13 @@
 mod confirm;
 mod input;
+mod password;
 mod select;
 
 use cliclack::{Theme, ThemeState};
 pub use confirm::PromptConfirm;
 use console::Style;
 pub use input::Prompt;
+pub use password::PromptPassword;
 pub use select::PromptSelect;
 
 pub struct CliclackTheme;
@@ -0,0 +1,19 @@
+use std::fmt::Display;
+
+use cliclack::Password;
+
+pub struct PromptPassword {
+    inner: Password,
+}
+
+impl PromptPassword {
+    pub fn new(question: impl Display) -> Self {
+        Self {
+            inner: Password::new(question).mask('▪'),
+        }
+    }
+
+    pub fn ask(mut self) -> String {
+        self.inner.interact().unwrap()
+    }
+}
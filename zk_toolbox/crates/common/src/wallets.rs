@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use ethers::{
     core::rand::Rng,
     signers::{coins_bip39::English, LocalWallet, MnemonicBuilder, Signer},
@@ -8,7 +10,12 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Wallet {
     pub address: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub private_key: Option<H256>,
+    /// Path to the encrypted keystore with the private key. Set instead of `private_key`
+    /// for wallets used by the server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keystore: Option<PathBuf>,
 }
 
 impl Wallet {
@@ -19,6 +26,7 @@ impl Wallet {
         Self {
             address: Address::from_slice(local_wallet.address().as_bytes()),
             private_key: Some(private_key),
+            keystore: None,
         }
     }
 
@@ -27,6 +35,7 @@ impl Wallet {
         Self {
             address: Address::from_slice(local_wallet.address().as_bytes()),
             private_key: Some(private_key),
+            keystore: None,
         }
     }
 
@@ -43,6 +52,7 @@ impl Wallet {
         Self {
             address: Address::zero(),
             private_key: Some(H256::zero()),
+            keystore: None,
         }
     }
 }
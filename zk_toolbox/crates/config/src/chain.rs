@@ -11,7 +11,8 @@ use xshell::Shell;
 
 use crate::{
     consts::{
-        CONFIG_NAME, CONTRACTS_FILE, GENESIS_FILE, L1_CONTRACTS_FOUNDRY, SECRETS_FILE, WALLETS_FILE,
+        CONFIG_NAME, CONTRACTS_FILE, GENESIS_FILE, KEYSTORE_DIR, L1_CONTRACTS_FOUNDRY,
+        SECRETS_FILE, WALLETS_FILE,
     },
     create_localhost_wallets,
     traits::{FileConfigWithDefaultName, ReadConfig, SaveConfig, SaveConfigWithBasePath},
@@ -91,6 +92,10 @@ impl ChainConfig {
         SecretsConfig::read(self.get_shell(), self.configs.join(SECRETS_FILE))
     }
 
+    pub fn path_to_keystores(&self) -> PathBuf {
+        self.configs.join(KEYSTORE_DIR)
+    }
+
     pub fn path_to_foundry(&self) -> PathBuf {
         self.link_to_code.join(L1_CONTRACTS_FOUNDRY)
     }
@@ -18,6 +18,8 @@ pub(crate) const INITIAL_DEPLOYMENT_FILE: &str = "initial_deployments.yaml";
 pub(crate) const ERC20_DEPLOYMENT_FILE: &str = "erc20_deployments.yaml";
 /// Name of the contracts file
 pub(crate) const CONTRACTS_FILE: &str = "contracts.yaml";
+/// Name of the directory with encrypted keystores
+pub(crate) const KEYSTORE_DIR: &str = "keystore";
 /// Main repository for the zkSync project
 pub const ZKSYNC_ERA_GIT_REPO: &str = "https://github.com/matter-labs/zksync-era";
 /// Name of the docker-compose file inside zksync repository
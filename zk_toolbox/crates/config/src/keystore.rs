@@ -0,0 +1,27 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use xshell::Shell;
+
+/// Encrypts the secret with the passphrase and saves it as a keystore named `name` in the `dir` directory.
+/// Keystores use the Web3 Secret Storage format, which the server can decrypt at startup.
+/// Returns the absolute path to the created keystore.
+pub fn encrypt_to_keystore(
+    shell: &Shell,
+    dir: &Path,
+    name: &str,
+    secret: &[u8],
+    passphrase: &str,
+) -> anyhow::Result<PathBuf> {
+    let dir = shell.current_dir().join(dir);
+    shell.create_dir(&dir)?;
+    eth_keystore::encrypt_key(
+        &dir,
+        &mut rand::thread_rng(),
+        secret,
+        passphrase,
+        Some(name),
+    )
+    .with_context(|| format!("failed encrypting keystore {name}"))?;
+    Ok(dir.join(name))
+}
@@ -5,6 +5,7 @@ mod ecosystem;
 mod file_config;
 mod general;
 mod genesis;
+mod keystore;
 mod manipulations;
 mod secrets;
 mod wallet_creation;
@@ -20,6 +21,7 @@ pub use ecosystem::*;
 pub use file_config::*;
 pub use general::*;
 pub use genesis::*;
+pub use keystore::*;
 pub use manipulations::*;
 pub use secrets::*;
 pub use wallet_creation::*;
@@ -1,5 +1,7 @@
+use std::fmt;
+
 use common::wallets::Wallet;
-use ethers::types::H256;
+use ethers::types::{Address, H256};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
@@ -15,6 +17,11 @@ pub struct WalletsConfig {
     pub blob_operator: Wallet,
     pub fee_account: Wallet,
     pub governor: Wallet,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prover_payer: Option<Wallet>,
+    /// Accounts used in tests, e.g. for sending transactions to the chain.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub test_rich_accounts: Vec<Wallet>,
 }
 
 impl WalletsConfig {
@@ -26,6 +33,8 @@ impl WalletsConfig {
             blob_operator: Wallet::random(rng),
             fee_account: Wallet::random(rng),
             governor: Wallet::random(rng),
+            prover_payer: Some(Wallet::random(rng)),
+            test_rich_accounts: vec![],
         }
     }
 
@@ -37,8 +46,44 @@ impl WalletsConfig {
             blob_operator: Wallet::empty(),
             fee_account: Wallet::empty(),
             governor: Wallet::empty(),
+            prover_payer: None,
+            test_rich_accounts: vec![],
+        }
+    }
+
+    /// Returns all wallets together with their roles.
+    pub fn wallets(&self) -> Vec<(WalletRole, &Wallet)> {
+        let mut wallets = vec![];
+        if let Some(deployer) = &self.deployer {
+            wallets.push((WalletRole::Deployer, deployer));
+        }
+        wallets.extend([
+            (WalletRole::Operator, &self.operator),
+            (WalletRole::BlobOperator, &self.blob_operator),
+            (WalletRole::FeeAccount, &self.fee_account),
+            (WalletRole::Governor, &self.governor),
+        ]);
+        if let Some(prover_payer) = &self.prover_payer {
+            wallets.push((WalletRole::ProverPayer, prover_payer));
         }
+        wallets.extend(
+            self.test_rich_accounts
+                .iter()
+                .enumerate()
+                .map(|(i, wallet)| (WalletRole::TestRichAccount(i), wallet)),
+        );
+        wallets
     }
+
+    /// Returns addresses of the wallets which send L1 transactions and thus must be funded.
+    pub fn funded_addresses(&self) -> Vec<Address> {
+        self.wallets()
+            .into_iter()
+            .filter(|(role, _)| role.requires_funding())
+            .map(|(_, wallet)| wallet.address)
+            .collect()
+    }
+
     pub fn deployer_private_key(&self) -> Option<H256> {
         self.deployer.as_ref().and_then(|wallet| wallet.private_key)
     }
@@ -48,6 +93,39 @@ impl WalletsConfig {
     }
 }
 
+/// Role of a wallet in the ecosystem or a chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalletRole {
+    Deployer,
+    Operator,
+    BlobOperator,
+    FeeAccount,
+    Governor,
+    ProverPayer,
+    TestRichAccount(usize),
+}
+
+impl WalletRole {
+    /// Checks whether the wallet with this role sends L1 transactions. The fee account only receives fees.
+    pub fn requires_funding(self) -> bool {
+        !matches!(self, Self::FeeAccount)
+    }
+}
+
+impl fmt::Display for WalletRole {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Deployer => formatter.write_str("deployer"),
+            Self::Operator => formatter.write_str("operator"),
+            Self::BlobOperator => formatter.write_str("blob_operator"),
+            Self::FeeAccount => formatter.write_str("fee_account"),
+            Self::Governor => formatter.write_str("governor"),
+            Self::ProverPayer => formatter.write_str("prover_payer"),
+            Self::TestRichAccount(i) => write!(formatter, "test_rich_account_{i}"),
+        }
+    }
+}
+
 impl FileConfigWithDefaultName for WalletsConfig {
     const FILE_NAME: &'static str = WALLETS_FILE;
 }
@@ -0,0 +1,18 @@
+use clap::Parser;
+use common::PromptPassword;
+use serde::{Deserialize, Serialize};
+
+use crate::messages::{MSG_KEYSTORE_PASSPHRASE_HELP, MSG_KEYSTORE_PASSPHRASE_PROMPT};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Parser)]
+pub struct EncryptKeysArgs {
+    #[clap(long, help = MSG_KEYSTORE_PASSPHRASE_HELP)]
+    pub passphrase: Option<String>,
+}
+
+impl EncryptKeysArgs {
+    pub fn fill_values_with_prompt(self) -> String {
+        self.passphrase
+            .unwrap_or_else(|| PromptPassword::new(MSG_KEYSTORE_PASSPHRASE_PROMPT).ask())
+    }
+}
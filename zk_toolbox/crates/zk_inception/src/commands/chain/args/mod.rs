@@ -1,3 +1,5 @@
 pub mod create;
+pub mod encrypt_keys;
 pub mod genesis;
 pub mod init;
+pub mod test;
@@ -0,0 +1,47 @@
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::messages::{MSG_TEST_FROM_SUITE_HELP, MSG_TEST_SUITE_HELP};
+
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    ValueEnum,
+    EnumIter,
+    strum_macros::Display,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+pub enum TestSuite {
+    Integration,
+    Revert,
+    Upgrade,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Parser)]
+pub struct TestArgs {
+    #[clap(value_enum, help = MSG_TEST_SUITE_HELP, conflicts_with = "from")]
+    pub suite: Option<TestSuite>,
+    #[clap(long, value_enum, help = MSG_TEST_FROM_SUITE_HELP)]
+    pub from: Option<TestSuite>,
+}
+
+impl TestArgs {
+    /// Returns the test suites to run in order. If neither a suite nor a starting suite is specified,
+    /// all suites are run.
+    pub fn suites(&self) -> Vec<TestSuite> {
+        if let Some(suite) = self.suite {
+            return vec![suite];
+        }
+        TestSuite::iter()
+            .filter(|suite| self.from.map_or(true, |from| *suite >= from))
+            .collect()
+    }
+}
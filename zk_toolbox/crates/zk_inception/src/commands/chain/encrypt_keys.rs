@@ -0,0 +1,83 @@
+use anyhow::Context;
+use common::{config::global_config, logger};
+use config::{encrypt_to_keystore, traits::SaveConfigWithBasePath, EcosystemConfig};
+use xshell::Shell;
+
+use crate::{
+    commands::chain::args::encrypt_keys::EncryptKeysArgs,
+    messages::{
+        msg_consensus_key_not_string_err, msg_key_encrypted, msg_keys_encrypted,
+        MSG_CHAIN_NOT_INITIALIZED, MSG_KEYSTORE_PASSPHRASE_NOTE, MSG_NO_KEYS_TO_ENCRYPT,
+    },
+};
+
+/// Consensus secret keys which can be moved to keystores.
+const CONSENSUS_KEYS: [&str; 2] = ["validator_key", "node_key"];
+
+pub(crate) fn run(args: EncryptKeysArgs, shell: &Shell) -> anyhow::Result<()> {
+    let passphrase = args.fill_values_with_prompt();
+    let ecosystem_config = EcosystemConfig::from_file(shell)?;
+    let chain_config = ecosystem_config
+        .load_chain(global_config().chain_name.clone())
+        .context(MSG_CHAIN_NOT_INITIALIZED)?;
+    let keystore_dir = chain_config.path_to_keystores();
+    let mut encrypted_any = false;
+
+    // Only the keys used by the server are encrypted; other wallets are used by the toolbox itself.
+    let mut wallets = chain_config.get_wallets_config()?;
+    for (name, wallet) in [
+        ("operator", &mut wallets.operator),
+        ("blob_operator", &mut wallets.blob_operator),
+    ] {
+        let Some(private_key) = wallet.private_key.take() else {
+            // The key is already encrypted
+            continue;
+        };
+        let path = encrypt_to_keystore(
+            shell,
+            &keystore_dir,
+            &format!("{name}.json"),
+            private_key.as_bytes(),
+            &passphrase,
+        )?;
+        logger::step(msg_key_encrypted(name, &path));
+        wallet.keystore = Some(path);
+        encrypted_any = true;
+    }
+    wallets.save_with_base_path(shell, &chain_config.configs)?;
+
+    let mut secrets = chain_config.get_secrets_config()?;
+    if let Some(consensus) = secrets
+        .other
+        .get_mut("consensus")
+        .and_then(serde_json::Value::as_object_mut)
+    {
+        for key in CONSENSUS_KEYS {
+            let Some(secret) = consensus.remove(key) else {
+                continue;
+            };
+            let secret = secret
+                .as_str()
+                .with_context(|| msg_consensus_key_not_string_err(key))?;
+            let path = encrypt_to_keystore(
+                shell,
+                &keystore_dir,
+                &format!("consensus_{key}.json"),
+                secret.as_bytes(),
+                &passphrase,
+            )?;
+            logger::step(msg_key_encrypted(key, &path));
+            consensus.insert(format!("{key}_keystore"), path.display().to_string().into());
+            encrypted_any = true;
+        }
+    }
+    secrets.save_with_base_path(shell, &chain_config.configs)?;
+
+    if !encrypted_any {
+        logger::outro(MSG_NO_KEYS_TO_ENCRYPT);
+        return Ok(());
+    }
+    logger::info(MSG_KEYSTORE_PASSPHRASE_NOTE);
+    logger::outro(msg_keys_encrypted(&chain_config.name));
+    Ok(())
+}
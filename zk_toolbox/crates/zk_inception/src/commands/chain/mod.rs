@@ -1,9 +1,11 @@
 pub(crate) mod args;
 mod create;
 pub mod deploy_paymaster;
+mod encrypt_keys;
 pub mod genesis;
 pub(crate) mod init;
 mod initialize_bridges;
+mod test;
 
 pub(crate) use args::create::ChainCreateArgsFinal;
 use clap::Subcommand;
@@ -11,7 +13,10 @@ use common::forge::ForgeScriptArgs;
 pub(crate) use create::create_chain_inner;
 use xshell::Shell;
 
-use crate::commands::chain::args::{create::ChainCreateArgs, genesis::GenesisArgs, init::InitArgs};
+use crate::commands::chain::args::{
+    create::ChainCreateArgs, encrypt_keys::EncryptKeysArgs, genesis::GenesisArgs, init::InitArgs,
+    test::TestArgs,
+};
 
 #[derive(Subcommand, Debug)]
 pub enum ChainCommands {
@@ -25,6 +30,10 @@ pub enum ChainCommands {
     InitializeBridges(ForgeScriptArgs),
     /// Initialize bridges on l2
     DeployPaymaster(ForgeScriptArgs),
+    /// Run integration, revert or upgrade test suites against the chain
+    Test(TestArgs),
+    /// Move private keys used by the server to encrypted keystores
+    EncryptKeys(EncryptKeysArgs),
 }
 
 pub(crate) async fn run(shell: &Shell, args: ChainCommands) -> anyhow::Result<()> {
@@ -34,5 +43,7 @@ pub(crate) async fn run(shell: &Shell, args: ChainCommands) -> anyhow::Result<()
         ChainCommands::Genesis(args) => genesis::run(args, shell).await,
         ChainCommands::InitializeBridges(args) => initialize_bridges::run(args, shell).await,
         ChainCommands::DeployPaymaster(args) => deploy_paymaster::run(args, shell).await,
+        ChainCommands::Test(args) => test::run(args, shell),
+        ChainCommands::EncryptKeys(args) => encrypt_keys::run(args, shell),
     }
 }
@@ -0,0 +1,125 @@
+use anyhow::Context;
+use common::{cmd::Cmd, config::global_config, logger, spinner::Spinner};
+use config::{traits::ReadConfigWithBasePath, ChainConfig, EcosystemConfig, GeneralConfig};
+use xshell::{cmd, Shell};
+
+use crate::{
+    commands::chain::args::test::{TestArgs, TestSuite},
+    messages::{
+        msg_running_test_suite, msg_test_suites_passed, MSG_CHAIN_NOT_INITIALIZED,
+        MSG_L2_RPC_URL_NOT_FOUND_ERR, MSG_TESTS_BUILDING_CONTRACTS,
+        MSG_TESTS_BUILDING_DEPENDENCIES,
+    },
+};
+
+const CONTRACTS_TEST_DATA_PATH: &str = "etc/contracts-test-data";
+
+impl TestSuite {
+    fn path(self) -> &'static str {
+        match self {
+            Self::Integration => "core/tests/ts-integration",
+            Self::Revert => "core/tests/revert-test",
+            Self::Upgrade => "core/tests/upgrade-test",
+        }
+    }
+}
+
+pub fn run(args: TestArgs, shell: &Shell) -> anyhow::Result<()> {
+    let ecosystem_config = EcosystemConfig::from_file(shell)?;
+    let chain_config = ecosystem_config
+        .load_chain(global_config().chain_name.clone())
+        .context(MSG_CHAIN_NOT_INITIALIZED)?;
+    let envs = test_envs(shell, &ecosystem_config, &chain_config)?;
+    let suites = args.suites();
+
+    build_dependencies(shell, &ecosystem_config)?;
+    for suite in suites {
+        logger::info(msg_running_test_suite(suite, &chain_config.name));
+        let _dir_guard = shell.push_dir(ecosystem_config.link_to_code.join(suite.path()));
+        let command = match suite {
+            TestSuite::Integration => {
+                build_test_contracts(shell, &ecosystem_config)?;
+                cmd!(shell, "yarn jest --forceExit --testTimeout 60000")
+            }
+            TestSuite::Revert => cmd!(shell, "yarn mocha tests/revert-and-restart.test.ts"),
+            TestSuite::Upgrade => cmd!(shell, "yarn mocha tests/upgrade.test.ts"),
+        };
+        Cmd::new(command.envs(envs.clone()))
+            .with_force_run()
+            .run()?;
+    }
+
+    logger::outro(msg_test_suites_passed(&chain_config.name));
+    Ok(())
+}
+
+/// Returns environment variables pointing the test suites to the chain, based on its generated configs.
+fn test_envs(
+    shell: &Shell,
+    ecosystem_config: &EcosystemConfig,
+    chain_config: &ChainConfig,
+) -> anyhow::Result<Vec<(&'static str, String)>> {
+    let general = GeneralConfig::read_with_base_path(shell, &chain_config.configs)?;
+    let secrets = chain_config.get_secrets_config()?;
+    let contracts = chain_config.get_contracts_config()?;
+    let wallets = chain_config.get_wallets_config()?;
+
+    let web3_json_rpc = &general.other["api"]["web3_json_rpc"];
+    let l2_rpc_url = web3_json_rpc["http_url"]
+        .as_str()
+        .context(MSG_L2_RPC_URL_NOT_FOUND_ERR)?;
+    let mut envs = vec![
+        // Makes TS integration tests load the file-based configs of the chain
+        ("CHAIN_NAME", chain_config.name.clone()),
+        (
+            "CHAIN_ETH_NETWORK",
+            ecosystem_config.l1_network.to_string().to_ascii_lowercase(),
+        ),
+        ("API_WEB3_JSON_RPC_HTTP_URL", l2_rpc_url.to_owned()),
+        ("ETH_CLIENT_WEB3_URL", secrets.l1.l1_rpc_url.clone()),
+        (
+            "CONTRACTS_BASE_TOKEN_ADDR",
+            format!("{:?}", contracts.l1.base_token_addr),
+        ),
+        (
+            "ETH_SENDER_SENDER_OPERATOR_COMMIT_ETH_ADDR",
+            format!("{:?}", wallets.operator.address),
+        ),
+    ];
+    if let Some(ws_url) = web3_json_rpc["ws_url"].as_str() {
+        envs.push(("API_WEB3_JSON_RPC_WS_URL", ws_url.to_owned()));
+    }
+    // For localhost, the main wallet is derived from the test mnemonic by the suites themselves.
+    if let Some(private_key) = wallets
+        .test_rich_accounts
+        .first()
+        .and_then(|wallet| wallet.private_key)
+    {
+        envs.push(("MASTER_WALLET_PK", format!("{private_key:?}")));
+    }
+    Ok(envs)
+}
+
+fn build_dependencies(shell: &Shell, ecosystem_config: &EcosystemConfig) -> anyhow::Result<()> {
+    let _dir_guard = shell.push_dir(&ecosystem_config.link_to_code);
+    let spinner = Spinner::new(MSG_TESTS_BUILDING_DEPENDENCIES);
+
+    Cmd::new(cmd!(shell, "yarn install --frozen-lockfile")).run()?;
+    Cmd::new(cmd!(shell, "yarn utils build")).run()?;
+
+    spinner.finish();
+    Ok(())
+}
+
+fn build_test_contracts(shell: &Shell, ecosystem_config: &EcosystemConfig) -> anyhow::Result<()> {
+    let spinner = Spinner::new(MSG_TESTS_BUILDING_CONTRACTS);
+
+    Cmd::new(cmd!(shell, "yarn build")).run()?;
+    Cmd::new(cmd!(shell, "yarn build-yul")).run()?;
+
+    let _dir_guard = shell.push_dir(ecosystem_config.link_to_code.join(CONTRACTS_TEST_DATA_PATH));
+    Cmd::new(cmd!(shell, "yarn build")).run()?;
+
+    spinner.finish();
+    Ok(())
+}
@@ -0,0 +1,178 @@
+use std::{fmt, path::Path};
+
+use anyhow::Context;
+use common::{config::global_config, files::read_yaml_file, logger};
+use config::{
+    traits::{FileConfigWithDefaultName, ReadConfigWithBasePath},
+    EcosystemConfig, GeneralConfig, SecretsConfig,
+};
+use serde::Serialize;
+use serde_yaml::Value;
+use xshell::Shell;
+
+use crate::{
+    config_manipulations::set_general_values,
+    messages::{
+        msg_checking_chain_configs, msg_config_drift_detected, msg_config_file_missing,
+        msg_config_up_to_date, MSG_CHAIN_NOT_INITIALIZED, MSG_CONFIGS_UP_TO_DATE,
+        MSG_CONFIG_DRIFT_DETECTED_ERR, MSG_CONFIG_MISSING_KEYS, MSG_CONFIG_MODIFIED_VALUES,
+        MSG_CONFIG_UNKNOWN_KEYS,
+    },
+};
+
+/// Secrets which are set during chain genesis and thus can't be derived from the templates.
+const CHAIN_SPECIFIC_SECRETS: &[&str] = &[
+    "database.server_url",
+    "database.prover_url",
+    "l1.l1_rpc_url",
+];
+/// Contract addresses which are set during chain initialization and thus can't be derived
+/// from the ecosystem contracts.
+const CHAIN_SPECIFIC_CONTRACTS: &[&str] = &[
+    "l1.diamond_proxy_addr",
+    "l1.governance_addr",
+    "bridges.shared.l2_address",
+    "l2.testnet_paymaster_addr",
+];
+
+pub(crate) fn run(shell: &Shell) -> anyhow::Result<()> {
+    let ecosystem_config = EcosystemConfig::from_file(shell)?;
+    let chain_config = ecosystem_config
+        .load_chain(global_config().chain_name.clone())
+        .context(MSG_CHAIN_NOT_INITIALIZED)?;
+    logger::info(msg_checking_chain_configs(&chain_config.name));
+
+    let templates_path = ecosystem_config.get_default_configs_path();
+    let mut general = GeneralConfig::read_with_base_path(shell, &templates_path)?;
+    set_general_values(shell, &mut general, &chain_config);
+    let secrets = SecretsConfig::read_with_base_path(shell, &templates_path)?;
+    let mut contracts = ecosystem_config.get_contracts_config()?;
+    contracts.l1.base_token_addr = chain_config.base_token.address;
+
+    let mut drift_detected = false;
+    drift_detected |= check_config(shell, &chain_config.configs, &general, &[])?;
+    drift_detected |= check_config(
+        shell,
+        &chain_config.configs,
+        &secrets,
+        CHAIN_SPECIFIC_SECRETS,
+    )?;
+    drift_detected |= check_config(
+        shell,
+        &chain_config.configs,
+        &contracts,
+        CHAIN_SPECIFIC_CONTRACTS,
+    )?;
+
+    if drift_detected {
+        anyhow::bail!(MSG_CONFIG_DRIFT_DETECTED_ERR);
+    }
+    logger::outro(MSG_CONFIGS_UP_TO_DATE);
+    Ok(())
+}
+
+/// Compares the expected config with the one stored in `configs_path`. Returns `true` if they differ.
+fn check_config<T: Serialize + FileConfigWithDefaultName>(
+    shell: &Shell,
+    configs_path: &Path,
+    expected: &T,
+    chain_specific_keys: &[&str],
+) -> anyhow::Result<bool> {
+    let path = T::get_path_with_base_path(configs_path);
+    if !shell.path_exists(&path) {
+        logger::warn(msg_config_file_missing(T::FILE_NAME));
+        return Ok(true);
+    }
+    let actual: Value = read_yaml_file(shell, &path)?;
+    let expected = serde_yaml::to_value(expected)?;
+
+    let mut drift = ConfigDrift::default();
+    drift.compare(String::new(), &expected, &actual, chain_specific_keys);
+    if drift.is_empty() {
+        logger::success(msg_config_up_to_date(T::FILE_NAME));
+        Ok(false)
+    } else {
+        logger::note(msg_config_drift_detected(T::FILE_NAME), drift);
+        Ok(true)
+    }
+}
+
+/// Differences between the expected and the actual config, identified by dotted key paths.
+#[derive(Debug, Default)]
+struct ConfigDrift {
+    /// Keys present in the expected config, but not in the actual one.
+    missing: Vec<String>,
+    /// Keys present in the actual config, but not in the expected one.
+    unknown: Vec<String>,
+    /// Keys with values differing between the configs.
+    modified: Vec<String>,
+}
+
+impl ConfigDrift {
+    fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.unknown.is_empty() && self.modified.is_empty()
+    }
+
+    fn compare(&mut self, path: String, expected: &Value, actual: &Value, skipped: &[&str]) {
+        if skipped.contains(&path.as_str()) {
+            return;
+        }
+        match (expected, actual) {
+            (Value::Mapping(expected), Value::Mapping(actual)) => {
+                for (key, expected_value) in expected {
+                    let key_path = join_key(&path, key);
+                    match actual.get(key) {
+                        Some(actual_value) => {
+                            self.compare(key_path, expected_value, actual_value, skipped)
+                        }
+                        // Omitted optional values are equivalent to `null`s.
+                        None if expected_value.is_null()
+                            || skipped.contains(&key_path.as_str()) => {}
+                        None => self.missing.push(key_path),
+                    }
+                }
+                for key in actual.keys() {
+                    let key_path = join_key(&path, key);
+                    if !expected.contains_key(key) && !skipped.contains(&key_path.as_str()) {
+                        self.unknown.push(key_path);
+                    }
+                }
+            }
+            _ if expected != actual => self.modified.push(path),
+            _ => {}
+        }
+    }
+}
+
+impl fmt::Display for ConfigDrift {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sections = [
+            (MSG_CONFIG_MISSING_KEYS, &self.missing),
+            (MSG_CONFIG_UNKNOWN_KEYS, &self.unknown),
+            (MSG_CONFIG_MODIFIED_VALUES, &self.modified),
+        ];
+        for (title, keys) in sections {
+            if keys.is_empty() {
+                continue;
+            }
+            writeln!(formatter, "{title}:")?;
+            for key in keys {
+                writeln!(formatter, "  {key}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn join_key(path: &str, key: &Value) -> String {
+    let key = match key {
+        Value::String(key) => key.clone(),
+        _ => serde_yaml::to_string(key)
+            .map_or_else(|_| format!("{key:?}"), |key| key.trim().to_owned()),
+    };
+    if path.is_empty() {
+        key
+    } else {
+        format!("{path}.{key}")
+    }
+}
@@ -0,0 +1,16 @@
+use clap::Subcommand;
+use xshell::Shell;
+
+mod check;
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+    /// Check chain configs for drift from the configs derived from ecosystem and chain definitions
+    Check,
+}
+
+pub(crate) fn run(shell: &Shell, args: ConfigCommands) -> anyhow::Result<()> {
+    match args {
+        ConfigCommands::Check => check::run(shell),
+    }
+}
@@ -142,17 +142,9 @@ pub async fn distribute_eth(
         let spinner = Spinner::new(MSG_DISTRIBUTING_ETH_SPINNER);
         let wallets = ecosystem_config.get_wallets()?;
         let chain_wallets = chain_config.get_wallets_config()?;
-        let mut addresses = vec![
-            chain_wallets.operator.address,
-            chain_wallets.blob_operator.address,
-            chain_wallets.governor.address,
-        ];
-        if let Some(deployer) = chain_wallets.deployer {
-            addresses.push(deployer.address)
-        }
         common::ethereum::distribute_eth(
             wallets.operator,
-            addresses,
+            chain_wallets.funded_addresses(),
             l1_rpc_url,
             ecosystem_config.l1_network.chain_id(),
             AMOUNT_FOR_DISTRIBUTION_TO_WALLETS,
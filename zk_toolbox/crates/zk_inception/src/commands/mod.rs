@@ -1,5 +1,7 @@
 pub mod args;
 pub mod chain;
+pub mod config;
 pub mod containers;
 pub mod ecosystem;
 pub mod server;
+pub mod wallets;
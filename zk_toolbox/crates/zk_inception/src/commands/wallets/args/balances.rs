@@ -0,0 +1,18 @@
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use types::L1Network;
+
+use super::fill_l1_rpc_url_with_prompt;
+use crate::messages::MSG_L1_RPC_URL_HELP;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Parser)]
+pub struct BalancesArgs {
+    #[clap(long, help = MSG_L1_RPC_URL_HELP)]
+    pub l1_rpc_url: Option<String>,
+}
+
+impl BalancesArgs {
+    pub fn fill_values_with_prompt(self, l1_network: L1Network) -> String {
+        fill_l1_rpc_url_with_prompt(self.l1_rpc_url, l1_network)
+    }
+}
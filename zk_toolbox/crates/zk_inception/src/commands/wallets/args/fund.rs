@@ -0,0 +1,32 @@
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use types::L1Network;
+
+use super::fill_l1_rpc_url_with_prompt;
+use crate::{
+    consts::AMOUNT_FOR_DISTRIBUTION_TO_WALLETS,
+    messages::{MSG_FUND_AMOUNT_HELP, MSG_L1_RPC_URL_HELP},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Parser)]
+pub struct FundArgs {
+    #[clap(long, help = MSG_L1_RPC_URL_HELP)]
+    pub l1_rpc_url: Option<String>,
+    #[clap(long, help = MSG_FUND_AMOUNT_HELP)]
+    pub amount: Option<u128>,
+}
+
+impl FundArgs {
+    pub fn fill_values_with_prompt(self, l1_network: L1Network) -> FundArgsFinal {
+        FundArgsFinal {
+            l1_rpc_url: fill_l1_rpc_url_with_prompt(self.l1_rpc_url, l1_network),
+            amount: self.amount.unwrap_or(AMOUNT_FOR_DISTRIBUTION_TO_WALLETS),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundArgsFinal {
+    pub l1_rpc_url: String,
+    pub amount: u128,
+}
@@ -0,0 +1,10 @@
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+
+use crate::messages::MSG_TEST_RICH_ACCOUNTS_HELP;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Parser)]
+pub struct GenerateArgs {
+    #[clap(long, help = MSG_TEST_RICH_ACCOUNTS_HELP, default_value_t = 0)]
+    pub test_rich_accounts: usize,
+}
@@ -0,0 +1,27 @@
+use std::{path::PathBuf, str::FromStr};
+
+use clap::Parser;
+use common::Prompt;
+use serde::{Deserialize, Serialize};
+
+use crate::messages::{MSG_WALLET_PATH_HELP, MSG_WALLET_PATH_INVALID_ERR, MSG_WALLET_PATH_PROMPT};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Parser)]
+pub struct ImportArgs {
+    #[clap(long, help = MSG_WALLET_PATH_HELP)]
+    pub path: Option<PathBuf>,
+}
+
+impl ImportArgs {
+    pub fn fill_values_with_prompt(self) -> PathBuf {
+        self.path.unwrap_or_else(|| {
+            Prompt::new(MSG_WALLET_PATH_PROMPT)
+                .validate_with(|val: &String| {
+                    PathBuf::from_str(val)
+                        .map(|_| ())
+                        .map_err(|_| MSG_WALLET_PATH_INVALID_ERR.to_string())
+                })
+                .ask()
+        })
+    }
+}
@@ -0,0 +1,29 @@
+use common::Prompt;
+use types::L1Network;
+use url::Url;
+
+use crate::{
+    defaults::LOCAL_RPC_URL,
+    messages::{MSG_L1_RPC_URL_INVALID_ERR, MSG_L1_RPC_URL_PROMPT},
+};
+
+pub mod balances;
+pub mod fund;
+pub mod generate;
+pub mod import;
+
+fn fill_l1_rpc_url_with_prompt(l1_rpc_url: Option<String>, l1_network: L1Network) -> String {
+    l1_rpc_url.unwrap_or_else(|| {
+        let mut prompt = Prompt::new(MSG_L1_RPC_URL_PROMPT);
+        if l1_network == L1Network::Localhost {
+            prompt = prompt.default(LOCAL_RPC_URL);
+        }
+        prompt
+            .validate_with(|val: &String| -> Result<(), String> {
+                Url::parse(val)
+                    .map(|_| ())
+                    .map_err(|_| MSG_L1_RPC_URL_INVALID_ERR.to_string())
+            })
+            .ask()
+    })
+}
@@ -0,0 +1,74 @@
+use std::collections::BTreeMap;
+
+use anyhow::Context;
+use common::{config::global_config, logger};
+use config::{EcosystemConfig, WalletsConfig};
+use ethers::{
+    providers::{Http, Middleware, Provider},
+    types::{Address, U256},
+    utils::format_ether,
+};
+use xshell::Shell;
+
+use super::args::balances::BalancesArgs;
+use crate::{
+    consts::MINIMUM_BALANCE_FOR_WALLET,
+    messages::{
+        msg_chain_wallet_balances, msg_wallet_underfunded, MSG_CHAIN_NOT_INITIALIZED,
+        MSG_ECOSYSTEM_WALLET_BALANCES,
+    },
+};
+
+pub(crate) async fn run(args: BalancesArgs, shell: &Shell) -> anyhow::Result<()> {
+    let ecosystem_config = EcosystemConfig::from_file(shell)?;
+    let chain_config = ecosystem_config
+        .load_chain(global_config().chain_name.clone())
+        .context(MSG_CHAIN_NOT_INITIALIZED)?;
+    let l1_rpc_url = args.fill_values_with_prompt(ecosystem_config.l1_network);
+    let provider = Provider::<Http>::try_from(l1_rpc_url)?;
+
+    let wallets = ecosystem_config.get_wallets()?;
+    show_balances(&provider, MSG_ECOSYSTEM_WALLET_BALANCES, &wallets).await?;
+    let chain_wallets = chain_config.get_wallets_config()?;
+    show_balances(
+        &provider,
+        &msg_chain_wallet_balances(&chain_config.name),
+        &chain_wallets,
+    )
+    .await?;
+    Ok(())
+}
+
+async fn show_balances(
+    provider: &Provider<Http>,
+    title: &str,
+    wallets: &WalletsConfig,
+) -> anyhow::Result<()> {
+    let mut balances = BTreeMap::new();
+    let mut underfunded = vec![];
+    for (role, wallet) in wallets.wallets() {
+        let balance = provider.get_balance(wallet.address, None).await?;
+        if role.requires_funding() && balance < U256::from(MINIMUM_BALANCE_FOR_WALLET) {
+            underfunded.push(msg_wallet_underfunded(role, &wallet.address));
+        }
+        balances.insert(
+            role.to_string(),
+            format!("{:?} ({} ETH)", wallet.address, format_ether(balance)),
+        );
+    }
+
+    logger::note(title, logger::object_to_string(balances));
+    for msg in underfunded {
+        logger::warn(msg);
+    }
+    Ok(())
+}
+
+/// Returns addresses of the wallets keyed by their roles.
+pub(super) fn wallet_addresses(wallets: &WalletsConfig) -> BTreeMap<String, Address> {
+    wallets
+        .wallets()
+        .into_iter()
+        .map(|(role, wallet)| (role.to_string(), wallet.address))
+        .collect()
+}
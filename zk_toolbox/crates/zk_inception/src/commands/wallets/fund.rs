@@ -0,0 +1,38 @@
+use anyhow::Context;
+use common::{config::global_config, logger, spinner::Spinner};
+use config::EcosystemConfig;
+use types::L1Network;
+use xshell::Shell;
+
+use super::args::fund::FundArgs;
+use crate::messages::{
+    msg_wallets_funded, MSG_CHAIN_NOT_INITIALIZED, MSG_DISTRIBUTING_ETH_SPINNER,
+    MSG_WALLETS_FUNDING_LOCALHOST_ONLY_ERR,
+};
+
+pub(crate) async fn run(args: FundArgs, shell: &Shell) -> anyhow::Result<()> {
+    let ecosystem_config = EcosystemConfig::from_file(shell)?;
+    if ecosystem_config.l1_network != L1Network::Localhost {
+        anyhow::bail!(MSG_WALLETS_FUNDING_LOCALHOST_ONLY_ERR);
+    }
+    let chain_config = ecosystem_config
+        .load_chain(global_config().chain_name.clone())
+        .context(MSG_CHAIN_NOT_INITIALIZED)?;
+    let args = args.fill_values_with_prompt(ecosystem_config.l1_network);
+
+    let spinner = Spinner::new(MSG_DISTRIBUTING_ETH_SPINNER);
+    let wallets = ecosystem_config.get_wallets()?;
+    let chain_wallets = chain_config.get_wallets_config()?;
+    common::ethereum::distribute_eth(
+        wallets.operator,
+        chain_wallets.funded_addresses(),
+        args.l1_rpc_url,
+        ecosystem_config.l1_network.chain_id(),
+        args.amount,
+    )
+    .await?;
+    spinner.finish();
+
+    logger::outro(msg_wallets_funded(&chain_config.name));
+    Ok(())
+}
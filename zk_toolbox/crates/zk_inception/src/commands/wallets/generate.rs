@@ -0,0 +1,45 @@
+use anyhow::Context;
+use common::{config::global_config, logger, wallets::Wallet, PromptConfirm};
+use config::{
+    traits::{FileConfigWithDefaultName, SaveConfigWithBasePath},
+    EcosystemConfig, WalletsConfig,
+};
+use ethers::core::rand::thread_rng;
+use xshell::Shell;
+
+use super::{args::generate::GenerateArgs, balances::wallet_addresses};
+use crate::messages::{
+    msg_overwrite_wallets_prompt, msg_wallets_generated, MSG_CHAIN_NOT_INITIALIZED,
+    MSG_WALLETS_NOT_CHANGED, MSG_WALLETS_SUMMARY,
+};
+
+pub(crate) fn run(args: GenerateArgs, shell: &Shell) -> anyhow::Result<()> {
+    let ecosystem_config = EcosystemConfig::from_file(shell)?;
+    let chain_config = ecosystem_config
+        .load_chain(global_config().chain_name.clone())
+        .context(MSG_CHAIN_NOT_INITIALIZED)?;
+
+    let path = WalletsConfig::get_path_with_base_path(&chain_config.configs);
+    if shell.path_exists(path)
+        && !PromptConfirm::new(msg_overwrite_wallets_prompt(&chain_config.name))
+            .default(false)
+            .ask()
+    {
+        logger::outro(MSG_WALLETS_NOT_CHANGED);
+        return Ok(());
+    }
+
+    let rng = &mut thread_rng();
+    let mut wallets = WalletsConfig::random(rng);
+    wallets.test_rich_accounts = (0..args.test_rich_accounts)
+        .map(|_| Wallet::random(rng))
+        .collect();
+    wallets.save_with_base_path(shell, &chain_config.configs)?;
+
+    logger::note(
+        MSG_WALLETS_SUMMARY,
+        logger::object_to_string(wallet_addresses(&wallets)),
+    );
+    logger::outro(msg_wallets_generated(&chain_config.name));
+    Ok(())
+}
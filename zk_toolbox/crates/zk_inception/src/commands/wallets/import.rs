@@ -0,0 +1,40 @@
+use anyhow::Context;
+use common::{config::global_config, logger, wallets::Wallet};
+use config::{
+    traits::{ReadConfig, SaveConfigWithBasePath},
+    EcosystemConfig, WalletsConfig,
+};
+use xshell::Shell;
+
+use super::{args::import::ImportArgs, balances::wallet_addresses};
+use crate::messages::{
+    msg_wallet_key_mismatch_err, msg_wallets_imported, MSG_CHAIN_NOT_INITIALIZED,
+    MSG_WALLETS_SUMMARY,
+};
+
+pub(crate) fn run(args: ImportArgs, shell: &Shell) -> anyhow::Result<()> {
+    let path = args.fill_values_with_prompt();
+    let ecosystem_config = EcosystemConfig::from_file(shell)?;
+    let chain_config = ecosystem_config
+        .load_chain(global_config().chain_name.clone())
+        .context(MSG_CHAIN_NOT_INITIALIZED)?;
+
+    let wallets = WalletsConfig::read(shell, &path)?;
+    for (role, wallet) in wallets.wallets() {
+        if let Some(private_key) = wallet.private_key {
+            // Placeholder wallets have zero addresses and keys.
+            if !private_key.is_zero() && Wallet::new_with_key(private_key).address != wallet.address
+            {
+                anyhow::bail!(msg_wallet_key_mismatch_err(role));
+            }
+        }
+    }
+    wallets.save_with_base_path(shell, &chain_config.configs)?;
+
+    logger::note(
+        MSG_WALLETS_SUMMARY,
+        logger::object_to_string(wallet_addresses(&wallets)),
+    );
+    logger::outro(msg_wallets_imported(&chain_config.name));
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use clap::Subcommand;
+use xshell::Shell;
+
+use crate::commands::wallets::args::{
+    balances::BalancesArgs, fund::FundArgs, generate::GenerateArgs, import::ImportArgs,
+};
+
+pub(crate) mod args;
+mod balances;
+mod fund;
+mod generate;
+mod import;
+
+#[derive(Subcommand, Debug)]
+pub enum WalletsCommands {
+    /// Generate random wallets for the chain
+    Generate(GenerateArgs),
+    /// Import chain wallets from a file
+    Import(ImportArgs),
+    /// Fund chain wallets sending L1 transactions from the ecosystem operator (only for localhost L1)
+    Fund(FundArgs),
+    /// Display L1 balances of ecosystem and chain wallets
+    Balances(BalancesArgs),
+}
+
+pub(crate) async fn run(shell: &Shell, args: WalletsCommands) -> anyhow::Result<()> {
+    match args {
+        WalletsCommands::Generate(args) => generate::run(args, shell),
+        WalletsCommands::Import(args) => import::run(args, shell),
+        WalletsCommands::Fund(args) => fund::run(args, shell).await,
+        WalletsCommands::Balances(args) => balances::run(args, shell).await,
+    }
+}
@@ -49,14 +49,22 @@ pub(crate) fn update_l1_rpc_url_secret(
 
 pub(crate) fn update_general_config(shell: &Shell, config: &ChainConfig) -> anyhow::Result<()> {
     let mut general = GeneralConfig::read_with_base_path(shell, &config.configs)?;
-    general.db.state_keeper_db_path =
-        shell.create_dir(config.rocks_db_path.join(ROCKS_DB_STATE_KEEPER))?;
-    general.db.merkle_tree.path = shell.create_dir(config.rocks_db_path.join(ROCKS_DB_TREE))?;
+    shell.create_dir(config.rocks_db_path.join(ROCKS_DB_STATE_KEEPER))?;
+    shell.create_dir(config.rocks_db_path.join(ROCKS_DB_TREE))?;
+    set_general_values(shell, &mut general, config);
+    general.save_with_base_path(shell, &config.configs)?;
+    Ok(())
+}
+
+/// Sets general config values derived from the chain config. RocksDB paths are resolved relative
+/// to the current shell directory, but the directories are not created.
+pub(crate) fn set_general_values(shell: &Shell, general: &mut GeneralConfig, config: &ChainConfig) {
+    let rocks_db_path = shell.current_dir().join(&config.rocks_db_path);
+    general.db.state_keeper_db_path = rocks_db_path.join(ROCKS_DB_STATE_KEEPER);
+    general.db.merkle_tree.path = rocks_db_path.join(ROCKS_DB_TREE);
     if config.prover_version != ProverMode::NoProofs {
         general.eth.sender.proof_sending_mode = "ONLY_REAL_PROOFS".to_string();
     }
-    general.save_with_base_path(shell, &config.configs)?;
-    Ok(())
 }
 
 pub fn update_l1_contracts(
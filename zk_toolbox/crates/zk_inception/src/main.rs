@@ -7,7 +7,10 @@ use common::{
 use config::EcosystemConfig;
 use xshell::Shell;
 
-use crate::commands::{args::RunServerArgs, chain::ChainCommands, ecosystem::EcosystemCommands};
+use crate::commands::{
+    args::RunServerArgs, chain::ChainCommands, config::ConfigCommands,
+    ecosystem::EcosystemCommands, wallets::WalletsCommands,
+};
 
 pub mod accept_ownership;
 mod commands;
@@ -33,12 +36,18 @@ pub enum InceptionSubcommands {
     #[command(subcommand)]
     Ecosystem(EcosystemCommands),
     /// Chain related commands
-    #[command(subcommand)]
+    #[command(subcommand, alias = "hyperchain")]
     Chain(ChainCommands),
+    /// Chain config related commands
+    #[command(subcommand)]
+    Config(ConfigCommands),
     /// Run server
     Server(RunServerArgs),
     /// Run containers for local development
     Containers,
+    /// Wallets related commands
+    #[command(subcommand)]
+    Wallets(WalletsCommands),
 }
 
 #[derive(Parser, Debug)]
@@ -101,8 +110,10 @@ async fn run_subcommand(inception_args: Inception, shell: &Shell) -> anyhow::Res
     match inception_args.command {
         InceptionSubcommands::Ecosystem(args) => commands::ecosystem::run(shell, args).await?,
         InceptionSubcommands::Chain(args) => commands::chain::run(shell, args).await?,
+        InceptionSubcommands::Config(args) => commands::config::run(shell, args)?,
         InceptionSubcommands::Server(args) => commands::server::run(shell, args)?,
         InceptionSubcommands::Containers => commands::containers::run(shell)?,
+        InceptionSubcommands::Wallets(args) => commands::wallets::run(shell, args).await?,
     }
     Ok(())
 }
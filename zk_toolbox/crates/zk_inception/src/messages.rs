@@ -1,5 +1,10 @@
+use std::path::Path;
+
+use config::WalletRole;
 use ethers::types::H160;
 
+use crate::commands::chain::args::test::TestSuite;
+
 /// Common messages
 pub(super) const MSG_SELECTED_CONFIG: &str = "Selected config";
 pub(super) const MSG_CHAIN_NOT_INITIALIZED: &str =
@@ -139,6 +144,37 @@ pub(super) fn msg_server_db_name_prompt(chain_name: &str) -> String {
     format!("Please provide server database name for chain {chain_name}")
 }
 
+/// Chain test related messages
+pub(super) const MSG_TEST_SUITE_HELP: &str = "Test suite to run";
+pub(super) const MSG_TEST_FROM_SUITE_HELP: &str =
+    "Run all test suites starting from the specified one (integration, revert, upgrade)";
+pub(super) const MSG_TESTS_BUILDING_DEPENDENCIES: &str = "Building repository dependencies...";
+pub(super) const MSG_TESTS_BUILDING_CONTRACTS: &str = "Building test contracts...";
+pub(super) const MSG_L2_RPC_URL_NOT_FOUND_ERR: &str =
+    "L2 RPC URL is not found in the general config of the chain";
+pub(super) fn msg_running_test_suite(suite: TestSuite, chain_name: &str) -> String {
+    format!("Running {suite} tests for chain {chain_name}")
+}
+pub(super) fn msg_test_suites_passed(chain_name: &str) -> String {
+    format!("Tests for chain {chain_name} ran successfully")
+}
+
+/// Chain encrypt keys related messages
+pub(super) const MSG_KEYSTORE_PASSPHRASE_HELP: &str = "Passphrase to encrypt keystores with";
+pub(super) const MSG_KEYSTORE_PASSPHRASE_PROMPT: &str =
+    "Enter the passphrase to encrypt keystores with";
+pub(super) const MSG_KEYSTORE_PASSPHRASE_NOTE: &str = "Provide the passphrase to the server via the ZKSYNC_KEYSTORE_PASSPHRASE env variable, or a path to a file with it via ZKSYNC_KEYSTORE_PASSPHRASE_FILE";
+pub(super) const MSG_NO_KEYS_TO_ENCRYPT: &str = "All keys are already encrypted";
+pub(super) fn msg_key_encrypted(name: &str, path: &Path) -> String {
+    format!("Encrypted {name} into {}", path.display())
+}
+pub(super) fn msg_keys_encrypted(chain_name: &str) -> String {
+    format!("Keys for chain {chain_name} encrypted successfully")
+}
+pub(super) fn msg_consensus_key_not_string_err(key: &str) -> String {
+    format!("Consensus {key} in secrets config is not a string")
+}
+
 /// Chain initialize bridges related messages
 pub(super) const MSG_INITIALIZING_BRIDGES_SPINNER: &str = "Initializing bridges";
 
@@ -166,6 +202,56 @@ pub(super) const MSG_STARTING_SERVER: &str = "Starting server";
 pub(super) const MSG_FAILED_TO_RUN_SERVER_ERR: &str = "Failed to start server";
 pub(super) const MSG_BUILDING_L1_CONTRACTS: &str = "Building L1 contracts...";
 
+/// Config check related messages
+pub(super) const MSG_CONFIG_MISSING_KEYS: &str = "Missing keys";
+pub(super) const MSG_CONFIG_UNKNOWN_KEYS: &str = "Unknown keys";
+pub(super) const MSG_CONFIG_MODIFIED_VALUES: &str = "Modified values";
+pub(super) const MSG_CONFIGS_UP_TO_DATE: &str = "Configs are up to date";
+pub(super) const MSG_CONFIG_DRIFT_DETECTED_ERR: &str =
+    "Chain configs differ from the expected ones. Update them or re-run chain genesis";
+pub(super) fn msg_checking_chain_configs(chain_name: &str) -> String {
+    format!("Checking configs of chain {chain_name}")
+}
+pub(super) fn msg_config_file_missing(file_name: &str) -> String {
+    format!("Config file {file_name} is missing")
+}
+pub(super) fn msg_config_up_to_date(file_name: &str) -> String {
+    format!("Config file {file_name} is up to date")
+}
+pub(super) fn msg_config_drift_detected(file_name: &str) -> String {
+    format!("Config file {file_name} differs from the expected one")
+}
+
+/// Wallets related messages
+pub(super) const MSG_TEST_RICH_ACCOUNTS_HELP: &str = "Number of test rich accounts to generate";
+pub(super) const MSG_FUND_AMOUNT_HELP: &str = "Amount of wei to send to each wallet";
+pub(super) const MSG_WALLETS_NOT_CHANGED: &str = "Wallets were not changed";
+pub(super) const MSG_WALLETS_SUMMARY: &str = "Wallets";
+pub(super) const MSG_WALLETS_FUNDING_LOCALHOST_ONLY_ERR: &str =
+    "Wallets can be funded only for the localhost L1 network";
+pub(super) const MSG_ECOSYSTEM_WALLET_BALANCES: &str = "Ecosystem wallet balances";
+pub(super) fn msg_overwrite_wallets_prompt(chain_name: &str) -> String {
+    format!("Wallets for chain {chain_name} already exist. Do you want to overwrite them?")
+}
+pub(super) fn msg_wallets_generated(chain_name: &str) -> String {
+    format!("Wallets for chain {chain_name} generated successfully")
+}
+pub(super) fn msg_wallets_imported(chain_name: &str) -> String {
+    format!("Wallets for chain {chain_name} imported successfully")
+}
+pub(super) fn msg_wallets_funded(chain_name: &str) -> String {
+    format!("Wallets for chain {chain_name} funded successfully")
+}
+pub(super) fn msg_wallet_key_mismatch_err(role: WalletRole) -> String {
+    format!("Private key of the {role} wallet doesn't match its address")
+}
+pub(super) fn msg_chain_wallet_balances(chain_name: &str) -> String {
+    format!("Wallet balances of chain {chain_name}")
+}
+pub(super) fn msg_wallet_underfunded(role: WalletRole, address: &H160) -> String {
+    format!("The {role} wallet {address:?} doesn't have enough money to send L1 transactions")
+}
+
 /// Forge utils related messages
 pub(super) const MSG_DEPLOYER_PK_NOT_SET_ERR: &str = "Deployer private key is not set";
 pub(super) fn msg_address_doesnt_have_enough_money_prompt(address: &H160) -> String {